@@ -0,0 +1,652 @@
+//! Cache management panel backend
+//!
+//! [`CacheAdminService`] reports per-cache statistics, purges the page cache
+//! by tag, clears individual caches and pre-renders the most visited pages
+//! into the page cache. Every action checks the acting admin's capabilities
+//! and is reported to the registered audit hooks, whether it succeeded or was
+//! denied.
+
+use chrono::{DateTime, Utc};
+use rustpress_performance::page_cache::WarmingConfig;
+use rustpress_performance::{CachedPage, ObjectCache, PageCache, QueryCache};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::dbmanager::AuditContext;
+
+/// Capability (`resource:action`) required to view cache statistics
+pub const CACHE_VIEW_CAPABILITY: (&str, &str) = ("cache", "read");
+
+/// Capability (`resource:action`) required to purge, clear or warm caches
+pub const CACHE_MANAGE_CAPABILITY: (&str, &str) = ("cache", "manage");
+
+/// Errors from cache administration
+#[derive(Debug, thiserror::Error)]
+pub enum CacheAdminError {
+    #[error("Missing capability {resource}:{action}")]
+    Forbidden {
+        resource: &'static str,
+        action: &'static str,
+    },
+
+    #[error("Cache not configured: {0}")]
+    NotConfigured(CacheKind),
+
+    #[error("Cache warmup is not configured")]
+    WarmupNotConfigured,
+
+    #[error("A cache warmup is already running")]
+    WarmupRunning,
+
+    #[error("Cache error: {0}")]
+    Cache(String),
+}
+
+/// The caches an admin can manage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheKind {
+    Page,
+    Object,
+    Query,
+}
+
+impl std::fmt::Display for CacheKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Page => "page",
+            Self::Object => "object",
+            Self::Query => "query",
+        })
+    }
+}
+
+/// Answers whether an admin holds a `resource:action` capability
+type CapabilityCheck = Box<dyn Fn(&str, &str) -> bool + Send + Sync>;
+
+/// The admin performing a cache action
+pub struct AdminActor {
+    pub context: AuditContext,
+    can: CapabilityCheck,
+}
+
+impl AdminActor {
+    /// `can` answers whether the admin holds a `resource:action` capability
+    pub fn new(
+        context: AuditContext,
+        can: impl Fn(&str, &str) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            context,
+            can: Box::new(can),
+        }
+    }
+
+    fn require(
+        &self,
+        (resource, action): (&'static str, &'static str),
+    ) -> Result<(), CacheAdminError> {
+        if (self.can)(resource, action) {
+            Ok(())
+        } else {
+            Err(CacheAdminError::Forbidden { resource, action })
+        }
+    }
+}
+
+/// Statistics for one cache
+#[derive(Debug, Clone, Serialize)]
+pub struct CachePanelStats {
+    pub kind: CacheKind,
+    pub hits: u64,
+    pub misses: u64,
+    /// Hits over lookups, if there were any lookups
+    pub hit_ratio: Option<f64>,
+    pub entries: u64,
+    /// Approximate memory held, when the cache can measure it
+    pub memory_bytes: Option<u64>,
+}
+
+impl CachePanelStats {
+    fn new(kind: CacheKind, hits: u64, misses: u64, entries: u64, memory: Option<u64>) -> Self {
+        let lookups = hits + misses;
+        Self {
+            kind,
+            hits,
+            misses,
+            hit_ratio: (lookups > 0).then(|| hits as f64 / lookups as f64),
+            entries,
+            memory_bytes: memory,
+        }
+    }
+}
+
+/// An audited cache action
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum CacheAuditEvent {
+    PurgeTag {
+        tag: String,
+        purged: usize,
+    },
+    Clear {
+        cache: CacheKind,
+    },
+    WarmupStarted {
+        pages: usize,
+    },
+    WarmupCancelled,
+    /// An action refused for missing capabilities
+    Denied {
+        action: String,
+    },
+}
+
+/// Audit hook, called after every action and every denial
+pub type CacheAuditHook = Arc<dyn Fn(&CacheAuditEvent, &AuditContext) + Send + Sync>;
+
+/// Source of the site's most visited paths
+#[axum::async_trait]
+pub trait PopularPages: Send + Sync {
+    /// Up to `limit` paths, most visited first
+    async fn most_visited(&self, limit: usize) -> Vec<String>;
+}
+
+/// Renders a page for the page cache
+#[axum::async_trait]
+pub trait PageRenderer: Send + Sync {
+    async fn render(&self, path: &str) -> Result<CachedPage, String>;
+}
+
+/// State of the current or last warmup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarmupState {
+    Running,
+    Completed,
+    Cancelled,
+}
+
+/// Progress of the current or last warmup
+#[derive(Debug, Clone, Serialize)]
+pub struct WarmupProgress {
+    pub state: WarmupState,
+    pub total: usize,
+    pub warmed: usize,
+    pub failed: usize,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+struct Warmup {
+    pages: Arc<dyn PopularPages>,
+    renderer: Arc<dyn PageRenderer>,
+    config: WarmingConfig,
+    /// Number of most visited pages to pre-render
+    limit: usize,
+}
+
+struct WarmupJob {
+    cancel: watch::Sender<bool>,
+    progress: watch::Receiver<WarmupProgress>,
+    task: JoinHandle<()>,
+}
+
+/// Backend for the admin cache panel
+pub struct CacheAdminService {
+    page_cache: Arc<PageCache>,
+    object_cache: Option<Arc<ObjectCache>>,
+    query_cache: Option<Arc<QueryCache>>,
+    warmup: Option<Arc<Warmup>>,
+    job: parking_lot::Mutex<Option<WarmupJob>>,
+    audit_hooks: Vec<CacheAuditHook>,
+}
+
+impl CacheAdminService {
+    pub fn new(page_cache: Arc<PageCache>) -> Self {
+        Self {
+            page_cache,
+            object_cache: None,
+            query_cache: None,
+            warmup: None,
+            job: parking_lot::Mutex::new(None),
+            audit_hooks: Vec::new(),
+        }
+    }
+
+    pub fn with_object_cache(mut self, cache: Arc<ObjectCache>) -> Self {
+        self.object_cache = Some(cache);
+        self
+    }
+
+    pub fn with_query_cache(mut self, cache: Arc<QueryCache>) -> Self {
+        self.query_cache = Some(cache);
+        self
+    }
+
+    /// Enable warmup of the `limit` most visited pages, paced by `config`:
+    /// at most `concurrency` renders at a time with `delay_ms` between
+    /// batches
+    pub fn with_warmup(
+        mut self,
+        pages: Arc<dyn PopularPages>,
+        renderer: Arc<dyn PageRenderer>,
+        config: WarmingConfig,
+        limit: usize,
+    ) -> Self {
+        self.warmup = Some(Arc::new(Warmup {
+            pages,
+            renderer,
+            config,
+            limit,
+        }));
+        self
+    }
+
+    pub fn with_audit_hook(mut self, hook: CacheAuditHook) -> Self {
+        self.audit_hooks.push(hook);
+        self
+    }
+
+    fn audit(&self, event: CacheAuditEvent, actor: &AdminActor) {
+        info!(
+            event = ?event,
+            user_id = actor.context.user_id.as_deref().unwrap_or("-"),
+            "Cache admin action"
+        );
+        for hook in &self.audit_hooks {
+            hook(&event, &actor.context);
+        }
+    }
+
+    fn authorize(
+        &self,
+        actor: &AdminActor,
+        capability: (&'static str, &'static str),
+        action: &str,
+    ) -> Result<(), CacheAdminError> {
+        let result = actor.require(capability);
+        if result.is_err() {
+            self.audit(
+                CacheAuditEvent::Denied {
+                    action: action.to_string(),
+                },
+                actor,
+            );
+        }
+        result
+    }
+
+    /// Statistics for every configured cache
+    pub fn stats(&self, actor: &AdminActor) -> Result<Vec<CachePanelStats>, CacheAdminError> {
+        actor.require(CACHE_VIEW_CAPABILITY)?;
+
+        let page = self.page_cache.stats();
+        let mut stats = vec![CachePanelStats::new(
+            CacheKind::Page,
+            page.hits,
+            page.misses,
+            self.page_cache.size() as u64,
+            Some(self.page_cache.memory_bytes() as u64),
+        )];
+
+        if let Some(cache) = &self.object_cache {
+            let object = cache.stats();
+            // The local cache doesn't track entry sizes
+            stats.push(CachePanelStats::new(
+                CacheKind::Object,
+                object.local_hits + object.remote_hits,
+                object.misses,
+                cache.local_entry_count(),
+                None,
+            ));
+        }
+
+        if let Some(cache) = &self.query_cache {
+            let query = cache.stats();
+            stats.push(CachePanelStats::new(
+                CacheKind::Query,
+                query.hits,
+                query.misses,
+                cache.size() as u64,
+                Some(cache.memory_bytes() as u64),
+            ));
+        }
+
+        Ok(stats)
+    }
+
+    /// Purge every cached page carrying `tag`, returning how many were removed
+    pub fn purge_tag(&self, actor: &AdminActor, tag: &str) -> Result<usize, CacheAdminError> {
+        self.authorize(actor, CACHE_MANAGE_CAPABILITY, "purge_tag")?;
+
+        let before = self.page_cache.size();
+        self.page_cache.invalidate_by_tag(tag);
+        let purged = before.saturating_sub(self.page_cache.size());
+
+        self.audit(
+            CacheAuditEvent::PurgeTag {
+                tag: tag.to_string(),
+                purged,
+            },
+            actor,
+        );
+        Ok(purged)
+    }
+
+    /// Empty one cache
+    pub async fn clear(&self, actor: &AdminActor, cache: CacheKind) -> Result<(), CacheAdminError> {
+        self.authorize(actor, CACHE_MANAGE_CAPABILITY, "clear")?;
+
+        match cache {
+            CacheKind::Page => self.page_cache.clear(),
+            CacheKind::Object => self
+                .object_cache
+                .as_ref()
+                .ok_or(CacheAdminError::NotConfigured(cache))?
+                .flush()
+                .await
+                .map_err(|e| CacheAdminError::Cache(e.to_string()))?,
+            CacheKind::Query => self
+                .query_cache
+                .as_ref()
+                .ok_or(CacheAdminError::NotConfigured(cache))?
+                .clear(),
+        }
+
+        self.audit(CacheAuditEvent::Clear { cache }, actor);
+        Ok(())
+    }
+
+    /// Start pre-rendering the most visited pages in the background
+    pub async fn start_warmup(
+        &self,
+        actor: &AdminActor,
+    ) -> Result<WarmupProgress, CacheAdminError> {
+        self.authorize(actor, CACHE_MANAGE_CAPABILITY, "warmup")?;
+        let warmup = self
+            .warmup
+            .clone()
+            .ok_or(CacheAdminError::WarmupNotConfigured)?;
+
+        if self
+            .job
+            .lock()
+            .as_ref()
+            .is_some_and(|job| !job.task.is_finished())
+        {
+            return Err(CacheAdminError::WarmupRunning);
+        }
+
+        let paths = warmup.pages.most_visited(warmup.limit).await;
+        let initial = WarmupProgress {
+            state: WarmupState::Running,
+            total: paths.len(),
+            warmed: 0,
+            failed: 0,
+            started_at: Utc::now(),
+            finished_at: None,
+        };
+
+        {
+            let mut job = self.job.lock();
+            // Another admin may have started one while we fetched the paths
+            if job.as_ref().is_some_and(|job| !job.task.is_finished()) {
+                return Err(CacheAdminError::WarmupRunning);
+            }
+            let (cancel, cancelled) = watch::channel(false);
+            let (progress_tx, progress) = watch::channel(initial.clone());
+            let task = tokio::spawn(run_warmup(
+                warmup,
+                self.page_cache.clone(),
+                paths,
+                cancelled,
+                progress_tx,
+            ));
+            *job = Some(WarmupJob {
+                cancel,
+                progress,
+                task,
+            });
+        }
+
+        self.audit(
+            CacheAuditEvent::WarmupStarted {
+                pages: initial.total,
+            },
+            actor,
+        );
+        Ok(initial)
+    }
+
+    /// Stop the running warmup, if any. Pages already warmed stay cached.
+    pub fn cancel_warmup(&self, actor: &AdminActor) -> Result<bool, CacheAdminError> {
+        self.authorize(actor, CACHE_MANAGE_CAPABILITY, "cancel_warmup")?;
+
+        let cancelled = match self.job.lock().as_ref() {
+            Some(job) if !job.task.is_finished() => {
+                job.cancel.send_replace(true);
+                true
+            }
+            _ => false,
+        };
+        if cancelled {
+            self.audit(CacheAuditEvent::WarmupCancelled, actor);
+        }
+        Ok(cancelled)
+    }
+
+    /// Progress of the current or last warmup
+    pub fn warmup_status(
+        &self,
+        actor: &AdminActor,
+    ) -> Result<Option<WarmupProgress>, CacheAdminError> {
+        actor.require(CACHE_VIEW_CAPABILITY)?;
+        Ok(self
+            .job
+            .lock()
+            .as_ref()
+            .map(|job| job.progress.borrow().clone()))
+    }
+}
+
+async fn run_warmup(
+    warmup: Arc<Warmup>,
+    page_cache: Arc<PageCache>,
+    paths: Vec<String>,
+    mut cancelled: watch::Receiver<bool>,
+    progress: watch::Sender<WarmupProgress>,
+) {
+    let delay = Duration::from_millis(warmup.config.delay_ms);
+    let no_vary = HashMap::new();
+    let mut state = WarmupState::Completed;
+
+    for (i, batch) in paths.chunks(warmup.config.concurrency.max(1)).enumerate() {
+        if i > 0 {
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = cancelled.wait_for(|c| *c) => {}
+            }
+        }
+        if *cancelled.borrow() {
+            state = WarmupState::Cancelled;
+            break;
+        }
+
+        let rendered =
+            futures::future::join_all(batch.iter().map(|path| warmup.renderer.render(path))).await;
+        for (path, result) in batch.iter().zip(rendered) {
+            match result {
+                Ok(page) => {
+                    page_cache.store(page_cache.cache_key("GET", path, &no_vary), page);
+                    progress.send_modify(|p| p.warmed += 1);
+                }
+                Err(e) => {
+                    warn!(path = %path, error = %e, "Cache warmup render failed");
+                    progress.send_modify(|p| p.failed += 1);
+                }
+            }
+        }
+    }
+
+    progress.send_modify(|p| {
+        p.state = state;
+        p.finished_at = Some(Utc::now());
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustpress_performance::PageCacheConfig;
+    use std::collections::HashSet;
+
+    struct Paths(Vec<&'static str>);
+
+    #[axum::async_trait]
+    impl PopularPages for Paths {
+        async fn most_visited(&self, limit: usize) -> Vec<String> {
+            self.0.iter().take(limit).map(|p| p.to_string()).collect()
+        }
+    }
+
+    struct Renderer;
+
+    #[axum::async_trait]
+    impl PageRenderer for Renderer {
+        async fn render(&self, path: &str) -> Result<CachedPage, String> {
+            if path == "/broken" {
+                return Err("template error".to_string());
+            }
+            Ok(page(path, &[]))
+        }
+    }
+
+    fn page(content: &str, tags: &[&str]) -> CachedPage {
+        CachedPage {
+            content: content.to_string(),
+            status_code: 200,
+            headers: HashMap::new(),
+            content_type: "text/html".to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect::<HashSet<_>>(),
+            created_at: 0,
+            ttl: 3600,
+            etag: String::new(),
+            last_modified: 0,
+        }
+    }
+
+    fn admin() -> AdminActor {
+        AdminActor::new(AuditContext::default(), |_, _| true)
+    }
+
+    fn viewer() -> AdminActor {
+        AdminActor::new(AuditContext::default(), |resource, action| {
+            (resource, action) == CACHE_VIEW_CAPABILITY
+        })
+    }
+
+    #[tokio::test]
+    async fn test_purge_tag_is_gated_and_audited() {
+        let cache = Arc::new(PageCache::new(PageCacheConfig::default()));
+        cache.store("a".to_string(), page("a", &["post:1"]));
+        cache.store("b".to_string(), page("b", &["post:2"]));
+
+        let events = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let service =
+            CacheAdminService::new(cache.clone()).with_audit_hook(Arc::new(move |event, _| {
+                recorded.lock().push(event.clone())
+            }));
+
+        assert!(matches!(
+            service.purge_tag(&viewer(), "post:1"),
+            Err(CacheAdminError::Forbidden { .. })
+        ));
+        assert_eq!(cache.size(), 2);
+
+        assert_eq!(service.purge_tag(&admin(), "post:1").unwrap(), 1);
+        assert_eq!(cache.size(), 1);
+
+        {
+            let events = events.lock();
+            assert!(
+                matches!(&events[0], CacheAuditEvent::Denied { action } if action == "purge_tag")
+            );
+            assert!(matches!(
+                &events[1],
+                CacheAuditEvent::PurgeTag { purged: 1, .. }
+            ));
+        }
+
+        let stats = service.stats(&viewer()).unwrap();
+        assert_eq!(stats[0].entries, 1);
+        assert!(matches!(
+            service.clear(&admin(), CacheKind::Query).await,
+            Err(CacheAdminError::NotConfigured(CacheKind::Query))
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_warmup_fills_page_cache() {
+        let cache = Arc::new(PageCache::new(PageCacheConfig::default()));
+        let config = WarmingConfig {
+            urls: Vec::new(),
+            concurrency: 2,
+            delay_ms: 100,
+        };
+        let service = CacheAdminService::new(cache.clone()).with_warmup(
+            Arc::new(Paths(vec!["/", "/about", "/broken", "/blog"])),
+            Arc::new(Renderer),
+            config,
+            10,
+        );
+
+        let progress = service.start_warmup(&admin()).await.unwrap();
+        assert_eq!(progress.total, 4);
+        assert!(matches!(
+            service.start_warmup(&admin()).await,
+            Err(CacheAdminError::WarmupRunning)
+        ));
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let progress = service.warmup_status(&viewer()).unwrap().unwrap();
+        assert_eq!(progress.state, WarmupState::Completed);
+        assert_eq!((progress.warmed, progress.failed), (3, 1));
+        assert!(cache
+            .get(&cache.cache_key("GET", "/about", &HashMap::new()))
+            .is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_warmup_can_be_cancelled() {
+        let cache = Arc::new(PageCache::new(PageCacheConfig::default()));
+        let config = WarmingConfig {
+            urls: Vec::new(),
+            concurrency: 1,
+            delay_ms: 1000,
+        };
+        let service = CacheAdminService::new(cache.clone()).with_warmup(
+            Arc::new(Paths(vec!["/a", "/b", "/c"])),
+            Arc::new(Renderer),
+            config,
+            10,
+        );
+
+        service.start_warmup(&admin()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(service.cancel_warmup(&admin()).unwrap());
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let progress = service.warmup_status(&admin()).unwrap().unwrap();
+        assert_eq!(progress.state, WarmupState::Cancelled);
+        assert_eq!(progress.warmed, 1);
+        assert_eq!(cache.size(), 1);
+        assert!(!service.cancel_warmup(&admin()).unwrap());
+    }
+}
@@ -149,8 +149,206 @@ pub struct QueryHistoryItem {
 /// Query explain plan
 #[derive(Debug, Clone, Serialize)]
 pub struct QueryExplain {
+    /// Raw JSON plan as returned by PostgreSQL, pretty printed
     pub plan: String,
+    /// Wall-clock time spent producing the plan (ms)
     pub execution_time: f64,
+    /// Whether the statement was actually executed (`ANALYZE`)
+    pub analyzed: bool,
+    /// Structured plan tree
+    #[serde(rename = "planTree")]
+    pub plan_tree: PlanNode,
+    /// Planner time reported by PostgreSQL (ms)
+    #[serde(rename = "planningTime", skip_serializing_if = "Option::is_none")]
+    pub planning_time: Option<f64>,
+    /// Executor time reported by PostgreSQL (ms, analyze only)
+    #[serde(rename = "executorTime", skip_serializing_if = "Option::is_none")]
+    pub executor_time: Option<f64>,
+    /// Id of the node with the highest self time (or self cost without analyze)
+    #[serde(rename = "mostExpensiveNode", skip_serializing_if = "Option::is_none")]
+    pub most_expensive_node: Option<usize>,
+}
+
+/// A node in a structured `EXPLAIN (FORMAT JSON)` plan
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanNode {
+    /// Pre-order index of the node in the tree
+    pub id: usize,
+    #[serde(rename = "nodeType")]
+    pub node_type: String,
+    #[serde(rename = "relationName", skip_serializing_if = "Option::is_none")]
+    pub relation_name: Option<String>,
+    #[serde(rename = "indexName", skip_serializing_if = "Option::is_none")]
+    pub index_name: Option<String>,
+    #[serde(rename = "startupCost")]
+    pub startup_cost: f64,
+    #[serde(rename = "totalCost")]
+    pub total_cost: f64,
+    /// Planner row estimate (per loop)
+    #[serde(rename = "planRows")]
+    pub plan_rows: f64,
+    /// Actual time to first row (ms, per loop)
+    #[serde(rename = "actualStartupTime", skip_serializing_if = "Option::is_none")]
+    pub actual_startup_time: Option<f64>,
+    /// Actual time to last row (ms, per loop)
+    #[serde(rename = "actualTotalTime", skip_serializing_if = "Option::is_none")]
+    pub actual_total_time: Option<f64>,
+    /// Actual rows produced (per loop)
+    #[serde(rename = "actualRows", skip_serializing_if = "Option::is_none")]
+    pub actual_rows: Option<f64>,
+    #[serde(rename = "actualLoops", skip_serializing_if = "Option::is_none")]
+    pub actual_loops: Option<f64>,
+    /// Time spent in this node excluding children, across all loops (ms)
+    #[serde(rename = "selfTime", skip_serializing_if = "Option::is_none")]
+    pub self_time: Option<f64>,
+    /// Cost of this node excluding children
+    #[serde(rename = "selfCost")]
+    pub self_cost: f64,
+    /// Actual rows divided by estimated rows (analyze only)
+    #[serde(rename = "rowEstimateRatio", skip_serializing_if = "Option::is_none")]
+    pub row_estimate_ratio: Option<f64>,
+    /// Remaining node attributes (filters, buffers, ...)
+    pub details: serde_json::Map<String, serde_json::Value>,
+    pub children: Vec<PlanNode>,
+}
+
+impl PlanNode {
+    /// Build a node tree from a PostgreSQL JSON plan node, numbering nodes
+    /// in pre-order starting at `next_id`.
+    fn from_json(node: &serde_json::Value, next_id: &mut usize) -> Self {
+        let num = |key: &str| node.get(key).and_then(|v| v.as_f64());
+        let text = |key: &str| node.get(key).and_then(|v| v.as_str()).map(String::from);
+
+        let id = *next_id;
+        *next_id += 1;
+
+        let children: Vec<PlanNode> = node
+            .get("Plans")
+            .and_then(|v| v.as_array())
+            .map(|plans| {
+                plans
+                    .iter()
+                    .map(|p| PlanNode::from_json(p, next_id))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let total_cost = num("Total Cost").unwrap_or(0.0);
+        let children_cost: f64 = children.iter().map(|c| c.total_cost).sum();
+
+        let actual_total_time = num("Actual Total Time");
+        let actual_loops = num("Actual Loops");
+        let self_time = match (actual_total_time, actual_loops) {
+            (Some(time), Some(loops)) => {
+                let children_time: f64 = children
+                    .iter()
+                    .map(|c| c.actual_total_time.unwrap_or(0.0) * c.actual_loops.unwrap_or(1.0))
+                    .sum();
+                Some((time * loops - children_time).max(0.0))
+            }
+            _ => None,
+        };
+
+        let plan_rows = num("Plan Rows").unwrap_or(0.0);
+        let actual_rows = num("Actual Rows");
+        let row_estimate_ratio = actual_rows.map(|actual| actual.max(1.0) / plan_rows.max(1.0));
+
+        const KNOWN: &[&str] = &[
+            "Node Type",
+            "Relation Name",
+            "Index Name",
+            "Startup Cost",
+            "Total Cost",
+            "Plan Rows",
+            "Actual Startup Time",
+            "Actual Total Time",
+            "Actual Rows",
+            "Actual Loops",
+            "Plans",
+        ];
+        let details = node
+            .as_object()
+            .map(|obj| {
+                obj.iter()
+                    .filter(|(k, _)| !KNOWN.contains(&k.as_str()))
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            id,
+            node_type: text("Node Type").unwrap_or_else(|| "Unknown".to_string()),
+            relation_name: text("Relation Name"),
+            index_name: text("Index Name"),
+            startup_cost: num("Startup Cost").unwrap_or(0.0),
+            total_cost,
+            plan_rows,
+            actual_startup_time: num("Actual Startup Time"),
+            actual_total_time,
+            actual_rows,
+            actual_loops,
+            self_time,
+            self_cost: (total_cost - children_cost).max(0.0),
+            row_estimate_ratio,
+            details,
+            children,
+        }
+    }
+
+    /// Id of the most expensive node: by self time when timings are
+    /// available, otherwise by self cost.
+    fn most_expensive(&self) -> Option<usize> {
+        fn walk<'a>(node: &'a PlanNode, out: &mut Vec<&'a PlanNode>) {
+            out.push(node);
+            for child in &node.children {
+                walk(child, out);
+            }
+        }
+
+        let mut nodes = Vec::new();
+        walk(self, &mut nodes);
+
+        let weight = |n: &PlanNode| n.self_time.unwrap_or(n.self_cost);
+        nodes
+            .into_iter()
+            .max_by(|a, b| {
+                weight(a)
+                    .partial_cmp(&weight(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map(|n| n.id)
+    }
+}
+
+/// Parse the output of `EXPLAIN (FORMAT JSON)` into a [`QueryExplain`]
+fn parse_explain_json(
+    output: &serde_json::Value,
+    analyzed: bool,
+    execution_time: f64,
+) -> Result<QueryExplain, String> {
+    let root = output
+        .get(0)
+        .or_else(|| output.as_object().map(|_| output))
+        .ok_or_else(|| "Unexpected EXPLAIN output".to_string())?;
+
+    let plan = root
+        .get("Plan")
+        .ok_or_else(|| "EXPLAIN output has no plan".to_string())?;
+
+    let mut next_id = 0;
+    let plan_tree = PlanNode::from_json(plan, &mut next_id);
+    let most_expensive_node = plan_tree.most_expensive();
+
+    Ok(QueryExplain {
+        plan: serde_json::to_string_pretty(output).unwrap_or_default(),
+        execution_time,
+        analyzed,
+        planning_time: root.get("Planning Time").and_then(|v| v.as_f64()),
+        executor_time: root.get("Execution Time").and_then(|v| v.as_f64()),
+        most_expensive_node,
+        plan_tree,
+    })
 }
 
 // ============================================================================
@@ -190,8 +388,20 @@ pub struct ExecuteQueryRequest {
     /// Confirmation flag required for destructive operations (DROP, TRUNCATE, DELETE)
     #[serde(default)]
     pub confirm: bool,
+    /// Statement timeout override in milliseconds (capped at `MAX_QUERY_TIMEOUT_MS`)
+    #[serde(default, rename = "timeoutMs")]
+    pub timeout_ms: Option<u64>,
+    /// Client-chosen history id, so the query can be cancelled while it runs
+    #[serde(default, rename = "queryId")]
+    pub query_id: Option<uuid::Uuid>,
 }
 
+/// Default server-side statement timeout for ad-hoc queries
+pub const DEFAULT_QUERY_TIMEOUT_MS: u64 = 30_000;
+
+/// Upper bound for a per-request statement timeout override
+pub const MAX_QUERY_TIMEOUT_MS: u64 = 300_000;
+
 /// Blocked SQL commands that cannot be executed through the UI
 pub const BLOCKED_COMMANDS: &[&str] = &[
     "DROP DATABASE",
@@ -235,6 +445,36 @@ fn is_destructive_query(query: &str) -> bool {
         .any(|cmd| query_upper.contains(cmd))
 }
 
+/// Explain query request
+#[derive(Debug, Deserialize)]
+pub struct ExplainQueryRequest {
+    pub query: String,
+    /// Run `EXPLAIN ANALYZE`, which executes the statement
+    #[serde(default)]
+    pub analyze: bool,
+    /// Confirmation flag required to analyze a statement that modifies data
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Check whether a statement is a plain read (SELECT / VALUES / TABLE / read-only CTE)
+fn is_read_only_query(query: &str) -> bool {
+    let query_upper = query.trim_start().to_uppercase();
+    let reads = query_upper.starts_with("SELECT")
+        || query_upper.starts_with("VALUES")
+        || query_upper.starts_with("TABLE")
+        || query_upper.starts_with("WITH");
+
+    const WRITES: &[&str] = &[
+        "INSERT", "UPDATE", "DELETE", "MERGE", "TRUNCATE", "CREATE", "ALTER", "DROP", "GRANT",
+        "REVOKE", "INTO",
+    ];
+    reads
+        && !query_upper
+            .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .any(|word| WRITES.contains(&word))
+}
+
 /// Save query request
 #[derive(Debug, Deserialize)]
 pub struct SaveQueryRequest {
@@ -390,12 +630,77 @@ pub struct ImportSqlResult {
 }
 
 /// Import CSV result
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct ImportCsvResult {
     pub success: bool,
     pub rows_imported: u64,
+    #[serde(rename = "rowsSkipped")]
+    pub rows_skipped: u64,
+    /// True when nothing was committed because `onError=rollback` hit a failure
+    #[serde(rename = "rolledBack")]
+    pub rolled_back: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub errors: Option<Vec<String>>,
+    #[serde(rename = "rowErrors", skip_serializing_if = "Vec::is_empty")]
+    pub row_errors: Vec<CsvRowError>,
+}
+
+/// A CSV row that could not be imported
+#[derive(Debug, Clone, Serialize)]
+pub struct CsvRowError {
+    /// 1-indexed line in the file (the header is line 1)
+    pub row: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    pub message: String,
+}
+
+/// What to do when some CSV rows fail validation or insertion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvErrorMode {
+    /// Commit the valid rows and report the failed ones
+    #[default]
+    Skip,
+    /// Roll back the whole import if any row fails
+    Rollback,
+}
+
+/// CSV import options, sent as multipart fields alongside the file
+#[derive(Debug, Clone)]
+pub struct CsvImportOptions {
+    /// CSV header -> table column; an empty target skips the CSV column
+    pub mapping: HashMap<String, String>,
+    pub on_error: CsvErrorMode,
+    /// Rows per multi-row INSERT
+    pub batch_size: usize,
+}
+
+impl Default for CsvImportOptions {
+    fn default() -> Self {
+        Self {
+            mapping: HashMap::new(),
+            on_error: CsvErrorMode::Skip,
+            batch_size: DEFAULT_CSV_BATCH_SIZE,
+        }
+    }
+}
+
+/// Default rows per CSV INSERT batch
+pub const DEFAULT_CSV_BATCH_SIZE: usize = 500;
+
+/// Postgres caps a statement at 65535 bind parameters
+const MAX_BIND_PARAMS: usize = 65_535;
+
+/// Target column metadata used to validate CSV values
+#[derive(Debug, Clone)]
+struct ImportColumn {
+    name: String,
+    /// `information_schema.columns.udt_name`, e.g. `int4`, `varchar`, `_text`
+    udt_name: String,
+    nullable: bool,
+    has_default: bool,
+    max_length: Option<usize>,
 }
 
 /// Audit log entry
@@ -428,6 +733,41 @@ pub struct AuditLogQuery {
     pub end_date: Option<String>,
 }
 
+impl AuditLogQuery {
+    /// WHERE clause for the set filters, with the values to bind in order
+    fn filter_sql(&self) -> (String, Vec<&str>) {
+        let mut conditions = vec!["1=1".to_string()];
+        let mut binds = Vec::new();
+
+        let filters = [
+            ("event_type = ${}", &self.event_type),
+            ("user_id = ${}", &self.user_id),
+            ("created_at >= ${}::timestamptz", &self.start_date),
+            ("created_at <= ${}::timestamptz", &self.end_date),
+        ];
+        for (condition, value) in filters {
+            if let Some(value) = value {
+                binds.push(value.as_str());
+                conditions.push(condition.replace("{}", &binds.len().to_string()));
+            }
+        }
+
+        (conditions.join(" AND "), binds)
+    }
+}
+
+/// Audit log event counts per event type
+#[derive(Debug, Serialize)]
+pub struct AuditLogSummary {
+    pub total: u64,
+    #[serde(rename = "byEventType")]
+    pub by_event_type: HashMap<String, u64>,
+    #[serde(rename = "byUser")]
+    pub by_user: HashMap<String, u64>,
+    #[serde(rename = "failedQueries")]
+    pub failed_queries: u64,
+}
+
 /// Audit log response
 #[derive(Debug, Serialize)]
 pub struct AuditLogResponse {
@@ -524,16 +864,25 @@ pub struct DbManagerState {
     pub query_history_cache: std::sync::RwLock<Vec<QueryHistoryItem>>,
     /// Rate limiter for query execution
     pub rate_limiter: RateLimiter,
+    /// Default statement timeout for executed queries
+    pub query_timeout: std::time::Duration,
+    /// In-flight queries by history id
+    pub running_queries: std::sync::Mutex<HashMap<uuid::Uuid, RunningQuery>>,
+}
+
+/// A query currently executing on a backend connection
+#[derive(Debug, Clone)]
+pub struct RunningQuery {
+    /// PostgreSQL backend process id running the query
+    pub backend_pid: i32,
+    /// Set when the query was cancelled through the API
+    pub cancelled: bool,
 }
 
 impl DbManagerState {
     /// Create new DbManagerState and initialize database tables
     pub fn new(pool: PgPool) -> Self {
-        Self {
-            pool,
-            query_history_cache: std::sync::RwLock::new(Vec::new()),
-            rate_limiter: RateLimiter::new(RateLimitConfig::default()),
-        }
+        Self::with_rate_limit(pool, RateLimitConfig::default())
     }
 
     /// Create with custom rate limit config
@@ -542,6 +891,22 @@ impl DbManagerState {
             pool,
             query_history_cache: std::sync::RwLock::new(Vec::new()),
             rate_limiter: RateLimiter::new(rate_config),
+            query_timeout: std::time::Duration::from_millis(DEFAULT_QUERY_TIMEOUT_MS),
+            running_queries: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Override the default statement timeout
+    pub fn with_query_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.query_timeout = timeout;
+        self
+    }
+
+    /// Effective timeout for a request, falling back to the configured default
+    fn effective_timeout(&self, requested_ms: Option<u64>) -> std::time::Duration {
+        match requested_ms {
+            Some(ms) if ms > 0 => std::time::Duration::from_millis(ms.min(MAX_QUERY_TIMEOUT_MS)),
+            _ => self.query_timeout,
         }
     }
 
@@ -736,21 +1101,10 @@ impl CsvParser {
             return Err("CSV file is empty or contains no valid data".to_string());
         }
 
-        // First row is headers
+        // First row is headers; rows with a different column count are
+        // reported individually by the importer
         let headers = rows.remove(0).values;
 
-        // Validate that all rows have the same number of columns as headers
-        for (i, row) in rows.iter().enumerate() {
-            if row.values.len() != headers.len() {
-                return Err(format!(
-                    "Row {} has {} columns but header has {} columns",
-                    i + 2, // +2 because row 1 is header and array is 0-indexed
-                    row.values.len(),
-                    headers.len()
-                ));
-            }
-        }
-
         Ok((headers, rows))
     }
 
@@ -765,6 +1119,265 @@ impl CsvParser {
     }
 }
 
+/// Resolve each CSV header to an index into `columns`, or `None` to skip it.
+///
+/// Headers map to the column of the same name unless `mapping` overrides them.
+/// Fails if a header targets an unknown column, two headers target the same
+/// column, or a NOT NULL column without a default is left unmapped.
+fn map_csv_columns(
+    headers: &[String],
+    columns: &[ImportColumn],
+    mapping: &HashMap<String, String>,
+) -> Result<Vec<Option<usize>>, Vec<String>> {
+    let mut errors = Vec::new();
+    let mut targets = Vec::with_capacity(headers.len());
+
+    for header in headers {
+        let target = mapping.get(header).unwrap_or(header);
+        if target.is_empty() {
+            targets.push(None);
+            continue;
+        }
+        match columns.iter().position(|c| &c.name == target) {
+            Some(idx) if targets.contains(&Some(idx)) => {
+                errors.push(format!("Column '{}' is mapped more than once", target));
+            }
+            Some(idx) => targets.push(Some(idx)),
+            None => errors.push(format!(
+                "CSV column '{}' maps to unknown column '{}'",
+                header, target
+            )),
+        }
+    }
+
+    for (idx, column) in columns.iter().enumerate() {
+        if !column.nullable && !column.has_default && !targets.contains(&Some(idx)) {
+            errors.push(format!("Required column '{}' is not mapped", column.name));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(targets)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validate a CSV value against its column type, returning the normalized
+/// text to bind (it is cast to the column type in SQL)
+fn coerce_csv_value(
+    value: Option<String>,
+    column: &ImportColumn,
+) -> Result<Option<String>, String> {
+    let Some(value) = value else {
+        if column.nullable || column.has_default {
+            return Ok(None);
+        }
+        return Err("NULL not allowed".to_string());
+    };
+
+    let invalid = |kind: &str| format!("'{}' is not a valid {}", value, kind);
+
+    let coerced = match column.udt_name.as_str() {
+        "int2" => value
+            .parse::<i16>()
+            .map_err(|_| invalid("smallint"))?
+            .to_string(),
+        "int4" => value
+            .parse::<i32>()
+            .map_err(|_| invalid("integer"))?
+            .to_string(),
+        "int8" => value
+            .parse::<i64>()
+            .map_err(|_| invalid("bigint"))?
+            .to_string(),
+        "float4" | "float8" | "numeric" => {
+            value.parse::<f64>().map_err(|_| invalid("number"))?;
+            value
+        }
+        "bool" => match value.to_ascii_lowercase().as_str() {
+            "true" | "t" | "yes" | "y" | "1" => "true".to_string(),
+            "false" | "f" | "no" | "n" | "0" => "false".to_string(),
+            _ => return Err(invalid("boolean")),
+        },
+        "uuid" => uuid::Uuid::parse_str(&value)
+            .map_err(|_| invalid("uuid"))?
+            .to_string(),
+        "date" => chrono::NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+            .map_err(|_| invalid("date (YYYY-MM-DD)"))?
+            .to_string(),
+        "timestamp" | "timestamptz" => {
+            let parsed = chrono::DateTime::parse_from_rfc3339(&value)
+                .map(|dt| dt.naive_utc())
+                .or_else(|_| chrono::NaiveDateTime::parse_from_str(&value, "%Y-%m-%d %H:%M:%S%.f"))
+                .or_else(|_| chrono::NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M:%S%.f"));
+            if parsed.is_err() {
+                return Err(invalid("timestamp"));
+            }
+            value
+        }
+        "json" | "jsonb" => {
+            serde_json::from_str::<serde_json::Value>(&value).map_err(|_| invalid("JSON value"))?;
+            value
+        }
+        "varchar" | "bpchar" => {
+            if let Some(max) = column.max_length {
+                let len = value.chars().count();
+                if len > max {
+                    return Err(format!(
+                        "Value is {} characters, column allows {}",
+                        len, max
+                    ));
+                }
+            }
+            value
+        }
+        _ => value,
+    };
+
+    Ok(Some(coerced))
+}
+
+/// Build a multi-row INSERT for `row_count` rows, casting each parameter to
+/// its column's type
+fn build_csv_insert_sql(table_name: &str, columns: &[&ImportColumn], row_count: usize) -> String {
+    let column_list: Vec<String> = columns.iter().map(|c| format!("\"{}\"", c.name)).collect();
+    let rows: Vec<String> = (0..row_count)
+        .map(|row| {
+            let values: Vec<String> = columns
+                .iter()
+                .enumerate()
+                .map(|(i, c)| format!("${}::\"{}\"", row * columns.len() + i + 1, c.udt_name))
+                .collect();
+            format!("({})", values.join(", "))
+        })
+        .collect();
+
+    format!(
+        "INSERT INTO \"{}\" ({}) VALUES {}",
+        table_name,
+        column_list.join(", "),
+        rows.join(", ")
+    )
+}
+
+/// Fetch the columns of a table for CSV import validation
+async fn get_import_columns(
+    pool: &PgPool,
+    table_name: &str,
+) -> Result<Vec<ImportColumn>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT column_name::text AS name,
+               udt_name::text AS udt_name,
+               is_nullable = 'YES' AS nullable,
+               (column_default IS NOT NULL OR is_identity = 'YES') AS has_default,
+               character_maximum_length
+        FROM information_schema.columns
+        WHERE table_name = $1 AND table_schema = 'public'
+        ORDER BY ordinal_position
+    "#,
+    )
+    .bind(table_name)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ImportColumn {
+            name: row.get("name"),
+            udt_name: row.get("udt_name"),
+            nullable: row.get("nullable"),
+            has_default: row.get("has_default"),
+            max_length: row
+                .get::<Option<i32>, _>("character_maximum_length")
+                .map(|l| l as usize),
+        })
+        .collect())
+}
+
+/// Insert validated rows inside one transaction.
+///
+/// Rows go in multi-row batches; a failing batch is retried row by row under
+/// savepoints so a single bad row only costs itself. Returns the number of
+/// rows inserted, or `None` if the transaction was rolled back.
+async fn insert_csv_rows(
+    pool: &PgPool,
+    table_name: &str,
+    columns: &[&ImportColumn],
+    rows: &[(usize, Vec<Option<String>>)],
+    options: &CsvImportOptions,
+    row_errors: &mut Vec<CsvRowError>,
+) -> Result<Option<u64>, sqlx::Error> {
+    let rows_per_batch = options
+        .batch_size
+        .clamp(1, (MAX_BIND_PARAMS / columns.len().max(1)).max(1));
+    let mut tx = pool.begin().await?;
+    let mut inserted = 0u64;
+
+    for batch in rows.chunks(rows_per_batch) {
+        let sql = build_csv_insert_sql(table_name, columns, batch.len());
+        let mut query = sqlx::query(&sql);
+        for (_, values) in batch {
+            for value in values {
+                query = query.bind(value.clone());
+            }
+        }
+
+        sqlx::query("SAVEPOINT csv_batch").execute(&mut *tx).await?;
+        match query.execute(&mut *tx).await {
+            Ok(result) => {
+                sqlx::query("RELEASE SAVEPOINT csv_batch")
+                    .execute(&mut *tx)
+                    .await?;
+                inserted += result.rows_affected();
+                continue;
+            }
+            Err(_) => {
+                sqlx::query("ROLLBACK TO SAVEPOINT csv_batch")
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        // Find the offending rows one at a time
+        let sql = build_csv_insert_sql(table_name, columns, 1);
+        for (line, values) in batch {
+            let mut query = sqlx::query(&sql);
+            for value in values {
+                query = query.bind(value.clone());
+            }
+
+            sqlx::query("SAVEPOINT csv_row").execute(&mut *tx).await?;
+            match query.execute(&mut *tx).await {
+                Ok(result) => {
+                    sqlx::query("RELEASE SAVEPOINT csv_row")
+                        .execute(&mut *tx)
+                        .await?;
+                    inserted += result.rows_affected();
+                }
+                Err(e) => {
+                    sqlx::query("ROLLBACK TO SAVEPOINT csv_row")
+                        .execute(&mut *tx)
+                        .await?;
+                    row_errors.push(CsvRowError {
+                        row: *line,
+                        column: None,
+                        message: e.to_string(),
+                    });
+                    if options.on_error == CsvErrorMode::Rollback {
+                        tx.rollback().await?;
+                        return Ok(None);
+                    }
+                }
+            }
+        }
+    }
+
+    tx.commit().await?;
+    Ok(Some(inserted))
+}
+
 // ============================================================================
 // Hook System
 // ============================================================================
@@ -858,12 +1471,90 @@ pub enum DbManagerEvent {
     },
 }
 
+impl DbManagerEvent {
+    /// Event type name, as stored in `dbmanager_audit_log.event_type`
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Self::BeforeQuery { .. } => "before_query",
+            Self::AfterQuery { .. } => "after_query",
+            Self::BeforeTableModify { .. } => "before_table_modify",
+            Self::AfterTableModify { .. } => "after_table_modify",
+            Self::BeforeImport { .. } => "before_import",
+            Self::AfterImport { .. } => "after_import",
+            Self::BeforeExport { .. } => "before_export",
+            Self::AfterExport { .. } => "after_export",
+            Self::SchemaChange { .. } => "schema_change",
+        }
+    }
+
+    /// User that triggered the event, if known
+    pub fn user_id(&self) -> Option<&str> {
+        match self {
+            Self::BeforeQuery { user_id, .. }
+            | Self::AfterQuery { user_id, .. }
+            | Self::BeforeTableModify { user_id, .. }
+            | Self::AfterTableModify { user_id, .. }
+            | Self::BeforeImport { user_id, .. }
+            | Self::AfterImport { user_id, .. }
+            | Self::BeforeExport { user_id, .. }
+            | Self::AfterExport { user_id, .. }
+            | Self::SchemaChange { user_id, .. } => user_id.as_deref(),
+        }
+    }
+}
+
+/// Who triggered an event: extracted from the request for audit logging
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AuditContext {
+    pub user_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// Authenticated admin user id, inserted into request extensions by the
+/// auth layer so audit entries can be attributed
+#[derive(Debug, Clone)]
+pub struct AuditUser(pub String);
+
+#[axum::async_trait]
+impl<S: Send + Sync> axum::extract::FromRequestParts<S> for AuditContext {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let ip_address = crate::middleware::client_ip_from_headers(&parts.headers)
+            .or_else(|| {
+                parts
+                    .extensions
+                    .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+                    .map(|info| info.0.ip())
+            })
+            .map(|ip| ip.to_string());
+
+        Ok(Self {
+            user_id: parts.extensions.get::<AuditUser>().map(|u| u.0.clone()),
+            ip_address,
+            user_agent: parts
+                .headers
+                .get(header::USER_AGENT)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from),
+        })
+    }
+}
+
 /// Hook handler function type
 pub type HookHandler = Arc<dyn Fn(DbManagerEvent) + Send + Sync>;
 
+/// Hook handler that also receives the request's audit context
+pub type ContextHookHandler = Arc<dyn Fn(DbManagerEvent, &AuditContext) + Send + Sync>;
+
 /// Hook registry for managing event handlers
 pub struct HookRegistry {
     handlers: std::sync::RwLock<Vec<HookHandler>>,
+    context_handlers: std::sync::RwLock<Vec<ContextHookHandler>>,
 }
 
 impl HookRegistry {
@@ -871,6 +1562,7 @@ impl HookRegistry {
     pub fn new() -> Self {
         Self {
             handlers: std::sync::RwLock::new(Vec::new()),
+            context_handlers: std::sync::RwLock::new(Vec::new()),
         }
     }
 
@@ -881,18 +1573,36 @@ impl HookRegistry {
         }
     }
 
+    /// Register a hook handler that receives the audit context
+    pub fn register_with_context(&self, handler: ContextHookHandler) {
+        if let Ok(mut handlers) = self.context_handlers.write() {
+            handlers.push(handler);
+        }
+    }
+
     /// Trigger an event to all registered handlers
     pub fn trigger(&self, event: DbManagerEvent) {
+        self.trigger_with_context(event, &AuditContext::default());
+    }
+
+    /// Trigger an event, passing the request's audit context to context handlers
+    pub fn trigger_with_context(&self, event: DbManagerEvent, context: &AuditContext) {
         if let Ok(handlers) = self.handlers.read() {
             for handler in handlers.iter() {
                 handler(event.clone());
             }
         }
+        if let Ok(handlers) = self.context_handlers.read() {
+            for handler in handlers.iter() {
+                handler(event.clone(), context);
+            }
+        }
     }
 
     /// Get the number of registered handlers
     pub fn handler_count(&self) -> usize {
         self.handlers.read().map(|h| h.len()).unwrap_or(0)
+            + self.context_handlers.read().map(|h| h.len()).unwrap_or(0)
     }
 
     /// Clear all handlers (useful for testing)
@@ -900,6 +1610,9 @@ impl HookRegistry {
         if let Ok(mut handlers) = self.handlers.write() {
             handlers.clear();
         }
+        if let Ok(mut handlers) = self.context_handlers.write() {
+            handlers.clear();
+        }
     }
 }
 
@@ -944,6 +1657,135 @@ pub fn trigger_hook(event: DbManagerEvent) {
     hooks().trigger(event);
 }
 
+/// Trigger a hook event with the request's audit context
+pub fn trigger_hook_with_context(event: DbManagerEvent, context: &AuditContext) {
+    hooks().trigger_with_context(event, context);
+}
+
+// ============================================================================
+// Persistent Audit Log Hook
+// ============================================================================
+
+/// Configuration for the built-in audit log hook
+#[derive(Debug, Clone)]
+pub struct AuditLogConfig {
+    /// Record `BeforeQuery` events (the matching `AfterQuery` carries the same query)
+    pub log_before_query: bool,
+    /// Only record successful `AfterQuery` events at least this slow (ms)
+    pub min_query_duration_ms: f64,
+    /// Record one in every N successful `AfterQuery` events that pass the
+    /// duration filter (1 = all)
+    pub query_sample_every: u64,
+    /// Buffered events awaiting insertion; events are dropped when full
+    pub buffer_size: usize,
+}
+
+impl Default for AuditLogConfig {
+    fn default() -> Self {
+        Self {
+            log_before_query: false,
+            min_query_duration_ms: 0.0,
+            query_sample_every: 1,
+            buffer_size: 1024,
+        }
+    }
+}
+
+/// Decides which events the audit log hook persists.
+///
+/// Failed queries and all non-query events are always kept; successful
+/// `AfterQuery` events are filtered by duration and then sampled.
+pub struct AuditSampler {
+    config: AuditLogConfig,
+    query_counter: std::sync::atomic::AtomicU64,
+}
+
+impl AuditSampler {
+    pub fn new(config: AuditLogConfig) -> Self {
+        Self {
+            config,
+            query_counter: std::sync::atomic::AtomicU64::new(0),
+        }
+    }
+
+    /// Whether an event should be written to the audit log
+    pub fn should_record(&self, event: &DbManagerEvent) -> bool {
+        match event {
+            DbManagerEvent::BeforeQuery { .. } => self.config.log_before_query,
+            DbManagerEvent::AfterQuery {
+                success,
+                execution_time_ms,
+                ..
+            } => {
+                if !success {
+                    return true;
+                }
+                if *execution_time_ms < self.config.min_query_duration_ms {
+                    return false;
+                }
+                let every = self.config.query_sample_every.max(1);
+                let n = self
+                    .query_counter
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                n.rem_euclid(every) == 0
+            }
+            _ => true,
+        }
+    }
+}
+
+static AUDIT_HOOK_INSTALLED: std::sync::OnceLock<()> = std::sync::OnceLock::new();
+
+/// Install the hook that persists dbmanager events into `dbmanager_audit_log`.
+///
+/// Events are queued to a background task so handlers never wait on the
+/// insert. Installing more than once is a no-op. Must be called from within
+/// a Tokio runtime.
+pub fn install_audit_log_hook(pool: PgPool, config: AuditLogConfig) {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        tracing::warn!("No Tokio runtime; dbmanager audit log hook not installed");
+        return;
+    };
+
+    if AUDIT_HOOK_INSTALLED.set(()).is_err() {
+        return;
+    }
+
+    let (tx, mut rx) =
+        tokio::sync::mpsc::channel::<(DbManagerEvent, AuditContext)>(config.buffer_size.max(1));
+    let sampler = AuditSampler::new(config);
+
+    runtime.spawn(async move {
+        while let Some((event, context)) = rx.recv().await {
+            let event_data = serde_json::to_value(&event).unwrap_or(serde_json::Value::Null);
+            let user_id = context
+                .user_id
+                .as_deref()
+                .or_else(|| event.user_id())
+                .map(String::from);
+
+            if let Err(e) = write_audit_log(
+                &pool,
+                event.event_type(),
+                event_data,
+                user_id.as_deref(),
+                context.ip_address.as_deref(),
+                context.user_agent.as_deref(),
+            )
+            .await
+            {
+                tracing::warn!("Failed to write dbmanager audit log: {}", e);
+            }
+        }
+    });
+
+    hooks().register_with_context(Arc::new(move |event, context| {
+        if sampler.should_record(&event) && tx.try_send((event, context.clone())).is_err() {
+            tracing::warn!("dbmanager audit log buffer full; dropping event");
+        }
+    }));
+}
+
 // ============================================================================
 // Handlers - Status & Info
 // ============================================================================
@@ -1705,6 +2547,7 @@ pub async fn get_table_foreign_keys(
 /// Create a new table
 pub async fn create_table(
     State(state): State<Arc<DbManagerState>>,
+    audit: AuditContext,
     Json(request): Json<CreateTableRequest>,
 ) -> impl IntoResponse {
     // Validate table name (prevent SQL injection)
@@ -1753,7 +2596,19 @@ pub async fn create_table(
         columns_sql.join(", ")
     );
 
-    match sqlx::query(&sql).execute(&state.pool).await {
+    let result = sqlx::query(&sql).execute(&state.pool).await;
+
+    trigger_hook_with_context(
+        DbManagerEvent::SchemaChange {
+            operation: "CREATE".to_string(),
+            table_name: request.name.clone(),
+            success: result.is_ok(),
+            user_id: audit.user_id.clone(),
+        },
+        &audit,
+    );
+
+    match result {
         Ok(_) => Json(ApiResponse {
             success: true,
             data: Some(format!("Table '{}' created successfully", request.name)),
@@ -1771,6 +2626,7 @@ pub async fn create_table(
 pub async fn alter_table(
     State(state): State<Arc<DbManagerState>>,
     Path(table_name): Path<String>,
+    audit: AuditContext,
     Json(request): Json<AlterTableRequest>,
 ) -> impl IntoResponse {
     if !is_valid_identifier(&table_name) {
@@ -1899,7 +2755,19 @@ pub async fn alter_table(
 
     if !statements.is_empty() {
         let sql = format!("ALTER TABLE \"{}\" {}", table_name, statements.join(", "));
-        if let Err(e) = sqlx::query(&sql).execute(&state.pool).await {
+        let result = sqlx::query(&sql).execute(&state.pool).await;
+
+        trigger_hook_with_context(
+            DbManagerEvent::SchemaChange {
+                operation: "ALTER".to_string(),
+                table_name: table_name.clone(),
+                success: result.is_ok(),
+                user_id: audit.user_id.clone(),
+            },
+            &audit,
+        );
+
+        if let Err(e) = result {
             return Json(ApiResponse {
                 success: false,
                 data: None,
@@ -1928,6 +2796,7 @@ pub async fn drop_table(
     State(state): State<Arc<DbManagerState>>,
     Path(table_name): Path<String>,
     Query(params): Query<ConfirmQuery>,
+    audit: AuditContext,
 ) -> impl IntoResponse {
     if !is_valid_identifier(&table_name) {
         return Json(ApiResponse {
@@ -1951,7 +2820,19 @@ pub async fn drop_table(
     }
 
     let sql = format!("DROP TABLE IF EXISTS \"{}\" CASCADE", table_name);
-    match sqlx::query(&sql).execute(&state.pool).await {
+    let result = sqlx::query(&sql).execute(&state.pool).await;
+
+    trigger_hook_with_context(
+        DbManagerEvent::SchemaChange {
+            operation: "DROP".to_string(),
+            table_name: table_name.clone(),
+            success: result.is_ok(),
+            user_id: audit.user_id.clone(),
+        },
+        &audit,
+    );
+
+    match result {
         Ok(_) => Json(ApiResponse {
             success: true,
             data: Some(format!("Table '{}' dropped successfully", table_name)),
@@ -1970,6 +2851,7 @@ pub async fn truncate_table(
     State(state): State<Arc<DbManagerState>>,
     Path(table_name): Path<String>,
     Query(params): Query<ConfirmQuery>,
+    audit: AuditContext,
 ) -> impl IntoResponse {
     if !is_valid_identifier(&table_name) {
         return Json(ApiResponse {
@@ -1992,11 +2874,34 @@ pub async fn truncate_table(
         });
     }
 
+    trigger_hook_with_context(
+        DbManagerEvent::BeforeTableModify {
+            table_name: table_name.clone(),
+            operation: "TRUNCATE".to_string(),
+            user_id: audit.user_id.clone(),
+        },
+        &audit,
+    );
+
     let sql = format!("TRUNCATE TABLE \"{}\" RESTART IDENTITY CASCADE", table_name);
-    match sqlx::query(&sql).execute(&state.pool).await {
-        Ok(_) => Json(ApiResponse {
-            success: true,
-            data: Some(format!("Table '{}' truncated successfully", table_name)),
+    let result = sqlx::query(&sql).execute(&state.pool).await;
+
+    trigger_hook_with_context(
+        DbManagerEvent::AfterTableModify {
+            table_name: table_name.clone(),
+            operation: "TRUNCATE".to_string(),
+            success: result.is_ok(),
+            affected_rows: None,
+            error: result.as_ref().err().map(|e| e.to_string()),
+            user_id: audit.user_id.clone(),
+        },
+        &audit,
+    );
+
+    match result {
+        Ok(_) => Json(ApiResponse {
+            success: true,
+            data: Some(format!("Table '{}' truncated successfully", table_name)),
             error: None,
         }),
         Err(e) => Json(ApiResponse {
@@ -2057,78 +2962,108 @@ async fn get_primary_key_column(pool: &sqlx::PgPool, table_name: &str) -> String
 // Handlers - Data Operations
 // ============================================================================
 
-/// Get table data with pagination
-pub async fn get_table_data(
-    State(state): State<Arc<DbManagerState>>,
-    Path(table_name): Path<String>,
-    Query(params): Query<TableDataQuery>,
-) -> impl IntoResponse {
-    if !is_valid_identifier(&table_name) {
-        return Json(ApiResponse {
-            success: false,
-            data: None,
-            error: Some("Invalid table name".to_string()),
-        });
+/// Default page size for table browsing
+pub const DEFAULT_TABLE_DATA_LIMIT: u32 = 50;
+
+/// Hard cap on rows returned per page when browsing a table
+pub const MAX_TABLE_DATA_LIMIT: u32 = 1000;
+
+/// Column types included in the "search all text columns" path
+const SEARCHABLE_COLUMN_TYPES: &[&str] = &[
+    "character varying",
+    "text",
+    "character",
+    "char",
+    "varchar",
+    "uuid",
+];
+
+/// SQL and bind values for one page of table data
+#[derive(Debug, Clone, PartialEq)]
+struct TableDataSql {
+    count_sql: String,
+    data_sql: String,
+    /// Text values bound as `$1..$n` in both statements
+    params: Vec<String>,
+    limit: u32,
+    offset: i64,
+    page: u32,
+}
+
+/// Escape `%`, `_` and `\` so a user value is matched literally by ILIKE
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Build the count and data statements for browsing a table.
+///
+/// `columns` is the table's real `(name, data_type)` list; sort and filter
+/// columns must appear in it. All user supplied values (filter, search,
+/// limit, offset) are bound as parameters, never interpolated.
+fn build_table_data_sql(
+    table_name: &str,
+    columns: &[(String, String)],
+    params: &TableDataQuery,
+) -> Result<TableDataSql, String> {
+    if !is_valid_identifier(table_name) {
+        return Err("Invalid table name".to_string());
     }
 
-    let page = params.page.unwrap_or(1);
-    let limit = params.limit.unwrap_or(50).min(1000); // Cap at 1000
-    let offset = (page - 1) * limit;
+    let known_column = |name: &str| columns.iter().any(|(col, _)| col == name);
+
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_TABLE_DATA_LIMIT)
+        .clamp(1, MAX_TABLE_DATA_LIMIT);
+    let offset = (page as i64 - 1) * limit as i64;
 
-    // Build WHERE clause with proper parameterization
     let mut where_clauses: Vec<String> = Vec::new();
-    let mut param_values: Vec<String> = Vec::new();
-    let mut param_idx = 1u32;
+    let mut values: Vec<String> = Vec::new();
 
     // Filter by specific column
     if let (Some(filter_col), Some(filter_val)) = (&params.filter_column, &params.filter_value) {
-        if !filter_val.is_empty() && is_valid_identifier(filter_col) {
+        if !filter_val.is_empty() {
+            if !is_valid_identifier(filter_col) || !known_column(filter_col) {
+                return Err(format!("Unknown filter column: {}", filter_col));
+            }
+            values.push(format!("%{}%", escape_like(filter_val)));
             where_clauses.push(format!(
-                "CAST(\"{}\" AS TEXT) ILIKE ${}",
-                filter_col, param_idx
+                "CAST(\"{}\" AS TEXT) ILIKE ${} ESCAPE '\\'",
+                filter_col,
+                values.len()
             ));
-            param_values.push(format!("%{}%", filter_val));
-            param_idx += 1;
         }
     }
 
     // Search across all text columns
-    if let Some(search) = &params.search {
-        if !search.is_empty() {
-            // Get text columns for the table
-            let columns_query = r#"
-                SELECT column_name, data_type
-                FROM information_schema.columns
-                WHERE table_name = $1 AND table_schema = 'public'
-                AND data_type IN ('character varying', 'text', 'char', 'varchar', 'uuid')
-            "#;
-
-            let text_columns: Vec<String> =
-                match sqlx::query_as::<_, (String, String)>(columns_query)
-                    .bind(&table_name)
-                    .fetch_all(&state.pool)
-                    .await
-                {
-                    Ok(cols) => cols.into_iter().map(|(name, _)| name).collect(),
-                    Err(_) => vec!["id".to_string()], // Fallback to id
-                };
-
-            if !text_columns.is_empty() {
-                let search_conditions: Vec<String> = text_columns
-                    .iter()
-                    .filter(|col| is_valid_identifier(col))
-                    .map(|col| format!("CAST(\"{}\" AS TEXT) ILIKE ${}", col, param_idx))
-                    .collect();
+    if let Some(search) = params.search.as_deref().filter(|s| !s.is_empty()) {
+        let text_columns: Vec<&str> = columns
+            .iter()
+            .filter(|(name, data_type)| {
+                is_valid_identifier(name) && SEARCHABLE_COLUMN_TYPES.contains(&data_type.as_str())
+            })
+            .map(|(name, _)| name.as_str())
+            .collect();
 
-                if !search_conditions.is_empty() {
-                    where_clauses.push(format!("({})", search_conditions.join(" OR ")));
-                    param_values.push(format!("%{}%", search));
-                    #[allow(unused_assignments)]
-                    {
-                        param_idx += 1;
-                    }
-                }
-            }
+        if text_columns.is_empty() {
+            // Nothing can match a text search
+            where_clauses.push("FALSE".to_string());
+        } else {
+            values.push(format!("%{}%", escape_like(search)));
+            let idx = values.len();
+            let conditions: Vec<String> = text_columns
+                .iter()
+                .map(|col| format!("CAST(\"{}\" AS TEXT) ILIKE ${} ESCAPE '\\'", col, idx))
+                .collect();
+            where_clauses.push(format!("({})", conditions.join(" OR ")));
         }
     }
 
@@ -2138,50 +3073,125 @@ pub async fn get_table_data(
         format!(" WHERE {}", where_clauses.join(" AND "))
     };
 
-    // Get total count with filter
-    let count_sql = format!(
-        "SELECT COUNT(*) as count FROM \"{}\"{}",
-        table_name, where_clause
-    );
-    let mut count_query = sqlx::query_scalar::<_, i64>(&count_sql);
-    for value in &param_values {
-        count_query = count_query.bind(value);
+    let count_sql = format!("SELECT COUNT(*) FROM \"{}\"{}", table_name, where_clause);
+
+    let mut data_sql = format!("SELECT * FROM \"{}\"{}", table_name, where_clause);
+
+    if let Some(sort_col) = params.sort_column.as_deref().filter(|s| !s.is_empty()) {
+        if !is_valid_identifier(sort_col) || !known_column(sort_col) {
+            return Err(format!("Unknown sort column: {}", sort_col));
+        }
+        let order = match params.sort_order.as_deref() {
+            Some(order) if order.eq_ignore_ascii_case("DESC") => "DESC",
+            _ => "ASC",
+        };
+        data_sql.push_str(&format!(" ORDER BY \"{}\" {}", sort_col, order));
+    }
+
+    data_sql.push_str(&format!(
+        " LIMIT ${} OFFSET ${}",
+        values.len() + 1,
+        values.len() + 2
+    ));
+
+    Ok(TableDataSql {
+        count_sql,
+        data_sql,
+        params: values,
+        limit,
+        offset,
+        page,
+    })
+}
+
+/// Get all `(column_name, data_type)` pairs for a public table
+async fn get_table_column_types(
+    pool: &sqlx::PgPool,
+    table_name: &str,
+) -> Result<Vec<(String, String)>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT column_name::text, data_type::text
+        FROM information_schema.columns
+        WHERE table_name = $1 AND table_schema = 'public'
+        ORDER BY ordinal_position
+    "#,
+    )
+    .bind(table_name)
+    .fetch_all(pool)
+    .await
+}
+
+/// Get table data with pagination
+pub async fn get_table_data(
+    State(state): State<Arc<DbManagerState>>,
+    Path(table_name): Path<String>,
+    Query(params): Query<TableDataQuery>,
+) -> impl IntoResponse {
+    if !is_valid_identifier(&table_name) {
+        return Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some("Invalid table name".to_string()),
+        });
     }
 
-    let total: i64 = match count_query.fetch_one(&state.pool).await {
-        Ok(count) => count,
+    // Sort/filter/search columns are checked against the real column list
+    let columns = match get_table_column_types(&state.pool, &table_name).await {
+        Ok(columns) if !columns.is_empty() => columns,
+        Ok(_) => {
+            return Json(ApiResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Table '{}' not found", table_name)),
+            });
+        }
         Err(e) => {
             return Json(ApiResponse {
                 success: false,
                 data: None,
-                error: Some(format!("Failed to count rows: {}", e)),
+                error: Some(format!("Failed to read table columns: {}", e)),
             });
         }
     };
 
-    // Build data query with sorting
-    let mut sql = format!("SELECT * FROM \"{}\"{}", table_name, where_clause);
-
-    if let Some(sort_col) = &params.sort_column {
-        if is_valid_identifier(sort_col) {
-            let order = params.sort_order.as_deref().unwrap_or("ASC");
-            let order = if order.to_uppercase() == "DESC" {
-                "DESC"
-            } else {
-                "ASC"
-            };
-            sql.push_str(&format!(" ORDER BY \"{}\" {}", sort_col, order));
+    let sql = match build_table_data_sql(&table_name, &columns, &params) {
+        Ok(sql) => sql,
+        Err(e) => {
+            return Json(ApiResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            });
         }
+    };
+
+    // Get total count with the same filters
+    let mut count_query = sqlx::query_scalar::<_, i64>(&sql.count_sql);
+    for value in &sql.params {
+        count_query = count_query.bind(value);
     }
 
-    sql.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset));
+    let total: i64 = match count_query.fetch_one(&state.pool).await {
+        Ok(count) => count,
+        Err(e) => {
+            return Json(ApiResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Failed to count rows: {}", e)),
+            });
+        }
+    };
 
-    let mut data_query = sqlx::query(&sql);
-    for value in &param_values {
+    let mut data_query = sqlx::query(&sql.data_sql);
+    for value in &sql.params {
         data_query = data_query.bind(value);
     }
-
-    let rows = data_query.fetch_all(&state.pool).await;
+    let rows = data_query
+        .bind(sql.limit as i64)
+        .bind(sql.offset)
+        .fetch_all(&state.pool)
+        .await;
 
     // Get primary key columns for this table
     let pk_columns = get_primary_key_columns(&state.pool, &table_name)
@@ -2208,8 +3218,8 @@ pub async fn get_table_data(
                 data: Some(TableDataResponse {
                     rows: data_rows,
                     total: total as u64,
-                    page,
-                    page_size: limit,
+                    page: sql.page,
+                    page_size: sql.limit,
                     primary_key_columns: pk_columns,
                 }),
                 error: None,
@@ -2597,6 +3607,7 @@ pub async fn bulk_delete_rows(
 /// Execute a SQL query
 pub async fn execute_query(
     State(state): State<Arc<DbManagerState>>,
+    audit: AuditContext,
     Json(request): Json<ExecuteQueryRequest>,
 ) -> impl IntoResponse {
     let start = std::time::Instant::now();
@@ -2659,109 +3670,38 @@ pub async fn execute_query(
         || query_upper.starts_with("EXPLAIN");
 
     // Trigger BeforeQuery hook
-    trigger_hook(DbManagerEvent::BeforeQuery {
-        query: query.to_string(),
-        user_id: None, // TODO: Extract from auth context when available
-    });
-
-    // Query timeout (30 seconds default)
-    let timeout_duration = std::time::Duration::from_secs(30);
-
-    let result = if is_select {
-        // Execute SELECT query with timeout
-        let query_future = sqlx::query(query).fetch_all(&state.pool);
-        match tokio::time::timeout(timeout_duration, query_future).await {
-            Ok(Ok(rows)) => {
-                let mut columns = Vec::new();
-                let mut data_rows = Vec::new();
-
-                if !rows.is_empty() {
-                    // Get column names from first row
-                    columns = rows[0].columns().iter().map(|c| c.name().to_string()).collect();
-
-                    // Convert rows to JSON
-                    for row in &rows {
-                        let mut map = HashMap::new();
-                        for col in row.columns() {
-                            let value = convert_pg_value_to_json(row, col);
-                            map.insert(col.name().to_string(), value);
-                        }
-                        data_rows.push(map);
-                    }
-                }
+    trigger_hook_with_context(
+        DbManagerEvent::BeforeQuery {
+            query: query.to_string(),
+            user_id: audit.user_id.clone(),
+        },
+        &audit,
+    );
 
-                QueryResult {
-                    columns,
-                    rows: data_rows.clone(),
-                    row_count: data_rows.len() as u64,
-                    execution_time: start.elapsed().as_secs_f64() * 1000.0,
-                    status: "success".to_string(),
-                    error: None,
-                    affected_rows: None,
-                }
-            }
-            Ok(Err(e)) => QueryResult {
-                columns: vec![],
-                rows: vec![],
-                row_count: 0,
-                execution_time: start.elapsed().as_secs_f64() * 1000.0,
-                status: "error".to_string(),
-                error: Some(e.to_string()),
-                affected_rows: None,
-            },
-            Err(_) => QueryResult {
-                columns: vec![],
-                rows: vec![],
-                row_count: 0,
-                execution_time: start.elapsed().as_secs_f64() * 1000.0,
-                status: "error".to_string(),
-                error: Some(format!("Query timed out after {} seconds. Consider optimizing your query or adding appropriate indexes.", timeout_duration.as_secs())),
-                affected_rows: None,
-            },
-        }
-    } else {
-        // Execute non-SELECT query with timeout (INSERT, UPDATE, DELETE, CREATE, etc.)
-        let query_future = sqlx::query(query).execute(&state.pool);
-        match tokio::time::timeout(timeout_duration, query_future).await {
-            Ok(Ok(result)) => QueryResult {
-                columns: vec![],
-                rows: vec![],
-                row_count: 0,
-                execution_time: start.elapsed().as_secs_f64() * 1000.0,
-                status: "success".to_string(),
-                error: None,
-                affected_rows: Some(result.rows_affected()),
-            },
-            Ok(Err(e)) => QueryResult {
-                columns: vec![],
-                rows: vec![],
-                row_count: 0,
-                execution_time: start.elapsed().as_secs_f64() * 1000.0,
-                status: "error".to_string(),
-                error: Some(e.to_string()),
-                affected_rows: None,
-            },
-            Err(_) => QueryResult {
-                columns: vec![],
-                rows: vec![],
-                row_count: 0,
-                execution_time: start.elapsed().as_secs_f64() * 1000.0,
-                status: "error".to_string(),
-                error: Some(format!(
-                    "Query timed out after {} seconds. Consider optimizing your query.",
-                    timeout_duration.as_secs()
-                )),
-                affected_rows: None,
-            },
-        }
-    };
+    let history_id = request.query_id.unwrap_or_else(uuid::Uuid::new_v4);
+    let timeout = state.effective_timeout(request.timeout_ms);
 
-    // Add to history (persist to database)
+    // Record the query as running so it can be found and cancelled
     let _ = sqlx::query(r#"
-        INSERT INTO dbmanager_query_history (query, execution_time_ms, status, row_count, error_message, executed_at)
-        VALUES ($1, $2, $3, $4, $5, NOW())
+        INSERT INTO dbmanager_query_history (id, query, execution_time_ms, status, row_count, error_message, executed_at)
+        VALUES ($1, $2, 0, 'running', NULL, NULL, NOW())
     "#)
+    .bind(history_id)
     .bind(query.chars().take(10000).collect::<String>()) // Store up to 10k chars
+    .execute(&state.pool)
+    .await;
+
+    let result = run_query_with_timeout(&state, history_id, query, is_select, timeout, start).await;
+
+    // Record the outcome (success, error, timeout or cancelled)
+    let _ = sqlx::query(
+        r#"
+        UPDATE dbmanager_query_history
+        SET execution_time_ms = $2, status = $3, row_count = $4, error_message = $5
+        WHERE id = $1
+    "#,
+    )
+    .bind(history_id)
     .bind(result.execution_time)
     .bind(&result.status)
     .bind(result.row_count as i64)
@@ -2786,18 +3726,21 @@ pub async fn execute_query(
     let success = result.status == "success";
 
     // Trigger AfterQuery hook
-    trigger_hook(DbManagerEvent::AfterQuery {
-        query: query.to_string(),
-        success,
-        execution_time_ms: result.execution_time,
-        row_count: if success {
-            Some(result.row_count)
-        } else {
-            None
+    trigger_hook_with_context(
+        DbManagerEvent::AfterQuery {
+            query: query.to_string(),
+            success,
+            execution_time_ms: result.execution_time,
+            row_count: if success {
+                Some(result.row_count)
+            } else {
+                None
+            },
+            error: result.error.clone(),
+            user_id: audit.user_id.clone(),
         },
-        error: result.error.clone(),
-        user_id: None,
-    });
+        &audit,
+    );
 
     Json(ApiResponse {
         success,
@@ -2806,14 +3749,261 @@ pub async fn execute_query(
     })
 }
 
+/// PostgreSQL SQLSTATE for a statement cancelled by timeout or by request
+const QUERY_CANCELED_SQLSTATE: &str = "57014";
+
+/// Run a query on a dedicated connection with a server-side `statement_timeout`.
+///
+/// The backend pid is registered under `history_id` while the query runs so
+/// [`cancel_query`] can reach it. The timeout is applied regardless of the
+/// statement kind, including confirmed destructive operations. A client-side
+/// deadline slightly past the server timeout guards against a stuck
+/// connection.
+async fn run_query_with_timeout(
+    state: &DbManagerState,
+    history_id: uuid::Uuid,
+    query: &str,
+    is_select: bool,
+    timeout: std::time::Duration,
+    start: std::time::Instant,
+) -> QueryResult {
+    let failed = |status: &str, error: String| QueryResult {
+        columns: vec![],
+        rows: vec![],
+        row_count: 0,
+        execution_time: start.elapsed().as_secs_f64() * 1000.0,
+        status: status.to_string(),
+        error: Some(error),
+        affected_rows: None,
+    };
+
+    let mut conn = match state.pool.acquire().await {
+        Ok(conn) => conn,
+        Err(e) => return failed("error", format!("Failed to acquire connection: {}", e)),
+    };
+
+    let timeout_ms = timeout.as_millis() as u64;
+    if let Err(e) = sqlx::query(&format!("SET statement_timeout = {}", timeout_ms))
+        .execute(&mut *conn)
+        .await
+    {
+        return failed("error", format!("Failed to set statement timeout: {}", e));
+    }
+
+    if let Ok(pid) = sqlx::query_scalar::<_, i32>("SELECT pg_backend_pid()")
+        .fetch_one(&mut *conn)
+        .await
+    {
+        state.running_queries.lock().unwrap().insert(
+            history_id,
+            RunningQuery {
+                backend_pid: pid,
+                cancelled: false,
+            },
+        );
+    }
+
+    let deadline = timeout + std::time::Duration::from_secs(5);
+    let outcome = if is_select {
+        tokio::time::timeout(deadline, sqlx::query(query).fetch_all(&mut *conn))
+            .await
+            .map(|r| r.map(Ok))
+    } else {
+        tokio::time::timeout(deadline, sqlx::query(query).execute(&mut *conn))
+            .await
+            .map(|r| r.map(Err))
+    };
+
+    let running = state.running_queries.lock().unwrap().remove(&history_id);
+    let cancelled = running.as_ref().is_some_and(|r| r.cancelled);
+
+    let result = match outcome {
+        Ok(Ok(Ok(rows))) => {
+            let columns = rows
+                .first()
+                .map(|row| row.columns().iter().map(|c| c.name().to_string()).collect())
+                .unwrap_or_default();
+
+            let data_rows: Vec<HashMap<String, serde_json::Value>> = rows
+                .iter()
+                .map(|row| {
+                    let mut map = HashMap::new();
+                    for col in row.columns() {
+                        map.insert(col.name().to_string(), convert_pg_value_to_json(row, col));
+                    }
+                    map
+                })
+                .collect();
+
+            QueryResult {
+                columns,
+                row_count: data_rows.len() as u64,
+                rows: data_rows,
+                execution_time: start.elapsed().as_secs_f64() * 1000.0,
+                status: "success".to_string(),
+                error: None,
+                affected_rows: None,
+            }
+        }
+        Ok(Ok(Err(done))) => QueryResult {
+            columns: vec![],
+            rows: vec![],
+            row_count: 0,
+            execution_time: start.elapsed().as_secs_f64() * 1000.0,
+            status: "success".to_string(),
+            error: None,
+            affected_rows: Some(done.rows_affected()),
+        },
+        Ok(Err(e)) => {
+            let canceled = e
+                .as_database_error()
+                .and_then(|db| db.code())
+                .is_some_and(|code| code == QUERY_CANCELED_SQLSTATE);
+
+            if canceled && cancelled {
+                failed("cancelled", "Query was cancelled".to_string())
+            } else if canceled {
+                failed(
+                    "timeout",
+                    format!(
+                        "Query exceeded the statement timeout of {} ms and was cancelled. \
+                        Consider optimizing your query or adding appropriate indexes.",
+                        timeout_ms
+                    ),
+                )
+            } else {
+                failed("error", e.to_string())
+            }
+        }
+        Err(_) => {
+            // The connection did not return in time; cancel on the server and drop it
+            if let Some(running) = running {
+                let _ = sqlx::query("SELECT pg_cancel_backend($1)")
+                    .bind(running.backend_pid)
+                    .execute(&state.pool)
+                    .await;
+            }
+            let _ = conn.detach();
+            return failed(
+                "timeout",
+                format!("Query timed out after {} ms", timeout_ms),
+            );
+        }
+    };
+
+    // Don't hand a connection with a custom timeout back to the pool
+    if sqlx::query("RESET statement_timeout")
+        .execute(&mut *conn)
+        .await
+        .is_err()
+    {
+        let _ = conn.detach();
+    }
+
+    result
+}
+
+/// Cancel an in-flight query by its history id
+pub async fn cancel_query(
+    State(state): State<Arc<DbManagerState>>,
+    Path(query_id): Path<uuid::Uuid>,
+) -> impl IntoResponse {
+    let pid = {
+        let mut running = state.running_queries.lock().unwrap();
+        match running.get_mut(&query_id) {
+            Some(query) => {
+                query.cancelled = true;
+                query.backend_pid
+            }
+            None => {
+                return Json(ApiResponse {
+                    success: false,
+                    data: None,
+                    error: Some("Query is not running".to_string()),
+                });
+            }
+        }
+    };
+
+    match sqlx::query_scalar::<_, bool>("SELECT pg_cancel_backend($1)")
+        .bind(pid)
+        .fetch_one(&state.pool)
+        .await
+    {
+        Ok(true) => Json(ApiResponse {
+            success: true,
+            data: Some(serde_json::json!({ "id": query_id, "cancelled": true })),
+            error: None,
+        }),
+        Ok(false) => Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some("Query could not be cancelled".to_string()),
+        }),
+        Err(e) => Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!("Failed to cancel query: {}", e)),
+        }),
+    }
+}
+
+impl DbManagerState {
+    /// Produce a structured plan for a query.
+    ///
+    /// Without `analyze` the statement is only planned, inside a read-only
+    /// transaction, so it can never mutate data. With `analyze` it is executed
+    /// under the configured statement timeout and the transaction is rolled
+    /// back afterwards; callers must gate this behind confirmation.
+    pub async fn explain(&self, query: &str, analyze: bool) -> Result<QueryExplain, String> {
+        let start = std::time::Instant::now();
+
+        let options = if analyze {
+            "ANALYZE, BUFFERS, VERBOSE, FORMAT JSON"
+        } else {
+            "VERBOSE, FORMAT JSON"
+        };
+        let explain_sql = format!("EXPLAIN ({}) {}", options, query);
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        if !analyze {
+            sqlx::query("SET TRANSACTION READ ONLY")
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to start read-only transaction: {}", e))?;
+        }
+
+        sqlx::query(&format!(
+            "SET LOCAL statement_timeout = {}",
+            self.query_timeout.as_millis()
+        ))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to set statement timeout: {}", e))?;
+
+        let output = sqlx::query_scalar::<_, serde_json::Value>(&explain_sql)
+            .fetch_one(&mut *tx)
+            .await;
+
+        // Never keep the effects of an analyzed statement
+        let _ = tx.rollback().await;
+
+        let output = output.map_err(|e| format!("Failed to explain query: {}", e))?;
+        parse_explain_json(&output, analyze, start.elapsed().as_secs_f64() * 1000.0)
+    }
+}
+
 /// Explain a query
 pub async fn explain_query(
     State(state): State<Arc<DbManagerState>>,
-    Json(request): Json<ExecuteQueryRequest>,
+    Json(request): Json<ExplainQueryRequest>,
 ) -> impl IntoResponse {
-    let start = std::time::Instant::now();
-
-    let query = request.query.trim();
+    let query = request.query.trim().trim_end_matches(';');
     if query.is_empty() {
         return Json(ApiResponse {
             success: false,
@@ -2822,32 +4012,43 @@ pub async fn explain_query(
         });
     }
 
-    // Build EXPLAIN ANALYZE query
-    let explain_query = format!("EXPLAIN (ANALYZE, BUFFERS, FORMAT TEXT) {}", query);
-
-    match sqlx::query(&explain_query).fetch_all(&state.pool).await {
-        Ok(rows) => {
-            let plan: String = rows
-                .iter()
-                .filter_map(|row| row.try_get::<String, _>(0).ok())
-                .collect::<Vec<_>>()
-                .join("\n");
-
-            let explain = QueryExplain {
-                plan,
-                execution_time: start.elapsed().as_secs_f64() * 1000.0,
-            };
-
-            Json(ApiResponse {
-                success: true,
-                data: Some(explain),
-                error: None,
-            })
-        }
+    if let Some(blocked_cmd) = is_blocked_query(query) {
+        return Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!(
+                "Blocked command: '{}' is not allowed for security reasons.",
+                blocked_cmd
+            )),
+        });
+    }
+
+    // ANALYZE executes the statement, so it gets the same guard as destructive queries
+    if request.analyze
+        && (is_destructive_query(query) || !is_read_only_query(query))
+        && !request.confirm
+    {
+        return Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(
+                "EXPLAIN ANALYZE executes the statement, which modifies data. \
+                Please set confirm=true in the request to proceed."
+                    .to_string(),
+            ),
+        });
+    }
+
+    match state.explain(query, request.analyze).await {
+        Ok(explain) => Json(ApiResponse {
+            success: true,
+            data: Some(explain),
+            error: None,
+        }),
         Err(e) => Json(ApiResponse {
             success: false,
             data: None,
-            error: Some(format!("Failed to explain query: {}", e)),
+            error: Some(e),
         }),
     }
 }
@@ -3037,8 +4238,35 @@ pub async fn delete_saved_query(
 /// Export tables
 pub async fn export_tables(
     State(state): State<Arc<DbManagerState>>,
+    audit: AuditContext,
     Json(request): Json<ExportTablesRequest>,
 ) -> impl IntoResponse {
+    trigger_hook_with_context(
+        DbManagerEvent::BeforeExport {
+            export_format: request.format.clone(),
+            tables: request.tables.clone(),
+            user_id: audit.user_id.clone(),
+        },
+        &audit,
+    );
+
+    let response = export_tables_content(&state, &request).await;
+
+    trigger_hook_with_context(
+        DbManagerEvent::AfterExport {
+            export_format: request.format.clone(),
+            tables: request.tables.clone(),
+            success: response.status().is_success(),
+            error: None,
+            user_id: audit.user_id.clone(),
+        },
+        &audit,
+    );
+
+    response
+}
+
+async fn export_tables_content(state: &DbManagerState, request: &ExportTablesRequest) -> Response {
     let mut content = String::new();
     let include_structure = request.include_structure.unwrap_or(true);
     let include_data = request.include_data.unwrap_or(true);
@@ -3345,8 +4573,18 @@ pub async fn export_query_result(
 /// Import SQL file
 pub async fn import_sql(
     State(state): State<Arc<DbManagerState>>,
+    audit: AuditContext,
     mut multipart: Multipart,
 ) -> impl IntoResponse {
+    trigger_hook_with_context(
+        DbManagerEvent::BeforeImport {
+            import_type: "sql".to_string(),
+            table_name: None,
+            user_id: audit.user_id.clone(),
+        },
+        &audit,
+    );
+
     let mut result = ImportSqlResult {
         success: true,
         total_statements: 0,
@@ -3394,6 +4632,18 @@ pub async fn import_sql(
         result.success = false;
     }
 
+    trigger_hook_with_context(
+        DbManagerEvent::AfterImport {
+            import_type: "sql".to_string(),
+            success: result.success,
+            table_name: None,
+            rows_imported: Some(result.successful_statements as u64),
+            error: result.errors.as_ref().map(|e| e.join("; ")),
+            user_id: audit.user_id.clone(),
+        },
+        &audit,
+    );
+
     Json(ApiResponse {
         success: result.success,
         data: Some(result),
@@ -3402,16 +4652,21 @@ pub async fn import_sql(
 }
 
 /// Import CSV file
+///
+/// Multipart fields: `file`, `table`, and optionally `mapping` (JSON object of
+/// CSV header to column name, `""` to skip), `onError` (`skip` or `rollback`)
+/// and `batchSize`.
 pub async fn import_csv(
     State(state): State<Arc<DbManagerState>>,
+    audit: AuditContext,
     mut multipart: Multipart,
 ) -> impl IntoResponse {
     let mut table_name = String::new();
     let mut csv_content = Vec::new();
+    let mut options = CsvImportOptions::default();
     let mut result = ImportCsvResult {
         success: true,
-        rows_imported: 0,
-        errors: None,
+        ..Default::default()
     };
     let mut errors = Vec::new();
 
@@ -3431,6 +4686,33 @@ pub async fn import_csv(
                     table_name = text;
                 }
             }
+            Some("mapping") => {
+                if let Ok(text) = field.text().await {
+                    match serde_json::from_str(&text) {
+                        Ok(mapping) => options.mapping = mapping,
+                        Err(e) => {
+                            result.success = false;
+                            errors.push(format!("Invalid column mapping: {}", e));
+                        }
+                    }
+                }
+            }
+            Some("onError") => {
+                if let Ok(text) = field.text().await {
+                    match serde_json::from_value(serde_json::Value::String(text.clone())) {
+                        Ok(mode) => options.on_error = mode,
+                        Err(_) => {
+                            result.success = false;
+                            errors.push(format!("Invalid onError mode: {}", text));
+                        }
+                    }
+                }
+            }
+            Some("batchSize") => {
+                if let Ok(size) = field.text().await.unwrap_or_default().parse() {
+                    options.batch_size = size;
+                }
+            }
             _ => {}
         }
     }
@@ -3439,9 +4721,8 @@ pub async fn import_csv(
         return Json(ApiResponse {
             success: false,
             data: Some(ImportCsvResult {
-                success: false,
-                rows_imported: 0,
                 errors: Some(vec!["Table name is required".to_string()]),
+                ..Default::default()
             }),
             error: None,
         });
@@ -3451,70 +4732,53 @@ pub async fn import_csv(
         return Json(ApiResponse {
             success: false,
             data: Some(ImportCsvResult {
-                success: false,
-                rows_imported: 0,
                 errors: Some(vec!["Invalid table name".to_string()]),
+                ..Default::default()
             }),
             error: None,
         });
     }
 
-    if !csv_content.is_empty() && result.success {
-        let content = String::from_utf8_lossy(&csv_content);
-        let parser = CsvParser::new();
-
-        match parser.parse(&content) {
-            Ok((headers, rows)) => {
-                // Validate headers are valid identifiers
-                for header in &headers {
-                    if !is_valid_identifier(header) {
-                        errors.push(format!("Invalid column name: {}", header));
-                    }
-                }
-
-                if errors.is_empty() {
-                    let columns: Vec<String> =
-                        headers.iter().map(|h| format!("\"{}\"", h)).collect();
-                    let placeholders: Vec<String> =
-                        (1..=headers.len()).map(|i| format!("${}", i)).collect();
-
-                    let sql = format!(
-                        "INSERT INTO \"{}\" ({}) VALUES ({})",
-                        table_name,
-                        columns.join(", "),
-                        placeholders.join(", ")
-                    );
-
-                    for (i, row) in rows.iter().enumerate() {
-                        let mut query = sqlx::query(&sql);
-
-                        for value in &row.values {
-                            // Handle NULL values and empty strings
-                            match parser.parse_value(value) {
-                                Some(v) => query = query.bind(v),
-                                None => query = query.bind(None::<String>),
-                            }
-                        }
+    trigger_hook_with_context(
+        DbManagerEvent::BeforeImport {
+            import_type: "csv".to_string(),
+            table_name: Some(table_name.clone()),
+            user_id: audit.user_id.clone(),
+        },
+        &audit,
+    );
 
-                        match query.execute(&state.pool).await {
-                            Ok(_) => result.rows_imported += 1,
-                            Err(e) => {
-                                errors.push(format!("Row {}: {}", i + 2, e)); // +2 for 1-indexed and header row
-                            }
-                        }
-                    }
-                }
-            }
-            Err(parse_error) => {
-                errors.push(format!("CSV parsing error: {}", parse_error));
-            }
-        }
+    if !csv_content.is_empty() && result.success {
+        import_csv_content(
+            &state.pool,
+            &table_name,
+            &String::from_utf8_lossy(&csv_content),
+            &options,
+            &mut result,
+            &mut errors,
+        )
+        .await;
     }
 
     if !errors.is_empty() {
         result.errors = Some(errors);
         result.success = false;
     }
+    if !result.row_errors.is_empty() {
+        result.success = false;
+    }
+
+    trigger_hook_with_context(
+        DbManagerEvent::AfterImport {
+            import_type: "csv".to_string(),
+            success: result.success,
+            table_name: Some(table_name.clone()),
+            rows_imported: Some(result.rows_imported),
+            error: result.errors.as_ref().map(|e| e.join("; ")),
+            user_id: audit.user_id.clone(),
+        },
+        &audit,
+    );
 
     Json(ApiResponse {
         success: result.success,
@@ -3523,6 +4787,118 @@ pub async fn import_csv(
     })
 }
 
+/// Parse, validate and insert CSV content into `table_name`
+async fn import_csv_content(
+    pool: &PgPool,
+    table_name: &str,
+    content: &str,
+    options: &CsvImportOptions,
+    result: &mut ImportCsvResult,
+    errors: &mut Vec<String>,
+) {
+    let parser = CsvParser::new();
+    let (headers, rows) = match parser.parse(content) {
+        Ok(parsed) => parsed,
+        Err(parse_error) => {
+            errors.push(format!("CSV parsing error: {}", parse_error));
+            return;
+        }
+    };
+
+    let columns = match get_import_columns(pool, table_name).await {
+        Ok(columns) if !columns.is_empty() => columns,
+        Ok(_) => {
+            errors.push(format!("Table '{}' not found", table_name));
+            return;
+        }
+        Err(e) => {
+            errors.push(format!("Failed to read table columns: {}", e));
+            return;
+        }
+    };
+
+    let targets = match map_csv_columns(&headers, &columns, &options.mapping) {
+        Ok(targets) => targets,
+        Err(mapping_errors) => {
+            errors.extend(mapping_errors);
+            return;
+        }
+    };
+    let insert_columns: Vec<&ImportColumn> =
+        targets.iter().flatten().map(|&i| &columns[i]).collect();
+    if insert_columns.is_empty() {
+        errors.push("No CSV columns map to table columns".to_string());
+        return;
+    }
+
+    // Validate every row up front; invalid rows are reported, not inserted
+    let mut valid_rows = Vec::with_capacity(rows.len());
+    for (i, row) in rows.into_iter().enumerate() {
+        let line = i + 2; // +2 for 1-indexed and header row
+        if row.values.len() != headers.len() {
+            result.row_errors.push(CsvRowError {
+                row: line,
+                column: None,
+                message: format!(
+                    "Row has {} columns but header has {}",
+                    row.values.len(),
+                    headers.len()
+                ),
+            });
+            continue;
+        }
+
+        let mut values = Vec::with_capacity(insert_columns.len());
+        let mut row_error = None;
+        for (value, target) in row.values.iter().zip(&targets) {
+            let Some(idx) = target else { continue };
+            let column = &columns[*idx];
+            match coerce_csv_value(parser.parse_value(value), column) {
+                Ok(v) => values.push(v),
+                Err(message) => {
+                    row_error = Some(CsvRowError {
+                        row: line,
+                        column: Some(column.name.clone()),
+                        message,
+                    });
+                    break;
+                }
+            }
+        }
+
+        match row_error {
+            Some(error) => result.row_errors.push(error),
+            None => valid_rows.push((line, values)),
+        }
+    }
+
+    if options.on_error == CsvErrorMode::Rollback && !result.row_errors.is_empty() {
+        result.rolled_back = true;
+        result.rows_skipped = (valid_rows.len() + result.row_errors.len()) as u64;
+        return;
+    }
+
+    let total_rows = (valid_rows.len() + result.row_errors.len()) as u64;
+    match insert_csv_rows(
+        pool,
+        table_name,
+        &insert_columns,
+        &valid_rows,
+        options,
+        &mut result.row_errors,
+    )
+    .await
+    {
+        Ok(Some(inserted)) => result.rows_imported = inserted,
+        Ok(None) => result.rolled_back = true,
+        Err(e) => {
+            result.rolled_back = true;
+            errors.push(format!("Import failed: {}", e));
+        }
+    }
+    result.rows_skipped = total_rows - result.rows_imported;
+}
+
 // ============================================================================
 // Router
 // ============================================================================
@@ -3561,31 +4937,7 @@ pub async fn get_audit_log(
     let limit = params.limit.unwrap_or(50).min(500);
     let offset = params.offset.unwrap_or(0);
 
-    // Build query with optional filters
-    let mut conditions = vec!["1=1".to_string()];
-    let mut bind_idx = 1;
-
-    if params.event_type.is_some() {
-        conditions.push(format!("event_type = ${}", bind_idx));
-        bind_idx += 1;
-    }
-    if params.user_id.is_some() {
-        conditions.push(format!("user_id = ${}", bind_idx));
-        bind_idx += 1;
-    }
-    if params.start_date.is_some() {
-        conditions.push(format!("created_at >= ${}::timestamptz", bind_idx));
-        bind_idx += 1;
-    }
-    if params.end_date.is_some() {
-        conditions.push(format!("created_at <= ${}::timestamptz", bind_idx));
-        #[allow(unused_assignments)]
-        {
-            bind_idx += 1;
-        }
-    }
-
-    let where_clause = conditions.join(" AND ");
+    let (where_clause, binds) = params.filter_sql();
 
     // Get total count
     let count_query = format!(
@@ -3594,17 +4946,8 @@ pub async fn get_audit_log(
     );
 
     let mut count_q = sqlx::query_scalar::<_, i64>(&count_query);
-    if let Some(ref event_type) = params.event_type {
-        count_q = count_q.bind(event_type);
-    }
-    if let Some(ref user_id) = params.user_id {
-        count_q = count_q.bind(user_id);
-    }
-    if let Some(ref start_date) = params.start_date {
-        count_q = count_q.bind(start_date);
-    }
-    if let Some(ref end_date) = params.end_date {
-        count_q = count_q.bind(end_date);
+    for value in &binds {
+        count_q = count_q.bind(*value);
     }
 
     let total = count_q.fetch_one(&state.pool).await.unwrap_or(0) as u64;
@@ -3622,17 +4965,8 @@ pub async fn get_audit_log(
     );
 
     let mut data_q = sqlx::query(&data_query);
-    if let Some(ref event_type) = params.event_type {
-        data_q = data_q.bind(event_type);
-    }
-    if let Some(ref user_id) = params.user_id {
-        data_q = data_q.bind(user_id);
-    }
-    if let Some(ref start_date) = params.start_date {
-        data_q = data_q.bind(start_date);
-    }
-    if let Some(ref end_date) = params.end_date {
-        data_q = data_q.bind(end_date);
+    for value in &binds {
+        data_q = data_q.bind(*value);
     }
 
     match data_q.fetch_all(&state.pool).await {
@@ -3671,6 +5005,67 @@ pub async fn get_audit_log(
     }
 }
 
+/// Summarize audit log entries matching the filters
+pub async fn get_audit_log_summary(
+    State(state): State<Arc<DbManagerState>>,
+    Query(params): Query<AuditLogQuery>,
+) -> impl IntoResponse {
+    let (where_clause, binds) = params.filter_sql();
+
+    let sql = format!(
+        r#"
+        SELECT event_type, COALESCE(user_id, '') AS user_id, COUNT(*) AS count,
+               COUNT(*) FILTER (
+                   WHERE event_type = 'after_query'
+                     AND (event_data->>'success')::boolean IS FALSE
+               ) AS failed
+        FROM dbmanager_audit_log
+        WHERE {}
+        GROUP BY event_type, COALESCE(user_id, '')
+        "#,
+        where_clause
+    );
+
+    let mut q = sqlx::query(&sql);
+    for value in &binds {
+        q = q.bind(*value);
+    }
+
+    match q.fetch_all(&state.pool).await {
+        Ok(rows) => {
+            let mut summary = AuditLogSummary {
+                total: 0,
+                by_event_type: HashMap::new(),
+                by_user: HashMap::new(),
+                failed_queries: 0,
+            };
+            for row in rows {
+                let event_type: String = row.get("event_type");
+                let user_id: String = row.get("user_id");
+                let count = row.get::<i64, _>("count") as u64;
+
+                summary.total += count;
+                summary.failed_queries += row.get::<i64, _>("failed") as u64;
+                *summary.by_event_type.entry(event_type).or_default() += count;
+                if !user_id.is_empty() {
+                    *summary.by_user.entry(user_id).or_default() += count;
+                }
+            }
+
+            Json(ApiResponse {
+                success: true,
+                data: Some(summary),
+                error: None,
+            })
+        }
+        Err(e) => Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!("Failed to summarize audit log: {}", e)),
+        }),
+    }
+}
+
 /// Clear old audit log entries (keeps last N days)
 pub async fn clear_audit_log(
     State(state): State<Arc<DbManagerState>>,
@@ -4425,63 +5820,43 @@ pub async fn execute_query_with_timeout(
     State(state): State<Arc<DbManagerState>>,
     Json(request): Json<QueryWithTimeoutRequest>,
 ) -> impl IntoResponse {
-    let timeout = std::time::Duration::from_millis(request.timeout_ms as u64);
-
-    // Set statement timeout
-    let set_timeout = format!("SET statement_timeout = {}", request.timeout_ms);
-    if let Err(e) = sqlx::query(&set_timeout).execute(&state.pool).await {
+    if let Some(blocked_cmd) = is_blocked_query(&request.query) {
         return Json(ApiResponse {
             success: false,
             data: None,
-            error: Some(format!("Failed to set timeout: {}", e)),
+            error: Some(format!("Blocked command: '{}' is not allowed", blocked_cmd)),
         });
     }
 
+    let timeout = state.effective_timeout(Some(request.timeout_ms as u64));
     let start = std::time::Instant::now();
-    let result =
-        tokio::time::timeout(timeout, sqlx::query(&request.query).fetch_all(&state.pool)).await;
-
-    // Reset timeout
-    let _ = sqlx::query("SET statement_timeout = 0")
-        .execute(&state.pool)
-        .await;
-
-    match result {
-        Ok(Ok(rows)) => {
-            let execution_time = start.elapsed().as_secs_f64() * 1000.0;
-            let columns: Vec<String> = if !rows.is_empty() {
-                rows[0]
-                    .columns()
-                    .iter()
-                    .map(|c| c.name().to_string())
-                    .collect()
-            } else {
-                vec![]
-            };
-            let data: Vec<HashMap<String, serde_json::Value>> =
-                rows.iter().map(row_to_json).collect();
+    let result = run_query_with_timeout(
+        &state,
+        uuid::Uuid::new_v4(),
+        &request.query,
+        true,
+        timeout,
+        start,
+    )
+    .await;
 
-            Json(ApiResponse {
-                success: true,
-                data: Some(serde_json::json!({
-                    "columns": columns,
-                    "rows": data,
-                    "rowCount": data.len(),
-                    "executionTime": execution_time,
-                })),
-                error: None,
-            })
-        }
-        Ok(Err(e)) => Json(ApiResponse {
-            success: false,
-            data: None,
-            error: Some(format!("Query failed: {}", e)),
-        }),
-        Err(_) => Json(ApiResponse {
+    if result.status == "success" {
+        Json(ApiResponse {
+            success: true,
+            data: Some(serde_json::json!({
+                "columns": result.columns,
+                "rows": result.rows,
+                "rowCount": result.row_count,
+                "executionTime": result.execution_time,
+            })),
+            error: None,
+        })
+    } else {
+        Json(ApiResponse {
             success: false,
             data: None,
-            error: Some(format!("Query timed out after {} ms", request.timeout_ms)),
-        }),
+            error: result.error.map(|e| format!("Query failed: {}", e)),
+        })
     }
 }
 
@@ -7296,6 +8671,7 @@ use axum::Router;
 /// Note: Call `init_dbmanager_tables(&pool)` before using this router
 /// to ensure the persistence tables exist
 pub fn dbmanager_router(pool: PgPool) -> Router {
+    install_audit_log_hook(pool.clone(), AuditLogConfig::default());
     let state = Arc::new(DbManagerState::new(pool));
 
     Router::new()
@@ -7345,6 +8721,7 @@ pub fn dbmanager_router(pool: PgPool) -> Router {
         .route("/query/parameterized", post(execute_parameterized_query))
         .route("/query/multi", post(execute_multi_statement))
         .route("/query/timeout", post(execute_query_with_timeout))
+        .route("/query/:query_id/cancel", post(cancel_query))
         .route("/query/cost", post(estimate_query_cost))
         .route("/query/slow", get(get_slow_queries))
         .route("/query/permissions", post(check_query_permissions))
@@ -7368,6 +8745,7 @@ pub fn dbmanager_router(pool: PgPool) -> Router {
         .route("/locks", get(get_locks))
         // Audit
         .route("/audit", get(get_audit_log))
+        .route("/audit/summary", get(get_audit_log_summary))
         .route("/audit/clear", post(clear_audit_log))
         .route("/audit/dashboard", get(get_audit_dashboard))
         .with_state(state)
@@ -7523,3 +8901,289 @@ pub async fn init_dbmanager_tables(pool: &PgPool) -> Result<(), sqlx::Error> {
     tracing::info!("Database Manager tables initialized successfully");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns() -> Vec<(String, String)> {
+        vec![
+            ("id".to_string(), "integer".to_string()),
+            ("title".to_string(), "text".to_string()),
+            ("slug".to_string(), "character varying".to_string()),
+        ]
+    }
+
+    fn query() -> TableDataQuery {
+        TableDataQuery {
+            page: None,
+            limit: None,
+            sort_column: None,
+            sort_order: None,
+            filter_column: None,
+            filter_value: None,
+            search: None,
+        }
+    }
+
+    #[test]
+    fn test_execute_request_timeout_fields() {
+        let request: ExecuteQueryRequest =
+            serde_json::from_str(r#"{"query": "SELECT 1", "timeoutMs": 500}"#).unwrap();
+        assert_eq!(request.timeout_ms, Some(500));
+        assert!(request.query_id.is_none());
+        assert!(!request.confirm);
+    }
+
+    #[test]
+    fn test_read_only_detection() {
+        assert!(is_read_only_query("SELECT * FROM posts"));
+        assert!(is_read_only_query(
+            "with recent as (select 1) select * from recent"
+        ));
+        assert!(!is_read_only_query(
+            "WITH gone AS (DELETE FROM posts RETURNING *) SELECT * FROM gone"
+        ));
+        assert!(!is_read_only_query("SELECT * INTO backup FROM posts"));
+        assert!(!is_read_only_query("UPDATE posts SET title = 'x'"));
+    }
+
+    #[test]
+    fn test_parse_explain_analyze_tree() {
+        let output = serde_json::json!([{
+            "Plan": {
+                "Node Type": "Hash Join",
+                "Startup Cost": 1.0,
+                "Total Cost": 50.0,
+                "Plan Rows": 10,
+                "Actual Startup Time": 0.1,
+                "Actual Total Time": 12.0,
+                "Actual Rows": 1000,
+                "Actual Loops": 1,
+                "Hash Cond": "(a.id = b.a_id)",
+                "Plans": [
+                    {
+                        "Node Type": "Seq Scan",
+                        "Relation Name": "a",
+                        "Startup Cost": 0.0,
+                        "Total Cost": 40.0,
+                        "Plan Rows": 100,
+                        "Actual Startup Time": 0.0,
+                        "Actual Total Time": 9.0,
+                        "Actual Rows": 100,
+                        "Actual Loops": 1
+                    },
+                    {
+                        "Node Type": "Index Scan",
+                        "Relation Name": "b",
+                        "Index Name": "b_pkey",
+                        "Startup Cost": 0.0,
+                        "Total Cost": 2.0,
+                        "Plan Rows": 1,
+                        "Actual Startup Time": 0.0,
+                        "Actual Total Time": 0.5,
+                        "Actual Rows": 1,
+                        "Actual Loops": 2
+                    }
+                ]
+            },
+            "Planning Time": 0.2,
+            "Execution Time": 12.5
+        }]);
+
+        let explain = parse_explain_json(&output, true, 15.0).unwrap();
+        let root = &explain.plan_tree;
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[1].id, 2);
+        assert_eq!(root.children[1].actual_loops, Some(2.0));
+        assert_eq!(root.self_time, Some(2.0));
+        assert_eq!(root.row_estimate_ratio, Some(100.0));
+        assert!(root.details.contains_key("Hash Cond"));
+        assert_eq!(explain.executor_time, Some(12.5));
+        // The sequential scan dominates the runtime
+        assert_eq!(explain.most_expensive_node, Some(1));
+    }
+
+    #[test]
+    fn test_table_data_rejects_unknown_columns() {
+        let params = TableDataQuery {
+            sort_column: Some("password".to_string()),
+            ..query()
+        };
+        assert!(build_table_data_sql("posts", &columns(), &params).is_err());
+
+        let params = TableDataQuery {
+            filter_column: Some("id\" OR 1=1 --".to_string()),
+            filter_value: Some("x".to_string()),
+            ..query()
+        };
+        assert!(build_table_data_sql("posts", &columns(), &params).is_err());
+    }
+
+    #[test]
+    fn test_table_data_binds_filter_and_search() {
+        let params = TableDataQuery {
+            page: Some(0),
+            limit: Some(1_000_000),
+            sort_column: Some("title".to_string()),
+            sort_order: Some("desc".to_string()),
+            filter_column: Some("slug".to_string()),
+            filter_value: Some("50%_off".to_string()),
+            search: Some("'; DROP TABLE posts; --".to_string()),
+        };
+
+        let sql = build_table_data_sql("posts", &columns(), &params).unwrap();
+        assert_eq!(sql.page, 1);
+        assert_eq!(sql.limit, MAX_TABLE_DATA_LIMIT);
+        assert_eq!(sql.offset, 0);
+        assert_eq!(sql.params[0], "%50\\%\\_off%");
+        assert!(!sql.data_sql.contains("DROP"));
+        assert!(!sql.count_sql.contains("ORDER BY"));
+        assert!(sql
+            .data_sql
+            .contains("ORDER BY \"title\" DESC LIMIT $3 OFFSET $4"));
+        // Search only covers text columns
+        assert!(!sql.count_sql.contains("\"id\""));
+        assert_eq!(sql.count_sql.matches("$2").count(), 2);
+    }
+
+    fn after_query(success: bool, execution_time_ms: f64) -> DbManagerEvent {
+        DbManagerEvent::AfterQuery {
+            query: "SELECT 1".to_string(),
+            success,
+            execution_time_ms,
+            row_count: None,
+            error: None,
+            user_id: None,
+        }
+    }
+
+    #[test]
+    fn test_audit_sampler_filters_after_query() {
+        let sampler = AuditSampler::new(AuditLogConfig {
+            min_query_duration_ms: 100.0,
+            query_sample_every: 2,
+            ..Default::default()
+        });
+
+        assert!(!sampler.should_record(&DbManagerEvent::BeforeQuery {
+            query: "SELECT 1".to_string(),
+            user_id: None,
+        }));
+        // Too fast
+        assert!(!sampler.should_record(&after_query(true, 5.0)));
+        // Failures are always kept
+        assert!(sampler.should_record(&after_query(false, 5.0)));
+        // Slow queries are sampled one in two
+        assert!(sampler.should_record(&after_query(true, 250.0)));
+        assert!(!sampler.should_record(&after_query(true, 250.0)));
+        assert!(sampler.should_record(&after_query(true, 250.0)));
+        assert!(sampler.should_record(&DbManagerEvent::SchemaChange {
+            operation: "DROP".to_string(),
+            table_name: "posts".to_string(),
+            success: true,
+            user_id: None,
+        }));
+    }
+
+    #[test]
+    fn test_audit_log_filter_sql_numbers_binds() {
+        let params = AuditLogQuery {
+            limit: None,
+            offset: None,
+            event_type: None,
+            user_id: Some("admin".to_string()),
+            start_date: None,
+            end_date: Some("2026-01-01T00:00:00Z".to_string()),
+        };
+
+        let (clause, binds) = params.filter_sql();
+        assert_eq!(
+            clause,
+            "1=1 AND user_id = $1 AND created_at <= $2::timestamptz"
+        );
+        assert_eq!(binds, vec!["admin", "2026-01-01T00:00:00Z"]);
+    }
+
+    fn import_column(name: &str, udt_name: &str, nullable: bool) -> ImportColumn {
+        ImportColumn {
+            name: name.to_string(),
+            udt_name: udt_name.to_string(),
+            nullable,
+            has_default: false,
+            max_length: None,
+        }
+    }
+
+    #[test]
+    fn test_map_csv_columns_with_override() {
+        let columns = vec![
+            import_column("id", "int4", false),
+            import_column("title", "text", true),
+            ImportColumn {
+                has_default: true,
+                ..import_column("created_at", "timestamptz", false)
+            },
+        ];
+        let headers = vec!["ID".to_string(), "title".to_string(), "notes".to_string()];
+
+        let mapping = HashMap::from([
+            ("ID".to_string(), "id".to_string()),
+            ("notes".to_string(), String::new()),
+        ]);
+        assert_eq!(
+            map_csv_columns(&headers, &columns, &mapping).unwrap(),
+            vec![Some(0), Some(1), None]
+        );
+
+        // Unknown column and missing required column
+        let errors = map_csv_columns(&headers, &columns, &HashMap::new()).unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().any(|e| e.contains("Required column 'id'")));
+    }
+
+    #[test]
+    fn test_coerce_csv_value() {
+        let int = import_column("n", "int4", false);
+        assert_eq!(
+            coerce_csv_value(Some("42".into()), &int).unwrap(),
+            Some("42".into())
+        );
+        assert!(coerce_csv_value(Some("4.2".into()), &int).is_err());
+        assert!(coerce_csv_value(Some("99999999999".into()), &int).is_err());
+        assert!(coerce_csv_value(None, &int).is_err());
+
+        let flag = import_column("f", "bool", true);
+        assert_eq!(
+            coerce_csv_value(Some("Yes".into()), &flag).unwrap(),
+            Some("true".into())
+        );
+        assert_eq!(coerce_csv_value(None, &flag).unwrap(), None);
+
+        let name = ImportColumn {
+            max_length: Some(3),
+            ..import_column("s", "varchar", true)
+        };
+        assert!(coerce_csv_value(Some("abcd".into()), &name).is_err());
+
+        let ts = import_column("t", "timestamptz", true);
+        assert!(coerce_csv_value(Some("2026-01-02 03:04:05".into()), &ts).is_ok());
+        assert!(coerce_csv_value(Some("yesterday".into()), &ts).is_err());
+
+        let doc = import_column("j", "jsonb", true);
+        assert!(coerce_csv_value(Some("{\"a\": 1}".into()), &doc).is_ok());
+        assert!(coerce_csv_value(Some("{a".into()), &doc).is_err());
+    }
+
+    #[test]
+    fn test_build_csv_insert_sql() {
+        let id = import_column("id", "int4", false);
+        let title = import_column("title", "text", true);
+        let sql = build_csv_insert_sql("posts", &[&id, &title], 2);
+        assert_eq!(
+            sql,
+            "INSERT INTO \"posts\" (\"id\", \"title\") VALUES \
+             ($1::\"int4\", $2::\"text\"), ($3::\"int4\", $4::\"text\")"
+        );
+    }
+}
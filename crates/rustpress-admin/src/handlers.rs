@@ -1,25 +1,37 @@
 //! Admin dashboard HTTP handlers
 
 use axum::{
-    extract::{Path, Query, State},
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
     response::{IntoResponse, Json},
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::dashboard::{DashboardData, DashboardService, SystemStatus};
+use crate::logs::{LogBuffer, LogFilter, LogRecord};
 
 /// Admin state
 pub struct AdminState {
     pub dashboard_service: DashboardService,
+    pub log_buffer: LogBuffer,
 }
 
 impl AdminState {
     pub fn new() -> Self {
         Self {
             dashboard_service: DashboardService::new(),
+            log_buffer: LogBuffer::global().clone(),
         }
     }
+
+    /// Use a specific log buffer instead of the process-wide one
+    pub fn with_log_buffer(mut self, log_buffer: LogBuffer) -> Self {
+        self.log_buffer = log_buffer;
+        self
+    }
 }
 
 impl Default for AdminState {
@@ -270,29 +282,67 @@ pub async fn update_settings(Json(settings): Json<serde_json::Value>) -> impl In
 #[derive(Debug, Deserialize)]
 pub struct LogQuery {
     pub level: Option<String>,
+    pub target: Option<String>,
     pub search: Option<String>,
+    /// Treat `search` as a regular expression instead of a substring
+    #[serde(default)]
+    pub regex: bool,
     pub limit: Option<u32>,
     pub offset: Option<u32>,
 }
 
-/// Get logs
-pub async fn get_logs(Query(query): Query<LogQuery>) -> impl IntoResponse {
-    // TODO: Implement log retrieval
+impl LogQuery {
+    fn filter(&self) -> Result<LogFilter, String> {
+        LogFilter::new(
+            self.level.as_deref(),
+            self.target.as_deref(),
+            self.search.as_deref(),
+            self.regex,
+        )
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// Get logs, newest first
+pub async fn get_logs(
+    State(state): State<Arc<AdminState>>,
+    Query(query): Query<LogQuery>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(100).min(1000);
+    let offset = query.offset.unwrap_or(0);
+
+    let filter = match query.filter() {
+        Ok(filter) => filter,
+        Err(e) => {
+            return Json(ApiResponse {
+                success: false,
+                data: None,
+                error: Some(e),
+            })
+        }
+    };
+
+    let (records, total) = state
+        .log_buffer
+        .query(&filter, limit as usize, offset as usize);
     Json(ApiResponse {
         success: true,
         data: Some(LogsResponse {
-            logs: vec![],
-            total: 0,
-            limit: query.limit.unwrap_or(100),
-            offset: query.offset.unwrap_or(0),
+            logs: records
+                .into_iter()
+                .map(|r| LogEntry::from_record(r, &filter))
+                .collect(),
+            total: total as u64,
+            limit,
+            offset,
         }),
         error: None,
     })
 }
 
 /// Clear logs
-pub async fn clear_logs() -> impl IntoResponse {
-    // TODO: Implement log clearing
+pub async fn clear_logs(State(state): State<Arc<AdminState>>) -> impl IntoResponse {
+    state.log_buffer.clear();
     Json(ApiResponse::<()> {
         success: true,
         data: None,
@@ -300,6 +350,57 @@ pub async fn clear_logs() -> impl IntoResponse {
     })
 }
 
+/// Live tail of new log lines over WebSocket, using the same filters as
+/// [`get_logs`]
+pub async fn tail_logs(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AdminState>>,
+    Query(query): Query<LogQuery>,
+) -> impl IntoResponse {
+    let filter = query.filter();
+    ws.on_upgrade(move |socket| stream_logs(socket, state.log_buffer.clone(), filter))
+}
+
+async fn stream_logs(mut socket: WebSocket, buffer: LogBuffer, filter: Result<LogFilter, String>) {
+    let filter = match filter {
+        Ok(filter) => filter,
+        Err(error) => {
+            let message = serde_json::json!({ "type": "error", "error": error });
+            let _ = socket.send(Message::Text(message.to_string())).await;
+            return;
+        }
+    };
+
+    let mut tail = buffer.tail();
+    loop {
+        tokio::select! {
+            batch = tail.next() => {
+                let entries: Vec<LogEntry> = batch
+                    .records
+                    .into_iter()
+                    .filter(|r| filter.matches(r))
+                    .map(|r| LogEntry::from_record(r, &filter))
+                    .collect();
+                if entries.is_empty() && batch.missed == 0 {
+                    continue;
+                }
+                let message = serde_json::json!({
+                    "type": "logs",
+                    "entries": entries,
+                    "missed": batch.missed,
+                });
+                if socket.send(Message::Text(message.to_string())).await.is_err() {
+                    return;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
 // ============================================================================
 // Activity
 // ============================================================================
@@ -368,9 +469,27 @@ pub struct LogsResponse {
 /// Log entry
 #[derive(Debug, Serialize)]
 pub struct LogEntry {
+    pub seq: u64,
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub level: String,
     pub message: String,
     pub target: Option<String>,
     pub fields: serde_json::Value,
+    /// Byte ranges of `message` matching the search
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<(usize, usize)>,
+}
+
+impl LogEntry {
+    fn from_record(record: LogRecord, filter: &LogFilter) -> Self {
+        Self {
+            seq: record.seq,
+            timestamp: record.timestamp,
+            level: record.level,
+            highlights: filter.highlights(&record.message),
+            message: record.message,
+            target: Some(record.target),
+            fields: serde_json::Value::Object(record.fields),
+        }
+    }
 }
@@ -13,16 +13,20 @@
 //! - Log viewer
 //! - Performance monitoring
 
+pub mod cache_admin;
 pub mod dashboard;
 pub mod dbmanager;
 pub mod functions;
 pub mod handlers;
+pub mod logs;
 pub mod middleware;
 pub mod routes;
 pub mod templates;
 pub mod widgets;
 
+pub use cache_admin::CacheAdminService;
 pub use dashboard::*;
+pub use logs::{LogBuffer, LogBufferLayer};
 pub use routes::admin_router;
 // Re-export PgPool for convenience
 pub use sqlx::PgPool;
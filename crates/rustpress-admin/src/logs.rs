@@ -0,0 +1,550 @@
+//! In-memory log buffer for the admin log viewer
+//!
+//! [`LogBufferLayer`] is a tracing layer that copies every event into a
+//! bounded [`LogBuffer`]. Values of sensitive fields and credential-looking
+//! text are redacted before they are stored, so the viewer never shows
+//! anything the layer didn't already scrub.
+//!
+//! Every record gets a sequence number. Live tails read from the buffer by
+//! sequence rather than through a channel, so a burst of events is never
+//! dropped as long as the tail catches up before the lines are evicted; if it
+//! falls further behind than that, it is told how many lines it missed.
+
+use chrono::{DateTime, Utc};
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use tokio::sync::watch;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Default number of records kept in memory
+pub const DEFAULT_CAPACITY: usize = 10_000;
+
+/// Longest message or field value kept, in bytes
+const MAX_VALUE_LEN: usize = 4096;
+
+/// Most fields kept per record
+const MAX_FIELDS: usize = 32;
+
+/// Most records returned to a tail in one batch
+const MAX_TAIL_BATCH: usize = 500;
+
+/// Replacement for redacted values
+const REDACTED: &str = "[REDACTED]";
+
+/// Field names whose values are never stored
+const SENSITIVE_FIELDS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "authorization",
+    "cookie",
+    "api_key",
+    "apikey",
+    "private_key",
+    "credentials",
+];
+
+/// A captured log event
+#[derive(Debug, Clone, Serialize)]
+pub struct LogRecord {
+    pub seq: u64,
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+struct Ring {
+    records: VecDeque<LogRecord>,
+    next_seq: u64,
+}
+
+struct Inner {
+    capacity: usize,
+    ring: Mutex<Ring>,
+    /// Next sequence number to be assigned, for waking tails
+    latest: watch::Sender<u64>,
+}
+
+/// Bounded, shareable buffer of recent log records
+#[derive(Clone)]
+pub struct LogBuffer {
+    inner: Arc<Inner>,
+}
+
+impl LogBuffer {
+    /// Create a buffer holding at most `capacity` records
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (latest, _) = watch::channel(0);
+        Self {
+            inner: Arc::new(Inner {
+                capacity,
+                ring: Mutex::new(Ring {
+                    records: VecDeque::with_capacity(capacity.min(1024)),
+                    next_seq: 0,
+                }),
+                latest,
+            }),
+        }
+    }
+
+    /// Process-wide buffer shared by the server's tracing layer and the
+    /// admin routes
+    pub fn global() -> &'static LogBuffer {
+        static GLOBAL: OnceLock<LogBuffer> = OnceLock::new();
+        GLOBAL.get_or_init(|| LogBuffer::new(DEFAULT_CAPACITY))
+    }
+
+    /// Maximum number of records kept
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity
+    }
+
+    /// Number of records currently held
+    pub fn len(&self) -> usize {
+        self.ring().records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn ring(&self) -> MutexGuard<'_, Ring> {
+        // A panic while holding the lock can't leave the ring inconsistent
+        self.inner.ring.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Append a record, evicting the oldest when full
+    pub fn push(&self, mut record: LogRecord) {
+        let next_seq = {
+            let mut ring = self.ring();
+            record.seq = ring.next_seq;
+            ring.next_seq += 1;
+            if ring.records.len() >= self.inner.capacity {
+                ring.records.pop_front();
+            }
+            ring.records.push_back(record);
+            ring.next_seq
+        };
+        self.inner.latest.send_replace(next_seq);
+    }
+
+    /// Remove all records. Sequence numbers keep increasing.
+    pub fn clear(&self) {
+        self.ring().records.clear();
+    }
+
+    /// Matching records, newest first, with the total number of matches
+    pub fn query(
+        &self,
+        filter: &LogFilter,
+        limit: usize,
+        offset: usize,
+    ) -> (Vec<LogRecord>, usize) {
+        let ring = self.ring();
+        let mut total = 0;
+        let mut page = Vec::new();
+        for record in ring.records.iter().rev().filter(|r| filter.matches(r)) {
+            if total >= offset && page.len() < limit {
+                page.push(record.clone());
+            }
+            total += 1;
+        }
+        (page, total)
+    }
+
+    /// Records with a sequence number of at least `seq`, and how many
+    /// records after `seq` were already evicted
+    fn since(&self, seq: u64, max: usize) -> (Vec<LogRecord>, u64) {
+        let ring = self.ring();
+        let first = ring.records.front().map_or(ring.next_seq, |r| r.seq);
+        let missed = first.saturating_sub(seq);
+        let skip = seq.saturating_sub(first) as usize;
+        let records = ring.records.iter().skip(skip).take(max).cloned().collect();
+        (records, missed)
+    }
+
+    /// Follow new records from now on
+    pub fn tail(&self) -> LogTail {
+        LogTail {
+            buffer: self.clone(),
+            next_seq: self.ring().next_seq,
+            latest: self.inner.latest.subscribe(),
+        }
+    }
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+/// New records delivered to a tail
+#[derive(Debug)]
+pub struct TailBatch {
+    pub records: Vec<LogRecord>,
+    /// Records evicted before the tail could read them
+    pub missed: u64,
+}
+
+/// Cursor following a [`LogBuffer`]
+pub struct LogTail {
+    buffer: LogBuffer,
+    next_seq: u64,
+    latest: watch::Receiver<u64>,
+}
+
+impl LogTail {
+    /// Wait for records newer than the last batch
+    ///
+    /// Cancel safe: if the future is dropped, no records are skipped.
+    pub async fn next(&mut self) -> TailBatch {
+        loop {
+            // Mark the current value seen before reading so a push between
+            // the read and the wait still wakes us
+            self.latest.borrow_and_update();
+            let (records, missed) = self.buffer.since(self.next_seq, MAX_TAIL_BATCH);
+            if let Some(last) = records.last() {
+                self.next_seq = last.seq + 1;
+            } else {
+                self.next_seq += missed;
+            }
+            if !records.is_empty() || missed > 0 {
+                return TailBatch { records, missed };
+            }
+            // The sender lives as long as the buffer we hold, so this
+            // can't fail
+            let _ = self.latest.changed().await;
+        }
+    }
+}
+
+/// Error building a [`LogFilter`]
+#[derive(Debug, thiserror::Error)]
+pub enum LogFilterError {
+    #[error("Unknown log level: {0}")]
+    InvalidLevel(String),
+    #[error("Invalid search pattern: {0}")]
+    InvalidPattern(#[from] regex::Error),
+}
+
+/// Level, target and text filter for log records
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    /// Least severe level shown
+    level: Option<Level>,
+    /// Target prefix, e.g. `rustpress_auth`
+    target: Option<String>,
+    search: Option<Regex>,
+}
+
+impl LogFilter {
+    /// Build a filter from the viewer's query parameters
+    ///
+    /// The search is a case-insensitive substring unless `regex` is set.
+    pub fn new(
+        level: Option<&str>,
+        target: Option<&str>,
+        search: Option<&str>,
+        regex: bool,
+    ) -> Result<Self, LogFilterError> {
+        let level = level
+            .filter(|l| !l.is_empty())
+            .map(|l| Level::from_str(l).map_err(|_| LogFilterError::InvalidLevel(l.to_string())))
+            .transpose()?;
+
+        let search = search
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                let pattern = if regex {
+                    s.to_string()
+                } else {
+                    regex::escape(s)
+                };
+                RegexBuilder::new(&pattern)
+                    .case_insensitive(true)
+                    .size_limit(1 << 20)
+                    .build()
+            })
+            .transpose()?;
+
+        Ok(Self {
+            level,
+            target: target.filter(|t| !t.is_empty()).map(str::to_string),
+            search,
+        })
+    }
+
+    /// Whether a record passes the filter
+    pub fn matches(&self, record: &LogRecord) -> bool {
+        if let Some(min) = self.level {
+            // More verbose levels compare greater
+            match Level::from_str(&record.level) {
+                Ok(level) if level <= min => {}
+                _ => return false,
+            }
+        }
+        if let Some(target) = &self.target {
+            if !record.target.starts_with(target.as_str()) {
+                return false;
+            }
+        }
+        match &self.search {
+            Some(search) => {
+                search.is_match(&record.message)
+                    || record.fields.values().any(|v| match v {
+                        serde_json::Value::String(s) => search.is_match(s),
+                        other => search.is_match(&other.to_string()),
+                    })
+            }
+            None => true,
+        }
+    }
+
+    /// Byte ranges of search matches in `text`, for highlighting
+    pub fn highlights(&self, text: &str) -> Vec<(usize, usize)> {
+        match &self.search {
+            Some(search) => search
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| (m.start(), m.end()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Tracing layer feeding a [`LogBuffer`]
+pub struct LogBufferLayer {
+    buffer: LogBuffer,
+}
+
+impl LogBufferLayer {
+    pub fn new(buffer: LogBuffer) -> Self {
+        Self { buffer }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = RecordVisitor::default();
+        event.record(&mut visitor);
+
+        self.buffer.push(LogRecord {
+            seq: 0,
+            timestamp: Utc::now(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            message: redact(&visitor.message),
+            fields: visitor.fields,
+        });
+    }
+}
+
+#[derive(Default)]
+struct RecordVisitor {
+    message: String,
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl RecordVisitor {
+    fn insert(&mut self, field: &Field, value: serde_json::Value) {
+        if self.fields.len() >= MAX_FIELDS {
+            return;
+        }
+        let value = if is_sensitive_field(field.name()) {
+            serde_json::Value::String(REDACTED.to_string())
+        } else {
+            value
+        };
+        self.fields.insert(field.name().to_string(), value);
+    }
+}
+
+impl Visit for RecordVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = truncate(value);
+        } else {
+            self.insert(field, serde_json::Value::String(redact(value)));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        let text = format!("{:?}", value);
+        if field.name() == "message" {
+            self.message = truncate(&text);
+        } else {
+            self.insert(field, serde_json::Value::String(redact(&text)));
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into());
+    }
+}
+
+fn is_sensitive_field(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SENSITIVE_FIELDS.iter().any(|s| name.contains(s))
+}
+
+fn truncate(value: &str) -> String {
+    if value.len() <= MAX_VALUE_LEN {
+        return value.to_string();
+    }
+    let mut end = MAX_VALUE_LEN;
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &value[..end])
+}
+
+/// Scrub credentials from free text: `password=...`-style pairs and bearer
+/// tokens
+fn redact(value: &str) -> String {
+    static PATTERNS: OnceLock<(Regex, Regex)> = OnceLock::new();
+    let (pairs, bearer) = PATTERNS.get_or_init(|| {
+        (
+            Regex::new(
+                r#"(?i)\b((?:password|passwd|secret|[a-z_]*token|api[_-]?key|authorization)"?\s*[=:]\s*)((?:(?:bearer|basic)\s+)?(?:"[^"]*"|[^\s,;&]+))"#,
+            )
+            .expect("valid redaction pattern"),
+            Regex::new(r"(?i)\bbearer\s+[a-z0-9\-._~+/]+=*").expect("valid redaction pattern"),
+        )
+    });
+
+    let value = truncate(value);
+    let value = pairs.replace_all(&value, format!("${{1}}{}", REDACTED));
+    bearer
+        .replace_all(&value, format!("Bearer {}", REDACTED))
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn record(level: &str, target: &str, message: &str) -> LogRecord {
+        LogRecord {
+            seq: 0,
+            timestamp: Utc::now(),
+            level: level.to_string(),
+            target: target.to_string(),
+            message: message.to_string(),
+            fields: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_layer_redacts_sensitive_data() {
+        let buffer = LogBuffer::new(10);
+        let subscriber = tracing_subscriber::registry().with(LogBufferLayer::new(buffer.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(
+                user = "alice",
+                password = "hunter2",
+                "Login with token=abc123 and header Authorization: Bearer eyJhbGciOi.xyz"
+            );
+        });
+
+        let (records, _) = buffer.query(&LogFilter::default(), 10, 0);
+        let record = &records[0];
+        assert_eq!(record.level, "INFO");
+        assert_eq!(record.fields["user"], "alice");
+        assert_eq!(record.fields["password"], REDACTED);
+        assert!(!record.message.contains("abc123"));
+        assert!(!record.message.contains("eyJhbGciOi"));
+        assert!(record.message.contains("token=[REDACTED]"));
+    }
+
+    #[test]
+    fn test_buffer_is_bounded() {
+        let buffer = LogBuffer::new(3);
+        for i in 0..5 {
+            buffer.push(record("INFO", "app", &format!("line {}", i)));
+        }
+        assert_eq!(buffer.len(), 3);
+
+        let (records, total) = buffer.query(&LogFilter::default(), 10, 0);
+        assert_eq!(total, 3);
+        assert_eq!(records[0].message, "line 4");
+        assert_eq!(records[2].message, "line 2");
+    }
+
+    #[test]
+    fn test_filter_level_target_and_search() {
+        let filter =
+            LogFilter::new(Some("warn"), Some("rustpress_auth"), Some("FAILED"), false).unwrap();
+        assert!(filter.matches(&record("ERROR", "rustpress_auth::jwt", "Login failed")));
+        assert!(!filter.matches(&record("INFO", "rustpress_auth::jwt", "Login failed")));
+        assert!(!filter.matches(&record("WARN", "rustpress_api", "Login failed")));
+        assert!(!filter.matches(&record("WARN", "rustpress_auth", "Login ok")));
+        assert_eq!(filter.highlights("failed, FAILED"), vec![(0, 6), (8, 14)]);
+
+        // Substring search treats regex syntax literally
+        let literal = LogFilter::new(None, None, Some("a.c"), false).unwrap();
+        assert!(!literal.matches(&record("INFO", "app", "abc")));
+        let regex = LogFilter::new(None, None, Some("a.c"), true).unwrap();
+        assert!(regex.matches(&record("INFO", "app", "abc")));
+
+        assert!(LogFilter::new(Some("loud"), None, None, false).is_err());
+        assert!(LogFilter::new(None, None, Some("("), true).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tail_keeps_burst_and_reports_missed() {
+        let buffer = LogBuffer::new(100);
+        let mut tail = buffer.tail();
+
+        for i in 0..50 {
+            buffer.push(record("INFO", "app", &format!("line {}", i)));
+        }
+        let batch = tail.next().await;
+        assert_eq!(batch.missed, 0);
+        assert_eq!(batch.records.len(), 50);
+        assert_eq!(batch.records[49].message, "line 49");
+
+        // Fall further behind than the buffer holds
+        for i in 50..250 {
+            buffer.push(record("INFO", "app", &format!("line {}", i)));
+        }
+        let batch = tail.next().await;
+        assert_eq!(batch.missed, 100);
+        assert_eq!(batch.records[0].message, "line 150");
+        assert_eq!(batch.records.len(), 100);
+
+        // Waits for the next push
+        let pusher = buffer.clone();
+        tokio::spawn(async move {
+            pusher.push(record("WARN", "app", "later"));
+        });
+        let batch = tail.next().await;
+        assert_eq!(batch.records[0].message, "later");
+    }
+}
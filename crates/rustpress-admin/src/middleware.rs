@@ -1,12 +1,12 @@
 //! Admin middleware for authentication and authorization
 
 use axum::{
-    extract::Request,
-    http::{header, StatusCode},
+    extract::{ConnectInfo, Request},
+    http::{header, HeaderMap, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
-use std::net::IpAddr;
+use std::net::{IpAddr, SocketAddr};
 
 /// Admin authentication middleware
 pub async fn require_admin_auth(request: Request, next: Next) -> Response {
@@ -64,8 +64,18 @@ pub async fn ip_whitelist(allowed_ips: &[String], request: Request, next: Next)
 
 /// Extract client IP from request
 fn get_client_ip(request: &Request) -> Option<IpAddr> {
+    client_ip_from_headers(request.headers()).or_else(|| {
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|info| info.0.ip())
+    })
+}
+
+/// Extract client IP from proxy headers (`X-Forwarded-For`, then `X-Real-IP`)
+pub(crate) fn client_ip_from_headers(headers: &HeaderMap) -> Option<IpAddr> {
     // Check X-Forwarded-For header first (for reverse proxies)
-    if let Some(forwarded) = request.headers().get("X-Forwarded-For") {
+    if let Some(forwarded) = headers.get("X-Forwarded-For") {
         if let Ok(value) = forwarded.to_str() {
             // Take the first IP in the chain
             if let Some(ip_str) = value.split(',').next() {
@@ -77,7 +87,7 @@ fn get_client_ip(request: &Request) -> Option<IpAddr> {
     }
 
     // Check X-Real-IP header
-    if let Some(real_ip) = request.headers().get("X-Real-IP") {
+    if let Some(real_ip) = headers.get("X-Real-IP") {
         if let Ok(value) = real_ip.to_str() {
             if let Ok(ip) = value.parse() {
                 return Some(ip);
@@ -85,7 +95,6 @@ fn get_client_ip(request: &Request) -> Option<IpAddr> {
         }
     }
 
-    // TODO: Get from connection info
     None
 }
 
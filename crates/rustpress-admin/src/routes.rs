@@ -44,6 +44,7 @@ pub fn admin_router(pool: PgPool) -> Router {
         // Logs
         .route("/logs", get(get_logs))
         .route("/logs/clear", post(clear_logs))
+        .route("/logs/tail", get(tail_logs))
         // Activity
         .route("/activity", get(get_activity))
         .with_state(state)
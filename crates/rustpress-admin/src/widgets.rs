@@ -1,6 +1,8 @@
 //! Dashboard widgets for the admin panel
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 
 /// Widget types available in the dashboard
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +26,8 @@ pub enum WidgetType {
     QuickActions,
     /// Table
     Table,
+    /// Values pushed over the WebSocket instead of polled
+    LiveMetrics,
     /// Custom HTML
     Custom,
 }
@@ -150,6 +154,7 @@ pub fn default_dashboard_widgets() -> Vec<Widget> {
             }),
             ..Default::default()
         },
+        SystemMetricsWidget::definition(),
         // Row 3: Charts
         Widget {
             id: "traffic_chart".to_string(),
@@ -205,6 +210,90 @@ pub fn default_dashboard_widgets() -> Vec<Widget> {
     ]
 }
 
+/// Capability (`resource:action`) required to stream live system metrics
+pub const SYSTEM_METRICS_CAPABILITY: (&str, &str) = ("system", "monitor");
+
+/// One sample of live system metrics, pushed to subscribed admins
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SystemMetricsSnapshot {
+    /// HTTP requests per second since the previous sample
+    pub requests_per_second: f64,
+
+    /// Database connections currently checked out
+    pub db_pool_in_use: u32,
+
+    /// Maximum database connections
+    pub db_pool_max: u32,
+
+    /// Cache hit ratio (0.0-1.0) since the previous sample, if there were lookups
+    pub cache_hit_ratio: Option<f64>,
+
+    /// Pending jobs across all queues
+    pub job_queue_depth: u64,
+
+    /// Open WebSocket connections
+    pub ws_connections: u64,
+
+    /// When the sample was taken
+    pub sampled_at: DateTime<Utc>,
+}
+
+/// Live system metrics widget
+///
+/// Holds a subscription to the server's metrics broadcast. Dropping the
+/// widget drops the subscription, which lets the server stop sampling once
+/// no admin has the panel open.
+pub struct SystemMetricsWidget {
+    receiver: broadcast::Receiver<SystemMetricsSnapshot>,
+}
+
+impl SystemMetricsWidget {
+    /// Widget ID used in the dashboard layout and WebSocket messages
+    pub const ID: &'static str = "system_metrics";
+
+    /// Wrap a subscription to the metrics broadcast
+    pub fn new(receiver: broadcast::Receiver<SystemMetricsSnapshot>) -> Self {
+        Self { receiver }
+    }
+
+    /// Dashboard definition for the widget
+    pub fn definition() -> Widget {
+        Widget {
+            id: Self::ID.to_string(),
+            widget_type: WidgetType::LiveMetrics,
+            title: "Live System Metrics".to_string(),
+            size: 4,
+            data_source: Some("/ws".to_string()),
+            refresh_interval: 0,
+            config: serde_json::json!({
+                "stream": Self::ID,
+                "metrics": [
+                    "requests_per_second",
+                    "db_pool_in_use",
+                    "cache_hit_ratio",
+                    "job_queue_depth",
+                    "ws_connections"
+                ]
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Wait for the next snapshot
+    ///
+    /// A subscriber that fell behind skips to the newest sample rather than
+    /// replaying stale ones. Returns `None` once the publisher shuts down.
+    pub async fn next(&mut self) -> Option<SystemMetricsSnapshot> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(snapshot) => return Some(snapshot),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
 /// Widget renderer trait
 pub trait WidgetRenderer {
     /// Render widget to HTML
@@ -222,6 +311,7 @@ impl WidgetRenderer for DefaultWidgetRenderer {
             WidgetType::StatusList => render_status_list(widget, data),
             WidgetType::ActivityFeed => render_activity_feed(widget, data),
             WidgetType::QuickActions => render_quick_actions(widget, data),
+            WidgetType::LiveMetrics => render_live_metrics(widget, data),
             _ => format!("<div>Widget: {}</div>", widget.title),
         }
     }
@@ -377,6 +467,46 @@ fn render_activity_feed(widget: &Widget, data: &serde_json::Value) -> String {
     html
 }
 
+fn render_live_metrics(widget: &Widget, data: &serde_json::Value) -> String {
+    let stream = widget
+        .config
+        .get("stream")
+        .and_then(|v| v.as_str())
+        .unwrap_or(&widget.id);
+    let mut html = format!(
+        r#"
+        <div class="bg-white dark:bg-gray-800 rounded-lg shadow p-6" data-live-stream="{}">
+            <h3 class="text-lg font-semibold text-gray-800 dark:text-white mb-4">{}</h3>
+            <div class="grid grid-cols-5 gap-4">
+    "#,
+        stream, widget.title
+    );
+
+    if let Some(metrics) = widget.config.get("metrics").and_then(|v| v.as_array()) {
+        for metric in metrics.iter().filter_map(|m| m.as_str()) {
+            // Initial values; the client replaces them as snapshots arrive
+            let value = match data.get(metric) {
+                Some(serde_json::Value::Null) | None => "-".to_string(),
+                Some(value) => value.to_string(),
+            };
+            html.push_str(&format!(
+                r#"
+                <div>
+                    <p class="text-sm text-gray-500 dark:text-gray-400">{}</p>
+                    <p class="text-xl font-bold text-gray-800 dark:text-white" data-metric="{}">{}</p>
+                </div>
+            "#,
+                metric.replace('_', " "),
+                metric,
+                value
+            ));
+        }
+    }
+
+    html.push_str("</div></div>");
+    html
+}
+
 fn render_quick_actions(widget: &Widget, _data: &serde_json::Value) -> String {
     let mut html = format!(
         r#"
@@ -413,3 +543,46 @@ fn render_quick_actions(widget: &Widget, _data: &serde_json::Value) -> String {
     html.push_str("</div></div>");
     html
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(requests_per_second: f64) -> SystemMetricsSnapshot {
+        SystemMetricsSnapshot {
+            requests_per_second,
+            db_pool_in_use: 2,
+            db_pool_max: 10,
+            cache_hit_ratio: Some(0.9),
+            job_queue_depth: 4,
+            ws_connections: 1,
+            sampled_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lagging_widget_skips_to_latest() {
+        let (tx, rx) = broadcast::channel(2);
+        let mut widget = SystemMetricsWidget::new(rx);
+
+        for rps in [1.0, 2.0, 3.0] {
+            tx.send(snapshot(rps)).unwrap();
+        }
+
+        assert_eq!(widget.next().await.unwrap().requests_per_second, 2.0);
+        assert_eq!(widget.next().await.unwrap().requests_per_second, 3.0);
+        drop(tx);
+        assert!(widget.next().await.is_none());
+    }
+
+    #[test]
+    fn test_live_metrics_render() {
+        let widget = SystemMetricsWidget::definition();
+        let data = serde_json::to_value(snapshot(12.5)).unwrap();
+
+        let html = DefaultWidgetRenderer.render(&widget, &data);
+
+        assert!(html.contains(r#"data-live-stream="system_metrics""#));
+        assert!(html.contains(r#"data-metric="requests_per_second">12.5<"#));
+    }
+}
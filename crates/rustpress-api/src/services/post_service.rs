@@ -5,8 +5,10 @@ use rustpress_admin::functions::EventDispatcher;
 use rustpress_core::error::{Error, Result};
 use rustpress_core::service::{ListParams, SortOrder};
 use rustpress_database::repository::posts::{PostRepository, PostRow};
+use rustpress_events::{EventBus, PostPublished};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Post status enum
@@ -185,6 +187,7 @@ pub struct PostService {
     pool: PgPool,
     site_id: Option<Uuid>,
     dispatcher: EventDispatcher,
+    event_bus: Option<Arc<EventBus>>,
 }
 
 impl PostService {
@@ -195,15 +198,43 @@ impl PostService {
             pool,
             site_id: None,
             dispatcher,
+            event_bus: None,
         }
     }
 
+    /// Publish `post.published` on the event bus when a post goes public
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
     /// Set the site ID for multi-site support
     pub fn with_site(mut self, site_id: Uuid) -> Self {
         self.site_id = Some(site_id);
         self
     }
 
+    /// Announce a post's move into the published status. The post is
+    /// already saved, so subscriber failures are only logged.
+    async fn emit_published(&self, post: &PostResponse, post_type: &str, previous_status: &str) {
+        let Some(event_bus) = &self.event_bus else {
+            return;
+        };
+        let event = PostPublished {
+            post_id: post.id,
+            author_id: post.author_id,
+            post_type: post_type.to_string(),
+            title: post.title.clone(),
+            slug: post.slug.clone(),
+            excerpt: post.excerpt.clone(),
+            previous_status: Some(previous_status.to_string()),
+        }
+        .into_event();
+        if let Err(e) = event_bus.publish(event).await {
+            tracing::warn!(post_id = %post.id, error = %e, "Failed to publish post.published event");
+        }
+    }
+
     /// Get the repository instance
     fn repo(&self) -> PostRepository {
         let repo = PostRepository::new(self.pool.clone());
@@ -361,6 +392,7 @@ impl PostService {
                 .dispatcher
                 .dispatch_after("post_published", &after_event_data)
                 .await;
+            self.emit_published(&response, "post", "new").await;
         }
 
         Ok(response)
@@ -513,6 +545,8 @@ impl PostService {
             }
         }
 
+        let previous_status = existing.status.clone();
+        let post_type = existing.post_type.clone();
         let was_published = existing.status == "published";
         let new_status = request.status.as_ref().unwrap_or(&existing.status);
         let is_publishing = !was_published && new_status == "published";
@@ -641,6 +675,8 @@ impl PostService {
                 .dispatcher
                 .dispatch_after("post_published", &after_event_data)
                 .await;
+            self.emit_published(&response, &post_type, &previous_status)
+                .await;
         }
 
         Ok(response)
@@ -771,6 +807,7 @@ impl PostService {
         }
 
         let old_status = existing.status.clone();
+        let post_type = existing.post_type.clone();
         let published_at = existing.published_at.or(Some(Utc::now()));
 
         let updated_post = PostRow {
@@ -809,6 +846,8 @@ impl PostService {
                 }),
             )
             .await;
+        self.emit_published(&response, &post_type, &old_status)
+            .await;
 
         Ok(response)
     }
@@ -1,7 +1,10 @@
 //! Brute Force Protection (Points 69, 77)
 //!
-//! Protection against brute force attacks with exponential backoff
-//! and account lockout after failed attempts.
+//! Protection against brute force attacks with a progressive response:
+//! an artificial delay after a few failed attempts, a CAPTCHA requirement
+//! after more, and an exponentially growing lockout after that. Attempts are
+//! counted per username and per IP address so that both distributed attacks
+//! on one account and single-IP attacks on many accounts are slowed down.
 
 use chrono::{DateTime, Duration, Utc};
 use rustpress_core::error::{Error, Result};
@@ -41,6 +44,12 @@ pub struct LockoutStatus {
     pub locked_until: Option<DateTime<Utc>>,
     pub remaining_attempts: u32,
     pub next_lockout_duration: Duration,
+    /// Artificial delay to apply before responding to the next attempt
+    #[serde(default)]
+    pub delay_ms: u64,
+    /// Whether the next attempt must include a solved CAPTCHA
+    #[serde(default)]
+    pub requires_captcha: bool,
 }
 
 impl LockoutStatus {
@@ -52,6 +61,8 @@ impl LockoutStatus {
             locked_until: None,
             remaining_attempts: max_attempts.saturating_sub(failed_attempts),
             next_lockout_duration: Duration::zero(),
+            delay_ms: 0,
+            requires_captcha: false,
         }
     }
 
@@ -68,6 +79,8 @@ impl LockoutStatus {
             locked_until: Some(until),
             remaining_attempts: 0,
             next_lockout_duration: next_duration,
+            delay_ms: 0,
+            requires_captcha: true,
         }
     }
 
@@ -75,6 +88,32 @@ impl LockoutStatus {
         self.locked_until
             .map(|until| (until - Utc::now()).num_seconds().max(0))
     }
+
+    /// Whole minutes until unlock, rounded up, for "try again in X minutes"
+    pub fn minutes_until_unlock(&self) -> Option<i64> {
+        self.seconds_until_unlock()
+            .map(|seconds| (seconds + 59) / 60)
+    }
+
+    /// Combine the status of the username and IP keys into one response
+    fn merge(self, other: LockoutStatus) -> LockoutStatus {
+        let (mut primary, secondary) = match (self.is_locked, other.is_locked) {
+            (false, true) => (other, self),
+            (true, true) if other.locked_until > self.locked_until => (other, self),
+            _ => (self, other),
+        };
+        primary.delay_ms = primary.delay_ms.max(secondary.delay_ms);
+        primary.requires_captcha |= secondary.requires_captcha;
+        primary
+    }
+}
+
+/// Thresholds applied to one kind of key (username or IP)
+#[derive(Debug, Clone, Copy)]
+struct Thresholds {
+    delay_after: u32,
+    captcha_after: u32,
+    lockout_after: u32,
 }
 
 /// Brute force protection configuration
@@ -94,8 +133,22 @@ pub struct BruteForceConfig {
     pub clear_on_success: bool,
     /// Track by IP in addition to username
     pub track_ip: bool,
+    /// Failed attempts before responses are artificially delayed
+    pub delay_after_attempts: u32,
+    /// Delay applied at the first delayed attempt (milliseconds)
+    pub initial_delay_ms: u64,
+    /// Maximum artificial delay (milliseconds)
+    pub max_delay_ms: u64,
+    /// Failed attempts before a CAPTCHA is required
+    pub captcha_after_attempts: u32,
     /// IP-specific settings
     pub ip_max_attempts: u32,
+    /// Failed attempts from one IP before responses are delayed
+    pub ip_delay_after_attempts: u32,
+    /// Failed attempts from one IP before a CAPTCHA is required
+    pub ip_captcha_after_attempts: u32,
+    /// Also clear the IP's counters after a successful login
+    pub clear_ip_on_success: bool,
     /// Notify on lockout
     pub notify_on_lockout: bool,
 }
@@ -110,7 +163,14 @@ impl Default for BruteForceConfig {
             backoff_multiplier: 2.0,
             clear_on_success: true,
             track_ip: true,
+            delay_after_attempts: 2,
+            initial_delay_ms: 1000,
+            max_delay_ms: 10_000,
+            captcha_after_attempts: 3,
             ip_max_attempts: 20,
+            ip_delay_after_attempts: 5,
+            ip_captcha_after_attempts: 10,
+            clear_ip_on_success: false,
             notify_on_lockout: true,
         }
     }
@@ -161,15 +221,35 @@ impl<S: BruteForceStore> BruteForceProtection<S> {
         Self { store, config }
     }
 
+    fn user_thresholds(&self) -> Thresholds {
+        Thresholds {
+            delay_after: self.config.delay_after_attempts,
+            captcha_after: self.config.captcha_after_attempts,
+            lockout_after: self.config.max_attempts,
+        }
+    }
+
+    fn ip_thresholds(&self) -> Thresholds {
+        Thresholds {
+            delay_after: self.config.ip_delay_after_attempts,
+            captcha_after: self.config.ip_captcha_after_attempts,
+            lockout_after: self.config.ip_max_attempts,
+        }
+    }
+
     /// Check if identifier is currently locked out
     pub async fn check_lockout(&self, identifier: &str) -> Result<LockoutStatus> {
+        self.check_key(identifier, self.user_thresholds()).await
+    }
+
+    async fn check_key(&self, identifier: &str, thresholds: Thresholds) -> Result<LockoutStatus> {
         // Check for active lockout
         if let Some((locked_until, attempt_count)) = self.store.get_lockout(identifier).await? {
             if Utc::now() < locked_until {
-                let next_duration = self.calculate_next_lockout_duration(attempt_count);
+                let next_duration = self.calculate_next_lockout_duration(attempt_count, thresholds);
                 return Ok(LockoutStatus::locked(
                     attempt_count,
-                    self.config.max_attempts,
+                    thresholds.lockout_after,
                     locked_until,
                     next_duration,
                 ));
@@ -186,13 +266,22 @@ impl<S: BruteForceStore> BruteForceProtection<S> {
             .get_failed_count(identifier, window_start)
             .await?;
 
-        Ok(LockoutStatus::unlocked(
-            failed_count,
-            self.config.max_attempts,
-        ))
+        Ok(self.unlocked_status(failed_count, thresholds))
+    }
+
+    /// Status for a key that has not reached the lockout threshold
+    fn unlocked_status(&self, failed_count: u32, thresholds: Thresholds) -> LockoutStatus {
+        let mut status = LockoutStatus::unlocked(failed_count, thresholds.lockout_after);
+        status.delay_ms = self.calculate_delay_ms(failed_count, thresholds);
+        status.requires_captcha = failed_count >= thresholds.captcha_after;
+        status
     }
 
     /// Record a failed login attempt
+    ///
+    /// The returned status combines the identifier's and (when tracked) the
+    /// IP's counters, so it carries the delay, CAPTCHA and lockout to apply
+    /// to the next attempt.
     pub async fn record_failure(
         &self,
         identifier: &str,
@@ -229,34 +318,50 @@ impl<S: BruteForceStore> BruteForceProtection<S> {
             self.store.record_attempt(&ip_attempt).await?;
         }
 
-        // Check if we should lock out
+        let status = self
+            .evaluate_failure(identifier, self.user_thresholds())
+            .await?;
+
+        if self.config.track_ip && identifier != ip_address {
+            let ip_status = self
+                .evaluate_failure(ip_address, self.ip_thresholds())
+                .await?;
+            return Ok(status.merge(ip_status));
+        }
+
+        Ok(status)
+    }
+
+    /// Count a key's recent failures and lock it out if over the threshold
+    async fn evaluate_failure(
+        &self,
+        identifier: &str,
+        thresholds: Thresholds,
+    ) -> Result<LockoutStatus> {
         let window_start = Utc::now() - Duration::seconds(self.config.window_seconds as i64);
         let failed_count = self
             .store
             .get_failed_count(identifier, window_start)
             .await?;
 
-        if failed_count >= self.config.max_attempts {
-            let lockout_duration = self.calculate_lockout_duration(failed_count);
+        if failed_count >= thresholds.lockout_after {
+            let lockout_duration = self.calculate_lockout_duration(failed_count, thresholds);
             let locked_until = Utc::now() + lockout_duration;
 
             self.store
                 .set_lockout(identifier, locked_until, failed_count)
                 .await?;
 
-            let next_duration = self.calculate_next_lockout_duration(failed_count);
+            let next_duration = self.calculate_next_lockout_duration(failed_count, thresholds);
             return Ok(LockoutStatus::locked(
                 failed_count,
-                self.config.max_attempts,
+                thresholds.lockout_after,
                 locked_until,
                 next_duration,
             ));
         }
 
-        Ok(LockoutStatus::unlocked(
-            failed_count,
-            self.config.max_attempts,
-        ))
+        Ok(self.unlocked_status(failed_count, thresholds))
     }
 
     /// Record a successful login
@@ -283,14 +388,21 @@ impl<S: BruteForceStore> BruteForceProtection<S> {
         if self.config.clear_on_success {
             self.store.clear_lockout(identifier).await?;
             self.store.clear_attempts(identifier).await?;
+
+            // IP counters are kept by default: one valid login from an IP
+            // spraying many accounts should not reset its budget.
+            if self.config.clear_ip_on_success && identifier != ip_address {
+                self.store.clear_lockout(ip_address).await?;
+                self.store.clear_attempts(ip_address).await?;
+            }
         }
 
         Ok(())
     }
 
     /// Calculate lockout duration with exponential backoff
-    fn calculate_lockout_duration(&self, attempt_count: u32) -> Duration {
-        let excess = attempt_count.saturating_sub(self.config.max_attempts);
+    fn calculate_lockout_duration(&self, attempt_count: u32, thresholds: Thresholds) -> Duration {
+        let excess = attempt_count.saturating_sub(thresholds.lockout_after);
         let multiplier = self.config.backoff_multiplier.powi(excess as i32);
         let seconds = (self.config.initial_lockout_seconds as f64 * multiplier) as u64;
         let capped = seconds.min(self.config.max_lockout_seconds);
@@ -298,8 +410,23 @@ impl<S: BruteForceStore> BruteForceProtection<S> {
     }
 
     /// Calculate next lockout duration (for warning)
-    fn calculate_next_lockout_duration(&self, current_attempts: u32) -> Duration {
-        self.calculate_lockout_duration(current_attempts + 1)
+    fn calculate_next_lockout_duration(
+        &self,
+        current_attempts: u32,
+        thresholds: Thresholds,
+    ) -> Duration {
+        self.calculate_lockout_duration(current_attempts + 1, thresholds)
+    }
+
+    /// Calculate the artificial response delay with exponential backoff
+    fn calculate_delay_ms(&self, failed_count: u32, thresholds: Thresholds) -> u64 {
+        if failed_count < thresholds.delay_after {
+            return 0;
+        }
+        let excess = failed_count - thresholds.delay_after;
+        let multiplier = self.config.backoff_multiplier.powi(excess as i32);
+        let delay = (self.config.initial_delay_ms as f64 * multiplier) as u64;
+        delay.min(self.config.max_delay_ms)
     }
 
     /// Manually unlock an identifier
@@ -319,17 +446,15 @@ impl<S: BruteForceStore> BruteForceProtection<S> {
     }
 
     /// Check multiple identifiers (username + IP)
+    ///
+    /// Returns the stricter of the two: a lockout on either key wins, and
+    /// the delay and CAPTCHA requirement are the maximum of both.
     pub async fn check_multiple(&self, username: &str, ip: &str) -> Result<LockoutStatus> {
         let user_status = self.check_lockout(username).await?;
-        if user_status.is_locked {
-            return Ok(user_status);
-        }
 
         if self.config.track_ip {
-            let ip_status = self.check_lockout(ip).await?;
-            if ip_status.is_locked {
-                return Ok(ip_status);
-            }
+            let ip_status = self.check_key(ip, self.ip_thresholds()).await?;
+            return Ok(user_status.merge(ip_status));
         }
 
         Ok(user_status)
@@ -520,5 +645,73 @@ mod tests {
         let status = protection.check_lockout("user@example.com").await.unwrap();
         assert!(!status.is_locked);
         assert_eq!(status.failed_attempts, 0);
+        assert_eq!(status.delay_ms, 0);
+        assert!(!status.requires_captcha);
+    }
+
+    #[tokio::test]
+    async fn test_progressive_delay_then_captcha_then_lockout() {
+        let config = BruteForceConfig {
+            delay_after_attempts: 2,
+            initial_delay_ms: 500,
+            max_delay_ms: 1500,
+            captcha_after_attempts: 3,
+            max_attempts: 5,
+            ..Default::default()
+        };
+        let protection = BruteForceProtection::new(InMemoryBruteForceStore::new(), config);
+
+        let mut statuses = Vec::new();
+        for _ in 0..5 {
+            statuses.push(
+                protection
+                    .record_failure("alice", IdentifierType::Username, "1.2.3.4", None, None)
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        let delays: Vec<u64> = statuses.iter().take(4).map(|s| s.delay_ms).collect();
+        assert_eq!(delays, vec![0, 500, 1000, 1500]);
+        let captcha: Vec<bool> = statuses.iter().map(|s| s.requires_captcha).collect();
+        assert_eq!(captcha, vec![false, false, true, true, true]);
+        assert!(!statuses[3].is_locked);
+        assert!(statuses[4].is_locked);
+        assert_eq!(statuses[4].minutes_until_unlock(), Some(1));
+
+        let status = protection.check_multiple("alice", "1.2.3.4").await.unwrap();
+        assert!(status.is_locked);
+    }
+
+    #[tokio::test]
+    async fn test_single_ip_against_many_accounts_is_locked() {
+        let config = BruteForceConfig {
+            ip_delay_after_attempts: 2,
+            ip_captcha_after_attempts: 3,
+            ip_max_attempts: 4,
+            ..Default::default()
+        };
+        let protection = BruteForceProtection::new(InMemoryBruteForceStore::new(), config);
+
+        for i in 0..4 {
+            protection
+                .record_failure(
+                    &format!("user{}", i),
+                    IdentifierType::Username,
+                    "6.6.6.6",
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+
+        let status = protection.check_multiple("fresh", "6.6.6.6").await.unwrap();
+        assert!(status.is_locked);
+        assert!(status.requires_captcha);
+
+        let other_ip = protection.check_multiple("fresh", "7.7.7.7").await.unwrap();
+        assert!(!other_ip.is_locked);
+        assert!(!other_ip.requires_captcha);
     }
 }
@@ -76,15 +76,22 @@ pub use jwt::{Claims, JwtConfig, JwtManager, TokenPair, TokenType};
 pub use middleware::{
     AuthContext, AuthMethod, AuthMiddleware, AuthRequest, AuthRequirement, RouteProtection,
 };
-pub use oauth2_client::{OAuth2Client, OAuth2ClientProvider, OAuth2UserInfo, SocialConnection};
+pub use oauth2_client::{
+    LoginMethodStore, OAuth2Client, OAuth2ClientProvider, OAuth2UserInfo, SocialConnection,
+};
 pub use oauth2_provider::{
-    GrantType, OAuth2Client as OAuth2RegisteredClient, OAuth2Provider, OAuth2ProviderConfig,
+    CodeChallengeMethod, ConsentScope, ConsentScreen, GrantType,
+    OAuth2Client as OAuth2RegisteredClient, OAuth2Consent, OAuth2Provider, OAuth2ProviderConfig,
 };
 pub use password::{PasswordHasher, PasswordRules, PasswordStrength, PasswordValidator};
-pub use permission::{Permission, PermissionChecker, Role};
-pub use rate_limit::{RateLimitConfig, RateLimitResult, RateLimiter};
+pub use permission::{AsResource, Permission, PermissionChecker, Resource, Role, Subject};
+pub use rate_limit::{
+    Clock, InMemoryRateLimitStore, MockClock, RateLimitConfig, RateLimitResult, RateLimiter,
+    SystemClock,
+};
 pub use refresh_token::{
-    RefreshToken, RefreshTokenConfig, RefreshTokenManager, RefreshTokenStore, RevokeReason,
+    DeviceFingerprint, FingerprintAction, FingerprintDecision, RefreshToken, RefreshTokenConfig,
+    RefreshTokenManager, RefreshTokenStore, RevokeReason,
 };
 pub use session::{SameSite, Session, SessionConfig, SessionManager, SessionStore};
 pub use tokens::{
@@ -103,7 +110,7 @@ pub mod prelude {
     pub use crate::jwt::{Claims, JwtManager};
     pub use crate::middleware::{AuthContext, AuthMiddleware, AuthRequirement};
     pub use crate::password::{PasswordHasher, PasswordValidator};
-    pub use crate::permission::{Permission, PermissionChecker, Role};
+    pub use crate::permission::{Permission, PermissionChecker, Resource, Role, Subject};
     pub use crate::rate_limit::RateLimiter;
     pub use crate::session::{Session, SessionManager};
     pub use crate::totp::TotpManager;
@@ -3,7 +3,7 @@
 //! Authentication and authorization middleware for protecting routes.
 
 use crate::jwt::{Claims, JwtManager};
-use crate::permission::{Permission, PermissionChecker};
+use crate::permission::{Permission, PermissionChecker, Subject};
 use rustpress_core::error::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -68,6 +68,11 @@ impl AuthContext {
         self.roles.iter().any(|r| r == role)
     }
 
+    /// This user as the subject of a resource-scoped permission check
+    pub fn subject(&self) -> Subject<'_> {
+        Subject::new(self.user_id, &self.roles)
+    }
+
     pub fn has_permission(&self, permission: &str) -> bool {
         self.permissions.contains(permission) || self.permissions.contains("*:*")
     }
@@ -6,9 +6,11 @@
 use chrono::{DateTime, Duration, Utc};
 use rand::Rng;
 use rustpress_core::error::{Error, Result};
+use rustpress_plugins::crypto::{ApiKeyEncryptor, EncryptionKey};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// OAuth2 provider configuration
@@ -306,6 +308,12 @@ pub struct SocialConnection {
     pub email: Option<String>,
     pub access_token_hash: String,
     pub refresh_token_hash: Option<String>,
+    /// Provider access token, encrypted with the client's token key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_token_encrypted: Option<String>,
+    /// Provider refresh token, encrypted with the client's token key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token_encrypted: Option<String>,
     pub token_expires_at: Option<DateTime<Utc>>,
     pub metadata: serde_json::Value,
     pub connected_at: DateTime<Utc>,
@@ -352,12 +360,24 @@ pub trait SocialConnectionStore: Send + Sync {
     async fn delete_user_connection(&self, user_id: Uuid, provider: &str) -> Result<()>;
 }
 
+/// Lookup of a user's non-social login methods
+///
+/// Used to decide whether a social connection is the last way a user can
+/// sign in before it is unlinked.
+#[async_trait::async_trait]
+pub trait LoginMethodStore: Send + Sync {
+    /// Whether the user has a local password set
+    async fn has_password(&self, user_id: Uuid) -> Result<bool>;
+}
+
 /// OAuth2 client for social logins
 pub struct OAuth2Client<S: OAuth2StateStore, C: SocialConnectionStore> {
     providers: HashMap<String, OAuth2ClientProvider>,
     state_store: S,
     connection_store: C,
     config: OAuth2ClientConfig,
+    token_encryptor: Option<ApiKeyEncryptor>,
+    login_methods: Option<Arc<dyn LoginMethodStore>>,
 }
 
 impl<S: OAuth2StateStore, C: SocialConnectionStore> OAuth2Client<S, C> {
@@ -367,9 +387,23 @@ impl<S: OAuth2StateStore, C: SocialConnectionStore> OAuth2Client<S, C> {
             state_store,
             connection_store,
             config,
+            token_encryptor: None,
+            login_methods: None,
         }
     }
 
+    /// Encrypt provider tokens on linked connections with the given key
+    pub fn with_token_encryption(mut self, key: &EncryptionKey) -> Self {
+        self.token_encryptor = Some(ApiKeyEncryptor::new(key));
+        self
+    }
+
+    /// Set the lookup used to guard against unlinking the last login method
+    pub fn with_login_methods(mut self, login_methods: Arc<dyn LoginMethodStore>) -> Self {
+        self.login_methods = Some(login_methods);
+        self
+    }
+
     /// Register a provider
     pub fn register_provider(&mut self, provider: OAuth2ClientProvider) {
         self.providers.insert(provider.name.clone(), provider);
@@ -381,20 +415,7 @@ impl<S: OAuth2StateStore, C: SocialConnectionStore> OAuth2Client<S, C> {
         provider_name: &str,
         redirect_after: Option<String>,
     ) -> Result<(String, OAuth2State)> {
-        let provider = self
-            .providers
-            .get(provider_name)
-            .ok_or_else(|| Error::InvalidInput {
-                field: "provider".to_string(),
-                message: format!("Unknown provider: {}", provider_name),
-            })?;
-
-        if !provider.enabled {
-            return Err(Error::InvalidInput {
-                field: "provider".to_string(),
-                message: "Provider is disabled".to_string(),
-            });
-        }
+        let provider = self.enabled_provider(provider_name)?;
 
         let state_value = generate_random_string(32);
         let code_verifier = if self.config.use_pkce {
@@ -483,25 +504,31 @@ impl<S: OAuth2StateStore, C: SocialConnectionStore> OAuth2Client<S, C> {
             .exchange_code(provider, code, oauth_state.code_verifier.as_deref())
             .await?;
 
-        // Get user info
-        let user_info = if let Some(ref userinfo_url) = provider.userinfo_url {
-            self.fetch_user_info(provider, &token_response.access_token, userinfo_url)
-                .await?
-        } else {
-            // Try to extract from ID token if available
-            if let Some(ref id_token) = token_response.id_token {
-                self.parse_id_token(provider, id_token)?
-            } else {
-                return Err(Error::Internal {
-                    message: "No userinfo URL or ID token available".to_string(),
-                    request_id: None,
-                });
-            }
-        };
+        let user_info = self.resolve_user_info(provider, &token_response).await?;
 
         Ok((token_response, user_info))
     }
 
+    /// Get user info from the userinfo endpoint or the ID token
+    async fn resolve_user_info(
+        &self,
+        provider: &OAuth2ClientProvider,
+        token_response: &OAuth2TokenResponse,
+    ) -> Result<OAuth2UserInfo> {
+        if let Some(ref userinfo_url) = provider.userinfo_url {
+            self.fetch_user_info(provider, &token_response.access_token, userinfo_url)
+                .await
+        } else if let Some(ref id_token) = token_response.id_token {
+            // Try to extract from ID token if available
+            self.parse_id_token(provider, id_token)
+        } else {
+            Err(Error::Internal {
+                message: "No userinfo URL or ID token available".to_string(),
+                request_id: None,
+            })
+        }
+    }
+
     /// Exchange authorization code for tokens
     async fn exchange_code(
         &self,
@@ -592,6 +619,8 @@ impl<S: OAuth2StateStore, C: SocialConnectionStore> OAuth2Client<S, C> {
             email: user_info.email.clone(),
             access_token_hash: hash_token(&token_response.access_token),
             refresh_token_hash: token_response.refresh_token.as_ref().map(|t| hash_token(t)),
+            access_token_encrypted: self.encrypt_token(Some(&token_response.access_token))?,
+            refresh_token_encrypted: self.encrypt_token(token_response.refresh_token.as_deref())?,
             token_expires_at: token_response
                 .expires_in
                 .map(|e| now + Duration::seconds(e)),
@@ -604,6 +633,191 @@ impl<S: OAuth2StateStore, C: SocialConnectionStore> OAuth2Client<S, C> {
         Ok(connection)
     }
 
+    /// Link an additional social provider to an already-authenticated user
+    ///
+    /// Exchanges `code` with the provider and stores the connection with its
+    /// tokens encrypted. Fails if the social account belongs to another user
+    /// or the user already has a different account linked for the provider.
+    pub async fn link_provider(
+        &self,
+        user_id: Uuid,
+        provider_name: &str,
+        code: &str,
+    ) -> Result<SocialConnection> {
+        if self.token_encryptor.is_none() {
+            return Err(Error::Configuration {
+                message: "Token encryption key is required to link social accounts".to_string(),
+            });
+        }
+
+        let provider = self.enabled_provider(provider_name)?;
+        let token_response = self.exchange_code(provider, code, None).await?;
+        let user_info = self.resolve_user_info(provider, &token_response).await?;
+
+        self.store_link(user_id, provider_name, &user_info, &token_response)
+            .await
+    }
+
+    /// Store a new link or refresh the tokens of an existing one
+    async fn store_link(
+        &self,
+        user_id: Uuid,
+        provider_name: &str,
+        user_info: &OAuth2UserInfo,
+        token_response: &OAuth2TokenResponse,
+    ) -> Result<SocialConnection> {
+        if let Some(mut existing) = self
+            .connection_store
+            .get_by_provider(provider_name, &user_info.provider_user_id)
+            .await?
+        {
+            if existing.user_id != user_id {
+                return Err(Error::InvalidInput {
+                    field: "provider".to_string(),
+                    message: format!(
+                        "This {} account is already linked to another user",
+                        provider_name
+                    ),
+                });
+            }
+
+            let now = Utc::now();
+            existing.access_token_hash = hash_token(&token_response.access_token);
+            existing.refresh_token_hash =
+                token_response.refresh_token.as_ref().map(|t| hash_token(t));
+            existing.access_token_encrypted =
+                self.encrypt_token(Some(&token_response.access_token))?;
+            existing.refresh_token_encrypted =
+                self.encrypt_token(token_response.refresh_token.as_deref())?;
+            existing.token_expires_at = token_response
+                .expires_in
+                .map(|e| now + Duration::seconds(e));
+            existing.last_used_at = Some(now);
+            self.connection_store.update(&existing).await?;
+            return Ok(existing);
+        }
+
+        let already_linked = self
+            .connection_store
+            .get_user_connections(user_id)
+            .await?
+            .into_iter()
+            .any(|c| c.provider == provider_name);
+        if already_linked {
+            return Err(Error::InvalidInput {
+                field: "provider".to_string(),
+                message: format!(
+                    "A different {} account is already linked; unlink it first",
+                    provider_name
+                ),
+            });
+        }
+
+        self.link_account(user_id, provider_name, user_info, token_response)
+            .await
+    }
+
+    /// Unlink a social provider from a user
+    ///
+    /// Refuses to remove the user's last social connection unless they have
+    /// a password set, since that would leave no way to sign in. Without a
+    /// configured [`LoginMethodStore`] the user is assumed to have no password.
+    pub async fn unlink_provider(&self, user_id: Uuid, provider_name: &str) -> Result<()> {
+        let connections = self.connection_store.get_user_connections(user_id).await?;
+
+        if !connections.iter().any(|c| c.provider == provider_name) {
+            return Err(Error::not_found("social_connection", provider_name));
+        }
+
+        let remaining = connections
+            .iter()
+            .filter(|c| c.provider != provider_name)
+            .count();
+        if remaining == 0 {
+            let has_password = match &self.login_methods {
+                Some(login_methods) => login_methods.has_password(user_id).await?,
+                None => false,
+            };
+            if !has_password {
+                return Err(Error::InvalidInput {
+                    field: "provider".to_string(),
+                    message: format!(
+                        "Cannot unlink {}: it is the only login method and no password is set",
+                        provider_name
+                    ),
+                });
+            }
+        }
+
+        self.connection_store
+            .delete_user_connection(user_id, provider_name)
+            .await
+    }
+
+    /// Decrypt the provider access and refresh tokens of a connection
+    pub fn decrypt_tokens(
+        &self,
+        connection: &SocialConnection,
+    ) -> Result<(Option<String>, Option<String>)> {
+        Ok((
+            self.decrypt_token(connection.access_token_encrypted.as_deref())?,
+            self.decrypt_token(connection.refresh_token_encrypted.as_deref())?,
+        ))
+    }
+
+    fn encrypt_token(&self, token: Option<&str>) -> Result<Option<String>> {
+        match (&self.token_encryptor, token) {
+            (Some(encryptor), Some(token)) => {
+                encryptor
+                    .encrypt(token)
+                    .map(Some)
+                    .map_err(|e| Error::Internal {
+                        message: format!("Failed to encrypt provider token: {}", e),
+                        request_id: None,
+                    })
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn decrypt_token(&self, token: Option<&str>) -> Result<Option<String>> {
+        let Some(token) = token else {
+            return Ok(None);
+        };
+        let encryptor = self
+            .token_encryptor
+            .as_ref()
+            .ok_or_else(|| Error::Configuration {
+                message: "Token encryption key is not configured".to_string(),
+            })?;
+        encryptor
+            .decrypt(token)
+            .map(Some)
+            .map_err(|e| Error::Internal {
+                message: format!("Failed to decrypt provider token: {}", e),
+                request_id: None,
+            })
+    }
+
+    fn enabled_provider(&self, provider_name: &str) -> Result<&OAuth2ClientProvider> {
+        let provider = self
+            .providers
+            .get(provider_name)
+            .ok_or_else(|| Error::InvalidInput {
+                field: "provider".to_string(),
+                message: format!("Unknown provider: {}", provider_name),
+            })?;
+
+        if !provider.enabled {
+            return Err(Error::InvalidInput {
+                field: "provider".to_string(),
+                message: "Provider is disabled".to_string(),
+            });
+        }
+
+        Ok(provider)
+    }
+
     /// Get user's social connections
     pub async fn get_user_connections(&self, user_id: Uuid) -> Result<Vec<SocialConnection>> {
         self.connection_store.get_user_connections(user_id).await
@@ -851,6 +1065,123 @@ mod tests {
         assert!(url.contains(&format!("state={}", state.state)));
     }
 
+    struct PasswordFlag(bool);
+
+    #[async_trait::async_trait]
+    impl LoginMethodStore for PasswordFlag {
+        async fn has_password(&self, _user_id: Uuid) -> Result<bool> {
+            Ok(self.0)
+        }
+    }
+
+    fn linking_client(
+        has_password: bool,
+    ) -> OAuth2Client<InMemoryOAuth2StateStore, InMemorySocialConnectionStore> {
+        OAuth2Client::new(
+            InMemoryOAuth2StateStore::new(),
+            InMemorySocialConnectionStore::new(),
+            OAuth2ClientConfig::default(),
+        )
+        .with_token_encryption(&EncryptionKey::generate())
+        .with_login_methods(Arc::new(PasswordFlag(has_password)))
+    }
+
+    fn user_info(provider: &str, provider_user_id: &str) -> OAuth2UserInfo {
+        OAuth2UserInfo {
+            provider: provider.to_string(),
+            provider_user_id: provider_user_id.to_string(),
+            email: None,
+            email_verified: None,
+            name: None,
+            first_name: None,
+            last_name: None,
+            avatar_url: None,
+            locale: None,
+            raw_data: serde_json::Value::Null,
+        }
+    }
+
+    fn tokens(access_token: &str) -> OAuth2TokenResponse {
+        OAuth2TokenResponse {
+            access_token: access_token.to_string(),
+            token_type: "Bearer".to_string(),
+            expires_in: Some(3600),
+            refresh_token: Some(format!("{}-refresh", access_token)),
+            scope: None,
+            id_token: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_link_stores_encrypted_tokens() {
+        let client = linking_client(true);
+        let user_id = Uuid::now_v7();
+
+        let connection = client
+            .store_link(
+                user_id,
+                "github",
+                &user_info("github", "42"),
+                &tokens("gho_abc"),
+            )
+            .await
+            .unwrap();
+
+        let stored = connection.access_token_encrypted.clone().unwrap();
+        assert_ne!(stored, "gho_abc");
+        let (access, refresh) = client.decrypt_tokens(&connection).unwrap();
+        assert_eq!(access.as_deref(), Some("gho_abc"));
+        assert_eq!(refresh.as_deref(), Some("gho_abc-refresh"));
+    }
+
+    #[tokio::test]
+    async fn test_link_rejects_account_owned_by_other_user() {
+        let client = linking_client(true);
+        let info = user_info("github", "42");
+
+        client
+            .store_link(Uuid::now_v7(), "github", &info, &tokens("first"))
+            .await
+            .unwrap();
+        let err = client
+            .store_link(Uuid::now_v7(), "github", &info, &tokens("second"))
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("already linked to another user"));
+    }
+
+    #[tokio::test]
+    async fn test_unlink_last_login_method_requires_password() {
+        let user_id = Uuid::now_v7();
+
+        let client = linking_client(false);
+        client
+            .store_link(user_id, "github", &user_info("github", "42"), &tokens("a"))
+            .await
+            .unwrap();
+        client
+            .store_link(user_id, "google", &user_info("google", "g-1"), &tokens("b"))
+            .await
+            .unwrap();
+
+        client.unlink_provider(user_id, "google").await.unwrap();
+        assert!(client.unlink_provider(user_id, "github").await.is_err());
+        assert_eq!(client.get_user_connections(user_id).await.unwrap().len(), 1);
+
+        let client = linking_client(true);
+        client
+            .store_link(user_id, "github", &user_info("github", "42"), &tokens("a"))
+            .await
+            .unwrap();
+        client.unlink_provider(user_id, "github").await.unwrap();
+        assert!(client
+            .get_user_connections(user_id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn test_code_challenge() {
         let verifier = "test_verifier_string_that_is_long_enough";
@@ -104,6 +104,45 @@ pub enum CodeChallengeMethod {
     S256,
 }
 
+/// Longest accepted authorization code lifetime, as recommended by RFC 6749
+const MAX_AUTHORIZATION_CODE_LIFETIME_MINUTES: i64 = 10;
+
+/// Scopes a user has granted to a client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuth2Consent {
+    pub user_id: Uuid,
+    pub client_id: String,
+    pub scopes: HashSet<String>,
+    pub granted_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl OAuth2Consent {
+    pub fn covers(&self, scopes: &HashSet<String>) -> bool {
+        scopes.is_subset(&self.scopes)
+    }
+}
+
+/// A requested scope as shown on the consent screen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsentScope {
+    pub scope: String,
+    pub description: String,
+    pub previously_granted: bool,
+}
+
+/// What the UI needs to render the consent screen of an authorization request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsentScreen {
+    pub client_id: String,
+    pub client_name: String,
+    pub client_description: Option<String>,
+    pub redirect_uri: String,
+    pub scopes: Vec<ConsentScope>,
+    /// False when the user already granted every requested scope
+    pub consent_required: bool,
+}
+
 /// OAuth2 access token
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OAuth2AccessToken {
@@ -164,7 +203,12 @@ pub struct OAuth2ProviderConfig {
     pub access_token_lifetime: Duration,
     pub refresh_token_lifetime: Duration,
     pub allow_public_clients: bool,
+    /// Require PKCE from confidential clients too; public clients always need it
     pub require_pkce: bool,
+    /// Accept the `plain` PKCE method instead of only `S256`
+    pub allow_plain_pkce: bool,
+    /// Human-readable descriptions shown on the consent screen
+    pub scope_descriptions: HashMap<String, String>,
 }
 
 impl Default for OAuth2ProviderConfig {
@@ -176,6 +220,16 @@ impl Default for OAuth2ProviderConfig {
             refresh_token_lifetime: Duration::days(30),
             allow_public_clients: true,
             require_pkce: true,
+            allow_plain_pkce: false,
+            scope_descriptions: [
+                ("read", "Read your content"),
+                ("write", "Create and edit content on your behalf"),
+                ("profile", "See your name and profile"),
+                ("email", "See your email address"),
+            ]
+            .into_iter()
+            .map(|(scope, description)| (scope.to_string(), description.to_string()))
+            .collect(),
         }
     }
 }
@@ -192,7 +246,14 @@ pub trait OAuth2ProviderStore: Send + Sync {
     // Authorization codes
     async fn store_auth_code(&self, code: &AuthorizationCode) -> Result<()>;
     async fn get_auth_code(&self, code_hash: &str) -> Result<Option<AuthorizationCode>>;
-    async fn mark_auth_code_used(&self, id: Uuid) -> Result<()>;
+    /// Mark a code used, returning false if it already was. Must be atomic so
+    /// a code can only ever be redeemed once.
+    async fn mark_auth_code_used(&self, id: Uuid) -> Result<bool>;
+
+    // Consent
+    async fn store_consent(&self, consent: &OAuth2Consent) -> Result<()>;
+    async fn get_consent(&self, user_id: Uuid, client_id: &str) -> Result<Option<OAuth2Consent>>;
+    async fn delete_consent(&self, user_id: Uuid, client_id: &str) -> Result<()>;
 
     // Access tokens
     async fn store_access_token(&self, token: &OAuth2AccessToken) -> Result<()>;
@@ -311,17 +372,13 @@ impl<S: OAuth2ProviderStore> OAuth2Provider<S> {
         Ok(client)
     }
 
-    /// Create authorization code
-    pub async fn create_authorization_code(
+    /// Check the client, redirect URI and scopes of an authorization request
+    async fn validate_authorization_request(
         &self,
         client_id: &str,
-        user_id: Uuid,
         redirect_uri: &str,
-        scopes: HashSet<String>,
-        state: Option<String>,
-        code_challenge: Option<String>,
-        code_challenge_method: Option<CodeChallengeMethod>,
-    ) -> Result<String> {
+        scopes: &HashSet<String>,
+    ) -> Result<OAuth2Client> {
         let client = self.authenticate_client(client_id, None).await?;
 
         // Validate redirect URI
@@ -333,7 +390,7 @@ impl<S: OAuth2ProviderStore> OAuth2Provider<S> {
         }
 
         // Validate scopes
-        for scope in &scopes {
+        for scope in scopes {
             if !client.has_scope(scope) {
                 return Err(Error::InvalidInput {
                     field: "scope".to_string(),
@@ -342,17 +399,140 @@ impl<S: OAuth2ProviderStore> OAuth2Provider<S> {
             }
         }
 
-        // Require PKCE if configured
-        if self.config.require_pkce && code_challenge.is_none() {
-            return Err(Error::InvalidInput {
-                field: "code_challenge".to_string(),
-                message: "PKCE is required".to_string(),
-            });
+        Ok(client)
+    }
+
+    /// Start an authorization request, returning the consent screen data
+    ///
+    /// `consent_required` is false when the user already granted all
+    /// requested scopes to this client, in which case the UI can go straight
+    /// to `create_authorization_code`.
+    pub async fn prepare_authorization(
+        &self,
+        client_id: &str,
+        user_id: Uuid,
+        redirect_uri: &str,
+        scopes: &HashSet<String>,
+    ) -> Result<ConsentScreen> {
+        let client = self
+            .validate_authorization_request(client_id, redirect_uri, scopes)
+            .await?;
+        let granted = self
+            .store
+            .get_consent(user_id, client_id)
+            .await?
+            .map(|consent| consent.scopes)
+            .unwrap_or_default();
+
+        let mut requested: Vec<&String> = scopes.iter().collect();
+        requested.sort();
+        let scopes: Vec<ConsentScope> = requested
+            .into_iter()
+            .map(|scope| ConsentScope {
+                scope: scope.clone(),
+                description: self
+                    .config
+                    .scope_descriptions
+                    .get(scope)
+                    .cloned()
+                    .unwrap_or_else(|| scope.clone()),
+                previously_granted: granted.contains(scope),
+            })
+            .collect();
+
+        Ok(ConsentScreen {
+            client_id: client.client_id,
+            client_name: client.name,
+            client_description: client.description,
+            redirect_uri: redirect_uri.to_string(),
+            consent_required: scopes.iter().any(|s| !s.previously_granted),
+            scopes,
+        })
+    }
+
+    /// Record that a user approved scopes for a client
+    pub async fn grant_consent(
+        &self,
+        user_id: Uuid,
+        client_id: &str,
+        scopes: &HashSet<String>,
+    ) -> Result<()> {
+        let now = Utc::now();
+        let consent = match self.store.get_consent(user_id, client_id).await? {
+            Some(mut consent) => {
+                consent.scopes.extend(scopes.iter().cloned());
+                consent.updated_at = now;
+                consent
+            }
+            None => OAuth2Consent {
+                user_id,
+                client_id: client_id.to_string(),
+                scopes: scopes.clone(),
+                granted_at: now,
+                updated_at: now,
+            },
+        };
+
+        self.store.store_consent(&consent).await
+    }
+
+    /// Withdraw all scopes a user granted to a client
+    pub async fn revoke_consent(&self, user_id: Uuid, client_id: &str) -> Result<()> {
+        self.store.delete_consent(user_id, client_id).await
+    }
+
+    /// Create authorization code
+    ///
+    /// The user must have consented to the requested scopes first, see
+    /// `prepare_authorization` and `grant_consent`.
+    pub async fn create_authorization_code(
+        &self,
+        client_id: &str,
+        user_id: Uuid,
+        redirect_uri: &str,
+        scopes: HashSet<String>,
+        state: Option<String>,
+        code_challenge: Option<String>,
+        code_challenge_method: Option<CodeChallengeMethod>,
+    ) -> Result<String> {
+        let client = self
+            .validate_authorization_request(client_id, redirect_uri, &scopes)
+            .await?;
+
+        let consented = self
+            .store
+            .get_consent(user_id, client_id)
+            .await?
+            .is_some_and(|consent| consent.covers(&scopes));
+        if !consented {
+            return Err(Error::authorization(
+                "authorize client",
+                "user consent to the requested scopes",
+            ));
         }
 
+        // Public clients can't keep a secret, so PKCE is their only protection
+        // against intercepted codes
+        let code_challenge_method = match &code_challenge {
+            Some(challenge) => {
+                Some(self.validate_code_challenge(challenge, code_challenge_method)?)
+            }
+            None if self.config.require_pkce || !client.is_confidential => {
+                return Err(Error::InvalidInput {
+                    field: "code_challenge".to_string(),
+                    message: "PKCE is required".to_string(),
+                });
+            }
+            None => None,
+        };
+
         let code = Self::generate_token(32);
         let code_hash = Self::hash_token(&code);
         let now = Utc::now();
+        let lifetime = self
+            .config
+            .authorization_code_lifetime
+            .min(Duration::minutes(MAX_AUTHORIZATION_CODE_LIFETIME_MINUTES));
 
         let auth_code = AuthorizationCode {
             id: Uuid::now_v7(),
@@ -364,7 +544,7 @@ impl<S: OAuth2ProviderStore> OAuth2Provider<S> {
             state,
             code_challenge,
             code_challenge_method,
-            expires_at: now + self.config.authorization_code_lifetime,
+            expires_at: now + lifetime,
             created_at: now,
             used_at: None,
         };
@@ -373,6 +553,39 @@ impl<S: OAuth2ProviderStore> OAuth2Provider<S> {
         Ok(code)
     }
 
+    /// Check a PKCE challenge, returning its method (`plain` when omitted)
+    fn validate_code_challenge(
+        &self,
+        challenge: &str,
+        method: Option<CodeChallengeMethod>,
+    ) -> Result<CodeChallengeMethod> {
+        let method = method.unwrap_or(CodeChallengeMethod::Plain);
+        let well_formed = match method {
+            CodeChallengeMethod::Plain if !self.config.allow_plain_pkce => {
+                return Err(Error::InvalidInput {
+                    field: "code_challenge_method".to_string(),
+                    message: "The plain PKCE method is not allowed, use S256".to_string(),
+                });
+            }
+            CodeChallengeMethod::Plain => is_valid_code_verifier(challenge),
+            // base64url of a SHA-256 digest, without padding
+            CodeChallengeMethod::S256 => {
+                challenge.len() == 43
+                    && challenge
+                        .bytes()
+                        .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+            }
+        };
+
+        if !well_formed {
+            return Err(Error::InvalidInput {
+                field: "code_challenge".to_string(),
+                message: "Malformed code challenge".to_string(),
+            });
+        }
+        Ok(method)
+    }
+
     /// Exchange authorization code for tokens
     pub async fn exchange_authorization_code(
         &self,
@@ -418,33 +631,51 @@ impl<S: OAuth2ProviderStore> OAuth2Provider<S> {
             });
         }
 
-        // Verify PKCE
-        if let Some(challenge) = &auth_code.code_challenge {
-            let verifier = code_verifier.ok_or_else(|| Error::InvalidInput {
-                field: "code_verifier".to_string(),
-                message: "Code verifier required".to_string(),
-            })?;
+        // Burn the code before checking the verifier, so a stolen code
+        // can't be used to guess it
+        if !self.store.mark_auth_code_used(auth_code.id).await? {
+            return Err(Error::Authentication {
+                message: "Authorization code expired or already used".to_string(),
+            });
+        }
 
-            let valid = match auth_code.code_challenge_method {
-                Some(CodeChallengeMethod::S256) => {
-                    let mut hasher = Sha256::new();
-                    hasher.update(verifier.as_bytes());
-                    let computed = base64_url_encode(&hasher.finalize());
-                    &computed == challenge
+        // Verify PKCE
+        match (&auth_code.code_challenge, code_verifier) {
+            (Some(challenge), Some(verifier)) => {
+                let valid = is_valid_code_verifier(verifier)
+                    && match auth_code.code_challenge_method {
+                        Some(CodeChallengeMethod::S256) => &s256_challenge(verifier) == challenge,
+                        Some(CodeChallengeMethod::Plain) | None => {
+                            self.config.allow_plain_pkce && verifier == challenge
+                        }
+                    };
+
+                if !valid {
+                    return Err(Error::Authentication {
+                        message: "Invalid code verifier".to_string(),
+                    });
                 }
-                Some(CodeChallengeMethod::Plain) | None => verifier == challenge,
-            };
-
-            if !valid {
+            }
+            (Some(_), None) => {
+                return Err(Error::InvalidInput {
+                    field: "code_verifier".to_string(),
+                    message: "Code verifier required".to_string(),
+                });
+            }
+            (None, Some(_)) => {
+                return Err(Error::InvalidInput {
+                    field: "code_verifier".to_string(),
+                    message: "Authorization code was not issued with PKCE".to_string(),
+                });
+            }
+            (None, None) if !client.is_confidential => {
                 return Err(Error::Authentication {
-                    message: "Invalid code verifier".to_string(),
+                    message: "PKCE is required for public clients".to_string(),
                 });
             }
+            (None, None) => {}
         }
 
-        // Mark code as used
-        self.store.mark_auth_code_used(auth_code.id).await?;
-
         // Generate tokens
         self.generate_tokens(&client, Some(auth_code.user_id), &auth_code.scopes)
             .await
@@ -622,6 +853,7 @@ impl<S: OAuth2ProviderStore> OAuth2Provider<S> {
 pub struct InMemoryOAuth2ProviderStore {
     clients: RwLock<HashMap<String, OAuth2Client>>,
     auth_codes: RwLock<HashMap<String, AuthorizationCode>>,
+    consents: RwLock<HashMap<(Uuid, String), OAuth2Consent>>,
     access_tokens: RwLock<HashMap<String, OAuth2AccessToken>>,
     refresh_tokens: RwLock<HashMap<String, OAuth2RefreshToken>>,
 }
@@ -631,6 +863,7 @@ impl InMemoryOAuth2ProviderStore {
         Self {
             clients: RwLock::new(HashMap::new()),
             auth_codes: RwLock::new(HashMap::new()),
+            consents: RwLock::new(HashMap::new()),
             access_tokens: RwLock::new(HashMap::new()),
             refresh_tokens: RwLock::new(HashMap::new()),
         }
@@ -697,17 +930,49 @@ impl OAuth2ProviderStore for InMemoryOAuth2ProviderStore {
         Ok(codes.get(code_hash).cloned())
     }
 
-    async fn mark_auth_code_used(&self, id: Uuid) -> Result<()> {
+    async fn mark_auth_code_used(&self, id: Uuid) -> Result<bool> {
         let mut codes = self.auth_codes.write().map_err(|_| Error::Internal {
             message: "Lock poisoned".to_string(),
             request_id: None,
         })?;
         for code in codes.values_mut() {
             if code.id == id {
+                if code.used_at.is_some() {
+                    return Ok(false);
+                }
                 code.used_at = Some(Utc::now());
-                break;
+                return Ok(true);
             }
         }
+        Ok(false)
+    }
+
+    async fn store_consent(&self, consent: &OAuth2Consent) -> Result<()> {
+        let mut consents = self.consents.write().map_err(|_| Error::Internal {
+            message: "Lock poisoned".to_string(),
+            request_id: None,
+        })?;
+        consents.insert(
+            (consent.user_id, consent.client_id.clone()),
+            consent.clone(),
+        );
+        Ok(())
+    }
+
+    async fn get_consent(&self, user_id: Uuid, client_id: &str) -> Result<Option<OAuth2Consent>> {
+        let consents = self.consents.read().map_err(|_| Error::Internal {
+            message: "Lock poisoned".to_string(),
+            request_id: None,
+        })?;
+        Ok(consents.get(&(user_id, client_id.to_string())).cloned())
+    }
+
+    async fn delete_consent(&self, user_id: Uuid, client_id: &str) -> Result<()> {
+        let mut consents = self.consents.write().map_err(|_| Error::Internal {
+            message: "Lock poisoned".to_string(),
+            request_id: None,
+        })?;
+        consents.remove(&(user_id, client_id.to_string()));
         Ok(())
     }
 
@@ -846,6 +1111,21 @@ fn base64_url_encode(bytes: &[u8]) -> String {
     base64::Engine::encode(&base64::engine::general_purpose::URL_SAFE_NO_PAD, bytes)
 }
 
+/// S256 code challenge for a PKCE verifier
+fn s256_challenge(verifier: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(verifier.as_bytes());
+    base64_url_encode(&hasher.finalize())
+}
+
+/// RFC 7636: 43-128 characters from the unreserved URI set
+fn is_valid_code_verifier(verifier: &str) -> bool {
+    (43..=128).contains(&verifier.len())
+        && verifier
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~'))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -902,4 +1182,222 @@ mod tests {
         assert!(!response.access_token.is_empty());
         assert_eq!(response.token_type, "Bearer");
     }
+
+    const REDIRECT: &str = "https://example.com/callback";
+    const VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+    async fn public_client(
+        config: OAuth2ProviderConfig,
+    ) -> (OAuth2Provider<InMemoryOAuth2ProviderStore>, OAuth2Client) {
+        let provider = OAuth2Provider::new(InMemoryOAuth2ProviderStore::new(), config);
+        let (client, _) = provider
+            .register_client(
+                "Mobile App".to_string(),
+                vec![REDIRECT.to_string()],
+                ["read".to_string(), "write".to_string()]
+                    .into_iter()
+                    .collect(),
+                [GrantType::AuthorizationCode].into_iter().collect(),
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+        (provider, client)
+    }
+
+    fn scopes(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_s256_challenge_matches_rfc_example() {
+        assert_eq!(
+            s256_challenge(VERIFIER),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        assert!(is_valid_code_verifier(VERIFIER));
+        assert!(!is_valid_code_verifier("too-short"));
+    }
+
+    #[tokio::test]
+    async fn test_public_client_requires_s256_pkce() {
+        let config = OAuth2ProviderConfig {
+            require_pkce: false,
+            ..Default::default()
+        };
+        let (provider, client) = public_client(config).await;
+        let user_id = Uuid::now_v7();
+        provider
+            .grant_consent(user_id, &client.client_id, &scopes(&["read"]))
+            .await
+            .unwrap();
+
+        let authorize = |challenge: Option<String>, method| {
+            provider.create_authorization_code(
+                &client.client_id,
+                user_id,
+                REDIRECT,
+                scopes(&["read"]),
+                None,
+                challenge,
+                method,
+            )
+        };
+
+        // No challenge, even though require_pkce is off
+        assert!(authorize(None, None).await.is_err());
+        // plain is disabled by default
+        assert!(
+            authorize(Some(VERIFIER.to_string()), Some(CodeChallengeMethod::Plain))
+                .await
+                .is_err()
+        );
+
+        let code = authorize(
+            Some(s256_challenge(VERIFIER)),
+            Some(CodeChallengeMethod::S256),
+        )
+        .await
+        .unwrap();
+
+        // A wrong verifier burns the code
+        let wrong = "x".repeat(43);
+        assert!(provider
+            .exchange_authorization_code(&code, &client.client_id, None, REDIRECT, Some(&wrong))
+            .await
+            .is_err());
+        assert!(provider
+            .exchange_authorization_code(&code, &client.client_id, None, REDIRECT, Some(VERIFIER))
+            .await
+            .is_err());
+
+        let code = authorize(
+            Some(s256_challenge(VERIFIER)),
+            Some(CodeChallengeMethod::S256),
+        )
+        .await
+        .unwrap();
+        assert!(provider
+            .exchange_authorization_code(&code, &client.client_id, None, REDIRECT, None)
+            .await
+            .is_err());
+
+        let code = authorize(
+            Some(s256_challenge(VERIFIER)),
+            Some(CodeChallengeMethod::S256),
+        )
+        .await
+        .unwrap();
+        let response = provider
+            .exchange_authorization_code(&code, &client.client_id, None, REDIRECT, Some(VERIFIER))
+            .await
+            .unwrap();
+        assert_eq!(response.scope.as_deref(), Some("read"));
+
+        // Codes are single-use
+        assert!(provider
+            .exchange_authorization_code(&code, &client.client_id, None, REDIRECT, Some(VERIFIER))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_authorization_code_lifetime_is_capped() {
+        let config = OAuth2ProviderConfig {
+            authorization_code_lifetime: Duration::hours(6),
+            ..Default::default()
+        };
+        let (provider, client) = public_client(config).await;
+        let user_id = Uuid::now_v7();
+        provider
+            .grant_consent(user_id, &client.client_id, &scopes(&["read"]))
+            .await
+            .unwrap();
+
+        let code = provider
+            .create_authorization_code(
+                &client.client_id,
+                user_id,
+                REDIRECT,
+                scopes(&["read"]),
+                None,
+                Some(s256_challenge(VERIFIER)),
+                Some(CodeChallengeMethod::S256),
+            )
+            .await
+            .unwrap();
+
+        let stored = provider
+            .store
+            .get_auth_code(&OAuth2Provider::<InMemoryOAuth2ProviderStore>::hash_token(
+                &code,
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(stored.expires_at - stored.created_at <= Duration::minutes(10));
+    }
+
+    #[tokio::test]
+    async fn test_consent_is_remembered() {
+        let (provider, client) = public_client(OAuth2ProviderConfig::default()).await;
+        let user_id = Uuid::now_v7();
+        let requested = scopes(&["read", "write"]);
+
+        let screen = provider
+            .prepare_authorization(&client.client_id, user_id, REDIRECT, &requested)
+            .await
+            .unwrap();
+        assert!(screen.consent_required);
+        assert_eq!(screen.client_name, "Mobile App");
+        assert_eq!(screen.scopes[0].scope, "read");
+        assert_eq!(screen.scopes[0].description, "Read your content");
+
+        // No code without consent
+        assert!(provider
+            .create_authorization_code(
+                &client.client_id,
+                user_id,
+                REDIRECT,
+                requested.clone(),
+                None,
+                Some(s256_challenge(VERIFIER)),
+                Some(CodeChallengeMethod::S256),
+            )
+            .await
+            .is_err());
+
+        provider
+            .grant_consent(user_id, &client.client_id, &scopes(&["read"]))
+            .await
+            .unwrap();
+        let screen = provider
+            .prepare_authorization(&client.client_id, user_id, REDIRECT, &requested)
+            .await
+            .unwrap();
+        assert!(screen.consent_required);
+        assert!(screen.scopes[0].previously_granted);
+        assert!(!screen.scopes[1].previously_granted);
+
+        provider
+            .grant_consent(user_id, &client.client_id, &requested)
+            .await
+            .unwrap();
+        let screen = provider
+            .prepare_authorization(&client.client_id, user_id, REDIRECT, &scopes(&["write"]))
+            .await
+            .unwrap();
+        assert!(!screen.consent_required);
+
+        provider
+            .revoke_consent(user_id, &client.client_id)
+            .await
+            .unwrap();
+        let screen = provider
+            .prepare_authorization(&client.client_id, user_id, REDIRECT, &requested)
+            .await
+            .unwrap();
+        assert!(screen.consent_required);
+    }
 }
@@ -1,8 +1,14 @@
 //! Permission and role management.
+//!
+//! Permissions are `resource:action` pairs granted to roles. A bare action
+//! (`posts:edit`) applies to every item of the resource type, while
+//! `{action}_own` and `{action}_others` (`posts:edit_own`, `posts:edit_others`)
+//! scope it by who owns the item; see [`PermissionChecker::can`].
 
 use rustpress_core::error::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
 
 /// A permission for a specific action on a resource
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -24,6 +30,16 @@ impl Permission {
         Self::new(resource, "*")
     }
 
+    /// Create a permission for an action on items the user owns
+    pub fn own(resource: impl Into<String>, action: &str) -> Self {
+        Self::new(resource, format!("{}_own", action))
+    }
+
+    /// Create a permission for an action on items owned by other users
+    pub fn others(resource: impl Into<String>, action: &str) -> Self {
+        Self::new(resource, format!("{}_others", action))
+    }
+
     /// Create a super admin permission
     pub fn super_admin() -> Self {
         Self::new("*", "*")
@@ -82,12 +98,27 @@ pub mod permissions {
     pub fn posts_edit() -> Permission {
         Permission::new("posts", "edit")
     }
+    pub fn posts_edit_own() -> Permission {
+        Permission::own("posts", "edit")
+    }
+    pub fn posts_edit_others() -> Permission {
+        Permission::others("posts", "edit")
+    }
     pub fn posts_delete() -> Permission {
         Permission::new("posts", "delete")
     }
+    pub fn posts_delete_own() -> Permission {
+        Permission::own("posts", "delete")
+    }
+    pub fn posts_delete_others() -> Permission {
+        Permission::others("posts", "delete")
+    }
     pub fn posts_publish() -> Permission {
         Permission::new("posts", "publish")
     }
+    pub fn posts_publish_own() -> Permission {
+        Permission::own("posts", "publish")
+    }
     pub fn posts_manage() -> Permission {
         Permission::all("posts")
     }
@@ -136,9 +167,15 @@ pub mod permissions {
     pub fn media_edit() -> Permission {
         Permission::new("media", "edit")
     }
+    pub fn media_edit_own() -> Permission {
+        Permission::own("media", "edit")
+    }
     pub fn media_delete() -> Permission {
         Permission::new("media", "delete")
     }
+    pub fn media_delete_own() -> Permission {
+        Permission::own("media", "delete")
+    }
     pub fn media_manage() -> Permission {
         Permission::all("media")
     }
@@ -180,6 +217,19 @@ pub mod permissions {
     pub fn themes_manage() -> Permission {
         Permission::all("themes")
     }
+
+    // Cache
+    pub fn cache_read() -> Permission {
+        Permission::new("cache", "read")
+    }
+    pub fn cache_manage() -> Permission {
+        Permission::new("cache", "manage")
+    }
+
+    // System
+    pub fn system_monitor() -> Permission {
+        Permission::new("system", "monitor")
+    }
 }
 
 /// A role with a set of permissions
@@ -259,10 +309,12 @@ pub mod roles {
             .with_description("Can create and edit own posts")
             .with_permissions([
                 posts_create(),
-                posts_edit(),
-                posts_publish(),
+                posts_edit_own(),
+                posts_delete_own(),
+                posts_publish_own(),
                 media_upload(),
-                media_edit(),
+                media_edit_own(),
+                media_delete_own(),
                 comments_read(),
             ])
     }
@@ -271,7 +323,12 @@ pub mod roles {
     pub fn contributor() -> Role {
         Role::new("contributor", "Contributor")
             .with_description("Can create posts but not publish")
-            .with_permissions([posts_create(), posts_edit(), media_upload()])
+            .with_permissions([
+                posts_create(),
+                posts_edit_own(),
+                posts_delete_own(),
+                media_upload(),
+            ])
     }
 
     /// Subscriber can read and comment
@@ -287,6 +344,57 @@ pub mod roles {
     }
 }
 
+/// The user a resource-scoped permission check is made for
+#[derive(Debug, Clone, Copy)]
+pub struct Subject<'a> {
+    pub user_id: Uuid,
+    pub roles: &'a [String],
+}
+
+impl<'a> Subject<'a> {
+    pub fn new(user_id: Uuid, roles: &'a [String]) -> Self {
+        Self { user_id, roles }
+    }
+}
+
+/// An item a permission check is made against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resource {
+    /// Resource type, matching [`Permission::resource`] (e.g. `posts`)
+    pub kind: String,
+    /// Owner of the item; `None` for unowned items or the type as a whole
+    pub owner_id: Option<Uuid>,
+}
+
+impl Resource {
+    /// The resource type as a whole, e.g. for `create`
+    pub fn new(kind: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            owner_id: None,
+        }
+    }
+
+    /// A single item owned by `owner_id`
+    pub fn owned_by(kind: impl Into<String>, owner_id: Uuid) -> Self {
+        Self {
+            kind: kind.into(),
+            owner_id: Some(owner_id),
+        }
+    }
+}
+
+/// Types that can be checked with [`PermissionChecker::can`]
+pub trait AsResource {
+    fn as_resource(&self) -> Resource;
+}
+
+impl AsResource for Resource {
+    fn as_resource(&self) -> Resource {
+        self.clone()
+    }
+}
+
 /// Permission checker with role hierarchy support
 pub struct PermissionChecker {
     roles: HashMap<String, Role>,
@@ -355,8 +463,42 @@ impl PermissionChecker {
         }
     }
 
-    /// Check if user with given roles can perform action
-    pub fn can(&self, user_roles: &[String], resource: &str, action: &str) -> bool {
+    /// Check whether `user` may perform `action` on `resource`.
+    ///
+    /// Permissions from all of the user's roles, inherited ones included, are
+    /// combined. A super admin may do anything, and a permission for the bare
+    /// action covers every item. Otherwise the user needs `{action}_own` for
+    /// items they own and `{action}_others` for items owned by someone else;
+    /// items without an owner need the bare action.
+    pub fn can(&self, user: &Subject<'_>, action: &str, resource: &impl AsResource) -> bool {
+        let resource = resource.as_resource();
+        let permissions: HashSet<Permission> = user
+            .roles
+            .iter()
+            .flat_map(|role| self.get_all_permissions(role))
+            .collect();
+
+        if permissions.contains(&Permission::super_admin()) {
+            return true;
+        }
+
+        let granted = |action: &str| {
+            let required = Permission::new(resource.kind.as_str(), action);
+            permissions.iter().any(|p| p.covers(&required))
+        };
+        if granted(action) {
+            return true;
+        }
+
+        match resource.owner_id {
+            Some(owner_id) if owner_id == user.user_id => granted(&format!("{}_own", action)),
+            Some(_) => granted(&format!("{}_others", action)),
+            None => false,
+        }
+    }
+
+    /// Check if user with given roles can perform action on a whole resource type
+    pub fn roles_can(&self, user_roles: &[String], resource: &str, action: &str) -> bool {
         let permission = Permission::new(resource, action);
         user_roles
             .iter()
@@ -412,9 +554,69 @@ mod tests {
     fn test_permission_checker() {
         let checker = PermissionChecker::with_default_roles();
 
-        assert!(checker.can(&["administrator".to_string()], "posts", "delete"));
-        assert!(checker.can(&["editor".to_string()], "posts", "edit"));
-        assert!(!checker.can(&["subscriber".to_string()], "posts", "delete"));
+        assert!(checker.roles_can(&["administrator".to_string()], "posts", "delete"));
+        assert!(checker.roles_can(&["editor".to_string()], "posts", "edit"));
+        assert!(!checker.roles_can(&["subscriber".to_string()], "posts", "delete"));
+    }
+
+    #[test]
+    fn test_ownership_scoped_permissions() {
+        let checker = PermissionChecker::with_default_roles();
+        let me = Uuid::new_v4();
+        let mine = Resource::owned_by("posts", me);
+        let theirs = Resource::owned_by("posts", Uuid::new_v4());
+
+        let author = ["author".to_string()];
+        let author = Subject::new(me, &author);
+        assert!(checker.can(&author, "edit", &mine));
+        assert!(checker.can(&author, "publish", &mine));
+        assert!(!checker.can(&author, "edit", &theirs));
+        assert!(!checker.can(&author, "delete", &theirs));
+        assert!(checker.can(&author, "create", &Resource::new("posts")));
+        assert!(!checker.can(&author, "edit", &Resource::new("posts")));
+
+        let editor = ["editor".to_string()];
+        let editor = Subject::new(me, &editor);
+        assert!(checker.can(&editor, "edit", &theirs));
+        assert!(checker.can(&editor, "delete", &theirs));
+    }
+
+    #[test]
+    fn test_scoped_permissions_compose_across_roles() {
+        let mut checker = PermissionChecker::with_default_roles();
+        checker.register_role(
+            Role::new("post_reviewer", "Post Reviewer")
+                .with_permission(permissions::posts_edit_others()),
+        );
+        let me = Uuid::new_v4();
+        let theirs = Resource::owned_by("posts", Uuid::new_v4());
+
+        let reviewer = ["post_reviewer".to_string()];
+        assert!(checker.can(&Subject::new(me, &reviewer), "edit", &theirs));
+        assert!(!checker.can(
+            &Subject::new(me, &reviewer),
+            "edit",
+            &Resource::owned_by("posts", me)
+        ));
+
+        let roles = ["contributor".to_string(), "post_reviewer".to_string()];
+        let user = Subject::new(me, &roles);
+        assert!(checker.can(&user, "edit", &theirs));
+        assert!(checker.can(&user, "edit", &Resource::owned_by("posts", me)));
+        assert!(!checker.can(&user, "delete", &theirs));
+        assert!(!checker.can(&user, "edit", &Resource::owned_by("pages", me)));
+    }
+
+    #[test]
+    fn test_super_admin_short_circuits() {
+        let checker = PermissionChecker::with_default_roles();
+        let roles = ["subscriber".to_string(), "administrator".to_string()];
+        let admin = Subject::new(Uuid::new_v4(), &roles);
+        let theirs = Resource::owned_by("anything", Uuid::new_v4());
+        assert!(checker.can(&admin, "purge", &theirs));
+
+        let nobody = Subject::new(Uuid::new_v4(), &[]);
+        assert!(!checker.can(&nobody, "read", &Resource::new("posts")));
     }
 
     #[test]
@@ -6,7 +6,59 @@ use chrono::{DateTime, Duration, Utc};
 use rustpress_core::error::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
+
+/// Source of the current time for rate limiting
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Wall-clock time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Manually advanced clock for deterministic tests
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<RwLock<DateTime<Utc>>>,
+}
+
+impl MockClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(RwLock::new(start)),
+        }
+    }
+
+    /// Move the clock forward
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.write().unwrap_or_else(|e| e.into_inner());
+        *now += by;
+    }
+
+    /// Set the clock to a specific time
+    pub fn set(&self, to: DateTime<Utc>) {
+        *self.now.write().unwrap_or_else(|e| e.into_inner()) = to;
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new(Utc::now())
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
 
 /// Rate limit configuration
 #[derive(Debug, Clone)]
@@ -128,11 +180,27 @@ pub trait RateLimitStore: Send + Sync {
 pub struct RateLimiter<S: RateLimitStore> {
     store: S,
     config: RateLimitConfig,
+    clock: Arc<dyn Clock>,
 }
 
 impl<S: RateLimitStore> RateLimiter<S> {
     pub fn new(store: S, config: RateLimitConfig) -> Self {
-        Self { store, config }
+        Self {
+            store,
+            config,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Use `clock` for reset and retry-after times; share it with the store
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Get the configuration
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.config
     }
 
     /// Check and record a request
@@ -157,7 +225,7 @@ impl<S: RateLimitStore> RateLimiter<S> {
         let reset_at = window_start + Duration::seconds(self.config.window_seconds as i64);
 
         if count > limit {
-            let retry_after = (reset_at - Utc::now()).num_seconds().max(0) as u64;
+            let retry_after = (reset_at - self.clock.now()).num_seconds().max(0) as u64;
             return Ok(RateLimitResult::denied(limit, reset_at, retry_after));
         }
 
@@ -173,15 +241,16 @@ impl<S: RateLimitStore> RateLimiter<S> {
             .await?;
 
         let count = requests.len() as u32;
-        let reset_at = Utc::now() + Duration::seconds(self.config.window_seconds as i64);
+        let reset_at = self.clock.now() + Duration::seconds(self.config.window_seconds as i64);
 
         if count > limit {
             // Find oldest request in window to calculate retry_after
-            let oldest = requests.first().cloned().unwrap_or(Utc::now());
+            let now = self.clock.now();
+            let oldest = requests.first().cloned().unwrap_or(now);
             let retry_after = ((oldest + Duration::seconds(self.config.window_seconds as i64))
-                - Utc::now())
-            .num_seconds()
-            .max(0) as u64;
+                - now)
+                .num_seconds()
+                .max(0) as u64;
             return Ok(RateLimitResult::denied(limit, reset_at, retry_after));
         }
 
@@ -194,7 +263,7 @@ impl<S: RateLimitStore> RateLimiter<S> {
         let key = format!("{}:{}", self.config.key_prefix, identifier);
         let total_limit = self.config.max_requests + self.config.burst_size;
 
-        let (count, window_start) = self.store.get(&key).await?.unwrap_or((0, Utc::now()));
+        let (count, window_start) = self.store.get(&key).await?.unwrap_or((0, self.clock.now()));
 
         let reset_at = window_start + Duration::seconds(self.config.window_seconds as i64);
         let remaining = total_limit.saturating_sub(count);
@@ -227,12 +296,19 @@ impl<S: RateLimitStore> RateLimiter<S> {
 /// In-memory rate limit store
 pub struct InMemoryRateLimitStore {
     entries: RwLock<HashMap<String, RateLimitEntry>>,
+    clock: Arc<dyn Clock>,
 }
 
 impl InMemoryRateLimitStore {
     pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// Create a store that reads the time from `clock`
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
         Self {
             entries: RwLock::new(HashMap::new()),
+            clock,
         }
     }
 }
@@ -260,7 +336,7 @@ impl RateLimitStore for InMemoryRateLimitStore {
             request_id: None,
         })?;
 
-        let now = Utc::now();
+        let now = self.clock.now();
         let window_duration = Duration::seconds(window_seconds as i64);
 
         let entry = entries
@@ -287,7 +363,7 @@ impl RateLimitStore for InMemoryRateLimitStore {
             request_id: None,
         })?;
 
-        let now = Utc::now();
+        let now = self.clock.now();
         let window_start = now - Duration::seconds(window_seconds as i64);
 
         Ok(entries
@@ -308,7 +384,7 @@ impl RateLimitStore for InMemoryRateLimitStore {
             request_id: None,
         })?;
 
-        let now = Utc::now();
+        let now = self.clock.now();
         let window_start = now - Duration::seconds(window_seconds as i64);
 
         let entry = entries
@@ -403,6 +479,34 @@ mod tests {
         assert!(result.retry_after.is_some());
     }
 
+    #[tokio::test]
+    async fn test_rate_limit_window_with_mock_clock() {
+        let clock = Arc::new(MockClock::default());
+        let store = InMemoryRateLimitStore::with_clock(clock.clone());
+        let config = RateLimitConfig {
+            max_requests: 2,
+            window_seconds: 60,
+            sliding_window: false,
+            burst_size: 0,
+            key_prefix: "test".to_string(),
+        };
+        let limiter = RateLimiter::new(store, config).with_clock(clock.clone());
+
+        limiter.check("user_1").await.unwrap();
+        clock.advance(Duration::seconds(20));
+        limiter.check("user_1").await.unwrap();
+
+        let result = limiter.check("user_1").await.unwrap();
+        assert!(!result.allowed);
+        assert_eq!(result.retry_after, Some(40));
+
+        // Window expires and the counter resets
+        clock.advance(Duration::seconds(41));
+        let result = limiter.check("user_1").await.unwrap();
+        assert!(result.allowed);
+        assert_eq!(result.remaining, 1);
+    }
+
     #[tokio::test]
     async fn test_rate_limit_different_users() {
         let store = InMemoryRateLimitStore::new();
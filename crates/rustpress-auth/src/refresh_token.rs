@@ -1,7 +1,9 @@
 //! Refresh Token Rotation (Point 58)
 //!
 //! Implements secure refresh token rotation with family tracking
-//! to prevent token reuse attacks.
+//! to prevent token reuse attacks. Token families can optionally be bound
+//! to a coarse device fingerprint so a refresh from a very different device
+//! forces re-authentication or revokes the family.
 
 use chrono::{DateTime, Duration, Utc};
 use rand::Rng;
@@ -9,9 +11,12 @@ use rustpress_core::error::{Error, Result};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::net::IpAddr;
+use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
+use crate::audit::{AuditLogStore, AuthAuditEvent, AuthEventType, EventOutcome};
+
 /// Refresh token entity
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RefreshToken {
@@ -32,6 +37,9 @@ pub struct RefreshToken {
     pub revoke_reason: Option<RevokeReason>,
     /// Last used at
     pub last_used_at: Option<DateTime<Utc>>,
+    /// Device fingerprint the family was bound to when it was created
+    #[serde(default)]
+    pub fingerprint: Option<DeviceFingerprint>,
 }
 
 impl RefreshToken {
@@ -58,6 +66,149 @@ pub struct DeviceInfo {
     pub location: Option<String>,
 }
 
+/// Coarse device fingerprint a token family can be bound to.
+///
+/// Each component is reduced before hashing so routine changes don't alter
+/// it: version numbers are stripped from the User-Agent, quality values from
+/// Accept-Language, and the client IP is cut down to its network (/16 for
+/// IPv4, /48 for IPv6). Only hashes are kept.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceFingerprint {
+    /// Hash over all components, stored with the token family
+    pub hash: String,
+    pub user_agent_hash: String,
+    pub accept_language_hash: String,
+    pub network_hash: String,
+}
+
+impl DeviceFingerprint {
+    /// Weight of a User-Agent change in the deviation score
+    pub const USER_AGENT_WEIGHT: f64 = 0.6;
+    /// Weight of an Accept-Language change in the deviation score
+    pub const ACCEPT_LANGUAGE_WEIGHT: f64 = 0.25;
+    /// Weight of a network change in the deviation score; kept low since
+    /// mobile clients switch networks all the time
+    pub const NETWORK_WEIGHT: f64 = 0.15;
+
+    pub fn new(
+        user_agent: Option<&str>,
+        accept_language: Option<&str>,
+        ip_address: Option<&str>,
+    ) -> Self {
+        let user_agent_hash = component_hash(&coarse_user_agent(user_agent.unwrap_or_default()));
+        let accept_language_hash =
+            component_hash(&coarse_accept_language(accept_language.unwrap_or_default()));
+        let network_hash = component_hash(&coarse_network(ip_address.unwrap_or_default()));
+        let hash = component_hash(&format!(
+            "{}:{}:{}",
+            user_agent_hash, accept_language_hash, network_hash
+        ));
+
+        Self {
+            hash,
+            user_agent_hash,
+            accept_language_hash,
+            network_hash,
+        }
+    }
+
+    /// Components as `(name, hash, weight)`
+    fn components(&self) -> [(&'static str, &str, f64); 3] {
+        [
+            ("user_agent", &self.user_agent_hash, Self::USER_AGENT_WEIGHT),
+            (
+                "accept_language",
+                &self.accept_language_hash,
+                Self::ACCEPT_LANGUAGE_WEIGHT,
+            ),
+            ("network", &self.network_hash, Self::NETWORK_WEIGHT),
+        ]
+    }
+
+    /// Components that differ from `other`
+    pub fn mismatched_components(&self, other: &DeviceFingerprint) -> Vec<&'static str> {
+        self.components()
+            .into_iter()
+            .zip(other.components())
+            .filter(|((_, a, _), (_, b, _))| a != b)
+            .map(|((name, _, _), _)| name)
+            .collect()
+    }
+
+    /// How far `other` is from this fingerprint (0.0 - 1.0)
+    pub fn deviation(&self, other: &DeviceFingerprint) -> f64 {
+        self.components()
+            .into_iter()
+            .zip(other.components())
+            .filter(|((_, a, _), (_, b, _))| a != b)
+            .map(|((_, _, weight), _)| weight)
+            .sum()
+    }
+}
+
+/// What a refresh from a bound family is allowed to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FingerprintAction {
+    /// Within tolerance, rotate as usual
+    Allow,
+    /// Reject the refresh but keep the family, so the device it was
+    /// issued to can carry on
+    Reauthenticate,
+    /// Treat the token as stolen and revoke the whole family
+    RevokeFamily,
+}
+
+/// Fingerprint check made when a bound refresh token was presented
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FingerprintDecision {
+    pub user_id: Uuid,
+    pub family_id: Uuid,
+    pub token_id: Uuid,
+    pub bound_hash: String,
+    /// Fingerprint hash presented with the refresh, if any
+    pub presented_hash: Option<String>,
+    pub deviation: f64,
+    pub mismatched: Vec<String>,
+    pub action: FingerprintAction,
+    pub decided_at: DateTime<Utc>,
+}
+
+impl FingerprintDecision {
+    /// Audit event recording this decision
+    pub fn audit_event(&self, ip_address: Option<&str>) -> AuthAuditEvent {
+        let (event_type, outcome) = match self.action {
+            FingerprintAction::Allow => (AuthEventType::TokenRefreshed, EventOutcome::Success),
+            FingerprintAction::Reauthenticate => {
+                (AuthEventType::SuspiciousActivity, EventOutcome::Denied)
+            }
+            FingerprintAction::RevokeFamily => {
+                (AuthEventType::SuspiciousActivity, EventOutcome::Blocked)
+            }
+        };
+        let description = match self.action {
+            FingerprintAction::Allow => "Refresh token used from a changed device fingerprint",
+            FingerprintAction::Reauthenticate => {
+                "Refresh rejected, device fingerprint deviates from the token family"
+            }
+            FingerprintAction::RevokeFamily => {
+                "Token family revoked, refresh presented from a different device"
+            }
+        };
+
+        AuthAuditEvent::new(event_type, outcome, ip_address.unwrap_or("unknown"))
+            .with_user(self.user_id)
+            .with_description(description)
+            .with_detail("family_id", self.family_id)
+            .with_detail("token_id", self.token_id)
+            .with_detail("bound_fingerprint", &self.bound_hash)
+            .with_detail("presented_fingerprint", &self.presented_hash)
+            .with_detail("deviation", self.deviation)
+            .with_detail("mismatched", &self.mismatched)
+            .with_detail("action", self.action)
+    }
+}
+
 /// Reason for token revocation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RevokeReason {
@@ -92,6 +243,13 @@ pub struct RefreshTokenConfig {
     pub revoke_family_on_reuse: bool,
     /// Grace period for old token after rotation (seconds)
     pub rotation_grace_period_secs: u64,
+    /// Bind token families to the device fingerprint they were created with
+    pub bind_fingerprint: bool,
+    /// Largest fingerprint deviation still accepted on refresh
+    pub fingerprint_tolerance: f64,
+    /// Deviation at which the family is revoked rather than the refresh
+    /// just being rejected
+    pub fingerprint_revoke_threshold: f64,
 }
 
 impl Default for RefreshTokenConfig {
@@ -103,6 +261,12 @@ impl Default for RefreshTokenConfig {
             max_families_per_user: 5,
             revoke_family_on_reuse: true,
             rotation_grace_period_secs: 60,
+            bind_fingerprint: false,
+            // A new network and language together (0.4) still passes; a
+            // different browser or OS does not
+            fingerprint_tolerance: 0.5,
+            // A different browser with a different language as well
+            fingerprint_revoke_threshold: 0.85,
         }
     }
 }
@@ -148,11 +312,22 @@ pub trait RefreshTokenStore: Send + Sync {
 pub struct RefreshTokenManager<S: RefreshTokenStore> {
     store: S,
     config: RefreshTokenConfig,
+    audit: Option<Arc<dyn AuditLogStore>>,
 }
 
 impl<S: RefreshTokenStore> RefreshTokenManager<S> {
     pub fn new(store: S, config: RefreshTokenConfig) -> Self {
-        Self { store, config }
+        Self {
+            store,
+            config,
+            audit: None,
+        }
+    }
+
+    /// Record fingerprint decisions in `audit`
+    pub fn with_audit_store(mut self, audit: Arc<dyn AuditLogStore>) -> Self {
+        self.audit = Some(audit);
+        self
     }
 
     /// Generate a new refresh token
@@ -176,6 +351,20 @@ impl<S: RefreshTokenStore> RefreshTokenManager<S> {
         ip_address: Option<&str>,
         user_agent: Option<&str>,
         device_info: Option<DeviceInfo>,
+    ) -> Result<(String, RefreshToken)> {
+        self.create_with_fingerprint(user_id, ip_address, user_agent, device_info, None)
+            .await
+    }
+
+    /// Create a new refresh token, binding its family to `fingerprint`
+    /// when [`RefreshTokenConfig::bind_fingerprint`] is set
+    pub async fn create_with_fingerprint(
+        &self,
+        user_id: Uuid,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+        device_info: Option<DeviceInfo>,
+        fingerprint: Option<DeviceFingerprint>,
     ) -> Result<(String, RefreshToken)> {
         // Check max families
         let families = self.store.get_user_families(user_id).await?;
@@ -207,6 +396,7 @@ impl<S: RefreshTokenStore> RefreshTokenManager<S> {
             revoked_at: None,
             revoke_reason: None,
             last_used_at: None,
+            fingerprint: fingerprint.filter(|_| self.config.bind_fingerprint),
         };
 
         self.store.create(&token).await?;
@@ -261,6 +451,23 @@ impl<S: RefreshTokenStore> RefreshTokenManager<S> {
         raw_token: &str,
         ip_address: Option<&str>,
         user_agent: Option<&str>,
+    ) -> Result<(String, RefreshToken)> {
+        self.rotate_with_fingerprint(raw_token, ip_address, user_agent, None)
+            .await
+    }
+
+    /// Rotate a refresh token presented from a device with `fingerprint`.
+    ///
+    /// If the family is bound, the fingerprint is compared against the one
+    /// it was created with: small deviations are accepted, larger ones fail
+    /// the refresh, and the largest revoke the family. Any deviation is
+    /// written to the audit store.
+    pub async fn rotate_with_fingerprint(
+        &self,
+        raw_token: &str,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+        fingerprint: Option<DeviceFingerprint>,
     ) -> Result<(String, RefreshToken)> {
         let old_token = self.validate(raw_token).await?;
 
@@ -276,6 +483,29 @@ impl<S: RefreshTokenStore> RefreshTokenManager<S> {
             });
         }
 
+        if let Some(decision) = self.check_fingerprint(&old_token, fingerprint.as_ref()) {
+            self.record_decision(&decision, ip_address).await;
+            match decision.action {
+                FingerprintAction::Allow => {}
+                FingerprintAction::Reauthenticate => {
+                    return Err(Error::Authentication {
+                        message:
+                            "Session could not be verified on this device. Please log in again."
+                                .to_string(),
+                    });
+                }
+                FingerprintAction::RevokeFamily => {
+                    self.store
+                        .revoke_family(old_token.family_id, RevokeReason::Security)
+                        .await?;
+                    return Err(Error::Authentication {
+                        message: "Refresh token used from an unrecognized device. Session revoked for security."
+                            .to_string(),
+                    });
+                }
+            }
+        }
+
         // Create new token in the same family
         let new_raw_token = self.generate_token();
         let new_token_hash = Self::hash_token(&new_raw_token);
@@ -295,6 +525,8 @@ impl<S: RefreshTokenStore> RefreshTokenManager<S> {
             revoked_at: None,
             revoke_reason: None,
             last_used_at: None,
+            // The family stays bound to the device it was issued to
+            fingerprint: old_token.fingerprint.clone(),
         };
 
         self.store.create(&new_token).await?;
@@ -307,6 +539,78 @@ impl<S: RefreshTokenStore> RefreshTokenManager<S> {
         Ok((new_raw_token, new_token))
     }
 
+    /// Compare the presented fingerprint with the one `token`'s family is
+    /// bound to. Returns `None` when the family isn't bound or nothing
+    /// changed; a missing fingerprint on a bound family needs a new login.
+    fn check_fingerprint(
+        &self,
+        token: &RefreshToken,
+        presented: Option<&DeviceFingerprint>,
+    ) -> Option<FingerprintDecision> {
+        if !self.config.bind_fingerprint {
+            return None;
+        }
+        let bound = token.fingerprint.as_ref()?;
+
+        let (deviation, mismatched, action) = match presented {
+            Some(presented) => {
+                let deviation = bound.deviation(presented);
+                if deviation == 0.0 {
+                    return None;
+                }
+                let action = if deviation >= self.config.fingerprint_revoke_threshold {
+                    FingerprintAction::RevokeFamily
+                } else if deviation > self.config.fingerprint_tolerance {
+                    FingerprintAction::Reauthenticate
+                } else {
+                    FingerprintAction::Allow
+                };
+                let mismatched = bound
+                    .mismatched_components(presented)
+                    .into_iter()
+                    .map(String::from)
+                    .collect();
+                (deviation, mismatched, action)
+            }
+            None => (
+                1.0,
+                vec!["missing".to_string()],
+                FingerprintAction::Reauthenticate,
+            ),
+        };
+
+        Some(FingerprintDecision {
+            user_id: token.user_id,
+            family_id: token.family_id,
+            token_id: token.id,
+            bound_hash: bound.hash.clone(),
+            presented_hash: presented.map(|p| p.hash.clone()),
+            deviation,
+            mismatched,
+            action,
+            decided_at: Utc::now(),
+        })
+    }
+
+    async fn record_decision(&self, decision: &FingerprintDecision, ip_address: Option<&str>) {
+        if decision.action != FingerprintAction::Allow {
+            tracing::warn!(
+                user_id = %decision.user_id,
+                family_id = %decision.family_id,
+                deviation = decision.deviation,
+                mismatched = ?decision.mismatched,
+                action = ?decision.action,
+                "Refresh token presented with a deviating device fingerprint"
+            );
+        }
+
+        if let Some(audit) = &self.audit {
+            if let Err(e) = audit.log(&decision.audit_event(ip_address)).await {
+                tracing::error!(error = %e, "Failed to audit refresh token fingerprint decision");
+            }
+        }
+    }
+
     /// Revoke a specific token
     pub async fn revoke(&self, raw_token: &str) -> Result<()> {
         let token_hash = Self::hash_token(raw_token);
@@ -501,6 +805,48 @@ impl RefreshTokenStore for InMemoryRefreshTokenStore {
     }
 }
 
+/// Short, stable hash of a fingerprint component
+fn component_hash(value: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(value.as_bytes());
+    hex::encode(&hasher.finalize()[..8])
+}
+
+/// User-Agent without version numbers, so browser updates don't count
+fn coarse_user_agent(user_agent: &str) -> String {
+    user_agent
+        .chars()
+        .filter(|c| !c.is_ascii_digit() && *c != '.')
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// Accept-Language tags in order, without quality values
+fn coarse_accept_language(accept_language: &str) -> String {
+    accept_language
+        .split(',')
+        .filter_map(|tag| tag.split(';').next())
+        .map(|tag| tag.trim().to_ascii_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Network a client address belongs to: /16 for IPv4, /48 for IPv6
+fn coarse_network(ip_address: &str) -> String {
+    match ip_address.trim().parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            let [a, b, _, _] = ip.octets();
+            format!("{}.{}.0.0/16", a, b)
+        }
+        Ok(IpAddr::V6(ip)) => {
+            let s = ip.segments();
+            format!("{:x}:{:x}:{:x}::/48", s[0], s[1], s[2])
+        }
+        Err(_) => ip_address.trim().to_string(),
+    }
+}
+
 /// Base64 URL-safe encoding
 fn base64_url_encode(bytes: &[u8]) -> String {
     base64::Engine::encode(&base64::engine::general_purpose::URL_SAFE_NO_PAD, bytes)
@@ -597,4 +943,92 @@ mod tests {
         let sessions = manager.get_user_sessions(user_id).await.unwrap();
         assert!(sessions.is_empty());
     }
+
+    const CHROME_120: &str =
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 Chrome/120.0.6099.71";
+    const CHROME_121: &str =
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 Chrome/121.0.6167.85";
+    const FIREFOX: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0";
+
+    fn bound_manager() -> (
+        RefreshTokenManager<InMemoryRefreshTokenStore>,
+        Arc<crate::audit::InMemoryAuditLogStore>,
+    ) {
+        let audit = Arc::new(crate::audit::InMemoryAuditLogStore::new(100));
+        let config = RefreshTokenConfig {
+            bind_fingerprint: true,
+            ..Default::default()
+        };
+        let manager = RefreshTokenManager::new(InMemoryRefreshTokenStore::new(), config)
+            .with_audit_store(audit.clone());
+        (manager, audit)
+    }
+
+    #[test]
+    fn test_device_fingerprint_tolerates_routine_changes() {
+        let home = DeviceFingerprint::new(
+            Some(CHROME_120),
+            Some("en-US,en;q=0.9"),
+            Some("81.2.69.160"),
+        );
+
+        // Browser update and a new address on the same network
+        let updated =
+            DeviceFingerprint::new(Some(CHROME_121), Some("en-US,en;q=0.8"), Some("81.2.12.7"));
+        assert_eq!(home, updated);
+
+        // Moving to a mobile network only changes the network component
+        let mobile =
+            DeviceFingerprint::new(Some(CHROME_121), Some("en-US,en"), Some("2a01:4b00:1::1"));
+        assert_eq!(home.mismatched_components(&mobile), vec!["network"]);
+        assert!(home.deviation(&mobile) <= RefreshTokenConfig::default().fingerprint_tolerance);
+
+        let other = DeviceFingerprint::new(Some(FIREFOX), Some("de-DE"), Some("10.0.0.1"));
+        assert!((home.deviation(&other) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn test_bound_family_acts_on_large_fingerprint_deviation() {
+        let (manager, audit) = bound_manager();
+        let user_id = Uuid::now_v7();
+        let device = DeviceFingerprint::new(Some(CHROME_120), Some("en-US"), Some("81.2.69.160"));
+
+        let (raw_token, token) = manager
+            .create_with_fingerprint(user_id, None, None, None, Some(device.clone()))
+            .await
+            .unwrap();
+        assert_eq!(token.fingerprint, Some(device));
+
+        // Network change: allowed, and the binding carries over
+        let roaming = DeviceFingerprint::new(Some(CHROME_120), Some("en-US"), Some("172.56.1.1"));
+        let (raw_token, rotated) = manager
+            .rotate_with_fingerprint(&raw_token, None, None, Some(roaming))
+            .await
+            .unwrap();
+        assert_eq!(rotated.fingerprint, token.fingerprint);
+
+        // Different browser: refresh refused but the family survives
+        let browser = DeviceFingerprint::new(Some(FIREFOX), Some("en-US"), Some("81.2.69.160"));
+        assert!(manager
+            .rotate_with_fingerprint(&raw_token, None, None, Some(browser))
+            .await
+            .is_err());
+        assert!(manager.validate(&raw_token).await.is_ok());
+
+        // Different browser and language: the family is revoked
+        let stranger = DeviceFingerprint::new(Some(FIREFOX), Some("ru-RU"), Some("81.2.69.160"));
+        assert!(manager
+            .rotate_with_fingerprint(&raw_token, Some("203.0.113.9"), None, Some(stranger))
+            .await
+            .is_err());
+        let revoked = manager.validate(&raw_token).await.unwrap_err();
+        assert!(revoked.to_string().contains("revoked"));
+
+        let events = audit.get_user_events(user_id, 10).await.unwrap();
+        let actions: Vec<_> = events.iter().map(|e| e.details["action"].clone()).collect();
+        assert_eq!(events.len(), 3);
+        for action in ["allow", "reauthenticate", "revoke_family"] {
+            assert!(actions.contains(&serde_json::json!(action)));
+        }
+    }
 }
@@ -21,8 +21,8 @@ use std::collections::HashMap;
 use tracing::{debug, info, warn};
 
 use crate::{
-    default_cache_rules, CacheRule, CacheSettings, CdnClient, CdnConfiguration, CdnError, CdnStats,
-    DnsRecord, PurgeResult, Result,
+    default_cache_rules, CacheKey, CacheRule, CacheSettings, CdnClient, CdnConfiguration, CdnError,
+    CdnStats, DnsRecord, OriginShield, PurgeResult, QueryKey, Result,
 };
 
 /// BunnyCDN configuration
@@ -60,6 +60,9 @@ fn default_storage_url() -> String {
     "https://storage.bunnycdn.com".to_string()
 }
 
+/// Shield location used when none is configured
+const DEFAULT_SHIELD_ZONE: &str = "NY";
+
 impl Default for BunnyCdnConfig {
     fn default() -> Self {
         Self {
@@ -109,7 +112,8 @@ impl BunnyCdnClient {
         let mut request = self
             .client
             .request(method, &url)
-            .headers(self.auth_headers());
+            .headers(self.auth_headers())
+            .headers(crate::correlation_headers());
 
         if let Some(body) = body {
             request = request.json(&body);
@@ -222,6 +226,26 @@ impl BunnyCdnClient {
         Ok(())
     }
 
+    /// Configure the origin shield of a pull zone
+    pub async fn configure_origin_shield(&self, zone_id: u64, shield: &OriginShield) -> Result<()> {
+        self.update_pull_zone(
+            zone_id,
+            serde_json::json!({
+                "EnableOriginShield": shield.enabled,
+                "OriginShieldZoneCode": shield.location.as_deref().unwrap_or(DEFAULT_SHIELD_ZONE)
+            }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Apply a cache key to a pull zone
+    pub async fn configure_cache_key(&self, zone_id: u64, key: &CacheKey) -> Result<()> {
+        self.update_pull_zone(zone_id, pull_zone_cache_key(key)?)
+            .await?;
+        Ok(())
+    }
+
     /// Create edge rule
     pub async fn create_edge_rule(&self, zone_id: u64, rule: EdgeRule) -> Result<()> {
         let _: serde_json::Value = self
@@ -298,6 +322,7 @@ impl BunnyCdnClient {
             .client
             .put(&url)
             .header("AccessKey", storage_key)
+            .headers(crate::correlation_headers())
             .header(CONTENT_TYPE, content_type)
             .body(data)
             .send()
@@ -330,6 +355,7 @@ impl BunnyCdnClient {
             .client
             .delete(&url)
             .header("AccessKey", storage_key)
+            .headers(crate::correlation_headers())
             .send()
             .await
             .map_err(|e| CdnError::Network(e.to_string()))?;
@@ -403,6 +429,15 @@ impl CdnClient for BunnyCdnClient {
             Err(e) => warn!("Failed to request SSL: {}", e),
         }
 
+        let cache_settings = CacheSettings::default();
+        match self
+            .configure_origin_shield(zone_id, &cache_settings.origin_shield)
+            .await
+        {
+            Ok(_) => info!("Origin shield configured"),
+            Err(e) => warn!("Failed to configure origin shield: {}", e),
+        }
+
         // Create edge rules for cache control
         let rules = default_cache_rules();
         for rule in &rules {
@@ -422,7 +457,7 @@ impl CdnClient for BunnyCdnClient {
                 .unwrap_or_else(|| format!("{}.b-cdn.net", pull_zone.name)),
             origin_domain: domain.to_string(),
             ssl_enabled: true,
-            cache_settings: CacheSettings::default(),
+            cache_settings,
             dns_records: vec![DnsRecord {
                 record_type: "CNAME".to_string(),
                 name: domain.to_string(),
@@ -508,6 +543,10 @@ impl CdnClient for BunnyCdnClient {
             .pull_zone_id
             .ok_or_else(|| CdnError::Configuration("Pull zone ID not configured".to_string()))?;
 
+        if let Some(key) = zone_cache_key(&rules)? {
+            self.configure_cache_key(zone_id, &key).await?;
+        }
+
         for rule in rules {
             let edge_rule = EdgeRule::from_cache_rule(&rule);
             self.create_edge_rule(zone_id, edge_rule).await?;
@@ -524,6 +563,79 @@ impl CdnClient for BunnyCdnClient {
     }
 }
 
+/// The cache key for a pull zone
+///
+/// BunnyCDN keys the cache per pull zone rather than per rule, so the keys
+/// of all rules are merged: cookies and included parameters are combined,
+/// and any rule keeping every parameter keeps them for the whole zone.
+pub fn zone_cache_key(rules: &[CacheRule]) -> Result<Option<CacheKey>> {
+    let mut merged: Option<CacheKey> = None;
+
+    for key in rules.iter().filter_map(|rule| rule.cache_key.as_ref()) {
+        key.validate()?;
+        let key = key.normalized();
+
+        merged = Some(match merged {
+            None => key,
+            Some(mut acc) => {
+                acc.headers.extend(key.headers);
+                acc.cookies.extend(key.cookies);
+                acc.query = match (acc.query, key.query) {
+                    (QueryKey::Exclude(p), _) | (_, QueryKey::Exclude(p)) => QueryKey::Exclude(p),
+                    (QueryKey::All, _) | (_, QueryKey::All) => QueryKey::All,
+                    (QueryKey::Include(mut a), QueryKey::Include(b)) => {
+                        a.extend(b);
+                        QueryKey::Include(a)
+                    }
+                    (QueryKey::Include(p), QueryKey::Ignore)
+                    | (QueryKey::Ignore, QueryKey::Include(p)) => QueryKey::Include(p),
+                    (QueryKey::Ignore, QueryKey::Ignore) => QueryKey::Ignore,
+                };
+                acc.sort_query |= key.sort_query;
+                acc.normalized()
+            }
+        });
+    }
+
+    Ok(merged)
+}
+
+/// Pull zone settings for a cache key
+///
+/// BunnyCDN can only vary on cookies and query parameters; keys it can't
+/// express are rejected rather than silently caching the wrong variant.
+fn pull_zone_cache_key(key: &CacheKey) -> Result<serde_json::Value> {
+    key.validate()?;
+    let key = key.normalized();
+
+    if !key.headers.is_empty() {
+        return Err(CdnError::Configuration(format!(
+            "BunnyCDN cannot vary the cache on request headers ({})",
+            key.headers.join(", ")
+        )));
+    }
+
+    let (ignore_query, vary_params) = match &key.query {
+        QueryKey::All => (false, Vec::new()),
+        QueryKey::Include(params) => (false, params.clone()),
+        QueryKey::Ignore => (true, Vec::new()),
+        QueryKey::Exclude(_) => {
+            return Err(CdnError::Configuration(
+                "BunnyCDN cannot exclude query parameters from the cache key; list the included ones instead"
+                    .to_string(),
+            ))
+        }
+    };
+
+    Ok(serde_json::json!({
+        "IgnoreQueryStrings": ignore_query,
+        "QueryStringVaryParameters": vary_params,
+        "EnableQueryStringOrdering": key.sort_query,
+        "EnableCookieVary": !key.cookies.is_empty(),
+        "CookieVaryParameters": key.cookies
+    }))
+}
+
 // BunnyCDN API types
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -627,10 +739,73 @@ mod tests {
             edge_ttl: None,
             browser_ttl: None,
             priority: 1,
+            cache_key: None,
         };
 
         let edge_rule = EdgeRule::from_cache_rule(&cache_rule);
         assert_eq!(edge_rule.description, "Test Rule");
         assert_eq!(edge_rule.action_type, 0);
     }
+
+    fn rule_with_key(name: &str, key: CacheKey) -> CacheRule {
+        CacheRule {
+            name: name.to_string(),
+            pattern: "/*".to_string(),
+            ttl: 3600,
+            cache_level: None,
+            edge_ttl: None,
+            browser_ttl: None,
+            priority: 1,
+            cache_key: Some(key),
+        }
+    }
+
+    #[test]
+    fn test_zone_cache_key_merges_rules() {
+        let rules = vec![
+            rule_with_key(
+                "Shop",
+                CacheKey {
+                    cookies: vec!["currency".to_string()],
+                    query: QueryKey::Include(vec!["page".to_string()]),
+                    ..Default::default()
+                },
+            ),
+            rule_with_key(
+                "Blog",
+                CacheKey {
+                    cookies: vec!["lang".to_string(), "currency".to_string()],
+                    query: QueryKey::Ignore,
+                    ..Default::default()
+                },
+            ),
+        ];
+
+        let key = zone_cache_key(&rules).unwrap().unwrap();
+        assert_eq!(key.cookies, vec!["currency", "lang"]);
+        assert_eq!(key.query, QueryKey::Include(vec!["page".to_string()]));
+
+        let settings = pull_zone_cache_key(&key).unwrap();
+        assert_eq!(settings["EnableCookieVary"], true);
+        assert_eq!(settings["QueryStringVaryParameters"][0], "page");
+        assert_eq!(settings["EnableQueryStringOrdering"], true);
+    }
+
+    #[test]
+    fn test_unsupported_cache_keys_rejected() {
+        let headers = CacheKey {
+            headers: vec!["Accept-Language".to_string()],
+            ..Default::default()
+        };
+        assert!(pull_zone_cache_key(&headers).is_err());
+
+        let session = rule_with_key(
+            "Session",
+            CacheKey {
+                cookies: vec!["rustpress_session".to_string()],
+                ..Default::default()
+            },
+        );
+        assert!(zone_cache_key(&[session]).is_err());
+    }
 }
@@ -19,8 +19,8 @@ use std::collections::HashMap;
 use tracing::{debug, info, warn};
 
 use crate::{
-    default_cache_rules, CacheRule, CacheSettings, CdnClient, CdnConfiguration, CdnError, CdnStats,
-    DnsRecord, PurgeResult, Result,
+    default_cache_rules, CacheKey, CacheRule, CacheSettings, CdnClient, CdnConfiguration, CdnError,
+    CdnStats, DnsRecord, OriginShield, PurgeResult, QueryKey, Result,
 };
 
 /// Cloudflare configuration
@@ -119,7 +119,8 @@ impl CloudflareClient {
         let mut request = self
             .client
             .request(method, &url)
-            .headers(self.auth_headers());
+            .headers(self.auth_headers())
+            .headers(crate::correlation_headers());
 
         if let Some(body) = body {
             request = request.json(&body);
@@ -225,10 +226,30 @@ impl CloudflareClient {
         self.update_zone_setting("brotli", serde_json::json!("on"))
             .await?;
 
+        self.configure_origin_shield(&settings.origin_shield)
+            .await?;
+
         info!("Caching settings configured");
         Ok(())
     }
 
+    /// Configure the origin shield
+    ///
+    /// Cloudflare's equivalent is Tiered Cache, where it picks the upper
+    /// tier closest to the origin itself.
+    pub async fn configure_origin_shield(&self, shield: &OriginShield) -> Result<()> {
+        let _: serde_json::Value = self
+            .api_request(
+                reqwest::Method::PATCH,
+                &format!("/zones/{}/argo/tiered_caching", self.config.zone_id),
+                Some(serde_json::json!({
+                    "value": if shield.enabled { "on" } else { "off" }
+                })),
+            )
+            .await?;
+        Ok(())
+    }
+
     /// Configure security settings
     pub async fn configure_security(&self) -> Result<()> {
         // Security level
@@ -283,6 +304,21 @@ impl CloudflareClient {
                     "value": browser_ttl
                 }));
             }
+
+            if let Some(cache_key) = &rule.cache_key {
+                cache_key.validate()?;
+                let cache_key = cache_key.normalized();
+                actions.push(serde_json::json!({
+                    "id": "cache_key_fields",
+                    "value": cache_key_fields(&cache_key)
+                }));
+                if cache_key.sort_query {
+                    actions.push(serde_json::json!({
+                        "id": "sort_query_string_for_cache",
+                        "value": "on"
+                    }));
+                }
+            }
         }
 
         let response: PageRule = self
@@ -475,6 +511,11 @@ impl CdnClient for CloudflareClient {
     }
 
     async fn configure_rules(&self, rules: Vec<CacheRule>) -> Result<()> {
+        // Check every key up front so a bad rule doesn't leave the zone
+        // half configured
+        for key in rules.iter().filter_map(|rule| rule.cache_key.as_ref()) {
+            key.validate()?;
+        }
         for rule in rules {
             self.create_page_rule(&rule).await?;
         }
@@ -489,6 +530,31 @@ impl CdnClient for CloudflareClient {
     }
 }
 
+/// `cache_key_fields` page rule value for a normalized cache key
+fn cache_key_fields(key: &CacheKey) -> serde_json::Value {
+    let query_string = match &key.query {
+        QueryKey::All => serde_json::json!({ "include": "*" }),
+        QueryKey::Include(params) => serde_json::json!({ "include": params }),
+        QueryKey::Exclude(params) => serde_json::json!({ "exclude": params }),
+        QueryKey::Ignore => serde_json::json!({ "exclude": "*" }),
+    };
+
+    serde_json::json!({
+        "query_string": query_string,
+        "header": {
+            "include": key.headers,
+            "check_presence": [],
+            "exclude": []
+        },
+        "cookie": {
+            "include": key.cookies,
+            "check_presence": []
+        },
+        "host": { "resolved": false },
+        "user": { "device_type": false, "geo": false, "lang": false }
+    })
+}
+
 // Cloudflare API response types
 
 #[derive(Debug, Deserialize)]
@@ -574,4 +640,26 @@ mod tests {
         let client = CloudflareClient::new(config);
         assert_eq!(client.provider_name(), "cloudflare");
     }
+
+    #[test]
+    fn test_cache_key_fields() {
+        let key = CacheKey {
+            headers: vec!["Accept-Language".to_string()],
+            cookies: vec!["currency".to_string()],
+            query: QueryKey::Exclude(vec!["utm_source".to_string()]),
+            sort_query: true,
+        }
+        .normalized();
+
+        let fields = cache_key_fields(&key);
+        assert_eq!(fields["header"]["include"][0], "accept-language");
+        assert_eq!(fields["cookie"]["include"][0], "currency");
+        assert_eq!(fields["query_string"]["exclude"][0], "utm_source");
+
+        let ignore = cache_key_fields(&CacheKey {
+            query: QueryKey::Ignore,
+            ..Default::default()
+        });
+        assert_eq!(ignore["query_string"]["exclude"], "*");
+    }
 }
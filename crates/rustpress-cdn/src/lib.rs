@@ -60,6 +60,18 @@ pub enum CdnError {
 /// Result type for CDN operations
 pub type Result<T> = std::result::Result<T, CdnError>;
 
+/// Headers forwarding the current request ID to the CDN provider, so
+/// provider-side logs can be matched with ours
+pub(crate) fn correlation_headers() -> reqwest::header::HeaderMap {
+    let mut headers = reqwest::header::HeaderMap::new();
+    if let Some(value) = rustpress_core::current_request_id()
+        .and_then(|id| reqwest::header::HeaderValue::from_str(&id).ok())
+    {
+        headers.insert(rustpress_core::REQUEST_ID_HEADER, value);
+    }
+    headers
+}
+
 /// CDN provider trait
 #[async_trait]
 pub trait CdnClient: Send + Sync {
@@ -133,6 +145,10 @@ pub struct CacheSettings {
 
     /// Cache API responses
     pub cache_api: bool,
+
+    /// Intermediate cache tier in front of the origin
+    #[serde(default)]
+    pub origin_shield: OriginShield,
 }
 
 impl Default for CacheSettings {
@@ -144,6 +160,30 @@ impl Default for CacheSettings {
             minify: MinifySettings::default(),
             cache_static: true,
             cache_api: false,
+            origin_shield: OriginShield::default(),
+        }
+    }
+}
+
+/// Origin shield settings
+///
+/// With a shield, edge locations that miss fetch from one upper-tier
+/// location instead of each going to the origin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OriginShield {
+    pub enabled: bool,
+
+    /// Provider-specific shield location, e.g. BunnyCDN's `NY` or `FR`.
+    /// Cloudflare picks its upper tier itself and ignores this.
+    #[serde(default)]
+    pub location: Option<String>,
+}
+
+impl Default for OriginShield {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            location: None,
         }
     }
 }
@@ -199,6 +239,179 @@ pub struct CacheRule {
 
     /// Priority (lower = higher priority)
     pub priority: i32,
+
+    /// Custom cache key; `None` keeps the provider's default key
+    #[serde(default)]
+    pub cache_key: Option<CacheKey>,
+}
+
+/// Cookies that identify a user or session. Caching on them would either
+/// fragment the cache per visitor or, worse, serve one user's page to
+/// another, so they can never be part of a cache key.
+const SENSITIVE_COOKIE_MARKERS: &[&str] = &[
+    "session",
+    "sessid",
+    "auth",
+    "token",
+    "jwt",
+    "csrf",
+    "xsrf",
+    "logged_in",
+    "remember",
+];
+
+/// Short markers that only count as a whole `_`/`-`/`.` separated word,
+/// so `sid` matches `app.sid` but not `residence`
+const SENSITIVE_COOKIE_WORDS: &[&str] = &["sid", "sess", "uid"];
+
+/// Headers that carry credentials or the whole cookie jar
+const SENSITIVE_HEADERS: &[&str] = &["authorization", "cookie", "proxy-authorization"];
+
+/// Parts of a request that select the cached variant
+///
+/// Header and cookie names are case-insensitive and order doesn't matter;
+/// [`CacheKey::normalized`] gives the canonical form that is sent to the
+/// provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheKey {
+    /// Request headers whose values vary the cache, e.g. `Accept-Language`
+    #[serde(default)]
+    pub headers: Vec<String>,
+
+    /// Cookies whose values vary the cache, e.g. a currency cookie
+    #[serde(default)]
+    pub cookies: Vec<String>,
+
+    /// Query parameters that are part of the key
+    #[serde(default)]
+    pub query: QueryKey,
+
+    /// Sort query parameters so `?a=1&b=2` and `?b=2&a=1` share an entry
+    #[serde(default = "default_sort_query")]
+    pub sort_query: bool,
+}
+
+fn default_sort_query() -> bool {
+    true
+}
+
+impl Default for CacheKey {
+    fn default() -> Self {
+        Self {
+            headers: Vec::new(),
+            cookies: Vec::new(),
+            query: QueryKey::All,
+            sort_query: default_sort_query(),
+        }
+    }
+}
+
+/// Query parameters included in a cache key
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "mode", content = "params")]
+pub enum QueryKey {
+    /// Every parameter
+    #[default]
+    All,
+    /// Only these parameters
+    Include(Vec<String>),
+    /// Every parameter except these, e.g. `utm_*` tracking parameters
+    Exclude(Vec<String>),
+    /// No parameters; every query string shares one entry
+    Ignore,
+}
+
+impl CacheKey {
+    /// Canonical form: lowercase header names, sorted and deduplicated
+    /// names and parameters
+    pub fn normalized(&self) -> Self {
+        fn canonical(names: &[String], lowercase: bool) -> Vec<String> {
+            let mut names: Vec<String> = names
+                .iter()
+                .map(|name| name.trim())
+                .filter(|name| !name.is_empty())
+                .map(|name| {
+                    if lowercase {
+                        name.to_ascii_lowercase()
+                    } else {
+                        name.to_string()
+                    }
+                })
+                .collect();
+            names.sort();
+            names.dedup();
+            names
+        }
+
+        Self {
+            headers: canonical(&self.headers, true),
+            cookies: canonical(&self.cookies, false),
+            query: match &self.query {
+                QueryKey::Include(params) => QueryKey::Include(canonical(params, false)),
+                QueryKey::Exclude(params) => QueryKey::Exclude(canonical(params, false)),
+                other => other.clone(),
+            },
+            sort_query: self.sort_query,
+        }
+    }
+
+    /// Reject keys that would vary the cache on credentials or sessions
+    pub fn validate(&self) -> Result<()> {
+        for header in &self.headers {
+            if SENSITIVE_HEADERS.contains(&header.trim().to_ascii_lowercase().as_str()) {
+                return Err(CdnError::Configuration(format!(
+                    "Header {} cannot be part of a cache key",
+                    header
+                )));
+            }
+        }
+        for cookie in &self.cookies {
+            if is_sensitive_cookie(cookie) {
+                return Err(CdnError::Configuration(format!(
+                    "Cookie {} looks like a session or auth cookie and cannot be part of a cache key",
+                    cookie
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// The query string as it contributes to the cache key
+    ///
+    /// Mirrors what the provider does with the key: drops parameters that
+    /// aren't part of it and, with `sort_query`, orders the rest by name.
+    pub fn normalize_query(&self, query: &str) -> String {
+        let mut pairs: Vec<&str> = query
+            .trim_start_matches('?')
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .filter(|pair| {
+                let name = pair.split('=').next().unwrap_or_default();
+                match &self.query {
+                    QueryKey::All => true,
+                    QueryKey::Include(params) => params.iter().any(|p| p == name),
+                    QueryKey::Exclude(params) => !params.iter().any(|p| p == name),
+                    QueryKey::Ignore => false,
+                }
+            })
+            .collect();
+        if self.sort_query {
+            // Stable, so repeated parameters keep their relative order
+            pairs.sort_by_key(|pair| pair.split('=').next().unwrap_or_default());
+        }
+        pairs.join("&")
+    }
+}
+
+/// Whether a cookie name looks like it identifies a session or user
+pub fn is_sensitive_cookie(name: &str) -> bool {
+    let name = name.trim().to_ascii_lowercase();
+    SENSITIVE_COOKIE_MARKERS
+        .iter()
+        .any(|marker| name.contains(marker))
+        || name
+            .split(['_', '-', '.'])
+            .any(|word| SENSITIVE_COOKIE_WORDS.contains(&word))
 }
 
 /// Purge result
@@ -249,6 +462,7 @@ pub fn default_cache_rules() -> Vec<CacheRule> {
             edge_ttl: Some(2592000),   // 30 days
             browser_ttl: Some(604800), // 7 days
             priority: 1,
+            cache_key: None,
         },
         // Images - long cache
         CacheRule {
@@ -259,6 +473,7 @@ pub fn default_cache_rules() -> Vec<CacheRule> {
             edge_ttl: Some(604800),   // 7 days
             browser_ttl: Some(86400), // 1 day
             priority: 2,
+            cache_key: None,
         },
         // Media uploads
         CacheRule {
@@ -269,6 +484,7 @@ pub fn default_cache_rules() -> Vec<CacheRule> {
             edge_ttl: Some(86400),   // 1 day
             browser_ttl: Some(3600), // 1 hour
             priority: 3,
+            cache_key: None,
         },
         // API responses - short cache
         CacheRule {
@@ -279,6 +495,7 @@ pub fn default_cache_rules() -> Vec<CacheRule> {
             edge_ttl: Some(60),
             browser_ttl: Some(0),
             priority: 4,
+            cache_key: None,
         },
         // Admin - no cache
         CacheRule {
@@ -289,6 +506,7 @@ pub fn default_cache_rules() -> Vec<CacheRule> {
             edge_ttl: None,
             browser_ttl: None,
             priority: 0,
+            cache_key: None,
         },
         // Auth endpoints - no cache
         CacheRule {
@@ -299,6 +517,7 @@ pub fn default_cache_rules() -> Vec<CacheRule> {
             edge_ttl: None,
             browser_ttl: None,
             priority: 0,
+            cache_key: None,
         },
     ]
 }
@@ -324,4 +543,88 @@ mod tests {
         assert!(admin_rule.is_some());
         assert_eq!(admin_rule.unwrap().ttl, 0);
     }
+
+    #[test]
+    fn test_cache_key_normalized() {
+        let key = CacheKey {
+            headers: vec![
+                "Accept-Language".to_string(),
+                " accept-language ".to_string(),
+                "X-Currency".to_string(),
+            ],
+            cookies: vec!["lang".to_string(), "currency".to_string()],
+            query: QueryKey::Include(vec!["page".to_string(), "lang".to_string()]),
+            sort_query: true,
+        };
+
+        let normalized = key.normalized();
+        assert_eq!(normalized.headers, vec!["accept-language", "x-currency"]);
+        assert_eq!(normalized.cookies, vec!["currency", "lang"]);
+        assert_eq!(
+            normalized.query,
+            QueryKey::Include(vec!["lang".to_string(), "page".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_normalize_query() {
+        let key = CacheKey {
+            query: QueryKey::Exclude(vec!["utm_source".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(key.normalize_query("?b=2&utm_source=x&a=1"), "a=1&b=2");
+        assert_eq!(
+            key.normalize_query("b=2&a=1"),
+            key.normalize_query("a=1&b=2")
+        );
+
+        let include = CacheKey {
+            query: QueryKey::Include(vec!["page".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(include.normalize_query("page=2&ref=feed"), "page=2");
+
+        let ignore = CacheKey {
+            query: QueryKey::Ignore,
+            ..Default::default()
+        };
+        assert_eq!(ignore.normalize_query("page=2"), "");
+
+        let unsorted = CacheKey {
+            sort_query: false,
+            ..Default::default()
+        };
+        assert_eq!(unsorted.normalize_query("b=2&a=1"), "b=2&a=1");
+    }
+
+    #[test]
+    fn test_sensitive_cache_keys_rejected() {
+        for cookie in [
+            "rustpress_session",
+            "wordpress_logged_in_abc",
+            "PHPSESSID",
+            "app.sid",
+        ] {
+            let key = CacheKey {
+                cookies: vec![cookie.to_string()],
+                ..Default::default()
+            };
+            assert!(key.validate().is_err(), "{} accepted", cookie);
+        }
+        assert!(!is_sensitive_cookie("residence"));
+        assert!(!is_sensitive_cookie("currency"));
+
+        let key = CacheKey {
+            headers: vec!["Authorization".to_string()],
+            ..Default::default()
+        };
+        assert!(key.validate().is_err());
+
+        let key = CacheKey {
+            headers: vec!["Accept-Language".to_string()],
+            cookies: vec!["currency".to_string()],
+            ..Default::default()
+        };
+        assert!(key.validate().is_ok());
+    }
 }
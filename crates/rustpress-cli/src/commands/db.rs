@@ -35,6 +35,17 @@ pub enum DbSubcommand {
     /// Show database status and statistics
     Status,
 
+    /// Compare the live schema against the migrations' expected state
+    Diff {
+        /// Print SQL that corrects the drift (it is not applied)
+        #[arg(long)]
+        fix: bool,
+
+        /// Write the corrective SQL to a file instead of stdout
+        #[arg(short, long, requires = "fix")]
+        output: Option<String>,
+    },
+
     /// Create a database backup
     Backup {
         /// Output file path
@@ -164,6 +175,33 @@ pub struct MigrationStatus {
     pub applied_at: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Tabled)]
+pub struct SchemaDrift {
+    #[tabled(rename = "Severity")]
+    pub severity: String,
+    #[tabled(rename = "Kind")]
+    pub kind: String,
+    #[tabled(rename = "Table")]
+    pub table: String,
+    #[tabled(rename = "Object", display_with = "display_option")]
+    pub object: Option<String>,
+    #[tabled(rename = "Expected", display_with = "display_option")]
+    pub expected: Option<String>,
+    #[tabled(rename = "Actual", display_with = "display_option")]
+    pub actual: Option<String>,
+}
+
+fn display_option(value: &Option<String>) -> String {
+    value.clone().unwrap_or_else(|| "-".to_string())
+}
+
+#[derive(Debug, Deserialize)]
+struct SchemaDiffResponse {
+    structural: bool,
+    drift: Vec<SchemaDrift>,
+    fix_sql: Option<Vec<String>>,
+}
+
 #[derive(Debug, Serialize, Tabled)]
 pub struct QueryResult {
     #[tabled(rename = "#")]
@@ -194,6 +232,7 @@ pub async fn execute(ctx: &CliContext, cmd: DbCommand) -> CliResult<()> {
             status,
         } => run_migrate(ctx, dry_run, rollback, status).await,
         DbSubcommand::Status => show_status(ctx).await,
+        DbSubcommand::Diff { fix, output } => schema_diff(ctx, fix, output).await,
         DbSubcommand::Backup {
             output,
             include_media,
@@ -426,6 +465,91 @@ async fn show_status(ctx: &CliContext) -> CliResult<()> {
     Ok(())
 }
 
+async fn schema_diff(ctx: &CliContext, fix: bool, output: Option<String>) -> CliResult<()> {
+    print_header("Schema Drift");
+
+    let spinner = ProgressBar::spinner("Comparing schema against migrations...");
+
+    let client = ctx.http_client();
+    let url = format!("{}/api/v1/db/diff?fix={}", ctx.server_url(), fix);
+
+    let response = client
+        .get(&url)
+        .header("Authorization", auth_header(ctx)?)
+        .send()
+        .await
+        .map_err(|e| CliError::Network(format!("Failed to fetch schema diff: {}", e)))?;
+
+    spinner.finish_and_clear();
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(CliError::OperationFailed(format!(
+            "Failed to diff schema ({}): {}",
+            status, body
+        )));
+    }
+
+    let diff: SchemaDiffResponse = response
+        .json()
+        .await
+        .map_err(|e| CliError::Serialization(format!("Failed to parse response: {}", e)))?;
+
+    if diff.drift.is_empty() {
+        println!(
+            "{}",
+            ctx.output_format
+                .success("Schema matches the migrations, no drift found")
+        );
+        return Ok(());
+    }
+
+    println!("{}", ctx.output_format.format(&diff.drift));
+    println!();
+
+    let benign = diff.drift.iter().filter(|d| d.severity == "benign").count();
+    print_kv("Structural", &(diff.drift.len() - benign).to_string());
+    print_kv("Benign", &benign.to_string());
+
+    if let Some(sql) = diff.fix_sql.filter(|sql| !sql.is_empty()) {
+        let script = format!(
+            "-- Corrective SQL generated by `rustpress db diff --fix`.\n\
+             -- Review before applying; destructive statements are commented out.\n\n{}\n",
+            sql.join("\n")
+        );
+        match output {
+            Some(path) => {
+                std::fs::write(&path, script)?;
+                println!();
+                println!(
+                    "{}",
+                    ctx.output_format
+                        .info(&format!("Corrective SQL written to {}", path))
+                );
+            }
+            None => {
+                println!();
+                println!("{}", script);
+            }
+        }
+    }
+
+    if diff.structural {
+        return Err(CliError::OperationFailed(
+            "Structural schema drift detected".to_string(),
+        ));
+    }
+
+    println!();
+    println!(
+        "{}",
+        ctx.output_format
+            .warning("Only benign differences (such as collation) found")
+    );
+    Ok(())
+}
+
 async fn create_backup(
     ctx: &CliContext,
     output: Option<String>,
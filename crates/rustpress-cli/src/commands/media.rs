@@ -57,14 +57,45 @@ pub enum MediaSubcommand {
         delete_file: bool,
     },
 
-    /// Optimize media files
+    /// Re-optimize images, generating missing format variants
     Optimize {
-        /// Optimize all media
+        /// Optimize all media (the default when no ID is given)
         #[arg(long)]
         all: bool,
 
         /// Specific media ID to optimize
         id: Option<String>,
+
+        /// Formats to generate (webp, avif, jpeg, png)
+        #[arg(long, value_delimiter = ',', default_value = "webp")]
+        format: Vec<String>,
+
+        /// Also regenerate thumbnails
+        #[arg(long)]
+        regenerate_thumbnails: bool,
+
+        /// Only include media uploaded on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Report what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Number of images to optimize at once
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+    },
+
+    /// Suggest alt text for images that have none, using the server's
+    /// configured generator
+    SuggestAltText {
+        /// Specific media ID (defaults to every image without alt text)
+        id: Option<String>,
+
+        /// Number of images to process at once
+        #[arg(long, default_value = "2")]
+        concurrency: usize,
     },
 
     /// Regenerate thumbnails
@@ -113,7 +144,27 @@ pub async fn execute(ctx: &CliContext, cmd: MediaCommand) -> CliResult<()> {
         MediaSubcommand::Upload { file, title, alt } => upload_media(ctx, &file, title, alt).await,
         MediaSubcommand::Get { id } => get_media(ctx, &id).await,
         MediaSubcommand::Delete { id, delete_file } => delete_media(ctx, &id, delete_file).await,
-        MediaSubcommand::Optimize { all, id } => optimize_media(ctx, all, id).await,
+        MediaSubcommand::Optimize {
+            all: _,
+            id,
+            format,
+            regenerate_thumbnails,
+            since,
+            dry_run,
+            concurrency,
+        } => {
+            let options = OptimizeOptions {
+                formats: format,
+                regenerate_thumbnails,
+                since: since.as_deref().map(parse_since).transpose()?,
+                dry_run,
+                concurrency: concurrency.max(1),
+            };
+            optimize_media(ctx, id, options).await
+        }
+        MediaSubcommand::SuggestAltText { id, concurrency } => {
+            suggest_alt_text(ctx, id, concurrency.max(1)).await
+        }
         MediaSubcommand::RegenerateThumbnails { all, id } => {
             regenerate_thumbnails(ctx, all, id).await
         }
@@ -351,71 +402,489 @@ async fn delete_media(ctx: &CliContext, id: &str, delete_file: bool) -> CliResul
     Ok(())
 }
 
-async fn optimize_media(ctx: &CliContext, all: bool, id: Option<String>) -> CliResult<()> {
-    print_header("Optimizing Media");
+/// Formats the server's image optimizer can be asked for
+const OPTIMIZE_FORMATS: &[&str] = &["webp", "avif", "jpeg", "png"];
 
-    if !all && id.is_none() {
-        return Err(CliError::InvalidInput(
-            "Specify --all or provide a media ID".to_string(),
-        ));
+/// Page size when listing images to optimize
+const CANDIDATE_PAGE_SIZE: usize = 500;
+
+struct OptimizeOptions {
+    formats: Vec<String>,
+    regenerate_thumbnails: bool,
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    dry_run: bool,
+    concurrency: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct OptimizeCandidate {
+    id: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct OptimizeReport {
+    generated: Vec<GeneratedVariant>,
+    unsupported: Vec<String>,
+    thumbnail_regenerated: bool,
+    bytes_saved: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeneratedVariant {
+    format: String,
+    file_size: u64,
+}
+
+/// Running totals for a bulk optimization
+#[derive(Default)]
+struct OptimizeSummary {
+    optimized: u64,
+    already_optimized: u64,
+    variants: u64,
+    thumbnails: u64,
+    bytes_saved: u64,
+    unsupported: std::collections::BTreeSet<String>,
+    failed: Vec<(String, CliError)>,
+    /// Per-image changes, listed on a dry run
+    changes: Vec<String>,
+}
+
+impl OptimizeSummary {
+    fn record(&mut self, id: String, result: CliResult<OptimizeReport>) {
+        match result {
+            Ok(report) => {
+                if report.generated.is_empty() && !report.thumbnail_regenerated {
+                    self.already_optimized += 1;
+                } else {
+                    self.optimized += 1;
+                }
+                for variant in &report.generated {
+                    self.changes.push(format!(
+                        "{}: {} ({})",
+                        id,
+                        variant.format,
+                        format_size(variant.file_size)
+                    ));
+                }
+                if report.thumbnail_regenerated {
+                    self.changes.push(format!("{}: thumbnail", id));
+                }
+                self.variants += report.generated.len() as u64;
+                self.thumbnails += report.thumbnail_regenerated as u64;
+                self.bytes_saved += report.bytes_saved;
+                self.unsupported.extend(report.unsupported);
+            }
+            Err(e) => self.failed.push((id, e)),
+        }
+    }
+}
+
+/// Parse `--since` as a date or an RFC 3339 timestamp
+fn parse_since(value: &str) -> CliResult<chrono::DateTime<chrono::Utc>> {
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    }
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .map_err(|_| {
+            CliError::InvalidInput(format!(
+                "Invalid --since date '{}', expected YYYY-MM-DD",
+                value
+            ))
+        })
+}
+
+async fn optimize_media(
+    ctx: &CliContext,
+    id: Option<String>,
+    options: OptimizeOptions,
+) -> CliResult<()> {
+    if options.dry_run {
+        print_header("Optimizing Media (dry run)");
+    } else {
+        print_header("Optimizing Media");
+    }
+
+    if let Some(format) = options
+        .formats
+        .iter()
+        .find(|f| !OPTIMIZE_FORMATS.contains(&f.as_str()))
+    {
+        return Err(CliError::InvalidInput(format!(
+            "Unknown format '{}', expected one of: {}",
+            format,
+            OPTIMIZE_FORMATS.join(", ")
+        )));
+    }
+
+    let ids = match id {
+        Some(id) => vec![id],
+        None => fetch_optimize_candidates(ctx, options.since).await?,
+    };
+
+    if ids.is_empty() {
+        println!("{}", ctx.output_format.info("No images to optimize"));
+        return Ok(());
     }
 
-    let spinner = ProgressBar::spinner("Optimizing media...");
+    print_kv("Images", &ids.len().to_string());
+    print_kv("Formats", &options.formats.join(", "));
+    print_kv("Concurrency", &options.concurrency.to_string());
+    println!();
 
     let client = ctx.http_client();
-    let url = if all {
-        format!("{}/api/v1/media/optimize", ctx.server_url())
+    let auth = auth_header(ctx)?;
+    let base_url = ctx.server_url().to_string();
+    let body = serde_json::json!({
+        "formats": options.formats,
+        "regenerate_thumbnails": options.regenerate_thumbnails,
+        "dry_run": options.dry_run,
+    });
+
+    let progress = ProgressBar::new(ids.len() as u64, "Optimizing");
+    let mut summary = OptimizeSummary::default();
+    let mut tasks = tokio::task::JoinSet::new();
+
+    for id in ids {
+        // Keep at most `concurrency` requests in flight
+        if tasks.len() >= options.concurrency {
+            if let Some(Ok((id, result))) = tasks.join_next().await {
+                summary.record(id, result);
+                progress.inc(1);
+            }
+        }
+
+        let client = client.clone();
+        let url = format!("{}/api/v1/media/{}/optimize", base_url, id);
+        let auth = auth.clone();
+        let body = body.clone();
+        tasks.spawn(async move {
+            let result = reoptimize_one(&client, &url, &auth, &body).await;
+            (id, result)
+        });
+    }
+
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((id, result)) = joined {
+            summary.record(id, result);
+        }
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    if options.dry_run && !summary.changes.is_empty() {
+        for change in &summary.changes {
+            ctx.print(&format!("  would write {}", change));
+        }
+        println!();
+    }
+
+    let (variants_label, saved_label) = if options.dry_run {
+        ("Variants to generate", "Estimated savings")
     } else {
-        format!(
-            "{}/api/v1/media/{}/optimize",
-            ctx.server_url(),
-            id.as_ref().unwrap()
-        )
+        ("Variants generated", "Bytes saved")
     };
+    print_kv("Optimized", &summary.optimized.to_string());
+    print_kv("Already optimized", &summary.already_optimized.to_string());
+    print_kv(variants_label, &summary.variants.to_string());
+    if options.regenerate_thumbnails {
+        print_kv("Thumbnails", &summary.thumbnails.to_string());
+    }
+    print_kv(saved_label, &format_size(summary.bytes_saved));
+    print_kv("Failed", &summary.failed.len().to_string());
+
+    if !summary.unsupported.is_empty() {
+        let formats: Vec<_> = summary.unsupported.into_iter().collect();
+        println!(
+            "{}",
+            ctx.output_format.warning(&format!(
+                "Not supported by the server's optimizer: {}",
+                formats.join(", ")
+            ))
+        );
+    }
 
+    if !summary.failed.is_empty() {
+        for (id, error) in &summary.failed {
+            println!("{}", ctx.output_format.error(&format!("{}: {}", id, error)));
+        }
+        return Err(CliError::OperationFailed(format!(
+            "{} image(s) failed to optimize; re-run to retry them",
+            summary.failed.len()
+        )));
+    }
+
+    println!();
+    println!(
+        "{}",
+        ctx.output_format.success(if options.dry_run {
+            "Dry run complete, nothing was written"
+        } else {
+            "Optimization complete"
+        })
+    );
+
+    Ok(())
+}
+
+/// Collect the ids of every image to optimize, page by page
+async fn fetch_optimize_candidates(
+    ctx: &CliContext,
+    since: Option<chrono::DateTime<chrono::Utc>>,
+) -> CliResult<Vec<String>> {
+    let spinner = ProgressBar::spinner("Listing images...");
+    let client = ctx.http_client();
+    let mut ids = Vec::new();
+    let mut after: Option<String> = None;
+
+    loop {
+        let mut url = format!(
+            "{}/api/v1/media/optimize/candidates?limit={}",
+            ctx.server_url(),
+            CANDIDATE_PAGE_SIZE
+        );
+        if let Some(since) = since {
+            url.push_str(&format!("&since={}", since.format("%Y-%m-%dT%H:%M:%SZ")));
+        }
+        if let Some(ref after) = after {
+            url.push_str(&format!("&after={}", after));
+        }
+
+        let response = client
+            .get(&url)
+            .header("Authorization", auth_header(ctx)?)
+            .send()
+            .await
+            .map_err(|e| CliError::Network(format!("Failed to list media: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(CliError::OperationFailed(format!(
+                "Failed to list media to optimize ({}): {}",
+                status, body
+            )));
+        }
+
+        let page: Vec<OptimizeCandidate> = response
+            .json()
+            .await
+            .map_err(|e| CliError::Serialization(format!("Failed to parse response: {}", e)))?;
+
+        let done = page.len() < CANDIDATE_PAGE_SIZE;
+        after = page.last().map(|c| c.id.clone());
+        ids.extend(page.into_iter().map(|c| c.id));
+        spinner.set_message(&format!("Listing images... {}", ids.len()));
+
+        if done {
+            break;
+        }
+    }
+
+    spinner.finish_and_clear();
+    Ok(ids)
+}
+
+async fn reoptimize_one(
+    client: &reqwest::Client,
+    url: &str,
+    auth: &str,
+    body: &serde_json::Value,
+) -> CliResult<OptimizeReport> {
     let response = client
-        .post(&url)
-        .header("Authorization", auth_header(ctx)?)
+        .post(url)
+        .header("Authorization", auth)
+        .json(body)
         .send()
         .await
         .map_err(|e| CliError::Network(format!("Failed to optimize media: {}", e)))?;
 
-    spinner.finish_and_clear();
-
     if !response.status().is_success() {
         let status = response.status();
-        // If endpoint doesn't exist, provide info
-        if status == reqwest::StatusCode::NOT_FOUND {
-            println!(
-                "{}",
-                ctx.output_format
-                    .info("Media optimization is not available via API")
-            );
-            return Ok(());
-        }
         let body = response.text().await.unwrap_or_default();
+        return Err(CliError::OperationFailed(format!("{}: {}", status, body)));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| CliError::Serialization(format!("Failed to parse response: {}", e)))
+}
+
+#[derive(Debug, Deserialize)]
+struct AltTextSuggestion {
+    suggestion: Option<String>,
+    applied: bool,
+}
+
+async fn suggest_alt_text(
+    ctx: &CliContext,
+    id: Option<String>,
+    concurrency: usize,
+) -> CliResult<()> {
+    print_header("Suggesting Alt Text");
+
+    let ids = match id {
+        Some(id) => vec![id],
+        None => fetch_alt_text_candidates(ctx).await?,
+    };
+
+    if ids.is_empty() {
+        println!("{}", ctx.output_format.info("No images need alt text"));
+        return Ok(());
+    }
+
+    print_kv("Images", &ids.len().to_string());
+    print_kv("Concurrency", &concurrency.to_string());
+    println!();
+
+    let client = ctx.http_client();
+    let auth = auth_header(ctx)?;
+    let base_url = ctx.server_url().to_string();
+
+    let progress = ProgressBar::new(ids.len() as u64, "Suggesting");
+    let (mut suggested, mut applied, mut empty) = (0u64, 0u64, 0u64);
+    let mut failed: Vec<(String, CliError)> = Vec::new();
+    let mut record = |id: String, result: CliResult<AltTextSuggestion>| match result {
+        Ok(AltTextSuggestion {
+            suggestion: Some(_),
+            applied: was_applied,
+        }) => {
+            suggested += 1;
+            applied += was_applied as u64;
+        }
+        Ok(_) => empty += 1,
+        Err(e) => failed.push((id, e)),
+    };
+    let mut tasks = tokio::task::JoinSet::new();
+
+    for id in ids {
+        // Keep at most `concurrency` requests in flight
+        if tasks.len() >= concurrency {
+            if let Some(Ok((id, result))) = tasks.join_next().await {
+                record(id, result);
+                progress.inc(1);
+            }
+        }
+
+        let client = client.clone();
+        let url = format!("{}/api/v1/media/{}/suggest-alt-text", base_url, id);
+        let auth = auth.clone();
+        tasks.spawn(async move {
+            let result = request_alt_text(&client, &url, &auth).await;
+            (id, result)
+        });
+    }
+
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((id, result)) = joined {
+            record(id, result);
+        }
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    print_kv("Suggested", &suggested.to_string());
+    print_kv("Applied as alt text", &applied.to_string());
+    print_kv("No suggestion", &empty.to_string());
+    print_kv("Failed", &failed.len().to_string());
+
+    if !failed.is_empty() {
+        for (id, error) in &failed {
+            println!("{}", ctx.output_format.error(&format!("{}: {}", id, error)));
+        }
         return Err(CliError::OperationFailed(format!(
-            "Failed to optimize media ({}): {}",
-            status, body
+            "{} image(s) failed; re-run to retry them",
+            failed.len()
         )));
     }
 
-    let result: serde_json::Value = response.json().await.unwrap_or_default();
-    let count = result
-        .get("optimized")
-        .and_then(|v| v.as_i64())
-        .unwrap_or(1);
-
     println!();
     println!(
         "{}",
-        ctx.output_format
-            .success(&format!("Optimized {} file(s)", count))
+        ctx.output_format.success("Alt text suggestions complete")
     );
 
     Ok(())
 }
 
+/// Collect the ids of every image without alt text or a suggestion
+async fn fetch_alt_text_candidates(ctx: &CliContext) -> CliResult<Vec<String>> {
+    let spinner = ProgressBar::spinner("Listing images...");
+    let client = ctx.http_client();
+    let mut ids = Vec::new();
+    let mut after: Option<String> = None;
+
+    loop {
+        let mut url = format!(
+            "{}/api/v1/media/alt-text/candidates?limit={}",
+            ctx.server_url(),
+            CANDIDATE_PAGE_SIZE
+        );
+        if let Some(ref after) = after {
+            url.push_str(&format!("&after={}", after));
+        }
+
+        let response = client
+            .get(&url)
+            .header("Authorization", auth_header(ctx)?)
+            .send()
+            .await
+            .map_err(|e| CliError::Network(format!("Failed to list media: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(CliError::OperationFailed(format!(
+                "Failed to list images without alt text ({}): {}",
+                status, body
+            )));
+        }
+
+        let page: Vec<OptimizeCandidate> = response
+            .json()
+            .await
+            .map_err(|e| CliError::Serialization(format!("Failed to parse response: {}", e)))?;
+
+        let done = page.len() < CANDIDATE_PAGE_SIZE;
+        after = page.last().map(|c| c.id.clone());
+        ids.extend(page.into_iter().map(|c| c.id));
+        spinner.set_message(&format!("Listing images... {}", ids.len()));
+
+        if done {
+            break;
+        }
+    }
+
+    spinner.finish_and_clear();
+    Ok(ids)
+}
+
+async fn request_alt_text(
+    client: &reqwest::Client,
+    url: &str,
+    auth: &str,
+) -> CliResult<AltTextSuggestion> {
+    let response = client
+        .post(url)
+        .header("Authorization", auth)
+        .send()
+        .await
+        .map_err(|e| CliError::Network(format!("Failed to suggest alt text: {}", e)))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(CliError::OperationFailed(format!("{}: {}", status, body)));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| CliError::Serialization(format!("Failed to parse response: {}", e)))
+}
+
 async fn regenerate_thumbnails(ctx: &CliContext, all: bool, id: Option<String>) -> CliResult<()> {
     print_header("Regenerating Thumbnails");
 
@@ -1,12 +1,15 @@
 //! Plugin management commands
 
 use clap::{Args, Subcommand};
+use rustpress_plugins::doctor::UpdateCheckStatus;
+use rustpress_plugins::{Finding, FindingSeverity, PluginDoctor, UpdateChecker, UpdateConfig};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use tabled::Tabled;
 
 use crate::context::CliContext;
 use crate::error::{CliError, CliResult};
-use crate::output::{print_header, print_kv, OutputFormatter, ProgressBar};
+use crate::output::{print_header, print_kv, OutputFormat, OutputFormatter, ProgressBar};
 
 #[derive(Args, Debug)]
 pub struct PluginsCommand {
@@ -44,6 +47,21 @@ pub enum PluginsSubcommand {
 
     /// Check for plugin updates
     CheckUpdates,
+
+    /// Diagnose conflicts, dependency and version problems in installed plugins
+    Doctor {
+        /// Directory containing the installed plugins
+        #[arg(long, default_value = "plugins")]
+        dir: PathBuf,
+
+        /// Skip the update check (the only part that needs network access)
+        #[arg(long)]
+        offline: bool,
+
+        /// RustPress version to check compatibility against (defaults to this CLI's version)
+        #[arg(long)]
+        rustpress_version: Option<String>,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize, Tabled)]
@@ -58,6 +76,40 @@ pub struct PluginRow {
     pub status: String,
 }
 
+#[derive(Debug, Serialize, Tabled)]
+pub struct DoctorFindingRow {
+    #[tabled(rename = "Severity")]
+    pub severity: String,
+    #[tabled(rename = "Plugin")]
+    pub plugin: String,
+    #[tabled(rename = "Category")]
+    pub category: String,
+    #[tabled(rename = "Problem")]
+    pub message: String,
+    #[tabled(rename = "Suggested Fix")]
+    pub remediation: String,
+}
+
+impl From<&Finding> for DoctorFindingRow {
+    fn from(finding: &Finding) -> Self {
+        Self {
+            severity: serde_label(&finding.severity),
+            plugin: finding.plugin.clone(),
+            category: serde_label(&finding.category),
+            message: finding.message.clone(),
+            remediation: finding.remediation.clone(),
+        }
+    }
+}
+
+/// The serialized (snake_case) name of a unit enum variant
+fn serde_label<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PluginDetails {
     pub id: String,
@@ -79,6 +131,11 @@ pub async fn execute(ctx: &CliContext, cmd: PluginsCommand) -> CliResult<()> {
             uninstall_plugin(ctx, &plugin, force).await
         }
         PluginsSubcommand::CheckUpdates => check_updates(ctx).await,
+        PluginsSubcommand::Doctor {
+            dir,
+            offline,
+            rustpress_version,
+        } => doctor(ctx, &dir, offline, rustpress_version).await,
     }
 }
 
@@ -403,3 +460,73 @@ async fn check_updates(ctx: &CliContext) -> CliResult<()> {
 
     Ok(())
 }
+
+async fn doctor(
+    ctx: &CliContext,
+    dir: &std::path::Path,
+    offline: bool,
+    rustpress_version: Option<String>,
+) -> CliResult<()> {
+    let machine_readable = matches!(ctx.output_format, OutputFormat::Json | OutputFormat::Yaml);
+    let doctor = PluginDoctor::new(
+        rustpress_version.unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string()),
+    );
+
+    let (mut report, manifests) = doctor.diagnose_dir(dir);
+
+    if !offline {
+        let spinner = (!machine_readable).then(|| ProgressBar::spinner("Checking for updates..."));
+        let checker = UpdateChecker::new(UpdateConfig::default());
+        doctor
+            .check_updates(&mut report, &checker, &manifests)
+            .await;
+        if let Some(spinner) = spinner {
+            spinner.finish_and_clear();
+        }
+    }
+
+    let errors = report.count(FindingSeverity::Error);
+    let warnings = report.count(FindingSeverity::Warning);
+
+    if machine_readable {
+        println!("{}", ctx.output_format.format_one(&report));
+    } else {
+        print_header("Plugin Doctor");
+        print_kv("Plugins directory", &dir.display().to_string());
+        print_kv("RustPress version", &report.rustpress_version);
+        print_kv("Plugins checked", &report.plugins.len().to_string());
+        print_kv(
+            "Update check",
+            &match &report.update_check {
+                UpdateCheckStatus::Skipped => "skipped (offline)".to_string(),
+                UpdateCheckStatus::Completed => "completed".to_string(),
+                UpdateCheckStatus::Failed(e) => format!("failed: {}", e),
+            },
+        );
+        println!();
+
+        if report.findings.is_empty() {
+            println!(
+                "{}",
+                ctx.output_format
+                    .success("No problems found in installed plugins")
+            );
+            return Ok(());
+        }
+
+        let rows: Vec<DoctorFindingRow> = report.findings.iter().map(Into::into).collect();
+        println!("{}", ctx.output_format.format(&rows));
+        println!();
+        print_kv("Errors", &errors.to_string());
+        print_kv("Warnings", &warnings.to_string());
+    }
+
+    if errors > 0 || warnings > 0 {
+        return Err(CliError::OperationFailed(format!(
+            "Plugin doctor found {} error(s) and {} warning(s)",
+            errors, warnings
+        )));
+    }
+
+    Ok(())
+}
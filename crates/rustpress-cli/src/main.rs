@@ -61,6 +61,12 @@ async fn run(cli: Cli) -> CliResult<()> {
         | Commands::Info
         | Commands::Server(_) => {}
         // ImportExport analyze doesn't need auth
+        // Plugin doctor reads local manifests; only its update check is remote
+        Commands::Plugins(ref cmd)
+            if matches!(
+                cmd.command,
+                commands::plugins::PluginsSubcommand::Doctor { .. }
+            ) => {}
         Commands::ImportExport(ref cmd) => {
             if !matches!(
                 cmd.command,
@@ -1,9 +1,10 @@
 //! Interactive REPL (Read-Eval-Print Loop) for RustPress CLI
 //!
-//! Provides an interactive shell with command history, auto-completion,
-//! and persistent session management.
+//! Provides an interactive shell with persistent command history,
+//! auto-completion of subcommands and of entity names (post slugs,
+//! usernames, theme IDs) fetched from the server.
 
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 use colored::Colorize;
 use rustyline::completion::{Completer, Pair};
 use rustyline::error::ReadlineError;
@@ -11,131 +12,235 @@ use rustyline::highlight::Highlighter;
 use rustyline::hint::Hinter;
 use rustyline::validate::Validator;
 use rustyline::{Config, Editor, Helper};
+use serde_json::Value;
 use std::borrow::Cow;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use crate::commands::{Cli, Commands};
 use crate::context::{CliContext, CliCredentials};
 use crate::error::{CliError, CliResult};
 use crate::output::print_header;
 
+/// Commands handled by the REPL itself rather than by clap
+const REPL_COMMANDS: &[&str] = &["help", "exit", "quit", "clear", "history", "status", "info"];
+
+/// Lines containing any of these (case-insensitive) are never written to history
+const SECRET_MARKERS: &[&str] = &[
+    "password", "passwd", "secret", "token", "api-key", "api_key", "apikey", "bearer",
+];
+
+/// Upper bound on a single entity name fetch so an unreachable server
+/// never stalls the shell
+const ENTITY_FETCH_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Number of entities of each kind fetched for completion
+const ENTITY_FETCH_LIMIT: u32 = 200;
+
+/// Path of the persistent history file
+fn history_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".rustpress_history")
+}
+
+/// Whether a line looks like it carries a credential and must stay out of history
+fn contains_secret(line: &str) -> bool {
+    let line = line.to_ascii_lowercase();
+    SECRET_MARKERS.iter().any(|marker| line.contains(marker))
+}
+
+/// Kind of server-side entity a positional argument refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntityKind {
+    Post,
+    User,
+    Theme,
+}
+
+impl EntityKind {
+    /// Entity expected as the first positional argument of `command subcommand`
+    fn for_subcommand(command: &str, subcommand: &str) -> Option<Self> {
+        match (command, subcommand) {
+            ("posts", "get" | "update" | "delete" | "publish" | "unpublish" | "duplicate") => {
+                Some(Self::Post)
+            }
+            ("users", "get" | "update" | "delete" | "reset-password") => Some(Self::User),
+            ("themes", "get" | "activate" | "delete" | "export") => Some(Self::Theme),
+            _ => None,
+        }
+    }
+}
+
+/// Entity names fetched from the server, used for argument completion
+#[derive(Debug, Default)]
+struct EntityNames {
+    posts: Vec<String>,
+    users: Vec<String>,
+    themes: Vec<String>,
+}
+
+impl EntityNames {
+    fn get(&self, kind: EntityKind) -> &[String] {
+        match kind {
+            EntityKind::Post => &self.posts,
+            EntityKind::User => &self.users,
+            EntityKind::Theme => &self.themes,
+        }
+    }
+}
+
+type SharedEntityNames = Arc<RwLock<EntityNames>>;
+
+/// Refresh the entity name cache in the background.
+///
+/// Does nothing when not logged in. Any failure (offline server, expired
+/// token, unexpected payload) leaves the previously cached names in place,
+/// so completion falls back to subcommands only.
+fn spawn_entity_refresh(entities: SharedEntityNames) {
+    let creds = CliCredentials::load();
+    let Some(token) = creds.access_token else {
+        return;
+    };
+    let server_url = if creds.server_url.is_empty() {
+        "http://localhost:3080".to_string()
+    } else {
+        creds.server_url.trim_end_matches('/').to_string()
+    };
+
+    tokio::spawn(async move {
+        let Ok(client) = reqwest::Client::builder()
+            .timeout(ENTITY_FETCH_TIMEOUT)
+            .build()
+        else {
+            return;
+        };
+        let auth = format!("Bearer {}", token);
+        let base = format!("{}/api/v1", server_url);
+
+        let (posts, users, themes) = tokio::join!(
+            fetch_names(
+                &client,
+                &auth,
+                format!("{}/posts?limit={}&type=post", base, ENTITY_FETCH_LIMIT),
+                &["slug", "id"],
+            ),
+            fetch_names(
+                &client,
+                &auth,
+                format!("{}/users?limit={}", base, ENTITY_FETCH_LIMIT),
+                &["username", "email", "id"],
+            ),
+            fetch_names(
+                &client,
+                &auth,
+                format!("{}/themes", base),
+                &["id", "slug", "name"],
+            ),
+        );
+
+        if let Ok(mut names) = entities.write() {
+            if let Some(posts) = posts {
+                names.posts = posts;
+            }
+            if let Some(users) = users {
+                names.users = users;
+            }
+            if let Some(themes) = themes {
+                names.themes = themes;
+            }
+        }
+    });
+}
+
+/// Fetch a listing and pull the first present field of `keys` from each item
+async fn fetch_names(
+    client: &reqwest::Client,
+    auth: &str,
+    url: String,
+    keys: &[&str],
+) -> Option<Vec<String>> {
+    let response = client
+        .get(&url)
+        .header("Authorization", auth)
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let body: Value = response.json().await.ok()?;
+    Some(extract_names(&body, keys))
+}
+
+/// Accepts both a bare array and the `{ "data": [...] }` envelope
+fn extract_names(body: &Value, keys: &[&str]) -> Vec<String> {
+    let items = body
+        .as_array()
+        .or_else(|| body.get("data").and_then(Value::as_array));
+
+    let mut names: Vec<String> = items
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            keys.iter()
+                .find_map(|key| item.get(*key).and_then(Value::as_str))
+                .map(str::to_string)
+        })
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
 /// REPL command completer
 #[derive(Default)]
 struct ReplCompleter {
     commands: Vec<String>,
+    entities: SharedEntityNames,
 }
 
 impl ReplCompleter {
-    fn new() -> Self {
-        Self {
-            commands: vec![
-                // Main commands
-                "auth".into(),
-                "server".into(),
-                "db".into(),
-                "users".into(),
-                "posts".into(),
-                "pages".into(),
-                "media".into(),
-                "themes".into(),
-                "plugins".into(),
-                "cache".into(),
-                "settings".into(),
-                "backup".into(),
-                "seo".into(),
-                "config".into(),
-                // Auth subcommands
-                "auth login".into(),
-                "auth logout".into(),
-                "auth whoami".into(),
-                "auth token".into(),
-                // Server subcommands
-                "server start".into(),
-                "server stop".into(),
-                "server status".into(),
-                "server health".into(),
-                // DB subcommands
-                "db migrate".into(),
-                "db status".into(),
-                "db backup".into(),
-                "db restore".into(),
-                "db tables".into(),
-                "db optimize".into(),
-                // User subcommands
-                "users list".into(),
-                "users create".into(),
-                "users get".into(),
-                "users delete".into(),
-                "users update".into(),
-                "users import".into(),
-                "users export".into(),
-                // Posts subcommands
-                "posts list".into(),
-                "posts create".into(),
-                "posts get".into(),
-                "posts update".into(),
-                "posts delete".into(),
-                "posts publish".into(),
-                "posts unpublish".into(),
-                // Pages subcommands
-                "pages list".into(),
-                "pages create".into(),
-                "pages get".into(),
-                "pages update".into(),
-                "pages delete".into(),
-                // Media subcommands
-                "media list".into(),
-                "media upload".into(),
-                "media get".into(),
-                "media delete".into(),
-                "media optimize".into(),
-                // Theme subcommands
-                "themes list".into(),
-                "themes get".into(),
-                "themes activate".into(),
-                "themes install".into(),
-                "themes delete".into(),
-                // Plugin subcommands
-                "plugins list".into(),
-                "plugins get".into(),
-                "plugins activate".into(),
-                "plugins deactivate".into(),
-                "plugins install".into(),
-                // Cache subcommands
-                "cache stats".into(),
-                "cache clear".into(),
-                "cache warm".into(),
-                // Settings subcommands
-                "settings list".into(),
-                "settings get".into(),
-                "settings set".into(),
-                // Backup subcommands
-                "backup create".into(),
-                "backup list".into(),
-                "backup restore".into(),
-                "backup delete".into(),
-                // SEO subcommands
-                "seo sitemap".into(),
-                "seo analyze".into(),
-                "seo robots".into(),
-                // Config subcommands
-                "config show".into(),
-                "config validate".into(),
-                "config init".into(),
-                // Health check
-                "health".into(),
-                "health check".into(),
-                // System info
-                "info".into(),
-                "system".into(),
-                // REPL commands
-                "help".into(),
-                "exit".into(),
-                "quit".into(),
-                "clear".into(),
-                "history".into(),
-                "status".into(),
-            ],
+    fn new(entities: SharedEntityNames) -> Self {
+        let mut commands = Vec::new();
+        for command in Cli::command().get_subcommands() {
+            let name = command.get_name();
+            commands.push(name.to_string());
+            for subcommand in command.get_subcommands() {
+                commands.push(format!("{} {}", name, subcommand.get_name()));
+            }
         }
+        for command in REPL_COMMANDS {
+            if !commands.iter().any(|c| c == command) {
+                commands.push(command.to_string());
+            }
+        }
+
+        Self { commands, entities }
+    }
+
+    /// Complete an entity argument when the cursor sits on the first
+    /// positional after an entity-taking subcommand
+    fn complete_entity(&self, preceding: &str, word: &str) -> Option<Vec<Pair>> {
+        let words: Vec<&str> = preceding.split_whitespace().collect();
+        let [command, subcommand] = words.as_slice() else {
+            return None;
+        };
+        let kind = EntityKind::for_subcommand(command, subcommand)?;
+        let names = self.entities.read().ok()?;
+
+        Some(
+            names
+                .get(kind)
+                .iter()
+                .filter(|name| name.starts_with(word))
+                .map(|name| Pair {
+                    display: name.clone(),
+                    replacement: name.clone(),
+                })
+                .collect(),
+        )
     }
 }
 
@@ -155,6 +260,10 @@ impl Completer for ReplCompleter {
             .unwrap_or(0);
         let word = &line_up_to_cursor[start..];
 
+        if let Some(matches) = self.complete_entity(&line_up_to_cursor[..start], word) {
+            return Ok((start, matches));
+        }
+
         let matches: Vec<Pair> = self
             .commands
             .iter()
@@ -240,13 +349,13 @@ pub async fn run_repl() -> CliResult<()> {
 
     let mut rl: Editor<ReplCompleter, _> = Editor::with_config(config)
         .map_err(|e| CliError::InvalidInput(format!("Failed to create readline: {}", e)))?;
-    rl.set_helper(Some(ReplCompleter::new()));
+
+    let entities = SharedEntityNames::default();
+    rl.set_helper(Some(ReplCompleter::new(entities.clone())));
+    spawn_entity_refresh(entities.clone());
 
     // Load history
-    let history_path = dirs::home_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join(".rustpress")
-        .join("history");
+    let history_path = history_path();
     let _ = rl.load_history(&history_path);
 
     loop {
@@ -265,7 +374,9 @@ pub async fn run_repl() -> CliResult<()> {
                     continue;
                 }
 
-                let _ = rl.add_history_entry(line);
+                if !contains_secret(line) {
+                    let _ = rl.add_history_entry(line);
+                }
 
                 // Handle REPL-specific commands
                 match line {
@@ -303,6 +414,15 @@ pub async fn run_repl() -> CliResult<()> {
                     eprintln!("{} {}", "Error:".red().bold(), e);
                 }
                 println!();
+
+                // Pick up new or renamed entities, and names that became
+                // visible after logging in
+                if matches!(
+                    line.split_whitespace().next(),
+                    Some("auth" | "posts" | "users" | "themes")
+                ) {
+                    spawn_entity_refresh(entities.clone());
+                }
             }
             Err(ReadlineError::Interrupted) => {
                 println!("{}", "Use 'exit' or 'quit' to leave the shell.".dimmed());
@@ -319,9 +439,6 @@ pub async fn run_repl() -> CliResult<()> {
     }
 
     // Save history
-    if let Some(parent) = history_path.parent() {
-        let _ = std::fs::create_dir_all(parent);
-    }
     let _ = rl.save_history(&history_path);
 
     Ok(())
@@ -370,6 +487,12 @@ async fn execute_command(line: &str) -> CliResult<()> {
         | Commands::Interactive
         | Commands::Health { .. }
         | Commands::Info => {}
+        // Plugin doctor reads local manifests; only its update check is remote
+        Commands::Plugins(ref cmd)
+            if matches!(
+                cmd.command,
+                crate::commands::plugins::PluginsSubcommand::Doctor { .. }
+            ) => {}
         Commands::ImportExport(ref cmd) => {
             if !matches!(
                 cmd.command,
@@ -464,8 +587,15 @@ fn print_repl_help() {
     );
     println!();
     println!("{}", "Tips:".yellow());
-    println!("  - Use {} to auto-complete commands", "Tab".cyan());
+    println!(
+        "  - Use {} to auto-complete commands, post slugs, usernames and themes",
+        "Tab".cyan()
+    );
     println!("  - Use {} to navigate history", "Up/Down arrows".cyan());
+    println!(
+        "  - Lines containing passwords or tokens are not saved to {}",
+        "~/.rustpress_history".cyan()
+    );
     println!("  - Add {} to any command for help", "--help".cyan());
 }
 
@@ -510,3 +640,62 @@ async fn print_system_info() {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_contains_secret() {
+        assert!(contains_secret(
+            "users create --email a@b.c --password hunter2"
+        ));
+        assert!(contains_secret("settings set smtp_PASSWORD x"));
+        assert!(contains_secret("auth login --token abc"));
+        assert!(!contains_secret("posts list --limit 10"));
+    }
+
+    #[test]
+    fn test_extract_names() {
+        let bare = json!([{ "slug": "b" }, { "id": "a" }, { "slug": "b" }]);
+        assert_eq!(extract_names(&bare, &["slug", "id"]), vec!["a", "b"]);
+
+        let wrapped = json!({ "data": [{ "username": "alice", "email": "x" }] });
+        assert_eq!(
+            extract_names(&wrapped, &["username", "email"]),
+            vec!["alice"]
+        );
+
+        assert!(extract_names(&json!({ "error": "nope" }), &["slug"]).is_empty());
+    }
+
+    #[test]
+    fn test_completer_commands_from_cli() {
+        let completer = ReplCompleter::new(SharedEntityNames::default());
+        assert!(completer.commands.iter().any(|c| c == "posts get"));
+        assert!(completer.commands.iter().any(|c| c == "db diff"));
+        assert!(completer.commands.iter().any(|c| c == "exit"));
+    }
+
+    #[test]
+    fn test_entity_completion() {
+        let entities = SharedEntityNames::default();
+        entities.write().unwrap().posts = vec!["hello-world".into(), "help".into(), "other".into()];
+        let completer = ReplCompleter::new(entities);
+
+        let matches = completer.complete_entity("posts get ", "hel").unwrap();
+        let names: Vec<_> = matches.iter().map(|p| p.replacement.as_str()).collect();
+        assert_eq!(names, vec!["hello-world", "help"]);
+
+        // Offline / not yet fetched: no candidates, but still an entity slot
+        assert!(completer
+            .complete_entity("themes activate ", "")
+            .unwrap()
+            .is_empty());
+
+        // Not an entity position: fall back to subcommand completion
+        assert!(completer.complete_entity("posts ", "g").is_none());
+        assert!(completer.complete_entity("posts list ", "").is_none());
+    }
+}
@@ -24,6 +24,8 @@ pub mod markdown;
 pub mod media;
 pub mod oembed;
 pub mod post_types;
+pub mod preview;
+pub mod redirects;
 pub mod related;
 pub mod revision;
 pub mod sanitize;
@@ -32,13 +34,16 @@ pub mod shortcode;
 pub mod taxonomy;
 pub mod templates;
 pub mod toc;
+pub mod transitions;
 pub mod trash;
 pub mod versioning;
 pub mod workflow;
 pub mod wxr;
 
 use chrono::{DateTime, Utc};
+use rustpress_events::EventBus;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -55,6 +60,13 @@ pub use markdown::*;
 pub use media::*;
 pub use oembed::*;
 pub use post_types::*;
+// Named explicitly so `PreviewToken` is the stored preview link rather than
+// the in-memory `access::PreviewToken`
+pub use preview::{
+    PreviewError, PreviewLinkManager, PreviewOptions, PreviewToken, MAX_PREVIEW_EXPIRY_DAYS,
+    PREVIEW_PATH_PREFIX,
+};
+pub use redirects::*;
 pub use related::*;
 pub use revision::*;
 pub use sanitize::*;
@@ -63,6 +75,7 @@ pub use shortcode::*;
 pub use taxonomy::*;
 pub use templates::*;
 pub use toc::*;
+pub use transitions::*;
 pub use trash::*;
 pub use versioning::*;
 pub use workflow::*;
@@ -97,6 +110,9 @@ pub enum ContentError {
 
     #[error("Scheduler error: {0}")]
     Scheduler(String),
+
+    #[error("Redirect loop: {0}")]
+    RedirectLoop(String),
 }
 
 pub type ContentResult<T> = Result<T, ContentError>;
@@ -410,12 +426,47 @@ impl Content {
     }
 }
 
+/// Content is checked as `{post_type}s` owned by its author, so an author's
+/// `posts:edit_own` covers their own posts but not anyone else's.
+impl rustpress_auth::AsResource for Content {
+    fn as_resource(&self) -> rustpress_auth::Resource {
+        rustpress_auth::Resource::owned_by(format!("{}s", self.post_type), self.author_id)
+    }
+}
+
 /// Content service for managing content operations
 pub struct ContentService {
     pool: sqlx::PgPool,
     versioning: VersioningService,
     scheduler: scheduler::PublishScheduler,
     autosave: AutosaveService,
+    redirects: RedirectManager,
+    previews: PreviewLinkManager,
+    event_bus: Option<Arc<EventBus>>,
+}
+
+/// Options for [`ContentService::update_with`]
+#[derive(Debug, Clone)]
+pub struct UpdateOptions {
+    /// Record a 301 from the old path when a published item's slug changes
+    pub redirect_old_slug: bool,
+}
+
+impl Default for UpdateOptions {
+    fn default() -> Self {
+        Self {
+            redirect_old_slug: true,
+        }
+    }
+}
+
+impl UpdateOptions {
+    /// Update without recording a redirect for a changed slug
+    pub fn without_redirect() -> Self {
+        Self {
+            redirect_old_slug: false,
+        }
+    }
 }
 
 impl ContentService {
@@ -425,14 +476,31 @@ impl ContentService {
             pool: pool.clone(),
             versioning: VersioningService::new(pool.clone()),
             scheduler: scheduler::PublishScheduler::new(pool.clone()),
-            autosave: AutosaveService::new(pool),
+            autosave: AutosaveService::new(pool.clone()),
+            redirects: RedirectManager::new(pool.clone()),
+            previews: PreviewLinkManager::new(pool),
+            event_bus: None,
         }
     }
 
+    /// Sign draft preview links with `secret`; without one, preview links
+    /// can't be created or opened
+    pub fn with_preview_secret(mut self, secret: impl AsRef<[u8]>) -> Self {
+        self.previews = self.previews.with_secret(secret);
+        self
+    }
+
+    /// Publish content lifecycle events (`post.published`, `post.trashed`,
+    /// ...) to `event_bus`
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
     /// Create new content
     pub async fn create(&self, content: Content) -> ContentResult<Content> {
         // Validate content
-        self.validate(&content)?;
+        Self::validate(&content)?;
 
         // Save to database
         sqlx::query(
@@ -480,9 +548,29 @@ impl ContentService {
     }
 
     /// Update existing content
-    pub async fn update(&self, mut content: Content) -> ContentResult<Content> {
+    ///
+    /// Changing the slug of published content records a 301 from the old
+    /// path; use [`update_with`](Self::update_with) to skip that.
+    pub async fn update(&self, content: Content) -> ContentResult<Content> {
+        self.update_with(content, UpdateOptions::default()).await
+    }
+
+    /// Update existing content with options
+    pub async fn update_with(
+        &self,
+        mut content: Content,
+        options: UpdateOptions,
+    ) -> ContentResult<Content> {
         // Validate content
-        self.validate(&content)?;
+        Self::validate(&content)?;
+
+        let moved_from = if options.redirect_old_slug {
+            let previous = self.get(content.id).await?;
+            (previous.status == ContentStatus::Published && previous.slug != content.slug)
+                .then(|| RedirectManager::content_path(&previous.post_type, &previous.slug))
+        } else {
+            None
+        };
 
         // Increment revision
         content.revision += 1;
@@ -526,6 +614,25 @@ impl ContentService {
         // Create new revision
         self.versioning.create_revision(&content).await?;
 
+        if let Some(old_path) = moved_from {
+            let new_path = RedirectManager::content_path(&content.post_type, &content.slug);
+            // The new path is live content now, so a redirect left over from
+            // an earlier rename must not shadow it
+            let recorded = match self.redirects.remove(&new_path).await {
+                Ok(_) => self.redirects.record(&old_path, &new_path, 301).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = recorded {
+                tracing::warn!(
+                    content_id = %content.id,
+                    from = %old_path,
+                    to = %new_path,
+                    error = %e,
+                    "Failed to record redirect for changed slug"
+                );
+            }
+        }
+
         Ok(content)
     }
 
@@ -605,7 +712,7 @@ impl ContentService {
     }
 
     /// Validate content
-    fn validate(&self, content: &Content) -> ContentResult<()> {
+    fn validate(content: &Content) -> ContentResult<()> {
         if content.title.is_empty() {
             return Err(ContentError::Validation("Title is required".to_string()));
         }
@@ -640,6 +747,81 @@ impl ContentService {
     pub fn autosave(&self) -> &AutosaveService {
         &self.autosave
     }
+
+    /// Get redirect manager
+    pub fn redirects(&self) -> &RedirectManager {
+        &self.redirects
+    }
+
+    /// Get preview link manager
+    pub fn previews(&self) -> &PreviewLinkManager {
+        &self.previews
+    }
+
+    /// Create a shareable link previewing content as it is when opened
+    pub async fn create_preview_link(
+        &self,
+        content_id: Uuid,
+        expiry: chrono::Duration,
+    ) -> ContentResult<PreviewToken> {
+        self.create_preview_link_with(content_id, expiry, PreviewOptions::default())
+            .await
+    }
+
+    /// Create a shareable preview link with options
+    pub async fn create_preview_link_with(
+        &self,
+        content_id: Uuid,
+        expiry: chrono::Duration,
+        options: PreviewOptions,
+    ) -> ContentResult<PreviewToken> {
+        let content = self.get(content_id).await?;
+        if content.status == ContentStatus::Trash {
+            return Err(ContentError::Invalid(
+                "Cannot preview trashed content".to_string(),
+            ));
+        }
+        self.previews.create(&content, expiry, options).await
+    }
+
+    /// Revoke a preview link; returns false if it was already revoked
+    pub async fn revoke_preview_link(&self, link_id: Uuid) -> ContentResult<bool> {
+        self.previews.revoke(link_id).await
+    }
+
+    /// Open a preview link for `content_id`, returning the content to show
+    ///
+    /// Live links show the content's current state; pinned links show the
+    /// revision they were created for. Content trashed since the link was
+    /// shared is [`PreviewError::NotFound`].
+    pub async fn open_preview(
+        &self,
+        content_id: Uuid,
+        token: &str,
+    ) -> Result<Content, PreviewError> {
+        let link = self.previews.verify(content_id, token).await?;
+        let mut content = self.get(content_id).await?;
+        if content.status == ContentStatus::Trash {
+            return Err(PreviewError::NotFound);
+        }
+
+        if let Some(revision) = link.revision.filter(|r| *r != content.revision) {
+            let revision = self
+                .versioning
+                .get_revision(content_id, revision)
+                .await
+                .map_err(|e| match e {
+                    ContentError::NotFound(_) => PreviewError::NotFound,
+                    e => e.into(),
+                })?;
+            content.blocks = revision.parsed_blocks();
+            content.title = revision.title;
+            content.content = revision.content;
+            content.revision = revision.revision;
+        }
+
+        Ok(content)
+    }
 }
 
 /// Content filter for listing
@@ -773,6 +955,10 @@ CREATE TABLE IF NOT EXISTS content_revisions (
 
 CREATE INDEX IF NOT EXISTS idx_revisions_content ON content_revisions(content_id, revision DESC);
 
+-- Status and term snapshots (NULL for revisions recorded before they existed)
+ALTER TABLE content_revisions ADD COLUMN IF NOT EXISTS status VARCHAR(20);
+ALTER TABLE content_revisions ADD COLUMN IF NOT EXISTS terms JSONB;
+
 -- Autosaves
 CREATE TABLE IF NOT EXISTS content_autosaves (
     id UUID PRIMARY KEY,
@@ -821,6 +1007,9 @@ CREATE TABLE IF NOT EXISTS post_types (
     created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
 );
 
+-- Capability base name for custom post types (NULL = slug)
+ALTER TABLE post_types ADD COLUMN IF NOT EXISTS capability_type VARCHAR(50);
+
 -- Insert default post types
 INSERT INTO post_types (slug, name, singular_name, is_system) VALUES
     ('post', 'Posts', 'Post', true),
@@ -888,4 +1077,17 @@ CREATE TABLE IF NOT EXISTS scheduled_publishes (
 
 CREATE INDEX IF NOT EXISTS idx_scheduled_pending ON scheduled_publishes(scheduled_at)
     WHERE status = 'pending';
+
+-- Draft preview links (tokens are signed, never stored)
+CREATE TABLE IF NOT EXISTS content_preview_links (
+    id UUID PRIMARY KEY,
+    content_id UUID NOT NULL REFERENCES contents(id) ON DELETE CASCADE,
+    revision INTEGER,
+    created_by UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_preview_links_content ON content_preview_links(content_id);
 "#;
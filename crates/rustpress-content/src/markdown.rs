@@ -3,9 +3,24 @@
 //! Provides markdown to HTML conversion with syntax highlighting,
 //! table support, and GFM (GitHub Flavored Markdown) extensions.
 
-use pulldown_cmark::{html, Event, HeadingLevel, Options, Parser, Tag};
+use pulldown_cmark::escape::escape_html;
+use pulldown_cmark::{html, CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::{styled_line_to_highlighted_html, IncludeBackground};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+use uuid::Uuid;
+
+/// Bundled theme used for `github` and for unknown theme names
+const DEFAULT_HIGHLIGHT_THEME: &str = "InspiredGitHub";
+
+/// Longest line that gets highlighted; longer ones (minified code, mostly)
+/// leave the whole block plain
+const MAX_HIGHLIGHT_LINE_BYTES: usize = 4 * 1024;
 
 /// Markdown processor configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +49,15 @@ pub struct MarkdownConfig {
     /// Syntax highlighting theme
     pub highlight_theme: String,
 
+    /// Largest code block, in bytes, that gets highlighted
+    #[serde(default = "default_highlight_max_bytes")]
+    pub highlight_max_bytes: usize,
+
+    /// Time budget for highlighting the code blocks of one document, in
+    /// milliseconds; blocks left when it runs out are rendered plain
+    #[serde(default = "default_highlight_timeout_ms")]
+    pub highlight_timeout_ms: u64,
+
     /// Enable table of contents generation
     pub toc: bool,
 
@@ -55,6 +79,8 @@ impl Default for MarkdownConfig {
             heading_anchors: true,
             syntax_highlighting: true,
             highlight_theme: "github".to_string(),
+            highlight_max_bytes: default_highlight_max_bytes(),
+            highlight_timeout_ms: default_highlight_timeout_ms(),
             toc: false,
             toc_max_depth: 3,
             autolink: true,
@@ -62,6 +88,14 @@ impl Default for MarkdownConfig {
     }
 }
 
+fn default_highlight_max_bytes() -> usize {
+    64 * 1024
+}
+
+fn default_highlight_timeout_ms() -> u64 {
+    250
+}
+
 /// Markdown processor
 pub struct MarkdownProcessor {
     config: MarkdownConfig,
@@ -80,29 +114,50 @@ impl MarkdownProcessor {
         Self { config }
     }
 
+    /// Create processor highlighting fenced code blocks with `theme`
+    ///
+    /// Any of syntect's bundled themes (e.g. `base16-ocean.dark`,
+    /// `Solarized (light)`) can be used; `github` and unknown names get
+    /// `InspiredGitHub`.
+    pub fn with_highlighting(theme: impl Into<String>) -> Self {
+        Self::with_config(MarkdownConfig {
+            syntax_highlighting: true,
+            highlight_theme: theme.into(),
+            ..MarkdownConfig::default()
+        })
+    }
+
     /// Convert markdown to HTML
     pub fn to_html(&self, markdown: &str) -> String {
         let options = self.build_options();
         let parser = Parser::new_ext(markdown, options);
 
         // Process events for custom handling
-        let events: Vec<Event> = if self.config.heading_anchors {
+        let mut events: Vec<Event> = if self.config.heading_anchors {
             self.add_heading_anchors(parser)
         } else {
             parser.collect()
         };
 
+        // Code blocks are swapped for placeholders and only put back after
+        // sanitizing: the highlighter escapes the code itself, and ammonia
+        // would strip its inline styles
+        let marker = format!("rp-code-{}", Uuid::new_v4().simple());
+        let mut code_blocks = Vec::new();
+        if self.config.syntax_highlighting {
+            events = self.highlight_code_blocks(events, &marker, &mut code_blocks);
+        }
+
         // Render to HTML
         let mut html_output = String::new();
         html::push_html(&mut html_output, events.into_iter());
 
-        // Apply syntax highlighting if enabled
-        if self.config.syntax_highlighting {
-            html_output = self.apply_syntax_highlighting(&html_output);
-        }
-
         // Sanitize output
-        ammonia::clean(&html_output)
+        let mut html_output = ammonia::clean(&html_output);
+        for (index, block) in code_blocks.iter().enumerate() {
+            html_output = html_output.replacen(&code_placeholder(&marker, index), block, 1);
+        }
+        html_output
     }
 
     /// Convert markdown to plain text (strip formatting)
@@ -353,28 +408,43 @@ impl MarkdownProcessor {
         events
     }
 
-    fn apply_syntax_highlighting(&self, html: &str) -> String {
-        // Simple syntax highlighting using regex
-        // In production, use a proper syntax highlighter like syntect
-        let mut result = html.to_string();
-
-        // Find code blocks with language
-        if let Ok(re) =
-            regex::Regex::new(r#"<pre><code class="language-(\w+)">([\s\S]*?)</code></pre>"#)
-        {
-            result = re.replace_all(&result, |caps: &regex::Captures| {
-                let lang = caps.get(1).map(|m| m.as_str()).unwrap_or("");
-                let code = caps.get(2).map(|m| m.as_str()).unwrap_or("");
-
-                format!(
-                    r#"<pre class="language-{lang}"><code class="language-{lang}">{code}</code></pre>"#,
-                    lang = lang,
-                    code = highlight_code(code, lang)
-                )
-            }).to_string();
+    fn highlight_code_blocks<'a>(
+        &self,
+        events: Vec<Event<'a>>,
+        marker: &str,
+        code_blocks: &mut Vec<String>,
+    ) -> Vec<Event<'a>> {
+        let highlighter = CodeHighlighter::new(&self.config);
+        let mut output = Vec::with_capacity(events.len());
+        let mut current: Option<(String, String)> = None;
+
+        for event in events {
+            match event {
+                Event::Start(Tag::CodeBlock(kind)) => {
+                    let language = match kind {
+                        CodeBlockKind::Fenced(info) => {
+                            info.split_whitespace().next().unwrap_or("").to_string()
+                        }
+                        CodeBlockKind::Indented => String::new(),
+                    };
+                    current = Some((language, String::new()));
+                }
+                Event::Text(text) => match &mut current {
+                    Some((_, code)) => code.push_str(&text),
+                    None => output.push(Event::Text(text)),
+                },
+                Event::End(Tag::CodeBlock(_)) => {
+                    if let Some((language, code)) = current.take() {
+                        let placeholder = code_placeholder(marker, code_blocks.len());
+                        output.push(Event::Html(format!("{}\n", placeholder).into()));
+                        code_blocks.push(highlighter.render(&code, &language));
+                    }
+                }
+                event => output.push(event),
+            }
         }
 
-        result
+        output
     }
 }
 
@@ -411,118 +481,89 @@ pub struct MarkdownLink {
     pub url: String,
 }
 
-/// Simple syntax highlighting (basic implementation)
-fn highlight_code(code: &str, language: &str) -> String {
-    // This is a basic implementation. For production, use syntect.
-    let keywords: HashMap<&str, Vec<&str>> = [
-        (
-            "rust",
-            vec![
-                "fn", "let", "mut", "const", "static", "pub", "mod", "use", "impl", "trait",
-                "struct", "enum", "type", "where", "async", "await", "match", "if", "else", "for",
-                "while", "loop", "return", "break", "continue", "self", "Self", "super", "crate",
-            ],
-        ),
-        (
-            "javascript",
-            vec![
-                "function", "const", "let", "var", "return", "if", "else", "for", "while", "do",
-                "switch", "case", "break", "continue", "class", "extends", "import", "export",
-                "default", "async", "await", "try", "catch", "finally", "throw", "new", "this",
-            ],
-        ),
-        (
-            "python",
-            vec![
-                "def", "class", "return", "if", "elif", "else", "for", "while", "break",
-                "continue", "import", "from", "as", "try", "except", "finally", "raise", "with",
-                "lambda", "yield", "global", "nonlocal", "pass", "True", "False", "None", "and",
-                "or", "not", "in", "is",
-            ],
-        ),
-        (
-            "go",
-            vec![
-                "func",
-                "package",
-                "import",
-                "type",
-                "struct",
-                "interface",
-                "const",
-                "var",
-                "return",
-                "if",
-                "else",
-                "for",
-                "switch",
-                "case",
-                "default",
-                "break",
-                "continue",
-                "go",
-                "chan",
-                "select",
-                "defer",
-                "range",
-                "map",
-                "make",
-                "new",
-            ],
-        ),
+/// Placeholder standing in for a code block until sanitizing is done
+fn code_placeholder(marker: &str, index: usize) -> String {
+    format!("<p>{}-{}</p>", marker, index)
+}
+
+/// Bundled syntaxes and themes, loaded on first use
+fn highlight_assets() -> &'static (SyntaxSet, ThemeSet) {
+    static ASSETS: OnceLock<(SyntaxSet, ThemeSet)> = OnceLock::new();
+    ASSETS.get_or_init(|| {
         (
-            "sql",
-            vec![
-                "SELECT", "FROM", "WHERE", "JOIN", "LEFT", "RIGHT", "INNER", "OUTER", "ON", "AND",
-                "OR", "NOT", "IN", "IS", "NULL", "AS", "ORDER", "BY", "GROUP", "HAVING", "LIMIT",
-                "OFFSET", "INSERT", "INTO", "VALUES", "UPDATE", "SET", "DELETE", "CREATE", "TABLE",
-                "ALTER", "DROP", "INDEX",
-            ],
-        ),
-    ]
-    .into_iter()
-    .collect();
-
-    let lang_keywords = keywords.get(language).cloned().unwrap_or_default();
-
-    if lang_keywords.is_empty() {
-        return code.to_string();
-    }
+            SyntaxSet::load_defaults_newlines(),
+            ThemeSet::load_defaults(),
+        )
+    })
+}
+
+/// Server-side highlighting for the code blocks of one document
+struct CodeHighlighter {
+    syntaxes: &'static SyntaxSet,
+    theme: &'static Theme,
+    max_bytes: usize,
+    deadline: Instant,
+}
 
-    let mut result = code.to_string();
+impl CodeHighlighter {
+    fn new(config: &MarkdownConfig) -> Self {
+        let (syntaxes, themes) = highlight_assets();
+        let theme = themes
+            .themes
+            .get(config.highlight_theme.as_str())
+            .unwrap_or(&themes.themes[DEFAULT_HIGHLIGHT_THEME]);
 
-    // Highlight strings
-    if let Ok(re) = regex::Regex::new(r#"("[^"]*"|'[^']*')"#) {
-        result = re
-            .replace_all(&result, r#"<span class="string">$1</span>"#)
-            .to_string();
+        Self {
+            syntaxes,
+            theme,
+            max_bytes: config.highlight_max_bytes,
+            deadline: Instant::now() + Duration::from_millis(config.highlight_timeout_ms),
+        }
     }
 
-    // Highlight comments (simple // and # style)
-    if let Ok(re) = regex::Regex::new(r"(//.*|#.*)$") {
-        result = re
-            .replace_all(&result, r#"<span class="comment">$1</span>"#)
-            .to_string();
+    /// Render a code block, as plain `<pre><code>` if it can't be highlighted
+    fn render(&self, code: &str, language: &str) -> String {
+        self.highlight(code, language).unwrap_or_else(|| {
+            let mut html = String::from("<pre><code>");
+            let _ = escape_html(&mut html, code);
+            html.push_str("</code></pre>\n");
+            html
+        })
     }
 
-    // Highlight keywords
-    for keyword in lang_keywords {
-        let pattern = format!(r"\b({})\b", regex::escape(keyword));
-        if let Ok(re) = regex::Regex::new(&pattern) {
-            result = re
-                .replace_all(&result, r#"<span class="keyword">$1</span>"#)
-                .to_string();
+    /// Highlighted HTML for a code block, or `None` if the language is
+    /// unknown, the block is too large or the time budget runs out
+    fn highlight(&self, code: &str, language: &str) -> Option<String> {
+        if language.is_empty() || code.len() > self.max_bytes {
+            return None;
+        }
+        let syntax = self.syntaxes.find_syntax_by_token(language)?;
+
+        let mut highlighter = HighlightLines::new(syntax, self.theme);
+        let mut lines = String::with_capacity(code.len() * 4);
+        for line in LinesWithEndings::from(code) {
+            // Checked per line so one slow block can't hold up the render
+            if line.len() > MAX_HIGHLIGHT_LINE_BYTES || Instant::now() >= self.deadline {
+                return None;
+            }
+            let regions = highlighter.highlight_line(line, self.syntaxes).ok()?;
+            lines.push_str(&styled_line_to_highlighted_html(&regions, IncludeBackground::No).ok()?);
         }
-    }
 
-    // Highlight numbers
-    if let Ok(re) = regex::Regex::new(r"\b(\d+(?:\.\d+)?)\b") {
-        result = re
-            .replace_all(&result, r#"<span class="number">$1</span>"#)
-            .to_string();
+        let mut html = String::from("<pre class=\"highlight\"");
+        if let Some(bg) = self.theme.settings.background {
+            html.push_str(&format!(
+                " style=\"background-color:#{:02x}{:02x}{:02x};\"",
+                bg.r, bg.g, bg.b
+            ));
+        }
+        html.push_str("><code class=\"language-");
+        escape_html(&mut html, language).ok()?;
+        html.push_str("\">");
+        html.push_str(&lines);
+        html.push_str("</code></pre>\n");
+        Some(html)
     }
-
-    result
 }
 
 /// Live preview data for editor synchronization
@@ -593,4 +634,42 @@ mod tests {
         assert_eq!(links[0].text, "Google");
         assert_eq!(links[0].url, "https://google.com");
     }
+
+    #[test]
+    fn test_highlighted_code_blocks() {
+        let processor = MarkdownProcessor::with_highlighting("base16-ocean.dark");
+        let html = processor.to_html(
+            "Text <script>alert(1)</script>\n\n```rust\nfn main() {}\n```\n\n```nosuchlang\n<b>x</b>\n```",
+        );
+
+        assert!(!html.contains("<script>"));
+        assert!(html.contains(r#"<pre class="highlight" style="background-color:#2b303b;"><code class="language-rust">"#));
+        assert!(html.contains("<span style=\"color:"));
+        // Unknown languages come out plain and escaped
+        assert!(html.contains("<pre><code>&lt;b&gt;x&lt;/b&gt;\n</code></pre>"));
+        assert!(!html.contains("rp-code-"));
+    }
+
+    #[test]
+    fn test_highlighting_limits() {
+        let code = "let x = 1;\n".repeat(100);
+        let markdown = format!("```rust\n{}```", code);
+
+        let html = MarkdownProcessor::with_config(MarkdownConfig {
+            highlight_max_bytes: 64,
+            ..MarkdownConfig::default()
+        })
+        .to_html(&markdown);
+        assert!(html.starts_with("<pre><code>let x = 1;"));
+
+        let html = MarkdownProcessor::with_config(MarkdownConfig {
+            highlight_timeout_ms: 0,
+            ..MarkdownConfig::default()
+        })
+        .to_html(&markdown);
+        assert!(html.starts_with("<pre><code>"));
+
+        let html = MarkdownProcessor::new().to_html(&markdown);
+        assert!(html.starts_with("<pre class=\"highlight\""));
+    }
 }
@@ -1,9 +1,13 @@
 //! Custom post types
 //!
 //! Allows creation of custom content types beyond standard posts and pages.
+//! Registering a type through [`PostTypeManager`] also creates its
+//! capabilities in the roles system and announces its routes.
 
 use chrono::{DateTime, Utc};
+use rustpress_users::{Capability, RoleManager};
 use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
 
 use crate::{ContentError, ContentResult};
 
@@ -420,6 +424,72 @@ impl PostTypeService {
         Ok(())
     }
 
+    /// Insert a post type, or update it if it already exists.
+    ///
+    /// System post types are never modified.
+    pub async fn save(&self, definition: &PostTypeDefinition) -> ContentResult<PostType> {
+        let post_type = &definition.post_type;
+        post_type.validate()?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO post_types (
+                slug, name, singular_name, description, public, hierarchical,
+                has_archive, rewrite_slug, supports, taxonomies, menu_icon,
+                menu_position, is_system, created_at, capability_type
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, false, $13, $14)
+            ON CONFLICT (slug) DO UPDATE SET
+                name = EXCLUDED.name, singular_name = EXCLUDED.singular_name,
+                description = EXCLUDED.description, public = EXCLUDED.public,
+                hierarchical = EXCLUDED.hierarchical, has_archive = EXCLUDED.has_archive,
+                rewrite_slug = EXCLUDED.rewrite_slug, supports = EXCLUDED.supports,
+                taxonomies = EXCLUDED.taxonomies, menu_icon = EXCLUDED.menu_icon,
+                menu_position = EXCLUDED.menu_position,
+                capability_type = EXCLUDED.capability_type
+            WHERE post_types.is_system = false
+            "#,
+        )
+        .bind(&post_type.slug)
+        .bind(&post_type.name)
+        .bind(&post_type.singular_name)
+        .bind(&post_type.description)
+        .bind(post_type.public)
+        .bind(post_type.hierarchical)
+        .bind(post_type.has_archive)
+        .bind(&post_type.rewrite_slug)
+        .bind(serde_json::to_value(&post_type.supports)?)
+        .bind(serde_json::to_value(&post_type.taxonomies)?)
+        .bind(&post_type.menu_icon)
+        .bind(post_type.menu_position)
+        .bind(post_type.created_at)
+        .bind(&definition.capability_type)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ContentError::PermissionDenied(
+                "Cannot modify system post types".to_string(),
+            ));
+        }
+
+        self.get(&post_type.slug).await
+    }
+
+    /// Get the stored definition of a post type, including its capability type
+    pub async fn get_definition(&self, slug: &str) -> ContentResult<PostTypeDefinition> {
+        let post_type = self.get(slug).await?;
+        let capability_type: Option<String> =
+            sqlx::query_scalar("SELECT capability_type FROM post_types WHERE slug = $1")
+                .bind(slug)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(PostTypeDefinition {
+            post_type,
+            capability_type,
+        })
+    }
+
     /// Check if post type exists
     pub async fn exists(&self, slug: &str) -> ContentResult<bool> {
         let result: (bool,) =
@@ -432,6 +502,293 @@ impl PostTypeService {
     }
 }
 
+/// Capabilities controlling access to content of a post type, named after
+/// its capability type (`edit_book`, `edit_books`, `publish_books`, ...)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostTypeCapabilities {
+    /// Meta capabilities, checked against a single item
+    pub edit_post: String,
+    pub read_post: String,
+    pub delete_post: String,
+
+    /// Primitive capabilities, granted to roles
+    pub edit_posts: String,
+    pub edit_others_posts: String,
+    pub edit_published_posts: String,
+    pub edit_private_posts: String,
+    pub publish_posts: String,
+    pub delete_posts: String,
+    pub delete_others_posts: String,
+    pub delete_published_posts: String,
+    pub delete_private_posts: String,
+    pub read_private_posts: String,
+}
+
+/// Labels for [`PostTypeCapabilities::primitive`], `{}` being the plural name
+const PRIMITIVE_CAPABILITY_LABELS: [&str; 10] = [
+    "Edit {}",
+    "Edit Others' {}",
+    "Edit Published {}",
+    "Edit Private {}",
+    "Publish {}",
+    "Delete {}",
+    "Delete Others' {}",
+    "Delete Published {}",
+    "Delete Private {}",
+    "Read Private {}",
+];
+
+impl PostTypeCapabilities {
+    pub fn for_type(capability_type: &str) -> Self {
+        Self {
+            edit_post: format!("edit_{}", capability_type),
+            read_post: format!("read_{}", capability_type),
+            delete_post: format!("delete_{}", capability_type),
+            edit_posts: format!("edit_{}s", capability_type),
+            edit_others_posts: format!("edit_others_{}s", capability_type),
+            edit_published_posts: format!("edit_published_{}s", capability_type),
+            edit_private_posts: format!("edit_private_{}s", capability_type),
+            publish_posts: format!("publish_{}s", capability_type),
+            delete_posts: format!("delete_{}s", capability_type),
+            delete_others_posts: format!("delete_others_{}s", capability_type),
+            delete_published_posts: format!("delete_published_{}s", capability_type),
+            delete_private_posts: format!("delete_private_{}s", capability_type),
+            read_private_posts: format!("read_private_{}s", capability_type),
+        }
+    }
+
+    /// Primitive capabilities, in the same order for every type
+    pub fn primitive(&self) -> [&str; 10] {
+        [
+            &self.edit_posts,
+            &self.edit_others_posts,
+            &self.edit_published_posts,
+            &self.edit_private_posts,
+            &self.publish_posts,
+            &self.delete_posts,
+            &self.delete_others_posts,
+            &self.delete_published_posts,
+            &self.delete_private_posts,
+            &self.read_private_posts,
+        ]
+    }
+}
+
+/// Front-end routes served for a post type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostTypeRoutes {
+    pub post_type: String,
+
+    /// Archive listing, e.g. `/books`
+    pub archive: Option<String>,
+
+    /// Single item, e.g. `/books/:slug` (`/docs/*path` for hierarchical types)
+    pub single: Option<String>,
+}
+
+impl PostTypeRoutes {
+    /// Routes for a post type; non-public types get none
+    pub fn for_post_type(post_type: &PostType) -> Self {
+        let base = format!("/{}", post_type.get_url_path().trim_matches('/'));
+        let single = if post_type.hierarchical {
+            format!("{}/*path", base)
+        } else {
+            format!("{}/:slug", base)
+        };
+
+        Self {
+            post_type: post_type.slug.clone(),
+            archive: (post_type.public && post_type.has_archive).then_some(base),
+            single: post_type.public.then_some(single),
+        }
+    }
+}
+
+/// Receives route changes when post types are registered or removed
+pub trait PostTypeRouter: Send + Sync {
+    /// Serve (or replace) the routes of a post type
+    fn register_routes(&self, routes: &PostTypeRoutes);
+
+    /// Stop serving the routes of a post type
+    fn remove_routes(&self, post_type: &str);
+}
+
+/// Post type registration request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostTypeDefinition {
+    #[serde(flatten)]
+    pub post_type: PostType,
+
+    /// Base name for capabilities, defaults to the slug
+    #[serde(default)]
+    pub capability_type: Option<String>,
+}
+
+impl PostTypeDefinition {
+    pub fn capabilities(&self) -> PostTypeCapabilities {
+        PostTypeCapabilities::for_type(
+            self.capability_type
+                .as_deref()
+                .unwrap_or(&self.post_type.slug),
+        )
+    }
+}
+
+impl From<PostType> for PostTypeDefinition {
+    fn from(post_type: PostType) -> Self {
+        Self {
+            post_type,
+            capability_type: None,
+        }
+    }
+}
+
+/// Registers custom post types end to end: persists them, creates their
+/// capabilities in the roles system and notifies routers.
+///
+/// Each role receives a type's capabilities matching the post capabilities
+/// it already holds, so an author can publish books as well as posts.
+pub struct PostTypeManager {
+    service: PostTypeService,
+    roles: Arc<RwLock<RoleManager>>,
+    routers: Vec<Arc<dyn PostTypeRouter>>,
+}
+
+impl PostTypeManager {
+    pub fn new(pool: sqlx::PgPool, roles: Arc<RwLock<RoleManager>>) -> Self {
+        Self {
+            service: PostTypeService::new(pool),
+            roles,
+            routers: Vec::new(),
+        }
+    }
+
+    /// Notify a router of post type route changes
+    pub fn with_router(mut self, router: Arc<dyn PostTypeRouter>) -> Self {
+        self.routers.push(router);
+        self
+    }
+
+    /// Register a post type, or update it if it already exists.
+    ///
+    /// Registering the same definition again leaves the stored type,
+    /// capabilities and routes unchanged.
+    pub async fn register(&self, definition: PostTypeDefinition) -> ContentResult<PostType> {
+        let previous = match self
+            .service
+            .get_definition(&definition.post_type.slug)
+            .await
+        {
+            Ok(previous) => Some(previous),
+            Err(ContentError::NotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+        if previous.as_ref().is_some_and(|p| p.post_type.is_system) {
+            return Err(ContentError::PermissionDenied(
+                "Cannot modify system post types".to_string(),
+            ));
+        }
+
+        let post_type = self.service.save(&definition).await?;
+        // Capabilities of a previous capability type are replaced
+        let capabilities = definition.capabilities();
+        if let Some(previous) = previous.map(|p| p.capabilities()) {
+            if previous != capabilities {
+                self.remove_capabilities(&previous);
+            }
+        }
+        self.grant_capabilities(&post_type, &capabilities);
+        self.announce_routes(&post_type);
+
+        Ok(post_type)
+    }
+
+    /// Delete a post type, its capabilities and its routes.
+    ///
+    /// System post types (post, page) cannot be deleted.
+    pub async fn unregister(&self, slug: &str) -> ContentResult<()> {
+        let definition = self.service.get_definition(slug).await?;
+        if definition.post_type.is_system {
+            return Err(ContentError::PermissionDenied(
+                "Cannot delete system post types".to_string(),
+            ));
+        }
+
+        self.service.unregister(slug).await?;
+        self.remove_capabilities(&definition.capabilities());
+
+        for router in &self.routers {
+            router.remove_routes(slug);
+        }
+
+        Ok(())
+    }
+
+    /// Re-create capabilities and routes for every stored custom post type,
+    /// e.g. at startup
+    pub async fn restore(&self) -> ContentResult<Vec<PostType>> {
+        let mut post_types = Vec::new();
+        for post_type in self.service.list().await? {
+            if post_type.is_system {
+                continue;
+            }
+            let definition = self.service.get_definition(&post_type.slug).await?;
+            self.grant_capabilities(&post_type, &definition.capabilities());
+            self.announce_routes(&post_type);
+            post_types.push(post_type);
+        }
+
+        Ok(post_types)
+    }
+
+    fn remove_capabilities(&self, capabilities: &PostTypeCapabilities) {
+        let mut roles = self.roles.write().unwrap_or_else(|e| e.into_inner());
+        for capability in capabilities.primitive() {
+            roles.unregister_capability(capability);
+        }
+    }
+
+    fn grant_capabilities(&self, post_type: &PostType, capabilities: &PostTypeCapabilities) {
+        let post_capabilities = PostTypeCapabilities::for_type("post");
+        let mut roles = self.roles.write().unwrap_or_else(|e| e.into_inner());
+
+        for (capability, label) in capabilities
+            .primitive()
+            .into_iter()
+            .zip(PRIMITIVE_CAPABILITY_LABELS)
+        {
+            roles.register_capability(Capability::new(
+                capability,
+                &label.replace("{}", &post_type.name),
+                &post_type.slug,
+            ));
+        }
+
+        let role_names: Vec<String> = roles.get_roles().iter().map(|r| r.name.clone()).collect();
+        for name in role_names {
+            let Some(role) = roles.get_role_mut(&name) else {
+                continue;
+            };
+            for (post_capability, capability) in post_capabilities
+                .primitive()
+                .into_iter()
+                .zip(capabilities.primitive())
+            {
+                if role.has_cap(post_capability) && !role.has_cap(capability) {
+                    role.add_cap(capability);
+                }
+            }
+        }
+    }
+
+    fn announce_routes(&self, post_type: &PostType) {
+        let routes = PostTypeRoutes::for_post_type(post_type);
+        for router in &self.routers {
+            router.register_routes(&routes);
+        }
+    }
+}
+
 /// Database row
 #[derive(Debug, sqlx::FromRow)]
 struct PostTypeRow {
@@ -507,4 +864,110 @@ mod tests {
         assert!(pt.has_support(PostTypeSupport::Title));
         assert!(pt.has_support(PostTypeSupport::Editor));
     }
+
+    #[derive(Default)]
+    struct RecordingRouter {
+        routes: std::sync::Mutex<Vec<PostTypeRoutes>>,
+    }
+
+    impl PostTypeRouter for RecordingRouter {
+        fn register_routes(&self, routes: &PostTypeRoutes) {
+            self.routes.lock().unwrap().push(routes.clone());
+        }
+
+        fn remove_routes(&self, post_type: &str) {
+            self.routes
+                .lock()
+                .unwrap()
+                .retain(|r| r.post_type != post_type);
+        }
+    }
+
+    fn manager(router: Arc<RecordingRouter>) -> (PostTypeManager, Arc<RwLock<RoleManager>>) {
+        let roles = Arc::new(RwLock::new(RoleManager::new()));
+        let pool = sqlx::PgPool::connect_lazy("postgres://localhost/rustpress").unwrap();
+        let manager = PostTypeManager::new(pool, roles.clone()).with_router(router);
+        (manager, roles)
+    }
+
+    #[test]
+    fn test_post_type_capabilities() {
+        let caps = PostTypeCapabilities::for_type("book");
+        assert_eq!(caps.edit_post, "edit_book");
+        assert_eq!(caps.publish_posts, "publish_books");
+
+        // The post capability type yields the built-in post capabilities
+        let post = PostTypeCapabilities::for_type("post");
+        assert!(post.primitive().contains(&"edit_others_posts"));
+        assert!(!post.primitive().contains(&"edit_post"));
+
+        let definition = PostTypeDefinition {
+            post_type: PostType::new("book", "Books", "Book"),
+            capability_type: Some("tome".to_string()),
+        };
+        assert_eq!(definition.capabilities().edit_posts, "edit_tomes");
+    }
+
+    #[test]
+    fn test_post_type_routes() {
+        let mut pt = PostType::new("book", "Books", "Book");
+        pt.rewrite_slug = Some("library/".to_string());
+        let routes = PostTypeRoutes::for_post_type(&pt);
+        assert_eq!(routes.archive.as_deref(), Some("/library"));
+        assert_eq!(routes.single.as_deref(), Some("/library/:slug"));
+
+        pt.hierarchical = true;
+        pt.has_archive = false;
+        let routes = PostTypeRoutes::for_post_type(&pt);
+        assert_eq!(routes.archive, None);
+        assert_eq!(routes.single.as_deref(), Some("/library/*path"));
+
+        pt.public = false;
+        let routes = PostTypeRoutes::for_post_type(&pt);
+        assert_eq!((routes.archive, routes.single), (None, None));
+    }
+
+    #[tokio::test]
+    async fn test_grant_capabilities_mirrors_post_capabilities() {
+        let router = Arc::new(RecordingRouter::default());
+        let (manager, roles) = manager(router.clone());
+        let pt = PostType::new("book", "Books", "Book");
+        let caps = PostTypeCapabilities::for_type("book");
+
+        // Granting twice is idempotent
+        manager.grant_capabilities(&pt, &caps);
+        manager.grant_capabilities(&pt, &caps);
+        manager.announce_routes(&pt);
+
+        let roles = roles.read().unwrap();
+        assert_eq!(
+            roles.get_capability("publish_books").unwrap().label,
+            "Publish Books"
+        );
+        assert!(roles
+            .get_role("administrator")
+            .unwrap()
+            .has_cap("delete_others_books"));
+        assert!(roles
+            .get_role("editor")
+            .unwrap()
+            .has_cap("edit_others_books"));
+        let author = roles.get_role("author").unwrap();
+        assert!(author.has_cap("publish_books"));
+        assert!(!author.has_cap("edit_others_books"));
+        let contributor = roles.get_role("contributor").unwrap();
+        assert!(contributor.has_cap("edit_books"));
+        assert!(!contributor.has_cap("publish_books"));
+        assert!(!roles.get_role("subscriber").unwrap().has_cap("edit_books"));
+        drop(roles);
+
+        assert_eq!(router.routes.lock().unwrap().len(), 1);
+        manager.remove_capabilities(&caps);
+        assert!(manager
+            .roles
+            .read()
+            .unwrap()
+            .get_capability("edit_books")
+            .is_none());
+    }
 }
@@ -0,0 +1,433 @@
+//! # Draft Preview Links
+//!
+//! Shareable links that let someone without an account read unpublished
+//! content, stored in the `content_preview_links` table.
+//!
+//! Features:
+//! - Signed tokens: a link carries `{link id}.{signature}`, an HMAC-SHA256 over
+//!   the link id, content id and expiry, so the token itself is never stored
+//! - Scoped to one piece of content; a token presented for any other content
+//!   id is rejected
+//! - Live previews show the content as it is when the link is opened, pinned
+//!   previews show the revision current when the link was created
+//! - Expiry and revocation, reported as 410 Gone so a stale link can be told
+//!   apart from one that never existed (404)
+
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::{Content, ContentError, ContentResult};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Path prefix preview links are served under: `/preview/{content_id}`
+pub const PREVIEW_PATH_PREFIX: &str = "/preview/";
+
+/// Longest a preview link may stay valid
+pub const MAX_PREVIEW_EXPIRY_DAYS: i64 = 30;
+
+/// Why a preview link could not be opened
+#[derive(Debug, thiserror::Error)]
+pub enum PreviewError {
+    #[error("preview link not found")]
+    NotFound,
+
+    #[error("preview link has expired")]
+    Expired,
+
+    #[error("preview link has been revoked")]
+    Revoked,
+
+    #[error(transparent)]
+    Content(#[from] ContentError),
+}
+
+impl PreviewError {
+    /// HTTP status to answer with: 404 for links that don't exist (or don't
+    /// match the content), 410 for links that did but no longer work
+    pub fn status_code(&self) -> u16 {
+        match self {
+            PreviewError::NotFound | PreviewError::Content(ContentError::NotFound(_)) => 404,
+            PreviewError::Expired | PreviewError::Revoked => 410,
+            PreviewError::Content(_) => 500,
+        }
+    }
+}
+
+/// Options for [`crate::ContentService::create_preview_link_with`]
+#[derive(Debug, Clone, Default)]
+pub struct PreviewOptions {
+    /// Show the revision current when the link is created rather than the
+    /// content as it is when the link is opened
+    pub pin_revision: bool,
+
+    /// User sharing the link
+    pub created_by: Option<Uuid>,
+}
+
+impl PreviewOptions {
+    /// Preview pinned to the content's current revision
+    pub fn pinned() -> Self {
+        Self {
+            pin_revision: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// A shareable preview link
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreviewToken {
+    pub id: Uuid,
+    pub content_id: Uuid,
+
+    /// Token to put in the link; only available when the link is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+
+    /// Pinned revision, or `None` to show the content as it is when opened
+    pub revision: Option<i32>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl PreviewToken {
+    /// Link for sharing, e.g. `https://example.com/preview/{id}?token=...`
+    pub fn url(&self, base_url: &str) -> Option<String> {
+        let token = self.token.as_ref()?;
+        Some(format!(
+            "{}{}{}?token={}",
+            base_url.trim_end_matches('/'),
+            PREVIEW_PATH_PREFIX,
+            self.content_id,
+            token
+        ))
+    }
+
+    pub fn is_expired(&self) -> bool {
+        Utc::now() > self.expires_at
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+}
+
+/// Signs preview tokens with a shared secret
+#[derive(Clone)]
+struct PreviewSigner {
+    secret: Vec<u8>,
+}
+
+impl PreviewSigner {
+    fn new(secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            secret: secret.as_ref().to_vec(),
+        }
+    }
+
+    fn mac(&self, id: Uuid, content_id: Uuid, expires_at: DateTime<Utc>) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(b"preview\n");
+        mac.update(id.as_bytes());
+        mac.update(content_id.as_bytes());
+        mac.update(expires_at.timestamp().to_string().as_bytes());
+        mac
+    }
+
+    /// Token for a link: `{id}.{signature}`
+    fn token(&self, id: Uuid, content_id: Uuid, expires_at: DateTime<Utc>) -> String {
+        let signature = self.mac(id, content_id, expires_at).finalize().into_bytes();
+        format!("{}.{}", id.simple(), hex::encode(signature))
+    }
+
+    /// Check a token's signature against a stored link, in constant time
+    fn verify(&self, signature: &str, link: &PreviewToken) -> bool {
+        hex::decode(signature).is_ok_and(|signature| {
+            self.mac(link.id, link.content_id, link.expires_at)
+                .verify_slice(&signature)
+                .is_ok()
+        })
+    }
+}
+
+/// Split a token into its link id and signature
+fn parse_token(token: &str) -> Option<(Uuid, &str)> {
+    let (id, signature) = token.split_once('.')?;
+    Some((Uuid::parse_str(id).ok()?, signature))
+}
+
+/// Preview link manager backed by the `content_preview_links` table
+pub struct PreviewLinkManager {
+    pool: sqlx::PgPool,
+    signer: Option<PreviewSigner>,
+}
+
+impl PreviewLinkManager {
+    /// Create a manager; links can only be created and opened once a
+    /// signing secret is set with [`Self::with_secret`]
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool, signer: None }
+    }
+
+    /// Sign tokens with `secret`
+    pub fn with_secret(mut self, secret: impl AsRef<[u8]>) -> Self {
+        self.signer = Some(PreviewSigner::new(secret));
+        self
+    }
+
+    /// Create a link to `content` valid for `expiry`
+    pub async fn create(
+        &self,
+        content: &Content,
+        expiry: Duration,
+        options: PreviewOptions,
+    ) -> ContentResult<PreviewToken> {
+        let signer = self.signer.as_ref().ok_or_else(|| {
+            ContentError::Invalid("preview link signing secret is not configured".to_string())
+        })?;
+        if expiry <= Duration::zero() || expiry > Duration::days(MAX_PREVIEW_EXPIRY_DAYS) {
+            return Err(ContentError::Validation(format!(
+                "Preview link expiry must be between 1 second and {} days",
+                MAX_PREVIEW_EXPIRY_DAYS
+            )));
+        }
+
+        let now = Utc::now();
+        let mut link = PreviewToken {
+            id: Uuid::new_v4(),
+            content_id: content.id,
+            token: None,
+            revision: options.pin_revision.then_some(content.revision),
+            created_by: options.created_by,
+            created_at: now,
+            // Whole seconds, as that is what the signature covers
+            expires_at: (now + expiry).trunc_subsecs(0),
+            revoked_at: None,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO content_preview_links (
+                id, content_id, revision, created_by, created_at, expires_at
+            ) VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(link.id)
+        .bind(link.content_id)
+        .bind(link.revision)
+        .bind(link.created_by)
+        .bind(link.created_at)
+        .bind(link.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        link.token = Some(signer.token(link.id, link.content_id, link.expires_at));
+        Ok(link)
+    }
+
+    /// Check `token` for a preview of `content_id`
+    ///
+    /// Malformed, forged and unknown tokens, and tokens issued for other
+    /// content, are all [`PreviewError::NotFound`].
+    pub async fn verify(
+        &self,
+        content_id: Uuid,
+        token: &str,
+    ) -> Result<PreviewToken, PreviewError> {
+        let signer = self.signer.as_ref().ok_or(PreviewError::NotFound)?;
+        let (id, signature) = parse_token(token).ok_or(PreviewError::NotFound)?;
+        let link = self.get(id).await?.ok_or(PreviewError::NotFound)?;
+
+        if link.content_id != content_id || !signer.verify(signature, &link) {
+            return Err(PreviewError::NotFound);
+        }
+        if link.is_revoked() {
+            return Err(PreviewError::Revoked);
+        }
+        if link.is_expired() {
+            return Err(PreviewError::Expired);
+        }
+        Ok(link)
+    }
+
+    /// Get a link by id
+    pub async fn get(&self, id: Uuid) -> ContentResult<Option<PreviewToken>> {
+        let row = sqlx::query_as::<_, PreviewLinkRow>(
+            r#"
+            SELECT id, content_id, revision, created_by, created_at, expires_at, revoked_at
+            FROM content_preview_links WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(PreviewLinkRow::into_token))
+    }
+
+    /// Links created for a piece of content, newest first
+    pub async fn list(&self, content_id: Uuid) -> ContentResult<Vec<PreviewToken>> {
+        let rows = sqlx::query_as::<_, PreviewLinkRow>(
+            r#"
+            SELECT id, content_id, revision, created_by, created_at, expires_at, revoked_at
+            FROM content_preview_links WHERE content_id = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(content_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(PreviewLinkRow::into_token).collect())
+    }
+
+    /// Revoke a link; returns false if it doesn't exist or was already revoked
+    pub async fn revoke(&self, id: Uuid) -> ContentResult<bool> {
+        let result = sqlx::query(
+            "UPDATE content_preview_links SET revoked_at = NOW() WHERE id = $1 AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Revoke every live link to a piece of content
+    pub async fn revoke_all(&self, content_id: Uuid) -> ContentResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE content_preview_links SET revoked_at = NOW()
+            WHERE content_id = $1 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(content_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct PreviewLinkRow {
+    id: Uuid,
+    content_id: Uuid,
+    revision: Option<i32>,
+    created_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl PreviewLinkRow {
+    fn into_token(self) -> PreviewToken {
+        PreviewToken {
+            id: self.id,
+            content_id: self.content_id,
+            token: None,
+            revision: self.revision,
+            created_by: self.created_by,
+            created_at: self.created_at,
+            expires_at: self.expires_at,
+            revoked_at: self.revoked_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(content_id: Uuid, expires_in: Duration) -> PreviewToken {
+        let now = Utc::now();
+        PreviewToken {
+            id: Uuid::new_v4(),
+            content_id,
+            token: None,
+            revision: None,
+            created_by: None,
+            created_at: now,
+            expires_at: (now + expires_in).trunc_subsecs(0),
+            revoked_at: None,
+        }
+    }
+
+    fn signature(token: &str) -> &str {
+        parse_token(token).unwrap().1
+    }
+
+    #[test]
+    fn test_token_round_trip() {
+        let signer = PreviewSigner::new("secret");
+        let link = link(Uuid::new_v4(), Duration::hours(1));
+        let token = signer.token(link.id, link.content_id, link.expires_at);
+
+        assert_eq!(parse_token(&token).unwrap().0, link.id);
+        assert!(signer.verify(signature(&token), &link));
+        assert!(!PreviewSigner::new("other").verify(signature(&token), &link));
+    }
+
+    #[test]
+    fn test_token_is_scoped_to_content_and_expiry() {
+        let signer = PreviewSigner::new("secret");
+        let original = link(Uuid::new_v4(), Duration::hours(1));
+        let token = signer.token(original.id, original.content_id, original.expires_at);
+
+        let other_content = PreviewToken {
+            content_id: Uuid::new_v4(),
+            ..original.clone()
+        };
+        assert!(!signer.verify(signature(&token), &other_content));
+
+        let extended = PreviewToken {
+            expires_at: original.expires_at + Duration::days(1),
+            ..original
+        };
+        assert!(!signer.verify(signature(&token), &extended));
+    }
+
+    #[test]
+    fn test_malformed_tokens() {
+        assert!(parse_token("no-dot").is_none());
+        assert!(parse_token("not-a-uuid.abcd").is_none());
+
+        let signer = PreviewSigner::new("secret");
+        assert!(!signer.verify("zz", &link(Uuid::new_v4(), Duration::hours(1))));
+    }
+
+    #[test]
+    fn test_status_codes() {
+        assert_eq!(PreviewError::NotFound.status_code(), 404);
+        assert_eq!(
+            PreviewError::Content(ContentError::NotFound("x".to_string())).status_code(),
+            404
+        );
+        assert_eq!(PreviewError::Expired.status_code(), 410);
+        assert_eq!(PreviewError::Revoked.status_code(), 410);
+    }
+
+    #[test]
+    fn test_url_and_state() {
+        let mut link = link(Uuid::new_v4(), Duration::hours(-1));
+        assert!(link.url("https://example.com").is_none());
+        assert!(link.is_expired());
+        assert!(!link.is_revoked());
+
+        link.token = Some("abc.def".to_string());
+        assert_eq!(
+            link.url("https://example.com/").unwrap(),
+            format!(
+                "https://example.com/preview/{}?token=abc.def",
+                link.content_id
+            )
+        );
+    }
+}
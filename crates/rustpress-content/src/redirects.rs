@@ -0,0 +1,242 @@
+//! # Redirects
+//!
+//! Permanent and temporary redirects stored in the `redirects` table.
+//!
+//! Features:
+//! - Record redirects from old paths (e.g. a published post's previous slug)
+//! - Chain collapsing: recording B→C turns an existing A→B into A→C, and a
+//!   target that itself redirects is followed to its end, so visitors never
+//!   take more than one hop
+//! - Loop prevention: a redirect that would lead back to its own source is
+//!   rejected
+//! - Hit counting on lookup
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{ContentError, ContentResult};
+
+/// Longest chain followed when resolving a target; anything longer is
+/// treated as a loop already present in the table
+pub const MAX_REDIRECT_HOPS: usize = 20;
+
+/// A stored redirect
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Redirect {
+    pub source: String,
+    pub target: String,
+    /// HTTP status: 301, 302, 307 or 308
+    pub status: u16,
+    pub hit_count: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Redirect manager backed by the `redirects` table
+pub struct RedirectManager {
+    pool: sqlx::PgPool,
+}
+
+impl RedirectManager {
+    /// Create new redirect manager
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Public path of a piece of content, matching the `/{post_type}/{slug}`
+    /// routes served by the site
+    pub fn content_path(post_type: &str, slug: &str) -> String {
+        format!("/{}/{}", post_type, slug)
+    }
+
+    /// Record a redirect from `from` to `to`
+    ///
+    /// Replaces any existing redirect from `from`, repoints redirects that
+    /// currently end at `from`, and follows `to` if it redirects elsewhere.
+    /// Fails with [`ContentError::RedirectLoop`] if the result would send
+    /// `from` back to itself.
+    pub async fn record(&self, from: &str, to: &str, status: u16) -> ContentResult<Redirect> {
+        if !matches!(status, 301 | 302 | 307 | 308) {
+            return Err(ContentError::Validation(format!(
+                "Unsupported redirect status: {}",
+                status
+            )));
+        }
+        let from = normalize_path(from);
+        let to = normalize_path(to);
+
+        let mut tx = self.pool.begin().await?;
+
+        let chain: Vec<String> = sqlx::query_scalar(
+            r#"
+            WITH RECURSIVE chain (target_url, depth) AS (
+                SELECT target_url, 1 FROM redirects
+                WHERE source_url = $1 AND is_active AND deleted_at IS NULL
+                UNION ALL
+                SELECT r.target_url, c.depth + 1 FROM redirects r
+                JOIN chain c ON r.source_url = c.target_url
+                WHERE r.is_active AND r.deleted_at IS NULL AND c.depth < $2
+            )
+            SELECT target_url FROM chain ORDER BY depth
+            "#,
+        )
+        .bind(&to)
+        .bind(MAX_REDIRECT_HOPS as i32)
+        .fetch_all(&mut *tx)
+        .await?;
+        let target = resolve_target(&from, &to, &chain)?;
+
+        // Anything that used to land on `from` now goes straight to the end
+        sqlx::query(
+            r#"
+            UPDATE redirects SET target_url = $2, updated_at = NOW()
+            WHERE target_url = $1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(&from)
+        .bind(&target)
+        .execute(&mut *tx)
+        .await?;
+
+        let row = sqlx::query_as::<_, RedirectRow>(
+            r#"
+            INSERT INTO redirects (source_url, source_url_hash, target_url, redirect_type)
+            VALUES ($1, md5($1), $2, $3)
+            ON CONFLICT (source_url) WHERE deleted_at IS NULL DO UPDATE SET
+                target_url = EXCLUDED.target_url,
+                redirect_type = EXCLUDED.redirect_type,
+                is_active = true,
+                updated_at = NOW()
+            RETURNING source_url, target_url, redirect_type, hit_count, updated_at
+            "#,
+        )
+        .bind(&from)
+        .bind(&target)
+        .bind(status.to_string())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        row.into_redirect()
+    }
+
+    /// Active redirect for `path`, counting the hit
+    pub async fn lookup(&self, path: &str) -> ContentResult<Option<Redirect>> {
+        let row = sqlx::query_as::<_, RedirectRow>(
+            r#"
+            UPDATE redirects SET hit_count = hit_count + 1, last_hit_at = NOW()
+            WHERE source_url = $1 AND is_active AND deleted_at IS NULL
+            RETURNING source_url, target_url, redirect_type, hit_count, updated_at
+            "#,
+        )
+        .bind(normalize_path(path))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(RedirectRow::into_redirect).transpose()
+    }
+
+    /// Remove the redirect from `source`, returning whether one existed
+    pub async fn remove(&self, source: &str) -> ContentResult<bool> {
+        let result = sqlx::query(
+            "UPDATE redirects SET deleted_at = NOW() WHERE source_url = $1 AND deleted_at IS NULL",
+        )
+        .bind(normalize_path(source))
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Normalize a redirect path: leading slash, no trailing slash.
+/// Absolute URLs are kept as given.
+pub fn normalize_path(path: &str) -> String {
+    let path = path.trim();
+    if path.starts_with("http://") || path.starts_with("https://") {
+        return path.to_string();
+    }
+    let trimmed = path.trim_matches('/');
+    format!("/{}", trimmed)
+}
+
+/// Final target for a new `from` → `to` redirect, given the chain of
+/// targets `to` currently redirects through
+fn resolve_target(from: &str, to: &str, chain: &[String]) -> ContentResult<String> {
+    if from == to || chain.iter().any(|hop| hop == from) {
+        return Err(ContentError::RedirectLoop(format!(
+            "{} would redirect back to itself",
+            from
+        )));
+    }
+    if chain.len() >= MAX_REDIRECT_HOPS {
+        return Err(ContentError::RedirectLoop(format!(
+            "{} redirects more than {} times",
+            to, MAX_REDIRECT_HOPS
+        )));
+    }
+    Ok(chain.last().cloned().unwrap_or_else(|| to.to_string()))
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct RedirectRow {
+    source_url: String,
+    target_url: String,
+    redirect_type: String,
+    hit_count: i64,
+    updated_at: DateTime<Utc>,
+}
+
+impl RedirectRow {
+    fn into_redirect(self) -> ContentResult<Redirect> {
+        let status = self.redirect_type.parse().map_err(|_| {
+            ContentError::Invalid(format!("Invalid redirect type: {}", self.redirect_type))
+        })?;
+        Ok(Redirect {
+            source: self.source_url,
+            target: self.target_url,
+            status,
+            hit_count: self.hit_count,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("post/hello/"), "/post/hello");
+        assert_eq!(normalize_path(" /post/hello "), "/post/hello");
+        assert_eq!(normalize_path("/"), "/");
+        assert_eq!(
+            normalize_path("https://example.com/a/"),
+            "https://example.com/a/"
+        );
+    }
+
+    #[test]
+    fn test_resolve_target_collapses_chains_and_rejects_loops() {
+        // B -> C already exists, so A -> B resolves to A -> C
+        let chain = vec!["/c".to_string()];
+        assert_eq!(resolve_target("/a", "/b", &chain).unwrap(), "/c");
+        assert_eq!(resolve_target("/a", "/b", &[]).unwrap(), "/b");
+
+        assert!(matches!(
+            resolve_target("/a", "/a", &[]),
+            Err(ContentError::RedirectLoop(_))
+        ));
+        // B -> C -> A: adding A -> B would close the loop
+        let chain = vec!["/c".to_string(), "/a".to_string()];
+        assert!(matches!(
+            resolve_target("/a", "/b", &chain),
+            Err(ContentError::RedirectLoop(_))
+        ));
+        // A cycle elsewhere in the table shows up as an over-long chain
+        let chain = vec!["/x".to_string(); MAX_REDIRECT_HOPS];
+        assert!(matches!(
+            resolve_target("/a", "/b", &chain),
+            Err(ContentError::RedirectLoop(_))
+        ));
+    }
+}
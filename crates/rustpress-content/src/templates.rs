@@ -1,12 +1,57 @@
 //! Content templates
 //!
 //! Provides reusable content templates for quick content creation.
+//!
+//! Templates may contain `{{variable}}` placeholders (or `{{variable|default}}`
+//! for optional ones) in their body and block attributes, which are filled in
+//! when a template is instantiated into new content.
 
 use chrono::{DateTime, Utc};
+use regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
 use uuid::Uuid;
 
-use crate::{Block, ContentError, ContentFormat, ContentResult};
+use crate::sanitize::escape_html;
+use crate::{Block, Content, ContentError, ContentFormat, ContentResult};
+
+/// Matches `{{name}}` and `{{name|default}}` placeholders
+fn placeholder_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*(?:\|([^}]*))?\}\}").unwrap())
+}
+
+/// A placeholder variable used by a template
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateVar {
+    /// Variable name, as written between the braces
+    pub name: String,
+
+    /// Prompt shown when asking for a value
+    pub label: String,
+
+    /// Optional help text
+    pub description: Option<String>,
+
+    /// Value used when none is supplied
+    pub default: Option<String>,
+
+    /// Must a value be supplied when instantiating
+    pub required: bool,
+}
+
+impl TemplateVar {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            label: name.replace('_', " "),
+            description: None,
+            default: None,
+            required: true,
+        }
+    }
+}
 
 /// Content template
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,6 +109,108 @@ impl ContentTemplate {
         }
     }
 
+    /// Placeholder variables used in the body and blocks, in order of first
+    /// appearance.
+    ///
+    /// Labels and descriptions can be declared under `meta.variables.<name>`;
+    /// a variable is optional when any of its placeholders gives a default or
+    /// the declaration sets `"required": false`.
+    pub fn variables(&self) -> Vec<TemplateVar> {
+        let mut vars: Vec<TemplateVar> = Vec::new();
+        let mut collect = |text: &str| {
+            for caps in placeholder_regex().captures_iter(text) {
+                let name = &caps[1];
+                let index = match vars.iter().position(|v| v.name == name) {
+                    Some(index) => index,
+                    None => {
+                        vars.push(TemplateVar::new(name));
+                        vars.len() - 1
+                    }
+                };
+                if let Some(default) = caps.get(2) {
+                    let var = &mut vars[index];
+                    var.required = false;
+                    var.default
+                        .get_or_insert_with(|| default.as_str().trim().to_string());
+                }
+            }
+        };
+
+        collect(&self.content);
+        for block in &self.blocks {
+            visit_block_text(block, &mut collect);
+        }
+
+        for var in &mut vars {
+            let Some(declared) = self.meta.get("variables").and_then(|v| v.get(&var.name)) else {
+                continue;
+            };
+            if let Some(label) = declared.get("label").and_then(|v| v.as_str()) {
+                var.label = label.to_string();
+            }
+            if let Some(description) = declared.get("description").and_then(|v| v.as_str()) {
+                var.description = Some(description.to_string());
+            }
+            if let Some(default) = declared.get("default").and_then(|v| v.as_str()) {
+                var.default = Some(default.to_string());
+                var.required = false;
+            }
+            if let Some(required) = declared.get("required").and_then(|v| v.as_bool()) {
+                var.required = required;
+            }
+        }
+
+        vars
+    }
+
+    /// Create new content from this template, substituting `values` for its
+    /// placeholders.
+    ///
+    /// Every required variable must have a non-empty value. Values are
+    /// inserted as text: they are HTML-escaped in the body and in block
+    /// markup and attributes, so they can never introduce markup of their own.
+    pub fn instantiate(&self, values: &HashMap<String, String>) -> ContentResult<Content> {
+        let vars = self.variables();
+        let missing: Vec<&str> = vars
+            .iter()
+            .filter(|v| v.required && v.default.is_none())
+            .filter(|v| match values.get(&v.name) {
+                Some(value) => value.trim().is_empty(),
+                None => true,
+            })
+            .map(|v| v.name.as_str())
+            .collect();
+        if !missing.is_empty() {
+            return Err(ContentError::Validation(format!(
+                "Missing required template variables: {}",
+                missing.join(", ")
+            )));
+        }
+
+        let resolved: HashMap<&str, String> = vars
+            .iter()
+            .filter_map(|v| {
+                let value = values
+                    .get(&v.name)
+                    .filter(|value| !value.trim().is_empty())
+                    .or(v.default.as_ref())?;
+                Some((v.name.as_str(), escape_html(value)))
+            })
+            .collect();
+
+        let mut content = Content::new(&self.post_type);
+        content.content = substitute(&self.content, &resolved);
+        content.blocks = self
+            .blocks
+            .iter()
+            .map(|block| substitute_block(block, &resolved))
+            .collect();
+        content.format = self.format.clone();
+        content.meta = serde_json::json!({ "content_template": self.id });
+
+        Ok(content)
+    }
+
     /// Create blank template
     pub fn blank() -> Self {
         Self::new("Blank", "post")
@@ -407,6 +554,16 @@ impl TemplateService {
         Ok(())
     }
 
+    /// Create content from a stored template, see
+    /// [`ContentTemplate::instantiate`]
+    pub async fn instantiate(
+        &self,
+        template_id: Uuid,
+        values: &HashMap<String, String>,
+    ) -> ContentResult<Content> {
+        self.get(template_id).await?.instantiate(values)
+    }
+
     /// Duplicate template
     pub async fn duplicate(&self, id: Uuid, new_name: &str) -> ContentResult<ContentTemplate> {
         let original = self.get(id).await?;
@@ -429,6 +586,77 @@ impl TemplateService {
     }
 }
 
+/// Replace placeholders with already-escaped values; placeholders without a
+/// value are left untouched
+fn substitute(text: &str, values: &HashMap<&str, String>) -> String {
+    placeholder_regex()
+        .replace_all(text, |caps: &Captures| match values.get(&caps[1]) {
+            Some(value) => value.clone(),
+            None => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// Substitute placeholders in a block's markup, string attributes and inner
+/// blocks, giving the copy fresh IDs
+fn substitute_block(block: &Block, values: &HashMap<&str, String>) -> Block {
+    Block {
+        id: Uuid::new_v4().to_string(),
+        block_type: block.block_type.clone(),
+        attributes: substitute_value(&block.attributes, values),
+        inner_blocks: block
+            .inner_blocks
+            .iter()
+            .map(|inner| substitute_block(inner, values))
+            .collect(),
+        inner_html: substitute(&block.inner_html, values),
+        inner_content: block
+            .inner_content
+            .iter()
+            .map(|part| part.as_ref().map(|part| substitute(part, values)))
+            .collect(),
+    }
+}
+
+fn substitute_value(
+    value: &serde_json::Value,
+    values: &HashMap<&str, String>,
+) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(s) => serde_json::Value::String(substitute(s, values)),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(|item| substitute_value(item, values))
+            .collect(),
+        serde_json::Value::Object(map) => map
+            .iter()
+            .map(|(key, item)| (key.clone(), substitute_value(item, values)))
+            .collect(),
+        other => other.clone(),
+    }
+}
+
+/// Call `f` with every piece of text in a block that may hold placeholders
+fn visit_block_text(block: &Block, f: &mut impl FnMut(&str)) {
+    fn visit_value(value: &serde_json::Value, f: &mut impl FnMut(&str)) {
+        match value {
+            serde_json::Value::String(s) => f(s),
+            serde_json::Value::Array(items) => items.iter().for_each(|item| visit_value(item, f)),
+            serde_json::Value::Object(map) => map.values().for_each(|item| visit_value(item, f)),
+            _ => {}
+        }
+    }
+
+    visit_value(&block.attributes, f);
+    f(&block.inner_html);
+    for part in block.inner_content.iter().flatten() {
+        f(part);
+    }
+    for inner in &block.inner_blocks {
+        visit_block_text(inner, f);
+    }
+}
+
 /// Database row
 #[derive(Debug, sqlx::FromRow)]
 struct TemplateRow {
@@ -490,4 +718,89 @@ mod tests {
         assert_eq!(template.name, "Landing Page");
         assert_eq!(template.post_type, "page");
     }
+
+    fn event_template() -> ContentTemplate {
+        let mut template = ContentTemplate::new("Event", "post");
+        template.content = "Join us on {{ event_date }} at {{location}}.".to_string();
+        template.blocks = vec![
+            Block::heading("{{event_name}}", 2),
+            Block::columns(vec![Block::column(vec![Block::button(
+                "RSVP by {{rsvp_by|the day before}}",
+                "https://example.com/rsvp?event={{event_name}}",
+                None,
+            )])]),
+        ];
+        template.meta = serde_json::json!({
+            "variables": { "location": { "label": "Venue", "description": "Where it happens" } }
+        });
+        template
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_template_variables() {
+        let vars = event_template().variables();
+        let names: Vec<&str> = vars.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, ["event_date", "location", "event_name", "rsvp_by"]);
+
+        assert!(vars[0].required);
+        assert_eq!(vars[1].label, "Venue");
+        assert_eq!(vars[1].description.as_deref(), Some("Where it happens"));
+        assert!(!vars[3].required);
+        assert_eq!(vars[3].default.as_deref(), Some("the day before"));
+
+        assert!(ContentTemplate::blog_post().variables().is_empty());
+    }
+
+    #[test]
+    fn test_instantiate_requires_variables() {
+        let err = event_template()
+            .instantiate(&values(&[("event_date", "May 1"), ("location", "  ")]))
+            .unwrap_err();
+        match err {
+            ContentError::Validation(msg) => {
+                assert!(msg.contains("location") && msg.contains("event_name"));
+                assert!(!msg.contains("rsvp_by"));
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_instantiate_substitutes_blocks_and_escapes() {
+        let template = event_template();
+        let content = template
+            .instantiate(&values(&[
+                ("event_date", "May 1"),
+                ("location", "<script>alert(1)</script>"),
+                ("event_name", "Launch & Learn"),
+            ]))
+            .unwrap();
+
+        assert_eq!(
+            content.content,
+            "Join us on May 1 at &lt;script&gt;alert(1)&lt;/script&gt;."
+        );
+        assert_eq!(
+            content.meta["content_template"],
+            serde_json::json!(template.id)
+        );
+
+        let heading = &content.blocks[0];
+        assert_eq!(heading.attributes["content"], "Launch &amp; Learn");
+        assert_eq!(heading.inner_html, "<h2>Launch &amp; Learn</h2>");
+        assert_ne!(heading.id, template.blocks[0].id);
+
+        let button = &content.blocks[1].inner_blocks[0].inner_blocks[0];
+        let attributes = button.attributes.to_string();
+        assert!(attributes.contains("RSVP by the day before"));
+        assert!(attributes.contains("event=Launch &amp; Learn"));
+        assert!(!attributes.contains("{{"));
+    }
 }
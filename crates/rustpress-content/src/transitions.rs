@@ -0,0 +1,348 @@
+//! # Status Transitions
+//!
+//! Bulk status changes for the editor screens.
+//!
+//! Features:
+//! - Each item is checked and saved on its own, so one failing item never
+//!   holds back the rest
+//! - Publishing and scheduling require content that passes validation
+//! - Trashing goes through [`Content::trash`]; nothing is deleted
+//! - Scheduling creates or moves the pending publish job, and leaving the
+//!   scheduled status cancels it
+//! - Every change raises the matching `post.*` events so caches, redirects
+//!   and announcements can react
+
+use chrono::{DateTime, Utc};
+use rustpress_events::event::events;
+use rustpress_events::{DomainEvent, PostPublished};
+use uuid::Uuid;
+
+use crate::{Content, ContentError, ContentResult, ContentService, ContentStatus};
+
+impl ContentService {
+    /// Move each of `ids` to `new_status`, returning one result per id in
+    /// the same order.
+    ///
+    /// Items already in `new_status` succeed without changes. Scheduling
+    /// through this method reuses each item's existing `scheduled_at`; use
+    /// [`bulk_schedule`](Self::bulk_schedule) to set a new time.
+    pub async fn bulk_update_status(
+        &self,
+        ids: &[Uuid],
+        new_status: ContentStatus,
+    ) -> Vec<ContentResult<Uuid>> {
+        let mut results = Vec::with_capacity(ids.len());
+        for &id in ids {
+            results.push(self.transition_status(id, &new_status, None).await);
+        }
+        results
+    }
+
+    /// Schedule each of `ids` for publication at `publish_at`, moving any
+    /// pending schedule to the new time
+    pub async fn bulk_schedule(
+        &self,
+        ids: &[Uuid],
+        publish_at: DateTime<Utc>,
+    ) -> Vec<ContentResult<Uuid>> {
+        let mut results = Vec::with_capacity(ids.len());
+        for &id in ids {
+            results.push(
+                self.transition_status(id, &ContentStatus::Scheduled, Some(publish_at))
+                    .await,
+            );
+        }
+        results
+    }
+
+    async fn transition_status(
+        &self,
+        id: Uuid,
+        new_status: &ContentStatus,
+        publish_at: Option<DateTime<Utc>>,
+    ) -> ContentResult<Uuid> {
+        let before = self.get(id).await?;
+        let Some(after) = plan_status_change(&before, new_status, publish_at, Utc::now())? else {
+            return Ok(id);
+        };
+
+        // Settle the publish queue first: a pending job left behind would
+        // publish the item later regardless of its new status
+        let pending = self.scheduler.get_content_schedule(id).await?;
+        match (after.scheduled_at.filter(|_| after.is_scheduled()), pending) {
+            (Some(at), Some(job)) if job.scheduled_at != at => {
+                self.scheduler.reschedule(job.id, at).await?;
+            }
+            (Some(_), Some(_)) => {}
+            (Some(at), None) => {
+                self.scheduler.schedule(id, at).await?;
+            }
+            (None, Some(job)) => self.scheduler.cancel(job.id).await?,
+            (None, None) => {}
+        }
+
+        let result = sqlx::query(
+            r#"
+            UPDATE contents
+            SET status = $2, published_at = $3, scheduled_at = $4, updated_at = $5
+            WHERE id = $1 AND updated_at = $6
+            "#,
+        )
+        .bind(id)
+        .bind(serde_json::to_string(&after.status)?)
+        .bind(after.published_at)
+        .bind(after.scheduled_at)
+        .bind(after.updated_at)
+        .bind(before.updated_at)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(ContentError::VersionConflict(format!(
+                "{} was changed while its status was being updated",
+                id
+            )));
+        }
+
+        if let Some(event_bus) = &self.event_bus {
+            for event in status_events(&before, &after) {
+                let event_type = event.event_type.clone();
+                if let Err(e) = event_bus.publish(event).await {
+                    tracing::warn!(
+                        content_id = %id,
+                        event = %event_type,
+                        error = %e,
+                        "Failed to publish content status event"
+                    );
+                }
+            }
+        }
+
+        Ok(id)
+    }
+}
+
+/// Content as it should be after moving `before` to `new_status`, or
+/// `None` if nothing changes.
+///
+/// Scheduling uses `publish_at`, falling back to the item's current
+/// `scheduled_at`, and fails if neither is in the future.
+pub(crate) fn plan_status_change(
+    before: &Content,
+    new_status: &ContentStatus,
+    publish_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> ContentResult<Option<Content>> {
+    let mut after = before.clone();
+
+    match new_status {
+        ContentStatus::Published | ContentStatus::Scheduled
+            if before.status == ContentStatus::Trash =>
+        {
+            return Err(ContentError::Validation(
+                "Restore trashed content before publishing it".to_string(),
+            ));
+        }
+        ContentStatus::Published => {
+            if before.is_published() {
+                return Ok(None);
+            }
+            ContentService::validate(before)?;
+            after.status = ContentStatus::Published;
+            // Republishing keeps the original publication date
+            after.published_at = before.published_at.or(Some(now));
+            after.scheduled_at = None;
+        }
+        ContentStatus::Scheduled => {
+            let at = publish_at.or(before.scheduled_at).ok_or_else(|| {
+                ContentError::Scheduler(format!("No publication time set for {}", before.id))
+            })?;
+            if at <= now {
+                return Err(ContentError::Scheduler(
+                    "Scheduled time must be in the future".to_string(),
+                ));
+            }
+            if before.is_scheduled() && before.scheduled_at == Some(at) {
+                return Ok(None);
+            }
+            ContentService::validate(before)?;
+            after.schedule(at);
+        }
+        ContentStatus::Trash => {
+            if before.status == ContentStatus::Trash {
+                return Ok(None);
+            }
+            after.trash();
+        }
+        status => {
+            if &before.status == status {
+                return Ok(None);
+            }
+            after.status = status.clone();
+            after.scheduled_at = None;
+        }
+    }
+
+    after.updated_at = now;
+    Ok(Some(after))
+}
+
+/// Events raised by a status change from `before` to `after`
+pub(crate) fn status_events(before: &Content, after: &Content) -> Vec<DomainEvent> {
+    let old_status = status_name(&before.status);
+    let new_status = status_name(&after.status);
+    let payload = serde_json::json!({
+        "post_id": after.id,
+        "author_id": after.author_id,
+        "post_type": after.post_type,
+        "title": after.title,
+        "slug": after.slug,
+        "old_status": old_status,
+        "new_status": new_status,
+    });
+    let event = |event_type: &str| {
+        DomainEvent::new(event_type, payload.clone()).with_aggregate(after.id, "post")
+    };
+
+    let mut raised = Vec::new();
+    if after.is_published() && !before.is_published() {
+        raised.push(
+            PostPublished {
+                post_id: after.id,
+                author_id: after.author_id,
+                post_type: after.post_type.clone(),
+                title: after.title.clone(),
+                slug: after.slug.clone(),
+                excerpt: after.excerpt.clone(),
+                previous_status: Some(old_status.clone()),
+            }
+            .into_event(),
+        );
+    }
+    if before.is_published() && !after.is_published() {
+        raised.push(event(events::POST_UNPUBLISHED));
+    }
+    if after.status == ContentStatus::Trash {
+        raised.push(event(events::POST_TRASHED));
+    }
+    if before.status == ContentStatus::Trash {
+        raised.push(event(events::POST_RESTORED));
+    }
+    raised.push(event(events::POST_UPDATED));
+    raised
+}
+
+/// Status as stored, e.g. `pending_review`
+fn status_name(status: &ContentStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn post(status: ContentStatus) -> Content {
+        Content::new("post")
+            .with_title("Hello World")
+            .with_status(status)
+    }
+
+    fn event_types(before: &Content, after: &Content) -> Vec<String> {
+        status_events(before, after)
+            .into_iter()
+            .map(|e| e.event_type)
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_status_change() {
+        let now = Utc::now();
+
+        // Publishing validates and keeps an earlier publication date
+        let mut draft = post(ContentStatus::Draft);
+        draft.published_at = Some(now - Duration::days(3));
+        let published = plan_status_change(&draft, &ContentStatus::Published, None, now)
+            .unwrap()
+            .unwrap();
+        assert_eq!(published.status, ContentStatus::Published);
+        assert_eq!(published.published_at, draft.published_at);
+
+        let untitled = post(ContentStatus::Draft).with_slug("");
+        assert!(matches!(
+            plan_status_change(&untitled, &ContentStatus::Published, None, now),
+            Err(ContentError::Validation(_))
+        ));
+
+        // Scheduling needs a future time, from the request or the item
+        let at = now + Duration::hours(2);
+        let scheduled = plan_status_change(&draft, &ContentStatus::Scheduled, Some(at), now)
+            .unwrap()
+            .unwrap();
+        assert_eq!(scheduled.scheduled_at, Some(at));
+        assert!(scheduled.is_scheduled());
+        assert!(
+            plan_status_change(&scheduled, &ContentStatus::Scheduled, None, now)
+                .unwrap()
+                .is_none()
+        );
+        assert!(matches!(
+            plan_status_change(&draft, &ContentStatus::Scheduled, None, now),
+            Err(ContentError::Scheduler(_))
+        ));
+        assert!(matches!(
+            plan_status_change(&draft, &ContentStatus::Scheduled, Some(now), now),
+            Err(ContentError::Scheduler(_))
+        ));
+
+        // Unscheduling clears the time; trashing keeps the item
+        let back = plan_status_change(&scheduled, &ContentStatus::Draft, None, now)
+            .unwrap()
+            .unwrap();
+        assert_eq!(back.scheduled_at, None);
+        let trashed = plan_status_change(&published, &ContentStatus::Trash, None, now)
+            .unwrap()
+            .unwrap();
+        assert_eq!(trashed.status, ContentStatus::Trash);
+        assert!(matches!(
+            plan_status_change(&trashed, &ContentStatus::Published, None, now),
+            Err(ContentError::Validation(_))
+        ));
+        assert!(
+            plan_status_change(&trashed, &ContentStatus::Draft, None, now)
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_status_events() {
+        let draft = post(ContentStatus::Draft);
+        let published = post(ContentStatus::Published);
+        let trashed = post(ContentStatus::Trash);
+
+        assert_eq!(
+            event_types(&draft, &published),
+            vec![events::POST_PUBLISHED, events::POST_UPDATED]
+        );
+        assert_eq!(
+            event_types(&published, &trashed),
+            vec![
+                events::POST_UNPUBLISHED,
+                events::POST_TRASHED,
+                events::POST_UPDATED
+            ]
+        );
+        assert_eq!(
+            event_types(&trashed, &draft),
+            vec![events::POST_RESTORED, events::POST_UPDATED]
+        );
+
+        let first = PostPublished::from_event(&status_events(&draft, &published)[0]).unwrap();
+        assert!(first.is_first_publication());
+        let update = &status_events(&draft, &post(ContentStatus::PendingReview))[0];
+        assert_eq!(update.payload["new_status"], "pending_review");
+    }
+}
@@ -1,13 +1,20 @@
 //! Content versioning and revision history
 //!
 //! Provides revision tracking, diff generation, and content history management.
+//! Revisions are compared line by line and block by block; blocks are paired
+//! across revisions by ID, so reordering is reported as a move rather than as
+//! a removal and an addition.
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use similar::{ChangeTag, TextDiff};
+use similar::{Algorithm, ChangeTag, DiffOp, TextDiff};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
-use crate::{Content, ContentError, ContentResult};
+use crate::sanitize::{strip_tags, unescape_html};
+use crate::{
+    Block, Content, ContentError, ContentResult, ContentService, ContentStatus, TaxonomyService,
+};
 
 /// Content revision
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +45,14 @@ pub struct Revision {
 
     /// Summary of changes
     pub change_summary: Option<String>,
+
+    /// Status at this revision (None for revisions recorded before status snapshots)
+    #[serde(default)]
+    pub status: Option<ContentStatus>,
+
+    /// Term IDs at this revision (None for revisions recorded before term snapshots)
+    #[serde(default)]
+    pub terms: Option<Vec<Uuid>>,
 }
 
 impl Revision {
@@ -53,6 +68,8 @@ impl Revision {
             author_id: Uuid::nil(),
             created_at: Utc::now(),
             change_summary: None,
+            status: None,
+            terms: None,
         }
     }
 
@@ -68,6 +85,8 @@ impl Revision {
             author_id: content.author_id,
             created_at: Utc::now(),
             change_summary: None,
+            status: Some(content.status.clone()),
+            terms: None,
         }
     }
 
@@ -88,6 +107,16 @@ impl Revision {
         self.author_id = author_id;
         self
     }
+
+    /// Parse the stored blocks JSON.
+    ///
+    /// Accepts the current block schema as well as older serialisations:
+    /// Gutenberg parser keys (`blockName`, `attrs`, `clientId`), a list
+    /// wrapped as `{"version": n, "blocks": [...]}`, or a JSON-encoded string.
+    /// Blocks without an ID are given one derived from their position.
+    pub fn parsed_blocks(&self) -> Vec<Block> {
+        revision_blocks(&self.blocks)
+    }
 }
 
 /// Diff between two revisions
@@ -108,10 +137,63 @@ pub struct RevisionDiff {
     /// Content changes
     pub content_changes: Vec<DiffChange>,
 
+    /// Block changes, paired by block ID
+    #[serde(default)]
+    pub block_changes: Vec<BlockChange>,
+
     /// Statistics
     pub stats: DiffStats,
 }
 
+/// Change to a single block between two revisions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockChange {
+    /// Block ID
+    pub block_id: String,
+
+    /// Block type in the newer revision (or the older one if removed)
+    pub block_type: String,
+
+    /// Kind of change
+    pub kind: BlockChangeKind,
+
+    /// Position in the old revision (depth-first, nested blocks included)
+    pub old_position: Option<usize>,
+
+    /// Position in the new revision (depth-first, nested blocks included)
+    pub new_position: Option<usize>,
+
+    /// Whether the block changed order relative to the other paired blocks
+    pub moved: bool,
+
+    /// Whether the block attributes changed
+    pub attributes_changed: bool,
+
+    /// Word-level diff of the block text (modified blocks only)
+    pub text_changes: Vec<BlockTextChange>,
+}
+
+/// Kind of block change
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockChangeKind {
+    Added,
+    Removed,
+    Modified,
+    /// Reordered without changes to the block itself
+    Moved,
+}
+
+/// Run of text within a modified block
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlockTextChange {
+    /// Change type
+    pub change_type: ChangeType,
+
+    /// The text of this run
+    pub text: String,
+}
+
 /// Individual change in a diff
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiffChange {
@@ -151,6 +233,34 @@ pub struct DiffStats {
 
     /// Total changes
     pub total_changes: usize,
+
+    /// Blocks added
+    #[serde(default)]
+    pub blocks_added: usize,
+
+    /// Blocks removed
+    #[serde(default)]
+    pub blocks_removed: usize,
+
+    /// Blocks modified
+    #[serde(default)]
+    pub blocks_modified: usize,
+
+    /// Blocks reordered without other changes
+    #[serde(default)]
+    pub blocks_moved: usize,
+}
+
+/// What to bring back when restoring a revision
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct RestoreOptions {
+    /// Also restore the status recorded with the revision
+    #[serde(default)]
+    pub status: bool,
+
+    /// Also restore the terms recorded with the revision
+    #[serde(default)]
+    pub terms: bool,
 }
 
 /// Versioning service
@@ -164,16 +274,23 @@ impl VersioningService {
         Self { pool }
     }
 
-    /// Create a new revision for content
+    /// Create a new revision for content.
+    ///
+    /// The content's current term assignments are snapshotted alongside it.
     pub async fn create_revision(&self, content: &Content) -> ContentResult<Revision> {
-        let revision = Revision::from_content(content);
+        let mut revision = Revision::from_content(content);
 
-        sqlx::query(
+        let terms: serde_json::Value = sqlx::query_scalar(
             r#"
             INSERT INTO content_revisions (
                 id, content_id, revision, title, content, blocks,
-                author_id, created_at, change_summary
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                author_id, created_at, change_summary, status, terms
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
+                (SELECT COALESCE(jsonb_agg(term_id), '[]'::jsonb)
+                 FROM content_terms WHERE content_id = $2)
+            )
+            RETURNING terms
             "#,
         )
         .bind(revision.id)
@@ -185,9 +302,11 @@ impl VersioningService {
         .bind(revision.author_id)
         .bind(revision.created_at)
         .bind(&revision.change_summary)
-        .execute(&self.pool)
+        .bind(serde_json::to_string(&content.status)?)
+        .fetch_one(&self.pool)
         .await?;
 
+        revision.terms = Some(serde_json::from_value(terms)?);
         Ok(revision)
     }
 
@@ -237,8 +356,18 @@ impl VersioningService {
         from_revision: i32,
         to_revision: i32,
     ) -> ContentResult<RevisionDiff> {
-        let from = self.get_revision(content_id, from_revision).await?;
-        let to = self.get_revision(content_id, to_revision).await?;
+        self.diff(content_id, from_revision, to_revision).await
+    }
+
+    /// Diff two revisions of a content item, line by line and block by block
+    pub async fn diff(
+        &self,
+        content_id: Uuid,
+        rev_a: i32,
+        rev_b: i32,
+    ) -> ContentResult<RevisionDiff> {
+        let from = self.get_revision(content_id, rev_a).await?;
+        let to = self.get_revision(content_id, rev_b).await?;
 
         Ok(Self::generate_diff(&from, &to))
     }
@@ -292,6 +421,9 @@ impl VersioningService {
             });
         }
 
+        let block_changes = diff_blocks(&from.parsed_blocks(), &to.parsed_blocks());
+        let count = |kind| block_changes.iter().filter(|c| c.kind == kind).count();
+
         RevisionDiff {
             from_revision: from.revision,
             to_revision: to.revision,
@@ -303,74 +435,83 @@ impl VersioningService {
                 deletions,
                 unchanged,
                 total_changes: additions + deletions,
+                blocks_added: count(BlockChangeKind::Added),
+                blocks_removed: count(BlockChangeKind::Removed),
+                blocks_modified: count(BlockChangeKind::Modified),
+                blocks_moved: count(BlockChangeKind::Moved),
             },
+            block_changes,
         }
     }
 
-    /// Restore content to a specific revision
+    /// Restore content to a specific revision, returning the new revision
     pub async fn restore_revision(
         &self,
         content_id: Uuid,
         revision: i32,
     ) -> ContentResult<Revision> {
-        let revision_data = self.get_revision(content_id, revision).await?;
+        let content = self.restore(content_id, revision).await?;
+        self.get_revision(content_id, content.revision).await
+    }
 
-        // Get current content to determine new revision number
-        let current = self.get_latest_revision(content_id).await?;
-        let new_revision = current.revision + 1;
+    /// Restore the title and body of a revision.
+    ///
+    /// See [`restore_with`](Self::restore_with).
+    pub async fn restore(&self, content_id: Uuid, revision: i32) -> ContentResult<Content> {
+        self.restore_with(content_id, revision, RestoreOptions::default())
+            .await
+    }
 
-        // Create new revision with restored content
-        let restored = Revision {
-            id: Uuid::new_v4(),
-            content_id,
-            revision: new_revision,
-            title: revision_data.title,
-            content: revision_data.content,
-            blocks: revision_data.blocks,
-            author_id: revision_data.author_id,
-            created_at: Utc::now(),
-            change_summary: Some(format!("Restored from revision {}", revision)),
-        };
+    /// Restore a revision as a new forward revision.
+    ///
+    /// The title, content and blocks of the old revision are applied to the
+    /// current content through [`ContentService::update`], so the restore is
+    /// itself versioned and can be undone. Status and terms are left as they
+    /// are unless requested in `options` and recorded with the revision.
+    pub async fn restore_with(
+        &self,
+        content_id: Uuid,
+        revision: i32,
+        options: RestoreOptions,
+    ) -> ContentResult<Content> {
+        let target = self.get_revision(content_id, revision).await?;
+        let contents = ContentService::new(self.pool.clone());
+        let mut content = contents.get(content_id).await?;
+
+        content.title = target.title.clone();
+        content.content = target.content.clone();
+        content.blocks = target.parsed_blocks();
+
+        if options.status {
+            if let Some(status) = target.status {
+                if status == ContentStatus::Published && content.published_at.is_none() {
+                    content.published_at = Some(Utc::now());
+                }
+                content.status = status;
+            }
+        }
 
-        // Update content in main table
-        sqlx::query(
-            r#"
-            UPDATE contents SET
-                title = $2, content = $3, blocks = $4, revision = $5, updated_at = $6
-            WHERE id = $1
-            "#,
-        )
-        .bind(content_id)
-        .bind(&restored.title)
-        .bind(&restored.content)
-        .bind(&restored.blocks)
-        .bind(new_revision)
-        .bind(Utc::now())
-        .execute(&self.pool)
-        .await?;
+        // Terms go first so the new revision's snapshot includes them
+        if options.terms {
+            if let Some(ref terms) = target.terms {
+                TaxonomyService::new(self.pool.clone())
+                    .set_content_terms(content_id, terms)
+                    .await?;
+            }
+        }
+
+        let content = contents.update(content).await?;
 
-        // Save the restored revision
         sqlx::query(
-            r#"
-            INSERT INTO content_revisions (
-                id, content_id, revision, title, content, blocks,
-                author_id, created_at, change_summary
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-            "#,
+            "UPDATE content_revisions SET change_summary = $3 WHERE content_id = $1 AND revision = $2",
         )
-        .bind(restored.id)
-        .bind(restored.content_id)
-        .bind(restored.revision)
-        .bind(&restored.title)
-        .bind(&restored.content)
-        .bind(&restored.blocks)
-        .bind(restored.author_id)
-        .bind(restored.created_at)
-        .bind(&restored.change_summary)
+        .bind(content_id)
+        .bind(content.revision)
+        .bind(format!("Restored from revision {}.", revision))
         .execute(&self.pool)
         .await?;
 
-        Ok(restored)
+        Ok(content)
     }
 
     /// Delete old revisions (keep most recent N)
@@ -419,6 +560,8 @@ struct RevisionRow {
     author_id: Uuid,
     created_at: DateTime<Utc>,
     change_summary: Option<String>,
+    status: Option<String>,
+    terms: Option<serde_json::Value>,
 }
 
 impl From<RevisionRow> for Revision {
@@ -433,8 +576,221 @@ impl From<RevisionRow> for Revision {
             author_id: row.author_id,
             created_at: row.created_at,
             change_summary: row.change_summary,
+            status: row
+                .status
+                .and_then(|status| serde_json::from_str(&status).ok()),
+            terms: row
+                .terms
+                .and_then(|terms| serde_json::from_value(terms).ok()),
+        }
+    }
+}
+
+/// Read a blocks JSON value written by any block schema version
+fn revision_blocks(value: &serde_json::Value) -> Vec<Block> {
+    match value {
+        serde_json::Value::Array(items) => legacy_blocks(items, ""),
+        serde_json::Value::Object(map) => {
+            map.get("blocks").map(revision_blocks).unwrap_or_default()
+        }
+        serde_json::Value::String(encoded) => serde_json::from_str(encoded)
+            .map(|value| revision_blocks(&value))
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+fn legacy_blocks(items: &[serde_json::Value], path: &str) -> Vec<Block> {
+    items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| legacy_block(item, &format!("{}{}", path, index)))
+        .collect()
+}
+
+fn legacy_block(value: &serde_json::Value, position: &str) -> Option<Block> {
+    let map = value.as_object()?;
+    let field = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| map.get(*key).filter(|value| !value.is_null()))
+    };
+    let text = |keys: &[&str]| field(keys).and_then(serde_json::Value::as_str);
+
+    Some(Block {
+        id: text(&["id", "clientId"])
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("legacy-{}", position)),
+        // The Gutenberg parser uses a null block name for freeform HTML
+        block_type: text(&["name", "blockName", "type"])
+            .unwrap_or("core/freeform")
+            .to_string(),
+        attributes: field(&["attributes", "attrs"])
+            .cloned()
+            .unwrap_or_else(|| serde_json::json!({})),
+        inner_blocks: field(&["innerBlocks", "inner_blocks"])
+            .and_then(serde_json::Value::as_array)
+            .map(|items| legacy_blocks(items, &format!("{}-", position)))
+            .unwrap_or_default(),
+        inner_html: text(&["innerHTML", "inner_html", "content"])
+            .unwrap_or_default()
+            .to_string(),
+        inner_content: field(&["innerContent", "inner_content"])
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default(),
+    })
+}
+
+/// Flatten blocks depth-first, keyed by ID.
+///
+/// Repeated IDs (e.g. from duplicated blocks) get an occurrence suffix so
+/// every block keeps a distinct key.
+fn keyed_blocks(blocks: &[Block]) -> Vec<(String, &Block)> {
+    fn walk<'a>(
+        blocks: &'a [Block],
+        seen: &mut HashMap<String, usize>,
+        out: &mut Vec<(String, &'a Block)>,
+    ) {
+        for block in blocks {
+            let occurrence = seen.entry(block.id.clone()).or_default();
+            let key = match *occurrence {
+                0 => block.id.clone(),
+                n => format!("{}#{}", block.id, n),
+            };
+            *occurrence += 1;
+            out.push((key, block));
+            walk(&block.inner_blocks, seen, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(blocks, &mut HashMap::new(), &mut out);
+    out
+}
+
+/// Diff two block trees, pairing blocks by ID rather than position.
+///
+/// Removed blocks come first, followed by the remaining changes in the
+/// new revision's order. Unchanged blocks are omitted.
+fn diff_blocks(from: &[Block], to: &[Block]) -> Vec<BlockChange> {
+    let old = keyed_blocks(from);
+    let new = keyed_blocks(to);
+    let old_index: HashMap<&str, usize> = old
+        .iter()
+        .enumerate()
+        .map(|(i, (key, _))| (key.as_str(), i))
+        .collect();
+    let new_index: HashMap<&str, usize> = new
+        .iter()
+        .enumerate()
+        .map(|(i, (key, _))| (key.as_str(), i))
+        .collect();
+
+    // Paired blocks outside the longest common ordering have been moved
+    let old_paired: Vec<&str> = old
+        .iter()
+        .map(|(key, _)| key.as_str())
+        .filter(|key| new_index.contains_key(key))
+        .collect();
+    let new_paired: Vec<&str> = new
+        .iter()
+        .map(|(key, _)| key.as_str())
+        .filter(|key| old_index.contains_key(key))
+        .collect();
+    let mut in_order: HashSet<&str> = HashSet::new();
+    for op in similar::capture_diff_slices(Algorithm::Myers, &old_paired, &new_paired) {
+        if let DiffOp::Equal { old_index, len, .. } = op {
+            in_order.extend(&old_paired[old_index..old_index + len]);
+        }
+    }
+
+    let mut changes: Vec<BlockChange> = old
+        .iter()
+        .enumerate()
+        .filter(|(_, (key, _))| !new_index.contains_key(key.as_str()))
+        .map(|(position, (_, block))| BlockChange {
+            block_id: block.id.clone(),
+            block_type: block.block_type.clone(),
+            kind: BlockChangeKind::Removed,
+            old_position: Some(position),
+            new_position: None,
+            moved: false,
+            attributes_changed: false,
+            text_changes: Vec::new(),
+        })
+        .collect();
+
+    for (position, (key, block)) in new.iter().enumerate() {
+        let Some(&old_position) = old_index.get(key.as_str()) else {
+            changes.push(BlockChange {
+                block_id: block.id.clone(),
+                block_type: block.block_type.clone(),
+                kind: BlockChangeKind::Added,
+                old_position: None,
+                new_position: Some(position),
+                moved: false,
+                attributes_changed: false,
+                text_changes: Vec::new(),
+            });
+            continue;
+        };
+
+        let previous = old[old_position].1;
+        let moved = !in_order.contains(key.as_str());
+        let attributes_changed = previous.attributes != block.attributes;
+        let modified = attributes_changed
+            || previous.block_type != block.block_type
+            || previous.inner_html != block.inner_html;
+
+        let kind = if modified {
+            BlockChangeKind::Modified
+        } else if moved {
+            BlockChangeKind::Moved
+        } else {
+            continue;
+        };
+
+        changes.push(BlockChange {
+            block_id: block.id.clone(),
+            block_type: block.block_type.clone(),
+            kind,
+            old_position: Some(old_position),
+            new_position: Some(position),
+            moved,
+            attributes_changed,
+            text_changes: if modified {
+                diff_block_text(&previous.inner_html, &block.inner_html)
+            } else {
+                Vec::new()
+            },
+        });
+    }
+
+    changes
+}
+
+/// Word-level diff of the text inside two block HTML fragments
+fn diff_block_text(old_html: &str, new_html: &str) -> Vec<BlockTextChange> {
+    let old_text = unescape_html(&strip_tags(old_html));
+    let new_text = unescape_html(&strip_tags(new_html));
+    let diff = TextDiff::from_words(&old_text, &new_text);
+
+    // Merge consecutive words with the same tag into runs
+    let mut runs: Vec<BlockTextChange> = Vec::new();
+    for change in diff.iter_all_changes() {
+        let change_type = match change.tag() {
+            ChangeTag::Delete => ChangeType::Delete,
+            ChangeTag::Insert => ChangeType::Insert,
+            ChangeTag::Equal => ChangeType::Equal,
+        };
+        match runs.last_mut() {
+            Some(run) if run.change_type == change_type => run.text.push_str(change.value()),
+            _ => runs.push(BlockTextChange {
+                change_type,
+                text: change.value().to_string(),
+            }),
         }
     }
+    runs
 }
 
 /// Generate HTML diff view
@@ -562,6 +918,8 @@ mod tests {
             author_id: Uuid::new_v4(),
             created_at: Utc::now(),
             change_summary: None,
+            status: None,
+            terms: None,
         };
 
         let to = Revision {
@@ -574,6 +932,8 @@ mod tests {
             author_id: Uuid::new_v4(),
             created_at: Utc::now(),
             change_summary: None,
+            status: None,
+            terms: None,
         };
 
         let diff = VersioningService::generate_diff(&from, &to);
@@ -594,6 +954,8 @@ mod tests {
             author_id: Uuid::new_v4(),
             created_at: Utc::now(),
             change_summary: None,
+            status: None,
+            terms: None,
         };
 
         let to = Revision {
@@ -606,6 +968,8 @@ mod tests {
             author_id: Uuid::new_v4(),
             created_at: Utc::now(),
             change_summary: None,
+            status: None,
+            terms: None,
         };
 
         let diff = VersioningService::generate_diff(&from, &to);
@@ -613,4 +977,153 @@ mod tests {
         assert!(diff.title_changed);
         assert!(diff.title_diff.is_some());
     }
+
+    fn paragraph(id: &str, html: &str) -> Block {
+        let mut block = Block::paragraph(html);
+        block.id = id.to_string();
+        block.inner_html = html.to_string();
+        block
+    }
+
+    fn revision_with_blocks(revision: i32, blocks: serde_json::Value) -> Revision {
+        let mut rev = Revision::new(Uuid::nil(), revision);
+        rev.blocks = blocks;
+        rev
+    }
+
+    #[test]
+    fn test_block_diff_pairs_by_id() {
+        let from = vec![
+            paragraph("a", "<p>First</p>"),
+            paragraph("b", "<p>Second</p>"),
+            paragraph("c", "<p>Third</p>"),
+        ];
+        let to = vec![
+            paragraph("c", "<p>Third</p>"),
+            paragraph("a", "<p>First &amp; best</p>"),
+            paragraph("d", "<p>Fourth</p>"),
+        ];
+
+        let diff = VersioningService::generate_diff(
+            &revision_with_blocks(1, serde_json::to_value(&from).unwrap()),
+            &revision_with_blocks(2, serde_json::to_value(&to).unwrap()),
+        );
+        let kinds: Vec<_> = diff
+            .block_changes
+            .iter()
+            .map(|c| (c.block_id.as_str(), c.kind))
+            .collect();
+
+        // Reordering "c" to the front must not read as a removal and addition
+        assert_eq!(
+            kinds,
+            vec![
+                ("b", BlockChangeKind::Removed),
+                ("c", BlockChangeKind::Moved),
+                ("a", BlockChangeKind::Modified),
+                ("d", BlockChangeKind::Added),
+            ]
+        );
+        assert_eq!(diff.stats.blocks_modified, 1);
+
+        let modified = &diff.block_changes[2];
+        assert_eq!(
+            (modified.old_position, modified.new_position),
+            (Some(0), Some(1))
+        );
+        assert!(!modified.moved);
+        assert_eq!(
+            modified.text_changes,
+            vec![
+                BlockTextChange {
+                    change_type: ChangeType::Equal,
+                    text: "First".to_string()
+                },
+                BlockTextChange {
+                    change_type: ChangeType::Insert,
+                    text: " & best".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_block_diff_reads_legacy_schema() {
+        // Parser output without IDs, wrapped with a schema version
+        let legacy = serde_json::json!({
+            "version": 1,
+            "blocks": [
+                {"blockName": "core/heading", "attrs": {"level": 2}, "innerHTML": "<h2>Intro</h2>"},
+                {"blockName": null, "attrs": {}, "innerHTML": "\n"}
+            ]
+        });
+        let rev = revision_with_blocks(1, legacy.clone());
+        let blocks = rev.parsed_blocks();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].block_type, "core/heading");
+        assert_eq!(blocks[0].attributes["level"], 2);
+        assert_eq!(blocks[1].block_type, "core/freeform");
+
+        let mut changed = legacy;
+        changed["blocks"][0]["attrs"]["level"] = serde_json::json!(3);
+        let diff = VersioningService::generate_diff(&rev, &revision_with_blocks(2, changed));
+        assert_eq!(diff.block_changes.len(), 1);
+        assert_eq!(diff.block_changes[0].block_id, "legacy-0");
+        assert!(diff.block_changes[0].attributes_changed);
+
+        // Double-encoded and unreadable values
+        let encoded = serde_json::Value::String(r#"[{"id":"x","name":"core/paragraph"}]"#.into());
+        assert_eq!(revision_with_blocks(1, encoded).parsed_blocks()[0].id, "x");
+        assert!(revision_with_blocks(1, serde_json::json!(42))
+            .parsed_blocks()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_block_diff_nested_and_duplicate_ids() {
+        let mut columns = Block::new("core/columns");
+        columns.id = "cols".to_string();
+        columns.inner_blocks = vec![
+            paragraph("dup", "<p>Left</p>"),
+            paragraph("dup", "<p>Right</p>"),
+        ];
+
+        let mut edited = columns.clone();
+        edited.inner_blocks[1].inner_html = "<p>Right side</p>".to_string();
+
+        let changes = diff_blocks(&[columns], &[edited]);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].block_id, "dup");
+        assert_eq!(changes[0].new_position, Some(2));
+        assert_eq!(changes[0].kind, BlockChangeKind::Modified);
+    }
+
+    #[test]
+    fn test_revision_snapshots_from_row() {
+        let row = |status: Option<&str>, terms: Option<serde_json::Value>| RevisionRow {
+            id: Uuid::new_v4(),
+            content_id: Uuid::new_v4(),
+            revision: 3,
+            title: "Title".to_string(),
+            content: "Content".to_string(),
+            blocks: serde_json::json!([]),
+            author_id: Uuid::new_v4(),
+            created_at: Utc::now(),
+            change_summary: None,
+            status: status.map(str::to_string),
+            terms,
+        };
+
+        let term = Uuid::new_v4();
+        let revision = Revision::from(row(Some("\"published\""), Some(serde_json::json!([term]))));
+        assert_eq!(revision.status, Some(ContentStatus::Published));
+        assert_eq!(revision.terms, Some(vec![term]));
+
+        // Older revisions have nothing to restore beyond the body
+        let legacy = Revision::from(row(None, None));
+        assert_eq!((legacy.status, legacy.terms), (None, None));
+
+        let options = RestoreOptions::default();
+        assert!(!options.status && !options.terms);
+    }
 }
@@ -1,6 +1,9 @@
-//! Configuration system for RustPress using config-rs.
+//! Configuration system for RustPress.
 //!
-//! Supports TOML, YAML, and environment variable configuration.
+//! [`ConfigLoader`] builds an [`AppConfig`] from layers: built-in defaults,
+//! the base TOML file, an environment-specific TOML file and environment
+//! variables. [`LoadedConfig::validate`] checks the result before startup
+//! and reports which layer set each offending key.
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -8,6 +11,7 @@ use std::time::Duration;
 
 /// Main application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
     /// Server configuration
     pub server: ServerConfig,
@@ -31,6 +35,8 @@ pub struct AppConfig {
     pub jobs: JobConfig,
     /// API configuration
     pub api: ApiConfig,
+    /// Event bus configuration
+    pub events: EventsConfig,
 }
 
 impl Default for AppConfig {
@@ -47,12 +53,14 @@ impl Default for AppConfig {
             multitenancy: MultitenancyConfig::default(),
             jobs: JobConfig::default(),
             api: ApiConfig::default(),
+            events: EventsConfig::default(),
         }
     }
 }
 
 /// Server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ServerConfig {
     /// Host to bind to
     pub host: String,
@@ -106,8 +114,10 @@ impl ServerConfig {
 
 /// Database configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DatabaseConfig {
-    /// Database URL
+    /// Database URL (`database_url` in files written by the setup wizard)
+    #[serde(alias = "database_url")]
     pub url: String,
     /// Minimum connection pool size
     pub pool_min: u32,
@@ -156,6 +166,7 @@ impl DatabaseConfig {
 
 /// Cache configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CacheConfig {
     /// Cache backend type
     pub backend: CacheBackend,
@@ -167,6 +178,8 @@ pub struct CacheConfig {
     pub max_memory_mb: usize,
     /// Enable cache metrics
     pub enable_metrics: bool,
+    /// Maximum entries held by the in-memory cache
+    pub max_entries: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -185,12 +198,14 @@ impl Default for CacheConfig {
             default_ttl_secs: 3600,
             max_memory_mb: 256,
             enable_metrics: true,
+            max_entries: 10_000,
         }
     }
 }
 
 /// Authentication configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AuthConfig {
     /// JWT secret key
     pub jwt_secret: String,
@@ -239,6 +254,7 @@ impl Default for AuthConfig {
 
 /// Storage configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StorageConfig {
     /// Storage backend type
     pub backend: StorageBackend,
@@ -256,6 +272,11 @@ pub struct StorageConfig {
     pub allowed_types: Vec<String>,
     /// CDN URL prefix
     pub cdn_url: Option<String>,
+    /// Secret for signing private local file URLs
+    #[serde(default)]
+    pub url_signing_secret: Option<String>,
+    /// Directory holding installed themes
+    pub themes_path: PathBuf,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -286,12 +307,15 @@ impl Default for StorageConfig {
                 "audio/mpeg".to_string(),
             ],
             cdn_url: None,
+            url_signing_secret: None,
+            themes_path: PathBuf::from("./themes"),
         }
     }
 }
 
 /// Logging configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LoggingConfig {
     /// Log level
     pub level: String,
@@ -333,6 +357,7 @@ impl Default for LoggingConfig {
 
 /// Metrics configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MetricsConfig {
     /// Enable metrics collection
     pub enabled: bool,
@@ -354,6 +379,7 @@ impl Default for MetricsConfig {
 
 /// Rate limiting configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RateLimitConfig {
     /// Enable rate limiting
     pub enabled: bool,
@@ -369,6 +395,31 @@ pub struct RateLimitConfig {
     pub by_api_key: bool,
     /// Endpoints exempt from rate limiting
     pub exempt_paths: Vec<String>,
+    /// Proxies (IPs or CIDR ranges) whose X-Forwarded-For header is trusted
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
+    /// Per-route-group limits, matched by longest path prefix
+    #[serde(default = "default_route_rate_limits")]
+    pub routes: Vec<RouteRateLimit>,
+}
+
+/// Rate limit for requests under a path prefix
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteRateLimit {
+    /// Path prefix the limit applies to
+    pub path_prefix: String,
+    /// Requests per window
+    pub requests_per_window: u32,
+    /// Window size in seconds
+    pub window_secs: u64,
+}
+
+fn default_route_rate_limits() -> Vec<RouteRateLimit> {
+    vec![RouteRateLimit {
+        path_prefix: "/api/v1/auth".to_string(),
+        requests_per_window: 5,
+        window_secs: 60,
+    }]
 }
 
 impl Default for RateLimitConfig {
@@ -386,12 +437,15 @@ impl Default for RateLimitConfig {
                 "/api/v4".to_string(),
                 "/admin".to_string(),
             ],
+            trusted_proxies: Vec::new(),
+            routes: default_route_rate_limits(),
         }
     }
 }
 
 /// Multi-tenancy configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MultitenancyConfig {
     /// Enable multi-tenancy
     pub enabled: bool,
@@ -443,6 +497,7 @@ impl Default for MultitenancyConfig {
 
 /// Job queue configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct JobConfig {
     /// Enable job processing
     pub enabled: bool,
@@ -480,6 +535,7 @@ impl Default for JobConfig {
 
 /// API configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ApiConfig {
     /// API prefix
     pub prefix: String,
@@ -511,6 +567,554 @@ impl Default for ApiConfig {
     }
 }
 
+/// Event bus configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventsConfig {
+    /// Persist events to the database for durable subscribers
+    pub log_enabled: bool,
+}
+
+// =============================================================================
+// Layered loading
+// =============================================================================
+
+/// Default location of the base config file
+pub const DEFAULT_CONFIG_PATH: &str = "./config/rustpress.toml";
+
+/// Environment variable overriding the base config file location
+pub const CONFIG_PATH_VAR: &str = "RUSTPRESS_CONFIG";
+
+/// Environment variable naming the environment, e.g. `production`
+pub const ENVIRONMENT_VAR: &str = "RUSTPRESS_ENV";
+
+/// Prefix of environment variables addressing any key, e.g.
+/// `RUSTPRESS__SERVER__PORT` for `server.port`
+pub const ENV_PREFIX: &str = "RUSTPRESS__";
+
+/// Minimum length of the JWT signing secret
+pub const MIN_SECRET_LEN: usize = 32;
+
+/// Built-in JWT secret, rejected by validation
+const DEFAULT_JWT_SECRET: &str = "change-me-in-production";
+
+/// Long-standing environment variables and the keys they set
+const ENV_ALIASES: &[(&str, &str)] = &[
+    ("DATABASE_URL", "database.url"),
+    ("RUSTPRESS_HOST", "server.host"),
+    ("RUSTPRESS_PORT", "server.port"),
+    ("JWT_SECRET", "auth.jwt_secret"),
+    ("STORAGE_PATH", "storage.local_path"),
+    ("STORAGE_URL_SIGNING_SECRET", "storage.url_signing_secret"),
+    ("THEMES_PATH", "storage.themes_path"),
+    ("CACHE_MAX_CAPACITY", "cache.max_entries"),
+    ("EVENT_LOG_ENABLED", "events.log_enabled"),
+];
+
+/// Keys accepted under another name in config files
+const KEY_ALIASES: &[(&str, &str)] = &[("database.database_url", "database.url")];
+
+/// Where a configuration value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigLayer {
+    /// Built-in default
+    Defaults,
+    /// Base or environment-specific config file
+    File(PathBuf),
+    /// Environment variable
+    Env(String),
+}
+
+impl std::fmt::Display for ConfigLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Defaults => write!(f, "defaults"),
+            Self::File(path) => write!(f, "config file {}", path.display()),
+            Self::Env(name) => write!(f, "environment variable {}", name),
+        }
+    }
+}
+
+/// A problem found by [`AppConfig::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    /// Dotted key, e.g. `auth.jwt_secret`
+    pub key: String,
+    pub message: String,
+    /// Layer that set the value, when known
+    pub layer: Option<ConfigLayer>,
+}
+
+impl ConfigProblem {
+    fn new(key: &str, message: impl Into<String>) -> Self {
+        Self {
+            key: key.to_string(),
+            message: message.into(),
+            layer: None,
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` {}", self.key, self.message)?;
+        if let Some(layer) = &self.layer {
+            write!(f, " (set by {})", layer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Errors loading or validating configuration
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("failed to read config file {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("failed to parse {layer}: {message}")]
+    Parse { layer: ConfigLayer, message: String },
+
+    #[error("invalid value for `{key}` in {layer}: {message}")]
+    InvalidValue {
+        layer: ConfigLayer,
+        key: String,
+        message: String,
+    },
+
+    #[error("invalid configuration: {}", format_problems(.0))]
+    Invalid(Vec<ConfigProblem>),
+}
+
+fn format_problems(problems: &[ConfigProblem]) -> String {
+    problems
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl From<ConfigError> for crate::error::Error {
+    fn from(err: ConfigError) -> Self {
+        Self::Configuration {
+            message: err.to_string(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Check required settings before startup, returning every problem found
+    pub fn validate(&self) -> Vec<ConfigProblem> {
+        let mut problems = Vec::new();
+
+        let url = self.database.url.trim();
+        if url.is_empty() {
+            problems.push(ConfigProblem::new("database.url", "is required"));
+        } else if !(url.starts_with("postgres://") || url.starts_with("postgresql://")) {
+            problems.push(ConfigProblem::new(
+                "database.url",
+                "must be a postgres:// URL",
+            ));
+        }
+        if self.database.pool_max == 0 {
+            problems.push(ConfigProblem::new(
+                "database.pool_max",
+                "must be at least 1",
+            ));
+        } else if self.database.pool_min > self.database.pool_max {
+            problems.push(ConfigProblem::new(
+                "database.pool_min",
+                format!(
+                    "must not exceed database.pool_max ({})",
+                    self.database.pool_max
+                ),
+            ));
+        }
+
+        if self.server.host.trim().is_empty() {
+            problems.push(ConfigProblem::new("server.host", "is required"));
+        }
+
+        if let Some(message) = secret_weakness(&self.auth.jwt_secret) {
+            problems.push(ConfigProblem::new("auth.jwt_secret", message));
+        }
+        if let Some(secret) = &self.storage.url_signing_secret {
+            if let Some(message) = secret_weakness(secret) {
+                problems.push(ConfigProblem::new("storage.url_signing_secret", message));
+            }
+        }
+
+        if matches!(
+            self.cache.backend,
+            CacheBackend::Redis | CacheBackend::Hybrid
+        ) && self
+            .cache
+            .redis_url
+            .as_deref()
+            .unwrap_or_default()
+            .is_empty()
+        {
+            problems.push(ConfigProblem::new(
+                "cache.redis_url",
+                "is required when the cache backend uses Redis",
+            ));
+        }
+
+        problems
+    }
+
+    /// Configuration as JSON with secrets and URL passwords masked, for logging
+    pub fn redacted(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        redact(&mut value);
+        value
+    }
+}
+
+/// Why a secret is too weak to sign with, if it is
+fn secret_weakness(secret: &str) -> Option<String> {
+    if secret.is_empty() {
+        return Some("is required".to_string());
+    }
+    if secret == DEFAULT_JWT_SECRET {
+        return Some("is the built-in placeholder; set a unique secret".to_string());
+    }
+    let len = secret.chars().count();
+    if len < MIN_SECRET_LEN {
+        return Some(format!(
+            "must be at least {} characters (got {})",
+            MIN_SECRET_LEN, len
+        ));
+    }
+    let distinct: std::collections::HashSet<char> = secret.chars().collect();
+    if distinct.len() < 8 {
+        return Some("is too repetitive to be a strong secret".to_string());
+    }
+    None
+}
+
+/// Mask secret values and URL passwords in place
+fn redact(value: &mut serde_json::Value) {
+    const SECRET_MARKERS: &[&str] = &["secret", "password", "token", "api_key"];
+
+    let serde_json::Value::Object(map) = value else {
+        return;
+    };
+    for (key, value) in map.iter_mut() {
+        if value.is_object() {
+            redact(value);
+        } else if SECRET_MARKERS.iter().any(|marker| key.contains(marker)) {
+            if !value.is_null() {
+                *value = serde_json::Value::String("[redacted]".to_string());
+            }
+        } else if key.ends_with("url") {
+            if let Some(masked) = value.as_str().and_then(mask_url_password) {
+                *value = serde_json::Value::String(masked);
+            }
+        }
+    }
+}
+
+fn mask_url_password(raw: &str) -> Option<String> {
+    let mut url = url::Url::parse(raw).ok()?;
+    url.password()?;
+    url.set_password(Some("redacted")).ok()?;
+    Some(url.to_string())
+}
+
+/// Loads [`AppConfig`] from layers, each overriding the one before:
+/// defaults < base file < environment file (`rustpress.{env}.toml`) <
+/// environment variables.
+///
+/// Environment variables are the long-standing names (`DATABASE_URL`,
+/// `JWT_SECRET`, ...) plus `RUSTPRESS__SECTION__KEY` for any key. Every
+/// value is type-checked on its own, so a bad one is reported with the
+/// layer and key it came from.
+#[derive(Debug, Clone)]
+pub struct ConfigLoader {
+    base_path: PathBuf,
+    environment: Option<String>,
+    vars: Option<Vec<(String, String)>>,
+}
+
+impl ConfigLoader {
+    /// Loader for the base file at `base_path`
+    pub fn new(base_path: impl Into<PathBuf>) -> Self {
+        Self {
+            base_path: base_path.into(),
+            environment: None,
+            vars: None,
+        }
+    }
+
+    /// Loader using `RUSTPRESS_CONFIG` and `RUSTPRESS_ENV` from the process
+    /// environment
+    pub fn from_env() -> Self {
+        let path =
+            std::env::var(CONFIG_PATH_VAR).unwrap_or_else(|_| DEFAULT_CONFIG_PATH.to_string());
+        let loader = Self::new(path);
+        match std::env::var(ENVIRONMENT_VAR) {
+            Ok(env) if !env.trim().is_empty() => loader.environment(env.trim()),
+            _ => loader,
+        }
+    }
+
+    /// Also load the environment-specific file for `environment`
+    pub fn environment(mut self, environment: impl Into<String>) -> Self {
+        self.environment = Some(environment.into());
+        self
+    }
+
+    /// Read overrides from these variables instead of the process environment
+    pub fn with_vars<K, V>(mut self, vars: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.vars = Some(
+            vars.into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        );
+        self
+    }
+
+    /// Path of the environment-specific file, next to the base file
+    pub fn environment_path(&self) -> Option<PathBuf> {
+        let environment = self.environment.as_ref()?;
+        let stem = self
+            .base_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("rustpress");
+        Some(
+            self.base_path
+                .with_file_name(format!("{}.{}.toml", stem, environment)),
+        )
+    }
+
+    /// Load every layer; missing files are skipped
+    pub fn load(&self) -> Result<LoadedConfig, ConfigError> {
+        let mut merged = toml::Value::Table(toml::Table::new());
+        let mut sources = std::collections::BTreeMap::new();
+        let mut layers = vec![ConfigLayer::Defaults];
+
+        let files = std::iter::once(self.base_path.clone()).chain(self.environment_path());
+        for path in files {
+            let content = match std::fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(source) => return Err(ConfigError::Read { path, source }),
+            };
+            let layer = ConfigLayer::File(path);
+            let table: toml::Value = toml::from_str(&content).map_err(|e| ConfigError::Parse {
+                layer: layer.clone(),
+                message: e.message().to_string(),
+            })?;
+
+            let mut leaves = Vec::new();
+            collect_leaves(&table, String::new(), &mut leaves);
+            for (key, value) in leaves {
+                check_leaf(&key, &value).map_err(|message| ConfigError::InvalidValue {
+                    layer: layer.clone(),
+                    key: canonical_key(&key),
+                    message,
+                })?;
+                sources.insert(canonical_key(&key), layer.clone());
+                set_leaf(&mut merged, &key, value);
+            }
+            layers.push(layer);
+        }
+
+        let vars = match &self.vars {
+            Some(vars) => vars.clone(),
+            None => std::env::vars().collect(),
+        };
+        let mut env_overrides: Vec<(String, String, String)> = vars
+            .into_iter()
+            .filter_map(|(name, raw)| env_key(&name).map(|key| (name, key, raw)))
+            .collect();
+        // Stable order, so the generic form wins over an alias for the same key
+        env_overrides.sort_by(|a, b| {
+            a.0.starts_with(ENV_PREFIX)
+                .cmp(&b.0.starts_with(ENV_PREFIX))
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        for (name, key, raw) in env_overrides {
+            let layer = ConfigLayer::Env(name);
+            let mut first_error = None;
+            let value = env_candidates(&raw).into_iter().find(|candidate| {
+                match check_leaf(&key, candidate) {
+                    Ok(()) => true,
+                    Err(message) => {
+                        first_error.get_or_insert(message);
+                        false
+                    }
+                }
+            });
+            let Some(value) = value else {
+                return Err(ConfigError::InvalidValue {
+                    layer,
+                    key,
+                    message: first_error.unwrap_or_default(),
+                });
+            };
+            sources.insert(key.clone(), layer.clone());
+            set_leaf(&mut merged, &key, value);
+            layers.push(layer);
+        }
+
+        let config = merged
+            .try_into::<AppConfig>()
+            .map_err(|e| ConfigError::Parse {
+                layer: layers.last().cloned().unwrap_or(ConfigLayer::Defaults),
+                message: e.message().to_string(),
+            })?;
+
+        Ok(LoadedConfig {
+            config,
+            layers,
+            sources,
+        })
+    }
+}
+
+/// Configuration together with the layers it was built from
+#[derive(Debug, Clone)]
+pub struct LoadedConfig {
+    pub config: AppConfig,
+    layers: Vec<ConfigLayer>,
+    sources: std::collections::BTreeMap<String, ConfigLayer>,
+}
+
+impl LoadedConfig {
+    /// Layers that contributed, lowest precedence first
+    pub fn layers(&self) -> &[ConfigLayer] {
+        &self.layers
+    }
+
+    /// Layer that set `key`; untouched keys come from the defaults
+    pub fn source(&self, key: &str) -> &ConfigLayer {
+        self.sources.get(key).unwrap_or(&ConfigLayer::Defaults)
+    }
+
+    /// Validate the effective configuration, naming the layer behind each
+    /// problem
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let problems: Vec<ConfigProblem> = self
+            .config
+            .validate()
+            .into_iter()
+            .map(|mut problem| {
+                problem.layer = Some(self.source(&problem.key).clone());
+                problem
+            })
+            .collect();
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(problems))
+        }
+    }
+
+    /// See [`AppConfig::redacted`]
+    pub fn redacted(&self) -> serde_json::Value {
+        self.config.redacted()
+    }
+}
+
+/// Dotted key an environment variable sets, if it is a config variable
+fn env_key(name: &str) -> Option<String> {
+    if let Some(rest) = name.strip_prefix(ENV_PREFIX) {
+        let key = rest
+            .split("__")
+            .map(str::to_ascii_lowercase)
+            .collect::<Vec<_>>()
+            .join(".");
+        return (!key.is_empty() && !key.split('.').any(str::is_empty)).then_some(key);
+    }
+    ENV_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, key)| key.to_string())
+}
+
+/// Interpretations of an environment value, most specific first
+fn env_candidates(raw: &str) -> Vec<toml::Value> {
+    let mut candidates = Vec::new();
+    if let Ok(toml::Value::Table(mut table)) =
+        toml::from_str::<toml::Value>(&format!("v = {}", raw))
+    {
+        if let Some(value) = table.remove("v") {
+            candidates.push(value);
+        }
+    }
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "1" | "yes" | "on" => candidates.push(toml::Value::Boolean(true)),
+        "0" | "no" | "off" => candidates.push(toml::Value::Boolean(false)),
+        _ => {}
+    }
+    candidates.push(toml::Value::String(raw.to_string()));
+    candidates
+}
+
+fn canonical_key(key: &str) -> String {
+    KEY_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == key)
+        .map_or_else(|| key.to_string(), |(_, canonical)| canonical.to_string())
+}
+
+/// Flatten a TOML table into dotted keys; arrays count as single values
+fn collect_leaves(value: &toml::Value, prefix: String, out: &mut Vec<(String, toml::Value)>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                collect_leaves(value, path, out);
+            }
+        }
+        _ => out.push((prefix, value.clone())),
+    }
+}
+
+fn set_leaf(root: &mut toml::Value, key: &str, value: toml::Value) {
+    let mut segments = key.split('.').peekable();
+    let mut current = root;
+    while let Some(segment) = segments.next() {
+        let toml::Value::Table(table) = current else {
+            return;
+        };
+        if segments.peek().is_none() {
+            table.insert(segment.to_string(), value);
+            return;
+        }
+        current = table
+            .entry(segment.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        if !current.is_table() {
+            *current = toml::Value::Table(toml::Table::new());
+        }
+    }
+}
+
+/// Check that `value` is acceptable for `key` on its own
+fn check_leaf(key: &str, value: &toml::Value) -> Result<(), String> {
+    let mut single = toml::Value::Table(toml::Table::new());
+    set_leaf(&mut single, key, value.clone());
+    single
+        .try_into::<AppConfig>()
+        .map(|_| ())
+        .map_err(|e| e.message().to_string())
+}
+
 // Helper function to get number of CPUs
 mod num_cpus {
     pub fn get() -> usize {
@@ -545,4 +1149,148 @@ mod tests {
         let deserialized: AppConfig = serde_json::from_str(&json).unwrap();
         assert_eq!(config.server.port, deserialized.server.port);
     }
+
+    const STRONG_SECRET: &str = "k2P9vQ7xL4mZ8rT1wY6bN3cH5jF0gD2s";
+
+    fn write(dir: &std::path::Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_layers_override_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = write(
+            dir.path(),
+            "rustpress.toml",
+            "setup_complete = true\n\n[database]\ndatabase_url = \"postgres://base/db\"\n\n[server]\nhost = \"0.0.0.0\"\nport = 8089\n",
+        );
+        let production = write(
+            dir.path(),
+            "rustpress.production.toml",
+            "[server]\nport = 80\n\n[cache]\nmax_entries = 500\n",
+        );
+
+        let loaded = ConfigLoader::new(&base)
+            .environment("production")
+            .with_vars([
+                ("RUSTPRESS__SERVER__PORT", "8443"),
+                ("EVENT_LOG_ENABLED", "1"),
+                ("UNRELATED", "x"),
+            ])
+            .load()
+            .unwrap();
+
+        let config = &loaded.config;
+        assert_eq!(config.database.url, "postgres://base/db");
+        assert_eq!(config.server.host, "0.0.0.0");
+        assert_eq!(config.server.port, 8443);
+        assert_eq!(config.cache.max_entries, 500);
+        assert!(config.events.log_enabled);
+        assert_eq!(config.database.pool_max, 10);
+
+        assert_eq!(loaded.source("database.url"), &ConfigLayer::File(base));
+        assert_eq!(
+            loaded.source("cache.max_entries"),
+            &ConfigLayer::File(production)
+        );
+        assert_eq!(
+            loaded.source("server.port"),
+            &ConfigLayer::Env("RUSTPRESS__SERVER__PORT".to_string())
+        );
+        assert_eq!(loaded.source("server.workers"), &ConfigLayer::Defaults);
+    }
+
+    #[test]
+    fn test_bad_values_name_layer_and_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = write(dir.path(), "rustpress.toml", "[server]\nport = 8080\n");
+        write(
+            dir.path(),
+            "rustpress.staging.toml",
+            "[cache]\nbackend = \"memcached\"\n",
+        );
+
+        let err = ConfigLoader::new(&base)
+            .environment("staging")
+            .with_vars(Vec::<(String, String)>::new())
+            .load()
+            .unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("`cache.backend`"), "{}", message);
+        assert!(message.contains("rustpress.staging.toml"), "{}", message);
+
+        let err = ConfigLoader::new(&base)
+            .with_vars([("RUSTPRESS_PORT", "eighty")])
+            .load()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigError::InvalidValue { ref key, layer: ConfigLayer::Env(ref name), .. }
+                if key == "server.port" && name == "RUSTPRESS_PORT"
+        ));
+
+        // Numeric-looking values still work for string keys
+        let loaded = ConfigLoader::new(&base)
+            .with_vars([("JWT_SECRET", "12345678901234567890123456789012")])
+            .load()
+            .unwrap();
+        assert_eq!(
+            loaded.config.auth.jwt_secret,
+            "12345678901234567890123456789012"
+        );
+    }
+
+    #[test]
+    fn test_validate_reports_problems_with_layers() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = write(dir.path(), "rustpress.toml", "[database]\nurl = \"\"\n");
+
+        let loaded = ConfigLoader::new(&base)
+            .with_vars([("JWT_SECRET", "short")])
+            .load()
+            .unwrap();
+        let ConfigError::Invalid(problems) = loaded.validate().unwrap_err() else {
+            panic!("expected validation problems");
+        };
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].key, "database.url");
+        assert_eq!(problems[0].layer, Some(ConfigLayer::File(base)));
+        assert_eq!(problems[1].key, "auth.jwt_secret");
+        assert_eq!(
+            problems[1].layer,
+            Some(ConfigLayer::Env("JWT_SECRET".to_string()))
+        );
+        assert!(problems[1].message.contains("at least 32"));
+
+        let placeholder = AppConfig::default().validate();
+        assert!(placeholder
+            .iter()
+            .any(|p| p.key == "auth.jwt_secret" && p.message.contains("placeholder")));
+
+        let mut config = AppConfig::default();
+        config.auth.jwt_secret = STRONG_SECRET.to_string();
+        assert!(config.validate().is_empty());
+    }
+
+    #[test]
+    fn test_redacted_masks_secrets() {
+        let mut config = AppConfig::default();
+        config.auth.jwt_secret = STRONG_SECRET.to_string();
+        config.storage.url_signing_secret = Some("signing".to_string());
+        config.database.url = "postgres://rustpress:hunter2@db:5432/rustpress".to_string();
+
+        let redacted = config.redacted();
+        let text = redacted.to_string();
+        assert!(!text.contains(STRONG_SECRET));
+        assert!(!text.contains("hunter2"));
+        assert!(!text.contains("\"signing\""));
+        assert_eq!(redacted["auth"]["jwt_secret"], "[redacted]");
+        assert_eq!(
+            redacted["database"]["url"],
+            "postgres://rustpress:redacted@db:5432/rustpress"
+        );
+        assert_eq!(redacted["server"]["port"], 8080);
+    }
 }
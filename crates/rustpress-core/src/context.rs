@@ -3,7 +3,7 @@
 //! Provides context objects that carry state through the request lifecycle.
 
 use crate::id::{TenantId, UserId};
-use crate::tenant::Tenant;
+use crate::tenant::{Tenant, TenantScope};
 use parking_lot::RwLock;
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
@@ -230,6 +230,17 @@ impl AppContext {
         self.current_tenant.as_ref()
     }
 
+    /// Tenant scope for repository queries made on behalf of `request`,
+    /// falling back to the configured default tenant
+    pub fn tenant_scope(&self, request: &RequestContext) -> TenantScope {
+        TenantScope::resolve(
+            self.config.multitenancy.enabled,
+            request
+                .tenant_id
+                .or_else(|| self.current_tenant.as_ref().map(|t| t.id)),
+        )
+    }
+
     /// Get a shutdown receiver
     pub fn shutdown_receiver(&self) -> tokio::sync::watch::Receiver<bool> {
         self.shutdown.subscribe()
@@ -330,4 +341,27 @@ mod tests {
         ctx.shutdown();
         assert!(ctx.is_shutting_down());
     }
+
+    #[test]
+    fn test_app_context_tenant_scope() {
+        let ctx = AppContext::new(crate::config::AppConfig::default());
+        let request = RequestContext::test().with_tenant(TenantId::new());
+        assert_eq!(ctx.tenant_scope(&request), TenantScope::Unscoped);
+
+        let mut config = crate::config::AppConfig::default();
+        config.multitenancy.enabled = true;
+        let default_tenant = Tenant::new("default", "Default");
+        let default_id = default_tenant.id;
+        let ctx = AppContextBuilder::new(config)
+            .with_tenant(default_tenant)
+            .build();
+
+        let tenant = TenantId::new();
+        let request = RequestContext::test().with_tenant(tenant);
+        assert_eq!(ctx.tenant_scope(&request), TenantScope::Tenant(tenant));
+        assert_eq!(
+            ctx.tenant_scope(&RequestContext::test()),
+            TenantScope::Tenant(default_id)
+        );
+    }
 }
@@ -0,0 +1,88 @@
+//! Request ID propagation.
+//!
+//! The server assigns every request an ID (taken from the client's
+//! `X-Request-ID` header when it is well formed) and runs the handler inside
+//! [`scope`]. Anything running in that task — logging, error responses,
+//! outgoing HTTP calls, jobs queued by the request — can then read the ID
+//! with [`current_request_id`] without it being threaded through every
+//! function signature.
+
+use std::future::Future;
+use uuid::Uuid;
+
+/// Header carrying the request ID, both inbound and on downstream calls
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest client-supplied request ID we accept
+pub const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Check whether a client-supplied request ID is safe to echo and log.
+///
+/// Only visible ASCII letters, digits and `-`, `_`, `.`, `:` are allowed, up
+/// to [`MAX_REQUEST_ID_LEN`] characters, so IDs can't inject log lines or
+/// header values.
+pub fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'))
+}
+
+/// Generate a fresh request ID
+pub fn generate_request_id() -> String {
+    Uuid::now_v7().to_string()
+}
+
+/// Use the client's request ID if it is valid, otherwise generate one
+pub fn accept_or_generate(header: Option<&str>) -> String {
+    match header.map(str::trim) {
+        Some(id) if is_valid_request_id(id) => id.to_string(),
+        _ => generate_request_id(),
+    }
+}
+
+/// Run `fut` with `request_id` as the current request ID
+pub async fn scope<F: Future>(request_id: String, fut: F) -> F::Output {
+    REQUEST_ID.scope(request_id, fut).await
+}
+
+/// The request ID of the request being handled, if any
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation() {
+        assert!(is_valid_request_id("abc-123_x.y:z"));
+        assert!(is_valid_request_id(&"a".repeat(MAX_REQUEST_ID_LEN)));
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id(&"a".repeat(MAX_REQUEST_ID_LEN + 1)));
+        assert!(!is_valid_request_id("bad id"));
+        assert!(!is_valid_request_id("line\nbreak"));
+    }
+
+    #[test]
+    fn test_accept_or_generate() {
+        assert_eq!(accept_or_generate(Some("client-id")), "client-id");
+        let generated = accept_or_generate(Some("not valid!"));
+        assert!(Uuid::parse_str(&generated).is_ok());
+        assert!(Uuid::parse_str(&accept_or_generate(None)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_scope() {
+        assert_eq!(current_request_id(), None);
+        let seen = scope("req-1".to_string(), async { current_request_id() }).await;
+        assert_eq!(seen.as_deref(), Some("req-1"));
+        assert_eq!(current_request_id(), None);
+    }
+}
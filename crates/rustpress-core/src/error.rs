@@ -93,6 +93,9 @@ pub enum Error {
     #[error("Tenant suspended: {tenant_id}")]
     TenantSuspended { tenant_id: String },
 
+    #[error("Tenant required: no tenant in context for {operation}")]
+    TenantRequired { operation: String },
+
     // Hook errors
     #[error("Hook error: {hook_name} - {message}")]
     Hook { hook_name: String, message: String },
@@ -336,6 +339,13 @@ impl Error {
         }
     }
 
+    /// Create a tenant required error
+    pub fn tenant_required(operation: impl Into<String>) -> Self {
+        Error::TenantRequired {
+            operation: operation.into(),
+        }
+    }
+
     /// Create a storage error
     pub fn storage(message: impl Into<String>) -> Self {
         Error::Storage {
@@ -412,6 +422,7 @@ impl Error {
             Error::RateLimited { .. } => 429,
             Error::ServiceUnavailable { .. } | Error::ShutdownInProgress => 503,
             Error::TenantNotFound { .. } | Error::TenantSuspended { .. } => 403,
+            Error::TenantRequired { .. } => 400,
             _ => 500,
         }
     }
@@ -440,6 +451,7 @@ impl Error {
             Error::RateLimited { .. } => "RATE_LIMITED",
             Error::TenantNotFound { .. } => "TENANT_NOT_FOUND",
             Error::TenantSuspended { .. } => "TENANT_SUSPENDED",
+            Error::TenantRequired { .. } => "TENANT_REQUIRED",
             Error::Hook { .. } => "HOOK_ERROR",
             Error::Network { .. } => "NETWORK_ERROR",
             Error::ServiceUnavailable { .. } => "SERVICE_UNAVAILABLE",
@@ -0,0 +1,382 @@
+//! Runtime feature flags.
+//!
+//! Flags are stored in a [`FeatureFlagStore`] (the `feature_flags` table in
+//! production) and cached in memory, so checking a flag never touches the
+//! database. A flag can be switched on or off globally, rolled out to a
+//! percentage of users or tenants, and overridden per tenant.
+
+use crate::context::RequestContext;
+use crate::error::Result;
+use crate::id::{TenantId, UserId};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A stored feature flag
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureFlag {
+    /// Unique key, e.g. `editor.collaboration`
+    pub key: String,
+    pub description: Option<String>,
+    /// Master switch; a disabled flag is off for everyone without a tenant
+    /// override
+    pub enabled: bool,
+    /// Share of users (or tenants, for anonymous requests) that see the flag,
+    /// 0-100. `None` means everyone.
+    pub rollout_percentage: Option<u8>,
+    /// Per-tenant on/off overrides, taking precedence over everything else
+    #[serde(default)]
+    pub tenant_overrides: HashMap<TenantId, bool>,
+}
+
+impl FeatureFlag {
+    /// A flag that is on for everyone
+    pub fn enabled(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            description: None,
+            enabled: true,
+            rollout_percentage: None,
+            tenant_overrides: HashMap::new(),
+        }
+    }
+
+    /// A flag that is off for everyone
+    pub fn disabled(key: impl Into<String>) -> Self {
+        Self {
+            enabled: false,
+            ..Self::enabled(key)
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Roll the flag out to a percentage of users
+    pub fn with_rollout(mut self, percentage: u8) -> Self {
+        self.rollout_percentage = Some(percentage.min(100));
+        self
+    }
+
+    pub fn with_tenant_override(mut self, tenant_id: TenantId, enabled: bool) -> Self {
+        self.tenant_overrides.insert(tenant_id, enabled);
+        self
+    }
+
+    /// Evaluate the flag for a context
+    pub fn evaluate(&self, context: &FlagContext) -> bool {
+        if let Some(enabled) = context
+            .tenant_id
+            .and_then(|tenant_id| self.tenant_overrides.get(&tenant_id))
+        {
+            return *enabled;
+        }
+
+        if !self.enabled {
+            return false;
+        }
+
+        match self.rollout_percentage {
+            None | Some(100..) => true,
+            Some(0) => false,
+            Some(percentage) => match context.rollout_subject() {
+                Some(subject) => rollout_bucket(&self.key, &subject) < percentage as u32,
+                // Nothing to bucket on, so only full rollouts apply
+                None => false,
+            },
+        }
+    }
+}
+
+/// Who a flag is being evaluated for
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FlagContext {
+    pub user_id: Option<UserId>,
+    pub tenant_id: Option<TenantId>,
+}
+
+impl FlagContext {
+    /// Context with no user or tenant; only global and fully rolled out flags
+    /// apply
+    pub fn anonymous() -> Self {
+        Self::default()
+    }
+
+    pub fn for_user(user_id: UserId) -> Self {
+        Self {
+            user_id: Some(user_id),
+            tenant_id: None,
+        }
+    }
+
+    pub fn for_tenant(tenant_id: TenantId) -> Self {
+        Self {
+            user_id: None,
+            tenant_id: Some(tenant_id),
+        }
+    }
+
+    pub fn with_tenant(mut self, tenant_id: TenantId) -> Self {
+        self.tenant_id = Some(tenant_id);
+        self
+    }
+
+    /// Identity percentage rollouts are bucketed on: the user when known,
+    /// otherwise the tenant
+    fn rollout_subject(&self) -> Option<String> {
+        self.user_id
+            .map(|id| format!("user:{}", id))
+            .or_else(|| self.tenant_id.map(|id| format!("tenant:{}", id)))
+    }
+}
+
+impl From<&RequestContext> for FlagContext {
+    fn from(ctx: &RequestContext) -> Self {
+        Self {
+            user_id: ctx.user_id,
+            tenant_id: ctx.tenant_id,
+        }
+    }
+}
+
+/// Stable rollout bucket in `0..100` for a flag and subject
+///
+/// Uses FNV-1a rather than `DefaultHasher`, whose output is not guaranteed
+/// across Rust releases, so a user keeps the same answer across deploys. The
+/// flag key is mixed in so the same users are not always first in line.
+fn rollout_bucket(flag: &str, subject: &str) -> u32 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET;
+    for byte in flag.bytes().chain([b':']).chain(subject.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    (hash % 100) as u32
+}
+
+/// Persistent storage for feature flags
+#[async_trait]
+pub trait FeatureFlagStore: Send + Sync {
+    /// Load every flag, including tenant overrides
+    async fn load_all(&self) -> Result<Vec<FeatureFlag>>;
+
+    /// Create or replace a flag
+    async fn save(&self, flag: &FeatureFlag) -> Result<()>;
+
+    /// Remove a flag and its overrides
+    async fn delete(&self, key: &str) -> Result<()>;
+}
+
+/// In-memory flag store for tests and single-process setups
+#[derive(Default)]
+pub struct InMemoryFeatureFlagStore {
+    flags: RwLock<HashMap<String, FeatureFlag>>,
+}
+
+impl InMemoryFeatureFlagStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_flags(flags: impl IntoIterator<Item = FeatureFlag>) -> Self {
+        Self {
+            flags: RwLock::new(flags.into_iter().map(|f| (f.key.clone(), f)).collect()),
+        }
+    }
+}
+
+#[async_trait]
+impl FeatureFlagStore for InMemoryFeatureFlagStore {
+    async fn load_all(&self) -> Result<Vec<FeatureFlag>> {
+        Ok(self.flags.read().values().cloned().collect())
+    }
+
+    async fn save(&self, flag: &FeatureFlag) -> Result<()> {
+        self.flags.write().insert(flag.key.clone(), flag.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.flags.write().remove(key);
+        Ok(())
+    }
+}
+
+/// Cached feature flag service
+///
+/// [`is_enabled`](Self::is_enabled) only reads the in-memory snapshot; the
+/// snapshot is replaced by [`refresh`](Self::refresh), by writes made through
+/// this service, and periodically by [`spawn_refresh`](Self::spawn_refresh).
+pub struct FeatureFlags {
+    store: Arc<dyn FeatureFlagStore>,
+    cache: RwLock<Arc<HashMap<String, FeatureFlag>>>,
+    default_value: bool,
+}
+
+impl FeatureFlags {
+    /// Create the service with an empty cache; call [`refresh`](Self::refresh)
+    /// before relying on stored flags
+    pub fn new(store: Arc<dyn FeatureFlagStore>) -> Self {
+        Self {
+            store,
+            cache: RwLock::new(Arc::new(HashMap::new())),
+            default_value: false,
+        }
+    }
+
+    /// Create the service and load the current flags
+    pub async fn load(store: Arc<dyn FeatureFlagStore>) -> Result<Self> {
+        let flags = Self::new(store);
+        flags.refresh().await?;
+        Ok(flags)
+    }
+
+    /// Value returned for flags that do not exist (defaults to `false`)
+    pub fn with_default(mut self, default_value: bool) -> Self {
+        self.default_value = default_value;
+        self
+    }
+
+    /// Whether `flag` is on for `context`
+    ///
+    /// Never touches the store. Unknown flags return the configured default.
+    pub fn is_enabled(&self, flag: &str, context: &FlagContext) -> bool {
+        let flags = self.cache.read().clone();
+        match flags.get(flag) {
+            Some(flag) => flag.evaluate(context),
+            None => self.default_value,
+        }
+    }
+
+    /// Cached definition of a flag
+    pub fn get(&self, flag: &str) -> Option<FeatureFlag> {
+        self.cache.read().get(flag).cloned()
+    }
+
+    /// Every cached flag, sorted by key
+    pub fn all(&self) -> Vec<FeatureFlag> {
+        let mut flags: Vec<FeatureFlag> = self.cache.read().values().cloned().collect();
+        flags.sort_by(|a, b| a.key.cmp(&b.key));
+        flags
+    }
+
+    /// Reload every flag from the store
+    ///
+    /// On error the previous snapshot stays in place.
+    pub async fn refresh(&self) -> Result<()> {
+        let flags = self.store.load_all().await?;
+        let snapshot = flags.into_iter().map(|f| (f.key.clone(), f)).collect();
+        *self.cache.write() = Arc::new(snapshot);
+        Ok(())
+    }
+
+    /// Persist a flag and make it visible immediately
+    pub async fn save(&self, flag: FeatureFlag) -> Result<()> {
+        self.store.save(&flag).await?;
+        let mut cache = self.cache.write();
+        let mut snapshot = HashMap::clone(&cache);
+        snapshot.insert(flag.key.clone(), flag);
+        *cache = Arc::new(snapshot);
+        Ok(())
+    }
+
+    /// Delete a flag; later checks get the default value
+    pub async fn delete(&self, key: &str) -> Result<()> {
+        self.store.delete(key).await?;
+        let mut cache = self.cache.write();
+        let mut snapshot = HashMap::clone(&cache);
+        snapshot.remove(key);
+        *cache = Arc::new(snapshot);
+        Ok(())
+    }
+
+    /// Refresh the cache in the background so changes made by other
+    /// instances are picked up
+    pub fn spawn_refresh(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let flags = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = flags.refresh().await {
+                    tracing::warn!(error = %e, "Failed to refresh feature flags");
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::Id;
+
+    #[tokio::test]
+    async fn test_missing_flag_uses_default() {
+        let store = Arc::new(InMemoryFeatureFlagStore::new());
+        let ctx = FlagContext::anonymous();
+
+        let flags = FeatureFlags::load(store.clone()).await.unwrap();
+        assert!(!flags.is_enabled("missing", &ctx));
+
+        let flags = FeatureFlags::load(store).await.unwrap().with_default(true);
+        assert!(flags.is_enabled("missing", &ctx));
+    }
+
+    #[tokio::test]
+    async fn test_boolean_flags_and_tenant_overrides() {
+        let tenant: TenantId = Id::new();
+        let store = Arc::new(InMemoryFeatureFlagStore::with_flags([
+            FeatureFlag::enabled("on"),
+            FeatureFlag::disabled("off").with_tenant_override(tenant, true),
+        ]));
+        let flags = FeatureFlags::load(store).await.unwrap();
+
+        assert!(flags.is_enabled("on", &FlagContext::anonymous()));
+        assert!(!flags.is_enabled("off", &FlagContext::anonymous()));
+        assert!(flags.is_enabled("off", &FlagContext::for_tenant(tenant)));
+    }
+
+    #[test]
+    fn test_rollout_is_stable_and_proportional() {
+        let flag = FeatureFlag::enabled("new-editor").with_rollout(30);
+        let users: Vec<FlagContext> = (0..2000)
+            .map(|_| FlagContext::for_user(Id::new()))
+            .collect();
+
+        let first: Vec<bool> = users.iter().map(|u| flag.evaluate(u)).collect();
+        let second: Vec<bool> = users.iter().map(|u| flag.evaluate(u)).collect();
+        assert_eq!(first, second);
+
+        let enabled = first.iter().filter(|e| **e).count();
+        assert!((450..750).contains(&enabled), "{} of 2000 enabled", enabled);
+
+        assert_eq!(
+            rollout_bucket("flag", "user:1"),
+            rollout_bucket("flag", "user:1")
+        );
+        assert!(!flag.evaluate(&FlagContext::anonymous()));
+    }
+
+    #[tokio::test]
+    async fn test_save_updates_cache_without_refresh() {
+        let store = Arc::new(InMemoryFeatureFlagStore::new());
+        let flags = FeatureFlags::new(store.clone());
+        let ctx = FlagContext::anonymous();
+
+        flags.save(FeatureFlag::enabled("beta")).await.unwrap();
+        assert!(flags.is_enabled("beta", &ctx));
+        assert_eq!(store.load_all().await.unwrap().len(), 1);
+
+        flags.delete("beta").await.unwrap();
+        assert!(!flags.is_enabled("beta", &ctx));
+    }
+}
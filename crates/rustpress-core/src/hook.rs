@@ -28,9 +28,21 @@ impl Default for Priority {
     }
 }
 
+/// Whether an action should keep running its remaining callbacks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ActionFlow {
+    /// Run the next callback
+    #[default]
+    Continue,
+    /// Skip all lower-priority callbacks
+    Stop,
+}
+
 /// Type alias for async action handlers
 pub type ActionHandler = Arc<
-    dyn Fn(Arc<dyn Any + Send + Sync>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync,
+    dyn Fn(Arc<dyn Any + Send + Sync>) -> Pin<Box<dyn Future<Output = ActionFlow> + Send>>
+        + Send
+        + Sync,
 >;
 
 /// Type alias for async filter handlers
@@ -70,16 +82,41 @@ impl Action {
         F: Fn(Arc<dyn Any + Send + Sync>) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = ()> + Send + 'static,
     {
-        let handler: ActionHandler = Arc::new(move |data| Box::pin(handler(data)));
-
-        self.callbacks.push(ActionCallback {
-            handler,
+        self.add_controlled(
+            move |data| {
+                let fut = handler(data);
+                async move {
+                    fut.await;
+                    ActionFlow::Continue
+                }
+            },
             priority,
             plugin_id,
-        });
+        );
+    }
 
-        // Sort by priority (higher priority executes first)
-        self.callbacks.sort_by(|a, b| b.priority.cmp(&a.priority));
+    /// Add a callback that can stop the remaining callbacks from running
+    pub fn add_controlled<F, Fut>(
+        &mut self,
+        handler: F,
+        priority: Priority,
+        plugin_id: Option<String>,
+    ) where
+        F: Fn(Arc<dyn Any + Send + Sync>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ActionFlow> + Send + 'static,
+    {
+        let handler: ActionHandler = Arc::new(move |data| Box::pin(handler(data)));
+
+        // Higher priority executes first; equal priorities run in registration order
+        let index = self.callbacks.partition_point(|cb| cb.priority >= priority);
+        self.callbacks.insert(
+            index,
+            ActionCallback {
+                handler,
+                priority,
+                plugin_id,
+            },
+        );
     }
 
     /// Remove callbacks from a specific plugin
@@ -88,11 +125,15 @@ impl Action {
             .retain(|cb| cb.plugin_id.as_deref() != Some(plugin_id));
     }
 
-    /// Execute all callbacks
-    pub async fn execute(&self, data: Arc<dyn Any + Send + Sync>) {
+    /// Execute callbacks until one returns [`ActionFlow::Stop`]
+    pub async fn execute(&self, data: Arc<dyn Any + Send + Sync>) -> ActionFlow {
         for callback in &self.callbacks {
-            (callback.handler)(data.clone()).await;
+            if (callback.handler)(data.clone()).await == ActionFlow::Stop {
+                tracing::debug!(action = %self.name, "Action stopped by callback");
+                return ActionFlow::Stop;
+            }
         }
+        ActionFlow::Continue
     }
 
     /// Get the number of registered callbacks
@@ -124,13 +165,16 @@ impl<T: Clone + Send + 'static> Filter<T> {
     {
         let handler: FilterHandler<T> = Arc::new(move |data| Box::pin(handler(data)));
 
-        self.callbacks.push(FilterCallback {
-            handler,
-            priority,
-            plugin_id,
-        });
-
-        self.callbacks.sort_by(|a, b| b.priority.cmp(&a.priority));
+        // Higher priority executes first; equal priorities run in registration order
+        let index = self.callbacks.partition_point(|cb| cb.priority >= priority);
+        self.callbacks.insert(
+            index,
+            FilterCallback {
+                handler,
+                priority,
+                plugin_id,
+            },
+        );
     }
 
     /// Remove callbacks from a specific plugin
@@ -212,15 +256,36 @@ impl HookRegistry {
         action.add(handler, priority, plugin_id);
     }
 
-    /// Execute an action hook
-    pub async fn do_action(&self, name: &str, data: Arc<dyn Any + Send + Sync>) {
+    /// Register an action hook that can short-circuit lower-priority callbacks
+    pub fn add_action_controlled<F, Fut>(
+        &self,
+        name: &str,
+        handler: F,
+        priority: Priority,
+        plugin_id: Option<String>,
+    ) where
+        F: Fn(Arc<dyn Any + Send + Sync>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ActionFlow> + Send + 'static,
+    {
+        let mut storage = self.actions.write();
+        let action = storage
+            .actions
+            .entry(name.to_string())
+            .or_insert_with(|| Action::new(name));
+        action.add_controlled(handler, priority, plugin_id);
+    }
+
+    /// Execute an action hook, returning [`ActionFlow::Stop`] if a callback
+    /// short-circuited it
+    pub async fn do_action(&self, name: &str, data: Arc<dyn Any + Send + Sync>) -> ActionFlow {
         let action = {
             let storage = self.actions.read();
             storage.actions.get(name).cloned()
         };
 
-        if let Some(action) = action {
-            action.execute(data).await;
+        match action {
+            Some(action) => action.execute(data).await,
+            None => ActionFlow::Continue,
         }
     }
 
@@ -261,8 +326,13 @@ impl HookRegistry {
             .entry(name.to_string())
             .or_insert_with(|| Box::new(RwLock::new(Filter::<T>::new(name))));
 
-        if let Some(filter) = filter.downcast_mut::<RwLock<Filter<T>>>() {
-            filter.write().add(handler, priority, plugin_id);
+        match filter.downcast_mut::<RwLock<Filter<T>>>() {
+            Some(filter) => filter.write().add(handler, priority, plugin_id),
+            None => tracing::warn!(
+                filter = name,
+                value_type = std::any::type_name::<T>(),
+                "Filter already registered with a different value type; callback ignored"
+            ),
         }
     }
 
@@ -464,6 +534,83 @@ mod tests {
         assert_eq!(result, 4);
     }
 
+    #[tokio::test]
+    async fn test_equal_priority_runs_in_registration_order() {
+        let mut filter: Filter<String> = Filter::new("order_test");
+
+        filter.add(|s| async move { s + "a" }, Priority::NORMAL, None);
+        filter.add(|s| async move { s + "b" }, Priority::HIGH, None);
+        filter.add(|s| async move { s + "c" }, Priority::NORMAL, None);
+        filter.add(|s| async move { s + "d" }, Priority::HIGH, None);
+
+        assert_eq!(filter.apply(String::new()).await, "bdac");
+    }
+
+    #[tokio::test]
+    async fn test_action_short_circuit() {
+        let registry = HookRegistry::new();
+        let counter = Arc::new(AtomicI32::new(0));
+
+        let c = counter.clone();
+        registry.add_action_controlled(
+            "guarded",
+            move |_| {
+                let c = c.clone();
+                async move {
+                    c.fetch_add(1, Ordering::SeqCst);
+                    ActionFlow::Stop
+                }
+            },
+            Priority::HIGH,
+            None,
+        );
+        let c = counter.clone();
+        registry.add_action(
+            "guarded",
+            move |_| {
+                let c = c.clone();
+                async move {
+                    c.fetch_add(10, Ordering::SeqCst);
+                }
+            },
+            Priority::NORMAL,
+            None,
+        );
+
+        let flow = registry.do_action("guarded", Arc::new(())).await;
+        assert_eq!(flow, ActionFlow::Stop);
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            registry.do_action("missing", Arc::new(())).await,
+            ActionFlow::Continue
+        );
+    }
+
+    #[tokio::test]
+    async fn test_registry_filter_mismatched_type_ignored() {
+        let registry = HookRegistry::new();
+
+        registry.add_filter(
+            "title",
+            |s: String| async move { s + "!" },
+            Priority::NORMAL,
+            None,
+        );
+        registry.add_filter(
+            "title",
+            |n: i32| async move { n + 1 },
+            Priority::NORMAL,
+            None,
+        );
+
+        assert!(registry.has_filter::<String>("title"));
+        assert!(!registry.has_filter::<i32>("title"));
+        assert_eq!(
+            registry.apply_filter("title", "Hello".to_string()).await,
+            "Hello!"
+        );
+    }
+
     #[tokio::test]
     async fn test_hook_registry() {
         let registry = HookRegistry::new();
@@ -175,7 +175,7 @@ pub mod entities {
     #[derive(Clone, Copy)]
     pub struct Theme;
     /// Tenant entity marker
-    #[derive(Clone, Copy)]
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
     pub struct Tenant;
     /// Role entity marker
     #[derive(Clone, Copy)]
@@ -6,8 +6,10 @@
 pub mod api;
 pub mod config;
 pub mod context;
+pub mod correlation;
 pub mod discovery;
 pub mod error;
+pub mod feature_flags;
 pub mod health;
 pub mod hook;
 pub mod id;
@@ -22,21 +24,25 @@ pub mod types;
 // Re-exports for convenience
 pub use config::AppConfig;
 pub use context::{AppContext, RequestContext};
+pub use correlation::{current_request_id, REQUEST_ID_HEADER};
 pub use discovery::{
     ComponentManifest, ComponentType, DiscoveryConfig, DiscoveryService, DiscoverySource,
 };
 pub use error::{Error, Result};
-pub use hook::{Action, Filter, Hook, HookRegistry};
+pub use feature_flags::{FeatureFlag, FeatureFlagStore, FeatureFlags, FlagContext};
+pub use hook::{Action, ActionFlow, Filter, Hook, HookRegistry};
 pub use id::TenantId;
 pub use id::{EntityId, Id};
 pub use plugin::{Plugin, PluginInfo, PluginManager};
 pub use plugin_loader::{LoadResult, PluginLoader, PluginManifest};
-pub use tenant::Tenant;
+pub use tenant::{Tenant, TenantScope};
 
 /// The current version of RustPress
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Feature flags for conditional compilation
+///
+/// For flags toggled at runtime see [`feature_flags`].
 pub mod features {
     /// Whether metrics collection is enabled
     #[cfg(feature = "metrics")]
@@ -2,6 +2,7 @@
 //!
 //! Enables SaaS deployments with isolated tenant data.
 
+use crate::error::{Error, Result};
 use crate::id::TenantId;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -357,6 +358,48 @@ impl std::fmt::Display for QuotaViolation {
     }
 }
 
+/// The tenant whose rows a repository may read and write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TenantScope {
+    /// Multi-tenancy is disabled; queries are not filtered by tenant
+    Unscoped,
+    /// Queries only see rows belonging to this tenant
+    Tenant(TenantId),
+    /// Multi-tenancy is enabled but no tenant was identified
+    Missing,
+}
+
+impl TenantScope {
+    /// Resolve the scope from the multi-tenancy setting and the active tenant
+    pub fn resolve(multitenancy_enabled: bool, tenant_id: Option<TenantId>) -> Self {
+        match (multitenancy_enabled, tenant_id) {
+            (false, _) => Self::Unscoped,
+            (true, Some(id)) => Self::Tenant(id),
+            (true, None) => Self::Missing,
+        }
+    }
+
+    /// Tenant to filter by, or `None` when queries are unscoped
+    pub fn require(&self, operation: &str) -> Result<Option<TenantId>> {
+        match self {
+            Self::Unscoped => Ok(None),
+            Self::Tenant(id) => Ok(Some(*id)),
+            Self::Missing => Err(Error::tenant_required(operation)),
+        }
+    }
+
+    /// Check that a row owned by `owner` may be accessed in this scope
+    pub fn check_owner(&self, operation: &str, owner: Option<TenantId>) -> Result<()> {
+        match self.require(operation)? {
+            Some(id) if owner != Some(id) => Err(Error::forbidden(format!(
+                "{} on another tenant's data",
+                operation
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
 /// Tenant resolver for identifying tenants from requests
 pub trait TenantResolver: Send + Sync {
     /// Resolve tenant from subdomain
@@ -410,6 +453,28 @@ mod tests {
         assert_eq!(violations.len(), 1);
     }
 
+    #[test]
+    fn test_tenant_scope() {
+        let tenant = TenantId::new();
+
+        let unscoped = TenantScope::resolve(false, Some(tenant));
+        assert_eq!(unscoped, TenantScope::Unscoped);
+        assert_eq!(unscoped.require("list").unwrap(), None);
+        assert!(unscoped.check_owner("update", None).is_ok());
+
+        let scoped = TenantScope::resolve(true, Some(tenant));
+        assert_eq!(scoped.require("list").unwrap(), Some(tenant));
+        assert!(scoped.check_owner("update", Some(tenant)).is_ok());
+        assert!(scoped.check_owner("update", Some(TenantId::new())).is_err());
+        assert!(scoped.check_owner("update", None).is_err());
+
+        let missing = TenantScope::resolve(true, None);
+        assert!(matches!(
+            missing.require("list"),
+            Err(Error::TenantRequired { .. })
+        ));
+    }
+
     #[test]
     fn test_unlimited_quotas() {
         let quotas = TenantQuotas::unlimited();
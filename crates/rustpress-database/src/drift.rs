@@ -0,0 +1,635 @@
+//! Schema drift detection.
+//!
+//! Compares a live schema against the end state the migrations produce and
+//! reports what differs, so manual changes and half-applied migrations show
+//! up before they cause trouble. Differences are split into structural ones
+//! (missing or changed tables, columns, constraints, indexes) and benign ones
+//! (collation), and [`SchemaDiff::fix_sql`] turns the structural drift into
+//! corrective SQL for an operator to review.
+
+use rustpress_core::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use std::collections::BTreeMap;
+
+/// Tables that belong to the tooling rather than the application schema
+const IGNORED_TABLES: &[&str] = &["_migrations"];
+
+/// Table, column, type, nullable, default, collation
+type ColumnRow = (String, String, String, bool, Option<String>, Option<String>);
+
+/// A column as it exists in a schema
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnInfo {
+    /// Type as printed by `format_type`, e.g. `character varying(255)`
+    pub data_type: String,
+    pub nullable: bool,
+    pub default: Option<String>,
+    /// Collation, when it differs from the database default
+    pub collation: Option<String>,
+}
+
+/// A table's columns, constraints and indexes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableInfo {
+    /// Columns in ordinal order
+    pub columns: Vec<(String, ColumnInfo)>,
+    /// Constraint name to definition
+    pub constraints: BTreeMap<String, String>,
+    /// Index name to definition, excluding indexes that back constraints
+    pub indexes: BTreeMap<String, String>,
+}
+
+impl TableInfo {
+    fn column(&self, name: &str) -> Option<&ColumnInfo> {
+        self.columns.iter().find(|(n, _)| n == name).map(|(_, c)| c)
+    }
+}
+
+/// Tables in a schema
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaSnapshot {
+    pub tables: BTreeMap<String, TableInfo>,
+}
+
+impl SchemaSnapshot {
+    /// Read the tables of `schema` from the catalog
+    pub async fn capture(conn: &mut PgConnection, schema: &str) -> Result<Self> {
+        let mut snapshot = Self::default();
+
+        let columns: Vec<ColumnRow> = sqlx::query_as(
+            r#"
+                SELECT c.relname::text, a.attname::text, format_type(a.atttypid, a.atttypmod),
+                       NOT a.attnotnull, pg_get_expr(d.adbin, d.adrelid),
+                       CASE WHEN a.attcollation <> t.typcollation THEN co.collname::text END
+                FROM pg_attribute a
+                JOIN pg_class c ON c.oid = a.attrelid
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                JOIN pg_type t ON t.oid = a.atttypid
+                LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+                LEFT JOIN pg_collation co ON co.oid = a.attcollation
+                WHERE n.nspname = $1 AND c.relkind IN ('r', 'p')
+                  AND a.attnum > 0 AND NOT a.attisdropped
+                ORDER BY c.relname, a.attnum
+                "#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| Error::database_with_source("Failed to read columns", e))?;
+
+        for (table, column, data_type, nullable, default, collation) in columns {
+            if IGNORED_TABLES.contains(&table.as_str()) {
+                continue;
+            }
+            snapshot.tables.entry(table).or_default().columns.push((
+                column,
+                ColumnInfo {
+                    data_type,
+                    nullable,
+                    default: default.map(|d| unqualify(&d, schema)),
+                    collation,
+                },
+            ));
+        }
+
+        let constraints: Vec<(String, String, String)> = sqlx::query_as(
+            r#"
+            SELECT c.relname::text, k.conname::text, pg_get_constraintdef(k.oid)
+            FROM pg_constraint k
+            JOIN pg_class c ON c.oid = k.conrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1
+            "#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| Error::database_with_source("Failed to read constraints", e))?;
+
+        for (table, name, definition) in constraints {
+            if let Some(info) = snapshot.tables.get_mut(&table) {
+                info.constraints
+                    .insert(name, unqualify(&definition, schema));
+            }
+        }
+
+        let indexes: Vec<(String, String, String)> = sqlx::query_as(
+            r#"
+            SELECT t.relname::text, i.relname::text, pg_get_indexdef(i.oid)
+            FROM pg_index x
+            JOIN pg_class i ON i.oid = x.indexrelid
+            JOIN pg_class t ON t.oid = x.indrelid
+            JOIN pg_namespace n ON n.oid = t.relnamespace
+            WHERE n.nspname = $1
+              AND NOT EXISTS (SELECT 1 FROM pg_constraint k WHERE k.conindid = x.indexrelid)
+            "#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| Error::database_with_source("Failed to read indexes", e))?;
+
+        for (table, name, definition) in indexes {
+            if let Some(info) = snapshot.tables.get_mut(&table) {
+                info.indexes.insert(name, unqualify(&definition, schema));
+            }
+        }
+
+        Ok(snapshot)
+    }
+}
+
+/// Strip `schema.` qualifiers so definitions from different schemas compare equal
+fn unqualify(definition: &str, schema: &str) -> String {
+    definition
+        .replace(&format!("{}.", quote_ident(schema)), "")
+        .replace(&format!("{}.", schema), "")
+}
+
+/// Whether a difference changes the shape of the schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Missing, unexpected or changed objects
+    Structural,
+    /// Differences that don't affect what can be stored, like collation
+    Benign,
+}
+
+/// Kind of difference between the expected and live schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftKind {
+    MissingTable,
+    UnexpectedTable,
+    MissingColumn,
+    UnexpectedColumn,
+    ColumnType,
+    ColumnNullability,
+    ColumnDefault,
+    ColumnCollation,
+    MissingConstraint,
+    UnexpectedConstraint,
+    ConstraintDefinition,
+    MissingIndex,
+    UnexpectedIndex,
+    IndexDefinition,
+}
+
+impl DriftKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            DriftKind::ColumnCollation => Severity::Benign,
+            _ => Severity::Structural,
+        }
+    }
+}
+
+/// One difference between the expected and live schema
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Drift {
+    pub kind: DriftKind,
+    pub severity: Severity,
+    pub table: String,
+    /// Column, constraint or index name; `None` for whole tables
+    pub object: Option<String>,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+impl Drift {
+    fn new(kind: DriftKind, table: &str, object: Option<&str>) -> Self {
+        Self {
+            kind,
+            severity: kind.severity(),
+            table: table.to_string(),
+            object: object.map(str::to_string),
+            expected: None,
+            actual: None,
+        }
+    }
+
+    fn values(mut self, expected: Option<String>, actual: Option<String>) -> Self {
+        self.expected = expected;
+        self.actual = actual;
+        self
+    }
+}
+
+/// Differences between the schema the migrations produce and the live one
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchemaDiff {
+    pub drift: Vec<Drift>,
+    #[serde(skip)]
+    expected: SchemaSnapshot,
+}
+
+impl SchemaDiff {
+    /// Compare the live schema against the expected one
+    pub fn compare(expected: SchemaSnapshot, actual: &SchemaSnapshot) -> Self {
+        let mut drift = Vec::new();
+
+        for (name, table) in &expected.tables {
+            match actual.tables.get(name) {
+                Some(live) => compare_table(name, table, live, &mut drift),
+                None => drift.push(Drift::new(DriftKind::MissingTable, name, None)),
+            }
+        }
+        for name in actual.tables.keys() {
+            if !expected.tables.contains_key(name) {
+                drift.push(Drift::new(DriftKind::UnexpectedTable, name, None));
+            }
+        }
+
+        Self { drift, expected }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.drift.is_empty()
+    }
+
+    /// Whether any difference goes beyond benign ones
+    pub fn has_structural(&self) -> bool {
+        self.drift
+            .iter()
+            .any(|d| d.severity == Severity::Structural)
+    }
+
+    /// SQL that brings the live schema back in line with the migrations.
+    ///
+    /// Nothing is applied. Statements that would drop data (unexpected
+    /// tables and columns) are emitted commented out, and benign drift is
+    /// left alone.
+    pub fn fix_sql(&self) -> Vec<String> {
+        let mut sql = Vec::new();
+
+        for drift in &self.drift {
+            let table = quote_ident(&drift.table);
+            let object = drift.object.as_deref().map(quote_ident).unwrap_or_default();
+            let expected_table = self.expected.tables.get(&drift.table);
+            let expected_column = || {
+                expected_table
+                    .zip(drift.object.as_deref())
+                    .and_then(|(t, c)| t.column(c))
+            };
+
+            match drift.kind {
+                DriftKind::MissingTable => {
+                    if let Some(info) = expected_table {
+                        sql.extend(create_table_sql(&drift.table, info));
+                    }
+                }
+                DriftKind::UnexpectedTable => {
+                    sql.push(format!("-- DROP TABLE {};", table));
+                }
+                DriftKind::MissingColumn => {
+                    if let Some(column) = expected_column() {
+                        sql.push(format!(
+                            "ALTER TABLE {} ADD COLUMN {};",
+                            table,
+                            column_sql(drift.object.as_deref().unwrap_or_default(), column)
+                        ));
+                    }
+                }
+                DriftKind::UnexpectedColumn => {
+                    sql.push(format!("-- ALTER TABLE {} DROP COLUMN {};", table, object));
+                }
+                DriftKind::ColumnType => {
+                    if let Some(column) = expected_column() {
+                        sql.push(format!(
+                            "ALTER TABLE {} ALTER COLUMN {} TYPE {} USING {}::{};",
+                            table, object, column.data_type, object, column.data_type
+                        ));
+                    }
+                }
+                DriftKind::ColumnNullability => {
+                    if let Some(column) = expected_column() {
+                        let action = if column.nullable {
+                            "DROP NOT NULL"
+                        } else {
+                            "SET NOT NULL"
+                        };
+                        sql.push(format!(
+                            "ALTER TABLE {} ALTER COLUMN {} {};",
+                            table, object, action
+                        ));
+                    }
+                }
+                DriftKind::ColumnDefault => match &drift.expected {
+                    Some(default) => sql.push(format!(
+                        "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {};",
+                        table, object, default
+                    )),
+                    None => sql.push(format!(
+                        "ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT;",
+                        table, object
+                    )),
+                },
+                DriftKind::ColumnCollation => {}
+                DriftKind::MissingConstraint => {
+                    if let Some(definition) = &drift.expected {
+                        sql.push(format!(
+                            "ALTER TABLE {} ADD CONSTRAINT {} {};",
+                            table, object, definition
+                        ));
+                    }
+                }
+                DriftKind::UnexpectedConstraint => {
+                    sql.push(format!("ALTER TABLE {} DROP CONSTRAINT {};", table, object));
+                }
+                DriftKind::ConstraintDefinition => {
+                    if let Some(definition) = &drift.expected {
+                        sql.push(format!("ALTER TABLE {} DROP CONSTRAINT {};", table, object));
+                        sql.push(format!(
+                            "ALTER TABLE {} ADD CONSTRAINT {} {};",
+                            table, object, definition
+                        ));
+                    }
+                }
+                DriftKind::MissingIndex => {
+                    if let Some(definition) = &drift.expected {
+                        sql.push(format!("{};", definition));
+                    }
+                }
+                DriftKind::UnexpectedIndex => {
+                    sql.push(format!("DROP INDEX {};", object));
+                }
+                DriftKind::IndexDefinition => {
+                    if let Some(definition) = &drift.expected {
+                        sql.push(format!("DROP INDEX {};", object));
+                        sql.push(format!("{};", definition));
+                    }
+                }
+            }
+        }
+
+        sql
+    }
+}
+
+fn compare_table(name: &str, expected: &TableInfo, live: &TableInfo, drift: &mut Vec<Drift>) {
+    for (column, want) in &expected.columns {
+        let Some(have) = live.column(column) else {
+            drift.push(Drift::new(DriftKind::MissingColumn, name, Some(column)));
+            continue;
+        };
+        let object = Some(column.as_str());
+
+        if want.data_type != have.data_type {
+            drift.push(
+                Drift::new(DriftKind::ColumnType, name, object)
+                    .values(Some(want.data_type.clone()), Some(have.data_type.clone())),
+            );
+        }
+        if want.nullable != have.nullable {
+            drift.push(
+                Drift::new(DriftKind::ColumnNullability, name, object)
+                    .values(Some(nullability(want)), Some(nullability(have))),
+            );
+        }
+        if want.default != have.default {
+            drift.push(
+                Drift::new(DriftKind::ColumnDefault, name, object)
+                    .values(want.default.clone(), have.default.clone()),
+            );
+        }
+        if want.collation != have.collation {
+            drift.push(
+                Drift::new(DriftKind::ColumnCollation, name, object)
+                    .values(want.collation.clone(), have.collation.clone()),
+            );
+        }
+    }
+    for (column, _) in &live.columns {
+        if expected.column(column).is_none() {
+            drift.push(Drift::new(DriftKind::UnexpectedColumn, name, Some(column)));
+        }
+    }
+
+    compare_definitions(
+        name,
+        &expected.constraints,
+        &live.constraints,
+        [
+            DriftKind::MissingConstraint,
+            DriftKind::UnexpectedConstraint,
+            DriftKind::ConstraintDefinition,
+        ],
+        drift,
+    );
+    compare_definitions(
+        name,
+        &expected.indexes,
+        &live.indexes,
+        [
+            DriftKind::MissingIndex,
+            DriftKind::UnexpectedIndex,
+            DriftKind::IndexDefinition,
+        ],
+        drift,
+    );
+}
+
+/// Compare named definitions; `kinds` is `[missing, unexpected, changed]`
+fn compare_definitions(
+    table: &str,
+    expected: &BTreeMap<String, String>,
+    live: &BTreeMap<String, String>,
+    [missing, unexpected, changed]: [DriftKind; 3],
+    drift: &mut Vec<Drift>,
+) {
+    for (name, want) in expected {
+        match live.get(name) {
+            None => {
+                drift.push(Drift::new(missing, table, Some(name)).values(Some(want.clone()), None))
+            }
+            Some(have) if have != want => drift.push(
+                Drift::new(changed, table, Some(name))
+                    .values(Some(want.clone()), Some(have.clone())),
+            ),
+            Some(_) => {}
+        }
+    }
+    for (name, have) in live {
+        if !expected.contains_key(name) {
+            drift.push(Drift::new(unexpected, table, Some(name)).values(None, Some(have.clone())));
+        }
+    }
+}
+
+fn nullability(column: &ColumnInfo) -> String {
+    if column.nullable { "NULL" } else { "NOT NULL" }.to_string()
+}
+
+fn column_sql(name: &str, column: &ColumnInfo) -> String {
+    let mut sql = format!("{} {}", quote_ident(name), column.data_type);
+    if let Some(collation) = &column.collation {
+        sql.push_str(&format!(" COLLATE {}", quote_ident(collation)));
+    }
+    if !column.nullable {
+        sql.push_str(" NOT NULL");
+    }
+    if let Some(default) = &column.default {
+        sql.push_str(&format!(" DEFAULT {}", default));
+    }
+    sql
+}
+
+fn create_table_sql(name: &str, table: &TableInfo) -> Vec<String> {
+    let table_name = quote_ident(name);
+    let columns: Vec<String> = table
+        .columns
+        .iter()
+        .map(|(column, info)| format!("    {}", column_sql(column, info)))
+        .collect();
+
+    let mut sql = vec![format!(
+        "CREATE TABLE {} (\n{}\n);",
+        table_name,
+        columns.join(",\n")
+    )];
+    for (constraint, definition) in &table.constraints {
+        sql.push(format!(
+            "ALTER TABLE {} ADD CONSTRAINT {} {};",
+            table_name,
+            quote_ident(constraint),
+            definition
+        ));
+    }
+    for definition in table.indexes.values() {
+        sql.push(format!("{};", definition));
+    }
+    sql
+}
+
+/// Quote an identifier when it isn't a plain lowercase name
+fn quote_ident(ident: &str) -> String {
+    let plain = ident
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && ident
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if plain {
+        ident.to_string()
+    } else {
+        format!("\"{}\"", ident.replace('"', "\"\""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(data_type: &str, nullable: bool) -> ColumnInfo {
+        ColumnInfo {
+            data_type: data_type.to_string(),
+            nullable,
+            default: None,
+            collation: None,
+        }
+    }
+
+    fn users() -> TableInfo {
+        TableInfo {
+            columns: vec![
+                ("id".to_string(), column("uuid", false)),
+                ("email".to_string(), column("character varying(255)", false)),
+            ],
+            constraints: BTreeMap::from([(
+                "users_pkey".to_string(),
+                "PRIMARY KEY (id)".to_string(),
+            )]),
+            indexes: BTreeMap::from([(
+                "idx_users_email".to_string(),
+                "CREATE INDEX idx_users_email ON users USING btree (email)".to_string(),
+            )]),
+        }
+    }
+
+    fn snapshot(tables: Vec<(&str, TableInfo)>) -> SchemaSnapshot {
+        SchemaSnapshot {
+            tables: tables
+                .into_iter()
+                .map(|(n, t)| (n.to_string(), t))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_identical_schemas_have_no_drift() {
+        let expected = snapshot(vec![("users", users())]);
+        let diff = SchemaDiff::compare(expected.clone(), &expected);
+        assert!(diff.is_empty());
+        assert!(diff.fix_sql().is_empty());
+    }
+
+    #[test]
+    fn test_structural_and_benign_drift() {
+        let expected = snapshot(vec![("users", users()), ("posts", users())]);
+
+        let mut live_users = users();
+        live_users.columns[1].1.nullable = true;
+        live_users.columns[1].1.collation = Some("C".to_string());
+        live_users
+            .columns
+            .push(("legacy".to_string(), column("text", true)));
+        live_users.indexes.clear();
+        let live = snapshot(vec![
+            ("users", live_users),
+            ("old_stuff", TableInfo::default()),
+        ]);
+
+        let diff = SchemaDiff::compare(expected, &live);
+        let kinds: Vec<_> = diff.drift.iter().map(|d| d.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                DriftKind::MissingTable,
+                DriftKind::ColumnNullability,
+                DriftKind::ColumnCollation,
+                DriftKind::UnexpectedColumn,
+                DriftKind::MissingIndex,
+                DriftKind::UnexpectedTable,
+            ]
+        );
+        assert!(diff.has_structural());
+
+        let sql = diff.fix_sql();
+        assert!(sql[0].starts_with("CREATE TABLE posts (\n    id uuid NOT NULL,"));
+        assert!(sql.contains(
+            &"ALTER TABLE posts ADD CONSTRAINT users_pkey PRIMARY KEY (id);".to_string()
+        ));
+        assert!(sql.contains(&"ALTER TABLE users ALTER COLUMN email SET NOT NULL;".to_string()));
+        assert!(sql.contains(&"-- ALTER TABLE users DROP COLUMN legacy;".to_string()));
+        assert!(
+            sql.contains(&"CREATE INDEX idx_users_email ON users USING btree (email);".to_string())
+        );
+        assert!(sql.contains(&"-- DROP TABLE old_stuff;".to_string()));
+        assert!(!sql.iter().any(|s| s.contains("COLLATE")));
+    }
+
+    #[test]
+    fn test_collation_only_drift_is_benign() {
+        let expected = snapshot(vec![("users", users())]);
+        let mut live_users = users();
+        live_users.columns[1].1.collation = Some("C".to_string());
+        let live = snapshot(vec![("users", live_users)]);
+
+        let diff = SchemaDiff::compare(expected, &live);
+        assert!(!diff.is_empty());
+        assert!(!diff.has_structural());
+    }
+
+    #[test]
+    fn test_unqualify_and_quote() {
+        assert_eq!(
+            unqualify("nextval('scratch.posts_id_seq'::regclass)", "scratch"),
+            "nextval('posts_id_seq'::regclass)"
+        );
+        assert_eq!(quote_ident("users"), "users");
+        assert_eq!(quote_ident("Users"), "\"Users\"");
+    }
+}
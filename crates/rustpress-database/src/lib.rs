@@ -30,6 +30,7 @@
 //! - Point 54: Redirects table for URL management
 //! - Point 55: Multi-site tables structure for network installations
 
+pub mod drift;
 pub mod migration;
 pub mod models;
 pub mod pool;
@@ -37,6 +38,7 @@ pub mod repository;
 pub mod schema;
 pub mod transaction;
 
+pub use drift::{Drift, DriftKind, SchemaDiff, SchemaSnapshot, Severity};
 pub use migration::Migrator;
 pub use pool::{DatabasePool, PoolConfig};
 pub use schema::*;
@@ -1,7 +1,8 @@
 //! Database migration system.
 
+use crate::drift::{SchemaDiff, SchemaSnapshot};
 use rustpress_core::error::{Error, Result};
-use sqlx::PgPool;
+use sqlx::{Connection, Executor, PgPool};
 #[allow(unused_imports)]
 use std::path::Path;
 
@@ -122,6 +123,67 @@ impl Migrator {
         Ok(statuses)
     }
 
+    /// Compare the live schema against the state the migrations produce
+    pub async fn diff(&self, pool: &PgPool) -> Result<SchemaDiff> {
+        let expected = self.expected_schema(pool).await?;
+
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| Error::database_with_source("Failed to acquire connection", e))?;
+        let schema: String = sqlx::query_scalar("SELECT current_schema()")
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| Error::database_with_source("Failed to read current schema", e))?;
+        let actual = SchemaSnapshot::capture(&mut conn, &schema).await?;
+
+        Ok(SchemaDiff::compare(expected, &actual))
+    }
+
+    /// Schema the migrations produce on an empty database.
+    ///
+    /// The migrations are replayed into a scratch schema inside a transaction
+    /// that is always rolled back, so nothing is left behind.
+    pub async fn expected_schema(&self, pool: &PgPool) -> Result<SchemaSnapshot> {
+        const SCRATCH_SCHEMA: &str = "_rustpress_expected";
+
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| Error::database_with_source("Failed to acquire connection", e))?;
+        let mut tx = conn
+            .begin()
+            .await
+            .map_err(|e| Error::database_with_source("Failed to begin transaction", e))?;
+
+        tx.execute(
+            format!(
+                "CREATE SCHEMA {0}; SET LOCAL search_path TO {0}, public",
+                SCRATCH_SCHEMA
+            )
+            .as_str(),
+        )
+        .await
+        .map_err(|e| Error::database_with_source("Failed to create scratch schema", e))?;
+
+        for migration in &self.migrations {
+            tx.execute(migration.sql.as_str()).await.map_err(|e| {
+                Error::database_with_source(
+                    format!("Migration {} failed in scratch schema", migration.version),
+                    e,
+                )
+            })?;
+        }
+
+        let snapshot = SchemaSnapshot::capture(&mut tx, SCRATCH_SCHEMA).await?;
+
+        tx.rollback()
+            .await
+            .map_err(|e| Error::database_with_source("Failed to roll back scratch schema", e))?;
+
+        Ok(snapshot)
+    }
+
     async fn ensure_migrations_table(&self, pool: &PgPool) -> Result<()> {
         sqlx::query(
             r#"
@@ -155,13 +217,11 @@ impl Migrator {
             "Applying migration"
         );
 
-        // Execute the migration SQL
-        sqlx::query(&migration.sql)
-            .execute(pool)
-            .await
-            .map_err(|e| {
-                Error::database_with_source(format!("Migration {} failed", migration.version), e)
-            })?;
+        // Execute the migration SQL; a plain string runs through the simple
+        // query protocol, which allows several statements per migration
+        pool.execute(migration.sql.as_str()).await.map_err(|e| {
+            Error::database_with_source(format!("Migration {} failed", migration.version), e)
+        })?;
 
         // Record the migration
         sqlx::query("INSERT INTO _migrations (version, name) VALUES ($1, $2)")
@@ -525,6 +585,112 @@ pub fn create_initial_migrations() -> Vec<Migration> {
             CREATE INDEX idx_audit_created ON audit_logs(created_at);
             "#,
         ),
+        Migration::new(
+            13,
+            "add_jobs_claim_index",
+            r#"
+            CREATE INDEX idx_jobs_claim ON jobs(queue, priority DESC, created_at)
+                WHERE status = 'pending';
+            "#,
+        ),
+        Migration::new(
+            14,
+            "create_failed_jobs_table",
+            r#"
+            CREATE TABLE failed_jobs (
+                id UUID PRIMARY KEY,
+                tenant_id UUID,
+                queue VARCHAR(100) NOT NULL,
+                job_type VARCHAR(255) NOT NULL,
+                payload JSONB NOT NULL,
+                priority INT NOT NULL DEFAULT 0,
+                attempts INT NOT NULL,
+                max_attempts INT NOT NULL,
+                last_error TEXT NOT NULL,
+                failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                created_at TIMESTAMPTZ NOT NULL
+            );
+
+            CREATE INDEX idx_failed_jobs_queue ON failed_jobs(queue, failed_at);
+            "#,
+        ),
+        Migration::new(
+            15,
+            "add_jobs_dedup_key",
+            r#"
+            ALTER TABLE jobs ADD COLUMN dedup_key VARCHAR(255);
+
+            CREATE UNIQUE INDEX idx_jobs_dedup_key ON jobs(dedup_key)
+                WHERE status = 'pending';
+            "#,
+        ),
+        Migration::new(
+            16,
+            "create_scheduled_task_runs_table",
+            r#"
+            CREATE TABLE scheduled_task_runs (
+                name VARCHAR(255) PRIMARY KEY,
+                last_run_at TIMESTAMPTZ NOT NULL
+            );
+            "#,
+        ),
+        Migration::new(
+            17,
+            "create_event_log_tables",
+            r#"
+            CREATE TABLE event_log (
+                "offset" BIGSERIAL PRIMARY KEY,
+                event_id UUID NOT NULL UNIQUE,
+                event_type VARCHAR(255) NOT NULL,
+                tenant_id UUID,
+                event JSONB NOT NULL,
+                occurred_at TIMESTAMPTZ NOT NULL,
+                recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );
+
+            CREATE INDEX idx_event_log_occurred ON event_log(occurred_at);
+
+            CREATE TABLE event_subscriptions (
+                name VARCHAR(255) PRIMARY KEY,
+                acked_offset BIGINT NOT NULL,
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );
+            "#,
+        ),
+        Migration::new(
+            18,
+            "scope_unique_slugs_to_live_rows",
+            r#"
+            ALTER TABLE posts DROP CONSTRAINT unique_slug_per_tenant;
+            CREATE UNIQUE INDEX unique_slug_per_tenant ON posts(tenant_id, slug)
+                WHERE deleted_at IS NULL;
+
+            ALTER TABLE pages DROP CONSTRAINT unique_page_slug_per_tenant;
+            CREATE UNIQUE INDEX unique_page_slug_per_tenant ON pages(tenant_id, slug)
+                WHERE deleted_at IS NULL;
+
+            CREATE INDEX idx_posts_deleted ON posts(deleted_at) WHERE deleted_at IS NOT NULL;
+            CREATE INDEX idx_pages_deleted ON pages(deleted_at) WHERE deleted_at IS NOT NULL;
+            CREATE INDEX idx_media_deleted ON media(deleted_at) WHERE deleted_at IS NOT NULL;
+            "#,
+        ),
+        Migration::new(
+            19,
+            "create_posts_search_index",
+            r#"
+            CREATE INDEX idx_posts_search ON posts USING GIN (
+                to_tsvector('english'::regconfig,
+                    coalesce(title, '') || ' ' || coalesce(excerpt, '') || ' ' || coalesce(content, ''))
+            );
+            "#,
+        ),
+        Migration::new(
+            20,
+            "add_jobs_request_id",
+            r#"
+            ALTER TABLE jobs ADD COLUMN request_id VARCHAR(128);
+            "#,
+        ),
     ]
 }
 
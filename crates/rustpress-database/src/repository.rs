@@ -3,15 +3,29 @@
 use rustpress_core::error::{Error, Result};
 use rustpress_core::id::TenantId;
 use rustpress_core::service::{ListParams, ListResult, SortOrder};
+use rustpress_core::tenant::TenantScope;
+use sqlx::postgres::PgRow;
 use sqlx::PgPool;
 use std::marker::PhantomData;
 use uuid::Uuid;
 
-/// Generic PostgreSQL repository
+/// Generic PostgreSQL repository over a table with a `tenant_id` column.
+///
+/// Every query is limited to the repository's [`TenantScope`]: with a
+/// tenant, a bound `tenant_id = $n` condition is added; on single-tenant
+/// installs nothing is added; and with multi-tenancy enabled but no tenant
+/// identified, queries fail with [`Error::TenantRequired`].
+///
+/// Tables with a nullable `deleted_at` column can opt into soft deletes, after
+/// which queries skip soft-deleted rows unless [`include_deleted`] is set.
+///
+/// [`include_deleted`]: PgRepository::include_deleted
 pub struct PgRepository<T> {
     pool: PgPool,
     table_name: String,
-    tenant_id: Option<TenantId>,
+    scope: TenantScope,
+    soft_deletes: bool,
+    include_deleted: bool,
     _phantom: PhantomData<T>,
 }
 
@@ -20,13 +34,33 @@ impl<T> PgRepository<T> {
         Self {
             pool,
             table_name: table_name.into(),
-            tenant_id: None,
+            scope: TenantScope::Unscoped,
+            soft_deletes: false,
+            include_deleted: false,
             _phantom: PhantomData,
         }
     }
 
+    /// Treat the table's `deleted_at` column as a soft-delete marker
+    pub fn with_soft_deletes(mut self) -> Self {
+        self.soft_deletes = true;
+        self
+    }
+
+    /// Include soft-deleted rows in queries
+    pub fn include_deleted(mut self) -> Self {
+        self.include_deleted = true;
+        self
+    }
+
     pub fn with_tenant(mut self, tenant_id: TenantId) -> Self {
-        self.tenant_id = Some(tenant_id);
+        self.scope = TenantScope::Tenant(tenant_id);
+        self
+    }
+
+    /// Limit queries to `scope`, typically from `AppContext::tenant_scope`
+    pub fn with_scope(mut self, scope: TenantScope) -> Self {
+        self.scope = scope;
         self
     }
 
@@ -38,16 +72,193 @@ impl<T> PgRepository<T> {
         &self.table_name
     }
 
-    /// Build a WHERE clause with tenant filtering
-    #[allow(dead_code)]
-    fn tenant_filter(&self, alias: Option<&str>) -> String {
-        if let Some(tenant_id) = &self.tenant_id {
-            let prefix = alias.map(|a| format!("{}.", a)).unwrap_or_default();
-            format!("{}tenant_id = '{}'", prefix, tenant_id)
+    pub fn scope(&self) -> TenantScope {
+        self.scope
+    }
+
+    /// Build a WHERE condition with tenant filtering, using bind parameter
+    /// `$param` for the tenant ID when one is returned
+    pub fn tenant_filter(&self, operation: &str, param: usize) -> Result<(String, Option<Uuid>)> {
+        Ok(match self.scope.require(operation)? {
+            Some(tenant_id) => (
+                format!("tenant_id = ${}", param),
+                Some(tenant_id.into_uuid()),
+            ),
+            None => ("1=1".to_string(), None),
+        })
+    }
+
+    /// Build a WHERE condition excluding soft-deleted rows
+    pub fn deleted_filter(&self) -> &'static str {
+        if self.soft_deletes && !self.include_deleted {
+            "deleted_at IS NULL"
         } else {
-            "1=1".to_string()
+            "1=1"
         }
     }
+
+    /// Reject writing a row owned by `owner` from outside its tenant
+    pub fn check_owner(&self, operation: &str, owner: Option<Uuid>) -> Result<()> {
+        self.scope
+            .check_owner(operation, owner.map(TenantId::from_uuid))
+    }
+}
+
+impl<T> PgRepository<T>
+where
+    T: for<'r> sqlx::FromRow<'r, PgRow> + Send + Unpin,
+{
+    /// Find a row by ID within the tenant
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<T>> {
+        let (condition, tenant) = self.tenant_filter("find", 2)?;
+        let query = format!(
+            "SELECT * FROM {} WHERE id = $1 AND {} AND {}",
+            self.table_name,
+            condition,
+            self.deleted_filter()
+        );
+
+        let mut q = sqlx::query_as::<_, T>(&query).bind(id);
+        if let Some(tenant) = tenant {
+            q = q.bind(tenant);
+        }
+        q.fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::database_with_source("Failed to find row", e))
+    }
+
+    /// Check if a row exists within the tenant
+    pub async fn exists(&self, id: Uuid) -> Result<bool> {
+        let (condition, tenant) = self.tenant_filter("exists", 2)?;
+        let query = format!(
+            "SELECT EXISTS(SELECT 1 FROM {} WHERE id = $1 AND {} AND {})",
+            self.table_name,
+            condition,
+            self.deleted_filter()
+        );
+
+        let mut q = sqlx::query_as::<_, (bool,)>(&query).bind(id);
+        if let Some(tenant) = tenant {
+            q = q.bind(tenant);
+        }
+        let (exists,) = q
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| Error::database_with_source("Failed to check row", e))?;
+        Ok(exists)
+    }
+
+    /// Count rows within the tenant
+    pub async fn count(&self) -> Result<u64> {
+        let (condition, tenant) = self.tenant_filter("count", 1)?;
+        let query = format!(
+            "SELECT COUNT(*) FROM {} WHERE {} AND {}",
+            self.table_name,
+            condition,
+            self.deleted_filter()
+        );
+
+        let mut q = sqlx::query_as::<_, (i64,)>(&query);
+        if let Some(tenant) = tenant {
+            q = q.bind(tenant);
+        }
+        let (count,) = q
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| Error::database_with_source("Failed to count rows", e))?;
+        Ok(count as u64)
+    }
+
+    /// Permanently delete a row within the tenant. Rows of other tenants are
+    /// reported as not found rather than revealing that they exist.
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let (condition, tenant) = self.tenant_filter("delete", 2)?;
+        let query = format!(
+            "DELETE FROM {} WHERE id = $1 AND {}",
+            self.table_name, condition
+        );
+
+        let mut q = sqlx::query(&query).bind(id);
+        if let Some(tenant) = tenant {
+            q = q.bind(tenant);
+        }
+        let result = q
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::database_with_source("Failed to delete row", e))?;
+        if result.rows_affected() == 0 {
+            return Err(Error::not_found(&self.table_name, id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Mark a row as deleted without removing it
+    pub async fn soft_delete(&self, id: Uuid) -> Result<()> {
+        let (condition, tenant) = self.tenant_filter("soft delete", 2)?;
+        let query = format!(
+            "UPDATE {} SET deleted_at = NOW(), updated_at = NOW() WHERE id = $1 AND {} AND deleted_at IS NULL",
+            self.table_name, condition
+        );
+
+        let mut q = sqlx::query(&query).bind(id);
+        if let Some(tenant) = tenant {
+            q = q.bind(tenant);
+        }
+        let result = q
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::database_with_source("Failed to soft delete row", e))?;
+        if result.rows_affected() == 0 {
+            return Err(Error::not_found(&self.table_name, id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Restore a soft-deleted row. Fails with [`Error::Duplicate`] if a live
+    /// row has since taken one of its unique values, such as its slug.
+    pub async fn restore(&self, id: Uuid) -> Result<()> {
+        let (condition, tenant) = self.tenant_filter("restore", 2)?;
+        let query = format!(
+            "UPDATE {} SET deleted_at = NULL, updated_at = NOW() WHERE id = $1 AND {} AND deleted_at IS NOT NULL",
+            self.table_name, condition
+        );
+
+        let mut q = sqlx::query(&query).bind(id);
+        if let Some(tenant) = tenant {
+            q = q.bind(tenant);
+        }
+        let result = q.execute(&self.pool).await.map_err(|e| match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => Error::Duplicate {
+                entity_type: self.table_name.clone(),
+                field: db.constraint().unwrap_or_default().to_string(),
+            },
+            _ => Error::database_with_source("Failed to restore row", e),
+        })?;
+        if result.rows_affected() == 0 {
+            return Err(Error::not_found(&self.table_name, id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Permanently delete rows soft-deleted more than `retention` ago,
+    /// returning how many were removed
+    pub async fn purge_deleted(&self, retention: chrono::Duration) -> Result<u64> {
+        let (condition, tenant) = self.tenant_filter("purge", 2)?;
+        let query = format!(
+            "DELETE FROM {} WHERE deleted_at < $1 AND {}",
+            self.table_name, condition
+        );
+
+        let mut q = sqlx::query(&query).bind(chrono::Utc::now() - retention);
+        if let Some(tenant) = tenant {
+            q = q.bind(tenant);
+        }
+        let result = q
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::database_with_source("Failed to purge deleted rows", e))?;
+        Ok(result.rows_affected())
+    }
 }
 
 impl<T> Clone for PgRepository<T> {
@@ -55,7 +266,9 @@ impl<T> Clone for PgRepository<T> {
         Self {
             pool: self.pool.clone(),
             table_name: self.table_name.clone(),
-            tenant_id: self.tenant_id,
+            scope: self.scope,
+            soft_deletes: self.soft_deletes,
+            include_deleted: self.include_deleted,
             _phantom: PhantomData,
         }
     }
@@ -66,6 +279,15 @@ pub trait Entity: Send + Sync {
     fn id(&self) -> Uuid;
 }
 
+/// Entity stored in a table with a `deleted_at` soft-delete column
+pub trait SoftDeletable: Entity {
+    fn deleted_at(&self) -> Option<chrono::DateTime<chrono::Utc>>;
+
+    fn is_deleted(&self) -> bool {
+        self.deleted_at().is_some()
+    }
+}
+
 /// Helper to build dynamic queries
 pub struct QueryHelper;
 
@@ -107,6 +329,347 @@ impl QueryHelper {
     }
 }
 
+/// Full-text search helpers
+pub mod search {
+    use super::*;
+    use sqlx::Row;
+
+    /// Default text search configuration, matching the indexes in the migrations
+    pub const DEFAULT_LANGUAGE: &str = "english";
+
+    /// A search result with its relevance and highlighted snippet
+    #[derive(Debug, Clone)]
+    pub struct SearchHit<T> {
+        pub row: T,
+        pub rank: f32,
+        pub headline: String,
+    }
+
+    impl<'r, T> sqlx::FromRow<'r, PgRow> for SearchHit<T>
+    where
+        T: sqlx::FromRow<'r, PgRow>,
+    {
+        fn from_row(row: &'r PgRow) -> std::result::Result<Self, sqlx::Error> {
+            Ok(Self {
+                row: T::from_row(row)?,
+                rank: row.try_get("search_rank")?,
+                headline: row.try_get("search_headline")?,
+            })
+        }
+    }
+
+    /// Ranked, highlighted full-text search over text columns of a table.
+    ///
+    /// Queries use `websearch_to_tsquery`, so quoted phrases, `or` and
+    /// `-exclusions` work and malformed input never fails the query. The
+    /// document is `to_tsvector` over the columns, so an expression GIN index
+    /// on the same columns and language is used by the planner.
+    #[derive(Debug, Clone)]
+    pub struct FullTextSearch {
+        table: String,
+        columns: String,
+        document_columns: Vec<String>,
+        headline_column: String,
+        language: String,
+        condition: String,
+    }
+
+    impl FullTextSearch {
+        pub fn new(table: impl Into<String>, document_columns: &[&str]) -> Self {
+            let document_columns: Vec<String> =
+                document_columns.iter().map(|c| c.to_string()).collect();
+            Self {
+                table: table.into(),
+                columns: "*".to_string(),
+                headline_column: document_columns.last().cloned().unwrap_or_default(),
+                document_columns,
+                language: DEFAULT_LANGUAGE.to_string(),
+                condition: "1=1".to_string(),
+            }
+        }
+
+        /// Columns to select for each hit
+        pub fn columns(mut self, columns: impl Into<String>) -> Self {
+            self.columns = columns.into();
+            self
+        }
+
+        /// Column the highlighted snippet is taken from
+        pub fn headline_column(mut self, column: impl Into<String>) -> Self {
+            self.headline_column = column.into();
+            self
+        }
+
+        /// Text search configuration, e.g. `english` or `simple`
+        pub fn language(mut self, language: impl Into<String>) -> Self {
+            self.language = language.into();
+            self
+        }
+
+        /// Additional WHERE condition, e.g. tenant or soft-delete filtering
+        pub fn condition(mut self, condition: impl Into<String>) -> Self {
+            self.condition = condition.into();
+            self
+        }
+
+        /// The configuration is inlined rather than bound so the expression
+        /// matches the index; only plain identifiers are accepted.
+        fn regconfig(&self) -> Result<String> {
+            let valid = !self.language.is_empty()
+                && self
+                    .language
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !valid {
+                return Err(Error::invalid_input(
+                    "language",
+                    format!("Invalid text search configuration '{}'", self.language),
+                ));
+            }
+            Ok(format!("'{}'::regconfig", self.language))
+        }
+
+        /// The `tsvector` expression searched
+        pub fn document(&self) -> Result<String> {
+            let text = self
+                .document_columns
+                .iter()
+                .map(|c| format!("coalesce({}, '')", c))
+                .collect::<Vec<_>>()
+                .join(" || ' ' || ");
+            Ok(format!("to_tsvector({}, {})", self.regconfig()?, text))
+        }
+
+        /// Build the count and page queries. `$1` is the search text, and
+        /// `$2`/`$3` are LIMIT and OFFSET.
+        pub fn build(&self) -> Result<(String, String)> {
+            let config = self.regconfig()?;
+            let document = self.document()?;
+            let tsquery = format!("websearch_to_tsquery({}, $1)", config);
+
+            let count = format!(
+                "SELECT COUNT(*) FROM {} WHERE {} @@ {} AND {}",
+                self.table, document, tsquery, self.condition
+            );
+            let select = format!(
+                "SELECT {columns}, \
+                 ts_rank_cd({document}, {tsquery}) AS search_rank, \
+                 ts_headline({config}, coalesce({headline}, ''), {tsquery}, \
+                 'StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MaxWords=30, MinWords=10') AS search_headline \
+                 FROM {table} WHERE {document} @@ {tsquery} AND {condition} \
+                 ORDER BY search_rank DESC, id \
+                 LIMIT $2 OFFSET $3",
+                columns = self.columns,
+                document = document,
+                tsquery = tsquery,
+                config = config,
+                headline = self.headline_column,
+                table = self.table,
+                condition = self.condition,
+            );
+            Ok((count, select))
+        }
+
+        /// Search for `params.search`, paginated by `params`
+        pub async fn search<T>(
+            &self,
+            pool: &PgPool,
+            params: &ListParams,
+        ) -> Result<ListResult<SearchHit<T>>>
+        where
+            T: for<'r> sqlx::FromRow<'r, PgRow> + Send + Unpin,
+        {
+            let query = params.search.as_deref().unwrap_or("").trim();
+            if query.is_empty() {
+                return Ok(ListResult::empty(params));
+            }
+            let (count_query, select_query) = self.build()?;
+
+            let (total,): (i64,) = sqlx::query_as(&count_query)
+                .bind(query)
+                .fetch_one(pool)
+                .await
+                .map_err(|e| Error::database_with_source("Failed to count search results", e))?;
+
+            let hits = sqlx::query_as::<_, SearchHit<T>>(&select_query)
+                .bind(query)
+                .bind(params.per_page as i64)
+                .bind(params.offset() as i64)
+                .fetch_all(pool)
+                .await
+                .map_err(|e| Error::database_with_source("Failed to search", e))?;
+
+            Ok(ListResult::new(hits, total as u64, params))
+        }
+    }
+}
+
+/// Keyset (cursor) pagination helpers
+pub mod keyset {
+    use super::*;
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use base64::Engine;
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Serialize};
+    use sqlx::postgres::PgArguments;
+    use sqlx::query::QueryAs;
+    use sqlx::Postgres;
+
+    /// Value of the sort column in the last row of a page
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "t", content = "v")]
+    pub enum CursorValue {
+        Text(String),
+        Int(i64),
+        Timestamp(DateTime<Utc>),
+    }
+
+    /// Decoded position after the last row of a page. The sort column and
+    /// direction are recorded so a cursor can't be replayed against a
+    /// different ordering.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Cursor {
+        pub sort: String,
+        pub desc: bool,
+        pub value: CursorValue,
+        pub id: Uuid,
+    }
+
+    impl Cursor {
+        /// Encode as an opaque, URL-safe token
+        pub fn encode(&self) -> String {
+            let json = serde_json::to_vec(self).unwrap_or_default();
+            URL_SAFE_NO_PAD.encode(json)
+        }
+
+        /// Decode a token produced by [`Cursor::encode`]
+        pub fn decode(token: &str) -> Result<Self> {
+            let json = URL_SAFE_NO_PAD
+                .decode(token)
+                .map_err(|_| Error::invalid_input("cursor", "Malformed cursor"))?;
+            serde_json::from_slice(&json)
+                .map_err(|_| Error::invalid_input("cursor", "Malformed cursor"))
+        }
+
+        /// Bind the cursor's sort value and ID, in that order
+        pub fn bind<'q, O>(
+            self,
+            query: QueryAs<'q, Postgres, O, PgArguments>,
+        ) -> QueryAs<'q, Postgres, O, PgArguments> {
+            let query = match self.value {
+                CursorValue::Text(v) => query.bind(v),
+                CursorValue::Int(v) => query.bind(v),
+                CursorValue::Timestamp(v) => query.bind(v),
+            };
+            query.bind(self.id)
+        }
+    }
+
+    /// One page of keyset-paginated rows
+    #[derive(Debug, Clone)]
+    pub struct KeysetPage<T> {
+        pub items: Vec<T>,
+        /// Cursor for the following page, or `None` on the last page
+        pub next_cursor: Option<String>,
+    }
+
+    /// Keyset pagination on `(sort_column, id)`.
+    ///
+    /// Each page continues strictly after the previous page's last row rather
+    /// than skipping a row count, so pages stay stable when rows are inserted
+    /// or deleted between fetches and deep pages cost the same as the first.
+    /// The sort column must be non-null, and an index on `(sort_column, id)`
+    /// keeps each page to an index range scan.
+    #[derive(Debug, Clone)]
+    pub struct Keyset {
+        sort_column: String,
+        order: SortOrder,
+        limit: u32,
+    }
+
+    impl Keyset {
+        pub fn new(sort_column: impl Into<String>, order: SortOrder, limit: u32) -> Self {
+            Self {
+                sort_column: sort_column.into(),
+                order,
+                limit: limit.max(1),
+            }
+        }
+
+        fn desc(&self) -> bool {
+            matches!(self.order, SortOrder::Desc)
+        }
+
+        /// Decode a client-supplied cursor, checking it was issued for this ordering
+        pub fn cursor(&self, token: Option<&str>) -> Result<Option<Cursor>> {
+            let Some(token) = token else {
+                return Ok(None);
+            };
+            let cursor = Cursor::decode(token)?;
+            if cursor.sort != self.sort_column || cursor.desc != self.desc() {
+                return Err(Error::invalid_input(
+                    "cursor",
+                    "Cursor was issued for a different sort order",
+                ));
+            }
+            Ok(Some(cursor))
+        }
+
+        /// WHERE condition continuing after `cursor`, with the cursor's value
+        /// and ID bound at `$param` and `$param + 1`
+        pub fn condition(&self, cursor: Option<&Cursor>, param: usize) -> String {
+            match cursor {
+                None => "1=1".to_string(),
+                Some(_) => format!(
+                    "({}, id) {} (${}, ${})",
+                    self.sort_column,
+                    if self.desc() { "<" } else { ">" },
+                    param,
+                    param + 1
+                ),
+            }
+        }
+
+        /// ORDER BY and LIMIT clause; one extra row is fetched to detect a next page
+        pub fn order_limit(&self) -> String {
+            let direction = if self.desc() { "DESC" } else { "ASC" };
+            format!(
+                "ORDER BY {} {}, id {} LIMIT {}",
+                self.sort_column,
+                direction,
+                direction,
+                self.limit as u64 + 1
+            )
+        }
+
+        /// Trim the extra row and build the next cursor from the last row kept
+        pub fn page<T>(
+            &self,
+            mut rows: Vec<T>,
+            key: impl Fn(&T) -> (CursorValue, Uuid),
+        ) -> KeysetPage<T> {
+            let has_more = rows.len() > self.limit as usize;
+            rows.truncate(self.limit as usize);
+
+            let next_cursor = rows.last().filter(|_| has_more).map(|last| {
+                let (value, id) = key(last);
+                Cursor {
+                    sort: self.sort_column.clone(),
+                    desc: self.desc(),
+                    value,
+                    id,
+                }
+                .encode()
+            });
+
+            KeysetPage {
+                items: rows,
+                next_cursor,
+            }
+        }
+    }
+}
+
 /// User repository implementation
 pub mod users {
     use super::*;
@@ -300,6 +863,18 @@ pub mod posts {
                 .map_err(|e| Error::database_with_source("Failed to find post by slug", e))
         }
 
+        /// Full-text search over title, excerpt and content, best matches first
+        pub async fn search(
+            &self,
+            params: &ListParams,
+        ) -> Result<ListResult<search::SearchHit<PostRow>>> {
+            search::FullTextSearch::new("posts", &["title", "excerpt", "content"])
+                .columns(PostRow::COLUMNS)
+                .condition(format!("{} AND deleted_at IS NULL", self.site_condition()))
+                .search(&self.pool, params)
+                .await
+        }
+
         pub async fn list(&self, params: &ListParams) -> Result<ListResult<PostRow>> {
             let search_condition = params
                 .search
@@ -335,6 +910,37 @@ pub mod posts {
             Ok(ListResult::new(posts, total.0 as u64, params))
         }
 
+        /// List posts newest first with keyset pagination, continuing after `cursor`
+        pub async fn list_after(
+            &self,
+            cursor: Option<&str>,
+            limit: u32,
+        ) -> Result<keyset::KeysetPage<PostRow>> {
+            let keyset = keyset::Keyset::new("created_at", SortOrder::Desc, limit);
+            let cursor = keyset.cursor(cursor)?;
+
+            let query = format!(
+                "SELECT {} FROM posts WHERE {} AND {} AND deleted_at IS NULL {}",
+                PostRow::COLUMNS,
+                self.site_condition(),
+                keyset.condition(cursor.as_ref(), 1),
+                keyset.order_limit()
+            );
+
+            let mut q = sqlx::query_as::<_, PostRow>(&query);
+            if let Some(cursor) = cursor {
+                q = cursor.bind(q);
+            }
+            let posts = q
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| Error::database_with_source("Failed to list posts", e))?;
+
+            Ok(keyset.page(posts, |p| {
+                (keyset::CursorValue::Timestamp(p.created_at), p.id)
+            }))
+        }
+
         pub async fn list_published(&self, params: &ListParams) -> Result<ListResult<PostRow>> {
             let search_condition = params
                 .search
@@ -2031,6 +2637,135 @@ pub mod themes {
     }
 }
 
+/// Feature flag store backed by the `feature_flags` tables
+pub mod feature_flags {
+    use super::*;
+    use async_trait::async_trait;
+    use rustpress_core::feature_flags::{FeatureFlag, FeatureFlagStore};
+    use std::collections::HashMap;
+
+    #[derive(Debug, Clone, sqlx::FromRow)]
+    struct FeatureFlagRow {
+        key: String,
+        description: Option<String>,
+        enabled: bool,
+        rollout_percentage: Option<i16>,
+    }
+
+    #[derive(Debug, Clone, sqlx::FromRow)]
+    struct OverrideRow {
+        flag_key: String,
+        tenant_id: Uuid,
+        enabled: bool,
+    }
+
+    pub struct PgFeatureFlagStore {
+        pool: PgPool,
+    }
+
+    impl PgFeatureFlagStore {
+        pub fn new(pool: PgPool) -> Self {
+            Self { pool }
+        }
+    }
+
+    #[async_trait]
+    impl FeatureFlagStore for PgFeatureFlagStore {
+        async fn load_all(&self) -> Result<Vec<FeatureFlag>> {
+            let rows = sqlx::query_as::<_, FeatureFlagRow>(
+                "SELECT key, description, enabled, rollout_percentage FROM feature_flags",
+            )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::database_with_source("Failed to load feature flags", e))?;
+
+            let overrides = sqlx::query_as::<_, OverrideRow>(
+                "SELECT flag_key, tenant_id, enabled FROM feature_flag_overrides",
+            )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::database_with_source("Failed to load feature flag overrides", e))?;
+
+            let mut by_flag: HashMap<String, HashMap<TenantId, bool>> = HashMap::new();
+            for o in overrides {
+                by_flag
+                    .entry(o.flag_key)
+                    .or_default()
+                    .insert(TenantId::from_uuid(o.tenant_id), o.enabled);
+            }
+
+            Ok(rows
+                .into_iter()
+                .map(|row| FeatureFlag {
+                    tenant_overrides: by_flag.remove(&row.key).unwrap_or_default(),
+                    key: row.key,
+                    description: row.description,
+                    enabled: row.enabled,
+                    rollout_percentage: row.rollout_percentage.map(|p| p.clamp(0, 100) as u8),
+                })
+                .collect())
+        }
+
+        async fn save(&self, flag: &FeatureFlag) -> Result<()> {
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .map_err(|e| Error::database_with_source("Failed to start transaction", e))?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO feature_flags (key, description, enabled, rollout_percentage)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (key) DO UPDATE SET
+                    description = EXCLUDED.description,
+                    enabled = EXCLUDED.enabled,
+                    rollout_percentage = EXCLUDED.rollout_percentage,
+                    updated_at = NOW()
+                "#,
+            )
+            .bind(&flag.key)
+            .bind(&flag.description)
+            .bind(flag.enabled)
+            .bind(flag.rollout_percentage.map(|p| p as i16))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::database_with_source("Failed to save feature flag", e))?;
+
+            sqlx::query("DELETE FROM feature_flag_overrides WHERE flag_key = $1")
+                .bind(&flag.key)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| Error::database_with_source("Failed to save feature flag", e))?;
+
+            for (tenant_id, enabled) in &flag.tenant_overrides {
+                sqlx::query(
+                    "INSERT INTO feature_flag_overrides (flag_key, tenant_id, enabled) VALUES ($1, $2, $3)",
+                )
+                .bind(&flag.key)
+                .bind(tenant_id.into_uuid())
+                .bind(*enabled)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| Error::database_with_source("Failed to save feature flag", e))?;
+            }
+
+            tx.commit()
+                .await
+                .map_err(|e| Error::database_with_source("Failed to save feature flag", e))
+        }
+
+        async fn delete(&self, key: &str) -> Result<()> {
+            sqlx::query("DELETE FROM feature_flags WHERE key = $1")
+                .bind(key)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| Error::database_with_source("Failed to delete feature flag", e))?;
+            Ok(())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2054,6 +2789,97 @@ mod tests {
         assert!(condition.contains("content ILIKE"));
     }
 
+    #[tokio::test]
+    async fn test_pg_repository_tenant_filter() {
+        let pool = PgPool::connect_lazy("postgres://").unwrap();
+
+        let repo = PgRepository::<()>::new(pool.clone(), "posts");
+        assert_eq!(
+            repo.tenant_filter("list", 1).unwrap(),
+            ("1=1".to_string(), None)
+        );
+        assert!(repo.check_owner("update", None).is_ok());
+
+        let tenant = TenantId::new();
+        let repo = repo.with_tenant(tenant);
+        assert_eq!(
+            repo.tenant_filter("list", 2).unwrap(),
+            ("tenant_id = $2".to_string(), Some(tenant.into_uuid()))
+        );
+        assert!(repo.check_owner("update", Some(tenant.into_uuid())).is_ok());
+        assert!(repo.check_owner("update", Some(Uuid::now_v7())).is_err());
+
+        let repo = PgRepository::<()>::new(pool, "posts").with_scope(TenantScope::Missing);
+        assert!(matches!(
+            repo.tenant_filter("list", 1),
+            Err(Error::TenantRequired { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_pg_repository_deleted_filter() {
+        let pool = PgPool::connect_lazy("postgres://").unwrap();
+
+        let repo = PgRepository::<()>::new(pool, "media");
+        assert_eq!(repo.deleted_filter(), "1=1");
+
+        let repo = repo.with_soft_deletes();
+        assert_eq!(repo.deleted_filter(), "deleted_at IS NULL");
+        assert_eq!(repo.include_deleted().deleted_filter(), "1=1");
+    }
+
+    #[test]
+    fn test_full_text_search_query() {
+        let search = search::FullTextSearch::new("posts", &["title", "content"])
+            .condition("deleted_at IS NULL");
+        let (count, select) = search.build().unwrap();
+
+        let document =
+            "to_tsvector('english'::regconfig, coalesce(title, '') || ' ' || coalesce(content, ''))";
+        assert_eq!(search.document().unwrap(), document);
+        assert!(count.contains("websearch_to_tsquery('english'::regconfig, $1)"));
+        assert!(select.contains(&format!("ts_rank_cd({}", document)));
+        assert!(select.contains("ts_headline('english'::regconfig, coalesce(content, '')"));
+        assert!(select.contains("AND deleted_at IS NULL"));
+        assert!(select.ends_with("LIMIT $2 OFFSET $3"));
+
+        let search = search.language("english'; DROP TABLE posts; --");
+        assert!(search.build().is_err());
+    }
+
+    #[test]
+    fn test_keyset_pagination() {
+        use keyset::{CursorValue, Keyset};
+
+        let asc = Keyset::new("title", SortOrder::Asc, 2);
+        assert_eq!(asc.condition(None, 1), "1=1");
+        assert_eq!(asc.order_limit(), "ORDER BY title ASC, id ASC LIMIT 3");
+
+        let rows = vec![
+            ("a", Uuid::now_v7()),
+            ("b", Uuid::now_v7()),
+            ("c", Uuid::now_v7()),
+        ];
+        let page = asc.page(rows.clone(), |r| (CursorValue::Text(r.0.to_string()), r.1));
+        assert_eq!(page.items.len(), 2);
+
+        let cursor = asc.cursor(page.next_cursor.as_deref()).unwrap().unwrap();
+        assert_eq!(cursor.value, CursorValue::Text("b".to_string()));
+        assert_eq!(cursor.id, rows[1].1);
+        assert_eq!(asc.condition(Some(&cursor), 3), "(title, id) > ($3, $4)");
+
+        // The last page has no next cursor
+        let page = asc.page(rows[..2].to_vec(), |r| {
+            (CursorValue::Text(r.0.to_string()), r.1)
+        });
+        assert!(page.next_cursor.is_none());
+
+        let desc = Keyset::new("title", SortOrder::Desc, 2);
+        assert_eq!(desc.condition(Some(&cursor), 1), "(title, id) < ($1, $2)");
+        assert!(desc.cursor(Some(&cursor.encode())).is_err());
+        assert!(asc.cursor(Some("not a cursor")).is_err());
+    }
+
     #[test]
     fn test_escape_like() {
         assert_eq!(QueryHelper::escape_like("test%"), "test\\%");
@@ -2,8 +2,10 @@
 //!
 //! Analyzes keyword usage and density in content.
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::OnceLock;
 use unicode_segmentation::UnicodeSegmentation;
 
 /// Keyword analyzer for content optimization
@@ -23,6 +25,21 @@ impl KeywordAnalyzer {
 
     /// Analyze keywords in text
     pub fn analyze(&self, text: &str, focus_keyword: Option<&str>) -> KeywordAnalysis {
+        self.analyze_in_context(text, focus_keyword, &KeywordContext::default())
+    }
+
+    /// Analyze keywords in text, also scoring focus keyword placement in the
+    /// title, headings, slug and meta description given in `context`.
+    ///
+    /// Code blocks and HTML tags (including attribute values) are ignored.
+    pub fn analyze_in_context(
+        &self,
+        text: &str,
+        focus_keyword: Option<&str>,
+        context: &KeywordContext,
+    ) -> KeywordAnalysis {
+        let cleaned = strip_markup(text);
+        let text = cleaned.as_str();
         let words: Vec<&str> = text.unicode_words().collect();
         let word_count = words.len();
 
@@ -38,8 +55,9 @@ impl KeywordAnalyzer {
         let top_phrases = self.get_top_phrases(&bigrams, &trigrams, 10);
 
         // Analyze focus keyword if provided
-        let focus_analysis =
-            focus_keyword.map(|kw| self.analyze_focus_keyword(kw, text, &words, word_count));
+        let focus_analysis = focus_keyword
+            .filter(|kw| !kw.trim().is_empty())
+            .map(|kw| self.analyze_focus_keyword(kw, text, context));
 
         // Generate suggestions
         let suggestions = self.generate_suggestions(&top_keywords, focus_analysis.as_ref());
@@ -149,36 +167,44 @@ impl KeywordAnalyzer {
         &self,
         keyword: &str,
         text: &str,
-        _words: &[&str],
-        word_count: usize,
+        context: &KeywordContext,
     ) -> FocusKeywordAnalysis {
-        let keyword_lower = keyword.to_lowercase();
-        let text_lower = text.to_lowercase();
-
-        // Count occurrences
-        let occurrences = text_lower.matches(&keyword_lower).count() as u32;
+        let keyword_stems = stems(keyword);
+        let tokens: Vec<(usize, &str)> = text.unicode_word_indices().collect();
+        let token_stems: Vec<String> = tokens.iter().map(|(_, w)| stem(w)).collect();
+        let word_count = tokens.len();
+
+        // Phrase matches: consecutive tokens whose stems equal the keyword's
+        let matches: Vec<usize> = if keyword_stems.is_empty() {
+            Vec::new()
+        } else {
+            token_stems
+                .windows(keyword_stems.len())
+                .enumerate()
+                .filter(|(_, window)| *window == keyword_stems.as_slice())
+                .map(|(i, _)| i)
+                .collect()
+        };
+        let occurrences = matches.len() as u32;
+        let positions: Vec<usize> = matches.iter().map(|&i| tokens[i].0).collect();
 
-        // Calculate density
-        let keyword_words: Vec<&str> = keyword.unicode_words().collect();
-        let keyword_word_count = keyword_words.len();
         let density = if word_count > 0 {
-            (occurrences as f32 * keyword_word_count as f32 / word_count as f32) * 100.0
+            (occurrences as f32 * keyword_stems.len() as f32 / word_count as f32) * 100.0
         } else {
             0.0
         };
 
-        // Find positions
-        let positions: Vec<usize> = text_lower
-            .match_indices(&keyword_lower)
-            .map(|(pos, _)| pos)
-            .collect();
-
         // Check if in first paragraph
-        let first_para_end = text.find("\n\n").unwrap_or(text.len().min(500));
-        let in_first_paragraph = positions
-            .first()
-            .map(|&p| p < first_para_end)
-            .unwrap_or(false);
+        let in_first_paragraph = match &context.first_paragraph {
+            Some(paragraph) => contains_phrase(paragraph, &keyword_stems),
+            None => {
+                let first_para_end = text.find("\n\n").unwrap_or(text.len().min(500));
+                positions
+                    .first()
+                    .map(|&p| p < first_para_end)
+                    .unwrap_or(false)
+            }
+        };
 
         // Check distribution (divided into quarters)
         let text_len = text.len();
@@ -190,9 +216,9 @@ impl KeywordAnalyzer {
         }
 
         // Assess density
-        let density_status = if density < 0.5 {
+        let density_status = if density < self.config.density_min {
             DensityStatus::TooLow
-        } else if density > 2.5 {
+        } else if density > self.config.density_max {
             DensityStatus::TooHigh
         } else {
             DensityStatus::Good
@@ -209,6 +235,14 @@ impl KeywordAnalyzer {
             0
         };
 
+        let signals =
+            self.placement_signals(&keyword_stems, density_status, in_first_paragraph, context);
+        let possible: u32 = signals.iter().map(|s| s.weight).sum();
+        let earned: u32 = signals.iter().filter(|s| s.found).map(|s| s.weight).sum();
+        let score = (earned * 100).checked_div(possible).unwrap_or(0);
+
+        let related_terms = self.related_terms(&keyword_stems, &tokens, &token_stems, &matches);
+
         FocusKeywordAnalysis {
             keyword: keyword.to_string(),
             occurrences,
@@ -219,20 +253,141 @@ impl KeywordAnalyzer {
             distribution,
             prominence_score,
             suggestions: self.focus_keyword_suggestions(
-                &keyword_lower,
+                &keyword.to_lowercase(),
                 occurrences,
-                density,
+                density_status,
                 in_first_paragraph,
+                &signals,
             ),
+            signals,
+            score,
+            related_terms,
+        }
+    }
+
+    /// Weighted placement signals. Locations missing from the context are
+    /// left out so they don't count against the score.
+    fn placement_signals(
+        &self,
+        keyword_stems: &[String],
+        density_status: DensityStatus,
+        in_first_paragraph: bool,
+        context: &KeywordContext,
+    ) -> Vec<KeywordSignal> {
+        let mut signals = vec![
+            KeywordSignal {
+                location: KeywordLocation::Density,
+                found: density_status == DensityStatus::Good,
+                weight: 30,
+            },
+            KeywordSignal {
+                location: KeywordLocation::FirstParagraph,
+                found: in_first_paragraph,
+                weight: 15,
+            },
+        ];
+
+        if let Some(title) = &context.title {
+            signals.push(KeywordSignal {
+                location: KeywordLocation::Title,
+                found: contains_phrase(title, keyword_stems),
+                weight: 20,
+            });
+        }
+        if !context.headings.is_empty() {
+            signals.push(KeywordSignal {
+                location: KeywordLocation::Headings,
+                found: context
+                    .headings
+                    .iter()
+                    .any(|h| contains_phrase(h, keyword_stems)),
+                weight: 10,
+            });
+        }
+        if let Some(slug) = &context.slug {
+            signals.push(KeywordSignal {
+                location: KeywordLocation::Slug,
+                found: contains_phrase(&slug.replace(['-', '_', '/'], " "), keyword_stems),
+                weight: 10,
+            });
+        }
+        if let Some(meta) = &context.meta_description {
+            signals.push(KeywordSignal {
+                location: KeywordLocation::MetaDescription,
+                found: contains_phrase(meta, keyword_stems),
+                weight: 15,
+            });
+        }
+
+        signals
+    }
+
+    /// Terms that frequently appear near the focus keyword
+    fn related_terms(
+        &self,
+        keyword_stems: &[String],
+        tokens: &[(usize, &str)],
+        token_stems: &[String],
+        matches: &[usize],
+    ) -> Vec<RelatedTerm> {
+        let window = self.config.co_occurrence_window;
+        let mut counts: HashMap<&str, u32> = HashMap::new();
+        let mut surface: HashMap<&str, HashMap<String, u32>> = HashMap::new();
+
+        for &start in matches {
+            let from = start.saturating_sub(window);
+            let to = (start + keyword_stems.len() + window).min(tokens.len());
+            for i in from..to {
+                if (start..start + keyword_stems.len()).contains(&i) {
+                    continue;
+                }
+                let word = tokens[i].1.to_lowercase();
+                let term = token_stems[i].as_str();
+                if word.chars().count() < self.config.min_word_length
+                    || self.stop_words.contains(&word)
+                    || keyword_stems.iter().any(|k| k == term)
+                    || word.chars().all(|c| c.is_ascii_digit())
+                {
+                    continue;
+                }
+                *counts.entry(term).or_insert(0) += 1;
+                *surface.entry(term).or_default().entry(word).or_insert(0) += 1;
+            }
         }
+
+        let mut terms: Vec<RelatedTerm> = counts
+            .into_iter()
+            .filter(|(_, count)| *count >= self.config.min_co_occurrences)
+            .map(|(stem, co_occurrences)| {
+                // Report the most common spelling of the stem
+                let term = surface[stem]
+                    .iter()
+                    .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+                    .map(|(word, _)| word.clone())
+                    .unwrap_or_else(|| stem.to_string());
+                RelatedTerm {
+                    term,
+                    co_occurrences,
+                }
+            })
+            .collect();
+
+        terms.sort_by(|a, b| {
+            b.co_occurrences
+                .cmp(&a.co_occurrences)
+                .then_with(|| a.term.cmp(&b.term))
+        });
+        terms.truncate(self.config.max_related_terms);
+        terms
     }
 
     fn focus_keyword_suggestions(
         &self,
         keyword: &str,
         occurrences: u32,
-        density: f32,
+        density_status: DensityStatus,
         in_first_paragraph: bool,
+        signals: &[KeywordSignal],
     ) -> Vec<String> {
         let mut suggestions = Vec::new();
 
@@ -247,10 +402,27 @@ impl KeywordAnalyzer {
             suggestions.push("Add the focus keyword to your first paragraph.".to_string());
         }
 
-        if density < 0.5 {
-            suggestions.push("Increase keyword usage slightly for better SEO.".to_string());
-        } else if density > 2.5 {
-            suggestions.push("Reduce keyword repetition to avoid keyword stuffing.".to_string());
+        match density_status {
+            DensityStatus::TooLow => {
+                suggestions.push("Increase keyword usage slightly for better SEO.".to_string());
+            }
+            DensityStatus::TooHigh => {
+                suggestions
+                    .push("Reduce keyword repetition to avoid keyword stuffing.".to_string());
+            }
+            DensityStatus::Good => {}
+        }
+
+        for signal in signals.iter().filter(|s| !s.found) {
+            let location = match signal.location {
+                KeywordLocation::Title => "the title",
+                KeywordLocation::Headings => "at least one subheading",
+                KeywordLocation::Slug => "the URL slug",
+                KeywordLocation::MetaDescription => "the meta description",
+                // Covered above
+                KeywordLocation::Density | KeywordLocation::FirstParagraph => continue,
+            };
+            suggestions.push(format!("Add the focus keyword to {}.", location));
         }
 
         suggestions
@@ -316,6 +488,15 @@ impl Default for KeywordAnalyzer {
 pub struct KeywordConfig {
     pub min_word_length: usize,
     pub min_phrase_frequency: u32,
+    /// Below this focus keyword density (%) content is under-optimized
+    pub density_min: f32,
+    /// Above this focus keyword density (%) content is keyword stuffed
+    pub density_max: f32,
+    /// Words either side of a keyword occurrence counted as co-occurring
+    pub co_occurrence_window: usize,
+    /// Minimum co-occurrences for a related term suggestion
+    pub min_co_occurrences: u32,
+    pub max_related_terms: usize,
 }
 
 impl Default for KeywordConfig {
@@ -323,10 +504,26 @@ impl Default for KeywordConfig {
         Self {
             min_word_length: 3,
             min_phrase_frequency: 2,
+            density_min: 0.5,
+            density_max: 2.5,
+            co_occurrence_window: 8,
+            min_co_occurrences: 2,
+            max_related_terms: 10,
         }
     }
 }
 
+/// Where else the focus keyword should appear
+#[derive(Debug, Clone, Default)]
+pub struct KeywordContext {
+    pub title: Option<String>,
+    /// First paragraph; derived from the text when not given
+    pub first_paragraph: Option<String>,
+    pub headings: Vec<String>,
+    pub slug: Option<String>,
+    pub meta_description: Option<String>,
+}
+
 /// Keyword analysis result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeywordAnalysis {
@@ -368,6 +565,43 @@ pub struct FocusKeywordAnalysis {
     pub distribution: [u32; 4],
     pub prominence_score: u32,
     pub suggestions: Vec<String>,
+    /// Weighted placement signals feeding `score`
+    #[serde(default)]
+    pub signals: Vec<KeywordSignal>,
+    /// Focus keyword optimization score (0-100)
+    #[serde(default)]
+    pub score: u32,
+    /// Terms that co-occur with the keyword
+    #[serde(default)]
+    pub related_terms: Vec<RelatedTerm>,
+}
+
+/// Focus keyword placement signal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeywordSignal {
+    pub location: KeywordLocation,
+    pub found: bool,
+    pub weight: u32,
+}
+
+/// Places the focus keyword is checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeywordLocation {
+    /// Density within the configured range
+    Density,
+    FirstParagraph,
+    Title,
+    Headings,
+    Slug,
+    MetaDescription,
+}
+
+/// Related term suggestion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedTerm {
+    pub term: String,
+    pub co_occurrences: u32,
 }
 
 /// Keyword density status
@@ -397,6 +631,107 @@ impl DensityStatus {
     }
 }
 
+/// Remove code blocks, HTML tags and attribute values so keywords inside
+/// them aren't counted
+fn strip_markup(text: &str) -> String {
+    static CODE: OnceLock<Regex> = OnceLock::new();
+    static TAGS: OnceLock<Regex> = OnceLock::new();
+
+    let code = CODE.get_or_init(|| {
+        Regex::new(
+            r"(?is)```.*?```|~~~.*?~~~|`[^`\n]*`|<(pre|code|script|style)\b[^>]*>.*?</(pre|code|script|style)>",
+        )
+        .expect("valid regex")
+    });
+    let tags = TAGS.get_or_init(|| Regex::new(r"(?s)<[^>]*>").expect("valid regex"));
+
+    let without_code = code.replace_all(text, " ");
+    tags.replace_all(&without_code, " ").into_owned()
+}
+
+/// Stems of each word in a phrase
+fn stems(phrase: &str) -> Vec<String> {
+    phrase.unicode_words().map(stem).collect()
+}
+
+/// Whether `text` contains the stemmed phrase as consecutive words
+fn contains_phrase(text: &str, phrase_stems: &[String]) -> bool {
+    if phrase_stems.is_empty() {
+        return false;
+    }
+    let text_stems = stems(text);
+    text_stems
+        .windows(phrase_stems.len())
+        .any(|w| w == phrase_stems)
+}
+
+/// Light English stemmer so inflected forms ("running", "runs", "ran")
+/// count as the same word
+fn stem(word: &str) -> String {
+    let word = word.to_lowercase();
+
+    const IRREGULAR: &[(&str, &str)] = &[
+        ("ran", "run"),
+        ("went", "go"),
+        ("gone", "go"),
+        ("wrote", "write"),
+        ("written", "write"),
+        ("bought", "buy"),
+        ("made", "make"),
+        ("took", "take"),
+        ("taken", "take"),
+        ("children", "child"),
+        ("men", "man"),
+        ("women", "woman"),
+        ("mice", "mouse"),
+        ("better", "good"),
+        ("best", "good"),
+    ];
+    if let Some((_, base)) = IRREGULAR.iter().find(|(form, _)| *form == word) {
+        return base.to_string();
+    }
+
+    let chars: Vec<char> = word.chars().collect();
+    if chars.len() <= 3 || !chars.iter().all(|c| c.is_alphabetic()) {
+        return word;
+    }
+
+    let is_vowel = |c: char| "aeiou".contains(c);
+    let strip = |suffix: &str| -> Option<String> {
+        let base = word.strip_suffix(suffix)?;
+        (base.chars().count() >= 2 && base.chars().any(is_vowel)).then(|| base.to_string())
+    };
+
+    let base = if let Some(base) = strip("ies") {
+        format!("{}y", base)
+    } else if let Some(base) = strip("ied") {
+        format!("{}y", base)
+    } else if let Some(base) = strip("ing").or_else(|| strip("ed")) {
+        // running -> runn -> run
+        let b: Vec<char> = base.chars().collect();
+        let n = b.len();
+        if n >= 2 && b[n - 1] == b[n - 2] && !"lsz".contains(b[n - 1]) {
+            b[..n - 1].iter().collect()
+        } else {
+            base
+        }
+    } else if let Some(base) = strip("sses") {
+        format!("{}ss", base)
+    } else if word.ends_with("ss") || word.ends_with("us") || word.ends_with("is") {
+        word.clone()
+    } else if let Some(base) = strip("s") {
+        base
+    } else {
+        word.clone()
+    };
+
+    // Drop a trailing silent "e" so "write"/"writing" share a stem
+    match base.strip_suffix('e') {
+        Some(b) if b.chars().count() >= 3 => b.to_string(),
+        _ => base,
+    }
+}
+
 /// Default English stop words
 fn default_stop_words() -> Vec<String> {
     vec![
@@ -415,3 +750,66 @@ fn default_stop_words() -> Vec<String> {
     .map(String::from)
     .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stem_groups_inflections() {
+        assert_eq!(stem("running"), stem("run"));
+        assert_eq!(stem("runs"), stem("run"));
+        assert_eq!(stem("ran"), stem("run"));
+        assert_eq!(stem("writing"), stem("write"));
+        assert_eq!(stem("stories"), stem("story"));
+        assert_eq!(stem("class"), "class");
+    }
+
+    #[test]
+    fn test_phrase_keyword_ignores_code_and_attributes() {
+        let text = "Trail running shoes matter. We tested trail runs all year.\n\n\
+                    <img alt=\"trail running\" src=\"x.png\"> \
+                    ```\ntrail running\n``` Pick shoes for trail running carefully.";
+        let analysis = KeywordAnalyzer::new().analyze(text, Some("trail running"));
+        let focus = analysis.focus_keyword_analysis.unwrap();
+        // "trail running", "trail runs", "trail running" (not the alt or code)
+        assert_eq!(focus.occurrences, 3);
+        assert!(focus.in_first_paragraph);
+        assert_eq!(focus.density_status, DensityStatus::TooHigh);
+    }
+
+    #[test]
+    fn test_placement_signals_and_related_terms() {
+        let text =
+            "Sourdough bread needs a starter. A healthy starter makes sourdough bread rise. \
+                    Feed the starter daily and your sourdough bread improves with flour and water. \
+                    Good flour matters for sourdough bread.";
+        let context = KeywordContext {
+            title: Some("How to Bake Sourdough Bread".to_string()),
+            headings: vec!["Feeding a starter".to_string()],
+            slug: Some("sourdough-bread-guide".to_string()),
+            meta_description: None,
+            first_paragraph: None,
+        };
+
+        let analysis =
+            KeywordAnalyzer::new().analyze_in_context(text, Some("sourdough bread"), &context);
+        let focus = analysis.focus_keyword_analysis.unwrap();
+
+        let found = |location| {
+            focus
+                .signals
+                .iter()
+                .find(|s| s.location == location)
+                .map(|s| s.found)
+        };
+        assert_eq!(found(KeywordLocation::Title), Some(true));
+        assert_eq!(found(KeywordLocation::Slug), Some(true));
+        assert_eq!(found(KeywordLocation::Headings), Some(false));
+        assert_eq!(found(KeywordLocation::MetaDescription), None);
+        assert!(focus.score > 0 && focus.score < 100);
+
+        assert_eq!(focus.related_terms[0].term, "starter");
+        assert!(focus.related_terms.iter().any(|t| t.term == "flour"));
+    }
+}
@@ -8,6 +8,10 @@ pub mod readability;
 pub mod seo_analyzer;
 
 pub use accessibility::AccessibilityChecker;
-pub use keyword::KeywordAnalyzer;
-pub use readability::ReadabilityAnalyzer;
-pub use seo_analyzer::SeoAnalyzer;
+pub use keyword::{KeywordAnalyzer, KeywordContext};
+pub use readability::{
+    LanguageReadability, ReadabilityAnalyzer, ReadabilityLanguage, UnsupportedLanguage,
+};
+pub use seo_analyzer::{classify_link, LinkKind, LinkStatus, SeoAnalyzer, SeoAnalyzerConfig};
+#[cfg(feature = "link-check")]
+pub use seo_analyzer::{LinkCheckConfig, LinkChecker};
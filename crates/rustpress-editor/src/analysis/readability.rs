@@ -55,7 +55,58 @@ impl ReadabilityAnalyzer {
         }
     }
 
+    /// Analyze text readability using the formula for its language.
+    ///
+    /// `lang` is a language code such as `en`, `es-MX` or `de_DE`. Returns
+    /// an error for languages without a supported formula rather than
+    /// falling back to English.
+    pub fn analyze_with_language(
+        &self,
+        text: &str,
+        lang: &str,
+    ) -> Result<LanguageReadability, UnsupportedLanguage> {
+        let language = ReadabilityLanguage::from_code(lang).ok_or_else(|| UnsupportedLanguage {
+            code: lang.to_string(),
+        })?;
+
+        let stats = self.calculate_stats_for(text, language);
+        let (formula, reading_ease, grade_level) = match language {
+            ReadabilityLanguage::English => (
+                "Flesch Reading Ease",
+                self.flesch_reading_ease(&stats),
+                Some(self.flesch_kincaid_grade(&stats)),
+            ),
+            ReadabilityLanguage::Spanish => {
+                ("Flesch-Szigriszt", self.flesch_szigriszt(&stats), None)
+            }
+            ReadabilityLanguage::German => (
+                "Amstad / Wiener Sachtextformel",
+                self.amstad(&stats),
+                Some(self.wiener_sachtextformel(text, &stats)),
+            ),
+        };
+
+        let issues = self.find_issues(&stats);
+        let suggestions = self.generate_suggestions(&stats, &issues);
+
+        Ok(LanguageReadability {
+            language,
+            formula: formula.to_string(),
+            reading_ease,
+            grade_level,
+            grade: language.grade(reading_ease),
+            stats,
+            issues,
+            suggestions,
+        })
+    }
+
     fn calculate_stats(&self, text: &str) -> TextStats {
+        self.calculate_stats_for(text, ReadabilityLanguage::English)
+    }
+
+    fn calculate_stats_for(&self, text: &str, language: ReadabilityLanguage) -> TextStats {
+        let count_syllables = |word: &str| language.count_syllables(word);
         let words: Vec<&str> = text.unicode_words().collect();
         let word_count = words.len();
 
@@ -123,6 +174,52 @@ impl ReadabilityAnalyzer {
         (206.835 - (1.015 * asl) - (84.6 * asw)).clamp(0.0, 100.0)
     }
 
+    /// Flesch-Szigriszt index for Spanish (0-100, higher = easier)
+    fn flesch_szigriszt(&self, stats: &TextStats) -> f32 {
+        if stats.word_count == 0 {
+            return 0.0;
+        }
+
+        let asl = stats.avg_words_per_sentence;
+        let asw = stats.avg_syllables_per_word;
+
+        (206.835 - (62.3 * asw) - asl).clamp(0.0, 100.0)
+    }
+
+    /// Amstad's German adaptation of Flesch Reading Ease (0-100)
+    fn amstad(&self, stats: &TextStats) -> f32 {
+        if stats.word_count == 0 {
+            return 0.0;
+        }
+
+        let asl = stats.avg_words_per_sentence;
+        let asw = stats.avg_syllables_per_word;
+
+        (180.0 - asl - (58.5 * asw)).clamp(0.0, 100.0)
+    }
+
+    /// First Wiener Sachtextformel for German, as a school grade (4-15)
+    fn wiener_sachtextformel(&self, text: &str, stats: &TextStats) -> f32 {
+        if stats.word_count == 0 {
+            return 0.0;
+        }
+
+        let words: Vec<&str> = text.unicode_words().collect();
+        let total = words.len() as f32;
+        let long_words = words.iter().filter(|w| w.chars().count() > 6).count() as f32;
+        let monosyllables = words
+            .iter()
+            .filter(|w| ReadabilityLanguage::German.count_syllables(w) == 1)
+            .count() as f32;
+
+        let ms = stats.complex_word_percentage;
+        let sl = stats.avg_words_per_sentence;
+        let iw = long_words / total * 100.0;
+        let es = monosyllables / total * 100.0;
+
+        (0.1935 * ms + 0.1672 * sl + 0.1297 * iw - 0.0327 * es - 0.875).max(0.0)
+    }
+
     /// Flesch-Kincaid Grade Level
     fn flesch_kincaid_grade(&self, stats: &TextStats) -> f32 {
         if stats.word_count == 0 || stats.sentence_count == 0 {
@@ -496,6 +593,137 @@ pub enum IssueSeverity {
     Info,
 }
 
+/// Languages with a supported readability formula
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadabilityLanguage {
+    English,
+    Spanish,
+    German,
+}
+
+impl ReadabilityLanguage {
+    /// Parse a language code, ignoring any region (`es-MX`, `de_AT`)
+    pub fn from_code(code: &str) -> Option<Self> {
+        let primary = code
+            .trim()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match primary.as_str() {
+            "en" => Some(Self::English),
+            "es" => Some(Self::Spanish),
+            "de" => Some(Self::German),
+            _ => None,
+        }
+    }
+
+    /// ISO 639-1 code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::Spanish => "es",
+            Self::German => "de",
+        }
+    }
+
+    /// Count syllables using the language's vowel rules
+    pub fn count_syllables(&self, word: &str) -> usize {
+        match self {
+            Self::English => count_syllables(word),
+            Self::Spanish => count_syllables_spanish(word),
+            Self::German => count_syllables_german(word),
+        }
+    }
+
+    /// Grade for a reading ease score on this language's scale
+    fn grade(&self, reading_ease: f32) -> ReadabilityGrade {
+        match self {
+            // INFLESZ scale for Flesch-Szigriszt
+            Self::Spanish => match reading_ease as u32 {
+                80..=100 => ReadabilityGrade::VeryEasy,
+                65..=79 => ReadabilityGrade::FairlyEasy,
+                55..=64 => ReadabilityGrade::Standard,
+                40..=54 => ReadabilityGrade::FairlyDifficult,
+                _ => ReadabilityGrade::VeryDifficult,
+            },
+            // Amstad shares Flesch's scale
+            Self::English | Self::German => ReadabilityGrade::from_flesch(reading_ease),
+        }
+    }
+}
+
+/// Readability scoring was requested for a language without a formula
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[error("readability scoring is not supported for language '{code}'")]
+pub struct UnsupportedLanguage {
+    pub code: String,
+}
+
+/// Readability result from a language-specific formula
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageReadability {
+    pub language: ReadabilityLanguage,
+    /// Formula(s) used
+    pub formula: String,
+    /// Reading ease on a 0-100 scale (higher = easier)
+    pub reading_ease: f32,
+    /// School grade level, where the language has a grade formula
+    pub grade_level: Option<f32>,
+    pub grade: ReadabilityGrade,
+    pub stats: TextStats,
+    pub issues: Vec<ReadabilityIssue>,
+    pub suggestions: Vec<String>,
+}
+
+/// Count syllables in a Spanish word.
+///
+/// Each vowel group is a syllable, except that two strong vowels (a, e, o)
+/// or an accented weak vowel (í, ú) next to another vowel form a hiatus.
+fn count_syllables_spanish(word: &str) -> usize {
+    let word = word.to_lowercase();
+    let is_vowel = |c: char| "aeiouáéíóúü".contains(c);
+    let is_strong = |c: char| "aeoáéóíú".contains(c);
+
+    let mut count = 0;
+    let mut prev: Option<char> = None;
+    for c in word.chars() {
+        if is_vowel(c) {
+            match prev {
+                Some(p) if is_vowel(p) => {
+                    if is_strong(p) && is_strong(c) {
+                        count += 1;
+                    }
+                }
+                _ => count += 1,
+            }
+        }
+        prev = Some(c);
+    }
+
+    count.max(usize::from(word.chars().any(char::is_alphabetic)))
+}
+
+/// Count syllables in a German word: each vowel group (including umlauts and
+/// diphthongs like ei, au, eu, äu, ie) is one syllable
+fn count_syllables_german(word: &str) -> usize {
+    let word = word.to_lowercase();
+    let is_vowel = |c: char| "aeiouyäöü".contains(c);
+
+    let mut count = 0;
+    let mut prev_was_vowel = false;
+    for c in word.chars() {
+        let vowel = is_vowel(c);
+        if vowel && !prev_was_vowel {
+            count += 1;
+        }
+        prev_was_vowel = vowel;
+    }
+
+    count.max(usize::from(word.chars().any(char::is_alphabetic)))
+}
+
 /// Count syllables in a word (English approximation)
 fn count_syllables(word: &str) -> usize {
     let word = word.to_lowercase();
@@ -530,3 +758,61 @@ fn count_syllables(word: &str) -> usize {
 
     count.max(1)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_syllables() {
+        assert_eq!(count_syllables_spanish("casa"), 2);
+        assert_eq!(count_syllables_spanish("teatro"), 3); // te-a-tro
+        assert_eq!(count_syllables_spanish("ciudad"), 2); // ciu-dad
+        assert_eq!(count_syllables_spanish("día"), 2); // dí-a
+        assert_eq!(count_syllables_german("Haus"), 1);
+        assert_eq!(count_syllables_german("Bäckerei"), 3);
+        assert_eq!(count_syllables_german("Idee"), 2);
+    }
+
+    #[test]
+    fn test_analyze_with_language() {
+        let analyzer = ReadabilityAnalyzer::new();
+
+        let es = analyzer
+            .analyze_with_language("El gato come pan. La casa es grande.", "es-MX")
+            .unwrap();
+        assert_eq!(es.language, ReadabilityLanguage::Spanish);
+        assert!(es.reading_ease > 80.0);
+        assert!(es.grade_level.is_none());
+
+        let de = analyzer
+            .analyze_with_language(
+                "Die Verwaltungsgerichtsbarkeit überprüft behördliche Entscheidungen \
+                 hinsichtlich ihrer Rechtmäßigkeit und Verhältnismäßigkeit.",
+                "de_DE",
+            )
+            .unwrap();
+        assert_eq!(de.language, ReadabilityLanguage::German);
+        assert!(de.grade_level.unwrap() > 10.0);
+        assert!(de.reading_ease < 30.0);
+
+        let en = analyzer
+            .analyze_with_language("The cat sat on the mat.", "en")
+            .unwrap();
+        assert_eq!(
+            en.reading_ease,
+            analyzer
+                .analyze("The cat sat on the mat.")
+                .flesch_reading_ease
+        );
+
+        assert_eq!(
+            analyzer
+                .analyze_with_language("Le chat mange.", "fr")
+                .unwrap_err(),
+            UnsupportedLanguage {
+                code: "fr".to_string()
+            }
+        );
+    }
+}
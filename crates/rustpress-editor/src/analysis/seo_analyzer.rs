@@ -3,6 +3,7 @@
 //! Analyzes content for search engine optimization.
 
 use serde::{Deserialize, Serialize};
+use url::Url;
 
 /// SEO Analyzer for content optimization
 #[derive(Debug, Clone)]
@@ -36,27 +37,44 @@ impl SeoAnalyzer {
         self
     }
 
+    /// Set the analyzer configuration
+    pub fn with_config(mut self, config: SeoAnalyzerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
     /// Analyze content for SEO
     pub fn analyze(&self, content: &SeoContent) -> SeoAnalysisResult {
-        let mut checks = Vec::new();
-        let mut score = 0;
-        let mut max_score = 0;
-
-        // Title analysis
-        checks.push(self.check_title(&content.title));
-        max_score += 15;
-
-        // Meta description analysis
-        checks.push(self.check_meta_description(&content.meta_description));
-        max_score += 15;
+        self.build_result(self.run_checks(content))
+    }
 
-        // Content length
-        checks.push(self.check_content_length(content.word_count));
-        max_score += 10;
+    /// Analyze content, additionally checking outbound links for 4xx/5xx
+    /// responses. Slower than [`analyze`](Self::analyze) as it makes HTTP
+    /// requests.
+    #[cfg(feature = "link-check")]
+    pub async fn analyze_with_link_check(
+        &self,
+        content: &SeoContent,
+        checker: &LinkChecker,
+    ) -> SeoAnalysisResult {
+        let mut checks = self.run_checks(content);
+        let site_url = self.site_url();
+        let statuses = checker.check(&content.links, site_url.as_ref()).await;
+        checks.push(self.check_broken_links(&statuses));
+        self.build_result(checks)
+    }
 
-        // Heading structure
-        checks.push(self.check_headings(&content.headings));
-        max_score += 10;
+    fn run_checks(&self, content: &SeoContent) -> Vec<SeoCheck> {
+        let mut checks = vec![
+            // Title analysis
+            self.check_title(&content.title),
+            // Meta description analysis
+            self.check_meta_description(&content.meta_description),
+            // Content length
+            self.check_content_length(content.word_count),
+            // Heading structure
+            self.check_headings(&content.headings),
+        ];
 
         // Keyword usage
         if let Some(ref keyword) = self.focus_keyword {
@@ -69,24 +87,28 @@ impl SeoAnalyzer {
             ));
             checks.push(self.check_keyword_in_headings(keyword, &content.headings));
             checks.push(self.check_keyword_in_url(keyword, &content.slug));
-            max_score += 50;
         }
 
         // Image optimization
         checks.push(self.check_images(&content.images));
-        max_score += 10;
 
         // Internal/external links
-        checks.push(self.check_links(&content.links));
-        max_score += 10;
-
-        // Calculate score
-        for check in &checks {
-            if check.passed {
-                score += check.weight;
-            }
+        let kinds = self.classify_links(&content.links);
+        checks.push(self.check_links(&kinds));
+        if let Some(check) = self.check_external_link_rel(&content.links, &kinds) {
+            checks.push(check);
+        }
+        if let Some(inbound) = content.inbound_link_count {
+            checks.push(self.check_orphan_content(inbound));
         }
 
+        checks
+    }
+
+    fn build_result(&self, checks: Vec<SeoCheck>) -> SeoAnalysisResult {
+        let max_score: u32 = checks.iter().map(|c| c.weight).sum();
+        let score: u32 = checks.iter().filter(|c| c.passed).map(|c| c.weight).sum();
+
         let normalized_score = if max_score > 0 {
             ((score as f32 / max_score as f32) * 100.0) as u32
         } else {
@@ -416,9 +438,33 @@ impl SeoAnalyzer {
         }
     }
 
-    fn check_links(&self, links: &[LinkInfo]) -> SeoCheck {
-        let internal_count = links.iter().filter(|l| l.is_internal).count();
-        let external_count = links.iter().filter(|l| !l.is_internal).count();
+    fn site_url(&self) -> Option<Url> {
+        self.config
+            .site_url
+            .as_deref()
+            .and_then(|u| Url::parse(u).ok())
+    }
+
+    /// Classify links against the configured site URL, falling back to the
+    /// caller's `is_internal` flag when no site URL is set
+    fn classify_links(&self, links: &[LinkInfo]) -> Vec<LinkKind> {
+        let site_url = self.site_url();
+        links
+            .iter()
+            .map(|link| match &site_url {
+                Some(site) => classify_link(&link.href, site),
+                None => match classify_link_scheme(&link.href) {
+                    Some(kind) => kind,
+                    None if link.is_internal => LinkKind::Internal,
+                    None => LinkKind::External,
+                },
+            })
+            .collect()
+    }
+
+    fn check_links(&self, kinds: &[LinkKind]) -> SeoCheck {
+        let internal_count = kinds.iter().filter(|k| **k == LinkKind::Internal).count();
+        let external_count = kinds.iter().filter(|k| **k == LinkKind::External).count();
 
         let (passed, message) = if internal_count == 0 && external_count == 0 {
             (
@@ -452,6 +498,130 @@ impl SeoAnalyzer {
         }
     }
 
+    fn check_external_link_rel(&self, links: &[LinkInfo], kinds: &[LinkKind]) -> Option<SeoCheck> {
+        if !self.config.require_external_nofollow && !self.config.require_external_noopener {
+            return None;
+        }
+
+        let external: Vec<&LinkInfo> = links
+            .iter()
+            .zip(kinds)
+            .filter(|(_, kind)| **kind == LinkKind::External)
+            .map(|(link, _)| link)
+            .collect();
+        if external.is_empty() {
+            return None;
+        }
+
+        let missing_nofollow = external
+            .iter()
+            .filter(|l| self.config.require_external_nofollow && !l.is_nofollow)
+            .count();
+        // noopener only matters for links that open a new tab
+        let missing_noopener = external
+            .iter()
+            .filter(|l| self.config.require_external_noopener && l.opens_new_tab && !l.is_noopener)
+            .count();
+
+        let mut problems = Vec::new();
+        if missing_nofollow > 0 {
+            problems.push(format!(
+                "{} external link(s) missing rel=\"nofollow\"",
+                missing_nofollow
+            ));
+        }
+        if missing_noopener > 0 {
+            problems.push(format!(
+                "{} external link(s) opening a new tab missing rel=\"noopener\"",
+                missing_noopener
+            ));
+        }
+
+        let (passed, message) = if problems.is_empty() {
+            (
+                true,
+                format!(
+                    "All {} external links have the required rel attributes",
+                    external.len()
+                ),
+            )
+        } else {
+            (false, problems.join("; "))
+        };
+
+        Some(SeoCheck {
+            id: "external_link_rel".to_string(),
+            name: "External Link Attributes".to_string(),
+            passed,
+            message,
+            weight: 5,
+            category: SeoCategory::Links,
+        })
+    }
+
+    fn check_orphan_content(&self, inbound_links: u32) -> SeoCheck {
+        let (passed, message) = if inbound_links == 0 {
+            (
+                false,
+                "No internal links point to this content. Link to it from related posts."
+                    .to_string(),
+            )
+        } else {
+            (
+                true,
+                format!("{} internal link(s) point to this content", inbound_links),
+            )
+        };
+
+        SeoCheck {
+            id: "orphan_content".to_string(),
+            name: "Inbound Internal Links".to_string(),
+            passed,
+            message,
+            weight: 5,
+            category: SeoCategory::Links,
+        }
+    }
+
+    /// Check result for outbound link statuses from a [`LinkChecker`]
+    pub fn check_broken_links(&self, statuses: &[LinkStatus]) -> SeoCheck {
+        let broken: Vec<&LinkStatus> = statuses.iter().filter(|s| s.is_broken()).collect();
+        let unreachable = statuses.iter().filter(|s| s.error.is_some()).count();
+
+        let (passed, mut message) = if broken.is_empty() {
+            (
+                true,
+                format!("No broken links among {} checked", statuses.len()),
+            )
+        } else {
+            let examples: Vec<String> = broken
+                .iter()
+                .take(3)
+                .map(|s| format!("{} ({})", s.href, s.status.unwrap_or_default()))
+                .collect();
+            (
+                false,
+                format!(
+                    "{} broken link(s) found: {}",
+                    broken.len(),
+                    examples.join(", ")
+                ),
+            )
+        };
+        if unreachable > 0 {
+            message.push_str(&format!(". {} link(s) could not be reached", unreachable));
+        }
+
+        SeoCheck {
+            id: "broken_links".to_string(),
+            name: "Broken Links".to_string(),
+            passed,
+            message,
+            weight: 10,
+            category: SeoCategory::Links,
+        }
+    }
+
     fn generate_suggestions(&self, checks: &[SeoCheck]) -> Vec<String> {
         checks
             .iter()
@@ -477,6 +647,12 @@ pub struct SeoAnalyzerConfig {
     pub min_word_count: u32,
     pub keyword_density_min: f32,
     pub keyword_density_max: f32,
+    /// Site base URL, used to classify absolute links as internal
+    pub site_url: Option<String>,
+    /// Flag external links without `rel="nofollow"`
+    pub require_external_nofollow: bool,
+    /// Flag external links opening a new tab without `rel="noopener"`
+    pub require_external_noopener: bool,
 }
 
 impl Default for SeoAnalyzerConfig {
@@ -489,6 +665,9 @@ impl Default for SeoAnalyzerConfig {
             min_word_count: 300,
             keyword_density_min: 0.5,
             keyword_density_max: 2.5,
+            site_url: None,
+            require_external_nofollow: false,
+            require_external_noopener: true,
         }
     }
 }
@@ -505,6 +684,8 @@ pub struct SeoContent {
     pub headings: Vec<HeadingInfo>,
     pub images: Vec<ImageInfo>,
     pub links: Vec<LinkInfo>,
+    /// Internal links from other content pointing here, if known
+    pub inbound_link_count: Option<u32>,
 }
 
 /// Heading information
@@ -529,6 +710,220 @@ pub struct LinkInfo {
     pub text: String,
     pub is_internal: bool,
     pub is_nofollow: bool,
+    pub is_noopener: bool,
+    pub opens_new_tab: bool,
+}
+
+impl LinkInfo {
+    /// Build from an anchor's `href`, `rel` and `target` attributes
+    pub fn from_anchor(
+        href: &str,
+        text: &str,
+        rel: Option<&str>,
+        target: Option<&str>,
+        site_url: &Url,
+    ) -> Self {
+        let rel_has = |value: &str| {
+            rel.is_some_and(|r| r.split_whitespace().any(|v| v.eq_ignore_ascii_case(value)))
+        };
+        Self {
+            href: href.to_string(),
+            text: text.to_string(),
+            is_internal: classify_link(href, site_url) == LinkKind::Internal,
+            is_nofollow: rel_has("nofollow"),
+            is_noopener: rel_has("noopener") || rel_has("noreferrer"),
+            opens_new_tab: target.is_some_and(|t| t.eq_ignore_ascii_case("_blank")),
+        }
+    }
+}
+
+/// Link classification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    /// Same site, including relative URLs
+    Internal,
+    /// Another host
+    External,
+    /// In-page `#fragment`
+    Anchor,
+    /// mailto:, tel:, javascript: and other non-navigational links
+    Other,
+}
+
+/// Classify a link relative to the site URL.
+///
+/// Relative URLs (`/path`, `path`, `../path`, `?query`) are internal;
+/// absolute and protocol-relative (`//host`) URLs are internal only when
+/// their host matches the site, ignoring a leading `www.`.
+pub fn classify_link(href: &str, site_url: &Url) -> LinkKind {
+    if let Some(kind) = classify_link_scheme(href) {
+        return kind;
+    }
+
+    let href = href.trim();
+    let Ok(resolved) = site_url.join(href) else {
+        return LinkKind::Other;
+    };
+    if !matches!(resolved.scheme(), "http" | "https") {
+        return LinkKind::Other;
+    }
+
+    let normalize =
+        |host: Option<&str>| host.map(|h| h.trim_start_matches("www.").to_ascii_lowercase());
+    if normalize(resolved.host_str()) == normalize(site_url.host_str()) {
+        LinkKind::Internal
+    } else {
+        LinkKind::External
+    }
+}
+
+/// Classify links whose kind doesn't depend on the site: fragments,
+/// non-HTTP schemes, and absolute URLs without a known site (`None` if
+/// the site is needed)
+fn classify_link_scheme(href: &str) -> Option<LinkKind> {
+    let href = href.trim();
+    if href.is_empty() {
+        return Some(LinkKind::Other);
+    }
+    if href.starts_with('#') {
+        return Some(LinkKind::Anchor);
+    }
+    match Url::parse(href) {
+        Ok(url) if !matches!(url.scheme(), "http" | "https") => Some(LinkKind::Other),
+        _ => None,
+    }
+}
+
+/// Result of checking one outbound link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkStatus {
+    pub href: String,
+    /// HTTP status, if a response was received
+    pub status: Option<u16>,
+    /// Network error, if the request failed
+    pub error: Option<String>,
+}
+
+impl LinkStatus {
+    /// Whether the link returned a 4xx or 5xx status
+    pub fn is_broken(&self) -> bool {
+        self.status.is_some_and(|s| s >= 400)
+    }
+}
+
+/// Link checker settings. Checks are bounded so analysis stays fast.
+#[derive(Debug, Clone)]
+pub struct LinkCheckConfig {
+    /// Maximum links checked per analysis
+    pub max_links: usize,
+    /// Concurrent requests
+    pub max_concurrent: usize,
+    /// Minimum delay between starting requests
+    pub min_interval: std::time::Duration,
+    /// Per-request timeout
+    pub timeout: std::time::Duration,
+}
+
+impl Default for LinkCheckConfig {
+    fn default() -> Self {
+        Self {
+            max_links: 50,
+            max_concurrent: 4,
+            min_interval: std::time::Duration::from_millis(100),
+            timeout: std::time::Duration::from_secs(5),
+        }
+    }
+}
+
+/// Checks outbound links with rate-limited HEAD requests
+#[cfg(feature = "link-check")]
+#[derive(Debug, Clone)]
+pub struct LinkChecker {
+    client: reqwest::Client,
+    config: LinkCheckConfig,
+}
+
+#[cfg(feature = "link-check")]
+impl LinkChecker {
+    pub fn new(config: LinkCheckConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .redirect(reqwest::redirect::Policy::limited(5))
+            .user_agent("RustPress-LinkChecker/1.0")
+            .build()
+            .unwrap_or_default();
+        Self { client, config }
+    }
+
+    /// Check HTTP(S) links, resolving relative ones against `site_url` when
+    /// given. Duplicate URLs are checked once.
+    pub async fn check(&self, links: &[LinkInfo], site_url: Option<&Url>) -> Vec<LinkStatus> {
+        use futures::StreamExt;
+
+        let mut urls: Vec<String> = Vec::new();
+        for link in links {
+            let resolved = match site_url {
+                Some(site) => site.join(link.href.trim()).ok(),
+                None => Url::parse(link.href.trim()).ok(),
+            };
+            if let Some(mut url) = resolved.filter(|u| matches!(u.scheme(), "http" | "https")) {
+                url.set_fragment(None);
+                let url = url.to_string();
+                if !urls.contains(&url) {
+                    urls.push(url);
+                }
+            }
+        }
+        urls.truncate(self.config.max_links);
+
+        let next_start = tokio::sync::Mutex::new(tokio::time::Instant::now());
+        futures::stream::iter(urls)
+            .map(|url| {
+                let next_start = &next_start;
+                async move {
+                    {
+                        let mut next = next_start.lock().await;
+                        tokio::time::sleep_until(*next).await;
+                        *next = tokio::time::Instant::now() + self.config.min_interval;
+                    }
+                    self.check_url(url).await
+                }
+            })
+            .buffer_unordered(self.config.max_concurrent.max(1))
+            .collect()
+            .await
+    }
+
+    async fn check_url(&self, href: String) -> LinkStatus {
+        let mut response = self.client.head(&href).send().await;
+        // Some servers reject HEAD; retry those with GET
+        if let Ok(r) = &response {
+            if r.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+                response = self.client.get(&href).send().await;
+            }
+        }
+
+        match response {
+            Ok(r) => LinkStatus {
+                href,
+                status: Some(r.status().as_u16()),
+                error: None,
+            },
+            Err(e) => LinkStatus {
+                href,
+                status: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "link-check")]
+impl Default for LinkChecker {
+    fn default() -> Self {
+        Self::new(LinkCheckConfig::default())
+    }
 }
 
 /// SEO analysis result
@@ -607,3 +1002,119 @@ impl SeoGrade {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site() -> Url {
+        Url::parse("https://example.com/blog/").unwrap()
+    }
+
+    fn content(links: Vec<LinkInfo>, inbound_link_count: Option<u32>) -> SeoContent {
+        SeoContent {
+            title: "A post".to_string(),
+            slug: "a-post".to_string(),
+            meta_description: None,
+            plain_text: String::new(),
+            first_paragraph: String::new(),
+            word_count: 0,
+            headings: Vec::new(),
+            images: Vec::new(),
+            links,
+            inbound_link_count,
+        }
+    }
+
+    fn find<'a>(result: &'a SeoAnalysisResult, id: &str) -> Option<&'a SeoCheck> {
+        result.checks.iter().find(|c| c.id == id)
+    }
+
+    #[test]
+    fn test_classify_link() {
+        let site = site();
+        assert_eq!(classify_link("/about", &site), LinkKind::Internal);
+        assert_eq!(classify_link("../tags/rust", &site), LinkKind::Internal);
+        assert_eq!(classify_link("?page=2", &site), LinkKind::Internal);
+        assert_eq!(
+            classify_link("https://www.example.com/x", &site),
+            LinkKind::Internal
+        );
+        assert_eq!(classify_link("//example.com/x", &site), LinkKind::Internal);
+        assert_eq!(
+            classify_link("//cdn.other.org/x", &site),
+            LinkKind::External
+        );
+        assert_eq!(
+            classify_link("https://example.com.evil.io/", &site),
+            LinkKind::External
+        );
+        assert_eq!(classify_link("#section", &site), LinkKind::Anchor);
+        assert_eq!(
+            classify_link("mailto:me@example.com", &site),
+            LinkKind::Other
+        );
+        assert_eq!(classify_link("javascript:void(0)", &site), LinkKind::Other);
+    }
+
+    #[test]
+    fn test_external_link_rel_and_orphan_checks() {
+        let site = site();
+        let links = vec![
+            LinkInfo::from_anchor("/about", "About", None, None, &site),
+            LinkInfo::from_anchor(
+                "https://rust-lang.org",
+                "Rust",
+                Some("nofollow"),
+                Some("_blank"),
+                &site,
+            ),
+        ];
+        let analyzer = SeoAnalyzer::new().with_config(SeoAnalyzerConfig {
+            site_url: Some(site.to_string()),
+            require_external_nofollow: true,
+            ..Default::default()
+        });
+
+        let result = analyzer.analyze(&content(links, Some(0)));
+        let rel = find(&result, "external_link_rel").unwrap();
+        assert!(!rel.passed);
+        assert!(rel.message.contains("noopener"));
+        assert!(!rel.message.contains("nofollow\""));
+        assert!(!find(&result, "orphan_content").unwrap().passed);
+        assert!(find(&result, "links")
+            .unwrap()
+            .message
+            .contains("1 internal and 1 external"));
+
+        // Unknown inbound count skips the orphan check
+        let result = analyzer.analyze(&content(Vec::new(), None));
+        assert!(find(&result, "orphan_content").is_none());
+        assert!(find(&result, "external_link_rel").is_none());
+    }
+
+    #[test]
+    fn test_broken_links_check() {
+        let statuses = vec![
+            LinkStatus {
+                href: "https://a.example/".to_string(),
+                status: Some(200),
+                error: None,
+            },
+            LinkStatus {
+                href: "https://b.example/gone".to_string(),
+                status: Some(404),
+                error: None,
+            },
+            LinkStatus {
+                href: "https://c.example/".to_string(),
+                status: None,
+                error: Some("timed out".to_string()),
+            },
+        ];
+        let check = SeoAnalyzer::new().check_broken_links(&statuses);
+        assert!(!check.passed);
+        assert!(check.message.contains("https://b.example/gone (404)"));
+        assert!(check.message.contains("1 link(s) could not be reached"));
+    }
+}
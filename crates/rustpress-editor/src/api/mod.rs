@@ -2,7 +2,7 @@
 //!
 //! Request and response types for the editor REST API.
 
-use crate::blocks::{Block, BlockId, BlockType};
+use crate::blocks::{Block, BlockId, BlockType, ValidationResult};
 use crate::post::{Author, FeaturedMedia, PostDocument, PostStats, PublishStatus};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -544,6 +544,31 @@ pub struct SeoCheckResponse {
     pub category: String,
 }
 
+impl From<crate::analysis::seo_analyzer::SeoCheck> for SeoCheckResponse {
+    fn from(check: crate::analysis::seo_analyzer::SeoCheck) -> Self {
+        Self {
+            name: check.name,
+            passed: check.passed,
+            message: check.message,
+            category: serde_json::to_value(check.category)
+                .ok()
+                .and_then(|v| v.as_str().map(String::from))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl From<crate::analysis::seo_analyzer::SeoAnalysisResult> for SeoAnalysisResponse {
+    fn from(result: crate::analysis::seo_analyzer::SeoAnalysisResult) -> Self {
+        Self {
+            score: result.score,
+            grade: result.grade.label().to_string(),
+            checks: result.checks.into_iter().map(Into::into).collect(),
+            suggestions: result.suggestions,
+        }
+    }
+}
+
 /// Readability analysis response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReadabilityAnalysisResponse {
@@ -629,6 +654,26 @@ impl ApiError {
         }
     }
 
+    /// Validation error listing every invalid block with its path
+    pub fn invalid_blocks(result: &ValidationResult) -> Self {
+        let blocks: Vec<_> = result
+            .errors
+            .iter()
+            .map(|error| {
+                serde_json::json!({
+                    "path": error.location(),
+                    "code": error.code,
+                    "message": error.message,
+                    "severity": error.severity,
+                })
+            })
+            .collect();
+        Self::validation_error(
+            "Content contains invalid blocks",
+            serde_json::json!({ "blocks": blocks }),
+        )
+    }
+
     pub fn internal_error(message: &str) -> Self {
         Self {
             code: "INTERNAL_ERROR".to_string(),
@@ -14,8 +14,14 @@ pub mod transform;
 pub mod types;
 pub mod validation;
 
-pub use registry::{BlockDefinition, BlockRegistry, BlockSupports};
-pub use serialization::BlockSerializer;
-pub use transform::BlockTransformer;
+pub use registry::{
+    AttributeDefinition, AttributeKind, BlockDefinition, BlockRegistry, BlockSupports,
+};
+pub use serialization::{BlockParseError, BlockSerializer};
+pub use transform::{
+    BlockTransformError, BlockTransformer, TransformFn, TransformOutcome, TransformWarning,
+};
 pub use types::*;
-pub use validation::{BlockValidator, ValidationConfig, ValidationError, ValidationResult};
+pub use validation::{
+    BlockValidator, UnknownBlockPolicy, ValidationConfig, ValidationError, ValidationResult,
+};
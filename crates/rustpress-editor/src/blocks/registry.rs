@@ -92,6 +92,8 @@ impl BlockRegistry {
             },
             transforms_to: vec![BlockType::Heading, BlockType::List, BlockType::Quote],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![],
             example: None,
         });
 
@@ -116,6 +118,11 @@ impl BlockRegistry {
             },
             transforms_to: vec![BlockType::Paragraph, BlockType::Quote],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![
+                AttributeDefinition::required("level", AttributeKind::Integer),
+                AttributeDefinition::optional("content", AttributeKind::String),
+            ],
             example: None,
         });
 
@@ -139,6 +146,33 @@ impl BlockRegistry {
             },
             transforms_to: vec![BlockType::Paragraph, BlockType::Quote],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![AttributeDefinition::optional(
+                "list_type",
+                AttributeKind::String,
+            )],
+            example: None,
+        });
+
+        self.register(BlockDefinition {
+            block_type: BlockType::ListItem,
+            name: "List Item".to_string(),
+            description: "A single item within a list.".to_string(),
+            category: BlockCategory::Text,
+            icon: "list-item".to_string(),
+            keywords: vec![],
+            supports: BlockSupports {
+                color: true,
+                typography: true,
+                ..Default::default()
+            },
+            transforms_to: vec![BlockType::Paragraph],
+            parent: Some(BlockType::List),
+            allowed_blocks: None,
+            attributes: vec![AttributeDefinition::optional(
+                "content",
+                AttributeKind::String,
+            )],
             example: None,
         });
 
@@ -158,6 +192,8 @@ impl BlockRegistry {
             },
             transforms_to: vec![BlockType::Paragraph, BlockType::PullQuote],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![],
             example: None,
         });
 
@@ -179,6 +215,11 @@ impl BlockRegistry {
             },
             transforms_to: vec![BlockType::Paragraph, BlockType::Preformatted],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![AttributeDefinition::optional(
+                "language",
+                AttributeKind::String,
+            )],
             example: None,
         });
 
@@ -202,6 +243,12 @@ impl BlockRegistry {
             },
             transforms_to: vec![BlockType::Cover, BlockType::MediaText],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![
+                AttributeDefinition::optional("url", AttributeKind::String),
+                AttributeDefinition::optional("media_id", AttributeKind::Integer),
+                AttributeDefinition::optional("alt", AttributeKind::String),
+            ],
             example: None,
         });
 
@@ -219,6 +266,8 @@ impl BlockRegistry {
             },
             transforms_to: vec![],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![],
             example: None,
         });
 
@@ -236,6 +285,8 @@ impl BlockRegistry {
             },
             transforms_to: vec![BlockType::Cover],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![],
             example: None,
         });
 
@@ -257,6 +308,8 @@ impl BlockRegistry {
             },
             transforms_to: vec![],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![],
             example: None,
         });
 
@@ -281,6 +334,8 @@ impl BlockRegistry {
             },
             transforms_to: vec![BlockType::Image, BlockType::Video],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![],
             example: None,
         });
 
@@ -301,6 +356,8 @@ impl BlockRegistry {
             },
             transforms_to: vec![],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![AttributeDefinition::required("url", AttributeKind::String)],
             example: None,
         });
 
@@ -325,6 +382,8 @@ impl BlockRegistry {
             },
             transforms_to: vec![BlockType::Columns],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![],
             example: None,
         });
 
@@ -344,6 +403,11 @@ impl BlockRegistry {
             },
             transforms_to: vec![BlockType::Group],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![AttributeDefinition::optional(
+                "columns",
+                AttributeKind::Integer,
+            )],
             example: None,
         });
 
@@ -362,6 +426,8 @@ impl BlockRegistry {
             },
             transforms_to: vec![],
             parent: Some(BlockType::Columns),
+            allowed_blocks: None,
+            attributes: vec![],
             example: None,
         });
 
@@ -375,6 +441,11 @@ impl BlockRegistry {
             supports: BlockSupports::default(),
             transforms_to: vec![BlockType::Separator],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![AttributeDefinition::optional(
+                "spacer_height",
+                AttributeKind::String,
+            )],
             example: None,
         });
 
@@ -396,6 +467,8 @@ impl BlockRegistry {
             },
             transforms_to: vec![BlockType::Spacer],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![],
             example: None,
         });
 
@@ -416,6 +489,11 @@ impl BlockRegistry {
             },
             transforms_to: vec![],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![
+                AttributeDefinition::optional("button_text", AttributeKind::String),
+                AttributeDefinition::optional("href", AttributeKind::String),
+            ],
             example: None,
         });
 
@@ -434,6 +512,8 @@ impl BlockRegistry {
             },
             transforms_to: vec![],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![],
             example: None,
         });
 
@@ -453,6 +533,8 @@ impl BlockRegistry {
             },
             transforms_to: vec![],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![],
             example: None,
         });
 
@@ -470,6 +552,26 @@ impl BlockRegistry {
             },
             transforms_to: vec![],
             parent: None,
+            allowed_blocks: None,
+            attributes: vec![],
+            example: None,
+        });
+
+        self.register(BlockDefinition {
+            block_type: BlockType::Html,
+            name: "Custom HTML".to_string(),
+            description: "Add custom HTML code and preview it as you edit.".to_string(),
+            category: BlockCategory::Widgets,
+            icon: "html".to_string(),
+            keywords: vec!["embed".to_string(), "code".to_string()],
+            supports: BlockSupports::default(),
+            transforms_to: vec![],
+            parent: None,
+            allowed_blocks: None,
+            attributes: vec![AttributeDefinition::optional(
+                "content",
+                AttributeKind::String,
+            )],
             example: None,
         });
 
@@ -513,10 +615,76 @@ pub struct BlockDefinition {
     /// Required parent block type (for nested blocks)
     pub parent: Option<BlockType>,
 
+    /// Block types allowed as direct children (`None` = any)
+    #[serde(default)]
+    pub allowed_blocks: Option<Vec<BlockType>>,
+
+    /// Attributes checked when a document is saved
+    #[serde(default)]
+    pub attributes: Vec<AttributeDefinition>,
+
     /// Example block configuration
     pub example: Option<serde_json::Value>,
 }
 
+/// Declared block attribute
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributeDefinition {
+    /// Attribute name, either a built-in attribute or a key of `custom`
+    pub name: String,
+
+    /// Expected value type
+    pub kind: AttributeKind,
+
+    /// Whether the attribute must be set
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl AttributeDefinition {
+    pub fn required(name: impl Into<String>, kind: AttributeKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            required: true,
+        }
+    }
+
+    pub fn optional(name: impl Into<String>, kind: AttributeKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            required: false,
+        }
+    }
+}
+
+/// JSON type of an attribute value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeKind {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Array,
+    Object,
+}
+
+impl AttributeKind {
+    /// Whether `value` has this type
+    pub fn matches(&self, value: &serde_json::Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::Number => value.is_number(),
+            Self::Boolean => value.is_boolean(),
+            Self::Array => value.is_array(),
+            Self::Object => value.is_object(),
+        }
+    }
+}
+
 /// Block support flags
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct BlockSupports {
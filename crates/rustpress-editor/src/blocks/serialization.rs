@@ -1,9 +1,46 @@
 //! Block Serialization
 //!
 //! Convert blocks to/from various formats (HTML, Markdown, JSON).
+//!
+//! Round-trip guarantees:
+//! - JSON is lossless.
+//! - Block HTML (`to_block_html`) wraps each block in `<!-- wp:type {...} -->`
+//!   comment delimiters carrying all of its fields, so `from_block_html`
+//!   restores the exact blocks, nesting included. The markup between
+//!   delimiters is for display only and is ignored when parsing.
+//! - Markdown writes paragraphs, headings, flat lists, quotes, code, images
+//!   and separators as plain Markdown when that loses nothing but the block
+//!   ID. Any other block, or one carrying styles, classes or attributes
+//!   Markdown can't express, is embedded as block HTML so `from_markdown`
+//!   restores it.
+//! - Plain HTML (`to_html`) and plain text are output only.
 
 use crate::blocks::{Block, BlockStyles, BlockType, ListType, Spacing};
-use pulldown_cmark::{html, Options, Parser};
+use pulldown_cmark::{html, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+/// Errors parsing block HTML
+#[derive(Debug, thiserror::Error)]
+pub enum BlockParseError {
+    #[error("unknown block type `{0}`")]
+    UnknownBlockType(String),
+
+    #[error("invalid attributes for `{name}` block: {source}")]
+    InvalidAttributes {
+        name: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("`{0}` block is never closed")]
+    Unclosed(String),
+
+    #[error("unexpected closing delimiter for `{0}` block")]
+    UnexpectedClose(String),
+}
 
 /// Block serializer for multiple formats
 #[derive(Debug, Clone, Default)]
@@ -26,8 +63,12 @@ impl BlockSerializer {
 
     /// Convert a single block to HTML
     pub fn block_to_html(&self, block: &Block) -> String {
+        self.render_block(block, &self.to_html(&block.children))
+    }
+
+    /// Render a block's HTML around already-rendered children
+    fn render_block(&self, block: &Block, children_html: &str) -> String {
         let inner_html = Self::block_inner_html(block);
-        let children_html = self.to_html(&block.children);
 
         let class_attr = if !block.css_classes.is_empty() {
             format!(r#" class="{}""#, block.css_classes.join(" "))
@@ -479,16 +520,104 @@ impl BlockSerializer {
         text
     }
 
-    /// Convert blocks to Markdown
+    /// Convert blocks to comment-delimited block HTML
+    pub fn to_block_html(&self, blocks: &[Block]) -> String {
+        blocks.iter().map(|b| self.delimited_block(b)).collect()
+    }
+
+    /// Parse comment-delimited block HTML. HTML outside any delimiter
+    /// becomes an `Html` block.
+    pub fn from_block_html(&self, input: &str) -> Result<Vec<Block>, BlockParseError> {
+        parse_delimited(input, |freeform| {
+            let freeform = freeform.trim();
+            if freeform.is_empty() {
+                return Vec::new();
+            }
+            let mut block = Block::new(BlockType::Html);
+            block.attributes.content = Some(freeform.to_string());
+            vec![block]
+        })
+    }
+
+    fn delimited_block(&self, block: &Block) -> String {
+        let name = block_type_name(block.block_type);
+
+        let mut fields = match serde_json::to_value(block) {
+            Ok(Value::Object(map)) => map,
+            _ => Map::new(),
+        };
+        fields.remove("type");
+        fields.remove("children");
+        let json = Value::Object(fields)
+            .to_string()
+            // Keep the JSON from closing or opening a comment
+            .replace("--", "\\u002d\\u002d")
+            .replace('<', "\\u003c")
+            .replace('>', "\\u003e");
+
+        let children: String = block
+            .children
+            .iter()
+            .map(|c| self.delimited_block(c))
+            .collect();
+
+        // Render around a placeholder so comments in the block's own
+        // content can be neutralized without touching child delimiters
+        let placeholder = format!("\u{1}{}\u{1}", Uuid::new_v4());
+        let mut inner = self
+            .render_block(block, &placeholder)
+            .replace("<!--", "&lt;!--");
+        if inner.contains(&placeholder) {
+            inner = inner.replacen(&placeholder, &children, 1);
+        } else {
+            inner.push_str(&children);
+        }
+        if !inner.is_empty() && !inner.ends_with('\n') {
+            inner.push('\n');
+        }
+
+        format!(
+            "<!-- wp:{name} {json} -->\n{inner}<!-- /wp:{name} -->\n",
+            name = name,
+            json = json,
+            inner = inner
+        )
+    }
+
+    /// Convert blocks to Markdown. Blocks Markdown can't represent are
+    /// embedded as block HTML.
     pub fn to_markdown(&self, blocks: &[Block]) -> String {
         let mut md = String::new();
         for block in blocks {
-            md.push_str(&self.block_to_markdown(block));
+            match self.native_markdown(block) {
+                Some(native) => md.push_str(&native),
+                None => md.push_str(self.delimited_block(block).trim_end()),
+            }
             md.push_str("\n\n");
         }
         md.trim().to_string()
     }
 
+    /// Plain Markdown for a block, if it reads back as the same block
+    fn native_markdown(&self, block: &Block) -> Option<String> {
+        if !matches!(
+            block.block_type,
+            BlockType::Paragraph
+                | BlockType::Heading
+                | BlockType::List
+                | BlockType::Quote
+                | BlockType::Code
+                | BlockType::Image
+                | BlockType::Separator
+        ) {
+            return None;
+        }
+
+        let md = self.block_to_markdown(block);
+        let parsed = markdown_to_blocks(&md);
+        (parsed.len() == 1 && eq_ignoring_ids(block, &parsed[0])).then_some(md)
+    }
+
     fn block_to_markdown(&self, block: &Block) -> String {
         let content = block.attributes.content.as_deref().unwrap_or("");
 
@@ -560,23 +689,13 @@ impl BlockSerializer {
         }
     }
 
-    /// Parse Markdown into blocks
+    /// Parse Markdown into blocks, restoring any embedded block HTML.
+    /// Constructs without a block equivalent (tables, footnotes) become
+    /// `Html` blocks.
     pub fn from_markdown(&self, markdown: &str) -> Vec<Block> {
-        // Convert markdown to HTML first
-        let mut options = Options::empty();
-        options.insert(Options::ENABLE_TABLES);
-        options.insert(Options::ENABLE_STRIKETHROUGH);
-        options.insert(Options::ENABLE_TASKLISTS);
-
-        let parser = Parser::new_ext(markdown, options);
-        let mut html_output = String::new();
-        html::push_html(&mut html_output, parser);
-
-        // For now, return a single HTML block
-        // A more sophisticated parser would convert to proper blocks
-        let mut block = Block::new(BlockType::Html);
-        block.attributes.content = Some(html_output);
-        vec![block]
+        // Malformed delimiters are left for the Markdown parser as raw HTML
+        parse_delimited(markdown, markdown_to_blocks)
+            .unwrap_or_else(|_| markdown_to_blocks(markdown))
     }
 
     /// Convert blocks to JSON
@@ -590,6 +709,409 @@ impl BlockSerializer {
     }
 }
 
+/// Serialized name of a block type, e.g. `media_text`
+fn block_type_name(block_type: BlockType) -> String {
+    match serde_json::to_value(block_type) {
+        Ok(Value::String(name)) => name,
+        _ => format!("{:?}", block_type).to_lowercase(),
+    }
+}
+
+/// Parse comment-delimited blocks, passing text outside any block to
+/// `freeform`
+fn parse_delimited<F>(input: &str, freeform: F) -> Result<Vec<Block>, BlockParseError>
+where
+    F: Fn(&str) -> Vec<Block>,
+{
+    static DELIMITER: OnceLock<Regex> = OnceLock::new();
+    let delimiter = DELIMITER.get_or_init(|| {
+        Regex::new(r"(?s)<!--\s*(/?)wp:([a-z0-9_]+)\s*(.*?)\s*(/?)-->").expect("valid regex")
+    });
+
+    let mut blocks = Vec::new();
+    let mut open: Vec<(String, Block)> = Vec::new();
+    let mut last = 0;
+
+    for caps in delimiter.captures_iter(input) {
+        let whole = caps.get(0).expect("match");
+        // Markup inside an open block is display only
+        if open.is_empty() {
+            blocks.extend(freeform(&input[last..whole.start()]));
+        }
+        last = whole.end();
+
+        let name = caps[2].to_string();
+        let closing = !caps[1].is_empty();
+        let self_closing = !caps[4].is_empty();
+
+        let block = if closing {
+            match open.pop() {
+                Some((open_name, block)) if open_name == name => block,
+                _ => return Err(BlockParseError::UnexpectedClose(name)),
+            }
+        } else {
+            let block = decode_block(&name, &caps[3])?;
+            if !self_closing {
+                open.push((name, block));
+                continue;
+            }
+            block
+        };
+
+        match open.last_mut() {
+            Some((_, parent)) => parent.children.push(block),
+            None => blocks.push(block),
+        }
+    }
+
+    if let Some((name, _)) = open.pop() {
+        return Err(BlockParseError::Unclosed(name));
+    }
+    blocks.extend(freeform(&input[last..]));
+    Ok(blocks)
+}
+
+fn decode_block(name: &str, json: &str) -> Result<Block, BlockParseError> {
+    let block_type: BlockType = serde_json::from_value(Value::String(name.to_string()))
+        .map_err(|_| BlockParseError::UnknownBlockType(name.to_string()))?;
+    let invalid = |source| BlockParseError::InvalidAttributes {
+        name: name.to_string(),
+        source,
+    };
+
+    let mut fields = if json.is_empty() {
+        Map::new()
+    } else {
+        serde_json::from_str(json).map_err(invalid)?
+    };
+    fields.insert("type".to_string(), Value::String(name.to_string()));
+    fields
+        .entry("id")
+        .or_insert_with(|| Value::String(Uuid::new_v4().to_string()));
+    fields.entry("attributes").or_insert_with(|| {
+        serde_json::to_value(crate::blocks::BlockAttributes::default_for_type(
+            &block_type,
+        ))
+        .unwrap_or_else(|_| Value::Object(Map::new()))
+    });
+
+    serde_json::from_value(Value::Object(fields)).map_err(invalid)
+}
+
+/// Compare blocks ignoring their (and their children's) IDs
+fn eq_ignoring_ids(expected: &Block, actual: &Block) -> bool {
+    fn copy_ids(from: &Block, to: &mut Block) {
+        to.id = from.id;
+        for (from, to) in from.children.iter().zip(to.children.iter_mut()) {
+            copy_ids(from, to);
+        }
+    }
+
+    let mut actual = actual.clone();
+    copy_ids(expected, &mut actual);
+    *expected == actual
+}
+
+/// Convert Markdown to blocks
+fn markdown_to_blocks(markdown: &str) -> Vec<Block> {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let mut builder = MarkdownBlocks::default();
+    for event in Parser::new_ext(markdown, options) {
+        builder.event(event);
+    }
+    builder.blocks
+}
+
+/// Builds blocks from Markdown events
+#[derive(Default)]
+struct MarkdownBlocks<'a> {
+    blocks: Vec<Block>,
+    /// Open list, list item and quote blocks
+    open: Vec<Block>,
+    /// Inline HTML of the current paragraph, heading or list item
+    inline: String,
+    heading: Option<u8>,
+    /// Image at the start of the current paragraph: (url, alt)
+    image: Option<(String, String)>,
+    /// Alt text of the image being read
+    image_alt: Option<(String, String)>,
+    /// Code block being read: (language, code)
+    code: Option<(Option<String>, String)>,
+    html_block: Option<String>,
+    /// Events of a construct with no block equivalent, with nesting depth
+    unsupported: Option<(usize, Vec<Event<'a>>)>,
+}
+
+impl<'a> MarkdownBlocks<'a> {
+    fn event(&mut self, event: Event<'a>) {
+        if let Some((depth, events)) = &mut self.unsupported {
+            match &event {
+                Event::Start(_) => *depth += 1,
+                Event::End(_) => *depth -= 1,
+                _ => {}
+            }
+            let done = *depth == 0;
+            events.push(event);
+            if done {
+                let (_, events) = self.unsupported.take().expect("unsupported events");
+                let mut output = String::new();
+                html::push_html(&mut output, events.into_iter());
+                self.push_block(html_block(output.trim_end()));
+            }
+            return;
+        }
+
+        match event {
+            Event::Start(Tag::Table(_) | Tag::FootnoteDefinition(_) | Tag::MetadataBlock(_)) => {
+                self.flush_item_text();
+                self.unsupported = Some((1, vec![event]));
+            }
+
+            Event::Start(Tag::Paragraph) => {
+                self.flush_item_text();
+                self.inline.clear();
+                self.image = None;
+            }
+            Event::End(TagEnd::Paragraph) => self.end_paragraph(),
+
+            Event::Start(Tag::Heading { level, .. }) => {
+                self.flush_item_text();
+                self.inline.clear();
+                self.heading = Some(level as u8);
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                self.flush_image();
+                let mut heading = Block::new(BlockType::Heading);
+                heading.attributes.level = self.heading.take();
+                heading.attributes.content = Some(std::mem::take(&mut self.inline));
+                self.push_block(heading);
+            }
+
+            Event::Start(Tag::BlockQuote) => {
+                self.flush_item_text();
+                self.open.push(Block::new(BlockType::Quote));
+            }
+            Event::End(TagEnd::BlockQuote) => {
+                if let Some(mut quote) = self.open.pop() {
+                    // "> — Author" closing line is the citation
+                    if let Some(content) = &quote.attributes.content {
+                        if let Some((body, cite)) = content.rsplit_once("\n— ") {
+                            quote.attributes.citation = Some(cite.to_string());
+                            quote.attributes.content = Some(body.to_string());
+                        }
+                    }
+                    self.push_block(quote);
+                }
+            }
+
+            Event::Start(Tag::List(start)) => {
+                self.flush_item_text();
+                let mut list = Block::new(BlockType::List);
+                if let Some(start) = start {
+                    list.attributes.list_type = Some(ListType::Ordered);
+                    if start != 1 {
+                        list.attributes.start = u32::try_from(start).ok();
+                    }
+                }
+                self.open.push(list);
+            }
+            Event::End(TagEnd::List(_)) => {
+                if let Some(list) = self.open.pop() {
+                    self.push_block(list);
+                }
+            }
+            Event::Start(Tag::Item) => {
+                self.inline.clear();
+                self.open.push(Block::new(BlockType::ListItem));
+            }
+            Event::End(TagEnd::Item) => {
+                self.flush_item_text();
+                if let Some(item) = self.open.pop() {
+                    if let Some(list) = self.open.last_mut() {
+                        list.children.push(item);
+                    }
+                }
+            }
+
+            Event::Start(Tag::CodeBlock(kind)) => {
+                self.flush_item_text();
+                let language = match kind {
+                    CodeBlockKind::Fenced(lang) if !lang.is_empty() => Some(lang.to_string()),
+                    _ => None,
+                };
+                self.code = Some((language, String::new()));
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((language, code)) = self.code.take() {
+                    let mut block = Block::new(BlockType::Code);
+                    block.attributes.language = language;
+                    block.attributes.content =
+                        Some(code.strip_suffix('\n').unwrap_or(&code).to_string());
+                    self.push_block(block);
+                }
+            }
+
+            Event::Start(Tag::HtmlBlock) => {
+                self.flush_item_text();
+                self.html_block = Some(String::new());
+            }
+            Event::End(TagEnd::HtmlBlock) => {
+                if let Some(content) = self.html_block.take() {
+                    self.push_block(html_block(content.trim_end()));
+                }
+            }
+
+            Event::Rule => {
+                self.flush_item_text();
+                self.push_block(Block::new(BlockType::Separator));
+            }
+
+            Event::Text(text) => {
+                if let Some((_, code)) = &mut self.code {
+                    code.push_str(&text);
+                } else if let Some((_, alt)) = &mut self.image_alt {
+                    alt.push_str(&text);
+                } else {
+                    self.push_inline(&escape_text(&text));
+                }
+            }
+            Event::Html(html) => match &mut self.html_block {
+                Some(content) => content.push_str(&html),
+                None => self.push_inline(&html),
+            },
+            Event::InlineHtml(html) => self.push_inline(&html),
+            Event::Code(code) => self.push_inline(&format!("<code>{}</code>", escape_text(&code))),
+            Event::SoftBreak => self.push_inline("\n"),
+            Event::HardBreak => self.push_inline("<br>"),
+
+            Event::Start(Tag::Emphasis) => self.push_inline("<em>"),
+            Event::End(TagEnd::Emphasis) => self.push_inline("</em>"),
+            Event::Start(Tag::Strong) => self.push_inline("<strong>"),
+            Event::End(TagEnd::Strong) => self.push_inline("</strong>"),
+            Event::Start(Tag::Strikethrough) => self.push_inline("<del>"),
+            Event::End(TagEnd::Strikethrough) => self.push_inline("</del>"),
+            Event::Start(Tag::Link {
+                dest_url, title, ..
+            }) => {
+                let title = if title.is_empty() {
+                    String::new()
+                } else {
+                    format!(r#" title="{}""#, html_escape(&title))
+                };
+                self.push_inline(&format!(
+                    r#"<a href="{}"{}>"#,
+                    html_escape(&dest_url),
+                    title
+                ));
+            }
+            Event::End(TagEnd::Link) => self.push_inline("</a>"),
+
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                self.image_alt = Some((dest_url.to_string(), String::new()));
+            }
+            Event::End(TagEnd::Image) => {
+                if let Some((url, alt)) = self.image_alt.take() {
+                    if self.inline.is_empty() && self.image.is_none() {
+                        // May turn out to be a standalone image block
+                        self.image = Some((url, alt));
+                    } else {
+                        self.push_inline(&image_html(&url, &alt));
+                    }
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn push_inline(&mut self, html: &str) {
+        self.flush_image();
+        self.inline.push_str(html);
+    }
+
+    /// Write a pending leading image inline, since it has company
+    fn flush_image(&mut self) {
+        if let Some((url, alt)) = self.image.take() {
+            self.inline.push_str(&image_html(&url, &alt));
+        }
+    }
+
+    /// Text of a tight list item, before a nested block starts
+    fn flush_item_text(&mut self) {
+        if matches!(self.open.last(), Some(b) if b.block_type == BlockType::ListItem)
+            && (!self.inline.is_empty() || self.image.is_some())
+        {
+            self.flush_image();
+            let text = std::mem::take(&mut self.inline);
+            append_content(self.open.last_mut().expect("open item"), &text);
+        }
+    }
+
+    fn end_paragraph(&mut self) {
+        if self.inline.is_empty() {
+            if let Some((url, alt)) = self.image.take() {
+                let mut image = Block::new(BlockType::Image);
+                image.attributes.url = Some(url);
+                image.attributes.alt = (!alt.is_empty()).then_some(alt);
+                self.push_block(image);
+                return;
+            }
+        }
+
+        self.flush_image();
+        let text = std::mem::take(&mut self.inline);
+        match self.open.last_mut() {
+            Some(parent) if matches!(parent.block_type, BlockType::ListItem | BlockType::Quote) => {
+                append_content(parent, &text);
+            }
+            _ => {
+                let mut paragraph = Block::new(BlockType::Paragraph);
+                paragraph.attributes.content = Some(text);
+                self.push_block(paragraph);
+            }
+        }
+    }
+
+    fn push_block(&mut self, block: Block) {
+        match self.open.last_mut() {
+            Some(parent) => parent.children.push(block),
+            None => self.blocks.push(block),
+        }
+    }
+}
+
+fn append_content(block: &mut Block, text: &str) {
+    block.attributes.content = Some(match block.attributes.content.take() {
+        Some(existing) => format!("{}\n\n{}", existing, text),
+        None => text.to_string(),
+    });
+}
+
+fn html_block(content: &str) -> Block {
+    let mut block = Block::new(BlockType::Html);
+    block.attributes.content = Some(content.to_string());
+    block
+}
+
+fn image_html(url: &str, alt: &str) -> String {
+    format!(
+        r#"<img src="{}" alt="{}">"#,
+        html_escape(url),
+        html_escape(alt)
+    )
+}
+
+/// Escape text for HTML content, leaving quotes alone
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
 /// Escape HTML special characters
 fn html_escape(text: &str) -> String {
     text.replace('&', "&amp;")
@@ -616,3 +1138,242 @@ fn strip_html(html: &str) -> String {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn text_block(block_type: BlockType, content: &str) -> Block {
+        let mut block = Block::new(block_type);
+        block.attributes.content = Some(content.to_string());
+        block
+    }
+
+    fn nested_columns() -> Block {
+        let mut left = Block::new(BlockType::Column);
+        left.attributes.width = Some("33%".to_string());
+        left.children = vec![text_block(BlockType::Paragraph, "Left")];
+
+        let mut inner_group = Block::new(BlockType::Group);
+        inner_group.css_classes = vec!["card".to_string()];
+        inner_group.children = vec![text_block(BlockType::Heading, "Right")];
+        let mut right = Block::new(BlockType::Column);
+        right.children = vec![inner_group];
+
+        let mut columns = Block::new(BlockType::Columns);
+        columns.children = vec![left, right];
+        let mut group = Block::new(BlockType::Group);
+        group.children = vec![columns];
+        group
+    }
+
+    #[test]
+    fn test_block_html_round_trips_nested_groups() {
+        let serializer = BlockSerializer::new();
+        let blocks = vec![nested_columns(), text_block(BlockType::Paragraph, "After")];
+
+        let html = serializer.to_block_html(&blocks);
+        assert!(html.starts_with("<!-- wp:group {"));
+        assert!(html.contains("<!-- /wp:columns -->"));
+        assert_eq!(serializer.from_block_html(&html).unwrap(), blocks);
+    }
+
+    #[test]
+    fn test_block_html_neutralizes_comments_in_content() {
+        let serializer = BlockSerializer::new();
+        let blocks = vec![text_block(
+            BlockType::Paragraph,
+            "<!-- wp:heading --> and --> and <!-- /wp:paragraph -->",
+        )];
+
+        let html = serializer.to_block_html(&blocks);
+        assert_eq!(serializer.from_block_html(&html).unwrap(), blocks);
+    }
+
+    #[test]
+    fn test_block_html_freeform_and_errors() {
+        let serializer = BlockSerializer::new();
+
+        let blocks = serializer
+            .from_block_html("<p>Legacy</p>\n<!-- wp:separator /-->")
+            .unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].block_type, BlockType::Html);
+        assert_eq!(
+            blocks[0].attributes.content.as_deref(),
+            Some("<p>Legacy</p>")
+        );
+        assert_eq!(blocks[1].block_type, BlockType::Separator);
+
+        assert!(matches!(
+            serializer.from_block_html("<!-- wp:group {} --><p>x</p>"),
+            Err(BlockParseError::Unclosed(name)) if name == "group"
+        ));
+        assert!(matches!(
+            serializer.from_block_html("<!-- wp:group {} --><!-- /wp:column -->"),
+            Err(BlockParseError::UnexpectedClose(name)) if name == "column"
+        ));
+        assert!(matches!(
+            serializer.from_block_html("<!-- wp:nonsense /-->"),
+            Err(BlockParseError::UnknownBlockType(_))
+        ));
+    }
+
+    #[test]
+    fn test_markdown_native_blocks_round_trip() {
+        let serializer = BlockSerializer::new();
+
+        let mut heading = text_block(BlockType::Heading, "Title");
+        heading.attributes.level = Some(3);
+        let mut code = text_block(BlockType::Code, "fn main() {}");
+        code.attributes.language = Some("rust".to_string());
+        let mut quote = text_block(BlockType::Quote, "Be kind");
+        quote.attributes.citation = Some("Someone".to_string());
+        let list = crate::blocks::transform::paragraphs_to_list(
+            &[
+                text_block(BlockType::Paragraph, "One"),
+                text_block(BlockType::Paragraph, "Two"),
+            ],
+            true,
+        );
+        let mut image = Block::new(BlockType::Image);
+        image.attributes.url = Some("https://example.com/a.png".to_string());
+        image.attributes.alt = Some("A".to_string());
+
+        let blocks = vec![
+            heading,
+            text_block(BlockType::Paragraph, "Some <strong>bold</strong> text"),
+            code,
+            quote,
+            list,
+            image,
+            Block::new(BlockType::Separator),
+        ];
+
+        let md = serializer.to_markdown(&blocks);
+        assert!(!md.contains("<!-- wp:"), "{}", md);
+        assert!(md.starts_with("### Title"));
+
+        let parsed = serializer.from_markdown(&md);
+        assert_eq!(parsed.len(), blocks.len());
+        for (expected, actual) in blocks.iter().zip(&parsed) {
+            assert!(eq_ignoring_ids(expected, actual), "{:?}", actual);
+        }
+    }
+
+    #[test]
+    fn test_markdown_preserves_unsupported_blocks() {
+        let serializer = BlockSerializer::new();
+
+        let mut styled = text_block(BlockType::Paragraph, "Styled");
+        styled
+            .attributes
+            .custom
+            .insert("data-tracking".to_string(), serde_json::json!({"id": 7}));
+        styled.styles.text_color = Some("#333".to_string());
+        let blocks = vec![
+            text_block(BlockType::Paragraph, "Intro"),
+            nested_columns(),
+            styled,
+        ];
+
+        let md = serializer.to_markdown(&blocks);
+        assert!(md.starts_with("Intro\n\n<!-- wp:group"));
+
+        let parsed = serializer.from_markdown(&md);
+        assert_eq!(parsed.len(), 3);
+        assert!(eq_ignoring_ids(&blocks[0], &parsed[0]));
+        // Embedded blocks come back exactly, IDs included
+        assert_eq!(parsed[1..], blocks[1..]);
+    }
+
+    #[test]
+    fn test_markdown_tables_become_html_blocks() {
+        let parsed = BlockSerializer::new().from_markdown("| a | b |\n|---|---|\n| 1 | 2 |");
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].block_type, BlockType::Html);
+        assert!(parsed[0]
+            .attributes
+            .content
+            .as_deref()
+            .unwrap()
+            .starts_with("<table>"));
+    }
+
+    fn arb_leaf() -> impl Strategy<Value = Block> {
+        let block_types = prop::sample::select(vec![
+            BlockType::Paragraph,
+            BlockType::Heading,
+            BlockType::Quote,
+            BlockType::Code,
+            BlockType::Image,
+            BlockType::Html,
+            BlockType::Button,
+            BlockType::Separator,
+        ]);
+        (
+            any::<u128>(),
+            block_types,
+            proptest::option::of(".{0,24}"),
+            proptest::option::of(1u8..=6),
+            prop::collection::vec("[a-z][a-z-]{0,8}", 0..3),
+            prop::collection::hash_map("[a-z]{1,6}", "[ -~]{0,8}", 0..3),
+            proptest::option::of("#[0-9a-f]{6}"),
+            any::<bool>(),
+        )
+            .prop_map(
+                |(id, block_type, content, level, classes, custom, color, mobile)| {
+                    let mut block = Block::new(block_type);
+                    block.id = Uuid::from_u128(id);
+                    block.attributes.content = content;
+                    block.attributes.level = level;
+                    block.attributes.custom = custom
+                        .into_iter()
+                        .map(|(k, v)| (k, Value::String(v)))
+                        .collect();
+                    block.css_classes = classes;
+                    block.styles.text_color = color;
+                    block.visibility.mobile = mobile;
+                    block
+                },
+            )
+    }
+
+    fn arb_block() -> impl Strategy<Value = Block> {
+        arb_leaf().prop_recursive(3, 24, 4, |inner| {
+            (
+                arb_leaf(),
+                prop::sample::select(vec![
+                    BlockType::Group,
+                    BlockType::Columns,
+                    BlockType::Column,
+                    BlockType::List,
+                    BlockType::Quote,
+                ]),
+                prop::collection::vec(inner, 0..4),
+            )
+                .prop_map(|(mut block, block_type, children)| {
+                    block.block_type = block_type;
+                    block.children = children;
+                    block
+                })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_json_round_trip(blocks in prop::collection::vec(arb_block(), 0..4)) {
+            let serializer = BlockSerializer::new();
+            let json = serializer.to_json(&blocks).unwrap();
+            prop_assert_eq!(serializer.from_json(&json).unwrap(), blocks);
+        }
+
+        #[test]
+        fn test_block_html_round_trip(blocks in prop::collection::vec(arb_block(), 0..4)) {
+            let serializer = BlockSerializer::new();
+            let html = serializer.to_block_html(&blocks);
+            prop_assert_eq!(serializer.from_block_html(&html).unwrap(), blocks);
+        }
+    }
+}
@@ -1,76 +1,198 @@
 //! Block Transformations
 //!
 //! Transform blocks between different types while preserving content.
+//!
+//! Transforms are registered per `(from, to)` pair. When no direct transform
+//! exists, the shortest chain of registered transforms is used instead
+//! (e.g. List -> Paragraph -> Heading). Attributes the target block can't
+//! carry are dropped and reported as warnings.
 
 use crate::blocks::{Block, BlockType, GalleryImage, ListType};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::sync::Arc;
 use uuid::Uuid;
 
+/// Transform step. Receives the source block and a new block of the target
+/// type with content, styles, classes, anchor and custom attributes already
+/// copied over.
+pub type TransformFn = Arc<dyn Fn(&Block, &mut Block) + Send + Sync>;
+
+/// Block transform errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BlockTransformError {
+    #[error("no transform from {from:?} to {to:?}")]
+    NoTransformPath { from: BlockType, to: BlockType },
+}
+
+/// Attribute dropped during a transform
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransformWarning {
+    pub attribute: String,
+    pub message: String,
+}
+
+/// Result of a transform along with any dropped attributes
+#[derive(Debug, Clone)]
+pub struct TransformOutcome {
+    pub block: Block,
+    /// Block types passed through, including source and target
+    pub path: Vec<BlockType>,
+    pub warnings: Vec<TransformWarning>,
+}
+
 /// Block transformer
-#[derive(Debug, Clone, Default)]
-pub struct BlockTransformer;
+#[derive(Clone)]
+pub struct BlockTransformer {
+    /// Registered transforms by source type, in registration order
+    transforms: HashMap<BlockType, Vec<(BlockType, TransformFn)>>,
+}
+
+impl fmt::Debug for BlockTransformer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pairs: Vec<(BlockType, BlockType)> = self
+            .transforms
+            .iter()
+            .flat_map(|(from, targets)| targets.iter().map(move |(to, _)| (*from, *to)))
+            .collect();
+        f.debug_struct("BlockTransformer")
+            .field("transforms", &pairs)
+            .finish()
+    }
+}
+
+impl Default for BlockTransformer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl BlockTransformer {
-    /// Create a new block transformer
+    /// Create a new block transformer with the built-in transforms
     pub fn new() -> Self {
-        Self
+        let mut transformer = Self::empty();
+        transformer.register_defaults();
+        transformer
     }
 
-    /// Transform a block to a different type
-    pub fn transform(&self, block: &Block, target_type: BlockType) -> Option<Block> {
-        // Check if transformation is valid
-        if !self.can_transform(&block.block_type, &target_type) {
-            return None;
+    /// Create a transformer with no transforms registered
+    pub fn empty() -> Self {
+        Self {
+            transforms: HashMap::new(),
         }
+    }
 
-        let mut new_block = Block::new(target_type);
-        new_block.id = Uuid::new_v4();
+    /// Register a transform, replacing any existing one for the same pair
+    pub fn register<F>(&mut self, from: BlockType, to: BlockType, transform: F)
+    where
+        F: Fn(&Block, &mut Block) + Send + Sync + 'static,
+    {
+        let targets = self.transforms.entry(from).or_default();
+        let transform: TransformFn = Arc::new(transform);
+        match targets.iter_mut().find(|(target, _)| *target == to) {
+            Some(existing) => existing.1 = transform,
+            None => targets.push((to, transform)),
+        }
+    }
 
-        // Transfer common attributes
-        self.transfer_attributes(block, &mut new_block);
+    /// Transform a block to a different type
+    pub fn transform(
+        &self,
+        block: &Block,
+        target_type: BlockType,
+    ) -> Result<Block, BlockTransformError> {
+        let outcome = self.transform_with_warnings(block, target_type)?;
+        for warning in &outcome.warnings {
+            tracing::warn!(block_id = %block.id, "{}", warning.message);
+        }
+        Ok(outcome.block)
+    }
 
-        // Apply type-specific transformations
-        self.apply_type_transformation(block, &mut new_block);
+    /// Transform a block, returning the path taken and dropped attributes
+    pub fn transform_with_warnings(
+        &self,
+        block: &Block,
+        target_type: BlockType,
+    ) -> Result<TransformOutcome, BlockTransformError> {
+        let path = self.find_path(block.block_type, target_type).ok_or(
+            BlockTransformError::NoTransformPath {
+                from: block.block_type,
+                to: target_type,
+            },
+        )?;
+
+        let mut current = block.clone();
+        for step in path.windows(2) {
+            let transform = self
+                .get(step[0], step[1])
+                .expect("path uses registered transforms");
+            let mut next = Block::new(step[1]);
+            next.id = Uuid::new_v4();
+            self.transfer_attributes(&current, &mut next);
+            transform(&current, &mut next);
+            current = next;
+        }
 
-        Some(new_block)
+        let warnings = dropped_attributes(block, &current);
+        Ok(TransformOutcome {
+            block: current,
+            path,
+            warnings,
+        })
     }
 
-    /// Check if a transformation is valid
+    /// Check if a block type can be transformed to another, directly or
+    /// through intermediate types
     pub fn can_transform(&self, from: &BlockType, to: &BlockType) -> bool {
-        let valid_transforms = self.get_valid_transforms(from);
-        valid_transforms.contains(to)
+        from != to && self.find_path(*from, *to).is_some()
     }
 
-    /// Get valid transformation targets for a block type
+    /// Get direct transformation targets for a block type
     pub fn get_valid_transforms(&self, block_type: &BlockType) -> Vec<BlockType> {
-        match block_type {
-            BlockType::Paragraph => vec![
-                BlockType::Heading,
-                BlockType::List,
-                BlockType::Quote,
-                BlockType::Code,
-                BlockType::Preformatted,
-                BlockType::PullQuote,
-            ],
-            BlockType::Heading => vec![BlockType::Paragraph, BlockType::Quote],
-            BlockType::List => vec![BlockType::Paragraph, BlockType::Quote],
-            BlockType::Quote => vec![
-                BlockType::Paragraph,
-                BlockType::Heading,
-                BlockType::PullQuote,
-            ],
-            BlockType::Code => vec![BlockType::Paragraph, BlockType::Preformatted],
-            BlockType::Preformatted => vec![BlockType::Paragraph, BlockType::Code],
-            BlockType::PullQuote => vec![BlockType::Quote, BlockType::Paragraph],
-            BlockType::Image => vec![BlockType::Cover, BlockType::MediaText, BlockType::Gallery],
-            BlockType::Video => vec![BlockType::Cover, BlockType::Embed],
-            BlockType::Cover => vec![BlockType::Image, BlockType::Video, BlockType::Group],
-            BlockType::MediaText => vec![BlockType::Image, BlockType::Group],
-            BlockType::Group => vec![BlockType::Columns, BlockType::Cover, BlockType::Section],
-            BlockType::Columns => vec![BlockType::Group],
-            BlockType::Spacer => vec![BlockType::Separator],
-            BlockType::Separator => vec![BlockType::Spacer],
-            _ => vec![],
+        self.transforms
+            .get(block_type)
+            .map(|targets| targets.iter().map(|(to, _)| *to).collect())
+            .unwrap_or_default()
+    }
+
+    /// Shortest chain of registered transforms from one type to another
+    pub fn find_path(&self, from: BlockType, to: BlockType) -> Option<Vec<BlockType>> {
+        if from == to {
+            return None;
         }
+
+        let mut previous: HashMap<BlockType, BlockType> = HashMap::new();
+        let mut visited = HashSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(current) = queue.pop_front() {
+            for next in self.get_valid_transforms(&current) {
+                if !visited.insert(next) {
+                    continue;
+                }
+                previous.insert(next, current);
+                if next == to {
+                    let mut path = vec![to];
+                    let mut node = to;
+                    while let Some(&prev) = previous.get(&node) {
+                        path.push(prev);
+                        node = prev;
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                queue.push_back(next);
+            }
+        }
+        None
+    }
+
+    fn get(&self, from: BlockType, to: BlockType) -> Option<&TransformFn> {
+        self.transforms
+            .get(&from)?
+            .iter()
+            .find(|(target, _)| *target == to)
+            .map(|(_, transform)| transform)
     }
 
     /// Transfer common attributes between blocks
@@ -88,152 +210,179 @@ impl BlockTransformer {
 
         // Transfer anchor from meta
         target.meta.anchor = source.meta.anchor.clone();
-    }
-
-    /// Apply type-specific transformation logic
-    fn apply_type_transformation(&self, source: &Block, target: &mut Block) {
-        match (&source.block_type, &target.block_type) {
-            // Paragraph to Heading
-            (BlockType::Paragraph, BlockType::Heading) => {
-                target.attributes.level = Some(2); // Default to H2
-            }
-
-            // Heading to Paragraph
-            (BlockType::Heading, BlockType::Paragraph) => {
-                target.attributes.level = None;
-            }
-
-            // Paragraph to List
-            (BlockType::Paragraph, BlockType::List) => {
-                // Split content into list items by newlines
-                if let Some(content) = &source.attributes.content {
-                    target.attributes.list_type = Some(ListType::Unordered);
-                    // Create child ListItem blocks
-                    target.children = content
-                        .lines()
-                        .map(|line| {
-                            let mut item = Block::new(BlockType::ListItem);
-                            item.attributes.content = Some(line.to_string());
-                            item
-                        })
-                        .collect();
-                }
-            }
-
-            // List to Paragraph
-            (BlockType::List, BlockType::Paragraph) => {
-                // Combine all list item content
-                let content: String = source
-                    .children
-                    .iter()
-                    .filter_map(|child| child.attributes.content.clone())
-                    .collect::<Vec<_>>()
-                    .join("\n");
-                target.attributes.content = Some(content);
-            }
-
-            // Paragraph/Heading to Quote
-            (BlockType::Paragraph | BlockType::Heading, BlockType::Quote) => {
-                // Content transfers automatically
-            }
-
-            // Quote to PullQuote
-            (BlockType::Quote, BlockType::PullQuote) => {
-                target.attributes.citation = source.attributes.citation.clone();
-            }
-
-            // PullQuote to Quote
-            (BlockType::PullQuote, BlockType::Quote) => {
-                target.attributes.citation = source.attributes.citation.clone();
-            }
-
-            // Image to Cover
-            (BlockType::Image, BlockType::Cover) => {
-                target.attributes.url = source.attributes.url.clone();
-                target.attributes.media_id = source.attributes.media_id;
-                target.attributes.alt = source.attributes.alt.clone();
-            }
-
-            // Cover to Image
-            (BlockType::Cover, BlockType::Image) => {
-                target.attributes.url = source.attributes.url.clone();
-                target.attributes.media_id = source.attributes.media_id;
-                target.attributes.alt = source.attributes.alt.clone();
-            }
-
-            // Image to Gallery
-            (BlockType::Image, BlockType::Gallery) => {
-                if let Some(url) = &source.attributes.url {
-                    target.attributes.images = vec![GalleryImage {
-                        id: source.attributes.media_id.unwrap_or(0),
-                        url: url.clone(),
-                        alt: source.attributes.alt.clone(),
-                        caption: source.attributes.caption.clone(),
-                        link: None,
-                    }];
-                }
-                target.attributes.columns = Some(3);
-            }
 
-            // Image to MediaText
-            (BlockType::Image, BlockType::MediaText) => {
-                target.attributes.url = source.attributes.url.clone();
-                target.attributes.media_id = source.attributes.media_id;
-                target.attributes.alt = source.attributes.alt.clone();
-            }
+        // Custom attributes are opaque to the transformer, keep them
+        target.attributes.custom = source.attributes.custom.clone();
+    }
 
-            // Video to Cover
-            (BlockType::Video, BlockType::Cover) => {
-                target.attributes.url = source.attributes.url.clone();
+    /// Register the built-in transforms
+    fn register_defaults(&mut self) {
+        use BlockType::*;
+
+        // Paragraph
+        self.register(Paragraph, Heading, |_, target| {
+            target.attributes.level = Some(2); // Default to H2
+        });
+        self.register(Paragraph, List, |source, target| {
+            // Split content into list items by newlines
+            if let Some(content) = &source.attributes.content {
+                target.attributes.list_type = Some(ListType::Unordered);
+                // Create child ListItem blocks
+                target.children = content
+                    .lines()
+                    .map(|line| {
+                        let mut item = Block::new(BlockType::ListItem);
+                        item.attributes.content = Some(line.to_string());
+                        item
+                    })
+                    .collect();
             }
-
-            // Code to Preformatted
-            (BlockType::Code, BlockType::Preformatted) => {
-                // Content transfers automatically
-                // Remove language-specific settings
+        });
+        self.register(Paragraph, Quote, |_, _| {});
+        self.register(Paragraph, Code, |_, _| {});
+        self.register(Paragraph, Preformatted, |_, _| {});
+        self.register(Paragraph, PullQuote, |_, _| {});
+
+        // Heading
+        self.register(Heading, Paragraph, |_, target| {
+            target.attributes.level = None;
+        });
+        self.register(Heading, Quote, |_, _| {});
+
+        // List
+        self.register(List, Paragraph, |source, target| {
+            target.attributes.content = Some(list_content(source));
+        });
+        self.register(List, Quote, |source, target| {
+            target.attributes.content = Some(list_content(source));
+        });
+
+        // Quote
+        self.register(Quote, Paragraph, |_, _| {});
+        self.register(Quote, Heading, |_, _| {});
+        self.register(Quote, PullQuote, |source, target| {
+            target.attributes.citation = source.attributes.citation.clone();
+        });
+
+        // Code
+        self.register(Code, Paragraph, |_, _| {});
+        // Remove language-specific settings
+        self.register(Code, Preformatted, |_, _| {});
+        self.register(Preformatted, Paragraph, |_, _| {});
+        self.register(Preformatted, Code, |_, target| {
+            target.attributes.language = None;
+        });
+
+        self.register(PullQuote, Quote, |source, target| {
+            target.attributes.citation = source.attributes.citation.clone();
+        });
+        self.register(PullQuote, Paragraph, |_, _| {});
+
+        // Media
+        self.register(Image, Cover, copy_media);
+        self.register(Image, MediaText, copy_media);
+        self.register(Image, Gallery, |source, target| {
+            if let Some(url) = &source.attributes.url {
+                target.attributes.images = vec![GalleryImage {
+                    id: source.attributes.media_id.unwrap_or(0),
+                    url: url.clone(),
+                    alt: source.attributes.alt.clone(),
+                    caption: source.attributes.caption.clone(),
+                    link: None,
+                }];
             }
-
-            // Preformatted to Code
-            (BlockType::Preformatted, BlockType::Code) => {
-                // Content transfers automatically
-                target.attributes.language = None;
-            }
-
-            // Group to Columns
-            (BlockType::Group, BlockType::Columns) => {
-                // Wrap existing children in a single column
-                if !source.children.is_empty() {
-                    let mut column = Block::new(BlockType::Column);
-                    column.children = source.children.clone();
-                    target.children = vec![column];
-                }
+            target.attributes.columns = Some(3);
+        });
+        self.register(Video, Cover, |source, target| {
+            target.attributes.url = source.attributes.url.clone();
+        });
+        self.register(Video, Embed, |source, target| {
+            target.attributes.url = source.attributes.url.clone();
+        });
+        self.register(Cover, Image, copy_media);
+        self.register(Cover, Video, |source, target| {
+            target.attributes.url = source.attributes.url.clone();
+        });
+        self.register(Cover, Group, |source, target| {
+            target.children = source.children.clone();
+        });
+        self.register(MediaText, Image, copy_media);
+        self.register(MediaText, Group, |source, target| {
+            target.children = source.children.clone();
+        });
+
+        // Layout
+        self.register(Group, Columns, |source, target| {
+            // Wrap existing children in a single column
+            if !source.children.is_empty() {
+                let mut column = Block::new(BlockType::Column);
+                column.children = source.children.clone();
+                target.children = vec![column];
             }
+        });
+        self.register(Group, Cover, |source, target| {
+            target.children = source.children.clone();
+        });
+        self.register(Group, Section, |source, target| {
+            target.children = source.children.clone();
+        });
+        self.register(Columns, Group, |source, target| {
+            // Flatten columns into group
+            target.children = source
+                .children
+                .iter()
+                .flat_map(|column| column.children.clone())
+                .collect();
+        });
 
-            // Columns to Group
-            (BlockType::Columns, BlockType::Group) => {
-                // Flatten columns into group
-                let mut all_children = Vec::new();
-                for column in &source.children {
-                    all_children.extend(column.children.clone());
-                }
-                target.children = all_children;
-            }
+        self.register(Spacer, Separator, |_, _| {});
+        self.register(Separator, Spacer, |_, target| {
+            target.attributes.spacer_height = Some("100px".to_string());
+        });
+    }
+}
 
-            // Spacer to Separator
-            (BlockType::Spacer, BlockType::Separator) => {
-                // No content to transfer
-            }
+fn copy_media(source: &Block, target: &mut Block) {
+    target.attributes.url = source.attributes.url.clone();
+    target.attributes.media_id = source.attributes.media_id;
+    target.attributes.alt = source.attributes.alt.clone();
+}
 
-            // Separator to Spacer
-            (BlockType::Separator, BlockType::Spacer) => {
-                target.attributes.spacer_height = Some("100px".to_string());
-            }
+/// Combine all list item content
+fn list_content(list: &Block) -> String {
+    list.children
+        .iter()
+        .filter_map(|child| child.attributes.content.clone())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-            _ => {
-                // No specific transformation needed
-            }
+/// Attributes set on the source block that the transformed block lost
+fn dropped_attributes(source: &Block, target: &Block) -> Vec<TransformWarning> {
+    let set_keys = |block: &Block| -> Vec<String> {
+        match serde_json::to_value(&block.attributes) {
+            Ok(serde_json::Value::Object(map)) => map.keys().cloned().collect(),
+            _ => Vec::new(),
         }
-    }
+    };
+
+    let kept: HashSet<String> = set_keys(target).into_iter().collect();
+    let mut dropped: Vec<String> = set_keys(source)
+        .into_iter()
+        .filter(|key| !kept.contains(key))
+        .collect();
+    dropped.sort();
+
+    dropped
+        .into_iter()
+        .map(|attribute| TransformWarning {
+            message: format!(
+                "attribute `{}` is not supported by {:?} and was dropped",
+                attribute, target.block_type
+            ),
+            attribute,
+        })
+        .collect()
 }
 
 /// Transform multiple paragraphs into a single list
@@ -330,3 +479,152 @@ pub fn split_block(block: &Block, at_offset: usize) -> Option<(Block, Block)> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_block(block_type: BlockType, content: &str) -> Block {
+        let mut block = Block::new(block_type);
+        block.attributes.content = Some(content.to_string());
+        block
+    }
+
+    #[test]
+    fn test_common_conversions_preserve_content() {
+        let transformer = BlockTransformer::new();
+        let matrix = [
+            (BlockType::Paragraph, BlockType::Heading),
+            (BlockType::Paragraph, BlockType::Quote),
+            (BlockType::Paragraph, BlockType::Code),
+            (BlockType::Heading, BlockType::Paragraph),
+            (BlockType::Heading, BlockType::Quote),
+            (BlockType::Quote, BlockType::Heading),
+            (BlockType::Quote, BlockType::PullQuote),
+            (BlockType::Code, BlockType::Preformatted),
+            (BlockType::Preformatted, BlockType::Paragraph),
+            // Multi-step
+            (BlockType::Heading, BlockType::Code),
+            (BlockType::Code, BlockType::Heading),
+            (BlockType::PullQuote, BlockType::Heading),
+        ];
+
+        for (from, to) in matrix {
+            let source = text_block(from, "Hello world");
+            let result = transformer
+                .transform(&source, to)
+                .unwrap_or_else(|e| panic!("{:?} -> {:?}: {}", from, to, e));
+            assert_eq!(result.block_type, to);
+            assert_eq!(
+                result.attributes.content.as_deref(),
+                Some("Hello world"),
+                "{:?} -> {:?}",
+                from,
+                to
+            );
+        }
+    }
+
+    #[test]
+    fn test_list_to_quote_and_heading() {
+        let transformer = BlockTransformer::new();
+        let list = paragraphs_to_list(
+            &[
+                text_block(BlockType::Paragraph, "One"),
+                text_block(BlockType::Paragraph, "Two"),
+            ],
+            false,
+        );
+
+        let quote = transformer.transform(&list, BlockType::Quote).unwrap();
+        assert_eq!(quote.attributes.content.as_deref(), Some("One\nTwo"));
+
+        let outcome = transformer
+            .transform_with_warnings(&list, BlockType::Heading)
+            .unwrap();
+        assert_eq!(
+            outcome.path,
+            vec![BlockType::List, BlockType::Paragraph, BlockType::Heading]
+        );
+        assert_eq!(
+            outcome.block.attributes.content.as_deref(),
+            Some("One\nTwo")
+        );
+        assert_eq!(outcome.block.attributes.level, Some(2));
+    }
+
+    #[test]
+    fn test_group_columns_round_trip() {
+        let transformer = BlockTransformer::new();
+        let mut group = Block::new(BlockType::Group);
+        group.children = vec![text_block(BlockType::Paragraph, "Inside")];
+
+        let columns = transformer.transform(&group, BlockType::Columns).unwrap();
+        assert_eq!(columns.children[0].block_type, BlockType::Column);
+
+        let back = transformer.transform(&columns, BlockType::Group).unwrap();
+        assert_eq!(back.children.len(), 1);
+        assert_eq!(
+            back.children[0].attributes.content.as_deref(),
+            Some("Inside")
+        );
+    }
+
+    #[test]
+    fn test_dropped_attributes_are_reported() {
+        let transformer = BlockTransformer::new();
+        let mut heading = text_block(BlockType::Heading, "Title");
+        heading.attributes.level = Some(3);
+        heading
+            .attributes
+            .custom
+            .insert("data-id".to_string(), serde_json::json!(7));
+
+        let outcome = transformer
+            .transform_with_warnings(&heading, BlockType::Paragraph)
+            .unwrap();
+        let dropped: Vec<&str> = outcome
+            .warnings
+            .iter()
+            .map(|w| w.attribute.as_str())
+            .collect();
+        assert_eq!(dropped, vec!["level"]);
+        assert_eq!(outcome.block.attributes.custom["data-id"], 7);
+    }
+
+    #[test]
+    fn test_no_path_is_an_error() {
+        let transformer = BlockTransformer::new();
+        let para = text_block(BlockType::Paragraph, "Text");
+
+        assert_eq!(
+            transformer.transform(&para, BlockType::Image).unwrap_err(),
+            BlockTransformError::NoTransformPath {
+                from: BlockType::Paragraph,
+                to: BlockType::Image,
+            }
+        );
+        assert!(transformer.transform(&para, BlockType::Paragraph).is_err());
+    }
+
+    #[test]
+    fn test_registered_transform_is_used_and_replaces() {
+        let mut transformer = BlockTransformer::empty();
+        transformer.register(BlockType::Paragraph, BlockType::Verse, |_, target| {
+            target.attributes.align = None;
+        });
+        transformer.register(BlockType::Paragraph, BlockType::Verse, |source, target| {
+            target.attributes.content =
+                source.attributes.content.as_ref().map(|c| c.to_uppercase());
+        });
+
+        let verse = transformer
+            .transform(&text_block(BlockType::Paragraph, "la"), BlockType::Verse)
+            .unwrap();
+        assert_eq!(verse.attributes.content.as_deref(), Some("LA"));
+        assert_eq!(
+            transformer.get_valid_transforms(&BlockType::Paragraph),
+            vec![BlockType::Verse]
+        );
+    }
+}
@@ -33,7 +33,7 @@ pub enum BlockCategory {
 }
 
 /// Core block structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Block {
     /// Unique block ID
     pub id: BlockId,
@@ -443,7 +443,7 @@ impl BlockType {
 }
 
 /// Block attributes container
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct BlockAttributes {
     /// Rich text content (for text blocks)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -747,7 +747,7 @@ impl BlockAttributes {
 }
 
 /// Gallery image
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GalleryImage {
     pub id: i64,
     pub url: String,
@@ -808,7 +808,7 @@ pub enum AlertType {
 }
 
 /// Table data structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TableData {
     pub headers: Vec<String>,
     pub rows: Vec<Vec<String>>,
@@ -818,14 +818,14 @@ pub struct TableData {
 }
 
 /// Map coordinates
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MapCoordinates {
     pub lat: f64,
     pub lng: f64,
 }
 
 /// Chart data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ChartData {
     pub chart_type: ChartType,
     pub labels: Vec<String>,
@@ -843,7 +843,7 @@ pub enum ChartType {
     Radar,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ChartDataset {
     pub label: String,
     pub data: Vec<f64>,
@@ -851,7 +851,7 @@ pub struct ChartDataset {
 }
 
 /// Select option
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SelectOption {
     pub label: String,
     pub value: String,
@@ -860,7 +860,7 @@ pub struct SelectOption {
 }
 
 /// Query parameters for dynamic blocks
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QueryParams {
     pub post_type: String,
     pub posts_per_page: u32,
@@ -874,14 +874,14 @@ pub struct QueryParams {
     pub meta_query: Option<Vec<MetaQuery>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DateQuery {
     pub after: Option<String>,
     pub before: Option<String>,
     pub inclusive: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MetaQuery {
     pub key: String,
     pub value: Option<String>,
@@ -890,7 +890,7 @@ pub struct MetaQuery {
 }
 
 /// Block styles
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct BlockStyles {
     /// Background color
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1010,7 +1010,7 @@ pub struct BlockStyles {
 }
 
 /// Spacing (padding/margin)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Spacing {
     All(String),
@@ -1023,7 +1023,7 @@ pub enum Spacing {
 }
 
 /// Block visibility settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BlockVisibility {
     /// Show on desktop
     #[serde(default = "default_true")]
@@ -1067,14 +1067,14 @@ fn default_true() -> bool {
     true
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VisibilitySchedule {
     pub start: Option<DateTime<Utc>>,
     pub end: Option<DateTime<Utc>>,
 }
 
 /// Block animation settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BlockAnimation {
     pub animation_type: AnimationType,
     pub duration: u32, // milliseconds
@@ -1116,7 +1116,7 @@ pub enum AnimationTrigger {
 }
 
 /// Block metadata
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct BlockMeta {
     /// Block label (for layers panel)
     #[serde(skip_serializing_if = "Option::is_none")]
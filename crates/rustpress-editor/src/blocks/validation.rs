@@ -1,8 +1,10 @@
 //! Block Validation
 //!
-//! Validates block structure, content, and constraints.
+//! Validates block structure, content, and constraints, and checks blocks
+//! against their registered [`BlockDefinition`](crate::blocks::BlockDefinition)s
+//! before a document is saved.
 
-use crate::blocks::{Block, BlockType};
+use crate::blocks::{Block, BlockRegistry, BlockSerializer, BlockType};
 use serde::{Deserialize, Serialize};
 
 /// Block validator
@@ -83,6 +85,160 @@ impl BlockValidator {
         }
     }
 
+    /// Validate blocks against their registered definitions before saving.
+    ///
+    /// Adds registry checks to [`validate_blocks`](Self::validate_blocks):
+    /// unregistered block types, required attributes, attribute types, and
+    /// allowed parents and children. Unregistered blocks are converted in
+    /// place when the policy is [`UnknownBlockPolicy::ConvertToHtml`]. In
+    /// strict mode warnings are reported as errors.
+    pub fn validate_against_registry(
+        &self,
+        registry: &BlockRegistry,
+        blocks: &mut [Block],
+    ) -> ValidationResult {
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+
+        for (i, block) in blocks.iter_mut().enumerate() {
+            self.validate_definition(
+                registry,
+                block,
+                None,
+                vec![format!("[{}]", i)],
+                &mut errors,
+                &mut warnings,
+            );
+        }
+
+        let structural = self.validate_blocks(blocks);
+        errors.extend(structural.errors);
+        warnings.extend(structural.warnings);
+
+        if self.config.strict {
+            errors.extend(warnings.drain(..).map(|warning| ValidationError {
+                code: warning.code,
+                message: warning.message,
+                path: warning.path,
+                severity: ErrorSeverity::Warning,
+            }));
+        }
+
+        ValidationResult {
+            is_valid: errors.is_empty(),
+            errors,
+            warnings,
+        }
+    }
+
+    fn validate_definition(
+        &self,
+        registry: &BlockRegistry,
+        block: &mut Block,
+        parent: Option<BlockType>,
+        path: Vec<String>,
+        errors: &mut Vec<ValidationError>,
+        warnings: &mut Vec<ValidationWarning>,
+    ) {
+        let at = |field: &str| {
+            let mut full = path.clone();
+            full.push(field.to_string());
+            full
+        };
+
+        let Some(definition) = registry.get(block.block_type) else {
+            match self.config.unknown_blocks {
+                UnknownBlockPolicy::Reject => errors.push(ValidationError {
+                    code: "UNKNOWN_BLOCK_TYPE".to_string(),
+                    message: format!("{:?} is not a registered block type", block.block_type),
+                    path: at("type"),
+                    severity: ErrorSeverity::Error,
+                }),
+                UnknownBlockPolicy::ConvertToHtml => {
+                    warnings.push(ValidationWarning {
+                        code: "BLOCK_CONVERTED_TO_HTML".to_string(),
+                        message: format!(
+                            "Unregistered {:?} block was converted to HTML",
+                            block.block_type
+                        ),
+                        path: at("type"),
+                    });
+                    *block = html_fallback(block);
+                }
+            }
+            // Children of an unknown block have no context to check against
+            return;
+        };
+
+        if let Some(required) = definition.parent {
+            if parent != Some(required) {
+                errors.push(ValidationError {
+                    code: "INVALID_PARENT".to_string(),
+                    message: format!(
+                        "{} blocks can only be placed inside {:?} blocks",
+                        definition.name, required
+                    ),
+                    path: path.clone(),
+                    severity: ErrorSeverity::Error,
+                });
+            }
+        }
+
+        if let Some(allowed) = &definition.allowed_blocks {
+            for (i, child) in block.children.iter().enumerate() {
+                if !allowed.contains(&child.block_type) {
+                    errors.push(ValidationError {
+                        code: "BLOCK_NOT_ALLOWED".to_string(),
+                        message: format!(
+                            "{:?} blocks are not allowed inside {} blocks",
+                            child.block_type, definition.name
+                        ),
+                        path: at(&format!("children[{}]", i)),
+                        severity: ErrorSeverity::Error,
+                    });
+                }
+            }
+        }
+
+        let attributes = serde_json::to_value(&block.attributes).unwrap_or_default();
+        for attribute in &definition.attributes {
+            let field = format!("attributes.{}", attribute.name);
+            match attribute_value(&attributes, &attribute.name) {
+                None if attribute.required => errors.push(ValidationError {
+                    code: "MISSING_REQUIRED_ATTRIBUTE".to_string(),
+                    message: format!(
+                        "{} blocks require the '{}' attribute",
+                        definition.name, attribute.name
+                    ),
+                    path: at(&field),
+                    severity: ErrorSeverity::Error,
+                }),
+                Some(value) if !attribute.kind.matches(value) => errors.push(ValidationError {
+                    code: "INVALID_ATTRIBUTE_TYPE".to_string(),
+                    message: format!(
+                        "Attribute '{}' must be of type {:?}",
+                        attribute.name, attribute.kind
+                    ),
+                    path: at(&field),
+                    severity: ErrorSeverity::Error,
+                }),
+                _ => {}
+            }
+        }
+
+        let block_type = block.block_type;
+        for (i, child) in block.children.iter_mut().enumerate() {
+            self.validate_definition(
+                registry,
+                child,
+                Some(block_type),
+                at(&format!("children[{}]", i)),
+                errors,
+                warnings,
+            );
+        }
+    }
+
     fn validate_block_type(
         &self,
         block: &Block,
@@ -414,6 +570,44 @@ impl Default for BlockValidator {
     }
 }
 
+/// Value of a built-in attribute or, failing that, a `custom` one; nulls
+/// count as unset
+fn attribute_value<'a>(
+    attributes: &'a serde_json::Value,
+    name: &str,
+) -> Option<&'a serde_json::Value> {
+    attributes
+        .get(name)
+        .or_else(|| attributes.get("custom").and_then(|custom| custom.get(name)))
+        .filter(|value| !value.is_null())
+}
+
+/// HTML block holding the rendered markup of `block`, keeping its id and
+/// original type so it can be recovered once the type is registered again
+fn html_fallback(block: &Block) -> Block {
+    let mut fallback = Block::new(BlockType::Html);
+    fallback.id = block.id;
+    fallback.attributes.content = Some(BlockSerializer::new().to_html(std::slice::from_ref(block)));
+    if let Ok(original) = serde_json::to_value(block.block_type) {
+        fallback
+            .attributes
+            .custom
+            .insert("original_type".to_string(), original);
+    }
+    fallback
+}
+
+/// What saving does with blocks whose type isn't registered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnknownBlockPolicy {
+    /// Report the block as an error
+    #[default]
+    Reject,
+    /// Replace the block with an HTML block holding its rendered markup
+    ConvertToHtml,
+}
+
 /// Validation configuration
 #[derive(Debug, Clone)]
 pub struct ValidationConfig {
@@ -423,6 +617,28 @@ pub struct ValidationConfig {
     pub max_nesting_depth: usize,
     /// Strict mode (treat warnings as errors)
     pub strict: bool,
+    /// Handling of unregistered block types on save
+    pub unknown_blocks: UnknownBlockPolicy,
+}
+
+impl ValidationConfig {
+    /// Reject unregistered blocks and treat warnings as errors
+    pub fn strict() -> Self {
+        Self {
+            strict: true,
+            unknown_blocks: UnknownBlockPolicy::Reject,
+            ..Default::default()
+        }
+    }
+
+    /// Convert unregistered blocks to HTML and only fail on errors
+    pub fn lenient() -> Self {
+        Self {
+            strict: false,
+            unknown_blocks: UnknownBlockPolicy::ConvertToHtml,
+            ..Default::default()
+        }
+    }
 }
 
 impl Default for ValidationConfig {
@@ -431,6 +647,7 @@ impl Default for ValidationConfig {
             max_content_length: 100_000,
             max_nesting_depth: 10,
             strict: false,
+            unknown_blocks: UnknownBlockPolicy::default(),
         }
     }
 }
@@ -487,6 +704,13 @@ pub struct ValidationError {
     pub severity: ErrorSeverity,
 }
 
+impl ValidationError {
+    /// Dotted location of the error, e.g. `[0].children[1].attributes.level`
+    pub fn location(&self) -> String {
+        self.path.join(".")
+    }
+}
+
 /// Error severity
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -505,3 +729,117 @@ pub struct ValidationWarning {
     /// Path to the warning location
     pub path: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{AttributeDefinition, AttributeKind, BlockCategory, BlockDefinition};
+
+    fn block(block_type: BlockType, children: Vec<Block>) -> Block {
+        let mut block = Block::new(block_type);
+        block.children = children;
+        block
+    }
+
+    #[test]
+    fn test_registry_validation_reports_nested_paths() {
+        let registry = BlockRegistry::new();
+        let validator = BlockValidator::new();
+
+        let mut heading = Block::new(BlockType::Heading);
+        heading.attributes.level = None;
+        let mut blocks = vec![
+            Block::new(BlockType::Paragraph),
+            block(
+                BlockType::Group,
+                vec![block(
+                    BlockType::Columns,
+                    vec![block(BlockType::Column, vec![heading])],
+                )],
+            ),
+            // A column outside of a columns block
+            Block::new(BlockType::Column),
+        ];
+
+        let result = validator.validate_against_registry(&registry, &mut blocks);
+        assert!(!result.is_valid);
+        let locations: Vec<_> = result
+            .errors
+            .iter()
+            .map(|e| (e.code.as_str(), e.location()))
+            .collect();
+        assert_eq!(
+            locations,
+            vec![
+                (
+                    "MISSING_REQUIRED_ATTRIBUTE",
+                    "[1].children[0].children[0].children[0].attributes.level".to_string()
+                ),
+                ("INVALID_PARENT", "[2]".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_registry_validation_checks_custom_definitions() {
+        let mut registry = BlockRegistry::new();
+        registry.register(BlockDefinition {
+            block_type: BlockType::Custom(7),
+            name: "Slider".to_string(),
+            description: "Image slider".to_string(),
+            category: BlockCategory::Media,
+            icon: "slides".to_string(),
+            keywords: vec![],
+            supports: Default::default(),
+            transforms_to: vec![],
+            parent: None,
+            allowed_blocks: Some(vec![BlockType::Image]),
+            attributes: vec![
+                AttributeDefinition::required("interval", AttributeKind::Integer),
+                AttributeDefinition::optional("autoplay", AttributeKind::Boolean),
+            ],
+            example: None,
+        });
+
+        let mut slider = block(BlockType::Custom(7), vec![Block::new(BlockType::Paragraph)]);
+        slider
+            .attributes
+            .custom
+            .insert("interval".to_string(), serde_json::json!(3000));
+        slider
+            .attributes
+            .custom
+            .insert("autoplay".to_string(), serde_json::json!("yes"));
+        let mut blocks = vec![slider];
+
+        let result = BlockValidator::new().validate_against_registry(&registry, &mut blocks);
+        let codes: Vec<_> = result.errors.iter().map(|e| e.code.as_str()).collect();
+        // The slider isn't a container for the structural checks either
+        assert_eq!(
+            codes,
+            vec![
+                "BLOCK_NOT_ALLOWED",
+                "INVALID_ATTRIBUTE_TYPE",
+                "UNEXPECTED_CHILDREN"
+            ]
+        );
+        assert_eq!(
+            result.errors[1].location(),
+            "[0].attributes.autoplay".to_string()
+        );
+
+        // Strict mode turns warnings, like a missing alt text, into errors
+        let mut image = Block::new(BlockType::Image);
+        image.attributes.url = Some("/a.jpg".to_string());
+        let mut blocks = vec![image];
+        assert!(
+            BlockValidator::new()
+                .validate_against_registry(&registry, &mut blocks)
+                .is_valid
+        );
+        let strict = BlockValidator::with_config(ValidationConfig::strict());
+        let result = strict.validate_against_registry(&registry, &mut blocks);
+        assert_eq!(result.errors[0].code, "MISSING_ALT_TEXT");
+        assert_eq!(result.errors[0].severity, ErrorSeverity::Warning);
+    }
+}
@@ -7,6 +7,12 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+pub mod suggestions;
+pub mod transform;
+
+pub use suggestions::{PendingSuggestion, SuggestionError, SuggestionQueue};
+pub use transform::{TransformEngine, TransformError, TransformResult};
+
 /// Collaboration session
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CollaborationSession {
@@ -28,6 +34,42 @@ pub struct CollaborationSession {
 
     /// Session settings
     pub settings: CollaborationSettings,
+
+    /// Block locks by block ID
+    #[serde(default)]
+    pub locks: HashMap<Uuid, BlockLock>,
+
+    /// Collaborator responsible for autosaving
+    #[serde(default)]
+    pub save_leader: Option<i64>,
+
+    /// Last completed autosave
+    #[serde(default)]
+    pub last_autosave_at: Option<DateTime<Utc>>,
+
+    /// Autosave currently being written
+    #[serde(skip)]
+    autosave_claim: Option<AutosaveClaim>,
+
+    /// Commenter edits awaiting review
+    #[serde(skip)]
+    pub suggestions: SuggestionQueue,
+}
+
+/// What happened to a submitted operation
+#[derive(Debug, Clone)]
+pub enum SubmitOutcome {
+    /// Queued for application
+    Queued,
+    /// Held as a suggestion; broadcast as `CommentAdded`
+    Suggested(Box<Comment>),
+}
+
+/// An autosave started by the save leader but not yet completed
+#[derive(Debug, Clone)]
+struct AutosaveClaim {
+    user_id: i64,
+    claimed_at: DateTime<Utc>,
 }
 
 impl CollaborationSession {
@@ -39,17 +81,298 @@ impl CollaborationSession {
             operations: Vec::new(),
             started_at: Utc::now(),
             settings: CollaborationSettings::default(),
+            locks: HashMap::new(),
+            save_leader: None,
+            last_autosave_at: None,
+            autosave_claim: None,
+            suggestions: SuggestionQueue::new(),
         }
     }
 
     /// Add a user to the session
     pub fn join(&mut self, user: CollaboratorInfo) {
         self.users.insert(user.user_id, user);
+        self.elect_save_leader();
     }
 
-    /// Remove a user from the session
-    pub fn leave(&mut self, user_id: i64) {
+    /// Remove a user from the session, releasing their block locks.
+    /// Returns the unlocked block IDs so `UnlockBlock` can be broadcast.
+    pub fn leave(&mut self, user_id: i64) -> Vec<Uuid> {
         self.users.remove(&user_id);
+        if self
+            .autosave_claim
+            .as_ref()
+            .is_some_and(|claim| claim.user_id == user_id)
+        {
+            // The save never completed, so the next leader finds it still due
+            self.autosave_claim = None;
+        }
+        self.elect_save_leader();
+        self.release_user_locks(user_id)
+    }
+
+    /// Elect the editor with the lowest user ID as save leader. Returns the
+    /// new leader if leadership changed.
+    pub fn elect_save_leader(&mut self) -> Option<i64> {
+        let leader = self
+            .users
+            .values()
+            .filter(|user| {
+                matches!(
+                    user.role,
+                    CollaboratorRole::Editor | CollaboratorRole::Owner
+                )
+            })
+            .map(|user| user.user_id)
+            .min();
+
+        if leader == self.save_leader {
+            return None;
+        }
+        self.save_leader = leader;
+        leader
+    }
+
+    /// Message announcing the current save leader
+    pub fn save_leader_message(&self) -> CollaborationMessage {
+        CollaborationMessage::SaveLeaderChanged {
+            user_id: self.save_leader,
+        }
+    }
+
+    /// Whether the autosave interval has elapsed since the last save
+    pub fn autosave_due(&self, now: DateTime<Utc>) -> bool {
+        let interval =
+            chrono::Duration::milliseconds(i64::from(self.settings.autosave_interval_ms));
+        now - self.last_autosave_at.unwrap_or(self.started_at) >= interval
+    }
+
+    /// Called when a client's autosave timer fires. Returns true if this
+    /// user should write the document now: they are the save leader, a save
+    /// is due, and no other save is in flight.
+    pub fn claim_autosave(&mut self, user_id: i64, now: DateTime<Utc>) -> bool {
+        if self.save_leader != Some(user_id) || !self.autosave_due(now) {
+            return false;
+        }
+
+        // A claim that outlived a full interval is treated as abandoned
+        let interval =
+            chrono::Duration::milliseconds(i64::from(self.settings.autosave_interval_ms));
+        if let Some(claim) = &self.autosave_claim {
+            if claim.user_id != user_id && now - claim.claimed_at < interval {
+                return false;
+            }
+        }
+
+        self.autosave_claim = Some(AutosaveClaim {
+            user_id,
+            claimed_at: now,
+        });
+        true
+    }
+
+    /// Record a finished autosave, returning the `DocumentSaved` message to
+    /// broadcast. Returns `None` if the user held no autosave claim.
+    pub fn complete_autosave(
+        &mut self,
+        user_id: i64,
+        version: u32,
+        now: DateTime<Utc>,
+    ) -> Option<CollaborationMessage> {
+        if self.autosave_claim.as_ref()?.user_id != user_id {
+            return None;
+        }
+        self.autosave_claim = None;
+        self.last_autosave_at = Some(now);
+        Some(CollaborationMessage::DocumentSaved {
+            version,
+            saved_by: user_id,
+        })
+    }
+
+    /// Drop a failed autosave claim so it can be retried
+    pub fn abandon_autosave(&mut self, user_id: i64) {
+        if self
+            .autosave_claim
+            .as_ref()
+            .is_some_and(|claim| claim.user_id == user_id)
+        {
+            self.autosave_claim = None;
+        }
+    }
+
+    /// Lock a block for a user. Succeeds if the block is unlocked, already
+    /// held by the user (refreshing the lock), or held by an expired lock.
+    pub fn try_lock(&mut self, block_id: Uuid, user_id: i64) -> bool {
+        let now = Utc::now();
+        let timeout = self.settings.lock_timeout();
+
+        if let Some(lock) = self.locks.get_mut(&block_id) {
+            if lock.user_id == user_id {
+                lock.refreshed_at = now;
+                return true;
+            }
+            if !lock.is_expired(now, timeout) && self.users.contains_key(&lock.user_id) {
+                return false;
+            }
+            let previous = lock.user_id;
+            if let Some(user) = self.users.get_mut(&previous) {
+                user.editing_block = None;
+            }
+        }
+
+        self.locks.insert(
+            block_id,
+            BlockLock {
+                user_id,
+                acquired_at: now,
+                refreshed_at: now,
+            },
+        );
+        if let Some(user) = self.users.get_mut(&user_id) {
+            user.editing_block = Some(block_id);
+        }
+        true
+    }
+
+    /// Release a block lock held by `user_id`
+    pub fn unlock(&mut self, block_id: Uuid, user_id: i64) -> bool {
+        match self.locks.get(&block_id) {
+            Some(lock) if lock.user_id == user_id => {
+                self.locks.remove(&block_id);
+                if let Some(user) = self.users.get_mut(&user_id) {
+                    if user.editing_block == Some(block_id) {
+                        user.editing_block = None;
+                    }
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// User currently holding a lock on a block
+    pub fn lock_holder(&self, block_id: &Uuid) -> Option<i64> {
+        self.locks.get(block_id).map(|lock| lock.user_id)
+    }
+
+    /// Release every lock held by a user
+    pub fn release_user_locks(&mut self, user_id: i64) -> Vec<Uuid> {
+        let released: Vec<Uuid> = self
+            .locks
+            .iter()
+            .filter(|(_, lock)| lock.user_id == user_id)
+            .map(|(block_id, _)| *block_id)
+            .collect();
+        for block_id in &released {
+            self.locks.remove(block_id);
+        }
+        if let Some(user) = self.users.get_mut(&user_id) {
+            user.editing_block = None;
+        }
+        released
+    }
+
+    /// Release locks not refreshed within the lock timeout, and locks whose
+    /// holder is no longer in the session (e.g. after an unclean
+    /// disconnect). Returns the unlocked block IDs.
+    pub fn release_expired_locks(&mut self, now: DateTime<Utc>) -> Vec<Uuid> {
+        let timeout = self.settings.lock_timeout();
+        let released: Vec<(Uuid, i64)> = self
+            .locks
+            .iter()
+            .filter(|(_, lock)| {
+                lock.is_expired(now, timeout) || !self.users.contains_key(&lock.user_id)
+            })
+            .map(|(block_id, lock)| (*block_id, lock.user_id))
+            .collect();
+
+        for (block_id, user_id) in &released {
+            self.locks.remove(block_id);
+            if let Some(user) = self.users.get_mut(user_id) {
+                if user.editing_block == Some(*block_id) {
+                    user.editing_block = None;
+                }
+            }
+        }
+        released.into_iter().map(|(block_id, _)| block_id).collect()
+    }
+
+    /// Check that an operation does not edit a block locked by another user.
+    /// Edits by the lock holder refresh their lock.
+    pub fn check_operation(&mut self, operation: &Operation) -> Result<(), LockConflict> {
+        let Some(block_id) = operation.op_type.block_id() else {
+            return Ok(());
+        };
+        let now = Utc::now();
+        let timeout = self.settings.lock_timeout();
+
+        match self.locks.get_mut(&block_id) {
+            Some(lock) if lock.user_id == operation.user_id => {
+                lock.refreshed_at = now;
+                Ok(())
+            }
+            Some(lock)
+                if !lock.is_expired(now, timeout) && self.users.contains_key(&lock.user_id) =>
+            {
+                Err(LockConflict {
+                    block_id,
+                    holder: lock.user_id,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Queue an operation after checking block locks. Operations from
+    /// commenters are held as suggestions instead.
+    pub fn submit_operation(
+        &mut self,
+        operation: Operation,
+    ) -> Result<SubmitOutcome, LockConflict> {
+        self.check_operation(&operation)?;
+
+        if let Some(user) = self.users.get(&operation.user_id) {
+            if user.role == CollaboratorRole::Commenter {
+                let author_name = user.name.clone();
+                let comment = self
+                    .suggestions
+                    .suggest(operation, author_name, String::new());
+                return Ok(SubmitOutcome::Suggested(Box::new(comment)));
+            }
+        }
+
+        self.operations.push(operation);
+        Ok(SubmitOutcome::Queued)
+    }
+
+    /// Accept a pending suggestion, applying it through the transform engine.
+    /// Only editors and owners may accept.
+    pub fn accept_suggestion(
+        &mut self,
+        id: Uuid,
+        user_id: i64,
+        engine: &mut TransformEngine,
+    ) -> Result<(TransformResult, Comment), SuggestionError> {
+        self.check_reviewer(user_id)?;
+        self.suggestions.accept(id, user_id, engine)
+    }
+
+    /// Reject a pending suggestion. Only editors and owners may reject.
+    pub fn reject_suggestion(
+        &mut self,
+        id: Uuid,
+        user_id: i64,
+    ) -> Result<Comment, SuggestionError> {
+        self.check_reviewer(user_id)?;
+        self.suggestions.reject(id, user_id)
+    }
+
+    fn check_reviewer(&self, user_id: i64) -> Result<(), SuggestionError> {
+        match self.users.get(&user_id).map(|u| u.role) {
+            Some(CollaboratorRole::Editor | CollaboratorRole::Owner) => Ok(()),
+            _ => Err(SuggestionError::NotPermitted(user_id)),
+        }
     }
 
     /// Update user cursor position
@@ -114,6 +437,44 @@ impl CollaboratorInfo {
     }
 }
 
+/// A user's exclusive edit lock on a block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockLock {
+    /// Lock holder
+    pub user_id: i64,
+
+    /// When the lock was taken
+    pub acquired_at: DateTime<Utc>,
+
+    /// Last lock refresh or edit by the holder
+    pub refreshed_at: DateTime<Utc>,
+}
+
+impl BlockLock {
+    /// Whether the lock went unrefreshed for longer than `timeout`
+    pub fn is_expired(&self, now: DateTime<Utc>, timeout: chrono::Duration) -> bool {
+        now - self.refreshed_at > timeout
+    }
+}
+
+/// An edit targeted a block locked by another user
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("block {block_id} is locked by user {holder}")]
+pub struct LockConflict {
+    pub block_id: Uuid,
+    pub holder: i64,
+}
+
+impl LockConflict {
+    /// Error message to send back to the editing client
+    pub fn to_message(&self) -> CollaborationMessage {
+        CollaborationMessage::Error {
+            code: "block_locked".to_string(),
+            message: self.to_string(),
+        }
+    }
+}
+
 /// Collaborator role
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -170,6 +531,20 @@ pub struct CollaborationSettings {
 
     /// Maximum collaborators
     pub max_collaborators: u32,
+
+    /// Seconds a block lock survives without activity from its holder
+    #[serde(default = "default_lock_timeout_secs")]
+    pub lock_timeout_secs: u32,
+}
+
+fn default_lock_timeout_secs() -> u32 {
+    60
+}
+
+impl CollaborationSettings {
+    fn lock_timeout(&self) -> chrono::Duration {
+        chrono::Duration::seconds(i64::from(self.lock_timeout_secs))
+    }
 }
 
 impl Default for CollaborationSettings {
@@ -181,6 +556,7 @@ impl Default for CollaborationSettings {
             sync_interval_ms: 100,
             autosave_interval_ms: 30000,
             max_collaborators: 10,
+            lock_timeout_secs: default_lock_timeout_secs(),
         }
     }
 }
@@ -275,6 +651,23 @@ pub enum OperationType {
     },
 }
 
+impl OperationType {
+    /// Block an operation edits, if it targets an existing block
+    pub fn block_id(&self) -> Option<Uuid> {
+        match self {
+            Self::InsertText { block_id, .. }
+            | Self::DeleteText { block_id, .. }
+            | Self::ReplaceText { block_id, .. }
+            | Self::DeleteBlock { block_id }
+            | Self::MoveBlock { block_id, .. }
+            | Self::UpdateBlockAttributes { block_id, .. }
+            | Self::UpdateBlockStyles { block_id, .. }
+            | Self::FormatText { block_id, .. } => Some(*block_id),
+            Self::InsertBlock { .. } | Self::SetSelection { .. } => None,
+        }
+    }
+}
+
 /// Text formatting
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TextFormat {
@@ -409,6 +802,11 @@ pub enum CollaborationMessage {
         saved_by: i64,
     },
 
+    /// Autosave leadership changed
+    SaveLeaderChanged {
+        user_id: Option<i64>,
+    },
+
     /// Sync request
     SyncRequest {
         from_version: u64,
@@ -460,3 +858,247 @@ pub const CURSOR_COLORS: &[&str] = &[
 pub fn get_collaborator_color(index: usize) -> &'static str {
     CURSOR_COLORS[index % CURSOR_COLORS.len()]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session_with_users() -> CollaborationSession {
+        let mut session = CollaborationSession::new(1);
+        session.join(CollaboratorInfo::new(1, "Ada".into(), "#ef4444".into()));
+        session.join(CollaboratorInfo::new(2, "Lin".into(), "#3b82f6".into()));
+        session
+    }
+
+    fn edit(user_id: i64, block_id: Uuid) -> Operation {
+        Operation {
+            id: Uuid::new_v4(),
+            op_type: OperationType::InsertText {
+                block_id,
+                offset: 0,
+                text: "x".into(),
+            },
+            user_id,
+            timestamp: Utc::now(),
+            version: 0,
+        }
+    }
+
+    #[test]
+    fn test_solo_editor_autosaves() {
+        let mut session = CollaborationSession::new(1);
+        session.join(CollaboratorInfo::new(7, "Solo".into(), "#ef4444".into()));
+        assert_eq!(session.save_leader, Some(7));
+
+        let start = session.started_at;
+        assert!(!session.claim_autosave(7, start));
+
+        let due = start + chrono::Duration::seconds(31);
+        assert!(session.claim_autosave(7, due));
+        assert!(matches!(
+            session.complete_autosave(7, 3, due),
+            Some(CollaborationMessage::DocumentSaved {
+                version: 3,
+                saved_by: 7
+            })
+        ));
+        assert!(!session.claim_autosave(7, due));
+    }
+
+    #[test]
+    fn test_save_leader_handoff_keeps_pending_save() {
+        let mut session = session_with_users();
+        let mut viewer = CollaboratorInfo::new(0, "Viewer".into(), "#22c55e".into());
+        viewer.role = CollaboratorRole::Viewer;
+        session.join(viewer);
+        assert_eq!(session.save_leader, Some(1));
+
+        let due = session.started_at + chrono::Duration::seconds(31);
+        assert!(!session.claim_autosave(2, due));
+        assert!(session.claim_autosave(1, due));
+
+        // Leader drops mid-save: the next leader can save right away
+        session.leave(1);
+        assert_eq!(session.save_leader, Some(2));
+        assert!(session.complete_autosave(1, 4, due).is_none());
+        assert!(session.claim_autosave(2, due));
+        assert!(session.complete_autosave(2, 4, due).is_some());
+    }
+
+    fn commenter_session() -> CollaborationSession {
+        let mut session = session_with_users();
+        let mut commenter = CollaboratorInfo::new(3, "Kim".into(), "#eab308".into());
+        commenter.role = CollaboratorRole::Commenter;
+        session.join(commenter);
+        session
+    }
+
+    fn text_op(user_id: i64, version: u64, op_type: OperationType) -> Operation {
+        Operation {
+            id: Uuid::new_v4(),
+            op_type,
+            user_id,
+            timestamp: Utc::now(),
+            version,
+        }
+    }
+
+    #[test]
+    fn test_commenter_edits_become_suggestions() {
+        let mut session = commenter_session();
+        let mut engine = TransformEngine::new();
+        let block = Uuid::new_v4();
+
+        let suggested = text_op(
+            3,
+            0,
+            OperationType::ReplaceText {
+                block_id: block,
+                offset: 6,
+                length: 5,
+                text: "there".into(),
+            },
+        );
+        let SubmitOutcome::Suggested(comment) = session.submit_operation(suggested).unwrap() else {
+            panic!("commenter edit was applied");
+        };
+        assert!(session.operations.is_empty());
+        assert_eq!(comment.comment_type, CommentType::Suggestion);
+        assert_eq!(comment.text_range.as_ref().unwrap().end_offset, 11);
+        assert_eq!(session.suggestions.for_block(block).len(), 1);
+
+        // An unrelated edit before the range shifts the suggestion
+        engine
+            .receive(text_op(
+                1,
+                0,
+                OperationType::InsertText {
+                    block_id: block,
+                    offset: 0,
+                    text: ">> ".into(),
+                },
+            ))
+            .unwrap();
+
+        assert_eq!(
+            session
+                .accept_suggestion(comment.id, 3, &mut engine)
+                .unwrap_err(),
+            SuggestionError::NotPermitted(3)
+        );
+        let (result, resolved) = session
+            .accept_suggestion(comment.id, 1, &mut engine)
+            .unwrap();
+        assert_eq!(resolved.status, CommentStatus::Resolved);
+        assert_eq!(resolved.resolved_by, Some(1));
+        assert!(matches!(
+            result.operations[0].op_type,
+            OperationType::DeleteText {
+                offset: 9,
+                length: 5,
+                ..
+            }
+        ));
+        assert!(session.suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_stale_suggestion_is_flagged() {
+        let mut session = commenter_session();
+        let mut engine = TransformEngine::new();
+        let block = Uuid::new_v4();
+
+        let suggested = text_op(
+            3,
+            0,
+            OperationType::DeleteText {
+                block_id: block,
+                offset: 4,
+                length: 4,
+            },
+        );
+        let SubmitOutcome::Suggested(comment) = session.submit_operation(suggested).unwrap() else {
+            panic!("commenter edit was applied");
+        };
+
+        // An editor rewrites the suggested text first
+        engine
+            .receive(text_op(
+                2,
+                0,
+                OperationType::InsertText {
+                    block_id: block,
+                    offset: 6,
+                    text: "new".into(),
+                },
+            ))
+            .unwrap();
+
+        assert_eq!(
+            session
+                .accept_suggestion(comment.id, 1, &mut engine)
+                .unwrap_err(),
+            SuggestionError::Stale(comment.id)
+        );
+        assert!(session.suggestions.get(&comment.id).unwrap().stale);
+        assert_eq!(engine.version(), 1);
+
+        let rejected = session.reject_suggestion(comment.id, 1).unwrap();
+        assert_eq!(rejected.status, CommentStatus::Rejected);
+        assert!(session.suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_lock_rejects_other_users_edits() {
+        let mut session = session_with_users();
+        let block = Uuid::new_v4();
+
+        assert!(session.try_lock(block, 1));
+        assert!(!session.try_lock(block, 2));
+        assert_eq!(session.users[&1].editing_block, Some(block));
+
+        assert!(session.submit_operation(edit(1, block)).is_ok());
+        let conflict = session.submit_operation(edit(2, block)).unwrap_err();
+        assert_eq!(conflict.holder, 1);
+        assert!(matches!(
+            conflict.to_message(),
+            CollaborationMessage::Error { ref code, .. } if code == "block_locked"
+        ));
+        assert_eq!(session.operations.len(), 1);
+
+        assert!(!session.unlock(block, 2));
+        assert!(session.unlock(block, 1));
+        assert!(session.try_lock(block, 2));
+    }
+
+    #[test]
+    fn test_locks_released_on_leave_and_expiry() {
+        let mut session = session_with_users();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        session.try_lock(a, 1);
+        session.try_lock(b, 2);
+
+        assert_eq!(session.leave(1), vec![a]);
+        assert!(session.try_lock(a, 2));
+
+        let later = Utc::now() + chrono::Duration::seconds(61);
+        let mut released = session.release_expired_locks(later);
+        released.sort();
+        let mut expected = vec![a, b];
+        expected.sort();
+        assert_eq!(released, expected);
+        assert!(session.locks.is_empty());
+    }
+
+    #[test]
+    fn test_lock_of_disconnected_user_is_not_enforced() {
+        let mut session = session_with_users();
+        let block = Uuid::new_v4();
+        session.try_lock(block, 1);
+
+        // Holder vanished without leaving
+        session.users.remove(&1);
+        assert!(session.check_operation(&edit(2, block)).is_ok());
+        assert_eq!(session.release_expired_locks(Utc::now()), vec![block]);
+    }
+}
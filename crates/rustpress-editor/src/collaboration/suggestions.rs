@@ -0,0 +1,206 @@
+//! Suggestion Mode
+//!
+//! Edits from commenters are held as pending suggestions instead of being
+//! applied. Each suggestion is surfaced as a `Comment` of type `Suggestion`
+//! on the affected text range until an editor accepts or rejects it.
+
+use chrono::Utc;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::transform::target_range;
+use super::{
+    CollaborationMessage, Comment, CommentStatus, CommentType, Operation, OperationType, TextRange,
+    TransformEngine, TransformError, TransformResult,
+};
+
+/// Errors from accepting or rejecting a suggestion
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SuggestionError {
+    #[error("suggestion {0} not found")]
+    NotFound(Uuid),
+
+    /// The suggested text was edited after the suggestion was made
+    #[error("suggestion {0} targets text that has changed since it was made")]
+    Stale(Uuid),
+
+    #[error("user {0} may not accept or reject suggestions")]
+    NotPermitted(i64),
+
+    #[error(transparent)]
+    Transform(#[from] TransformError),
+}
+
+impl SuggestionError {
+    /// Error message to send back to the client
+    pub fn to_message(&self) -> CollaborationMessage {
+        let code = match self {
+            Self::NotFound(_) => "suggestion_not_found",
+            Self::Stale(_) => "suggestion_stale",
+            Self::NotPermitted(_) => "forbidden",
+            Self::Transform(e) => return e.to_message(),
+        };
+        CollaborationMessage::Error {
+            code: code.to_string(),
+            message: self.to_string(),
+        }
+    }
+}
+
+/// A suggested edit waiting for review
+#[derive(Debug, Clone)]
+pub struct PendingSuggestion {
+    /// Proposed operation, with `version` set to the document version it
+    /// was made against
+    pub operation: Operation,
+
+    /// Comment shown on the affected range
+    pub comment: Comment,
+
+    /// Set when an accept found the target text changed
+    pub stale: bool,
+}
+
+/// Pending suggestions for a document
+#[derive(Debug, Clone, Default)]
+pub struct SuggestionQueue {
+    pending: HashMap<Uuid, PendingSuggestion>,
+}
+
+impl SuggestionQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an operation as a pending suggestion. `quoted_text` is the
+    /// current text of the targeted range, shown to reviewers.
+    pub fn suggest(
+        &mut self,
+        operation: Operation,
+        author_name: String,
+        quoted_text: String,
+    ) -> Comment {
+        let text_range = target_range(&operation.op_type).map(|(start, end)| TextRange {
+            start_offset: start,
+            end_offset: end,
+            quoted_text,
+        });
+
+        let comment = Comment {
+            id: Uuid::new_v4(),
+            author_id: operation.user_id,
+            author_name,
+            author_avatar: None,
+            content: describe(&operation.op_type),
+            block_id: operation.op_type.block_id(),
+            text_range,
+            comment_type: CommentType::Suggestion,
+            status: CommentStatus::Open,
+            replies: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: None,
+            resolved_at: None,
+            resolved_by: None,
+        };
+
+        self.pending.insert(
+            comment.id,
+            PendingSuggestion {
+                operation,
+                comment: comment.clone(),
+                stale: false,
+            },
+        );
+        comment
+    }
+
+    /// Get a pending suggestion
+    pub fn get(&self, id: &Uuid) -> Option<&PendingSuggestion> {
+        self.pending.get(id)
+    }
+
+    /// Pending suggestions on a block
+    pub fn for_block(&self, block_id: Uuid) -> Vec<&PendingSuggestion> {
+        self.pending
+            .values()
+            .filter(|s| s.comment.block_id == Some(block_id))
+            .collect()
+    }
+
+    /// Number of pending suggestions
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Check if there are no pending suggestions
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Apply a suggestion, transformed against edits made since it was
+    /// suggested. Fails with `Stale` (and flags the suggestion) if those
+    /// edits touched the suggested range.
+    pub fn accept(
+        &mut self,
+        id: Uuid,
+        accepted_by: i64,
+        engine: &mut TransformEngine,
+    ) -> Result<(TransformResult, Comment), SuggestionError> {
+        let suggestion = self
+            .pending
+            .get_mut(&id)
+            .ok_or(SuggestionError::NotFound(id))?;
+
+        if engine.target_changed(&suggestion.operation)? {
+            suggestion.stale = true;
+            return Err(SuggestionError::Stale(id));
+        }
+
+        let result = engine.receive(suggestion.operation.clone())?;
+        let mut suggestion = self.pending.remove(&id).expect("suggestion exists");
+        resolve(
+            &mut suggestion.comment,
+            CommentStatus::Resolved,
+            accepted_by,
+        );
+        Ok((result, suggestion.comment))
+    }
+
+    /// Discard a suggestion
+    pub fn reject(&mut self, id: Uuid, rejected_by: i64) -> Result<Comment, SuggestionError> {
+        let mut suggestion = self
+            .pending
+            .remove(&id)
+            .ok_or(SuggestionError::NotFound(id))?;
+        resolve(
+            &mut suggestion.comment,
+            CommentStatus::Rejected,
+            rejected_by,
+        );
+        Ok(suggestion.comment)
+    }
+}
+
+fn resolve(comment: &mut Comment, status: CommentStatus, user_id: i64) {
+    let now = Utc::now();
+    comment.status = status;
+    comment.updated_at = Some(now);
+    comment.resolved_at = Some(now);
+    comment.resolved_by = Some(user_id);
+}
+
+/// Human-readable summary of a suggested edit
+fn describe(op: &OperationType) -> String {
+    match op {
+        OperationType::InsertText { text, .. } => format!("Insert \"{}\"", text),
+        OperationType::DeleteText { length, .. } => format!("Delete {} characters", length),
+        OperationType::ReplaceText { text, .. } => format!("Replace with \"{}\"", text),
+        OperationType::FormatText { .. } => "Format text".to_string(),
+        OperationType::InsertBlock { .. } => "Insert block".to_string(),
+        OperationType::DeleteBlock { .. } => "Delete block".to_string(),
+        OperationType::MoveBlock { .. } => "Move block".to_string(),
+        OperationType::UpdateBlockAttributes { .. } => "Update block attributes".to_string(),
+        OperationType::UpdateBlockStyles { .. } => "Update block styles".to_string(),
+        OperationType::SetSelection { .. } => "Select text".to_string(),
+    }
+}
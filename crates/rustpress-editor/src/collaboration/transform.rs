@@ -0,0 +1,753 @@
+//! Server-side Operational Transform
+//!
+//! Reconciles concurrent edits: an operation built against an older document
+//! version is transformed against every operation the server applied since,
+//! so all clients converge on the same content.
+//!
+//! Text operations are transformed within a block. `ReplaceText` is split
+//! into a delete followed by an insert, and a delete that spans a concurrent
+//! insert is split around it, so one incoming operation may become several.
+//! Text edits to a block deleted concurrently are dropped; other block-level
+//! operations are applied in arrival order.
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::{CollaborationMessage, Operation, OperationType};
+
+/// Default number of operations kept for transforming late clients
+pub const DEFAULT_HISTORY_LIMIT: usize = 10_000;
+
+/// Errors returned when an operation cannot be transformed
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TransformError {
+    /// Client claims a version the server has not reached
+    #[error("operation version {client} is ahead of server version {server}")]
+    VersionAhead { client: u64, server: u64 },
+
+    /// Operations since the client's version were discarded from history
+    #[error("operation version {client} is older than the oldest retained version {oldest}")]
+    VersionTooOld { client: u64, oldest: u64 },
+}
+
+impl TransformError {
+    /// Error message for the client; it should resync from a snapshot
+    pub fn to_message(&self) -> CollaborationMessage {
+        let code = match self {
+            Self::VersionAhead { .. } => "version_ahead",
+            Self::VersionTooOld { .. } => "version_too_old",
+        };
+        CollaborationMessage::Error {
+            code: code.to_string(),
+            message: self.to_string(),
+        }
+    }
+}
+
+/// Result of applying a client operation on the server
+#[derive(Debug, Clone)]
+pub struct TransformResult {
+    /// Transformed operations, each stamped with the version it produced.
+    /// Empty if the operation was made obsolete by concurrent edits.
+    pub operations: Vec<Operation>,
+
+    /// Server version after applying the operations
+    pub version: u64,
+}
+
+impl TransformResult {
+    /// Message to broadcast to every client in the session
+    pub fn to_message(&self) -> Option<CollaborationMessage> {
+        match self.operations.as_slice() {
+            [] => None,
+            [operation] => Some(CollaborationMessage::Operation {
+                operation: operation.clone(),
+            }),
+            operations => Some(CollaborationMessage::OperationsBatch {
+                operations: operations.to_vec(),
+            }),
+        }
+    }
+}
+
+/// Server-side transform engine for one document
+#[derive(Debug, Clone)]
+pub struct TransformEngine {
+    /// Applied operations; `history[i]` produced version `base_version + i + 1`
+    history: Vec<Operation>,
+
+    /// Version before the oldest retained operation
+    base_version: u64,
+
+    /// Maximum number of retained operations
+    history_limit: usize,
+}
+
+impl Default for TransformEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransformEngine {
+    pub fn new() -> Self {
+        Self::with_history_limit(DEFAULT_HISTORY_LIMIT)
+    }
+
+    /// Start from an existing document version
+    pub fn at_version(version: u64) -> Self {
+        Self {
+            base_version: version,
+            ..Self::new()
+        }
+    }
+
+    pub fn with_history_limit(history_limit: usize) -> Self {
+        Self {
+            history: Vec::new(),
+            base_version: 0,
+            history_limit: history_limit.max(1),
+        }
+    }
+
+    /// Current server version
+    pub fn version(&self) -> u64 {
+        self.base_version + self.history.len() as u64
+    }
+
+    /// Operations applied after `version`, for a `SyncResponse`
+    pub fn operations_since(&self, version: u64) -> Result<&[Operation], TransformError> {
+        let current = self.version();
+        if version > current {
+            return Err(TransformError::VersionAhead {
+                client: version,
+                server: current,
+            });
+        }
+        if version < self.base_version {
+            return Err(TransformError::VersionTooOld {
+                client: version,
+                oldest: self.base_version,
+            });
+        }
+        Ok(&self.history[(version - self.base_version) as usize..])
+    }
+
+    /// Answer a `SyncRequest`
+    pub fn sync_response(&self, from_version: u64) -> CollaborationMessage {
+        match self.operations_since(from_version) {
+            Ok(operations) => CollaborationMessage::SyncResponse {
+                operations: operations.to_vec(),
+                current_version: self.version(),
+            },
+            Err(e) => e.to_message(),
+        }
+    }
+
+    /// Whether operations applied since `operation.version` edited the text
+    /// range (or block) it targets. Used to detect stale suggestions, where
+    /// transforming would silently apply the edit to different text.
+    pub fn target_changed(&self, operation: &Operation) -> Result<bool, TransformError> {
+        let concurrent = self.operations_since(operation.version)?;
+        let Some(block_id) = operation.op_type.block_id() else {
+            return Ok(false);
+        };
+        let Some((mut start, mut end)) = target_range(&operation.op_type) else {
+            // Block-level operations only go stale if the block is deleted
+            return Ok(concurrent.iter().any(|op| {
+                matches!(op.op_type, OperationType::DeleteBlock { block_id: id } if id == block_id)
+            }));
+        };
+
+        for op in concurrent {
+            if let OperationType::DeleteBlock { block_id: id } = op.op_type {
+                if id == block_id {
+                    return Ok(true);
+                }
+            }
+            if text_block(&op.op_type) != Some(block_id) {
+                continue;
+            }
+            for primitive in decompose(&op.op_type) {
+                let touched = match &primitive {
+                    OperationType::InsertText { offset, .. } => start < *offset && *offset < end,
+                    OperationType::DeleteText { offset, length, .. } => {
+                        if start == end {
+                            *offset < start && start < offset + length
+                        } else {
+                            *offset < end && offset + length > start
+                        }
+                    }
+                    _ => false,
+                };
+                if touched {
+                    return Ok(true);
+                }
+                (start, end) = transform_range(start, end, &primitive);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Apply a client operation whose `version` is the server version it was
+    /// based on. The operation is transformed against everything applied
+    /// since, recorded, and returned for broadcast.
+    pub fn receive(&mut self, operation: Operation) -> Result<TransformResult, TransformError> {
+        let concurrent: Vec<Component> = self
+            .operations_since(operation.version)?
+            .iter()
+            .map(|op| Component::new(op.op_type.clone(), op.user_id))
+            .collect();
+
+        let incoming = decompose(&operation.op_type)
+            .into_iter()
+            .map(|op_type| Component::new(op_type, operation.user_id))
+            .collect();
+        let (transformed, _) = transform_lists(incoming, concurrent);
+
+        let mut operations = Vec::with_capacity(transformed.len());
+        for (i, component) in transformed.into_iter().enumerate() {
+            let applied = Operation {
+                id: if i == 0 { operation.id } else { Uuid::new_v4() },
+                op_type: component.op,
+                user_id: operation.user_id,
+                timestamp: Utc::now(),
+                version: self.version() + 1,
+            };
+            self.history.push(applied.clone());
+            operations.push(applied);
+        }
+
+        if self.history.len() > self.history_limit {
+            let excess = self.history.len() - self.history_limit;
+            self.history.drain(..excess);
+            self.base_version += excess as u64;
+        }
+
+        Ok(TransformResult {
+            operations,
+            version: self.version(),
+        })
+    }
+}
+
+/// Transform `op` so it applies after `against`, where both were made
+/// against the same document state. May return zero or several operations.
+pub fn transform(op: &Operation, against: &Operation) -> Vec<OperationType> {
+    let incoming = decompose(&op.op_type)
+        .into_iter()
+        .map(|op_type| Component::new(op_type, op.user_id))
+        .collect();
+    let concurrent = vec![Component::new(against.op_type.clone(), against.user_id)];
+
+    transform_lists(incoming, concurrent)
+        .0
+        .into_iter()
+        .map(|c| c.op)
+        .collect()
+}
+
+/// A primitive operation tagged with its author for tie-breaking
+#[derive(Debug, Clone)]
+struct Component {
+    op: OperationType,
+    user_id: i64,
+}
+
+impl Component {
+    fn new(op: OperationType, user_id: i64) -> Self {
+        Self { op, user_id }
+    }
+
+    fn with_op(&self, op: OperationType) -> Self {
+        Self {
+            op,
+            user_id: self.user_id,
+        }
+    }
+}
+
+/// Split compound operations into primitives
+fn decompose(op: &OperationType) -> Vec<OperationType> {
+    match op {
+        OperationType::ReplaceText {
+            block_id,
+            offset,
+            length,
+            text,
+        } => {
+            let mut ops = Vec::with_capacity(2);
+            if *length > 0 {
+                ops.push(OperationType::DeleteText {
+                    block_id: *block_id,
+                    offset: *offset,
+                    length: *length,
+                });
+            }
+            if !text.is_empty() {
+                ops.push(OperationType::InsertText {
+                    block_id: *block_id,
+                    offset: *offset,
+                    text: text.clone(),
+                });
+            }
+            ops
+        }
+        other => vec![other.clone()],
+    }
+}
+
+/// Transform two sequences made against the same state, returning
+/// `(a after b, b after a)`
+fn transform_lists(a: Vec<Component>, b: Vec<Component>) -> (Vec<Component>, Vec<Component>) {
+    if a.is_empty() || b.is_empty() {
+        return (a, b);
+    }
+
+    if a.len() == 1 && b.len() == 1 {
+        return transform_pair(&a[0], &b[0]);
+    }
+
+    if a.len() > 1 {
+        let mut a = a;
+        let rest = a.split_off(1);
+        let (mut first, b) = transform_lists(a, b);
+        let (rest, b) = transform_lists(rest, b);
+        first.extend(rest);
+        (first, b)
+    } else {
+        let mut b = b;
+        let rest = b.split_off(1);
+        let (a, mut first) = transform_lists(a, b);
+        let (a, rest) = transform_lists(a, rest);
+        first.extend(rest);
+        (a, first)
+    }
+}
+
+/// Transform two primitives made against the same state
+fn transform_pair(a: &Component, b: &Component) -> (Vec<Component>, Vec<Component>) {
+    let a_after_b = transform_component(a, b, true);
+    let b_after_a = transform_component(b, a, false);
+    (a_after_b, b_after_a)
+}
+
+/// Transform `op` to apply after `against`.
+///
+/// `op_is_left` only matters for equal user ids, where the left side is
+/// ordered after the right so the two sides still agree.
+fn transform_component(op: &Component, against: &Component, op_is_left: bool) -> Vec<Component> {
+    use OperationType::*;
+
+    // Text edits to a concurrently deleted block are dropped
+    if let DeleteBlock { block_id } = &against.op {
+        if text_block(&op.op).is_some_and(|id| id == *block_id) {
+            return Vec::new();
+        }
+    }
+
+    let same_block = match (text_block(&op.op), text_block(&against.op)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    };
+    if !same_block {
+        return vec![op.clone()];
+    }
+
+    match (&op.op, &against.op) {
+        (
+            InsertText {
+                block_id,
+                offset,
+                text,
+            },
+            InsertText {
+                offset: other_offset,
+                text: other_text,
+                ..
+            },
+        ) => {
+            let op_first = match offset.cmp(other_offset) {
+                std::cmp::Ordering::Less => true,
+                std::cmp::Ordering::Greater => false,
+                std::cmp::Ordering::Equal => {
+                    if op.user_id == against.user_id {
+                        !op_is_left
+                    } else {
+                        op.user_id < against.user_id
+                    }
+                }
+            };
+            let offset = if op_first {
+                *offset
+            } else {
+                offset + char_len(other_text)
+            };
+            vec![op.with_op(InsertText {
+                block_id: *block_id,
+                offset,
+                text: text.clone(),
+            })]
+        }
+
+        (
+            InsertText {
+                block_id,
+                offset,
+                text,
+            },
+            DeleteText {
+                offset: del_offset,
+                length: del_length,
+                ..
+            },
+        ) => {
+            let offset = if offset <= del_offset {
+                *offset
+            } else if *offset >= del_offset + del_length {
+                offset - del_length
+            } else {
+                // Inserted inside the deleted range: keep it at the cut
+                *del_offset
+            };
+            vec![op.with_op(InsertText {
+                block_id: *block_id,
+                offset,
+                text: text.clone(),
+            })]
+        }
+
+        (
+            DeleteText {
+                block_id,
+                offset,
+                length,
+            },
+            InsertText {
+                offset: ins_offset,
+                text,
+                ..
+            },
+        ) => {
+            let inserted = char_len(text);
+            if ins_offset <= offset {
+                vec![op.with_op(DeleteText {
+                    block_id: *block_id,
+                    offset: offset + inserted,
+                    length: *length,
+                })]
+            } else if *ins_offset >= offset + length {
+                vec![op.clone()]
+            } else {
+                // Delete around the inserted text
+                let before = ins_offset - offset;
+                vec![
+                    op.with_op(DeleteText {
+                        block_id: *block_id,
+                        offset: *offset,
+                        length: before,
+                    }),
+                    op.with_op(DeleteText {
+                        block_id: *block_id,
+                        offset: offset + inserted,
+                        length: length - before,
+                    }),
+                ]
+            }
+        }
+
+        (
+            DeleteText {
+                block_id,
+                offset,
+                length,
+            },
+            DeleteText {
+                offset: other_offset,
+                length: other_length,
+                ..
+            },
+        ) => {
+            let (start, end) = (*offset, offset + length);
+            let (other_start, other_end) = (*other_offset, other_offset + other_length);
+
+            let overlap = end.min(other_end).saturating_sub(start.max(other_start));
+            let removed_before = other_end.min(start).saturating_sub(other_start);
+            let length = length - overlap;
+            if length == 0 {
+                return Vec::new();
+            }
+            vec![op.with_op(DeleteText {
+                block_id: *block_id,
+                offset: start - removed_before,
+                length,
+            })]
+        }
+
+        (
+            FormatText {
+                block_id,
+                offset,
+                length,
+                format,
+            },
+            _,
+        ) => {
+            let (start, end) = transform_range(*offset, offset + length, &against.op);
+            if start == end {
+                return Vec::new();
+            }
+            vec![op.with_op(FormatText {
+                block_id: *block_id,
+                offset: start,
+                length: end - start,
+                format: format.clone(),
+            })]
+        }
+
+        (
+            SetSelection {
+                block_id,
+                start,
+                end,
+            },
+            _,
+        ) => {
+            let (start, end) = transform_range(*start, *end, &against.op);
+            vec![op.with_op(SetSelection {
+                block_id: *block_id,
+                start,
+                end,
+            })]
+        }
+
+        // Insert/delete are unaffected by formatting and selections
+        _ => vec![op.clone()],
+    }
+}
+
+/// Map a `[start, end)` range through a text insert or delete
+fn transform_range(start: usize, end: usize, against: &OperationType) -> (usize, usize) {
+    match against {
+        OperationType::InsertText { offset, text, .. } => {
+            let len = char_len(text);
+            let shift = |pos: usize, inclusive: bool| {
+                if *offset < pos || (inclusive && *offset == pos) {
+                    pos + len
+                } else {
+                    pos
+                }
+            };
+            // Text typed inside the range (but not at its start) joins it
+            (
+                shift(start, true),
+                shift(end, false).max(shift(start, true)),
+            )
+        }
+        OperationType::DeleteText { offset, length, .. } => {
+            let shift = |pos: usize| {
+                if pos <= *offset {
+                    pos
+                } else {
+                    pos - (*length).min(pos - offset)
+                }
+            };
+            (shift(start), shift(end))
+        }
+        _ => (start, end),
+    }
+}
+
+/// Text range `[start, end)` an operation reads or replaces
+pub(crate) fn target_range(op: &OperationType) -> Option<(usize, usize)> {
+    match op {
+        OperationType::InsertText { offset, .. } => Some((*offset, *offset)),
+        OperationType::DeleteText { offset, length, .. }
+        | OperationType::ReplaceText { offset, length, .. }
+        | OperationType::FormatText { offset, length, .. } => Some((*offset, offset + length)),
+        OperationType::SetSelection { start, end, .. } => Some((*start, *end)),
+        _ => None,
+    }
+}
+
+/// Block targeted by a text-level operation
+fn text_block(op: &OperationType) -> Option<uuid::Uuid> {
+    match op {
+        OperationType::InsertText { block_id, .. }
+        | OperationType::DeleteText { block_id, .. }
+        | OperationType::ReplaceText { block_id, .. }
+        | OperationType::FormatText { block_id, .. }
+        | OperationType::SetSelection { block_id, .. } => Some(*block_id),
+        _ => None,
+    }
+}
+
+fn char_len(text: &str) -> usize {
+    text.chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn block() -> Uuid {
+        Uuid::from_u128(1)
+    }
+
+    fn op(user_id: i64, version: u64, op_type: OperationType) -> Operation {
+        Operation {
+            id: Uuid::new_v4(),
+            op_type,
+            user_id,
+            timestamp: Utc::now(),
+            version,
+        }
+    }
+
+    fn insert(offset: usize, text: &str) -> OperationType {
+        OperationType::InsertText {
+            block_id: block(),
+            offset,
+            text: text.to_string(),
+        }
+    }
+
+    fn delete(offset: usize, length: usize) -> OperationType {
+        OperationType::DeleteText {
+            block_id: block(),
+            offset,
+            length,
+        }
+    }
+
+    fn apply(text: &str, op: &OperationType) -> String {
+        let mut chars: Vec<char> = text.chars().collect();
+        match op {
+            OperationType::InsertText { offset, text, .. } => {
+                let at = (*offset).min(chars.len());
+                chars.splice(at..at, text.chars());
+            }
+            OperationType::DeleteText { offset, length, .. } => {
+                let start = (*offset).min(chars.len());
+                let end = (offset + length).min(chars.len());
+                chars.drain(start..end);
+            }
+            OperationType::ReplaceText {
+                offset,
+                length,
+                text,
+                ..
+            } => {
+                let start = (*offset).min(chars.len());
+                let end = (offset + length).min(chars.len());
+                chars.splice(start..end, text.chars());
+            }
+            _ => {}
+        }
+        chars.into_iter().collect()
+    }
+
+    /// Apply concurrent operations (all based on version 0) in the given
+    /// order and return the resulting text
+    fn run(doc: &str, ops: &[Operation]) -> String {
+        let mut engine = TransformEngine::new();
+        let mut text = doc.to_string();
+        for op in ops {
+            let result = engine.receive(op.clone()).unwrap();
+            for applied in &result.operations {
+                text = apply(&text, &applied.op_type);
+            }
+        }
+        text
+    }
+
+    #[test]
+    fn test_insert_tie_breaks_by_user_id() {
+        let a = op(1, 0, insert(2, "A"));
+        let b = op(2, 0, insert(2, "B"));
+        assert_eq!(run("xxxx", &[a.clone(), b.clone()]), "xxABxx");
+        assert_eq!(run("xxxx", &[b, a]), "xxABxx");
+    }
+
+    #[test]
+    fn test_delete_spanning_insert_is_split() {
+        let del = op(1, 0, delete(1, 4));
+        let ins = op(2, 0, insert(3, "new"));
+        assert_eq!(transform(&del, &ins).len(), 2);
+        assert_eq!(run("abcdefg", &[del.clone(), ins.clone()]), "anewfg");
+        assert_eq!(run("abcdefg", &[ins, del]), "anewfg");
+    }
+
+    #[test]
+    fn test_edits_to_deleted_block_are_dropped() {
+        let mut engine = TransformEngine::new();
+        engine
+            .receive(op(1, 0, OperationType::DeleteBlock { block_id: block() }))
+            .unwrap();
+        let result = engine.receive(op(2, 0, insert(0, "hi"))).unwrap();
+        assert!(result.operations.is_empty());
+        assert!(result.to_message().is_none());
+        assert_eq!(engine.version(), 1);
+    }
+
+    #[test]
+    fn test_version_checks() {
+        let mut engine = TransformEngine::with_history_limit(2);
+        for _ in 0..3 {
+            let version = engine.version();
+            engine.receive(op(1, version, insert(0, "x"))).unwrap();
+        }
+        assert_eq!(engine.version(), 3);
+        assert_eq!(engine.operations_since(1).unwrap().len(), 2);
+        assert_eq!(
+            engine.receive(op(1, 0, insert(0, "x"))).unwrap_err(),
+            TransformError::VersionTooOld {
+                client: 0,
+                oldest: 1
+            }
+        );
+        assert!(matches!(
+            engine.receive(op(1, 9, insert(0, "x"))),
+            Err(TransformError::VersionAhead { .. })
+        ));
+    }
+
+    /// Insert, delete or replace within a non-empty document of `len` chars
+    fn text_op(len: usize) -> impl Strategy<Value = OperationType> {
+        let insert_op = (0..=len, "[a-z]{1,4}").prop_map(|(offset, text)| insert(offset, &text));
+        let delete_op = (0..len).prop_flat_map(move |offset| {
+            (Just(offset), 1..=len - offset).prop_map(|(offset, length)| delete(offset, length))
+        });
+        let replace_op = (0..=len).prop_flat_map(move |offset| {
+            (Just(offset), 0..=len - offset, "[A-Z]{0,3}").prop_map(|(offset, length, text)| {
+                OperationType::ReplaceText {
+                    block_id: block(),
+                    offset,
+                    length,
+                    text,
+                }
+            })
+        });
+        prop_oneof![insert_op, delete_op, replace_op]
+    }
+
+    /// A document and two concurrent edits to it by different users
+    fn concurrent_edits() -> impl Strategy<Value = (String, Operation, Operation)> {
+        "[a-z]{1,12}".prop_flat_map(|doc| {
+            let len = doc.chars().count();
+            (Just(doc), text_op(len), text_op(len), 1i64..4, 1i64..4).prop_map(
+                |(doc, a, b, user_a, offset)| (doc, op(user_a, 0, a), op(user_a + offset, 0, b)),
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_concurrent_ops_converge((doc, a, b) in concurrent_edits()) {
+            let expected = run(&doc, &[a.clone(), b.clone()]);
+            prop_assert_eq!(expected, run(&doc, &[b, a]));
+        }
+    }
+}
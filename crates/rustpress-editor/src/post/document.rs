@@ -2,7 +2,10 @@
 //!
 //! The main document structure for posts in RustPress.
 
-use crate::blocks::{Block, BlockId, BlockSerializer};
+use crate::blocks::{
+    Block, BlockId, BlockRegistry, BlockSerializer, BlockType, BlockValidator, ValidationResult,
+};
+use crate::post::toc::{self, TocEntry};
 use crate::post::{
     FeaturedMedia, PostMetadata, PostPublishing, PostRevision, PostSeo, PostStats, PublishStatus,
 };
@@ -316,6 +319,25 @@ impl PostDocument {
         }
     }
 
+    /// Check every content block against its registered definition before
+    /// saving. Unregistered blocks may be converted to HTML, depending on
+    /// the validator's configuration.
+    pub fn validate_blocks(
+        &mut self,
+        registry: &BlockRegistry,
+        validator: &BlockValidator,
+    ) -> ValidationResult {
+        let result = validator.validate_against_registry(registry, &mut self.content.blocks);
+        if result
+            .warnings
+            .iter()
+            .any(|w| w.code == "BLOCK_CONVERTED_TO_HTML")
+        {
+            self.update_stats();
+        }
+        result
+    }
+
     /// Validate the document
     pub fn validate(&self) -> Vec<ValidationError> {
         let mut errors = Vec::new();
@@ -405,9 +427,28 @@ impl PostContent {
     }
 
     /// Get HTML from all blocks
+    ///
+    /// Headings carry the anchor ids used by [`Self::table_of_contents`], and
+    /// table of contents blocks are filled with links to them.
     pub fn get_html(&self) -> String {
+        let mut blocks = self.blocks.clone();
+        toc::assign_heading_anchors(&mut blocks);
+
+        let entries = toc::table_of_contents(&blocks);
+        fill_toc_blocks(&mut blocks, &entries);
+
         let serializer = BlockSerializer::new();
-        serializer.to_html(&self.blocks)
+        serializer.to_html(&blocks)
+    }
+
+    /// Store anchor ids on every content heading that lacks one
+    pub fn assign_heading_anchors(&mut self) {
+        toc::assign_heading_anchors(&mut self.blocks);
+    }
+
+    /// Nested table of contents built from the content's headings
+    pub fn table_of_contents(&self) -> Vec<TocEntry> {
+        toc::table_of_contents(&self.blocks)
     }
 
     /// Get all block IDs
@@ -511,8 +552,20 @@ pub struct CursorPosition {
     pub offset: usize,
 }
 
+/// Render table of contents blocks from the post's headings
+fn fill_toc_blocks(blocks: &mut [Block], entries: &[TocEntry]) {
+    for block in blocks {
+        if block.block_type == BlockType::TableOfContents {
+            let depth = block.attributes.toc_max_depth.unwrap_or(3);
+            let numbered = block.attributes.toc_numbered.unwrap_or(true);
+            block.attributes.content = Some(toc::toc_html(entries, depth, numbered));
+        }
+        fill_toc_blocks(&mut block.children, entries);
+    }
+}
+
 /// Simple slugify function
-fn slugify(text: &str) -> String {
+pub(crate) fn slugify(text: &str) -> String {
     let mut result = String::new();
     let mut last_was_dash = true; // Start true to avoid leading dashes
 
@@ -556,6 +609,63 @@ mod tests {
         assert_eq!(post.content.blocks.len(), 1);
     }
 
+    #[test]
+    fn test_validate_blocks_converts_unknown_when_lenient() {
+        use crate::blocks::ValidationConfig;
+
+        let mut post = PostDocument::new_post("Test");
+        let mut unknown = Block::new(BlockType::Custom(42));
+        unknown.attributes.content = Some("Plugin output".to_string());
+        let id = unknown.id;
+        post.add_block(unknown);
+
+        let registry = BlockRegistry::new();
+        let strict = BlockValidator::with_config(ValidationConfig::strict());
+        let result = post.clone().validate_blocks(&registry, &strict);
+        assert!(!result.is_valid);
+        assert_eq!(result.errors[0].location(), "[0].type");
+
+        let lenient = BlockValidator::with_config(ValidationConfig::lenient());
+        let result = post.validate_blocks(&registry, &lenient);
+        assert!(result.is_valid);
+        let converted = post.get_block(id).unwrap();
+        assert_eq!(converted.block_type, BlockType::Html);
+        assert_eq!(
+            converted.attributes.custom["original_type"],
+            serde_json::json!({ "custom": 42 })
+        );
+    }
+
+    #[test]
+    fn test_html_headings_match_toc() {
+        let mut post = PostDocument::new_post("Guide");
+        let mut toc_block = Block::new(BlockType::TableOfContents);
+        toc_block.attributes.toc_numbered = Some(false);
+        post.add_block(toc_block);
+        for text in ["Install", "Install"] {
+            let mut heading = Block::new(BlockType::Heading);
+            heading.attributes.level = Some(2);
+            heading.attributes.content = Some(text.to_string());
+            post.add_block(heading);
+        }
+
+        let toc = post.content.table_of_contents();
+        assert_eq!(toc[1].id, "install-2");
+
+        let html = post.content.get_html();
+        assert!(html.contains(r#"<h2 id="install">Install</h2>"#));
+        assert!(html.contains(r#"<h2 id="install-2">Install</h2>"#));
+        assert!(html.contains(r##"<li><a href="#install">Install</a></li>"##));
+        assert!(html.contains("<ul>"));
+        assert!(post.content.blocks[1].meta.anchor.is_none());
+
+        post.content.assign_heading_anchors();
+        assert_eq!(
+            post.content.blocks[2].meta.anchor.as_deref(),
+            Some("install-2")
+        );
+    }
+
     #[test]
     fn test_slugify() {
         assert_eq!(slugify("Hello World"), "hello-world");
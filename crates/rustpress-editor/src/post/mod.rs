@@ -7,6 +7,7 @@
 //! - Publishing workflow
 //! - Version history
 //! - Media attachments
+//! - Table of contents
 
 pub mod document;
 pub mod media;
@@ -15,6 +16,7 @@ pub mod publishing;
 pub mod revision;
 pub mod seo;
 pub mod stats;
+pub mod toc;
 
 pub use document::*;
 pub use media::*;
@@ -23,3 +25,4 @@ pub use publishing::*;
 pub use revision::*;
 pub use seo::*;
 pub use stats::*;
+pub use toc::TocEntry;
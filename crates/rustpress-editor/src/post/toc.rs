@@ -0,0 +1,311 @@
+//! Table of Contents
+//!
+//! Heading anchors and the nested table of contents built from them.
+//!
+//! Every heading in the post's content flow gets an anchor id derived from
+//! its text. Ids are slugified and deduplicated in document order (`intro`,
+//! `intro-2`, ...), and anchors set by the author are kept as-is and never
+//! reused. Headings nested inside non-content blocks such as quotes, cards or
+//! testimonials are not part of the outline and are skipped.
+
+use crate::blocks::{Block, BlockId, BlockType};
+use crate::post::document::slugify;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Anchor used when a heading's text has nothing to slugify
+const FALLBACK_ANCHOR: &str = "section";
+
+/// One heading in a table of contents
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TocEntry {
+    /// Anchor id of the heading (without `#`)
+    pub id: String,
+
+    /// Heading text, with inline markup removed
+    pub text: String,
+
+    /// Heading level (1-6)
+    pub level: u8,
+
+    /// Block the heading came from
+    pub block_id: BlockId,
+
+    /// Lower-level headings that follow this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TocEntry>,
+}
+
+impl TocEntry {
+    /// Link target for this entry
+    pub fn href(&self) -> String {
+        format!("#{}", self.id)
+    }
+}
+
+/// Give every content heading without an anchor a unique one
+pub fn assign_heading_anchors(blocks: &mut [Block]) {
+    let mut used = HashSet::new();
+    collect_anchors(blocks, &mut used);
+    assign_recursive(blocks, &mut used);
+}
+
+/// Build the nested table of contents for `blocks`.
+///
+/// Ids match the ones [`assign_heading_anchors`] gives the same blocks.
+pub fn table_of_contents(blocks: &[Block]) -> Vec<TocEntry> {
+    let mut blocks = blocks.to_vec();
+    assign_heading_anchors(&mut blocks);
+
+    let mut flat = Vec::new();
+    collect_headings(&blocks, &mut flat);
+    nest(flat)
+}
+
+/// Render a table of contents as nested lists.
+///
+/// Entries deeper than `max_depth` levels of nesting are left out.
+pub fn toc_html(entries: &[TocEntry], max_depth: u8, numbered: bool) -> String {
+    if entries.is_empty() || max_depth == 0 {
+        return String::new();
+    }
+
+    let tag = if numbered { "ol" } else { "ul" };
+    let mut html = format!("<{tag}>\n");
+    for entry in entries {
+        html.push_str(&format!(
+            r##"<li><a href="#{}">{}</a>"##,
+            html_escape(&entry.id),
+            html_escape(&entry.text)
+        ));
+        let children = toc_html(&entry.children, max_depth - 1, numbered);
+        if !children.is_empty() {
+            html.push('\n');
+            html.push_str(&children);
+        }
+        html.push_str("</li>\n");
+    }
+    html.push_str(&format!("</{tag}>\n"));
+    html
+}
+
+/// Whether headings inside this block belong to the post outline
+fn is_content_container(block_type: &BlockType) -> bool {
+    matches!(
+        block_type,
+        BlockType::Group
+            | BlockType::Columns
+            | BlockType::Column
+            | BlockType::Section
+            | BlockType::Row
+            | BlockType::Stack
+            | BlockType::Grid
+            | BlockType::MediaText
+            | BlockType::Cover
+    )
+}
+
+fn collect_anchors(blocks: &[Block], used: &mut HashSet<String>) {
+    for block in blocks {
+        if let Some(anchor) = &block.meta.anchor {
+            used.insert(anchor.clone());
+        }
+        collect_anchors(&block.children, used);
+    }
+}
+
+fn assign_recursive(blocks: &mut [Block], used: &mut HashSet<String>) {
+    for block in blocks {
+        if block.block_type == BlockType::Heading {
+            let text = heading_text(block);
+            if block.meta.anchor.is_none() && !text.is_empty() {
+                block.meta.anchor = Some(unique_anchor(&text, used));
+            }
+        } else if is_content_container(&block.block_type) {
+            assign_recursive(&mut block.children, used);
+        }
+    }
+}
+
+fn unique_anchor(text: &str, used: &mut HashSet<String>) -> String {
+    let mut base = slugify(text);
+    if base.is_empty() {
+        base = FALLBACK_ANCHOR.to_string();
+    }
+
+    let mut candidate = base.clone();
+    let mut n = 2;
+    while used.contains(&candidate) {
+        candidate = format!("{}-{}", base, n);
+        n += 1;
+    }
+    used.insert(candidate.clone());
+    candidate
+}
+
+fn collect_headings(blocks: &[Block], out: &mut Vec<TocEntry>) {
+    for block in blocks {
+        if block.block_type == BlockType::Heading {
+            if let Some(id) = &block.meta.anchor {
+                let text = heading_text(block);
+                if !text.is_empty() {
+                    out.push(TocEntry {
+                        id: id.clone(),
+                        text,
+                        level: block.attributes.level.unwrap_or(2).clamp(1, 6),
+                        block_id: block.id,
+                        children: Vec::new(),
+                    });
+                }
+            }
+        } else if is_content_container(&block.block_type) {
+            collect_headings(&block.children, out);
+        }
+    }
+}
+
+/// Nest a flat, document-ordered list of headings by level
+fn nest(flat: Vec<TocEntry>) -> Vec<TocEntry> {
+    // Open entries from the outermost to the innermost
+    let mut stack: Vec<TocEntry> = Vec::new();
+    let mut roots = Vec::new();
+
+    for entry in flat {
+        while stack.last().is_some_and(|open| open.level >= entry.level) {
+            close_last(&mut stack, &mut roots);
+        }
+        stack.push(entry);
+    }
+    while !stack.is_empty() {
+        close_last(&mut stack, &mut roots);
+    }
+    roots
+}
+
+fn close_last(stack: &mut Vec<TocEntry>, roots: &mut Vec<TocEntry>) {
+    if let Some(done) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(done),
+            None => roots.push(done),
+        }
+    }
+}
+
+/// Heading text with inline markup removed and whitespace collapsed
+fn heading_text(block: &Block) -> String {
+    let content = block.get_text_content();
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in content.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heading(level: u8, text: &str) -> Block {
+        let mut block = Block::new(BlockType::Heading);
+        block.attributes.level = Some(level);
+        block.attributes.content = Some(text.to_string());
+        block
+    }
+
+    fn container(block_type: BlockType, children: Vec<Block>) -> Block {
+        let mut block = Block::new(block_type);
+        block.children = children;
+        block
+    }
+
+    #[test]
+    fn test_duplicate_headings_get_unique_ids() {
+        let mut blocks = vec![
+            heading(2, "Setup"),
+            heading(2, "Setup"),
+            heading(2, "Setup!"),
+            heading(2, "<em>Setup</em>"),
+        ];
+        assign_heading_anchors(&mut blocks);
+        let ids: Vec<_> = blocks
+            .iter()
+            .map(|b| b.meta.anchor.clone().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["setup", "setup-2", "setup-3", "setup-4"]);
+    }
+
+    #[test]
+    fn test_author_anchors_are_kept_and_reserved() {
+        let mut custom = heading(2, "Other");
+        custom.meta.anchor = Some("setup".to_string());
+        let mut blocks = vec![heading(2, "Setup"), custom, heading(2, "!!!")];
+        assign_heading_anchors(&mut blocks);
+        assert_eq!(blocks[0].meta.anchor.as_deref(), Some("setup-2"));
+        assert_eq!(blocks[1].meta.anchor.as_deref(), Some("setup"));
+        assert_eq!(blocks[2].meta.anchor.as_deref(), Some("section"));
+    }
+
+    #[test]
+    fn test_nesting_follows_levels() {
+        let blocks = vec![
+            heading(2, "Intro"),
+            heading(3, "Background"),
+            heading(4, "History"),
+            heading(3, "Goals"),
+            heading(2, "Usage"),
+            heading(4, "Deep"),
+        ];
+        let toc = table_of_contents(&blocks);
+        assert_eq!(toc.len(), 2);
+        assert_eq!(toc[0].text, "Intro");
+        assert_eq!(toc[0].children.len(), 2);
+        assert_eq!(toc[0].children[0].children[0].id, "history");
+        assert_eq!(toc[0].children[1].id, "goals");
+        assert_eq!(toc[1].children[0].id, "deep");
+        assert_eq!(toc[1].href(), "#usage");
+    }
+
+    #[test]
+    fn test_skips_headings_in_non_content_blocks() {
+        let blocks = vec![
+            container(BlockType::Quote, vec![heading(2, "Quoted")]),
+            container(
+                BlockType::Group,
+                vec![container(BlockType::Column, vec![heading(2, "Inside")])],
+            ),
+        ];
+        let toc = table_of_contents(&blocks);
+        assert_eq!(toc.len(), 1);
+        assert_eq!(toc[0].id, "inside");
+
+        let mut blocks = blocks;
+        assign_heading_anchors(&mut blocks);
+        assert!(blocks[0].children[0].meta.anchor.is_none());
+    }
+
+    #[test]
+    fn test_toc_html_respects_depth() {
+        let toc = table_of_contents(&[heading(2, "A & B"), heading(3, "Child")]);
+        let html = toc_html(&toc, 1, false);
+        assert_eq!(
+            html,
+            "<ul>\n<li><a href=\"#a-b\">A &amp; B</a></li>\n</ul>\n"
+        );
+        let html = toc_html(&toc, 2, true);
+        assert!(html.starts_with("<ol>"));
+        assert!(html.contains(r##"<a href="#child">Child</a>"##));
+    }
+}
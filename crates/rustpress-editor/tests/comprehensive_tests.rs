@@ -1153,7 +1153,7 @@ fn test_111_transform_paragraph_to_heading() {
     para.attributes.content = Some("Test heading".to_string());
 
     let result = transformer.transform(&para, BlockType::Heading);
-    assert!(result.is_some());
+    assert!(result.is_ok());
     let heading = result.unwrap();
     assert_eq!(heading.block_type, BlockType::Heading);
     assert_eq!(heading.attributes.content, Some("Test heading".to_string()));
@@ -1167,7 +1167,7 @@ fn test_112_transform_heading_to_paragraph() {
     heading.attributes.level = Some(2);
 
     let result = transformer.transform(&heading, BlockType::Paragraph);
-    assert!(result.is_some());
+    assert!(result.is_ok());
     let para = result.unwrap();
     assert_eq!(para.block_type, BlockType::Paragraph);
     assert!(para.attributes.level.is_none());
@@ -1180,7 +1180,7 @@ fn test_113_transform_paragraph_to_list() {
     para.attributes.content = Some("Item 1\nItem 2\nItem 3".to_string());
 
     let result = transformer.transform(&para, BlockType::List);
-    assert!(result.is_some());
+    assert!(result.is_ok());
     let list = result.unwrap();
     assert_eq!(list.children.len(), 3);
 }
@@ -1192,7 +1192,7 @@ fn test_114_transform_image_to_gallery() {
     image.attributes.url = Some("https://example.com/image.jpg".to_string());
 
     let result = transformer.transform(&image, BlockType::Gallery);
-    assert!(result.is_some());
+    assert!(result.is_ok());
     let gallery = result.unwrap();
     assert_eq!(gallery.attributes.images.len(), 1);
 }
@@ -1204,7 +1204,7 @@ fn test_115_transform_invalid() {
 
     // Paragraph can't directly transform to Image
     let result = transformer.transform(&para, BlockType::Image);
-    assert!(result.is_none());
+    assert!(result.is_err());
 }
 
 #[test]
@@ -1233,7 +1233,7 @@ fn test_118_transform_preserves_styles() {
     para.styles.text_color = Some("#ff0000".to_string());
 
     let result = transformer.transform(&para, BlockType::Heading);
-    assert!(result.is_some());
+    assert!(result.is_ok());
     let heading = result.unwrap();
     assert_eq!(heading.styles.text_color, Some("#ff0000".to_string()));
 }
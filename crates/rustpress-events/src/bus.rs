@@ -1,12 +1,15 @@
 //! Event bus for publishing and subscribing to events.
 
+use crate::durable::DurableSubscription;
 use crate::event::{DomainEvent, EventType};
+use crate::store::{EventRetention, EventStore};
 use crate::subscriber::Subscriber;
 use dashmap::DashMap;
 use parking_lot::RwLock;
-use rustpress_core::error::Result;
+use rustpress_core::error::{Error, Result};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, Notify};
 
 /// Event bus for decoupled component communication
 pub struct EventBus {
@@ -16,6 +19,14 @@ pub struct EventBus {
     broadcast_tx: broadcast::Sender<Arc<DomainEvent>>,
     /// Event history for replay (optional)
     history: Option<RwLock<Vec<Arc<DomainEvent>>>>,
+    /// Durable event log (optional)
+    store: Option<Arc<dyn EventStore>>,
+    /// Wakes durable subscriptions when an event is appended
+    appended: Arc<Notify>,
+    /// Async dispatches still running
+    pending: Arc<AtomicUsize>,
+    /// Notified when the last pending dispatch finishes
+    drained: Arc<Notify>,
     /// Configuration
     config: EventBusConfig,
 }
@@ -63,36 +74,93 @@ impl EventBus {
             subscribers: DashMap::new(),
             broadcast_tx,
             history,
+            store: None,
+            appended: Arc::new(Notify::new()),
+            pending: Arc::new(AtomicUsize::new(0)),
+            drained: Arc::new(Notify::new()),
             config,
         }
     }
 
+    /// Persist published events to `store`, enabling durable subscriptions
+    pub fn with_store(mut self, store: Arc<dyn EventStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Open a durable subscription. With `from_offset` of `None` it resumes
+    /// after the subscriber's last acknowledged offset, or from the start of
+    /// the retained log if it has none; otherwise it replays events after
+    /// `from_offset`.
+    pub async fn subscribe_durable(
+        &self,
+        name: impl Into<String>,
+        from_offset: Option<u64>,
+    ) -> Result<DurableSubscription> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::internal("Event bus has no durable event store"))?;
+        let name = name.into();
+
+        let position = match from_offset {
+            Some(offset) => offset,
+            None => store.acked_offset(&name).await?.unwrap_or(0),
+        };
+        tracing::info!(subscription = %name, position, "Durable subscription opened");
+
+        Ok(DurableSubscription::new(
+            name,
+            store,
+            self.appended.clone(),
+            position,
+        ))
+    }
+
+    /// Remove old events from the durable log
+    pub async fn prune_event_log(&self, retention: &EventRetention) -> Result<u64> {
+        match &self.store {
+            Some(store) => store.prune(retention).await,
+            None => Ok(0),
+        }
+    }
+
     /// Subscribe to events
     pub fn subscribe(&self, subscriber: Subscriber) -> &Self {
         let subscriber = Arc::new(subscriber);
 
-        for event_type in &subscriber.config.event_types {
-            self.subscribers
-                .entry(event_type.clone())
-                .or_insert_with(Vec::new)
-                .push(subscriber.clone());
-        }
+        let mut event_types = subscriber.config.event_types.clone();
+        event_types.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        event_types.dedup();
 
-        // Sort by priority (higher priority first)
-        for mut entry in self.subscribers.iter_mut() {
-            entry
-                .value_mut()
-                .sort_by(|a, b| b.config.priority.cmp(&a.config.priority));
+        for event_type in event_types {
+            let mut entry = self.subscribers.entry(event_type).or_default();
+            // Keep higher priority first, after existing subscribers of equal priority
+            let index = entry.partition_point(|s| s.config.priority >= subscriber.config.priority);
+            entry.insert(index, subscriber.clone());
         }
 
         self
     }
 
+    /// Subscribe to only the given event types, replacing the types in the
+    /// subscriber's config. Any event type may be given, including ones no
+    /// publisher has used yet.
+    pub fn subscribe_filtered(
+        &self,
+        event_types: Vec<EventType>,
+        mut subscriber: Subscriber,
+    ) -> &Self {
+        subscriber.config.event_types = event_types;
+        self.subscribe(subscriber)
+    }
+
     /// Unsubscribe by subscriber name
     pub fn unsubscribe(&self, name: &str) {
-        for mut entry in self.subscribers.iter_mut() {
-            entry.value_mut().retain(|s| s.name != name);
-        }
+        self.subscribers.retain(|_, subscribers| {
+            subscribers.retain(|s| s.name != name);
+            !subscribers.is_empty()
+        });
     }
 
     /// Publish an event
@@ -105,6 +173,12 @@ impl EventBus {
             "Publishing event"
         );
 
+        // Persist before dispatch so durable subscribers can't miss it
+        if let Some(store) = &self.store {
+            store.append(&event).await?;
+            self.appended.notify_waiters();
+        }
+
         // Add to history if enabled
         if let Some(history) = &self.history {
             let mut h = history.write();
@@ -148,6 +222,9 @@ impl EventBus {
 
         if !async_subscribers.is_empty() {
             let event_clone = event.clone();
+            let pending = self.pending.clone();
+            let drained = self.drained.clone();
+            pending.fetch_add(1, Ordering::SeqCst);
             tokio::spawn(async move {
                 for subscriber in async_subscribers {
                     if let Err(e) = subscriber.handle(event_clone.clone()).await {
@@ -158,6 +235,9 @@ impl EventBus {
                         );
                     }
                 }
+                if pending.fetch_sub(1, Ordering::SeqCst) == 1 {
+                    drained.notify_waiters();
+                }
             });
         }
 
@@ -180,6 +260,24 @@ impl EventBus {
         Ok(())
     }
 
+    /// Number of async dispatches that haven't finished yet
+    pub fn pending_dispatches(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    /// Wait until async handlers for every event published so far have run
+    pub async fn flush(&self) {
+        loop {
+            let drained = self.drained.notified();
+            let pending = self.pending.load(Ordering::SeqCst);
+            if pending == 0 {
+                return;
+            }
+            tracing::debug!(pending, "Waiting for async event handlers");
+            drained.await;
+        }
+    }
+
     /// Get a broadcast receiver for external listeners
     pub fn subscribe_broadcast(&self) -> broadcast::Receiver<Arc<DomainEvent>> {
         self.broadcast_tx.subscribe()
@@ -232,6 +330,7 @@ impl Default for EventBus {
 pub struct EventBusBuilder {
     config: EventBusConfig,
     subscribers: Vec<Subscriber>,
+    store: Option<Arc<dyn EventStore>>,
 }
 
 impl EventBusBuilder {
@@ -239,9 +338,15 @@ impl EventBusBuilder {
         Self {
             config: EventBusConfig::default(),
             subscribers: Vec::new(),
+            store: None,
         }
     }
 
+    pub fn durable(mut self, store: Arc<dyn EventStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
     pub fn with_history(mut self, max_size: usize) -> Self {
         self.config.enable_history = true;
         self.config.max_history = max_size;
@@ -264,7 +369,10 @@ impl EventBusBuilder {
     }
 
     pub fn build(self) -> EventBus {
-        let bus = EventBus::with_config(self.config);
+        let mut bus = EventBus::with_config(self.config);
+        if let Some(store) = self.store {
+            bus = bus.with_store(store);
+        }
         for subscriber in self.subscribers {
             bus.subscribe(subscriber);
         }
@@ -325,6 +433,39 @@ mod tests {
         assert_eq!(counter.load(Ordering::SeqCst), 3);
     }
 
+    #[tokio::test]
+    async fn test_flush_waits_for_async_handlers() {
+        let bus = EventBus::new();
+        let counter = Arc::new(AtomicU32::new(0));
+        let c = counter.clone();
+
+        bus.subscribe(
+            crate::subscriber::SubscriberBuilder::new()
+                .event_type("test.event")
+                .async_handler()
+                .build(move |_| {
+                    let c = c.clone();
+                    async move {
+                        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                        c.fetch_add(1, Ordering::SeqCst);
+                        Ok(())
+                    }
+                }),
+        );
+
+        for _ in 0..3 {
+            let event = DomainEvent::new("test.event", serde_json::json!({}));
+            bus.publish(event).await.unwrap();
+        }
+
+        bus.flush().await;
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+        assert_eq!(bus.pending_dispatches(), 0);
+
+        // Nothing pending returns immediately
+        bus.flush().await;
+    }
+
     #[tokio::test]
     async fn test_event_history() {
         let bus = EventBusBuilder::new().with_history(10).build();
@@ -372,6 +513,133 @@ mod tests {
             .await
             .unwrap();
         assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(bus.event_types().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_filtered() {
+        let bus = EventBus::new();
+        let counter = Arc::new(AtomicU32::new(0));
+        let counter_clone = counter.clone();
+
+        bus.subscribe_filtered(
+            vec![
+                EventType::new("plugin.synced"),
+                EventType::new("plugin.synced"),
+            ],
+            Subscriber::for_event("test.event", move |_| {
+                let c = counter_clone.clone();
+                async move {
+                    c.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }
+            }),
+        );
+        assert_eq!(bus.subscriber_count(&EventType::new("plugin.synced")), 1);
+        assert_eq!(bus.subscriber_count(&EventType::new("test.event")), 0);
+
+        bus.publish(DomainEvent::new("test.event", serde_json::json!({})))
+            .await
+            .unwrap();
+        bus.publish(DomainEvent::new("plugin.synced", serde_json::json!({})))
+            .await
+            .unwrap();
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_order_by_priority_then_registration() {
+        let bus = EventBus::new();
+        let order = Arc::new(RwLock::new(Vec::new()));
+
+        for (name, priority) in [("a", 0), ("b", 10), ("c", 0), ("d", 10)] {
+            let order = order.clone();
+            bus.subscribe(
+                crate::subscriber::SubscriberBuilder::new()
+                    .name(name)
+                    .event_type("test.event")
+                    .priority(priority)
+                    .build(move |_| {
+                        order.write().push(name);
+                        async { Ok(()) }
+                    }),
+            );
+        }
+
+        bus.publish(DomainEvent::new("test.event", serde_json::json!({})))
+            .await
+            .unwrap();
+        assert_eq!(*order.read(), ["b", "d", "a", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_durable_subscription_resumes_from_ack() {
+        let store = Arc::new(crate::store::MemoryEventStore::new());
+        let bus = EventBusBuilder::new().durable(store.clone()).build();
+        for i in 0..3 {
+            bus.publish(DomainEvent::new("test.event", serde_json::json!({"i": i})))
+                .await
+                .unwrap();
+        }
+
+        let mut search = bus.subscribe_durable("search", None).await.unwrap();
+        let batch = search.recv().await.unwrap();
+        assert_eq!(batch.len(), 3);
+        search.ack(batch[1].offset).await.unwrap();
+
+        // The unacknowledged third event is delivered again after a restart
+        let mut search = bus.subscribe_durable("search", None).await.unwrap();
+        let batch = search.next_batch().await.unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].event.payload["i"], 2);
+
+        // Other subscribers and explicit offsets are independent
+        let mut webhooks = bus.subscribe_durable("webhooks", None).await.unwrap();
+        assert_eq!(webhooks.next_batch().await.unwrap().len(), 3);
+        let mut replay = bus.subscribe_durable("search", Some(0)).await.unwrap();
+        assert_eq!(replay.next_batch().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_spawned_durable_subscriber_retries_failures() {
+        let store = Arc::new(crate::store::MemoryEventStore::new());
+        let bus = EventBus::new().with_store(store.clone());
+        let attempts = Arc::new(AtomicU32::new(0));
+        let attempts_clone = attempts.clone();
+
+        let subscriber = Subscriber::new(
+            "flaky",
+            crate::subscriber::SubscriberConfig::new(vec![EventType::new("test.event")])
+                .with_retries(0, 0),
+            move |_| {
+                let attempts = attempts_clone.clone();
+                async move {
+                    if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                        Err(Error::internal("temporary failure"))
+                    } else {
+                        Ok(())
+                    }
+                }
+            },
+        );
+        bus.subscribe_durable("flaky", None)
+            .await
+            .unwrap()
+            .with_poll_interval(std::time::Duration::from_millis(10))
+            .spawn(subscriber);
+
+        bus.publish(DomainEvent::new("test.event", serde_json::json!({})))
+            .await
+            .unwrap();
+
+        for _ in 0..100 {
+            if store.acked_offset("flaky").await.unwrap() == Some(1) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(store.acked_offset("flaky").await.unwrap(), Some(1));
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
     }
 
     #[tokio::test]
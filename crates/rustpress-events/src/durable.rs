@@ -0,0 +1,148 @@
+//! Durable subscriptions reading from the event log.
+//!
+//! Each subscription keeps its own position in the log, so a slow subscriber
+//! only falls behind itself. Delivery is at-least-once: offsets are
+//! acknowledged after handling, and anything delivered but not yet
+//! acknowledged is delivered again when the subscription resumes.
+
+use crate::event::EventType;
+use crate::store::{EventStore, StoredEvent};
+use crate::subscriber::Subscriber;
+use rustpress_core::error::Result;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Default number of events read from the log at a time
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+
+/// A named subscriber's cursor into the event log
+pub struct DurableSubscription {
+    name: String,
+    store: Arc<dyn EventStore>,
+    appended: Arc<Notify>,
+    position: u64,
+    batch_size: usize,
+    poll_interval: Duration,
+}
+
+impl DurableSubscription {
+    pub(crate) fn new(
+        name: String,
+        store: Arc<dyn EventStore>,
+        appended: Arc<Notify>,
+        position: u64,
+    ) -> Self {
+        Self {
+            name,
+            store,
+            appended,
+            position,
+            batch_size: DEFAULT_BATCH_SIZE,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// How often to check the log for events appended by other processes
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Subscriber name the offset is tracked under
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Offset of the last event delivered
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Move the cursor so delivery continues after `offset`
+    pub fn seek(&mut self, offset: u64) {
+        self.position = offset;
+    }
+
+    /// Events after the current position, possibly none
+    pub async fn next_batch(&mut self) -> Result<Vec<StoredEvent>> {
+        let batch = self
+            .store
+            .read_after(self.position, self.batch_size)
+            .await?;
+        if let Some(last) = batch.last() {
+            self.position = last.offset;
+        }
+        Ok(batch)
+    }
+
+    /// Wait for the next non-empty batch
+    pub async fn recv(&mut self) -> Result<Vec<StoredEvent>> {
+        loop {
+            let batch = self.next_batch().await?;
+            if !batch.is_empty() {
+                return Ok(batch);
+            }
+            // Local appends wake us early; other processes' are picked up by polling
+            let _ = tokio::time::timeout(self.poll_interval, self.appended.notified()).await;
+        }
+    }
+
+    /// Record that everything up to `offset` has been handled
+    pub async fn ack(&self, offset: u64) -> Result<()> {
+        self.store.ack(&self.name, offset).await
+    }
+
+    /// Deliver events to `subscriber` in the background, acknowledging each
+    /// batch once handled. Events of types the subscriber doesn't handle are
+    /// acknowledged without delivery. A failed event is retried after the
+    /// poll interval, holding back later events for this subscription only.
+    pub fn spawn(mut self, subscriber: Subscriber) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let batch = match self.recv().await {
+                    Ok(batch) => batch,
+                    Err(e) => {
+                        tracing::warn!(subscription = %self.name, error = %e, "Failed to read event log");
+                        tokio::time::sleep(self.poll_interval).await;
+                        continue;
+                    }
+                };
+
+                let mut handled = None;
+                let mut failed = false;
+                for stored in batch {
+                    let event_type = EventType::new(&stored.event.event_type);
+                    if subscriber.handles(&event_type) {
+                        if let Err(e) = subscriber.handle(Arc::new(stored.event)).await {
+                            tracing::error!(
+                                subscription = %self.name,
+                                offset = stored.offset,
+                                error = %e,
+                                "Durable event handler failed; will retry"
+                            );
+                            self.seek(stored.offset - 1);
+                            failed = true;
+                            break;
+                        }
+                    }
+                    handled = Some(stored.offset);
+                }
+
+                if let Some(offset) = handled {
+                    if let Err(e) = self.ack(offset).await {
+                        tracing::warn!(subscription = %self.name, error = %e, "Failed to acknowledge events");
+                    }
+                }
+                if failed {
+                    tokio::time::sleep(self.poll_interval).await;
+                }
+            }
+        })
+    }
+}
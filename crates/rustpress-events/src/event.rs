@@ -153,6 +153,54 @@ pub struct EventMetadata {
     pub data: std::collections::HashMap<String, serde_json::Value>,
 }
 
+/// Payload of a [`events::POST_PUBLISHED`] event raised by a post moving
+/// into the published status
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostPublished {
+    pub post_id: Uuid,
+    pub author_id: Uuid,
+    #[serde(default = "default_post_type")]
+    pub post_type: String,
+    pub title: String,
+    #[serde(default)]
+    pub slug: String,
+    #[serde(default)]
+    pub excerpt: Option<String>,
+    /// Status before publishing, if the publisher knows it
+    #[serde(default)]
+    pub previous_status: Option<String>,
+}
+
+fn default_post_type() -> String {
+    "post".to_string()
+}
+
+impl PostPublished {
+    /// Whether the post went public with this event, as opposed to an
+    /// already-published post being saved again. Unknown transitions count
+    /// as not first.
+    pub fn is_first_publication(&self) -> bool {
+        matches!(self.previous_status.as_deref(), Some(status) if status != "published")
+    }
+
+    /// Read the payload of a post published event
+    pub fn from_event(event: &DomainEvent) -> Option<Self> {
+        if event.event_type != events::POST_PUBLISHED {
+            return None;
+        }
+        serde_json::from_value(event.payload.clone()).ok()
+    }
+
+    pub fn into_event(self) -> DomainEvent {
+        let post_id = self.post_id;
+        DomainEvent::new(
+            events::POST_PUBLISHED,
+            serde_json::to_value(self).unwrap_or_default(),
+        )
+        .with_aggregate(post_id, "post")
+    }
+}
+
 /// Predefined event types
 pub mod events {
     use super::*;
@@ -291,6 +339,35 @@ mod tests {
         assert_eq!(et.to_string(), "user.created");
     }
 
+    #[test]
+    fn test_post_published_transition() {
+        let published = PostPublished {
+            post_id: Uuid::new_v4(),
+            author_id: Uuid::new_v4(),
+            post_type: "post".to_string(),
+            title: "Hello".to_string(),
+            slug: "hello".to_string(),
+            excerpt: None,
+            previous_status: Some("draft".to_string()),
+        };
+        assert!(published.is_first_publication());
+
+        let event = published.clone().into_event();
+        assert_eq!(PostPublished::from_event(&event), Some(published.clone()));
+
+        let edit = PostPublished {
+            previous_status: Some("published".to_string()),
+            ..published
+        };
+        assert!(!edit.is_first_publication());
+
+        // Events without a known previous status are not treated as new
+        let legacy = events::post_published(Uuid::new_v4(), Uuid::new_v4(), "Hello");
+        let legacy = PostPublished::from_event(&legacy).unwrap();
+        assert_eq!(legacy.post_type, "post");
+        assert!(!legacy.is_first_publication());
+    }
+
     #[test]
     fn test_predefined_events() {
         let event = events::user_created(Uuid::now_v7(), "test@example.com", "testuser");
@@ -3,9 +3,13 @@
 //! Event bus and messaging system for decoupled component communication.
 
 pub mod bus;
+pub mod durable;
 pub mod event;
+pub mod store;
 pub mod subscriber;
 
 pub use bus::EventBus;
-pub use event::{DomainEvent, Event, EventType};
+pub use durable::DurableSubscription;
+pub use event::{DomainEvent, Event, EventType, PostPublished};
+pub use store::{EventRetention, EventStore, MemoryEventStore, PgEventStore, StoredEvent};
 pub use subscriber::{EventHandler, Subscriber};
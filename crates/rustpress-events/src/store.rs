@@ -0,0 +1,373 @@
+//! Durable event log backing replayable subscriptions.
+//!
+//! Every event published on a bus with a store gets a monotonically
+//! increasing offset. Durable subscribers read the log from their own
+//! position and acknowledge offsets once handled, so after a restart they
+//! resume from the last acknowledged event.
+
+use crate::event::DomainEvent;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::RwLock;
+use rustpress_core::error::{Error, Result};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// An event together with its position in the log
+#[derive(Debug, Clone)]
+pub struct StoredEvent {
+    pub offset: u64,
+    pub event: DomainEvent,
+}
+
+/// How long events are kept in the log
+#[derive(Debug, Clone)]
+pub struct EventRetention {
+    /// Events older than this are removed once every durable subscriber
+    /// has acknowledged them
+    pub min_age: Duration,
+    /// Events older than this are removed even if a subscriber hasn't
+    /// acknowledged them, so an abandoned subscriber can't grow the log
+    /// without bound
+    pub max_age: Duration,
+}
+
+impl Default for EventRetention {
+    fn default() -> Self {
+        Self {
+            min_age: Duration::days(1),
+            max_age: Duration::days(30),
+        }
+    }
+}
+
+impl EventRetention {
+    /// Whether an event may be removed
+    fn prunable(&self, fully_acked: bool, occurred_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        let age = now - occurred_at;
+        age > self.max_age || (age > self.min_age && fully_acked)
+    }
+}
+
+/// Storage for the durable event log and subscriber offsets
+#[async_trait]
+pub trait EventStore: Send + Sync {
+    /// Append an event, returning its offset
+    async fn append(&self, event: &DomainEvent) -> Result<u64>;
+
+    /// Up to `limit` events with offsets greater than `after`, oldest first
+    async fn read_after(&self, after: u64, limit: usize) -> Result<Vec<StoredEvent>>;
+
+    /// Last offset acknowledged by a subscriber
+    async fn acked_offset(&self, subscriber: &str) -> Result<Option<u64>>;
+
+    /// Record that a subscriber has handled everything up to `offset`
+    async fn ack(&self, subscriber: &str, offset: u64) -> Result<()>;
+
+    /// Remove events per `retention`, returning how many were removed
+    async fn prune(&self, retention: &EventRetention) -> Result<u64>;
+}
+
+/// In-process event log, for tests and single-instance setups that only
+/// need replay within one process lifetime
+#[derive(Default)]
+pub struct MemoryEventStore {
+    events: RwLock<BTreeMap<u64, DomainEvent>>,
+    last_offset: AtomicU64,
+    offsets: RwLock<HashMap<String, u64>>,
+}
+
+impl MemoryEventStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of events in the log
+    pub fn len(&self) -> usize {
+        self.events.read().len()
+    }
+
+    /// Check if the log is empty
+    pub fn is_empty(&self) -> bool {
+        self.events.read().is_empty()
+    }
+}
+
+#[async_trait]
+impl EventStore for MemoryEventStore {
+    async fn append(&self, event: &DomainEvent) -> Result<u64> {
+        let mut events = self.events.write();
+        // Taken under the lock so offsets land in the map in order
+        let offset = self.last_offset.fetch_add(1, Ordering::SeqCst) + 1;
+        events.insert(offset, event.clone());
+        Ok(offset)
+    }
+
+    async fn read_after(&self, after: u64, limit: usize) -> Result<Vec<StoredEvent>> {
+        Ok(self
+            .events
+            .read()
+            .range(after + 1..)
+            .take(limit)
+            .map(|(&offset, event)| StoredEvent {
+                offset,
+                event: event.clone(),
+            })
+            .collect())
+    }
+
+    async fn acked_offset(&self, subscriber: &str) -> Result<Option<u64>> {
+        Ok(self.offsets.read().get(subscriber).copied())
+    }
+
+    async fn ack(&self, subscriber: &str, offset: u64) -> Result<()> {
+        let mut offsets = self.offsets.write();
+        let acked = offsets.entry(subscriber.to_string()).or_insert(offset);
+        *acked = (*acked).max(offset);
+        Ok(())
+    }
+
+    async fn prune(&self, retention: &EventRetention) -> Result<u64> {
+        let min_acked = self.offsets.read().values().min().copied();
+        let now = Utc::now();
+
+        let mut events = self.events.write();
+        let before = events.len();
+        events.retain(|&offset, event| {
+            let fully_acked = min_acked.map(|m| offset <= m).unwrap_or(false);
+            !retention.prunable(fully_acked, event.occurred_at, now)
+        });
+        Ok((before - events.len()) as u64)
+    }
+}
+
+/// Periodically prune the event log per `retention`
+pub fn spawn_pruning(
+    store: Arc<dyn EventStore>,
+    retention: EventRetention,
+    interval: std::time::Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match store.prune(&retention).await {
+                Ok(0) => {}
+                Ok(removed) => tracing::info!(removed, "Pruned event log"),
+                Err(e) => tracing::warn!(error = %e, "Event log pruning failed"),
+            }
+        }
+    })
+}
+
+/// Postgres event log in the `event_log` and `event_subscriptions` tables
+pub struct PgEventStore {
+    pool: PgPool,
+}
+
+impl PgEventStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl EventStore for PgEventStore {
+    async fn append(&self, event: &DomainEvent) -> Result<u64> {
+        let body = serde_json::to_value(event)
+            .map_err(|e| Error::serialization_with_source("Failed to serialize event", e))?;
+
+        // Appends are serialized so offsets commit in order; otherwise a
+        // reader could pass over an offset whose transaction commits later.
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::database_with_source("Failed to begin transaction", e))?;
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('event_log'))")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::database_with_source("Failed to lock event log", e))?;
+        let (offset,): (i64,) = sqlx::query_as(
+            r#"
+            INSERT INTO event_log (event_id, event_type, tenant_id, event, occurred_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING "offset"
+            "#,
+        )
+        .bind(event.id)
+        .bind(&event.event_type)
+        .bind(event.tenant_id)
+        .bind(body)
+        .bind(event.occurred_at)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::database_with_source("Failed to append event", e))?;
+        tx.commit()
+            .await
+            .map_err(|e| Error::database_with_source("Failed to commit event", e))?;
+
+        Ok(offset as u64)
+    }
+
+    async fn read_after(&self, after: u64, limit: usize) -> Result<Vec<StoredEvent>> {
+        let rows: Vec<(i64, serde_json::Value)> = sqlx::query_as(
+            r#"
+            SELECT "offset", event FROM event_log
+            WHERE "offset" > $1
+            ORDER BY "offset"
+            LIMIT $2
+            "#,
+        )
+        .bind(after as i64)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::database_with_source("Failed to read event log", e))?;
+
+        rows.into_iter()
+            .map(|(offset, body)| {
+                let event = serde_json::from_value(body)
+                    .map_err(|e| Error::deserialization_with_source("Invalid stored event", e))?;
+                Ok(StoredEvent {
+                    offset: offset as u64,
+                    event,
+                })
+            })
+            .collect()
+    }
+
+    async fn acked_offset(&self, subscriber: &str) -> Result<Option<u64>> {
+        let row: Option<(i64,)> =
+            sqlx::query_as("SELECT acked_offset FROM event_subscriptions WHERE name = $1")
+                .bind(subscriber)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| Error::database_with_source("Failed to load subscriber offset", e))?;
+        Ok(row.map(|(offset,)| offset as u64))
+    }
+
+    async fn ack(&self, subscriber: &str, offset: u64) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO event_subscriptions (name, acked_offset, updated_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (name) DO UPDATE
+            SET acked_offset = GREATEST(event_subscriptions.acked_offset, EXCLUDED.acked_offset),
+                updated_at = NOW()
+            "#,
+        )
+        .bind(subscriber)
+        .bind(offset as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::database_with_source("Failed to save subscriber offset", e))?;
+        Ok(())
+    }
+
+    async fn prune(&self, retention: &EventRetention) -> Result<u64> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            r#"
+            DELETE FROM event_log
+            WHERE occurred_at < $1
+               OR (occurred_at < $2
+                   AND "offset" <= (SELECT MIN(acked_offset) FROM event_subscriptions))
+            "#,
+        )
+        .bind(now - retention.max_age)
+        .bind(now - retention.min_age)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::database_with_source("Failed to prune event log", e))?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event_at(occurred_at: DateTime<Utc>) -> DomainEvent {
+        let mut event = DomainEvent::new("test.event", serde_json::json!({}));
+        event.occurred_at = occurred_at;
+        event
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_offsets() {
+        let store = MemoryEventStore::new();
+        for _ in 0..3 {
+            store.append(&event_at(Utc::now())).await.unwrap();
+        }
+
+        let events = store.read_after(1, 10).await.unwrap();
+        assert_eq!(events.iter().map(|e| e.offset).collect::<Vec<_>>(), [2, 3]);
+
+        store.ack("search", 2).await.unwrap();
+        store.ack("search", 1).await.unwrap();
+        assert_eq!(store.acked_offset("search").await.unwrap(), Some(2));
+        assert_eq!(store.acked_offset("webhooks").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_prune_keeps_unacked_events_until_max_age() {
+        let store = MemoryEventStore::new();
+        let now = Utc::now();
+        store
+            .append(&event_at(now - Duration::days(60)))
+            .await
+            .unwrap();
+        store
+            .append(&event_at(now - Duration::days(2)))
+            .await
+            .unwrap();
+        store
+            .append(&event_at(now - Duration::days(2)))
+            .await
+            .unwrap();
+        store.append(&event_at(now)).await.unwrap();
+
+        store.ack("search", 4).await.unwrap();
+        store.ack("webhooks", 2).await.unwrap();
+
+        // The 60-day-old event is past max_age; offset 2 is acked by both
+        // subscribers; offset 3 still waits on webhooks; offset 4 is too new
+        let removed = store.prune(&EventRetention::default()).await.unwrap();
+        assert_eq!(removed, 2);
+        let left = store.read_after(0, 10).await.unwrap();
+        assert_eq!(left.iter().map(|e| e.offset).collect::<Vec<_>>(), [3, 4]);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_pg_store_round_trip() {
+        use rustpress_database::migration::{create_initial_migrations, Migrator};
+
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+        let pool = PgPool::connect(&url).await.unwrap();
+        Migrator::new()
+            .with_migrations(create_initial_migrations())
+            .run(&pool)
+            .await
+            .unwrap();
+        let store = PgEventStore::new(pool);
+
+        let event = event_at(Utc::now()).with_metadata("k", serde_json::json!("v"));
+        let first = store.append(&event).await.unwrap();
+        let second = store.append(&event_at(Utc::now())).await.unwrap();
+        assert!(second > first);
+
+        let read = store.read_after(first - 1, 2).await.unwrap();
+        assert_eq!(read[0].offset, first);
+        assert_eq!(read[0].event.id, event.id);
+        assert_eq!(read[0].event.metadata.data["k"], "v");
+
+        let name = format!("test:{}", uuid::Uuid::now_v7());
+        store.ack(&name, second).await.unwrap();
+        store.ack(&name, first).await.unwrap();
+        assert_eq!(store.acked_offset(&name).await.unwrap(), Some(second));
+    }
+}
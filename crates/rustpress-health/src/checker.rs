@@ -1,13 +1,41 @@
 //! Health checker implementation
 
-use crate::status::{ComponentHealth, HealthReport, ServiceHealth};
+use crate::probes::{ProbeCheckResult, ProbeConfig, ProbeResult, ProbeScope, ProbeType};
+use crate::status::{ComponentHealth, ComponentStatus, HealthReport, ServiceHealth};
 use crate::system::SystemHealth;
 use chrono::Utc;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex as AsyncMutex, RwLock};
 use tracing::{debug, warn};
 
+type CheckFuture<'a> = Pin<Box<dyn Future<Output = ComponentHealth> + Send + 'a>>;
+
+/// A named check to run, as (name, critical, check)
+type ProbeCheck<'a> = (String, bool, CheckFuture<'a>);
+
+/// State of a check, locked while the check runs
+type CheckSlot = Arc<AsyncMutex<CheckState>>;
+
+/// Cached result and run history of a single check
+#[derive(Default)]
+struct CheckState {
+    cached: Option<CachedCheck>,
+    consecutive_failures: u32,
+    last_success: Option<chrono::DateTime<Utc>>,
+}
+
+/// Cached result of a single check
+#[derive(Clone)]
+struct CachedCheck {
+    health: ComponentHealth,
+    completed_at: Instant,
+}
+
 /// Health checker that performs health checks on various components
 pub struct HealthChecker {
     /// Database connection pool
@@ -36,6 +64,41 @@ pub struct HealthChecker {
 
     /// Check timeout
     timeout: Duration,
+
+    /// Probe check scopes and per-check timeout
+    probe_config: ProbeConfig,
+
+    /// Set once startup work (e.g. migrations) is done
+    startup: Option<StartupSignal>,
+
+    /// Per-check result cache
+    check_cache: Mutex<HashMap<String, CheckSlot>>,
+}
+
+/// Signals that application startup (e.g. migrations) has completed.
+///
+/// Clone it before handing it to the builder and call `mark_complete` once
+/// startup work is done; until then startup and readiness probes fail.
+#[derive(Clone, Debug, Default)]
+pub struct StartupSignal {
+    complete: Arc<AtomicBool>,
+}
+
+impl StartupSignal {
+    /// Create a signal in the not-started state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mark startup as complete
+    pub fn mark_complete(&self) {
+        self.complete.store(true, Ordering::Release);
+    }
+
+    /// Check if startup has completed
+    pub fn is_complete(&self) -> bool {
+        self.complete.load(Ordering::Acquire)
+    }
 }
 
 /// External service configuration
@@ -66,6 +129,11 @@ pub trait HealthCheck {
     fn is_critical(&self) -> bool {
         false
     }
+
+    /// Probes this check affects unless overridden in `ProbeConfig`
+    fn probe_scope(&self) -> ProbeScope {
+        ProbeScope::Readiness
+    }
 }
 
 impl HealthChecker {
@@ -74,8 +142,18 @@ impl HealthChecker {
         HealthCheckerBuilder::new()
     }
 
-    /// Perform all health checks
+    /// Perform all health checks, reusing cached results within each
+    /// check's cache TTL
     pub async fn check_all(&self) -> HealthReport {
+        self.build_report(false).await
+    }
+
+    /// Perform all health checks, ignoring cache TTLs
+    pub async fn refresh_all(&self) -> HealthReport {
+        self.build_report(true).await
+    }
+
+    async fn build_report(&self, force_refresh: bool) -> HealthReport {
         let start = Instant::now();
         let mut report = HealthReport::new();
 
@@ -92,28 +170,16 @@ impl HealthChecker {
             started_at: self.started_at,
         };
 
-        // Check database
-        if let Some(ref pool) = self.database {
-            let health = self.check_database(pool).await;
-            report.add_component("database", health);
-        }
-
-        // Check Redis
-        if let Some(ref redis) = self.redis {
-            let health = self.check_redis(redis).await;
-            report.add_component("cache", health);
-        }
-
-        // Check external services
-        for service in &self.external_services {
-            let health = self.check_external_service(service).await;
-            report.add_component(&service.name, health);
-        }
-
-        // Run custom checks
-        for check in &self.custom_checks {
-            let health = check.check().await;
-            report.add_component(check.name(), health);
+        // Run checks, reusing results still within their cache TTL
+        let checks = self
+            .checks_for(None)
+            .into_iter()
+            .map(|(name, _, check)| async move {
+                let health = self.cached_check(&name, check, force_refresh).await;
+                (name, health)
+            });
+        for (name, health) in futures::future::join_all(checks).await {
+            report.add_component(name, health);
         }
 
         // Add system metrics
@@ -129,6 +195,59 @@ impl HealthChecker {
         report
     }
 
+    /// Run a check through its cache. The slot stays locked while the check
+    /// runs, so concurrent callers wait for the in-flight run instead of
+    /// starting another.
+    async fn cached_check(
+        &self,
+        name: &str,
+        check: CheckFuture<'_>,
+        force_refresh: bool,
+    ) -> ComponentHealth {
+        let requested_at = Instant::now();
+        let slot = self.check_slot(name);
+        let mut state = slot.lock().await;
+
+        if let Some(entry) = state.cached.as_ref() {
+            // A run that finished after this request began is as fresh as a
+            // new one, even when forcing a refresh
+            let fresh = entry.completed_at >= requested_at
+                || (!force_refresh
+                    && entry.completed_at.elapsed() < self.probe_config.cache_ttl(name));
+            if fresh {
+                return entry.health.clone();
+            }
+        }
+
+        // Time only the check itself, not waiting for the slot
+        let start = Instant::now();
+        let mut health = check.await;
+        health.latency_ms = Some(start.elapsed().as_millis() as u64);
+
+        if matches!(
+            health.status,
+            ComponentStatus::Down | ComponentStatus::Unknown
+        ) {
+            state.consecutive_failures += 1;
+        } else {
+            state.consecutive_failures = 0;
+            state.last_success = Some(Utc::now());
+        }
+        health.consecutive_failures = state.consecutive_failures;
+        health.last_success = state.last_success;
+
+        state.cached = Some(CachedCheck {
+            health: health.clone(),
+            completed_at: Instant::now(),
+        });
+        health
+    }
+
+    fn check_slot(&self, name: &str) -> CheckSlot {
+        let mut slots = self.check_cache.lock().unwrap_or_else(|e| e.into_inner());
+        slots.entry(name.to_string()).or_default().clone()
+    }
+
     /// Check database health
     async fn check_database(&self, pool: &sqlx::PgPool) -> ComponentHealth {
         let start = Instant::now();
@@ -271,57 +390,143 @@ impl HealthChecker {
         }
     }
 
-    /// Quick liveness check (is the application alive?)
-    pub async fn check_liveness(&self) -> bool {
-        // Application is alive if we can respond
-        true
+    /// Run a probe. Checks scoped to the probe run concurrently, each
+    /// bounded by the per-check timeout; the probe fails if a critical one
+    /// is down. Readiness also fails until startup has completed.
+    pub async fn probe(&self, probe_type: ProbeType) -> ProbeResult {
+        let start = Instant::now();
+
+        if probe_type != ProbeType::Liveness && !self.is_started() {
+            return ProbeResult::failure(
+                probe_type,
+                "Application still starting",
+                start.elapsed().as_millis() as u64,
+            );
+        }
+
+        let checks = self.run_probe_checks(probe_type).await;
+        let failing: Vec<&str> = checks
+            .iter()
+            .filter(|c| c.is_failing())
+            .map(|c| c.name.as_str())
+            .collect();
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        let result = if failing.is_empty() {
+            ProbeResult::success(probe_type, duration_ms)
+        } else {
+            debug!("{:?} probe failed: {}", probe_type, failing.join(", "));
+            ProbeResult::failure(
+                probe_type,
+                format!("Unavailable: {}", failing.join(", ")),
+                duration_ms,
+            )
+        };
+        result.with_checks(checks)
     }
 
-    /// Readiness check (is the application ready to accept traffic?)
-    pub async fn check_readiness(&self) -> bool {
-        // Check database connection
-        if let Some(ref pool) = self.database {
-            let result = tokio::time::timeout(Duration::from_secs(5), async {
-                sqlx::query("SELECT 1").execute(pool).await
-            })
-            .await;
+    /// Check if startup has completed
+    pub fn is_started(&self) -> bool {
+        match &self.startup {
+            Some(signal) => signal.is_complete(),
+            None => true,
+        }
+    }
+
+    fn scope(&self, name: &str, default: ProbeScope) -> ProbeScope {
+        self.probe_config
+            .scopes
+            .get(name)
+            .copied()
+            .unwrap_or(default)
+    }
+
+    /// Registered checks, limited to those affecting `probe_type` if given
+    fn checks_for(&self, probe_type: Option<ProbeType>) -> Vec<ProbeCheck<'_>> {
+        let included = |name: &str, default: ProbeScope| match probe_type {
+            Some(probe_type) => self.scope(name, default).affects(probe_type),
+            None => true,
+        };
+        let mut checks: Vec<ProbeCheck<'_>> = Vec::new();
 
-            if result.is_err() || result.unwrap().is_err() {
-                debug!("Readiness check failed: database unavailable");
-                return false;
+        if let Some(ref pool) = self.database {
+            if included("database", ProbeScope::Readiness) {
+                checks.push((
+                    "database".to_string(),
+                    true,
+                    Box::pin(self.check_database(pool)),
+                ));
             }
         }
 
-        // Check Redis connection
         if let Some(ref redis) = self.redis {
-            let mut conn = redis.clone();
-            let result = tokio::time::timeout(Duration::from_secs(3), async {
-                redis::cmd("PING").query_async::<_, String>(&mut conn).await
-            })
-            .await;
+            if included("cache", ProbeScope::Readiness) {
+                checks.push(("cache".to_string(), true, Box::pin(self.check_redis(redis))));
+            }
+        }
+
+        for service in &self.external_services {
+            if included(&service.name, ProbeScope::Readiness) {
+                checks.push((
+                    service.name.clone(),
+                    service.critical,
+                    Box::pin(self.check_external_service(service)),
+                ));
+            }
+        }
 
-            if result.is_err() || result.unwrap().is_err() {
-                debug!("Readiness check failed: cache unavailable");
-                return false;
+        for check in &self.custom_checks {
+            if included(check.name(), check.probe_scope()) {
+                checks.push((check.name().to_string(), check.is_critical(), check.check()));
             }
         }
 
-        true
+        checks
     }
 
-    /// Startup check (has the application finished starting?)
-    pub async fn check_startup(&self) -> bool {
-        // Check if we've been running for at least a few seconds
-        let uptime = Utc::now()
-            .signed_duration_since(self.started_at)
-            .num_seconds();
+    async fn run_probe_checks(&self, probe_type: ProbeType) -> Vec<ProbeCheckResult> {
+        let timeout = self.probe_config.check_timeout;
+
+        let checks = self.checks_for(Some(probe_type)).into_iter().map(
+            |(name, critical, check)| async move {
+                let start = Instant::now();
+                let health = match tokio::time::timeout(timeout, check).await {
+                    Ok(health) => health,
+                    Err(_) => {
+                        warn!("{} health check timed out", name);
+                        ComponentHealth::unhealthy(format!(
+                            "Check timed out after {}ms",
+                            timeout.as_millis()
+                        ))
+                    }
+                };
+
+                ProbeCheckResult {
+                    name,
+                    status: health.status,
+                    critical,
+                    message: health.error,
+                    duration_ms: start.elapsed().as_millis() as u64,
+                }
+            },
+        );
 
-        if uptime < 2 {
-            return false;
-        }
+        futures::future::join_all(checks).await
+    }
 
-        // Verify critical services
-        self.check_readiness().await
+    /// Quick liveness check (is the application alive?)
+    pub async fn check_liveness(&self) -> bool {
+        self.probe(ProbeType::Liveness).await.success
+    }
+
+    /// Readiness check (is the application ready to accept traffic?)
+    pub async fn check_readiness(&self) -> bool {
+        self.probe(ProbeType::Readiness).await.success
+    }
+
+    /// Startup check (has the application finished starting?)
+    pub async fn check_startup(&self) -> bool {
+        self.is_started()
     }
 }
 
@@ -332,6 +537,8 @@ pub struct HealthCheckerBuilder {
     external_services: Vec<ExternalService>,
     custom_checks: Vec<Box<dyn HealthCheck + Send + Sync>>,
     timeout: Duration,
+    probe_config: ProbeConfig,
+    startup: Option<StartupSignal>,
 }
 
 impl HealthCheckerBuilder {
@@ -343,6 +550,8 @@ impl HealthCheckerBuilder {
             external_services: Vec::new(),
             custom_checks: Vec::new(),
             timeout: Duration::from_secs(10),
+            probe_config: ProbeConfig::readiness(),
+            startup: None,
         }
     }
 
@@ -376,6 +585,18 @@ impl HealthCheckerBuilder {
         self
     }
 
+    /// Set probe check scopes and per-check timeout
+    pub fn with_probe_config(mut self, config: ProbeConfig) -> Self {
+        self.probe_config = config;
+        self
+    }
+
+    /// Hold startup and readiness probes until `signal` is marked complete
+    pub fn with_startup_signal(mut self, signal: StartupSignal) -> Self {
+        self.startup = Some(signal);
+        self
+    }
+
     /// Build the health checker
     pub fn build(self) -> HealthChecker {
         HealthChecker {
@@ -388,6 +609,9 @@ impl HealthCheckerBuilder {
             last_redis_success: Arc::new(RwLock::new(None)),
             system_health: SystemHealth::new(),
             timeout: self.timeout,
+            probe_config: self.probe_config,
+            startup: self.startup,
+            check_cache: Mutex::new(HashMap::new()),
         }
     }
 }
@@ -428,4 +652,224 @@ mod tests {
         assert_eq!(service.name, "test");
         assert!(!service.critical);
     }
+
+    struct StubCheck {
+        name: &'static str,
+        healthy: bool,
+        delay: Duration,
+        scope: ProbeScope,
+    }
+
+    #[async_trait::async_trait]
+    impl HealthCheck for StubCheck {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn check(&self) -> ComponentHealth {
+            tokio::time::sleep(self.delay).await;
+            if self.healthy {
+                ComponentHealth::healthy()
+            } else {
+                ComponentHealth::unhealthy("down")
+            }
+        }
+
+        fn is_critical(&self) -> bool {
+            true
+        }
+
+        fn probe_scope(&self) -> ProbeScope {
+            self.scope
+        }
+    }
+
+    fn stub(name: &'static str, healthy: bool, scope: ProbeScope) -> StubCheck {
+        StubCheck {
+            name,
+            healthy,
+            delay: Duration::ZERO,
+            scope,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_readiness_fails_while_liveness_passes() {
+        let checker = HealthChecker::builder()
+            .with_custom_check(stub("database", false, ProbeScope::Readiness))
+            .with_custom_check(stub("worker", true, ProbeScope::Liveness))
+            .build();
+
+        let live = checker.probe(ProbeType::Liveness).await;
+        assert!(live.success);
+        assert_eq!(live.checks.len(), 1);
+        assert_eq!(live.checks[0].name, "worker");
+
+        let ready = checker.probe(ProbeType::Readiness).await;
+        assert!(!ready.success);
+        assert_eq!(ready.message.as_deref(), Some("Unavailable: database"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_config_overrides_scope() {
+        let checker = HealthChecker::builder()
+            .with_custom_check(stub("queue", false, ProbeScope::Readiness))
+            .with_probe_config(ProbeConfig::readiness().with_scope("queue", ProbeScope::Both))
+            .build();
+
+        assert!(!checker.check_liveness().await);
+        assert!(!checker.check_readiness().await);
+    }
+
+    #[tokio::test]
+    async fn test_slow_check_times_out_individually() {
+        let slow = StubCheck {
+            delay: Duration::from_secs(30),
+            ..stub("search", true, ProbeScope::Readiness)
+        };
+        let checker = HealthChecker::builder()
+            .with_custom_check(slow)
+            .with_custom_check(stub("database", true, ProbeScope::Readiness))
+            .with_probe_config(
+                ProbeConfig::readiness().with_check_timeout(Duration::from_millis(50)),
+            )
+            .build();
+
+        let start = Instant::now();
+        let ready = checker.probe(ProbeType::Readiness).await;
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(!ready.success);
+
+        let search = ready.checks.iter().find(|c| c.name == "search").unwrap();
+        assert!(search.message.as_deref().unwrap().contains("timed out"));
+        let database = ready.checks.iter().find(|c| c.name == "database").unwrap();
+        assert!(!database.is_failing());
+    }
+
+    struct CountingCheck {
+        name: &'static str,
+        runs: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl HealthCheck for CountingCheck {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn check(&self) -> ComponentHealth {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            ComponentHealth::healthy()
+        }
+    }
+
+    fn counting(name: &'static str) -> (CountingCheck, Arc<std::sync::atomic::AtomicUsize>) {
+        let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        (
+            CountingCheck {
+                name,
+                runs: runs.clone(),
+            },
+            runs,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_per_check_cache_ttl() {
+        let (expensive, expensive_runs) = counting("search_api");
+        let (cheap, cheap_runs) = counting("ping");
+        let checker = HealthChecker::builder()
+            .with_custom_check(expensive)
+            .with_custom_check(cheap)
+            .with_probe_config(ProbeConfig::readiness().with_cache_ttl("search_api", 60))
+            .build();
+
+        checker.check_all().await;
+        let report = checker.check_all().await;
+        assert_eq!(report.components.len(), 2);
+        assert_eq!(expensive_runs.load(Ordering::SeqCst), 1);
+        assert_eq!(cheap_runs.load(Ordering::SeqCst), 2);
+
+        // Forced refresh bypasses the TTL
+        checker.refresh_all().await;
+        assert_eq!(expensive_runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_check_is_not_started_twice() {
+        let (check, runs) = counting("slow");
+        let checker = HealthChecker::builder().with_custom_check(check).build();
+
+        tokio::join!(
+            checker.check_all(),
+            checker.check_all(),
+            checker.refresh_all()
+        );
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    struct FlappingCheck {
+        healthy: Arc<AtomicBool>,
+    }
+
+    #[async_trait::async_trait]
+    impl HealthCheck for FlappingCheck {
+        fn name(&self) -> &str {
+            "payments"
+        }
+
+        async fn check(&self) -> ComponentHealth {
+            tokio::time::sleep(Duration::from_millis(15)).await;
+            if self.healthy.load(Ordering::SeqCst) {
+                ComponentHealth::healthy()
+            } else {
+                ComponentHealth::unhealthy("down")
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_latency_and_consecutive_failures() {
+        let healthy = Arc::new(AtomicBool::new(true));
+        let checker = HealthChecker::builder()
+            .with_custom_check(FlappingCheck {
+                healthy: healthy.clone(),
+            })
+            .build();
+
+        let report = checker.refresh_all().await;
+        let payments = &report.components["payments"];
+        assert!(payments.latency_ms.unwrap() >= 15);
+        assert_eq!(payments.consecutive_failures, 0);
+        let last_success = payments.last_success;
+        assert!(last_success.is_some());
+
+        healthy.store(false, Ordering::SeqCst);
+        checker.refresh_all().await;
+        let report = checker.refresh_all().await;
+        let payments = &report.components["payments"];
+        assert_eq!(payments.consecutive_failures, 2);
+        assert_eq!(payments.last_success, last_success);
+
+        healthy.store(true, Ordering::SeqCst);
+        let report = checker.refresh_all().await;
+        assert_eq!(report.components["payments"].consecutive_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_startup_signal_gates_startup_and_readiness() {
+        let signal = StartupSignal::new();
+        let checker = HealthChecker::builder()
+            .with_startup_signal(signal.clone())
+            .build();
+
+        assert!(!checker.check_startup().await);
+        assert!(!checker.check_readiness().await);
+        assert!(checker.check_liveness().await);
+
+        signal.mark_complete();
+        assert!(checker.check_startup().await);
+        assert!(checker.check_readiness().await);
+    }
 }
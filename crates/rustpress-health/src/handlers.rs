@@ -1,14 +1,13 @@
 //! HTTP handlers for health check endpoints
 
 use crate::probes::{ProbeResult, ProbeType};
-use crate::status::{HealthStatus, KubernetesProbeResponse};
+use crate::status::{ComponentChecksResponse, HealthStatus, KubernetesProbeResponse};
 use crate::HealthState;
 use axum::extract::State;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 use std::sync::Arc;
-use std::time::Instant;
 
 /// Health check response wrapper
 pub struct HealthResponse {
@@ -39,73 +38,35 @@ pub async fn health_handler(State(state): State<Arc<HealthState>>) -> HealthResp
 
 /// Liveness probe handler
 /// GET /health/live
+///
+/// Only checks scoped to liveness are run, so a dependency outage
+/// doesn't get the container restarted.
 pub async fn liveness_handler(State(state): State<Arc<HealthState>>) -> HealthResponse {
-    let start = Instant::now();
-    let alive = state.checker.check_liveness().await;
-    let duration_ms = start.elapsed().as_millis() as u64;
-
-    if alive {
-        let result = ProbeResult::success(ProbeType::Liveness, duration_ms);
-        HealthResponse {
-            status: StatusCode::OK,
-            body: serde_json::to_value(&result).unwrap_or_default(),
-        }
-    } else {
-        let result =
-            ProbeResult::failure(ProbeType::Liveness, "Application not alive", duration_ms);
-        HealthResponse {
-            status: StatusCode::SERVICE_UNAVAILABLE,
-            body: serde_json::to_value(&result).unwrap_or_default(),
-        }
-    }
+    probe_response(state.checker.probe(ProbeType::Liveness).await)
 }
 
 /// Readiness probe handler
 /// GET /health/ready
 pub async fn readiness_handler(State(state): State<Arc<HealthState>>) -> HealthResponse {
-    let start = Instant::now();
-    let ready = state.checker.check_readiness().await;
-    let duration_ms = start.elapsed().as_millis() as u64;
-
-    if ready {
-        let result = ProbeResult::success(ProbeType::Readiness, duration_ms);
-        HealthResponse {
-            status: StatusCode::OK,
-            body: serde_json::to_value(&result).unwrap_or_default(),
-        }
-    } else {
-        let result =
-            ProbeResult::failure(ProbeType::Readiness, "Application not ready", duration_ms);
-        HealthResponse {
-            status: StatusCode::SERVICE_UNAVAILABLE,
-            body: serde_json::to_value(&result).unwrap_or_default(),
-        }
-    }
+    probe_response(state.checker.probe(ProbeType::Readiness).await)
 }
 
 /// Startup probe handler
 /// GET /health/startup
 pub async fn startup_handler(State(state): State<Arc<HealthState>>) -> HealthResponse {
-    let start = Instant::now();
-    let started = state.checker.check_startup().await;
-    let duration_ms = start.elapsed().as_millis() as u64;
+    probe_response(state.checker.probe(ProbeType::Startup).await)
+}
 
-    if started {
-        let result = ProbeResult::success(ProbeType::Startup, duration_ms);
-        HealthResponse {
-            status: StatusCode::OK,
-            body: serde_json::to_value(&result).unwrap_or_default(),
-        }
+fn probe_response(result: ProbeResult) -> HealthResponse {
+    let status = if result.success {
+        StatusCode::OK
     } else {
-        let result = ProbeResult::failure(
-            ProbeType::Startup,
-            "Application still starting",
-            duration_ms,
-        );
-        HealthResponse {
-            status: StatusCode::SERVICE_UNAVAILABLE,
-            body: serde_json::to_value(&result).unwrap_or_default(),
-        }
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    HealthResponse {
+        status,
+        body: serde_json::to_value(&result).unwrap_or_default(),
     }
 }
 
@@ -145,6 +106,22 @@ pub async fn detailed_health_handler(State(state): State<Arc<HealthState>>) -> H
     }
 }
 
+/// Per-component status, latency and failure streaks. Served from the
+/// health cache so it is cheap to poll.
+/// GET /health/checks
+pub async fn checks_handler(State(state): State<Arc<HealthState>>) -> HealthResponse {
+    let report = state.get_health(false).await;
+    let status = match report.status {
+        HealthStatus::Healthy | HealthStatus::Degraded => StatusCode::OK,
+        HealthStatus::Unhealthy => StatusCode::SERVICE_UNAVAILABLE,
+    };
+
+    HealthResponse {
+        status,
+        body: serde_json::to_value(ComponentChecksResponse::from(&report)).unwrap_or_default(),
+    }
+}
+
 /// Simple status endpoint (for load balancers)
 /// GET /status
 pub async fn status_handler() -> StatusCode {
@@ -223,4 +200,52 @@ mod tests {
 
         assert_eq!(response.status, StatusCode::OK);
     }
+
+    struct DatabaseDown;
+
+    #[async_trait::async_trait]
+    impl crate::HealthCheck for DatabaseDown {
+        fn name(&self) -> &str {
+            "database"
+        }
+
+        async fn check(&self) -> crate::ComponentHealth {
+            crate::ComponentHealth::unhealthy("connection refused")
+        }
+
+        fn is_critical(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_database_down_fails_readiness_only() {
+        let checker = crate::HealthChecker::builder()
+            .with_custom_check(DatabaseDown)
+            .build();
+        let state = Arc::new(HealthState::new(checker, crate::HealthConfig::default()));
+
+        let live = liveness_handler(State(state.clone())).await;
+        assert_eq!(live.status, StatusCode::OK);
+
+        let ready = readiness_handler(State(state)).await;
+        assert_eq!(ready.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(ready.body["checks"][0]["name"], "database");
+    }
+
+    #[tokio::test]
+    async fn test_checks_endpoint_reports_failures() {
+        let checker = crate::HealthChecker::builder()
+            .with_custom_check(DatabaseDown)
+            .build();
+        let state = Arc::new(HealthState::new(checker, crate::HealthConfig::default()));
+
+        let response = checks_handler(State(state)).await;
+        assert_eq!(response.status, StatusCode::SERVICE_UNAVAILABLE);
+        let check = &response.body["checks"][0];
+        assert_eq!(check["name"], "database");
+        assert_eq!(check["status"], "down");
+        assert_eq!(check["consecutive_failures"], 1);
+        assert!(check["latency_ms"].is_u64());
+    }
 }
@@ -31,13 +31,17 @@ mod router;
 mod status;
 mod system;
 
-pub use checker::{ExternalService, HealthCheck, HealthChecker, HealthCheckerBuilder};
+pub use checker::{
+    ExternalService, HealthCheck, HealthChecker, HealthCheckerBuilder, StartupSignal,
+};
 pub use handlers::*;
-pub use probes::{ProbeConfig, ProbeResult, ProbeType, ProbeYamlGenerator};
+pub use probes::{
+    ProbeCheckResult, ProbeConfig, ProbeResult, ProbeScope, ProbeType, ProbeYamlGenerator,
+};
 pub use router::HealthRouter;
 pub use status::{
-    ComponentHealth, ComponentStatus, HealthReport, HealthStatus, KubernetesProbeResponse,
-    ServiceHealth, SystemMetrics,
+    ComponentCheck, ComponentChecksResponse, ComponentHealth, ComponentStatus, HealthReport,
+    HealthStatus, KubernetesProbeResponse, ServiceHealth, SystemMetrics,
 };
 pub use system::{DetailedSystemInfo, SystemHealth};
 
@@ -123,8 +127,12 @@ impl HealthState {
             }
         }
 
-        // Perform health check
-        let report = self.checker.check_all().await;
+        // Perform health check; a forced refresh also bypasses per-check TTLs
+        let report = if force_refresh {
+            self.checker.refresh_all().await
+        } else {
+            self.checker.check_all().await
+        };
 
         // Update cache
         let mut cache = self.cached_status.write().await;
@@ -1,6 +1,8 @@
 //! Kubernetes probe types and configuration
 
+use crate::status::ComponentStatus;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::Duration;
 
 /// Probe type
@@ -15,6 +17,57 @@ pub enum ProbeType {
     Startup,
 }
 
+/// Which probes a health check affects
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProbeScope {
+    /// Failing restarts the container
+    Liveness,
+    /// Failing stops traffic to the container
+    #[default]
+    Readiness,
+    /// Affects both probes
+    Both,
+}
+
+impl ProbeScope {
+    /// Check if this scope affects a probe
+    pub fn affects(&self, probe_type: ProbeType) -> bool {
+        match probe_type {
+            ProbeType::Liveness => matches!(self, ProbeScope::Liveness | ProbeScope::Both),
+            ProbeType::Readiness => matches!(self, ProbeScope::Readiness | ProbeScope::Both),
+            ProbeType::Startup => false,
+        }
+    }
+}
+
+/// Result of a single check run for a probe
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProbeCheckResult {
+    /// Check name
+    pub name: String,
+    /// Component status
+    pub status: ComponentStatus,
+    /// Whether a failure fails the probe
+    pub critical: bool,
+    /// Error or timeout message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Check duration in milliseconds
+    pub duration_ms: u64,
+}
+
+impl ProbeCheckResult {
+    /// Check if this result fails its probe
+    pub fn is_failing(&self) -> bool {
+        self.critical
+            && matches!(
+                self.status,
+                ComponentStatus::Down | ComponentStatus::Unknown
+            )
+    }
+}
+
 /// Probe result
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ProbeResult {
@@ -27,6 +80,9 @@ pub struct ProbeResult {
     pub message: Option<String>,
     /// Check duration in milliseconds
     pub duration_ms: u64,
+    /// Individual checks run for this probe
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checks: Vec<ProbeCheckResult>,
 }
 
 impl ProbeResult {
@@ -37,6 +93,7 @@ impl ProbeResult {
             success: true,
             message: None,
             duration_ms,
+            checks: Vec::new(),
         }
     }
 
@@ -47,8 +104,15 @@ impl ProbeResult {
             success: false,
             message: Some(message.into()),
             duration_ms,
+            checks: Vec::new(),
         }
     }
+
+    /// Attach the checks run for this probe
+    pub fn with_checks(mut self, checks: Vec<ProbeCheckResult>) -> Self {
+        self.checks = checks;
+        self
+    }
 }
 
 /// Probe configuration
@@ -64,6 +128,23 @@ pub struct ProbeConfig {
     pub success_threshold: u32,
     /// Number of consecutive failures before considered unhealthy
     pub failure_threshold: u32,
+    /// Timeout for each individual check. Checks run concurrently, so
+    /// keeping this below `timeout` keeps one slow dependency from making
+    /// the whole probe exceed the kubelet timeout.
+    #[serde(default = "default_check_timeout")]
+    pub check_timeout: Duration,
+    /// Probes affected by each check, by check name. Unlisted checks
+    /// use the check's own default (readiness for built-in checks).
+    #[serde(default)]
+    pub scopes: HashMap<String, ProbeScope>,
+    /// How long each check's result is reused, by check name. Unlisted
+    /// checks run on every health report.
+    #[serde(default)]
+    pub cache_ttl_secs: HashMap<String, u64>,
+}
+
+fn default_check_timeout() -> Duration {
+    Duration::from_secs(2)
 }
 
 impl Default for ProbeConfig {
@@ -74,6 +155,9 @@ impl Default for ProbeConfig {
             timeout: Duration::from_secs(3),
             success_threshold: 1,
             failure_threshold: 3,
+            check_timeout: default_check_timeout(),
+            scopes: HashMap::new(),
+            cache_ttl_secs: HashMap::new(),
         }
     }
 }
@@ -87,6 +171,9 @@ impl ProbeConfig {
             timeout: Duration::from_secs(3),
             success_threshold: 1,
             failure_threshold: 3,
+            check_timeout: default_check_timeout(),
+            scopes: HashMap::new(),
+            cache_ttl_secs: HashMap::new(),
         }
     }
 
@@ -98,6 +185,9 @@ impl ProbeConfig {
             timeout: Duration::from_secs(3),
             success_threshold: 1,
             failure_threshold: 3,
+            check_timeout: default_check_timeout(),
+            scopes: HashMap::new(),
+            cache_ttl_secs: HashMap::new(),
         }
     }
 
@@ -109,8 +199,34 @@ impl ProbeConfig {
             timeout: Duration::from_secs(3),
             success_threshold: 1,
             failure_threshold: 30, // Allow up to 150 seconds for startup
+            check_timeout: default_check_timeout(),
+            scopes: HashMap::new(),
+            cache_ttl_secs: HashMap::new(),
         }
     }
+
+    /// Set which probes a check affects
+    pub fn with_scope(mut self, check: impl Into<String>, scope: ProbeScope) -> Self {
+        self.scopes.insert(check.into(), scope);
+        self
+    }
+
+    /// Set how long a check's result is cached
+    pub fn with_cache_ttl(mut self, check: impl Into<String>, secs: u64) -> Self {
+        self.cache_ttl_secs.insert(check.into(), secs);
+        self
+    }
+
+    /// Cache TTL for a check
+    pub fn cache_ttl(&self, check: &str) -> Duration {
+        Duration::from_secs(self.cache_ttl_secs.get(check).copied().unwrap_or(0))
+    }
+
+    /// Set the per-check timeout
+    pub fn with_check_timeout(mut self, timeout: Duration) -> Self {
+        self.check_timeout = timeout;
+        self
+    }
 }
 
 /// Kubernetes probe YAML generator
@@ -213,6 +329,18 @@ mod tests {
         assert_eq!(startup.failure_threshold, 30);
     }
 
+    #[test]
+    fn test_probe_scope() {
+        assert!(ProbeScope::Readiness.affects(ProbeType::Readiness));
+        assert!(!ProbeScope::Readiness.affects(ProbeType::Liveness));
+        assert!(ProbeScope::Both.affects(ProbeType::Liveness));
+        assert!(!ProbeScope::Both.affects(ProbeType::Startup));
+
+        let config = ProbeConfig::readiness().with_scope("worker", ProbeScope::Liveness);
+        assert_eq!(config.scopes.get("worker"), Some(&ProbeScope::Liveness));
+        assert!(config.check_timeout < config.timeout);
+    }
+
     #[test]
     fn test_yaml_generation() {
         let config = ProbeConfig::liveness();
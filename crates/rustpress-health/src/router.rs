@@ -31,6 +31,8 @@ impl HealthRouter {
             .route("/health/startup", get(startup_handler))
             // Detailed health check
             .route("/health/detailed", get(detailed_health_handler))
+            // Per-component latency and failure streaks
+            .route("/health/checks", get(checks_handler))
             // Database health
             .route("/health/db", get(database_health_handler))
             // Cache health
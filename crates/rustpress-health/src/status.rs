@@ -218,6 +218,14 @@ pub struct ComponentHealth {
     /// Last successful check
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_success: Option<DateTime<Utc>>,
+
+    /// Measured duration of the check run in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+
+    /// Failed runs in a row, reset by a successful run
+    #[serde(default)]
+    pub consecutive_failures: u32,
 }
 
 impl ComponentHealth {
@@ -230,6 +238,8 @@ impl ComponentHealth {
             details: None,
             error: None,
             last_success: Some(Utc::now()),
+            latency_ms: None,
+            consecutive_failures: 0,
         }
     }
 
@@ -242,6 +252,8 @@ impl ComponentHealth {
             details: None,
             error: Some(error.into()),
             last_success: None,
+            latency_ms: None,
+            consecutive_failures: 0,
         }
     }
 
@@ -254,6 +266,8 @@ impl ComponentHealth {
             details: None,
             error: Some(reason.into()),
             last_success: Some(Utc::now()),
+            latency_ms: None,
+            consecutive_failures: 0,
         }
     }
 
@@ -279,6 +293,75 @@ impl ComponentHealth {
     }
 }
 
+/// Per-component entry of the checks endpoint
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ComponentCheck {
+    /// Component name
+    pub name: String,
+
+    /// Component status
+    pub status: ComponentStatus,
+
+    /// Measured check duration in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+
+    /// Last successful check
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<DateTime<Utc>>,
+
+    /// Failed runs in a row
+    pub consecutive_failures: u32,
+
+    /// Error message if unhealthy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ComponentCheck {
+    /// Build from a named component
+    pub fn from_component(name: impl Into<String>, health: &ComponentHealth) -> Self {
+        Self {
+            name: name.into(),
+            status: health.status,
+            latency_ms: health.latency_ms,
+            last_success: health.last_success,
+            consecutive_failures: health.consecutive_failures,
+            error: health.error.clone(),
+        }
+    }
+}
+
+/// Response of the checks endpoint
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ComponentChecksResponse {
+    /// Overall status
+    pub status: HealthStatus,
+
+    /// When the checks were run
+    pub timestamp: DateTime<Utc>,
+
+    /// Components, sorted by name
+    pub checks: Vec<ComponentCheck>,
+}
+
+impl From<&HealthReport> for ComponentChecksResponse {
+    fn from(report: &HealthReport) -> Self {
+        let mut checks: Vec<ComponentCheck> = report
+            .components
+            .iter()
+            .map(|(name, health)| ComponentCheck::from_component(name, health))
+            .collect();
+        checks.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Self {
+            status: report.status,
+            timestamp: report.timestamp,
+            checks,
+        }
+    }
+}
+
 /// System metrics
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SystemMetrics {
@@ -0,0 +1,320 @@
+//! Outbound announcements when content is first published.
+//!
+//! [`announcement_subscriber`] listens for `post.published` events and, for
+//! posts moving into the published status, queues one job per configured
+//! target: a webhook POST or a social post. The subscriber only enqueues, so
+//! a slow or failing target never holds up the publish itself; delivery and
+//! retries are left to the job queue.
+
+use async_trait::async_trait;
+use rustpress_core::correlation::{current_request_id, REQUEST_ID_HEADER};
+use rustpress_core::error::{Error, Result};
+use rustpress_events::event::events;
+use rustpress_events::subscriber::SubscriberConfig;
+use rustpress_events::{EventType, PostPublished, Subscriber};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::job::{Job, JobHandler, JobPayload};
+use crate::queue::JobQueue;
+
+/// Announcement settings, usually the `[announcements]` config section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnnouncementConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Public base URL used to build post links, e.g. `https://example.com`
+    #[serde(default)]
+    pub site_url: String,
+    /// Targets per post type; post types not listed are never announced
+    #[serde(default)]
+    pub post_types: HashMap<String, PostTypeAnnouncements>,
+}
+
+/// Announcement targets for one post type
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PostTypeAnnouncements {
+    /// Path between the site URL and the slug, e.g. `/blog`
+    #[serde(default)]
+    pub path_prefix: String,
+    /// URLs to POST the announcement to
+    #[serde(default)]
+    pub webhooks: Vec<String>,
+    /// Social channels to queue a post for, handled by whichever
+    /// integration registers an [`AnnounceSocialJob`] handler
+    #[serde(default)]
+    pub social_channels: Vec<String>,
+}
+
+/// What gets announced
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Announcement {
+    pub post_id: Uuid,
+    pub post_type: String,
+    pub title: String,
+    pub url: String,
+    pub excerpt: Option<String>,
+}
+
+impl AnnouncementConfig {
+    /// Jobs announcing `post`, empty unless this publish is the post's
+    /// transition into the published status and its type is configured
+    pub fn jobs_for(&self, post: &PostPublished) -> Vec<Job> {
+        if !self.enabled || !post.is_first_publication() {
+            return Vec::new();
+        }
+        let Some(targets) = self.post_types.get(&post.post_type) else {
+            return Vec::new();
+        };
+
+        let base = self.site_url.trim_end_matches('/');
+        let prefix = targets.path_prefix.trim_matches('/');
+        let url = if prefix.is_empty() {
+            format!("{}/{}", base, post.slug)
+        } else {
+            format!("{}/{}/{}", base, prefix, post.slug)
+        };
+        let announcement = Announcement {
+            post_id: post.post_id,
+            post_type: post.post_type.clone(),
+            title: post.title.clone(),
+            url,
+            excerpt: post.excerpt.clone(),
+        };
+
+        let webhooks = targets.webhooks.iter().map(|url| {
+            Job::new(AnnounceWebhookJob {
+                url: url.clone(),
+                announcement: announcement.clone(),
+            })
+            .with_dedup_key(format!("announce:{}:webhook:{}", post.post_id, url))
+        });
+        let social = targets.social_channels.iter().map(|channel| {
+            Job::new(AnnounceSocialJob {
+                channel: channel.clone(),
+                announcement: announcement.clone(),
+            })
+            .with_dedup_key(format!("announce:{}:social:{}", post.post_id, channel))
+        });
+        webhooks.chain(social).collect()
+    }
+}
+
+/// Subscriber queueing announcements for newly published posts
+///
+/// Runs asynchronously so publishing never waits on it.
+pub fn announcement_subscriber(config: AnnouncementConfig, queue: Arc<JobQueue>) -> Subscriber {
+    let config = Arc::new(config);
+    Subscriber::new(
+        "announcements",
+        SubscriberConfig::new(vec![EventType::new(events::POST_PUBLISHED)]).async_handler(),
+        move |event| {
+            let config = config.clone();
+            let queue = queue.clone();
+            async move {
+                let Some(post) = PostPublished::from_event(&event) else {
+                    return Ok(());
+                };
+                for job in config.jobs_for(&post) {
+                    let (_, queued) = queue.push_unique(job).await?;
+                    if queued {
+                        info!(post_id = %post.post_id, "Queued publish announcement");
+                    }
+                }
+                Ok(())
+            }
+        },
+    )
+}
+
+/// POST an announcement to a webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnounceWebhookJob {
+    pub url: String,
+    pub announcement: Announcement,
+}
+
+impl JobPayload for AnnounceWebhookJob {
+    fn job_type() -> &'static str {
+        "announce_webhook"
+    }
+
+    fn queue() -> &'static str {
+        "webhooks"
+    }
+
+    fn max_attempts() -> u32 {
+        5
+    }
+
+    fn timeout_secs() -> u64 {
+        30
+    }
+}
+
+/// Post an announcement to a social channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnounceSocialJob {
+    pub channel: String,
+    pub announcement: Announcement,
+}
+
+impl JobPayload for AnnounceSocialJob {
+    fn job_type() -> &'static str {
+        "announce_social"
+    }
+
+    fn queue() -> &'static str {
+        "social"
+    }
+
+    fn max_attempts() -> u32 {
+        5
+    }
+}
+
+/// Delivers [`AnnounceWebhookJob`]s; non-2xx responses are retried
+pub struct AnnounceWebhookHandler {
+    client: reqwest::Client,
+}
+
+impl AnnounceWebhookHandler {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Default for AnnounceWebhookHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl JobHandler for AnnounceWebhookHandler {
+    type Payload = AnnounceWebhookJob;
+
+    async fn handle(&self, payload: Self::Payload) -> Result<()> {
+        let mut request = self.client.post(&payload.url).json(&serde_json::json!({
+            "event": events::POST_PUBLISHED,
+            "post": payload.announcement,
+        }));
+        // The worker runs the job under the ID of the request that queued it
+        if let Some(request_id) = current_request_id() {
+            request = request.header(REQUEST_ID_HEADER, request_id);
+        }
+
+        let response = request.send().await.map_err(|e| Error::Network {
+            message: format!("Announcement webhook {} failed", payload.url),
+            source: Some(Box::new(e)),
+        })?;
+
+        if !response.status().is_success() {
+            return Err(Error::Network {
+                message: format!(
+                    "Announcement webhook {} returned {}",
+                    payload.url,
+                    response.status()
+                ),
+                source: None,
+            });
+        }
+        Ok(())
+    }
+
+    async fn failed(&self, payload: Self::Payload, error: &str) -> Result<()> {
+        warn!(
+            url = %payload.url,
+            post_id = %payload.announcement.post_id,
+            error,
+            "Giving up on announcement webhook"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AnnouncementConfig {
+        AnnouncementConfig {
+            enabled: true,
+            site_url: "https://example.com/".to_string(),
+            post_types: HashMap::from([(
+                "post".to_string(),
+                PostTypeAnnouncements {
+                    path_prefix: "/blog/".to_string(),
+                    webhooks: vec!["https://hooks.example.com/a".to_string()],
+                    social_channels: vec!["mastodon".to_string()],
+                },
+            )]),
+        }
+    }
+
+    fn published(post_type: &str, previous_status: Option<&str>) -> PostPublished {
+        PostPublished {
+            post_id: Uuid::new_v4(),
+            author_id: Uuid::new_v4(),
+            post_type: post_type.to_string(),
+            title: "Hello".to_string(),
+            slug: "hello-world".to_string(),
+            excerpt: Some("First post".to_string()),
+            previous_status: previous_status.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_jobs_only_on_first_publication() {
+        let config = config();
+
+        let jobs = config.jobs_for(&published("post", Some("draft")));
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].job_type, "announce_webhook");
+        assert_eq!(jobs[1].job_type, "announce_social");
+
+        let webhook: AnnounceWebhookJob = jobs[0].payload().unwrap();
+        assert_eq!(
+            webhook.announcement.url,
+            "https://example.com/blog/hello-world"
+        );
+        assert_eq!(webhook.announcement.excerpt.as_deref(), Some("First post"));
+
+        // Edits to a published post, unknown transitions, unconfigured
+        // post types and disabled announcements all stay quiet
+        assert!(config
+            .jobs_for(&published("post", Some("published")))
+            .is_empty());
+        assert!(config.jobs_for(&published("post", None)).is_empty());
+        assert!(config
+            .jobs_for(&published("page", Some("draft")))
+            .is_empty());
+        let disabled = AnnouncementConfig {
+            enabled: false,
+            ..config
+        };
+        assert!(disabled
+            .jobs_for(&published("post", Some("draft")))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_post_url_without_prefix() {
+        let mut config = config();
+        config.post_types.get_mut("post").unwrap().path_prefix = String::new();
+
+        let jobs = config.jobs_for(&published("post", Some("scheduled")));
+        let social: AnnounceSocialJob = jobs[1].payload().unwrap();
+        assert_eq!(social.announcement.url, "https://example.com/hello-world");
+        assert_eq!(social.channel, "mastodon");
+    }
+}
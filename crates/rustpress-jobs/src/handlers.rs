@@ -1,22 +1,42 @@
 //! Job handlers for RustPress background tasks.
 //!
 //! This module contains handlers for scheduled tasks like publishing
-//! scheduled posts and cleaning up expired theme previews.
+//! scheduled posts and cleaning up expired theme previews, and for
+//! maintenance runs like rebuilding the content search index.
 
 use async_trait::async_trait;
-use rustpress_core::error::Result;
+use chrono::{DateTime, Utc};
+use rustpress_core::error::{Error, Result};
+use rustpress_events::{EventBus, PostPublished};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
-use tracing::{error, info};
+use std::sync::Arc;
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
-use crate::job::{JobHandler, JobPayload};
+use crate::job::{Job, JobHandler, JobPayload};
 
-/// Publish scheduled posts job - runs periodically to publish posts that are due
+/// Publish scheduled posts job - runs periodically to publish posts that are due,
+/// or once for a single post when enqueued with a delay via [`Self::for_post`]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PublishScheduledPostsJob {
     /// Optional site ID to limit scope (None = all sites)
     pub site_id: Option<Uuid>,
+    /// Publish only this post (None = every due post)
+    #[serde(default)]
+    pub post_id: Option<Uuid>,
+}
+
+impl PublishScheduledPostsJob {
+    /// Job publishing one post. Enqueue it with
+    /// `JobQueue::enqueue_delayed(job, priority, Some(scheduled_at))` so it
+    /// runs at the scheduled time rather than on the next poll.
+    pub fn for_post(post_id: Uuid) -> Self {
+        Self {
+            site_id: None,
+            post_id: Some(post_id),
+        }
+    }
 }
 
 impl JobPayload for PublishScheduledPostsJob {
@@ -40,60 +60,107 @@ impl JobPayload for PublishScheduledPostsJob {
 /// Handler for publishing scheduled posts
 pub struct PublishScheduledPostsHandler {
     pool: PgPool,
+    event_bus: Option<Arc<EventBus>>,
 }
 
 impl PublishScheduledPostsHandler {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            event_bus: None,
+        }
+    }
+
+    /// Publish a `post.published` event for every post this handler publishes
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
     }
 }
 
+/// A post published by the handler
+type PublishedRow = (Uuid, Uuid, String, String, String, Option<String>);
+
 #[async_trait]
 impl JobHandler for PublishScheduledPostsHandler {
     type Payload = PublishScheduledPostsJob;
 
     async fn handle(&self, payload: Self::Payload) -> Result<()> {
-        info!(site_id = ?payload.site_id, "Processing scheduled posts for publication");
+        info!(site_id = ?payload.site_id, post_id = ?payload.post_id, "Processing scheduled posts for publication");
 
         let now = chrono::Utc::now();
 
-        // Find all posts that are scheduled and due for publication
-        let query = if let Some(site_id) = payload.site_id {
-            sqlx::query(
+        // Find all posts that are scheduled and due for publication. A post
+        // that was unscheduled or rescheduled later is left alone.
+        let query = if let Some(post_id) = payload.post_id {
+            sqlx::query_as::<_, PublishedRow>(
+                r#"
+                UPDATE posts
+                SET status = 'published', published_at = $1, scheduled_at = NULL, updated_at = $1
+                WHERE status = 'scheduled'
+                  AND scheduled_at <= $1
+                  AND id = $2
+                RETURNING id, author_id, post_type, title, slug, excerpt
+                "#,
+            )
+            .bind(now)
+            .bind(post_id)
+        } else if let Some(site_id) = payload.site_id {
+            sqlx::query_as::<_, PublishedRow>(
                 r#"
                 UPDATE posts
                 SET status = 'published', published_at = $1, scheduled_at = NULL, updated_at = $1
                 WHERE status = 'scheduled'
                   AND scheduled_at <= $1
                   AND site_id = $2
-                RETURNING id
+                RETURNING id, author_id, post_type, title, slug, excerpt
                 "#,
             )
             .bind(now)
             .bind(site_id)
         } else {
-            sqlx::query(
+            sqlx::query_as::<_, PublishedRow>(
                 r#"
                 UPDATE posts
                 SET status = 'published', published_at = $1, scheduled_at = NULL, updated_at = $1
                 WHERE status = 'scheduled'
                   AND scheduled_at <= $1
-                RETURNING id
+                RETURNING id, author_id, post_type, title, slug, excerpt
                 "#,
             )
             .bind(now)
         };
 
-        let result = query.execute(&self.pool).await.map_err(|e| {
+        let published = query.fetch_all(&self.pool).await.map_err(|e| {
             rustpress_core::error::Error::database(format!(
                 "Failed to publish scheduled posts: {}",
                 e
             ))
         })?;
 
-        let published_count = result.rows_affected();
+        let published_count = published.len();
         info!(published_count, "Published scheduled posts");
 
+        // The posts are already published; a failing subscriber must not
+        // fail (and so retry) the job
+        if let Some(event_bus) = &self.event_bus {
+            for (post_id, author_id, post_type, title, slug, excerpt) in published {
+                let event = PostPublished {
+                    post_id,
+                    author_id,
+                    post_type,
+                    title,
+                    slug,
+                    excerpt,
+                    previous_status: Some("scheduled".to_string()),
+                }
+                .into_event();
+                if let Err(e) = event_bus.publish(event).await {
+                    warn!(%post_id, error = %e, "Failed to publish post.published event");
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -199,6 +266,382 @@ impl JobHandler for CleanThemePreviewsHandler {
     }
 }
 
+/// Default number of content rows indexed per batch
+pub const DEFAULT_REINDEX_BATCH_SIZE: u32 = 500;
+
+/// Largest accepted batch; bigger batches mean longer transactions
+pub const MAX_REINDEX_BATCH_SIZE: u32 = 5_000;
+
+/// Default pause between batches, leaving room for site traffic
+pub const DEFAULT_REINDEX_PAUSE_MS: u64 = 100;
+
+/// Priority of reindex jobs; below everything queued at the default of 0
+pub const REINDEX_PRIORITY: i32 = -10;
+
+/// Rebuild search documents in `content_search`, batch by batch.
+///
+/// Progress and the cursor are stored in `search_reindex_runs` under
+/// `run_id`, so a retried or re-dispatched job with the same `run_id`
+/// continues after the last committed batch instead of starting over.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReindexContentJob {
+    /// Identifies the run whose progress is recorded
+    pub run_id: Uuid,
+    /// Only reindex this post type (None = all)
+    #[serde(default)]
+    pub post_type: Option<String>,
+    /// Only reindex content created at or after this time
+    #[serde(default)]
+    pub created_from: Option<DateTime<Utc>>,
+    /// Only reindex content created before this time
+    #[serde(default)]
+    pub created_until: Option<DateTime<Utc>>,
+    /// Rows per batch
+    #[serde(default = "default_reindex_batch_size")]
+    pub batch_size: u32,
+    /// Pause between batches in milliseconds
+    #[serde(default = "default_reindex_pause_ms")]
+    pub pause_ms: u64,
+    /// Text search configuration, e.g. `english` or `simple`
+    #[serde(default = "default_reindex_language")]
+    pub language: String,
+}
+
+fn default_reindex_batch_size() -> u32 {
+    DEFAULT_REINDEX_BATCH_SIZE
+}
+
+fn default_reindex_pause_ms() -> u64 {
+    DEFAULT_REINDEX_PAUSE_MS
+}
+
+fn default_reindex_language() -> String {
+    "english".to_string()
+}
+
+impl Default for ReindexContentJob {
+    fn default() -> Self {
+        Self {
+            run_id: Uuid::now_v7(),
+            post_type: None,
+            created_from: None,
+            created_until: None,
+            batch_size: DEFAULT_REINDEX_BATCH_SIZE,
+            pause_ms: DEFAULT_REINDEX_PAUSE_MS,
+            language: default_reindex_language(),
+        }
+    }
+}
+
+impl ReindexContentJob {
+    /// Reindex all content in a new run
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the run to one post type
+    pub fn post_type(mut self, post_type: impl Into<String>) -> Self {
+        self.post_type = Some(post_type.into());
+        self
+    }
+
+    /// Limit the run to content created in `[from, until)`
+    pub fn created_between(
+        mut self,
+        from: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.created_from = from;
+        self.created_until = until;
+        self
+    }
+
+    /// Set the batch size
+    pub fn batch_size(mut self, batch_size: u32) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Set the text search configuration
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = language.into();
+        self
+    }
+
+    /// Low-priority job for this run. At most one run per post type is
+    /// pending at a time.
+    pub fn into_job(self) -> Job {
+        let scope = self.post_type.clone().unwrap_or_else(|| "*".to_string());
+        Job::new(self)
+            .with_priority(REINDEX_PRIORITY)
+            .with_dedup_key(format!("reindex_content:{}", scope))
+    }
+
+    /// Check the payload before touching the database
+    pub fn validate(&self) -> Result<()> {
+        if self.batch_size == 0 || self.batch_size > MAX_REINDEX_BATCH_SIZE {
+            return Err(Error::invalid_input(
+                "batch_size",
+                format!(
+                    "Batch size must be between 1 and {}",
+                    MAX_REINDEX_BATCH_SIZE
+                ),
+            ));
+        }
+        let valid_language = !self.language.is_empty()
+            && self
+                .language
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid_language {
+            return Err(Error::invalid_input(
+                "language",
+                format!("Invalid text search configuration '{}'", self.language),
+            ));
+        }
+        if let (Some(from), Some(until)) = (self.created_from, self.created_until) {
+            if from >= until {
+                return Err(Error::invalid_input(
+                    "created_until",
+                    "End of the date range must be after its start",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl JobPayload for ReindexContentJob {
+    fn job_type() -> &'static str {
+        "reindex_content"
+    }
+
+    fn queue() -> &'static str {
+        "maintenance"
+    }
+
+    fn max_attempts() -> u32 {
+        5
+    }
+
+    fn timeout_secs() -> u64 {
+        3600 // 1 hour; a run cut short resumes from its cursor
+    }
+}
+
+/// Progress of a reindex run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ReindexProgress {
+    pub cursor_id: Option<Uuid>,
+    pub processed: i64,
+    pub total: i64,
+    pub status: String,
+}
+
+impl ReindexProgress {
+    pub fn is_completed(&self) -> bool {
+        self.status == "completed"
+    }
+
+    /// Share of the run done, from 0 to 100
+    pub fn percent(&self) -> f64 {
+        if self.total <= 0 {
+            return 100.0;
+        }
+        (self.processed as f64 / self.total as f64 * 100.0).min(100.0)
+    }
+}
+
+/// Filter on `contents` shared by the count and batch queries.
+/// `$2` is the post type, `$3`/`$4` the creation date range.
+const REINDEX_FILTER: &str = "($2::text IS NULL OR c.post_type = $2) \
+     AND ($3::timestamptz IS NULL OR c.created_at >= $3) \
+     AND ($4::timestamptz IS NULL OR c.created_at < $4)";
+
+/// Handler rebuilding content search documents
+pub struct ReindexContentHandler {
+    pool: PgPool,
+}
+
+impl ReindexContentHandler {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Progress of a run, if it has started
+    pub async fn progress(&self, run_id: Uuid) -> Result<Option<ReindexProgress>> {
+        sqlx::query_as::<_, ReindexProgress>(
+            "SELECT cursor_id, processed, total, status FROM search_reindex_runs WHERE id = $1",
+        )
+        .bind(run_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::database(format!("Failed to load reindex progress: {}", e)))
+    }
+
+    /// Load the run, registering it with its total on first start
+    async fn start_run(&self, payload: &ReindexContentJob) -> Result<ReindexProgress> {
+        let query = format!(
+            r#"
+            INSERT INTO search_reindex_runs (id, post_type, created_from, created_until, total)
+            SELECT $1, $2, $3, $4, COUNT(*) FROM contents c WHERE {}
+            ON CONFLICT (id) DO NOTHING
+            "#,
+            REINDEX_FILTER
+        );
+        sqlx::query(&query)
+            .bind(payload.run_id)
+            .bind(&payload.post_type)
+            .bind(payload.created_from)
+            .bind(payload.created_until)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::database(format!("Failed to start reindex run: {}", e)))?;
+
+        self.progress(payload.run_id)
+            .await?
+            .ok_or_else(|| Error::not_found("search_reindex_run", payload.run_id.to_string()))
+    }
+
+    /// Index the batch after `cursor` and advance the run, in one short
+    /// transaction. Content rows are only read, so writers are never
+    /// blocked. Returns the rows indexed and the new cursor.
+    async fn index_batch(
+        &self,
+        payload: &ReindexContentJob,
+        cursor: Option<Uuid>,
+    ) -> Result<(i64, Option<Uuid>)> {
+        let map_err = |e: sqlx::Error| Error::database(format!("Reindex batch failed: {}", e));
+        let mut tx = self.pool.begin().await.map_err(map_err)?;
+        sqlx::query("SET LOCAL statement_timeout = '30s'")
+            .execute(&mut *tx)
+            .await
+            .map_err(map_err)?;
+
+        let query = format!(
+            r#"
+            WITH batch AS (
+                SELECT c.id, c.post_type, c.title, c.excerpt, c.content
+                FROM contents c
+                WHERE ($1::uuid IS NULL OR c.id > $1) AND {filter}
+                ORDER BY c.id
+                LIMIT $5
+            ), indexed AS (
+                INSERT INTO content_search (content_id, post_type, language, document, indexed_at)
+                SELECT id, post_type, $6,
+                    setweight(to_tsvector($6::regconfig, coalesce(title, '')), 'A')
+                    || setweight(to_tsvector($6::regconfig, coalesce(excerpt, '')), 'B')
+                    || setweight(to_tsvector($6::regconfig, coalesce(content, '')), 'C'),
+                    NOW()
+                FROM batch
+                ON CONFLICT (content_id) DO UPDATE SET
+                    post_type = EXCLUDED.post_type,
+                    language = EXCLUDED.language,
+                    document = EXCLUDED.document,
+                    indexed_at = EXCLUDED.indexed_at
+                RETURNING content_id
+            )
+            SELECT COUNT(*), (array_agg(content_id ORDER BY content_id DESC))[1] FROM indexed
+            "#,
+            filter = REINDEX_FILTER
+        );
+        let (count, last): (i64, Option<Uuid>) = sqlx::query_as(&query)
+            .bind(cursor)
+            .bind(&payload.post_type)
+            .bind(payload.created_from)
+            .bind(payload.created_until)
+            .bind(payload.batch_size as i64)
+            .bind(&payload.language)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(map_err)?;
+
+        let next = last.or(cursor);
+        let done = count < payload.batch_size as i64;
+        sqlx::query(
+            r#"
+            UPDATE search_reindex_runs SET
+                cursor_id = $2,
+                processed = processed + $3,
+                status = CASE WHEN $4 THEN 'completed' ELSE status END,
+                completed_at = CASE WHEN $4 THEN NOW() ELSE completed_at END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(payload.run_id)
+        .bind(next)
+        .bind(count)
+        .bind(done)
+        .execute(&mut *tx)
+        .await
+        .map_err(map_err)?;
+
+        tx.commit().await.map_err(map_err)?;
+        Ok((count, next))
+    }
+}
+
+#[async_trait]
+impl JobHandler for ReindexContentHandler {
+    type Payload = ReindexContentJob;
+
+    async fn handle(&self, payload: Self::Payload) -> Result<()> {
+        payload.validate()?;
+
+        let mut progress = self.start_run(&payload).await?;
+        if progress.is_completed() {
+            info!(run_id = %payload.run_id, "Reindex run already completed");
+            return Ok(());
+        }
+        info!(
+            run_id = %payload.run_id,
+            post_type = ?payload.post_type,
+            processed = progress.processed,
+            total = progress.total,
+            resumed = progress.cursor_id.is_some(),
+            "Reindexing content"
+        );
+
+        let mut cursor = progress.cursor_id;
+        loop {
+            let (count, next) = self.index_batch(&payload, cursor).await?;
+            cursor = next;
+            progress.processed += count;
+            info!(
+                run_id = %payload.run_id,
+                processed = progress.processed,
+                total = progress.total,
+                percent = format!("{:.1}", progress.percent()),
+                "Reindexed content batch"
+            );
+
+            if count < payload.batch_size as i64 {
+                break;
+            }
+            if payload.pause_ms > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(payload.pause_ms)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn failed(&self, payload: Self::Payload, error: &str) -> Result<()> {
+        error!(
+            run_id = %payload.run_id,
+            error,
+            "Content reindex failed; dispatch the same run_id again to resume"
+        );
+        Ok(())
+    }
+
+    async fn completed(&self, payload: Self::Payload) -> Result<()> {
+        info!(run_id = %payload.run_id, "Completed content reindex job");
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -212,9 +655,78 @@ mod tests {
         assert_eq!(PublishScheduledPostsJob::queue(), "content");
     }
 
+    #[test]
+    fn test_publish_single_post_payload() {
+        let post_id = Uuid::now_v7();
+        let job = crate::job::Job::new(PublishScheduledPostsJob::for_post(post_id));
+        let payload: PublishScheduledPostsJob = job.payload().unwrap();
+        assert_eq!(payload.post_id, Some(post_id));
+
+        // Payloads queued before `post_id` existed still deserialize
+        let legacy: PublishScheduledPostsJob =
+            serde_json::from_value(serde_json::json!({ "site_id": null })).unwrap();
+        assert_eq!(legacy.post_id, None);
+    }
+
     #[test]
     fn test_clean_theme_previews_job_type() {
         assert_eq!(CleanThemePreviewsJob::job_type(), "clean_theme_previews");
         assert_eq!(CleanThemePreviewsJob::queue(), "maintenance");
     }
+
+    #[test]
+    fn test_reindex_content_job() {
+        assert_eq!(ReindexContentJob::job_type(), "reindex_content");
+        assert_eq!(ReindexContentJob::queue(), "maintenance");
+
+        let job = ReindexContentJob::new().post_type("page").into_job();
+        assert_eq!(job.priority, REINDEX_PRIORITY);
+        assert_eq!(job.dedup_key.as_deref(), Some("reindex_content:page"));
+
+        // Only the run id is required; the rest falls back to defaults
+        let run_id = Uuid::now_v7();
+        let payload: ReindexContentJob =
+            serde_json::from_value(serde_json::json!({ "run_id": run_id })).unwrap();
+        assert_eq!(payload.run_id, run_id);
+        assert_eq!(payload.batch_size, DEFAULT_REINDEX_BATCH_SIZE);
+        assert_eq!(payload.language, "english");
+        assert!(payload.validate().is_ok());
+    }
+
+    #[test]
+    fn test_reindex_content_job_validation() {
+        assert!(ReindexContentJob::new().batch_size(0).validate().is_err());
+        assert!(ReindexContentJob::new()
+            .batch_size(MAX_REINDEX_BATCH_SIZE + 1)
+            .validate()
+            .is_err());
+        assert!(ReindexContentJob::new()
+            .language("english'; DROP TABLE contents; --")
+            .validate()
+            .is_err());
+
+        let now = Utc::now();
+        let earlier = now - chrono::Duration::days(1);
+        assert!(ReindexContentJob::new()
+            .created_between(Some(earlier), Some(now))
+            .validate()
+            .is_ok());
+        assert!(ReindexContentJob::new()
+            .created_between(Some(now), Some(earlier))
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_reindex_progress_percent() {
+        let mut progress = ReindexProgress {
+            cursor_id: None,
+            processed: 250,
+            total: 1000,
+            status: "running".to_string(),
+        };
+        assert_eq!(progress.percent(), 25.0);
+        progress.total = 0;
+        assert_eq!(progress.percent(), 100.0);
+    }
 }
@@ -6,6 +6,7 @@ use rustpress_core::error::Result;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 #[allow(unused_imports)]
 use std::any::Any;
+use std::cmp::Ordering;
 use uuid::Uuid;
 
 /// Job status
@@ -26,6 +27,33 @@ impl Default for JobStatus {
     }
 }
 
+impl JobStatus {
+    /// Value stored in the `status` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Reserved => "reserved",
+            Self::Processing => "processing",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    /// Parse a `status` column value. Older rows were written JSON-quoted.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim_matches('"') {
+            "pending" => Some(Self::Pending),
+            "reserved" => Some(Self::Reserved),
+            "processing" => Some(Self::Processing),
+            "completed" => Some(Self::Completed),
+            "failed" => Some(Self::Failed),
+            "cancelled" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+}
+
 /// Job payload trait for type-safe job data
 pub trait JobPayload: Serialize + DeserializeOwned + Send + Sync + 'static {
     /// Unique job type identifier
@@ -90,6 +118,13 @@ pub struct Job {
     pub reserved_at: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
+    /// At most one pending job may hold a given key
+    #[serde(default)]
+    pub dedup_key: Option<String>,
+    /// ID of the request that queued the job, so its logs and downstream
+    /// calls can be correlated with it
+    #[serde(default)]
+    pub request_id: Option<String>,
 }
 
 impl Job {
@@ -111,6 +146,8 @@ impl Job {
             reserved_at: None,
             completed_at: None,
             created_at: Utc::now(),
+            dedup_key: None,
+            request_id: rustpress_core::current_request_id(),
         }
     }
 
@@ -140,6 +177,12 @@ impl Job {
         self
     }
 
+    /// Set the key that keeps duplicate pending jobs out of the queue
+    pub fn with_dedup_key(mut self, key: impl Into<String>) -> Self {
+        self.dedup_key = Some(key.into());
+        self
+    }
+
     /// Delay job execution
     pub fn delay(mut self, seconds: u64) -> Self {
         self.available_at = Utc::now() + chrono::Duration::seconds(seconds as i64);
@@ -152,6 +195,21 @@ impl Job {
         self
     }
 
+    /// Check if a pending job may be claimed at `now`
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.status == JobStatus::Pending && self.available_at <= now
+    }
+
+    /// Order in which due jobs are claimed: highest priority first, then
+    /// first in, first out. Mirrors the `ORDER BY` used by the queue.
+    pub fn claim_order(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .cmp(&self.priority)
+            .then(self.created_at.cmp(&other.created_at))
+            .then(self.id.cmp(&other.id))
+    }
+
     /// Check if job can be retried
     pub fn can_retry(&self) -> bool {
         self.attempts < self.max_attempts
@@ -210,6 +268,22 @@ impl Job {
     }
 }
 
+/// A job that exhausted its attempts, kept in `failed_jobs` until retried
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FailedJob {
+    pub id: Uuid,
+    pub tenant_id: Option<Uuid>,
+    pub queue: String,
+    pub job_type: String,
+    pub payload: serde_json::Value,
+    pub priority: i32,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub last_error: String,
+    pub failed_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
 /// Common job types
 pub mod jobs {
     use super::*;
@@ -391,4 +465,44 @@ mod tests {
         let job = Job::new(payload).delay(60);
         assert!(job.available_at > Utc::now());
     }
+
+    fn cleanup(priority: i32) -> Job {
+        Job::new(CleanupJob {
+            cleanup_type: "sessions".to_string(),
+            older_than_days: 1,
+        })
+        .with_priority(priority)
+    }
+
+    #[test]
+    fn test_delayed_job_not_due_early() {
+        let now = Utc::now();
+        let job = cleanup(10).schedule_at(now + chrono::Duration::minutes(5));
+
+        assert!(!job.is_due(now));
+        assert!(job.is_due(now + chrono::Duration::minutes(5)));
+    }
+
+    #[test]
+    fn test_claim_order_priority_then_fifo() {
+        let first_low = cleanup(0);
+        let first_high = cleanup(5);
+        let second_high = cleanup(5);
+        let mut jobs = [first_low.clone(), second_high.clone(), first_high.clone()];
+
+        jobs.sort_by(Job::claim_order);
+
+        let ids: Vec<_> = jobs.iter().map(|j| j.id).collect();
+        assert_eq!(ids, vec![first_high.id, second_high.id, first_low.id]);
+    }
+
+    #[test]
+    fn test_status_column_round_trip() {
+        assert_eq!(
+            JobStatus::parse(JobStatus::Failed.as_str()),
+            Some(JobStatus::Failed)
+        );
+        assert_eq!(JobStatus::parse("\"pending\""), Some(JobStatus::Pending));
+        assert_eq!(JobStatus::parse("unknown"), None);
+    }
 }
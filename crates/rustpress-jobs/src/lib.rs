@@ -2,17 +2,24 @@
 //!
 //! Background job queue system for asynchronous task processing.
 
+pub mod announcements;
 pub mod handlers;
 pub mod job;
 pub mod queue;
 pub mod scheduler;
+pub mod storage_lifecycle;
 pub mod worker;
 
+pub use announcements::{
+    announcement_subscriber, AnnounceSocialJob, AnnounceWebhookHandler, AnnounceWebhookJob,
+    AnnouncementConfig,
+};
 pub use handlers::{
     CleanThemePreviewsHandler, CleanThemePreviewsJob, PublishScheduledPostsHandler,
-    PublishScheduledPostsJob,
+    PublishScheduledPostsJob, ReindexContentHandler, ReindexContentJob, ReindexProgress,
 };
-pub use job::{Job, JobHandler, JobPayload, JobStatus};
-pub use queue::{JobQueue, QueueConfig};
-pub use scheduler::{Schedule, Scheduler};
+pub use job::{FailedJob, Job, JobHandler, JobPayload, JobStatus};
+pub use queue::{DuplicatePolicy, FailedJobFilter, JobQueue, QueueConfig};
+pub use scheduler::{CatchUpPolicy, CronSchedule, Schedule, ScheduledTask, Scheduler};
+pub use storage_lifecycle::{PgAccessLog, StorageLifecycleHandler, StorageLifecycleJob};
 pub use worker::{Worker, WorkerPool};
@@ -1,6 +1,6 @@
 //! Job queue implementation.
 
-use crate::job::{Job, JobPayload, JobStatus};
+use crate::job::{FailedJob, Job, JobPayload, JobStatus};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use rustpress_core::error::{Error, Result};
@@ -9,6 +9,16 @@ use sqlx::PgPool;
 use std::sync::Arc;
 use uuid::Uuid;
 
+/// What `enqueue_unique` does when a pending job already holds the key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicatePolicy {
+    /// Leave the existing job untouched
+    #[default]
+    Ignore,
+    /// Replace the existing job's payload, priority and run time
+    Reschedule,
+}
+
 /// Queue configuration
 #[derive(Debug, Clone)]
 pub struct QueueConfig {
@@ -16,12 +26,16 @@ pub struct QueueConfig {
     pub default_timeout: u64,
     /// Maximum retry attempts
     pub max_retries: u32,
-    /// Retry delay in seconds
+    /// Retry delay in seconds, doubled after each failed attempt
     pub retry_delay: u64,
+    /// Upper bound for the retry delay in seconds
+    pub max_retry_delay: u64,
     /// Number of jobs to fetch at once
     pub batch_size: u32,
     /// Sleep duration when queue is empty (milliseconds)
     pub sleep_on_empty_ms: u64,
+    /// Handling of unique jobs whose key is already pending
+    pub on_duplicate: DuplicatePolicy,
 }
 
 impl Default for QueueConfig {
@@ -30,12 +44,67 @@ impl Default for QueueConfig {
             default_timeout: 300,
             max_retries: 3,
             retry_delay: 60,
+            max_retry_delay: 3600,
             batch_size: 10,
             sleep_on_empty_ms: 1000,
+            on_duplicate: DuplicatePolicy::Ignore,
         }
     }
 }
 
+impl QueueConfig {
+    /// Delay before retrying a job that has failed `attempts` times
+    pub fn backoff_delay(&self, attempts: u32) -> u64 {
+        let factor = 2_u64.saturating_pow(attempts.saturating_sub(1));
+        self.retry_delay
+            .saturating_mul(factor)
+            .min(self.max_retry_delay)
+    }
+}
+
+/// Selects dead jobs in `failed_jobs`; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct FailedJobFilter {
+    pub queue: Option<String>,
+    pub job_type: Option<String>,
+    pub ids: Option<Vec<Uuid>>,
+    pub failed_after: Option<DateTime<Utc>>,
+}
+
+impl FailedJobFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn queue(mut self, queue: impl Into<String>) -> Self {
+        self.queue = Some(queue.into());
+        self
+    }
+
+    pub fn job_type(mut self, job_type: impl Into<String>) -> Self {
+        self.job_type = Some(job_type.into());
+        self
+    }
+
+    pub fn ids(mut self, ids: Vec<Uuid>) -> Self {
+        self.ids = Some(ids);
+        self
+    }
+
+    pub fn failed_after(mut self, time: DateTime<Utc>) -> Self {
+        self.failed_after = Some(time);
+        self
+    }
+}
+
+/// `WHERE` clause for [`FailedJobFilter`], binding its fields as $1..$4
+const FAILED_JOB_FILTER: &str = r#"
+    ($1::text IS NULL OR queue = $1)
+    AND ($2::text IS NULL OR job_type = $2)
+    AND ($3::uuid[] IS NULL OR id = ANY($3))
+    AND ($4::timestamptz IS NULL OR failed_at >= $4)
+"#;
+
 /// Job queue trait
 #[async_trait]
 pub trait Queue: Send + Sync {
@@ -54,7 +123,7 @@ pub trait Queue: Send + Sync {
     /// Mark job as completed
     async fn complete(&self, job_id: Uuid) -> Result<()>;
 
-    /// Mark job as failed
+    /// Mark job as failed for good, moving it to the dead-letter table
     async fn fail(&self, job_id: Uuid, error: &str) -> Result<()>;
 
     /// Release job back to queue
@@ -72,8 +141,8 @@ pub trait Queue: Send + Sync {
     /// Clear all jobs from a queue
     async fn clear(&self, queue: &str) -> Result<u64>;
 
-    /// Retry failed jobs
-    async fn retry_failed(&self, queue: &str) -> Result<u64>;
+    /// Requeue dead jobs matching `filter` with a fresh set of attempts
+    async fn retry_failed(&self, filter: &FailedJobFilter) -> Result<u64>;
 
     /// Release stale reserved jobs
     async fn release_stale(&self, older_than_secs: u64) -> Result<u64>;
@@ -82,7 +151,6 @@ pub trait Queue: Send + Sync {
 /// Database-backed job queue
 pub struct JobQueue {
     pool: PgPool,
-    #[allow(dead_code)]
     config: QueueConfig,
     tenant_id: Option<Uuid>,
 }
@@ -109,6 +177,70 @@ impl JobQueue {
         self
     }
 
+    /// Queue configuration
+    pub fn config(&self) -> &QueueConfig {
+        &self.config
+    }
+
+    /// Connection pool the queue stores jobs in
+    pub(crate) fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Put a failed job back with a delay, recording the error
+    pub async fn release_failed(&self, job_id: Uuid, delay_secs: u64, error: &str) -> Result<()> {
+        let available_at = Utc::now() + chrono::Duration::seconds(delay_secs as i64);
+
+        sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = 'pending', reserved_at = NULL, available_at = $2, last_error = $3
+            WHERE id = $1
+            "#,
+        )
+        .bind(job_id)
+        .bind(available_at)
+        .bind(error)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::database_with_source("Failed to release job", e))?;
+
+        tracing::debug!(job_id = %job_id, delay_secs = delay_secs, error = %error, "Job released for retry");
+        Ok(())
+    }
+
+    /// Dead jobs matching `filter`, most recent first
+    pub async fn failed_jobs(&self, filter: &FailedJobFilter) -> Result<Vec<FailedJob>> {
+        let query = format!(
+            "SELECT * FROM failed_jobs WHERE {} ORDER BY failed_at DESC",
+            FAILED_JOB_FILTER
+        );
+
+        sqlx::query_as(&query)
+            .bind(&filter.queue)
+            .bind(&filter.job_type)
+            .bind(&filter.ids)
+            .bind(filter.failed_after)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::database_with_source("Failed to list failed jobs", e))
+    }
+
+    /// Pending job count for every queue that has pending jobs
+    pub async fn depths(&self) -> Result<Vec<(String, u64)>> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT queue, COUNT(*) FROM jobs WHERE status = 'pending' GROUP BY queue ORDER BY queue",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::database_with_source("Failed to get queue depths", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(queue, count)| (queue, count as u64))
+            .collect())
+    }
+
     /// Dispatch a job
     pub async fn dispatch<P: JobPayload>(&self, payload: P) -> Result<Uuid> {
         let mut job = Job::new(payload);
@@ -131,6 +263,86 @@ impl JobQueue {
         self.push(job).await
     }
 
+    /// Enqueue a job with a priority (higher runs first) that won't be
+    /// claimed before `run_at`. `None` makes it available immediately.
+    pub async fn enqueue_delayed<P: JobPayload>(
+        &self,
+        payload: P,
+        priority: i32,
+        run_at: Option<DateTime<Utc>>,
+    ) -> Result<Uuid> {
+        let mut job = Job::new(payload).with_priority(priority);
+        if let Some(run_at) = run_at {
+            job = job.schedule_at(run_at);
+        }
+        if let Some(tenant_id) = self.tenant_id {
+            job = job.with_tenant(tenant_id);
+        }
+        self.push(job).await
+    }
+
+    /// Enqueue a job unless one with the same `dedup_key` is already
+    /// pending. Returns the job ID and whether a new job was queued; an
+    /// existing job is left alone or rescheduled per `on_duplicate`.
+    pub async fn enqueue_unique<P: JobPayload>(
+        &self,
+        dedup_key: impl Into<String>,
+        payload: P,
+    ) -> Result<(Uuid, bool)> {
+        let mut job = Job::new(payload).with_dedup_key(dedup_key);
+        if let Some(tenant_id) = self.tenant_id {
+            job = job.with_tenant(tenant_id);
+        }
+        self.push_unique(job).await
+    }
+
+    /// Push a job carrying a `dedup_key`, see [`Self::enqueue_unique`]
+    pub async fn push_unique(&self, job: Job) -> Result<(Uuid, bool)> {
+        let Some(dedup_key) = job.dedup_key.as_deref() else {
+            return Ok((self.push(job).await?, true));
+        };
+
+        let on_conflict = match self.config.on_duplicate {
+            DuplicatePolicy::Ignore => "dedup_key = jobs.dedup_key",
+            DuplicatePolicy::Reschedule => {
+                "payload = EXCLUDED.payload, priority = EXCLUDED.priority, available_at = EXCLUDED.available_at"
+            }
+        };
+        // The unique index only covers pending jobs, so the conflict target
+        // repeats its predicate. `xmax = 0` holds only for freshly inserted rows.
+        let query = format!(
+            r#"
+            INSERT INTO jobs (id, tenant_id, queue, job_type, payload, status, priority, attempts, max_attempts, available_at, created_at, dedup_key, request_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (dedup_key) WHERE status = 'pending'
+            DO UPDATE SET {}
+            RETURNING id, (xmax = 0) AS inserted
+            "#,
+            on_conflict
+        );
+
+        let (id, inserted): (Uuid, bool) = sqlx::query_as(&query)
+            .bind(job.id)
+            .bind(job.tenant_id)
+            .bind(&job.queue)
+            .bind(&job.job_type)
+            .bind(&job.payload)
+            .bind(job.status.as_str())
+            .bind(job.priority)
+            .bind(job.attempts as i32)
+            .bind(job.max_attempts as i32)
+            .bind(job.available_at)
+            .bind(job.created_at)
+            .bind(dedup_key)
+            .bind(&job.request_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| Error::database_with_source("Failed to push unique job", e))?;
+
+        tracing::debug!(job_id = %id, dedup_key = %dedup_key, inserted, "Unique job pushed");
+        Ok((id, inserted))
+    }
+
     /// Dispatch a job at a specific time
     pub async fn dispatch_at<P: JobPayload>(
         &self,
@@ -152,8 +364,8 @@ impl Queue for JobQueue {
 
         sqlx::query(
             r#"
-            INSERT INTO jobs (id, tenant_id, queue, job_type, payload, status, priority, attempts, max_attempts, available_at, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            INSERT INTO jobs (id, tenant_id, queue, job_type, payload, status, priority, attempts, max_attempts, available_at, created_at, dedup_key, request_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
             "#,
         )
         .bind(job.id)
@@ -161,12 +373,14 @@ impl Queue for JobQueue {
         .bind(&job.queue)
         .bind(&job.job_type)
         .bind(&job.payload)
-        .bind(serde_json::to_string(&job.status).unwrap_or_default())
+        .bind(job.status.as_str())
         .bind(job.priority)
         .bind(job.attempts as i32)
         .bind(job.max_attempts as i32)
         .bind(job.available_at)
         .bind(job.created_at)
+        .bind(&job.dedup_key)
+        .bind(&job.request_id)
         .execute(&self.pool)
         .await
         .map_err(|e| Error::database_with_source("Failed to push job", e))?;
@@ -196,11 +410,11 @@ impl Queue for JobQueue {
                 AND status = 'pending'
                 AND available_at <= NOW()
                 {}
-                ORDER BY priority DESC, available_at ASC
+                ORDER BY priority DESC, created_at ASC, id ASC
                 FOR UPDATE SKIP LOCKED
                 LIMIT 1
             )
-            RETURNING id, tenant_id, queue, job_type, payload, status, priority, attempts, max_attempts, last_error, available_at, reserved_at, completed_at, created_at
+            RETURNING id, tenant_id, queue, job_type, payload, status, priority, attempts, max_attempts, last_error, available_at, reserved_at, completed_at, created_at, dedup_key, request_id
             "#,
             tenant_condition
         );
@@ -237,8 +451,17 @@ impl Queue for JobQueue {
     }
 
     async fn fail(&self, job_id: Uuid, error: &str) -> Result<()> {
+        // Move in one statement so a job is never in both tables or neither
         sqlx::query(
-            "UPDATE jobs SET status = 'failed', last_error = $2, reserved_at = NULL WHERE id = $1",
+            r#"
+            WITH dead AS (
+                DELETE FROM jobs WHERE id = $1
+                RETURNING id, tenant_id, queue, job_type, payload, priority, attempts, max_attempts, created_at
+            )
+            INSERT INTO failed_jobs (id, tenant_id, queue, job_type, payload, priority, attempts, max_attempts, last_error, failed_at, created_at)
+            SELECT id, tenant_id, queue, job_type, payload, COALESCE(priority, 0), COALESCE(attempts, 0), COALESCE(max_attempts, 3), $2, NOW(), created_at
+            FROM dead
+            "#,
         )
         .bind(job_id)
         .bind(error)
@@ -246,7 +469,7 @@ impl Queue for JobQueue {
         .await
         .map_err(|e| Error::database_with_source("Failed to fail job", e))?;
 
-        tracing::debug!(job_id = %job_id, error = %error, "Job failed");
+        tracing::warn!(job_id = %job_id, error = %error, "Job moved to failed_jobs");
         Ok(())
     }
 
@@ -307,18 +530,32 @@ impl Queue for JobQueue {
         Ok(result.rows_affected())
     }
 
-    async fn retry_failed(&self, queue: &str) -> Result<u64> {
-        let result = sqlx::query(
+    async fn retry_failed(&self, filter: &FailedJobFilter) -> Result<u64> {
+        let query = format!(
             r#"
-            UPDATE jobs
-            SET status = 'pending', reserved_at = NULL, available_at = NOW()
-            WHERE queue = $1 AND status = 'failed' AND attempts < max_attempts
+            WITH revived AS (
+                DELETE FROM failed_jobs WHERE {}
+                RETURNING id, tenant_id, queue, job_type, payload, priority, max_attempts, last_error, created_at
+            )
+            INSERT INTO jobs (id, tenant_id, queue, job_type, payload, status, priority, attempts, max_attempts, last_error, available_at, created_at)
+            SELECT id, tenant_id, queue, job_type, payload, 'pending', priority, 0, max_attempts, last_error, NOW(), created_at
+            FROM revived
             "#,
-        )
-        .bind(queue)
-        .execute(&self.pool)
-        .await
-        .map_err(|e| Error::database_with_source("Failed to retry failed jobs", e))?;
+            FAILED_JOB_FILTER
+        );
+
+        let result = sqlx::query(&query)
+            .bind(&filter.queue)
+            .bind(&filter.job_type)
+            .bind(&filter.ids)
+            .bind(filter.failed_after)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::database_with_source("Failed to retry failed jobs", e))?;
+
+        if result.rows_affected() > 0 {
+            tracing::info!(count = result.rows_affected(), "Requeued failed jobs");
+        }
 
         Ok(result.rows_affected())
     }
@@ -363,6 +600,8 @@ struct JobRow {
     reserved_at: Option<DateTime<Utc>>,
     completed_at: Option<DateTime<Utc>>,
     created_at: DateTime<Utc>,
+    dedup_key: Option<String>,
+    request_id: Option<String>,
 }
 
 impl From<JobRow> for Job {
@@ -373,7 +612,7 @@ impl From<JobRow> for Job {
             queue: row.queue,
             job_type: row.job_type,
             payload: row.payload,
-            status: serde_json::from_str(&row.status).unwrap_or(JobStatus::Pending),
+            status: JobStatus::parse(&row.status).unwrap_or(JobStatus::Pending),
             priority: row.priority.unwrap_or(0),
             attempts: row.attempts.unwrap_or(0) as u32,
             max_attempts: row.max_attempts.unwrap_or(3) as u32,
@@ -383,6 +622,88 @@ impl From<JobRow> for Job {
             reserved_at: row.reserved_at,
             completed_at: row.completed_at,
             created_at: row.created_at,
+            dedup_key: row.dedup_key,
+            request_id: row.request_id,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::job::jobs::CleanupJob;
+    use rustpress_database::migration::{create_initial_migrations, Migrator};
+
+    pub(crate) async fn test_queue(config: QueueConfig) -> Arc<JobQueue> {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+        let pool = PgPool::connect(&url).await.unwrap();
+        Migrator::new()
+            .with_migrations(create_initial_migrations())
+            .run(&pool)
+            .await
+            .unwrap();
+        Arc::new(JobQueue::with_config(pool, config))
+    }
+
+    fn cleanup(cleanup_type: &str) -> CleanupJob {
+        CleanupJob {
+            cleanup_type: cleanup_type.to_string(),
+            older_than_days: 1,
         }
     }
+
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_concurrent_enqueue_unique_queues_once() {
+        let queue = test_queue(QueueConfig::default()).await;
+        let key = format!("test:{}", Uuid::now_v7());
+
+        let enqueue = |queue: Arc<JobQueue>, key: String| {
+            tokio::spawn(async move { queue.enqueue_unique(key, cleanup("sessions")).await })
+        };
+        let (a, b) = tokio::join!(
+            enqueue(queue.clone(), key.clone()),
+            enqueue(queue.clone(), key.clone())
+        );
+        let (a, b) = (a.unwrap().unwrap(), b.unwrap().unwrap());
+
+        assert_eq!(a.0, b.0);
+        assert!(a.1 != b.1, "exactly one call should insert");
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM jobs WHERE dedup_key = $1")
+            .bind(&key)
+            .fetch_one(&queue.pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_enqueue_unique_reschedule_policy() {
+        let config = QueueConfig {
+            on_duplicate: DuplicatePolicy::Reschedule,
+            ..Default::default()
+        };
+        let queue = test_queue(config).await;
+        let key = format!("test:{}", Uuid::now_v7());
+
+        let (first, inserted) = queue.enqueue_unique(&key, cleanup("old")).await.unwrap();
+        assert!(inserted);
+        let (second, inserted) = queue.enqueue_unique(&key, cleanup("new")).await.unwrap();
+        assert!(!inserted);
+        assert_eq!(first, second);
+
+        let job = queue.get(first).await.unwrap().unwrap();
+        assert_eq!(job.payload::<CleanupJob>().unwrap().cleanup_type, "new");
+    }
+
+    #[test]
+    fn test_backoff_doubles_until_cap() {
+        let config = QueueConfig::default();
+        assert_eq!(config.backoff_delay(1), 60);
+        assert_eq!(config.backoff_delay(2), 120);
+        assert_eq!(config.backoff_delay(3), 240);
+        assert_eq!(config.backoff_delay(7), 3600);
+        assert_eq!(config.backoff_delay(200), 3600);
+    }
 }
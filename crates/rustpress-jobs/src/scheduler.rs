@@ -1,20 +1,43 @@
 //! Job scheduler for recurring and scheduled tasks.
+//!
+//! Each task's last run is stored in `scheduled_task_runs`, so after a
+//! restart the scheduler can tell which runs were missed while it was down
+//! and handle them according to the task's [`CatchUpPolicy`].
 
 use crate::job::{Job, JobPayload};
-use crate::queue::{JobQueue, Queue};
-use chrono::{DateTime, Datelike, Duration, Utc};
+use crate::queue::JobQueue;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
 use parking_lot::RwLock;
 use rustpress_core::error::{Error, Result};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+/// Default cap on the runs [`CatchUpPolicy::RunAll`] queues after downtime
+pub const DEFAULT_MAX_CATCH_UP_RUNS: usize = 100;
+
+/// Upper bound on the steps taken searching for the next cron match
+const CRON_SEARCH_STEPS: usize = 10_000;
+
+/// What to do about runs a task missed while the scheduler was down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CatchUpPolicy {
+    /// Drop missed runs and wait for the next scheduled time
+    #[default]
+    Skip,
+    /// Run once if any runs were missed
+    RunOnce,
+    /// Run once per missed run, up to the scheduler's catch-up limit
+    RunAll,
+}
+
 /// Scheduled task definition
 pub struct ScheduledTask {
     pub name: String,
     pub schedule: Schedule,
     pub job_factory: Box<dyn Fn() -> Job + Send + Sync>,
     pub enabled: bool,
+    pub catch_up: CatchUpPolicy,
     pub last_run: Option<DateTime<Utc>>,
     pub next_run: Option<DateTime<Utc>>,
 }
@@ -30,6 +53,7 @@ impl ScheduledTask {
             schedule,
             job_factory: Box::new(factory),
             enabled: true,
+            catch_up: CatchUpPolicy::default(),
             last_run: None,
             next_run: Some(schedule_clone.next_run_time()),
         }
@@ -40,6 +64,12 @@ impl ScheduledTask {
         self
     }
 
+    /// Set how runs missed during downtime are handled
+    pub fn with_catch_up(mut self, policy: CatchUpPolicy) -> Self {
+        self.catch_up = policy;
+        self
+    }
+
     pub fn is_due(&self) -> bool {
         if !self.enabled {
             return false;
@@ -47,10 +77,63 @@ impl ScheduledTask {
         self.next_run.map(|t| Utc::now() >= t).unwrap_or(false)
     }
 
+    /// Build the task's job. Its dedup key is the task name, so instances
+    /// sharing a queue don't stack up copies of the same pending run.
+    pub fn create_job(&self) -> Job {
+        (self.job_factory)().with_dedup_key(format!("schedule:{}", self.name))
+    }
+
     pub fn update_schedule(&mut self) {
         self.last_run = Some(Utc::now());
         self.next_run = Some(self.schedule.next_run_time());
     }
+
+    /// Jobs to queue for runs missed since `last_run`, and the next regular
+    /// run time. Catch-up jobs from `RunAll` are keyed by the missed time so
+    /// they don't collapse into one another.
+    fn catch_up_jobs(
+        &self,
+        last_run: DateTime<Utc>,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> (Vec<Job>, DateTime<Utc>) {
+        // One past the limit tells a truncated backlog from an exact fit
+        let missed = self.schedule.runs_between(last_run, now, limit + 1);
+
+        let next_run = match missed.last() {
+            Some(&latest) if missed.len() <= limit => self.schedule.next_run_after(latest),
+            Some(_) => self.schedule.next_run_after(now),
+            None => self.schedule.next_run_after(last_run),
+        };
+
+        let jobs = match self.catch_up {
+            CatchUpPolicy::Skip => Vec::new(),
+            CatchUpPolicy::RunOnce if missed.is_empty() => Vec::new(),
+            CatchUpPolicy::RunOnce => vec![self.create_job()],
+            CatchUpPolicy::RunAll => {
+                if missed.len() > limit {
+                    tracing::warn!(
+                        task = %self.name,
+                        limit,
+                        "Missed runs exceed the catch-up limit; running the most recent"
+                    );
+                }
+                let skip = missed.len().saturating_sub(limit);
+                missed[skip..]
+                    .iter()
+                    .map(|at| {
+                        (self.job_factory)().with_dedup_key(format!(
+                            "schedule:{}:{}",
+                            self.name,
+                            at.timestamp()
+                        ))
+                    })
+                    .collect()
+            }
+        };
+
+        (jobs, next_run)
+    }
 }
 
 /// Schedule definition
@@ -112,25 +195,29 @@ impl Schedule {
     }
 
     pub fn next_run_time(&self) -> DateTime<Utc> {
-        let now = Utc::now();
+        self.next_run_after(Utc::now())
+    }
+
+    /// First run time after `after`
+    pub fn next_run_after(&self, after: DateTime<Utc>) -> DateTime<Utc> {
         match self {
-            Schedule::EverySeconds(secs) => now + Duration::seconds(*secs as i64),
-            Schedule::EveryMinutes(mins) => now + Duration::minutes(*mins as i64),
-            Schedule::EveryHours(hours) => now + Duration::hours(*hours as i64),
+            Schedule::EverySeconds(secs) => after + Duration::seconds(*secs as i64),
+            Schedule::EveryMinutes(mins) => after + Duration::minutes(*mins as i64),
+            Schedule::EveryHours(hours) => after + Duration::hours(*hours as i64),
             Schedule::DailyAt(hour) => {
-                let today = now.date_naive();
+                let today = after.date_naive();
                 let time =
                     chrono::NaiveTime::from_hms_opt(*hour, 0, 0).unwrap_or(chrono::NaiveTime::MIN);
                 let datetime = today.and_time(time);
                 let scheduled = datetime.and_utc();
-                if scheduled <= now {
+                if scheduled <= after {
                     scheduled + Duration::days(1)
                 } else {
                     scheduled
                 }
             }
             Schedule::WeeklyAt(day, hour) => {
-                let today = now.date_naive();
+                let today = after.date_naive();
                 let time =
                     chrono::NaiveTime::from_hms_opt(*hour, 0, 0).unwrap_or(chrono::NaiveTime::MIN);
                 let current_weekday = today.weekday().num_days_from_sunday();
@@ -141,11 +228,37 @@ impl Schedule {
                     7 - (current_weekday - target_day)
                 };
                 let target_date = today + Duration::days(days_until as i64);
-                target_date.and_time(time).and_utc()
+                let scheduled = target_date.and_time(time).and_utc();
+                if scheduled <= after {
+                    scheduled + Duration::days(7)
+                } else {
+                    scheduled
+                }
             }
-            Schedule::Cron(cron) => cron.next_run_time(),
+            Schedule::Cron(cron) => cron.next_run_after(after),
         }
     }
+
+    /// Run times after `since` up to and including `until`, at most `limit`
+    pub fn runs_between(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        limit: usize,
+    ) -> Vec<DateTime<Utc>> {
+        let mut runs = Vec::new();
+        let mut last = since;
+        while runs.len() < limit {
+            let next = self.next_run_after(last);
+            // A zero interval would never advance
+            if next > until || next <= last {
+                break;
+            }
+            runs.push(next);
+            last = next;
+        }
+        runs
+    }
 }
 
 /// Simple cron-like schedule
@@ -195,12 +308,57 @@ impl CronSchedule {
     }
 
     pub fn next_run_time(&self) -> DateTime<Utc> {
-        // Simplified implementation - just adds 1 day for now
-        // A real implementation would properly calculate based on cron fields
-        Utc::now() + Duration::days(1)
+        self.next_run_after(Utc::now())
+    }
+
+    /// First minute after `after` matching every set field. Falls back to a
+    /// day later if the fields can never match (e.g. February 30th).
+    pub fn next_run_after(&self, after: DateTime<Utc>) -> DateTime<Utc> {
+        let matches = |field: Option<u32>, value: u32| field.map(|f| f == value).unwrap_or(true);
+        let start_of_minute = after
+            .naive_utc()
+            .with_second(0)
+            .and_then(|t| t.with_nanosecond(0));
+        let mut t = start_of_minute.unwrap_or_else(|| after.naive_utc()) + Duration::minutes(1);
+
+        for _ in 0..CRON_SEARCH_STEPS {
+            if !matches(self.month, t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                match NaiveDate::from_ymd_opt(year, month, 1) {
+                    Some(date) => t = date.and_time(NaiveTime::MIN),
+                    None => break,
+                }
+                continue;
+            }
+            if !matches(self.day_of_month, t.day())
+                || !matches(self.day_of_week, t.weekday().num_days_from_sunday())
+            {
+                t = t.date().and_time(NaiveTime::MIN) + Duration::days(1);
+                continue;
+            }
+            if !matches(self.hour, t.hour()) {
+                t = start_of_hour(t) + Duration::hours(1);
+                continue;
+            }
+            if !matches(self.minute, t.minute()) {
+                t += Duration::minutes(1);
+                continue;
+            }
+            return t.and_utc();
+        }
+
+        after + Duration::days(1)
     }
 }
 
+fn start_of_hour(t: NaiveDateTime) -> NaiveDateTime {
+    t.date().and_time(NaiveTime::MIN) + Duration::hours(t.hour() as i64)
+}
+
 impl Default for CronSchedule {
     fn default() -> Self {
         Self::new()
@@ -213,6 +371,7 @@ pub struct Scheduler {
     tasks: RwLock<HashMap<String, ScheduledTask>>,
     running: Arc<AtomicBool>,
     check_interval: std::time::Duration,
+    max_catch_up_runs: usize,
 }
 
 impl Scheduler {
@@ -222,6 +381,7 @@ impl Scheduler {
             tasks: RwLock::new(HashMap::new()),
             running: Arc::new(AtomicBool::new(false)),
             check_interval: std::time::Duration::from_secs(60),
+            max_catch_up_runs: DEFAULT_MAX_CATCH_UP_RUNS,
         }
     }
 
@@ -230,6 +390,12 @@ impl Scheduler {
         self
     }
 
+    /// Cap the runs a `RunAll` task queues after downtime
+    pub fn with_max_catch_up_runs(mut self, max: usize) -> Self {
+        self.max_catch_up_runs = max;
+        self
+    }
+
     /// Schedule a task
     pub fn schedule(&self, task: ScheduledTask) -> &Self {
         let name = task.name.clone();
@@ -307,6 +473,10 @@ impl Scheduler {
 
         tracing::info!("Scheduler started");
 
+        if let Err(e) = self.catch_up().await {
+            tracing::error!(error = %e, "Failed to catch up missed scheduled runs");
+        }
+
         while self.running.load(Ordering::SeqCst) {
             self.process_due_tasks().await?;
             tokio::time::sleep(self.check_interval).await;
@@ -326,6 +496,70 @@ impl Scheduler {
         self.running.load(Ordering::SeqCst)
     }
 
+    /// Restore each task's last run and queue runs missed since then per
+    /// its catch-up policy. Returns the number of jobs queued.
+    pub async fn catch_up(&self) -> Result<u32> {
+        let rows: Vec<(String, DateTime<Utc>)> =
+            sqlx::query_as("SELECT name, last_run_at FROM scheduled_task_runs")
+                .fetch_all(self.queue.pool())
+                .await
+                .map_err(|e| {
+                    Error::database_with_source("Failed to load scheduled task runs", e)
+                })?;
+        let last_runs: HashMap<String, DateTime<Utc>> = rows.into_iter().collect();
+
+        let now = Utc::now();
+        let jobs_to_dispatch: Vec<(String, Vec<Job>)> = {
+            let mut tasks = self.tasks.write();
+            let mut jobs = Vec::new();
+            for task in tasks.values_mut() {
+                let Some(&last_run) = last_runs.get(&task.name) else {
+                    continue;
+                };
+                let (task_jobs, next_run) =
+                    task.catch_up_jobs(last_run, now, self.max_catch_up_runs);
+                task.last_run = Some(last_run);
+                task.next_run = Some(next_run);
+                if task.enabled && !task_jobs.is_empty() {
+                    jobs.push((task.name.clone(), task_jobs));
+                }
+            }
+            jobs
+        };
+
+        let mut count = 0;
+        for (task_name, jobs) in jobs_to_dispatch {
+            let missed = jobs.len();
+            for job in jobs {
+                if self.queue.push_unique(job).await?.1 {
+                    count += 1;
+                }
+            }
+            self.record_run(&task_name, now).await?;
+            tracing::info!(task = %task_name, runs = missed, "Caught up missed scheduled runs");
+        }
+
+        Ok(count)
+    }
+
+    /// Persist a task's last run so missed runs can be found after a restart
+    async fn record_run(&self, task_name: &str, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO scheduled_task_runs (name, last_run_at)
+            VALUES ($1, $2)
+            ON CONFLICT (name)
+            DO UPDATE SET last_run_at = GREATEST(scheduled_task_runs.last_run_at, EXCLUDED.last_run_at)
+            "#,
+        )
+        .bind(task_name)
+        .bind(at)
+        .execute(self.queue.pool())
+        .await
+        .map_err(|e| Error::database_with_source("Failed to record scheduled task run", e))?;
+        Ok(())
+    }
+
     /// Process all due tasks
     async fn process_due_tasks(&self) -> Result<()> {
         // Collect jobs to dispatch while holding the lock, then release it before async operations
@@ -340,7 +574,7 @@ impl Scheduler {
             let mut jobs = Vec::new();
             for task_name in due_task_names {
                 if let Some(task) = tasks.get_mut(&task_name) {
-                    let job = (task.job_factory)();
+                    let job = task.create_job();
                     task.update_schedule();
                     jobs.push((task_name, job));
                 }
@@ -350,14 +584,27 @@ impl Scheduler {
 
         // Now dispatch jobs without holding the lock
         for (task_name, job) in jobs_to_dispatch {
-            match self.queue.push(job).await {
-                Ok(job_id) => {
+            let dispatched = self.queue.push_unique(job).await;
+            if dispatched.is_ok() {
+                if let Err(e) = self.record_run(&task_name, Utc::now()).await {
+                    tracing::warn!(task = %task_name, error = %e, "Failed to record scheduled task run");
+                }
+            }
+            match dispatched {
+                Ok((job_id, true)) => {
                     tracing::info!(
                         task = %task_name,
                         job_id = %job_id,
                         "Scheduled task dispatched"
                     );
                 }
+                Ok((job_id, false)) => {
+                    tracing::debug!(
+                        task = %task_name,
+                        job_id = %job_id,
+                        "Scheduled task already pending"
+                    );
+                }
                 Err(e) => {
                     tracing::error!(
                         task = %task_name,
@@ -374,7 +621,7 @@ impl Scheduler {
     /// Run due tasks once (for testing)
     pub async fn tick(&self) -> Result<u32> {
         // Collect jobs to dispatch while holding the lock
-        let jobs_to_dispatch: Vec<Job> = {
+        let jobs_to_dispatch: Vec<(String, Job)> = {
             let mut tasks = self.tasks.write();
             let due_task_names: Vec<String> = tasks
                 .iter()
@@ -385,18 +632,21 @@ impl Scheduler {
             let mut jobs = Vec::new();
             for task_name in due_task_names {
                 if let Some(task) = tasks.get_mut(&task_name) {
-                    let job = (task.job_factory)();
+                    let job = task.create_job();
                     task.update_schedule();
-                    jobs.push(job);
+                    jobs.push((task_name, job));
                 }
             }
             jobs
         }; // Lock is released here
 
         // Dispatch jobs without holding the lock
-        let count = jobs_to_dispatch.len() as u32;
-        for job in jobs_to_dispatch {
-            self.queue.push(job).await?;
+        let mut count = 0;
+        for (task_name, job) in jobs_to_dispatch {
+            if self.queue.push_unique(job).await?.1 {
+                count += 1;
+            }
+            self.record_run(&task_name, Utc::now()).await?;
         }
 
         Ok(count)
@@ -461,4 +711,112 @@ mod tests {
 
         assert!(!task.is_due());
     }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn cleanup_task(name: &str, schedule: Schedule) -> ScheduledTask {
+        ScheduledTask::new(name, schedule, || {
+            Job::new(crate::job::jobs::CleanupJob {
+                cleanup_type: "test".to_string(),
+                older_than_days: 30,
+            })
+        })
+    }
+
+    #[test]
+    fn test_cron_next_run_after() {
+        let daily = CronSchedule::new().hour(3).minute(30);
+        assert_eq!(
+            daily.next_run_after(at("2024-01-10T03:29:59Z")),
+            at("2024-01-10T03:30:00Z")
+        );
+        assert_eq!(
+            daily.next_run_after(at("2024-01-10T03:30:00Z")),
+            at("2024-01-11T03:30:00Z")
+        );
+
+        // 2024-01-14 is a Sunday
+        let sundays = CronSchedule::new().day_of_week(0).hour(0).minute(0);
+        assert_eq!(
+            sundays.next_run_after(at("2024-01-10T12:00:00Z")),
+            at("2024-01-14T00:00:00Z")
+        );
+
+        let new_year = CronSchedule::new()
+            .month(1)
+            .day_of_month(1)
+            .hour(0)
+            .minute(0);
+        assert_eq!(
+            new_year.next_run_after(at("2024-03-01T00:00:00Z")),
+            at("2025-01-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_weekly_next_run_is_in_future() {
+        // Wednesday 2024-01-10 at noon, after that day's 09:00 run
+        let next = Schedule::WeeklyAt(3, 9).next_run_after(at("2024-01-10T12:00:00Z"));
+        assert_eq!(next, at("2024-01-17T09:00:00Z"));
+    }
+
+    #[test]
+    fn test_catch_up_policies() {
+        let last_run = at("2024-01-10T12:00:00Z");
+        let now = at("2024-01-10T12:05:30Z");
+
+        let task = cleanup_task("skip", Schedule::every_minute());
+        let (jobs, next_run) = task.catch_up_jobs(last_run, now, 100);
+        assert!(jobs.is_empty());
+        assert_eq!(next_run, at("2024-01-10T12:06:00Z"));
+
+        let task =
+            cleanup_task("once", Schedule::every_minute()).with_catch_up(CatchUpPolicy::RunOnce);
+        assert_eq!(task.catch_up_jobs(last_run, now, 100).0.len(), 1);
+        let (jobs, _) = task.catch_up_jobs(last_run, at("2024-01-10T12:00:30Z"), 100);
+        assert!(jobs.is_empty());
+
+        let task =
+            cleanup_task("all", Schedule::every_minute()).with_catch_up(CatchUpPolicy::RunAll);
+        let (jobs, _) = task.catch_up_jobs(last_run, now, 100);
+        assert_eq!(jobs.len(), 5);
+        let keys: std::collections::HashSet<_> = jobs.iter().map(|j| j.dedup_key.clone()).collect();
+        assert_eq!(keys.len(), 5);
+    }
+
+    #[test]
+    fn test_run_all_is_bounded() {
+        let task =
+            cleanup_task("all", Schedule::every_minute()).with_catch_up(CatchUpPolicy::RunAll);
+        let last_run = at("2024-01-01T00:00:00Z");
+        let now = at("2024-01-31T00:00:30Z");
+
+        let (jobs, next_run) = task.catch_up_jobs(last_run, now, 10);
+        assert_eq!(jobs.len(), 10);
+        assert_eq!(next_run, at("2024-01-31T00:01:30Z"));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_catch_up_after_restart() {
+        let queue = crate::queue::tests::test_queue(Default::default()).await;
+        let name = format!("test:{}", uuid::Uuid::now_v7());
+        let scheduler = Scheduler::new(queue.clone()).with_max_catch_up_runs(3);
+        scheduler.schedule(
+            cleanup_task(&name, Schedule::every_minute()).with_catch_up(CatchUpPolicy::RunAll),
+        );
+
+        // Nothing recorded yet, so nothing was missed
+        assert_eq!(scheduler.catch_up().await.unwrap(), 0);
+
+        scheduler
+            .record_run(&name, Utc::now() - Duration::minutes(10))
+            .await
+            .unwrap();
+        assert_eq!(scheduler.catch_up().await.unwrap(), 3);
+        // Catch-up moved the recorded run forward
+        assert_eq!(scheduler.catch_up().await.unwrap(), 0);
+    }
 }
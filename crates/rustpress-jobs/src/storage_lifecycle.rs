@@ -0,0 +1,262 @@
+//! Scheduled storage lifecycle runs.
+//!
+//! [`PgAccessLog`] keeps the access times [`Storage`](rustpress_storage::Storage)
+//! records in the `storage_objects` table, and [`StorageLifecycleHandler`]
+//! runs a [`LifecycleManager`] over them whenever a [`StorageLifecycleJob`]
+//! comes up, moving idle media to colder storage classes.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rustpress_core::error::{Error, Result};
+use rustpress_storage::{AccessLog, AccessRecord, LifecycleManager, StorageClass, StoredFile};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::job::{JobHandler, JobPayload};
+
+/// Reads within this many minutes of the last recorded one are not written,
+/// so popular files don't cost a database write per request
+const ACCESS_WRITE_INTERVAL_MINUTES: i64 = 60;
+
+/// Every storage class, hottest first
+const STORAGE_CLASSES: [StorageClass; 3] = [
+    StorageClass::Standard,
+    StorageClass::InfrequentAccess,
+    StorageClass::Archive,
+];
+
+/// Access log stored in the `storage_objects` table
+pub struct PgAccessLog {
+    pool: PgPool,
+}
+
+impl PgAccessLog {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct StorageObjectRow {
+    path: String,
+    storage_class: String,
+    size: i64,
+    created_at: DateTime<Utc>,
+    last_accessed_at: Option<DateTime<Utc>>,
+}
+
+impl From<StorageObjectRow> for AccessRecord {
+    fn from(row: StorageObjectRow) -> Self {
+        Self {
+            path: row.path,
+            storage_class: StorageClass::parse(&row.storage_class).unwrap_or_default(),
+            size: row.size.max(0) as u64,
+            created_at: row.created_at,
+            last_accessed_at: row.last_accessed_at,
+        }
+    }
+}
+
+fn db_error(action: &str, e: sqlx::Error) -> Error {
+    Error::database(format!("Failed to {}: {}", action, e))
+}
+
+#[async_trait]
+impl AccessLog for PgAccessLog {
+    async fn record_upload(&self, file: &StoredFile) -> Result<()> {
+        // An upload over an existing path starts the object's life again
+        sqlx::query(
+            r#"
+            INSERT INTO storage_objects (path, storage_class, size, created_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (path) DO UPDATE
+            SET storage_class = EXCLUDED.storage_class,
+                size = EXCLUDED.size,
+                created_at = EXCLUDED.created_at,
+                last_accessed_at = NULL
+            "#,
+        )
+        .bind(&file.path)
+        .bind(file.storage_class.as_str())
+        .bind(file.size as i64)
+        .bind(file.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| db_error("record upload", e))?;
+        Ok(())
+    }
+
+    async fn record_access(&self, path: &str, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE storage_objects
+            SET last_accessed_at = $2
+            WHERE path = $1
+              AND (last_accessed_at IS NULL OR last_accessed_at < $2 - make_interval(mins => $3))
+            "#,
+        )
+        .bind(path)
+        .bind(at)
+        .bind(ACCESS_WRITE_INTERVAL_MINUTES as i32)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| db_error("record file access", e))?;
+        Ok(())
+    }
+
+    async fn get(&self, path: &str) -> Result<Option<AccessRecord>> {
+        let row = sqlx::query_as::<_, StorageObjectRow>(
+            r#"
+            SELECT path, storage_class, size, created_at, last_accessed_at
+            FROM storage_objects
+            WHERE path = $1
+            "#,
+        )
+        .bind(path)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| db_error("load storage object", e))?;
+        Ok(row.map(Into::into))
+    }
+
+    async fn cold_candidates(
+        &self,
+        class: StorageClass,
+        created_before: DateTime<Utc>,
+        idle_since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<AccessRecord>> {
+        let warmer: Vec<&str> = STORAGE_CLASSES
+            .iter()
+            .filter(|c| **c < class)
+            .map(|c| c.as_str())
+            .collect();
+
+        let rows = sqlx::query_as::<_, StorageObjectRow>(
+            r#"
+            SELECT path, storage_class, size, created_at, last_accessed_at
+            FROM storage_objects
+            WHERE storage_class = ANY($1)
+              AND created_at < $2
+              AND COALESCE(last_accessed_at, created_at) < $3
+            ORDER BY COALESCE(last_accessed_at, created_at)
+            LIMIT $4
+            "#,
+        )
+        .bind(&warmer)
+        .bind(created_before)
+        .bind(idle_since)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| db_error("find cold storage objects", e))?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn set_storage_class(&self, path: &str, class: StorageClass) -> Result<()> {
+        sqlx::query("UPDATE storage_objects SET storage_class = $2 WHERE path = $1")
+            .bind(path)
+            .bind(class.as_str())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| db_error("update storage class", e))?;
+        Ok(())
+    }
+
+    async fn remove(&self, path: &str) -> Result<()> {
+        sqlx::query("DELETE FROM storage_objects WHERE path = $1")
+            .bind(path)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| db_error("forget storage object", e))?;
+        Ok(())
+    }
+}
+
+/// Move idle media to colder storage classes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageLifecycleJob {}
+
+impl JobPayload for StorageLifecycleJob {
+    fn job_type() -> &'static str {
+        "storage_lifecycle"
+    }
+
+    fn queue() -> &'static str {
+        "maintenance"
+    }
+
+    fn max_attempts() -> u32 {
+        3
+    }
+
+    fn timeout_secs() -> u64 {
+        1800 // 30 minutes
+    }
+}
+
+/// Handler running the lifecycle rules for [`StorageLifecycleJob`]s
+pub struct StorageLifecycleHandler {
+    manager: Arc<LifecycleManager>,
+}
+
+impl StorageLifecycleHandler {
+    pub fn new(manager: Arc<LifecycleManager>) -> Self {
+        Self { manager }
+    }
+}
+
+#[async_trait]
+impl JobHandler for StorageLifecycleHandler {
+    type Payload = StorageLifecycleJob;
+
+    async fn handle(&self, _payload: Self::Payload) -> Result<()> {
+        let report = self.manager.run().await?;
+        info!(
+            transitioned = report.transitioned.len(),
+            failed = report.failed.len(),
+            missing = report.missing.len(),
+            "Storage lifecycle run finished"
+        );
+        Ok(())
+    }
+
+    async fn failed(&self, _payload: Self::Payload, error: &str) -> Result<()> {
+        error!(error, "Storage lifecycle run failed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_object_row_into_record() {
+        let now = Utc::now();
+        let record: AccessRecord = StorageObjectRow {
+            path: "2024/01/a.jpg".to_string(),
+            storage_class: "infrequent_access".to_string(),
+            size: 42,
+            created_at: now,
+            last_accessed_at: None,
+        }
+        .into();
+        assert_eq!(record.storage_class, StorageClass::InfrequentAccess);
+        assert_eq!(record.size, 42);
+        assert_eq!(record.last_used(), now);
+
+        let unknown: AccessRecord = StorageObjectRow {
+            path: "b".to_string(),
+            storage_class: "deep_glacier".to_string(),
+            size: -1,
+            created_at: now,
+            last_accessed_at: Some(now),
+        }
+        .into();
+        assert_eq!(unknown.storage_class, StorageClass::Standard);
+        assert_eq!(unknown.size, 0);
+    }
+}
@@ -4,12 +4,17 @@ use crate::job::{Job, JobHandler, JobPayload};
 use crate::queue::{JobQueue, Queue};
 use async_trait::async_trait;
 use dashmap::DashMap;
+use futures::FutureExt;
 use parking_lot::RwLock;
+use rustpress_core::correlation;
 use rustpress_core::error::{Error, Result};
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Semaphore;
+use tracing::Instrument;
 #[allow(unused_imports)]
 use uuid::Uuid;
 
@@ -166,50 +171,80 @@ impl Worker {
         // Find handler
         let handler = handlers.get(&job_type).map(|h| h.clone());
 
-        match handler {
-            Some(handler) => {
-                // Process with timeout
-                let timeout = Duration::from_secs(job.timeout_secs);
-                let result = tokio::time::timeout(timeout, handler.handle_job(&job)).await;
-
-                match result {
-                    Ok(Ok(())) => {
-                        queue.complete(job_id).await?;
-                    }
-                    Ok(Err(e)) => {
-                        let error = e.to_string();
-                        if job.can_retry() {
-                            // Retry with exponential backoff
-                            let delay = 60 * (2_u64.pow(job.attempts - 1));
-                            queue.release(job_id, delay).await?;
-                        } else {
-                            queue.fail(job_id, &error).await?;
-                        }
-                    }
-                    Err(_) => {
-                        let error = "Job timed out";
-                        if job.can_retry() {
-                            queue.release(job_id, 60).await?;
-                        } else {
-                            queue.fail(job_id, error).await?;
-                        }
-                    }
-                }
+        let Some(handler) = handler else {
+            let error = format!("No handler registered for job type: {}", job_type);
+            return queue.fail(job_id, &error).await;
+        };
+
+        match Self::run_handler(handler.as_ref(), &job).await {
+            Ok(()) => {
+                queue.complete(job_id).await?;
             }
-            None => {
-                let error = format!("No handler registered for job type: {}", job_type);
+            Err(error) if job.can_retry() => {
+                // Retry with exponential backoff
+                let delay = queue.config().backoff_delay(job.attempts);
+                queue.release_failed(job_id, delay, &error).await?;
+            }
+            Err(error) => {
                 queue.fail(job_id, &error).await?;
+                if let Err(e) = handler.job_failed(&job, &error).await {
+                    tracing::error!(job_id = %job_id, error = %e, "Job failure hook failed");
+                }
             }
         }
 
         Ok(())
     }
+
+    /// Run a job's handler with its timeout. Errors, timeouts and panics
+    /// all come back as an error message so they count as a failed attempt.
+    async fn run_handler(
+        handler: &dyn JobHandlerDyn,
+        job: &Job,
+    ) -> std::result::Result<(), String> {
+        let timeout = Duration::from_secs(job.timeout_secs);
+        let run = AssertUnwindSafe(handler.handle_job(job)).catch_unwind();
+
+        // Run as part of the request that queued the job, if any, so its
+        // logs and outgoing calls carry that request's ID
+        let span = tracing::info_span!(
+            "job",
+            job_id = %job.id,
+            job_type = %job.job_type,
+            request_id = job.request_id.as_deref().unwrap_or_default(),
+        );
+        let run = tokio::time::timeout(timeout, run).instrument(span);
+        let result = match job.request_id.clone() {
+            Some(request_id) => correlation::scope(request_id, run).await,
+            None => run.await,
+        };
+
+        match result {
+            Ok(Ok(Ok(()))) => Ok(()),
+            Ok(Ok(Err(e))) => Err(e.to_string()),
+            Ok(Err(panic)) => Err(format!("Job panicked: {}", panic_message(&*panic))),
+            Err(_) => Err("Job timed out".to_string()),
+        }
+    }
+}
+
+/// Message carried by a panic payload
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
 }
 
 /// Dynamic job handler trait for type erasure
 #[async_trait]
 trait JobHandlerDyn: Send + Sync {
     async fn handle_job(&self, job: &Job) -> Result<()>;
+
+    async fn job_failed(&self, job: &Job, error: &str) -> Result<()>;
 }
 
 /// Typed handler wrapper
@@ -231,6 +266,11 @@ where
         let payload: P = job.payload()?;
         self.handler.handle(payload).await
     }
+
+    async fn job_failed(&self, job: &Job, error: &str) -> Result<()> {
+        let payload: P = job.payload()?;
+        self.handler.failed(payload, error).await
+    }
 }
 
 /// Worker pool for managing multiple workers
@@ -316,4 +356,66 @@ mod tests {
         assert_eq!(config.concurrency, 4);
         assert!(config.queues.contains(&"default".to_string()));
     }
+
+    struct PanickingHandler;
+
+    #[async_trait]
+    impl JobHandler for PanickingHandler {
+        type Payload = crate::job::jobs::CleanupJob;
+
+        async fn handle(&self, payload: Self::Payload) -> Result<()> {
+            panic!("cannot clean {}", payload.cleanup_type);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_panicking_handler_is_a_failure() {
+        let handler = TypedHandler {
+            handler: PanickingHandler,
+        };
+        let job = Job::new(crate::job::jobs::CleanupJob {
+            cleanup_type: "sessions".to_string(),
+            older_than_days: 1,
+        });
+
+        let result = Worker::run_handler(&handler, &job).await;
+        assert_eq!(
+            result,
+            Err("Job panicked: cannot clean sessions".to_string())
+        );
+    }
+
+    struct RequestIdHandler;
+
+    #[async_trait]
+    impl JobHandler for RequestIdHandler {
+        type Payload = crate::job::jobs::CleanupJob;
+
+        async fn handle(&self, _payload: Self::Payload) -> Result<()> {
+            match correlation::current_request_id().as_deref() {
+                Some("req-42") => Ok(()),
+                other => Err(Error::internal(format!(
+                    "unexpected request id {:?}",
+                    other
+                ))),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_job_runs_under_queuing_request_id() {
+        let handler = TypedHandler {
+            handler: RequestIdHandler,
+        };
+        let job = correlation::scope("req-42".to_string(), async {
+            Job::new(crate::job::jobs::CleanupJob {
+                cleanup_type: "sessions".to_string(),
+                older_than_days: 1,
+            })
+        })
+        .await;
+        assert_eq!(job.request_id.as_deref(), Some("req-42"));
+
+        assert_eq!(Worker::run_handler(&handler, &job).await, Ok(()));
+    }
 }
@@ -0,0 +1,244 @@
+//! Alt text suggestions
+//!
+//! Provides a pluggable alt text generation step for images:
+//! - Generators run in the background after upload, never on the upload path
+//! - Suggestions are stored in metadata as `alt_text_suggested`
+//! - The human-facing `alt_text` is only filled in when configured, and only
+//!   while it is still empty
+//! - Generator errors and timeouts are logged and otherwise ignored
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::{MediaError, MediaResult};
+
+/// Default time limit for generating a single suggestion
+pub const DEFAULT_ALT_TEXT_TIMEOUT_SECS: u64 = 60;
+
+/// Metadata key holding the generated suggestion
+pub const ALT_TEXT_SUGGESTED_KEY: &str = "alt_text_suggested";
+
+/// Longest suggestion kept; anything longer is cut at a word boundary
+pub const MAX_ALT_TEXT_LEN: usize = 250;
+
+/// Generator suggesting alt text for images, e.g. a vision model
+/// integration.
+///
+/// `suggest` is called on the blocking thread pool, so implementations may
+/// block on HTTP requests or subprocesses. A call that outlives the timeout
+/// is abandoned rather than interrupted, so implementations should bound
+/// their own IO as well.
+pub trait AltTextGenerator: Send + Sync {
+    /// Generator name used in logs
+    fn name(&self) -> &str;
+
+    /// Suggest alt text for the image at `path`, or `None` if there is
+    /// nothing useful to say
+    fn suggest(&self, path: &Path, mime_type: &str) -> MediaResult<Option<String>>;
+}
+
+/// Generator that never suggests anything, used when none is configured
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopAltTextGenerator;
+
+impl AltTextGenerator for NoopAltTextGenerator {
+    fn name(&self) -> &str {
+        "noop"
+    }
+
+    fn suggest(&self, _path: &Path, _mime_type: &str) -> MediaResult<Option<String>> {
+        Ok(None)
+    }
+}
+
+/// Result of suggesting alt text for one image
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AltTextSuggestion {
+    pub media_id: Uuid,
+    /// The stored suggestion, if the generator produced one
+    pub suggestion: Option<String>,
+    /// Whether the suggestion was also copied into `alt_text`
+    pub applied: bool,
+}
+
+/// Generate alt text for the image at `path` with a time limit.
+///
+/// The suggestion is tidied with [`normalize_alt_text`].
+pub async fn generate_alt_text(
+    generator: Arc<dyn AltTextGenerator>,
+    path: PathBuf,
+    mime_type: String,
+    timeout: Duration,
+) -> MediaResult<Option<String>> {
+    let name = generator.name().to_string();
+    let task = tokio::task::spawn_blocking(move || generator.suggest(&path, &mime_type));
+
+    match tokio::time::timeout(timeout, task).await {
+        Ok(Ok(Ok(suggestion))) => Ok(suggestion.as_deref().and_then(normalize_alt_text)),
+        Ok(Ok(Err(e))) => Err(e),
+        Ok(Err(e)) => Err(MediaError::ProcessingError(format!(
+            "Alt text generation by {} failed: {}",
+            name, e
+        ))),
+        Err(_) => Err(MediaError::ProcessingError(format!(
+            "Alt text generation by {} timed out after {}s",
+            name,
+            timeout.as_secs_f64()
+        ))),
+    }
+}
+
+/// Store a suggestion for `media_id`.
+///
+/// With `apply` set the suggestion also becomes the image's `alt_text`,
+/// unless someone has already written one.
+pub async fn store_alt_text_suggestion(
+    pool: &PgPool,
+    media_id: Uuid,
+    suggestion: &str,
+    apply: bool,
+) -> MediaResult<AltTextSuggestion> {
+    let applied: Option<bool> = sqlx::query_scalar(
+        r#"
+        WITH previous AS (SELECT alt_text FROM media_items WHERE id = $1)
+        UPDATE media_items
+        SET metadata = metadata || jsonb_build_object('alt_text_suggested', $2::text),
+            alt_text = CASE WHEN $3 AND media_items.alt_text = '' THEN $2 ELSE media_items.alt_text END,
+            updated_at = NOW()
+        FROM previous
+        WHERE media_items.id = $1
+        RETURNING $3 AND previous.alt_text = ''
+        "#,
+    )
+    .bind(media_id)
+    .bind(suggestion)
+    .bind(apply)
+    .fetch_optional(pool)
+    .await?;
+
+    let applied = applied.ok_or(MediaError::NotFound(media_id))?;
+    Ok(AltTextSuggestion {
+        media_id,
+        suggestion: Some(suggestion.to_string()),
+        applied,
+    })
+}
+
+/// Tidy generated alt text: collapse whitespace, drop wrapping quotes and
+/// "image of" style prefixes, and cap the length at [`MAX_ALT_TEXT_LEN`].
+///
+/// Returns `None` when nothing is left.
+pub fn normalize_alt_text(text: &str) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut text = text.trim_matches(|c| c == '"' || c == '\'').trim();
+
+    for prefix in ["image of ", "a picture of ", "picture of ", "photo of "] {
+        if text
+            .get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+        {
+            text = text[prefix.len()..].trim_start();
+            break;
+        }
+    }
+
+    if text.is_empty() {
+        return None;
+    }
+
+    if text.chars().count() <= MAX_ALT_TEXT_LEN {
+        return Some(capitalize(text));
+    }
+
+    let cut: String = text.chars().take(MAX_ALT_TEXT_LEN).collect();
+    let cut = match cut.rfind(' ') {
+        Some(space) => &cut[..space],
+        None => cut.as_str(),
+    };
+    Some(capitalize(cut.trim_end_matches([',', ';', ':', ' '])))
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedGenerator(Option<&'static str>, Duration);
+
+    impl AltTextGenerator for FixedGenerator {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        fn suggest(&self, _path: &Path, _mime_type: &str) -> MediaResult<Option<String>> {
+            std::thread::sleep(self.1);
+            match self.0 {
+                Some("error") => Err(MediaError::ProcessingError("model unavailable".into())),
+                other => Ok(other.map(str::to_string)),
+            }
+        }
+    }
+
+    fn generator(result: Option<&'static str>, delay_ms: u64) -> Arc<dyn AltTextGenerator> {
+        Arc::new(FixedGenerator(result, Duration::from_millis(delay_ms)))
+    }
+
+    const TIMEOUT: Duration = Duration::from_millis(200);
+
+    async fn generate(generator: Arc<dyn AltTextGenerator>) -> MediaResult<Option<String>> {
+        generate_alt_text(
+            generator,
+            PathBuf::from("photo.jpg"),
+            "image/jpeg".to_string(),
+            TIMEOUT,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_generate_normalizes_suggestion() {
+        let suggestion = generate(generator(Some("  \"image of a red  bicycle\" "), 0))
+            .await
+            .unwrap();
+        assert_eq!(suggestion.as_deref(), Some("A red bicycle"));
+
+        assert_eq!(
+            generate(Arc::new(NoopAltTextGenerator)).await.unwrap(),
+            None
+        );
+        assert_eq!(generate(generator(Some("   "), 0)).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_generator_failures_are_errors() {
+        let result = generate(generator(Some("error"), 0)).await;
+        assert!(
+            matches!(result, Err(MediaError::ProcessingError(msg)) if msg.contains("model unavailable"))
+        );
+
+        let result = generate(generator(Some("late"), 1000)).await;
+        assert!(
+            matches!(result, Err(MediaError::ProcessingError(msg)) if msg.contains("timed out"))
+        );
+    }
+
+    #[test]
+    fn test_long_suggestions_cut_at_word_boundary() {
+        let long = "word, ".repeat(100);
+        let text = normalize_alt_text(&long).unwrap();
+        assert!(text.chars().count() <= MAX_ALT_TEXT_LEN);
+        assert!(text.ends_with("word"));
+        assert!(text.starts_with("Word"));
+    }
+}
@@ -0,0 +1,260 @@
+//! Document handling
+//!
+//! Provides document functionality including:
+//! - PDF page count and encryption detection
+//! - First-page PDF thumbnail rendering (via poppler-utils)
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::{MediaError, MediaResult};
+
+/// Default resolution for rendering PDF thumbnails
+pub const DEFAULT_PDF_THUMBNAIL_DPI: u32 = 72;
+
+/// Resolution bounds, so a misconfigured DPI cannot produce huge bitmaps
+const MIN_PDF_THUMBNAIL_DPI: u32 = 18;
+const MAX_PDF_THUMBNAIL_DPI: u32 = 300;
+
+/// Default time limit for each poppler invocation
+const DEFAULT_PDF_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How often a running tool is checked for completion
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Check whether upload data is a PDF
+pub fn is_pdf(content_type: &str, data: &[u8]) -> bool {
+    content_type == "application/pdf" && data.starts_with(b"%PDF-")
+}
+
+/// Properties reported by `pdfinfo`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PdfInfo {
+    pub page_count: u32,
+    pub encrypted: bool,
+}
+
+impl PdfInfo {
+    /// Parse `pdfinfo` output
+    fn parse(output: &str) -> Option<Self> {
+        let mut page_count = None;
+        let mut encrypted = false;
+
+        for line in output.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            match key.trim() {
+                "Pages" => page_count = value.trim().parse().ok(),
+                "Encrypted" => encrypted = value.trim().starts_with("yes"),
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            page_count: page_count?,
+            encrypted,
+        })
+    }
+}
+
+/// Renders PDF thumbnails with `pdfinfo` and `pdftoppm`.
+///
+/// Every invocation runs on the blocking thread pool and is killed once it
+/// exceeds the timeout, so huge or hostile PDFs cannot stall an upload.
+#[derive(Debug, Clone)]
+pub struct PdfThumbnailer {
+    pdfinfo_path: String,
+    pdftoppm_path: String,
+    dpi: u32,
+    timeout: Duration,
+}
+
+impl Default for PdfThumbnailer {
+    fn default() -> Self {
+        Self::new(DEFAULT_PDF_THUMBNAIL_DPI)
+    }
+}
+
+impl PdfThumbnailer {
+    pub fn new(dpi: u32) -> Self {
+        Self {
+            pdfinfo_path: "pdfinfo".to_string(),
+            pdftoppm_path: "pdftoppm".to_string(),
+            dpi: dpi.clamp(MIN_PDF_THUMBNAIL_DPI, MAX_PDF_THUMBNAIL_DPI),
+            timeout: DEFAULT_PDF_TIMEOUT,
+        }
+    }
+
+    /// Use specific poppler binaries
+    pub fn with_tools(mut self, pdfinfo: impl Into<String>, pdftoppm: impl Into<String>) -> Self {
+        self.pdfinfo_path = pdfinfo.into();
+        self.pdftoppm_path = pdftoppm.into();
+        self
+    }
+
+    /// Set the time limit for each tool invocation
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Read the page count and encryption status of a PDF
+    pub async fn info(&self, pdf: &Path) -> MediaResult<PdfInfo> {
+        let mut command = Command::new(&self.pdfinfo_path);
+        command.arg(pdf);
+        let output = run_blocking(command, self.timeout).await?;
+
+        PdfInfo::parse(&String::from_utf8_lossy(&output.stdout))
+            .ok_or_else(|| MediaError::ProcessingError("pdfinfo reported no page count".into()))
+    }
+
+    /// Render the first page of a PDF to PNG data
+    pub async fn render_first_page(&self, pdf: &Path) -> MediaResult<Vec<u8>> {
+        let prefix = std::env::temp_dir().join(format!("rustpress-pdf-{}", Uuid::new_v4()));
+        let png_path = prefix.with_extension("png");
+
+        let mut command = Command::new(&self.pdftoppm_path);
+        command.args(self.render_args(pdf, &prefix));
+        let result = run_blocking(command, self.timeout).await;
+
+        let data = match result {
+            Ok(_) => tokio::fs::read(&png_path).await.map_err(MediaError::from),
+            Err(e) => Err(e),
+        };
+        let _ = tokio::fs::remove_file(&png_path).await;
+        data
+    }
+
+    fn render_args(&self, pdf: &Path, prefix: &Path) -> Vec<String> {
+        vec![
+            "-f".into(),
+            "1".into(),
+            "-l".into(),
+            "1".into(),
+            "-r".into(),
+            self.dpi.to_string(),
+            "-png".into(),
+            "-singlefile".into(),
+            pdf.display().to_string(),
+            prefix.display().to_string(),
+        ]
+    }
+}
+
+/// Run a command on the blocking thread pool, killing it after `timeout`
+async fn run_blocking(command: Command, timeout: Duration) -> MediaResult<Output> {
+    tokio::task::spawn_blocking(move || run_with_timeout(command, timeout))
+        .await
+        .map_err(|e| MediaError::ProcessingError(format!("PDF task failed: {}", e)))?
+}
+
+fn run_with_timeout(mut command: Command, timeout: Duration) -> MediaResult<Output> {
+    let program = PathBuf::from(command.get_program());
+    let program = program.display();
+
+    // Output is only read once the tool exits, so it must stay small:
+    // rendered pages go to a file rather than stdout
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| MediaError::ProcessingError(format!("Failed to run {}: {}", program, e)))?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if child.try_wait()?.is_some() {
+            break;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(MediaError::ProcessingError(format!(
+                "{} timed out after {}s",
+                program,
+                timeout.as_secs_f64()
+            )));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(MediaError::ProcessingError(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_pdf() {
+        assert!(is_pdf("application/pdf", b"%PDF-1.7\n"));
+        assert!(!is_pdf("application/pdf", b"<html>"));
+        assert!(!is_pdf("text/plain", b"%PDF-1.7\n"));
+    }
+
+    #[test]
+    fn test_parse_pdfinfo() {
+        let output = "Title:          Report\nPages:          12\nEncrypted:      no\nPage size:      612 x 792 pts (letter)\n";
+        assert_eq!(
+            PdfInfo::parse(output),
+            Some(PdfInfo {
+                page_count: 12,
+                encrypted: false
+            })
+        );
+
+        let encrypted = "Pages: 3\nEncrypted: yes (print:yes copy:no change:no addNotes:no)\n";
+        assert!(PdfInfo::parse(encrypted).unwrap().encrypted);
+
+        assert_eq!(
+            PdfInfo::parse("Syntax Error: Couldn't find trailer\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_render_args_clamp_dpi() {
+        let args = PdfThumbnailer::new(10_000).render_args(Path::new("a.pdf"), Path::new("out"));
+        assert!(args.windows(2).any(|w| w == ["-r", "300"]));
+        assert!(args.windows(4).any(|w| w == ["-f", "1", "-l", "1"]));
+        assert_eq!(args.last().map(String::as_str), Some("out"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_blocking_times_out() {
+        let mut command = Command::new("sleep");
+        command.arg("5");
+
+        let started = Instant::now();
+        let result = run_blocking(command, Duration::from_millis(100)).await;
+        assert!(
+            matches!(result, Err(MediaError::ProcessingError(msg)) if msg.contains("timed out"))
+        );
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_missing_tool_is_an_error() {
+        let thumbnailer =
+            PdfThumbnailer::default().with_tools("rustpress-no-pdfinfo", "rustpress-no-pdftoppm");
+        assert!(thumbnailer.info(Path::new("a.pdf")).await.is_err());
+        assert!(thumbnailer
+            .render_first_page(Path::new("a.pdf"))
+            .await
+            .is_err());
+    }
+}
@@ -5,12 +5,21 @@
 //! - Resize
 //! - Rotate/Flip
 //! - Filters and adjustments
+//! - Non-destructive edit stacks with revert and flatten
+//! - Focal-point-aware smart cropping
 
+use chrono::{DateTime, Utc};
 use image::{DynamicImage, GenericImageView, ImageFormat, Rgba};
 use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
 use std::io::Cursor;
+use std::path::Path;
+use tokio::fs;
+use uuid::Uuid;
 
-use crate::{MediaError, MediaResult};
+use crate::image_optimizer::{ImageOptimizer, OptimizationConfig};
+use crate::upload::THUMBNAIL_SIZE;
+use crate::{MediaConfig, MediaError, MediaItem, MediaResult, MediaType};
 
 /// Image editor
 pub struct ImageEditor {
@@ -60,6 +69,19 @@ impl ImageEditor {
         self.crop(x, y, crop_w, crop_h)
     }
 
+    /// Crop to the target aspect ratio keeping the focal point in frame,
+    /// then scale down to the target size (smaller crops are not upscaled)
+    pub fn smart_crop(&mut self, width: u32, height: u32, focal_point: FocalPoint) -> &mut Self {
+        let (w, h) = self.dimensions();
+        let (x, y, crop_w, crop_h) = focal_point.crop_rect(w, h, width, height);
+        self.crop(x, y, crop_w, crop_h);
+
+        if crop_w > width && crop_h > height {
+            self.resize_exact(width, height);
+        }
+        self
+    }
+
     /// Resize image maintaining aspect ratio
     pub fn resize(&mut self, max_width: u32, max_height: u32) -> &mut Self {
         self.image =
@@ -294,7 +316,7 @@ impl ImageEditor {
 }
 
 /// Filter presets
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum FilterPreset {
     Original,
@@ -307,6 +329,81 @@ pub enum FilterPreset {
     Muted,
 }
 
+/// Point of interest kept in frame when cropping, as fractions of the image
+/// size (0.0 = left/top, 1.0 = right/bottom)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FocalPoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Default for FocalPoint {
+    fn default() -> Self {
+        Self::center()
+    }
+}
+
+impl FocalPoint {
+    /// Create a focal point, clamping coordinates into 0..1
+    pub fn new(x: f32, y: f32) -> Self {
+        let clamp = |v: f32| if v.is_nan() { 0.5 } else { v.clamp(0.0, 1.0) };
+        Self {
+            x: clamp(x),
+            y: clamp(y),
+        }
+    }
+
+    pub fn center() -> Self {
+        Self { x: 0.5, y: 0.5 }
+    }
+
+    /// Read the focal point stored in media metadata
+    pub fn from_metadata(metadata: &serde_json::Value) -> Option<Self> {
+        let point: Self = serde_json::from_value(metadata.get("focal_point")?.clone()).ok()?;
+        Some(Self::new(point.x, point.y))
+    }
+
+    /// Largest crop of the target aspect ratio that fits inside the source,
+    /// centered on the focal point as far as the image edges allow.
+    ///
+    /// Returns `(x, y, width, height)`.
+    pub fn crop_rect(
+        &self,
+        source_w: u32,
+        source_h: u32,
+        target_w: u32,
+        target_h: u32,
+    ) -> (u32, u32, u32, u32) {
+        if source_w == 0 || source_h == 0 || target_w == 0 || target_h == 0 {
+            return (0, 0, source_w, source_h);
+        }
+
+        let target_ratio = target_w as f64 / target_h as f64;
+        let (crop_w, crop_h) = if source_w as f64 / source_h as f64 > target_ratio {
+            // Source is wider, crop width
+            let w = (source_h as f64 * target_ratio).round() as u32;
+            (w.clamp(1, source_w), source_h)
+        } else {
+            // Source is taller, crop height
+            let h = (source_w as f64 / target_ratio).round() as u32;
+            (source_w, h.clamp(1, source_h))
+        };
+
+        let point = Self::new(self.x, self.y);
+        let offset = |source: u32, crop: u32, focus: f32| {
+            let start = focus as f64 * source as f64 - crop as f64 / 2.0;
+            start.round().clamp(0.0, (source - crop) as f64) as u32
+        };
+
+        (
+            offset(source_w, crop_w, point.x),
+            offset(source_h, crop_h, point.y),
+            crop_w,
+            crop_h,
+        )
+    }
+}
+
 /// Watermark position
 #[derive(Debug, Clone, Copy)]
 pub enum WatermarkPosition {
@@ -319,7 +416,7 @@ pub enum WatermarkPosition {
 }
 
 /// Edit operations for batch processing
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum EditOperation {
     Crop {
@@ -331,6 +428,12 @@ pub enum EditOperation {
     CropToAspect {
         ratio: f64,
     },
+    SmartCrop {
+        width: u32,
+        height: u32,
+        #[serde(default)]
+        focal_point: FocalPoint,
+    },
     Resize {
         width: u32,
         height: u32,
@@ -375,6 +478,28 @@ pub enum EditOperation {
 }
 
 impl EditOperation {
+    /// Reject operations that would produce an empty image
+    pub fn validate(&self) -> MediaResult<()> {
+        let valid = match self {
+            EditOperation::Crop { width, height, .. }
+            | EditOperation::SmartCrop { width, height, .. }
+            | EditOperation::Resize { width, height }
+            | EditOperation::ResizeExact { width, height } => *width > 0 && *height > 0,
+            EditOperation::CropToAspect { ratio } => ratio.is_finite() && *ratio > 0.0,
+            EditOperation::Scale { percent } => percent.is_finite() && *percent > 0.0,
+            _ => true,
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(MediaError::ProcessingError(format!(
+                "Invalid edit operation: {}",
+                serde_json::to_string(self).unwrap_or_default()
+            )))
+        }
+    }
+
     /// Apply operation to editor
     pub fn apply(&self, editor: &mut ImageEditor) {
         match self {
@@ -389,6 +514,13 @@ impl EditOperation {
             EditOperation::CropToAspect { ratio } => {
                 editor.crop_to_aspect(*ratio);
             }
+            EditOperation::SmartCrop {
+                width,
+                height,
+                focal_point,
+            } => {
+                editor.smart_crop(*width, *height, *focal_point);
+            }
             EditOperation::Resize { width, height } => {
                 editor.resize(*width, *height);
             }
@@ -458,6 +590,386 @@ pub fn apply_operations(data: &[u8], operations: &[EditOperation]) -> MediaResul
     editor.to_jpeg(85)
 }
 
+/// Non-destructive edit history of a media item, stored in
+/// `metadata["edits"]`.
+///
+/// The operations are always replayed against the untouched original, so
+/// the same stack renders the same image every time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EditStack {
+    /// Operations applied to the original, oldest first
+    #[serde(default)]
+    pub operations: Vec<EditOperation>,
+    /// Current rendering of the stack, if it is not empty
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rendered: Option<RenderedEdit>,
+    /// Originals replaced by flattening, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flattened: Vec<FlattenedOriginal>,
+}
+
+/// Derived image produced by replaying an edit stack
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenderedEdit {
+    pub path: String,
+    pub url: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// An original that was superseded when its edit stack was flattened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlattenedOriginal {
+    pub path: String,
+    pub url: String,
+    pub operations: Vec<EditOperation>,
+    pub flattened_at: DateTime<Utc>,
+}
+
+impl EditStack {
+    /// Read the stack from media metadata, empty if none was recorded
+    pub fn from_metadata(metadata: &serde_json::Value) -> Self {
+        metadata
+            .get("edits")
+            .and_then(|edits| serde_json::from_value(edits.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Drop the last `steps` operations, returning how many were removed
+    pub fn revert(&mut self, steps: usize) -> usize {
+        let steps = steps.min(self.operations.len());
+        self.operations.truncate(self.operations.len() - steps);
+        steps
+    }
+
+    /// Short key identifying the operations, used to name renderings
+    pub fn key(&self) -> String {
+        let json = serde_json::to_vec(&self.operations).unwrap_or_default();
+        rustpress_storage::dedup::content_hash(&json)[..12].to_string()
+    }
+
+    /// Replay the operations against the original image data
+    pub fn render(&self, original: &[u8]) -> MediaResult<ImageEditor> {
+        let mut editor = ImageEditor::from_bytes(original)?;
+        for op in &self.operations {
+            op.apply(&mut editor);
+        }
+        Ok(editor)
+    }
+}
+
+/// Encoding for renderings as (format, extension, MIME type), keeping the
+/// original's format where possible
+fn render_format(mime_type: &str) -> (ImageFormat, &'static str, &'static str) {
+    match mime_type {
+        "image/png" => (ImageFormat::Png, "png", "image/png"),
+        "image/webp" => (ImageFormat::WebP, "webp", "image/webp"),
+        "image/gif" => (ImageFormat::Gif, "gif", "image/gif"),
+        _ => (ImageFormat::Jpeg, "jpg", "image/jpeg"),
+    }
+}
+
+/// Applies, reverts and flattens non-destructive edits of stored images.
+///
+/// Original files are never modified: edits are rendered into an `edited`
+/// variant next to the original.
+pub struct EditorService {
+    pool: PgPool,
+    config: MediaConfig,
+}
+
+impl EditorService {
+    pub fn new(pool: PgPool, config: MediaConfig) -> Self {
+        Self { pool, config }
+    }
+
+    /// Get the edit stack of a media item
+    pub async fn edit_stack(&self, media_id: Uuid) -> MediaResult<EditStack> {
+        let media = self.get_image(media_id).await?;
+        Ok(EditStack::from_metadata(&media.metadata))
+    }
+
+    /// Append an operation to the edit stack and regenerate the edited image
+    pub async fn apply_edit(&self, media_id: Uuid, op: EditOperation) -> MediaResult<MediaItem> {
+        op.validate()?;
+
+        let media = self.get_image(media_id).await?;
+        let mut stack = EditStack::from_metadata(&media.metadata);
+        stack.operations.push(op);
+
+        self.save_stack(&media, stack).await
+    }
+
+    /// Crop to `target_w`x`target_h` around a focal point, recorded as an edit.
+    ///
+    /// Without an explicit focal point the one stored on the media item is
+    /// used, falling back to the center.
+    pub async fn smart_crop(
+        &self,
+        media_id: Uuid,
+        target_w: u32,
+        target_h: u32,
+        focal_point: Option<FocalPoint>,
+    ) -> MediaResult<MediaItem> {
+        let focal_point = match focal_point {
+            Some(point) => FocalPoint::new(point.x, point.y),
+            None => {
+                let media = self.get_image(media_id).await?;
+                FocalPoint::from_metadata(&media.metadata).unwrap_or_default()
+            }
+        };
+
+        self.apply_edit(
+            media_id,
+            EditOperation::SmartCrop {
+                width: target_w,
+                height: target_h,
+                focal_point,
+            },
+        )
+        .await
+    }
+
+    /// Store the focal point of a media item and regenerate its thumbnail
+    /// around it
+    pub async fn set_focal_point(
+        &self,
+        media_id: Uuid,
+        focal_point: FocalPoint,
+    ) -> MediaResult<MediaItem> {
+        let focal_point = FocalPoint::new(focal_point.x, focal_point.y);
+        let media = self.get_image(media_id).await?;
+
+        let thumb_path = media.thumbnail_url.as_deref().and_then(|url| {
+            url.strip_prefix(&self.config.base_url)
+                .map(|path| path.trim_start_matches('/'))
+        });
+        if let Some(thumb_path) = thumb_path {
+            let original = fs::read(self.full_path(&media.path)).await?;
+            let (width, height) = THUMBNAIL_SIZE;
+            let thumb = ImageOptimizer::new(OptimizationConfig::default())
+                .generate_thumbnail_focused(&original, width, height, focal_point)?;
+            fs::write(self.full_path(thumb_path), thumb).await?;
+        }
+
+        let media: MediaItem = sqlx::query_as(
+            r#"
+            UPDATE media_items
+            SET metadata = jsonb_set(metadata, '{focal_point}', $2), updated_at = NOW()
+            WHERE id = $1
+            RETURNING
+                id, filename, title, alt_text, caption, description,
+                media_type, mime_type, file_size, path, url, thumbnail_url,
+                width, height, duration, file_hash, folder_id,
+                metadata, uploaded_by, created_at, updated_at
+            "#,
+        )
+        .bind(media_id)
+        .bind(serde_json::json!(focal_point))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(media)
+    }
+
+    /// Undo the last `steps` edits and regenerate the edited image.
+    ///
+    /// Reverting every edit removes the edited image, leaving the original.
+    pub async fn revert(&self, media_id: Uuid, steps: usize) -> MediaResult<MediaItem> {
+        let media = self.get_image(media_id).await?;
+        let mut stack = EditStack::from_metadata(&media.metadata);
+        if stack.revert(steps) == 0 {
+            return Ok(media);
+        }
+
+        self.save_stack(&media, stack).await
+    }
+
+    /// Bake the current edits into a new original.
+    ///
+    /// The edited image becomes the media item's file and the stack is
+    /// cleared; the previous original stays on disk and is recorded in the
+    /// stack's history. Thumbnails and srcset variants still describe the
+    /// previous original until they are regenerated.
+    pub async fn flatten(&self, media_id: Uuid) -> MediaResult<MediaItem> {
+        let media = self.get_image(media_id).await?;
+        let mut stack = EditStack::from_metadata(&media.metadata);
+        if stack.operations.is_empty() {
+            return Ok(media);
+        }
+
+        // Render from scratch rather than trusting a stale rendering
+        let original = fs::read(self.full_path(&media.path)).await?;
+        let (rendered, data, mime_type) = self.write_rendering(&media, &stack, &original).await?;
+
+        stack.flattened.push(FlattenedOriginal {
+            path: media.path.clone(),
+            url: media.url.clone(),
+            operations: std::mem::take(&mut stack.operations),
+            flattened_at: Utc::now(),
+        });
+        stack.rendered = None;
+
+        self.delete_edited_variant(media_id).await?;
+
+        let media: MediaItem = sqlx::query_as(
+            r#"
+            UPDATE media_items
+            SET path = $2, url = $3, mime_type = $4, width = $5, height = $6,
+                file_size = $7, file_hash = $8,
+                metadata = jsonb_set(metadata, '{edits}', $9), updated_at = NOW()
+            WHERE id = $1
+            RETURNING
+                id, filename, title, alt_text, caption, description,
+                media_type, mime_type, file_size, path, url, thumbnail_url,
+                width, height, duration, file_hash, folder_id,
+                metadata, uploaded_by, created_at, updated_at
+            "#,
+        )
+        .bind(media_id)
+        .bind(&rendered.path)
+        .bind(&rendered.url)
+        .bind(mime_type)
+        .bind(rendered.width as i32)
+        .bind(rendered.height as i32)
+        .bind(data.len() as i64)
+        .bind(rustpress_storage::dedup::content_hash(&data))
+        .bind(serde_json::to_value(&stack).map_err(|e| MediaError::ProcessingError(e.to_string()))?)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(media)
+    }
+
+    /// Render the stack (or drop the rendering when it is empty) and persist it
+    async fn save_stack(&self, media: &MediaItem, mut stack: EditStack) -> MediaResult<MediaItem> {
+        let previous = stack.rendered.take();
+        self.delete_edited_variant(media.id).await?;
+
+        if !stack.operations.is_empty() {
+            let original = fs::read(self.full_path(&media.path)).await?;
+            let (rendered, data, _) = self.write_rendering(media, &stack, &original).await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO media_variants (media_id, variant_type, width, height, file_size, path, url, format)
+                VALUES ($1, 'edited', $2, $3, $4, $5, $6, $7)
+                "#,
+            )
+            .bind(media.id)
+            .bind(rendered.width as i32)
+            .bind(rendered.height as i32)
+            .bind(data.len() as i64)
+            .bind(&rendered.path)
+            .bind(&rendered.url)
+            .bind(render_format(&media.mime_type).1)
+            .execute(&self.pool)
+            .await?;
+
+            stack.rendered = Some(rendered);
+        }
+
+        // Remove the superseded rendering unless the new one reused its name
+        if let Some(previous) = previous {
+            if stack.rendered.as_ref().map(|r| &r.path) != Some(&previous.path) {
+                let _ = fs::remove_file(self.full_path(&previous.path)).await;
+            }
+        }
+
+        let media: MediaItem = sqlx::query_as(
+            r#"
+            UPDATE media_items
+            SET metadata = jsonb_set(metadata, '{edits}', $2), updated_at = NOW()
+            WHERE id = $1
+            RETURNING
+                id, filename, title, alt_text, caption, description,
+                media_type, mime_type, file_size, path, url, thumbnail_url,
+                width, height, duration, file_hash, folder_id,
+                metadata, uploaded_by, created_at, updated_at
+            "#,
+        )
+        .bind(media.id)
+        .bind(serde_json::to_value(&stack).map_err(|e| MediaError::ProcessingError(e.to_string()))?)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(media)
+    }
+
+    /// Replay the stack against the original and write the result next to
+    /// it, named after the stack so identical stacks share a file
+    async fn write_rendering(
+        &self,
+        media: &MediaItem,
+        stack: &EditStack,
+        original: &[u8],
+    ) -> MediaResult<(RenderedEdit, Vec<u8>, &'static str)> {
+        let editor = stack.render(original)?;
+        let (width, height) = editor.dimensions();
+        let (format, extension, mime_type) = render_format(&media.mime_type);
+        let data = match format {
+            ImageFormat::Jpeg => editor.to_jpeg(90)?,
+            format => editor.to_bytes(format)?,
+        };
+
+        let base_path = Path::new(&media.path);
+        let stem = base_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("img");
+        let path = base_path
+            .with_file_name(format!("{}-edit-{}.{}", stem, stack.key(), extension))
+            .to_string_lossy()
+            .to_string();
+
+        fs::write(self.full_path(&path), &data).await?;
+
+        let rendered = RenderedEdit {
+            url: format!("{}/{}", self.config.base_url, path),
+            path,
+            width,
+            height,
+        };
+
+        Ok((rendered, data, mime_type))
+    }
+
+    async fn delete_edited_variant(&self, media_id: Uuid) -> MediaResult<()> {
+        sqlx::query("DELETE FROM media_variants WHERE media_id = $1 AND variant_type = 'edited'")
+            .bind(media_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_image(&self, media_id: Uuid) -> MediaResult<MediaItem> {
+        let media: MediaItem = sqlx::query_as(
+            r#"
+            SELECT
+                id, filename, title, alt_text, caption, description,
+                media_type, mime_type, file_size, path, url, thumbnail_url,
+                width, height, duration, file_hash, folder_id,
+                metadata, uploaded_by, created_at, updated_at
+            FROM media_items
+            WHERE id = $1
+            "#,
+        )
+        .bind(media_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(MediaError::NotFound(media_id))?;
+
+        if media.media_type != MediaType::Image {
+            return Err(MediaError::InvalidType(media.mime_type));
+        }
+        Ok(media)
+    }
+
+    fn full_path(&self, path: &str) -> String {
+        format!("{}/{}", self.config.storage_path, path)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -477,4 +989,162 @@ mod tests {
         assert!(json.contains("brightness"));
         assert!(json.contains("10"));
     }
+
+    fn sample_png() -> Vec<u8> {
+        let image =
+            image::RgbaImage::from_fn(8, 4, |x, y| Rgba([x as u8 * 30, y as u8 * 60, 90, 255]));
+        let mut buffer = Cursor::new(Vec::new());
+        DynamicImage::ImageRgba8(image)
+            .write_to(&mut buffer, ImageFormat::Png)
+            .unwrap();
+        buffer.into_inner()
+    }
+
+    fn sample_stack() -> EditStack {
+        EditStack {
+            operations: vec![
+                EditOperation::Crop {
+                    x: 1,
+                    y: 0,
+                    width: 6,
+                    height: 4,
+                },
+                EditOperation::Rotate90,
+                EditOperation::Preset {
+                    preset: FilterPreset::Vintage,
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_edit_stack_replay_is_deterministic() {
+        let original = sample_png();
+        let stack = sample_stack();
+
+        let first = stack.render(&original).unwrap();
+        assert_eq!(first.dimensions(), (4, 6));
+
+        // A stack read back from metadata renders the same pixels
+        let metadata = serde_json::json!({ "edits": stack });
+        let replayed = EditStack::from_metadata(&metadata);
+        assert_eq!(replayed, stack);
+        assert_eq!(replayed.key(), stack.key());
+        assert_eq!(
+            replayed.render(&original).unwrap().to_png().unwrap(),
+            first.to_png().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_edit_stack_revert() {
+        let mut stack = sample_stack();
+        let key = stack.key();
+
+        assert_eq!(stack.revert(1), 1);
+        assert_eq!(stack.operations.len(), 2);
+        assert_ne!(stack.key(), key);
+
+        assert_eq!(stack.revert(10), 2);
+        assert!(stack.operations.is_empty());
+        assert_eq!(stack.revert(1), 0);
+
+        assert_eq!(
+            EditStack::from_metadata(&serde_json::json!({})),
+            EditStack::default()
+        );
+    }
+
+    #[test]
+    fn test_edit_operation_validate() {
+        assert!(EditOperation::Rotate90.validate().is_ok());
+        assert!(EditOperation::Scale { percent: 50.0 }.validate().is_ok());
+        assert!(EditOperation::Scale { percent: 0.0 }.validate().is_err());
+        assert!(EditOperation::Crop {
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 10
+        }
+        .validate()
+        .is_err());
+        assert!(EditOperation::CropToAspect { ratio: f64::NAN }
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_render_format() {
+        assert_eq!(render_format("image/png").1, "png");
+        assert_eq!(render_format("image/jpeg").2, "image/jpeg");
+        assert_eq!(render_format("image/bmp").2, "image/jpeg");
+    }
+
+    #[test]
+    fn test_focal_point_crop_rect() {
+        // Centered by default
+        assert_eq!(
+            FocalPoint::center().crop_rect(1000, 500, 300, 300),
+            (250, 0, 500, 500)
+        );
+        // Follows the focal point, stopping at the image edges
+        assert_eq!(
+            FocalPoint::new(0.3, 0.5).crop_rect(1000, 500, 300, 300),
+            (50, 0, 500, 500)
+        );
+        assert_eq!(
+            FocalPoint::new(1.0, 0.5).crop_rect(1000, 500, 300, 300),
+            (500, 0, 500, 500)
+        );
+        assert_eq!(
+            FocalPoint::new(0.5, 0.1).crop_rect(400, 900, 400, 300),
+            (0, 0, 400, 300)
+        );
+    }
+
+    #[test]
+    fn test_focal_point_clamping() {
+        assert_eq!(FocalPoint::new(5.0, -2.0), FocalPoint { x: 1.0, y: 0.0 });
+        assert_eq!(
+            FocalPoint::new(f32::NAN, 0.2),
+            FocalPoint { x: 0.5, y: 0.2 }
+        );
+
+        // Out-of-range points read back from metadata are clamped too
+        let metadata = serde_json::json!({ "focal_point": { "x": 1.7, "y": 0.25 } });
+        assert_eq!(
+            FocalPoint::from_metadata(&metadata),
+            Some(FocalPoint { x: 1.0, y: 0.25 })
+        );
+        assert_eq!(FocalPoint::from_metadata(&serde_json::json!({})), None);
+
+        // Unclamped fields never push the crop outside the source
+        let rogue = FocalPoint { x: 9.0, y: -9.0 };
+        let (x, y, w, h) = rogue.crop_rect(640, 480, 16, 9);
+        assert!(x + w <= 640 && y + h <= 480);
+    }
+
+    #[test]
+    fn test_smart_crop() {
+        let mut editor = ImageEditor::from_bytes(&sample_png()).unwrap();
+        editor.smart_crop(2, 2, FocalPoint::new(1.0, 0.0));
+        assert_eq!(editor.dimensions(), (2, 2));
+
+        // Targets larger than the source are cropped to shape, not upscaled
+        let mut editor = ImageEditor::from_bytes(&sample_png()).unwrap();
+        editor.smart_crop(100, 100, FocalPoint::center());
+        assert_eq!(editor.dimensions(), (4, 4));
+
+        let op: EditOperation =
+            serde_json::from_str(r#"{"type":"smart_crop","width":3,"height":3}"#).unwrap();
+        assert_eq!(
+            op,
+            EditOperation::SmartCrop {
+                width: 3,
+                height: 3,
+                focal_point: FocalPoint::center()
+            }
+        );
+    }
 }
@@ -10,6 +10,7 @@ use image::{DynamicImage, GenericImageView, ImageFormat};
 use std::io::Cursor;
 use std::path::Path;
 
+use crate::editor::FocalPoint;
 use crate::{MediaError, MediaResult};
 
 /// Image optimization configuration
@@ -131,30 +132,29 @@ impl ImageOptimizer {
         Ok(buffer.into_inner())
     }
 
-    /// Generate thumbnail with exact dimensions (crop to fit)
+    /// Generate thumbnail with exact dimensions (crop to fit, centered)
     pub fn generate_thumbnail_exact(
         &self,
         data: &[u8],
         width: u32,
         height: u32,
+    ) -> MediaResult<Vec<u8>> {
+        self.generate_thumbnail_focused(data, width, height, FocalPoint::center())
+    }
+
+    /// Generate thumbnail with exact dimensions, cropping around a focal point
+    pub fn generate_thumbnail_focused(
+        &self,
+        data: &[u8],
+        width: u32,
+        height: u32,
+        focal_point: FocalPoint,
     ) -> MediaResult<Vec<u8>> {
         let img = image::load_from_memory(data)?;
 
         // Calculate crop region to maintain aspect ratio
         let (orig_w, orig_h) = img.dimensions();
-        let target_ratio = width as f64 / height as f64;
-        let orig_ratio = orig_w as f64 / orig_h as f64;
-
-        let (crop_w, crop_h) = if orig_ratio > target_ratio {
-            // Image is wider, crop width
-            ((orig_h as f64 * target_ratio) as u32, orig_h)
-        } else {
-            // Image is taller, crop height
-            (orig_w, (orig_w as f64 / target_ratio) as u32)
-        };
-
-        let crop_x = (orig_w - crop_w) / 2;
-        let crop_y = (orig_h - crop_h) / 2;
+        let (crop_x, crop_y, crop_w, crop_h) = focal_point.crop_rect(orig_w, orig_h, width, height);
 
         let cropped = img.crop_imm(crop_x, crop_y, crop_w, crop_h);
         let thumb = cropped.resize_exact(width, height, image::imageops::FilterType::Lanczos3);
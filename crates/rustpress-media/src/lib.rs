@@ -5,17 +5,25 @@
 //! - Lazy loading support
 //! - Responsive image srcsets
 //! - Media library with folders
+//! - Media tags and smart collections
 //! - Drag-and-drop upload support
 //! - Image editing (crop, resize, filters)
 //! - Video transcoding
+//! - PDF first-page thumbnails
 //! - Audio player support
+//! - Pluggable virus/malware scanning of uploads
+//! - Pluggable alt text suggestions for images
 
+pub mod alt_text;
 pub mod audio;
+pub mod document;
 pub mod editor;
 pub mod image_optimizer;
 pub mod lazy_loading;
 pub mod library;
+pub mod scan;
 pub mod srcset;
+pub mod tags;
 pub mod upload;
 pub mod video;
 
@@ -26,12 +34,16 @@ use thiserror::Error;
 use uuid::Uuid;
 
 // Re-exports
+pub use alt_text::*;
 pub use audio::*;
+pub use document::*;
 pub use editor::*;
 pub use image_optimizer::*;
 pub use lazy_loading::*;
 pub use library::*;
+pub use scan::*;
 pub use srcset::*;
+pub use tags::*;
 pub use upload::*;
 pub use video::*;
 
@@ -286,6 +298,65 @@ pub struct MediaConfig {
 
     /// Enable srcset generation
     pub enable_srcset: bool,
+
+    /// Convert animated GIFs to looping MP4/WebM (requires ffmpeg); the
+    /// original GIF is kept as fallback
+    #[serde(default)]
+    pub gif_to_video: bool,
+
+    /// Render the first page of uploaded PDFs as their thumbnail (requires
+    /// poppler-utils)
+    #[serde(default)]
+    pub document_thumbnails: bool,
+
+    /// Resolution used when rendering PDF thumbnails
+    #[serde(default = "default_document_thumbnail_dpi")]
+    pub document_thumbnail_dpi: u32,
+
+    /// Seconds a resumable upload may sit idle before cleanup removes it
+    #[serde(default = "default_resumable_upload_ttl")]
+    pub resumable_upload_ttl_secs: u64,
+
+    /// Scan uploads in quarantine before accepting them into the library
+    #[serde(default)]
+    pub scan_uploads: bool,
+
+    /// Seconds a single upload scan may take
+    #[serde(default = "default_scan_timeout")]
+    pub scan_timeout_secs: u64,
+
+    /// Whether uploads are rejected or accepted when the scanner fails
+    #[serde(default)]
+    pub scan_failure_policy: ScanFailurePolicy,
+
+    /// Suggest alt text for uploaded images in the background, stored in
+    /// metadata as `alt_text_suggested`
+    #[serde(default)]
+    pub suggest_alt_text: bool,
+
+    /// Also use the suggestion as the image's alt text when it has none
+    #[serde(default)]
+    pub apply_suggested_alt_text: bool,
+
+    /// Seconds a single alt text suggestion may take
+    #[serde(default = "default_alt_text_timeout")]
+    pub alt_text_timeout_secs: u64,
+}
+
+fn default_document_thumbnail_dpi() -> u32 {
+    document::DEFAULT_PDF_THUMBNAIL_DPI
+}
+
+fn default_resumable_upload_ttl() -> u64 {
+    upload::DEFAULT_RESUMABLE_UPLOAD_TTL_SECS
+}
+
+fn default_scan_timeout() -> u64 {
+    scan::DEFAULT_SCAN_TIMEOUT_SECS
+}
+
+fn default_alt_text_timeout() -> u64 {
+    alt_text::DEFAULT_ALT_TEXT_TIMEOUT_SECS
 }
 
 impl Default for MediaConfig {
@@ -318,6 +389,16 @@ impl Default for MediaConfig {
             ],
             enable_lazy_loading: true,
             enable_srcset: true,
+            gif_to_video: false,
+            document_thumbnails: false,
+            document_thumbnail_dpi: default_document_thumbnail_dpi(),
+            resumable_upload_ttl_secs: default_resumable_upload_ttl(),
+            scan_uploads: false,
+            scan_timeout_secs: default_scan_timeout(),
+            scan_failure_policy: ScanFailurePolicy::default(),
+            suggest_alt_text: false,
+            apply_suggested_alt_text: false,
+            alt_text_timeout_secs: default_alt_text_timeout(),
         }
     }
 }
@@ -402,6 +483,12 @@ impl MediaService {
             OR filename ILIKE $1
             OR alt_text ILIKE $1
             OR caption ILIKE $1
+            OR EXISTS (
+                SELECT 1
+                FROM media_item_tags mt
+                JOIN media_tags t ON t.id = mt.tag_id
+                WHERE mt.media_id = media_items.id AND t.name ILIKE $1
+            )
             ORDER BY created_at DESC
             LIMIT $2
             "#,
@@ -553,6 +640,32 @@ CREATE TABLE IF NOT EXISTS media_variants (
 CREATE INDEX IF NOT EXISTS idx_media_variants_media ON media_variants(media_id);
 CREATE INDEX IF NOT EXISTS idx_media_variants_type ON media_variants(variant_type);
 
+-- Media tags
+CREATE TABLE IF NOT EXISTS media_tags (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    slug VARCHAR(100) NOT NULL UNIQUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS media_item_tags (
+    media_id UUID NOT NULL REFERENCES media_items(id) ON DELETE CASCADE,
+    tag_id UUID NOT NULL REFERENCES media_tags(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (media_id, tag_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_media_item_tags_tag ON media_item_tags(tag_id);
+
+-- Smart collections, evaluated from their filter on each view
+CREATE TABLE IF NOT EXISTS media_smart_collections (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL,
+    filter JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
 -- Video metadata table
 CREATE TABLE IF NOT EXISTS video_metadata (
     id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
@@ -0,0 +1,281 @@
+//! Upload scanning
+//!
+//! Provides a pluggable virus/malware scanning step for uploads:
+//! - Uploads are written to a quarantine directory before scanning
+//! - Clean files are promoted into the library, rejected ones deleted
+//! - Scanners run on the blocking thread pool with a time limit
+//! - Scanner outages fail closed or open, as configured
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+use crate::{MediaError, MediaResult};
+
+/// Default time limit for a single scan
+pub const DEFAULT_SCAN_TIMEOUT_SECS: u64 = 30;
+
+/// Directory under the storage path holding uploads awaiting a scan
+pub const QUARANTINE_DIR: &str = "quarantine";
+
+/// Outcome of scanning an upload
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "verdict", content = "reason", rename_all = "snake_case")]
+pub enum ScanVerdict {
+    Clean,
+    /// Known malware, with the signature name
+    Infected(String),
+    /// Not known malware but not acceptable either, e.g. an encrypted archive
+    Suspicious(String),
+}
+
+/// What to do when the scanner errors or times out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanFailurePolicy {
+    /// Reject the upload
+    #[default]
+    FailClosed,
+    /// Log a warning and accept the upload unscanned
+    FailOpen,
+}
+
+/// Scanner checking uploads before they are accepted into the library,
+/// e.g. a ClamAV integration.
+///
+/// `scan` is called on the blocking thread pool, so implementations may
+/// block on sockets or subprocesses. A scan that outlives the timeout is
+/// abandoned rather than interrupted, so implementations should bound their
+/// own IO as well.
+pub trait UploadScanner: Send + Sync {
+    /// Scanner name used in logs and rejection messages
+    fn name(&self) -> &str;
+
+    /// Scan the quarantined file at `path`
+    fn scan(&self, path: &Path) -> MediaResult<ScanVerdict>;
+}
+
+/// Scanner accepting every file, used when no scanner is configured
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopScanner;
+
+impl UploadScanner for NoopScanner {
+    fn name(&self) -> &str {
+        "noop"
+    }
+
+    fn scan(&self, _path: &Path) -> MediaResult<ScanVerdict> {
+        Ok(ScanVerdict::Clean)
+    }
+}
+
+/// An upload held in quarantine until it is scanned.
+///
+/// The file is removed when this is dropped without being promoted, so an
+/// error anywhere in the upload pipeline never leaves it behind.
+#[derive(Debug)]
+pub struct QuarantinedFile {
+    path: PathBuf,
+    released: bool,
+}
+
+impl QuarantinedFile {
+    /// Write `data` to a new file in `dir`
+    pub async fn create(dir: &Path, data: &[u8]) -> MediaResult<Self> {
+        fs::create_dir_all(dir).await?;
+        let quarantined = Self {
+            path: dir.join(Uuid::new_v4().to_string()),
+            released: false,
+        };
+
+        let mut file = fs::File::create(&quarantined.path).await?;
+        file.write_all(data).await?;
+        file.flush().await?;
+        Ok(quarantined)
+    }
+
+    /// Path of the quarantined file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Scan the file, deleting it if the scanner rejects it.
+    ///
+    /// Infected and suspicious files fail with
+    /// [`MediaError::ProcessingError`]. Scanner errors and timeouts are
+    /// handled according to `policy`.
+    pub async fn scan(
+        mut self,
+        scanner: Arc<dyn UploadScanner>,
+        timeout: Duration,
+        policy: ScanFailurePolicy,
+    ) -> MediaResult<Self> {
+        let name = scanner.name().to_string();
+        let path = self.path.clone();
+        let task = tokio::task::spawn_blocking(move || scanner.scan(&path));
+
+        let failure = match tokio::time::timeout(timeout, task).await {
+            Ok(Ok(Ok(ScanVerdict::Clean))) => return Ok(self),
+            Ok(Ok(Ok(ScanVerdict::Infected(reason) | ScanVerdict::Suspicious(reason)))) => {
+                self.discard().await;
+                return Err(MediaError::ProcessingError(format!(
+                    "Upload rejected by {}: {}",
+                    name, reason
+                )));
+            }
+            Ok(Ok(Err(e))) => e.to_string(),
+            Ok(Err(e)) => format!("scan task failed: {}", e),
+            Err(_) => format!("timed out after {}s", timeout.as_secs_f64()),
+        };
+
+        match policy {
+            ScanFailurePolicy::FailOpen => {
+                tracing::warn!(
+                    scanner = %name,
+                    error = %failure,
+                    "Upload scan failed, accepting file unscanned"
+                );
+                Ok(self)
+            }
+            ScanFailurePolicy::FailClosed => {
+                self.discard().await;
+                Err(MediaError::ProcessingError(format!(
+                    "Upload scan by {} failed: {}",
+                    name, failure
+                )))
+            }
+        }
+    }
+
+    /// Move the file to `dest`, its place in the library
+    pub async fn promote(mut self, dest: &Path) -> MediaResult<()> {
+        fs::rename(&self.path, dest).await?;
+        self.released = true;
+        Ok(())
+    }
+
+    /// Delete the file
+    pub async fn discard(&mut self) {
+        if !self.released {
+            if let Err(e) = fs::remove_file(&self.path).await {
+                tracing::warn!(path = %self.path.display(), error = %e, "Failed to remove quarantined upload");
+            }
+            self.released = true;
+        }
+    }
+}
+
+impl Drop for QuarantinedFile {
+    fn drop(&mut self) {
+        if !self.released {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedScanner(MediaResult<ScanVerdict>, Duration);
+
+    impl UploadScanner for FixedScanner {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        fn scan(&self, _path: &Path) -> MediaResult<ScanVerdict> {
+            std::thread::sleep(self.1);
+            match &self.0 {
+                Ok(verdict) => Ok(verdict.clone()),
+                Err(e) => Err(MediaError::ProcessingError(e.to_string())),
+            }
+        }
+    }
+
+    fn scanner(result: MediaResult<ScanVerdict>, delay_ms: u64) -> Arc<dyn UploadScanner> {
+        Arc::new(FixedScanner(result, Duration::from_millis(delay_ms)))
+    }
+
+    const TIMEOUT: Duration = Duration::from_millis(200);
+
+    #[tokio::test]
+    async fn test_clean_file_is_promoted_and_infected_file_deleted() {
+        let dir = tempfile::tempdir().unwrap();
+        let quarantine = dir.path().join(QUARANTINE_DIR);
+
+        let file = QuarantinedFile::create(&quarantine, b"hello")
+            .await
+            .unwrap();
+        let file = file
+            .scan(
+                Arc::new(NoopScanner),
+                TIMEOUT,
+                ScanFailurePolicy::FailClosed,
+            )
+            .await
+            .unwrap();
+        let dest = dir.path().join("hello.txt");
+        file.promote(&dest).await.unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), b"hello");
+
+        let file = QuarantinedFile::create(&quarantine, b"X5O!P%@AP")
+            .await
+            .unwrap();
+        let path = file.path().to_path_buf();
+        let result = file
+            .scan(
+                scanner(Ok(ScanVerdict::Infected("Eicar-Test-Signature".into())), 0),
+                TIMEOUT,
+                ScanFailurePolicy::FailOpen,
+            )
+            .await;
+        assert!(
+            matches!(result, Err(MediaError::ProcessingError(msg)) if msg.contains("Eicar-Test-Signature"))
+        );
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_scanner_failures_follow_policy() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // A hung scanner times out
+        let file = QuarantinedFile::create(dir.path(), b"data").await.unwrap();
+        let path = file.path().to_path_buf();
+        let result = file
+            .scan(
+                scanner(Ok(ScanVerdict::Clean), 1000),
+                TIMEOUT,
+                ScanFailurePolicy::FailClosed,
+            )
+            .await;
+        assert!(
+            matches!(result, Err(MediaError::ProcessingError(msg)) if msg.contains("timed out"))
+        );
+        assert!(!path.exists());
+
+        // An unreachable scanner is tolerated when failing open
+        let file = QuarantinedFile::create(dir.path(), b"data").await.unwrap();
+        let file = file
+            .scan(
+                scanner(
+                    Err(MediaError::ProcessingError("clamd unreachable".into())),
+                    0,
+                ),
+                TIMEOUT,
+                ScanFailurePolicy::FailOpen,
+            )
+            .await
+            .unwrap();
+
+        // Dropping an unpromoted file removes it
+        let path = file.path().to_path_buf();
+        drop(file);
+        assert!(!path.exists());
+    }
+}
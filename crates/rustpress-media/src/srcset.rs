@@ -3,11 +3,13 @@
 //! Provides functionality for generating responsive image variants
 //! and srcset attributes for optimal image loading.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use uuid::Uuid;
 
 use crate::image_optimizer::ImageOptimizer;
-use crate::{MediaError, MediaItem, MediaResult};
+use crate::{MediaError, MediaItem, MediaResult, MediaService, MediaType};
 
 /// Srcset configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -163,7 +165,8 @@ impl SrcsetGenerator {
         }
     }
 
-    /// Generate complete picture element HTML
+    /// Generate complete picture element HTML, or a looping `<video>` for
+    /// animated GIFs that were converted on upload
     pub fn generate_picture_html(
         &self,
         media: &MediaItem,
@@ -171,6 +174,11 @@ impl SrcsetGenerator {
         layout: SrcsetLayout,
         lazy: bool,
     ) -> String {
+        // Animated GIFs converted to video render as a looping <video>
+        if let Some(html) = crate::video::animated_gif_video_html(media) {
+            return html;
+        }
+
         let filename = &media.filename;
         let alt = &media.alt_text;
         let sizes = self.generate_sizes(layout);
@@ -247,8 +255,33 @@ pub struct ImageVariant {
     pub size_descriptor: String,
 }
 
+/// A stored variant of a media item, as recorded in `media_variants`
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MediaVariant {
+    pub id: Uuid,
+    pub media_id: Uuid,
+    /// Size descriptor such as `640w`, or `full`, `edited`, `video`
+    pub variant_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub file_size: i64,
+    pub path: String,
+    pub url: String,
+    /// File extension of the variant
+    pub format: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MediaVariant {
+    /// Image format of the variant, `None` for videos and unknown formats
+    pub fn image_format(&self) -> Option<ImageVariantFormat> {
+        ImageVariantFormat::from_extension(&self.format)
+    }
+}
+
 /// Image variant formats
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ImageVariantFormat {
     Jpeg,
     Png,
@@ -257,6 +290,17 @@ pub enum ImageVariantFormat {
 }
 
 impl ImageVariantFormat {
+    /// Parse a file extension or format name
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "png" => Some(Self::Png),
+            "webp" => Some(Self::WebP),
+            "avif" => Some(Self::Avif),
+            _ => None,
+        }
+    }
+
     /// Get file extension
     pub fn extension(&self) -> &str {
         match self {
@@ -278,6 +322,149 @@ impl ImageVariantFormat {
     }
 }
 
+impl MediaItem {
+    /// Render a responsive `<picture>` from the variants that exist.
+    ///
+    /// Each requested AVIF or WebP format with at least one stored variant
+    /// gets a `<source>` (AVIF first); formats that weren't generated are
+    /// skipped. The `<img>` fallback lists the JPEG/PNG variants plus the
+    /// original. Srcset descriptors use each file's real width, and
+    /// `width`/`height` come from the original so the browser can reserve
+    /// space. A stored blurhash is exposed as `data-blurhash` for the client
+    /// to paint while loading.
+    ///
+    /// Non-image media renders as a plain link, and animated GIFs that were
+    /// converted on upload render as a looping `<video>`.
+    pub fn render_responsive(
+        &self,
+        variants: &[MediaVariant],
+        sizes: &str,
+        formats: &[ImageVariantFormat],
+    ) -> String {
+        if self.media_type != MediaType::Image {
+            let label = if self.title.is_empty() {
+                &self.filename
+            } else {
+                &self.title
+            };
+            return format!(
+                "<a href=\"{}\">{}</a>",
+                escape_html(&self.url),
+                escape_html(label)
+            );
+        }
+
+        if let Some(html) = crate::video::animated_gif_video_html(self) {
+            return html;
+        }
+
+        let usable: Vec<(&MediaVariant, ImageVariantFormat)> = variants
+            .iter()
+            .filter(|v| v.media_id == self.id && v.variant_type != "edited")
+            .filter(|v| v.width > 0 && v.height > 0)
+            .filter_map(|v| v.image_format().map(|format| (v, format)))
+            .collect();
+
+        let mut html = String::from("<picture>");
+
+        for format in [ImageVariantFormat::Avif, ImageVariantFormat::WebP] {
+            if !formats.contains(&format) {
+                continue;
+            }
+            let candidates = usable
+                .iter()
+                .filter(|(_, f)| *f == format)
+                .map(|(v, _)| (v.url.as_str(), v.width));
+            if let Some(srcset) = build_srcset(candidates) {
+                html.push_str(&format!(
+                    "<source type=\"{}\" srcset=\"{}\" sizes=\"{}\">",
+                    format.mime_type(),
+                    srcset,
+                    escape_html(sizes)
+                ));
+            }
+        }
+
+        // The original is part of the fallback set, at its own width
+        let fallback = usable
+            .iter()
+            .filter(|(_, f)| matches!(f, ImageVariantFormat::Jpeg | ImageVariantFormat::Png))
+            .map(|(v, _)| (v.url.as_str(), v.width))
+            .chain(self.width.map(|w| (self.url.as_str(), w)));
+
+        html.push_str(&format!("<img src=\"{}\"", escape_html(&self.url)));
+        if let Some(srcset) = build_srcset(fallback) {
+            html.push_str(&format!(
+                " srcset=\"{}\" sizes=\"{}\"",
+                srcset,
+                escape_html(sizes)
+            ));
+        }
+        if let (Some(w), Some(h)) = (self.width, self.height) {
+            html.push_str(&format!(" width=\"{}\" height=\"{}\"", w, h));
+        }
+        html.push_str(&format!(
+            " alt=\"{}\" loading=\"lazy\" decoding=\"async\"",
+            escape_html(&self.alt_text)
+        ));
+        if let Some(blurhash) = self.metadata.get("blurhash").and_then(|b| b.as_str()) {
+            html.push_str(&format!(" data-blurhash=\"{}\"", escape_html(blurhash)));
+        }
+        html.push_str("></picture>");
+
+        html
+    }
+}
+
+/// Build a `w`-descriptor srcset, smallest first, one entry per width
+fn build_srcset<'a>(candidates: impl Iterator<Item = (&'a str, i32)>) -> Option<String> {
+    let mut entries: Vec<(&str, i32)> = candidates.filter(|(_, w)| *w > 0).collect();
+    entries.sort_by_key(|(_, w)| *w);
+    entries.dedup_by_key(|(_, w)| *w);
+
+    if entries.is_empty() {
+        return None;
+    }
+    Some(
+        entries
+            .iter()
+            .map(|(url, w)| format!("{} {}w", escape_html(url), w))
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+impl MediaService {
+    /// Stored variants of a media item
+    pub async fn variants(&self, media_id: Uuid) -> MediaResult<Vec<MediaVariant>> {
+        let variants: Vec<MediaVariant> = sqlx::query_as(
+            r#"
+            SELECT id, media_id, variant_type, width, height, file_size, path, url, format, created_at
+            FROM media_variants
+            WHERE media_id = $1
+            ORDER BY width, created_at
+            "#,
+        )
+        .bind(media_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(variants)
+    }
+
+    /// Render responsive markup for a media item from its stored variants
+    pub async fn render_responsive(
+        &self,
+        id: Uuid,
+        sizes: &str,
+        formats: &[ImageVariantFormat],
+    ) -> MediaResult<String> {
+        let media = self.get(id).await?;
+        let variants = self.variants(id).await?;
+        Ok(media.render_responsive(&variants, sizes, formats))
+    }
+}
+
 /// Common srcset layouts
 #[derive(Debug, Clone)]
 pub enum SrcsetLayout {
@@ -358,7 +545,7 @@ pub fn generate_art_directed_picture(
 }
 
 /// Simple HTML escaping
-fn escape_html(s: &str) -> String {
+pub(crate) fn escape_html(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -391,4 +578,109 @@ mod tests {
         assert_eq!(ImageVariantFormat::WebP.extension(), "webp");
         assert_eq!(ImageVariantFormat::WebP.mime_type(), "image/webp");
     }
+
+    fn responsive_item(media_type: MediaType) -> MediaItem {
+        MediaItem {
+            id: Uuid::new_v4(),
+            filename: "beach.jpg".to_string(),
+            title: "Beach & sun".to_string(),
+            alt_text: "A \"sunny\" beach".to_string(),
+            caption: String::new(),
+            description: String::new(),
+            media_type,
+            mime_type: "image/jpeg".to_string(),
+            file_size: 1000,
+            path: "images/2024/01/beach.jpg".to_string(),
+            url: "/uploads/images/2024/01/beach.jpg".to_string(),
+            thumbnail_url: None,
+            width: Some(1600),
+            height: Some(900),
+            duration: None,
+            file_hash: String::new(),
+            folder_id: None,
+            metadata: serde_json::json!({ "blurhash": "LEHV6nWB2yk8pyo0adR*.7kCMdnj" }),
+            uploaded_by: Uuid::new_v4(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn variant(media: &MediaItem, variant_type: &str, width: i32, format: &str) -> MediaVariant {
+        MediaVariant {
+            id: Uuid::new_v4(),
+            media_id: media.id,
+            variant_type: variant_type.to_string(),
+            width,
+            height: width * 9 / 16,
+            file_size: 100,
+            path: String::new(),
+            url: format!("/uploads/images/2024/01/beach-{}.{}", width, format),
+            format: format.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_render_responsive_uses_existing_variants() {
+        let media = responsive_item(MediaType::Image);
+        let variants = vec![
+            variant(&media, "1280w", 1280, "webp"),
+            variant(&media, "640w", 640, "webp"),
+            variant(&media, "640w", 640, "jpg"),
+            variant(&media, "1280w", 1280, "jpg"),
+            variant(&media, "edited", 800, "jpg"),
+            variant(&media, "video", 1600, "mp4"),
+        ];
+
+        let html = media.render_responsive(
+            &variants,
+            "100vw",
+            &[ImageVariantFormat::WebP, ImageVariantFormat::Avif],
+        );
+
+        // No AVIF variants were generated, so no AVIF source
+        assert!(!html.contains("image/avif"));
+        assert!(html.contains(
+            "<source type=\"image/webp\" srcset=\"/uploads/images/2024/01/beach-640.webp 640w, /uploads/images/2024/01/beach-1280.webp 1280w\" sizes=\"100vw\">"
+        ));
+        assert!(html.contains(
+            "srcset=\"/uploads/images/2024/01/beach-640.jpg 640w, /uploads/images/2024/01/beach-1280.jpg 1280w, /uploads/images/2024/01/beach.jpg 1600w\""
+        ));
+        assert!(!html.contains("beach-800"));
+        assert!(!html.contains("mp4"));
+        assert!(html.contains(" width=\"1600\" height=\"900\""));
+        assert!(html.contains("alt=\"A &quot;sunny&quot; beach\""));
+        assert!(html.contains("loading=\"lazy\" decoding=\"async\""));
+        assert!(html.contains("data-blurhash=\"LEHV6nWB2yk8pyo0adR*.7kCMdnj\""));
+        assert!(html.starts_with("<picture>") && html.ends_with("></picture>"));
+    }
+
+    #[test]
+    fn test_render_responsive_skips_unrequested_and_foreign_variants() {
+        let media = responsive_item(MediaType::Image);
+        let other = responsive_item(MediaType::Image);
+        let variants = vec![
+            variant(&media, "640w", 640, "avif"),
+            variant(&other, "640w", 640, "webp"),
+            variant(&media, "320w", 0, "jpg"),
+        ];
+
+        let html = media.render_responsive(&variants, "50vw", &[ImageVariantFormat::WebP]);
+        assert!(!html.contains("<source"));
+        assert!(html.contains("srcset=\"/uploads/images/2024/01/beach.jpg 1600w\""));
+
+        let html = media.render_responsive(&variants, "50vw", &[ImageVariantFormat::Avif]);
+        assert!(html.contains("<source type=\"image/avif\""));
+    }
+
+    #[test]
+    fn test_render_responsive_non_image_is_a_link() {
+        let mut media = responsive_item(MediaType::Document);
+        media.url = "/uploads/documents/report.pdf".to_string();
+
+        assert_eq!(
+            media.render_responsive(&[], "100vw", &[ImageVariantFormat::WebP]),
+            "<a href=\"/uploads/documents/report.pdf\">Beach &amp; sun</a>"
+        );
+    }
 }
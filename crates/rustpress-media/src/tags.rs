@@ -0,0 +1,509 @@
+//! Media tagging and smart collections
+//!
+//! Provides free-form tags on media items and saved collections whose
+//! contents are worked out from a filter each time they are viewed.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{MediaError, MediaItem, MediaResult, MediaService, MediaType};
+
+/// Longest tag name accepted, in characters
+const MAX_TAG_LENGTH: usize = 100;
+
+/// A media tag
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MediaTag {
+    pub id: Uuid,
+    /// Display name, as first entered
+    pub name: String,
+    /// Normalized name used for matching
+    pub slug: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// How a list of tags is matched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+    /// Media with at least one of the tags
+    #[default]
+    Any,
+    /// Media with every one of the tags
+    All,
+}
+
+/// Filter defining a smart collection; unset fields match everything
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CollectionFilter {
+    #[serde(default)]
+    pub media_type: Option<MediaType>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub tag_match: TagMatch,
+    /// Uploaded at or after
+    #[serde(default)]
+    pub created_after: Option<DateTime<Utc>>,
+    /// Uploaded before
+    #[serde(default)]
+    pub created_before: Option<DateTime<Utc>>,
+    /// Minimum file size in bytes
+    #[serde(default)]
+    pub min_size: Option<i64>,
+    /// Maximum file size in bytes
+    #[serde(default)]
+    pub max_size: Option<i64>,
+    /// Text matched against title, filename, alt text, caption and tag names
+    #[serde(default)]
+    pub search: Option<String>,
+}
+
+/// A saved collection evaluated from its filter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmartCollection {
+    pub id: Uuid,
+    pub name: String,
+    pub filter: CollectionFilter,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct SmartCollectionRow {
+    id: Uuid,
+    name: String,
+    filter: serde_json::Value,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<SmartCollectionRow> for SmartCollection {
+    type Error = MediaError;
+
+    fn try_from(row: SmartCollectionRow) -> MediaResult<Self> {
+        Ok(Self {
+            id: row.id,
+            name: row.name,
+            filter: serde_json::from_value(row.filter)
+                .map_err(|e| MediaError::ProcessingError(e.to_string()))?,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+impl MediaService {
+    // ========================================================================
+    // Tags
+    // ========================================================================
+
+    /// Tag a media item, creating tags that don't exist yet.
+    ///
+    /// Returns all of the item's tags.
+    pub async fn add_tags(&self, media_id: Uuid, tags: &[&str]) -> MediaResult<Vec<MediaTag>> {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM media_items WHERE id = $1)")
+                .bind(media_id)
+                .fetch_one(&self.pool)
+                .await?;
+        if !exists {
+            return Err(MediaError::NotFound(media_id));
+        }
+
+        let mut names = Vec::new();
+        let mut slugs: Vec<String> = Vec::new();
+        for (name, slug) in tags.iter().filter_map(|tag| normalize_tag(tag)) {
+            if !slugs.contains(&slug) {
+                names.push(name);
+                slugs.push(slug);
+            }
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        // The no-op update makes existing tags come back from RETURNING
+        let tag_ids: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            INSERT INTO media_tags (name, slug)
+            SELECT * FROM UNNEST($1::text[], $2::text[])
+            ON CONFLICT (slug) DO UPDATE SET slug = EXCLUDED.slug
+            RETURNING id
+            "#,
+        )
+        .bind(&names)
+        .bind(&slugs)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO media_item_tags (media_id, tag_id)
+            SELECT $1, UNNEST($2::uuid[])
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(media_id)
+        .bind(&tag_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        self.tags_for(media_id).await
+    }
+
+    /// Remove tags from a media item; the tags themselves are kept
+    pub async fn remove_tags(&self, media_id: Uuid, tags: &[&str]) -> MediaResult<u64> {
+        let slugs = tag_slugs(tags);
+
+        let result = sqlx::query(
+            r#"
+            DELETE FROM media_item_tags
+            WHERE media_id = $1
+            AND tag_id IN (SELECT id FROM media_tags WHERE slug = ANY($2))
+            "#,
+        )
+        .bind(media_id)
+        .bind(&slugs)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Tags on a media item
+    pub async fn tags_for(&self, media_id: Uuid) -> MediaResult<Vec<MediaTag>> {
+        let tags: Vec<MediaTag> = sqlx::query_as(
+            r#"
+            SELECT t.id, t.name, t.slug, t.created_at
+            FROM media_tags t
+            JOIN media_item_tags mt ON mt.tag_id = t.id
+            WHERE mt.media_id = $1
+            ORDER BY t.name
+            "#,
+        )
+        .bind(media_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// All tags with the number of media items using each
+    pub async fn list_tags(&self) -> MediaResult<Vec<(MediaTag, i64)>> {
+        let rows: Vec<(Uuid, String, String, DateTime<Utc>, i64)> = sqlx::query_as(
+            r#"
+            SELECT t.id, t.name, t.slug, t.created_at, COUNT(mt.media_id)
+            FROM media_tags t
+            LEFT JOIN media_item_tags mt ON mt.tag_id = t.id
+            GROUP BY t.id
+            ORDER BY t.name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, name, slug, created_at, count)| {
+                (
+                    MediaTag {
+                        id,
+                        name,
+                        slug,
+                        created_at,
+                    },
+                    count,
+                )
+            })
+            .collect())
+    }
+
+    /// Delete a tag; its associations go with it and the media is untouched
+    pub async fn delete_tag(&self, tag_id: Uuid) -> MediaResult<()> {
+        let result = sqlx::query("DELETE FROM media_tags WHERE id = $1")
+            .bind(tag_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(MediaError::NotFound(tag_id));
+        }
+
+        Ok(())
+    }
+
+    /// List media with any or all of the given tags
+    pub async fn list_by_tags(
+        &self,
+        tags: &[&str],
+        tag_match: TagMatch,
+        limit: i64,
+        offset: i64,
+    ) -> MediaResult<Vec<MediaItem>> {
+        let filter = CollectionFilter {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            tag_match,
+            ..Default::default()
+        };
+        self.filter_media(&filter, limit, offset).await
+    }
+
+    // ========================================================================
+    // Smart Collections
+    // ========================================================================
+
+    /// List media matching a collection filter
+    pub async fn filter_media(
+        &self,
+        filter: &CollectionFilter,
+        limit: i64,
+        offset: i64,
+    ) -> MediaResult<Vec<MediaItem>> {
+        let tags: Vec<&str> = filter.tags.iter().map(String::as_str).collect();
+        let slugs = tag_slugs(&tags);
+        // Tags given but none usable can't match anything
+        if slugs.is_empty() && !filter.tags.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let search_pattern = filter
+            .search
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| format!("%{}%", s));
+
+        let media: Vec<MediaItem> = sqlx::query_as(
+            r#"
+            SELECT
+                id, filename, title, alt_text, caption, description,
+                media_type, mime_type, file_size, path, url, thumbnail_url,
+                width, height, duration, file_hash, folder_id,
+                metadata, uploaded_by, created_at, updated_at
+            FROM media_items
+            WHERE ($1::text IS NULL OR media_type::text = $1)
+            AND ($2::timestamptz IS NULL OR created_at >= $2)
+            AND ($3::timestamptz IS NULL OR created_at < $3)
+            AND ($4::bigint IS NULL OR file_size >= $4)
+            AND ($5::bigint IS NULL OR file_size <= $5)
+            AND (cardinality($6::text[]) = 0 OR id IN (
+                SELECT mt.media_id
+                FROM media_item_tags mt
+                JOIN media_tags t ON t.id = mt.tag_id
+                WHERE t.slug = ANY($6)
+                GROUP BY mt.media_id
+                HAVING NOT $7 OR COUNT(DISTINCT t.id) = cardinality($6)
+            ))
+            AND ($8::text IS NULL
+                OR title ILIKE $8
+                OR filename ILIKE $8
+                OR alt_text ILIKE $8
+                OR caption ILIKE $8
+                OR EXISTS (
+                    SELECT 1
+                    FROM media_item_tags mt
+                    JOIN media_tags t ON t.id = mt.tag_id
+                    WHERE mt.media_id = media_items.id AND t.name ILIKE $8
+                ))
+            ORDER BY created_at DESC
+            LIMIT $9 OFFSET $10
+            "#,
+        )
+        .bind(filter.media_type.as_ref().map(|t| t.to_string()))
+        .bind(filter.created_after)
+        .bind(filter.created_before)
+        .bind(filter.min_size)
+        .bind(filter.max_size)
+        .bind(&slugs)
+        .bind(filter.tag_match == TagMatch::All)
+        .bind(search_pattern)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(media)
+    }
+
+    /// Save a smart collection
+    pub async fn create_collection(
+        &self,
+        name: &str,
+        filter: &CollectionFilter,
+    ) -> MediaResult<SmartCollection> {
+        let row: SmartCollectionRow = sqlx::query_as(
+            r#"
+            INSERT INTO media_smart_collections (name, filter)
+            VALUES ($1, $2)
+            RETURNING id, name, filter, created_at, updated_at
+            "#,
+        )
+        .bind(name)
+        .bind(filter_json(filter)?)
+        .fetch_one(&self.pool)
+        .await?;
+
+        row.try_into()
+    }
+
+    /// Get a smart collection by ID
+    pub async fn get_collection(&self, id: Uuid) -> MediaResult<SmartCollection> {
+        let row: Option<SmartCollectionRow> = sqlx::query_as(
+            "SELECT id, name, filter, created_at, updated_at FROM media_smart_collections WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.ok_or(MediaError::NotFound(id))?.try_into()
+    }
+
+    /// List smart collections
+    pub async fn list_collections(&self) -> MediaResult<Vec<SmartCollection>> {
+        let rows: Vec<SmartCollectionRow> = sqlx::query_as(
+            "SELECT id, name, filter, created_at, updated_at FROM media_smart_collections ORDER BY name",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(SmartCollection::try_from).collect()
+    }
+
+    /// Rename a smart collection or change its filter
+    pub async fn update_collection(
+        &self,
+        id: Uuid,
+        name: &str,
+        filter: &CollectionFilter,
+    ) -> MediaResult<SmartCollection> {
+        let row: Option<SmartCollectionRow> = sqlx::query_as(
+            r#"
+            UPDATE media_smart_collections
+            SET name = $2, filter = $3, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, name, filter, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(name)
+        .bind(filter_json(filter)?)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.ok_or(MediaError::NotFound(id))?.try_into()
+    }
+
+    /// Delete a smart collection; its media is untouched
+    pub async fn delete_collection(&self, id: Uuid) -> MediaResult<()> {
+        sqlx::query("DELETE FROM media_smart_collections WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Current contents of a smart collection
+    pub async fn collection_items(
+        &self,
+        id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> MediaResult<Vec<MediaItem>> {
+        let collection = self.get_collection(id).await?;
+        self.filter_media(&collection.filter, limit, offset).await
+    }
+}
+
+fn filter_json(filter: &CollectionFilter) -> MediaResult<serde_json::Value> {
+    serde_json::to_value(filter).map_err(|e| MediaError::ProcessingError(e.to_string()))
+}
+
+/// Clean up a tag name, returning the display name and slug
+fn normalize_tag(tag: &str) -> Option<(String, String)> {
+    let name: String = tag
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(MAX_TAG_LENGTH)
+        .collect();
+
+    let mut slug = String::with_capacity(name.len());
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+
+    if slug.is_empty() {
+        None
+    } else {
+        Some((name.trim().to_string(), slug))
+    }
+}
+
+/// Unique slugs for a list of tag names
+fn tag_slugs(tags: &[&str]) -> Vec<String> {
+    let mut slugs: Vec<String> = tags
+        .iter()
+        .filter_map(|tag| normalize_tag(tag))
+        .map(|(_, slug)| slug)
+        .collect();
+    slugs.sort();
+    slugs.dedup();
+    slugs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tag() {
+        assert_eq!(
+            normalize_tag("  Summer   Trip 2024 "),
+            Some((
+                "Summer Trip 2024".to_string(),
+                "summer-trip-2024".to_string()
+            ))
+        );
+        assert_eq!(
+            normalize_tag("C++ & Rust!"),
+            Some(("C++ & Rust!".to_string(), "c-rust".to_string()))
+        );
+        assert_eq!(normalize_tag(" -- "), None);
+        assert_eq!(
+            tag_slugs(&["Beach", "beach ", "BEACH", "sunset"]),
+            vec!["beach", "sunset"]
+        );
+    }
+
+    #[test]
+    fn test_collection_filter_defaults() {
+        let filter: CollectionFilter =
+            serde_json::from_str(r#"{"media_type": "image", "tags": ["logo"]}"#).unwrap();
+        assert_eq!(filter.media_type, Some(MediaType::Image));
+        assert_eq!(filter.tag_match, TagMatch::Any);
+        assert!(filter.created_after.is_none());
+
+        let json = serde_json::to_value(CollectionFilter {
+            tags: vec!["logo".to_string(), "dark".to_string()],
+            tag_match: TagMatch::All,
+            min_size: Some(1024),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(json["tag_match"], "all");
+        let round_trip: CollectionFilter = serde_json::from_value(json).unwrap();
+        assert_eq!(round_trip.tags.len(), 2);
+        assert_eq!(round_trip.min_size, Some(1024));
+    }
+}
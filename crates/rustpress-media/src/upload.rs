@@ -2,42 +2,104 @@
 //!
 //! Provides secure file upload functionality including:
 //! - Chunked uploads for large files
+//! - Resumable uploads that survive dropped connections
 //! - Progress tracking
 //! - File validation
+//! - Quarantine and scanning before files join the library
+//! - Background alt text suggestions for images
 //! - Automatic thumbnail generation
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 use sqlx::PgPool;
+use std::collections::HashMap;
+use std::io::SeekFrom;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
 use tokio::fs;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
 use uuid::Uuid;
 
 use crate::{
+    alt_text::{
+        generate_alt_text, store_alt_text_suggestion, AltTextGenerator, AltTextSuggestion,
+        NoopAltTextGenerator,
+    },
+    document::{is_pdf, PdfThumbnailer},
+    editor::FocalPoint,
     image_optimizer::{ImageOptimizer, OptimizationConfig},
+    scan::{NoopScanner, QuarantinedFile, UploadScanner, QUARANTINE_DIR},
+    srcset::ImageVariantFormat,
+    video::{GifAnimation, GifVideoConverter, GifVideoFormat, TranscodedVersion},
     MediaConfig, MediaError, MediaItem, MediaResult, MediaType,
 };
 
+/// Size of the cropped thumbnail generated for images
+pub(crate) const THUMBNAIL_SIZE: (u32, u32) = (300, 300);
+
+/// Default idle time before an unfinished resumable upload is removed
+pub const DEFAULT_RESUMABLE_UPLOAD_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Files kept in a resumable upload's temp directory
+const RESUMABLE_STATE_FILE: &str = "upload.json";
+const RESUMABLE_DATA_FILE: &str = "data";
+
 /// Upload service
 pub struct UploadService {
     pool: PgPool,
     config: MediaConfig,
     optimizer: ImageOptimizer,
+    gif_converter: GifVideoConverter,
+    pdf_thumbnailer: PdfThumbnailer,
+    scanner: Arc<dyn UploadScanner>,
+    alt_text_generator: Arc<dyn AltTextGenerator>,
 }
 
 impl UploadService {
     /// Create new upload service
     pub fn new(pool: PgPool, config: MediaConfig) -> Self {
+        let pdf_thumbnailer = PdfThumbnailer::new(config.document_thumbnail_dpi);
         Self {
             pool,
             config,
             optimizer: ImageOptimizer::new(OptimizationConfig::default()),
+            gif_converter: GifVideoConverter::default(),
+            pdf_thumbnailer,
+            scanner: Arc::new(NoopScanner),
+            alt_text_generator: Arc::new(NoopAltTextGenerator),
         }
     }
 
+    /// Use a specific ffmpeg binary for GIF to video conversion
+    pub fn with_gif_converter(mut self, converter: GifVideoConverter) -> Self {
+        self.gif_converter = converter;
+        self
+    }
+
+    /// Use a specific PDF thumbnailer for document uploads
+    pub fn with_pdf_thumbnailer(mut self, thumbnailer: PdfThumbnailer) -> Self {
+        self.pdf_thumbnailer = thumbnailer;
+        self
+    }
+
+    /// Scan uploads with `scanner` when `scan_uploads` is enabled
+    pub fn with_scanner(mut self, scanner: Arc<dyn UploadScanner>) -> Self {
+        self.scanner = scanner;
+        self
+    }
+
+    /// Suggest alt text for images with `generator` when `suggest_alt_text`
+    /// is enabled
+    pub fn with_alt_text_generator(mut self, generator: Arc<dyn AltTextGenerator>) -> Self {
+        self.alt_text_generator = generator;
+        self
+    }
+
     /// Upload a file
+    ///
+    /// With `scan_uploads` enabled the file is written to quarantine and
+    /// scanned first; it only reaches the library if the scan passes.
     pub async fn upload(
         &self,
         filename: &str,
@@ -57,6 +119,21 @@ impl UploadService {
             return Ok(existing);
         }
 
+        let quarantined = if self.config.scan_uploads {
+            let dir = Path::new(&self.config.storage_path).join(QUARANTINE_DIR);
+            let file = QuarantinedFile::create(&dir, data).await?;
+            Some(
+                file.scan(
+                    self.scanner.clone(),
+                    Duration::from_secs(self.config.scan_timeout_secs),
+                    self.config.scan_failure_policy,
+                )
+                .await?,
+            )
+        } else {
+            None
+        };
+
         // Determine media type
         let media_type = MediaType::from_mime(content_type);
 
@@ -69,24 +146,51 @@ impl UploadService {
             fs::create_dir_all(parent).await?;
         }
 
+        // Animated GIFs are stored untouched: re-encoding keeps only the first frame
+        let animation = if content_type == "image/gif" {
+            GifAnimation::probe(data)
+        } else {
+            None
+        };
+
         // Process and save file
-        let (width, height, processed_data) =
-            if media_type == MediaType::Image && self.config.optimize_images {
-                let (w, h) = ImageOptimizer::dimensions(data)?;
-                let optimized = self.optimizer.optimize(data, image::guess_format(data)?)?;
-                (Some(w as i32), Some(h as i32), optimized)
-            } else {
-                (None, None, data.to_vec())
-            };
+        let (width, height, processed_data) = if let Some(animation) = &animation {
+            (
+                Some(animation.width as i32),
+                Some(animation.height as i32),
+                None,
+            )
+        } else if media_type == MediaType::Image && self.config.optimize_images {
+            let (w, h) = ImageOptimizer::dimensions(data)?;
+            let optimized = self.optimizer.optimize(data, image::guess_format(data)?)?;
+            (Some(w as i32), Some(h as i32), Some(optimized))
+        } else {
+            (None, None, None)
+        };
 
-        // Write file
-        let mut file = fs::File::create(&full_path).await?;
-        file.write_all(&processed_data).await?;
-        file.flush().await?;
+        // Write file; a scanned upload stored as-is is moved out of quarantine
+        match (processed_data.as_deref(), quarantined) {
+            (None, Some(quarantined)) => quarantined.promote(Path::new(&full_path)).await?,
+            (processed, quarantined) => {
+                let mut file = fs::File::create(&full_path).await?;
+                file.write_all(processed.unwrap_or(data)).await?;
+                file.flush().await?;
+                if let Some(mut quarantined) = quarantined {
+                    quarantined.discard().await;
+                }
+            }
+        }
+        let file_size = processed_data.as_ref().map_or(data.len(), Vec::len);
 
-        // Generate thumbnail for images
+        // Generate thumbnail for images and, if enabled, PDFs
+        let mut metadata = serde_json::json!({});
         let thumbnail_url = if media_type == MediaType::Image {
-            Some(self.generate_thumbnail(&full_path, data).await?)
+            Some(
+                self.generate_thumbnail(&full_path, data, FocalPoint::center())
+                    .await?,
+            )
+        } else if self.config.document_thumbnails && is_pdf(content_type, data) {
+            self.generate_pdf_thumbnail(&full_path, &mut metadata).await
         } else {
             None
         };
@@ -99,7 +203,7 @@ impl UploadService {
                 file_size, path, url, thumbnail_url, width, height,
                 file_hash, folder_id, metadata, uploaded_by
             )
-            VALUES ($1, $2, '', $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, '{}', $13)
+            VALUES ($1, $2, '', $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             RETURNING
                 id, filename, title, alt_text, caption, description,
                 media_type, mime_type, file_size, path, url, thumbnail_url,
@@ -111,7 +215,7 @@ impl UploadService {
         .bind(filename) // title defaults to filename
         .bind(media_type.to_string())
         .bind(content_type)
-        .bind(processed_data.len() as i64)
+        .bind(file_size as i64)
         .bind(&path)
         .bind(&url)
         .bind(&thumbnail_url)
@@ -119,18 +223,166 @@ impl UploadService {
         .bind(height)
         .bind(&file_hash)
         .bind(folder_id)
+        .bind(&metadata)
         .bind(uploaded_by)
         .fetch_one(&self.pool)
         .await?;
 
+        if media_type == MediaType::Image && self.config.suggest_alt_text {
+            self.spawn_alt_text_suggestion(&media, full_path.clone());
+        }
+
         // Generate srcset variants if enabled
         if media_type == MediaType::Image && self.config.enable_srcset {
             self.generate_srcset_variants(&media, data).await?;
         }
 
+        match animation {
+            Some(animation) => self.record_animation(media, &full_path, animation).await,
+            None => Ok(media),
+        }
+    }
+
+    /// Store animation metadata for an animated GIF and, when enabled,
+    /// convert it to looping video.
+    ///
+    /// A failed conversion is logged and leaves the GIF as the only version.
+    async fn record_animation(
+        &self,
+        media: MediaItem,
+        full_path: &str,
+        animation: GifAnimation,
+    ) -> MediaResult<MediaItem> {
+        let versions = if self.config.gif_to_video {
+            self.convert_gif_to_video(&media, full_path, &animation)
+                .await?
+        } else {
+            Vec::new()
+        };
+
+        let mut patch = serde_json::json!({ "animation": animation });
+        if !versions.is_empty() {
+            patch["animated_video"] = serde_json::to_value(&versions)
+                .map_err(|e| MediaError::ProcessingError(e.to_string()))?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO video_metadata (
+                    media_id, codec, framerate, resolution, has_audio, poster_url, transcoded_versions
+                )
+                VALUES ($1, $2, $3, $4, FALSE, $5, $6)
+                ON CONFLICT (media_id) DO UPDATE SET
+                    codec = EXCLUDED.codec,
+                    framerate = EXCLUDED.framerate,
+                    resolution = EXCLUDED.resolution,
+                    has_audio = FALSE,
+                    poster_url = EXCLUDED.poster_url,
+                    transcoded_versions = EXCLUDED.transcoded_versions
+                "#,
+            )
+            .bind(media.id)
+            .bind(&versions[0].codec)
+            .bind(animation.framerate())
+            .bind(format!("{}x{}", animation.width, animation.height))
+            .bind(&media.thumbnail_url)
+            .bind(&patch["animated_video"])
+            .execute(&self.pool)
+            .await?;
+        }
+
+        let media: MediaItem = sqlx::query_as(
+            r#"
+            UPDATE media_items
+            SET duration = $2, metadata = metadata || $3, updated_at = NOW()
+            WHERE id = $1
+            RETURNING
+                id, filename, title, alt_text, caption, description,
+                media_type, mime_type, file_size, path, url, thumbnail_url,
+                width, height, duration, file_hash, folder_id,
+                metadata, uploaded_by, created_at, updated_at
+            "#,
+        )
+        .bind(media.id)
+        .bind(animation.duration_secs())
+        .bind(&patch)
+        .fetch_one(&self.pool)
+        .await?;
+
         Ok(media)
     }
 
+    /// Transcode an animated GIF to each video format next to the original,
+    /// recording every successful conversion in `media_variants`
+    async fn convert_gif_to_video(
+        &self,
+        media: &MediaItem,
+        full_path: &str,
+        animation: &GifAnimation,
+    ) -> MediaResult<Vec<TranscodedVersion>> {
+        let base_path = Path::new(&media.path);
+        let stem = base_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("animation");
+        let parent = base_path
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_default();
+
+        let mut versions = Vec::new();
+        for format in GifVideoFormat::ALL {
+            let variant_path = parent
+                .join(format!("{}.{}", stem, format.extension()))
+                .to_string_lossy()
+                .to_string();
+            let full_variant_path = format!("{}/{}", self.config.storage_path, variant_path);
+
+            if let Err(e) = self
+                .gif_converter
+                .convert(Path::new(full_path), Path::new(&full_variant_path), format)
+                .await
+            {
+                tracing::warn!(media_id = %media.id, error = %e, "GIF to video conversion failed");
+                continue;
+            }
+
+            let file_size = fs::metadata(&full_variant_path).await?.len() as i64;
+            let url = format!("{}/{}", self.config.base_url, variant_path);
+            let bitrate = if animation.duration_ms > 0 {
+                (file_size as f64 * 8.0 / animation.duration_secs() / 1000.0) as i32
+            } else {
+                0
+            };
+
+            sqlx::query(
+                r#"
+                INSERT INTO media_variants (media_id, variant_type, width, height, file_size, path, url, format)
+                VALUES ($1, 'video', $2, $3, $4, $5, $6, $7)
+                "#,
+            )
+            .bind(media.id)
+            .bind(animation.width as i32)
+            .bind(animation.height as i32)
+            .bind(file_size)
+            .bind(&variant_path)
+            .bind(&url)
+            .bind(format.extension())
+            .execute(&self.pool)
+            .await?;
+
+            versions.push(TranscodedVersion {
+                quality: "original".to_string(),
+                format: format.extension().to_string(),
+                codec: format.codec().to_string(),
+                bitrate,
+                file_size,
+                url,
+            });
+        }
+
+        Ok(versions)
+    }
+
     /// Start chunked upload
     pub async fn start_chunked_upload(
         &self,
@@ -252,6 +504,252 @@ impl UploadService {
         Ok(())
     }
 
+    /// Start a resumable upload.
+    ///
+    /// Chunks are written straight into a single file at their offsets, so
+    /// they may arrive in any order, be retried, or be sent in parallel.
+    /// State lives next to the data in the temp directory, which lets any
+    /// server process sharing the storage path continue the upload.
+    pub async fn initiate_upload(&self, metadata: UploadMetadata) -> MediaResult<Uuid> {
+        self.validate_upload(
+            &metadata.filename,
+            &metadata.content_type,
+            metadata.total_size,
+        )?;
+
+        let upload_id = Uuid::new_v4();
+        fs::create_dir_all(self.resumable_dir(upload_id)).await?;
+
+        let now = Utc::now();
+        let upload = ResumableUpload {
+            id: upload_id,
+            metadata,
+            received: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        };
+        self.save_resumable(&upload).await?;
+
+        Ok(upload_id)
+    }
+
+    /// Write a chunk at `offset` and return the updated upload state.
+    ///
+    /// Re-sending a range that was already received is harmless.
+    pub async fn put_chunk(
+        &self,
+        upload_id: Uuid,
+        offset: u64,
+        data: &[u8],
+    ) -> MediaResult<ResumableUpload> {
+        let upload = self.load_live_resumable(upload_id).await?;
+
+        let end = offset.saturating_add(data.len() as u64);
+        if end > upload.metadata.total_size {
+            return Err(MediaError::FileTooLarge {
+                size: end,
+                max: upload.metadata.total_size,
+            });
+        }
+        if data.is_empty() {
+            return Ok(upload);
+        }
+
+        // Writes to distinct ranges go through separate handles and never
+        // overlap; only the state update below is serialized
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(self.resumable_dir(upload_id).join(RESUMABLE_DATA_FILE))
+            .await?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        file.write_all(data).await?;
+        file.sync_data().await?;
+
+        let lock = upload_lock(upload_id);
+        let _guard = lock.lock().await;
+
+        let mut upload = self.load_resumable(upload_id).await?;
+        upload.record_range(offset, end);
+        upload.updated_at = Utc::now();
+        self.save_resumable(&upload).await?;
+
+        Ok(upload)
+    }
+
+    /// Current state of a resumable upload, including the offset to resume from
+    pub async fn upload_status(&self, upload_id: Uuid) -> MediaResult<ResumableUpload> {
+        self.load_live_resumable(upload_id).await
+    }
+
+    /// Assemble a finished resumable upload into a media item.
+    ///
+    /// The assembled file must cover the declared size, fit within
+    /// `max_upload_size` and match the checksum given at initiation. A
+    /// checksum mismatch discards the upload, since there is no way to tell
+    /// which chunk was bad.
+    pub async fn complete_upload(&self, upload_id: Uuid) -> MediaResult<MediaItem> {
+        let lock = upload_lock(upload_id);
+        let _guard = lock.lock().await;
+
+        let (upload, data) = self.assemble_upload(upload_id).await?;
+        let metadata = &upload.metadata;
+        let media = self
+            .upload(
+                &metadata.filename,
+                &metadata.content_type,
+                &data,
+                metadata.uploaded_by,
+                metadata.folder_id,
+            )
+            .await?;
+
+        fs::remove_dir_all(self.resumable_dir(upload_id)).await?;
+        release_upload_lock(upload_id);
+
+        Ok(media)
+    }
+
+    /// Read and verify the assembled file; callers hold the upload lock
+    async fn assemble_upload(&self, upload_id: Uuid) -> MediaResult<(ResumableUpload, Vec<u8>)> {
+        let upload = self.load_live_resumable(upload_id).await?;
+        let total_size = upload.metadata.total_size;
+
+        if !upload.is_complete() {
+            return Err(MediaError::ProcessingError(format!(
+                "Upload incomplete: {} of {} bytes received",
+                upload.received_bytes(),
+                total_size
+            )));
+        }
+
+        let data_path = self.resumable_dir(upload_id).join(RESUMABLE_DATA_FILE);
+        let size = match fs::metadata(&data_path).await {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e.into()),
+        };
+        if size > self.config.max_upload_size {
+            return Err(MediaError::FileTooLarge {
+                size,
+                max: self.config.max_upload_size,
+            });
+        }
+        if size != total_size {
+            return Err(MediaError::ProcessingError(format!(
+                "Assembled upload is {} bytes, expected {}",
+                size, total_size
+            )));
+        }
+
+        let data = if size == 0 {
+            Vec::new()
+        } else {
+            fs::read(&data_path).await?
+        };
+
+        if let Some(expected) = &upload.metadata.sha256 {
+            let actual = self.hash_file(&data);
+            if !actual.eq_ignore_ascii_case(expected) {
+                fs::remove_dir_all(self.resumable_dir(upload_id)).await?;
+                release_upload_lock(upload_id);
+                return Err(MediaError::ProcessingError(format!(
+                    "Checksum mismatch: expected {}, got {}",
+                    expected, actual
+                )));
+            }
+        }
+
+        Ok((upload, data))
+    }
+
+    /// Remove resumable uploads idle for longer than the configured TTL.
+    ///
+    /// Also clears leftover chunk directories from the legacy chunked upload
+    /// API, judged by their modification time. Uploads that are being
+    /// written to are skipped. Returns the number of uploads removed.
+    pub async fn cleanup_expired_uploads(&self) -> MediaResult<usize> {
+        let temp_dir = Path::new(&self.config.storage_path).join("temp");
+        let mut entries = match fs::read_dir(&temp_dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+
+        let ttl = chrono::Duration::seconds(self.config.resumable_upload_ttl_secs as i64);
+        let cutoff = Utc::now() - ttl;
+        let mut removed = 0;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let Ok(upload_id) = entry.file_name().to_string_lossy().parse::<Uuid>() else {
+                continue;
+            };
+
+            let lock = upload_lock(upload_id);
+            let Ok(guard) = lock.try_lock() else {
+                continue;
+            };
+
+            let last_active = match self.load_resumable(upload_id).await {
+                Ok(upload) => upload.updated_at,
+                Err(_) => match entry.metadata().await.and_then(|m| m.modified()) {
+                    Ok(modified) => DateTime::<Utc>::from(modified),
+                    Err(_) => continue,
+                },
+            };
+
+            if last_active < cutoff {
+                fs::remove_dir_all(entry.path()).await?;
+                drop(guard);
+                release_upload_lock(upload_id);
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    fn resumable_dir(&self, upload_id: Uuid) -> PathBuf {
+        Path::new(&self.config.storage_path)
+            .join("temp")
+            .join(upload_id.to_string())
+    }
+
+    async fn load_resumable(&self, upload_id: Uuid) -> MediaResult<ResumableUpload> {
+        let path = self.resumable_dir(upload_id).join(RESUMABLE_STATE_FILE);
+        let json = match fs::read(&path).await {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(MediaError::NotFound(upload_id))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        serde_json::from_slice(&json).map_err(|e| MediaError::ProcessingError(e.to_string()))
+    }
+
+    /// Load an upload, treating one past its TTL as gone
+    async fn load_live_resumable(&self, upload_id: Uuid) -> MediaResult<ResumableUpload> {
+        let upload = self.load_resumable(upload_id).await?;
+        let idle = Utc::now().signed_duration_since(upload.updated_at);
+        if idle.num_seconds() > self.config.resumable_upload_ttl_secs as i64 {
+            return Err(MediaError::NotFound(upload_id));
+        }
+        Ok(upload)
+    }
+
+    /// Persist upload state, replacing the previous file atomically
+    async fn save_resumable(&self, upload: &ResumableUpload) -> MediaResult<()> {
+        let dir = self.resumable_dir(upload.id);
+        let json =
+            serde_json::to_vec(upload).map_err(|e| MediaError::ProcessingError(e.to_string()))?;
+
+        let temp = dir.join(format!("{}.tmp", RESUMABLE_STATE_FILE));
+        fs::write(&temp, json).await?;
+        fs::rename(&temp, dir.join(RESUMABLE_STATE_FILE)).await?;
+        Ok(())
+    }
+
     /// Validate upload
     fn validate_upload(&self, filename: &str, content_type: &str, size: u64) -> MediaResult<()> {
         // Check size
@@ -275,11 +773,9 @@ impl UploadService {
         Ok(())
     }
 
-    /// Hash file for deduplication
+    /// Hash file for deduplication, keyed the same as storage-level dedup
     fn hash_file(&self, data: &[u8]) -> String {
-        let mut hasher = Sha256::new();
-        hasher.update(data);
-        hex::encode(hasher.finalize())
+        rustpress_storage::dedup::content_hash(data)
     }
 
     /// Find existing file by hash
@@ -334,8 +830,16 @@ impl UploadService {
     }
 
     /// Generate thumbnail
-    async fn generate_thumbnail(&self, original_path: &str, data: &[u8]) -> MediaResult<String> {
-        let thumb_data = self.optimizer.generate_thumbnail_exact(data, 300, 300)?;
+    async fn generate_thumbnail(
+        &self,
+        original_path: &str,
+        data: &[u8],
+        focal_point: FocalPoint,
+    ) -> MediaResult<String> {
+        let (width, height) = THUMBNAIL_SIZE;
+        let thumb_data =
+            self.optimizer
+                .generate_thumbnail_focused(data, width, height, focal_point)?;
 
         let path = Path::new(original_path);
         let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("thumb");
@@ -358,6 +862,47 @@ impl UploadService {
         Ok(format!("{}/{}", self.config.base_url, relative_path))
     }
 
+    /// Render the first page of a PDF as its thumbnail, recording the page
+    /// count in `metadata`.
+    ///
+    /// Encrypted or malformed PDFs, and missing poppler tools, leave the
+    /// document without a thumbnail rather than failing the upload.
+    async fn generate_pdf_thumbnail(
+        &self,
+        full_path: &str,
+        metadata: &mut serde_json::Value,
+    ) -> Option<String> {
+        let pdf = Path::new(full_path);
+        let info = match self.pdf_thumbnailer.info(pdf).await {
+            Ok(info) => info,
+            Err(e) => {
+                tracing::warn!(path = %full_path, error = %e, "Failed to read PDF");
+                return None;
+            }
+        };
+
+        metadata["page_count"] = info.page_count.into();
+        if info.encrypted {
+            metadata["encrypted"] = true.into();
+            return None;
+        }
+
+        let result = match self.pdf_thumbnailer.render_first_page(pdf).await {
+            Ok(page) => {
+                self.generate_thumbnail(full_path, &page, FocalPoint::center())
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(url) => Some(url),
+            Err(e) => {
+                tracing::warn!(path = %full_path, error = %e, "Failed to render PDF thumbnail");
+                None
+            }
+        }
+    }
+
     /// Generate srcset variants
     async fn generate_srcset_variants(&self, media: &MediaItem, data: &[u8]) -> MediaResult<()> {
         let srcset_gen = crate::srcset::SrcsetGenerator::default_config();
@@ -406,6 +951,346 @@ impl UploadService {
 
         Ok(())
     }
+
+    /// Suggest alt text for a new image in a background task.
+    ///
+    /// The upload never waits for the generator, and a failed suggestion is
+    /// only logged.
+    fn spawn_alt_text_suggestion(&self, media: &MediaItem, full_path: String) {
+        let pool = self.pool.clone();
+        let generator = self.alt_text_generator.clone();
+        let timeout = Duration::from_secs(self.config.alt_text_timeout_secs);
+        let apply = self.config.apply_suggested_alt_text;
+        let media_id = media.id;
+        let mime_type = media.mime_type.clone();
+
+        tokio::spawn(async move {
+            let name = generator.name().to_string();
+            let result =
+                match generate_alt_text(generator, full_path.into(), mime_type, timeout).await {
+                    Ok(Some(suggestion)) => {
+                        store_alt_text_suggestion(&pool, media_id, &suggestion, apply)
+                            .await
+                            .map(|_| ())
+                    }
+                    Ok(None) => Ok(()),
+                    Err(e) => Err(e),
+                };
+            if let Err(e) = result {
+                tracing::warn!(%media_id, generator = %name, error = %e, "Alt text suggestion failed");
+            }
+        });
+    }
+
+    /// List images without alt text or a stored suggestion, ordered by id,
+    /// for backfilling suggestions.
+    ///
+    /// Pass the last id of the previous page as `after` to continue.
+    pub async fn alt_text_candidates(
+        &self,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> MediaResult<Vec<MediaItem>> {
+        let media: Vec<MediaItem> = sqlx::query_as(
+            r#"
+            SELECT
+                id, filename, title, alt_text, caption, description,
+                media_type, mime_type, file_size, path, url, thumbnail_url,
+                width, height, duration, file_hash, folder_id,
+                metadata, uploaded_by, created_at, updated_at
+            FROM media_items
+            WHERE media_type = 'image'
+              AND alt_text = ''
+              AND NOT metadata ? 'alt_text_suggested'
+              AND ($1::uuid IS NULL OR id > $1)
+            ORDER BY id
+            LIMIT $2
+            "#,
+        )
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(media)
+    }
+
+    /// Suggest alt text for an existing image and store it, waiting for the
+    /// generator.
+    ///
+    /// Unlike uploads, errors are returned so a backfill can report them.
+    /// The suggestion is applied to `alt_text` as `apply_suggested_alt_text`
+    /// configures.
+    pub async fn suggest_alt_text(&self, id: Uuid) -> MediaResult<AltTextSuggestion> {
+        let media: MediaItem = sqlx::query_as(
+            r#"
+            SELECT
+                id, filename, title, alt_text, caption, description,
+                media_type, mime_type, file_size, path, url, thumbnail_url,
+                width, height, duration, file_hash, folder_id,
+                metadata, uploaded_by, created_at, updated_at
+            FROM media_items
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(MediaError::NotFound(id))?;
+
+        if media.media_type != MediaType::Image {
+            return Err(MediaError::InvalidType(media.mime_type));
+        }
+
+        let full_path = Path::new(&self.config.storage_path).join(&media.path);
+        let suggestion = generate_alt_text(
+            self.alt_text_generator.clone(),
+            full_path,
+            media.mime_type,
+            Duration::from_secs(self.config.alt_text_timeout_secs),
+        )
+        .await?;
+
+        match suggestion {
+            Some(suggestion) => {
+                store_alt_text_suggestion(
+                    &self.pool,
+                    id,
+                    &suggestion,
+                    self.config.apply_suggested_alt_text,
+                )
+                .await
+            }
+            None => Ok(AltTextSuggestion {
+                media_id: id,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// List images eligible for re-optimization, ordered by id.
+    ///
+    /// Pass the last id of the previous page as `after` to continue.
+    pub async fn optimization_candidates(
+        &self,
+        since: Option<chrono::DateTime<Utc>>,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> MediaResult<Vec<MediaItem>> {
+        let media: Vec<MediaItem> = sqlx::query_as(
+            r#"
+            SELECT
+                id, filename, title, alt_text, caption, description,
+                media_type, mime_type, file_size, path, url, thumbnail_url,
+                width, height, duration, file_hash, folder_id,
+                metadata, uploaded_by, created_at, updated_at
+            FROM media_items
+            WHERE media_type = 'image'
+              AND ($1::timestamptz IS NULL OR created_at >= $1)
+              AND ($2::uuid IS NULL OR id > $2)
+            ORDER BY id
+            LIMIT $3
+            "#,
+        )
+        .bind(since)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(media)
+    }
+
+    /// Generate missing format variants (and optionally thumbnails) for an
+    /// existing image, using the same optimizer as uploads.
+    ///
+    /// Formats that already have a full-size variant are skipped, so running
+    /// this repeatedly only does the outstanding work. With `dry_run` nothing
+    /// is written; the report describes what would have been generated.
+    pub async fn reoptimize(
+        &self,
+        id: Uuid,
+        options: &ReoptimizeOptions,
+    ) -> MediaResult<ReoptimizeReport> {
+        let media: MediaItem = sqlx::query_as(
+            r#"
+            SELECT
+                id, filename, title, alt_text, caption, description,
+                media_type, mime_type, file_size, path, url, thumbnail_url,
+                width, height, duration, file_hash, folder_id,
+                metadata, uploaded_by, created_at, updated_at
+            FROM media_items
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(MediaError::NotFound(id))?;
+
+        if media.media_type != MediaType::Image {
+            return Err(MediaError::InvalidType(media.mime_type));
+        }
+
+        let full_path = format!("{}/{}", self.config.storage_path, media.path);
+        let data = fs::read(&full_path).await?;
+
+        let existing: Vec<String> = sqlx::query_scalar(
+            "SELECT format FROM media_variants WHERE media_id = $1 AND variant_type = 'full'",
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut report = ReoptimizeReport {
+            media_id: id,
+            original_size: data.len() as u64,
+            dry_run: options.dry_run,
+            ..Default::default()
+        };
+
+        let base_path = Path::new(&media.path);
+        let stem = base_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("img");
+        let parent = base_path
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_default();
+
+        for &format in &options.formats {
+            if media.mime_type == format.mime_type()
+                || existing.iter().any(|f| f == format.extension())
+            {
+                report.skipped.push(format);
+                continue;
+            }
+
+            let encoded = match format {
+                ImageVariantFormat::WebP => self.optimizer.to_webp(&data),
+                ImageVariantFormat::Avif => self.optimizer.to_avif(&data),
+                ImageVariantFormat::Jpeg => {
+                    self.optimizer.optimize(&data, image::ImageFormat::Jpeg)
+                }
+                ImageVariantFormat::Png => self.optimizer.optimize(&data, image::ImageFormat::Png),
+            };
+            let encoded = match encoded {
+                Ok(encoded) => encoded,
+                Err(MediaError::UnsupportedFormat(_)) => {
+                    report.unsupported.push(format);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            let variant_path = parent.join(format!("{}.{}", stem, format.extension()));
+            let variant_path = variant_path.to_string_lossy().to_string();
+
+            if !options.dry_run {
+                let full_variant_path = format!("{}/{}", self.config.storage_path, variant_path);
+                let mut file = fs::File::create(&full_variant_path).await?;
+                file.write_all(&encoded).await?;
+                file.flush().await?;
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO media_variants (media_id, variant_type, width, height, file_size, path, url, format)
+                    VALUES ($1, 'full', $2, $3, $4, $5, $6, $7)
+                    "#,
+                )
+                .bind(id)
+                .bind(media.width)
+                .bind(media.height)
+                .bind(encoded.len() as i64)
+                .bind(&variant_path)
+                .bind(format!("{}/{}", self.config.base_url, variant_path))
+                .bind(format.extension())
+                .execute(&self.pool)
+                .await?;
+            }
+
+            report.bytes_saved += report.original_size.saturating_sub(encoded.len() as u64);
+            report.generated.push(GeneratedVariant {
+                format,
+                file_size: encoded.len() as u64,
+                path: variant_path,
+            });
+        }
+
+        if options.regenerate_thumbnails {
+            if !options.dry_run {
+                let focal_point = FocalPoint::from_metadata(&media.metadata).unwrap_or_default();
+                let thumbnail_url = self
+                    .generate_thumbnail(&full_path, &data, focal_point)
+                    .await?;
+                sqlx::query(
+                    "UPDATE media_items SET thumbnail_url = $2, updated_at = NOW() WHERE id = $1",
+                )
+                .bind(id)
+                .bind(&thumbnail_url)
+                .execute(&self.pool)
+                .await?;
+            }
+            report.thumbnail_regenerated = true;
+        }
+
+        Ok(report)
+    }
+}
+
+/// Options for re-optimizing an existing image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReoptimizeOptions {
+    /// Alternate formats to generate next to the original
+    #[serde(default = "default_reoptimize_formats")]
+    pub formats: Vec<ImageVariantFormat>,
+    /// Regenerate the thumbnail from the original
+    #[serde(default)]
+    pub regenerate_thumbnails: bool,
+    /// Report what would change without writing anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn default_reoptimize_formats() -> Vec<ImageVariantFormat> {
+    vec![ImageVariantFormat::WebP]
+}
+
+impl Default for ReoptimizeOptions {
+    fn default() -> Self {
+        Self {
+            formats: default_reoptimize_formats(),
+            regenerate_thumbnails: false,
+            dry_run: false,
+        }
+    }
+}
+
+/// Outcome of re-optimizing a single image
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReoptimizeReport {
+    pub media_id: Uuid,
+    /// Size of the original file in bytes
+    pub original_size: u64,
+    /// Variants generated, or that would be on a dry run
+    pub generated: Vec<GeneratedVariant>,
+    /// Formats that already had a variant
+    pub skipped: Vec<ImageVariantFormat>,
+    /// Formats the optimizer can't encode
+    pub unsupported: Vec<ImageVariantFormat>,
+    pub thumbnail_regenerated: bool,
+    /// Bytes saved by serving the generated variants instead of the original
+    pub bytes_saved: u64,
+    pub dry_run: bool,
+}
+
+/// A format variant produced by re-optimization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedVariant {
+    pub format: ImageVariantFormat,
+    pub file_size: u64,
+    pub path: String,
 }
 
 /// Chunked upload state
@@ -430,6 +1315,95 @@ pub struct ChunkUploadResult {
     pub chunks_received: u32,
 }
 
+/// Client-supplied details for a resumable upload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadMetadata {
+    pub filename: String,
+    pub content_type: String,
+    /// Size of the complete file in bytes
+    pub total_size: u64,
+    /// Expected SHA-256 of the complete file, hex encoded
+    #[serde(default)]
+    pub sha256: Option<String>,
+    pub uploaded_by: Uuid,
+    #[serde(default)]
+    pub folder_id: Option<Uuid>,
+}
+
+/// Resumable upload state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResumableUpload {
+    pub id: Uuid,
+    pub metadata: UploadMetadata,
+    /// Received byte ranges as `(start, end)`, end-exclusive, sorted and merged
+    pub received: Vec<(u64, u64)>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ResumableUpload {
+    /// Bytes received contiguously from the start; where a client resumes
+    pub fn offset(&self) -> u64 {
+        match self.received.first() {
+            Some(&(0, end)) => end,
+            _ => 0,
+        }
+    }
+
+    /// Total bytes received across all ranges
+    pub fn received_bytes(&self) -> u64 {
+        self.received.iter().map(|(start, end)| end - start).sum()
+    }
+
+    /// Whether every byte of the file has been received
+    pub fn is_complete(&self) -> bool {
+        self.offset() == self.metadata.total_size
+    }
+
+    /// Add a received range, merging it with any it touches
+    fn record_range(&mut self, start: u64, end: u64) {
+        if start >= end {
+            return;
+        }
+
+        let mut merged = (start, end);
+        let mut ranges = Vec::with_capacity(self.received.len() + 1);
+        for &(s, e) in &self.received {
+            if e < merged.0 || s > merged.1 {
+                ranges.push((s, e));
+            } else {
+                merged = (merged.0.min(s), merged.1.max(e));
+            }
+        }
+        ranges.push(merged);
+        ranges.sort_unstable();
+        self.received = ranges;
+    }
+}
+
+/// Per-upload locks, shared by every `UploadService` in the process since
+/// services are created per request
+fn upload_lock(upload_id: Uuid) -> Arc<tokio::sync::Mutex<()>> {
+    upload_locks()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(upload_id)
+        .or_default()
+        .clone()
+}
+
+fn release_upload_lock(upload_id: Uuid) {
+    upload_locks()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&upload_id);
+}
+
+fn upload_locks() -> &'static Mutex<HashMap<Uuid, Arc<tokio::sync::Mutex<()>>>> {
+    static LOCKS: OnceLock<Mutex<HashMap<Uuid, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
+    LOCKS.get_or_init(Default::default)
+}
+
 /// Upload progress
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UploadProgress {
@@ -662,11 +1636,181 @@ mod tests {
 
     #[test]
     fn test_hash_consistency() {
+        use sha2::{Digest, Sha256};
+
         let data = b"test data";
         let mut hasher = Sha256::new();
         hasher.update(data);
         let hash = hex::encode(hasher.finalize());
 
         assert_eq!(hash.len(), 64);
+        assert_eq!(hash, rustpress_storage::dedup::content_hash(data));
+    }
+
+    #[test]
+    fn test_reoptimize_options_defaults() {
+        let options: ReoptimizeOptions = serde_json::from_str(r#"{"dry_run": true}"#).unwrap();
+        assert_eq!(options.formats, vec![ImageVariantFormat::WebP]);
+        assert!(!options.regenerate_thumbnails);
+        assert!(options.dry_run);
+
+        let options: ReoptimizeOptions =
+            serde_json::from_str(r#"{"formats": ["webp", "avif"]}"#).unwrap();
+        assert_eq!(
+            options.formats,
+            vec![ImageVariantFormat::WebP, ImageVariantFormat::Avif]
+        );
+    }
+
+    fn resumable_service(storage: &Path) -> UploadService {
+        let pool = PgPool::connect_lazy("postgres://localhost/rustpress").unwrap();
+        let config = MediaConfig {
+            storage_path: storage.display().to_string(),
+            max_upload_size: 64,
+            ..Default::default()
+        };
+        UploadService::new(pool, config)
+    }
+
+    fn resumable_metadata(data: &[u8]) -> UploadMetadata {
+        UploadMetadata {
+            filename: "notes.pdf".to_string(),
+            content_type: "application/pdf".to_string(),
+            total_size: data.len() as u64,
+            sha256: Some(rustpress_storage::dedup::content_hash(data)),
+            uploaded_by: Uuid::new_v4(),
+            folder_id: None,
+        }
+    }
+
+    #[test]
+    fn test_resumable_upload_ranges() {
+        let mut upload = ResumableUpload {
+            id: Uuid::new_v4(),
+            metadata: resumable_metadata(&[0; 30]),
+            received: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        upload.record_range(10, 20);
+        upload.record_range(25, 30);
+        assert_eq!(upload.offset(), 0);
+        assert_eq!(upload.received_bytes(), 15);
+
+        upload.record_range(0, 10);
+        upload.record_range(12, 15);
+        assert_eq!(upload.received, vec![(0, 20), (25, 30)]);
+        assert_eq!(upload.offset(), 20);
+        assert!(!upload.is_complete());
+
+        upload.record_range(18, 26);
+        assert_eq!(upload.received, vec![(0, 30)]);
+        assert!(upload.is_complete());
+    }
+
+    #[tokio::test]
+    async fn test_resumable_upload_assembles_concurrent_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = resumable_service(dir.path());
+        let data: Vec<u8> = (0..40u8).collect();
+
+        let upload_id = service
+            .initiate_upload(resumable_metadata(&data))
+            .await
+            .unwrap();
+
+        let status = service.put_chunk(upload_id, 0, &data[..10]).await.unwrap();
+        assert_eq!(status.offset(), 10);
+
+        // The rest arrives out of order and in parallel, with a retried chunk
+        let (a, b, c) = tokio::join!(
+            service.put_chunk(upload_id, 30, &data[30..]),
+            service.put_chunk(upload_id, 10, &data[10..20]),
+            service.put_chunk(upload_id, 20, &data[20..30]),
+        );
+        a.unwrap();
+        b.unwrap();
+        c.unwrap();
+        service
+            .put_chunk(upload_id, 10, &data[10..20])
+            .await
+            .unwrap();
+
+        let status = service.upload_status(upload_id).await.unwrap();
+        assert_eq!(status.offset(), 40);
+        assert!(status.is_complete());
+
+        let (_, assembled) = service.assemble_upload(upload_id).await.unwrap();
+        assert_eq!(assembled, data);
+    }
+
+    #[tokio::test]
+    async fn test_resumable_upload_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = resumable_service(dir.path());
+
+        // The declared total must fit max_upload_size
+        let too_big = resumable_metadata(&[0; 65]);
+        assert!(matches!(
+            service.initiate_upload(too_big).await,
+            Err(MediaError::FileTooLarge { size: 65, max: 64 })
+        ));
+
+        // Chunks can't extend past the declared total
+        let data = [7u8; 16];
+        let upload_id = service
+            .initiate_upload(resumable_metadata(&data))
+            .await
+            .unwrap();
+        assert!(matches!(
+            service.put_chunk(upload_id, 8, &[0; 16]).await,
+            Err(MediaError::FileTooLarge { size: 24, max: 16 })
+        ));
+
+        // Incomplete uploads can't be assembled
+        service.put_chunk(upload_id, 0, &data[..8]).await.unwrap();
+        assert!(matches!(
+            service.assemble_upload(upload_id).await,
+            Err(MediaError::ProcessingError(msg)) if msg.contains("8 of 16")
+        ));
+
+        // A checksum mismatch discards the upload
+        service.put_chunk(upload_id, 8, &[0; 8]).await.unwrap();
+        assert!(matches!(
+            service.assemble_upload(upload_id).await,
+            Err(MediaError::ProcessingError(msg)) if msg.contains("Checksum mismatch")
+        ));
+        assert!(matches!(
+            service.upload_status(upload_id).await,
+            Err(MediaError::NotFound(id)) if id == upload_id
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_expired_uploads() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = resumable_service(dir.path());
+
+        let fresh = service
+            .initiate_upload(resumable_metadata(b"fresh"))
+            .await
+            .unwrap();
+        let stale = service
+            .initiate_upload(resumable_metadata(b"stale"))
+            .await
+            .unwrap();
+
+        let mut upload = service.load_resumable(stale).await.unwrap();
+        upload.updated_at = Utc::now() - chrono::Duration::days(2);
+        service.save_resumable(&upload).await.unwrap();
+
+        assert!(matches!(
+            service.put_chunk(stale, 0, b"stale").await,
+            Err(MediaError::NotFound(_))
+        ));
+        assert_eq!(service.cleanup_expired_uploads().await.unwrap(), 1);
+        assert!(!service.resumable_dir(stale).exists());
+        assert!(service.upload_status(fresh).await.is_ok());
     }
 }
@@ -4,13 +4,16 @@
 //! - Metadata extraction
 //! - Thumbnail/poster generation
 //! - Transcoding support (via external tools)
+//! - Animated GIF to MP4/WebM conversion
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
+use std::path::Path;
 use uuid::Uuid;
 
-use crate::{MediaError, MediaResult};
+use crate::srcset::escape_html;
+use crate::{MediaError, MediaItem, MediaResult};
 
 /// Video metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -286,3 +289,484 @@ impl Default for VideoPlayerConfig {
         }
     }
 }
+
+// ============================================================================
+// Animated GIF conversion
+// ============================================================================
+
+/// Browsers play GIF frames with a delay of 0 or 1 centiseconds at 100ms
+const MIN_GIF_FRAME_DELAY_CS: u16 = 2;
+const DEFAULT_GIF_FRAME_DELAY_CS: u16 = 10;
+
+/// Animation properties of a multi-frame GIF
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GifAnimation {
+    pub width: u32,
+    pub height: u32,
+    pub frame_count: u32,
+    /// Length of one pass through all frames, as browsers play it
+    pub duration_ms: u64,
+    /// NETSCAPE2.0 loop count: `Some(0)` loops forever, `Some(n)` repeats
+    /// `n` more times, `None` plays once
+    pub loop_count: Option<u16>,
+}
+
+impl GifAnimation {
+    /// Inspect GIF data, returning `None` unless it has more than one frame
+    pub fn probe(data: &[u8]) -> Option<Self> {
+        let animation = parse_gif(data)?;
+        (animation.frame_count > 1).then_some(animation)
+    }
+
+    /// Whether the animation repeats indefinitely
+    pub fn loops_forever(&self) -> bool {
+        self.loop_count == Some(0)
+    }
+
+    /// Duration of one pass in seconds
+    pub fn duration_secs(&self) -> f64 {
+        self.duration_ms as f64 / 1000.0
+    }
+
+    /// Average frames per second
+    pub fn framerate(&self) -> f64 {
+        if self.duration_ms == 0 {
+            return 0.0;
+        }
+        self.frame_count as f64 / self.duration_secs()
+    }
+}
+
+/// Walk the GIF block structure, counting frames, summing frame delays and
+/// picking up the NETSCAPE2.0 loop extension
+fn parse_gif(data: &[u8]) -> Option<GifAnimation> {
+    if data.len() < 13 || !(data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a")) {
+        return None;
+    }
+
+    let width = u16::from_le_bytes([data[6], data[7]]) as u32;
+    let height = u16::from_le_bytes([data[8], data[9]]) as u32;
+    let mut pos = 13 + color_table_len(data[10]);
+
+    let mut frame_count = 0u32;
+    let mut duration_ms = 0u64;
+    let mut loop_count = None;
+    let mut pending_delay = None;
+
+    while let Some(&block) = data.get(pos) {
+        match block {
+            // Extension
+            0x21 => {
+                let label = *data.get(pos + 1)?;
+                pos += 2;
+                match label {
+                    // Graphic control extension: delay of the next frame
+                    0xF9 => {
+                        let delay = data.get(pos + 2..pos + 4)?;
+                        pending_delay = Some(u16::from_le_bytes([delay[0], delay[1]]));
+                    }
+                    // Application extension: NETSCAPE2.0 / ANIMEXTS1.0 looping
+                    0xFF => {
+                        let identifier = data.get(pos + 1..pos + 12)?;
+                        if identifier == b"NETSCAPE2.0" || identifier == b"ANIMEXTS1.0" {
+                            let sub = data.get(pos + 12..pos + 16)?;
+                            if sub[0] >= 3 && sub[1] == 1 {
+                                loop_count = Some(u16::from_le_bytes([sub[2], sub[3]]));
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                pos = skip_sub_blocks(data, pos)?;
+            }
+            // Image descriptor: one frame
+            0x2C => {
+                let packed = *data.get(pos + 9)?;
+                pos += 10 + color_table_len(packed);
+                // LZW minimum code size, then the image data sub-blocks
+                pos = match skip_sub_blocks(data, pos + 1) {
+                    Some(next) => next,
+                    // Truncated frame data: browsers still show it
+                    None => data.len(),
+                };
+
+                let delay = match pending_delay.take().unwrap_or(0) {
+                    d if d < MIN_GIF_FRAME_DELAY_CS => DEFAULT_GIF_FRAME_DELAY_CS,
+                    d => d,
+                };
+                frame_count += 1;
+                duration_ms += delay as u64 * 10;
+            }
+            // Trailer
+            0x3B => break,
+            _ => break,
+        }
+    }
+
+    (frame_count > 0).then_some(GifAnimation {
+        width,
+        height,
+        frame_count,
+        duration_ms,
+        loop_count,
+    })
+}
+
+/// Size in bytes of the color table announced by a packed field
+fn color_table_len(packed: u8) -> usize {
+    if packed & 0x80 != 0 {
+        3 * (1 << ((packed & 0x07) + 1))
+    } else {
+        0
+    }
+}
+
+/// Skip a chain of data sub-blocks starting at `pos`, returning the
+/// position after the zero-length terminator
+fn skip_sub_blocks(data: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        let len = *data.get(pos)? as usize;
+        pos += 1;
+        if len == 0 {
+            return Some(pos);
+        }
+        pos += len;
+    }
+}
+
+/// Video formats an animated GIF is converted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GifVideoFormat {
+    WebM,
+    Mp4,
+}
+
+impl GifVideoFormat {
+    /// All formats, in the order browsers should try them
+    pub const ALL: [GifVideoFormat; 2] = [GifVideoFormat::WebM, GifVideoFormat::Mp4];
+
+    pub fn extension(&self) -> &str {
+        match self {
+            Self::WebM => "webm",
+            Self::Mp4 => "mp4",
+        }
+    }
+
+    pub fn mime_type(&self) -> &str {
+        match self {
+            Self::WebM => "video/webm",
+            Self::Mp4 => "video/mp4",
+        }
+    }
+
+    pub fn codec(&self) -> &str {
+        match self {
+            Self::WebM => "vp9",
+            Self::Mp4 => "h264",
+        }
+    }
+}
+
+/// Converts animated GIFs to silent video with ffmpeg
+#[derive(Debug, Clone)]
+pub struct GifVideoConverter {
+    ffmpeg_path: String,
+}
+
+impl Default for GifVideoConverter {
+    fn default() -> Self {
+        Self::new("ffmpeg")
+    }
+}
+
+impl GifVideoConverter {
+    pub fn new(ffmpeg_path: impl Into<String>) -> Self {
+        Self {
+            ffmpeg_path: ffmpeg_path.into(),
+        }
+    }
+
+    /// Transcode one pass of the GIF; looping is left to the `<video>` element
+    pub async fn convert(
+        &self,
+        input: &Path,
+        output: &Path,
+        format: GifVideoFormat,
+    ) -> MediaResult<()> {
+        let result = tokio::process::Command::new(&self.ffmpeg_path)
+            .args(Self::args(input, output, format))
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|e| {
+                MediaError::ProcessingError(format!("Failed to run {}: {}", self.ffmpeg_path, e))
+            })?;
+
+        if !result.status.success() {
+            return Err(MediaError::ProcessingError(format!(
+                "ffmpeg failed to convert GIF to {}: {}",
+                format.extension(),
+                String::from_utf8_lossy(&result.stderr).trim()
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn args(input: &Path, output: &Path, format: GifVideoFormat) -> Vec<String> {
+        let mut args: Vec<String> = vec![
+            "-y".into(),
+            "-hide_banner".into(),
+            "-loglevel".into(),
+            "error".into(),
+            "-i".into(),
+            input.display().to_string(),
+            // Silent, and even dimensions for yuv420p
+            "-an".into(),
+            "-vf".into(),
+            "scale=trunc(iw/2)*2:trunc(ih/2)*2".into(),
+            "-pix_fmt".into(),
+            "yuv420p".into(),
+        ];
+
+        let codec_args: &[&str] = match format {
+            GifVideoFormat::Mp4 => &[
+                "-c:v",
+                "libx264",
+                "-crf",
+                "23",
+                "-preset",
+                "medium",
+                "-movflags",
+                "+faststart",
+            ],
+            GifVideoFormat::WebM => &["-c:v", "libvpx-vp9", "-crf", "35", "-b:v", "0"],
+        };
+        args.extend(codec_args.iter().map(|a| a.to_string()));
+        args.push(output.display().to_string());
+        args
+    }
+}
+
+/// Render a converted animated GIF as an autoplaying, muted, looping video.
+///
+/// Returns `None` for media without converted video versions, so callers can
+/// fall back to their usual `<img>` markup.
+pub fn animated_gif_video_html(media: &MediaItem) -> Option<String> {
+    let versions: Vec<TranscodedVersion> =
+        serde_json::from_value(media.metadata.get("animated_video")?.clone()).ok()?;
+    if versions.is_empty() {
+        return None;
+    }
+    let animation: Option<GifAnimation> = media
+        .metadata
+        .get("animation")
+        .and_then(|a| serde_json::from_value(a.clone()).ok());
+
+    // Infinite loops map to `loop`; GIFs that play once or a fixed number of
+    // times keep their play count for scripts that want to honour it
+    let play_count = match animation.map(|a| a.loop_count) {
+        None | Some(Some(0)) => None,
+        Some(None) => Some(1),
+        Some(Some(n)) => Some(n as u32 + 1),
+    };
+
+    let mut attrs = vec!["autoplay"];
+    if play_count.is_none() {
+        attrs.push("loop");
+    }
+    attrs.extend(["muted", "playsinline"]);
+
+    let mut html = format!("<video {}", attrs.join(" "));
+    if let Some(n) = play_count {
+        html.push_str(&format!(" data-play-count=\"{}\"", n));
+    }
+    if let (Some(w), Some(h)) = (media.width, media.height) {
+        html.push_str(&format!(" width=\"{}\" height=\"{}\"", w, h));
+    }
+    if let Some(poster) = &media.thumbnail_url {
+        html.push_str(&format!(" poster=\"{}\"", poster));
+    }
+    if !media.alt_text.is_empty() {
+        html.push_str(&format!(" aria-label=\"{}\"", escape_html(&media.alt_text)));
+    }
+    html.push('>');
+
+    for format in GifVideoFormat::ALL {
+        for version in versions.iter().filter(|v| v.format == format.extension()) {
+            html.push_str(&format!(
+                "<source src=\"{}\" type=\"{}\">",
+                version.url,
+                format.mime_type()
+            ));
+        }
+    }
+
+    // Browsers without video support get the original GIF
+    html.push_str(&format!(
+        "<img src=\"{}\" alt=\"{}\">",
+        media.url,
+        escape_html(&media.alt_text)
+    ));
+    html.push_str("</video>");
+
+    Some(html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use image::codecs::gif::{GifEncoder, Repeat};
+    use image::{Delay, Frame, Rgba, RgbaImage};
+    use uuid::Uuid;
+
+    fn encode_gif(frames: usize, delay_ms: u32, repeat: Option<Repeat>) -> Vec<u8> {
+        let mut data = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut data);
+            if let Some(repeat) = repeat {
+                encoder.set_repeat(repeat).unwrap();
+            }
+            for i in 0..frames {
+                let image = RgbaImage::from_pixel(4, 2, Rgba([i as u8 * 60, 0, 0, 255]));
+                let delay = Delay::from_numer_denom_ms(delay_ms, 1);
+                encoder
+                    .encode_frame(Frame::from_parts(image, 0, 0, delay))
+                    .unwrap();
+            }
+        }
+        data
+    }
+
+    fn gif_media(metadata: serde_json::Value) -> MediaItem {
+        MediaItem {
+            id: Uuid::new_v4(),
+            filename: "cat.gif".to_string(),
+            title: "Cat".to_string(),
+            alt_text: "A \"dancing\" cat".to_string(),
+            caption: String::new(),
+            description: String::new(),
+            media_type: crate::MediaType::Image,
+            mime_type: "image/gif".to_string(),
+            file_size: 2048,
+            path: "2026/10/cat.gif".to_string(),
+            url: "/uploads/2026/10/cat.gif".to_string(),
+            thumbnail_url: None,
+            width: Some(4),
+            height: Some(2),
+            duration: Some(0.15),
+            file_hash: String::new(),
+            folder_id: None,
+            metadata,
+            uploaded_by: Uuid::new_v4(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn version(format: GifVideoFormat) -> TranscodedVersion {
+        TranscodedVersion {
+            quality: "original".to_string(),
+            format: format.extension().to_string(),
+            codec: format.codec().to_string(),
+            bitrate: 100,
+            file_size: 512,
+            url: format!("/uploads/2026/10/cat.{}", format.extension()),
+        }
+    }
+
+    #[test]
+    fn test_probe_animated_gif() {
+        let animation = GifAnimation::probe(&encode_gif(3, 50, Some(Repeat::Infinite))).unwrap();
+
+        assert_eq!((animation.width, animation.height), (4, 2));
+        assert_eq!(animation.frame_count, 3);
+        assert_eq!(animation.duration_ms, 150);
+        assert!(animation.loops_forever());
+        assert_eq!(animation.framerate(), 20.0);
+
+        let finite = GifAnimation::probe(&encode_gif(2, 50, Some(Repeat::Finite(2)))).unwrap();
+        assert_eq!(finite.loop_count, Some(2));
+        assert!(!finite.loops_forever());
+
+        let once = GifAnimation::probe(&encode_gif(2, 50, None)).unwrap();
+        assert_eq!(once.loop_count, None);
+    }
+
+    #[test]
+    fn test_probe_ignores_still_images() {
+        assert_eq!(GifAnimation::probe(&encode_gif(1, 50, None)), None);
+        assert_eq!(GifAnimation::probe(b"\x89PNG\r\n\x1a\n"), None);
+        assert_eq!(GifAnimation::probe(&[]), None);
+    }
+
+    #[test]
+    fn test_probe_normalizes_short_delays() {
+        // 0ms frames are played at 100ms by browsers
+        let animation = GifAnimation::probe(&encode_gif(4, 0, Some(Repeat::Infinite))).unwrap();
+        assert_eq!(animation.duration_ms, 400);
+    }
+
+    #[test]
+    fn test_animated_gif_video_html() {
+        let animation = GifAnimation::probe(&encode_gif(3, 50, Some(Repeat::Infinite))).unwrap();
+        let media = gif_media(serde_json::json!({
+            "animation": animation,
+            "animated_video": [version(GifVideoFormat::Mp4), version(GifVideoFormat::WebM)],
+        }));
+
+        let html = animated_gif_video_html(&media).unwrap();
+        assert!(html.starts_with("<video autoplay loop muted playsinline width=\"4\" height=\"2\""));
+        assert!(html.contains("aria-label=\"A &quot;dancing&quot; cat\""));
+        let webm = html.find("type=\"video/webm\"").unwrap();
+        let mp4 = html.find("type=\"video/mp4\"").unwrap();
+        assert!(webm < mp4);
+        assert!(html.ends_with(
+            "<img src=\"/uploads/2026/10/cat.gif\" alt=\"A &quot;dancing&quot; cat\"></video>"
+        ));
+    }
+
+    #[test]
+    fn test_animated_gif_video_html_play_count() {
+        let animation = GifAnimation::probe(&encode_gif(2, 50, Some(Repeat::Finite(2)))).unwrap();
+        let media = gif_media(serde_json::json!({
+            "animation": animation,
+            "animated_video": [version(GifVideoFormat::Mp4)],
+        }));
+
+        let html = animated_gif_video_html(&media).unwrap();
+        assert!(!html.contains(" loop"));
+        assert!(html.contains("data-play-count=\"3\""));
+    }
+
+    #[test]
+    fn test_animated_gif_video_html_requires_versions() {
+        assert_eq!(
+            animated_gif_video_html(&gif_media(serde_json::json!({}))),
+            None
+        );
+        let media = gif_media(serde_json::json!({ "animated_video": [] }));
+        assert_eq!(animated_gif_video_html(&media), None);
+    }
+
+    #[test]
+    fn test_gif_converter_args() {
+        let args = GifVideoConverter::args(
+            Path::new("in.gif"),
+            Path::new("out.mp4"),
+            GifVideoFormat::Mp4,
+        );
+        assert_eq!(args.last().map(String::as_str), Some("out.mp4"));
+        assert!(args.windows(2).any(|w| w == ["-c:v", "libx264"]));
+        assert!(args.contains(&"-an".to_string()));
+
+        let args = GifVideoConverter::args(
+            Path::new("in.gif"),
+            Path::new("out.webm"),
+            GifVideoFormat::WebM,
+        );
+        assert!(args.windows(2).any(|w| w == ["-c:v", "libvpx-vp9"]));
+    }
+}
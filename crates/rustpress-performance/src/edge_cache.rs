@@ -2,9 +2,13 @@
 //!
 //! Configuration for edge/CDN caching with proper cache directives.
 
+use rustpress_cdn::CacheRule;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Browser TTL for private rules without their own TTL (seconds)
+const DEFAULT_PRIVATE_TTL: u64 = 3600;
+
 /// Edge caching configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EdgeCacheConfig {
@@ -52,6 +56,76 @@ impl Default for EdgeCacheConfig {
     }
 }
 
+impl EdgeCacheConfig {
+    /// Translate the edge rules into CDN cache rules.
+    ///
+    /// The result can be pushed to any provider with
+    /// `CdnClient::configure_rules`. TTLs mirror the `max-age` and
+    /// `s-maxage`/`Surrogate-Control` values `EdgeCacheHeaders` emits for
+    /// the same path, and no-cache paths come first with the highest
+    /// priority, as they are checked before any rule. Cookie and
+    /// authentication bypasses cannot be expressed as path rules and
+    /// remain enforced by the origin headers.
+    pub fn to_cdn_rules(&self) -> Vec<CacheRule> {
+        let bypass = self.no_cache_paths.iter().map(|path| CacheRule {
+            name: format!("No Cache {}", path),
+            pattern: format!("{}*", path),
+            ttl: 0,
+            cache_level: Some("bypass".to_string()),
+            edge_ttl: None,
+            browser_ttl: None,
+            priority: 0,
+            cache_key: None,
+        });
+
+        // EdgeCacheRule priorities run high to low, CacheRule priorities
+        // low to high; the sort is stable so ties keep their order
+        let mut rules: Vec<_> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.applies_to_cacheable_methods())
+            .collect();
+        rules.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
+
+        let custom = rules
+            .into_iter()
+            .enumerate()
+            .map(|(index, rule)| self.cdn_rule(rule, index as i32 + 1));
+
+        bypass.chain(custom).collect()
+    }
+
+    fn cdn_rule(&self, rule: &EdgeCacheRule, priority: i32) -> CacheRule {
+        let (ttl, cache_level, edge_ttl, browser_ttl) = match rule.behavior {
+            CacheBehavior::Cache => {
+                let ttl = rule.ttl.unwrap_or(self.dynamic_ttl);
+                (ttl, "cache_everything", Some(ttl), Some(ttl))
+            }
+            // Revalidating on every request means the edge cannot serve
+            // from cache, so the CDN passes requests through to the origin
+            CacheBehavior::Revalidate => (0, "bypass", Some(0), Some(0)),
+            CacheBehavior::Bypass => (0, "bypass", None, None),
+            CacheBehavior::Private => (
+                0,
+                "bypass",
+                None,
+                Some(rule.ttl.unwrap_or(DEFAULT_PRIVATE_TTL)),
+            ),
+        };
+
+        CacheRule {
+            name: rule.name.clone(),
+            pattern: rule.path_pattern.clone(),
+            ttl,
+            cache_level: Some(cache_level.to_string()),
+            edge_ttl,
+            browser_ttl,
+            priority,
+            cache_key: None,
+        }
+    }
+}
+
 /// Custom cache rule
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EdgeCacheRule {
@@ -73,6 +147,18 @@ pub struct EdgeCacheRule {
     pub priority: i32,
 }
 
+impl EdgeCacheRule {
+    /// CDNs only cache GET and HEAD, so rules for other methods have no
+    /// edge equivalent. An empty method list applies to every method.
+    fn applies_to_cacheable_methods(&self) -> bool {
+        self.methods.is_empty()
+            || self
+                .methods
+                .iter()
+                .any(|m| m.eq_ignore_ascii_case("GET") || m.eq_ignore_ascii_case("HEAD"))
+    }
+}
+
 /// Cache behavior
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CacheBehavior {
@@ -164,7 +250,7 @@ impl EdgeCacheHeaders {
             CacheBehavior::Bypass => "no-store, no-cache, must-revalidate".to_string(),
             CacheBehavior::Revalidate => "no-cache, must-revalidate".to_string(),
             CacheBehavior::Private => {
-                let ttl = rule.ttl.unwrap_or(3600);
+                let ttl = rule.ttl.unwrap_or(DEFAULT_PRIVATE_TTL);
                 format!("private, max-age={}", ttl)
             }
         };
@@ -528,4 +614,82 @@ mod tests {
             generator.generate_headers("/assets/main.js", "application/javascript", false);
         assert!(headers.get("Cache-Control").unwrap().contains("max-age="));
     }
+
+    fn rule(name: &str, pattern: &str, behavior: CacheBehavior, priority: i32) -> EdgeCacheRule {
+        EdgeCacheRule {
+            name: name.to_string(),
+            path_pattern: pattern.to_string(),
+            methods: vec!["GET".to_string()],
+            behavior,
+            ttl: None,
+            surrogate_keys: Vec::new(),
+            vary: Vec::new(),
+            priority,
+        }
+    }
+
+    #[test]
+    fn test_to_cdn_rules_priority() {
+        let config = EdgeCacheConfig {
+            rules: vec![
+                rule("Posts", "/posts/*", CacheBehavior::Cache, 10),
+                rule("Preview", "/posts/preview-*", CacheBehavior::Bypass, 20),
+                EdgeCacheRule {
+                    methods: vec!["POST".to_string()],
+                    ..rule("Forms", "/forms/*", CacheBehavior::Cache, 30)
+                },
+            ],
+            ..EdgeCacheConfig::default()
+        };
+
+        let rules = config.to_cdn_rules();
+        let summary: Vec<_> = rules
+            .iter()
+            .map(|r| (r.name.as_str(), r.priority, r.ttl))
+            .collect();
+
+        // Admin and auth bypasses lead; the POST-only rule is dropped
+        assert_eq!(
+            summary,
+            vec![
+                ("No Cache /wp-admin", 0, 0),
+                ("No Cache /admin", 0, 0),
+                ("No Cache /api/auth", 0, 0),
+                ("No Cache /wp-login.php", 0, 0),
+                ("Preview", 1, 0),
+                ("Posts", 2, 3600),
+            ]
+        );
+        assert_eq!(rules[1].pattern, "/admin*");
+        assert_eq!(rules[4].cache_level.as_deref(), Some("bypass"));
+    }
+
+    #[test]
+    fn test_to_cdn_rules_match_emitted_headers() {
+        let config = EdgeCacheConfig {
+            rules: vec![
+                EdgeCacheRule {
+                    ttl: Some(600),
+                    ..rule("Posts", "/posts/*", CacheBehavior::Cache, 0)
+                },
+                rule("Account", "/account/*", CacheBehavior::Private, 0),
+            ],
+            ..EdgeCacheConfig::default()
+        };
+        let cdn = config.to_cdn_rules();
+        let generator = EdgeCacheHeaders::new(config);
+
+        let posts = cdn.iter().find(|r| r.name == "Posts").unwrap();
+        let headers = generator.generate_headers("/posts/hello", "text/html", false);
+        let cache_control = &headers["Cache-Control"];
+        assert!(cache_control.contains(&format!("max-age={}", posts.browser_ttl.unwrap())));
+        assert!(cache_control.contains(&format!("s-maxage={}", posts.edge_ttl.unwrap())));
+        let surrogate = generator.fastly_headers(posts.edge_ttl.unwrap(), &[]);
+        assert!(surrogate["Surrogate-Control"].starts_with("max-age=600,"));
+
+        let account = cdn.iter().find(|r| r.name == "Account").unwrap();
+        let headers = generator.generate_headers("/account/profile", "text/html", false);
+        assert_eq!(headers["Cache-Control"], "private, max-age=3600");
+        assert_eq!((account.ttl, account.browser_ttl), (0, Some(3600)));
+    }
 }
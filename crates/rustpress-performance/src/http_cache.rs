@@ -404,17 +404,167 @@ impl ConditionalRequest {
     }
 }
 
+/// One year, the longest max-age caches reliably honour
+const ONE_YEAR_SECS: u64 = 31536000;
+
+/// File extensions served as static assets
+const STATIC_ASSET_EXTENSIONS: &[&str] = &[
+    "css", "js", "mjs", "map", "woff", "woff2", "ttf", "eot", "otf", "ico", "png", "jpg", "jpeg",
+    "gif", "svg", "webp", "avif",
+];
+
+/// Path prefixes whose responses must never be cached
+const NO_STORE_PREFIXES: &[&str] = &["/admin", "/wp-admin", "/login", "/logout", "/setup"];
+
+/// Who a response is for, used by [`CacheProfile::for_route`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheAudience {
+    /// The request carries a logged-in session
+    pub authenticated: bool,
+    /// The response contains per-visitor content, e.g. a cart or a
+    /// "welcome back" banner, even without a login
+    pub personalized: bool,
+}
+
+impl CacheAudience {
+    /// Anonymous visitor seeing the same response as everyone else
+    pub fn anonymous() -> Self {
+        Self::default()
+    }
+
+    /// Logged-in user
+    pub fn authenticated() -> Self {
+        Self {
+            authenticated: true,
+            personalized: false,
+        }
+    }
+
+    fn is_private(&self) -> bool {
+        self.authenticated || self.personalized
+    }
+}
+
 /// Cache profile for different content types
 #[derive(Debug, Clone)]
 pub struct CacheProfile {
     pub name: String,
     pub cache_control: CacheControl,
     pub vary: Vec<String>,
+    /// Surrogate-Control for CDNs, overriding Cache-Control at the edge
+    pub surrogate_control: Option<CacheControl>,
     pub enable_etag: bool,
     pub enable_last_modified: bool,
 }
 
 impl CacheProfile {
+    /// Fingerprinted static asset: cached by browsers and CDNs for a year
+    /// and never revalidated
+    pub fn public_static() -> Self {
+        Self {
+            name: "public_static".to_string(),
+            cache_control: CacheControl::immutable_asset(),
+            vary: vec!["Accept-Encoding".to_string()],
+            surrogate_control: Some(CacheControl::new().with_max_age(ONE_YEAR_SECS)),
+            enable_etag: true,
+            enable_last_modified: true,
+        }
+    }
+
+    /// Page that is the same for every anonymous visitor: short browser
+    /// lifetime, longer at the CDN. Does not vary on Cookie, which would
+    /// make every visitor a cache miss.
+    pub fn public_page() -> Self {
+        Self {
+            name: "public_page".to_string(),
+            cache_control: CacheControl::public()
+                .with_max_age(300)
+                .with_stale_while_revalidate(60),
+            vary: vec!["Accept-Encoding".to_string()],
+            surrogate_control: Some(
+                CacheControl::new()
+                    .with_max_age(3600)
+                    .with_stale_while_revalidate(86400)
+                    .with_stale_if_error(86400),
+            ),
+            enable_etag: true,
+            enable_last_modified: true,
+        }
+    }
+
+    /// Response for a logged-in or personalized visitor: never stored by
+    /// any cache
+    pub fn private() -> Self {
+        Self {
+            name: "private".to_string(),
+            cache_control: CacheControl {
+                private: true,
+                no_store: true,
+                ..Default::default()
+            },
+            vary: vec!["Accept-Encoding".to_string(), "Cookie".to_string()],
+            surrogate_control: Some(CacheControl {
+                no_store: true,
+                ..Default::default()
+            }),
+            enable_etag: false,
+            enable_last_modified: false,
+        }
+    }
+
+    /// Response that must never be stored, e.g. login forms and admin
+    /// screens
+    pub fn no_store() -> Self {
+        Self {
+            name: "no_store".to_string(),
+            cache_control: CacheControl {
+                no_store: true,
+                ..Default::default()
+            },
+            vary: vec![],
+            surrogate_control: Some(CacheControl {
+                no_store: true,
+                ..Default::default()
+            }),
+            enable_etag: false,
+            enable_last_modified: false,
+        }
+    }
+
+    /// Pick a preset for a response to `path`.
+    ///
+    /// Authenticated and personalized responses always get
+    /// [`Self::private`], whatever the path. Otherwise static assets get
+    /// [`Self::public_static`], admin and login screens [`Self::no_store`]
+    /// and everything else [`Self::public_page`].
+    pub fn for_route(path: &str, audience: CacheAudience) -> Self {
+        if audience.is_private() {
+            Self::private()
+        } else if is_static_asset_path(path) {
+            Self::public_static()
+        } else if NO_STORE_PREFIXES.iter().any(|prefix| {
+            path.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        }) {
+            Self::no_store()
+        } else {
+            Self::public_page()
+        }
+    }
+
+    /// Cache-Control, Vary and Surrogate-Control headers for this profile
+    pub fn headers(&self) -> HttpCacheHeaders {
+        let mut headers = HttpCacheHeaders::new().cache_control(self.cache_control.clone());
+        if !self.vary.is_empty() {
+            let vary: Vec<&str> = self.vary.iter().map(String::as_str).collect();
+            headers = headers.vary(&vary);
+        }
+        if let Some(surrogate) = &self.surrogate_control {
+            headers = headers.surrogate_control(&surrogate.to_header_value());
+        }
+        headers
+    }
+
     /// Profile for static assets
     pub fn static_assets() -> Self {
         Self {
@@ -426,6 +576,7 @@ impl CacheProfile {
                 ..Default::default()
             },
             vary: vec![],
+            surrogate_control: None,
             enable_etag: true,
             enable_last_modified: true,
         }
@@ -443,6 +594,7 @@ impl CacheProfile {
                 ..Default::default()
             },
             vary: vec!["Accept-Encoding".to_string(), "Cookie".to_string()],
+            surrogate_control: None,
             enable_etag: true,
             enable_last_modified: true,
         }
@@ -459,6 +611,7 @@ impl CacheProfile {
                 ..Default::default()
             },
             vary: vec!["Accept".to_string(), "Authorization".to_string()],
+            surrogate_control: None,
             enable_etag: true,
             enable_last_modified: false,
         }
@@ -471,10 +624,12 @@ impl CacheProfile {
             cache_control: CacheControl {
                 private: true,
                 no_cache: true,
+                no_store: true,
                 must_revalidate: true,
                 ..Default::default()
             },
             vary: vec!["Cookie".to_string()],
+            surrogate_control: None,
             enable_etag: true,
             enable_last_modified: true,
         }
@@ -486,12 +641,26 @@ impl CacheProfile {
             name: "no_cache".to_string(),
             cache_control: CacheControl::no_cache(),
             vary: vec![],
+            surrogate_control: None,
             enable_etag: false,
             enable_last_modified: false,
         }
     }
 }
 
+/// Whether `path` is a static asset, by directory or file extension
+pub fn is_static_asset_path(path: &str) -> bool {
+    if path.starts_with("/static/") || path.starts_with("/assets/") {
+        return true;
+    }
+    let file = path.rsplit('/').next().unwrap_or(path);
+    file.rsplit_once('.').is_some_and(|(_, ext)| {
+        STATIC_ASSET_EXTENSIONS
+            .iter()
+            .any(|known| ext.eq_ignore_ascii_case(known))
+    })
+}
+
 /// Cache profile registry
 pub struct CacheProfileRegistry {
     profiles: HashMap<String, CacheProfile>,
@@ -511,6 +680,10 @@ impl CacheProfileRegistry {
         registry.register(CacheProfile::api_responses());
         registry.register(CacheProfile::authenticated());
         registry.register(CacheProfile::no_cache());
+        registry.register(CacheProfile::public_static());
+        registry.register(CacheProfile::public_page());
+        registry.register(CacheProfile::private());
+        registry.register(CacheProfile::no_store());
 
         // Default path patterns
         registry.add_path_pattern(r"^/static/.*", "static_assets");
@@ -619,4 +792,60 @@ mod tests {
         let conditional = ConditionalRequest::from_headers(&headers);
         assert!(conditional.is_not_modified(Some(&etag), None));
     }
+
+    fn header(headers: &HeaderMap, name: &str) -> String {
+        headers
+            .get(name)
+            .map(|v| v.to_str().unwrap().to_string())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_profile_headers() {
+        let headers = CacheProfile::public_static().headers().build();
+        assert_eq!(
+            header(&headers, "cache-control"),
+            "public, max-age=31536000, immutable"
+        );
+        assert_eq!(header(&headers, "vary"), "Accept-Encoding");
+        assert_eq!(header(&headers, "surrogate-control"), "max-age=31536000");
+
+        let headers = CacheProfile::public_page().headers().build();
+        assert!(header(&headers, "cache-control").starts_with("public, max-age=300"));
+        assert_eq!(header(&headers, "vary"), "Accept-Encoding");
+
+        let headers = CacheProfile::private().headers().build();
+        assert_eq!(header(&headers, "cache-control"), "private, no-store");
+        assert_eq!(header(&headers, "vary"), "Accept-Encoding, Cookie");
+        assert_eq!(header(&headers, "surrogate-control"), "no-store");
+
+        let headers = CacheProfile::no_store().headers().build();
+        assert_eq!(header(&headers, "cache-control"), "no-store");
+        assert!(headers.get("vary").is_none());
+    }
+
+    #[test]
+    fn test_profile_for_route() {
+        let anonymous = CacheAudience::anonymous();
+        let name = |path: &str, audience| CacheProfile::for_route(path, audience).name;
+
+        assert_eq!(name("/assets/app.3f9a.js", anonymous), "public_static");
+        assert_eq!(name("/uploads/2024/photo.JPG", anonymous), "public_static");
+        assert_eq!(name("/post/hello-world", anonymous), "public_page");
+        assert_eq!(name("/admin/posts", anonymous), "no_store");
+        assert_eq!(name("/login", anonymous), "no_store");
+        assert_eq!(name("/login-tips", anonymous), "public_page");
+
+        // Logged-in and personalized responses are never shared
+        for audience in [
+            CacheAudience::authenticated(),
+            CacheAudience {
+                authenticated: false,
+                personalized: true,
+            },
+        ] {
+            assert_eq!(name("/post/hello-world", audience), "private");
+            assert_eq!(name("/assets/app.js", audience), "private");
+        }
+    }
 }
@@ -1,6 +1,10 @@
 //! Image Optimization Pipeline
 //!
 //! Optimizes images for web delivery with format conversion, resizing, and compression.
+//!
+//! JPEG quality is either fixed or, with [`ImageOptimizerConfig::target_ssim`],
+//! searched per image for the lowest quality whose SSIM against the source
+//! meets the target.
 
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
@@ -81,6 +85,16 @@ impl ImageFormat {
 pub struct ImageOptimizerConfig {
     /// Target quality for lossy formats (1-100)
     pub quality: u8,
+    /// Perceptual quality to aim for instead of the fixed `quality`, as an
+    /// SSIM score between 0 and 1 (e.g. 0.95). Falls back to `quality` when
+    /// no quality in range reaches it within the iteration budget.
+    pub target_ssim: Option<f32>,
+    /// Lowest quality tried when targeting SSIM
+    pub min_quality: u8,
+    /// Highest quality tried when targeting SSIM
+    pub max_quality: u8,
+    /// Encodes tried per variant when targeting SSIM
+    pub max_quality_iterations: u8,
     /// Enable WebP conversion
     pub enable_webp: bool,
     /// Enable AVIF conversion
@@ -109,6 +123,10 @@ impl Default for ImageOptimizerConfig {
     fn default() -> Self {
         Self {
             quality: 80,
+            target_ssim: None,
+            min_quality: 40,
+            max_quality: 95,
+            max_quality_iterations: 6,
             enable_webp: true,
             enable_avif: false, // AVIF encoding is slower
             max_width: 2560,
@@ -161,6 +179,21 @@ pub struct ImageVariant {
     pub size: usize,
     /// URL path
     pub url: String,
+    /// Encoder quality used, for lossy formats
+    #[serde(default)]
+    pub quality: Option<u8>,
+    /// SSIM against the source when the quality was chosen to meet
+    /// `target_ssim`; None when the fixed quality was used
+    #[serde(default)]
+    pub ssim: Option<f32>,
+}
+
+/// Quality picked for a lossy encode
+#[derive(Debug, Clone, PartialEq)]
+struct QualityChoice {
+    quality: u8,
+    ssim: Option<f32>,
+    data: Vec<u8>,
 }
 
 /// Image optimizer
@@ -315,6 +348,8 @@ impl ImageOptimizer {
             height: 0,
             size: minified.len(),
             url: format!("/uploads/{}", filename),
+            quality: None,
+            ssim: None,
         };
 
         Ok(OptimizedImage {
@@ -340,17 +375,13 @@ impl ImageOptimizer {
         hash: &str,
     ) -> Result<ImageVariant, ImageError> {
         let mut buffer = Cursor::new(Vec::new());
+        let mut choice = None;
 
         let filename = generate_variant_filename(original_filename, width, format, hash);
 
         match format {
             ImageFormat::Jpeg => {
-                let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(
-                    &mut buffer,
-                    self.config.quality,
-                );
-                img.write_with_encoder(encoder)
-                    .map_err(|e| ImageError::EncodeError(e.to_string()))?;
+                choice = Some(self.choose_jpeg_quality(img)?);
             }
             ImageFormat::Png => {
                 let encoder = image::codecs::png::PngEncoder::new_with_quality(
@@ -377,7 +408,10 @@ impl ImageOptimizer {
             }
         }
 
-        let data = buffer.into_inner();
+        let (data, quality, ssim) = match choice {
+            Some(choice) => (choice.data, Some(choice.quality), choice.ssim),
+            None => (buffer.into_inner(), None, None),
+        };
 
         Ok(ImageVariant {
             filename: filename.clone(),
@@ -386,9 +420,76 @@ impl ImageOptimizer {
             height,
             size: data.len(),
             url: format!("/uploads/optimized/{}", filename),
+            quality,
+            ssim,
         })
     }
 
+    fn encode_jpeg(&self, img: &image::DynamicImage, quality: u8) -> Result<Vec<u8>, ImageError> {
+        let mut buffer = Cursor::new(Vec::new());
+        let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, quality);
+        img.write_with_encoder(encoder)
+            .map_err(|e| ImageError::EncodeError(e.to_string()))?;
+        Ok(buffer.into_inner())
+    }
+
+    /// Encode `img` as JPEG at the fixed quality or, with a target SSIM, at
+    /// the lowest quality found by binary search that meets it
+    fn choose_jpeg_quality(&self, img: &image::DynamicImage) -> Result<QualityChoice, ImageError> {
+        let fixed = |optimizer: &Self| -> Result<QualityChoice, ImageError> {
+            Ok(QualityChoice {
+                quality: optimizer.config.quality,
+                ssim: None,
+                data: optimizer.encode_jpeg(img, optimizer.config.quality)?,
+            })
+        };
+        let Some(target) = self.config.target_ssim else {
+            return fixed(self);
+        };
+
+        let source = img.to_luma8();
+        let mut low = self.config.min_quality.clamp(1, 100);
+        let mut high = self.config.max_quality.clamp(low, 100);
+        let mut best: Option<QualityChoice> = None;
+
+        for _ in 0..self.config.max_quality_iterations {
+            if low > high {
+                break;
+            }
+            let quality = low + (high - low) / 2;
+            let data = self.encode_jpeg(img, quality)?;
+            let decoded = image::load_from_memory_with_format(&data, image::ImageFormat::Jpeg)
+                .map_err(|e| ImageError::ProcessingError(e.to_string()))?;
+            let score = ssim(&source, &decoded.to_luma8());
+
+            if score >= target {
+                best = Some(QualityChoice {
+                    quality,
+                    ssim: Some(score),
+                    data,
+                });
+                if quality == low {
+                    break;
+                }
+                high = quality - 1;
+            } else {
+                low = quality + 1;
+            }
+        }
+
+        match best {
+            Some(choice) => Ok(choice),
+            None => {
+                tracing::debug!(
+                    target_ssim = target,
+                    quality = self.config.quality,
+                    "SSIM target not reached, using fixed quality"
+                );
+                fixed(self)
+            }
+        }
+    }
+
     fn generate_placeholder(&self, img: &image::DynamicImage) -> Result<String, ImageError> {
         // Create a tiny blurred version
         let tiny = img.resize(32, 32, image::imageops::FilterType::Gaussian);
@@ -476,6 +577,50 @@ fn generate_variant_filename(
     format!("{}-{}w-{}.{}", stem, width, &hash[..8], format.extension())
 }
 
+/// Mean structural similarity of two grayscale images over 8x8 windows,
+/// from 1.0 for identical images down towards 0. Images of different sizes
+/// score 0.
+fn ssim(a: &image::GrayImage, b: &image::GrayImage) -> f32 {
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+
+    if a.dimensions() != b.dimensions() || a.width() == 0 || a.height() == 0 {
+        return 0.0;
+    }
+    let (width, height) = a.dimensions();
+    let window = 8.min(width).min(height);
+
+    let mut total = 0.0;
+    let mut windows = 0u32;
+    for y0 in (0..=height - window).step_by(window as usize) {
+        for x0 in (0..=width - window).step_by(window as usize) {
+            let n = (window * window) as f64;
+            let (mut sum_a, mut sum_b, mut sum_aa, mut sum_bb, mut sum_ab) =
+                (0.0, 0.0, 0.0, 0.0, 0.0);
+            for y in y0..y0 + window {
+                for x in x0..x0 + window {
+                    let pa = a.get_pixel(x, y)[0] as f64;
+                    let pb = b.get_pixel(x, y)[0] as f64;
+                    sum_a += pa;
+                    sum_b += pb;
+                    sum_aa += pa * pa;
+                    sum_bb += pb * pb;
+                    sum_ab += pa * pb;
+                }
+            }
+            let (mean_a, mean_b) = (sum_a / n, sum_b / n);
+            let var_a = sum_aa / n - mean_a * mean_a;
+            let var_b = sum_bb / n - mean_b * mean_b;
+            let covariance = sum_ab / n - mean_a * mean_b;
+
+            total += ((2.0 * mean_a * mean_b + C1) * (2.0 * covariance + C2))
+                / ((mean_a * mean_a + mean_b * mean_b + C1) * (var_a + var_b + C2));
+            windows += 1;
+        }
+    }
+    (total / windows as f64) as f32
+}
+
 /// Minify SVG content
 fn minify_svg(svg: &str) -> String {
     let mut result = svg.to_string();
@@ -752,4 +897,55 @@ mod tests {
         assert!(!minified.contains("<!--"));
         assert!(minified.len() < svg.len());
     }
+
+    fn test_image() -> image::DynamicImage {
+        let img = image::RgbImage::from_fn(96, 64, |x, y| {
+            let noise = ((x * 7919 + y * 104729) % 37) as u8;
+            image::Rgb([(x * 2) as u8 + noise, (y * 3) as u8, noise * 5])
+        });
+        image::DynamicImage::ImageRgb8(img)
+    }
+
+    #[test]
+    fn test_ssim() {
+        let img = test_image().to_luma8();
+        assert!((ssim(&img, &img) - 1.0).abs() < 1e-6);
+
+        let mut damaged = img.clone();
+        for pixel in damaged.pixels_mut().step_by(3) {
+            pixel[0] = 255 - pixel[0];
+        }
+        assert!(ssim(&img, &damaged) < 0.9);
+        assert_eq!(ssim(&img, &image::GrayImage::new(8, 8)), 0.0);
+    }
+
+    #[test]
+    fn test_ssim_targeted_quality() {
+        let img = test_image();
+
+        let optimizer = ImageOptimizer::new(ImageOptimizerConfig {
+            target_ssim: Some(0.9),
+            ..Default::default()
+        });
+        let choice = optimizer.choose_jpeg_quality(&img).unwrap();
+        assert!((40..=95).contains(&choice.quality));
+        assert!(choice.ssim.unwrap() >= 0.9);
+
+        // A lower target never needs a higher quality
+        let optimizer = ImageOptimizer::new(ImageOptimizerConfig {
+            target_ssim: Some(0.5),
+            ..Default::default()
+        });
+        assert!(optimizer.choose_jpeg_quality(&img).unwrap().quality <= choice.quality);
+
+        // An unreachable target falls back to the fixed quality
+        let optimizer = ImageOptimizer::new(ImageOptimizerConfig {
+            target_ssim: Some(1.5),
+            quality: 77,
+            ..Default::default()
+        });
+        let fallback = optimizer.choose_jpeg_quality(&img).unwrap();
+        assert_eq!(fallback.quality, 77);
+        assert_eq!(fallback.ssim, None);
+    }
 }
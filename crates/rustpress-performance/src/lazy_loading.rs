@@ -1,11 +1,13 @@
 //! Lazy Loading for Admin Components
 //!
-//! Implements lazy loading patterns for admin UI components to improve initial load time.
+//! Implements lazy loading patterns for admin UI components to improve initial load time,
+//! and native lazy loading for images and iframes in rendered content.
 
 use parking_lot::RwLock;
+use regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 /// Component metadata for lazy loading
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +57,8 @@ pub struct LazyLoadingRegistry {
     route_map: Arc<RwLock<HashMap<String, Vec<String>>>>,
     /// Loaded components tracking
     loaded: Arc<RwLock<HashSet<String>>>,
+    /// Known media dimensions by URL
+    media_dimensions: Arc<RwLock<HashMap<String, MediaDimensions>>>,
 }
 
 impl LazyLoadingRegistry {
@@ -64,6 +68,7 @@ impl LazyLoadingRegistry {
             chunks: Arc::new(RwLock::new(HashMap::new())),
             route_map: Arc::new(RwLock::new(HashMap::new())),
             loaded: Arc::new(RwLock::new(HashSet::new())),
+            media_dimensions: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -236,6 +241,94 @@ impl LazyLoadingRegistry {
             route_map: route_map.clone(),
         }
     }
+
+    /// Record the intrinsic size of a media URL for [`Self::transform_html`]
+    pub fn register_media_dimensions(&self, url: &str, width: u32, height: u32) {
+        self.media_dimensions
+            .write()
+            .insert(url.to_string(), MediaDimensions { width, height });
+    }
+
+    /// Get the registered size of a media URL, ignoring its query string
+    pub fn media_dimensions(&self, url: &str) -> Option<MediaDimensions> {
+        let dimensions = self.media_dimensions.read();
+        dimensions
+            .get(url)
+            .or_else(|| dimensions.get(url.split(['?', '#']).next().unwrap_or(url)))
+            .copied()
+    }
+
+    /// Add native lazy loading to the images and iframes in rendered HTML.
+    ///
+    /// The first `eager_images` images are treated as above the fold and get
+    /// `loading="eager" fetchpriority="high"`; later ones get `loading="lazy"
+    /// decoding="async"`. Attributes already present are never changed, images
+    /// already marked `loading="eager"` are left alone entirely, and `width` /
+    /// `height` are filled in from registered dimensions when both are missing.
+    /// Running the transform on its own output changes nothing.
+    pub fn transform_html(&self, html: &str, config: &ContentLazyLoadConfig) -> String {
+        let mut images_seen = 0;
+
+        media_tag_regex()
+            .replace_all(html, |caps: &Captures| {
+                let name = &caps[1];
+                let attrs = caps[2].trim_end();
+                let (attrs, self_closing) = match attrs.strip_suffix('/') {
+                    Some(attrs) => (attrs.trim_end(), true),
+                    None => (attrs, false),
+                };
+                let present = parse_attributes(attrs);
+                let loading = present.get("loading").map(|v| v.to_ascii_lowercase());
+
+                let mut added = Vec::new();
+                if name.eq_ignore_ascii_case("img") {
+                    let above_fold = images_seen < config.eager_images;
+                    images_seen += 1;
+
+                    match loading.as_deref() {
+                        Some("eager") => return caps[0].to_string(),
+                        Some(_) => {}
+                        None if above_fold => {
+                            added.push(r#"loading="eager""#.to_string());
+                            if !present.contains_key("fetchpriority") {
+                                added.push(r#"fetchpriority="high""#.to_string());
+                            }
+                        }
+                        None => added.push(r#"loading="lazy""#.to_string()),
+                    }
+                    if !above_fold && !present.contains_key("decoding") {
+                        added.push(r#"decoding="async""#.to_string());
+                    }
+
+                    if config.inject_dimensions
+                        && !present.contains_key("width")
+                        && !present.contains_key("height")
+                    {
+                        if let Some(size) = present
+                            .get("src")
+                            .and_then(|src| self.media_dimensions(src))
+                        {
+                            added.push(format!(r#"width="{}""#, size.width));
+                            added.push(format!(r#"height="{}""#, size.height));
+                        }
+                    }
+                } else if config.lazy_iframes && loading.is_none() {
+                    added.push(r#"loading="lazy""#.to_string());
+                }
+
+                if added.is_empty() {
+                    return caps[0].to_string();
+                }
+                format!(
+                    "<{}{} {}{}>",
+                    name,
+                    attrs,
+                    added.join(" "),
+                    if self_closing { " /" } else { "" }
+                )
+            })
+            .into_owned()
+    }
 }
 
 impl Default for LazyLoadingRegistry {
@@ -333,6 +426,61 @@ pub struct LazyLoadManifest {
     pub route_map: HashMap<String, Vec<String>>,
 }
 
+/// Intrinsic size of a media item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaDimensions {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Options for lazy loading media in rendered content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContentLazyLoadConfig {
+    /// Leading images treated as above the fold and loaded eagerly
+    pub eager_images: usize,
+    /// Lazy load iframes as well as images
+    pub lazy_iframes: bool,
+    /// Fill in missing width/height from registered media dimensions
+    pub inject_dimensions: bool,
+}
+
+impl Default for ContentLazyLoadConfig {
+    fn default() -> Self {
+        Self {
+            eager_images: 1,
+            lazy_iframes: true,
+            inject_dimensions: true,
+        }
+    }
+}
+
+/// Matches `<img>` and `<iframe>` start tags, allowing `>` inside quoted values
+fn media_tag_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"(?i)<(img|iframe)\b((?:[^>"']|"[^"]*"|'[^']*')*)>"#).unwrap())
+}
+
+/// Parse a tag's attributes into lowercase names and unquoted values
+fn parse_attributes(attrs: &str) -> HashMap<String, String> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(r#"([^\s"'=/>]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+)))?"#).unwrap()
+    });
+
+    re.captures_iter(attrs)
+        .map(|caps| {
+            let value = caps
+                .get(2)
+                .or_else(|| caps.get(3))
+                .or_else(|| caps.get(4))
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default();
+            (caps[1].to_ascii_lowercase(), value)
+        })
+        .collect()
+}
+
 /// Intersection Observer configuration for lazy loading
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IntersectionObserverConfig {
@@ -689,6 +837,49 @@ mod tests {
         assert_eq!(components.len(), 3);
     }
 
+    #[test]
+    fn test_transform_html() {
+        let registry = LazyLoadingRegistry::new();
+        registry.register_media_dimensions("/uploads/b.jpg", 800, 600);
+        let config = ContentLazyLoadConfig::default();
+
+        let html = concat!(
+            r#"<p><img src="/uploads/a.jpg" alt="a > b"></p>"#,
+            r#"<img src="/uploads/b.jpg?v=2"/>"#,
+            r#"<IMG src="/uploads/c.jpg" loading="eager">"#,
+            r#"<img src="/uploads/d.jpg" width="10" decoding="sync">"#,
+            r#"<iframe src="https://example.com/embed"></iframe>"#,
+            r#"<imgur-embed></imgur-embed>"#,
+        );
+        let out = registry.transform_html(html, &config);
+
+        assert!(out.contains(
+            r#"<img src="/uploads/a.jpg" alt="a > b" loading="eager" fetchpriority="high">"#
+        ));
+        assert!(out.contains(
+            r#"<img src="/uploads/b.jpg?v=2" loading="lazy" decoding="async" width="800" height="600" />"#
+        ));
+        assert!(out.contains(r#"<IMG src="/uploads/c.jpg" loading="eager">"#));
+        assert!(
+            out.contains(r#"<img src="/uploads/d.jpg" width="10" decoding="sync" loading="lazy">"#)
+        );
+        assert!(out.contains(r#"<iframe src="https://example.com/embed" loading="lazy">"#));
+        assert!(out.contains("<imgur-embed>"));
+
+        assert_eq!(registry.transform_html(&out, &config), out);
+    }
+
+    #[test]
+    fn test_transform_html_eager_image_counts_toward_fold() {
+        let registry = LazyLoadingRegistry::new();
+        let html = r#"<img src="/a.jpg" loading="eager"><img src="/b.jpg">"#;
+        let out = registry.transform_html(html, &ContentLazyLoadConfig::default());
+        assert_eq!(
+            out,
+            r#"<img src="/a.jpg" loading="eager"><img src="/b.jpg" loading="lazy" decoding="async">"#
+        );
+    }
+
     #[test]
     fn test_preload_hints() {
         let registry = create_admin_component_registry();
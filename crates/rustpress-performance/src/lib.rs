@@ -78,18 +78,22 @@ pub mod static_files;
 pub use cdn::{CdnConfig, CdnManager, CdnPurger, CdnRewriter};
 pub use connection_pool::{PoolConfig, PoolHealth, PoolMonitor, PoolStats};
 pub use edge_cache::{EdgeCacheConfig, EdgeCacheHeaders, EdgeCacheRule};
-pub use http_cache::{CacheControl, CacheProfile, ETag, HttpCacheHeaders};
+pub use http_cache::{CacheAudience, CacheControl, CacheProfile, ETag, HttpCacheHeaders};
 pub use image_optimization::{ImageOptimizer, ImageOptimizerConfig, OptimizedImage};
 pub use isr::{IsrConfig, IsrHandler, IsrStore, StaticPage};
-pub use lazy_loading::{LazyComponent, LazyLoadingRegistry};
-pub use load_balancing::{LoadBalancer, LoadBalancingStrategy, Session, SessionConfig};
+pub use lazy_loading::{
+    ContentLazyLoadConfig, LazyComponent, LazyLoadingRegistry, MediaDimensions,
+};
+pub use load_balancing::{
+    AffinityKey, LoadBalancer, LoadBalancingStrategy, Session, SessionConfig, StickyRoute,
+};
 pub use minification::{MinificationConfig, MinifiedAsset, Minifier};
 pub use object_cache::{CacheBackend, ObjectCache, ObjectCacheConfig};
 pub use page_cache::{CacheTagBuilder, CachedPage, PageCache, PageCacheConfig};
 pub use preload::{HintType, ResourceHint, ResourceHintsManager};
-pub use profiling::{PerformanceMetrics, Profiler, ProfilingConfig, RequestTrace};
+pub use profiling::{PerformanceMetrics, Profiler, ProfilingConfig, RequestTrace, TraceObserver};
 pub use query_cache::{QueryCache, QueryCacheConfig};
-pub use query_logging::{QueryLogEntry, QueryLogger, QueryLoggerConfig};
+pub use query_logging::{QueryLogEntry, QueryLogger, QueryLoggerConfig, SlowQueryStat};
 pub use service_worker::{ServiceWorkerConfig, ServiceWorkerGenerator};
 pub use ssr::{SsrConfig, SsrRenderer, StreamingRenderer};
 pub use static_files::{AssetManifest, StaticFileServer};
@@ -240,6 +240,35 @@ pub enum LoadBalancingStrategy {
     Random,
     /// Least response time
     LeastResponseTime,
+    /// Pin each client to one backend, failing over when it is unhealthy
+    StickySession { affinity_key: AffinityKey },
+}
+
+/// How sticky sessions identify a client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AffinityKey {
+    /// The affinity cookie names the backend; the session cookie is the key
+    Cookie,
+    /// The client IP address is the key
+    ClientIp,
+    /// Use the cookies when present, otherwise the client IP
+    CookieOrIp,
+}
+
+/// Default time a recovered node must stay healthy before taking new clients
+const DEFAULT_FAILBACK_DELAY: Duration = Duration::from_secs(30);
+
+/// Routing decision for a sticky session request
+#[derive(Debug, Clone)]
+pub struct StickyRoute {
+    /// Backend to send the request to
+    pub node: ServerNode,
+    /// Previous backend, if the client was moved off an unhealthy one
+    pub failed_over_from: Option<String>,
+    /// Session state loaded from the shared store after a failover
+    pub session: Option<Session>,
+    /// Affinity cookie to send when the client's backend changed
+    pub set_cookie: Option<SessionCookie>,
 }
 
 /// Load balancer
@@ -248,6 +277,11 @@ pub struct LoadBalancer {
     strategy: LoadBalancingStrategy,
     current_index: Arc<RwLock<usize>>,
     session_affinity: Arc<RwLock<HashMap<String, String>>>,
+    session_config: SessionConfig,
+    session_store: Option<Arc<MemorySessionStore>>,
+    failback_delay: Duration,
+    /// Nodes that recently became healthy again, by node ID
+    recovered_at: Arc<RwLock<HashMap<String, Instant>>>,
 }
 
 impl LoadBalancer {
@@ -257,9 +291,26 @@ impl LoadBalancer {
             strategy,
             current_index: Arc::new(RwLock::new(0)),
             session_affinity: Arc::new(RwLock::new(HashMap::new())),
+            session_config: SessionConfig::default(),
+            session_store: None,
+            failback_delay: DEFAULT_FAILBACK_DELAY,
+            recovered_at: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Use these cookie names and re-hydrate failed-over sessions from `store`
+    pub fn with_sessions(mut self, config: SessionConfig, store: Arc<MemorySessionStore>) -> Self {
+        self.session_config = config;
+        self.session_store = Some(store);
+        self
+    }
+
+    /// Set how long a recovered node must stay healthy before taking new clients
+    pub fn with_failback_delay(mut self, delay: Duration) -> Self {
+        self.failback_delay = delay;
+        self
+    }
+
     /// Add a server node
     pub fn add_node(&self, node: ServerNode) {
         self.nodes.write().push(node);
@@ -272,6 +323,15 @@ impl LoadBalancer {
 
     /// Get next server based on strategy
     pub fn next(&self, client_ip: Option<&str>, session_id: Option<&str>) -> Option<ServerNode> {
+        if let LoadBalancingStrategy::StickySession { affinity_key } = self.strategy {
+            let key = match affinity_key {
+                AffinityKey::Cookie => session_id,
+                AffinityKey::ClientIp => client_ip,
+                AffinityKey::CookieOrIp => session_id.or(client_ip),
+            };
+            return self.sticky(key, None, session_id).map(|route| route.node);
+        }
+
         // Check session affinity first
         if let Some(sid) = session_id {
             let affinity = self.session_affinity.read();
@@ -322,6 +382,7 @@ impl LoadBalancer {
                     .min_by_key(|n| n.active_connections)
                     .unwrap()
             }
+            LoadBalancingStrategy::StickySession { .. } => unreachable!(),
         };
 
         // Set session affinity
@@ -334,6 +395,148 @@ impl LoadBalancer {
         Some((*selected).clone())
     }
 
+    /// Route a request, honouring session affinity.
+    ///
+    /// The session and affinity cookies are read from the `Cookie` header
+    /// using the names in the session config. Other strategies fall back to
+    /// [`next`](Self::next) keyed by the session cookie.
+    pub fn route(
+        &self,
+        client_ip: Option<&str>,
+        cookie_header: Option<&str>,
+    ) -> Option<StickyRoute> {
+        let session_id =
+            cookie_header.and_then(|header| cookie_value(header, &self.session_config.cookie_name));
+
+        let LoadBalancingStrategy::StickySession { affinity_key } = self.strategy else {
+            return self.next(client_ip, session_id).map(|node| StickyRoute {
+                node,
+                failed_over_from: None,
+                session: None,
+                set_cookie: None,
+            });
+        };
+
+        let pinned = cookie_header
+            .and_then(|header| cookie_value(header, &self.session_config.affinity_cookie));
+        let cookie_key = session_id.or(pinned);
+
+        match affinity_key {
+            AffinityKey::Cookie => self.sticky_with_cookie(cookie_key, pinned, session_id),
+            AffinityKey::ClientIp => self.sticky(client_ip, None, session_id),
+            AffinityKey::CookieOrIp if cookie_key.is_some() => {
+                self.sticky_with_cookie(cookie_key, pinned, session_id)
+            }
+            AffinityKey::CookieOrIp => self.sticky(client_ip, None, session_id),
+        }
+    }
+
+    /// Sticky routing that reissues the affinity cookie when the backend changes
+    fn sticky_with_cookie(
+        &self,
+        key: Option<&str>,
+        pinned: Option<&str>,
+        session_id: Option<&str>,
+    ) -> Option<StickyRoute> {
+        let mut route = self.sticky(key, pinned, session_id)?;
+        if pinned != Some(route.node.id.as_str()) {
+            route.set_cookie = Some(generate_affinity_cookie(
+                &route.node.id,
+                &self.session_config.affinity_cookie,
+            ));
+        }
+        Some(route)
+    }
+
+    /// Keep `key` on its backend while healthy, otherwise move it.
+    ///
+    /// A move rebinds the key to the new backend, so the client stays there
+    /// when the old one recovers instead of bouncing back. Recovered nodes
+    /// only take new clients after the failback delay.
+    fn sticky(
+        &self,
+        key: Option<&str>,
+        pinned: Option<&str>,
+        session_id: Option<&str>,
+    ) -> Option<StickyRoute> {
+        let bound = key.and_then(|k| self.session_affinity.read().get(k).cloned());
+        let target = pinned.map(str::to_string).or(bound);
+
+        let nodes = self.nodes.read();
+        if let Some(node) = target
+            .as_deref()
+            .and_then(|id| nodes.iter().find(|n| n.id == id && n.healthy))
+        {
+            if let Some(k) = key {
+                self.session_affinity
+                    .write()
+                    .insert(k.to_string(), node.id.clone());
+            }
+            return Some(StickyRoute {
+                node: node.clone(),
+                failed_over_from: None,
+                session: None,
+                set_cookie: None,
+            });
+        }
+
+        let healthy: Vec<&ServerNode> = nodes.iter().filter(|n| n.healthy).collect();
+        let settled: Vec<&ServerNode> = {
+            let recovered_at = self.recovered_at.read();
+            let recovering = |n: &ServerNode| {
+                recovered_at
+                    .get(&n.id)
+                    .is_some_and(|at| at.elapsed() < self.failback_delay)
+            };
+            healthy.iter().copied().filter(|n| !recovering(n)).collect()
+        };
+        let candidates = if settled.is_empty() { healthy } else { settled };
+
+        // Least connections, breaking ties by rendezvous hash so clients
+        // spread evenly instead of all landing on the first node
+        let key_str = key.unwrap_or_default();
+        let node = candidates.iter().copied().min_by_key(|n| {
+            (
+                n.active_connections,
+                self.hash_string(&format!("{}|{}", key_str, n.id)),
+            )
+        })?;
+
+        if let Some(k) = key {
+            self.session_affinity
+                .write()
+                .insert(k.to_string(), node.id.clone());
+        }
+
+        let failed_over_from = target.filter(|id| *id != node.id);
+        let session = match (&failed_over_from, session_id) {
+            (Some(_), Some(sid)) => self.rehydrate_session(sid, &node.id),
+            _ => None,
+        };
+
+        Some(StickyRoute {
+            node: node.clone(),
+            failed_over_from,
+            session,
+            set_cookie: None,
+        })
+    }
+
+    /// Load a session from the shared store and hand it to a new backend
+    fn rehydrate_session(&self, session_id: &str, node_id: &str) -> Option<Session> {
+        let store = self.session_store.as_ref()?;
+        let mut session = store.get(session_id)?;
+        if session.is_expired(self.session_config.timeout_seconds) {
+            store.delete(session_id);
+            return None;
+        }
+
+        session.server_id = Some(node_id.to_string());
+        session.touch();
+        store.save(session.clone());
+        Some(session)
+    }
+
     fn weighted_round_robin<'a>(&self, nodes: &[&'a ServerNode]) -> &'a ServerNode {
         let total_weight: u32 = nodes.iter().map(|n| n.weight).sum();
 
@@ -361,6 +564,13 @@ impl LoadBalancer {
     pub fn set_node_health(&self, id: &str, healthy: bool) {
         let mut nodes = self.nodes.write();
         if let Some(node) = nodes.iter_mut().find(|n| n.id == id) {
+            if healthy && !node.healthy {
+                self.recovered_at
+                    .write()
+                    .insert(id.to_string(), Instant::now());
+            } else if !healthy {
+                self.recovered_at.write().remove(id);
+            }
             node.healthy = healthy;
             node.last_health_check = chrono::Utc::now().timestamp();
         }
@@ -455,6 +665,7 @@ impl Default for HealthChecker {
 }
 
 /// Session cookie generator
+#[derive(Debug, Clone)]
 pub struct SessionCookie {
     pub name: String,
     pub value: String,
@@ -525,6 +736,16 @@ impl SessionCookie {
     }
 }
 
+/// Read a cookie value from a `Cookie` header
+fn cookie_value<'a>(header: &'a str, name: &str) -> Option<&'a str> {
+    header
+        .split(';')
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.trim())
+        .filter(|value| !value.is_empty())
+}
+
 /// Server affinity cookie generator
 pub fn generate_affinity_cookie(node_id: &str, cookie_name: &str) -> SessionCookie {
     SessionCookie {
@@ -598,4 +819,123 @@ mod tests {
         assert!(header.contains("HttpOnly"));
         assert!(header.contains("SameSite=Lax"));
     }
+
+    fn sticky_balancer(affinity_key: AffinityKey) -> LoadBalancer {
+        let lb = LoadBalancer::new(LoadBalancingStrategy::StickySession { affinity_key });
+        lb.add_node(ServerNode::new("server1", "http://server1:8080"));
+        lb.add_node(ServerNode::new("server2", "http://server2:8080"));
+        lb.add_node(ServerNode::new("server3", "http://server3:8080"));
+        lb
+    }
+
+    #[test]
+    fn test_sticky_cookie_affinity_and_failover() {
+        let store = Arc::new(MemorySessionStore::new(SessionConfig::default()));
+        let mut session = Session::new("sess1".to_string());
+        session.set("cart", vec![1, 2]);
+        store.save(session);
+
+        let lb = sticky_balancer(AffinityKey::Cookie)
+            .with_sessions(SessionConfig::default(), store.clone());
+
+        // A new client gets an affinity cookie and keeps its backend
+        let first = lb.route(None, Some("RSESSID=sess1")).unwrap();
+        let cookie = first.set_cookie.expect("affinity cookie");
+        assert_eq!(cookie.name, "SERVERID");
+        assert_eq!(cookie.value, first.node.id);
+
+        let header = format!("RSESSID=sess1; SERVERID={}", first.node.id);
+        let again = lb.route(None, Some(&header)).unwrap();
+        assert_eq!(again.node.id, first.node.id);
+        assert!(again.set_cookie.is_none());
+        assert!(again.failed_over_from.is_none());
+
+        // The pinned backend goes down: move and re-hydrate from the store
+        lb.set_node_health(&first.node.id, false);
+        let failover = lb.route(None, Some(&header)).unwrap();
+        assert_ne!(failover.node.id, first.node.id);
+        assert_eq!(
+            failover.failed_over_from.as_deref(),
+            Some(first.node.id.as_str())
+        );
+        assert_eq!(failover.set_cookie.unwrap().value, failover.node.id);
+        let session = failover.session.expect("re-hydrated session");
+        assert_eq!(session.get::<Vec<i32>>("cart"), Some(vec![1, 2]));
+        assert_eq!(
+            store.get("sess1").unwrap().server_id.as_deref(),
+            Some(failover.node.id.as_str())
+        );
+
+        // The original backend recovering doesn't pull the client back
+        lb.set_node_health(&first.node.id, true);
+        let header = format!("RSESSID=sess1; SERVERID={}", failover.node.id);
+        let settled = lb.route(None, Some(&header)).unwrap();
+        assert_eq!(settled.node.id, failover.node.id);
+        assert!(settled.failed_over_from.is_none());
+    }
+
+    #[test]
+    fn test_sticky_client_ip_affinity() {
+        let lb = sticky_balancer(AffinityKey::ClientIp);
+
+        let first = lb.route(Some("10.0.0.1"), None).unwrap();
+        for _ in 0..5 {
+            let node = lb.route(Some("10.0.0.1"), None).unwrap();
+            assert_eq!(node.node.id, first.node.id);
+            assert!(node.set_cookie.is_none());
+        }
+        assert_eq!(lb.next(Some("10.0.0.1"), None).unwrap().id, first.node.id);
+
+        lb.set_node_health(&first.node.id, false);
+        let moved = lb.route(Some("10.0.0.1"), None).unwrap();
+        assert_ne!(moved.node.id, first.node.id);
+        assert!(moved.session.is_none());
+
+        lb.set_node_health(&first.node.id, true);
+        assert_eq!(
+            lb.route(Some("10.0.0.1"), None).unwrap().node.id,
+            moved.node.id
+        );
+    }
+
+    #[test]
+    fn test_recovered_node_waits_for_failback_delay() {
+        let lb = LoadBalancer::new(LoadBalancingStrategy::StickySession {
+            affinity_key: AffinityKey::CookieOrIp,
+        });
+        lb.add_node(ServerNode::new("server1", "http://server1:8080"));
+        lb.add_node(ServerNode::new("server2", "http://server2:8080"));
+
+        lb.set_node_health("server1", false);
+        lb.set_node_health("server1", true);
+
+        // server1 is healthy but still settling, so new clients avoid it
+        for i in 0..10 {
+            let ip = format!("10.0.0.{}", i);
+            assert_eq!(lb.route(Some(&ip), None).unwrap().node.id, "server2");
+        }
+
+        // Cookies take precedence over the IP
+        let route = lb
+            .route(Some("10.0.0.1"), Some("SERVERID=server1"))
+            .unwrap();
+        assert_eq!(route.node.id, "server1");
+
+        let lb = lb.with_failback_delay(Duration::ZERO);
+        let mut seen = std::collections::HashSet::new();
+        for i in 10..40 {
+            let ip = format!("10.0.0.{}", i);
+            seen.insert(lb.route(Some(&ip), None).unwrap().node.id);
+        }
+        assert!(seen.contains("server1"));
+    }
+
+    #[test]
+    fn test_cookie_value() {
+        let header = "theme=dark; RSESSID=abc; SERVERID=server2";
+        assert_eq!(cookie_value(header, "RSESSID"), Some("abc"));
+        assert_eq!(cookie_value(header, "SERVERID"), Some("server2"));
+        assert_eq!(cookie_value(header, "missing"), None);
+        assert_eq!(cookie_value("SERVERID=", "SERVERID"), None);
+    }
 }
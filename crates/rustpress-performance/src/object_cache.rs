@@ -1,8 +1,14 @@
 //! Object Caching with Redis Integration
 //!
 //! Multi-tier object caching with local memory cache and Redis backend.
+//!
+//! [`ObjectCache::get_or_init`] coalesces concurrent misses for the same key
+//! into a single computation, so a hot key expiring does not send every
+//! request to the database at once.
 
 use async_trait::async_trait;
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
 use moka::future::Cache as MokaCache;
 use parking_lot::RwLock;
 use redis::AsyncCommands;
@@ -11,6 +17,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
+use tokio::sync::broadcast;
 
 /// Object cache errors
 #[derive(Debug, Error)]
@@ -29,6 +36,10 @@ pub enum ObjectCacheError {
 
     #[error("Invalid cache group: {0}")]
     InvalidGroup(String),
+
+    /// The computation another caller ran for this key failed
+    #[error("Shared computation failed: {0}")]
+    Compute(String),
 }
 
 /// Cache backend trait
@@ -116,9 +127,10 @@ impl CacheBackend for RedisBackend {
         let prefixed = self.prefixed_key(key);
 
         if let Some(ttl) = ttl {
-            conn.set_ex(&prefixed, value, ttl.as_secs()).await?;
+            conn.set_ex::<_, _, ()>(&prefixed, value, ttl.as_secs())
+                .await?;
         } else {
-            conn.set(&prefixed, value).await?;
+            conn.set::<_, _, ()>(&prefixed, value).await?;
         }
 
         Ok(())
@@ -199,7 +211,7 @@ impl CacheBackend for RedisBackend {
             }
         }
 
-        pipe.query_async(&mut conn).await?;
+        pipe.query_async::<_, ()>(&mut conn).await?;
         Ok(())
     }
 }
@@ -314,6 +326,25 @@ pub struct ObjectCache {
     stats: Arc<RwLock<ObjectCacheStats>>,
     /// Configuration
     config: ObjectCacheConfig,
+    /// Computations running in `get_or_init`, by full key
+    in_flight: Arc<DashMap<String, broadcast::Sender<FlightResult>>>,
+}
+
+/// Serialized value, or error message, shared with callers waiting on a
+/// computation
+type FlightResult = Result<Arc<Vec<u8>>, String>;
+
+/// Removes a key's in-flight entry when the computing caller finishes or is
+/// cancelled. Waiters then see their channel close and try again.
+struct FlightGuard<'a> {
+    in_flight: &'a DashMap<String, broadcast::Sender<FlightResult>>,
+    key: &'a str,
+}
+
+impl Drop for FlightGuard<'_> {
+    fn drop(&mut self) {
+        self.in_flight.remove(self.key);
+    }
 }
 
 /// Cache group configuration
@@ -372,6 +403,8 @@ pub struct ObjectCacheStats {
     pub misses: u64,
     pub stores: u64,
     pub deletes: u64,
+    /// `get_or_init` callers served by another caller's computation
+    pub coalesced: u64,
 }
 
 impl ObjectCache {
@@ -421,6 +454,7 @@ impl ObjectCache {
             groups: Arc::new(RwLock::new(groups)),
             stats: Arc::new(RwLock::new(ObjectCacheStats::default())),
             config,
+            in_flight: Arc::new(DashMap::new()),
         })
     }
 
@@ -437,6 +471,7 @@ impl ObjectCache {
             groups: Arc::new(RwLock::new(HashMap::new())),
             stats: Arc::new(RwLock::new(ObjectCacheStats::default())),
             config,
+            in_flight: Arc::new(DashMap::new()),
         }
     }
 
@@ -527,7 +562,16 @@ impl ObjectCache {
         let full_key = self.build_key(group, key);
         let data = serde_json::to_vec(value)
             .map_err(|e| ObjectCacheError::Serialization(e.to_string()))?;
+        self.store_with_ttl(full_key, data, ttl).await
+    }
 
+    /// Store serialized data in both tiers
+    async fn store_with_ttl(
+        &self,
+        full_key: String,
+        data: Vec<u8>,
+        ttl: Duration,
+    ) -> Result<(), ObjectCacheError> {
         // Store in local cache
         self.local.insert(full_key.clone(), data.clone()).await;
 
@@ -630,6 +674,97 @@ impl ObjectCache {
         Ok(value)
     }
 
+    /// Get or compute a value, running at most one computation per key at
+    /// a time.
+    ///
+    /// On a miss the first caller runs `f` and stores the result for `ttl`;
+    /// callers missing the same key meanwhile wait for that result instead
+    /// of running `f` themselves. If `f` fails, the caller that ran it gets
+    /// its error, waiters get [`ObjectCacheError::Compute`], and nothing is
+    /// cached, so the next call computes again. If the computing caller is
+    /// cancelled, one of the waiters takes over. Only callers of the same key
+    /// wait on each other.
+    pub async fn get_or_init<T, F, Fut>(
+        &self,
+        group: &str,
+        key: &str,
+        ttl: Duration,
+        f: F,
+    ) -> Result<T, ObjectCacheError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<T, ObjectCacheError>>,
+    {
+        let full_key = self.build_key(group, key);
+        let decode = |data: &[u8]| {
+            serde_json::from_slice(data).map_err(|e| ObjectCacheError::Serialization(e.to_string()))
+        };
+
+        let sender = loop {
+            if let Some(value) = self.get(group, key).await? {
+                return Ok(value);
+            }
+
+            // The shard lock is only held for the lookup, never across an await
+            let mut receiver = match self.in_flight.entry(full_key.clone()) {
+                Entry::Occupied(entry) => entry.get().subscribe(),
+                Entry::Vacant(entry) => {
+                    let (sender, _) = broadcast::channel(1);
+                    entry.insert(sender.clone());
+                    break sender;
+                }
+            };
+
+            match receiver.recv().await {
+                Ok(Ok(data)) => {
+                    self.stats.write().coalesced += 1;
+                    return decode(&data);
+                }
+                Ok(Err(message)) => return Err(ObjectCacheError::Compute(message)),
+                // The computing caller was dropped before finishing
+                Err(_) => continue,
+            }
+        };
+        let guard = FlightGuard {
+            in_flight: &self.in_flight,
+            key: &full_key,
+        };
+
+        // A computation finishing between our miss and taking the slot has
+        // already filled the local tier
+        if let Some(data) = self.local.get(&full_key).await {
+            drop(guard);
+            let _ = sender.send(Ok(Arc::new(data.clone())));
+            return decode(&data);
+        }
+
+        let result = match f().await {
+            Ok(value) => match serde_json::to_vec(&value) {
+                Ok(data) => self
+                    .store_with_ttl(full_key.clone(), data.clone(), ttl)
+                    .await
+                    .map(|_| (value, data)),
+                Err(e) => Err(ObjectCacheError::Serialization(e.to_string())),
+            },
+            Err(e) => Err(e),
+        };
+
+        // Release the slot before notifying, so callers arriving from now on
+        // find the stored value or, after a failure, compute afresh
+        drop(guard);
+        match result {
+            Ok((value, data)) => {
+                let _ = sender.send(Ok(Arc::new(data)));
+                Ok(value)
+            }
+            Err(e) => {
+                let _ = sender.send(Err(e.to_string()));
+                Err(e)
+            }
+        }
+    }
+
     /// Get multiple values
     pub async fn mget<T: DeserializeOwned>(
         &self,
@@ -690,6 +825,11 @@ impl ObjectCache {
         self.stats.read().clone()
     }
 
+    /// Approximate number of entries in the local cache
+    pub fn local_entry_count(&self) -> u64 {
+        self.local.entry_count()
+    }
+
     /// Check if remote backend is available
     pub fn has_remote(&self) -> bool {
         self.remote.is_some()
@@ -797,4 +937,110 @@ mod tests {
         let result2 = cache.incr("counters", "visits", 5).await.unwrap();
         assert_eq!(result2, 6);
     }
+
+    #[tokio::test]
+    async fn test_get_or_init_single_flight() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let cache = Arc::new(ObjectCache::memory_only(ObjectCacheConfig::default()));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..16)
+            .map(|_| {
+                let cache = cache.clone();
+                let calls = calls.clone();
+                tokio::spawn(async move {
+                    cache
+                        .get_or_init("posts", "hot", Duration::from_secs(60), || async move {
+                            calls.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            Ok("computed".to_string())
+                        })
+                        .await
+                })
+            })
+            .collect();
+
+        for task in tasks {
+            assert_eq!(task.await.unwrap().unwrap(), "computed");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(cache.stats().coalesced > 0);
+        assert!(cache.in_flight.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_or_init_failure_is_not_cached() {
+        let cache = Arc::new(ObjectCache::memory_only(ObjectCacheConfig::default()));
+        let ttl = Duration::from_secs(60);
+
+        let leader = {
+            let cache = cache.clone();
+            tokio::spawn(async move {
+                cache
+                    .get_or_init::<String, _, _>("posts", "flaky", ttl, || async {
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        Err(ObjectCacheError::Pool("database down".to_string()))
+                    })
+                    .await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let waiter = cache
+            .get_or_init("posts", "flaky", ttl, || async {
+                Ok("unexpected".to_string())
+            })
+            .await;
+
+        assert!(matches!(
+            leader.await.unwrap(),
+            Err(ObjectCacheError::Pool(_))
+        ));
+        assert!(
+            matches!(waiter, Err(ObjectCacheError::Compute(msg)) if msg.contains("database down"))
+        );
+
+        // The next caller computes again
+        let value = cache
+            .get_or_init("posts", "flaky", ttl, || async {
+                Ok("recovered".to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, "recovered");
+    }
+
+    #[tokio::test]
+    async fn test_get_or_init_survives_cancelled_leader() {
+        let cache = Arc::new(ObjectCache::memory_only(ObjectCacheConfig::default()));
+        let ttl = Duration::from_secs(60);
+
+        let leader = {
+            let cache = cache.clone();
+            tokio::spawn(async move {
+                cache
+                    .get_or_init("posts", "slow", ttl, || async {
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        Ok("never".to_string())
+                    })
+                    .await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let waiter = {
+            let cache = cache.clone();
+            tokio::spawn(async move {
+                cache
+                    .get_or_init("posts", "slow", ttl, || async {
+                        Ok("takeover".to_string())
+                    })
+                    .await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        leader.abort();
+
+        assert_eq!(waiter.await.unwrap().unwrap(), "takeover");
+    }
 }
@@ -362,6 +362,23 @@ impl PageCache {
         self.cache.read().len()
     }
 
+    /// Approximate memory held by cached pages, in bytes
+    pub fn memory_bytes(&self) -> usize {
+        self.cache
+            .read()
+            .iter()
+            .map(|(key, entry)| {
+                let headers: usize = entry
+                    .page
+                    .headers
+                    .iter()
+                    .map(|(k, v)| k.len() + v.len())
+                    .sum();
+                key.len() + entry.page.content.len() + headers
+            })
+            .sum()
+    }
+
     /// Evict entries if over capacity
     fn evict_if_needed(&self) {
         let mut cache = self.cache.write();
@@ -2,8 +2,10 @@
 //!
 //! Generates resource hints for browsers to optimize loading performance.
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
 
 /// Resource hint type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -97,6 +99,12 @@ pub struct ResourceHint {
     pub integrity: Option<String>,
     /// Fetch priority hint
     pub fetch_priority: Option<FetchPriority>,
+    /// Responsive image candidates (for image preloads)
+    #[serde(default)]
+    pub image_srcset: Option<String>,
+    /// Responsive image sizes (for image preloads)
+    #[serde(default)]
+    pub image_sizes: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -148,6 +156,8 @@ impl ResourceHint {
             media: None,
             integrity: None,
             fetch_priority: None,
+            image_srcset: None,
+            image_sizes: None,
         }
     }
 
@@ -162,6 +172,8 @@ impl ResourceHint {
             media: None,
             integrity: None,
             fetch_priority: None,
+            image_srcset: None,
+            image_sizes: None,
         }
     }
 
@@ -176,6 +188,8 @@ impl ResourceHint {
             media: None,
             integrity: None,
             fetch_priority: None,
+            image_srcset: None,
+            image_sizes: None,
         }
     }
 
@@ -190,6 +204,8 @@ impl ResourceHint {
             media: None,
             integrity: None,
             fetch_priority: None,
+            image_srcset: None,
+            image_sizes: None,
         }
     }
 
@@ -204,6 +220,8 @@ impl ResourceHint {
             media: None,
             integrity: None,
             fetch_priority: None,
+            image_srcset: None,
+            image_sizes: None,
         }
     }
 
@@ -231,6 +249,20 @@ impl ResourceHint {
         self
     }
 
+    /// Add MIME type
+    pub fn with_mime_type(mut self, mime_type: &str) -> Self {
+        self.mime_type = Some(mime_type.to_string());
+        self
+    }
+
+    /// Add responsive image candidates, so the browser preloads the same
+    /// candidate the `<img>` will select
+    pub fn with_image_srcset(mut self, srcset: &str, sizes: Option<&str>) -> Self {
+        self.image_srcset = Some(srcset.to_string());
+        self.image_sizes = sizes.map(str::to_string);
+        self
+    }
+
     /// Generate HTML link element
     pub fn to_html(&self) -> String {
         let mut attrs = vec![
@@ -262,6 +294,14 @@ impl ResourceHint {
             attrs.push(format!("fetchpriority=\"{}\"", priority.as_str()));
         }
 
+        if let Some(ref srcset) = self.image_srcset {
+            attrs.push(format!("imagesrcset=\"{}\"", srcset));
+        }
+
+        if let Some(ref sizes) = self.image_sizes {
+            attrs.push(format!("imagesizes=\"{}\"", sizes));
+        }
+
         format!("<link {}>", attrs.join(" "))
     }
 
@@ -358,6 +398,321 @@ impl ResourceHintsManager {
             .map(|h| h.to_link_header())
             .collect()
     }
+
+    /// Derive hints for a rendered page: preconnects to allowlisted
+    /// third-party origins, then preloads for the hero image, web fonts
+    /// and render-blocking or module scripts in the head.
+    ///
+    /// Resources the page already hints are skipped, and preloads are capped
+    /// so the critical path is not crowded out.
+    pub fn derive_from_html(html: &str, config: &HintDerivationConfig) -> Vec<ResourceHint> {
+        let head_end = find_ignore_case(html, "</head>").unwrap_or(html.len());
+        let (head, body) = html.split_at(head_end);
+
+        let mut seen: HashSet<(HintType, String)> = existing_hints(html);
+        let mut preconnects = Vec::new();
+        let mut preloads = Vec::new();
+        let mut cors_origins = HashSet::new();
+
+        let mut add_preload = |hint: ResourceHint, preloads: &mut Vec<ResourceHint>| {
+            if preloads.len() < config.max_preloads
+                && seen.insert((hint.hint_type, hint.href.clone()))
+            {
+                preloads.push(hint);
+            }
+        };
+
+        if config.preload_hero_image {
+            if let Some(hint) = hero_image(body) {
+                add_preload(hint, &mut preloads);
+            }
+        }
+
+        for font in font_faces(html).into_iter().take(config.max_font_preloads) {
+            cors_origins.extend(origin_of(&font.href));
+            add_preload(font, &mut preloads);
+        }
+
+        for script in critical_scripts(head) {
+            if script.crossorigin.is_some() {
+                cors_origins.extend(origin_of(&script.href));
+            }
+            add_preload(script, &mut preloads);
+        }
+
+        // Preconnect only to allowlisted origins the page actually uses
+        for url in resource_urls(html) {
+            let Some(origin) = origin_of(&url) else {
+                continue;
+            };
+            if preconnects.len() >= config.max_preconnects
+                || !config.allows_preconnect(&origin)
+                || !seen.insert((HintType::Preconnect, origin.clone()))
+            {
+                continue;
+            }
+            preconnects.push(origin);
+        }
+
+        preconnects
+            .into_iter()
+            .map(|origin| {
+                // Fonts and module scripts are fetched in CORS mode, which
+                // uses a separate connection from no-CORS requests
+                let mut hint = ResourceHint::preconnect(&origin);
+                if !cors_origins.contains(&origin) {
+                    hint.crossorigin = None;
+                }
+                hint
+            })
+            .chain(preloads)
+            .collect()
+    }
+
+    /// Insert hints at the start of the document head
+    pub fn inject_into_head(html: &str, hints: &[ResourceHint]) -> String {
+        let Some(start) = find_ignore_case(html, "<head") else {
+            return html.to_string();
+        };
+        let Some(end) = html[start..].find('>').map(|i| start + i + 1) else {
+            return html.to_string();
+        };
+
+        let links: String = hints.iter().map(|h| format!("\n{}", h.to_html())).collect();
+        format!("{}{}{}", &html[..end], links, &html[end..])
+    }
+}
+
+/// Settings for [`ResourceHintsManager::derive_from_html`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HintDerivationConfig {
+    /// Maximum preload hints across images, fonts and scripts
+    pub max_preloads: usize,
+    /// Maximum font preloads
+    pub max_font_preloads: usize,
+    /// Maximum preconnect hints
+    pub max_preconnects: usize,
+    /// Origins that may receive preconnect hints (e.g. "https://fonts.gstatic.com")
+    pub preconnect_allowlist: Vec<String>,
+    /// Preload the first eagerly loaded image in the body
+    pub preload_hero_image: bool,
+}
+
+impl Default for HintDerivationConfig {
+    fn default() -> Self {
+        Self {
+            max_preloads: 5,
+            max_font_preloads: 2,
+            max_preconnects: 3,
+            preconnect_allowlist: Vec::new(),
+            preload_hero_image: true,
+        }
+    }
+}
+
+impl HintDerivationConfig {
+    fn allows_preconnect(&self, origin: &str) -> bool {
+        self.preconnect_allowlist
+            .iter()
+            .any(|allowed| allowed.trim_end_matches('/').eq_ignore_ascii_case(origin))
+    }
+}
+
+fn tag_regex(tag: &'static str) -> Regex {
+    Regex::new(&format!(r"(?is)<{}\b([^>]*)>", tag)).unwrap()
+}
+
+/// Parse the attributes of a tag into lowercase names and raw values
+fn attributes(tag: &str) -> HashMap<String, String> {
+    static ATTR: OnceLock<Regex> = OnceLock::new();
+    let re = ATTR.get_or_init(|| {
+        Regex::new(r#"([a-zA-Z_:][-a-zA-Z0-9_:.]*)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+)))?"#)
+            .unwrap()
+    });
+
+    re.captures_iter(tag)
+        .map(|cap| {
+            let value = cap
+                .get(2)
+                .or_else(|| cap.get(3))
+                .or_else(|| cap.get(4))
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default();
+            (cap[1].to_ascii_lowercase(), value)
+        })
+        .collect()
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack.to_ascii_lowercase().find(needle)
+}
+
+/// Origin of an absolute URL (protocol-relative URLs are treated as https)
+fn origin_of(url: &str) -> Option<String> {
+    let url = if url.starts_with("//") {
+        format!("https:{}", url)
+    } else {
+        url.to_string()
+    };
+    let parsed = url::Url::parse(&url).ok()?;
+    match parsed.scheme() {
+        "http" | "https" => Some(parsed.origin().ascii_serialization()),
+        _ => None,
+    }
+}
+
+/// Hints the page already declares
+fn existing_hints(html: &str) -> HashSet<(HintType, String)> {
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    let re = LINK.get_or_init(|| tag_regex("link"));
+
+    re.captures_iter(html)
+        .filter_map(|cap| {
+            let attrs = attributes(&cap[1]);
+            let href = attrs.get("href")?.clone();
+            let hint_type = match attrs.get("rel")?.to_ascii_lowercase().as_str() {
+                "preload" => HintType::Preload,
+                "modulepreload" => HintType::ModulePreload,
+                "preconnect" => return Some((HintType::Preconnect, origin_of(&href)?)),
+                _ => return None,
+            };
+            Some((hint_type, href))
+        })
+        .collect()
+}
+
+/// The likely LCP image: the first image marked high priority, otherwise
+/// the first image that is not lazy loaded
+fn hero_image(body: &str) -> Option<ResourceHint> {
+    static IMG: OnceLock<Regex> = OnceLock::new();
+    let re = IMG.get_or_init(|| tag_regex("img"));
+
+    let images: Vec<_> = re
+        .captures_iter(body)
+        .map(|cap| attributes(&cap[1]))
+        .collect();
+    let hero = images
+        .iter()
+        .find(|attrs| {
+            attrs
+                .get("fetchpriority")
+                .is_some_and(|p| p.eq_ignore_ascii_case("high"))
+        })
+        .or_else(|| {
+            images.iter().find(|attrs| {
+                !attrs
+                    .get("loading")
+                    .is_some_and(|l| l.eq_ignore_ascii_case("lazy"))
+            })
+        })?;
+
+    let src = hero.get("src").filter(|src| !src.starts_with("data:"))?;
+    let mut hint =
+        ResourceHint::preload(src, ResourceAs::Image).with_fetch_priority(FetchPriority::High);
+    if let Some(srcset) = hero.get("srcset").filter(|s| !s.is_empty()) {
+        hint = hint.with_image_srcset(srcset, hero.get("sizes").map(String::as_str));
+    }
+    Some(hint)
+}
+
+/// Font preloads from `@font-face` rules, preferring the WOFF2 source
+fn font_faces(html: &str) -> Vec<ResourceHint> {
+    static FONT_FACE: OnceLock<Regex> = OnceLock::new();
+    static SRC_URL: OnceLock<Regex> = OnceLock::new();
+    let face_re = FONT_FACE.get_or_init(|| Regex::new(r"(?is)@font-face\s*\{([^}]*)\}").unwrap());
+    let url_re =
+        SRC_URL.get_or_init(|| Regex::new(r#"url\(\s*["']?([^"')]+?)["']?\s*\)"#).unwrap());
+
+    face_re
+        .captures_iter(html)
+        .filter_map(|face| {
+            let urls: Vec<&str> = url_re
+                .captures_iter(&face[1])
+                .filter_map(|cap| cap.get(1).map(|m| m.as_str()))
+                .filter(|url| font_mime(url).is_some())
+                .collect();
+            let url = urls
+                .iter()
+                .find(|url| font_mime(url) == Some("font/woff2"))
+                .or(urls.first())?;
+
+            Some(
+                ResourceHint::preload(url, ResourceAs::Font)
+                    .with_mime_type(font_mime(url)?)
+                    .with_crossorigin(CrossOrigin::Anonymous),
+            )
+        })
+        .collect()
+}
+
+fn font_mime(url: &str) -> Option<&'static str> {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let ext = path.rsplit('.').next()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "woff2" => Some("font/woff2"),
+        "woff" => Some("font/woff"),
+        "ttf" => Some("font/ttf"),
+        "otf" => Some("font/otf"),
+        _ => None,
+    }
+}
+
+/// Scripts in the head that block rendering or are ES modules. Async
+/// scripts are not on the critical path and are left alone.
+fn critical_scripts(head: &str) -> Vec<ResourceHint> {
+    static SCRIPT: OnceLock<Regex> = OnceLock::new();
+    let re = SCRIPT.get_or_init(|| tag_regex("script"));
+
+    re.captures_iter(head)
+        .filter_map(|cap| {
+            let attrs = attributes(&cap[1]);
+            let src = attrs.get("src")?;
+            if attrs.contains_key("async") {
+                return None;
+            }
+
+            let is_module = attrs
+                .get("type")
+                .is_some_and(|t| t.eq_ignore_ascii_case("module"));
+            let mut hint = if is_module {
+                ResourceHint::module_preload(src)
+            } else {
+                ResourceHint::preload(src, ResourceAs::Script)
+            };
+
+            // Keep the script's CORS mode so the preload can be reused
+            match attrs.get("crossorigin").map(|c| c.to_ascii_lowercase()) {
+                Some(mode) if mode == "use-credentials" => {
+                    hint.crossorigin = Some(CrossOrigin::UseCredentials)
+                }
+                Some(_) => hint.crossorigin = Some(CrossOrigin::Anonymous),
+                None if !is_module => hint.crossorigin = None,
+                None => {}
+            }
+            if let Some(integrity) = attrs.get("integrity") {
+                hint = hint.with_integrity(integrity);
+            }
+            Some(hint)
+        })
+        .collect()
+}
+
+/// URLs of resources referenced by the page
+fn resource_urls(html: &str) -> Vec<String> {
+    static SRC: OnceLock<Regex> = OnceLock::new();
+    static CSS_URL: OnceLock<Regex> = OnceLock::new();
+    let src_re = SRC.get_or_init(|| {
+        Regex::new(r#"(?i)<(?:img|script|link|source|iframe)\b[^>]*?\s(?:src|href)\s*=\s*["']([^"']+)["']"#)
+            .unwrap()
+    });
+    let css_re = CSS_URL
+        .get_or_init(|| Regex::new(r#"url\(\s*["']?((?:https?:)?//[^"')]+)["']?\s*\)"#).unwrap());
+
+    src_re
+        .captures_iter(html)
+        .chain(css_re.captures_iter(html))
+        .map(|cap| cap[1].to_string())
+        .collect()
 }
 
 impl Default for ResourceHintsManager {
@@ -605,4 +960,71 @@ mod tests {
 
         assert!(!hints.is_empty());
     }
+
+    #[test]
+    fn test_derive_from_html() {
+        let html = r#"<!DOCTYPE html><html><head>
+            <link rel="preload" href="/already.js" as="script">
+            <script src="/already.js"></script>
+            <script type="module" src="/app.mjs"></script>
+            <script async src="https://tracker.example.net/t.js"></script>
+            <style>
+              @font-face { font-family: Inter; src: url(/fonts/inter.woff) format("woff"), url('/fonts/inter.woff2') format("woff2"); }
+            </style>
+            <link rel="stylesheet" href="https://fonts.googleapis.com/css2?family=Inter">
+            </head><body>
+            <img src="/logo.svg" loading="lazy">
+            <img src="/hero.jpg" srcset="/hero-800.jpg 800w, /hero.jpg 1600w" sizes="100vw">
+            <img src="https://ads.example.org/banner.png">
+            </body></html>"#;
+        let config = HintDerivationConfig {
+            preconnect_allowlist: vec!["https://fonts.googleapis.com/".to_string()],
+            ..Default::default()
+        };
+
+        let hints = ResourceHintsManager::derive_from_html(html, &config);
+        let html_hints: Vec<String> = hints.iter().map(|h| h.to_html()).collect();
+
+        assert_eq!(
+            html_hints,
+            vec![
+                r#"<link rel="preconnect" href="https://fonts.googleapis.com">"#,
+                r#"<link rel="preload" href="/hero.jpg" as="image" fetchpriority="high" imagesrcset="/hero-800.jpg 800w, /hero.jpg 1600w" imagesizes="100vw">"#,
+                r#"<link rel="preload" href="/fonts/inter.woff2" as="font" type="font/woff2" crossorigin="anonymous">"#,
+                r#"<link rel="modulepreload" href="/app.mjs" crossorigin="anonymous">"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_derive_caps_and_dedupes() {
+        let html = r#"<head><script src="/a.js"></script><script src="/b.js"></script>
+            <script src="/a.js"></script><script src="/c.js"></script></head>"#;
+        let config = HintDerivationConfig {
+            max_preloads: 2,
+            ..Default::default()
+        };
+
+        let hints = ResourceHintsManager::derive_from_html(html, &config);
+        let hrefs: Vec<&str> = hints.iter().map(|h| h.href.as_str()).collect();
+        assert_eq!(hrefs, vec!["/a.js", "/b.js"]);
+        assert!(hints.iter().all(|h| h.crossorigin.is_none()));
+    }
+
+    #[test]
+    fn test_inject_into_head() {
+        let hints = vec![ResourceHint::preload("/app.js", ResourceAs::Script)];
+        let html = ResourceHintsManager::inject_into_head(
+            r#"<html><head lang="en"><title>T</title></head></html>"#,
+            &hints,
+        );
+        assert_eq!(
+            html,
+            "<html><head lang=\"en\">\n<link rel=\"preload\" href=\"/app.js\" as=\"script\"><title>T</title></head></html>"
+        );
+        assert_eq!(
+            ResourceHintsManager::inject_into_head("<p>x</p>", &hints),
+            "<p>x</p>"
+        );
+    }
 }
@@ -138,12 +138,16 @@ pub struct DatabaseMetrics {
     pub connection_pool_used: u32,
 }
 
+/// Callback invoked with every recorded trace
+pub type TraceObserver = Arc<dyn Fn(&RequestTrace) + Send + Sync>;
+
 /// Profiler
 pub struct Profiler {
     config: ProfilingConfig,
     traces: Arc<RwLock<Vec<RequestTrace>>>,
     metrics: Arc<RwLock<PerformanceMetrics>>,
     response_times: Arc<RwLock<Vec<u64>>>,
+    observers: RwLock<Vec<TraceObserver>>,
 }
 
 impl Profiler {
@@ -153,9 +157,16 @@ impl Profiler {
             traces: Arc::new(RwLock::new(Vec::new())),
             metrics: Arc::new(RwLock::new(PerformanceMetrics::default())),
             response_times: Arc::new(RwLock::new(Vec::new())),
+            observers: RwLock::new(Vec::new()),
         }
     }
 
+    /// Call `observer` with each sampled trace as it's recorded, e.g. to
+    /// export span and query latencies
+    pub fn observe(&self, observer: TraceObserver) {
+        self.observers.write().push(observer);
+    }
+
     /// Start timing a request
     pub fn start_request(&self) -> RequestTimer {
         RequestTimer {
@@ -194,6 +205,10 @@ impl Profiler {
             }
         }
 
+        for observer in self.observers.read().iter() {
+            observer(&trace);
+        }
+
         // Store response time for percentile calculation
         {
             let mut times = self.response_times.write();
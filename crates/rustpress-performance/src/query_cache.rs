@@ -253,6 +253,15 @@ impl QueryCache {
         self.cache.read().len()
     }
 
+    /// Memory held by cached results, in bytes
+    pub fn memory_bytes(&self) -> usize {
+        self.cache
+            .read()
+            .iter()
+            .map(|(key, entry)| key.len() + entry.data.len())
+            .sum()
+    }
+
     /// Evict entries if over capacity
     fn evict_if_needed(&self) {
         let mut cache = self.cache.write();
@@ -2,11 +2,14 @@
 //!
 //! Logs and analyzes database queries for performance optimization opportunities.
 
+use async_trait::async_trait;
 use parking_lot::RwLock;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::Instant;
+use sqlx::PgPool;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
 /// Query log entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -121,6 +124,10 @@ pub struct QueryLoggerConfig {
     pub log_all_queries: bool,
     /// Sample rate for logging (0.0-1.0)
     pub sample_rate: f64,
+    /// Fraction of slow queries whose EXPLAIN plan is captured (0.0-1.0)
+    pub explain_sample_rate: f64,
+    /// Minimum time between EXPLAIN captures for the same fingerprint
+    pub explain_interval: Duration,
 }
 
 impl Default for QueryLoggerConfig {
@@ -133,10 +140,96 @@ impl Default for QueryLoggerConfig {
             analyze_slow_queries: true,
             log_all_queries: false,
             sample_rate: 1.0,
+            explain_sample_rate: 0.1,
+            explain_interval: Duration::from_secs(60),
         }
     }
 }
 
+/// Runs EXPLAIN for slow queries selected by the logger
+#[async_trait]
+pub trait QueryExplainer: Send + Sync {
+    /// Return the query plan as text
+    async fn explain(&self, query: &str) -> Result<String, sqlx::Error>;
+}
+
+/// PostgreSQL explainer.
+///
+/// Only plans are requested, never `ANALYZE`, so the statement is not run
+/// again. Queries with bind placeholders use `GENERIC_PLAN` (PostgreSQL 16+).
+pub struct PgQueryExplainer {
+    pool: PgPool,
+}
+
+impl PgQueryExplainer {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl QueryExplainer for PgQueryExplainer {
+    async fn explain(&self, query: &str) -> Result<String, sqlx::Error> {
+        let query = query.trim().trim_end_matches(';');
+        if query.contains(';') {
+            return Err(sqlx::Error::Protocol(
+                "refusing to EXPLAIN multiple statements".to_string(),
+            ));
+        }
+
+        let explain = if bind_placeholder_regex().is_match(query) {
+            format!("EXPLAIN (GENERIC_PLAN) {}", query)
+        } else {
+            format!("EXPLAIN {}", query)
+        };
+        let lines: Vec<String> = sqlx::query_scalar(&explain).fetch_all(&self.pool).await?;
+        Ok(lines.join("\n"))
+    }
+}
+
+/// Slow-query statistics for one query fingerprint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowQueryStat {
+    /// Short hash of the normalized query
+    pub fingerprint: String,
+    /// Normalized query with literals stripped
+    pub query: String,
+    /// Most recent original query with this fingerprint
+    pub example_query: String,
+    /// Number of slow executions in the period
+    pub count: u64,
+    /// Total execution time in microseconds
+    pub total_time_us: u64,
+    /// Mean execution time in microseconds
+    pub mean_time_us: u64,
+    /// Maximum execution time in microseconds
+    pub max_time_us: u64,
+    /// Timestamp of the most recent slow execution
+    pub last_seen: i64,
+    /// Most recently captured query plan
+    pub query_plan: Option<String>,
+    /// Tables accessed by this query
+    pub tables: Vec<String>,
+}
+
+/// A single slow execution
+#[derive(Debug, Clone)]
+struct SlowQuerySample {
+    fingerprint: String,
+    execution_time_us: u64,
+    timestamp: i64,
+}
+
+/// Per-fingerprint details kept alongside the samples
+#[derive(Debug, Clone, Default)]
+struct SlowQueryPattern {
+    query: String,
+    example_query: String,
+    tables: Vec<String>,
+    query_plan: Option<String>,
+    last_explained: Option<Instant>,
+}
+
 /// Database query logger and analyzer
 pub struct QueryLogger {
     config: QueryLoggerConfig,
@@ -148,6 +241,12 @@ pub struct QueryLogger {
     nplusone_detector: Arc<RwLock<HashMap<String, NplusOnePattern>>>,
     /// Generated optimization suggestions
     suggestions: Arc<RwLock<Vec<OptimizationSuggestion>>>,
+    /// Recent slow executions, oldest first
+    slow_samples: Arc<RwLock<VecDeque<SlowQuerySample>>>,
+    /// Slow query details by fingerprint
+    slow_patterns: Arc<RwLock<HashMap<String, SlowQueryPattern>>>,
+    /// Plan capture for sampled slow queries
+    explainer: Option<Arc<dyn QueryExplainer>>,
 }
 
 #[derive(Debug, Clone)]
@@ -165,9 +264,18 @@ impl QueryLogger {
             stats: Arc::new(RwLock::new(HashMap::new())),
             nplusone_detector: Arc::new(RwLock::new(HashMap::new())),
             suggestions: Arc::new(RwLock::new(Vec::new())),
+            slow_samples: Arc::new(RwLock::new(VecDeque::new())),
+            slow_patterns: Arc::new(RwLock::new(HashMap::new())),
+            explainer: None,
         }
     }
 
+    /// Capture EXPLAIN plans for a sample of slow queries
+    pub fn with_explainer(mut self, explainer: Arc<dyn QueryExplainer>) -> Self {
+        self.explainer = Some(explainer);
+        self
+    }
+
     /// Log a query execution
     pub fn log_query(&self, entry: QueryLogEntry) {
         if !self.config.enabled {
@@ -209,10 +317,117 @@ impl QueryLogger {
 
         // Generate suggestions for slow queries
         if is_slow {
+            self.record_slow_query(&entry);
             self.analyze_slow_query(&entry);
         }
     }
 
+    /// Aggregate a slow query by fingerprint and maybe capture its plan
+    fn record_slow_query(&self, entry: &QueryLogEntry) {
+        let normalized = fingerprint_query(&entry.original_query);
+        let fingerprint = fingerprint_hash(&normalized);
+
+        tracing::warn!(
+            fingerprint = %fingerprint,
+            duration_ms = entry.execution_time_us / 1000,
+            query = %normalized,
+            "Slow query"
+        );
+
+        let should_explain = {
+            let mut patterns = self.slow_patterns.write();
+            let pattern = patterns.entry(fingerprint.clone()).or_default();
+            pattern.query = normalized;
+            pattern.example_query = entry.original_query.clone();
+            pattern.tables = entry.tables.clone();
+            if entry.query_plan.is_some() {
+                pattern.query_plan = entry.query_plan.clone();
+            }
+
+            let explain = self.should_explain(entry, pattern);
+            if explain {
+                pattern.last_explained = Some(Instant::now());
+            }
+            explain
+        };
+
+        {
+            let mut samples = self.slow_samples.write();
+            samples.push_back(SlowQuerySample {
+                fingerprint: fingerprint.clone(),
+                execution_time_us: entry.execution_time_us,
+                timestamp: entry.timestamp,
+            });
+            while samples.len() > self.config.max_entries {
+                samples.pop_front();
+            }
+        }
+
+        if should_explain {
+            self.capture_plan(fingerprint, entry.original_query.clone());
+        }
+    }
+
+    /// Decide whether this slow query's plan should be captured
+    fn should_explain(&self, entry: &QueryLogEntry, pattern: &SlowQueryPattern) -> bool {
+        if !self.config.analyze_slow_queries
+            || self.explainer.is_none()
+            || entry.query_plan.is_some()
+        {
+            return false;
+        }
+
+        if let Some(last) = pattern.last_explained {
+            if last.elapsed() < self.config.explain_interval {
+                return false;
+            }
+        }
+
+        self.config.explain_sample_rate >= 1.0
+            || rand::random::<f64>() < self.config.explain_sample_rate
+    }
+
+    /// Run EXPLAIN in the background and store the plan for the fingerprint
+    fn capture_plan(&self, fingerprint: String, query: String) {
+        let Some(explainer) = self.explainer.clone() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let patterns = self.slow_patterns.clone();
+        let suggestions = self.suggestions.clone();
+
+        runtime.spawn(async move {
+            let plan = match explainer.explain(&query).await {
+                Ok(plan) => plan,
+                Err(e) => {
+                    tracing::debug!(fingerprint = %fingerprint, "EXPLAIN failed: {}", e);
+                    return;
+                }
+            };
+
+            let normalized = {
+                let mut patterns = patterns.write();
+                let Some(pattern) = patterns.get_mut(&fingerprint) else {
+                    return;
+                };
+                pattern.query_plan = Some(plan.clone());
+                pattern.query.clone()
+            };
+
+            if let Some(suggestion) = full_table_scan_suggestion(&plan, &normalized) {
+                let mut suggestions = suggestions.write();
+                let exists = suggestions.iter().any(|s| {
+                    s.suggestion_type == SuggestionType::FullTableScan && s.query == normalized
+                });
+                if !exists {
+                    suggestions.push(suggestion);
+                }
+            }
+        });
+    }
+
     /// Start timing a query
     pub fn start_query(&self) -> QueryTimer {
         QueryTimer {
@@ -338,16 +553,7 @@ impl QueryLogger {
 
         // Check for full table scan indication
         if let Some(ref plan) = entry.query_plan {
-            if plan.contains("Seq Scan") || plan.contains("full table scan") {
-                suggestions.push(OptimizationSuggestion {
-                    suggestion_type: SuggestionType::FullTableScan,
-                    description: "Query performs a full table scan".to_string(),
-                    suggested_fix: "Consider adding an index on the columns used in WHERE clause"
-                        .to_string(),
-                    impact_score: 7,
-                    query: entry.query.clone(),
-                });
-            }
+            suggestions.extend(full_table_scan_suggestion(plan, &entry.query));
         }
 
         // Check if query used an index
@@ -392,6 +598,52 @@ impl QueryLogger {
         slow
     }
 
+    /// Top slow-query patterns seen within `period`, by total time
+    pub fn slow_query_report(&self, period: Duration) -> Vec<SlowQueryStat> {
+        let cutoff = chrono::Utc::now().timestamp_millis() - period.as_millis() as i64;
+        let samples = self.slow_samples.read();
+        let patterns = self.slow_patterns.read();
+
+        let mut report: HashMap<&str, SlowQueryStat> = HashMap::new();
+        for sample in samples.iter().filter(|s| s.timestamp >= cutoff) {
+            let Some(pattern) = patterns.get(&sample.fingerprint) else {
+                continue;
+            };
+            let stat = report
+                .entry(sample.fingerprint.as_str())
+                .or_insert_with(|| SlowQueryStat {
+                    fingerprint: sample.fingerprint.clone(),
+                    query: pattern.query.clone(),
+                    example_query: pattern.example_query.clone(),
+                    count: 0,
+                    total_time_us: 0,
+                    mean_time_us: 0,
+                    max_time_us: 0,
+                    last_seen: 0,
+                    query_plan: pattern.query_plan.clone(),
+                    tables: pattern.tables.clone(),
+                });
+            stat.count += 1;
+            stat.total_time_us += sample.execution_time_us;
+            stat.max_time_us = stat.max_time_us.max(sample.execution_time_us);
+            stat.last_seen = stat.last_seen.max(sample.timestamp);
+        }
+
+        let mut report: Vec<_> = report
+            .into_values()
+            .map(|mut stat| {
+                stat.mean_time_us = stat.total_time_us / stat.count;
+                stat
+            })
+            .collect();
+        report.sort_by(|a, b| {
+            b.total_time_us
+                .cmp(&a.total_time_us)
+                .then_with(|| b.count.cmp(&a.count))
+        });
+        report
+    }
+
     /// Get query statistics
     pub fn get_stats(&self) -> HashMap<String, QueryStats> {
         self.stats.read().clone()
@@ -428,6 +680,8 @@ impl QueryLogger {
         self.stats.write().clear();
         self.nplusone_detector.write().clear();
         self.suggestions.write().clear();
+        self.slow_samples.write().clear();
+        self.slow_patterns.write().clear();
     }
 
     /// Get summary report
@@ -534,33 +788,199 @@ impl QueryLogMiddleware {
 
 /// Normalize a SQL query for grouping
 fn normalize_query(query: &str) -> String {
-    let mut normalized = query.trim().to_uppercase();
-
-    // Remove extra whitespace
-    normalized = regex::Regex::new(r"\s+")
-        .unwrap()
-        .replace_all(&normalized, " ")
-        .to_string();
-
-    // Replace IN lists with placeholder
-    normalized = regex::Regex::new(r"IN\s*\([^)]+\)")
-        .unwrap()
-        .replace_all(&normalized, "IN (?)")
-        .to_string();
-
-    // Replace numeric values
-    normalized = regex::Regex::new(r"\b\d+\b")
-        .unwrap()
-        .replace_all(&normalized, "?")
-        .to_string();
-
-    // Replace string values
-    normalized = regex::Regex::new(r"'[^']*'")
-        .unwrap()
-        .replace_all(&normalized, "?")
-        .to_string();
-
-    normalized
+    fingerprint_query(query)
+}
+
+/// Normalize a query into its fingerprint text.
+///
+/// Comments are dropped, whitespace collapsed and keywords uppercased.
+/// String, numeric and dollar-quoted literals and bind placeholders all
+/// become `?`; literal `IN` lists, `ARRAY[...]` lists and multi-row
+/// `VALUES` collapse to a single item, so queries that differ only in
+/// parameters or list lengths share a fingerprint. Quoted identifiers and
+/// subqueries are kept as-is.
+pub fn fingerprint_query(query: &str) -> String {
+    let chars: Vec<char> = query.chars().collect();
+    let mut out = String::with_capacity(query.len());
+    let mut i = 0;
+
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let push_space = |out: &mut String| {
+        if !out.is_empty() && !out.ends_with(' ') {
+            out.push(' ');
+        }
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let prev_ident = i > 0 && (is_ident(chars[i - 1]) || chars[i - 1] == '$');
+
+        match c {
+            c if c.is_whitespace() => {
+                push_space(&mut out);
+                i += 1;
+            }
+            '-' if next == Some('-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                push_space(&mut out);
+            }
+            '-' if next.is_some_and(|c| c.is_ascii_digit())
+                && out
+                    .trim_end()
+                    .ends_with(&['=', '<', '>', '(', ',', '+', '-', '*', '/'][..]) =>
+            {
+                // A sign is part of the literal when it follows an operator
+                i += 1;
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+                push_space(&mut out);
+            }
+            '\'' => {
+                // E'...' strings allow backslash escapes
+                let escapes = out.ends_with('E')
+                    && !out[..out.len() - 1].ends_with(|c: char| is_ident(c) || c == '"');
+                if escapes {
+                    out.pop();
+                }
+                i += 1;
+                while i < chars.len() {
+                    let escaped = (escapes && chars[i] == '\\')
+                        || (chars[i] == '\'' && chars.get(i + 1) == Some(&'\''));
+                    if escaped {
+                        i += 2;
+                    } else if chars[i] == '\'' {
+                        i += 1;
+                        break;
+                    } else {
+                        i += 1;
+                    }
+                }
+                out.push('?');
+            }
+            '"' => {
+                out.push('"');
+                i += 1;
+                while i < chars.len() {
+                    out.push(chars[i]);
+                    i += 1;
+                    if chars[i - 1] == '"' {
+                        break;
+                    }
+                }
+            }
+            '$' if !prev_ident && next.is_some_and(|c| c.is_ascii_digit()) => {
+                i += 1;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+                out.push('?');
+            }
+            '$' if !prev_ident => {
+                // Dollar-quoted string: $$...$$ or $tag$...$tag$
+                let tag_end = chars[i + 1..]
+                    .iter()
+                    .position(|&c| !is_ident(c))
+                    .map(|p| i + 1 + p)
+                    .filter(|&end| chars[end] == '$');
+                match tag_end {
+                    Some(end) => {
+                        let tag: String = chars[i..=end].iter().collect();
+                        let body: String = chars[end + 1..].iter().collect();
+                        i = match body.find(&tag) {
+                            Some(close) => end + 1 + body[..close].chars().count() + tag.len(),
+                            None => chars.len(),
+                        };
+                        out.push('?');
+                    }
+                    None => {
+                        out.push('$');
+                        i += 1;
+                    }
+                }
+            }
+            '?' => {
+                out.push('?');
+                i += 1;
+            }
+            c if c.is_ascii_digit() || (c == '.' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                if prev_ident {
+                    out.push(c);
+                    i += 1;
+                    continue;
+                }
+                i += 1;
+                while i < chars.len()
+                    && (chars[i].is_ascii_alphanumeric()
+                        || chars[i] == '.'
+                        || ((chars[i] == '+' || chars[i] == '-')
+                            && matches!(chars[i - 1], 'e' | 'E')))
+                {
+                    i += 1;
+                }
+                out.push('?');
+            }
+            c => {
+                out.extend(c.to_uppercase());
+                i += 1;
+            }
+        }
+    }
+
+    let fingerprint = out.trim().trim_end_matches(';').trim_end();
+    let fingerprint = in_list_regex().replace_all(fingerprint, "IN (?)");
+    let fingerprint = array_list_regex().replace_all(&fingerprint, "ARRAY[?]");
+    values_list_regex()
+        .replace_all(&fingerprint, "VALUES $1")
+        .into_owned()
+}
+
+/// Short, stable identifier for a normalized query
+pub fn fingerprint_hash(normalized: &str) -> String {
+    blake3::hash(normalized.as_bytes()).to_hex()[..16].to_string()
+}
+
+/// Build a full table scan suggestion if the plan shows one
+fn full_table_scan_suggestion(plan: &str, query: &str) -> Option<OptimizationSuggestion> {
+    if !plan.contains("Seq Scan") && !plan.contains("full table scan") {
+        return None;
+    }
+    Some(OptimizationSuggestion {
+        suggestion_type: SuggestionType::FullTableScan,
+        description: "Query performs a full table scan".to_string(),
+        suggested_fix: "Consider adding an index on the columns used in WHERE clause".to_string(),
+        impact_score: 7,
+        query: query.to_string(),
+    })
+}
+
+fn bind_placeholder_regex() -> &'static Regex {
+    static BIND: OnceLock<Regex> = OnceLock::new();
+    BIND.get_or_init(|| Regex::new(r"\$\d+").unwrap())
+}
+
+fn in_list_regex() -> &'static Regex {
+    static IN_LIST: OnceLock<Regex> = OnceLock::new();
+    IN_LIST.get_or_init(|| Regex::new(r"\bIN ?\( ?\?(?: ?, ?\?)* ?\)").unwrap())
+}
+
+fn array_list_regex() -> &'static Regex {
+    static ARRAY_LIST: OnceLock<Regex> = OnceLock::new();
+    ARRAY_LIST.get_or_init(|| Regex::new(r"\bARRAY ?\[ ?\?(?: ?, ?\?)* ?\]").unwrap())
+}
+
+fn values_list_regex() -> &'static Regex {
+    static VALUES_LIST: OnceLock<Regex> = OnceLock::new();
+    VALUES_LIST.get_or_init(|| {
+        Regex::new(r"\bVALUES ?(\( ?\?(?: ?, ?\?)* ?\))(?: ?, ?\( ?\?(?: ?, ?\?)* ?\))*").unwrap()
+    })
 }
 
 #[cfg(test)]
@@ -615,4 +1035,140 @@ mod tests {
         let suggestions = logger.get_suggestions();
         assert!(!suggestions.is_empty());
     }
+
+    fn slow_entry(query: &str, execution_time_us: u64) -> QueryLogEntry {
+        QueryLogEntry {
+            query: normalize_query(query),
+            original_query: query.to_string(),
+            execution_time_us,
+            rows_affected: 1,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            stack_trace: None,
+            query_plan: None,
+            used_index: None,
+            tables: vec!["posts".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_strips_literals_and_lists() {
+        let a = fingerprint_query(
+            "SELECT id FROM posts /* list */ WHERE author = 'O''Brien' AND id IN (1, 2, 3) AND score > -1.5e3",
+        );
+        let b = fingerprint_query(
+            "select id\n  from posts -- trailing\n where author = E'it\\'s' and id in ($1,$2) and score > 7;",
+        );
+        assert_eq!(a, b);
+        assert_eq!(
+            a,
+            "SELECT ID FROM POSTS WHERE AUTHOR = ? AND ID IN (?) AND SCORE > ?"
+        );
+
+        assert_eq!(
+            fingerprint_query("INSERT INTO t2 (a, b) VALUES (1, 'x'), (2, 'y'), (3, $$z$$)"),
+            "INSERT INTO T2 (A, B) VALUES (?, ?)"
+        );
+        assert_eq!(
+            fingerprint_query("SELECT * FROM t WHERE id = ANY(ARRAY[1,2,3])"),
+            "SELECT * FROM T WHERE ID = ANY(ARRAY[?])"
+        );
+
+        // Subqueries and quoted identifiers are structure, not parameters
+        assert_eq!(
+            fingerprint_query(r#"SELECT "Col1" FROM t WHERE id IN (SELECT post_id FROM meta)"#),
+            r#"SELECT "Col1" FROM T WHERE ID IN (SELECT POST_ID FROM META)"#
+        );
+    }
+
+    #[test]
+    fn test_slow_query_report_aggregates_by_fingerprint() {
+        let config = QueryLoggerConfig {
+            slow_query_threshold_us: 1000,
+            ..Default::default()
+        };
+        let logger = QueryLogger::new(config);
+
+        logger.log_query(slow_entry("SELECT * FROM posts WHERE id = 1", 2000));
+        logger.log_query(slow_entry("SELECT * FROM posts WHERE id = 2", 4000));
+        logger.log_query(slow_entry("SELECT * FROM posts WHERE id = 3", 500));
+        logger.log_query(slow_entry("SELECT * FROM users WHERE id IN (1, 2)", 9000));
+
+        let mut old = slow_entry("SELECT * FROM posts WHERE id = 4", 3000);
+        old.timestamp -= 2 * 3600 * 1000;
+        logger.log_query(old);
+
+        let report = logger.slow_query_report(Duration::from_secs(3600));
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].query, "SELECT * FROM USERS WHERE ID IN (?)");
+        assert_eq!(report[1].query, "SELECT * FROM POSTS WHERE ID = ?");
+        assert_eq!(report[1].count, 2);
+        assert_eq!(report[1].total_time_us, 6000);
+        assert_eq!(report[1].mean_time_us, 3000);
+        assert_eq!(report[1].max_time_us, 4000);
+        assert_eq!(report[1].example_query, "SELECT * FROM posts WHERE id = 4");
+
+        // Ties on total time rank the more frequent pattern first
+        let report = logger.slow_query_report(Duration::from_secs(3 * 3600));
+        assert_eq!(report[0].query, "SELECT * FROM POSTS WHERE ID = ?");
+        assert_eq!(report[0].count, 3);
+    }
+
+    struct RecordingExplainer {
+        calls: Arc<RwLock<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl QueryExplainer for RecordingExplainer {
+        async fn explain(&self, query: &str) -> Result<String, sqlx::Error> {
+            self.calls.write().push(query.to_string());
+            Ok("Seq Scan on posts  (cost=0.00..35.50 rows=10 width=4)".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_explain_capture_is_sampled_per_fingerprint() {
+        let calls = Arc::new(RwLock::new(Vec::new()));
+        let config = QueryLoggerConfig {
+            slow_query_threshold_us: 1000,
+            explain_sample_rate: 1.0,
+            ..Default::default()
+        };
+        let logger = QueryLogger::new(config).with_explainer(Arc::new(RecordingExplainer {
+            calls: calls.clone(),
+        }));
+
+        for id in 0..5 {
+            logger.log_query(slow_entry(
+                &format!("SELECT * FROM posts WHERE id = {}", id),
+                2000,
+            ));
+        }
+        tokio::task::yield_now().await;
+
+        // Only the first occurrence is explained within the interval
+        assert_eq!(*calls.read(), vec!["SELECT * FROM posts WHERE id = 0"]);
+
+        let report = logger.slow_query_report(Duration::from_secs(60));
+        assert!(report[0]
+            .query_plan
+            .as_deref()
+            .unwrap()
+            .contains("Seq Scan"));
+        assert!(logger
+            .get_suggestions()
+            .iter()
+            .any(|s| s.suggestion_type == SuggestionType::FullTableScan));
+
+        let disabled = QueryLogger::new(QueryLoggerConfig {
+            slow_query_threshold_us: 1000,
+            explain_sample_rate: 0.0,
+            ..Default::default()
+        })
+        .with_explainer(Arc::new(RecordingExplainer {
+            calls: calls.clone(),
+        }));
+        disabled.log_query(slow_entry("SELECT * FROM users WHERE id = 1", 2000));
+        tokio::task::yield_now().await;
+        assert_eq!(calls.read().len(), 1);
+    }
 }
@@ -3,7 +3,12 @@
 //! Generates service worker scripts and manages offline caching strategies.
 
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::static_files::AssetManifest;
+
+/// Matches asset URLs carrying the content hash added by `StaticFileServer`
+pub const HASHED_ASSET_PATTERN: &str = "\\.[0-9a-f]{8}\\.[A-Za-z0-9]+$";
 
 /// Service worker configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +29,20 @@ pub struct ServiceWorkerConfig {
     pub skip_waiting: bool,
     /// Clients claim immediately
     pub clients_claim: bool,
+    /// Path prefixes the service worker never caches (admin, auth)
+    #[serde(default = "default_no_cache_paths")]
+    pub no_cache_paths: Vec<String>,
+}
+
+fn default_no_cache_paths() -> Vec<String> {
+    vec![
+        "/admin".to_string(),
+        "/wp-admin".to_string(),
+        "/api/auth".to_string(),
+        "/wp-login.php".to_string(),
+        "/login".to_string(),
+        "/logout".to_string(),
+    ]
 }
 
 impl Default for ServiceWorkerConfig {
@@ -35,6 +54,7 @@ impl Default for ServiceWorkerConfig {
             runtime_caching: vec![
                 RuntimeCachingRule {
                     url_pattern: "^/api/".to_string(),
+                    navigate: false,
                     strategy: CachingStrategy::NetworkFirst,
                     options: CacheOptions {
                         cache_name: Some("api-cache".to_string()),
@@ -45,6 +65,7 @@ impl Default for ServiceWorkerConfig {
                 },
                 RuntimeCachingRule {
                     url_pattern: "\\.(js|css)$".to_string(),
+                    navigate: false,
                     strategy: CachingStrategy::StaleWhileRevalidate,
                     options: CacheOptions {
                         cache_name: Some("static-cache".to_string()),
@@ -55,6 +76,7 @@ impl Default for ServiceWorkerConfig {
                 },
                 RuntimeCachingRule {
                     url_pattern: "\\.(png|jpg|jpeg|gif|webp|svg)$".to_string(),
+                    navigate: false,
                     strategy: CachingStrategy::CacheFirst,
                     options: CacheOptions {
                         cache_name: Some("image-cache".to_string()),
@@ -68,6 +90,7 @@ impl Default for ServiceWorkerConfig {
             navigation_preload: true,
             skip_waiting: true,
             clients_claim: true,
+            no_cache_paths: default_no_cache_paths(),
         }
     }
 }
@@ -77,6 +100,9 @@ impl Default for ServiceWorkerConfig {
 pub struct RuntimeCachingRule {
     /// URL pattern (regex)
     pub url_pattern: String,
+    /// Only match navigation (page) requests
+    #[serde(default)]
+    pub navigate: bool,
     /// Caching strategy
     pub strategy: CachingStrategy,
     /// Cache options
@@ -108,6 +134,17 @@ impl CachingStrategy {
             Self::CacheOnly => "CacheOnly",
         }
     }
+
+    /// Name of the strategy function in the generated worker
+    pub fn function_name(&self) -> &'static str {
+        match self {
+            Self::NetworkFirst => "networkFirst",
+            Self::CacheFirst => "cacheFirst",
+            Self::StaleWhileRevalidate => "staleWhileRevalidate",
+            Self::NetworkOnly => "networkOnly",
+            Self::CacheOnly => "cacheOnly",
+        }
+    }
 }
 
 /// Cache options
@@ -135,22 +172,25 @@ impl ServiceWorkerGenerator {
         Self { config }
     }
 
-    /// Generate the service worker JavaScript code
-    pub fn generate(&self) -> String {
+    /// Generate the service worker JavaScript code.
+    ///
+    /// Every asset in `manifest` is precached alongside the configured URLs,
+    /// and `strategies` are tried before the configured runtime caching
+    /// rules. The cache version includes a hash of the manifest, so a new
+    /// build produces a new worker and fresh caches.
+    pub fn generate(&self, manifest: &AssetManifest, strategies: &[RuntimeCachingRule]) -> String {
         let mut sw = String::new();
+        let version = self.version(manifest);
 
         // Header comment
-        sw.push_str(&format!(
-            "// RustPress Service Worker v{}\n",
-            self.config.version
-        ));
+        sw.push_str(&format!("// RustPress Service Worker v{}\n", version));
         sw.push_str("// Auto-generated - do not edit manually\n\n");
 
         // Cache names
-        sw.push_str(&self.generate_cache_names());
+        sw.push_str(&self.generate_cache_names(&version));
 
         // Precache manifest
-        sw.push_str(&self.generate_precache_manifest());
+        sw.push_str(&self.generate_precache_manifest(manifest));
 
         // Install event
         sw.push_str(&self.generate_install_handler());
@@ -159,7 +199,7 @@ impl ServiceWorkerGenerator {
         sw.push_str(&self.generate_activate_handler());
 
         // Fetch event
-        sw.push_str(&self.generate_fetch_handler());
+        sw.push_str(&self.generate_fetch_handler(strategies));
 
         // Strategy implementations
         sw.push_str(&self.generate_strategies());
@@ -170,7 +210,51 @@ impl ServiceWorkerGenerator {
         sw
     }
 
-    fn generate_cache_names(&self) -> String {
+    /// Configured version plus a digest of the manifest's hashed assets
+    pub fn version(&self, manifest: &AssetManifest) -> String {
+        let assets: BTreeSet<(&str, &str)> = manifest
+            .assets
+            .values()
+            .map(|asset| (asset.hashed_path.as_str(), asset.hash.as_str()))
+            .collect();
+
+        let mut hasher = blake3::Hasher::new();
+        for (url, hash) in assets {
+            hasher.update(url.as_bytes());
+            hasher.update(b"|");
+            hasher.update(hash.as_bytes());
+            hasher.update(b"\n");
+        }
+
+        format!(
+            "{}-{}",
+            self.config.version,
+            &hasher.finalize().to_hex()[..12]
+        )
+    }
+
+    /// URLs to precache: the configured app shell plus every manifest asset
+    pub fn precache_urls(&self, manifest: &AssetManifest) -> Vec<String> {
+        let urls: BTreeSet<&str> = self
+            .config
+            .precache_urls
+            .iter()
+            .map(String::as_str)
+            .chain(manifest.assets.values().map(|a| a.hashed_path.as_str()))
+            .filter(|url| !self.is_no_cache_path(url))
+            .collect();
+
+        urls.into_iter().map(str::to_string).collect()
+    }
+
+    fn is_no_cache_path(&self, url: &str) -> bool {
+        self.config
+            .no_cache_paths
+            .iter()
+            .any(|prefix| url.starts_with(prefix.as_str()))
+    }
+
+    fn generate_cache_names(&self, version: &str) -> String {
         format!(
             r#"
 const CACHE_VERSION = '{}';
@@ -179,16 +263,15 @@ const PRECACHE_NAME = `${{CACHE_PREFIX}}-precache-${{CACHE_VERSION}}`;
 const RUNTIME_CACHE_NAME = `${{CACHE_PREFIX}}-runtime-${{CACHE_VERSION}}`;
 
 "#,
-            self.config.version, self.config.cache_name_prefix
+            version, self.config.cache_name_prefix
         )
     }
 
-    fn generate_precache_manifest(&self) -> String {
+    fn generate_precache_manifest(&self, manifest: &AssetManifest) -> String {
         let urls_json: Vec<String> = self
-            .config
-            .precache_urls
+            .precache_urls(manifest)
             .iter()
-            .map(|u| format!("  '{}'", u))
+            .map(|u| format!("  {}", js_string(u)))
             .collect();
 
         format!(
@@ -197,8 +280,11 @@ const PRECACHE_URLS = [
 {}
 ];
 
+const NO_CACHE_PATHS = {};
+
 "#,
-            urls_json.join(",\n")
+            urls_json.join(",\n"),
+            serde_json::to_string(&self.config.no_cache_paths).unwrap_or_else(|_| "[]".into())
         )
     }
 
@@ -269,11 +355,11 @@ self.addEventListener('activate', (event) => {{
         )
     }
 
-    fn generate_fetch_handler(&self) -> String {
+    fn generate_fetch_handler(&self, strategies: &[RuntimeCachingRule]) -> String {
         let mut rules_code = String::new();
 
-        for rule in &self.config.runtime_caching {
-            let strategy = rule.strategy.as_str();
+        for rule in strategies.iter().chain(&self.config.runtime_caching) {
+            let strategy = rule.strategy.function_name();
             let cache_name = rule
                 .options
                 .cache_name
@@ -283,15 +369,23 @@ self.addEventListener('activate', (event) => {{
 
             let options = self.generate_cache_options(&rule.options);
 
+            let navigate = if rule.navigate {
+                "event.request.mode === 'navigate' && "
+            } else {
+                ""
+            };
+
+            // Patterns are passed as strings so slashes need no escaping
             rules_code.push_str(&format!(
                 r#"
-  if (/{pattern}/.test(url.pathname) || /{pattern}/.test(url.href)) {{
+  if ({navigate}matches({pattern}, url)) {{
     event.respondWith({strategy}(event.request, {cache}, {options}));
     return;
   }}
 "#,
-                pattern = rule.url_pattern,
-                strategy = strategy.to_lowercase(),
+                navigate = navigate,
+                pattern = js_string(&rule.url_pattern),
+                strategy = strategy,
                 cache = cache_name,
                 options = options
             ));
@@ -342,6 +436,11 @@ self.addEventListener('fetch', (event) => {{
   if (event.request.method !== 'GET') {{
     return;
   }}
+
+  // Never cache admin or authentication routes
+  if (NO_CACHE_PATHS.some((prefix) => url.pathname.startsWith(prefix))) {{
+    return;
+  }}
 {rules}
 {offline}
   // Default: Network first for navigation, cache first for others
@@ -463,6 +562,12 @@ async function cacheOnly(request, cacheName, options) {
 
     fn generate_utilities(&self) -> String {
         r#"
+// Test a runtime caching pattern against the path or full URL
+function matches(pattern, url) {
+  const regex = new RegExp(pattern);
+  return regex.test(url.pathname) || regex.test(url.href);
+}
+
 // Fetch with timeout
 function fetchWithTimeout(request, timeoutSeconds) {
   return new Promise((resolve, reject) => {
@@ -572,6 +677,39 @@ if ('serviceWorker' in navigator) {{
     }
 }
 
+/// Runtime strategies for a build served by `StaticFileServer`: cache-first
+/// for content-hashed assets, which never change, and stale-while-revalidate
+/// for pages
+pub fn default_runtime_strategies() -> Vec<RuntimeCachingRule> {
+    vec![
+        RuntimeCachingRule {
+            url_pattern: HASHED_ASSET_PATTERN.to_string(),
+            navigate: false,
+            strategy: CachingStrategy::CacheFirst,
+            options: CacheOptions {
+                cache_name: Some("hashed-assets".to_string()),
+                max_entries: Some(200),
+                ..Default::default()
+            },
+        },
+        RuntimeCachingRule {
+            url_pattern: "^/".to_string(),
+            navigate: true,
+            strategy: CachingStrategy::StaleWhileRevalidate,
+            options: CacheOptions {
+                cache_name: Some("pages".to_string()),
+                max_entries: Some(50),
+                ..Default::default()
+            },
+        },
+    ]
+}
+
+/// Quote a string as a JavaScript string literal
+fn js_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "''".to_string())
+}
+
 /// Workbox-compatible manifest entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkboxManifestEntry {
@@ -625,7 +763,7 @@ mod tests {
     fn test_service_worker_generation() {
         let config = ServiceWorkerConfig::default();
         let generator = ServiceWorkerGenerator::new(config);
-        let sw = generator.generate();
+        let sw = generator.generate(&AssetManifest::new(), &[]);
 
         assert!(sw.contains("self.addEventListener('install'"));
         assert!(sw.contains("self.addEventListener('activate'"));
@@ -651,4 +789,66 @@ mod tests {
             "StaleWhileRevalidate"
         );
     }
+
+    fn manifest(assets: &[(&str, &str)]) -> AssetManifest {
+        let mut manifest = AssetManifest::new();
+        for (path, hash) in assets {
+            let (stem, ext) = path.rsplit_once('.').unwrap();
+            manifest.assets.insert(
+                path.to_string(),
+                crate::static_files::HashedAsset {
+                    hashed_path: format!("{}.{}.{}", stem, hash, ext),
+                    hash: hash.to_string(),
+                    size: 0,
+                    gzip_size: None,
+                    brotli_size: None,
+                    mime_type: String::new(),
+                    integrity: String::new(),
+                },
+            );
+        }
+        manifest
+    }
+
+    #[test]
+    fn test_precache_from_manifest() {
+        let generator = ServiceWorkerGenerator::new(ServiceWorkerConfig::default());
+        let build = manifest(&[
+            ("/static/app.js", "0123abcd"),
+            ("/admin/panel.css", "89abcdef"),
+        ]);
+
+        let urls = generator.precache_urls(&build);
+        assert!(urls.contains(&"/static/app.0123abcd.js".to_string()));
+        assert!(urls.contains(&"/offline".to_string()));
+        // Admin assets are never cached
+        assert!(!urls.iter().any(|u| u.starts_with("/admin")));
+
+        let sw = generator.generate(&build, &default_runtime_strategies());
+        assert!(sw.contains("\"/static/app.0123abcd.js\""));
+        assert!(sw.contains("NO_CACHE_PATHS.some("));
+        assert!(sw.contains("cacheFirst(event.request, 'hashed-assets'"));
+        assert!(sw.contains("event.request.mode === 'navigate' && matches(\"^/\", url)"));
+    }
+
+    #[test]
+    fn test_version_tracks_manifest() {
+        let generator = ServiceWorkerGenerator::new(ServiceWorkerConfig::default());
+        let v1 = generator.version(&manifest(&[("/static/app.js", "0123abcd")]));
+        let v2 = generator.version(&manifest(&[("/static/app.js", "4567cdef")]));
+
+        assert!(v1.starts_with("1.0.0-"));
+        assert_ne!(v1, v2);
+        assert_eq!(
+            v1,
+            generator.version(&manifest(&[("/static/app.js", "0123abcd")]))
+        );
+    }
+
+    #[test]
+    fn test_hashed_asset_pattern() {
+        let pattern = regex::Regex::new(HASHED_ASSET_PATTERN).unwrap();
+        assert!(pattern.is_match("/static/js/app.0123abcd.js"));
+        assert!(!pattern.is_match("/static/js/app.js"));
+    }
 }
@@ -2,9 +2,20 @@
 //!
 //! Optimizes SSR performance with streaming, caching, and hydration strategies.
 
+use axum::body::Body;
+use axum::http::{header, HeaderValue};
+use axum::response::Response;
+use bytes::Bytes;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures::future::BoxFuture;
+use futures::stream::{self, FuturesUnordered, Stream, StreamExt};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::Future;
+use std::io::Write;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use thiserror::Error;
@@ -340,30 +351,7 @@ impl StreamingRenderer {
 
     /// Resolve a suspense boundary
     pub fn resolve_suspense(&self, id: &str, content: &str, state: Option<&str>) {
-        let state_script = state
-            .map(|s| {
-                format!(
-                    r#"<script>window.__COMPONENT_STATE__["{}"] = {};</script>"#,
-                    id, s
-                )
-            })
-            .unwrap_or_default();
-
-        let chunk = format!(
-            r#"<template id="{}$">{}</template>{}
-<script>
-(function() {{
-  var t = document.getElementById("{}$");
-  var b = document.getElementById("{}");
-  b.innerHTML = t.content.textContent;
-  b.dataset.suspense = "resolved";
-  t.remove();
-}})();
-</script>"#,
-            id, content, state_script, id, id
-        );
-
-        self.push_chunk(&chunk);
+        self.push_chunk(&resolution_chunk(id, content, state, "resolved"));
     }
 
     /// Complete the stream
@@ -386,6 +374,199 @@ impl StreamingRenderer {
     pub fn is_complete(&self) -> bool {
         *self.completed.read()
     }
+
+    /// Stream a page: the shell and boundary placeholders are sent at once,
+    /// each boundary follows as soon as it resolves (in completion order),
+    /// and the closing HTML ends the stream.
+    ///
+    /// A boundary that fails or exceeds the render timeout is replaced by
+    /// its fallback; the rest of the page keeps streaming.
+    pub fn render_stream(&self, page: StreamingPage) -> impl Stream<Item = Bytes> + Send + 'static {
+        let timeout = Duration::from_millis(self.config.timeout_ms);
+
+        let mut head = page.shell;
+        for boundary in &page.boundaries {
+            head.push_str(&format!(
+                r#"<div id="{}" data-suspense="pending">{}</div>"#,
+                boundary.id, boundary.fallback
+            ));
+        }
+
+        let resolved: FuturesUnordered<_> = page
+            .boundaries
+            .into_iter()
+            .map(|boundary| boundary.resolve(timeout))
+            .collect();
+
+        let body = stream::once(async move { Bytes::from(head) }).chain(resolved);
+        if !self.config.streaming {
+            // Buffer the whole document into a single chunk
+            let buffered = body
+                .chain(stream::once(async move { Bytes::from(page.closing) }))
+                .collect::<Vec<_>>();
+            return stream::once(async move { Bytes::from(buffered.await.concat()) }).boxed();
+        }
+
+        body.chain(stream::once(async move { Bytes::from(page.closing) }))
+            .boxed()
+    }
+
+    /// Build an HTML response streaming `page`.
+    ///
+    /// When the client accepts gzip the body is compressed here, with a sync
+    /// flush after every chunk, and marked with `Content-Encoding` so
+    /// compression middleware leaves it alone rather than buffering it.
+    pub fn stream_response(&self, page: StreamingPage, accept_encoding: Option<&str>) -> Response {
+        let rendered = self.render_stream(page);
+        let gzip = accept_encoding.is_some_and(accepts_gzip);
+
+        let body = if gzip {
+            Body::from_stream(gzip_stream(rendered).map(Ok::<_, Infallible>))
+        } else {
+            Body::from_stream(rendered.map(Ok::<_, Infallible>))
+        };
+
+        let mut response = Response::new(body);
+        let headers = response.headers_mut();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/html; charset=utf-8"),
+        );
+        // Stop reverse proxies (nginx) from buffering the stream
+        headers.insert("x-accel-buffering", HeaderValue::from_static("no"));
+        if gzip {
+            headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+            headers.insert(header::VARY, HeaderValue::from_static("accept-encoding"));
+        }
+        response
+    }
+}
+
+/// Page rendered by [`StreamingRenderer::render_stream`]
+pub struct StreamingPage {
+    /// Document head and above-the-fold shell, flushed immediately
+    pub shell: String,
+    /// Deferred regions, placed after the shell
+    pub boundaries: Vec<SuspenseBoundary>,
+    /// Closing HTML, sent once every boundary has settled
+    pub closing: String,
+}
+
+impl StreamingPage {
+    pub fn new(shell: impl Into<String>, closing: impl Into<String>) -> Self {
+        Self {
+            shell: shell.into(),
+            boundaries: Vec::new(),
+            closing: closing.into(),
+        }
+    }
+
+    /// Add a boundary showing `fallback` until `content` resolves
+    pub fn with_boundary(
+        mut self,
+        id: impl Into<String>,
+        fallback: impl Into<String>,
+        content: impl Future<Output = Result<String, SsrError>> + Send + 'static,
+    ) -> Self {
+        self.boundaries.push(SuspenseBoundary {
+            id: id.into(),
+            fallback: fallback.into(),
+            content: Box::pin(content),
+        });
+        self
+    }
+}
+
+/// Deferred region of a streamed page
+pub struct SuspenseBoundary {
+    /// Element ID of the placeholder
+    pub id: String,
+    /// HTML shown while pending, and kept if rendering fails
+    pub fallback: String,
+    /// Rendered content
+    pub content: BoxFuture<'static, Result<String, SsrError>>,
+}
+
+impl SuspenseBoundary {
+    async fn resolve(self, timeout: Duration) -> Bytes {
+        let result = match tokio::time::timeout(timeout, self.content).await {
+            Ok(result) => result,
+            Err(_) => Err(SsrError::Timeout),
+        };
+
+        let chunk = match result {
+            Ok(html) => resolution_chunk(&self.id, &html, None, "resolved"),
+            Err(e) => {
+                tracing::warn!(boundary = %self.id, error = %e, "Suspense boundary failed");
+                resolution_chunk(&self.id, &self.fallback, None, "error")
+            }
+        };
+        Bytes::from(chunk)
+    }
+}
+
+/// Template and script swapping a boundary placeholder for its content
+fn resolution_chunk(id: &str, content: &str, state: Option<&str>, status: &str) -> String {
+    let state_script = state
+        .map(|s| {
+            format!(
+                r#"<script>window.__COMPONENT_STATE__["{}"] = {};</script>"#,
+                id, s
+            )
+        })
+        .unwrap_or_default();
+
+    format!(
+        r#"<template id="{id}$">{content}</template>{state_script}
+<script>
+(function() {{
+  var t = document.getElementById("{id}$");
+  var b = document.getElementById("{id}");
+  b.replaceChildren(t.content);
+  b.dataset.suspense = "{status}";
+  t.remove();
+}})();
+</script>"#
+    )
+}
+
+/// Check whether an `Accept-Encoding` value allows gzip
+fn accepts_gzip(accept_encoding: &str) -> bool {
+    accept_encoding.split(',').any(|part| {
+        let mut params = part.split(';').map(str::trim);
+        let coding = params.next().unwrap_or_default();
+        let rejected = params.any(|p| {
+            p.strip_prefix("q=")
+                .and_then(|q| q.parse::<f32>().ok())
+                .is_some_and(|q| q == 0.0)
+        });
+        (coding.eq_ignore_ascii_case("gzip") || coding == "*") && !rejected
+    })
+}
+
+/// Gzip a byte stream, sync-flushing after every chunk so each one reaches
+/// the client as soon as it is produced
+pub fn gzip_stream(
+    input: impl Stream<Item = Bytes> + Send + 'static,
+) -> impl Stream<Item = Bytes> + Send + 'static {
+    let encoder = GzEncoder::new(Vec::new(), Compression::default());
+
+    stream::unfold(Some((input.boxed(), encoder)), |state| async move {
+        let (mut input, mut encoder) = state?;
+        match input.next().await {
+            Some(chunk) => {
+                // Writing to a Vec cannot fail
+                let _ = encoder.write_all(&chunk);
+                let _ = encoder.flush();
+                let compressed = std::mem::take(encoder.get_mut());
+                Some((Bytes::from(compressed), Some((input, encoder))))
+            }
+            None => {
+                let trailer = encoder.finish().unwrap_or_default();
+                Some((Bytes::from(trailer), None))
+            }
+        }
+    })
 }
 
 /// Hydration script generator
@@ -771,4 +952,84 @@ mod tests {
         assert!(html.contains("data-island=\"Counter\""));
         assert!(html.contains("data-hydrate=\"visible\""));
     }
+
+    #[tokio::test]
+    async fn test_render_stream_order_and_fallback() {
+        let renderer = StreamingRenderer::new(SsrConfig::default());
+        let page = StreamingPage::new("<html><head></head><body><h1>Hi</h1>", "</body></html>")
+            .with_boundary("slow", "<p>Loading</p>", async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Ok("<p>Slow</p>".to_string())
+            })
+            .with_boundary("broken", "<p>Unavailable</p>", async {
+                Err(SsrError::RenderError("widget failed".into()))
+            })
+            .with_boundary("fast", "<p>Loading</p>", async {
+                Ok("<p>Fast</p>".to_string())
+            });
+
+        let chunks: Vec<String> = renderer
+            .render_stream(page)
+            .map(|b| String::from_utf8(b.to_vec()).unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(chunks.len(), 5);
+        assert!(chunks[0].starts_with("<html><head></head><body><h1>Hi</h1>"));
+        assert!(
+            chunks[0].contains(r#"<div id="slow" data-suspense="pending"><p>Loading</p></div>"#)
+        );
+        // The failed boundary falls back without ending the stream
+        assert!(chunks[1..4]
+            .iter()
+            .any(|c| c.contains("<p>Unavailable</p>")
+                && c.contains(r#"b.dataset.suspense = "error""#)));
+        // Boundaries stream in completion order
+        assert!(chunks[3].contains("<p>Slow</p>"));
+        assert_eq!(chunks[4], "</body></html>");
+    }
+
+    #[tokio::test]
+    async fn test_render_stream_timeout() {
+        let config = SsrConfig {
+            timeout_ms: 10,
+            ..SsrConfig::default()
+        };
+        let renderer = StreamingRenderer::new(config);
+        let page = StreamingPage::new("<body>", "</body>").with_boundary(
+            "stuck",
+            "<p>Later</p>",
+            std::future::pending(),
+        );
+
+        let chunks: Vec<Bytes> = renderer.render_stream(page).collect().await;
+        assert_eq!(chunks.len(), 3);
+        assert!(String::from_utf8_lossy(&chunks[1]).contains("<p>Later</p>"));
+    }
+
+    #[tokio::test]
+    async fn test_gzip_stream_flushes_each_chunk() {
+        use std::io::Read;
+
+        let input = stream::iter(vec![Bytes::from("<html>"), Bytes::from("</html>")]);
+        let chunks: Vec<Bytes> = gzip_stream(input).collect().await;
+
+        // Every rendered chunk produces output immediately, then the trailer
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[..2].iter().all(|c| !c.is_empty()));
+
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(&chunks.concat()[..])
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, "<html></html>");
+    }
+
+    #[test]
+    fn test_accepts_gzip() {
+        assert!(accepts_gzip("gzip, deflate, br"));
+        assert!(accepts_gzip("br;q=1.0, GZIP;q=0.5"));
+        assert!(!accepts_gzip("gzip;q=0, br"));
+        assert!(!accepts_gzip("br"));
+    }
 }
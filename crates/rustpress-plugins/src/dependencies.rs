@@ -286,6 +286,29 @@ impl DependencyResolver {
         dependents
     }
 
+    /// Find a dependency cycle among the available plugins, returning a
+    /// plugin that participates in it
+    pub fn find_cycle(&self) -> Option<String> {
+        let mut graph: DiGraph<&str, ()> = DiGraph::new();
+        let nodes: HashMap<&str, NodeIndex> = self
+            .available
+            .keys()
+            .map(|id| (id.as_str(), graph.add_node(id.as_str())))
+            .collect();
+
+        for (plugin_id, manifest) in &self.available {
+            for dep_id in manifest.dependencies.plugins.keys() {
+                if let Some(&dep_node) = nodes.get(dep_id.as_str()) {
+                    graph.add_edge(nodes[plugin_id.as_str()], dep_node, ());
+                }
+            }
+        }
+
+        toposort(&graph, None)
+            .err()
+            .and_then(|cycle| graph.node_weight(cycle.node_id()).map(|id| id.to_string()))
+    }
+
     /// Validate all available plugins have satisfiable dependencies
     pub fn validate_all(&self) -> Vec<ValidationIssue> {
         let mut issues = Vec::new();
@@ -656,6 +679,7 @@ mod tests {
 
         let result = resolver.resolve(&["plugin-b".to_string()]).unwrap();
         assert_eq!(result.load_order, vec!["plugin-a", "plugin-b"]);
+        assert!(resolver.find_cycle().is_none());
     }
 
     #[test]
@@ -680,6 +704,7 @@ mod tests {
 
         let result = resolver.resolve(&["plugin-a".to_string()]);
         assert!(matches!(result, Err(ResolutionError::CyclicDependency(_))));
+        assert!(resolver.find_cycle().is_some());
     }
 
     #[test]
@@ -0,0 +1,682 @@
+//! Plugin Health Diagnostics
+//!
+//! Combines dependency resolution, conflict detection, RustPress version
+//! compatibility and update checking into a single report with suggested
+//! remediation. Everything except the update check works from the plugin
+//! manifests alone, so it can run without network access.
+
+use crate::dependencies::{DependencyResolver, IssueSeverity, IssueType};
+use crate::manifest::{ManifestError, PluginManifest};
+use crate::updates::{
+    ConflictDetector, ConflictSeverity, ConflictType, KnownConflict, UpdateChecker,
+};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Area a finding belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingCategory {
+    Manifest,
+    Dependency,
+    Conflict,
+    Compatibility,
+    Update,
+}
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingSeverity {
+    Warning,
+    Error,
+}
+
+/// A single problem found by the doctor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    pub plugin: String,
+    pub category: FindingCategory,
+    pub severity: FindingSeverity,
+    pub message: String,
+    pub remediation: String,
+}
+
+impl Finding {
+    fn new(
+        plugin: impl Into<String>,
+        category: FindingCategory,
+        severity: FindingSeverity,
+        message: impl Into<String>,
+        remediation: impl Into<String>,
+    ) -> Self {
+        Self {
+            plugin: plugin.into(),
+            category,
+            severity,
+            message: message.into(),
+            remediation: remediation.into(),
+        }
+    }
+}
+
+/// Outcome of the (network-bound) update check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "error", rename_all = "snake_case")]
+pub enum UpdateCheckStatus {
+    /// Not attempted (offline mode)
+    Skipped,
+    /// Completed; available updates are reported as findings
+    Completed,
+    /// Attempted but the update server could not be queried
+    Failed(String),
+}
+
+/// Full diagnostic report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorReport {
+    /// RustPress version the plugins were checked against
+    pub rustpress_version: String,
+    /// IDs of the plugins that were examined
+    pub plugins: Vec<String>,
+    /// Problems found, most severe first
+    pub findings: Vec<Finding>,
+    /// Whether update information is included
+    pub update_check: UpdateCheckStatus,
+}
+
+impl DoctorReport {
+    /// Whether any finding is an error
+    pub fn has_errors(&self) -> bool {
+        self.findings
+            .iter()
+            .any(|f| f.severity == FindingSeverity::Error)
+    }
+
+    /// Number of findings at the given severity
+    pub fn count(&self, severity: FindingSeverity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.severity == severity)
+            .count()
+    }
+
+    fn sort(&mut self) {
+        self.findings.sort_by(|a, b| {
+            b.severity
+                .cmp(&a.severity)
+                .then_with(|| a.plugin.cmp(&b.plugin))
+        });
+    }
+}
+
+/// Plugin doctor
+pub struct PluginDoctor {
+    rustpress_version: String,
+    known_conflicts: Vec<KnownConflict>,
+}
+
+impl PluginDoctor {
+    /// Create a doctor checking plugins against the given RustPress version
+    pub fn new(rustpress_version: impl Into<String>) -> Self {
+        Self {
+            rustpress_version: rustpress_version.into(),
+            known_conflicts: Vec::new(),
+        }
+    }
+
+    /// Add a conflict known from outside the manifests
+    pub fn with_known_conflict(mut self, conflict: KnownConflict) -> Self {
+        self.known_conflicts.push(conflict);
+        self
+    }
+
+    /// Load every `plugin.toml` directly below `dir`.
+    ///
+    /// Manifests that cannot be read, parsed or validated are returned as
+    /// findings instead of being skipped silently.
+    pub fn load_manifests(dir: &Path) -> (Vec<PluginManifest>, Vec<Finding>) {
+        let mut manifests = Vec::new();
+        let mut findings = Vec::new();
+
+        let mut paths: Vec<PathBuf> = match std::fs::read_dir(dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path().join("plugin.toml"))
+                .filter(|path| path.is_file())
+                .collect(),
+            Err(e) => {
+                findings.push(Finding::new(
+                    dir.display().to_string(),
+                    FindingCategory::Manifest,
+                    FindingSeverity::Error,
+                    format!("Cannot read plugins directory: {}", e),
+                    "Point the doctor at the directory containing your plugins",
+                ));
+                return (manifests, findings);
+            }
+        };
+        paths.sort();
+
+        for path in paths {
+            let name = path
+                .parent()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            let manifest = match PluginManifest::from_file(&path) {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    let detail = match e {
+                        ManifestError::Io(e) | ManifestError::Parse(e) => e,
+                        ManifestError::Validation(_) => e.to_string(),
+                    };
+                    findings.push(Finding::new(
+                        name,
+                        FindingCategory::Manifest,
+                        FindingSeverity::Error,
+                        format!("Invalid manifest: {}", summarize_error(&detail)),
+                        format!("Fix or remove {}", path.display()),
+                    ));
+                    continue;
+                }
+            };
+
+            if let Err(errors) = manifest.validate() {
+                for error in errors {
+                    findings.push(Finding::new(
+                        &manifest.plugin.id,
+                        FindingCategory::Manifest,
+                        FindingSeverity::Error,
+                        format!("{}: {}", error.field, error.message),
+                        format!("Correct `{}` in {}", error.field, path.display()),
+                    ));
+                }
+            }
+
+            manifests.push(manifest);
+        }
+
+        (manifests, findings)
+    }
+
+    /// Load the plugins installed in `dir` and run all offline checks.
+    ///
+    /// The loaded manifests are returned for a follow-up [`Self::check_updates`].
+    pub fn diagnose_dir(&self, dir: &Path) -> (DoctorReport, Vec<PluginManifest>) {
+        let (manifests, load_findings) = Self::load_manifests(dir);
+        let mut report = self.diagnose(&manifests);
+        report.findings.extend(load_findings);
+        report.sort();
+        (report, manifests)
+    }
+
+    /// Run all offline checks
+    pub fn diagnose(&self, manifests: &[PluginManifest]) -> DoctorReport {
+        let mut findings = Vec::new();
+
+        findings.extend(duplicate_ids(manifests));
+
+        let mut resolver = DependencyResolver::new();
+        resolver.add_available(manifests.iter().cloned());
+        findings.extend(self.dependency_findings(&resolver));
+        findings.extend(self.conflict_findings(manifests));
+        findings.extend(self.compatibility_findings(manifests));
+
+        let mut report = DoctorReport {
+            rustpress_version: self.rustpress_version.clone(),
+            plugins: manifests.iter().map(|m| m.plugin.id.clone()).collect(),
+            findings,
+            update_check: UpdateCheckStatus::Skipped,
+        };
+        report.sort();
+        report
+    }
+
+    /// Query the update server and add available updates to the report
+    pub async fn check_updates(
+        &self,
+        report: &mut DoctorReport,
+        checker: &UpdateChecker,
+        manifests: &[PluginManifest],
+    ) {
+        let installed: Vec<(String, String)> = manifests
+            .iter()
+            .map(|m| (m.plugin.id.clone(), m.plugin.version.clone()))
+            .collect();
+
+        match checker.check_all(&installed).await {
+            Ok(updates) => {
+                for update in updates {
+                    let mut remediation =
+                        format!("Update `{}` to {}", update.plugin_id, update.latest_version);
+                    if let Some(required) = &update.requires_rustpress {
+                        if !self.satisfies_min(required) {
+                            remediation.push_str(&format!(
+                                " after upgrading RustPress to {} or later",
+                                required
+                            ));
+                        }
+                    }
+
+                    report.findings.push(Finding::new(
+                        &update.plugin_id,
+                        FindingCategory::Update,
+                        if update.is_security_update {
+                            FindingSeverity::Error
+                        } else {
+                            FindingSeverity::Warning
+                        },
+                        format!(
+                            "{}update available: {} -> {}",
+                            if update.is_security_update {
+                                "Security "
+                            } else {
+                                ""
+                            },
+                            update.current_version,
+                            update.latest_version
+                        ),
+                        remediation,
+                    ));
+                }
+                report.update_check = UpdateCheckStatus::Completed;
+            }
+            Err(e) => report.update_check = UpdateCheckStatus::Failed(e.to_string()),
+        }
+
+        report.sort();
+    }
+
+    fn dependency_findings(&self, resolver: &DependencyResolver) -> Vec<Finding> {
+        let mut findings: Vec<Finding> = resolver
+            .validate_all()
+            .into_iter()
+            .map(|issue| {
+                let severity = match issue.severity {
+                    IssueSeverity::Error => FindingSeverity::Error,
+                    IssueSeverity::Warning => FindingSeverity::Warning,
+                };
+                match issue.issue_type {
+                    IssueType::MissingDependency(dependency) => Finding::new(
+                        &issue.plugin,
+                        FindingCategory::Dependency,
+                        severity,
+                        format!("Requires `{}`, which is not installed", dependency),
+                        format!("Install `{}` or deactivate `{}`", dependency, issue.plugin),
+                    ),
+                    IssueType::VersionMismatch {
+                        dependency,
+                        required,
+                        available,
+                    } => Finding::new(
+                        &issue.plugin,
+                        FindingCategory::Dependency,
+                        severity,
+                        format!(
+                            "Requires `{}` {}, but {} is installed",
+                            dependency, required, available
+                        ),
+                        format!(
+                            "Install a release of `{}` matching {}",
+                            dependency, required
+                        ),
+                    ),
+                }
+            })
+            .collect();
+
+        if let Some(plugin) = resolver.find_cycle() {
+            findings.push(Finding::new(
+                &plugin,
+                FindingCategory::Dependency,
+                FindingSeverity::Error,
+                "Part of a circular dependency chain",
+                "Remove one of the dependencies in the cycle so plugins can be loaded in order",
+            ));
+        }
+
+        findings
+    }
+
+    fn conflict_findings(&self, manifests: &[PluginManifest]) -> Vec<Finding> {
+        let mut detector = ConflictDetector::new();
+        for conflict in &self.known_conflicts {
+            detector.add_known_conflict(conflict.clone());
+        }
+
+        // Conflicts declared in manifests, once per unordered pair
+        let mut declared = HashSet::new();
+        for manifest in manifests {
+            for other in &manifest.dependencies.conflicts {
+                let mut pair = [manifest.plugin.id.clone(), other.clone()];
+                pair.sort();
+                if declared.insert(pair.clone()) {
+                    let [plugin_a, plugin_b] = pair;
+                    detector.add_known_conflict(KnownConflict {
+                        plugin_a,
+                        plugin_b,
+                        conflict_type: ConflictType::Incompatible,
+                        description: format!(
+                            "`{}` declares a conflict with `{}`",
+                            manifest.plugin.id, other
+                        ),
+                        resolution: None,
+                    });
+                }
+            }
+        }
+
+        let installed: Vec<String> = manifests.iter().map(|m| m.plugin.id.clone()).collect();
+        let routes: Vec<(String, String)> = manifests
+            .iter()
+            .flat_map(|m| {
+                let prefix = format!("/{}/{}", m.api_namespace(), m.api.version);
+                m.api.endpoints.iter().map(move |endpoint| {
+                    (
+                        m.plugin.id.clone(),
+                        format!(
+                            "{} {}{}",
+                            format!("{:?}", endpoint.method).to_uppercase(),
+                            prefix,
+                            endpoint.path
+                        ),
+                    )
+                })
+            })
+            .collect();
+
+        let resolutions: HashMap<(&str, &str), &str> = self
+            .known_conflicts
+            .iter()
+            .filter_map(|c| {
+                c.resolution
+                    .as_deref()
+                    .map(|r| ((c.plugin_a.as_str(), c.plugin_b.as_str()), r))
+            })
+            .collect();
+
+        detector
+            .check(&installed)
+            .into_iter()
+            .chain(detector.check_routes(&routes))
+            .map(|conflict| {
+                let remediation = match conflict.conflict_type {
+                    ConflictType::Resource => format!(
+                        "Change the conflicting endpoint in `{}` or `{}`",
+                        conflict.plugin_a, conflict.plugin_b
+                    ),
+                    _ => resolutions
+                        .get(&(conflict.plugin_a.as_str(), conflict.plugin_b.as_str()))
+                        .map(|r| r.to_string())
+                        .unwrap_or_else(|| {
+                            format!(
+                                "Deactivate or uninstall either `{}` or `{}`",
+                                conflict.plugin_a, conflict.plugin_b
+                            )
+                        }),
+                };
+                let message = match &conflict.resource {
+                    Some(resource) => format!(
+                        "{} (with `{}`, {})",
+                        conflict.description, conflict.plugin_b, resource
+                    ),
+                    None => conflict.description.clone(),
+                };
+
+                Finding::new(
+                    &conflict.plugin_a,
+                    FindingCategory::Conflict,
+                    match conflict.severity {
+                        ConflictSeverity::High | ConflictSeverity::Critical => {
+                            FindingSeverity::Error
+                        }
+                        ConflictSeverity::Low | ConflictSeverity::Medium => {
+                            FindingSeverity::Warning
+                        }
+                    },
+                    message,
+                    remediation,
+                )
+            })
+            .collect()
+    }
+
+    fn compatibility_findings(&self, manifests: &[PluginManifest]) -> Vec<Finding> {
+        let Some(current) = parse_version(&self.rustpress_version) else {
+            return Vec::new();
+        };
+        let mut findings = Vec::new();
+
+        for manifest in manifests {
+            let id = &manifest.plugin.id;
+            let bounds = [
+                (
+                    "min_rustpress_version",
+                    &manifest.plugin.min_rustpress_version,
+                ),
+                (
+                    "max_rustpress_version",
+                    &manifest.plugin.max_rustpress_version,
+                ),
+            ];
+
+            for (field, bound) in bounds {
+                let Some(bound) = bound else { continue };
+                let Some(version) = parse_version(bound) else {
+                    findings.push(Finding::new(
+                        id,
+                        FindingCategory::Manifest,
+                        FindingSeverity::Warning,
+                        format!("`{}` is not a valid version: {}", field, bound),
+                        format!("Use a semver version for `{}` in plugin.toml", field),
+                    ));
+                    continue;
+                };
+
+                if field == "min_rustpress_version" && current < version {
+                    findings.push(Finding::new(
+                        id,
+                        FindingCategory::Compatibility,
+                        FindingSeverity::Error,
+                        format!(
+                            "Requires RustPress {} or later, running {}",
+                            bound, self.rustpress_version
+                        ),
+                        format!(
+                            "Upgrade RustPress to {} or install an older release of `{}`",
+                            bound, id
+                        ),
+                    ));
+                } else if field == "max_rustpress_version" && current > version {
+                    findings.push(Finding::new(
+                        id,
+                        FindingCategory::Compatibility,
+                        FindingSeverity::Error,
+                        format!(
+                            "Supports RustPress up to {}, running {}",
+                            bound, self.rustpress_version
+                        ),
+                        format!(
+                            "Update `{}` to a release supporting RustPress {}",
+                            id, self.rustpress_version
+                        ),
+                    ));
+                }
+            }
+        }
+
+        findings
+    }
+
+    fn satisfies_min(&self, required: &str) -> bool {
+        match (
+            parse_version(&self.rustpress_version),
+            parse_version(required),
+        ) {
+            (Some(current), Some(required)) => current >= required,
+            _ => true,
+        }
+    }
+}
+
+/// Plugins installed more than once under the same ID
+fn duplicate_ids(manifests: &[PluginManifest]) -> Vec<Finding> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for manifest in manifests {
+        *counts.entry(manifest.plugin.id.as_str()).or_default() += 1;
+    }
+
+    counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(id, count)| {
+            Finding::new(
+                id,
+                FindingCategory::Manifest,
+                FindingSeverity::Error,
+                format!("Installed {} times under the same ID", count),
+                "Remove the duplicate plugin directories",
+            )
+        })
+        .collect()
+}
+
+/// Collapse a multi-line TOML error (location, source excerpt, reason)
+/// into a single line
+fn summarize_error(detail: &str) -> String {
+    let lines: Vec<&str> = detail
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    match lines.as_slice() {
+        [] => String::new(),
+        [only] => only.to_string(),
+        [first, .., last] => format!("{}: {}", first, last),
+    }
+}
+
+/// Parse a version, accepting shorthand like `1` or `1.2`
+fn parse_version(version: &str) -> Option<Version> {
+    let version = version.trim().trim_start_matches('v');
+    Version::parse(version).ok().or_else(|| {
+        let parts = version.split('.').count();
+        let padded = match parts {
+            1 => format!("{}.0.0", version),
+            2 => format!("{}.0", version),
+            _ => return None,
+        };
+        Version::parse(&padded).ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(toml: &str) -> PluginManifest {
+        PluginManifest::from_toml(toml).unwrap()
+    }
+
+    fn plugin(id: &str, version: &str, extra: &str) -> PluginManifest {
+        manifest(&format!(
+            "[plugin]\nid = \"{}\"\nname = \"{}\"\nversion = \"{}\"\n{}",
+            id, id, version, extra
+        ))
+    }
+
+    #[test]
+    fn test_healthy_plugins() {
+        let report = PluginDoctor::new("1.2.0").diagnose(&[
+            plugin("base", "1.0.0", ""),
+            plugin(
+                "addon",
+                "1.0.0",
+                "min_rustpress_version = \"1.0\"\n[dependencies.plugins]\nbase = \"^1.0\"",
+            ),
+        ]);
+
+        assert!(report.findings.is_empty());
+        assert!(!report.has_errors());
+        assert_eq!(report.update_check, UpdateCheckStatus::Skipped);
+    }
+
+    #[test]
+    fn test_dependency_and_compatibility_problems() {
+        let report = PluginDoctor::new("1.2.0").diagnose(&[
+            plugin("base", "2.0.0", "max_rustpress_version = \"1.1.0\""),
+            plugin(
+                "addon",
+                "1.0.0",
+                "min_rustpress_version = \"2.0.0\"\n[dependencies.plugins]\nbase = \"^1.0\"\nmissing = \"^1.0\"",
+            ),
+        ]);
+
+        let categories: Vec<_> = report
+            .findings
+            .iter()
+            .map(|f| (f.plugin.as_str(), f.category))
+            .collect();
+        assert!(categories.contains(&("addon", FindingCategory::Dependency)));
+        assert!(categories.contains(&("addon", FindingCategory::Compatibility)));
+        assert!(categories.contains(&("base", FindingCategory::Compatibility)));
+        assert_eq!(report.count(FindingSeverity::Error), 4);
+        assert!(report.has_errors());
+    }
+
+    #[test]
+    fn test_declared_and_route_conflicts() {
+        let endpoint = "[api]\nnamespace = \"shared\"\n[[api.endpoints]]\npath = \"/stats\"\nmethod = \"GET\"\nhandler = \"stats\"";
+        let report = PluginDoctor::new("1.0.0").diagnose(&[
+            plugin("a", "1.0.0", "[dependencies]\nconflicts = [\"b\"]"),
+            plugin("b", "1.0.0", "[dependencies]\nconflicts = [\"a\"]"),
+            plugin("c", "1.0.0", endpoint),
+            plugin("d", "1.0.0", endpoint),
+        ]);
+
+        let conflicts: Vec<_> = report
+            .findings
+            .iter()
+            .filter(|f| f.category == FindingCategory::Conflict)
+            .collect();
+        assert_eq!(conflicts.len(), 2);
+        assert!(conflicts
+            .iter()
+            .any(|f| f.message.contains("GET /shared/v1/stats")));
+    }
+
+    #[tokio::test]
+    async fn test_update_check_marks_completed() {
+        let doctor = PluginDoctor::new("1.0.0");
+        let manifests = [plugin("base", "1.0.0", "")];
+        let mut report = doctor.diagnose(&manifests);
+
+        let checker = UpdateChecker::new(Default::default());
+        doctor
+            .check_updates(&mut report, &checker, &manifests)
+            .await;
+
+        assert_eq!(report.update_check, UpdateCheckStatus::Completed);
+        assert!(!report.has_errors());
+    }
+
+    #[test]
+    fn test_summarize_error() {
+        let detail = "TOML parse error at line 19, column 1\n   |\n19 | [plugin.tags]\n   | ^^^\ninvalid type: map, expected a sequence\n";
+        assert_eq!(
+            summarize_error(detail),
+            "TOML parse error at line 19, column 1: invalid type: map, expected a sequence"
+        );
+    }
+
+    #[test]
+    fn test_parse_version_shorthand() {
+        assert_eq!(parse_version("1"), Some(Version::new(1, 0, 0)));
+        assert_eq!(parse_version("v1.2"), Some(Version::new(1, 2, 0)));
+        assert_eq!(parse_version("nope"), None);
+    }
+}
@@ -26,6 +26,7 @@
 //! - **Update Checking** - Automatic update detection
 //! - **Marketplace** - Plugin marketplace integration
 //! - **Conflict Detection** - Plugin incompatibility detection
+//! - **Plugin Doctor** - Combined dependency, conflict and compatibility report
 //! - **Performance Monitoring** - Resource usage tracking
 //! - **Error Isolation** - Automatic error containment
 //! - **Config Export/Import** - Configuration portability
@@ -66,6 +67,9 @@ pub mod registrations;
 // Updates and marketplace (Points 177-179)
 pub mod updates;
 
+// Plugin health diagnostics
+pub mod doctor;
+
 // Monitoring and isolation (Points 180-181)
 pub mod monitoring;
 
@@ -125,6 +129,9 @@ pub use updates::{
     UpdateConfig, UpdateInfo,
 };
 
+// Re-export diagnostics types
+pub use doctor::{DoctorReport, Finding, FindingCategory, FindingSeverity, PluginDoctor};
+
 // Re-export monitoring types (Points 180-181)
 pub use monitoring::{ErrorIsolator, PerformanceMonitor, PluginMetrics};
 
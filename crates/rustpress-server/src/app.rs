@@ -1,6 +1,7 @@
 //! Main application struct and server setup.
 
-use axum::{middleware as axum_middleware, Router};
+use axum::{extract::DefaultBodyLimit, middleware as axum_middleware, Router};
+use rustpress_content::{ContentService, RedirectManager};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -9,11 +10,13 @@ use tower::ServiceBuilder;
 use tower_http::trace::TraceLayer;
 use tracing::{info, warn};
 
+use crate::background::BackgroundTasks;
 use crate::error::HttpError;
-use crate::metrics::Metrics;
+use crate::metrics::{track_metrics, Metrics};
 use crate::middleware::{
-    api_version, body_limit, compression_layer, cors_layer, rate_limit, request_id,
-    request_logging, security_headers, tenant_identification,
+    api_version, compression_layer, cors_layer, profile_requests, rate_limit, request_id,
+    request_logging, security_headers, serve_previews, serve_redirects, tenant_identification,
+    RouteRateLimiter,
 };
 use crate::routes::create_router;
 use crate::security::{
@@ -24,8 +27,8 @@ use crate::security::{
     security_audit::{security_audit, SecurityAuditConfig, SecurityAuditLogger},
 };
 use crate::shutdown::{
-    graceful_shutdown, listen_for_shutdown_signals, ShutdownController, ShutdownExecutor,
-    ShutdownPhase,
+    graceful_shutdown, listen_for_shutdown_signals, track_in_flight, ShutdownController,
+    ShutdownExecutor, ShutdownHandle, ShutdownPhase,
 };
 use crate::state::AppState;
 
@@ -34,6 +37,10 @@ pub struct App {
     state: AppState,
     metrics: Arc<Metrics>,
     shutdown_controller: ShutdownController,
+    background: Option<Arc<BackgroundTasks>>,
+    rate_limiter: RouteRateLimiter,
+    redirects: Arc<RedirectManager>,
+    content: Arc<ContentService>,
     // Security middleware
     security_middleware: SecurityMiddleware,
     content_security: ContentSecurityMiddleware,
@@ -45,10 +52,24 @@ pub struct App {
 impl App {
     /// Create a new application instance
     pub fn new(state: AppState) -> Self {
+        let rate_limiter =
+            RouteRateLimiter::from_config(&state.config.rate_limit).with_jwt(state.jwt.clone());
+        let metrics = state.metrics.clone();
+        let redirects = Arc::new(RedirectManager::new(state.db().inner().clone()));
+        // Preview tokens are signed with the JWT secret; the signer prefixes
+        // its own domain tag, so a token can't double as anything else
+        let content = Arc::new(
+            ContentService::new(state.db().inner().clone())
+                .with_preview_secret(&state.config.auth.jwt_secret),
+        );
         Self {
             state,
-            metrics: Arc::new(Metrics::new()),
+            metrics,
             shutdown_controller: ShutdownController::with_default_timeout(),
+            background: None,
+            rate_limiter,
+            redirects,
+            content,
             // Initialize security middleware with default configs
             security_middleware: SecurityMiddleware::new(SecurityConfig::default()),
             content_security: ContentSecurityMiddleware::new(ContentSecurityConfig::default()),
@@ -64,6 +85,12 @@ impl App {
         self
     }
 
+    /// Stop and await these background tasks during shutdown
+    pub fn with_background_tasks(mut self, tasks: BackgroundTasks) -> Self {
+        self.background = Some(Arc::new(tasks));
+        self
+    }
+
     /// Get the application state
     pub fn state(&self) -> &AppState {
         &self.state
@@ -81,13 +108,17 @@ impl App {
 
     /// Build the router with all middleware
     pub fn build_router(&self) -> Router {
-        let router = create_router(self.state.clone());
+        let router = create_router(self.state.clone()).layer(DefaultBodyLimit::disable());
 
         // Apply middleware stack (order matters - last added is first executed)
-        // Execution order: Compression -> Tracing -> Request ID -> Security Audit ->
-        // Fingerprint -> Bot Detection -> Logging -> Security Headers ->
-        // Request Validation -> Content Security -> CORS -> Body Limit ->
-        // API Version -> Rate Limit -> Tenant ID -> Route Handler
+        // Execution order: Request ID -> Profiling -> Metrics -> Previews ->
+        // Redirects -> Tenant ID -> Rate Limit -> API Version -> CORS ->
+        // Content Security -> Request Validation -> Security Headers -> Logging ->
+        // Bot Detection -> Fingerprint -> Security Audit -> Tracing ->
+        // Compression -> Route Handler
+        //
+        // Body size limits are enforced per route by content security, so
+        // axum's fixed default extractor limit is disabled.
         router
             .layer(
                 ServiceBuilder::new()
@@ -96,8 +127,6 @@ impl App {
                     // Tracing
                     .layer(TraceLayer::new_for_http()),
             )
-            // Request ID (first, so all subsequent middleware can use it)
-            .layer(axum_middleware::from_fn(request_id))
             // Security audit logging (captures all security events)
             .layer(axum_middleware::from_fn_with_state(
                 self.audit_logger.clone(),
@@ -129,13 +158,11 @@ impl App {
             ))
             // CORS
             .layer(cors_layer())
-            // Body size limit
-            .layer(axum_middleware::from_fn(body_limit))
             // API versioning
             .layer(axum_middleware::from_fn(api_version))
             // Rate limiting
             .layer(axum_middleware::from_fn_with_state(
-                self.state.clone(),
+                self.rate_limiter.clone(),
                 rate_limit,
             ))
             // Tenant identification
@@ -143,11 +170,46 @@ impl App {
                 self.state.clone(),
                 tenant_identification,
             ))
+            // Redirects for paths that would otherwise 404 (old slugs)
+            .layer(axum_middleware::from_fn_with_state(
+                self.redirects.clone(),
+                serve_redirects,
+            ))
+            // Draft preview links, answered before any other handler
+            .layer(axum_middleware::from_fn_with_state(
+                self.content.clone(),
+                serve_previews,
+            ))
+            // Request metrics, labelled by matched route template
+            .layer(axum_middleware::from_fn_with_state(
+                self.metrics.clone(),
+                track_metrics,
+            ))
+            // Profiler traces, keyed by request ID
+            .layer(axum_middleware::from_fn_with_state(
+                self.state.profiler.clone(),
+                profile_requests,
+            ))
+            // Request ID (added last so it runs first and every other
+            // middleware, log line and error response sees it)
+            .layer(axum_middleware::from_fn(request_id))
     }
 
     /// Run the HTTP server
+    ///
+    /// On SIGTERM or Ctrl-C the server stops accepting connections and waits
+    /// up to the shutdown timeout for in-flight requests, closing whatever is
+    /// left after that. Background tasks are then stopped, the event bus is
+    /// flushed and the database pool closed, in that order.
     pub async fn run(self, addr: SocketAddr) -> Result<(), Box<dyn std::error::Error>> {
-        let router = self.build_router();
+        let shutdown_controller = self.shutdown_controller.clone();
+        let in_flight = ShutdownHandle::new(shutdown_controller.clone());
+        let router = self
+            .build_router()
+            .layer(axum_middleware::from_fn_with_state(
+                in_flight.clone(),
+                track_in_flight,
+            ));
 
         info!("Starting RustPress server on {}", addr);
 
@@ -156,35 +218,88 @@ impl App {
         info!("Server listening on {}", addr);
 
         // Spawn shutdown signal listener
-        let shutdown_controller = self.shutdown_controller.clone();
         tokio::spawn(listen_for_shutdown_signals(shutdown_controller.clone()));
 
         // Create shutdown executor for ordered cleanup
         let mut shutdown_executor = ShutdownExecutor::new(shutdown_controller.clone());
 
         // Register shutdown handlers
+        if let Some(background) = self.background.clone() {
+            shutdown_executor.register(ShutdownPhase::StopWorkers, move || {
+                let background = background.clone();
+                async move {
+                    info!("Stopping background workers...");
+                    background.shutdown().await;
+                }
+            });
+        }
+
         let state_clone = self.state.clone();
         shutdown_executor.register(ShutdownPhase::FlushCaches, move || {
-            let _state = state_clone.clone();
+            let state = state_clone.clone();
             async move {
-                info!("Flushing caches...");
-                // Cache flush logic would go here
+                info!(
+                    pending = state.event_bus.pending_dispatches(),
+                    "Flushing event bus..."
+                );
+                state.event_bus.flush().await;
             }
         });
 
         let state_clone = self.state.clone();
         shutdown_executor.register(ShutdownPhase::CloseDatabase, move || {
-            let _state = state_clone.clone();
+            let state = state_clone.clone();
             async move {
                 info!("Closing database connections...");
-                // Database close logic would go here
+                state.database.close().await;
             }
         });
 
-        // Run server with graceful shutdown
-        axum::serve(listener, router)
-            .with_graceful_shutdown(graceful_shutdown(shutdown_controller))
-            .await?;
+        // Stop accepting connections once shutdown starts; hyper then lets
+        // open connections finish their current request
+        let server = axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(graceful_shutdown(shutdown_controller.clone()));
+        let mut server = tokio::spawn(async move { server.await });
+
+        tokio::select! {
+            result = &mut server => {
+                // Server exited without a shutdown signal
+                result??;
+                return Ok(());
+            }
+            _ = graceful_shutdown(shutdown_controller.clone()) => {}
+        }
+
+        // Drain in-flight requests, forcing remaining connections closed at the timeout
+        let deadline = tokio::time::sleep(shutdown_controller.timeout());
+        tokio::pin!(deadline);
+        let mut progress = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            tokio::select! {
+                result = &mut server => {
+                    result??;
+                    info!("All in-flight requests completed");
+                    break;
+                }
+                _ = &mut deadline => {
+                    warn!(
+                        in_flight = in_flight.active_task_count(),
+                        "Shutdown timeout reached, closing remaining connections"
+                    );
+                    server.abort();
+                    break;
+                }
+                _ = progress.tick() => {
+                    info!(
+                        in_flight = in_flight.active_task_count(),
+                        "Waiting for in-flight requests to complete"
+                    );
+                }
+            }
+        }
 
         // Execute ordered shutdown
         shutdown_executor.execute().await;
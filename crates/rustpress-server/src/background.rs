@@ -4,22 +4,30 @@
 //! for periodic tasks like publishing scheduled posts and cleaning up expired data.
 
 use std::sync::Arc;
-use tracing::{error, info};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
 
+use rustpress_events::EventBus;
 use rustpress_jobs::{
-    CleanThemePreviewsHandler, CleanThemePreviewsJob, JobQueue, PublishScheduledPostsHandler,
-    PublishScheduledPostsJob, Schedule, Scheduler, Worker,
+    AnnounceWebhookHandler, CleanThemePreviewsHandler, CleanThemePreviewsJob, JobQueue,
+    PublishScheduledPostsHandler, PublishScheduledPostsJob, ReindexContentHandler, Schedule,
+    Scheduler, StorageLifecycleHandler, StorageLifecycleJob, Worker,
 };
+use rustpress_storage::LifecycleManager;
 
 /// Initialize and start the job scheduler with periodic tasks
-pub fn init_scheduler(job_queue: Arc<JobQueue>) -> Arc<Scheduler> {
+pub fn init_scheduler(job_queue: Arc<JobQueue>, storage_lifecycle: bool) -> Arc<Scheduler> {
     let scheduler = Arc::new(Scheduler::new(job_queue.clone()));
 
     // Schedule: Publish scheduled posts every minute
     scheduler.schedule_job(
         "publish_scheduled_posts",
         Schedule::every_minute(),
-        PublishScheduledPostsJob { site_id: None },
+        PublishScheduledPostsJob {
+            site_id: None,
+            post_id: None,
+        },
     );
 
     // Schedule: Clean expired theme previews every hour
@@ -29,52 +37,139 @@ pub fn init_scheduler(job_queue: Arc<JobQueue>) -> Arc<Scheduler> {
         CleanThemePreviewsJob { site_id: None },
     );
 
+    // Schedule: Move idle media to colder storage classes once a day
+    if storage_lifecycle {
+        scheduler.schedule_job(
+            "storage_lifecycle",
+            Schedule::daily_at(3),
+            StorageLifecycleJob::default(),
+        );
+    }
+
     info!("Job scheduler initialized with periodic tasks:");
     info!("  - publish_scheduled_posts: every minute");
     info!("  - clean_theme_previews: hourly");
+    if storage_lifecycle {
+        info!("  - storage_lifecycle: daily at 03:00");
+    }
 
     scheduler
 }
 
 /// Start the background worker for processing jobs
-pub fn start_worker(job_queue: Arc<JobQueue>, pool: sqlx::PgPool) {
-    let worker = Worker::new(job_queue);
+pub fn start_worker(
+    job_queue: Arc<JobQueue>,
+    pool: sqlx::PgPool,
+    event_bus: Arc<EventBus>,
+    storage_lifecycle: Option<Arc<LifecycleManager>>,
+) -> (Arc<Worker>, JoinHandle<()>) {
+    let worker = Arc::new(Worker::new(job_queue));
 
     // Register job handlers
-    worker.register(PublishScheduledPostsHandler::new(pool.clone()));
+    worker.register(PublishScheduledPostsHandler::new(pool.clone()).with_event_bus(event_bus));
     worker.register(CleanThemePreviewsHandler::new(pool.clone()));
+    worker.register(ReindexContentHandler::new(pool.clone()));
+    worker.register(AnnounceWebhookHandler::new());
+    if let Some(manager) = storage_lifecycle {
+        worker.register(StorageLifecycleHandler::new(manager));
+    }
 
     // Spawn worker in background
-    tokio::spawn(async move {
+    let runner = worker.clone();
+    let handle = tokio::spawn(async move {
         info!("Background job worker started");
-        if let Err(e) = worker.run().await {
+        if let Err(e) = runner.run().await {
             error!("Worker error: {}", e);
         }
     });
+
+    (worker, handle)
 }
 
 /// Start the scheduler loop in a background task
-pub fn start_scheduler(scheduler: Arc<Scheduler>) {
+pub fn start_scheduler(scheduler: Arc<Scheduler>) -> JoinHandle<()> {
     tokio::spawn(async move {
         info!("Scheduler loop started");
         if let Err(e) = scheduler.run().await {
             error!("Scheduler error: {}", e);
         }
-    });
+    })
+}
+
+/// Running background worker and scheduler, stopped during shutdown
+pub struct BackgroundTasks {
+    worker: Arc<Worker>,
+    scheduler: Arc<Scheduler>,
+    handles: Mutex<Vec<(&'static str, JoinHandle<()>)>>,
+}
+
+impl BackgroundTasks {
+    /// Get the scheduler
+    pub fn scheduler(&self) -> &Arc<Scheduler> {
+        &self.scheduler
+    }
+
+    /// Signal the worker and scheduler to stop and wait for their loops to exit.
+    ///
+    /// If this future is dropped (e.g. the shutdown phase timed out), tasks
+    /// still running are aborted when `BackgroundTasks` is dropped.
+    pub async fn shutdown(&self) {
+        self.scheduler.stop();
+        self.worker.stop();
+
+        let mut handles = self.handles.lock().await;
+        while let Some((name, handle)) = handles.last_mut() {
+            info!(task = *name, "Waiting for background task to stop");
+            if let Err(e) = handle.await {
+                if e.is_panic() {
+                    error!(task = *name, "Background task panicked");
+                }
+            }
+            handles.pop();
+        }
+    }
+}
+
+impl Drop for BackgroundTasks {
+    fn drop(&mut self) {
+        for (name, handle) in self.handles.get_mut().drain(..) {
+            if !handle.is_finished() {
+                warn!(
+                    task = name,
+                    "Aborting background task that did not stop in time"
+                );
+                handle.abort();
+            }
+        }
+    }
 }
 
 /// Initialize all background tasks (scheduler + worker)
-pub async fn init_background_tasks(job_queue: JobQueue, pool: sqlx::PgPool) -> Arc<Scheduler> {
-    let job_queue_arc = Arc::new(job_queue);
+pub fn init_background_tasks(
+    job_queue: Arc<JobQueue>,
+    pool: sqlx::PgPool,
+    event_bus: Arc<EventBus>,
+    storage_lifecycle: Option<Arc<LifecycleManager>>,
+) -> BackgroundTasks {
+    let schedule_lifecycle = storage_lifecycle.is_some();
 
     // Initialize and start worker
-    start_worker(job_queue_arc.clone(), pool);
+    let (worker, worker_handle) =
+        start_worker(job_queue.clone(), pool, event_bus, storage_lifecycle);
 
     // Initialize scheduler
-    let scheduler = init_scheduler(job_queue_arc);
+    let scheduler = init_scheduler(job_queue, schedule_lifecycle);
 
     // Start scheduler loop
-    start_scheduler(scheduler.clone());
-
-    scheduler
+    let scheduler_handle = start_scheduler(scheduler.clone());
+
+    BackgroundTasks {
+        worker,
+        scheduler,
+        // Awaited in reverse: the scheduler stops enqueueing before the worker drains
+        handles: Mutex::new(vec![
+            ("worker", worker_handle),
+            ("scheduler", scheduler_handle),
+        ]),
+    }
 }
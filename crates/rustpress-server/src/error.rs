@@ -79,6 +79,18 @@ impl HttpError {
         Self::new(StatusCode::CONFLICT, "CONFLICT", message)
     }
 
+    pub fn payload_too_large(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::PAYLOAD_TOO_LARGE, "PAYLOAD_TOO_LARGE", message)
+    }
+
+    pub fn unsupported_media_type(message: impl Into<String>) -> Self {
+        Self::new(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "UNSUPPORTED_MEDIA_TYPE",
+            message,
+        )
+    }
+
     pub fn unprocessable_entity(message: impl Into<String>) -> Self {
         Self::new(
             StatusCode::UNPROCESSABLE_ENTITY,
@@ -111,7 +123,12 @@ impl HttpError {
 }
 
 impl IntoResponse for HttpError {
-    fn into_response(self) -> Response {
+    fn into_response(mut self) -> Response {
+        // Errors raised while handling a request carry its ID, so a client
+        // reporting one can be matched to the logs
+        if self.body.request_id.is_none() {
+            self.body.request_id = rustpress_core::current_request_id();
+        }
         (self.status, Json(self.body)).into_response()
     }
 }
@@ -226,6 +243,7 @@ impl From<CoreError> for HttpError {
             CoreError::TenantSuspended { tenant_id } => {
                 HttpError::forbidden(format!("Tenant '{}' is suspended", tenant_id))
             }
+            CoreError::TenantRequired { .. } => HttpError::bad_request("No tenant was identified"),
             CoreError::Hook { hook_name, message } => {
                 tracing::error!("Hook error ({}): {}", hook_name, message);
                 HttpError::internal_error("A hook error occurred")
@@ -249,6 +267,26 @@ impl From<CoreError> for HttpError {
     }
 }
 
+impl From<rustpress_media::MediaError> for HttpError {
+    fn from(err: rustpress_media::MediaError) -> Self {
+        use rustpress_media::MediaError;
+
+        match err {
+            MediaError::NotFound(id) => {
+                HttpError::not_found(format!("Media with id '{}' not found", id))
+            }
+            MediaError::InvalidType(_) | MediaError::UnsupportedFormat(_) => {
+                HttpError::bad_request(err.to_string())
+            }
+            MediaError::FileTooLarge { .. } => HttpError::payload_too_large(err.to_string()),
+            err => {
+                tracing::error!("Media error: {}", err);
+                HttpError::internal_error("A media processing error occurred")
+            }
+        }
+    }
+}
+
 /// Result type for HTTP handlers
 pub type HttpResult<T> = Result<T, HttpError>;
 
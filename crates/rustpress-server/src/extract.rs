@@ -6,16 +6,19 @@ use axum::{
     http::{header, request::Parts, HeaderMap, StatusCode},
     Json,
 };
-use rustpress_auth::{Claims, JwtManager};
+use rustpress_auth::{Claims, JwtManager, Subject};
 use rustpress_core::context::RequestContext;
+use rustpress_core::correlation::{current_request_id, generate_request_id};
 use rustpress_core::types::Pagination;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
+use std::convert::Infallible;
 use std::sync::Arc;
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::error::HttpError;
+pub use crate::middleware::RequestId;
 use crate::state::AppState;
 
 /// Authenticated user extracted from JWT
@@ -35,6 +38,11 @@ impl AuthUser {
     pub fn is_admin(&self) -> bool {
         self.has_role("administrator")
     }
+
+    /// This user as the subject of a resource-scoped permission check
+    pub fn subject(&self) -> Subject<'_> {
+        Subject::new(self.id, &self.roles)
+    }
 }
 
 #[async_trait]
@@ -135,10 +143,46 @@ where
             }
         }
 
+        // Share the middleware's request ID when it is a UUID (ours always are)
+        if let Some(id) = parts.extensions.get::<RequestId>() {
+            if let Ok(request_id) = id.0.parse() {
+                ctx.request_id = request_id;
+            }
+        }
+
+        // Tenant identified by the tenant middleware, for scoping repositories
+        if let Some(tenant) = parts.extensions.get::<crate::middleware::TenantId>() {
+            if let Ok(tenant_id) = tenant.0.parse() {
+                ctx = ctx.with_tenant(tenant_id);
+            }
+        }
+
         Ok(ReqContext(ctx))
     }
 }
 
+/// Request ID extractor
+///
+/// Yields the ID assigned by the request ID middleware, falling back to the
+/// task's current request ID (or a fresh one) when the middleware isn't
+/// installed, e.g. in handler tests.
+#[async_trait]
+impl<S> FromRequestParts<S> for RequestId
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<RequestId>()
+            .cloned()
+            .or_else(|| current_request_id().map(RequestId))
+            .unwrap_or_else(|| RequestId(generate_request_id())))
+    }
+}
+
 /// Validated JSON body extractor
 #[derive(Debug)]
 pub struct ValidatedJson<T>(pub T);
@@ -155,9 +199,16 @@ where
         req: axum::http::Request<axum::body::Body>,
         state: &S,
     ) -> Result<Self, Self::Rejection> {
-        let Json(value) = Json::<T>::from_request(req, state)
-            .await
-            .map_err(|e| HttpError::bad_request(format!("Invalid JSON: {}", e)))?;
+        let Json(value) =
+            Json::<T>::from_request(req, state)
+                .await
+                .map_err(|e| match e.status() {
+                    // Body cap from the content security middleware
+                    StatusCode::PAYLOAD_TOO_LARGE => {
+                        HttpError::payload_too_large("Request body too large")
+                    }
+                    _ => HttpError::bad_request(format!("Invalid JSON: {}", e)),
+                })?;
 
         value
             .validate()
@@ -257,7 +308,10 @@ pub async fn require_permission(
     resource: &str,
     action: &str,
 ) -> Result<(), HttpError> {
-    if !app_state.permissions.can(&user.roles, resource, action) {
+    if !app_state
+        .permissions
+        .roles_can(&user.roles, resource, action)
+    {
         return Err(HttpError::forbidden(format!(
             "Missing permission: {}:{}",
             resource, action
@@ -345,6 +399,33 @@ mod tests {
         assert_eq!(pagination.total, 100);
     }
 
+    #[tokio::test]
+    async fn test_request_id_extractor() {
+        let (mut parts, _) = axum::http::Request::builder()
+            .body(())
+            .unwrap()
+            .into_parts();
+        parts
+            .extensions
+            .insert(RequestId("from-middleware".to_string()));
+        let id = RequestId::from_request_parts(&mut parts, &())
+            .await
+            .unwrap();
+        assert_eq!(id.0, "from-middleware");
+
+        let (mut parts, _) = axum::http::Request::builder()
+            .body(())
+            .unwrap()
+            .into_parts();
+        let id = rustpress_core::correlation::scope("scoped".to_string(), async {
+            RequestId::from_request_parts(&mut parts, &())
+                .await
+                .unwrap()
+        })
+        .await;
+        assert_eq!(id.0, "scoped");
+    }
+
     #[test]
     fn test_pagination_offset_limit() {
         let params = PaginationParams {
@@ -18,7 +18,7 @@ pub mod state;
 pub mod websocket;
 
 pub use app::App;
-pub use background::init_background_tasks;
+pub use background::{init_background_tasks, BackgroundTasks};
 pub use services::{EmailConfig, EmailService, EmailTemplate};
 pub use state::AppState;
 pub use websocket::{websocket_handler, WebSocketHub};
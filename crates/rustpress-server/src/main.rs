@@ -29,17 +29,21 @@ struct Cli {
 
 use rustpress_auth::{JwtConfig, JwtManager, PermissionChecker};
 use rustpress_cache::{Cache, CacheConfig, MemoryBackend};
-use rustpress_core::config::AppConfig;
+use rustpress_core::config::{
+    AppConfig, ConfigError, ConfigLoader, LoadedConfig, CONFIG_PATH_VAR, DEFAULT_CONFIG_PATH,
+};
 use rustpress_core::context::AppContext;
 use rustpress_core::discovery::{ComponentType, DiscoveryService};
 use rustpress_core::hook::HookRegistry;
 use rustpress_core::plugin::PluginManager;
 use rustpress_core::plugin_loader::PluginLoader;
 use rustpress_database::{DatabasePool, PoolConfig};
-use rustpress_events::EventBus;
-use rustpress_jobs::JobQueue;
-use rustpress_storage::{LocalBackend, Storage, StorageConfig};
+use rustpress_events::store::spawn_pruning;
+use rustpress_events::{EventBus, EventRetention, PgEventStore};
+use rustpress_jobs::{announcement_subscriber, AnnouncementConfig, JobQueue, PgAccessLog};
+use rustpress_storage::{LifecycleConfig, LocalBackend, Storage, StorageConfig};
 
+use rustpress_server::init_background_tasks;
 use rustpress_server::setup;
 use rustpress_server::state::AppState;
 use rustpress_server::App;
@@ -47,13 +51,6 @@ use rustpress_server::App;
 /// Environment variable names
 mod env_vars {
     pub const DATABASE_URL: &str = "DATABASE_URL";
-    pub const SERVER_HOST: &str = "RUSTPRESS_HOST";
-    pub const SERVER_PORT: &str = "RUSTPRESS_PORT";
-    pub const JWT_SECRET: &str = "JWT_SECRET";
-    pub const STORAGE_PATH: &str = "STORAGE_PATH";
-    pub const THEMES_PATH: &str = "THEMES_PATH";
-    pub const CACHE_MAX_CAPACITY: &str = "CACHE_MAX_CAPACITY";
-    pub const LOG_LEVEL: &str = "RUST_LOG";
 }
 
 /// Initialize the tracing/logging subsystem
@@ -64,14 +61,17 @@ fn init_tracing() {
                 .unwrap_or_else(|_| "rustpress=info,tower_http=info,sqlx=warn".into()),
         )
         .with(tracing_subscriber::fmt::layer())
+        .with(rustpress_admin::LogBufferLayer::new(
+            rustpress_admin::LogBuffer::global().clone(),
+        ))
         .init();
 }
 
 /// Get the config file path
 fn get_config_path() -> PathBuf {
-    env::var("RUSTPRESS_CONFIG")
+    env::var(CONFIG_PATH_VAR)
         .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from("./config/rustpress.toml"))
+        .unwrap_or_else(|_| PathBuf::from(DEFAULT_CONFIG_PATH))
 }
 
 /// Check if setup is needed (config file does not exist or is invalid)
@@ -100,7 +100,9 @@ fn needs_setup() -> bool {
 
 /// Test if database connection works
 async fn test_database_connection() -> bool {
-    let config = load_config();
+    let Ok(LoadedConfig { config, .. }) = load_config() else {
+        return false;
+    };
 
     if config.database.url.is_empty() {
         return false;
@@ -124,70 +126,32 @@ async fn test_database_connection() -> bool {
     }
 }
 
-/// Load configuration from config file and environment variables
-fn load_config() -> AppConfig {
-    let mut config = AppConfig::default();
-
-    // Try to load from config file first
-    let config_path = get_config_path();
-    if config_path.exists() {
-        if let Ok(content) = std::fs::read_to_string(&config_path) {
-            if let Ok(file_config) = toml::from_str::<toml::Value>(&content) {
-                // Load database URL from config file
-                if let Some(db) = file_config.get("database") {
-                    if let Some(url) = db.get("database_url").and_then(|v| v.as_str()) {
-                        config.database.url = url.to_string();
-                        env::set_var(env_vars::DATABASE_URL, url);
-                    }
-                }
-
-                // Load server config
-                if let Some(server) = file_config.get("server") {
-                    if let Some(host) = server.get("host").and_then(|v| v.as_str()) {
-                        config.server.host = host.to_string();
-                    }
-                    if let Some(port) = server.get("port").and_then(|v| v.as_integer()) {
-                        config.server.port = port as u16;
-                    }
-                }
-
-                // Load auth config
-                if let Some(auth) = file_config.get("auth") {
-                    if let Some(secret) = auth.get("jwt_secret").and_then(|v| v.as_str()) {
-                        config.auth.jwt_secret = secret.to_string();
-                    }
-                }
-            }
-        }
-    }
+/// Load configuration: defaults < config file < `rustpress.{RUSTPRESS_ENV}.toml`
+/// < environment variables
+fn load_config() -> Result<LoadedConfig, ConfigError> {
+    ConfigLoader::from_env().load()
+}
 
-    // Environment variables override config file
-    if let Ok(host) = env::var(env_vars::SERVER_HOST) {
-        config.server.host = host;
-    }
-    if let Ok(port) = env::var(env_vars::SERVER_PORT) {
-        if let Ok(port) = port.parse() {
-            config.server.port = port;
+/// Load the `[announcements]` section of the config file, if any
+fn load_announcement_config() -> AnnouncementConfig {
+    let content = match std::fs::read_to_string(get_config_path()) {
+        Ok(content) => content,
+        Err(_) => return AnnouncementConfig::default(),
+    };
+    let section = toml::from_str::<toml::Value>(&content)
+        .ok()
+        .and_then(|config| config.get("announcements").cloned());
+    match section.map(|s| s.try_into::<AnnouncementConfig>()) {
+        Some(Ok(config)) => config,
+        Some(Err(e)) => {
+            warn!(
+                "Invalid [announcements] config, announcements disabled: {}",
+                e
+            );
+            AnnouncementConfig::default()
         }
+        None => AnnouncementConfig::default(),
     }
-
-    if let Ok(url) = env::var(env_vars::DATABASE_URL) {
-        config.database.url = url;
-    }
-
-    if let Ok(secret) = env::var(env_vars::JWT_SECRET) {
-        config.auth.jwt_secret = secret;
-    } else if config.auth.jwt_secret.is_empty()
-        || config.auth.jwt_secret == "change-me-in-production"
-    {
-        warn!("JWT_SECRET not set, using default (not recommended for production)");
-    }
-
-    if let Ok(path) = env::var(env_vars::STORAGE_PATH) {
-        config.storage.local_path = PathBuf::from(path);
-    }
-
-    config
 }
 
 /// Initialize the database connection pool
@@ -206,10 +170,7 @@ async fn init_database(config: &AppConfig) -> Result<DatabasePool, Box<dyn std::
 
 /// Initialize the cache subsystem
 fn init_cache(config: &AppConfig) -> Cache {
-    let max_capacity = env::var(env_vars::CACHE_MAX_CAPACITY)
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(10_000);
+    let max_capacity = config.cache.max_entries;
 
     let backend = Arc::new(MemoryBackend::with_ttl(
         max_capacity,
@@ -226,10 +187,22 @@ fn init_cache(config: &AppConfig) -> Cache {
     Cache::with_config(backend, cache_config)
 }
 
-/// Initialize the event bus
-fn init_event_bus() -> EventBus {
-    info!("Event bus initialized");
-    EventBus::new()
+/// Initialize the event bus, persisting events for durable subscribers
+/// when the event log is enabled
+fn init_event_bus(config: &AppConfig, pool: &DatabasePool) -> EventBus {
+    if !config.events.log_enabled {
+        info!("Event bus initialized");
+        return EventBus::new();
+    }
+
+    let store = Arc::new(PgEventStore::new(pool.inner().clone()));
+    spawn_pruning(
+        store.clone(),
+        EventRetention::default(),
+        Duration::from_secs(3600),
+    );
+    info!("Event bus initialized with durable event log");
+    EventBus::new().with_store(store)
 }
 
 /// Initialize the job queue
@@ -239,17 +212,22 @@ fn init_job_queue(pool: &DatabasePool) -> JobQueue {
 }
 
 /// Initialize the storage subsystem
-fn init_storage(config: &AppConfig) -> Storage {
-    let backend = Arc::new(LocalBackend::new(&config.storage.local_path).with_base_url("/uploads"));
+fn init_storage(config: &AppConfig, pool: &DatabasePool) -> Storage {
+    let mut backend = LocalBackend::new(&config.storage.local_path).with_base_url("/uploads");
+    if let Some(secret) = &config.storage.url_signing_secret {
+        backend = backend.with_signing_secret(secret);
+    }
 
     let storage_config = StorageConfig {
         max_upload_size: config.storage.max_upload_size as u64,
         allowed_types: config.storage.allowed_types.clone(),
+        url_signing_secret: config.storage.url_signing_secret.clone(),
         ..Default::default()
     };
 
     info!(path = ?config.storage.local_path, "Storage initialized");
-    Storage::with_config(backend, storage_config)
+    Storage::with_config(Arc::new(backend), storage_config)
+        .with_access_log(Arc::new(PgAccessLog::new(pool.inner().clone())))
 }
 
 /// Initialize the JWT manager
@@ -275,9 +253,7 @@ fn build_app_state(
     storage: Storage,
     jwt: JwtManager,
 ) -> Result<AppState, &'static str> {
-    let themes_dir = env::var(env_vars::THEMES_PATH)
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from("./themes"));
+    let themes_dir = config.storage.themes_path.clone();
 
     AppState::builder()
         .config(config)
@@ -301,10 +277,8 @@ async fn ensure_directories(config: &AppConfig) -> Result<(), Box<dyn std::error
     info!(path = ?config.storage.local_path, "Storage directory ready");
 
     // Create themes directory
-    let themes_dir = env::var(env_vars::THEMES_PATH)
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from("./themes"));
-    tokio::fs::create_dir_all(&themes_dir).await?;
+    let themes_dir = &config.storage.themes_path;
+    tokio::fs::create_dir_all(themes_dir).await?;
     info!(path = ?themes_dir, "Themes directory ready");
 
     Ok(())
@@ -333,17 +307,35 @@ async fn run_app(cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
     info!("Version: {}", env!("CARGO_PKG_VERSION"));
 
     // Load configuration
-    let mut config = load_config();
+    let mut loaded = load_config().map_err(|e| {
+        error!("{}", e);
+        e
+    })?;
 
     // CLI arguments override config
     if let Some(port) = cli.port {
-        config.server.port = port;
+        loaded.config.server.port = port;
     }
     if let Some(ref host) = cli.host {
-        config.server.host = host.clone();
+        loaded.config.server.host = host.clone();
+    }
+
+    if let Err(e) = loaded.validate() {
+        if let ConfigError::Invalid(problems) = &e {
+            for problem in problems {
+                error!("Invalid configuration: {}", problem);
+            }
+        }
+        return Err(e.into());
     }
 
-    info!(host = %config.server.host, port = config.server.port, "Configuration loaded");
+    let layers: Vec<String> = loaded.layers().iter().map(ToString::to_string).collect();
+    info!(
+        layers = ?layers,
+        config = %loaded.redacted(),
+        "Configuration loaded"
+    );
+    let config = loaded.config;
 
     // Ensure required directories exist
     ensure_directories(&config).await?;
@@ -360,9 +352,9 @@ async fn run_app(cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
     };
 
     let cache = init_cache(&config);
-    let event_bus = init_event_bus();
+    let event_bus = init_event_bus(&config, &database);
     let job_queue = init_job_queue(&database);
-    let storage = init_storage(&config);
+    let storage = init_storage(&config, &database);
     let jwt = init_jwt(&config);
 
     // Build application state
@@ -376,6 +368,9 @@ async fn run_app(cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
         jwt,
     )?;
 
+    // Sweep deduplicated blobs left without references
+    rustpress_storage::dedup::spawn_gc(state.storage.clone(), Duration::from_secs(3600));
+
     // Load plugins from the plugins directory
     info!("Loading plugins...");
     let plugins_dir = std::env::current_dir()?.join("plugins");
@@ -473,8 +468,22 @@ async fn run_app(cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
     info!("=================================================");
 
     // Create and run the application
+    state.event_bus.subscribe(announcement_subscriber(
+        load_announcement_config(),
+        state.job_queue.clone(),
+    ));
+    let background = init_background_tasks(
+        state.job_queue.clone(),
+        state.database.inner().clone(),
+        state.event_bus.clone(),
+        state
+            .storage
+            .lifecycle_manager(LifecycleConfig::default())
+            .map(Arc::new),
+    );
     let app = App::new(state)
-        .with_shutdown_timeout(Duration::from_secs(config.server.shutdown_timeout_secs));
+        .with_shutdown_timeout(Duration::from_secs(config.server.shutdown_timeout_secs))
+        .with_background_tasks(background);
 
     // Run the server
     if let Err(e) = app.run(addr).await {
@@ -566,14 +575,14 @@ mod tests {
 
     #[test]
     fn test_load_config_defaults() {
-        let config = load_config();
+        let config = load_config().unwrap().config;
         assert!(!config.server.host.is_empty());
         assert!(config.server.port > 0);
     }
 
     #[test]
     fn test_jwt_config() {
-        let config = load_config();
+        let config = load_config().unwrap().config;
         let jwt = init_jwt(&config);
         assert!(jwt.config().access_expiry_secs > 0);
     }
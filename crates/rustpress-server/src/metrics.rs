@@ -1,5 +1,13 @@
 //! Prometheus metrics for monitoring.
 
+use async_trait::async_trait;
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use parking_lot::{Mutex, RwLock};
 use prometheus_client::{
     encoding::{EncodeLabelSet, EncodeLabelValue},
     metrics::{
@@ -10,7 +18,19 @@ use prometheus_client::{
     },
     registry::Registry,
 };
+use rustpress_jobs::JobQueue;
+use rustpress_performance::{ObjectCache, PageCache, Profiler};
 use std::sync::Arc;
+use std::time::Instant;
+
+use crate::websocket::hub::HubMetrics;
+
+/// Content type of the `/metrics` exposition
+pub const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Route label for requests that didn't match any route
+const UNMATCHED_ROUTE: &str = "unmatched";
 
 /// HTTP request labels
 #[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
@@ -56,15 +76,59 @@ pub enum JobStatus {
     Failed,
 }
 
+/// Job queue depth labels
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct QueueLabels {
+    pub queue: String,
+}
+
+/// Cache lookup labels, per named cache
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct CacheLookupLabels {
+    pub cache: String,
+    pub result: CacheResult,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelValue)]
+pub enum CacheResult {
+    Hit,
+    Miss,
+}
+
+/// Profiler span labels
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct SpanLabels {
+    pub span: String,
+}
+
+/// A subsystem that exposes its own metrics through the shared registry.
+///
+/// `register` is called once when the source is added; `refresh` runs before
+/// every scrape so sources backed by polled state (queue depth, cache stats)
+/// can update their values.
+#[async_trait]
+pub trait MetricsSource: Send + Sync {
+    /// Register this source's metrics
+    fn register(&self, registry: &mut Registry);
+
+    /// Bring values up to date before encoding
+    async fn refresh(&self) {}
+}
+
 /// Application metrics
 #[derive(Clone)]
 pub struct Metrics {
     /// Prometheus registry
-    pub registry: Arc<Registry>,
+    pub registry: Arc<RwLock<Registry>>,
+    /// Registered subsystem sources, refreshed on every scrape
+    sources: Arc<RwLock<Vec<Arc<dyn MetricsSource>>>>,
 
     // HTTP metrics
     /// Total HTTP requests
     pub http_requests_total: Family<HttpRequestLabels, Counter>,
+    /// Unlabelled request total, for sampling the request rate without
+    /// walking every label set. Not registered; `http_requests` covers it.
+    pub http_requests_served: Counter,
     /// HTTP request duration in seconds
     pub http_request_duration_seconds: Family<HttpRequestLabels, Histogram>,
     /// Currently active HTTP connections
@@ -123,7 +187,7 @@ impl Metrics {
         // HTTP metrics
         let http_requests_total = Family::<HttpRequestLabels, Counter>::default();
         registry.register(
-            "http_requests_total",
+            "http_requests",
             "Total number of HTTP requests",
             http_requests_total.clone(),
         );
@@ -148,7 +212,7 @@ impl Metrics {
         // Database metrics
         let db_queries_total = Family::<DbOperationLabels, Counter>::default();
         registry.register(
-            "db_queries_total",
+            "db_queries",
             "Total number of database queries",
             db_queries_total.clone(),
         );
@@ -180,7 +244,7 @@ impl Metrics {
         // Cache metrics
         let cache_operations_total = Family::<CacheLabels, Counter>::default();
         registry.register(
-            "cache_operations_total",
+            "cache_operations",
             "Total cache operations",
             cache_operations_total.clone(),
         );
@@ -201,11 +265,7 @@ impl Metrics {
 
         // Job metrics
         let jobs_total = Family::<JobLabels, Counter>::default();
-        registry.register(
-            "jobs_total",
-            "Total jobs by type and status",
-            jobs_total.clone(),
-        );
+        registry.register("jobs", "Total jobs by type and status", jobs_total.clone());
 
         let job_duration_seconds = Family::<JobLabels, Histogram>::new_with_constructor(|| {
             Histogram::new(exponential_buckets(0.1, 2.0, 12))
@@ -265,8 +325,10 @@ impl Metrics {
         registry.register("total_media", "Total media files", total_media.clone());
 
         Self {
-            registry: Arc::new(registry),
+            registry: Arc::new(RwLock::new(registry)),
+            sources: Arc::new(RwLock::new(Vec::new())),
             http_requests_total,
+            http_requests_served: Counter::default(),
             http_request_duration_seconds,
             http_connections_active,
             db_queries_total,
@@ -299,6 +361,22 @@ impl Metrics {
         };
 
         self.http_requests_total.get_or_create(&labels).inc();
+        self.http_requests_served.inc();
+        self.http_request_duration_seconds
+            .get_or_create(&labels)
+            .observe(duration_secs);
+    }
+
+    /// Record an HTTP request against its route template, e.g. `/api/v1/posts/:id`
+    pub fn record_http_route(&self, method: &Method, route: &str, status: u16, duration_secs: f64) {
+        let labels = HttpRequestLabels {
+            method: method_label(method).to_string(),
+            path: route.to_string(),
+            status,
+        };
+
+        self.http_requests_total.get_or_create(&labels).inc();
+        self.http_requests_served.inc();
         self.http_request_duration_seconds
             .get_or_create(&labels)
             .observe(duration_secs);
@@ -338,10 +416,25 @@ impl Metrics {
         }
     }
 
+    /// Register a subsystem's metrics and refresh it on every scrape
+    pub fn register_source(&self, source: Arc<dyn MetricsSource>) {
+        source.register(&mut self.registry.write());
+        self.sources.write().push(source);
+    }
+
+    /// Refresh all sources and encode the registry
+    pub async fn render(&self) -> String {
+        let sources = self.sources.read().clone();
+        for source in sources {
+            source.refresh().await;
+        }
+        self.encode()
+    }
+
     /// Encode metrics to Prometheus format
     pub fn encode(&self) -> String {
         let mut buffer = String::new();
-        prometheus_client::encoding::text::encode(&mut buffer, &self.registry).unwrap();
+        prometheus_client::encoding::text::encode(&mut buffer, &self.registry.read()).unwrap();
         buffer
     }
 }
@@ -352,6 +445,248 @@ impl Default for Metrics {
     }
 }
 
+/// Record request count, latency and in-flight connections by route template.
+///
+/// Uses the matched route rather than the raw path so ids don't blow up label
+/// cardinality. The `/metrics` endpoint itself is not recorded.
+pub async fn track_metrics(
+    State(metrics): State<Arc<Metrics>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let route = match request.extensions().get::<MatchedPath>() {
+        Some(path) if path.as_str() == "/metrics" => return next.run(request).await,
+        Some(path) => path.as_str().to_string(),
+        None => UNMATCHED_ROUTE.to_string(),
+    };
+    let method = request.method().clone();
+
+    metrics.http_connections_active.inc();
+    let start = Instant::now();
+    let response = next.run(request).await;
+    metrics.http_connections_active.dec();
+
+    metrics.record_http_route(
+        &method,
+        &route,
+        response.status().as_u16(),
+        start.elapsed().as_secs_f64(),
+    );
+    response
+}
+
+/// Collapse non-standard methods into one label value
+fn method_label(method: &Method) -> &str {
+    match *method {
+        Method::GET
+        | Method::POST
+        | Method::PUT
+        | Method::PATCH
+        | Method::DELETE
+        | Method::HEAD
+        | Method::OPTIONS => method.as_str(),
+        _ => "OTHER",
+    }
+}
+
+impl MetricsSource for HubMetrics {
+    fn register(&self, registry: &mut Registry) {
+        HubMetrics::register(self, registry);
+    }
+}
+
+/// Pending job counts per queue
+pub struct JobQueueMetrics {
+    queue: Arc<JobQueue>,
+    depth: Family<QueueLabels, Gauge>,
+}
+
+impl JobQueueMetrics {
+    pub fn new(queue: Arc<JobQueue>) -> Self {
+        Self {
+            queue,
+            depth: Family::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl MetricsSource for JobQueueMetrics {
+    fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "job_queue_depth",
+            "Pending jobs per queue",
+            self.depth.clone(),
+        );
+    }
+
+    async fn refresh(&self) {
+        match self.queue.depths().await {
+            Ok(depths) => {
+                // Drop queues that have since emptied out
+                self.depth.clear();
+                for (queue, depth) in depths {
+                    self.depth
+                        .get_or_create(&QueueLabels { queue })
+                        .set(depth as i64);
+                }
+            }
+            Err(e) => tracing::warn!(error = %e, "Failed to read job queue depth"),
+        }
+    }
+}
+
+/// Hit/miss snapshot of a cache that keeps its own statistics
+#[async_trait]
+pub trait CacheStatsProvider: Send + Sync {
+    /// Cumulative `(hits, misses)` since the cache was created
+    async fn hits_misses(&self) -> (u64, u64);
+}
+
+#[async_trait]
+impl CacheStatsProvider for rustpress_cache::Cache {
+    async fn hits_misses(&self) -> (u64, u64) {
+        let stats = self.stats().await;
+        (stats.hits, stats.misses)
+    }
+}
+
+#[async_trait]
+impl CacheStatsProvider for ObjectCache {
+    async fn hits_misses(&self) -> (u64, u64) {
+        let stats = self.stats();
+        (stats.local_hits + stats.remote_hits, stats.misses)
+    }
+}
+
+#[async_trait]
+impl CacheStatsProvider for PageCache {
+    async fn hits_misses(&self) -> (u64, u64) {
+        let stats = self.stats();
+        (stats.hits, stats.misses)
+    }
+}
+
+struct TrackedCache {
+    name: String,
+    provider: Arc<dyn CacheStatsProvider>,
+    /// Totals already added to the counters
+    seen: Mutex<(u64, u64)>,
+}
+
+/// Cache hit/miss counters, synced from each cache's statistics on scrape
+#[derive(Default)]
+pub struct CacheMetrics {
+    caches: Vec<TrackedCache>,
+    lookups: Family<CacheLookupLabels, Counter>,
+}
+
+impl CacheMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track a cache under the given `cache` label
+    pub fn with_cache(
+        mut self,
+        name: impl Into<String>,
+        provider: Arc<dyn CacheStatsProvider>,
+    ) -> Self {
+        self.caches.push(TrackedCache {
+            name: name.into(),
+            provider,
+            seen: Mutex::new((0, 0)),
+        });
+        self
+    }
+
+    fn add(&self, cache: &str, result: CacheResult, delta: u64) {
+        if delta > 0 {
+            self.lookups
+                .get_or_create(&CacheLookupLabels {
+                    cache: cache.to_string(),
+                    result,
+                })
+                .inc_by(delta);
+        }
+    }
+}
+
+#[async_trait]
+impl MetricsSource for CacheMetrics {
+    fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "cache_lookups",
+            "Cache lookups by cache and result",
+            self.lookups.clone(),
+        );
+    }
+
+    async fn refresh(&self) {
+        for cache in &self.caches {
+            let (hits, misses) = cache.provider.hits_misses().await;
+            let (hit_delta, miss_delta) = {
+                let mut seen = cache.seen.lock();
+                // A cache whose stats were reset starts counting from zero again
+                let delta = (hits.saturating_sub(seen.0), misses.saturating_sub(seen.1));
+                *seen = (hits, misses);
+                delta
+            };
+            self.add(&cache.name, CacheResult::Hit, hit_delta);
+            self.add(&cache.name, CacheResult::Miss, miss_delta);
+        }
+    }
+}
+
+/// Span and query latencies from the profiler's recorded traces
+pub struct ProfilerMetrics {
+    span_duration_seconds: Family<SpanLabels, Histogram>,
+    query_duration_seconds: Histogram,
+}
+
+impl ProfilerMetrics {
+    /// Observe every trace the profiler records from now on
+    pub fn new(profiler: &Profiler) -> Self {
+        let metrics = Self {
+            span_duration_seconds: Family::new_with_constructor(|| {
+                Histogram::new(exponential_buckets(0.0005, 2.0, 14))
+            }),
+            query_duration_seconds: Histogram::new(exponential_buckets(0.0001, 2.0, 16)),
+        };
+
+        let spans = metrics.span_duration_seconds.clone();
+        let queries = metrics.query_duration_seconds.clone();
+        profiler.observe(Arc::new(move |trace| {
+            // Timings are recorded in microseconds
+            for (span, duration_us) in &trace.timings {
+                spans
+                    .get_or_create(&SpanLabels { span: span.clone() })
+                    .observe(*duration_us as f64 / 1_000_000.0);
+            }
+            for query in &trace.queries {
+                queries.observe(query.duration_us as f64 / 1_000_000.0);
+            }
+        }));
+
+        metrics
+    }
+}
+
+impl MetricsSource for ProfilerMetrics {
+    fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "profiler_span_duration_seconds",
+            "Profiled span duration (e.g. render) in seconds",
+            self.span_duration_seconds.clone(),
+        );
+        registry.register(
+            "profiler_db_query_duration_seconds",
+            "Profiled database query duration in seconds",
+            self.query_duration_seconds.clone(),
+        );
+    }
+}
+
 /// Normalize path for metrics (replace IDs with placeholders)
 fn normalize_path(path: &str) -> String {
     let parts: Vec<&str> = path.split('/').collect();
@@ -380,7 +715,8 @@ mod tests {
     #[test]
     fn test_metrics_creation() {
         let metrics = Metrics::new();
-        assert!(metrics.encode().contains("http_requests_total"));
+        // Counters get their `_total` suffix from the encoder
+        assert!(metrics.encode().contains("# TYPE http_requests counter"));
     }
 
     #[test]
@@ -411,4 +747,81 @@ mod tests {
         let encoded = metrics.encode();
         assert!(encoded.contains("cache_operations_total"));
     }
+
+    struct FakeCache(parking_lot::Mutex<(u64, u64)>);
+
+    #[async_trait]
+    impl CacheStatsProvider for FakeCache {
+        async fn hits_misses(&self) -> (u64, u64) {
+            *self.0.lock()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_source_counts_deltas() {
+        let metrics = Metrics::new();
+        let cache = Arc::new(FakeCache(parking_lot::Mutex::new((3, 1))));
+        metrics.register_source(Arc::new(
+            CacheMetrics::new().with_cache("page", cache.clone()),
+        ));
+
+        let encoded = metrics.render().await;
+        assert!(encoded.contains("cache_lookups_total{cache=\"page\",result=\"Hit\"} 3"));
+        assert!(encoded.contains("cache_lookups_total{cache=\"page\",result=\"Miss\"} 1"));
+
+        *cache.0.lock() = (5, 1);
+        let encoded = metrics.render().await;
+        assert!(encoded.contains("cache_lookups_total{cache=\"page\",result=\"Hit\"} 5"));
+        assert!(encoded.contains("cache_lookups_total{cache=\"page\",result=\"Miss\"} 1"));
+    }
+
+    #[tokio::test]
+    async fn test_profiler_source_observes_traces() {
+        let profiler = Profiler::new(rustpress_performance::ProfilingConfig {
+            sample_rate: 1.0,
+            ..Default::default()
+        });
+        let metrics = Metrics::new();
+        metrics.register_source(Arc::new(ProfilerMetrics::new(&profiler)));
+
+        let mut timer = profiler.start_request();
+        timer.start_span("render");
+        timer.end_span();
+        timer.record_query("SELECT 1", 1500, 1);
+        profiler.record_request(timer.finish("t".into(), "GET", "/", 200));
+
+        let encoded = metrics.render().await;
+        assert!(encoded.contains("profiler_span_duration_seconds_count{span=\"render\"} 1"));
+        assert!(encoded.contains("profiler_db_query_duration_seconds_count 1"));
+    }
+
+    #[tokio::test]
+    async fn test_track_metrics_uses_route_templates() {
+        use axum::{body::Body, routing::get, Router};
+        use tower::ServiceExt;
+
+        let metrics = Arc::new(Metrics::new());
+        let app = Router::new()
+            .route("/posts/:id", get(|| async { "post" }))
+            .route("/metrics", get(|| async { "metrics" }))
+            .layer(axum::middleware::from_fn_with_state(
+                metrics.clone(),
+                track_metrics,
+            ));
+
+        for uri in ["/posts/1", "/posts/2", "/metrics", "/nope"] {
+            let request = axum::http::Request::builder()
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+
+        let encoded = metrics.encode();
+        assert!(encoded
+            .contains("http_requests_total{method=\"GET\",path=\"/posts/:id\",status=\"200\"} 2"));
+        assert!(encoded.contains("path=\"unmatched\",status=\"404\""));
+        assert!(!encoded.contains("path=\"/metrics\""));
+        assert!(!encoded.contains("/posts/1"));
+    }
 }
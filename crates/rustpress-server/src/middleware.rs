@@ -2,41 +2,61 @@
 
 use axum::{
     body::Body,
-    extract::State,
-    http::{header, Method, Request, StatusCode},
+    extract::{ConnectInfo, State},
+    http::{header, HeaderName, HeaderValue, Method, Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
+use rustpress_auth::{
+    Clock, InMemoryRateLimitStore, IpPattern, JwtManager, RateLimitConfig, RateLimiter, SystemClock,
+};
+use rustpress_content::{
+    escape_html, Content, ContentService, RedirectManager, PREVIEW_PATH_PREFIX,
+};
+use rustpress_core::config::RateLimitConfig as RateLimitSettings;
+use rustpress_core::correlation;
+use rustpress_performance::Profiler;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tower_http::cors::{Any, CorsLayer};
-use tracing::{info, warn, Span};
+use tracing::{info, warn, Instrument, Span};
 use uuid::Uuid;
 
+use crate::error::HttpError;
 use crate::state::AppState;
 
 /// Request ID middleware - adds unique ID to each request
+///
+/// A well-formed `X-Request-ID` from the client is kept; anything missing,
+/// oversized or containing unexpected characters is replaced with a fresh ID.
+/// The rest of the request runs inside a `request` span carrying the ID and
+/// with the ID set as the task's current request ID, so logs, error bodies,
+/// downstream calls and queued jobs all pick it up.
 pub async fn request_id(mut request: Request<Body>, next: Next) -> Response {
-    let request_id = request
-        .headers()
-        .get("x-request-id")
-        .and_then(|v| v.to_str().ok())
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| Uuid::now_v7().to_string());
+    let request_id = correlation::accept_or_generate(
+        request
+            .headers()
+            .get(correlation::REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok()),
+    );
 
     // Store in extensions for later use
     request
         .extensions_mut()
         .insert(RequestId(request_id.clone()));
 
-    let mut response = next.run(request).await;
+    let span = tracing::info_span!("request", request_id = %request_id);
+    let mut response = correlation::scope(request_id.clone(), next.run(request))
+        .instrument(span)
+        .await;
 
     // Add request ID to response headers
-    response.headers_mut().insert(
-        "x-request-id",
-        request_id
-            .parse()
-            .unwrap_or_else(|_| "unknown".parse().unwrap()),
-    );
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response
+            .headers_mut()
+            .insert(correlation::REQUEST_ID_HEADER, value);
+    }
 
     response
 }
@@ -45,36 +65,55 @@ pub async fn request_id(mut request: Request<Body>, next: Next) -> Response {
 #[derive(Clone, Debug)]
 pub struct RequestId(pub String);
 
+/// Record a profiler trace for each request, keyed by its request ID
+pub async fn profile_requests(
+    State(profiler): State<Arc<Profiler>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let user_agent = request
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+    let trace_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|r| r.0.clone())
+        .unwrap_or_else(correlation::generate_request_id);
+
+    let timer = profiler.start_request();
+    let response = next.run(request).await;
+
+    let mut trace = timer.finish(trace_id, &method, &path, response.status().as_u16());
+    trace.user_agent = user_agent;
+    profiler.record_request(trace);
+
+    response
+}
+
 /// Request logging middleware
 pub async fn request_logging(request: Request<Body>, next: Next) -> Response {
     let start = Instant::now();
     let method = request.method().clone();
     let uri = request.uri().clone();
-    let request_id = request
-        .extensions()
-        .get::<RequestId>()
-        .map(|r| r.0.clone())
-        .unwrap_or_else(|| "unknown".to_string());
 
-    // Create span for this request
+    // Nested in the request ID span, so the ID is already on every event
     let span = tracing::info_span!(
-        "request",
-        request_id = %request_id,
+        "http",
         method = %method,
         uri = %uri,
     );
 
-    let response = {
-        let _guard = span.enter();
-        next.run(request).await
-    };
+    let response = next.run(request).instrument(span).await;
 
     let duration = start.elapsed();
     let status = response.status();
 
     if status.is_server_error() {
         warn!(
-            request_id = %request_id,
             method = %method,
             uri = %uri,
             status = %status.as_u16(),
@@ -83,7 +122,6 @@ pub async fn request_logging(request: Request<Body>, next: Next) -> Response {
         );
     } else {
         info!(
-            request_id = %request_id,
             method = %method,
             uri = %uri,
             status = %status.as_u16(),
@@ -115,81 +153,230 @@ pub struct RateLimitInfo {
     pub reset_at: Instant,
 }
 
-/// Simple in-memory rate limiter (for production, use Redis)
+/// In-memory rate limiter for one route group
+type GroupLimiter = RateLimiter<InMemoryRateLimitStore>;
+
+/// Per-route-group rate limiting keyed by user id or client IP.
+///
+/// Requests are matched to the group with the longest path prefix and
+/// otherwise fall back to the default limit. Authenticated requests are
+/// keyed by user id; anonymous ones by client IP, where X-Forwarded-For is
+/// only honoured for hops added by a trusted proxy.
+#[derive(Clone)]
+pub struct RouteRateLimiter {
+    enabled: bool,
+    by_user: bool,
+    by_ip: bool,
+    exempt_paths: Arc<Vec<String>>,
+    /// Sorted longest prefix first
+    groups: Arc<Vec<(String, GroupLimiter)>>,
+    default: Arc<GroupLimiter>,
+    trusted_proxies: Arc<Vec<IpPattern>>,
+    jwt: Option<Arc<JwtManager>>,
+}
+
+impl RouteRateLimiter {
+    /// Build limiters from configuration using the system clock
+    pub fn from_config(config: &RateLimitSettings) -> Self {
+        Self::with_clock(config, Arc::new(SystemClock))
+    }
+
+    /// Build limiters from configuration reading time from `clock`
+    pub fn with_clock(config: &RateLimitSettings, clock: Arc<dyn Clock>) -> Self {
+        let limiter = |name: &str, requests: u32, window_secs: u64| {
+            let config = RateLimitConfig {
+                max_requests: requests,
+                window_seconds: window_secs,
+                sliding_window: false,
+                burst_size: 0,
+                key_prefix: format!("rate_limit:{}", name),
+            };
+            RateLimiter::new(InMemoryRateLimitStore::with_clock(clock.clone()), config)
+                .with_clock(clock.clone())
+        };
+
+        let mut groups: Vec<(String, GroupLimiter)> = config
+            .routes
+            .iter()
+            .map(|route| {
+                (
+                    route.path_prefix.clone(),
+                    limiter(
+                        &route.path_prefix,
+                        route.requests_per_window,
+                        route.window_secs,
+                    ),
+                )
+            })
+            .collect();
+        groups.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+
+        let trusted_proxies = config
+            .trusted_proxies
+            .iter()
+            .filter_map(|proxy| {
+                let pattern = if proxy.contains('/') {
+                    IpPattern::from_cidr_string(proxy)
+                } else {
+                    proxy.parse().ok().map(IpPattern::single)
+                };
+                if pattern.is_none() {
+                    warn!(proxy = %proxy, "Ignoring invalid trusted proxy");
+                }
+                pattern
+            })
+            .collect();
+
+        Self {
+            enabled: config.enabled,
+            by_user: config.by_user,
+            by_ip: config.by_ip,
+            exempt_paths: Arc::new(config.exempt_paths.clone()),
+            groups: Arc::new(groups),
+            default: Arc::new(limiter(
+                "default",
+                config.requests_per_window,
+                config.window_secs,
+            )),
+            trusted_proxies: Arc::new(trusted_proxies),
+            jwt: None,
+        }
+    }
+
+    /// Key authenticated requests by the user id in their access token
+    pub fn with_jwt(mut self, jwt: Arc<JwtManager>) -> Self {
+        self.jwt = Some(jwt);
+        self
+    }
+
+    /// Limiter for the route group `path` belongs to
+    fn limiter_for(&self, path: &str) -> &GroupLimiter {
+        self.groups
+            .iter()
+            .find(|(prefix, _)| path.starts_with(prefix.as_str()))
+            .map(|(_, limiter)| limiter)
+            .unwrap_or(&self.default)
+    }
+
+    fn is_trusted(&self, ip: &IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|p| p.matches(ip))
+    }
+
+    /// Resolve the client IP from the peer address and, when the peer is a
+    /// trusted proxy, the X-Forwarded-For chain read right to left
+    pub fn client_ip(&self, request: &Request<Body>) -> Option<IpAddr> {
+        let peer = request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|info| info.0.ip())?;
+
+        if !self.is_trusted(&peer) {
+            return Some(peer);
+        }
+
+        let mut client = peer;
+        let hops = request
+            .headers()
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .collect::<Vec<_>>();
+        for hop in hops.into_iter().rev() {
+            match hop.trim().parse::<IpAddr>() {
+                Ok(ip) => {
+                    client = ip;
+                    if !self.is_trusted(&ip) {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+
+        Some(client)
+    }
+
+    /// Rate limit key for the request, or `None` if it shouldn't be limited
+    fn key(&self, request: &Request<Body>) -> Option<String> {
+        if self.by_user {
+            let user = self.jwt.as_ref().and_then(|jwt| {
+                let token = request
+                    .headers()
+                    .get(header::AUTHORIZATION)?
+                    .to_str()
+                    .ok()?
+                    .strip_prefix("Bearer ")?;
+                jwt.validate_access_token(token).ok()
+            });
+            if let Some(claims) = user {
+                return Some(format!("user:{}", claims.sub));
+            }
+        }
+
+        if self.by_ip {
+            let ip = self
+                .client_ip(request)
+                .map(|ip| ip.to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            return Some(format!("ip:{}", ip));
+        }
+
+        None
+    }
+}
+
+/// Rate limiting middleware; see [`RouteRateLimiter`]
 pub async fn rate_limit(
-    State(state): State<AppState>,
+    State(limiter): State<RouteRateLimiter>,
     request: Request<Body>,
     next: Next,
 ) -> Response {
-    // Get client identifier (IP address)
-    let client_ip = request
-        .headers()
-        .get("x-forwarded-for")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|s| s.split(',').next())
-        .map(|s| s.trim().to_string())
-        .unwrap_or_else(|| "unknown".to_string());
-
-    let rate_limit = &state.config.rate_limit;
-
-    // Check if rate limiting is enabled
-    if !rate_limit.enabled {
+    if !limiter.enabled {
         return next.run(request).await;
     }
 
     // Check if path is exempt from rate limiting
     let path = request.uri().path();
-    for exempt_path in &rate_limit.exempt_paths {
-        if path.starts_with(exempt_path) {
-            return next.run(request).await;
-        }
+    if limiter
+        .exempt_paths
+        .iter()
+        .any(|exempt| path.starts_with(exempt.as_str()))
+    {
+        return next.run(request).await;
     }
 
-    // Simple rate limit check using cache
-    let cache_key = format!("rate_limit:{}", client_ip);
-    let current_count: u32 = state
-        .cache
-        .get(&cache_key)
-        .await
-        .unwrap_or(Some(0))
-        .unwrap_or(0);
+    let Some(key) = limiter.key(&request) else {
+        return next.run(request).await;
+    };
 
-    if current_count >= rate_limit.requests_per_window {
-        let mut response = Response::new(Body::from("Too many requests"));
-        *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
-        response
-            .headers_mut()
-            .insert("retry-after", "60".parse().unwrap());
-        return response;
-    }
+    let group = limiter.limiter_for(path);
+    let result = match group.check(&key).await {
+        Ok(result) => result,
+        Err(e) => {
+            // Fail open rather than rejecting traffic on a limiter fault
+            warn!(error = %e, "Rate limit check failed");
+            return next.run(request).await;
+        }
+    };
 
-    // Increment counter
-    let _ = state
-        .cache
-        .set(
-            &cache_key,
-            &(current_count + 1),
-            Some(Duration::from_secs(60)),
+    let mut response = if result.allowed {
+        next.run(request).await
+    } else {
+        info!(key = %key, path = %path, "Rate limit exceeded");
+        HttpError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            "RATE_LIMITED",
+            "Too many requests",
         )
-        .await;
-
-    let mut response = next.run(request).await;
+        .into_response()
+    };
 
-    // Add rate limit headers
-    response.headers_mut().insert(
-        "x-ratelimit-limit",
-        rate_limit.requests_per_window.to_string().parse().unwrap(),
-    );
-    response.headers_mut().insert(
-        "x-ratelimit-remaining",
-        (rate_limit
-            .requests_per_window
-            .saturating_sub(current_count)
-            .saturating_sub(1))
-        .to_string()
-        .parse()
-        .unwrap(),
-    );
+    for (name, value) in group.get_headers(&result) {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+            response.headers_mut().insert(name, value);
+        }
+    }
 
     response
 }
@@ -392,6 +579,130 @@ pub async fn body_limit(request: Request<Body>, next: Next) -> Result<Response,
     Ok(next.run(request).await)
 }
 
+/// Redirect middleware
+///
+/// Only consulted for GET/HEAD requests that would otherwise 404, so live
+/// content always wins over a stale redirect and normal page views never
+/// touch the redirects table.
+pub async fn serve_redirects(
+    State(redirects): State<Arc<RedirectManager>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if !matches!(*request.method(), Method::GET | Method::HEAD)
+        || request.uri().path().starts_with("/api/")
+    {
+        return next.run(request).await;
+    }
+
+    let path = request.uri().path().to_string();
+    let query = request.uri().query().map(str::to_string);
+    let response = next.run(request).await;
+    if response.status() != StatusCode::NOT_FOUND {
+        return response;
+    }
+
+    match redirects.lookup(&path).await {
+        Ok(Some(redirect)) => {
+            let status =
+                StatusCode::from_u16(redirect.status).unwrap_or(StatusCode::MOVED_PERMANENTLY);
+            let location = redirect_location(&redirect.target, query.as_deref());
+            match HeaderValue::from_str(&location) {
+                Ok(location) => (status, [(header::LOCATION, location)]).into_response(),
+                Err(_) => response,
+            }
+        }
+        Ok(None) => response,
+        Err(e) => {
+            warn!(path = %path, error = %e, "Redirect lookup failed");
+            response
+        }
+    }
+}
+
+/// Redirect target carrying over the request's query string, unless the
+/// target sets its own
+fn redirect_location(target: &str, query: Option<&str>) -> String {
+    match query {
+        Some(query) if !query.is_empty() && !target.contains('?') => {
+            format!("{}?{}", target, query)
+        }
+        _ => target.to_string(),
+    }
+}
+
+/// Draft preview middleware
+///
+/// Serves `/preview/{content_id}?token=...` links shared with people who have
+/// no account. Unknown tokens, or tokens for other content, are 404; expired
+/// and revoked ones are 410. Preview pages are never cached or indexed.
+pub async fn serve_previews(
+    State(content): State<Arc<ContentService>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some((content_id, token)) = preview_request(&request) else {
+        return next.run(request).await;
+    };
+
+    let no_store = (header::CACHE_CONTROL, "private, no-store");
+    let noindex = (HeaderName::from_static("x-robots-tag"), "noindex, nofollow");
+    match content.open_preview(content_id, &token).await {
+        Ok(draft) => (
+            [
+                no_store,
+                noindex,
+                (header::CONTENT_TYPE, "text/html; charset=utf-8"),
+            ],
+            preview_page(&draft),
+        )
+            .into_response(),
+        Err(e) => {
+            let status =
+                StatusCode::from_u16(e.status_code()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            if status.is_server_error() {
+                warn!(content_id = %content_id, error = %e, "Preview lookup failed");
+            }
+            (
+                status,
+                [no_store, noindex],
+                status.canonical_reason().unwrap_or_default(),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Content ID and token of a preview link request
+fn preview_request(request: &Request<Body>) -> Option<(Uuid, String)> {
+    if !matches!(*request.method(), Method::GET | Method::HEAD) {
+        return None;
+    }
+    let id = request
+        .uri()
+        .path()
+        .strip_prefix(PREVIEW_PATH_PREFIX)?
+        .trim_end_matches('/');
+    let token = request
+        .uri()
+        .query()?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))?;
+    Some((Uuid::parse_str(id).ok()?, token.to_string()))
+}
+
+/// Standalone page showing a draft
+fn preview_page(content: &Content) -> String {
+    let title = escape_html(&content.title);
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"robots\" content=\"noindex, nofollow\">\n\
+         <title>Preview: {title}</title>\n</head>\n<body>\n\
+         <article class=\"preview\">\n<h1>{title}</h1>\n{}\n</article>\n</body>\n</html>\n",
+        content.render_html()
+    )
+}
+
 /// API versioning middleware
 pub async fn api_version(request: Request<Body>, next: Next) -> Response {
     let version = request
@@ -465,9 +776,177 @@ mod tests {
         assert_eq!(id.0, "test-123");
     }
 
+    #[tokio::test]
+    async fn test_request_id_propagation() {
+        use axum::{routing::get, Router};
+        use tower::ServiceExt;
+
+        let app = Router::new()
+            .route(
+                "/",
+                get(|| async { correlation::current_request_id().unwrap_or_default() }),
+            )
+            .layer(axum::middleware::from_fn(request_id));
+
+        let request = Request::builder()
+            .uri("/")
+            .header("x-request-id", "client-abc")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()["x-request-id"], "client-abc");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"client-abc");
+
+        // Oversized IDs are replaced rather than echoed
+        let request = Request::builder()
+            .uri("/")
+            .header("x-request-id", "a".repeat(4096))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let id = response.headers()["x-request-id"].to_str().unwrap();
+        assert!(uuid::Uuid::parse_str(id).is_ok());
+    }
+
+    #[test]
+    fn test_preview_request_parsing() {
+        let id = Uuid::new_v4();
+        let request = |method: Method, uri: String| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let parsed = preview_request(&request(
+            Method::GET,
+            format!("/preview/{}?utm=x&token=abc.def", id),
+        ));
+        assert_eq!(parsed, Some((id, "abc.def".to_string())));
+
+        assert!(preview_request(&request(Method::GET, format!("/preview/{}", id))).is_none());
+        assert!(preview_request(&request(Method::GET, "/preview/nope?token=a".into())).is_none());
+        assert!(
+            preview_request(&request(Method::POST, format!("/preview/{}?token=a", id))).is_none()
+        );
+    }
+
+    #[test]
+    fn test_redirect_location_keeps_query() {
+        assert_eq!(redirect_location("/post/new", None), "/post/new");
+        assert_eq!(
+            redirect_location("/post/new", Some("utm_source=feed")),
+            "/post/new?utm_source=feed"
+        );
+        assert_eq!(
+            redirect_location("/post/new?a=1", Some("b=2")),
+            "/post/new?a=1"
+        );
+    }
+
     #[test]
     fn test_tenant_id_wrapper() {
         let id = TenantId("tenant-456".to_string());
         assert_eq!(id.0, "tenant-456");
     }
+
+    fn limiter_settings() -> RateLimitSettings {
+        RateLimitSettings {
+            requests_per_window: 10,
+            window_secs: 60,
+            trusted_proxies: vec!["10.0.0.0/8".to_string()],
+            ..RateLimitSettings::default()
+        }
+    }
+
+    fn request_from(peer: &str, path: &str, forwarded_for: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().uri(path);
+        if let Some(xff) = forwarded_for {
+            builder = builder.header("x-forwarded-for", xff);
+        }
+        let mut request = builder.body(Body::empty()).unwrap();
+        let addr: SocketAddr = format!("{}:40000", peer).parse().unwrap();
+        request.extensions_mut().insert(ConnectInfo(addr));
+        request
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_route_groups() {
+        use axum::{routing::get, Router};
+        use rustpress_auth::MockClock;
+        use tower::ServiceExt;
+
+        let clock = Arc::new(MockClock::default());
+        let limiter = RouteRateLimiter::with_clock(&limiter_settings(), clock.clone());
+        let app = Router::new()
+            .route("/api/v1/auth/login", get(|| async { "ok" }))
+            .route("/api/v1/posts", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(limiter, rate_limit));
+
+        for _ in 0..5 {
+            let response = app
+                .clone()
+                .oneshot(request_from("198.51.100.7", "/api/v1/auth/login", None))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let response = app
+            .clone()
+            .oneshot(request_from("198.51.100.7", "/api/v1/auth/login", None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["retry-after"], "60");
+        assert_eq!(response.headers()["x-ratelimit-limit"], "5");
+        assert_eq!(response.headers()["x-ratelimit-remaining"], "0");
+
+        // Other route groups have their own, looser budget
+        let response = app
+            .clone()
+            .oneshot(request_from("198.51.100.7", "/api/v1/posts", None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["x-ratelimit-limit"], "10");
+        assert_eq!(response.headers()["x-ratelimit-remaining"], "9");
+
+        clock.advance(chrono::Duration::seconds(61));
+        let response = app
+            .oneshot(request_from("198.51.100.7", "/api/v1/auth/login", None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_client_ip_trusted_proxies() {
+        let limiter = RouteRateLimiter::from_config(&limiter_settings());
+
+        // Direct connection: forwarded header can't be spoofed
+        let request = request_from("198.51.100.7", "/", Some("203.0.113.9"));
+        assert_eq!(
+            limiter.client_ip(&request),
+            Some("198.51.100.7".parse().unwrap())
+        );
+
+        // Through trusted proxies: first untrusted hop from the right
+        let request = request_from("10.0.0.1", "/", Some("1.2.3.4, 203.0.113.9, 10.0.0.2"));
+        assert_eq!(
+            limiter.client_ip(&request),
+            Some("203.0.113.9".parse().unwrap())
+        );
+
+        // Trusted proxy without a forwarded header
+        let request = request_from("10.0.0.1", "/", None);
+        assert_eq!(
+            limiter.client_ip(&request),
+            Some("10.0.0.1".parse().unwrap())
+        );
+    }
 }
@@ -253,6 +253,8 @@ fn public_routes() -> Router<AppState> {
         .route("/robots.txt", get(public_robots_handler))
         // Theme assets
         .route("/themes/:theme_id/*path", get(theme_asset_handler))
+        // Private uploads behind signed URLs
+        .route("/uploads/*path", get(signed_upload_handler))
 }
 
 /// Health check routes
@@ -372,6 +374,8 @@ fn api_v1_routes() -> Router<AppState> {
         .nest("/settings", settings_routes())
         // Storage configuration routes
         .nest("/storage", storage_routes())
+        // Database maintenance routes
+        .nest("/db", db_routes())
         // Plugin routes
         .nest("/plugins", plugin_routes())
         // Theme routes
@@ -521,6 +525,10 @@ fn media_routes() -> Router<AppState> {
             "/folders",
             get(list_media_folders_handler).post(create_media_folder_handler),
         )
+        .route("/optimize/candidates", get(optimization_candidates_handler))
+        .route("/:id/optimize", post(reoptimize_media_handler))
+        .route("/alt-text/candidates", get(alt_text_candidates_handler))
+        .route("/:id/suggest-alt-text", post(suggest_alt_text_handler))
         .route(
             "/:id",
             get(get_media_handler)
@@ -581,6 +589,11 @@ fn storage_routes() -> Router<AppState> {
         )
 }
 
+/// Database maintenance routes
+fn db_routes() -> Router<AppState> {
+    Router::new().route("/diff", get(schema_diff_handler))
+}
+
 /// Plugin routes
 fn plugin_routes() -> Router<AppState> {
     Router::new()
@@ -630,8 +643,13 @@ async fn readiness_check(State(state): State<AppState>) -> axum::response::Respo
 }
 
 async fn metrics_handler(State(state): State<AppState>) -> impl axum::response::IntoResponse {
-    // Return Prometheus metrics
-    "# Metrics endpoint - implement with prometheus-client"
+    (
+        [(
+            axum::http::header::CONTENT_TYPE,
+            crate::metrics::OPENMETRICS_CONTENT_TYPE,
+        )],
+        state.metrics.render().await,
+    )
 }
 
 // =============================================================================
@@ -1763,7 +1781,8 @@ async fn create_post_handler(
     State(state): State<AppState>,
     Json(payload): Json<CreatePostRequest>,
 ) -> HttpResult<impl axum::response::IntoResponse> {
-    let service = PostService::new(state.db().inner().clone());
+    let service =
+        PostService::new(state.db().inner().clone()).with_event_bus(state.event_bus.clone());
     let post = service.create_post(payload, user.id).await?;
     Ok(created(post))
 }
@@ -1786,7 +1805,8 @@ async fn update_post_handler(
     State(state): State<AppState>,
     Json(payload): Json<UpdatePostRequest>,
 ) -> HttpResult<impl axum::response::IntoResponse> {
-    let service = PostService::new(state.db().inner().clone());
+    let service =
+        PostService::new(state.db().inner().clone()).with_event_bus(state.event_bus.clone());
     let post = service.update_post(id, payload).await?;
     Ok(json(post))
 }
@@ -1806,7 +1826,8 @@ async fn publish_post_handler(
     PathId(id): PathId,
     State(state): State<AppState>,
 ) -> HttpResult<impl axum::response::IntoResponse> {
-    let service = PostService::new(state.db().inner().clone());
+    let service =
+        PostService::new(state.db().inner().clone()).with_event_bus(state.event_bus.clone());
     let post = service.publish_post(id).await?;
     Ok(json(post))
 }
@@ -2181,6 +2202,93 @@ async fn delete_media_handler(
     Ok(no_content())
 }
 
+/// Upload service over the media library, configured like uploads
+fn media_upload_service(state: &AppState) -> rustpress_media::UploadService {
+    let config = rustpress_media::MediaConfig {
+        storage_path: state.config.storage.local_path.display().to_string(),
+        ..Default::default()
+    };
+    rustpress_media::UploadService::new(state.db().inner().clone(), config)
+}
+
+#[derive(Debug, Deserialize)]
+struct OptimizationCandidatesQuery {
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    after: Option<Uuid>,
+    limit: Option<i64>,
+}
+
+/// List images for bulk re-optimization, keyset-paginated by id
+async fn optimization_candidates_handler(
+    user: AuthUser,
+    Query(query): Query<OptimizationCandidatesQuery>,
+    State(state): State<AppState>,
+) -> HttpResult<impl axum::response::IntoResponse> {
+    if !user.is_admin() {
+        return Err(rustpress_core::error::Error::forbidden("optimize media").into());
+    }
+
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+    let media = media_upload_service(&state)
+        .optimization_candidates(query.since, query.after, limit)
+        .await?;
+    Ok(json(media))
+}
+
+/// Generate missing format variants for one image
+async fn reoptimize_media_handler(
+    user: AuthUser,
+    PathId(id): PathId,
+    State(state): State<AppState>,
+    Json(options): Json<rustpress_media::ReoptimizeOptions>,
+) -> HttpResult<impl axum::response::IntoResponse> {
+    if !user.is_admin() {
+        return Err(rustpress_core::error::Error::forbidden("optimize media").into());
+    }
+
+    let report = media_upload_service(&state)
+        .reoptimize(id, &options)
+        .await?;
+    Ok(json(report))
+}
+
+#[derive(Debug, Deserialize)]
+struct AltTextCandidatesQuery {
+    after: Option<Uuid>,
+    limit: Option<i64>,
+}
+
+/// List images still needing an alt text suggestion, keyset-paginated by id
+async fn alt_text_candidates_handler(
+    user: AuthUser,
+    Query(query): Query<AltTextCandidatesQuery>,
+    State(state): State<AppState>,
+) -> HttpResult<impl axum::response::IntoResponse> {
+    if !user.is_admin() {
+        return Err(rustpress_core::error::Error::forbidden("suggest alt text").into());
+    }
+
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+    let media = media_upload_service(&state)
+        .alt_text_candidates(query.after, limit)
+        .await?;
+    Ok(json(media))
+}
+
+/// Generate and store an alt text suggestion for one image
+async fn suggest_alt_text_handler(
+    user: AuthUser,
+    PathId(id): PathId,
+    State(state): State<AppState>,
+) -> HttpResult<impl axum::response::IntoResponse> {
+    if !user.is_admin() {
+        return Err(rustpress_core::error::Error::forbidden("suggest alt text").into());
+    }
+
+    let suggestion = media_upload_service(&state).suggest_alt_text(id).await?;
+    Ok(json(suggestion))
+}
+
 /// List media folders
 async fn list_media_folders_handler(
     State(state): State<AppState>,
@@ -2644,6 +2752,38 @@ async fn cancel_migration_handler(
     }
 }
 
+// =============================================================================
+// Database Handlers
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct SchemaDiffQuery {
+    /// Include corrective SQL in the response
+    #[serde(default)]
+    fix: bool,
+}
+
+/// Compare the live schema against the migrations' end state
+async fn schema_diff_handler(
+    user: AuthUser,
+    Query(query): Query<SchemaDiffQuery>,
+    State(state): State<AppState>,
+) -> HttpResult<impl axum::response::IntoResponse> {
+    if !user.is_admin() {
+        return Err(rustpress_core::error::Error::forbidden("inspect database schema").into());
+    }
+
+    let migrator = rustpress_database::Migrator::new()
+        .with_migrations(rustpress_database::migration::create_initial_migrations());
+    let diff = migrator.diff(state.db().inner()).await?;
+
+    Ok(json(serde_json::json!({
+        "structural": diff.has_structural(),
+        "fix_sql": query.fix.then(|| diff.fix_sql()),
+        "drift": diff.drift,
+    })))
+}
+
 // =============================================================================
 // Plugin Handlers (Placeholder implementations)
 // =============================================================================
@@ -3404,6 +3544,77 @@ async fn theme_asset_handler(
     }
 }
 
+/// Query parameters of a signed upload URL
+#[derive(Debug, Deserialize)]
+struct SignedUploadQuery {
+    expires: Option<i64>,
+    signature: Option<String>,
+}
+
+/// Serve a stored file after checking its URL signature
+async fn signed_upload_handler(
+    State(state): State<AppState>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+    Query(query): Query<SignedUploadQuery>,
+) -> Response {
+    use rustpress_storage::storage::MimeDetector;
+    use rustpress_storage::{Retrieval, SignatureError};
+
+    let (Some(expires), Some(signature)) = (query.expires, query.signature.as_deref()) else {
+        return (axum::http::StatusCode::FORBIDDEN, "Missing URL signature").into_response();
+    };
+
+    match state.storage().verify_signed_url(&path, expires, signature) {
+        Ok(()) => {}
+        Err(SignatureError::NotConfigured) => {
+            return (axum::http::StatusCode::NOT_FOUND, "File not found").into_response();
+        }
+        Err(e) => {
+            return (axum::http::StatusCode::FORBIDDEN, e.to_string()).into_response();
+        }
+    }
+
+    // A valid signature only vouches for the path it was issued for
+    if path.split('/').any(|segment| segment == "..") {
+        return (axum::http::StatusCode::FORBIDDEN, "Access denied").into_response();
+    }
+
+    match state.storage().retrieve(&path).await {
+        Ok(Retrieval::Ready(contents)) => {
+            let content_type =
+                MimeDetector::from_filename(&path).unwrap_or("application/octet-stream");
+            (
+                [
+                    (header::CONTENT_TYPE, content_type),
+                    (header::CACHE_CONTROL, "private, no-store"),
+                ],
+                contents,
+            )
+                .into_response()
+        }
+        Ok(Retrieval::Restoring { ready_at }) => {
+            // Archived file: tell the client to come back instead of failing
+            let retry_after = ready_at
+                .map(|at| (at - chrono::Utc::now()).num_seconds().max(60))
+                .unwrap_or(3600);
+            (
+                axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                [
+                    (header::RETRY_AFTER, retry_after.to_string()),
+                    (header::CACHE_CONTROL, "no-store".to_string()),
+                ],
+                Json(serde_json::json!({
+                    "status": "restoring",
+                    "message": "This file is archived and is being restored",
+                    "ready_at": ready_at,
+                })),
+            )
+                .into_response()
+        }
+        Err(_) => (axum::http::StatusCode::NOT_FOUND, "File not found").into_response(),
+    }
+}
+
 // =============================================================================
 // Search Routes and Handlers
 // =============================================================================
@@ -9,13 +9,16 @@
 use axum::{
     body::{Body, Bytes},
     extract::{Request, State},
-    http::{header, Method, StatusCode},
+    http::{header, Method},
     middleware::Next,
     response::{IntoResponse, Response},
 };
+use http_body_util::Limited;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+use crate::error::HttpError;
+
 /// Content security configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContentSecurityConfig {
@@ -33,6 +36,13 @@ pub struct ContentSecurityConfig {
     pub route_body_limits: Vec<(String, usize)>,
     /// Default maximum body size (10MB)
     pub default_max_body_size: usize,
+    /// Content-Types allowed per route prefix, overriding `allowed_content_types`
+    #[serde(default)]
+    pub route_content_types: Vec<(String, Vec<String>)>,
+    /// Upload routes that stream to storage; multipart and octet-stream bodies
+    /// on these prefixes are exempt from the in-memory body cap
+    #[serde(default)]
+    pub streaming_routes: Vec<String>,
     /// Allowed file upload MIME types
     pub allowed_upload_types: Vec<String>,
     /// Enable magic byte validation for uploads
@@ -57,6 +67,11 @@ impl Default for ContentSecurityConfig {
                 ("/api/".to_string(), 10 * 1024 * 1024),              // 10MB for API
             ],
             default_max_body_size: 10 * 1024 * 1024, // 10MB
+            route_content_types: Vec::new(),
+            streaming_routes: vec![
+                "/api/v1/media".to_string(),
+                "/api/v1/themes/upload".to_string(),
+            ],
             allowed_upload_types: vec![
                 "image/jpeg".to_string(),
                 "image/png".to_string(),
@@ -99,15 +114,27 @@ impl ContentSecurityMiddleware {
 
     /// Validate Content-Type header
     pub fn validate_content_type(&self, content_type: Option<&str>) -> bool {
+        self.validate_route_content_type("", content_type)
+    }
+
+    /// Validate Content-Type header against the allowlist for `path`
+    pub fn validate_route_content_type(&self, path: &str, content_type: Option<&str>) -> bool {
         if !self.config.enforce_content_type {
             return true;
         }
 
+        let allowed_types = self
+            .config
+            .route_content_types
+            .iter()
+            .find(|(pattern, _)| !path.is_empty() && path.starts_with(pattern))
+            .map(|(_, types)| types)
+            .unwrap_or(&self.config.allowed_content_types);
+
         match content_type {
             Some(ct) => {
                 let ct_lower = ct.to_lowercase();
-                self.config
-                    .allowed_content_types
+                allowed_types
                     .iter()
                     .any(|allowed| ct_lower.starts_with(allowed))
             }
@@ -115,6 +142,20 @@ impl ContentSecurityMiddleware {
         }
     }
 
+    /// Whether the body streams to storage instead of being buffered
+    pub fn is_streaming_upload(&self, path: &str, content_type: Option<&str>) -> bool {
+        let streamable = content_type.is_some_and(|ct| {
+            let ct = ct.to_lowercase();
+            ct.starts_with("multipart/form-data") || ct.starts_with("application/octet-stream")
+        });
+        streamable
+            && self
+                .config
+                .streaming_routes
+                .iter()
+                .any(|pattern| path.starts_with(pattern))
+    }
+
     /// Validate JSON depth
     pub fn validate_json_depth(
         &self,
@@ -333,12 +374,16 @@ impl std::fmt::Display for ContentSecurityError {
 
 impl IntoResponse for ContentSecurityError {
     fn into_response(self) -> Response {
-        let status = match &self {
-            ContentSecurityError::BodyTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
-            _ => StatusCode::BAD_REQUEST,
-        };
-
-        (status, self.to_string()).into_response()
+        let message = self.to_string();
+        match self {
+            ContentSecurityError::BodyTooLarge { .. } => HttpError::payload_too_large(message),
+            ContentSecurityError::InvalidContentType { .. }
+            | ContentSecurityError::InvalidUploadType { .. } => {
+                HttpError::unsupported_media_type(message)
+            }
+            _ => HttpError::bad_request(message),
+        }
+        .into_response()
     }
 }
 
@@ -362,7 +407,7 @@ pub async fn content_security(
         .get(header::CONTENT_TYPE)
         .and_then(|v| v.to_str().ok());
 
-    if !security.validate_content_type(content_type) {
+    if !security.validate_route_content_type(&path, content_type) {
         tracing::warn!(
             content_type = ?content_type,
             path = %path,
@@ -374,6 +419,11 @@ pub async fn content_security(
         .into_response();
     }
 
+    // Streaming uploads are capped by the media crate as they're written out
+    if security.is_streaming_upload(&path, content_type) {
+        return next.run(request).await;
+    }
+
     // Check body size limit from Content-Length header
     let body_limit = security.get_body_limit(&path);
     if let Some(content_length) = request
@@ -397,6 +447,11 @@ pub async fn content_security(
         }
     }
 
+    // Cap bodies without (or with a lying) Content-Length while they're read;
+    // extractors see a length limit error and reject with 413
+    let (parts, body) = request.into_parts();
+    let request = Request::from_parts(parts, Body::new(Limited::new(body, body_limit)));
+
     next.run(request).await
 }
 
@@ -414,6 +469,7 @@ pub fn validate_json_body(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use axum::http::StatusCode;
     use serde_json::json;
 
     #[test]
@@ -497,4 +553,115 @@ mod tests {
         assert_eq!(middleware.get_body_limit("/api/users"), 1024 * 1024);
         assert_eq!(middleware.get_body_limit("/other"), 512 * 1024);
     }
+
+    #[test]
+    fn test_route_content_types_and_streaming() {
+        let config = ContentSecurityConfig {
+            route_content_types: vec![(
+                "/api/v1/auth".to_string(),
+                vec!["application/json".to_string()],
+            )],
+            ..Default::default()
+        };
+        let middleware = ContentSecurityMiddleware::new(config);
+
+        assert!(!middleware.validate_route_content_type("/api/v1/auth/login", Some("text/plain")));
+        assert!(middleware.validate_route_content_type("/api/v1/posts", Some("text/plain")));
+
+        assert!(middleware
+            .is_streaming_upload("/api/v1/media", Some("multipart/form-data; boundary=x")));
+        assert!(!middleware.is_streaming_upload("/api/v1/media/abc", Some("application/json")));
+        assert!(!middleware.is_streaming_upload("/api/v1/posts", Some("multipart/form-data")));
+    }
+
+    #[tokio::test]
+    async fn test_content_security_rejections() {
+        use crate::extract::ValidatedJson;
+        use axum::{routing::post, Router};
+        use tower::ServiceExt;
+
+        #[derive(Deserialize)]
+        struct Payload {
+            #[allow(dead_code)]
+            title: String,
+        }
+
+        impl validator::Validate for Payload {
+            fn validate(&self) -> Result<(), validator::ValidationErrors> {
+                Ok(())
+            }
+        }
+
+        let config = ContentSecurityConfig {
+            route_body_limits: vec![("/api/".to_string(), 64)],
+            ..Default::default()
+        };
+        let app = Router::new()
+            .route(
+                "/api/v1/posts",
+                post(|ValidatedJson(_): ValidatedJson<Payload>| async { "ok" }),
+            )
+            .route("/api/v1/media", post(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                ContentSecurityMiddleware::new(config),
+                content_security,
+            ));
+
+        let send = |path: &str, content_type: &str, body: String, length: bool| {
+            let mut builder = Request::builder()
+                .method(Method::POST)
+                .uri(path)
+                .header(header::CONTENT_TYPE, content_type);
+            if length {
+                builder = builder.header(header::CONTENT_LENGTH, body.len());
+            }
+            app.clone().oneshot(builder.body(Body::from(body)).unwrap())
+        };
+        let error_code = |response: Response| async move {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            json["code"].as_str().unwrap().to_string()
+        };
+
+        let small = json!({ "title": "Hello" }).to_string();
+        let large = json!({ "title": "x".repeat(200) }).to_string();
+
+        let response = send("/api/v1/posts", "application/json", small.clone(), true)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = send("/api/v1/posts", "text/html", small, true)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(error_code(response).await, "UNSUPPORTED_MEDIA_TYPE");
+
+        // Rejected from Content-Length before the body is read
+        let response = send("/api/v1/posts", "application/json", large.clone(), true)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(error_code(response).await, "PAYLOAD_TOO_LARGE");
+
+        // Without Content-Length the cap applies while the body is read
+        let response = send("/api/v1/posts", "application/json", large.clone(), false)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(error_code(response).await, "PAYLOAD_TOO_LARGE");
+
+        // Streaming uploads are exempt
+        let response = send(
+            "/api/v1/media",
+            "multipart/form-data; boundary=x",
+            large,
+            true,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
 }
@@ -5,6 +5,7 @@
 //! - Anomaly detection
 //! - Session correlation
 //! - Bot detection augmentation
+//! - Device fingerprints for binding refresh tokens
 
 use axum::{
     body::Body,
@@ -15,6 +16,7 @@ use axum::{
 };
 use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
+use rustpress_auth::DeviceFingerprint;
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
@@ -131,6 +133,20 @@ impl RequestFingerprint {
     }
 }
 
+/// Coarse device fingerprint of the client sending `request`, for binding
+/// refresh token families. Unlike [`RequestFingerprint`] it ignores header
+/// order and versions so it stays stable across requests from one device.
+pub fn device_fingerprint(request: &Request<Body>, client_ip: Option<&str>) -> DeviceFingerprint {
+    let headers = request.headers();
+    let header = |name| headers.get(name).and_then(|v| v.to_str().ok());
+
+    DeviceFingerprint::new(
+        header(header::USER_AGENT),
+        header(header::ACCEPT_LANGUAGE),
+        client_ip,
+    )
+}
+
 /// Client profile built from multiple fingerprints
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClientProfile {
@@ -461,6 +477,24 @@ mod tests {
         assert_eq!(fp1.similarity(&fp2), 1.0);
     }
 
+    #[test]
+    fn test_device_fingerprint_ignores_header_order() {
+        let request1 = create_request(vec![
+            ("user-agent", "Mozilla/5.0 Chrome/120.0"),
+            ("accept-language", "en-US"),
+            ("accept", "text/html"),
+        ]);
+        let request2 = create_request(vec![
+            ("accept", "application/json"),
+            ("accept-language", "en-US"),
+            ("user-agent", "Mozilla/5.0 Chrome/121.0"),
+        ]);
+
+        let fp1 = device_fingerprint(&request1, Some("192.168.1.1"));
+        let fp2 = device_fingerprint(&request2, Some("192.168.7.20"));
+        assert_eq!(fp1.hash, fp2.hash);
+    }
+
     #[test]
     fn test_client_profile() {
         let mut profile = ClientProfile::new("test-client".to_string());
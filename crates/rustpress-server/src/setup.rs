@@ -3,6 +3,11 @@
 //! Provides a web-based setup wizard for initial RustPress configuration.
 //! This module handles database connection setup, schema installation,
 //! and initial configuration when RustPress is first started.
+//!
+//! Progress is saved next to the config file after each step, so an
+//! interrupted setup resumes where it left off. The database password is
+//! kept there (it ends up in the config file anyway) but never sent back to
+//! the browser; the admin password is never saved.
 
 use argon2::password_hash::rand_core::{OsRng, RngCore};
 use axum::{
@@ -11,30 +16,178 @@ use axum::{
     routing::{get, post},
     Router,
 };
+use chrono::{DateTime, Utc};
+use rustpress_auth::{PasswordRules, PasswordValidator};
 use serde::{Deserialize, Serialize};
-use sqlx::postgres::PgPoolOptions;
+use sqlx::postgres::{PgConnectOptions, PgConnection, PgPoolOptions};
+use sqlx::Connection;
 use std::fs;
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::sync::RwLock;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+
+/// File next to the config file holding wizard progress
+const PROGRESS_FILE: &str = "setup-progress.json";
+
+/// Database used to check privileges and create the target database
+const MAINTENANCE_DATABASE: &str = "postgres";
+
+/// Time limit for a single connection attempt
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Setup wizard state
 #[derive(Clone)]
 pub struct SetupState {
     pub config_path: PathBuf,
     pub setup_complete: Arc<RwLock<bool>>,
+    pub progress: Arc<RwLock<SetupProgress>>,
 }
 
 impl SetupState {
     pub fn new(config_path: PathBuf) -> Self {
+        let progress = SetupProgress::load(&config_path);
         Self {
             config_path,
             setup_complete: Arc::new(RwLock::new(false)),
+            progress: Arc::new(RwLock::new(progress)),
+        }
+    }
+
+    /// Apply `update` to the progress and persist it
+    async fn update_progress(&self, update: impl FnOnce(&mut SetupProgress)) {
+        let mut progress = self.progress.write().await;
+        update(&mut progress);
+        progress.updated_at = Some(Utc::now());
+        if let Err(e) = progress.save(&self.config_path) {
+            warn!("Failed to save setup progress: {}", e);
+        }
+    }
+
+    /// Use the saved password when the browser resumed without one
+    async fn fill_saved_password(&self, req: &mut TestConnectionRequest) {
+        if !req.password.is_empty() {
+            return;
+        }
+        let progress = self.progress.read().await;
+        if let Some(saved) = &progress.database {
+            if saved.same_target(req) {
+                req.password = saved.password.clone().unwrap_or_default();
+            }
+        }
+    }
+}
+
+/// Wizard steps, in order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SetupStep {
+    /// Database connection
+    #[default]
+    Database,
+    /// Admin account and site details
+    Account,
+    /// Installed
+    Complete,
+}
+
+/// Wizard progress persisted between runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SetupProgress {
+    pub step: SetupStep,
+    pub database: Option<SavedDatabase>,
+    pub admin_email: Option<String>,
+    pub admin_username: Option<String>,
+    pub site_title: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Database connection details saved by the wizard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedDatabase {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub database: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+impl SavedDatabase {
+    fn same_target(&self, req: &TestConnectionRequest) -> bool {
+        self.host == req.host
+            && self.port == req.port
+            && self.username == req.username
+            && self.database == req.database
+    }
+}
+
+impl From<&TestConnectionRequest> for SavedDatabase {
+    fn from(req: &TestConnectionRequest) -> Self {
+        Self {
+            host: req.host.clone(),
+            port: req.port,
+            username: req.username.clone(),
+            database: req.database.clone(),
+            password: Some(req.password.clone()),
+        }
+    }
+}
+
+impl SetupProgress {
+    /// Progress file for the config file at `config_path`
+    pub fn path_for(config_path: &Path) -> PathBuf {
+        config_path.with_file_name(PROGRESS_FILE)
+    }
+
+    /// Saved progress, or a fresh start if there is none or it is unreadable
+    pub fn load(config_path: &Path) -> Self {
+        let path = Self::path_for(config_path);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => return Self::default(),
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!(path = %path.display(), "Ignoring unreadable setup progress: {}", e);
+            Self::default()
+        })
+    }
+
+    /// Write the progress file, readable by the owner only
+    pub fn save(&self, config_path: &Path) -> std::io::Result<()> {
+        let path = Self::path_for(config_path);
+        let temp = path.with_extension("json.tmp");
+        let content = serde_json::to_vec_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        fs::write(&temp, content)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&temp, fs::Permissions::from_mode(0o600))?;
+        }
+        fs::rename(temp, path)
+    }
+
+    /// Remove the progress file once setup is finished
+    pub fn clear(config_path: &Path) {
+        let path = Self::path_for(config_path);
+        if let Err(e) = fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!(path = %path.display(), "Failed to remove setup progress: {}", e);
+            }
+        }
+    }
+
+    /// Progress as shown to the browser, without the database password
+    fn public_view(&self) -> Self {
+        let mut view = self.clone();
+        if let Some(database) = &mut view.database {
+            database.password = None;
         }
+        view
     }
 }
 
@@ -58,35 +211,288 @@ impl DatabaseConfig {
 }
 
 /// Request to test database connection
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct TestConnectionRequest {
     pub host: String,
     pub port: u16,
     pub username: String,
+    #[serde(default)]
     pub password: String,
     pub database: String,
 }
 
+impl TestConnectionRequest {
+    fn connect_options(&self, database: &str) -> PgConnectOptions {
+        PgConnectOptions::new()
+            .host(&self.host)
+            .port(self.port)
+            .username(&self.username)
+            .password(&self.password)
+            .database(database)
+    }
+
+    fn database_url(&self) -> String {
+        format!(
+            "postgres://{}:{}@{}:{}/{}",
+            urlencoding::encode(&self.username),
+            urlencoding::encode(&self.password),
+            self.host,
+            self.port,
+            self.database
+        )
+    }
+}
+
 /// Response from connection test
 #[derive(Debug, Serialize)]
 pub struct TestConnectionResponse {
     pub success: bool,
     pub message: String,
     pub details: Option<String>,
+    pub diagnosis: ConnectionDiagnosis,
+}
+
+impl From<ConnectionDiagnosis> for TestConnectionResponse {
+    fn from(diagnosis: ConnectionDiagnosis) -> Self {
+        Self {
+            success: diagnosis.is_connected(),
+            message: diagnosis.message(),
+            details: diagnosis.details(),
+            diagnosis,
+        }
+    }
+}
+
+/// Why a database connection did or did not work
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConnectionDiagnosis {
+    Connected {
+        server_version: String,
+    },
+    /// Wrong username or password, or rejected by `pg_hba.conf`
+    AuthenticationFailed {
+        error: String,
+    },
+    /// Nothing answered at the host and port
+    HostUnreachable {
+        error: String,
+    },
+    /// The server is up but the database does not exist
+    DatabaseMissing {
+        database: String,
+        can_create: bool,
+    },
+    /// The user may not connect to or create the database
+    PermissionDenied {
+        error: String,
+    },
+    /// The server did not answer in time
+    Timeout,
+    /// Invalid database name given for creation
+    InvalidDatabaseName {
+        database: String,
+    },
+    Other {
+        error: String,
+    },
+}
+
+impl ConnectionDiagnosis {
+    pub fn is_connected(&self) -> bool {
+        matches!(self, Self::Connected { .. })
+    }
+
+    /// Summary for the wizard
+    pub fn message(&self) -> String {
+        match self {
+            Self::Connected { .. } => "Connection successful!".to_string(),
+            Self::AuthenticationFailed { .. } => {
+                "Authentication failed: check the username and password".to_string()
+            }
+            Self::HostUnreachable { .. } => {
+                "Could not reach the database server: check the host and port".to_string()
+            }
+            Self::DatabaseMissing {
+                database,
+                can_create: true,
+            } => format!(
+                "Database \"{}\" does not exist yet, but it can be created for you",
+                database
+            ),
+            Self::DatabaseMissing { database, .. } => format!(
+                "Database \"{}\" does not exist and this user is not allowed to create it",
+                database
+            ),
+            Self::PermissionDenied { .. } => {
+                "This user is not allowed to use the database".to_string()
+            }
+            Self::Timeout => "The database server did not respond in time".to_string(),
+            Self::InvalidDatabaseName { .. } => {
+                "Database names may only contain letters, digits and underscores".to_string()
+            }
+            Self::Other { .. } => "Connection failed".to_string(),
+        }
+    }
+
+    fn details(&self) -> Option<String> {
+        match self {
+            Self::Connected { server_version } => Some(format!("PostgreSQL {}", server_version)),
+            Self::AuthenticationFailed { error }
+            | Self::HostUnreachable { error }
+            | Self::PermissionDenied { error }
+            | Self::Other { error } => Some(error.clone()),
+            _ => None,
+        }
+    }
+
+    /// Classify a connection error
+    pub fn from_error(err: &sqlx::Error, database: &str) -> Self {
+        match err {
+            sqlx::Error::Database(db) => Self::from_sqlstate(
+                db.code().as_deref().unwrap_or_default(),
+                db.message(),
+                database,
+            ),
+            sqlx::Error::Io(e) => Self::HostUnreachable {
+                error: e.to_string(),
+            },
+            sqlx::Error::PoolTimedOut => Self::Timeout,
+            sqlx::Error::Tls(e) => Self::Other {
+                error: format!("TLS error: {}", e),
+            },
+            other => Self::Other {
+                error: other.to_string(),
+            },
+        }
+    }
+
+    /// Classify a server error by its SQLSTATE code
+    fn from_sqlstate(code: &str, message: &str, database: &str) -> Self {
+        match code {
+            // invalid_password, invalid_authorization_specification
+            "28P01" | "28000" => Self::AuthenticationFailed {
+                error: message.to_string(),
+            },
+            // invalid_catalog_name
+            "3D000" => Self::DatabaseMissing {
+                database: database.to_string(),
+                can_create: false,
+            },
+            // insufficient_privilege
+            "42501" => Self::PermissionDenied {
+                error: message.to_string(),
+            },
+            _ => Self::Other {
+                error: message.to_string(),
+            },
+        }
+    }
+}
+
+/// Open a single connection to `database`
+async fn connect(
+    req: &TestConnectionRequest,
+    database: &str,
+) -> Result<PgConnection, ConnectionDiagnosis> {
+    match tokio::time::timeout(
+        CONNECT_TIMEOUT,
+        PgConnection::connect_with(&req.connect_options(database)),
+    )
+    .await
+    {
+        Ok(Ok(conn)) => Ok(conn),
+        Ok(Err(e)) => Err(ConnectionDiagnosis::from_error(&e, database)),
+        Err(_) => Err(ConnectionDiagnosis::Timeout),
+    }
+}
+
+/// Test the connection described by `req`
+pub async fn diagnose_connection(req: &TestConnectionRequest) -> ConnectionDiagnosis {
+    let mut conn = match connect(req, &req.database).await {
+        Ok(conn) => conn,
+        Err(ConnectionDiagnosis::DatabaseMissing { database, .. }) => {
+            return ConnectionDiagnosis::DatabaseMissing {
+                database,
+                can_create: can_create_database(req).await,
+            };
+        }
+        Err(diagnosis) => return diagnosis,
+    };
+
+    let diagnosis = match sqlx::query_scalar::<_, String>("SHOW server_version")
+        .fetch_one(&mut conn)
+        .await
+    {
+        Ok(server_version) => ConnectionDiagnosis::Connected { server_version },
+        Err(e) => ConnectionDiagnosis::from_error(&e, &req.database),
+    };
+    let _ = conn.close().await;
+    diagnosis
+}
+
+/// Whether the user may create databases
+async fn can_create_database(req: &TestConnectionRequest) -> bool {
+    let Ok(mut conn) = connect(req, MAINTENANCE_DATABASE).await else {
+        return false;
+    };
+    let allowed = sqlx::query_scalar::<_, bool>(
+        "SELECT rolcreatedb OR rolsuper FROM pg_roles WHERE rolname = current_user",
+    )
+    .fetch_one(&mut conn)
+    .await
+    .unwrap_or(false);
+    let _ = conn.close().await;
+    allowed
+}
+
+/// Check a database name is safe to create: PostgreSQL's 63 byte limit and
+/// a conservative character set
+fn is_valid_database_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 63
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Create the database named in `req`; an existing database is fine
+pub async fn create_database(req: &TestConnectionRequest) -> Result<(), ConnectionDiagnosis> {
+    if !is_valid_database_name(&req.database) {
+        return Err(ConnectionDiagnosis::InvalidDatabaseName {
+            database: req.database.clone(),
+        });
+    }
+
+    let mut conn = connect(req, MAINTENANCE_DATABASE).await?;
+    // Identifiers cannot be bound as parameters; the name was checked above
+    let result = sqlx::query(&format!("CREATE DATABASE \"{}\"", req.database))
+        .execute(&mut conn)
+        .await;
+    let _ = conn.close().await;
+
+    match result {
+        Ok(_) => {
+            info!(database = %req.database, "Created database");
+            Ok(())
+        }
+        // duplicate_database
+        Err(sqlx::Error::Database(db)) if db.code().as_deref() == Some("42P04") => Ok(()),
+        Err(e) => Err(ConnectionDiagnosis::from_error(&e, &req.database)),
+    }
 }
 
 /// Request to install schema
 #[derive(Debug, Deserialize)]
 pub struct InstallSchemaRequest {
-    pub host: String,
-    pub port: u16,
-    pub username: String,
-    pub password: String,
-    pub database: String,
+    #[serde(flatten)]
+    pub connection: TestConnectionRequest,
     pub admin_email: String,
     pub admin_username: String,
     pub admin_password: String,
     pub site_title: String,
+    /// Create the database first if it does not exist
+    #[serde(default)]
+    pub create_database: bool,
 }
 
 /// Response from schema installation
@@ -94,6 +500,45 @@ pub struct InstallSchemaRequest {
 pub struct InstallSchemaResponse {
     pub success: bool,
     pub message: String,
+    /// Step to send the user back to when the failure is theirs to fix
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<SetupStep>,
+}
+
+impl InstallSchemaResponse {
+    fn failed(message: impl Into<String>, step: Option<SetupStep>) -> Json<Self> {
+        Json(Self {
+            success: false,
+            message: message.into(),
+            step,
+        })
+    }
+}
+
+/// Check the admin account against the password policy before installing
+fn validate_admin_account(req: &InstallSchemaRequest) -> Result<(), String> {
+    if req.admin_username.trim().is_empty() {
+        return Err("Admin username is required".to_string());
+    }
+    if !req.admin_email.contains('@') {
+        return Err("Admin email address is invalid".to_string());
+    }
+    PasswordValidator::new(PasswordRules::default())
+        .validate(&req.admin_password)
+        .map_err(|e| e.to_string())?;
+    let password = req.admin_password.to_lowercase();
+    if password.contains(&req.admin_username.trim().to_lowercase()) {
+        return Err("Password must not contain the username".to_string());
+    }
+    Ok(())
+}
+
+/// Progress update from the wizard; absent fields are left as they are
+#[derive(Debug, Default, Deserialize)]
+pub struct SaveProgressRequest {
+    pub admin_email: Option<String>,
+    pub admin_username: Option<String>,
+    pub site_title: Option<String>,
 }
 
 /// RustPress configuration file structure (nested for compatibility)
@@ -155,8 +600,10 @@ pub fn create_setup_router(state: SetupState) -> Router {
         .route("/", get(setup_page))
         .route("/setup", get(setup_page))
         .route("/api/setup/test-connection", post(test_connection))
+        .route("/api/setup/create-database", post(create_database_handler))
         .route("/api/setup/install", post(install_schema))
         .route("/api/setup/status", get(setup_status))
+        .route("/api/setup/progress", get(get_progress).put(save_progress))
         .with_state(state)
 }
 
@@ -173,68 +620,98 @@ async fn setup_status(State(state): State<SetupState>) -> Json<serde_json::Value
     }))
 }
 
-/// Test database connection
-async fn test_connection(Json(req): Json<TestConnectionRequest>) -> Json<TestConnectionResponse> {
-    let database_url = format!(
-        "postgres://{}:{}@{}:{}/{}",
-        req.username, req.password, req.host, req.port, req.database
-    );
+/// Saved progress, for resuming the wizard
+async fn get_progress(State(state): State<SetupState>) -> Json<serde_json::Value> {
+    let progress = state.progress.read().await;
+    let has_database_password = progress
+        .database
+        .as_ref()
+        .and_then(|db| db.password.as_ref())
+        .is_some();
+    Json(serde_json::json!({
+        "progress": progress.public_view(),
+        "has_database_password": has_database_password,
+    }))
+}
+
+/// Save account and site details as they are entered
+async fn save_progress(
+    State(state): State<SetupState>,
+    Json(req): Json<SaveProgressRequest>,
+) -> Json<serde_json::Value> {
+    state
+        .update_progress(|progress| {
+            if req.admin_email.is_some() {
+                progress.admin_email = req.admin_email;
+            }
+            if req.admin_username.is_some() {
+                progress.admin_username = req.admin_username;
+            }
+            if req.site_title.is_some() {
+                progress.site_title = req.site_title;
+            }
+        })
+        .await;
+    Json(serde_json::json!({ "success": true }))
+}
 
+/// Test database connection
+async fn test_connection(
+    State(state): State<SetupState>,
+    Json(mut req): Json<TestConnectionRequest>,
+) -> Json<TestConnectionResponse> {
+    state.fill_saved_password(&mut req).await;
     info!(
         "Testing database connection to {}:{}/{}",
         req.host, req.port, req.database
     );
 
-    match PgPoolOptions::new()
-        .max_connections(1)
-        .acquire_timeout(Duration::from_secs(5))
-        .connect(&database_url)
-        .await
-    {
-        Ok(pool) => {
-            // Try a simple query to verify connection
-            match sqlx::query("SELECT 1").execute(&pool).await {
-                Ok(_) => {
-                    info!("Database connection successful");
-                    Json(TestConnectionResponse {
-                        success: true,
-                        message: "Connection successful!".to_string(),
-                        details: Some(format!(
-                            "Connected to PostgreSQL at {}:{}",
-                            req.host, req.port
-                        )),
-                    })
-                }
-                Err(e) => {
-                    error!("Database query failed: {}", e);
-                    Json(TestConnectionResponse {
-                        success: false,
-                        message: "Connection failed".to_string(),
-                        details: Some(format!("Query error: {}", e)),
-                    })
-                }
-            }
-        }
-        Err(e) => {
-            error!("Database connection failed: {}", e);
-            Json(TestConnectionResponse {
-                success: false,
-                message: "Connection failed".to_string(),
-                details: Some(format!("Error: {}", e)),
+    let diagnosis = diagnose_connection(&req).await;
+    if diagnosis.is_connected() {
+        info!("Database connection successful");
+        state
+            .update_progress(|progress| {
+                progress.database = Some(SavedDatabase::from(&req));
+                progress.step = SetupStep::Account;
             })
-        }
+            .await;
+    } else {
+        error!(?diagnosis, "Database connection failed");
     }
+
+    Json(diagnosis.into())
+}
+
+/// Create the database, then test the connection to it
+async fn create_database_handler(
+    State(state): State<SetupState>,
+    Json(mut req): Json<TestConnectionRequest>,
+) -> Json<TestConnectionResponse> {
+    state.fill_saved_password(&mut req).await;
+    if let Err(diagnosis) = create_database(&req).await {
+        error!(?diagnosis, "Failed to create database");
+        return Json(diagnosis.into());
+    }
+    test_connection(State(state), Json(req)).await
 }
 
 /// Install the database schema
 async fn install_schema(
     State(state): State<SetupState>,
-    Json(req): Json<InstallSchemaRequest>,
+    Json(mut req): Json<InstallSchemaRequest>,
 ) -> Json<InstallSchemaResponse> {
-    let database_url = format!(
-        "postgres://{}:{}@{}:{}/{}",
-        req.username, req.password, req.host, req.port, req.database
-    );
+    if let Err(message) = validate_admin_account(&req) {
+        return InstallSchemaResponse::failed(message, Some(SetupStep::Account));
+    }
+    state.fill_saved_password(&mut req.connection).await;
+
+    if req.create_database {
+        if let Err(diagnosis) = create_database(&req.connection).await {
+            return InstallSchemaResponse::failed(diagnosis.message(), Some(SetupStep::Database));
+        }
+    }
+
+    let database_url = req.connection.database_url();
 
     info!("Installing RustPress schema...");
 
@@ -242,16 +719,17 @@ async fn install_schema(
     let pool = match PgPoolOptions::new()
         .max_connections(5)
         .acquire_timeout(Duration::from_secs(10))
-        .connect(&database_url)
+        .connect_with(req.connection.connect_options(&req.connection.database))
         .await
     {
         Ok(pool) => pool,
         Err(e) => {
             error!("Failed to connect to database: {}", e);
-            return Json(InstallSchemaResponse {
-                success: false,
-                message: format!("Database connection failed: {}", e),
-            });
+            let diagnosis = ConnectionDiagnosis::from_error(&e, &req.connection.database);
+            return InstallSchemaResponse::failed(
+                format!("Database connection failed: {}", diagnosis.message()),
+                Some(SetupStep::Database),
+            );
         }
     };
 
@@ -259,20 +737,14 @@ async fn install_schema(
     let password_hash = match hash_password(&req.admin_password) {
         Ok(hash) => hash,
         Err(e) => {
-            return Json(InstallSchemaResponse {
-                success: false,
-                message: format!("Failed to hash password: {}", e),
-            });
+            return InstallSchemaResponse::failed(format!("Failed to hash password: {}", e), None);
         }
     };
 
     // Run schema installation
     if let Err(e) = run_schema_installation(&pool, &req, &password_hash).await {
         error!("Schema installation failed: {}", e);
-        return Json(InstallSchemaResponse {
-            success: false,
-            message: format!("Schema installation failed: {}", e),
-        });
+        return InstallSchemaResponse::failed(format!("Schema installation failed: {}", e), None);
     }
 
     // Generate JWT secret
@@ -290,12 +762,13 @@ async fn install_schema(
     };
 
     if let Err(e) = save_config(&state.config_path, &config) {
-        return Json(InstallSchemaResponse {
-            success: false,
-            message: format!("Failed to save configuration: {}", e),
-        });
+        return InstallSchemaResponse::failed(format!("Failed to save configuration: {}", e), None);
     }
 
+    // The config file now holds everything the progress file did
+    SetupProgress::clear(&state.config_path);
+    state.progress.write().await.step = SetupStep::Complete;
+
     // Mark setup as complete
     *state.setup_complete.write().await = true;
 
@@ -305,6 +778,7 @@ async fn install_schema(
         message:
             "RustPress has been installed successfully! The server will restart automatically."
                 .to_string(),
+        step: Some(SetupStep::Complete),
     })
 }
 
@@ -1288,6 +1762,7 @@ const SETUP_PAGE_HTML: &str = r#"<!DOCTYPE html>
 
             <div class="btn-group">
                 <button class="btn btn-secondary" onclick="testConnection()">Test Connection</button>
+                <button class="btn btn-secondary" onclick="createDatabase()" id="create-db" style="display: none;">Create Database</button>
                 <button class="btn btn-primary" onclick="goToStep(2)" id="next-step1" disabled>Continue</button>
             </div>
         </div>
@@ -1299,14 +1774,16 @@ const SETUP_PAGE_HTML: &str = r#"<!DOCTYPE html>
                 <p>Create your administrator account</p>
             </div>
 
+            <div id="admin-alert" class="alert"></div>
+
             <div class="form-group">
                 <label for="admin-email">Email Address</label>
-                <input type="email" id="admin-email" placeholder="admin@example.com">
+                <input type="email" id="admin-email" onchange="saveAccountProgress()" placeholder="admin@example.com">
             </div>
 
             <div class="form-group">
                 <label for="admin-username">Username</label>
-                <input type="text" id="admin-username" placeholder="admin">
+                <input type="text" id="admin-username" onchange="saveAccountProgress()" placeholder="admin">
             </div>
 
             <div class="form-group">
@@ -1316,7 +1793,7 @@ const SETUP_PAGE_HTML: &str = r#"<!DOCTYPE html>
 
             <div class="form-group">
                 <label for="site-title">Site Title</label>
-                <input type="text" id="site-title" value="My RustPress Site" placeholder="My RustPress Site">
+                <input type="text" id="site-title" onchange="saveAccountProgress()" value="My RustPress Site" placeholder="My RustPress Site">
             </div>
 
             <div class="btn-group">
@@ -1414,44 +1891,104 @@ const SETUP_PAGE_HTML: &str = r#"<!DOCTYPE html>
             });
         }
 
-        async function testConnection() {
-            const btn = event.target;
-            btn.disabled = true;
-            btn.innerHTML = '<span class="loading"></span>Testing...';
-            hideAlert('db-alert');
-
-            const data = {
+        function connectionData() {
+            return {
                 host: document.getElementById('db-host').value,
                 port: parseInt(document.getElementById('db-port').value),
                 username: document.getElementById('db-username').value,
                 password: document.getElementById('db-password').value,
                 database: document.getElementById('db-name').value
             };
+        }
+
+        function showConnectionResult(result) {
+            const text = result.message + (result.details ? ' - ' + result.details : '');
+            showAlert('db-alert', text, result.success ? 'success' : 'error');
+            document.getElementById('next-step1').disabled = !result.success;
+            connectionVerified = result.success;
+
+            const diagnosis = result.diagnosis || {};
+            const canCreate = diagnosis.kind === 'database_missing' && diagnosis.can_create;
+            document.getElementById('create-db').style.display = canCreate ? '' : 'none';
+        }
+
+        async function postConnection(url, label, busyLabel) {
+            const btn = event.target;
+            btn.disabled = true;
+            btn.innerHTML = '<span class="loading"></span>' + busyLabel;
+            hideAlert('db-alert');
 
             try {
-                const response = await fetch('/api/setup/test-connection', {
+                const response = await fetch(url, {
                     method: 'POST',
                     headers: { 'Content-Type': 'application/json' },
-                    body: JSON.stringify(data)
+                    body: JSON.stringify(connectionData())
+                });
+
+                showConnectionResult(await response.json());
+            } catch (error) {
+                showAlert('db-alert', label + ' failed: ' + error.message, 'error');
+            }
+
+            btn.disabled = false;
+            btn.textContent = label;
+        }
+
+        async function testConnection() {
+            await postConnection('/api/setup/test-connection', 'Test Connection', 'Testing...');
+        }
+
+        async function createDatabase() {
+            await postConnection('/api/setup/create-database', 'Create Database', 'Creating...');
+        }
+
+        async function saveAccountProgress() {
+            try {
+                await fetch('/api/setup/progress', {
+                    method: 'PUT',
+                    headers: { 'Content-Type': 'application/json' },
+                    body: JSON.stringify({
+                        admin_email: document.getElementById('admin-email').value,
+                        admin_username: document.getElementById('admin-username').value,
+                        site_title: document.getElementById('site-title').value
+                    })
                 });
+            } catch (error) {
+                // Progress is a convenience; the wizard works without it
+            }
+        }
 
+        async function loadProgress() {
+            try {
+                const response = await fetch('/api/setup/progress');
                 const result = await response.json();
+                const progress = result.progress;
 
-                if (result.success) {
-                    showAlert('db-alert', result.message + (result.details ? ' - ' + result.details : ''), 'success');
+                if (progress.database) {
+                    document.getElementById('db-host').value = progress.database.host;
+                    document.getElementById('db-port').value = progress.database.port;
+                    document.getElementById('db-username').value = progress.database.username;
+                    document.getElementById('db-name').value = progress.database.database;
+                }
+                if (result.has_database_password) {
+                    document.getElementById('db-password').placeholder = 'Saved password (leave blank to keep)';
+                }
+                if (progress.admin_email) document.getElementById('admin-email').value = progress.admin_email;
+                if (progress.admin_username) document.getElementById('admin-username').value = progress.admin_username;
+                if (progress.site_title) document.getElementById('site-title').value = progress.site_title;
+
+                if (progress.step === 'account' && result.has_database_password) {
                     document.getElementById('next-step1').disabled = false;
                     connectionVerified = true;
-                } else {
-                    showAlert('db-alert', result.message + (result.details ? ' - ' + result.details : ''), 'error');
+                    goToStep(2);
                 }
             } catch (error) {
-                showAlert('db-alert', 'Connection test failed: ' + error.message, 'error');
+                // Start from the beginning
             }
-
-            btn.disabled = false;
-            btn.textContent = 'Test Connection';
         }
 
+        loadProgress();
+
         async function installRustPress() {
             const btn = event.target;
             btn.disabled = true;
@@ -1460,12 +1997,10 @@ const SETUP_PAGE_HTML: &str = r#"<!DOCTYPE html>
             // Show progress bar
             showProgress(10, 'Connecting to database...');
 
+            hideAlert('admin-alert');
+
             const data = {
-                host: document.getElementById('db-host').value,
-                port: parseInt(document.getElementById('db-port').value),
-                username: document.getElementById('db-username').value,
-                password: document.getElementById('db-password').value,
-                database: document.getElementById('db-name').value,
+                ...connectionData(),
                 admin_email: document.getElementById('admin-email').value,
                 admin_username: document.getElementById('admin-username').value,
                 admin_password: document.getElementById('admin-password').value,
@@ -1502,6 +2037,17 @@ const SETUP_PAGE_HTML: &str = r#"<!DOCTYPE html>
                     goToStep(3);
                     // Reload after a short delay
                     setTimeout(() => window.location.reload(), 3000);
+                } else if (result.step === 'account') {
+                    hideProgress();
+                    showAlert('admin-alert', result.message, 'error');
+                    btn.disabled = false;
+                    btn.textContent = 'Install RustPress';
+                } else if (result.step === 'database') {
+                    hideProgress();
+                    goToStep(1);
+                    showAlert('db-alert', result.message, 'error');
+                    btn.disabled = false;
+                    btn.textContent = 'Install RustPress';
                 } else {
                     showError(result.message);
                     btn.disabled = false;
@@ -1517,3 +2063,105 @@ const SETUP_PAGE_HTML: &str = r#"<!DOCTYPE html>
 </body>
 </html>
 "#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection(password: &str) -> TestConnectionRequest {
+        TestConnectionRequest {
+            host: "localhost".to_string(),
+            port: 5432,
+            username: "rustpress".to_string(),
+            password: password.to_string(),
+            database: "rustpress".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sqlstate_diagnosis() {
+        assert!(matches!(
+            ConnectionDiagnosis::from_sqlstate("28P01", "password authentication failed", "db"),
+            ConnectionDiagnosis::AuthenticationFailed { .. }
+        ));
+        assert_eq!(
+            ConnectionDiagnosis::from_sqlstate("3D000", "database \"db\" does not exist", "db"),
+            ConnectionDiagnosis::DatabaseMissing {
+                database: "db".to_string(),
+                can_create: false,
+            }
+        );
+        assert!(matches!(
+            ConnectionDiagnosis::from_sqlstate("42501", "permission denied", "db"),
+            ConnectionDiagnosis::PermissionDenied { .. }
+        ));
+
+        let json = serde_json::to_value(TestConnectionResponse::from(
+            ConnectionDiagnosis::DatabaseMissing {
+                database: "db".to_string(),
+                can_create: true,
+            },
+        ))
+        .unwrap();
+        assert_eq!(json["success"], false);
+        assert_eq!(json["diagnosis"]["kind"], "database_missing");
+        assert_eq!(json["diagnosis"]["can_create"], true);
+    }
+
+    #[test]
+    fn test_database_name_validation() {
+        assert!(is_valid_database_name("rustpress_2"));
+        assert!(!is_valid_database_name(""));
+        assert!(!is_valid_database_name("2rustpress"));
+        assert!(!is_valid_database_name("rust\"; DROP"));
+        assert!(!is_valid_database_name(&"a".repeat(64)));
+    }
+
+    #[test]
+    fn test_progress_roundtrip_hides_password() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("rustpress.toml");
+        assert_eq!(SetupProgress::load(&config_path).step, SetupStep::Database);
+
+        let progress = SetupProgress {
+            step: SetupStep::Account,
+            database: Some(SavedDatabase::from(&connection("secret"))),
+            site_title: Some("Blog".to_string()),
+            ..Default::default()
+        };
+        progress.save(&config_path).unwrap();
+
+        let loaded = SetupProgress::load(&config_path);
+        assert_eq!(loaded.step, SetupStep::Account);
+        let saved = loaded.database.as_ref().unwrap();
+        assert_eq!(saved.password.as_deref(), Some("secret"));
+        assert!(saved.same_target(&connection("")));
+        assert!(loaded.public_view().database.unwrap().password.is_none());
+
+        SetupProgress::clear(&config_path);
+        assert!(!SetupProgress::path_for(&config_path).exists());
+    }
+
+    #[test]
+    fn test_admin_account_validation() {
+        let mut req = InstallSchemaRequest {
+            connection: connection("secret"),
+            admin_email: "admin@example.com".to_string(),
+            admin_username: "admin".to_string(),
+            admin_password: "Corr3ct-Horse-Battery".to_string(),
+            site_title: "Blog".to_string(),
+            create_database: false,
+        };
+        assert!(validate_admin_account(&req).is_ok());
+
+        req.admin_password = "short".to_string();
+        assert!(validate_admin_account(&req).is_err());
+
+        req.admin_password = "Admin-Passw0rd-123".to_string();
+        assert!(validate_admin_account(&req).is_err());
+
+        req.admin_password = "Corr3ct-Horse-Battery".to_string();
+        req.admin_email = "not-an-email".to_string();
+        assert!(validate_admin_account(&req).is_err());
+    }
+}
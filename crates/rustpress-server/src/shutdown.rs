@@ -1,5 +1,8 @@
 //! Graceful shutdown handling.
 
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::Response;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -125,8 +128,10 @@ pub async fn listen_for_shutdown_signals(controller: ShutdownController) {
 pub async fn graceful_shutdown(controller: ShutdownController) {
     let mut receiver = controller.subscribe();
 
-    // Wait for shutdown signal
-    let _ = receiver.recv().await;
+    // Wait for shutdown signal, unless it was sent before we subscribed
+    if !controller.is_shutting_down() {
+        let _ = receiver.recv().await;
+    }
 
     info!("Starting graceful shutdown sequence");
 }
@@ -203,6 +208,16 @@ impl Drop for TaskGuard {
     }
 }
 
+/// Middleware counting in-flight requests so shutdown can wait for them to drain
+pub async fn track_in_flight(
+    State(handle): State<ShutdownHandle>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let _guard = handle.register_task();
+    next.run(request).await
+}
+
 /// Shutdown-aware sleep that returns early if shutdown is initiated
 pub async fn shutdown_aware_sleep(duration: Duration, controller: &ShutdownController) -> bool {
     let mut receiver = controller.subscribe();
@@ -373,6 +388,60 @@ mod tests {
         assert_eq!(handle.active_task_count(), 0);
     }
 
+    #[tokio::test]
+    async fn test_graceful_shutdown_after_signal() {
+        let controller = ShutdownController::with_default_timeout();
+        controller.shutdown();
+
+        // Signal sent before the server subscribed must not hang
+        tokio::time::timeout(Duration::from_secs(1), graceful_shutdown(controller))
+            .await
+            .expect("graceful_shutdown should return once shutdown has started");
+    }
+
+    #[tokio::test]
+    async fn test_track_in_flight() {
+        use axum::{body::Body, routing::get, Router};
+        use tower::ServiceExt;
+
+        let handle = ShutdownHandle::new(ShutdownController::with_default_timeout());
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel::<()>();
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+        let started_tx = Arc::new(parking_lot::Mutex::new(Some(started_tx)));
+        let release_rx = Arc::new(tokio::sync::Mutex::new(Some(release_rx)));
+
+        let app = Router::new()
+            .route(
+                "/",
+                get(move || {
+                    let started_tx = started_tx.clone();
+                    let release_rx = release_rx.clone();
+                    async move {
+                        if let Some(tx) = started_tx.lock().take() {
+                            let _ = tx.send(());
+                        }
+                        if let Some(rx) = release_rx.lock().await.take() {
+                            let _ = rx.await;
+                        }
+                    }
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                handle.clone(),
+                track_in_flight,
+            ));
+
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = tokio::spawn(app.oneshot(request));
+
+        started_rx.await.unwrap();
+        assert_eq!(handle.active_task_count(), 1);
+
+        release_tx.send(()).unwrap();
+        response.await.unwrap().unwrap();
+        assert_eq!(handle.active_task_count(), 0);
+    }
+
     #[tokio::test]
     async fn test_shutdown_phases() {
         let phases = ShutdownPhase::all();
@@ -8,13 +8,16 @@ use rustpress_core::plugin::PluginManager;
 use rustpress_database::{pool::DatabaseExecutor, DatabasePool};
 use rustpress_events::EventBus;
 use rustpress_jobs::JobQueue;
+use rustpress_performance::{Profiler, ProfilingConfig};
 use rustpress_storage::Storage;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use crate::metrics::{CacheMetrics, JobQueueMetrics, Metrics, ProfilerMetrics};
 use crate::services::{EmailConfig, EmailService, RenderService, ThemeService};
-use crate::websocket::WebSocketHub;
+use crate::websocket::system_metrics::{AppSystemSampler, DEFAULT_SAMPLE_INTERVAL};
+use crate::websocket::{SystemMetricsPublisher, WebSocketHub};
 
 /// Application state shared across all requests
 #[derive(Clone)]
@@ -47,6 +50,12 @@ pub struct AppState {
     pub email_service: Arc<EmailService>,
     /// WebSocket hub for real-time collaboration
     pub ws_hub: Arc<WebSocketHub>,
+    /// Request profiler
+    pub profiler: Arc<Profiler>,
+    /// Prometheus metrics registry shared by all subsystems
+    pub metrics: Arc<Metrics>,
+    /// Live system metrics for the admin dashboard
+    pub system_metrics: Arc<SystemMetricsPublisher>,
 }
 
 impl AppState {
@@ -233,12 +242,38 @@ impl AppStateBuilder {
         let email_service = Arc::new(EmailService::new());
         // Email configuration will be applied at runtime via configure()
 
+        let cache = Arc::new(self.cache.ok_or("cache is required")?);
+        let job_queue = Arc::new(self.job_queue.ok_or("job_queue is required")?);
+        let ws_hub = WebSocketHub::new();
+        let profiler = Arc::new(Profiler::new(ProfilingConfig::default()));
+
+        // Subsystems register into the shared registry served at /metrics
+        let metrics = Arc::new(Metrics::new());
+        metrics.register_source(Arc::new(ws_hub.metrics().clone()));
+        metrics.register_source(Arc::new(JobQueueMetrics::new(job_queue.clone())));
+        metrics.register_source(Arc::new(
+            CacheMetrics::new().with_cache("object", cache.clone()),
+        ));
+        metrics.register_source(Arc::new(ProfilerMetrics::new(&profiler)));
+
+        let database = Arc::new(database);
+        let system_metrics = SystemMetricsPublisher::new(
+            Arc::new(AppSystemSampler::new(
+                metrics.clone(),
+                database.clone(),
+                cache.clone(),
+                job_queue.clone(),
+                ws_hub.clone(),
+            )),
+            DEFAULT_SAMPLE_INTERVAL,
+        );
+
         Ok(AppState {
             config: Arc::new(self.config.ok_or("config is required")?),
-            database: Arc::new(database),
-            cache: Arc::new(self.cache.ok_or("cache is required")?),
+            database,
+            cache,
             event_bus: Arc::new(self.event_bus.ok_or("event_bus is required")?),
-            job_queue: Arc::new(self.job_queue.ok_or("job_queue is required")?),
+            job_queue,
             storage: Arc::new(self.storage.ok_or("storage is required")?),
             jwt: Arc::new(self.jwt.ok_or("jwt is required")?),
             permissions: Arc::new(self.permissions.unwrap_or_else(PermissionChecker::default)),
@@ -247,7 +282,10 @@ impl AppStateBuilder {
             theme_service,
             render_service,
             email_service,
-            ws_hub: WebSocketHub::new(),
+            ws_hub,
+            profiler,
+            metrics,
+            system_metrics,
         })
     }
 }
@@ -2,15 +2,16 @@
 
 use axum::{
     extract::{
-        ws::{Message, WebSocket, WebSocketUpgrade},
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
         Query, State,
     },
     response::IntoResponse,
 };
 use futures::{SinkExt, StreamExt};
+use rustpress_admin::widgets::SYSTEM_METRICS_CAPABILITY;
 use serde::Deserialize;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::time::Duration;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
@@ -19,6 +20,9 @@ use super::hub::WebSocketHub;
 use super::message::{ClientMessage, ServerMessage};
 use crate::state::AppState;
 
+/// How long to wait for a close frame to be written to a dropped client
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Query parameters for WebSocket connection
 #[derive(Debug, Deserialize)]
 pub struct WsQuery {
@@ -64,6 +68,14 @@ async fn handle_socket(socket: WebSocket, query: WsQuery, state: AppState) {
     };
     let session_id = Uuid::new_v4();
 
+    // Live system metrics are for admins only
+    let (resource, action) = SYSTEM_METRICS_CAPABILITY;
+    let can_monitor = claims.role.as_ref().is_some_and(|role| {
+        state
+            .permissions
+            .roles_can(&[role.clone()], resource, action)
+    });
+
     // Get user info from database
     let user_info = match get_user_info(&state, user_id).await {
         Some(info) => info,
@@ -81,12 +93,12 @@ async fn handle_socket(socket: WebSocket, query: WsQuery, state: AppState) {
     // Split the socket
     let (mut sender, mut receiver) = socket.split();
 
-    // Create channel for outgoing messages
-    let (tx, mut rx) = mpsc::unbounded_channel::<ServerMessage>();
-
     // Get hub reference
     let hub = state.ws_hub.clone();
 
+    // Create bounded channel for outgoing messages
+    let (tx, mut outgoing) = hub.channel();
+
     // Register connection
     hub.register(
         session_id,
@@ -119,29 +131,76 @@ async fn handle_socket(socket: WebSocket, query: WsQuery, state: AppState) {
     // Create chat service
     let chat_service = ChatService::new(state.db().inner().clone());
 
-    // Spawn task to send outgoing messages
+    // Spawn task to send outgoing messages. The hub closes the connection
+    // (with a reason) if this task falls too far behind.
     let hub_clone = hub.clone();
     let send_task = tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
-            match serde_json::to_string(&msg) {
-                Ok(json) => {
-                    if sender.send(Message::Text(json.into())).await.is_err() {
-                        break;
+        let close_reason = loop {
+            tokio::select! {
+                biased;
+                reason = &mut outgoing.close => break reason.ok(),
+                msg = outgoing.messages.recv() => {
+                    let Some(msg) = msg else { break None };
+                    let json = match serde_json::to_string(&msg) {
+                        Ok(json) => json,
+                        Err(e) => {
+                            error!("Failed to serialize WebSocket message: {}", e);
+                            continue;
+                        }
+                    };
+                    // A stalled socket must not delay the close
+                    tokio::select! {
+                        biased;
+                        reason = &mut outgoing.close => break reason.ok(),
+                        result = sender.send(Message::Text(json)) => {
+                            if result.is_err() {
+                                break None;
+                            }
+                        }
                     }
                 }
-                Err(e) => {
-                    error!("Failed to serialize WebSocket message: {}", e);
-                }
             }
+        };
+
+        if let Some(reason) = close_reason {
+            let frame = CloseFrame {
+                code: close_code::POLICY,
+                reason: reason.into(),
+            };
+            let _ =
+                tokio::time::timeout(CLOSE_TIMEOUT, sender.send(Message::Close(Some(frame)))).await;
         }
     });
 
     // Handle incoming messages
     let hub_clone2 = hub.clone();
+    let system_metrics = state.system_metrics.clone();
     let recv_task = tokio::spawn(async move {
+        // Dropped (and its forwarding task aborted) on unsubscribe or disconnect
+        let mut metrics_stream = None;
         while let Some(result) = receiver.next().await {
             match result {
                 Ok(Message::Text(text)) => match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(ClientMessage::SubscribeSystemMetrics) => {
+                        if !can_monitor {
+                            hub_clone2
+                                .send_to_session(
+                                    session_id,
+                                    ServerMessage::error(
+                                        "forbidden",
+                                        "Not allowed to view system metrics",
+                                    ),
+                                )
+                                .await;
+                        } else if metrics_stream.is_none() {
+                            metrics_stream = Some(
+                                system_metrics.stream_to_session(hub_clone2.clone(), session_id),
+                            );
+                        }
+                    }
+                    Ok(ClientMessage::UnsubscribeSystemMetrics) => {
+                        metrics_stream = None;
+                    }
                     Ok(msg) => {
                         handle_client_message(&hub_clone2, &chat_service, session_id, user_id, msg)
                             .await;
@@ -171,11 +230,16 @@ async fn handle_socket(socket: WebSocket, query: WsQuery, state: AppState) {
         }
     });
 
-    // Wait for either task to complete
+    // Wait for either task to complete, then stop the other so nothing it
+    // owns (like a metrics stream) outlives the connection
+    let send_abort = send_task.abort_handle();
+    let recv_abort = recv_task.abort_handle();
     tokio::select! {
         _ = send_task => {},
         _ = recv_task => {},
     }
+    send_abort.abort();
+    recv_abort.abort();
 
     // Unregister connection
     hub.unregister(session_id).await;
@@ -452,6 +516,9 @@ async fn handle_client_message(
             hub.unsubscribe_from_conversation(session_id, conversation_id)
                 .await;
         }
+
+        // The connection loop owns the metrics stream and handles these
+        ClientMessage::SubscribeSystemMetrics | ClientMessage::UnsubscribeSystemMetrics => {}
     }
 }
 
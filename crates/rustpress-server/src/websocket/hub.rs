@@ -1,16 +1,54 @@
 //! WebSocket connection hub for managing all active connections.
 
+use prometheus_client::metrics::{counter::Counter, gauge::Gauge};
+use prometheus_client::registry::Registry;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::{broadcast, mpsc, RwLock};
+use tokio::sync::{broadcast, mpsc, oneshot, RwLock};
+use tracing::warn;
 use uuid::Uuid;
 
 use super::collaboration::CollaborationState;
 use super::message::{CursorPosition, Selection, ServerMessage, UserPresence, UserStatus};
 use super::presence::PresenceTracker;
 
-/// Message sender for a single connection
-pub type ConnectionSender = mpsc::UnboundedSender<ServerMessage>;
+/// Default number of messages buffered per connection before it's dropped
+pub const DEFAULT_BUFFER_SIZE: usize = 256;
+
+/// Close reason sent to clients that can't keep up
+pub const SLOW_CLIENT_REASON: &str = "send buffer overflow";
+
+/// Outgoing half of a connection: a bounded message queue and a close signal
+#[derive(Debug)]
+pub struct ConnectionSender {
+    messages: mpsc::Sender<ServerMessage>,
+    close: Option<oneshot::Sender<String>>,
+}
+
+/// Receiving half of a connection, drained by the socket's send task
+#[derive(Debug)]
+pub struct ConnectionReceiver {
+    /// Messages to write to the socket
+    pub messages: mpsc::Receiver<ServerMessage>,
+    /// Resolves with a reason when the hub force-disconnects the client
+    pub close: oneshot::Receiver<String>,
+}
+
+/// Create a connection channel buffering at most `capacity` messages
+pub fn connection_channel(capacity: usize) -> (ConnectionSender, ConnectionReceiver) {
+    let (messages_tx, messages_rx) = mpsc::channel(capacity);
+    let (close_tx, close_rx) = oneshot::channel();
+    (
+        ConnectionSender {
+            messages: messages_tx,
+            close: Some(close_tx),
+        },
+        ConnectionReceiver {
+            messages: messages_rx,
+            close: close_rx,
+        },
+    )
+}
 
 /// A single WebSocket connection
 #[derive(Debug)]
@@ -25,6 +63,31 @@ pub struct Connection {
     pub subscribed_conversations: HashSet<Uuid>,
 }
 
+/// WebSocket hub metrics
+#[derive(Debug, Clone, Default)]
+pub struct HubMetrics {
+    /// Currently connected sessions
+    pub connections_active: Gauge,
+    /// Clients disconnected because their send buffer overflowed
+    pub clients_dropped_total: Counter,
+}
+
+impl HubMetrics {
+    /// Register the hub metrics with a Prometheus registry
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "websocket_connections_active",
+            "Number of active WebSocket connections",
+            self.connections_active.clone(),
+        );
+        registry.register(
+            "websocket_clients_dropped",
+            "WebSocket clients disconnected for not keeping up with messages",
+            self.clients_dropped_total.clone(),
+        );
+    }
+}
+
 /// WebSocket hub that manages all active connections
 #[derive(Debug)]
 pub struct WebSocketHub {
@@ -38,22 +101,36 @@ pub struct WebSocketHub {
     collaboration: RwLock<CollaborationState>,
     /// Broadcast channel for global events
     broadcast_tx: broadcast::Sender<ServerMessage>,
+    /// Per-connection send buffer size
+    buffer_size: usize,
+    /// Connection and drop metrics
+    metrics: HubMetrics,
 }
 
 impl WebSocketHub {
     /// Create a new WebSocket hub
     pub fn new() -> Arc<Self> {
-        let (broadcast_tx, _) = broadcast::channel(1024);
+        Arc::new(Self::default())
+    }
 
+    /// Create a hub whose connections buffer at most `buffer_size` messages
+    pub fn with_buffer_size(buffer_size: usize) -> Arc<Self> {
         Arc::new(Self {
-            connections: RwLock::new(HashMap::new()),
-            user_sessions: RwLock::new(HashMap::new()),
-            presence: RwLock::new(PresenceTracker::new()),
-            collaboration: RwLock::new(CollaborationState::new()),
-            broadcast_tx,
+            buffer_size: buffer_size.max(1),
+            ..Self::default()
         })
     }
 
+    /// Create a channel for a new connection using the hub's buffer size
+    pub fn channel(&self) -> (ConnectionSender, ConnectionReceiver) {
+        connection_channel(self.buffer_size)
+    }
+
+    /// Get the hub metrics
+    pub fn metrics(&self) -> &HubMetrics {
+        &self.metrics
+    }
+
     /// Register a new connection
     pub async fn register(
         &self,
@@ -82,6 +159,7 @@ impl WebSocketHub {
             let mut connections = self.connections.write().await;
             connections.insert(session_id, connection);
         }
+        self.metrics.connections_active.inc();
 
         // Track user session
         {
@@ -126,18 +204,49 @@ impl WebSocketHub {
 
     /// Unregister a connection
     pub async fn unregister(&self, session_id: Uuid) {
-        let connection = {
-            let mut connections = self.connections.write().await;
-            connections.remove(&session_id)
-        };
+        self.disconnect(vec![(session_id, None)]).await;
+    }
+
+    /// Remove sessions and clean up their presence and collaboration state.
+    ///
+    /// Sessions with a reason are force-disconnected: the reason is sent to
+    /// the client in a close frame. Clients that overflow while being told
+    /// about a departure are dropped in turn.
+    async fn disconnect(&self, mut pending: Vec<(Uuid, Option<String>)>) {
+        while let Some((session_id, reason)) = pending.pop() {
+            let connection = {
+                let mut connections = self.connections.write().await;
+                connections.remove(&session_id)
+            };
+
+            let Some(mut conn) = connection else {
+                continue;
+            };
+            self.metrics.connections_active.dec();
+
+            if let Some(reason) = reason {
+                warn!(
+                    session_id = %session_id,
+                    user_id = %conn.user_id,
+                    reason = %reason,
+                    "Force-disconnecting WebSocket client"
+                );
+                self.metrics.clients_dropped_total.inc();
+                if let Some(close) = conn.sender.close.take() {
+                    let _ = close.send(reason);
+                }
+            }
 
-        if let Some(conn) = connection {
             // Remove from user sessions
             let should_notify_offline = {
                 let mut user_sessions = self.user_sessions.write().await;
                 if let Some(sessions) = user_sessions.get_mut(&conn.user_id) {
                     sessions.remove(&session_id);
-                    sessions.is_empty()
+                    let empty = sessions.is_empty();
+                    if empty {
+                        user_sessions.remove(&conn.user_id);
+                    }
+                    empty
                 } else {
                     false
                 }
@@ -156,51 +265,75 @@ impl WebSocketHub {
                     presence.user_offline(conn.user_id);
                 }
 
-                self.broadcast(ServerMessage::UserLeft {
-                    user_id: conn.user_id,
-                })
-                .await;
+                let slow = self
+                    .fan_out(
+                        &ServerMessage::UserLeft {
+                            user_id: conn.user_id,
+                        },
+                        |_, _| true,
+                    )
+                    .await;
+                pending.extend(
+                    slow.into_iter()
+                        .map(|id| (id, Some(SLOW_CLIENT_REASON.to_string()))),
+                );
             }
         }
     }
 
-    /// Send message to a specific session
-    pub async fn send_to_session(&self, session_id: Uuid, message: ServerMessage) {
+    /// Queue `message` on every connection matching `filter` without
+    /// waiting on any of them. Returns sessions whose buffer is full.
+    async fn fan_out(
+        &self,
+        message: &ServerMessage,
+        filter: impl Fn(&Uuid, &Connection) -> bool,
+    ) -> Vec<Uuid> {
         let connections = self.connections.read().await;
-        if let Some(conn) = connections.get(&session_id) {
-            let _ = conn.sender.send(message);
+        connections
+            .iter()
+            .filter(|(session_id, conn)| filter(session_id, conn))
+            .filter_map(|(session_id, conn)| {
+                match conn.sender.messages.try_send(message.clone()) {
+                    Err(mpsc::error::TrySendError::Full(_)) => Some(*session_id),
+                    // Closed connections are unregistered by their handler
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Fan out and drop any clients that couldn't keep up
+    async fn deliver(&self, message: ServerMessage, filter: impl Fn(&Uuid, &Connection) -> bool) {
+        let slow = self.fan_out(&message, filter).await;
+        if !slow.is_empty() {
+            self.disconnect(
+                slow.into_iter()
+                    .map(|id| (id, Some(SLOW_CLIENT_REASON.to_string())))
+                    .collect(),
+            )
+            .await;
         }
     }
 
+    /// Send message to a specific session
+    pub async fn send_to_session(&self, session_id: Uuid, message: ServerMessage) {
+        self.deliver(message, |id, _| *id == session_id).await;
+    }
+
     /// Send message to a specific user (all their sessions)
     pub async fn send_to_user(&self, user_id: Uuid, message: ServerMessage) {
-        let user_sessions = self.user_sessions.read().await;
-        if let Some(sessions) = user_sessions.get(&user_id) {
-            let connections = self.connections.read().await;
-            for session_id in sessions {
-                if let Some(conn) = connections.get(session_id) {
-                    let _ = conn.sender.send(message.clone());
-                }
-            }
-        }
+        self.deliver(message, |_, conn| conn.user_id == user_id)
+            .await;
     }
 
     /// Broadcast message to all connections
     pub async fn broadcast(&self, message: ServerMessage) {
-        let connections = self.connections.read().await;
-        for conn in connections.values() {
-            let _ = conn.sender.send(message.clone());
-        }
+        self.deliver(message, |_, _| true).await;
     }
 
     /// Broadcast message to all connections except one
     pub async fn broadcast_except(&self, except_session: Uuid, message: ServerMessage) {
-        let connections = self.connections.read().await;
-        for (session_id, conn) in connections.iter() {
-            if *session_id != except_session {
-                let _ = conn.sender.send(message.clone());
-            }
-        }
+        self.deliver(message, |id, _| *id != except_session).await;
     }
 
     /// Broadcast message to all users viewing a specific file
@@ -210,17 +343,15 @@ impl WebSocketHub {
         message: ServerMessage,
         except_session: Option<Uuid>,
     ) {
-        let collab = self.collaboration.read().await;
-        let sessions = collab.get_file_sessions(file_path);
+        let sessions: HashSet<Uuid> = {
+            let collab = self.collaboration.read().await;
+            collab.get_file_sessions(file_path).into_iter().collect()
+        };
 
-        let connections = self.connections.read().await;
-        for session_id in sessions {
-            if except_session.map_or(true, |e| e != session_id) {
-                if let Some(conn) = connections.get(&session_id) {
-                    let _ = conn.sender.send(message.clone());
-                }
-            }
-        }
+        self.deliver(message, |id, _| {
+            sessions.contains(id) && except_session != Some(*id)
+        })
+        .await;
     }
 
     /// Broadcast message to all users in a conversation
@@ -230,14 +361,10 @@ impl WebSocketHub {
         message: ServerMessage,
         except_session: Option<Uuid>,
     ) {
-        let connections = self.connections.read().await;
-        for (session_id, conn) in connections.iter() {
-            if conn.subscribed_conversations.contains(&conversation_id) {
-                if except_session.map_or(true, |e| e != *session_id) {
-                    let _ = conn.sender.send(message.clone());
-                }
-            }
-        }
+        self.deliver(message, |id, conn| {
+            conn.subscribed_conversations.contains(&conversation_id) && except_session != Some(*id)
+        })
+        .await;
     }
 
     /// Subscribe a session to a conversation
@@ -399,6 +526,8 @@ impl Default for WebSocketHub {
             presence: RwLock::new(PresenceTracker::new()),
             collaboration: RwLock::new(CollaborationState::new()),
             broadcast_tx,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            metrics: HubMetrics::default(),
         }
     }
 }
@@ -424,3 +553,52 @@ fn generate_user_color(user_id: &Uuid) -> String {
     let index = (bytes[0] as usize + bytes[1] as usize) % colors.len();
     colors[index].to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn connect(hub: &WebSocketHub, user_id: Uuid) -> (Uuid, ConnectionReceiver) {
+        let session_id = Uuid::new_v4();
+        let (tx, rx) = hub.channel();
+        hub.register(
+            session_id,
+            user_id,
+            "user".to_string(),
+            "User".to_string(),
+            None,
+            tx,
+        )
+        .await;
+        (session_id, rx)
+    }
+
+    #[tokio::test]
+    async fn test_slow_client_is_dropped() {
+        let hub = WebSocketHub::with_buffer_size(4);
+        let slow_user = Uuid::new_v4();
+        let (_slow, mut slow_rx) = connect(&hub, slow_user).await;
+        let (_fast, mut fast_rx) = connect(&hub, Uuid::new_v4()).await;
+        assert_eq!(hub.metrics().connections_active.get(), 2);
+
+        let mut left = false;
+        for _ in 0..10 {
+            hub.broadcast(ServerMessage::error("test", "message")).await;
+            // The fast client keeps up
+            while let Ok(msg) = fast_rx.messages.try_recv() {
+                left |= matches!(msg, ServerMessage::UserLeft { user_id } if user_id == slow_user);
+            }
+        }
+
+        // Slow client was disconnected with a reason; the broadcast didn't block
+        assert_eq!(slow_rx.close.try_recv().unwrap(), SLOW_CLIENT_REASON);
+        assert_eq!(hub.connection_count().await, 1);
+        assert_eq!(hub.metrics().clients_dropped_total.get(), 1);
+        assert_eq!(hub.metrics().connections_active.get(), 1);
+
+        // Presence is cleaned up and others are told the user left
+        let online = hub.get_online_users().await;
+        assert!(online.iter().all(|u| u.user_id != slow_user));
+        assert!(left);
+    }
+}
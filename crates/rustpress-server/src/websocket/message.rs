@@ -1,6 +1,7 @@
 //! WebSocket message types for real-time collaboration and chat.
 
 use chrono::{DateTime, Utc};
+use rustpress_admin::widgets::SystemMetricsSnapshot;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -165,6 +166,10 @@ pub enum ClientMessage {
     LeaveConversation {
         conversation_id: Uuid,
     },
+
+    // Admin dashboard
+    SubscribeSystemMetrics,
+    UnsubscribeSystemMetrics,
 }
 
 /// Outbound WebSocket messages (to client)
@@ -273,6 +278,11 @@ pub enum ServerMessage {
         conversation_id: Uuid,
         count: u32,
     },
+
+    // Admin dashboard
+    SystemMetrics {
+        snapshot: SystemMetricsSnapshot,
+    },
 }
 
 /// Collaborator information for a file
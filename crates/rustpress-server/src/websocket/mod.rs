@@ -5,6 +5,7 @@
 //! - User presence tracking
 //! - Real-time file collaboration (cursors, selections, edits)
 //! - Chat messaging system
+//! - Live system metrics for the admin dashboard
 
 pub mod chat;
 pub mod collaboration;
@@ -12,7 +13,9 @@ pub mod handler;
 pub mod hub;
 pub mod message;
 pub mod presence;
+pub mod system_metrics;
 
 pub use handler::websocket_handler;
 pub use hub::WebSocketHub;
 pub use message::{ClientMessage, ServerMessage, UserPresence, UserStatus};
+pub use system_metrics::SystemMetricsPublisher;
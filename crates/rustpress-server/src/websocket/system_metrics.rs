@@ -0,0 +1,302 @@
+//! Live system metrics stream for the admin dashboard.
+//!
+//! A single sampler task reads the subsystems every few seconds and
+//! broadcasts a [`SystemMetricsSnapshot`]. It only runs while at least one
+//! admin is subscribed: the first subscription starts it and it exits on the
+//! first tick after the last subscription is dropped.
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use rustpress_admin::widgets::{SystemMetricsSnapshot, SystemMetricsWidget};
+use rustpress_database::DatabasePool;
+use rustpress_jobs::JobQueue;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, MissedTickBehavior};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use super::hub::WebSocketHub;
+use super::message::ServerMessage;
+use crate::metrics::{CacheStatsProvider, Metrics};
+
+/// Default time between samples
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Snapshots buffered per subscriber; laggards skip to the newest
+const CHANNEL_CAPACITY: usize = 8;
+
+/// Raw readings from the subsystems
+///
+/// Request and cache counts are cumulative; the publisher turns them into
+/// rates between consecutive readings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemReading {
+    pub requests_total: u64,
+    pub db_pool_in_use: u32,
+    pub db_pool_max: u32,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub job_queue_depth: u64,
+    pub ws_connections: u64,
+}
+
+impl SystemReading {
+    /// Build a snapshot from the change since `previous`
+    fn snapshot_since(&self, previous: &SystemReading, elapsed: Duration) -> SystemMetricsSnapshot {
+        // Counters that went backwards were reset; count from zero
+        let requests = self.requests_total.saturating_sub(previous.requests_total);
+        let hits = self.cache_hits.saturating_sub(previous.cache_hits);
+        let misses = self.cache_misses.saturating_sub(previous.cache_misses);
+
+        let seconds = elapsed.as_secs_f64();
+        SystemMetricsSnapshot {
+            requests_per_second: if seconds > 0.0 {
+                requests as f64 / seconds
+            } else {
+                0.0
+            },
+            db_pool_in_use: self.db_pool_in_use,
+            db_pool_max: self.db_pool_max,
+            cache_hit_ratio: (hits + misses > 0).then(|| hits as f64 / (hits + misses) as f64),
+            job_queue_depth: self.job_queue_depth,
+            ws_connections: self.ws_connections,
+            sampled_at: chrono::Utc::now(),
+        }
+    }
+}
+
+/// Source of system readings
+#[async_trait]
+pub trait SystemSampler: Send + Sync {
+    /// Read the current state of the subsystems
+    async fn read(&self) -> SystemReading;
+}
+
+/// Samples the application's own subsystems
+pub struct AppSystemSampler {
+    metrics: Arc<Metrics>,
+    database: Arc<DatabasePool>,
+    cache: Arc<dyn CacheStatsProvider>,
+    job_queue: Arc<JobQueue>,
+    ws_hub: Arc<WebSocketHub>,
+}
+
+impl AppSystemSampler {
+    pub fn new(
+        metrics: Arc<Metrics>,
+        database: Arc<DatabasePool>,
+        cache: Arc<dyn CacheStatsProvider>,
+        job_queue: Arc<JobQueue>,
+        ws_hub: Arc<WebSocketHub>,
+    ) -> Self {
+        Self {
+            metrics,
+            database,
+            cache,
+            job_queue,
+            ws_hub,
+        }
+    }
+}
+
+#[async_trait]
+impl SystemSampler for AppSystemSampler {
+    async fn read(&self) -> SystemReading {
+        let pool = self.database.stats();
+        let (cache_hits, cache_misses) = self.cache.hits_misses().await;
+        let job_queue_depth = match self.job_queue.depths().await {
+            Ok(depths) => depths.iter().map(|(_, depth)| depth).sum(),
+            Err(e) => {
+                warn!(error = %e, "Failed to read job queue depth");
+                0
+            }
+        };
+
+        SystemReading {
+            requests_total: self.metrics.http_requests_served.get(),
+            db_pool_in_use: pool.size.saturating_sub(pool.idle as u32),
+            db_pool_max: pool.max,
+            cache_hits,
+            cache_misses,
+            job_queue_depth,
+            ws_connections: self.ws_hub.connection_count().await as u64,
+        }
+    }
+}
+
+/// Publishes system metrics snapshots to subscribed admins
+pub struct SystemMetricsPublisher {
+    sampler: Arc<dyn SystemSampler>,
+    interval: Duration,
+    sender: broadcast::Sender<SystemMetricsSnapshot>,
+    /// Running sampler task, if any. Subscribing and the sampler's decision
+    /// to exit both happen under this lock so a new subscriber never waits
+    /// on a sampler that is about to stop.
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl SystemMetricsPublisher {
+    /// Create a publisher sampling every `interval` while subscribed
+    pub fn new(sampler: Arc<dyn SystemSampler>, interval: Duration) -> Arc<Self> {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Arc::new(Self {
+            sampler,
+            interval,
+            sender,
+            task: Mutex::new(None),
+        })
+    }
+
+    /// Subscribe to snapshots, starting the sampler if it isn't running
+    pub fn subscribe(self: &Arc<Self>) -> SystemMetricsWidget {
+        let mut task = self.task.lock();
+        let receiver = self.sender.subscribe();
+        if task.is_none() {
+            debug!("Starting system metrics sampler");
+            *task = Some(tokio::spawn(self.clone().run()));
+        }
+        SystemMetricsWidget::new(receiver)
+    }
+
+    /// Forward snapshots to a WebSocket session until the stream is dropped
+    pub fn stream_to_session(
+        self: &Arc<Self>,
+        hub: Arc<WebSocketHub>,
+        session_id: Uuid,
+    ) -> SystemMetricsStream {
+        let mut widget = self.subscribe();
+        SystemMetricsStream(tokio::spawn(async move {
+            while let Some(snapshot) = widget.next().await {
+                hub.send_to_session(session_id, ServerMessage::SystemMetrics { snapshot })
+                    .await;
+            }
+        }))
+    }
+
+    /// Whether the sampler task is currently running
+    pub fn is_sampling(&self) -> bool {
+        self.task.lock().is_some()
+    }
+
+    async fn run(self: Arc<Self>) {
+        let mut ticker = tokio::time::interval(self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        // The first tick completes immediately; use it for the baseline
+        ticker.tick().await;
+        let mut previous = self.sampler.read().await;
+        let mut previous_at = Instant::now();
+
+        loop {
+            ticker.tick().await;
+            {
+                let mut task = self.task.lock();
+                if self.sender.receiver_count() == 0 {
+                    debug!("No system metrics subscribers left, stopping sampler");
+                    *task = None;
+                    return;
+                }
+            }
+
+            let reading = self.sampler.read().await;
+            let now = Instant::now();
+            let snapshot = reading.snapshot_since(&previous, now - previous_at);
+            previous = reading;
+            previous_at = now;
+
+            // Subscribers may all have left since the check; that's fine
+            let _ = self.sender.send(snapshot);
+        }
+    }
+}
+
+/// A session's metrics forwarding task, aborted when dropped
+pub struct SystemMetricsStream(JoinHandle<()>);
+
+impl Drop for SystemMetricsStream {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct FakeSampler(Mutex<SystemReading>);
+
+    #[async_trait]
+    impl SystemSampler for FakeSampler {
+        async fn read(&self) -> SystemReading {
+            *self.0.lock()
+        }
+    }
+
+    #[test]
+    fn test_snapshot_rates() {
+        let previous = SystemReading {
+            requests_total: 100,
+            cache_hits: 10,
+            cache_misses: 10,
+            ..Default::default()
+        };
+        let current = SystemReading {
+            requests_total: 130,
+            cache_hits: 19,
+            cache_misses: 11,
+            job_queue_depth: 7,
+            ..Default::default()
+        };
+
+        let snapshot = current.snapshot_since(&previous, Duration::from_secs(3));
+        assert_eq!(snapshot.requests_per_second, 10.0);
+        assert_eq!(snapshot.cache_hit_ratio, Some(0.9));
+        assert_eq!(snapshot.job_queue_depth, 7);
+
+        let idle = current.snapshot_since(&current, Duration::from_secs(3));
+        assert_eq!(idle.requests_per_second, 0.0);
+        assert_eq!(idle.cache_hit_ratio, None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sampler_stops_when_last_subscriber_leaves() {
+        let sampler = Arc::new(FakeSampler::default());
+        let publisher = SystemMetricsPublisher::new(sampler.clone(), Duration::from_secs(1));
+        assert!(!publisher.is_sampling());
+
+        let mut widget = publisher.subscribe();
+        assert!(publisher.is_sampling());
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        sampler.0.lock().requests_total = 5;
+        let snapshot = widget.next().await.unwrap();
+        assert_eq!(snapshot.requests_per_second, 5.0);
+
+        drop(widget);
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(!publisher.is_sampling());
+
+        // A new subscriber starts a fresh sampler
+        let mut widget = publisher.subscribe();
+        assert!(publisher.is_sampling());
+        assert!(widget.next().await.is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_dropping_stream_releases_subscription() {
+        let publisher =
+            SystemMetricsPublisher::new(Arc::new(FakeSampler::default()), Duration::from_secs(1));
+        let hub = WebSocketHub::new();
+
+        let stream = publisher.stream_to_session(hub, Uuid::new_v4());
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(publisher.is_sampling());
+
+        drop(stream);
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(!publisher.is_sampling());
+    }
+}
@@ -1,10 +1,34 @@
 //! Storage backend implementations.
 
-use crate::file::{PathGenerator, StoredFile, UploadRequest};
+use crate::file::{PathGenerator, StorageClass, StoredFile, UploadRequest};
+use crate::lifecycle::RestoreStatus;
+use crate::signing::UrlSigner;
+use crate::storage::MimeDetector;
 use async_trait::async_trait;
 use bytes::Bytes;
 use rustpress_core::error::{Error, Result};
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
+#[cfg(feature = "s3")]
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use uuid::Uuid;
+
+/// Read buffer size used when streaming to the local filesystem
+const STREAM_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Default multipart part size (matches `object_store`'s own writer)
+#[cfg(feature = "s3")]
+pub const DEFAULT_PART_SIZE: usize = 10 * 1024 * 1024;
+
+/// Smallest part size S3 accepts for all but the last part
+#[cfg(feature = "s3")]
+pub const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+
+/// Default number of parts uploaded concurrently
+#[cfg(feature = "s3")]
+pub const DEFAULT_UPLOAD_CONCURRENCY: usize = 8;
 
 /// Storage backend trait
 #[async_trait]
@@ -15,6 +39,16 @@ pub trait StorageBackend: Send + Sync {
     /// Store a file
     async fn store(&self, request: UploadRequest) -> Result<StoredFile>;
 
+    /// Stream a file to `path` without buffering it in memory.
+    ///
+    /// The returned file carries the SHA-256 of the content, computed while
+    /// streaming. A failed or dropped upload leaves nothing behind at `path`.
+    async fn put_stream(
+        &self,
+        path: &str,
+        reader: &mut (dyn AsyncRead + Send + Unpin),
+    ) -> Result<StoredFile>;
+
     /// Get file contents
     async fn get(&self, path: &str) -> Result<Bytes>;
 
@@ -39,17 +73,50 @@ pub trait StorageBackend: Send + Sync {
     /// Get temporary/signed URL
     async fn temporary_url(&self, path: &str, expires_in_secs: u64) -> Result<String>;
 
+    /// Time-limited URL granting read access to a private file
+    async fn signed_url(&self, path: &str, expiry: Duration) -> Result<String>;
+
     /// List files in a directory
     async fn list(&self, prefix: &str) -> Result<Vec<String>>;
 
     /// Health check
     async fn health_check(&self) -> Result<()>;
+
+    /// Storage classes objects can be moved between
+    fn storage_classes(&self) -> &[StorageClass] {
+        &[StorageClass::Standard]
+    }
+
+    /// Move the object at `path` to `class`, keeping its path
+    async fn set_storage_class(&self, path: &str, class: StorageClass) -> Result<()> {
+        if class == StorageClass::Standard {
+            return Ok(());
+        }
+        Err(Error::Storage {
+            message: format!(
+                "The {} backend does not support the {} storage class (path {})",
+                self.name(),
+                class.as_str(),
+                path
+            ),
+            source: None,
+        })
+    }
+
+    /// Make an archived object readable, starting a restore if needed.
+    ///
+    /// Backends whose archive tier is readable directly return
+    /// [`RestoreStatus::Ready`].
+    async fn restore(&self, _path: &str) -> Result<RestoreStatus> {
+        Ok(RestoreStatus::Ready)
+    }
 }
 
 /// Local filesystem storage backend
 pub struct LocalBackend {
     root: PathBuf,
     base_url: Option<String>,
+    signer: Option<UrlSigner>,
 }
 
 impl LocalBackend {
@@ -57,6 +124,7 @@ impl LocalBackend {
         Self {
             root: root.into(),
             base_url: None,
+            signer: None,
         }
     }
 
@@ -65,6 +133,12 @@ impl LocalBackend {
         self
     }
 
+    /// Enable signed URLs; the serving route must verify with the same secret
+    pub fn with_signing_secret(mut self, secret: impl AsRef<[u8]>) -> Self {
+        self.signer = Some(UrlSigner::new(secret));
+        self
+    }
+
     fn full_path(&self, path: &str) -> PathBuf {
         self.root.join(path)
     }
@@ -82,6 +156,56 @@ impl LocalBackend {
     }
 }
 
+/// Removes a partially written temp file unless the upload completed
+struct PartialFile(Option<PathBuf>);
+
+impl PartialFile {
+    fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for PartialFile {
+    fn drop(&mut self) {
+        if let Some(path) = self.0.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Copy `reader` into `file`, returning the byte count and hex SHA-256
+async fn write_hashed(
+    file: &mut tokio::fs::File,
+    reader: &mut (dyn AsyncRead + Send + Unpin),
+) -> std::io::Result<(u64, String)> {
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; STREAM_BUFFER_SIZE];
+    let mut size = 0u64;
+
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        file.write_all(&buf[..n]).await?;
+        size += n as u64;
+    }
+    file.sync_all().await?;
+
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
+
+/// Filename and guessed MIME type for a streamed path
+fn stream_file_info(path: &str) -> (&str, &'static str) {
+    let filename = Path::new(path)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("file");
+    let mime_type = MimeDetector::from_filename(filename).unwrap_or("application/octet-stream");
+    (filename, mime_type)
+}
+
 #[async_trait]
 impl StorageBackend for LocalBackend {
     fn name(&self) -> &str {
@@ -115,6 +239,53 @@ impl StorageBackend for LocalBackend {
         Ok(file)
     }
 
+    async fn put_stream(
+        &self,
+        path: &str,
+        reader: &mut (dyn AsyncRead + Send + Unpin),
+    ) -> Result<StoredFile> {
+        let full_path = self.full_path(path);
+        self.ensure_directory(&full_path).await?;
+
+        // Write next to the target so the final rename stays on one filesystem
+        let (filename, mime_type) = stream_file_info(path);
+        let temp_path = full_path.with_file_name(format!(".{}.{}.part", filename, Uuid::new_v4()));
+        let partial = PartialFile(Some(temp_path.clone()));
+
+        let mut temp = tokio::fs::File::create(&temp_path)
+            .await
+            .map_err(|e| Error::Storage {
+                message: format!("Failed to create temp file: {}", e),
+                source: Some(Box::new(e)),
+            })?;
+        let (size, hash) = write_hashed(&mut temp, reader)
+            .await
+            .map_err(|e| Error::Storage {
+                message: format!("Failed to write file: {}", e),
+                source: Some(Box::new(e)),
+            })?;
+        drop(temp);
+
+        tokio::fs::rename(&temp_path, &full_path)
+            .await
+            .map_err(|e| Error::Storage {
+                message: format!("Failed to move temp file into place: {}", e),
+                source: Some(Box::new(e)),
+            })?;
+        partial.disarm();
+
+        let mut file = StoredFile::new(path, filename, mime_type, size)
+            .with_backend("local")
+            .with_content_hash(hash);
+
+        if let Some(url) = self.url(path) {
+            file = file.with_url(url);
+        }
+
+        tracing::debug!(path = %path, size = size, "File streamed locally");
+        Ok(file)
+    }
+
     async fn get(&self, path: &str) -> Result<Bytes> {
         let full_path = self.full_path(path);
 
@@ -239,12 +410,22 @@ impl StorageBackend for LocalBackend {
             .map(|base| format!("{}/{}", base.trim_end_matches('/'), path))
     }
 
-    async fn temporary_url(&self, path: &str, _expires_in_secs: u64) -> Result<String> {
-        // Local storage doesn't support signed URLs, just return the regular URL
-        self.url(path).ok_or_else(|| Error::Storage {
+    async fn temporary_url(&self, path: &str, expires_in_secs: u64) -> Result<String> {
+        self.signed_url(path, Duration::from_secs(expires_in_secs))
+            .await
+    }
+
+    async fn signed_url(&self, path: &str, expiry: Duration) -> Result<String> {
+        let base = self.base_url.as_ref().ok_or_else(|| Error::Storage {
             message: "Base URL not configured".to_string(),
             source: None,
-        })
+        })?;
+        let signer = self.signer.as_ref().ok_or_else(|| Error::Storage {
+            message: "URL signing secret not configured".to_string(),
+            source: None,
+        })?;
+
+        Ok(signer.signed_url(base, path, expiry))
     }
 
     async fn list(&self, prefix: &str) -> Result<Vec<String>> {
@@ -304,9 +485,13 @@ impl StorageBackend for LocalBackend {
 /// S3-compatible storage backend
 #[cfg(feature = "s3")]
 pub struct S3Backend {
-    store: object_store::aws::AmazonS3,
+    store: Arc<object_store::aws::AmazonS3>,
+    /// Clients sending `x-amz-storage-class`, used to copy objects between tiers
+    class_stores: Vec<(StorageClass, object_store::aws::AmazonS3)>,
     bucket: String,
     base_url: Option<String>,
+    part_size: usize,
+    upload_concurrency: usize,
 }
 
 #[cfg(feature = "s3")]
@@ -319,23 +504,11 @@ impl S3Backend {
     ) -> Result<Self> {
         use object_store::aws::AmazonS3Builder;
 
-        let bucket = bucket.into();
-        let store = AmazonS3Builder::new()
-            .with_bucket_name(&bucket)
+        let builder = AmazonS3Builder::new()
             .with_region(region)
             .with_access_key_id(access_key)
-            .with_secret_access_key(secret_key)
-            .build()
-            .map_err(|e| Error::Storage {
-                message: format!("Failed to create S3 backend: {}", e),
-                source: Some(Box::new(e)),
-            })?;
-
-        Ok(Self {
-            store,
-            bucket,
-            base_url: None,
-        })
+            .with_secret_access_key(secret_key);
+        Self::build(builder, bucket.into())
     }
 
     pub fn with_endpoint(
@@ -343,26 +516,59 @@ impl S3Backend {
         endpoint: impl Into<String>,
         access_key: impl Into<String>,
         secret_key: impl Into<String>,
+    ) -> Result<Self> {
+        Self::with_endpoint_and_region(bucket, endpoint, "us-east-1", access_key, secret_key)
+    }
+
+    /// S3-compatible provider (e.g. MinIO) in a specific region. The region
+    /// is part of every request signature, presigned URLs included.
+    pub fn with_endpoint_and_region(
+        bucket: impl Into<String>,
+        endpoint: impl Into<String>,
+        region: impl Into<String>,
+        access_key: impl Into<String>,
+        secret_key: impl Into<String>,
     ) -> Result<Self> {
         use object_store::aws::AmazonS3Builder;
 
-        let bucket = bucket.into();
-        let store = AmazonS3Builder::new()
-            .with_bucket_name(&bucket)
+        let builder = AmazonS3Builder::new()
             .with_endpoint(endpoint)
+            .with_region(region)
             .with_access_key_id(access_key)
             .with_secret_access_key(secret_key)
-            .with_allow_http(true)
-            .build()
-            .map_err(|e| Error::Storage {
+            .with_allow_http(true);
+        Self::build(builder, bucket.into())
+    }
+
+    fn build(builder: object_store::aws::AmazonS3Builder, bucket: String) -> Result<Self> {
+        use object_store::ClientOptions;
+
+        let builder = builder.with_bucket_name(&bucket);
+        let build = |builder: object_store::aws::AmazonS3Builder| {
+            builder.build().map_err(|e| Error::Storage {
                 message: format!("Failed to create S3 backend: {}", e),
                 source: Some(Box::new(e)),
-            })?;
+            })
+        };
+
+        let mut class_stores = Vec::new();
+        for class in S3_STORAGE_CLASSES {
+            let mut headers = http::HeaderMap::new();
+            headers.insert(
+                "x-amz-storage-class",
+                http::HeaderValue::from_static(s3_storage_class(*class)),
+            );
+            let options = ClientOptions::new().with_default_headers(headers);
+            class_stores.push((*class, build(builder.clone().with_client_options(options))?));
+        }
 
         Ok(Self {
-            store,
+            store: Arc::new(build(builder)?),
+            class_stores,
             bucket,
             base_url: None,
+            part_size: DEFAULT_PART_SIZE,
+            upload_concurrency: DEFAULT_UPLOAD_CONCURRENCY,
         })
     }
 
@@ -370,6 +576,173 @@ impl S3Backend {
         self.base_url = Some(url.into());
         self
     }
+
+    /// Set the multipart part size, raised to [`MIN_PART_SIZE`] if smaller
+    pub fn with_part_size(mut self, bytes: usize) -> Self {
+        self.part_size = bytes.max(MIN_PART_SIZE);
+        self
+    }
+
+    /// Set how many parts are uploaded at once
+    pub fn with_upload_concurrency(mut self, parts: usize) -> Self {
+        self.upload_concurrency = parts.max(1);
+        self
+    }
+
+    /// Upload `first` and the rest of `reader` as a multipart upload,
+    /// returning the total size. The upload is aborted if this fails or is
+    /// dropped before completing.
+    async fn upload_parts(
+        &self,
+        location: &object_store::path::Path,
+        first: Bytes,
+        reader: &mut (dyn AsyncRead + Send + Unpin),
+        hasher: &mut Sha256,
+    ) -> Result<u64> {
+        use futures::stream::{FuturesUnordered, StreamExt};
+        use object_store::multipart::MultiPartStore;
+
+        let id = self
+            .store
+            .create_multipart(location)
+            .await
+            .map_err(|e| Error::Storage {
+                message: format!("Failed to start S3 multipart upload: {}", e),
+                source: Some(Box::new(e)),
+            })?;
+        let pending = PendingMultipart {
+            store: Arc::clone(&self.store),
+            location: location.clone(),
+            id: Some(id.clone()),
+        };
+
+        let store = &self.store;
+        let id_ref = &id;
+        let mut parts = Vec::new();
+        let mut in_flight = FuturesUnordered::new();
+        let mut size = 0u64;
+        let mut part = first;
+
+        loop {
+            let idx = parts.len();
+            let last = part.len() < self.part_size;
+            parts.push(None);
+            size += part.len() as u64;
+            in_flight.push(async move { (idx, store.put_part(location, id_ref, idx, part).await) });
+
+            while in_flight.len() >= self.upload_concurrency {
+                if let Some((idx, result)) = in_flight.next().await {
+                    parts[idx] = Some(result.map_err(|e| Error::Storage {
+                        message: format!("Failed to upload S3 part: {}", e),
+                        source: Some(Box::new(e)),
+                    })?);
+                }
+            }
+
+            if last {
+                break;
+            }
+            part = read_part(reader, self.part_size)
+                .await
+                .map_err(|e| Error::Storage {
+                    message: format!("Failed to read upload stream: {}", e),
+                    source: Some(Box::new(e)),
+                })?;
+            if part.is_empty() {
+                break;
+            }
+            hasher.update(&part);
+        }
+
+        while let Some((idx, result)) = in_flight.next().await {
+            parts[idx] = Some(result.map_err(|e| Error::Storage {
+                message: format!("Failed to upload S3 part: {}", e),
+                source: Some(Box::new(e)),
+            })?);
+        }
+
+        self.store
+            .complete_multipart(location, &id, parts.into_iter().flatten().collect())
+            .await
+            .map_err(|e| Error::Storage {
+                message: format!("Failed to complete S3 multipart upload: {}", e),
+                source: Some(Box::new(e)),
+            })?;
+        pending.disarm();
+
+        Ok(size)
+    }
+}
+
+/// Storage classes the S3 backend moves objects between
+#[cfg(feature = "s3")]
+const S3_STORAGE_CLASSES: &[StorageClass] = &[
+    StorageClass::Standard,
+    StorageClass::InfrequentAccess,
+    StorageClass::Archive,
+];
+
+/// S3 name of a storage class. Archive maps to Glacier Instant Retrieval,
+/// which is read like any other object, so no restore step is needed.
+#[cfg(feature = "s3")]
+fn s3_storage_class(class: StorageClass) -> &'static str {
+    match class {
+        StorageClass::Standard => "STANDARD",
+        StorageClass::InfrequentAccess => "STANDARD_IA",
+        StorageClass::Archive => "GLACIER_IR",
+    }
+}
+
+/// Aborts an unfinished multipart upload so S3 doesn't keep the parts
+#[cfg(feature = "s3")]
+struct PendingMultipart {
+    store: Arc<object_store::aws::AmazonS3>,
+    location: object_store::path::Path,
+    id: Option<object_store::MultipartId>,
+}
+
+#[cfg(feature = "s3")]
+impl PendingMultipart {
+    fn disarm(mut self) {
+        self.id = None;
+    }
+}
+
+#[cfg(feature = "s3")]
+impl Drop for PendingMultipart {
+    fn drop(&mut self) {
+        use object_store::multipart::MultiPartStore;
+
+        let Some(id) = self.id.take() else {
+            return;
+        };
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let store = Arc::clone(&self.store);
+        let location = self.location.clone();
+        handle.spawn(async move {
+            if let Err(e) = store.abort_multipart(&location, &id).await {
+                tracing::warn!(path = %location, error = %e, "Failed to abort S3 multipart upload");
+            }
+        });
+    }
+}
+
+/// Read up to `part_size` bytes, stopping early only at end of stream
+#[cfg(feature = "s3")]
+async fn read_part(
+    reader: &mut (dyn AsyncRead + Send + Unpin),
+    part_size: usize,
+) -> std::io::Result<Bytes> {
+    let mut buf = bytes::BytesMut::with_capacity(part_size);
+    while buf.len() < part_size {
+        let remaining = (part_size - buf.len()) as u64;
+        if (&mut *reader).take(remaining).read_buf(&mut buf).await? == 0 {
+            break;
+        }
+    }
+    Ok(buf.freeze())
 }
 
 #[cfg(feature = "s3")]
@@ -406,19 +779,66 @@ impl StorageBackend for S3Backend {
         Ok(file)
     }
 
-    async fn get(&self, path: &str) -> Result<Bytes> {
+    async fn put_stream(
+        &self,
+        path: &str,
+        reader: &mut (dyn AsyncRead + Send + Unpin),
+    ) -> Result<StoredFile> {
         use object_store::ObjectStore;
 
         let location = object_store::path::Path::from(path);
+        let mut hasher = Sha256::new();
 
-        let result = self
-            .store
-            .get(&location)
+        let first = read_part(reader, self.part_size)
             .await
             .map_err(|e| Error::Storage {
-                message: format!("Failed to get from S3: {}", e),
+                message: format!("Failed to read upload stream: {}", e),
                 source: Some(Box::new(e)),
             })?;
+        hasher.update(&first);
+
+        let size = if first.len() < self.part_size {
+            // Fits in a single request, skip the multipart round trips
+            let size = first.len() as u64;
+            self.store
+                .put(&location, first)
+                .await
+                .map_err(|e| Error::Storage {
+                    message: format!("Failed to upload to S3: {}", e),
+                    source: Some(Box::new(e)),
+                })?;
+            size
+        } else {
+            self.upload_parts(&location, first, reader, &mut hasher)
+                .await?
+        };
+
+        let (filename, mime_type) = stream_file_info(path);
+        let mut file = StoredFile::new(path, filename, mime_type, size)
+            .with_backend("s3")
+            .with_content_hash(format!("{:x}", hasher.finalize()));
+
+        if let Some(url) = self.url(path) {
+            file = file.with_url(url);
+        }
+
+        Ok(file)
+    }
+
+    async fn get(&self, path: &str) -> Result<Bytes> {
+        use object_store::ObjectStore;
+
+        let location = object_store::path::Path::from(path);
+
+        let result = self.store.get(&location).await.map_err(|e| match e {
+            object_store::Error::NotFound { .. } => Error::FileNotFound {
+                path: path.to_string(),
+            },
+            e => Error::Storage {
+                message: format!("Failed to get from S3: {}", e),
+                source: Some(Box::new(e)),
+            },
+        })?;
 
         let bytes = result.bytes().await.map_err(|e| Error::Storage {
             message: format!("Failed to read S3 object: {}", e),
@@ -511,12 +931,27 @@ impl StorageBackend for S3Backend {
             .map(|base| format!("{}/{}", base.trim_end_matches('/'), path))
     }
 
-    async fn temporary_url(&self, path: &str, _expires_in_secs: u64) -> Result<String> {
-        // For a real implementation, you'd use pre-signed URLs
-        self.url(path).ok_or_else(|| Error::Storage {
-            message: "Base URL not configured".to_string(),
-            source: None,
-        })
+    async fn temporary_url(&self, path: &str, expires_in_secs: u64) -> Result<String> {
+        self.signed_url(path, Duration::from_secs(expires_in_secs))
+            .await
+    }
+
+    async fn signed_url(&self, path: &str, expiry: Duration) -> Result<String> {
+        use object_store::signer::Signer;
+
+        // Presigning is local: the URL uses the region and endpoint the
+        // store was built with, so MinIO and other providers work as-is
+        let location = object_store::path::Path::from(path);
+        let url = self
+            .store
+            .signed_url(http::Method::GET, &location, expiry)
+            .await
+            .map_err(|e| Error::Storage {
+                message: format!("Failed to presign S3 URL: {}", e),
+                source: Some(Box::new(e)),
+            })?;
+
+        Ok(url.to_string())
     }
 
     async fn list(&self, prefix: &str) -> Result<Vec<String>> {
@@ -551,6 +986,43 @@ impl StorageBackend for S3Backend {
 
         Ok(())
     }
+
+    fn storage_classes(&self) -> &[StorageClass] {
+        S3_STORAGE_CLASSES
+    }
+
+    async fn set_storage_class(&self, path: &str, class: StorageClass) -> Result<()> {
+        use object_store::ObjectStore;
+
+        let store = self
+            .class_stores
+            .iter()
+            .find(|(c, _)| *c == class)
+            .map(|(_, store)| store)
+            .ok_or_else(|| Error::Storage {
+                message: format!("Unsupported S3 storage class: {}", class.as_str()),
+                source: None,
+            })?;
+
+        // Copying an object onto itself with a new storage class changes
+        // the tier in place; the key and contents stay the same
+        let location = object_store::path::Path::from(path);
+        store
+            .copy(&location, &location)
+            .await
+            .map_err(|e| match e {
+                object_store::Error::NotFound { .. } => Error::FileNotFound {
+                    path: path.to_string(),
+                },
+                e => Error::Storage {
+                    message: format!("Failed to change S3 storage class: {}", e),
+                    source: Some(Box::new(e)),
+                },
+            })?;
+
+        tracing::debug!(path = %path, class = s3_storage_class(class), "S3 storage class changed");
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -619,4 +1091,106 @@ mod tests {
             Some("https://cdn.example.com/uploads/2024/01/test.jpg".to_string())
         );
     }
+
+    #[tokio::test]
+    async fn test_local_backend_put_stream() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = LocalBackend::new(temp_dir.path());
+
+        let content = vec![7u8; STREAM_BUFFER_SIZE * 3 + 11];
+        let mut reader = content.as_slice();
+        let stored = backend
+            .put_stream("2024/03/large.bin", &mut reader)
+            .await
+            .unwrap();
+
+        assert_eq!(stored.size, content.len() as u64);
+        assert_eq!(
+            stored.content_hash,
+            Some(format!("{:x}", Sha256::digest(&content)))
+        );
+        assert_eq!(backend.get("2024/03/large.bin").await.unwrap(), content);
+        assert_eq!(backend.list("2024/03").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_local_backend_put_stream_failure_cleans_up() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = LocalBackend::new(temp_dir.path());
+
+        let mut reader = failing_reader(b"partial data");
+        let result = backend.put_stream("2024/03/broken.bin", &mut reader).await;
+
+        assert!(result.is_err());
+        assert!(!backend.exists("2024/03/broken.bin").await.unwrap());
+        assert!(backend.list("2024/03").await.unwrap().is_empty());
+    }
+
+    /// Reader that yields `data` then fails
+    fn failing_reader(data: &'static [u8]) -> impl AsyncRead + Send + Unpin {
+        let error = std::io::Error::new(std::io::ErrorKind::ConnectionReset, "client went away");
+        data.chain(FailingReader(Some(error)))
+    }
+
+    struct FailingReader(Option<std::io::Error>);
+
+    impl AsyncRead for FailingReader {
+        fn poll_read(
+            mut self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            _buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Err(self.0.take().unwrap()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_local_backend_signed_url() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = LocalBackend::new(temp_dir.path()).with_base_url("/uploads");
+        assert!(backend
+            .signed_url("2024/01/a.pdf", Duration::from_secs(60))
+            .await
+            .is_err());
+
+        let backend = backend.with_signing_secret("secret");
+        let url = backend
+            .signed_url("2024/01/a.pdf", Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert!(url.starts_with("/uploads/2024/01/a.pdf?expires="));
+        assert!(url.contains("&signature="));
+    }
+
+    #[cfg(feature = "s3")]
+    #[tokio::test]
+    async fn test_s3_presign_uses_endpoint_and_region() {
+        let backend = S3Backend::with_endpoint_and_region(
+            "media",
+            "http://localhost:9000",
+            "eu-central-1",
+            "minio",
+            "minio-secret",
+        )
+        .unwrap();
+
+        let url = backend
+            .signed_url("2024/01/a.pdf", Duration::from_secs(300))
+            .await
+            .unwrap();
+        assert!(url.starts_with("http://localhost:9000/media/2024/01/a.pdf?"));
+        assert!(url.contains("eu-central-1"));
+        assert!(url.contains("X-Amz-Expires=300"));
+    }
+
+    #[cfg(feature = "s3")]
+    #[tokio::test]
+    async fn test_read_part_fills_whole_parts() {
+        let content = vec![1u8; 25];
+        let mut reader = content.as_slice().chain(&[2u8; 5][..]);
+
+        assert_eq!(read_part(&mut reader, 20).await.unwrap().len(), 20);
+        assert_eq!(read_part(&mut reader, 20).await.unwrap().len(), 10);
+        assert!(read_part(&mut reader, 20).await.unwrap().is_empty());
+    }
 }
@@ -0,0 +1,93 @@
+//! Content-addressable storage helpers.
+//!
+//! Deduplicated blobs live at `cas/{sha256}` with a `cas/{sha256}.refs`
+//! sidecar holding the reference count. The hash is the same hex SHA-256 the
+//! media library stores as `file_hash`, so both layers agree on identity.
+//!
+//! Reference count updates are serialized per hash within a process; running
+//! several instances against one bucket needs external coordination.
+
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::storage::Storage;
+
+/// Directory holding deduplicated blobs
+pub const CAS_PREFIX: &str = "cas";
+
+/// Suffix of reference count sidecars
+const REFS_SUFFIX: &str = ".refs";
+
+/// Number of lock stripes; hashes are spread across them by their first byte
+const LOCK_STRIPES: usize = 64;
+
+/// Hex SHA-256 of `data`, the key for deduplicated content
+pub fn content_hash(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Storage path of the blob for `hash`
+pub fn blob_path(hash: &str) -> String {
+    format!("{}/{}", CAS_PREFIX, hash)
+}
+
+/// Storage path of the reference count for `hash`
+pub(crate) fn refs_path(hash: &str) -> String {
+    format!("{}/{}{}", CAS_PREFIX, hash, REFS_SUFFIX)
+}
+
+/// The hash a deduplicated blob path refers to, if `path` is one
+pub fn blob_hash(path: &str) -> Option<&str> {
+    let hash = path.strip_prefix(CAS_PREFIX)?.strip_prefix('/')?;
+    let is_hash = hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit());
+    is_hash.then_some(hash)
+}
+
+/// Striped locks serializing reference count updates for the same hash
+pub(crate) struct RefLocks {
+    stripes: Vec<Mutex<()>>,
+}
+
+impl RefLocks {
+    pub(crate) fn new() -> Self {
+        Self {
+            stripes: (0..LOCK_STRIPES).map(|_| Mutex::new(())).collect(),
+        }
+    }
+
+    pub(crate) async fn lock(&self, hash: &str) -> MutexGuard<'_, ()> {
+        let stripe = u8::from_str_radix(hash.get(..2).unwrap_or("00"), 16).unwrap_or(0) as usize;
+        self.stripes[stripe % LOCK_STRIPES].lock().await
+    }
+}
+
+/// Periodically remove deduplicated blobs that no longer have references
+pub fn spawn_gc(storage: Arc<Storage>, interval: Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match storage.collect_garbage().await {
+                Ok(0) => {}
+                Ok(removed) => tracing::info!(removed, "Removed orphaned blobs"),
+                Err(e) => tracing::warn!(error = %e, "Blob garbage collection failed"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blob_paths() {
+        let hash = content_hash(b"hello");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(blob_hash(&blob_path(&hash)), Some(hash.as_str()));
+        assert_eq!(blob_hash(&refs_path(&hash)), None);
+        assert_eq!(blob_hash("2024/01/photo.jpg"), None);
+    }
+}
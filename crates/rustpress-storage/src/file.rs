@@ -27,6 +27,12 @@ pub struct StoredFile {
     pub backend: String,
     /// Public URL (if available)
     pub url: Option<String>,
+    /// SHA-256 of the content, hex encoded (if computed on upload)
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// Storage tier the object lives in
+    #[serde(default)]
+    pub storage_class: StorageClass,
     /// Created timestamp
     pub created_at: DateTime<Utc>,
 }
@@ -55,6 +61,8 @@ impl StoredFile {
             metadata: FileMetadata::default(),
             backend: "local".to_string(),
             url: None,
+            content_hash: None,
+            storage_class: StorageClass::default(),
             created_at: Utc::now(),
         }
     }
@@ -74,6 +82,16 @@ impl StoredFile {
         self
     }
 
+    pub fn with_content_hash(mut self, hash: impl Into<String>) -> Self {
+        self.content_hash = Some(hash.into());
+        self
+    }
+
+    pub fn with_storage_class(mut self, class: StorageClass) -> Self {
+        self.storage_class = class;
+        self
+    }
+
     /// Get file extension
     pub fn extension(&self) -> Option<&str> {
         Path::new(&self.filename)
@@ -128,6 +146,40 @@ impl StoredFile {
     }
 }
 
+/// Storage tier of an object, ordered from hottest to coldest
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageClass {
+    /// Regular storage
+    #[default]
+    Standard,
+    /// Cheaper to keep, more expensive to read (e.g. S3 `STANDARD_IA`)
+    InfrequentAccess,
+    /// Cheapest tier; some backends need a restore before reads
+    Archive,
+}
+
+impl StorageClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::InfrequentAccess => "infrequent_access",
+            Self::Archive => "archive",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "standard" => Some(Self::Standard),
+            "infrequent_access" => Some(Self::InfrequentAccess),
+            "archive" => Some(Self::Archive),
+            _ => None,
+        }
+    }
+}
+
 /// File metadata
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FileMetadata {
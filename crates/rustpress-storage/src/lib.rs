@@ -3,11 +3,19 @@
 //! File storage abstraction supporting local and cloud storage backends.
 
 pub mod backend;
+pub mod dedup;
 pub mod file;
+pub mod lifecycle;
+pub mod signing;
 pub mod storage;
 
 pub use backend::{LocalBackend, StorageBackend};
-pub use file::{FileMetadata, StoredFile};
+pub use file::{FileMetadata, StorageClass, StoredFile};
+pub use lifecycle::{
+    AccessLog, AccessRecord, InMemoryAccessLog, LifecycleConfig, LifecycleManager, LifecycleReport,
+    LifecycleRule, RestoreStatus, Retrieval, Transition,
+};
+pub use signing::{SignatureError, UrlSigner};
 pub use storage::{Storage, StorageConfig};
 
 #[cfg(feature = "s3")]
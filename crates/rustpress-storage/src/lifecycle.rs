@@ -0,0 +1,560 @@
+//! Storage lifecycle: moving objects nobody reads to cheaper tiers.
+//!
+//! Reads and uploads made through [`Storage`](crate::Storage) are recorded
+//! in an [`AccessLog`]. A scheduled run of [`LifecycleManager`] picks the
+//! objects that have been idle longer than a rule allows and changes their
+//! storage class in place, so paths and URLs keep working. Recent uploads
+//! are never moved, however quiet they have been.
+
+use crate::backend::StorageBackend;
+use crate::file::{StorageClass, StoredFile};
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Duration, Utc};
+use rustpress_core::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Whether an archived object can be read right now
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RestoreStatus {
+    /// The object can be read
+    Ready,
+    /// A restore is running; reads fail until it finishes
+    InProgress {
+        /// Expected completion, if the backend knows it
+        ready_at: Option<DateTime<Utc>>,
+    },
+}
+
+/// Result of reading an object that may be archived
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Retrieval {
+    /// Object contents
+    Ready(Bytes),
+    /// The object is archived and being restored; retry later
+    Restoring {
+        /// Expected completion, if the backend knows it
+        ready_at: Option<DateTime<Utc>>,
+    },
+}
+
+/// Tracked state of a stored object
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessRecord {
+    /// Storage path
+    pub path: String,
+    /// Current storage class
+    pub storage_class: StorageClass,
+    /// File size in bytes
+    pub size: u64,
+    /// When the object was uploaded
+    pub created_at: DateTime<Utc>,
+    /// Last read, if any
+    pub last_accessed_at: Option<DateTime<Utc>>,
+}
+
+impl AccessRecord {
+    pub fn new(file: &StoredFile) -> Self {
+        Self {
+            path: file.path.clone(),
+            storage_class: file.storage_class,
+            size: file.size,
+            created_at: file.created_at,
+            last_accessed_at: None,
+        }
+    }
+
+    /// Last read, or the upload time for objects never read
+    pub fn last_used(&self) -> DateTime<Utc> {
+        self.last_accessed_at.unwrap_or(self.created_at)
+    }
+
+    /// Whether this object is due for a move to `class`
+    fn is_candidate(
+        &self,
+        class: StorageClass,
+        created_before: DateTime<Utc>,
+        idle_since: DateTime<Utc>,
+    ) -> bool {
+        self.storage_class < class
+            && self.created_at < created_before
+            && self.last_used() < idle_since
+    }
+}
+
+/// Access-time tracking for stored objects
+#[async_trait]
+pub trait AccessLog: Send + Sync {
+    /// Start tracking a newly stored object
+    async fn record_upload(&self, file: &StoredFile) -> Result<()>;
+
+    /// Note a read of `path`. Untracked paths are ignored.
+    async fn record_access(&self, path: &str, at: DateTime<Utc>) -> Result<()>;
+
+    /// Tracked state of `path`
+    async fn get(&self, path: &str) -> Result<Option<AccessRecord>>;
+
+    /// Objects in a class warmer than `class`, uploaded before
+    /// `created_before` and not used since `idle_since`, least recently
+    /// used first
+    async fn cold_candidates(
+        &self,
+        class: StorageClass,
+        created_before: DateTime<Utc>,
+        idle_since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<AccessRecord>>;
+
+    /// Record that `path` now lives in `class`
+    async fn set_storage_class(&self, path: &str, class: StorageClass) -> Result<()>;
+
+    /// Stop tracking `path`
+    async fn remove(&self, path: &str) -> Result<()>;
+}
+
+/// Access log kept in memory, for tests and single-process setups
+#[derive(Default)]
+pub struct InMemoryAccessLog {
+    records: RwLock<HashMap<String, AccessRecord>>,
+}
+
+impl InMemoryAccessLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track `record` as-is, replacing any existing record for its path
+    pub fn insert(&self, record: AccessRecord) {
+        self.records
+            .write()
+            .unwrap()
+            .insert(record.path.clone(), record);
+    }
+}
+
+#[async_trait]
+impl AccessLog for InMemoryAccessLog {
+    async fn record_upload(&self, file: &StoredFile) -> Result<()> {
+        self.insert(AccessRecord::new(file));
+        Ok(())
+    }
+
+    async fn record_access(&self, path: &str, at: DateTime<Utc>) -> Result<()> {
+        if let Some(record) = self.records.write().unwrap().get_mut(path) {
+            record.last_accessed_at = Some(at);
+        }
+        Ok(())
+    }
+
+    async fn get(&self, path: &str) -> Result<Option<AccessRecord>> {
+        Ok(self.records.read().unwrap().get(path).cloned())
+    }
+
+    async fn cold_candidates(
+        &self,
+        class: StorageClass,
+        created_before: DateTime<Utc>,
+        idle_since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<AccessRecord>> {
+        let mut candidates: Vec<_> = self
+            .records
+            .read()
+            .unwrap()
+            .values()
+            .filter(|r| r.is_candidate(class, created_before, idle_since))
+            .cloned()
+            .collect();
+        candidates.sort_by_key(|r| r.last_used());
+        candidates.truncate(limit);
+        Ok(candidates)
+    }
+
+    async fn set_storage_class(&self, path: &str, class: StorageClass) -> Result<()> {
+        if let Some(record) = self.records.write().unwrap().get_mut(path) {
+            record.storage_class = class;
+        }
+        Ok(())
+    }
+
+    async fn remove(&self, path: &str) -> Result<()> {
+        self.records.write().unwrap().remove(path);
+        Ok(())
+    }
+}
+
+/// Move objects idle for `idle_days` to `storage_class`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecycleRule {
+    /// Days without reads before the rule applies
+    pub idle_days: u32,
+    /// Target storage class
+    pub storage_class: StorageClass,
+}
+
+/// Lifecycle configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecycleConfig {
+    /// Transition rules; rules for classes the backend lacks are skipped
+    pub rules: Vec<LifecycleRule>,
+    /// Objects younger than this are never moved
+    pub min_age_days: u32,
+    /// Most objects moved per rule in one run
+    pub batch_size: usize,
+}
+
+impl Default for LifecycleConfig {
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                LifecycleRule {
+                    idle_days: 30,
+                    storage_class: StorageClass::InfrequentAccess,
+                },
+                LifecycleRule {
+                    idle_days: 180,
+                    storage_class: StorageClass::Archive,
+                },
+            ],
+            min_age_days: 30,
+            batch_size: 500,
+        }
+    }
+}
+
+/// An object moved to another storage class
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transition {
+    pub path: String,
+    pub from: StorageClass,
+    pub to: StorageClass,
+}
+
+/// Outcome of a lifecycle run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LifecycleReport {
+    /// Objects moved
+    pub transitioned: Vec<Transition>,
+    /// Objects that could not be moved, with the error
+    pub failed: Vec<(String, String)>,
+    /// Tracked objects found missing from the backend and forgotten
+    pub missing: Vec<String>,
+}
+
+/// Applies [`LifecycleConfig`] rules to tracked objects
+pub struct LifecycleManager {
+    backend: Arc<dyn StorageBackend>,
+    access_log: Arc<dyn AccessLog>,
+    config: LifecycleConfig,
+}
+
+impl LifecycleManager {
+    pub fn new(
+        backend: Arc<dyn StorageBackend>,
+        access_log: Arc<dyn AccessLog>,
+        config: LifecycleConfig,
+    ) -> Self {
+        Self {
+            backend,
+            access_log,
+            config,
+        }
+    }
+
+    /// Run all rules now
+    pub async fn run(&self) -> Result<LifecycleReport> {
+        self.run_at(Utc::now()).await
+    }
+
+    /// Run all rules as of `now`
+    pub async fn run_at(&self, now: DateTime<Utc>) -> Result<LifecycleReport> {
+        let mut report = LifecycleReport::default();
+        let created_before = now - Duration::days(i64::from(self.config.min_age_days));
+
+        // Coldest first, so an object idle long enough for the archive goes
+        // straight there instead of stopping at every tier on the way
+        let mut rules = self.config.rules.clone();
+        rules.sort_by_key(|r| std::cmp::Reverse(r.storage_class));
+
+        for rule in rules {
+            if !self.backend.storage_classes().contains(&rule.storage_class) {
+                tracing::debug!(
+                    backend = self.backend.name(),
+                    class = rule.storage_class.as_str(),
+                    "Skipping lifecycle rule for unsupported storage class"
+                );
+                continue;
+            }
+
+            let idle_since = now - Duration::days(i64::from(rule.idle_days));
+            let candidates = self
+                .access_log
+                .cold_candidates(
+                    rule.storage_class,
+                    created_before,
+                    idle_since,
+                    self.config.batch_size,
+                )
+                .await?;
+
+            for record in candidates {
+                match self
+                    .backend
+                    .set_storage_class(&record.path, rule.storage_class)
+                    .await
+                {
+                    Ok(()) => {
+                        self.access_log
+                            .set_storage_class(&record.path, rule.storage_class)
+                            .await?;
+                        report.transitioned.push(Transition {
+                            path: record.path,
+                            from: record.storage_class,
+                            to: rule.storage_class,
+                        });
+                    }
+                    Err(Error::FileNotFound { .. }) => {
+                        self.access_log.remove(&record.path).await?;
+                        report.missing.push(record.path);
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            path = %record.path,
+                            class = rule.storage_class.as_str(),
+                            error = %e,
+                            "Failed to change storage class"
+                        );
+                        report.failed.push((record.path, e.to_string()));
+                    }
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::LocalBackend;
+    use crate::file::UploadRequest;
+    use crate::Storage;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+    use tokio::io::AsyncRead;
+
+    /// Local backend pretending to have storage tiers, with archived
+    /// objects needing a restore
+    struct TieredBackend {
+        inner: LocalBackend,
+        classes: Mutex<HashMap<String, StorageClass>>,
+        restore: RestoreStatus,
+    }
+
+    #[async_trait]
+    impl StorageBackend for TieredBackend {
+        fn name(&self) -> &str {
+            "tiered"
+        }
+        async fn store(&self, request: UploadRequest) -> Result<StoredFile> {
+            self.inner.store(request).await
+        }
+        async fn put_stream(
+            &self,
+            path: &str,
+            reader: &mut (dyn AsyncRead + Send + Unpin),
+        ) -> Result<StoredFile> {
+            self.inner.put_stream(path, reader).await
+        }
+        async fn get(&self, path: &str) -> Result<Bytes> {
+            self.inner.get(path).await
+        }
+        async fn delete(&self, path: &str) -> Result<bool> {
+            self.inner.delete(path).await
+        }
+        async fn exists(&self, path: &str) -> Result<bool> {
+            self.inner.exists(path).await
+        }
+        async fn size(&self, path: &str) -> Result<u64> {
+            self.inner.size(path).await
+        }
+        async fn copy(&self, from: &str, to: &str) -> Result<StoredFile> {
+            self.inner.copy(from, to).await
+        }
+        async fn move_file(&self, from: &str, to: &str) -> Result<StoredFile> {
+            self.inner.move_file(from, to).await
+        }
+        fn url(&self, path: &str) -> Option<String> {
+            self.inner.url(path)
+        }
+        async fn temporary_url(&self, path: &str, expires_in_secs: u64) -> Result<String> {
+            self.inner.temporary_url(path, expires_in_secs).await
+        }
+        async fn signed_url(&self, path: &str, expiry: std::time::Duration) -> Result<String> {
+            self.inner.signed_url(path, expiry).await
+        }
+        async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+            self.inner.list(prefix).await
+        }
+        async fn health_check(&self) -> Result<()> {
+            self.inner.health_check().await
+        }
+        fn storage_classes(&self) -> &[StorageClass] {
+            &[
+                StorageClass::Standard,
+                StorageClass::InfrequentAccess,
+                StorageClass::Archive,
+            ]
+        }
+        async fn set_storage_class(&self, path: &str, class: StorageClass) -> Result<()> {
+            if !self.inner.exists(path).await? {
+                return Err(Error::FileNotFound {
+                    path: path.to_string(),
+                });
+            }
+            self.classes.lock().unwrap().insert(path.to_string(), class);
+            Ok(())
+        }
+        async fn restore(&self, _path: &str) -> Result<RestoreStatus> {
+            Ok(self.restore.clone())
+        }
+    }
+
+    fn tiered(temp_dir: &TempDir, restore: RestoreStatus) -> Arc<TieredBackend> {
+        Arc::new(TieredBackend {
+            inner: LocalBackend::new(temp_dir.path()),
+            classes: Mutex::new(HashMap::new()),
+            restore,
+        })
+    }
+
+    fn record(
+        path: &str,
+        age_days: i64,
+        idle_days: Option<i64>,
+        now: DateTime<Utc>,
+    ) -> AccessRecord {
+        AccessRecord {
+            path: path.to_string(),
+            storage_class: StorageClass::Standard,
+            size: 1,
+            created_at: now - Duration::days(age_days),
+            last_accessed_at: idle_days.map(|d| now - Duration::days(d)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_moves_idle_objects() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = tiered(&temp_dir, RestoreStatus::Ready);
+        let log = Arc::new(InMemoryAccessLog::new());
+        let now = Utc::now();
+
+        for path in [
+            "a/never-read",
+            "a/read-40-days-ago",
+            "a/read-yesterday",
+            "a/new",
+        ] {
+            backend.put_stream(path, &mut &b"x"[..]).await.unwrap();
+        }
+        log.insert(record("a/never-read", 400, None, now));
+        log.insert(record("a/read-40-days-ago", 400, Some(40), now));
+        log.insert(record("a/read-yesterday", 400, Some(1), now));
+        // Never read, but uploaded too recently to move
+        log.insert(record("a/new", 5, None, now));
+        log.insert(record("a/deleted-elsewhere", 400, None, now));
+
+        let manager = LifecycleManager::new(
+            backend.clone(),
+            log.clone(),
+            LifecycleConfig {
+                min_age_days: 7,
+                ..Default::default()
+            },
+        );
+        let report = manager.run_at(now).await.unwrap();
+
+        let class = |path: &str| backend.classes.lock().unwrap().get(path).copied();
+        assert_eq!(class("a/never-read"), Some(StorageClass::Archive));
+        assert_eq!(
+            class("a/read-40-days-ago"),
+            Some(StorageClass::InfrequentAccess)
+        );
+        assert_eq!(class("a/read-yesterday"), None);
+        assert_eq!(class("a/new"), None);
+        assert_eq!(report.transitioned.len(), 2);
+        assert_eq!(report.missing, vec!["a/deleted-elsewhere".to_string()]);
+        assert_eq!(
+            log.get("a/never-read")
+                .await
+                .unwrap()
+                .unwrap()
+                .storage_class,
+            StorageClass::Archive
+        );
+        assert!(log.get("a/deleted-elsewhere").await.unwrap().is_none());
+
+        // Nothing left to do on the next run
+        let report = manager.run_at(now).await.unwrap();
+        assert!(report.transitioned.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_archived_reports_restoring() {
+        let temp_dir = TempDir::new().unwrap();
+        let ready_at = Utc::now() + Duration::hours(4);
+        let backend = tiered(
+            &temp_dir,
+            RestoreStatus::InProgress {
+                ready_at: Some(ready_at),
+            },
+        );
+        let log = Arc::new(InMemoryAccessLog::new());
+        let storage = Storage::new(backend.clone()).with_access_log(log.clone());
+
+        let file = storage
+            .upload(Bytes::from("cold"), "cold.txt", "text/plain")
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.retrieve(&file.path).await.unwrap(),
+            Retrieval::Ready(Bytes::from("cold"))
+        );
+        assert!(log
+            .get(&file.path)
+            .await
+            .unwrap()
+            .unwrap()
+            .last_accessed_at
+            .is_some());
+
+        log.set_storage_class(&file.path, StorageClass::Archive)
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.retrieve(&file.path).await.unwrap(),
+            Retrieval::Restoring {
+                ready_at: Some(ready_at)
+            }
+        );
+
+        storage.delete(&file.path).await.unwrap();
+        assert!(log.get(&file.path).await.unwrap().is_none());
+
+        // Backends without tiers refuse anything but the standard class
+        let local = LocalBackend::new(temp_dir.path());
+        assert!(local
+            .set_storage_class(&file.path, StorageClass::Standard)
+            .await
+            .is_ok());
+        assert!(local
+            .set_storage_class(&file.path, StorageClass::Archive)
+            .await
+            .is_err());
+    }
+}
@@ -1,10 +1,16 @@
 //! High-level storage API.
 
 use crate::backend::StorageBackend;
-use crate::file::{FileMetadata, StoredFile, UploadRequest};
+use crate::dedup::{self, RefLocks};
+use crate::file::{FileMetadata, StorageClass, StoredFile, UploadRequest};
+use crate::lifecycle::{AccessLog, LifecycleConfig, LifecycleManager, RestoreStatus, Retrieval};
+use crate::signing::{SignatureError, UrlSigner};
 use bytes::Bytes;
+use chrono::Utc;
 use rustpress_core::error::{Error, Result};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncRead;
 
 /// Storage configuration
 #[derive(Debug, Clone)]
@@ -19,6 +25,8 @@ pub struct StorageConfig {
     pub default_directory: Option<String>,
     /// CDN URL for serving files
     pub cdn_url: Option<String>,
+    /// Secret for HMAC-signed local URLs (must match the backend's)
+    pub url_signing_secret: Option<String>,
 }
 
 impl Default for StorageConfig {
@@ -45,6 +53,7 @@ impl Default for StorageConfig {
             ],
             default_directory: None,
             cdn_url: None,
+            url_signing_secret: None,
         }
     }
 }
@@ -53,20 +62,30 @@ impl Default for StorageConfig {
 pub struct Storage {
     backend: Arc<dyn StorageBackend>,
     config: StorageConfig,
+    ref_locks: RefLocks,
+    access_log: Option<Arc<dyn AccessLog>>,
 }
 
 impl Storage {
     /// Create new storage with backend
     pub fn new(backend: Arc<dyn StorageBackend>) -> Self {
+        Self::with_config(backend, StorageConfig::default())
+    }
+
+    /// Create storage with custom configuration
+    pub fn with_config(backend: Arc<dyn StorageBackend>, config: StorageConfig) -> Self {
         Self {
             backend,
-            config: StorageConfig::default(),
+            config,
+            ref_locks: RefLocks::new(),
+            access_log: None,
         }
     }
 
-    /// Create storage with custom configuration
-    pub fn with_config(backend: Arc<dyn StorageBackend>, config: StorageConfig) -> Self {
-        Self { backend, config }
+    /// Track uploads and reads so lifecycle rules can find idle objects
+    pub fn with_access_log(mut self, access_log: Arc<dyn AccessLog>) -> Self {
+        self.access_log = Some(access_log);
+        self
     }
 
     /// Upload a file
@@ -83,7 +102,7 @@ impl Storage {
             request = request.with_directory(dir);
         }
 
-        self.backend.store(request).await
+        self.stored(self.backend.store(request).await?).await
     }
 
     /// Upload a file with metadata
@@ -101,7 +120,7 @@ impl Storage {
             request = request.with_directory(dir);
         }
 
-        self.backend.store(request).await
+        self.stored(self.backend.store(request).await?).await
     }
 
     /// Upload to a specific directory
@@ -115,17 +134,175 @@ impl Storage {
         self.validate_upload(&content, mime_type)?;
 
         let request = UploadRequest::new(content, filename, mime_type).with_directory(directory);
-        self.backend.store(request).await
+        self.stored(self.backend.store(request).await?).await
+    }
+
+    /// Stream a large file to `path` without buffering it in memory
+    pub async fn put_stream(
+        &self,
+        path: &str,
+        reader: &mut (dyn AsyncRead + Send + Unpin),
+    ) -> Result<StoredFile> {
+        self.stored(self.backend.put_stream(path, reader).await?)
+            .await
     }
 
     /// Get file contents
     pub async fn get(&self, path: &str) -> Result<Bytes> {
-        self.backend.get(path).await
+        let content = self.backend.get(path).await?;
+        if let Some(log) = &self.access_log {
+            if let Err(e) = log.record_access(path, Utc::now()).await {
+                tracing::warn!(path = %path, error = %e, "Failed to record file access");
+            }
+        }
+        Ok(content)
+    }
+
+    /// Get file contents, or the restore status if the file is archived
+    /// and not readable yet. Starts a restore when one is needed.
+    pub async fn retrieve(&self, path: &str) -> Result<Retrieval> {
+        let class = match &self.access_log {
+            Some(log) => log.get(path).await?.map(|r| r.storage_class),
+            None => None,
+        };
+        if class == Some(StorageClass::Archive) {
+            if let RestoreStatus::InProgress { ready_at } = self.backend.restore(path).await? {
+                return Ok(Retrieval::Restoring { ready_at });
+            }
+        }
+        Ok(Retrieval::Ready(self.get(path).await?))
+    }
+
+    /// Start tracking a newly written file
+    async fn stored(&self, file: StoredFile) -> Result<StoredFile> {
+        if let Some(log) = &self.access_log {
+            if let Err(e) = log.record_upload(&file).await {
+                tracing::warn!(path = %file.path, error = %e, "Failed to record upload");
+            }
+        }
+        Ok(file)
+    }
+
+    /// Stop tracking a removed file
+    async fn forget(&self, path: &str) {
+        if let Some(log) = &self.access_log {
+            if let Err(e) = log.remove(path).await {
+                tracing::warn!(path = %path, error = %e, "Failed to forget deleted file");
+            }
+        }
     }
 
-    /// Delete a file
+    /// Store content once per distinct hash. Returns the blob and whether
+    /// it was newly written; either way the blob gains a reference.
+    pub async fn put_dedup(&self, content: Bytes) -> Result<(StoredFile, bool)> {
+        self.validate_size(&content)?;
+
+        let hash = dedup::content_hash(&content);
+        let path = dedup::blob_path(&hash);
+        let _lock = self.ref_locks.lock(&hash).await;
+
+        let refs = self.read_refs(&hash).await?;
+        let was_new = !self.backend.exists(&path).await?;
+        let file = if was_new {
+            let file = self
+                .backend
+                .put_stream(&path, &mut content.as_ref())
+                .await?;
+            self.stored(file).await?
+        } else {
+            let mut file = StoredFile::new(
+                &path,
+                &hash,
+                "application/octet-stream",
+                content.len() as u64,
+            )
+            .with_backend(self.backend.name())
+            .with_content_hash(&hash);
+            if let Some(url) = self.backend.url(&path) {
+                file = file.with_url(url);
+            }
+            file
+        };
+
+        self.write_refs(&hash, refs + 1).await?;
+        Ok((file, was_new))
+    }
+
+    /// Delete a file. For deduplicated blobs this releases one reference and
+    /// only removes the blob once the last one is gone.
     pub async fn delete(&self, path: &str) -> Result<bool> {
-        self.backend.delete(path).await
+        let Some(hash) = dedup::blob_hash(path) else {
+            let deleted = self.backend.delete(path).await?;
+            self.forget(path).await;
+            return Ok(deleted);
+        };
+
+        let _lock = self.ref_locks.lock(hash).await;
+        match self.read_refs(hash).await? {
+            0 => Ok(false),
+            1 => {
+                // Refs first: a crash in between leaves an orphan for GC
+                self.backend.delete(&dedup::refs_path(hash)).await?;
+                self.backend.delete(path).await?;
+                self.forget(path).await;
+                Ok(true)
+            }
+            refs => {
+                self.write_refs(hash, refs - 1).await?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// Current reference count of a deduplicated blob
+    pub async fn ref_count(&self, hash: &str) -> Result<u64> {
+        let _lock = self.ref_locks.lock(hash).await;
+        self.read_refs(hash).await
+    }
+
+    /// Remove deduplicated blobs left without references, e.g. by a crash
+    /// between writing a blob and recording its reference. Returns the
+    /// number of blobs removed.
+    pub async fn collect_garbage(&self) -> Result<usize> {
+        let paths = match self.backend.list(dedup::CAS_PREFIX).await {
+            Ok(paths) => paths,
+            Err(Error::FileNotFound { .. }) => return Ok(0),
+            Err(e) => return Err(e),
+        };
+
+        let mut removed = 0;
+        for path in &paths {
+            let Some(hash) = dedup::blob_hash(path) else {
+                continue;
+            };
+            let _lock = self.ref_locks.lock(hash).await;
+            if self.read_refs(hash).await? == 0 && self.backend.delete(path).await? {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    async fn read_refs(&self, hash: &str) -> Result<u64> {
+        match self.backend.get(&dedup::refs_path(hash)).await {
+            Ok(bytes) => std::str::from_utf8(&bytes)
+                .ok()
+                .and_then(|s| s.trim().parse().ok())
+                .ok_or_else(|| Error::Storage {
+                    message: format!("Corrupt reference count for blob {}", hash),
+                    source: None,
+                }),
+            Err(Error::FileNotFound { .. }) => Ok(0),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn write_refs(&self, hash: &str, refs: u64) -> Result<()> {
+        let count = refs.to_string();
+        self.backend
+            .put_stream(&dedup::refs_path(hash), &mut count.as_bytes())
+            .await?;
+        Ok(())
     }
 
     /// Check if file exists
@@ -163,6 +340,26 @@ impl Storage {
         self.backend.temporary_url(path, expires_in_secs).await
     }
 
+    /// Get a time-limited URL for a private file
+    pub async fn signed_url(&self, path: &str, expiry: Duration) -> Result<String> {
+        self.backend.signed_url(path, expiry).await
+    }
+
+    /// Check the `expires` and `signature` of a signed local URL
+    pub fn verify_signed_url(
+        &self,
+        path: &str,
+        expires: i64,
+        signature: &str,
+    ) -> std::result::Result<(), SignatureError> {
+        let secret = self
+            .config
+            .url_signing_secret
+            .as_ref()
+            .ok_or(SignatureError::NotConfigured)?;
+        UrlSigner::new(secret).verify(path, expires, signature)
+    }
+
     /// List files in directory
     pub async fn list(&self, prefix: &str) -> Result<Vec<String>> {
         self.backend.list(prefix).await
@@ -173,6 +370,17 @@ impl Storage {
         self.backend.health_check().await
     }
 
+    /// Lifecycle manager over this storage's backend and access log, or
+    /// `None` if uploads and reads aren't tracked
+    pub fn lifecycle_manager(&self, config: LifecycleConfig) -> Option<LifecycleManager> {
+        let access_log = self.access_log.clone()?;
+        Some(LifecycleManager::new(
+            Arc::clone(&self.backend),
+            access_log,
+            config,
+        ))
+    }
+
     /// Get backend name
     pub fn backend_name(&self) -> &str {
         self.backend.name()
@@ -180,16 +388,7 @@ impl Storage {
 
     /// Validate upload
     fn validate_upload(&self, content: &Bytes, mime_type: &str) -> Result<()> {
-        // Check size
-        if content.len() as u64 > self.config.max_upload_size {
-            return Err(Error::InvalidInput {
-                field: "file".to_string(),
-                message: format!(
-                    "File too large. Maximum size is {} bytes",
-                    self.config.max_upload_size
-                ),
-            });
-        }
+        self.validate_size(content)?;
 
         // Check denied types
         if self.config.denied_types.iter().any(|t| t == mime_type) {
@@ -211,6 +410,21 @@ impl Storage {
 
         Ok(())
     }
+
+    /// Validate upload size
+    fn validate_size(&self, content: &Bytes) -> Result<()> {
+        if content.len() as u64 > self.config.max_upload_size {
+            return Err(Error::InvalidInput {
+                field: "file".to_string(),
+                message: format!(
+                    "File too large. Maximum size is {} bytes",
+                    self.config.max_upload_size
+                ),
+            });
+        }
+
+        Ok(())
+    }
 }
 
 /// MIME type detection utilities
@@ -360,6 +574,111 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_storage_signed_url_verifies() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = LocalBackend::new(temp_dir.path())
+            .with_base_url("/uploads")
+            .with_signing_secret("secret");
+        let config = StorageConfig {
+            url_signing_secret: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let storage = Storage::with_config(Arc::new(backend), config);
+
+        let url = storage
+            .signed_url("2024/01/a.pdf", Duration::from_secs(60))
+            .await
+            .unwrap();
+        let query = url.split_once('?').unwrap().1;
+        let (expires, signature) = query.split_once('&').unwrap();
+        let expires = expires.trim_start_matches("expires=").parse().unwrap();
+        let signature = signature.trim_start_matches("signature=");
+
+        assert!(storage
+            .verify_signed_url("2024/01/a.pdf", expires, signature)
+            .is_ok());
+        assert_eq!(
+            storage.verify_signed_url("2024/01/b.pdf", expires, signature),
+            Err(SignatureError::Invalid)
+        );
+        assert_eq!(
+            create_test_storage()
+                .0
+                .verify_signed_url("2024/01/a.pdf", expires, signature),
+            Err(SignatureError::NotConfigured)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_put_dedup_reference_counts() {
+        let (storage, _temp) = create_test_storage();
+        let content = Bytes::from("same bytes");
+
+        let (first, was_new) = storage.put_dedup(content.clone()).await.unwrap();
+        assert!(was_new);
+        let (second, was_new) = storage.put_dedup(content.clone()).await.unwrap();
+        assert!(!was_new);
+        assert_eq!(first.path, second.path);
+        assert_eq!(first.content_hash, second.content_hash);
+
+        let hash = first.content_hash.clone().unwrap();
+        assert_eq!(storage.ref_count(&hash).await.unwrap(), 2);
+
+        assert!(storage.delete(&first.path).await.unwrap());
+        assert!(storage.exists(&first.path).await.unwrap());
+        assert!(storage.delete(&first.path).await.unwrap());
+        assert!(!storage.exists(&first.path).await.unwrap());
+        assert!(!storage.delete(&first.path).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_put_dedup_concurrent() {
+        let (storage, _temp) = create_test_storage();
+        let storage = Arc::new(storage);
+
+        let puts = (0..16).map(|_| {
+            let storage = Arc::clone(&storage);
+            tokio::spawn(async move { storage.put_dedup(Bytes::from("shared")).await })
+        });
+        let results = futures::future::join_all(puts).await;
+        let new_count = results
+            .into_iter()
+            .filter(|r| r.as_ref().unwrap().as_ref().unwrap().1)
+            .count();
+        assert_eq!(new_count, 1);
+
+        let hash = dedup::content_hash(b"shared");
+        assert_eq!(storage.ref_count(&hash).await.unwrap(), 16);
+
+        let deletes = (0..16).map(|_| {
+            let storage = Arc::clone(&storage);
+            let path = dedup::blob_path(&hash);
+            tokio::spawn(async move { storage.delete(&path).await })
+        });
+        futures::future::join_all(deletes).await;
+        assert_eq!(storage.ref_count(&hash).await.unwrap(), 0);
+        assert!(!storage.exists(&dedup::blob_path(&hash)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_collect_garbage_removes_orphans() {
+        let (storage, _temp) = create_test_storage();
+        assert_eq!(storage.collect_garbage().await.unwrap(), 0);
+
+        let (kept, _) = storage.put_dedup(Bytes::from("kept")).await.unwrap();
+        let orphan = dedup::blob_path(&dedup::content_hash(b"orphan"));
+        storage
+            .backend
+            .put_stream(&orphan, &mut &b"orphan"[..])
+            .await
+            .unwrap();
+
+        assert_eq!(storage.collect_garbage().await.unwrap(), 1);
+        assert!(!storage.exists(&orphan).await.unwrap());
+        assert!(storage.exists(&kept.path).await.unwrap());
+    }
+
     #[test]
     fn test_mime_detector() {
         assert_eq!(MimeDetector::from_extension("jpg"), Some("image/jpeg"));
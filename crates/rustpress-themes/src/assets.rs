@@ -4,7 +4,7 @@
 
 use blake3::Hasher;
 use parking_lot::RwLock;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use thiserror::Error;
@@ -22,6 +22,14 @@ pub enum AssetError {
     #[error("SCSS compilation error: {0}")]
     ScssCompilation(String),
 
+    #[error("SCSS compilation error in {file}:{line}:{column}: {message}")]
+    ScssSyntax {
+        file: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
     #[error("JavaScript error: {0}")]
     JavaScript(String),
 
@@ -32,11 +40,37 @@ pub enum AssetError {
     InvalidPath(String),
 }
 
+impl AssetError {
+    fn from_sass(error: Box<grass::Error>) -> Self {
+        match error.kind() {
+            grass::ErrorKind::ParseError { message, loc, .. } => Self::ScssSyntax {
+                file: loc.file.name().to_string(),
+                line: loc.begin.line + 1,
+                column: loc.begin.column + 1,
+                message,
+            },
+            grass::ErrorKind::IoError(e) => Self::ScssCompilation(e.to_string()),
+            grass::ErrorKind::FromUtf8Error(e) => Self::ScssCompilation(e),
+            _ => Self::ScssCompilation("unknown SCSS error".to_string()),
+        }
+    }
+}
+
+impl From<AssetError> for crate::ThemeError {
+    fn from(error: AssetError) -> Self {
+        match error {
+            AssetError::Io(e) => crate::ThemeError::Io(e),
+            e => crate::ThemeError::Asset(e.to_string()),
+        }
+    }
+}
+
 /// Asset compiler configuration
 #[derive(Debug, Clone)]
 pub struct AssetConfig {
     pub minify: bool,
-    pub sourcemaps: bool,
+    /// Emit `.map` files next to compiled stylesheets (development only)
+    pub source_maps: bool,
     pub bundle: bool,
     pub cache_bust: bool,
     pub output_dir: PathBuf,
@@ -47,7 +81,7 @@ impl Default for AssetConfig {
     fn default() -> Self {
         Self {
             minify: true,
-            sourcemaps: true,
+            source_maps: false,
             bundle: true,
             cache_bust: true,
             output_dir: PathBuf::from("dist/assets"),
@@ -56,6 +90,71 @@ impl Default for AssetConfig {
     }
 }
 
+impl AssetConfig {
+    /// Configuration for local development: readable output with source maps
+    pub fn development() -> Self {
+        Self {
+            minify: false,
+            source_maps: true,
+            cache_bust: false,
+            ..Default::default()
+        }
+    }
+}
+
+/// SCSS `@import`/`@use`/`@forward` dependency graph.
+///
+/// Tracks which files each entry point pulls in (transitively), so a watch
+/// mode can recompile only the entry points affected by a changed partial.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    /// Entry point -> every file it depends on
+    dependencies: HashMap<PathBuf, BTreeSet<PathBuf>>,
+}
+
+impl DependencyGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the recorded dependencies of an entry point
+    pub fn update(&mut self, entry: PathBuf, dependencies: BTreeSet<PathBuf>) {
+        self.dependencies.insert(entry, dependencies);
+    }
+
+    /// Forget an entry point
+    pub fn remove(&mut self, entry: &Path) {
+        self.dependencies.remove(entry);
+    }
+
+    /// Files an entry point depends on
+    pub fn dependencies_of(&self, entry: &Path) -> Vec<PathBuf> {
+        self.dependencies
+            .get(entry)
+            .map(|deps| deps.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Entry points that must be rebuilt when `path` changes, in sorted order
+    pub fn entry_points_for(&self, path: &Path) -> Vec<PathBuf> {
+        let mut entries: Vec<PathBuf> = self
+            .dependencies
+            .iter()
+            .filter(|(entry, deps)| entry.as_path() == path || deps.contains(path))
+            .map(|(entry, _)| entry.clone())
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    /// All tracked entry points, in sorted order
+    pub fn entry_points(&self) -> Vec<PathBuf> {
+        let mut entries: Vec<PathBuf> = self.dependencies.keys().cloned().collect();
+        entries.sort();
+        entries
+    }
+}
+
 /// Asset compiler for themes
 pub struct AssetCompiler {
     config: AssetConfig,
@@ -63,6 +162,8 @@ pub struct AssetCompiler {
     cache: Arc<RwLock<HashMap<String, CompiledAsset>>>,
     /// Asset manifest
     manifest: Arc<RwLock<AssetManifest>>,
+    /// SCSS dependency graph
+    dependency_graph: Arc<RwLock<DependencyGraph>>,
 }
 
 /// Compiled asset information
@@ -99,6 +200,7 @@ impl AssetCompiler {
             config,
             cache: Arc::new(RwLock::new(HashMap::new())),
             manifest: Arc::new(RwLock::new(AssetManifest::default())),
+            dependency_graph: Arc::new(RwLock::new(DependencyGraph::new())),
         }
     }
 
@@ -133,7 +235,7 @@ impl AssetCompiler {
         Ok(results)
     }
 
-    async fn find_files(
+    pub(crate) async fn find_files(
         &self,
         dir: &Path,
         extensions: &[&str],
@@ -165,6 +267,7 @@ impl AssetCompiler {
             }
         }
 
+        files.sort();
         Ok(files)
     }
 
@@ -198,7 +301,15 @@ impl AssetCompiler {
         Ok(asset)
     }
 
-    fn process_css(&self, content: &str) -> Result<String, AssetError> {
+    pub(crate) fn process_css(&self, content: &str) -> Result<String, AssetError> {
+        self.process_css_with_map(content, None)
+    }
+
+    fn process_css_with_map(
+        &self,
+        content: &str,
+        source_map: Option<&mut parcel_sourcemap::SourceMap>,
+    ) -> Result<String, AssetError> {
         use lightningcss::stylesheet::{MinifyOptions, ParserOptions, PrinterOptions, StyleSheet};
         use lightningcss::targets::Targets;
 
@@ -223,6 +334,7 @@ impl AssetCompiler {
             .to_css(PrinterOptions {
                 targets,
                 minify: self.config.minify,
+                source_map,
                 ..Default::default()
             })
             .map_err(|e| AssetError::CssCompilation(format!("{:?}", e)))?;
@@ -231,27 +343,60 @@ impl AssetCompiler {
     }
 
     /// Compile SCSS to CSS
+    ///
+    /// Imports are resolved relative to the entry point, and every file it
+    /// pulls in is recorded in the [`DependencyGraph`]. When
+    /// [`AssetConfig::source_maps`] is set a `.map` file is written next to
+    /// the output. grass does not emit source maps itself, so the map points
+    /// into the expanded CSS of the entry point, which is embedded as the
+    /// source content.
     pub async fn compile_scss(&self, path: &Path) -> Result<CompiledAsset, AssetError> {
-        let content = fs::read_to_string(path).await?;
-
-        // Use grass for SCSS compilation
-        let options = grass::Options::default().style(if self.config.minify {
+        let mut options = grass::Options::default().style(if self.config.minify {
             grass::OutputStyle::Compressed
         } else {
             grass::OutputStyle::Expanded
         });
+        if let Some(parent) = path.parent() {
+            options = options.load_path(parent);
+        }
 
-        let css = grass::from_string(content, &options)
-            .map_err(|e| AssetError::ScssCompilation(e.to_string()))?;
+        let css = grass::from_path(path, &options).map_err(AssetError::from_sass)?;
+
+        let dependencies = self.resolve_scss_dependencies(path).await?;
 
         // Further process with lightningcss for autoprefixing
-        let processed = self.process_css(&css)?;
+        let (processed, source_map) = if self.config.source_maps {
+            let mut map = parcel_sourcemap::SourceMap::new("/");
+            let index = map.add_source(&path.to_string_lossy());
+            map.set_source_content(index as usize, &css)
+                .map_err(|e| AssetError::CssCompilation(format!("{:?}", e)))?;
+            let processed = self.process_css_with_map(&css, Some(&mut map))?;
+            let json = map
+                .to_json(None)
+                .map_err(|e| AssetError::CssCompilation(format!("{:?}", e)))?;
+            (processed, Some(json))
+        } else {
+            (self.process_css(&css)?, None)
+        };
 
         let hash = self.hash_content(&processed);
         let output_path = self.get_output_path(path, &hash, "css");
 
         fs::create_dir_all(output_path.parent().unwrap()).await?;
-        fs::write(&output_path, &processed).await?;
+
+        match source_map {
+            Some(map) => {
+                let map_path = output_path.with_extension("css.map");
+                let map_name = map_path.file_name().unwrap().to_string_lossy().to_string();
+                fs::write(&map_path, map).await?;
+                fs::write(
+                    &output_path,
+                    format!("{}\n/*# sourceMappingURL={} */\n", processed, map_name),
+                )
+                .await?;
+            }
+            None => fs::write(&output_path, &processed).await?,
+        }
 
         let asset = CompiledAsset {
             original_path: path.to_path_buf(),
@@ -260,9 +405,16 @@ impl AssetCompiler {
             size: processed.len() as u64,
             mime_type: "text/css".to_string(),
             compiled_at: chrono::Utc::now(),
-            dependencies: self.extract_scss_imports(path).await?,
+            dependencies: dependencies
+                .iter()
+                .map(|d| d.to_string_lossy().to_string())
+                .collect(),
         };
 
+        self.dependency_graph
+            .write()
+            .update(path.to_path_buf(), dependencies);
+
         self.cache
             .write()
             .insert(path.to_string_lossy().to_string(), asset.clone());
@@ -270,20 +422,87 @@ impl AssetCompiler {
         Ok(asset)
     }
 
+    /// Recompile whatever is affected by a changed file.
+    ///
+    /// A changed SCSS partial rebuilds only the entry points that import it;
+    /// any other changed asset is compiled on its own.
+    pub async fn rebuild_changed(&self, path: &Path) -> Result<Vec<CompiledAsset>, AssetError> {
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        match ext.as_str() {
+            "scss" | "sass" => {
+                let mut entries = self.dependency_graph.read().entry_points_for(path);
+                let is_partial = path
+                    .file_name()
+                    .map_or(true, |n| n.to_string_lossy().starts_with('_'));
+                if !is_partial && !entries.iter().any(|e| e == path) {
+                    entries.push(path.to_path_buf());
+                }
+
+                let mut results = Vec::with_capacity(entries.len());
+                for entry in entries {
+                    if entry.exists() {
+                        results.push(self.compile_scss(&entry).await?);
+                    } else {
+                        self.dependency_graph.write().remove(&entry);
+                    }
+                }
+                Ok(results)
+            }
+            "css" => Ok(vec![self.compile_css(path).await?]),
+            "js" => Ok(vec![self.compile_js(path).await?]),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Snapshot of the SCSS dependency graph
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        self.dependency_graph.read().clone()
+    }
+
+    /// Resolve every file an SCSS entry point imports, transitively
+    async fn resolve_scss_dependencies(
+        &self,
+        entry: &Path,
+    ) -> Result<BTreeSet<PathBuf>, AssetError> {
+        let mut resolved = BTreeSet::new();
+        let mut visited = HashSet::new();
+        let mut pending = vec![entry.to_path_buf()];
+
+        while let Some(file) = pending.pop() {
+            if !visited.insert(file.clone()) {
+                continue;
+            }
+
+            let base = file.parent().unwrap_or_else(|| Path::new("."));
+            for import in self.extract_scss_imports(&file).await? {
+                if let Some(dep) = resolve_scss_import(base, &import) {
+                    if dep != entry {
+                        resolved.insert(dep.clone());
+                    }
+                    pending.push(dep);
+                }
+            }
+        }
+
+        Ok(resolved)
+    }
+
     async fn extract_scss_imports(&self, path: &Path) -> Result<Vec<String>, AssetError> {
         let content = fs::read_to_string(path).await?;
         let mut imports = Vec::new();
 
-        // Simple regex-based import extraction
-        let import_regex = regex::Regex::new(r#"@import\s+["']([^"']+)["']"#).unwrap();
-        let use_regex = regex::Regex::new(r#"@use\s+["']([^"']+)["']"#).unwrap();
+        // Simple regex-based import extraction; `@import` may list several files
+        let rule_regex = regex::Regex::new(r#"@(?:import|use|forward)\s+([^;]+)"#).unwrap();
+        let string_regex = regex::Regex::new(r#"["']([^"']+)["']"#).unwrap();
 
-        for cap in import_regex.captures_iter(&content) {
-            imports.push(cap[1].to_string());
-        }
-
-        for cap in use_regex.captures_iter(&content) {
-            imports.push(cap[1].to_string());
+        for rule in rule_regex.captures_iter(&content) {
+            for cap in string_regex.captures_iter(&rule[1]) {
+                imports.push(cap[1].to_string());
+            }
         }
 
         Ok(imports)
@@ -323,7 +542,7 @@ impl AssetCompiler {
         Ok(asset)
     }
 
-    fn minify_js(&self, content: &str) -> String {
+    pub(crate) fn minify_js(&self, content: &str) -> String {
         // Basic JavaScript minification
         // In production, use a proper minifier like swc or terser
         let mut result = String::with_capacity(content.len());
@@ -584,6 +803,40 @@ impl AssetCompiler {
     }
 }
 
+/// Resolve an SCSS import to a file on disk, following Sass lookup rules
+/// (partials, `_index` files and the `.scss`/`.sass`/`.css` extensions).
+/// Built-in modules and remote or plain CSS URLs resolve to `None`.
+fn resolve_scss_import(base: &Path, import: &str) -> Option<PathBuf> {
+    if import.starts_with("sass:")
+        || import.starts_with("http://")
+        || import.starts_with("https://")
+        || import.starts_with("//")
+        || import.starts_with("url(")
+    {
+        return None;
+    }
+
+    let target = base.join(import);
+    let dir = target.parent().unwrap_or(base);
+    let name = target.file_name()?.to_string_lossy().to_string();
+
+    let mut candidates = Vec::new();
+    if target.extension().is_some() {
+        candidates.push(target.clone());
+        candidates.push(dir.join(format!("_{}", name)));
+    }
+    for ext in ["scss", "sass", "css"] {
+        candidates.push(dir.join(format!("{}.{}", name, ext)));
+        candidates.push(dir.join(format!("_{}.{}", name, ext)));
+    }
+    for ext in ["scss", "sass", "css"] {
+        candidates.push(target.join(format!("index.{}", ext)));
+        candidates.push(target.join(format!("_index.{}", ext)));
+    }
+
+    candidates.into_iter().find(|c| c.is_file())
+}
+
 /// Asset watcher for development
 pub struct AssetWatcher {
     compiler: Arc<AssetCompiler>,
@@ -633,14 +886,10 @@ impl AssetWatcher {
                     if let Some(ext) = path.extension() {
                         let ext_str = ext.to_string_lossy().to_lowercase();
                         match ext_str.as_str() {
-                            "css" => {
-                                let _ = compiler.compile_css(&path).await;
-                            }
-                            "scss" | "sass" => {
-                                let _ = compiler.compile_scss(&path).await;
-                            }
-                            "js" => {
-                                let _ = compiler.compile_js(&path).await;
+                            "css" | "scss" | "sass" | "js" => {
+                                if let Err(e) = compiler.rebuild_changed(&path).await {
+                                    tracing::warn!("Asset rebuild failed: {}", e);
+                                }
                             }
                             _ => {}
                         }
@@ -732,6 +981,80 @@ mod tests {
         assert_eq!(result.mime_type, "text/css");
     }
 
+    #[tokio::test]
+    async fn test_scss_partial_rebuilds_dependent_entries() {
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir_all(&src).await.unwrap();
+
+        fs::write(src.join("_colors.scss"), "$primary: #0073aa;")
+            .await
+            .unwrap();
+        fs::write(src.join("_unused.scss"), "$gap: 1rem;")
+            .await
+            .unwrap();
+        fs::write(
+            src.join("main.scss"),
+            "@import 'colors';\nbody { color: $primary; }",
+        )
+        .await
+        .unwrap();
+        fs::write(src.join("print.scss"), "body { color: black; }")
+            .await
+            .unwrap();
+
+        let compiler = AssetCompiler::new(AssetConfig {
+            output_dir: dir.path().join("dist"),
+            ..AssetConfig::development()
+        });
+        compiler.compile_all(&src).await.unwrap();
+
+        let graph = compiler.dependency_graph();
+        assert_eq!(
+            graph.dependencies_of(&src.join("main.scss")),
+            vec![src.join("_colors.scss")]
+        );
+
+        let rebuilt = compiler
+            .rebuild_changed(&src.join("_colors.scss"))
+            .await
+            .unwrap();
+        assert_eq!(rebuilt.len(), 1);
+        assert_eq!(rebuilt[0].original_path, src.join("main.scss"));
+
+        let css = fs::read_to_string(&rebuilt[0].output_path).await.unwrap();
+        assert!(css.contains("sourceMappingURL=main.css.map"));
+        assert!(dir.path().join("dist/main.css.map").exists());
+
+        assert!(compiler
+            .rebuild_changed(&src.join("_unused.scss"))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_scss_error_reports_location() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("broken.scss");
+        fs::write(&path, "body {\n  color: $missing;\n}")
+            .await
+            .unwrap();
+
+        let compiler = AssetCompiler::new(AssetConfig {
+            output_dir: dir.path().join("dist"),
+            ..Default::default()
+        });
+
+        match compiler.compile_scss(&path).await {
+            Err(AssetError::ScssSyntax { file, line, .. }) => {
+                assert!(file.ends_with("broken.scss"));
+                assert_eq!(line, 2);
+            }
+            other => panic!("expected syntax error, got {:?}", other.map(|a| a.hash)),
+        }
+    }
+
     #[test]
     fn test_js_minification() {
         let compiler = AssetCompiler::new(AssetConfig::default());
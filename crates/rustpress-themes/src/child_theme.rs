@@ -1,11 +1,15 @@
 //! Child Theme Support
 //!
 //! Inheritance and override system for child themes.
+//!
+//! A child theme's files override its parent's by relative path, its
+//! `theme.json` is deep-merged over the parent's, and function files load
+//! parent first so the child's hooks run after (and can undo) the parent's.
 
 use crate::manifest::ThemeManifest;
 use parking_lot::RwLock;
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 use thiserror::Error;
 use tokio::fs;
@@ -13,7 +17,7 @@ use tokio::fs;
 /// Child theme errors
 #[derive(Debug, Error)]
 pub enum ChildThemeError {
-    #[error("Parent theme not found: {0}")]
+    #[error("Parent theme not installed: {0}")]
     ParentNotFound(String),
 
     #[error("Circular dependency detected: {0}")]
@@ -64,6 +68,18 @@ impl ThemeInheritance {
         theme_id: &'a str,
     ) -> std::pin::Pin<
         Box<dyn std::future::Future<Output = Result<LoadedTheme, ChildThemeError>> + Send + 'a>,
+    > {
+        self.load_with_stack(theme_id, Vec::new())
+    }
+
+    /// Load a theme, with `stack` holding the children waiting on it, so a
+    /// theme appearing twice in its own ancestry is caught before recursing
+    fn load_with_stack<'a>(
+        &'a self,
+        theme_id: &'a str,
+        mut stack: Vec<String>,
+    ) -> std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<LoadedTheme, ChildThemeError>> + Send + 'a>,
     > {
         Box::pin(async move {
             // Check if already loaded
@@ -89,15 +105,26 @@ impl ThemeInheritance {
             // Check for parent theme
             let parent_id = manifest.parent.as_ref().map(|p| p.id.clone());
 
-            // Detect circular dependencies
-            if let Some(ref parent) = parent_id {
-                self.check_circular_dependency(theme_id, parent)?;
-            }
-
             // Load parent if exists
             if let Some(ref parent) = parent_id {
+                // Detect circular dependencies
+                stack.push(theme_id.to_string());
+                if stack.contains(parent) {
+                    stack.push(parent.clone());
+                    return Err(ChildThemeError::CircularDependency(stack.join(" -> ")));
+                }
+
+                let parent_installed = self.themes.read().contains_key(parent)
+                    || self.themes_dir.join(parent).join("theme.toml").exists();
+                if !parent_installed {
+                    return Err(ChildThemeError::ParentNotFound(format!(
+                        "'{}' is required by '{}'",
+                        parent, theme_id
+                    )));
+                }
+
                 // Load parent first
-                self.load_theme(parent).await?;
+                self.load_with_stack(parent, stack).await?;
 
                 // Verify parent version compatibility
                 if let Some(parent_info) = &manifest.parent {
@@ -137,27 +164,6 @@ impl ThemeInheritance {
         })
     }
 
-    fn check_circular_dependency(
-        &self,
-        theme_id: &str,
-        parent_id: &str,
-    ) -> Result<(), ChildThemeError> {
-        let hierarchy = self.hierarchy.read();
-        let mut current = Some(parent_id.to_string());
-        let mut chain = vec![theme_id.to_string()];
-
-        while let Some(ref id) = current {
-            if id == theme_id {
-                chain.push(id.clone());
-                return Err(ChildThemeError::CircularDependency(chain.join(" -> ")));
-            }
-            chain.push(id.clone());
-            current = hierarchy.get(id).cloned();
-        }
-
-        Ok(())
-    }
-
     /// Get the full inheritance chain for a theme
     pub fn get_inheritance_chain(&self, theme_id: &str) -> Vec<String> {
         let hierarchy = self.hierarchy.read();
@@ -172,21 +178,26 @@ impl ThemeInheritance {
         chain
     }
 
-    /// Resolve a template, checking child theme first then parent chain
-    pub async fn resolve_template(&self, theme_id: &str, template_name: &str) -> Option<PathBuf> {
+    /// Resolve a file by its path relative to the theme root, checking the
+    /// child theme first then the parent chain. Paths escaping the theme
+    /// directory resolve to nothing.
+    fn resolve_relative(&self, theme_id: &str, relative: &Path) -> Option<PathBuf> {
+        if !is_safe_relative_path(relative) {
+            return None;
+        }
         let chain = self.get_inheritance_chain(theme_id);
         let themes = self.themes.read();
 
-        for id in chain {
-            if let Some(theme) = themes.get(&id) {
-                let template_path = theme.path.join("templates").join(template_name);
-                if template_path.exists() {
-                    return Some(template_path);
-                }
-            }
-        }
+        chain
+            .iter()
+            .filter_map(|id| themes.get(id))
+            .map(|theme| theme.path.join(relative))
+            .find(|path| path.is_file())
+    }
 
-        None
+    /// Resolve a template, checking child theme first then parent chain
+    pub async fn resolve_template(&self, theme_id: &str, template_name: &str) -> Option<PathBuf> {
+        self.resolve_relative(theme_id, &Path::new("templates").join(template_name))
     }
 
     /// Resolve a template part
@@ -238,19 +249,98 @@ impl ThemeInheritance {
 
     /// Resolve an asset (CSS, JS, images)
     pub fn resolve_asset(&self, theme_id: &str, asset_path: &str) -> Option<PathBuf> {
-        let chain = self.get_inheritance_chain(theme_id);
+        self.resolve_relative(theme_id, Path::new(asset_path))
+    }
+
+    /// `theme.json` of the theme with its ancestors', deep-merged from the
+    /// root parent down: objects merge key by key, anything else (including
+    /// arrays) is replaced by the child's value
+    pub async fn merged_theme_json(
+        &self,
+        theme_id: &str,
+    ) -> Result<serde_json::Value, ChildThemeError> {
+        let paths: Vec<PathBuf> = {
+            let themes = self.themes.read();
+            self.get_inheritance_chain(theme_id)
+                .iter()
+                .rev()
+                .filter_map(|id| themes.get(id))
+                .map(|theme| theme.path.join("theme.json"))
+                .collect()
+        };
+
+        let mut merged = serde_json::json!({});
+        for path in paths {
+            if !path.exists() {
+                continue;
+            }
+            let content = fs::read_to_string(&path).await?;
+            let value: serde_json::Value = serde_json::from_str(&content).map_err(|e| {
+                ChildThemeError::Manifest(format!("Invalid {}: {}", path.display(), e))
+            })?;
+            deep_merge(&mut merged, value);
+        }
+
+        Ok(merged)
+    }
+
+    /// Function files to load for the theme, root parent first so child
+    /// hooks are registered after the ones they build on
+    pub fn function_files(&self, theme_id: &str) -> Vec<PathBuf> {
         let themes = self.themes.read();
+        self.get_inheritance_chain(theme_id)
+            .iter()
+            .rev()
+            .filter_map(|id| themes.get(id))
+            .map(|theme| theme.path.join(FUNCTIONS_FILE))
+            .filter(|path| path.is_file())
+            .collect()
+    }
 
-        for id in chain {
-            if let Some(theme) = themes.get(&id) {
-                let full_path = theme.path.join(asset_path);
-                if full_path.exists() {
-                    return Some(full_path);
-                }
+    /// Files in the theme that replace a file an ancestor provides at the
+    /// same relative path, for auditing a child theme. Manifests and
+    /// function files are merged or loaded together rather than overridden,
+    /// so they are not reported.
+    pub fn overridden_files(&self, theme_id: &str) -> Result<Vec<ThemeOverride>, ChildThemeError> {
+        let themes = self.themes.read();
+        let theme = themes
+            .get(theme_id)
+            .ok_or_else(|| ChildThemeError::Manifest(format!("Theme not found: {}", theme_id)))?;
+        let ancestors: Vec<&LoadedTheme> = self
+            .get_inheritance_chain(theme_id)
+            .iter()
+            .skip(1)
+            .filter_map(|id| themes.get(id))
+            .collect();
+
+        let mut overrides = Vec::new();
+        for entry in walkdir::WalkDir::new(&theme.path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let Ok(relative) = entry.path().strip_prefix(&theme.path) else {
+                continue;
+            };
+            if MERGED_FILES.iter().any(|f| relative == Path::new(f)) {
+                continue;
+            }
+            let parent_path = ancestors
+                .iter()
+                .map(|ancestor| ancestor.path.join(relative))
+                .find(|path| path.is_file());
+
+            if let Some(parent_path) = parent_path {
+                overrides.push(ThemeOverride {
+                    file_path: entry.path().to_path_buf(),
+                    parent_path: Some(parent_path),
+                    override_type: OverrideType::for_path(relative),
+                });
             }
         }
+        overrides.sort_by(|a, b| a.file_path.cmp(&b.file_path));
 
-        None
+        Ok(overrides)
     }
 
     /// Merge theme settings (child overrides parent)
@@ -338,6 +428,34 @@ impl ThemeInheritance {
     }
 }
 
+/// Functions file loaded from each theme in the chain
+const FUNCTIONS_FILE: &str = "functions.rs";
+
+/// Files combined across the chain instead of overridden
+const MERGED_FILES: &[&str] = &["theme.toml", "theme.json", FUNCTIONS_FILE];
+
+/// Whether `path` stays inside the directory it is joined to
+fn is_safe_relative_path(path: &Path) -> bool {
+    !path.as_os_str().is_empty() && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Merge `overlay` into `base`: objects key by key, other values replaced
+fn deep_merge(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
 /// Check if a version is compatible with a requirement
 fn is_version_compatible(version: &str, requirement: &str) -> bool {
     // Simple version checking - in production use semver crate
@@ -417,7 +535,6 @@ min_version = "1.0.0"
 
 [supports]
 block_editor = true
-custom_logo = true
 post_thumbnails = true
 
 [colors]
@@ -491,6 +608,25 @@ pub enum OverrideType {
     Style,
 }
 
+impl OverrideType {
+    /// Kind of file at `relative` in a theme directory
+    fn for_path(relative: &Path) -> Self {
+        let top = relative
+            .components()
+            .next()
+            .and_then(|c| c.as_os_str().to_str())
+            .unwrap_or_default();
+        match top {
+            "templates" => Self::Template,
+            "parts" | "template-parts" => Self::TemplatePart,
+            "patterns" => Self::Pattern,
+            _ if relative.extension().is_some_and(|ext| ext == "css") => Self::Style,
+            _ if relative.extension().is_some_and(|ext| ext == "rs") => Self::Function,
+            _ => Self::Asset,
+        }
+    }
+}
+
 /// Analyze overrides in a child theme
 pub async fn analyze_overrides(
     inheritance: &ThemeInheritance,
@@ -595,4 +731,112 @@ mod tests {
         let chain = inheritance.get_inheritance_chain("grandchild");
         assert_eq!(chain, vec!["grandchild", "child", "parent"]);
     }
+
+    async fn write_theme(dir: &Path, id: &str, parent: Option<&str>) {
+        let parent = parent
+            .map(|p| format!("[parent]\nid = \"{}\"\n", p))
+            .unwrap_or_default();
+        fs::create_dir_all(dir.join(id)).await.unwrap();
+        fs::write(
+            dir.join(id).join("theme.toml"),
+            format!(
+                "[theme]\nid = \"{id}\"\nname = \"{id}\"\nversion = \"1.0.0\"\n\
+                 description = \"\"\nauthor = \"\"\n{parent}"
+            ),
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn write_file(path: PathBuf, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+        fs::write(path, content).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_child_overrides_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        let themes = dir.path();
+        write_theme(themes, "base", None).await;
+        write_file(themes.join("base/templates/index.html"), "parent index").await;
+        write_file(themes.join("base/templates/page.html"), "parent page").await;
+        write_file(themes.join("base/assets/css/style.css"), "body {}").await;
+        write_file(themes.join("base/functions.rs"), "// parent").await;
+        write_file(
+            themes.join("base/theme.json"),
+            r#"{"settings": {"color": {"custom": true}, "layout": {"contentSize": "800px"}}}"#,
+        )
+        .await;
+
+        ChildThemeBuilder::new("base", "kid", "Kid")
+            .create(themes)
+            .await
+            .unwrap();
+        write_file(themes.join("kid/templates/index.html"), "child index").await;
+        write_file(
+            themes.join("kid/theme.json"),
+            r#"{"settings": {"layout": {"contentSize": "900px"}}}"#,
+        )
+        .await;
+
+        let inheritance = ThemeInheritance::new(themes.to_path_buf());
+        inheritance.load_theme("kid").await.unwrap();
+
+        assert_eq!(
+            inheritance.resolve_template("kid", "index.html").await,
+            Some(themes.join("kid/templates/index.html"))
+        );
+        assert_eq!(
+            inheritance.resolve_template("kid", "page.html").await,
+            Some(themes.join("base/templates/page.html"))
+        );
+        assert_eq!(inheritance.resolve_asset("kid", "../base/theme.toml"), None);
+
+        let merged = inheritance.merged_theme_json("kid").await.unwrap();
+        assert_eq!(merged["settings"]["color"]["custom"], true);
+        assert_eq!(merged["settings"]["layout"]["contentSize"], "900px");
+
+        assert_eq!(
+            inheritance.function_files("kid"),
+            vec![
+                themes.join("base/functions.rs"),
+                themes.join("kid/functions.rs")
+            ]
+        );
+
+        let overrides = inheritance.overridden_files("kid").unwrap();
+        let kinds: Vec<_> = overrides
+            .iter()
+            .map(|o| (o.file_path.clone(), o.override_type.clone()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (themes.join("kid/assets/css/style.css"), OverrideType::Style),
+                (
+                    themes.join("kid/templates/index.html"),
+                    OverrideType::Template
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_and_circular_parents() {
+        let dir = tempfile::tempdir().unwrap();
+        let themes = dir.path();
+        write_theme(themes, "orphan", Some("ghost")).await;
+        write_theme(themes, "a", Some("b")).await;
+        write_theme(themes, "b", Some("a")).await;
+
+        let inheritance = ThemeInheritance::new(themes.to_path_buf());
+        assert!(matches!(
+            inheritance.load_theme("orphan").await,
+            Err(ChildThemeError::ParentNotFound(msg)) if msg.contains("ghost")
+        ));
+        assert!(matches!(
+            inheritance.load_theme("a").await,
+            Err(ChildThemeError::CircularDependency(chain)) if chain == "a -> b -> a"
+        ));
+    }
 }
@@ -1,9 +1,15 @@
 //! Design Tokens System
 //!
 //! Color palette (201), typography (202), and layout (203) management.
+//!
+//! Tokens can also be exported as flat CSS custom properties or SCSS
+//! variables named `{group}-{slug}` (`color-primary`, `font-size-large`,
+//! `spacing-40`, ...), with nested values such as fluid font size bounds
+//! appended as further segments (`font-size-large-fluid-min`).
 
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 /// Color definition with accessibility metadata
@@ -739,6 +745,360 @@ impl DesignTokens {
 
         css
     }
+
+    /// Flatten every token into `name => value`, sorted by name.
+    ///
+    /// Colors are normalized to valid CSS (hex colors become lowercase
+    /// `#rrggbb` or `#rrggbbaa`); colors that are not valid CSS are left out.
+    pub fn flatten(&self) -> BTreeMap<String, String> {
+        let mut tokens = BTreeMap::new();
+
+        for color in self.colors.get_colors() {
+            if let Some(value) = css_color(&color.color) {
+                tokens.insert(format!("color-{}", token_name(&color.slug)), value);
+            }
+        }
+
+        for gradient in self.colors.get_gradients() {
+            tokens.insert(
+                format!("gradient-{}", token_name(&gradient.slug)),
+                gradient.gradient,
+            );
+        }
+
+        let typography = &self.typography;
+        for family in &typography.font_families {
+            tokens.insert(
+                format!("font-family-{}", token_name(&family.slug)),
+                family.font_family.clone(),
+            );
+        }
+        for size in &typography.font_sizes {
+            let name = format!("font-size-{}", token_name(&size.slug));
+            if let Some(fluid) = &size.fluid {
+                tokens.insert(format!("{}-fluid-min", name), fluid.min.clone());
+                tokens.insert(format!("{}-fluid-max", name), fluid.max.clone());
+            }
+            tokens.insert(name, size.size.clone());
+        }
+        for height in &typography.line_heights {
+            tokens.insert(
+                format!("line-height-{}", token_name(&height.slug)),
+                height.value.clone(),
+            );
+        }
+        for spacing in &typography.letter_spacings {
+            tokens.insert(
+                format!("letter-spacing-{}", token_name(&spacing.slug)),
+                spacing.value.clone(),
+            );
+        }
+
+        let layout = &self.layout;
+        tokens.insert(
+            "layout-content-size".to_string(),
+            layout.content_size.clone(),
+        );
+        tokens.insert("layout-wide-size".to_string(), layout.wide_size.clone());
+        if let Some(gap) = &layout.block_gap {
+            tokens.insert("layout-block-gap".to_string(), gap.clone());
+        }
+        for spacing in &layout.spacing {
+            tokens.insert(
+                format!("spacing-{}", token_name(&spacing.slug)),
+                spacing.size.clone(),
+            );
+        }
+
+        tokens.retain(|_, value| !value.trim().is_empty());
+        tokens
+    }
+
+    /// Export the tokens as CSS custom properties on `:root`
+    pub fn to_css_variables(&self) -> String {
+        let mut css = String::from(":root {\n");
+        for (name, value) in self.flatten() {
+            css.push_str(&format!("  --{}: {};\n", name, value));
+        }
+        css.push_str("}\n");
+        css
+    }
+
+    /// Export the tokens as SCSS variables
+    pub fn to_scss_variables(&self) -> String {
+        self.flatten()
+            .into_iter()
+            .map(|(name, value)| format!("${}: {};\n", name, value))
+            .collect()
+    }
+
+    /// Parse tokens from CSS custom properties, as written by
+    /// [`to_css_variables`](Self::to_css_variables).
+    ///
+    /// Comments and unknown properties are ignored. Groups missing from
+    /// `css` are left empty; the content and wide sizes keep their defaults.
+    pub fn from_css_variables(css: &str) -> Self {
+        let mut layout = LayoutSettings::new();
+        layout.block_gap = None;
+
+        let mut tokens = Self {
+            colors: ColorPalette::new(),
+            typography: TypographySettings::new(),
+            layout,
+        };
+        for (name, value) in parse_custom_properties(css) {
+            tokens.set_token(&name, &value);
+        }
+        tokens
+    }
+
+    /// Set a single token by its flattened name (with or without the
+    /// leading `--`), replacing any existing value.
+    ///
+    /// Returns `false` if the name is not a known token or, for colors, the
+    /// value is not a valid CSS color.
+    pub fn set_token(&mut self, name: &str, value: &str) -> bool {
+        let name = name.trim().trim_start_matches("--");
+        let value = value.trim();
+        if value.is_empty() {
+            return false;
+        }
+
+        if let Some(slug) = name.strip_prefix("color-") {
+            let Some(color) = css_color(value) else {
+                return false;
+            };
+            self.colors.remove_color(slug);
+            self.colors
+                .add_color(Color::new(slug, &token_label(slug), &color));
+        } else if let Some(slug) = name.strip_prefix("gradient-") {
+            let mut gradients = self.colors.gradients.write();
+            gradients.retain(|g| g.slug != slug);
+            gradients.push(Gradient {
+                slug: slug.to_string(),
+                name: token_label(slug),
+                gradient: value.to_string(),
+            });
+        } else if let Some(slug) = name.strip_prefix("font-family-") {
+            let families = &mut self.typography.font_families;
+            match families.iter_mut().find(|f| f.slug == slug) {
+                Some(family) => family.font_family = value.to_string(),
+                None => families.push(FontFamily {
+                    slug: slug.to_string(),
+                    name: token_label(slug),
+                    font_family: value.to_string(),
+                    font_face: None,
+                }),
+            }
+        } else if let Some(rest) = name.strip_prefix("font-size-") {
+            let (slug, bound) = if let Some(slug) = rest.strip_suffix("-fluid-min") {
+                (slug, Some(true))
+            } else if let Some(slug) = rest.strip_suffix("-fluid-max") {
+                (slug, Some(false))
+            } else {
+                (rest, None)
+            };
+
+            let sizes = &mut self.typography.font_sizes;
+            let index = match sizes.iter().position(|s| s.slug == slug) {
+                Some(index) => index,
+                None => {
+                    sizes.push(FontSize {
+                        slug: slug.to_string(),
+                        name: token_label(slug),
+                        size: String::new(),
+                        fluid: None,
+                    });
+                    sizes.len() - 1
+                }
+            };
+            let size = &mut sizes[index];
+            match bound {
+                None => size.size = value.to_string(),
+                Some(is_min) => {
+                    let fluid = size.fluid.get_or_insert_with(|| FluidFontSize {
+                        min: String::new(),
+                        max: String::new(),
+                    });
+                    if is_min {
+                        fluid.min = value.to_string();
+                    } else {
+                        fluid.max = value.to_string();
+                    }
+                }
+            }
+        } else if let Some(slug) = name.strip_prefix("line-height-") {
+            let heights = &mut self.typography.line_heights;
+            match heights.iter_mut().find(|h| h.slug == slug) {
+                Some(height) => height.value = value.to_string(),
+                None => heights.push(LineHeight {
+                    slug: slug.to_string(),
+                    name: token_label(slug),
+                    value: value.to_string(),
+                }),
+            }
+        } else if let Some(slug) = name.strip_prefix("letter-spacing-") {
+            let spacings = &mut self.typography.letter_spacings;
+            match spacings.iter_mut().find(|s| s.slug == slug) {
+                Some(spacing) => spacing.value = value.to_string(),
+                None => spacings.push(LetterSpacing {
+                    slug: slug.to_string(),
+                    name: token_label(slug),
+                    value: value.to_string(),
+                }),
+            }
+        } else if let Some(slug) = name.strip_prefix("spacing-") {
+            let spacing = &mut self.layout.spacing;
+            match spacing.iter_mut().find(|s| s.slug == slug) {
+                Some(preset) => preset.size = value.to_string(),
+                None => spacing.push(SpacingPreset {
+                    slug: slug.to_string(),
+                    name: token_label(slug),
+                    size: value.to_string(),
+                }),
+            }
+        } else {
+            match name {
+                "layout-content-size" => self.layout.content_size = value.to_string(),
+                "layout-wide-size" => self.layout.wide_size = value.to_string(),
+                "layout-block-gap" => self.layout.block_gap = Some(value.to_string()),
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
+/// CSS color functions accepted as token values
+const CSS_COLOR_FUNCTIONS: &[&str] = &[
+    "rgb",
+    "rgba",
+    "hsl",
+    "hsla",
+    "hwb",
+    "lab",
+    "lch",
+    "oklab",
+    "oklch",
+    "color",
+    "color-mix",
+    "var",
+];
+
+/// Normalize a color value to valid CSS.
+///
+/// Hex colors (with or without `#`) become lowercase `#rrggbb`/`#rrggbbaa`,
+/// color functions and keywords are kept with lowercase names. Anything
+/// else is rejected.
+fn css_color(value: &str) -> Option<String> {
+    let value = value.trim();
+
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        let hex = hex.to_ascii_lowercase();
+        let hex = if hex.len() <= 4 {
+            hex.chars().flat_map(|c| [c, c]).collect()
+        } else {
+            hex
+        };
+        return Some(format!("#{}", hex));
+    }
+    if value.starts_with('#') {
+        return None;
+    }
+
+    if let Some((function, args)) = value.split_once('(') {
+        let function = function.trim().to_ascii_lowercase();
+        let balanced = args.matches('(').count() + 1 == args.matches(')').count();
+        return (CSS_COLOR_FUNCTIONS.contains(&function.as_str())
+            && args.ends_with(')')
+            && balanced)
+            .then(|| format!("{}({}", function, args));
+    }
+
+    // Named colors, `transparent`, `currentColor`
+    (!value.is_empty() && value.chars().all(|c| c.is_ascii_alphabetic()))
+        .then(|| value.to_ascii_lowercase())
+}
+
+/// Slug as a CSS identifier / SCSS variable name segment
+fn token_name(slug: &str) -> String {
+    slug.trim()
+        .to_ascii_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Display name for a token parsed without one (`gray-100` -> `Gray 100`)
+fn token_label(slug: &str) -> String {
+    slug.split(['-', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Extract `--name: value` declarations from CSS, in order.
+///
+/// Semicolons inside quotes or parentheses do not end a declaration.
+fn parse_custom_properties(css: &str) -> Vec<(String, String)> {
+    // Strip comments
+    let mut source = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        source.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    source.push_str(rest);
+
+    let mut declarations = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+
+    for c in source.chars() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth = depth.saturating_sub(1),
+            (None, ';' | '{' | '}') if depth == 0 => {
+                declarations.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    declarations.push(current);
+
+    declarations
+        .iter()
+        .filter_map(|declaration| {
+            let (name, value) = declaration.split_once(':')?;
+            let name = name.trim().strip_prefix("--")?;
+            let value = value.trim();
+            (!name.is_empty() && !value.is_empty()).then(|| (name.to_string(), value.to_string()))
+        })
+        .collect()
 }
 
 impl Default for DesignTokens {
@@ -787,6 +1147,106 @@ mod tests {
         assert!(css.contains("--wp--preset--font-size--medium"));
     }
 
+    #[test]
+    fn test_css_variables_are_flat_and_sorted() {
+        let tokens = DesignTokens::new();
+        let css = tokens.to_css_variables();
+
+        assert!(css.starts_with(":root {\n"));
+        assert!(css.contains("  --color-primary: #0073aa;\n"));
+        assert!(css.contains("  --font-size-large-fluid-min: 1.125rem;\n"));
+        assert!(css.contains("  --spacing-40: min(4rem, 5vw);\n"));
+        assert!(css.contains("  --layout-block-gap: 2rem;\n"));
+
+        let names: Vec<&str> = css
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("--"))
+            .map(|line| line.split(':').next().unwrap())
+            .collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+        assert_eq!(css, DesignTokens::new().to_css_variables());
+    }
+
+    #[test]
+    fn test_scss_variables() {
+        let scss = DesignTokens::new().to_scss_variables();
+        assert!(scss.contains("$color-primary: #0073aa;\n"));
+        assert!(scss.contains("$font-family-serif: Georgia, 'Times New Roman', Times, serif;\n"));
+        assert_eq!(
+            scss.lines().count(),
+            DesignTokens::new().to_css_variables().lines().count() - 2
+        );
+    }
+
+    #[test]
+    fn test_colors_are_normalized() {
+        assert_eq!(css_color("#FFF").as_deref(), Some("#ffffff"));
+        assert_eq!(css_color("0073AA").as_deref(), Some("#0073aa"));
+        assert_eq!(css_color("#0008").as_deref(), Some("#00000088"));
+        assert_eq!(
+            css_color("RGB(0 115 170 / 50%)").as_deref(),
+            Some("rgb(0 115 170 / 50%)")
+        );
+        assert_eq!(css_color("RebeccaPurple").as_deref(), Some("rebeccapurple"));
+        assert_eq!(css_color("#12345"), None);
+        assert_eq!(css_color("url(x.png)"), None);
+        assert_eq!(css_color("red; color: blue"), None);
+
+        let tokens = DesignTokens::new();
+        tokens
+            .colors
+            .add_color(Color::new("bad", "Bad", "not a color"));
+        tokens
+            .colors
+            .add_color(Color::new("Brand Blue", "Brand", "#ABC"));
+        let css = tokens.to_css_variables();
+        assert!(!css.contains("--color-bad"));
+        assert!(css.contains("--color-brand-blue: #aabbcc;"));
+    }
+
+    #[test]
+    fn test_css_variables_round_trip() {
+        let tokens = DesignTokens::new();
+        let css = tokens.to_css_variables();
+        let parsed = DesignTokens::from_css_variables(&css);
+
+        assert_eq!(parsed.to_css_variables(), css);
+        assert_eq!(parsed.flatten(), tokens.flatten());
+        assert_eq!(
+            parsed.colors.get_color("gray-100").unwrap().name,
+            "Gray 100"
+        );
+        let large = parsed
+            .typography
+            .font_sizes
+            .iter()
+            .find(|s| s.slug == "large")
+            .unwrap();
+        assert_eq!(large.fluid.as_ref().unwrap().min, "1.125rem");
+    }
+
+    #[test]
+    fn test_from_css_variables_is_lenient() {
+        let parsed = DesignTokens::from_css_variables(
+            "/* brand */ :root{--color-primary:#FF0000;--font-family-body:'A;B', serif;--unknown:1;color:red}",
+        );
+        assert_eq!(parsed.colors.get_color("primary").unwrap().color, "#ff0000");
+        assert_eq!(
+            parsed.typography.font_families[0].font_family,
+            "'A;B', serif"
+        );
+        assert!(parsed.layout.block_gap.is_none());
+        assert_eq!(parsed.flatten().len(), 4);
+
+        let mut tokens = DesignTokens::new();
+        assert!(tokens.set_token("--color-primary", "#111"));
+        assert!(!tokens.set_token("--color-primary", "nope!"));
+        assert!(!tokens.set_token("--shadow-small", "0 1px 2px #000"));
+        assert_eq!(tokens.colors.get_color("primary").unwrap().color, "#111111");
+    }
+
     #[test]
     fn test_layout_css() {
         let layout = LayoutSettings::new().with_defaults();
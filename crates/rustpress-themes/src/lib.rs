@@ -44,14 +44,20 @@ pub use fse::{FseManager, FseTemplate, TemplatePart};
 pub use images::{ImageSize, ResponsiveImageGenerator};
 pub use manager::{RegisteredTheme, ThemeManager, ThemePreview};
 pub use manifest::ThemeManifest;
-pub use marketplace::{MarketplaceClient, MarketplaceConfig, ThemeListing};
+pub use marketplace::{MarketplaceClient, MarketplaceConfig, ThemeInstallResult, ThemeListing};
 pub use patterns::{BlockPattern, PatternRegistry};
-pub use quality::{AccessibilityChecker, AmpCompatibility, PerformanceScorer};
+pub use quality::{
+    AccessibilityChecker, AmpCompatibility, PerformanceFinding, PerformanceReport,
+    PerformanceScorer,
+};
 pub use settings::{GlobalSettingsRegistry, ThemeSettings};
-pub use starter_content::StarterContent;
+pub use starter_content::{StarterContent, StarterContentResult, StarterContentServices};
 pub use templates::{TemplateEngine, TemplateHierarchy, TemplatePartManager};
 pub use theme_json::ThemeJson;
-pub use variations::{DarkModeConfig, StyleVariation, VariationManager};
+pub use variations::{
+    DarkModeConfig, PreferenceStore, ResolvedVariation, StyleVariation, VariationManager,
+    VariationPreference,
+};
 
 use thiserror::Error;
 
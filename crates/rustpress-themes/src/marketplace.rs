@@ -2,6 +2,8 @@
 //!
 //! Browse, search, and install themes from remote repositories.
 
+use crate::manager::ThemeManager;
+use crate::manifest::ThemeManifest;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -30,6 +32,12 @@ pub enum MarketplaceError {
 
     #[error("Invalid response: {0}")]
     InvalidResponse(String),
+
+    #[error("Checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+
+    #[error("Theme requires RustPress {required}, running {running}")]
+    Incompatible { required: String, running: String },
 }
 
 /// Marketplace configuration
@@ -43,6 +51,8 @@ pub struct MarketplaceConfig {
     pub download_dir: PathBuf,
     /// Number of results per page
     pub per_page: u32,
+    /// RustPress version themes are checked against
+    pub rustpress_version: String,
 }
 
 impl Default for MarketplaceConfig {
@@ -52,6 +62,7 @@ impl Default for MarketplaceConfig {
             cache_duration: 3600, // 1 hour
             download_dir: PathBuf::from("temp/theme-downloads"),
             per_page: 24,
+            rustpress_version: rustpress_core::VERSION.to_string(),
         }
     }
 }
@@ -76,6 +87,9 @@ pub struct ThemeListing {
     pub last_updated: String,
     pub requires: Option<String>,
     pub requires_php: Option<String>,
+    /// BLAKE3 hash of the package, hex encoded (optionally `blake3:` prefixed)
+    #[serde(default)]
+    pub package_hash: Option<String>,
     pub tags: Vec<String>,
     pub features: Vec<String>,
     pub is_block_theme: bool,
@@ -145,10 +159,21 @@ pub struct SearchResults {
     pub total_pages: u32,
 }
 
+/// Result of a marketplace theme installation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeInstallResult {
+    pub theme_id: String,
+    pub version: String,
+    pub path: PathBuf,
+    pub package_hash: String,
+}
+
 /// Theme marketplace client
 pub struct MarketplaceClient {
     config: MarketplaceConfig,
     client: reqwest::Client,
+    /// Theme manager installed themes are registered with
+    manager: Option<Arc<ThemeManager>>,
     /// Cache of search results
     search_cache: Arc<RwLock<HashMap<String, CachedResult<SearchResults>>>>,
     /// Cache of theme details
@@ -173,11 +198,18 @@ impl MarketplaceClient {
         Self {
             config,
             client,
+            manager: None,
             search_cache: Arc::new(RwLock::new(HashMap::new())),
             theme_cache: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Register installed themes with a theme manager
+    pub fn with_manager(mut self, manager: Arc<ThemeManager>) -> Self {
+        self.manager = Some(manager);
+        self
+    }
+
     /// Search for themes
     pub async fn search(&self, params: &SearchParams) -> Result<SearchResults, MarketplaceError> {
         let cache_key = format!("{:?}", params);
@@ -292,26 +324,97 @@ impl MarketplaceClient {
         Ok(download_path)
     }
 
-    /// Install a theme from marketplace
-    pub async fn install(
+    /// Install a theme from the marketplace.
+    ///
+    /// The package is verified against the listing's published hash and the
+    /// theme's RustPress requirement before anything is written to the
+    /// themes directory. Extraction happens in a temporary directory that
+    /// is only moved into place once the package has been validated.
+    pub async fn install(&self, listing_id: &str) -> Result<ThemeInstallResult, MarketplaceError> {
+        let manager = self.manager.as_ref().ok_or_else(|| {
+            MarketplaceError::InstallFailed("No theme manager configured".to_string())
+        })?;
+
+        let listing = self.get_theme(listing_id).await?;
+
+        if let Some(required) = &listing.requires {
+            check_compatibility(required, &self.config.rustpress_version)?;
+        }
+
+        let response = self.client.get(&listing.download_url).send().await?;
+
+        if !response.status().is_success() {
+            return Err(MarketplaceError::DownloadFailed(
+                response.status().to_string(),
+            ));
+        }
+
+        let bytes = response.bytes().await?;
+
+        self.install_package(manager, &listing, &bytes).await
+    }
+
+    /// Verify, extract and register a downloaded theme package
+    async fn install_package(
         &self,
-        theme: &ThemeListing,
-        themes_dir: &Path,
-    ) -> Result<PathBuf, MarketplaceError> {
-        // Download theme
-        let zip_path = self.download(theme).await?;
-
-        // Extract to themes directory
-        let importer = crate::export::ThemeImporter::new(themes_dir.to_path_buf());
-        let theme_id = importer
-            .import_zip(&zip_path)
-            .await
-            .map_err(|e| MarketplaceError::InstallFailed(e.to_string()))?;
+        manager: &ThemeManager,
+        listing: &ThemeListing,
+        package: &[u8],
+    ) -> Result<ThemeInstallResult, MarketplaceError> {
+        let package_hash = verify_checksum(listing, package)?;
+
+        let themes_dir = manager.themes_dir().to_path_buf();
+        fs::create_dir_all(&themes_dir).await?;
+
+        // Extract next to the final location so the move is a rename
+        let staging = tempfile::Builder::new()
+            .prefix(".install-")
+            .tempdir_in(&themes_dir)?;
+
+        let package = package.to_vec();
+        let staging_path = staging.path().to_path_buf();
+        let theme_root =
+            tokio::task::spawn_blocking(move || extract_package(&package, &staging_path))
+                .await
+                .map_err(|e| MarketplaceError::InstallFailed(e.to_string()))??;
+
+        let manifest_content = fs::read_to_string(theme_root.join("theme.toml")).await?;
+        let manifest = ThemeManifest::from_toml(&manifest_content)
+            .map_err(|e| MarketplaceError::InstallFailed(format!("Invalid theme.toml: {}", e)))?;
+
+        if let Some(required) = &manifest.theme.requires_rustpress {
+            check_compatibility(required, &self.config.rustpress_version)?;
+        }
+
+        let theme_id = manifest.theme.id.clone();
+        if theme_id.is_empty() || theme_id.contains(['/', '\\', '.']) {
+            return Err(MarketplaceError::InstallFailed(format!(
+                "Invalid theme id: {:?}",
+                theme_id
+            )));
+        }
+
+        let dest = themes_dir.join(&theme_id);
+        if dest.exists() {
+            return Err(MarketplaceError::InstallFailed(format!(
+                "Theme already installed: {}",
+                theme_id
+            )));
+        }
 
-        // Clean up download
-        let _ = fs::remove_file(zip_path).await;
+        fs::rename(&theme_root, &dest).await?;
 
-        Ok(themes_dir.join(theme_id))
+        if let Err(e) = manager.register_theme(&theme_id).await {
+            let _ = fs::remove_dir_all(&dest).await;
+            return Err(MarketplaceError::InstallFailed(e.to_string()));
+        }
+
+        Ok(ThemeInstallResult {
+            theme_id,
+            version: manifest.theme.version,
+            path: dest,
+            package_hash,
+        })
     }
 
     /// Get featured themes
@@ -452,6 +555,104 @@ impl MarketplaceClient {
     }
 }
 
+/// Check a downloaded package against the listing's published hash,
+/// returning the computed hash.
+fn verify_checksum(listing: &ThemeListing, package: &[u8]) -> Result<String, MarketplaceError> {
+    let expected = listing
+        .package_hash
+        .as_deref()
+        .map(|h| h.trim_start_matches("blake3:").to_ascii_lowercase())
+        .ok_or_else(|| {
+            MarketplaceError::InstallFailed(format!(
+                "Listing {} has no published package hash",
+                listing.slug
+            ))
+        })?;
+
+    let actual = blake3::hash(package).to_hex().to_string();
+
+    if actual != expected {
+        return Err(MarketplaceError::ChecksumMismatch { expected, actual });
+    }
+
+    Ok(actual)
+}
+
+/// Check a theme's RustPress requirement against the running version.
+///
+/// A bare version (`"0.4.0"`) is a minimum; anything else is parsed as a
+/// semver requirement (`"^0.4"`, `">=0.3, <0.5"`).
+fn check_compatibility(required: &str, running: &str) -> Result<(), MarketplaceError> {
+    let incompatible = || MarketplaceError::Incompatible {
+        required: required.to_string(),
+        running: running.to_string(),
+    };
+
+    let trimmed = required.trim();
+    let req = if trimmed.starts_with(|c: char| c.is_ascii_digit()) {
+        semver::VersionReq::parse(&format!(">={}", trimmed))
+    } else {
+        semver::VersionReq::parse(trimmed)
+    }
+    .map_err(|_| incompatible())?;
+
+    let version = semver::Version::parse(running).map_err(|_| incompatible())?;
+
+    if req.matches(&version) {
+        Ok(())
+    } else {
+        Err(incompatible())
+    }
+}
+
+/// Extract a theme package into `dest`, returning the theme root
+/// (the directory containing `theme.toml`).
+fn extract_package(package: &[u8], dest: &Path) -> Result<PathBuf, MarketplaceError> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(package))
+        .map_err(|e| MarketplaceError::InstallFailed(format!("Invalid package: {}", e)))?;
+
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| MarketplaceError::InstallFailed(format!("Invalid package: {}", e)))?;
+
+        let relative = file.enclosed_name().map(Path::to_path_buf).ok_or_else(|| {
+            MarketplaceError::InstallFailed(format!("Unsafe path in package: {}", file.name()))
+        })?;
+        let outpath = dest.join(relative);
+
+        if file.is_dir() {
+            std::fs::create_dir_all(&outpath)?;
+        } else {
+            if let Some(parent) = outpath.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut outfile = std::fs::File::create(&outpath)?;
+            std::io::copy(&mut file, &mut outfile)?;
+        }
+    }
+
+    if dest.join("theme.toml").is_file() {
+        return Ok(dest.to_path_buf());
+    }
+
+    // Packages normally wrap the theme in a single top-level directory
+    let mut roots = std::fs::read_dir(dest)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.join("theme.toml").is_file());
+
+    match (roots.next(), roots.next()) {
+        (Some(root), None) => Ok(root),
+        (Some(_), Some(_)) => Err(MarketplaceError::InstallFailed(
+            "Package contains more than one theme".to_string(),
+        )),
+        _ => Err(MarketplaceError::InstallFailed(
+            "No theme.toml found in package".to_string(),
+        )),
+    }
+}
+
 /// Tag information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TagInfo {
@@ -532,6 +733,125 @@ mod tests {
         assert_eq!(SortOption::Rating.as_str(), "rating");
     }
 
+    fn listing(package: &[u8]) -> ThemeListing {
+        ThemeListing {
+            id: "1".to_string(),
+            name: "Aurora".to_string(),
+            slug: "aurora".to_string(),
+            version: "1.0.0".to_string(),
+            author: ThemeAuthor {
+                name: "RustPress".to_string(),
+                url: None,
+                avatar: None,
+            },
+            description: String::new(),
+            short_description: None,
+            homepage: None,
+            preview_url: None,
+            screenshot_url: None,
+            download_url: String::new(),
+            download_count: 0,
+            rating: 0.0,
+            ratings_count: 0,
+            last_updated: String::new(),
+            requires: None,
+            requires_php: None,
+            package_hash: Some(blake3::hash(package).to_hex().to_string()),
+            tags: vec![],
+            features: vec![],
+            is_block_theme: false,
+            price: None,
+        }
+    }
+
+    fn package(requires: &str) -> Vec<u8> {
+        use std::io::Write;
+
+        let mut buf = std::io::Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut buf);
+            let options = zip::write::FileOptions::default();
+            zip.start_file("aurora/theme.toml", options).unwrap();
+            write!(
+                zip,
+                "[theme]\nid = \"aurora\"\nname = \"Aurora\"\nversion = \"1.0.0\"\ndescription = \"\"\nauthor = \"RustPress\"\nrequires_rustpress = \"{}\"\n",
+                requires
+            )
+            .unwrap();
+            zip.start_file("aurora/templates/index.html", options)
+                .unwrap();
+            write!(zip, "<main></main>").unwrap();
+            zip.finish().unwrap();
+        }
+        buf.into_inner()
+    }
+
+    #[test]
+    fn test_check_compatibility() {
+        assert!(check_compatibility("0.3.0", "0.4.0").is_ok());
+        assert!(check_compatibility("^0.4", "0.4.2").is_ok());
+        assert!(matches!(
+            check_compatibility("0.5.0", "0.4.0"),
+            Err(MarketplaceError::Incompatible { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_install_rejects_checksum_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = Arc::new(ThemeManager::new(dir.path().join("themes")));
+        let client = MarketplaceClient::new(MarketplaceConfig::default());
+
+        let pkg = package("0.1.0");
+        let mut listing = listing(&pkg);
+        listing.package_hash = Some("blake3:deadbeef".to_string());
+
+        let result = client.install_package(&manager, &listing, &pkg).await;
+        assert!(matches!(
+            result,
+            Err(MarketplaceError::ChecksumMismatch { .. })
+        ));
+        assert!(!dir.path().join("themes/aurora").exists());
+    }
+
+    #[tokio::test]
+    async fn test_install_rejects_incompatible_theme_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let themes_dir = dir.path().join("themes");
+        let manager = Arc::new(ThemeManager::new(themes_dir.clone()));
+        let client = MarketplaceClient::new(MarketplaceConfig {
+            rustpress_version: "0.4.0".to_string(),
+            ..Default::default()
+        });
+
+        let pkg = package("9.0.0");
+        let result = client.install_package(&manager, &listing(&pkg), &pkg).await;
+        assert!(matches!(result, Err(MarketplaceError::Incompatible { .. })));
+
+        // Nothing left behind, not even the staging directory
+        assert_eq!(std::fs::read_dir(&themes_dir).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_install_package_registers_theme() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = Arc::new(ThemeManager::new(dir.path().join("themes")));
+        let client = MarketplaceClient::new(MarketplaceConfig {
+            rustpress_version: "0.4.0".to_string(),
+            ..Default::default()
+        });
+
+        let pkg = package("0.4.0");
+        let result = client
+            .install_package(&manager, &listing(&pkg), &pkg)
+            .await
+            .unwrap();
+
+        assert_eq!(result.theme_id, "aurora");
+        assert!(result.path.join("templates/index.html").exists());
+        assert!(manager.get_theme("aurora").is_some());
+    }
+
     #[test]
     fn test_marketplace_config_default() {
         let config = MarketplaceConfig::default();
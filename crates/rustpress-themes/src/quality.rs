@@ -1,9 +1,16 @@
 //! Theme Quality: AMP (216), Accessibility (217), Performance (218)
 //!
-//! AMP compatibility, accessibility checks, and performance scoring.
+//! AMP compatibility, accessibility checks, and performance scoring, including
+//! static analysis of a theme's templates and assets for Web Vitals risks.
 
+use crate::assets::{AssetCompiler, AssetConfig};
+use crate::critical_css::{CriticalCssConfig, CriticalCssExtractor};
+use crate::manager::RegisteredTheme;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
+use tokio::fs;
 
 /// Quality check errors
 #[derive(Debug, Error)]
@@ -726,27 +733,596 @@ impl PerformanceScorer {
         score += compression_score * self.weights.compression;
 
         let overall = (score * 100.0).round() / 100.0;
-        let grade = if overall >= 0.9 {
-            'A'
-        } else if overall >= 0.8 {
-            'B'
-        } else if overall >= 0.7 {
-            'C'
-        } else if overall >= 0.6 {
-            'D'
-        } else {
-            'F'
-        };
 
         PerformanceScore {
             overall,
-            grade,
+            grade: grade_for(overall),
             metrics,
             recommendations,
         }
     }
 }
 
+fn grade_for(overall: f32) -> char {
+    if overall >= 0.9 {
+        'A'
+    } else if overall >= 0.8 {
+        'B'
+    } else if overall >= 0.7 {
+        'C'
+    } else if overall >= 0.6 {
+        'D'
+    } else {
+        'F'
+    }
+}
+
+//=============================================================================
+// Static theme analysis
+//=============================================================================
+
+/// Maximum score a single kind of finding can take off, so one noisy
+/// template cannot sink the whole report
+const MAX_PENALTY_PER_KIND: f32 = 0.25;
+/// Unique resources referenced by templates before requests become a concern
+const REQUEST_BUDGET: usize = 25;
+/// Text assets below this size are not worth reporting as unminified
+const MIN_REPORTED_TEXT_ASSET: u64 = 20_000;
+/// Images above this size are reported as oversized
+const MAX_IMAGE_SIZE: u64 = 200_000;
+
+/// Severity of a static performance finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingSeverity {
+    Low,
+    Medium,
+    High,
+}
+
+impl FindingSeverity {
+    fn penalty(self) -> f32 {
+        match self {
+            Self::Low => 0.02,
+            Self::Medium => 0.05,
+            Self::High => 0.1,
+        }
+    }
+}
+
+/// Core Web Vital a finding is most likely to hurt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum WebVital {
+    /// Largest Contentful Paint
+    Lcp,
+    /// Cumulative Layout Shift
+    Cls,
+    /// Interaction to Next Paint
+    Inp,
+}
+
+/// Kind of static performance finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    RenderBlockingCss,
+    RenderBlockingScript,
+    UnsizedImage,
+    LargeAsset,
+    FontDisplayMissing,
+    TooManyRequests,
+}
+
+/// A likely Web Vitals problem found in a theme's files
+#[derive(Debug, Clone, Serialize)]
+pub struct PerformanceFinding {
+    pub kind: FindingKind,
+    pub vital: WebVital,
+    pub severity: FindingSeverity,
+    /// Theme-relative path of the file the finding was raised against
+    pub file: String,
+    pub message: String,
+    /// Concrete change that resolves the finding
+    pub fix: String,
+}
+
+/// Result of statically analyzing a theme
+///
+/// Built only from the theme's files, so the same theme always produces the
+/// same report.
+#[derive(Debug, Clone, Serialize)]
+pub struct PerformanceReport {
+    pub theme_id: String,
+    pub overall: f32,
+    pub grade: char,
+    /// Findings ordered from most to least severe
+    pub findings: Vec<PerformanceFinding>,
+    /// Unique resources referenced by the theme's templates
+    pub request_count: usize,
+    pub css_size: u64,
+    pub js_size: u64,
+    /// Size of the above-the-fold CSS the templates need, when extractable
+    pub critical_css_size: Option<usize>,
+}
+
+impl PerformanceReport {
+    /// Findings affecting a single Web Vital
+    pub fn findings_for(&self, vital: WebVital) -> impl Iterator<Item = &PerformanceFinding> {
+        self.findings.iter().filter(move |f| f.vital == vital)
+    }
+}
+
+/// A theme template read from disk
+struct ThemeTemplate {
+    file: String,
+    content: String,
+}
+
+impl ThemeTemplate {
+    /// Markup up to `</head>`, for templates that open the document
+    fn head(&self) -> Option<&str> {
+        let end = self.content.to_lowercase().find("</head>")?;
+        Some(&self.content[..end])
+    }
+}
+
+impl PerformanceScorer {
+    /// Statically analyze a theme's templates and assets for likely Web
+    /// Vitals problems
+    ///
+    /// Looks for render-blocking CSS and JS in the document head, images
+    /// without dimensions, large or unminified assets, web fonts without
+    /// `font-display`, and templates pulling in too many resources.
+    /// Unreadable files are skipped rather than failing the report.
+    pub async fn score_theme(&self, theme: &RegisteredTheme) -> PerformanceReport {
+        let root = theme.path.as_path();
+        let compiler = AssetCompiler::new(AssetConfig::default());
+        let mut findings = Vec::new();
+
+        let templates = read_templates(&root.join("templates")).await;
+        let templates: Vec<ThemeTemplate> = templates
+            .into_iter()
+            .map(|(path, content)| ThemeTemplate {
+                file: relative_path(root, &path),
+                content,
+            })
+            .collect();
+
+        let css_files = compiler
+            .find_files(root, &["css"])
+            .await
+            .unwrap_or_default();
+        let js_files = compiler.find_files(root, &["js"]).await.unwrap_or_default();
+        let image_files = compiler
+            .find_files(root, &["jpg", "jpeg", "png", "gif", "webp", "avif"])
+            .await
+            .unwrap_or_default();
+
+        let mut stylesheets = Vec::new();
+        for path in &css_files {
+            if let Ok(content) = fs::read_to_string(path).await {
+                stylesheets.push((relative_path(root, path), content));
+            }
+        }
+        let css_size = stylesheets.iter().map(|(_, c)| c.len() as u64).sum();
+
+        // Above-the-fold CSS the templates actually use, via the same
+        // extractor the critical CSS pipeline runs
+        let critical_css_size = if stylesheets.is_empty() || templates.is_empty() {
+            None
+        } else {
+            let html: String = templates.iter().map(|t| t.content.as_str()).collect();
+            let css: String = stylesheets.iter().map(|(_, c)| c.as_str()).collect();
+            CriticalCssExtractor::new(CriticalCssConfig::default())
+                .extract(&html, &css)
+                .await
+                .ok()
+                .map(|result| result.size)
+        };
+        let inlines_critical_css = templates
+            .iter()
+            .any(|t| t.content.contains("id=\"critical-css\""));
+
+        self.check_render_blocking(
+            &templates,
+            critical_css_size,
+            inlines_critical_css,
+            &mut findings,
+        );
+        self.check_image_dimensions(&templates, &mut findings);
+        self.check_font_display(&templates, &stylesheets, &mut findings);
+
+        for (file, content) in &stylesheets {
+            let size = content.len() as u64;
+            let minified = compiler
+                .process_css(content)
+                .map(|css| css.len() as u64)
+                .unwrap_or(size);
+            self.check_text_asset(file, size, minified, &mut findings);
+        }
+
+        let mut js_size = 0;
+        for path in &js_files {
+            if let Ok(content) = fs::read_to_string(path).await {
+                let size = content.len() as u64;
+                js_size += size;
+                let minified = compiler.minify_js(&content).len() as u64;
+                self.check_text_asset(&relative_path(root, path), size, minified, &mut findings);
+            }
+        }
+
+        for path in &image_files {
+            if let Ok(metadata) = fs::metadata(path).await {
+                self.check_image_size(&relative_path(root, path), metadata.len(), &mut findings);
+            }
+        }
+
+        let request_count = self.check_request_count(&templates, &mut findings);
+
+        findings.sort_by(|a, b| {
+            b.severity
+                .cmp(&a.severity)
+                .then(a.kind.cmp(&b.kind))
+                .then_with(|| a.file.cmp(&b.file))
+                .then_with(|| a.message.cmp(&b.message))
+        });
+
+        let overall = score_findings(&findings);
+
+        PerformanceReport {
+            theme_id: theme.id.clone(),
+            overall,
+            grade: grade_for(overall),
+            findings,
+            request_count,
+            css_size,
+            js_size,
+            critical_css_size,
+        }
+    }
+
+    fn check_render_blocking(
+        &self,
+        templates: &[ThemeTemplate],
+        critical_css_size: Option<usize>,
+        inlines_critical_css: bool,
+        findings: &mut Vec<PerformanceFinding>,
+    ) {
+        let link_re = regex::Regex::new(r#"(?i)<link\s+([^>]*)>"#).unwrap();
+        let script_re = regex::Regex::new(r#"(?i)<script\s+([^>]*)>"#).unwrap();
+
+        let css_fix = match critical_css_size {
+            Some(size) if !inlines_critical_css => format!(
+                "Inline the ~{} bytes of above-the-fold CSS with CriticalCssInliner and load \
+                 this stylesheet with CriticalCssInliner::add_preload",
+                size
+            ),
+            _ => "Load this stylesheet with CriticalCssInliner::add_preload or give it a \
+                  non-blocking media attribute"
+                .to_string(),
+        };
+
+        for template in templates {
+            let Some(head) = template.head() else {
+                continue;
+            };
+
+            for cap in link_re.captures_iter(head) {
+                let attrs = &cap[1];
+                if !attr_has(attrs, "rel", "stylesheet") || attr_has(attrs, "media", "print") {
+                    continue;
+                }
+                findings.push(PerformanceFinding {
+                    kind: FindingKind::RenderBlockingCss,
+                    vital: WebVital::Lcp,
+                    severity: if inlines_critical_css {
+                        FindingSeverity::Low
+                    } else {
+                        FindingSeverity::High
+                    },
+                    file: template.file.clone(),
+                    message: format!(
+                        "Stylesheet {} blocks rendering",
+                        attr_value(attrs, "href").unwrap_or("(inline)")
+                    ),
+                    fix: css_fix.clone(),
+                });
+            }
+
+            for cap in script_re.captures_iter(head) {
+                let attrs = &cap[1];
+                let Some(src) = attr_value(attrs, "src") else {
+                    continue;
+                };
+                if attr_present(attrs, "async")
+                    || attr_present(attrs, "defer")
+                    || attr_has(attrs, "type", "module")
+                {
+                    continue;
+                }
+                findings.push(PerformanceFinding {
+                    kind: FindingKind::RenderBlockingScript,
+                    vital: WebVital::Lcp,
+                    severity: FindingSeverity::High,
+                    file: template.file.clone(),
+                    message: format!("Script {} blocks parsing of the document", src),
+                    fix: "Add `defer` to the script tag, or move it before </body>".to_string(),
+                });
+            }
+        }
+    }
+
+    fn check_image_dimensions(
+        &self,
+        templates: &[ThemeTemplate],
+        findings: &mut Vec<PerformanceFinding>,
+    ) {
+        let img_re = regex::Regex::new(r#"(?i)<img\s+([^>]*)>"#).unwrap();
+
+        for template in templates {
+            for cap in img_re.captures_iter(&template.content) {
+                let attrs = &cap[1];
+                let sized =
+                    attr_value(attrs, "width").is_some() && attr_value(attrs, "height").is_some();
+                let has_aspect_ratio =
+                    attr_value(attrs, "style").map_or(false, |s| s.contains("aspect-ratio"));
+                if sized || has_aspect_ratio {
+                    continue;
+                }
+                findings.push(PerformanceFinding {
+                    kind: FindingKind::UnsizedImage,
+                    vital: WebVital::Cls,
+                    severity: FindingSeverity::Medium,
+                    file: template.file.clone(),
+                    message: format!(
+                        "Image {} has no width/height, so the layout shifts when it loads",
+                        attr_value(attrs, "src").unwrap_or("(unknown)")
+                    ),
+                    fix: "Add width and height attributes matching the intrinsic size, or \
+                          reserve space with a CSS aspect-ratio"
+                        .to_string(),
+                });
+            }
+        }
+    }
+
+    fn check_font_display(
+        &self,
+        templates: &[ThemeTemplate],
+        stylesheets: &[(String, String)],
+        findings: &mut Vec<PerformanceFinding>,
+    ) {
+        let font_face_re = regex::Regex::new(r#"(?i)@font-face\s*\{([^{}]*)\}"#).unwrap();
+        let family_re = regex::Regex::new(r#"(?i)font-family\s*:\s*([^;]+)"#).unwrap();
+        let link_re = regex::Regex::new(r#"(?i)<link\s+([^>]*)>"#).unwrap();
+
+        let sources = stylesheets
+            .iter()
+            .map(|(file, css)| (file, css.as_str()))
+            .chain(templates.iter().map(|t| (&t.file, t.content.as_str())));
+
+        for (file, source) in sources {
+            for cap in font_face_re.captures_iter(source) {
+                let body = &cap[1];
+                if body.to_lowercase().contains("font-display") {
+                    continue;
+                }
+                let family = family_re
+                    .captures(body)
+                    .map(|c| {
+                        c[1].trim()
+                            .trim_matches(|c| c == '"' || c == '\'')
+                            .to_string()
+                    })
+                    .unwrap_or_else(|| "(unnamed)".to_string());
+                findings.push(PerformanceFinding {
+                    kind: FindingKind::FontDisplayMissing,
+                    vital: WebVital::Lcp,
+                    severity: FindingSeverity::Medium,
+                    file: file.clone(),
+                    message: format!("@font-face for {} hides text until the font loads", family),
+                    fix: "Add `font-display: swap;` to the @font-face rule".to_string(),
+                });
+            }
+        }
+
+        for template in templates {
+            for cap in link_re.captures_iter(&template.content) {
+                let Some(href) = attr_value(&cap[1], "href") else {
+                    continue;
+                };
+                if href.contains("fonts.googleapis.com") && !href.contains("display=") {
+                    findings.push(PerformanceFinding {
+                        kind: FindingKind::FontDisplayMissing,
+                        vital: WebVital::Lcp,
+                        severity: FindingSeverity::Medium,
+                        file: template.file.clone(),
+                        message: format!("Web font stylesheet {} sets no font-display", href),
+                        fix: "Append `&display=swap` to the font stylesheet URL".to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn check_text_asset(
+        &self,
+        file: &str,
+        size: u64,
+        minified: u64,
+        findings: &mut Vec<PerformanceFinding>,
+    ) {
+        if size < MIN_REPORTED_TEXT_ASSET {
+            return;
+        }
+        let savings = size.saturating_sub(minified);
+        // Already minified: nothing concrete to suggest at this size
+        if savings * 10 < size {
+            return;
+        }
+        let is_js = file.ends_with(".js");
+        findings.push(PerformanceFinding {
+            kind: FindingKind::LargeAsset,
+            vital: if is_js { WebVital::Inp } else { WebVital::Lcp },
+            severity: size_severity(size, 50_000, 100_000),
+            file: file.to_string(),
+            message: format!(
+                "{} bytes unminified; about {} bytes could be saved",
+                size, savings
+            ),
+            fix: "Serve the AssetCompiler output (minified, cache-busted) instead of the source \
+                  file"
+                .to_string(),
+        });
+    }
+
+    fn check_image_size(&self, file: &str, size: u64, findings: &mut Vec<PerformanceFinding>) {
+        if size <= MAX_IMAGE_SIZE {
+            return;
+        }
+        findings.push(PerformanceFinding {
+            kind: FindingKind::LargeAsset,
+            vital: WebVital::Lcp,
+            severity: size_severity(size, MAX_IMAGE_SIZE, 500_000),
+            file: file.to_string(),
+            message: format!("Image is {} bytes", size),
+            fix: "Re-encode as WebP/AVIF and serve responsive sizes via \
+                  ResponsiveImageGenerator"
+                .to_string(),
+        });
+    }
+
+    /// Count unique resources referenced by the templates and report when
+    /// they exceed the request budget
+    fn check_request_count(
+        &self,
+        templates: &[ThemeTemplate],
+        findings: &mut Vec<PerformanceFinding>,
+    ) -> usize {
+        let resource_re = regex::Regex::new(
+            r#"(?i)<(?:link|script|img|iframe|video|audio|source)\s+[^>]*\b(?:href|src)="([^"]+)""#,
+        )
+        .unwrap();
+
+        let resources: BTreeSet<&str> = templates
+            .iter()
+            .flat_map(|t| resource_re.captures_iter(&t.content))
+            .filter_map(|cap| cap.get(1))
+            .map(|m| m.as_str())
+            .filter(|url| !url.starts_with("data:") && !url.starts_with('#'))
+            .collect();
+
+        let count = resources.len();
+        if count > REQUEST_BUDGET {
+            findings.push(PerformanceFinding {
+                kind: FindingKind::TooManyRequests,
+                vital: WebVital::Lcp,
+                severity: if count > REQUEST_BUDGET * 2 {
+                    FindingSeverity::High
+                } else {
+                    FindingSeverity::Medium
+                },
+                file: "templates".to_string(),
+                message: format!(
+                    "Templates reference {} resources (budget {})",
+                    count, REQUEST_BUDGET
+                ),
+                fix: "Bundle stylesheets and scripts with AssetCompiler::bundle_css/bundle_js \
+                      and lazy-load below-the-fold media"
+                    .to_string(),
+            });
+        }
+        count
+    }
+}
+
+/// Read every template under `dir`, sorted by path
+async fn read_templates(dir: &Path) -> Vec<(PathBuf, String)> {
+    let mut paths: Vec<PathBuf> = walkdir::WalkDir::new(dir)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .map(|e| e.into_path())
+        .filter(|p| {
+            p.is_file()
+                && p.extension().map_or(false, |ext| {
+                    matches!(ext.to_str(), Some("html" | "twig" | "tera"))
+                })
+        })
+        .collect();
+    paths.sort();
+
+    let mut templates = Vec::with_capacity(paths.len());
+    for path in paths {
+        if let Ok(content) = fs::read_to_string(&path).await {
+            templates.push((path, content));
+        }
+    }
+    templates
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Value of a double-quoted attribute in a tag's attribute list
+fn attr_value<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!(r#"(?i)(?:^|\s){}\s*=\s*"([^"]*)""#, regex::escape(name));
+    let re = regex::Regex::new(&pattern).unwrap();
+    re.captures(attrs)
+        .and_then(|c| c.get(1))
+        .map(|m| m.as_str())
+}
+
+/// Whether a tag's attribute list contains `name`, including boolean
+/// attributes such as `defer`
+fn attr_present(attrs: &str, name: &str) -> bool {
+    let quoted = regex::Regex::new(r#""[^"]*"|'[^']*'"#).unwrap();
+    quoted
+        .replace_all(attrs, "")
+        .split_whitespace()
+        .any(|token| {
+            token
+                .split('=')
+                .next()
+                .map_or(false, |attr| attr.eq_ignore_ascii_case(name))
+        })
+}
+
+fn attr_has(attrs: &str, name: &str, value: &str) -> bool {
+    attr_value(attrs, name).map_or(false, |v| {
+        v.split_whitespace()
+            .any(|part| part.eq_ignore_ascii_case(value))
+    })
+}
+
+fn size_severity(size: u64, medium: u64, high: u64) -> FindingSeverity {
+    if size > high {
+        FindingSeverity::High
+    } else if size > medium {
+        FindingSeverity::Medium
+    } else {
+        FindingSeverity::Low
+    }
+}
+
+/// Deduct each finding's penalty, capped per finding kind
+fn score_findings(findings: &[PerformanceFinding]) -> f32 {
+    let mut penalties: BTreeMap<FindingKind, f32> = BTreeMap::new();
+    for finding in findings {
+        *penalties.entry(finding.kind).or_default() += finding.severity.penalty();
+    }
+    let total: f32 = penalties
+        .values()
+        .map(|p| p.min(MAX_PENALTY_PER_KIND))
+        .sum();
+    ((1.0 - total).max(0.0) * 100.0).round() / 100.0
+}
+
 impl Default for PerformanceScorer {
     fn default() -> Self {
         Self::new()
@@ -756,6 +1332,8 @@ impl Default for PerformanceScorer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::manager::ThemeStatus;
+    use crate::manifest::ThemeManifest;
 
     #[test]
     fn test_amp_transform_images() {
@@ -777,6 +1355,112 @@ mod tests {
         assert!(report.errors > 0);
     }
 
+    async fn write_file(path: PathBuf, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+        fs::write(path, content).await.unwrap();
+    }
+
+    fn registered_theme(path: &Path) -> RegisteredTheme {
+        let manifest = ThemeManifest::from_toml(
+            "[theme]\nid = \"perf\"\nname = \"perf\"\nversion = \"1.0.0\"\n\
+             description = \"\"\nauthor = \"\"\n",
+        )
+        .unwrap();
+        RegisteredTheme {
+            id: "perf".to_string(),
+            manifest,
+            path: path.to_path_buf(),
+            status: ThemeStatus::Inactive,
+            parent_id: None,
+            screenshot: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_score_theme_reports_web_vitals_findings() {
+        let dir = tempfile::tempdir().unwrap();
+        write_file(
+            dir.path().join("templates/base.html"),
+            r#"<html><head>
+<link rel="stylesheet" href="/assets/css/style.css">
+<link rel="stylesheet" href="https://fonts.googleapis.com/css2?family=Inter">
+<script src="/assets/js/app.js"></script>
+<script src="/assets/js/menu.js" defer></script>
+</head><body class="site"><img src="/hero.jpg"><img src="/logo.png" width="10" height="10"></body></html>"#,
+        )
+        .await;
+        write_file(
+            dir.path().join("assets/css/style.css"),
+            "@font-face { font-family: \"Brand\"; src: url(brand.woff2); }\n.site { color: red; }",
+        )
+        .await;
+
+        let theme = registered_theme(dir.path());
+        let report = PerformanceScorer::new().score_theme(&theme).await;
+
+        let kinds: Vec<FindingKind> = report.findings.iter().map(|f| f.kind).collect();
+        assert_eq!(
+            kinds
+                .iter()
+                .filter(|k| **k == FindingKind::RenderBlockingCss)
+                .count(),
+            2
+        );
+        assert_eq!(
+            kinds
+                .iter()
+                .filter(|k| **k == FindingKind::RenderBlockingScript)
+                .count(),
+            1
+        );
+        assert_eq!(
+            kinds
+                .iter()
+                .filter(|k| **k == FindingKind::UnsizedImage)
+                .count(),
+            1
+        );
+        assert_eq!(
+            kinds
+                .iter()
+                .filter(|k| **k == FindingKind::FontDisplayMissing)
+                .count(),
+            2
+        );
+        assert_eq!(report.findings[0].severity, FindingSeverity::High);
+        assert!(report.critical_css_size.is_some());
+        assert!(report.overall < 1.0);
+        assert!(report.findings.iter().all(|f| !f.fix.is_empty()));
+
+        let again = PerformanceScorer::new().score_theme(&theme).await;
+        assert_eq!(again.overall, report.overall);
+        assert_eq!(
+            serde_json::to_string(&again.findings).unwrap(),
+            serde_json::to_string(&report.findings).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_score_theme_clean_theme() {
+        let dir = tempfile::tempdir().unwrap();
+        write_file(
+            dir.path().join("templates/base.html"),
+            r#"<html><head><style id="critical-css">.site{color:red}</style>
+<script src="/assets/js/app.js" defer></script></head>
+<body class="site"><img src="/hero.jpg" width="800" height="400"></body></html>"#,
+        )
+        .await;
+
+        let report = PerformanceScorer::new()
+            .score_theme(&registered_theme(dir.path()))
+            .await;
+
+        assert!(report.findings.is_empty());
+        assert_eq!(report.overall, 1.0);
+        assert_eq!(report.grade, 'A');
+        assert_eq!(report.request_count, 2);
+    }
+
     #[test]
     fn test_performance_scorer() {
         let scorer = PerformanceScorer::new();
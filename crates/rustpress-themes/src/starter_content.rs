@@ -1,9 +1,13 @@
 //! Theme Starter Content
 //!
 //! Pre-defined content that gets installed when a theme is activated.
+//!
+//! [`StarterContent::apply`] imports it through [`StarterContentServices`],
+//! idempotently and with rollback on failure.
 
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use thiserror::Error;
 use tokio::fs;
@@ -19,6 +23,15 @@ pub enum StarterContentError {
 
     #[error("Content creation failed: {0}")]
     CreationFailed(String),
+
+    #[error("Invalid starter content: {0}")]
+    Invalid(String),
+
+    #[error("{error}; rollback incomplete: {}", .rollback_errors.join("; "))]
+    RollbackIncomplete {
+        error: String,
+        rollback_errors: Vec<String>,
+    },
 }
 
 /// Starter content definition
@@ -241,6 +254,505 @@ impl Default for StarterContent {
     }
 }
 
+/// Kinds of item created by [`StarterContent::apply`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StarterItemKind {
+    Media,
+    Page,
+    Post,
+    Menu,
+}
+
+impl StarterItemKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Media => "media",
+            Self::Page => "page",
+            Self::Post => "post",
+            Self::Menu => "menu",
+        }
+    }
+}
+
+/// Site services starter content is imported through, implemented on top of
+/// the content, media and menu services.
+///
+/// Items are passed with their starter key in `id` and references already
+/// rewritten to real ids. Implementations record the key with what they
+/// create so [`Self::find_imported`] can find it again, which is what makes
+/// a repeated import a no-op.
+#[async_trait]
+pub trait StarterContentServices: Send + Sync {
+    /// Id of the item previously imported under `key`, if it still exists
+    async fn find_imported(
+        &self,
+        kind: StarterItemKind,
+        key: &str,
+    ) -> Result<Option<String>, StarterContentError>;
+
+    /// Add the bundled file at `file` to the media library
+    async fn import_media(
+        &self,
+        attachment: &StarterAttachment,
+        file: &Path,
+    ) -> Result<String, StarterContentError>;
+
+    /// Create a page
+    async fn create_page(&self, page: &StarterPage) -> Result<String, StarterContentError>;
+
+    /// Create a post
+    async fn create_post(&self, post: &StarterPost) -> Result<String, StarterContentError>;
+
+    /// Create a menu and assign it to `location`
+    async fn create_menu(
+        &self,
+        location: &str,
+        menu: &StarterNavMenu,
+    ) -> Result<String, StarterContentError>;
+
+    /// Current value of a site option
+    async fn get_option(&self, key: &str)
+        -> Result<Option<serde_json::Value>, StarterContentError>;
+
+    /// Set a site option; `Null` removes it
+    async fn set_option(
+        &self,
+        key: &str,
+        value: serde_json::Value,
+    ) -> Result<(), StarterContentError>;
+
+    /// Delete an item created during a failed import
+    async fn remove(&self, kind: StarterItemKind, id: &str) -> Result<(), StarterContentError>;
+}
+
+/// Options whose value is the key of a starter page, e.g. the front page
+pub const PAGE_KEY_OPTIONS: &[&str] = &["page_on_front", "page_for_posts"];
+
+/// Result of [`StarterContent::apply`]: real ids by starter key
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StarterContentResult {
+    pub media: HashMap<String, String>,
+    pub pages: HashMap<String, String>,
+    pub posts: HashMap<String, String>,
+    /// Menu ids by theme location
+    pub menus: HashMap<String, String>,
+    /// Page shown on the front page, if set
+    pub front_page: Option<String>,
+    /// Items created by this run
+    pub created: usize,
+    /// Items found from an earlier run and left alone
+    pub existing: usize,
+}
+
+impl StarterContentResult {
+    fn ids(&self, kind: StarterItemKind) -> &HashMap<String, String> {
+        match kind {
+            StarterItemKind::Media => &self.media,
+            StarterItemKind::Page => &self.pages,
+            StarterItemKind::Post => &self.posts,
+            StarterItemKind::Menu => &self.menus,
+        }
+    }
+
+    fn ids_mut(&mut self, kind: StarterItemKind) -> &mut HashMap<String, String> {
+        match kind {
+            StarterItemKind::Media => &mut self.media,
+            StarterItemKind::Page => &mut self.pages,
+            StarterItemKind::Post => &mut self.posts,
+            StarterItemKind::Menu => &mut self.menus,
+        }
+    }
+}
+
+/// What an import changed, undone in reverse if it fails
+#[derive(Default)]
+struct ImportLog {
+    created: Vec<(StarterItemKind, String)>,
+    /// Options with the value they had before
+    options: Vec<(String, Option<serde_json::Value>)>,
+}
+
+impl ImportLog {
+    async fn rollback(self, services: &dyn StarterContentServices) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (key, previous) in self.options.into_iter().rev() {
+            let value = previous.unwrap_or(serde_json::Value::Null);
+            if let Err(e) = services.set_option(&key, value).await {
+                errors.push(format!("option {}: {}", key, e));
+            }
+        }
+        for (kind, id) in self.created.into_iter().rev() {
+            if let Err(e) = services.remove(kind, &id).await {
+                errors.push(format!("{} {}: {}", kind.as_str(), id, e));
+            }
+        }
+        errors
+    }
+}
+
+/// `{{media:key}}`, `{{page:key}}` and `{{post:key}}` placeholders in content
+fn placeholder_regex() -> &'static regex::Regex {
+    static REGEX: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    REGEX.get_or_init(|| {
+        regex::Regex::new(r"\{\{\s*(media|page|post):([A-Za-z0-9_-]+)\s*\}\}").unwrap()
+    })
+}
+
+fn placeholder_kind(kind: &str) -> StarterItemKind {
+    match kind {
+        "media" => StarterItemKind::Media,
+        "page" => StarterItemKind::Page,
+        _ => StarterItemKind::Post,
+    }
+}
+
+impl StarterContent {
+    /// Import the starter content through `services`, with bundled media
+    /// files looked up under `theme_path`.
+    ///
+    /// Media is imported first, then pages (parents before children), posts,
+    /// menus and options. Content may reference media, and pages or posts
+    /// imported before it, with `{{media:key}}`, `{{page:key}}` and
+    /// `{{post:key}}`; these, featured images, page parents, menu targets
+    /// and [`PAGE_KEY_OPTIONS`] are rewritten to the imported ids.
+    ///
+    /// Items imported by an earlier run are reused rather than duplicated.
+    /// References are checked before anything is created, and if an import
+    /// step fails, everything this run created is removed and changed
+    /// options are restored.
+    pub async fn apply(
+        &self,
+        services: &dyn StarterContentServices,
+        theme_path: &Path,
+    ) -> Result<StarterContentResult, StarterContentError> {
+        let pages = self.validate()?;
+
+        let mut result = StarterContentResult::default();
+        let mut log = ImportLog::default();
+        match self
+            .import(services, theme_path, &pages, &mut result, &mut log)
+            .await
+        {
+            Ok(()) => Ok(result),
+            Err(error) => {
+                let rollback_errors = log.rollback(services).await;
+                if rollback_errors.is_empty() {
+                    Err(error)
+                } else {
+                    Err(StarterContentError::RollbackIncomplete {
+                        error: error.to_string(),
+                        rollback_errors,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Check every reference resolves, returning the pages ordered so that
+    /// parents come before their children
+    fn validate(&self) -> Result<Vec<&StarterPage>, StarterContentError> {
+        let invalid = |message: String| Err(StarterContentError::Invalid(message));
+
+        let media: HashSet<&str> = self.attachments.iter().map(|a| a.id.as_str()).collect();
+        for attachment in &self.attachments {
+            if !is_bundled_path(&attachment.file) {
+                return invalid(format!(
+                    "Attachment {} must use a path inside the theme: {}",
+                    attachment.id, attachment.file
+                ));
+            }
+        }
+
+        // Order pages parents first
+        let by_key: HashMap<&str, &StarterPage> =
+            self.pages.iter().map(|p| (p.id.as_str(), p)).collect();
+        let mut ordered: Vec<&StarterPage> = Vec::with_capacity(self.pages.len());
+        let mut placed: HashSet<&str> = HashSet::new();
+        while ordered.len() < self.pages.len() {
+            let before = ordered.len();
+            for page in &self.pages {
+                if placed.contains(page.id.as_str()) {
+                    continue;
+                }
+                match page.parent.as_deref() {
+                    Some(parent) if !by_key.contains_key(parent) => {
+                        return invalid(format!("Page {} has unknown parent {}", page.id, parent));
+                    }
+                    Some(parent) if !placed.contains(parent) => continue,
+                    _ => {
+                        placed.insert(&page.id);
+                        ordered.push(page);
+                    }
+                }
+            }
+            if ordered.len() == before {
+                return invalid("Page parents form a cycle".to_string());
+            }
+        }
+
+        // Content may only reference media and items imported before it
+        let mut available: HashSet<(StarterItemKind, &str)> = media
+            .iter()
+            .map(|key| (StarterItemKind::Media, *key))
+            .collect();
+        let check = |owner: &str,
+                     content: &str,
+                     featured: Option<&str>,
+                     available: &HashSet<(StarterItemKind, &str)>|
+         -> Result<(), StarterContentError> {
+            for caps in placeholder_regex().captures_iter(content) {
+                let kind = placeholder_kind(&caps[1]);
+                if !available.contains(&(kind, &caps[2])) {
+                    return Err(StarterContentError::Invalid(format!(
+                        "{} references {}, which is not imported before it",
+                        owner, &caps[0]
+                    )));
+                }
+            }
+            if let Some(key) = featured {
+                if !media.contains(key) {
+                    return Err(StarterContentError::Invalid(format!(
+                        "{} has unknown featured image {}",
+                        owner, key
+                    )));
+                }
+            }
+            Ok(())
+        };
+        for page in &ordered {
+            check(
+                &page.id,
+                &page.content,
+                page.featured_image.as_deref(),
+                &available,
+            )?;
+            available.insert((StarterItemKind::Page, &page.id));
+        }
+        for post in &self.posts {
+            check(
+                &post.id,
+                &post.content,
+                post.featured_image.as_deref(),
+                &available,
+            )?;
+            available.insert((StarterItemKind::Post, &post.id));
+        }
+
+        for (location, menu) in &self.nav_menus {
+            let mut items: Vec<&StarterMenuItem> = menu.items.iter().collect();
+            while let Some(item) = items.pop() {
+                let target = match &item.item_type {
+                    MenuItemType::Page { page_id } => Some((StarterItemKind::Page, page_id)),
+                    MenuItemType::Post { post_id } => Some((StarterItemKind::Post, post_id)),
+                    _ => None,
+                };
+                if let Some((kind, key)) = target {
+                    if !available.contains(&(kind, key.as_str())) {
+                        return invalid(format!(
+                            "Menu {} links to unknown {} {}",
+                            location,
+                            kind.as_str(),
+                            key
+                        ));
+                    }
+                }
+                items.extend(item.children.iter());
+            }
+        }
+
+        for option in PAGE_KEY_OPTIONS {
+            if let Some(key) = self.options.get(*option).and_then(|v| v.as_str()) {
+                if !by_key.contains_key(key) {
+                    return invalid(format!("Option {} names unknown page {}", option, key));
+                }
+            }
+        }
+
+        Ok(ordered)
+    }
+
+    async fn import(
+        &self,
+        services: &dyn StarterContentServices,
+        theme_path: &Path,
+        pages: &[&StarterPage],
+        result: &mut StarterContentResult,
+        log: &mut ImportLog,
+    ) -> Result<(), StarterContentError> {
+        for attachment in &self.attachments {
+            let file = theme_path.join(&attachment.file);
+            if !file.is_file() {
+                return Err(StarterContentError::CreationFailed(format!(
+                    "Attachment {}: file not found: {}",
+                    attachment.id,
+                    file.display()
+                )));
+            }
+            import_item(
+                services,
+                StarterItemKind::Media,
+                &attachment.id,
+                result,
+                log,
+                || services.import_media(attachment, &file),
+            )
+            .await?;
+        }
+
+        for page in pages {
+            let mut page = (*page).clone();
+            page.content = resolve_placeholders(&page.content, result);
+            page.featured_image = resolve(result, StarterItemKind::Media, page.featured_image);
+            page.parent = resolve(result, StarterItemKind::Page, page.parent);
+            import_item(
+                services,
+                StarterItemKind::Page,
+                &page.id,
+                result,
+                log,
+                || services.create_page(&page),
+            )
+            .await?;
+        }
+
+        for post in &self.posts {
+            let mut post = post.clone();
+            post.content = resolve_placeholders(&post.content, result);
+            post.featured_image = resolve(result, StarterItemKind::Media, post.featured_image);
+            import_item(
+                services,
+                StarterItemKind::Post,
+                &post.id,
+                result,
+                log,
+                || services.create_post(&post),
+            )
+            .await?;
+        }
+
+        let mut locations: Vec<&String> = self.nav_menus.keys().collect();
+        locations.sort();
+        for location in locations {
+            let mut menu = self.nav_menus[location].clone();
+            resolve_menu_items(&mut menu.items, result);
+            import_item(
+                services,
+                StarterItemKind::Menu,
+                location,
+                result,
+                log,
+                || services.create_menu(location, &menu),
+            )
+            .await?;
+        }
+
+        let mut options: Vec<(&String, &serde_json::Value)> = self.options.iter().collect();
+        options.sort_by(|a, b| a.0.cmp(b.0));
+        for (key, value) in options {
+            let value = match value.as_str() {
+                Some(page) if PAGE_KEY_OPTIONS.contains(&key.as_str()) => {
+                    serde_json::Value::String(result.pages[page].clone())
+                }
+                _ => value.clone(),
+            };
+            let previous = services.get_option(key).await?;
+            if previous.as_ref() == Some(&value) {
+                continue;
+            }
+            services.set_option(key, value).await?;
+            log.options.push((key.clone(), previous));
+        }
+        result.front_page = self
+            .options
+            .get("page_on_front")
+            .and_then(|v| v.as_str())
+            .map(|key| result.pages[key].clone());
+
+        Ok(())
+    }
+}
+
+/// Reuse the item imported earlier under `key`, or create it
+async fn import_item<'a, F, Fut>(
+    services: &dyn StarterContentServices,
+    kind: StarterItemKind,
+    key: &str,
+    result: &mut StarterContentResult,
+    log: &mut ImportLog,
+    create: F,
+) -> Result<(), StarterContentError>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<String, StarterContentError>> + 'a,
+{
+    let id = match services.find_imported(kind, key).await? {
+        Some(id) => {
+            result.existing += 1;
+            id
+        }
+        None => {
+            let id = create().await.map_err(|e| {
+                StarterContentError::CreationFailed(format!("{} {}: {}", kind.as_str(), key, e))
+            })?;
+            log.created.push((kind, id.clone()));
+            result.created += 1;
+            id
+        }
+    };
+    result.ids_mut(kind).insert(key.to_string(), id);
+    Ok(())
+}
+
+/// Rewrite a starter key to the imported id; validation guarantees it exists
+fn resolve(
+    result: &StarterContentResult,
+    kind: StarterItemKind,
+    key: Option<String>,
+) -> Option<String> {
+    key.and_then(|key| result.ids(kind).get(&key).cloned())
+}
+
+fn resolve_placeholders(content: &str, result: &StarterContentResult) -> String {
+    placeholder_regex()
+        .replace_all(content, |caps: &regex::Captures| {
+            result
+                .ids(placeholder_kind(&caps[1]))
+                .get(&caps[2])
+                .cloned()
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+fn resolve_menu_items(items: &mut [StarterMenuItem], result: &StarterContentResult) {
+    for item in items {
+        match &mut item.item_type {
+            MenuItemType::Page { page_id } => {
+                if let Some(id) = result.pages.get(page_id.as_str()) {
+                    *page_id = id.clone();
+                }
+            }
+            MenuItemType::Post { post_id } => {
+                if let Some(id) = result.posts.get(post_id.as_str()) {
+                    *post_id = id.clone();
+                }
+            }
+            _ => {}
+        }
+        resolve_menu_items(&mut item.children, result);
+    }
+}
+
+/// Whether a bundled file path stays inside the theme directory
+fn is_bundled_path(file: &str) -> bool {
+    let path = Path::new(file);
+    !file.is_empty()
+        && path
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)))
+}
+
 /// Starter content installer
 pub struct StarterContentInstaller {
     content: StarterContent,
@@ -569,4 +1081,233 @@ mod tests {
         assert_eq!(content.pages.len(), 1);
         assert!(content.theme_mods.contains_key("logo"));
     }
+
+    /// In-memory site recording what the import does
+    #[derive(Default)]
+    struct MemoryServices {
+        items: std::sync::Mutex<Vec<(StarterItemKind, String, String, String)>>,
+        options: std::sync::Mutex<HashMap<String, serde_json::Value>>,
+        /// Post or option key whose write fails
+        fail_on: Option<String>,
+    }
+
+    impl MemoryServices {
+        fn create(&self, kind: StarterItemKind, key: &str, body: String) -> String {
+            let mut items = self.items.lock().unwrap();
+            let id = format!("{}-{}", kind.as_str(), items.len() + 1);
+            items.push((kind, key.to_string(), id.clone(), body));
+            id
+        }
+
+        fn body(&self, kind: StarterItemKind, key: &str) -> String {
+            let items = self.items.lock().unwrap();
+            let item = items.iter().find(|i| i.0 == kind && i.1 == key).unwrap();
+            item.3.clone()
+        }
+    }
+
+    #[async_trait]
+    impl StarterContentServices for MemoryServices {
+        async fn find_imported(
+            &self,
+            kind: StarterItemKind,
+            key: &str,
+        ) -> Result<Option<String>, StarterContentError> {
+            let items = self.items.lock().unwrap();
+            Ok(items
+                .iter()
+                .find(|i| i.0 == kind && i.1 == key)
+                .map(|i| i.2.clone()))
+        }
+
+        async fn import_media(
+            &self,
+            attachment: &StarterAttachment,
+            file: &Path,
+        ) -> Result<String, StarterContentError> {
+            let body = std::fs::read_to_string(file)?;
+            Ok(self.create(StarterItemKind::Media, &attachment.id, body))
+        }
+
+        async fn create_page(&self, page: &StarterPage) -> Result<String, StarterContentError> {
+            let body = format!("{:?}|{}", page.parent, page.content);
+            Ok(self.create(StarterItemKind::Page, &page.id, body))
+        }
+
+        async fn create_post(&self, post: &StarterPost) -> Result<String, StarterContentError> {
+            if self.fail_on.as_deref() == Some(post.id.as_str()) {
+                return Err(StarterContentError::CreationFailed("disk full".into()));
+            }
+            let body = format!("{:?}|{}", post.featured_image, post.content);
+            Ok(self.create(StarterItemKind::Post, &post.id, body))
+        }
+
+        async fn create_menu(
+            &self,
+            location: &str,
+            menu: &StarterNavMenu,
+        ) -> Result<String, StarterContentError> {
+            let body = serde_json::to_string(&menu.items).unwrap();
+            Ok(self.create(StarterItemKind::Menu, location, body))
+        }
+
+        async fn get_option(
+            &self,
+            key: &str,
+        ) -> Result<Option<serde_json::Value>, StarterContentError> {
+            Ok(self.options.lock().unwrap().get(key).cloned())
+        }
+
+        async fn set_option(
+            &self,
+            key: &str,
+            value: serde_json::Value,
+        ) -> Result<(), StarterContentError> {
+            if self.fail_on.as_deref() == Some(key) {
+                return Err(StarterContentError::CreationFailed("disk full".into()));
+            }
+            let mut options = self.options.lock().unwrap();
+            if value.is_null() {
+                options.remove(key);
+            } else {
+                options.insert(key.to_string(), value);
+            }
+            Ok(())
+        }
+
+        async fn remove(&self, kind: StarterItemKind, id: &str) -> Result<(), StarterContentError> {
+            self.items
+                .lock()
+                .unwrap()
+                .retain(|i| !(i.0 == kind && i.2 == id));
+            Ok(())
+        }
+    }
+
+    fn sample_content() -> (StarterContent, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("images")).unwrap();
+        std::fs::write(dir.path().join("images/hero.jpg"), "jpeg").unwrap();
+
+        let page = |id: &str, parent: Option<&str>, content: &str| StarterPage {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: content.to_string(),
+            template: None,
+            parent: parent.map(str::to_string),
+            menu_order: 0,
+            featured_image: None,
+            meta: HashMap::new(),
+        };
+
+        let mut content = StarterContent::new();
+        content
+            .add_attachment(StarterAttachment {
+                id: "hero".to_string(),
+                file: "images/hero.jpg".to_string(),
+                title: None,
+                alt: None,
+                caption: None,
+            })
+            // Child listed before its parent
+            .add_page(page("team", Some("about"), "Meet the team"))
+            .add_page(page("about", None, "<img src=\"{{media:hero}}\">"))
+            .add_post(StarterPost {
+                id: "hello".to_string(),
+                title: "Hello".to_string(),
+                content: "See {{ page:about }}".to_string(),
+                featured_image: Some("hero".to_string()),
+                ..Default::default()
+            })
+            .add_nav_menu(
+                "primary",
+                StarterNavMenu {
+                    name: "Primary".to_string(),
+                    items: vec![StarterMenuItem {
+                        title: "About".to_string(),
+                        item_type: MenuItemType::Page {
+                            page_id: "about".to_string(),
+                        },
+                        children: Vec::new(),
+                    }],
+                },
+            )
+            .set_option("page_on_front", serde_json::json!("about"));
+        (content, dir)
+    }
+
+    #[tokio::test]
+    async fn test_apply_resolves_references_and_is_idempotent() {
+        let (content, dir) = sample_content();
+        let services = MemoryServices::default();
+
+        let result = content.apply(&services, dir.path()).await.unwrap();
+        assert_eq!(result.created, 5);
+        assert_eq!(result.existing, 0);
+        let hero = &result.media["hero"];
+        let about = &result.pages["about"];
+        assert_eq!(result.front_page.as_ref(), Some(about));
+
+        assert_eq!(
+            services.body(StarterItemKind::Page, "about"),
+            format!("None|<img src=\"{}\">", hero)
+        );
+        assert_eq!(
+            services.body(StarterItemKind::Page, "team"),
+            format!("Some({:?})|Meet the team", about)
+        );
+        assert_eq!(
+            services.body(StarterItemKind::Post, "hello"),
+            format!("Some({:?})|See {}", hero, about)
+        );
+        assert!(services
+            .body(StarterItemKind::Menu, "primary")
+            .contains(about.as_str()));
+        assert_eq!(
+            services.options.lock().unwrap()["page_on_front"],
+            serde_json::json!(about)
+        );
+
+        // A second run finds everything and creates nothing
+        let again = content.apply(&services, dir.path()).await.unwrap();
+        assert_eq!(again.created, 0);
+        assert_eq!(again.existing, 5);
+        assert_eq!(again.pages, result.pages);
+        assert_eq!(services.items.lock().unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_apply_rolls_back_on_failure() {
+        let (mut content, dir) = sample_content();
+        content.set_option("blogname", serde_json::json!("Starter"));
+        let services = MemoryServices {
+            // Fails after blogname has been changed
+            fail_on: Some("page_on_front".to_string()),
+            ..Default::default()
+        };
+        services
+            .options
+            .lock()
+            .unwrap()
+            .insert("blogname".to_string(), serde_json::json!("Mine"));
+
+        let err = content.apply(&services, dir.path()).await.unwrap_err();
+        assert!(matches!(err, StarterContentError::CreationFailed(_)));
+        assert!(services.items.lock().unwrap().is_empty());
+        assert_eq!(
+            services.options.lock().unwrap()["blogname"],
+            serde_json::json!("Mine")
+        );
+
+        // Bad references are rejected before anything is created
+        let services = MemoryServices::default();
+        content.posts[0].content = "{{media:missing}}".to_string();
+        let err = content.apply(&services, dir.path()).await.unwrap_err();
+        assert!(matches!(err, StarterContentError::Invalid(_)));
+        content.posts[0].content.clear();
+        content.attachments[0].file = "../secret".to_string();
+        let err = content.apply(&services, dir.path()).await.unwrap_err();
+        assert!(matches!(err, StarterContentError::Invalid(_)));
+        assert!(services.items.lock().unwrap().is_empty());
+    }
 }
@@ -4,11 +4,18 @@
 
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::sync::Arc;
 use thiserror::Error;
 use tokio::fs;
+use uuid::Uuid;
+
+/// Cookie holding the visitor's style variation and color mode preference
+pub const PREFERENCE_COOKIE: &str = "rp_style_pref";
+
+/// Lifetime of the preference cookie (one year)
+const PREFERENCE_COOKIE_MAX_AGE: u64 = 60 * 60 * 24 * 365;
 
 /// Variation errors
 #[derive(Debug, Error)]
@@ -140,6 +147,314 @@ impl VariationManager {
 
         Some(css)
     }
+
+    /// Resolve the variation and color mode to render for a request.
+    ///
+    /// The user's requested variation is only honoured if it is registered
+    /// for this theme; otherwise the active variation is used and the
+    /// rejected slug is reported on the result. A missing color mode falls
+    /// back to the theme's default, and `system_pref` (e.g. from the
+    /// `Sec-CH-Prefers-Color-Scheme` client hint) is used to pick the
+    /// effective mode when the preference is `auto`.
+    pub fn resolve_for_request(
+        &self,
+        theme: &DarkModeConfig,
+        user_pref: Option<&VariationPreference>,
+        system_pref: Option<ColorMode>,
+    ) -> ResolvedVariation {
+        let requested = user_pref.and_then(|p| p.variation.as_deref());
+
+        let (variation, rejected_variation) = match requested {
+            Some(slug) => match self.get(slug) {
+                Some(variation) => (Some(variation), None),
+                None => (self.get_active(), Some(slug.to_string())),
+            },
+            None => (self.get_active(), None),
+        };
+
+        let preference = if theme.enabled {
+            user_pref
+                .and_then(|p| p.color_mode)
+                .unwrap_or(theme.default_mode)
+        } else {
+            ColorMode::Light
+        };
+
+        let color_mode = match preference {
+            ColorMode::Auto if theme.respect_system => match system_pref {
+                Some(ColorMode::Dark) => ColorMode::Dark,
+                Some(ColorMode::Light) => ColorMode::Light,
+                _ => ColorMode::Auto,
+            },
+            ColorMode::Auto => ColorMode::Light,
+            mode => mode,
+        };
+
+        let properties = variation
+            .as_ref()
+            .map(variation_custom_properties)
+            .unwrap_or_default();
+
+        let (light_colors, dark_colors) = if theme.enabled {
+            (
+                theme.light_colors.clone().into_iter().collect(),
+                theme.dark_colors.clone().into_iter().collect(),
+            )
+        } else {
+            (BTreeMap::new(), BTreeMap::new())
+        };
+
+        ResolvedVariation {
+            variation,
+            rejected_variation,
+            preference,
+            color_mode,
+            media_fallback: preference == ColorMode::Auto && theme.respect_system && theme.enabled,
+            properties,
+            light_colors,
+            dark_colors,
+        }
+    }
+}
+
+/// Build the CSS custom properties contributed by a style variation.
+///
+/// Palette entries map to `--wp--preset--color--{slug}`, and top level
+/// style colors map to `--wp--style--color--{name}`.
+fn variation_custom_properties(variation: &StyleVariation) -> BTreeMap<String, String> {
+    let mut properties = BTreeMap::new();
+
+    if let Some(palette) = variation
+        .settings
+        .pointer("/color/palette")
+        .and_then(|v| v.as_array())
+    {
+        for entry in palette {
+            if let (Some(slug), Some(color)) = (
+                entry.get("slug").and_then(|v| v.as_str()),
+                entry.get("color").and_then(|v| v.as_str()),
+            ) {
+                properties.insert(format!("--wp--preset--color--{}", slug), color.to_string());
+            }
+        }
+    }
+
+    if let Some(colors) = variation.styles.get("color").and_then(|v| v.as_object()) {
+        for (name, value) in colors {
+            if let Some(value) = value.as_str() {
+                properties.insert(format!("--wp--style--color--{}", name), value.to_string());
+            }
+        }
+    }
+
+    properties
+}
+
+/// A visitor's style variation and color mode choice.
+///
+/// Persisted in the [`PREFERENCE_COOKIE`] for anonymous visitors and in a
+/// [`PreferenceStore`] for signed-in accounts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VariationPreference {
+    /// Requested variation slug
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variation: Option<String>,
+    /// Requested color mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color_mode: Option<ColorMode>,
+}
+
+impl VariationPreference {
+    /// Parse a preference cookie value (`<mode>|<variation>`).
+    ///
+    /// Unknown modes and empty parts are ignored rather than rejected so a
+    /// stale cookie never breaks rendering.
+    pub fn from_cookie(value: &str) -> Self {
+        let (mode, variation) = value.split_once('|').unwrap_or((value, ""));
+
+        let variation = variation.trim();
+        let variation = if !variation.is_empty()
+            && variation
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            Some(variation.to_string())
+        } else {
+            None
+        };
+
+        Self {
+            variation,
+            color_mode: ColorMode::parse(mode.trim()),
+        }
+    }
+
+    /// Serialize into a preference cookie value
+    pub fn to_cookie(&self) -> String {
+        format!(
+            "{}|{}",
+            self.color_mode.map(|m| m.as_str()).unwrap_or(""),
+            self.variation.as_deref().unwrap_or("")
+        )
+    }
+
+    /// Build a `Set-Cookie` header value persisting this preference
+    pub fn set_cookie_header(&self) -> String {
+        format!(
+            "{}={}; Path=/; Max-Age={}; SameSite=Lax",
+            PREFERENCE_COOKIE,
+            self.to_cookie(),
+            PREFERENCE_COOKIE_MAX_AGE
+        )
+    }
+
+    /// Fill unset fields from another preference
+    pub fn or(self, fallback: &VariationPreference) -> Self {
+        Self {
+            variation: self.variation.or_else(|| fallback.variation.clone()),
+            color_mode: self.color_mode.or(fallback.color_mode),
+        }
+    }
+}
+
+/// Server-side, per-account preference storage
+pub struct PreferenceStore {
+    preferences: Arc<RwLock<HashMap<Uuid, VariationPreference>>>,
+}
+
+impl PreferenceStore {
+    pub fn new() -> Self {
+        Self {
+            preferences: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Get an account's stored preference
+    pub fn get(&self, user_id: Uuid) -> Option<VariationPreference> {
+        self.preferences.read().get(&user_id).cloned()
+    }
+
+    /// Store an account's preference
+    pub fn set(&self, user_id: Uuid, preference: VariationPreference) {
+        self.preferences.write().insert(user_id, preference);
+    }
+
+    /// Remove an account's preference
+    pub fn remove(&self, user_id: Uuid) {
+        self.preferences.write().remove(&user_id);
+    }
+
+    /// Effective preference for a request: the account's stored choice
+    /// wins, with the cookie filling any gaps.
+    pub fn for_request(&self, user_id: Option<Uuid>, cookie: Option<&str>) -> VariationPreference {
+        let cookie = cookie
+            .map(VariationPreference::from_cookie)
+            .unwrap_or_default();
+
+        match user_id.and_then(|id| self.get(id)) {
+            Some(stored) => stored.or(&cookie),
+            None => cookie,
+        }
+    }
+}
+
+impl Default for PreferenceStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The variation and color mode chosen for a single request
+#[derive(Debug, Clone)]
+pub struct ResolvedVariation {
+    /// Variation to render, if any
+    pub variation: Option<StyleVariation>,
+    /// Requested variation slug that does not exist for the theme
+    pub rejected_variation: Option<String>,
+    /// Color mode preference (may be `Auto`)
+    pub preference: ColorMode,
+    /// Effective color mode; `Auto` when it can only be decided client-side
+    pub color_mode: ColorMode,
+    /// Whether a `prefers-color-scheme` fallback must be emitted
+    pub media_fallback: bool,
+    properties: BTreeMap<String, String>,
+    light_colors: BTreeMap<String, String>,
+    dark_colors: BTreeMap<String, String>,
+}
+
+impl ResolvedVariation {
+    /// Slug of the resolved variation
+    pub fn variation_slug(&self) -> Option<&str> {
+        self.variation.as_ref().map(|v| v.slug.as_str())
+    }
+
+    /// Generate the CSS custom properties for this request
+    pub fn generate_css(&self) -> String {
+        let mut css = String::new();
+
+        let base = match self.preference {
+            ColorMode::Dark => &self.dark_colors,
+            _ => &self.light_colors,
+        };
+
+        css.push_str(":root {\n");
+        for (prop, value) in self.properties.iter().chain(base.iter()) {
+            css.push_str(&format!("  {}: {};\n", prop, value));
+        }
+        css.push_str("}\n");
+
+        if self.media_fallback && !self.dark_colors.is_empty() {
+            css.push_str("@media (prefers-color-scheme: dark) {\n");
+            css.push_str("  :root:not([data-theme=\"light\"]) {\n");
+            for (prop, value) in &self.dark_colors {
+                css.push_str(&format!("    {}: {};\n", prop, value));
+            }
+            css.push_str("  }\n");
+            css.push_str("}\n");
+        }
+
+        css
+    }
+
+    /// Attributes for the `<html>` element.
+    ///
+    /// An explicit light/dark choice is rendered server-side so the first
+    /// paint is already correct; `auto` is left to the media query.
+    pub fn html_attributes(&self) -> String {
+        let mut attrs = Vec::new();
+
+        match self.preference {
+            ColorMode::Light | ColorMode::Dark => {
+                attrs.push(format!("data-theme=\"{}\"", self.preference.as_str()))
+            }
+            ColorMode::Auto => {}
+        }
+
+        if let Some(slug) = self.variation_slug() {
+            attrs.push(format!("data-variation=\"{}\"", slug));
+        }
+
+        attrs.join(" ")
+    }
+
+    /// Value for the `color-scheme` meta tag
+    pub fn color_scheme(&self) -> &'static str {
+        match self.color_mode {
+            ColorMode::Light => "light",
+            ColorMode::Dark => "dark",
+            ColorMode::Auto => "light dark",
+        }
+    }
+
+    /// Markup to inline in `<head>` before any stylesheet, avoiding a
+    /// flash of the wrong theme on first paint.
+    pub fn inline_head(&self) -> String {
+        format!(
+            "<meta name=\"color-scheme\" content=\"{}\">\n<style id=\"rp-variation\">\n{}</style>\n",
+            self.color_scheme(),
+            self.generate_css()
+        )
+    }
 }
 
 /// Dark mode support
@@ -169,6 +484,26 @@ pub enum ColorMode {
     Auto,
 }
 
+impl ColorMode {
+    /// Parse a mode name (`light`, `dark`, `auto`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "light" => Some(Self::Light),
+            "dark" => Some(Self::Dark),
+            "auto" => Some(Self::Auto),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+            Self::Auto => "auto",
+        }
+    }
+}
+
 impl Default for ColorMode {
     fn default() -> Self {
         Self::Auto
@@ -553,6 +888,95 @@ mod tests {
         assert!(css.contains("#1a1a1a"));
     }
 
+    fn variation(slug: &str, background: &str) -> StyleVariation {
+        StyleVariation {
+            slug: slug.to_string(),
+            title: slug.to_string(),
+            settings: serde_json::json!({
+                "color": { "palette": [{ "slug": "primary", "color": background }] }
+            }),
+            styles: serde_json::json!({}),
+            preview: None,
+        }
+    }
+
+    fn dark_mode() -> DarkModeConfig {
+        let mut config = DarkModeConfig::default();
+        config
+            .dark_colors
+            .insert("--background".to_string(), "#111111".to_string());
+        config
+            .light_colors
+            .insert("--background".to_string(), "#ffffff".to_string());
+        config
+    }
+
+    #[test]
+    fn test_preference_cookie_round_trip() {
+        let pref = VariationPreference {
+            variation: Some("midnight".to_string()),
+            color_mode: Some(ColorMode::Dark),
+        };
+
+        let parsed = VariationPreference::from_cookie(&pref.to_cookie());
+        assert_eq!(parsed, pref);
+
+        let garbage = VariationPreference::from_cookie("neon|<script>");
+        assert_eq!(garbage, VariationPreference::default());
+    }
+
+    #[test]
+    fn test_account_preference_overrides_cookie() {
+        let store = PreferenceStore::new();
+        let user = Uuid::new_v4();
+        store.set(
+            user,
+            VariationPreference {
+                variation: None,
+                color_mode: Some(ColorMode::Light),
+            },
+        );
+
+        let pref = store.for_request(Some(user), Some("dark|midnight"));
+        assert_eq!(pref.color_mode, Some(ColorMode::Light));
+        assert_eq!(pref.variation.as_deref(), Some("midnight"));
+    }
+
+    #[test]
+    fn test_resolve_rejects_unknown_variation() {
+        let manager = VariationManager::new(PathBuf::from("themes/test"));
+        manager.register(variation("default", "#000000"));
+        manager.set_active("default").unwrap();
+
+        let pref = VariationPreference::from_cookie("dark|missing");
+        let resolved = manager.resolve_for_request(&dark_mode(), Some(&pref), None);
+
+        assert_eq!(resolved.variation_slug(), Some("default"));
+        assert_eq!(resolved.rejected_variation.as_deref(), Some("missing"));
+        assert!(resolved.html_attributes().contains("data-theme=\"dark\""));
+        assert!(resolved.generate_css().contains("--background: #111111"));
+    }
+
+    #[test]
+    fn test_resolve_auto_emits_media_fallback() {
+        let manager = VariationManager::new(PathBuf::from("themes/test"));
+        manager.register(variation("ocean", "#0055aa"));
+
+        let pref = VariationPreference::from_cookie("auto|ocean");
+        let resolved = manager.resolve_for_request(&dark_mode(), Some(&pref), None);
+
+        assert_eq!(resolved.color_mode, ColorMode::Auto);
+        assert!(!resolved.html_attributes().contains("data-theme"));
+
+        let head = resolved.inline_head();
+        assert!(head.contains("--wp--preset--color--primary: #0055aa"));
+        assert!(head.contains("prefers-color-scheme: dark"));
+        assert!(head.contains("light dark"));
+
+        let hinted = manager.resolve_for_request(&dark_mode(), Some(&pref), Some(ColorMode::Dark));
+        assert_eq!(hinted.color_mode, ColorMode::Dark);
+    }
+
     #[test]
     fn test_template_lock() {
         let manager = TemplateLockManager::new();
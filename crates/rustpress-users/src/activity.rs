@@ -8,7 +8,12 @@
 //! - Activity streams
 //! - User session tracking
 //! - Login history
+//! - Suspicious login detection
 
+use crate::audit::{AuditAction, AuditEntry, AuditManager, AuditSeverity};
+use crate::notifications::{
+    NotificationPreferencesManager, NotificationType, OutgoingNotification,
+};
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -400,6 +405,12 @@ pub struct ActivityManager {
     activities: Vec<Activity>,
     retention_days: u32,
     enabled_categories: Vec<ActivityCategory>,
+    login_history: LoginHistory,
+    login_detection: LoginDetectionSettings,
+    suspicious_logins: Vec<SuspiciousLogin>,
+    notification_preferences: NotificationPreferencesManager,
+    pending_notifications: Vec<OutgoingNotification>,
+    audit: AuditManager,
 }
 
 impl Default for ActivityManager {
@@ -414,6 +425,12 @@ impl Default for ActivityManager {
                 ActivityCategory::Settings,
                 ActivityCategory::System,
             ],
+            login_history: LoginHistory::new(),
+            login_detection: LoginDetectionSettings::default(),
+            suspicious_logins: Vec::new(),
+            notification_preferences: NotificationPreferencesManager::new(),
+            pending_notifications: Vec::new(),
+            audit: AuditManager::new(),
         }
     }
 }
@@ -523,6 +540,205 @@ impl ActivityManager {
         let cutoff = Utc::now() - Duration::days(self.retention_days as i64);
         self.activities.retain(|a| a.created_at > cutoff);
     }
+
+    pub fn with_login_detection(mut self, settings: LoginDetectionSettings) -> Self {
+        self.login_detection = settings;
+        self
+    }
+
+    pub fn with_notification_preferences(
+        mut self,
+        preferences: NotificationPreferencesManager,
+    ) -> Self {
+        self.notification_preferences = preferences;
+        self
+    }
+
+    /// Record a login attempt and check it for signs of account takeover
+    ///
+    /// Successful logins are compared against the user's earlier logins for a
+    /// new country or device, impossible travel, and a burst of failures
+    /// right before the success. When something is found, the most serious
+    /// finding is recorded, an audit entry is written, and a security
+    /// notification is queued if the user's preferences allow it. The record
+    /// is added to the login history either way.
+    pub fn evaluate_login(&mut self, record: LoginRecord) -> Option<SuspiciousActivity> {
+        let finding = if record.success {
+            self.detect_suspicious_login(&record)
+        } else {
+            None
+        };
+
+        if let Some(ref activity) = finding {
+            self.suspicious_logins.push(SuspiciousLogin {
+                id: Uuid::new_v4(),
+                user_id: record.user_id,
+                login_id: record.id,
+                activity: activity.clone(),
+                ip_address: record.ip_address.clone(),
+                detected_at: Utc::now(),
+            });
+
+            self.audit.log(
+                AuditEntry::new(AuditAction::SuspiciousActivity)
+                    .user(record.user_id, None)
+                    .request_info(&record.ip_address, Some(&record.user_agent))
+                    .description(&activity.message())
+                    .metadata(serde_json::to_value(activity).unwrap_or_default())
+                    .severity(activity.severity()),
+            );
+
+            if let Some(notification) = self.notification_preferences.prepare(
+                record.user_id,
+                activity.notification_type(),
+                "Unusual sign-in to your account",
+                &activity.message(),
+            ) {
+                self.pending_notifications.push(notification);
+            }
+        }
+
+        self.login_history.record_login(record);
+        finding
+    }
+
+    fn detect_suspicious_login(&self, record: &LoginRecord) -> Option<SuspiciousActivity> {
+        let settings = &self.login_detection;
+        let history = self.login_history.get_history(record.user_id);
+
+        // Consecutive failures immediately before this success
+        let burst_start = record.login_at - settings.failure_burst_window;
+        let failures = history
+            .iter()
+            .take_while(|r| !r.success)
+            .filter(|r| r.login_at >= burst_start)
+            .count();
+
+        let previous: Vec<&LoginRecord> = history.into_iter().filter(|r| r.success).collect();
+
+        // Impossible travel needs coordinates on both ends; skip it otherwise
+        if let Some(last) = previous.first() {
+            if let (Some(from), Some(to)) = (&last.location, &record.location) {
+                if let Some(distance_km) = from.distance_km(to) {
+                    let hours =
+                        (record.login_at - last.login_at).num_seconds().abs() as f64 / 3600.0;
+                    // Treat near-simultaneous logins as at least a minute apart
+                    let speed = distance_km / hours.max(1.0 / 60.0);
+                    if distance_km >= settings.min_travel_distance_km
+                        && speed > settings.max_travel_speed_kmh
+                    {
+                        return Some(SuspiciousActivity::ImpossibleTravel {
+                            from: from.label(),
+                            to: to.label(),
+                            distance_km: distance_km.round(),
+                            hours: (hours * 100.0).round() / 100.0,
+                        });
+                    }
+                }
+            }
+        }
+
+        if failures >= settings.failure_burst_threshold {
+            return Some(SuspiciousActivity::FailedAttemptsThenSuccess { failures });
+        }
+
+        // The very first login has nothing to compare against
+        if previous.is_empty() {
+            return None;
+        }
+
+        if let Some(country) = record.location.as_ref().and_then(|l| l.country_key()) {
+            let known: Vec<String> = previous
+                .iter()
+                .filter_map(|r| r.location.as_ref().and_then(|l| l.country_key()))
+                .collect();
+            if !known.is_empty() && !known.contains(&country) {
+                return Some(SuspiciousActivity::NewLocation {
+                    location: record
+                        .location
+                        .as_ref()
+                        .map(|l| l.label())
+                        .unwrap_or(country),
+                });
+            }
+        }
+
+        if !previous
+            .iter()
+            .any(|r| r.device_info.same_device(&record.device_info))
+        {
+            return Some(SuspiciousActivity::NewDevice {
+                device: record.device_info.label(),
+            });
+        }
+
+        None
+    }
+
+    /// Login history backing suspicious login detection
+    pub fn login_history(&self) -> &LoginHistory {
+        &self.login_history
+    }
+
+    /// Suspicious logins detected for a user, newest first
+    pub fn suspicious_logins(&self, user_id: i64) -> Vec<&SuspiciousLogin> {
+        self.suspicious_logins
+            .iter()
+            .rev()
+            .filter(|s| s.user_id == user_id)
+            .collect()
+    }
+
+    /// Notification preferences consulted before alerting users
+    pub fn notification_preferences_mut(&mut self) -> &mut NotificationPreferencesManager {
+        &mut self.notification_preferences
+    }
+
+    /// Drain security notifications queued for delivery
+    pub fn take_notifications(&mut self) -> Vec<OutgoingNotification> {
+        std::mem::take(&mut self.pending_notifications)
+    }
+
+    /// Audit log receiving suspicious login events
+    pub fn audit(&self) -> &AuditManager {
+        &self.audit
+    }
+}
+
+/// Thresholds for suspicious login detection
+#[derive(Debug, Clone)]
+pub struct LoginDetectionSettings {
+    /// Failures right before a success that count as a burst
+    pub failure_burst_threshold: usize,
+    /// How far back failures count towards a burst
+    pub failure_burst_window: Duration,
+    /// Fastest plausible travel between two logins
+    pub max_travel_speed_kmh: f64,
+    /// Distances below this are never treated as travel (GeoIP jitter)
+    pub min_travel_distance_km: f64,
+}
+
+impl Default for LoginDetectionSettings {
+    fn default() -> Self {
+        Self {
+            failure_burst_threshold: 5,
+            failure_burst_window: Duration::minutes(15),
+            max_travel_speed_kmh: 900.0,
+            min_travel_distance_km: 500.0,
+        }
+    }
+}
+
+/// A login flagged by suspicious login detection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspiciousLogin {
+    pub id: Uuid,
+    pub user_id: i64,
+    /// The [`LoginRecord`] that triggered detection
+    pub login_id: Uuid,
+    pub activity: SuspiciousActivity,
+    pub ip_address: String,
+    pub detected_at: DateTime<Utc>,
 }
 
 // ============================================================================
@@ -553,6 +769,49 @@ pub struct GeoLocation {
     pub region: Option<String>,
     pub city: Option<String>,
     pub timezone: Option<String>,
+    #[serde(default)]
+    pub latitude: Option<f64>,
+    #[serde(default)]
+    pub longitude: Option<f64>,
+}
+
+impl GeoLocation {
+    /// Great-circle distance in kilometres, when both sides have coordinates
+    pub fn distance_km(&self, other: &GeoLocation) -> Option<f64> {
+        const EARTH_RADIUS_KM: f64 = 6371.0;
+
+        let (lat1, lon1) = (self.latitude?, self.longitude?);
+        let (lat2, lon2) = (other.latitude?, other.longitude?);
+
+        let d_lat = (lat2 - lat1).to_radians();
+        let d_lon = (lon2 - lon1).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2)
+            + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lon / 2.0).sin().powi(2);
+
+        Some(2.0 * EARTH_RADIUS_KM * a.sqrt().asin())
+    }
+
+    /// Human-readable place name, most specific first
+    pub fn label(&self) -> String {
+        let country = self.country.as_ref().or(self.country_code.as_ref());
+        let parts: Vec<&str> = [self.city.as_ref(), self.region.as_ref(), country]
+            .into_iter()
+            .flatten()
+            .map(|p| p.as_str())
+            .collect();
+        if parts.is_empty() {
+            "Unknown location".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+
+    fn country_key(&self) -> Option<String> {
+        self.country_code
+            .as_deref()
+            .or(self.country.as_deref())
+            .map(|c| c.to_uppercase())
+    }
 }
 
 /// Device information
@@ -627,6 +886,25 @@ impl DeviceInfo {
     }
 }
 
+impl DeviceInfo {
+    /// Whether two logins came from the same kind of device and browser
+    pub fn same_device(&self, other: &DeviceInfo) -> bool {
+        self.device_type == other.device_type
+            && self.browser == other.browser
+            && self.os == other.os
+    }
+
+    /// Short description, e.g. "Chrome on Windows"
+    pub fn label(&self) -> String {
+        match (&self.browser, &self.os) {
+            (Some(browser), Some(os)) => format!("{} on {}", browser, os),
+            (Some(browser), None) => browser.clone(),
+            (None, Some(os)) => os.clone(),
+            (None, None) => "Unknown device".to_string(),
+        }
+    }
+}
+
 impl LoginRecord {
     pub fn new(user_id: i64, ip: &str, user_agent: &str, success: bool) -> Self {
         Self {
@@ -790,11 +1068,29 @@ impl LoginHistory {
 /// Suspicious activity types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SuspiciousActivity {
-    NewIpAddress { ip: String },
+    NewIpAddress {
+        ip: String,
+    },
     NewDeviceType,
-    NewLocation { location: String },
-    MultipleFailedAttempts { count: usize },
+    NewLocation {
+        location: String,
+    },
+    MultipleFailedAttempts {
+        count: usize,
+    },
     RapidLocationChange,
+    NewDevice {
+        device: String,
+    },
+    ImpossibleTravel {
+        from: String,
+        to: String,
+        distance_km: f64,
+        hours: f64,
+    },
+    FailedAttemptsThenSuccess {
+        failures: usize,
+    },
 }
 
 impl SuspiciousActivity {
@@ -813,6 +1109,45 @@ impl SuspiciousActivity {
             Self::RapidLocationChange => {
                 "Login from geographically distant location in short time".to_string()
             }
+            Self::NewDevice { device } => format!("Login from a new device: {}", device),
+            Self::ImpossibleTravel {
+                from,
+                to,
+                distance_km,
+                hours,
+            } => format!(
+                "Login from {} {:.0} km away from {} only {:.1} hours after the previous login",
+                to, distance_km, from, hours
+            ),
+            Self::FailedAttemptsThenSuccess { failures } => {
+                format!("Successful login after {} failed attempts", failures)
+            }
+        }
+    }
+
+    /// Notification type used to alert the user. New devices and IPs use
+    /// the opt-out-able new device notification; everything else is a
+    /// security alert.
+    pub fn notification_type(&self) -> NotificationType {
+        match self {
+            Self::NewIpAddress { .. } | Self::NewDeviceType | Self::NewDevice { .. } => {
+                NotificationType::LoginFromNewDevice
+            }
+            _ => NotificationType::SecurityAlert,
+        }
+    }
+
+    pub fn severity(&self) -> AuditSeverity {
+        match self {
+            Self::NewIpAddress { .. } | Self::NewDeviceType | Self::NewDevice { .. } => {
+                AuditSeverity::Notice
+            }
+            Self::NewLocation { .. } | Self::MultipleFailedAttempts { .. } => {
+                AuditSeverity::Warning
+            }
+            Self::RapidLocationChange
+            | Self::ImpossibleTravel { .. }
+            | Self::FailedAttemptsThenSuccess { .. } => AuditSeverity::Error,
         }
     }
 }
@@ -820,6 +1155,7 @@ impl SuspiciousActivity {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::notifications::NotificationChannel;
 
     #[test]
     fn test_activity_creation() {
@@ -894,4 +1230,115 @@ mod tests {
         let failed = history.get_failed_attempts(1, Utc::now() - Duration::hours(1));
         assert_eq!(failed.len(), 1);
     }
+
+    const CHROME_WINDOWS: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) Chrome/120.0";
+
+    fn location(country_code: &str, coordinates: Option<(f64, f64)>) -> GeoLocation {
+        GeoLocation {
+            country: None,
+            country_code: Some(country_code.to_string()),
+            region: None,
+            city: None,
+            timezone: None,
+            latitude: coordinates.map(|c| c.0),
+            longitude: coordinates.map(|c| c.1),
+        }
+    }
+
+    fn login_at(minutes_ago: i64, success: bool) -> LoginRecord {
+        let mut record = LoginRecord::new(1, "203.0.113.7", CHROME_WINDOWS, success);
+        record.login_at = Utc::now() - Duration::minutes(minutes_ago);
+        record
+    }
+
+    #[test]
+    fn test_evaluate_login_impossible_travel() {
+        let mut manager = ActivityManager::new();
+        let london = location("GB", Some((51.5074, -0.1278)));
+        let sydney = location("AU", Some((-33.8688, 151.2093)));
+
+        assert!(manager
+            .evaluate_login(login_at(60, true).with_location(london))
+            .is_none());
+        let finding = manager
+            .evaluate_login(login_at(0, true).with_location(sydney))
+            .unwrap();
+
+        assert!(matches!(
+            finding,
+            SuspiciousActivity::ImpossibleTravel { distance_km, .. } if distance_km > 16_000.0
+        ));
+        assert_eq!(manager.suspicious_logins(1).len(), 1);
+        assert_eq!(manager.audit().recent(10).len(), 1);
+        assert_eq!(manager.take_notifications().len(), 1);
+        assert!(manager.take_notifications().is_empty());
+        assert_eq!(manager.login_history().get_history(1).len(), 2);
+    }
+
+    #[test]
+    fn test_evaluate_login_missing_coordinates_falls_back_to_country() {
+        let mut manager = ActivityManager::new();
+
+        manager.evaluate_login(login_at(60, true).with_location(location("GB", None)));
+        let finding = manager
+            .evaluate_login(login_at(0, true).with_location(location("AU", None)))
+            .unwrap();
+
+        assert!(matches!(finding, SuspiciousActivity::NewLocation { .. }));
+
+        // Nothing to compare against at all
+        let mut manager = ActivityManager::new();
+        manager.evaluate_login(login_at(60, true));
+        assert!(manager.evaluate_login(login_at(0, true)).is_none());
+    }
+
+    #[test]
+    fn test_evaluate_login_failure_burst() {
+        let mut manager = ActivityManager::new();
+
+        for minutes_ago in (1..=5).rev() {
+            assert!(manager
+                .evaluate_login(login_at(minutes_ago, false))
+                .is_none());
+        }
+        let finding = manager.evaluate_login(login_at(0, true)).unwrap();
+
+        assert!(matches!(
+            finding,
+            SuspiciousActivity::FailedAttemptsThenSuccess { failures: 5 }
+        ));
+        assert_eq!(manager.audit().recent(1)[0].severity, AuditSeverity::Error);
+    }
+
+    #[test]
+    fn test_evaluate_login_new_device_respects_opt_out() {
+        let mut manager = ActivityManager::new();
+        let prefs = manager.notification_preferences_mut().get_or_create(1);
+        prefs.update_preference(
+            NotificationType::LoginFromNewDevice,
+            NotificationChannel::InApp,
+            false,
+        );
+        prefs.update_preference(
+            NotificationType::LoginFromNewDevice,
+            NotificationChannel::Email,
+            false,
+        );
+
+        manager.evaluate_login(login_at(60, true));
+        let mut phone = LoginRecord::new(
+            1,
+            "203.0.113.7",
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0) Mobile Safari",
+            true,
+        );
+        phone.login_at = Utc::now();
+        let finding = manager.evaluate_login(phone).unwrap();
+
+        assert!(matches!(finding, SuspiciousActivity::NewDevice { .. }));
+        // Still recorded and audited, but the user is not notified
+        assert_eq!(manager.suspicious_logins(1).len(), 1);
+        assert_eq!(manager.audit().recent(10).len(), 1);
+        assert!(manager.take_notifications().is_empty());
+    }
 }
@@ -26,7 +26,7 @@
 //! - [`import_export`] - User import/export functionality
 //!
 //! ## Activity & Engagement
-//! - [`activity`] - User activity tracking and login history
+//! - [`activity`] - User activity tracking, login history, and suspicious login detection
 //! - [`notifications`] - User notification preferences
 //! - [`dashboard`] - Personalized user dashboard with widgets
 //!
@@ -82,8 +82,8 @@ pub mod gdpr;
 
 // Re-export commonly used types
 pub use activity::{
-    Activity, ActivityCategory, ActivityManager, ActivityQuery, ActivityType, LoginHistory,
-    LoginRecord, SuspiciousActivity,
+    Activity, ActivityCategory, ActivityManager, ActivityQuery, ActivityType, GeoLocation,
+    LoginDetectionSettings, LoginHistory, LoginRecord, SuspiciousActivity, SuspiciousLogin,
 };
 
 pub use avatar::{
@@ -117,7 +117,8 @@ pub use listing::{
 
 pub use notifications::{
     NotificationChannel, NotificationFrequency, NotificationPreference,
-    NotificationPreferencesManager, NotificationType, UserNotificationPreferences,
+    NotificationPreferencesManager, NotificationType, OutgoingNotification,
+    UserNotificationPreferences,
 };
 
 pub use ownership::{
@@ -417,6 +417,51 @@ impl NotificationPreferencesManager {
             .collect()
     }
 
+    /// Build a notification for a user on every channel their preferences
+    /// allow
+    ///
+    /// Users without stored preferences get the defaults. Returns `None` when
+    /// the user has switched this notification type off everywhere.
+    pub fn prepare(
+        &self,
+        user_id: i64,
+        notification_type: NotificationType,
+        subject: &str,
+        message: &str,
+    ) -> Option<OutgoingNotification> {
+        let defaults;
+        let prefs = match self.preferences.get(&user_id) {
+            Some(prefs) => prefs,
+            None => {
+                defaults = UserNotificationPreferences::new(user_id);
+                &defaults
+            }
+        };
+
+        let channels: Vec<NotificationChannel> = [
+            NotificationChannel::Email,
+            NotificationChannel::InApp,
+            NotificationChannel::Push,
+            NotificationChannel::Sms,
+        ]
+        .into_iter()
+        .filter(|channel| prefs.should_notify(notification_type, *channel))
+        .collect();
+
+        if channels.is_empty() {
+            return None;
+        }
+
+        Some(OutgoingNotification {
+            user_id,
+            notification_type,
+            channels,
+            subject: subject.to_string(),
+            message: message.to_string(),
+            created_at: Utc::now(),
+        })
+    }
+
     /// Get users needing digest
     pub fn get_digest_recipients(&self, frequency: NotificationFrequency) -> Vec<i64> {
         self.preferences
@@ -427,6 +472,17 @@ impl NotificationPreferencesManager {
     }
 }
 
+/// A notification resolved against a user's preferences, ready for delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutgoingNotification {
+    pub user_id: i64,
+    pub notification_type: NotificationType,
+    pub channels: Vec<NotificationChannel>,
+    pub subject: String,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
 /// Email unsubscribe token
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnsubscribeToken {
@@ -507,6 +563,37 @@ mod tests {
         assert!(prefs.is_quiet_hours());
     }
 
+    #[test]
+    fn test_prepare_respects_opt_out() {
+        let mut manager = NotificationPreferencesManager::new();
+        let prefs = manager.get_or_create(1);
+        prefs.email_verified = true;
+        prefs.update_preference(
+            NotificationType::LoginFromNewDevice,
+            NotificationChannel::Email,
+            false,
+        );
+        prefs.update_preference(
+            NotificationType::LoginFromNewDevice,
+            NotificationChannel::InApp,
+            false,
+        );
+
+        assert!(manager
+            .prepare(1, NotificationType::LoginFromNewDevice, "New device", "")
+            .is_none());
+
+        let alert = manager
+            .prepare(1, NotificationType::SecurityAlert, "Alert", "")
+            .unwrap();
+        assert!(alert.channels.contains(&NotificationChannel::Email));
+
+        // Users without stored preferences fall back to the defaults
+        assert!(manager
+            .prepare(2, NotificationType::SecurityAlert, "Alert", "")
+            .is_some());
+    }
+
     #[test]
     fn test_preferences_manager() {
         let mut manager = NotificationPreferencesManager::new();
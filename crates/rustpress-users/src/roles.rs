@@ -610,6 +610,14 @@ impl RoleManager {
             .insert(capability.name.clone(), capability);
     }
 
+    /// Remove a capability and revoke it from every role
+    pub fn unregister_capability(&mut self, name: &str) {
+        self.capabilities.remove(name);
+        for role in self.roles.values_mut().filter(|role| role.has_cap(name)) {
+            role.remove_cap(name);
+        }
+    }
+
     /// Register a role
     pub fn register_role(&mut self, role: Role) {
         self.roles.insert(role.name.clone(), role);
@@ -958,6 +966,20 @@ mod tests {
         assert!(!cloned.is_builtin);
     }
 
+    #[test]
+    fn test_unregister_capability() {
+        let mut manager = RoleManager::new();
+        manager.register_capability(Capability::new("edit_books", "Edit Books", "book"));
+        manager
+            .get_role_mut("editor")
+            .unwrap()
+            .add_cap("edit_books");
+
+        manager.unregister_capability("edit_books");
+        assert!(manager.get_capability("edit_books").is_none());
+        assert!(!manager.get_role("editor").unwrap().has_cap("edit_books"));
+    }
+
     #[test]
     fn test_cannot_delete_builtin() {
         let mut manager = RoleManager::new();
@@ -16,6 +16,7 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Session token type
@@ -272,6 +273,9 @@ impl Default for SessionSettings {
     }
 }
 
+/// Resolves a human-readable location (e.g. "Berlin, Germany") from an IP
+pub type LocationResolver = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
 /// Session manager
 pub struct SessionManager {
     /// Sessions by ID
@@ -285,6 +289,9 @@ pub struct SessionManager {
 
     /// Settings
     settings: SessionSettings,
+
+    /// Location lookup for new sessions
+    location_resolver: Option<LocationResolver>,
 }
 
 impl Default for SessionManager {
@@ -294,6 +301,7 @@ impl Default for SessionManager {
             user_sessions: HashMap::new(),
             sessions_by_token: HashMap::new(),
             settings: SessionSettings::default(),
+            location_resolver: None,
         }
     }
 }
@@ -308,6 +316,12 @@ impl SessionManager {
         self
     }
 
+    /// Resolve each new session's location from its IP address
+    pub fn with_location_resolver(mut self, resolver: LocationResolver) -> Self {
+        self.location_resolver = Some(resolver);
+        self
+    }
+
     /// Create a new session
     pub fn create_session(
         &mut self,
@@ -339,7 +353,10 @@ impl SessionManager {
         };
 
         // Create session
-        let session = Session::new(user_id, &token.token_hash, user_agent, ip, duration);
+        let mut session = Session::new(user_id, &token.token_hash, user_agent, ip, duration);
+        if let Some(resolver) = &self.location_resolver {
+            session.location = resolver(ip);
+        }
         let session_id = session.id;
 
         // Store session
@@ -402,6 +419,56 @@ impl SessionManager {
             .unwrap_or_default()
     }
 
+    /// List a user's active sessions for the "where you're signed in" view
+    ///
+    /// The session matching `current_session_id` is flagged with
+    /// `is_current` and listed first; the rest follow by most recent
+    /// activity.
+    pub fn list_for_user(&self, user_id: i64, current_session_id: Option<Uuid>) -> Vec<Session> {
+        let mut sessions: Vec<Session> = self
+            .get_user_sessions(user_id)
+            .into_iter()
+            .cloned()
+            .map(|mut session| {
+                session.is_current = Some(session.id) == current_session_id;
+                session
+            })
+            .collect();
+
+        sessions.sort_by(|a, b| {
+            b.is_current
+                .cmp(&a.is_current)
+                .then(b.last_activity_at.cmp(&a.last_activity_at))
+        });
+        sessions
+    }
+
+    /// Revoke a session
+    ///
+    /// The session's token is removed immediately, so the next request
+    /// presenting it fails validation rather than waiting for expiry.
+    pub fn revoke(&mut self, session_id: Uuid) -> Result<(), String> {
+        self.revoke_session(session_id)
+    }
+
+    /// Revoke every session of a user except the current one
+    ///
+    /// Used for "sign out everywhere else", e.g. after a password change.
+    /// Fails without revoking anything if `current_session_id` is not an
+    /// active session of `user_id`, so a stale ID cannot sign the caller out.
+    pub fn revoke_all_except(
+        &mut self,
+        user_id: i64,
+        current_session_id: Uuid,
+    ) -> Result<usize, String> {
+        match self.sessions.get(&current_session_id) {
+            Some(session) if session.user_id == user_id && session.is_valid() => {}
+            _ => return Err("Current session not found".to_string()),
+        }
+
+        Ok(self.revoke_other_sessions(user_id, current_session_id))
+    }
+
     /// Revoke a specific session
     pub fn revoke_session(&mut self, session_id: Uuid) -> Result<(), String> {
         let session = self
@@ -582,4 +649,54 @@ mod tests {
         // Should only have 2 sessions
         assert_eq!(manager.get_user_sessions(1).len(), 2);
     }
+
+    #[test]
+    fn test_list_for_user_flags_current_session() {
+        let mut manager = SessionManager::new().with_location_resolver(Arc::new(|ip: &str| {
+            (ip == "203.0.113.7").then(|| "Berlin, Germany".to_string())
+        }));
+        let (first, _) = manager
+            .create_session(1, Some("Firefox on Linux"), "203.0.113.7", false)
+            .unwrap();
+        let (second, _) = manager.create_session(1, None, "127.0.0.1", false).unwrap();
+        manager.create_session(2, None, "127.0.0.1", false).unwrap();
+
+        let sessions = manager.list_for_user(1, Some(first.id));
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].id, first.id);
+        assert!(sessions[0].is_current);
+        assert_eq!(sessions[0].location.as_deref(), Some("Berlin, Germany"));
+        assert_eq!(sessions[1].id, second.id);
+        assert!(!sessions[1].is_current);
+        assert_eq!(sessions[1].location, None);
+    }
+
+    #[test]
+    fn test_revoke_all_except_current() {
+        let mut manager = SessionManager::new();
+        let (current, current_token) = manager.create_session(1, None, "127.0.0.1", false).unwrap();
+        let (_, other_token) = manager.create_session(1, None, "127.0.0.2", false).unwrap();
+        let (_, third_token) = manager.create_session(1, None, "127.0.0.3", false).unwrap();
+
+        assert!(manager.revoke_all_except(1, Uuid::new_v4()).is_err());
+        assert_eq!(manager.revoke_all_except(1, current.id), Ok(2));
+
+        // Revoked tokens are rejected on the very next validation
+        assert!(manager.validate_token(&other_token).is_none());
+        assert!(manager.validate_token(&third_token).is_none());
+        assert!(manager.validate_token(&current_token).is_some());
+        assert_eq!(manager.list_for_user(1, Some(current.id)).len(), 1);
+    }
+
+    #[test]
+    fn test_revoke_rejects_token_immediately() {
+        let mut manager = SessionManager::new();
+        let (session, token) = manager.create_session(1, None, "127.0.0.1", false).unwrap();
+
+        manager.revoke(session.id).unwrap();
+
+        assert!(manager.validate_token(&token).is_none());
+        assert!(manager.revoke(session.id).is_err());
+    }
 }
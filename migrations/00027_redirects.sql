@@ -0,0 +1,29 @@
+-- Redirects table for URL management (old slugs, moved pages)
+-- Chains are collapsed when a redirect is recorded, so every row points at its final target
+
+CREATE TABLE IF NOT EXISTS redirects (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID REFERENCES sites(id) ON DELETE CASCADE,
+    source_url TEXT NOT NULL,
+    source_url_hash VARCHAR(32) NOT NULL,
+    match_type VARCHAR(20) NOT NULL DEFAULT 'exact' CHECK (match_type IN ('exact', 'prefix', 'regex')),
+    is_case_sensitive BOOLEAN NOT NULL DEFAULT false,
+    target_url TEXT NOT NULL,
+    redirect_type VARCHAR(3) NOT NULL DEFAULT '301' CHECK (redirect_type IN ('301', '302', '307', '308')),
+    preserve_query_string BOOLEAN NOT NULL DEFAULT true,
+    conditions JSONB NOT NULL DEFAULT '{}',
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    priority INTEGER NOT NULL DEFAULT 0,
+    hit_count BIGINT NOT NULL DEFAULT 0,
+    last_hit_at TIMESTAMP WITH TIME ZONE,
+    notes TEXT,
+    created_by UUID,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    CHECK (source_url <> target_url)
+);
+
+-- One live redirect per source
+CREATE UNIQUE INDEX IF NOT EXISTS idx_redirects_source ON redirects(source_url) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_redirects_target ON redirects(target_url) WHERE deleted_at IS NULL;
@@ -0,0 +1,28 @@
+-- Search documents for content, rebuilt by the reindex_content job
+-- Kept apart from contents so reindexing never rewrites (or locks) content rows
+
+CREATE TABLE IF NOT EXISTS content_search (
+    content_id UUID PRIMARY KEY,
+    post_type VARCHAR(50) NOT NULL,
+    language VARCHAR(63) NOT NULL DEFAULT 'english',
+    document TSVECTOR NOT NULL,
+    indexed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_content_search_document ON content_search USING GIN(document);
+CREATE INDEX IF NOT EXISTS idx_content_search_post_type ON content_search(post_type);
+
+-- Progress of each reindex run; the cursor lets an interrupted run resume
+CREATE TABLE IF NOT EXISTS search_reindex_runs (
+    id UUID PRIMARY KEY,
+    post_type VARCHAR(50),
+    created_from TIMESTAMP WITH TIME ZONE,
+    created_until TIMESTAMP WITH TIME ZONE,
+    cursor_id UUID,
+    processed BIGINT NOT NULL DEFAULT 0,
+    total BIGINT NOT NULL DEFAULT 0,
+    status VARCHAR(20) NOT NULL DEFAULT 'running' CHECK (status IN ('running', 'completed')),
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMP WITH TIME ZONE
+);
@@ -0,0 +1,15 @@
+-- Access tracking for stored files, used by storage lifecycle rules
+-- to move objects nobody reads to cheaper storage classes
+
+CREATE TABLE IF NOT EXISTS storage_objects (
+    path TEXT PRIMARY KEY,
+    storage_class VARCHAR(32) NOT NULL DEFAULT 'standard'
+        CHECK (storage_class IN ('standard', 'infrequent_access', 'archive')),
+    size BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_accessed_at TIMESTAMP WITH TIME ZONE
+);
+
+-- Lifecycle runs look for the least recently used objects per class
+CREATE INDEX IF NOT EXISTS idx_storage_objects_last_used
+    ON storage_objects(storage_class, (COALESCE(last_accessed_at, created_at)));
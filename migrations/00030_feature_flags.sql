@@ -0,0 +1,20 @@
+-- Runtime feature flags, cached in memory by rustpress_core::FeatureFlags
+-- and reloaded periodically, so these tables are never read per request
+
+CREATE TABLE IF NOT EXISTS feature_flags (
+    key VARCHAR(191) PRIMARY KEY,
+    description TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT false,
+    rollout_percentage SMALLINT CHECK (rollout_percentage BETWEEN 0 AND 100),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Per-tenant overrides take precedence over the flag's own settings
+CREATE TABLE IF NOT EXISTS feature_flag_overrides (
+    flag_key VARCHAR(191) NOT NULL REFERENCES feature_flags(key) ON DELETE CASCADE,
+    tenant_id UUID NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (flag_key, tenant_id)
+);
@@ -0,0 +1,83 @@
+-- =============================================================================
+-- Migration 010: Dead Letter Replay
+-- =============================================================================
+-- Visual Queue Manager - Inspecting and replaying failed messages
+--
+-- This migration creates tables for:
+-- - Dead lettered messages
+-- - Per-message processing history (failed attempts, DLQ moves, replays)
+-- And adds the payload schema handlers declare for edit-and-retry validation
+-- =============================================================================
+
+-- -----------------------------------------------------------------------------
+-- Dead Letter Queue Table
+-- -----------------------------------------------------------------------------
+-- Messages that failed processing, kept for inspection and replay
+
+CREATE TABLE IF NOT EXISTS vqm_dead_letter_queue (
+    -- Primary identifier
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Original message
+    original_message_id UUID NOT NULL,
+    queue_id UUID NOT NULL REFERENCES vqm_queues(id) ON DELETE CASCADE,
+    message_type VARCHAR(255) NOT NULL,
+    payload JSONB NOT NULL,
+    headers JSONB DEFAULT '{}'::jsonb,
+    metadata JSONB DEFAULT '{}'::jsonb,
+    original_created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    -- Failure details
+    moved_to_dlq_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    reason TEXT NOT NULL,
+    failure_count INTEGER DEFAULT 0,
+    last_error TEXT DEFAULT NULL,
+
+    -- Replay tracking
+    retry_count INTEGER DEFAULT 0,
+    can_retry BOOLEAN DEFAULT true,
+    last_retry_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    retried_message_id UUID DEFAULT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_vqm_dlq_queue_moved
+    ON vqm_dead_letter_queue(queue_id, moved_to_dlq_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_vqm_dlq_moved
+    ON vqm_dead_letter_queue(moved_to_dlq_at DESC);
+
+-- -----------------------------------------------------------------------------
+-- Message History Table
+-- -----------------------------------------------------------------------------
+-- Processing events for a message, shown as its retry history
+
+CREATE TABLE IF NOT EXISTS vqm_message_history (
+    -- Primary identifier
+    id BIGSERIAL PRIMARY KEY,
+
+    -- Message the event belongs to (kept after the message is deleted)
+    message_id UUID NOT NULL,
+
+    -- Event details
+    event_type VARCHAR(50) NOT NULL,
+    event_data JSONB NOT NULL DEFAULT '{}'::jsonb,
+    worker_id UUID DEFAULT NULL,
+
+    -- Timestamp
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_vqm_message_history_message
+    ON vqm_message_history(message_id, created_at);
+
+-- -----------------------------------------------------------------------------
+-- Handler Payload Schemas
+-- -----------------------------------------------------------------------------
+-- JSON Schema of the payload a handler expects; edited payloads are checked
+-- against it before a failed message is replayed
+
+ALTER TABLE vqm_event_handlers
+    ADD COLUMN IF NOT EXISTS payload_schema JSONB DEFAULT NULL;
+
+COMMENT ON COLUMN vqm_event_handlers.payload_schema IS 'JSON Schema for message payloads, checked when replaying edited messages';
+COMMENT ON TABLE vqm_message_history IS 'Per-message processing events: failed attempts, DLQ moves and replays';
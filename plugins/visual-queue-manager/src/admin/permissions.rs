@@ -42,6 +42,7 @@ impl PermissionManager {
 
         // Message capabilities
         caps.insert(Capability::ViewMessages);
+        caps.insert(Capability::ViewSensitivePayloads);
         caps.insert(Capability::EnqueueMessage);
         caps.insert(Capability::DeleteMessage);
         caps.insert(Capability::RetryMessage);
@@ -304,6 +305,8 @@ pub enum Capability {
 
     // Message management
     ViewMessages,
+    /// View decrypted payloads of encrypted messages
+    ViewSensitivePayloads,
     EnqueueMessage,
     DeleteMessage,
     RetryMessage,
@@ -361,6 +364,7 @@ impl std::fmt::Display for Capability {
             Capability::PauseQueue => "pause_queue",
             Capability::PurgeQueue => "purge_queue",
             Capability::ViewMessages => "view_messages",
+            Capability::ViewSensitivePayloads => "view_sensitive_payloads",
             Capability::EnqueueMessage => "enqueue_message",
             Capability::DeleteMessage => "delete_message",
             Capability::RetryMessage => "retry_message",
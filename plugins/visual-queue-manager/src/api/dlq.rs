@@ -0,0 +1,197 @@
+// =============================================================================
+// Visual Queue Manager - Dead Letter Replay API Endpoints
+// =============================================================================
+// REST API endpoints behind the admin replay screen: inspect failed messages
+// with their retry history, edit and retry them, or delete them.
+// All endpoints require `vqm_manage_all`; every action is audit logged.
+// =============================================================================
+
+use axum::{
+    extract::{Extension, Json, Path, Query},
+    http::StatusCode,
+    routing::{get, post},
+    Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{
+    parse_uuid, ApiError, ApiResponse, AppError, AuthUser, PaginationParams, ResponseMeta,
+};
+use crate::engine::{
+    AuditActor, DlqReplay, EngineError, FailedMessage, FailedSource, ReplayOutcome,
+};
+use crate::enterprise::redact_encrypted_payload;
+use crate::VisualQueueManager;
+
+/// Largest page of failed messages returned at once
+const MAX_PER_PAGE: i32 = 100;
+
+// -----------------------------------------------------------------------------
+// Router Configuration
+// -----------------------------------------------------------------------------
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/", get(list_failed_messages))
+        .route(
+            "/:id",
+            get(get_failed_message).delete(delete_failed_message),
+        )
+        .route("/:id/replay", post(replay_failed_message))
+}
+
+// -----------------------------------------------------------------------------
+// Data Types
+// -----------------------------------------------------------------------------
+
+/// List failed messages query parameters
+#[derive(Debug, Deserialize)]
+pub struct ListFailedParams {
+    #[serde(flatten)]
+    pub pagination: PaginationParams,
+    pub queue_id: Option<Uuid>,
+    pub source: Option<FailedSource>,
+}
+
+/// Replay request
+#[derive(Debug, Default, Deserialize)]
+pub struct ReplayRequest {
+    /// Replacement payload; the stored payload is replayed when omitted
+    pub payload: Option<serde_json::Value>,
+}
+
+// -----------------------------------------------------------------------------
+// Handlers
+// -----------------------------------------------------------------------------
+
+/// List failed and dead lettered messages with their retry history
+async fn list_failed_messages(
+    Extension(plugin): Extension<Arc<VisualQueueManager>>,
+    auth: AuthUser,
+    Query(params): Query<ListFailedParams>,
+) -> Result<Json<ApiResponse<Vec<FailedMessage>>>, AppError> {
+    let replay = replay_service(&plugin, &auth)?;
+
+    let per_page = params.pagination.per_page.clamp(1, MAX_PER_PAGE);
+    let page = params.pagination.page.max(1);
+    let offset = i64::from((page - 1) * per_page);
+
+    let (mut messages, total) = replay
+        .list(params.queue_id, params.source, offset, i64::from(per_page))
+        .await
+        .map_err(engine_error)?;
+    // Payloads are only ever decrypted one message at a time
+    for message in &mut messages {
+        message.payload = redact_encrypted_payload(message.payload.take());
+    }
+
+    let meta = ResponseMeta::new(total, page, per_page);
+    Ok(Json(ApiResponse::success_with_meta(messages, meta)))
+}
+
+/// Get a failed message, decrypting its payload if the user may see it
+async fn get_failed_message(
+    Extension(plugin): Extension<Arc<VisualQueueManager>>,
+    Path(id): Path<String>,
+    auth: AuthUser,
+) -> Result<Json<ApiResponse<FailedMessage>>, AppError> {
+    let replay = replay_service(&plugin, &auth)?;
+    let id = parse_uuid(&id)?;
+
+    let mut message = replay.get(id).await.map_err(engine_error)?;
+    message.payload = if auth.can_view_sensitive_payloads() {
+        replay
+            .reveal_payload(&message, &actor(&auth))
+            .await
+            .map_err(engine_error)?
+    } else {
+        redact_encrypted_payload(message.payload)
+    };
+
+    Ok(Json(ApiResponse::success(message)))
+}
+
+/// Retry a failed message, optionally with an edited payload
+async fn replay_failed_message(
+    Extension(plugin): Extension<Arc<VisualQueueManager>>,
+    Path(id): Path<String>,
+    auth: AuthUser,
+    body: Option<Json<ReplayRequest>>,
+) -> Result<Json<ApiResponse<ReplayOutcome>>, AppError> {
+    let replay = replay_service(&plugin, &auth)?;
+    let id = parse_uuid(&id)?;
+    let Json(req) = body.unwrap_or_default();
+
+    let outcome = replay
+        .replay(id, req.payload, &actor(&auth))
+        .await
+        .map_err(engine_error)?;
+
+    Ok(Json(ApiResponse::success(outcome)))
+}
+
+/// Delete a failed message
+async fn delete_failed_message(
+    Extension(plugin): Extension<Arc<VisualQueueManager>>,
+    Path(id): Path<String>,
+    auth: AuthUser,
+) -> Result<StatusCode, AppError> {
+    let replay = replay_service(&plugin, &auth)?;
+    let id = parse_uuid(&id)?;
+
+    replay
+        .delete(id, &actor(&auth))
+        .await
+        .map_err(engine_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// -----------------------------------------------------------------------------
+// Helper Functions
+// -----------------------------------------------------------------------------
+
+/// Replay service, once the caller is known to hold `vqm_manage_all`
+fn replay_service<'a>(
+    plugin: &'a VisualQueueManager,
+    auth: &AuthUser,
+) -> Result<&'a Arc<DlqReplay>, AppError> {
+    if !auth.can_admin() {
+        return Err(AppError::forbidden());
+    }
+    plugin.dlq_replay().ok_or_else(|| {
+        AppError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::new("ENGINE_UNAVAILABLE", "The queue engine is not running"),
+        )
+    })
+}
+
+fn actor(auth: &AuthUser) -> AuditActor {
+    AuditActor {
+        id: auth.id,
+        name: auth.username.clone(),
+    }
+}
+
+fn engine_error(err: EngineError) -> AppError {
+    match err {
+        EngineError::MessageNotFound(_) => AppError::not_found("Failed message"),
+        EngineError::NotRetryable(_) => {
+            AppError::conflict("Message has been marked as not retryable")
+        }
+        EngineError::Conflict(message) => AppError::conflict(message),
+        EngineError::SchemaViolation(violations) => AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::validation("Payload does not match the handler schema")
+                .with_details(serde_json::json!({ "violations": violations })),
+        ),
+        EngineError::Database(e) => e.into(),
+        other => {
+            tracing::error!("Failed message operation failed: {}", other);
+            AppError::internal(other.to_string())
+        }
+    }
+}
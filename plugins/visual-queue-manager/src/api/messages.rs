@@ -22,6 +22,7 @@ use super::{
     parse_uuid, validate_request, ApiError, ApiResponse, AppError, AuthUser, DateRangeParams,
     PaginationParams, ResponseMeta, SortParams,
 };
+use crate::enterprise::{is_encrypted_payload, redact_encrypted_payload};
 use crate::VisualQueueManager;
 
 // -----------------------------------------------------------------------------
@@ -52,6 +53,7 @@ pub fn router() -> Router {
         // Acknowledge message (for workers)
         .route("/:id/ack", post(acknowledge_message))
         .route("/:id/nack", post(negative_acknowledge))
+        .route("/batch/ack", post(batch_acknowledge))
         // Claim message for processing
         .route("/claim", post(claim_messages))
         // Release claimed message
@@ -152,6 +154,14 @@ pub struct AckRequest {
     pub result: Option<serde_json::Value>,
 }
 
+/// Batch acknowledge request
+#[derive(Debug, Deserialize, Validate)]
+pub struct BatchAckRequest {
+    pub worker_id: Uuid,
+    #[validate(length(min = 1, max = 1000))]
+    pub message_ids: Vec<Uuid>,
+}
+
 /// Negative acknowledge request
 #[derive(Debug, Deserialize)]
 pub struct NackRequest {
@@ -252,6 +262,15 @@ pub struct BatchEnqueueResult {
     pub errors: Vec<BatchError>,
 }
 
+/// Batch acknowledge result
+#[derive(Debug, Serialize)]
+pub struct BatchAckResult {
+    /// Messages marked completed
+    pub acknowledged: Vec<Uuid>,
+    /// Messages not locked by the worker, left untouched
+    pub rejected: Vec<Uuid>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct BatchError {
     pub index: i32,
@@ -460,6 +479,7 @@ async fn enqueue_message(
     let message_id = Uuid::new_v4();
     let metadata = req.metadata.unwrap_or(serde_json::json!({}));
     let headers = req.headers.unwrap_or(serde_json::json!({}));
+    let payload = seal_payload(&plugin, &req.payload).await?;
 
     sqlx::query(
         r#"
@@ -476,7 +496,7 @@ async fn enqueue_message(
     .bind(message_id)
     .bind(req.queue_id)
     .bind(&req.message_type)
-    .bind(&req.payload)
+    .bind(&payload)
     .bind(status)
     .bind(req.priority.unwrap_or(5))
     .bind(max_attempts)
@@ -549,11 +569,18 @@ async fn batch_enqueue(
         return Err(AppError::not_found("Queue"));
     }
 
+    // Seal every payload up front so an encryption failure rejects the
+    // whole batch instead of storing part of it
+    let mut payloads = Vec::with_capacity(req.messages.len());
+    for item in &req.messages {
+        payloads.push(seal_payload(&plugin, &item.payload).await?);
+    }
+
     let mut message_ids = Vec::new();
     let mut errors = Vec::new();
     let mut success_count = 0;
 
-    for (index, item) in req.messages.iter().enumerate() {
+    for (index, (item, payload)) in req.messages.iter().zip(&payloads).enumerate() {
         let message_id = Uuid::new_v4();
 
         let scheduled_at = item
@@ -576,7 +603,7 @@ async fn batch_enqueue(
         .bind(message_id)
         .bind(req.queue_id)
         .bind(&item.message_type)
-        .bind(&item.payload)
+        .bind(payload)
         .bind(status)
         .bind(item.priority.unwrap_or(5))
         .bind(scheduled_at)
@@ -621,7 +648,12 @@ async fn get_message(
     let message_id = parse_uuid(&id)?;
     let pool = plugin.db_pool();
 
-    let message = get_message_by_id(pool, message_id).await?;
+    let mut message = fetch_message(pool, message_id).await?;
+    message.payload = if auth.can_view_sensitive_payloads() {
+        open_payload(&plugin, message.payload).await?
+    } else {
+        redact_encrypted_payload(message.payload)
+    };
 
     Ok(Json(ApiResponse::success(message)))
 }
@@ -990,6 +1022,58 @@ async fn acknowledge_message(
     }
 }
 
+/// Acknowledge several completed messages in one round trip (for workers)
+async fn batch_acknowledge(
+    Extension(plugin): Extension<Arc<VisualQueueManager>>,
+    Json(req): Json<BatchAckRequest>,
+) -> Result<Json<ApiResponse<BatchAckResult>>, AppError> {
+    validate_request(&req)?;
+
+    let pool = plugin.db_pool();
+
+    // Only messages the worker still holds the lock on are completed
+    let acknowledged: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        UPDATE vqm_messages
+        SET status = 'completed',
+            completed_at = CURRENT_TIMESTAMP,
+            result = '{}'::jsonb,
+            locked_by = NULL,
+            locked_until = NULL,
+            processing_time_ms = EXTRACT(EPOCH FROM (CURRENT_TIMESTAMP - started_at)) * 1000,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = ANY($1) AND locked_by = $2 AND status = 'processing'
+        RETURNING id
+        "#,
+    )
+    .bind(&req.message_ids)
+    .bind(req.worker_id)
+    .fetch_all(pool)
+    .await?;
+
+    if !acknowledged.is_empty() {
+        sqlx::query(
+            "UPDATE vqm_workers SET jobs_completed = jobs_completed + $1, current_job_id = NULL WHERE id = $2"
+        )
+        .bind(acknowledged.len() as i64)
+        .bind(req.worker_id)
+        .execute(pool)
+        .await?;
+    }
+
+    let rejected = req
+        .message_ids
+        .iter()
+        .filter(|id| !acknowledged.contains(id))
+        .copied()
+        .collect();
+
+    Ok(Json(ApiResponse::success(BatchAckResult {
+        acknowledged,
+        rejected,
+    })))
+}
+
 /// Negative acknowledge (for workers to report failure)
 async fn negative_acknowledge(
     Extension(plugin): Extension<Arc<VisualQueueManager>>,
@@ -1112,29 +1196,51 @@ async fn claim_messages(
     .fetch_all(pool)
     .await?;
 
-    // Update worker's current job if single claim
-    if claimed.len() == 1 {
-        sqlx::query("UPDATE vqm_workers SET current_job_id = $1, status = 'active' WHERE id = $2")
-            .bind(claimed[0].id)
-            .bind(req.worker_id)
-            .execute(pool)
-            .await?;
-    }
+    let mut messages = Vec::with_capacity(claimed.len());
+    for r in claimed {
+        let payload = match open_payload(&plugin, r.payload).await {
+            Ok(payload) => payload,
+            Err(e) => {
+                // Retrying can't fix an unreadable payload; fail it so it
+                // doesn't block the queue
+                tracing::error!(message_id = %r.id, "Failed to decrypt payload: {}", e.error.message);
+                sqlx::query(
+                    r#"
+                    UPDATE vqm_messages
+                    SET status = 'failed', last_error = $2, locked_by = NULL,
+                        locked_until = NULL, updated_at = CURRENT_TIMESTAMP
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(r.id)
+                .bind(&e.error.message)
+                .execute(pool)
+                .await?;
+                continue;
+            }
+        };
 
-    let messages: Vec<ClaimedMessage> = claimed
-        .into_iter()
-        .map(|r| ClaimedMessage {
+        messages.push(ClaimedMessage {
             id: r.id,
             message_type: r.message_type,
-            payload: r.payload,
+            payload,
             priority: r.priority,
             attempts: r.attempts,
             correlation_id: r.correlation_id,
             metadata: r.metadata,
             headers: r.headers,
             lock_until,
-        })
-        .collect();
+        });
+    }
+
+    // Update worker's current job if single claim
+    if messages.len() == 1 {
+        sqlx::query("UPDATE vqm_workers SET current_job_id = $1, status = 'active' WHERE id = $2")
+            .bind(messages[0].id)
+            .bind(req.worker_id)
+            .execute(pool)
+            .await?;
+    }
 
     Ok(Json(ApiResponse::success(messages)))
 }
@@ -1322,7 +1428,53 @@ struct ClaimedMessageRow {
     headers: serde_json::Value,
 }
 
+/// Encrypt a payload for storage when payload encryption is enabled
+async fn seal_payload(
+    plugin: &VisualQueueManager,
+    payload: &serde_json::Value,
+) -> Result<serde_json::Value, AppError> {
+    if !plugin.config().await.encrypt_payloads {
+        return Ok(payload.clone());
+    }
+
+    let encryptor = plugin
+        .payload_encryptor()
+        .await
+        .ok_or_else(|| AppError::internal("Payload encryption is enabled but no key is loaded"))?;
+    encryptor
+        .seal(payload)
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to encrypt payload: {}", e)))
+}
+
+/// Decrypt a stored payload; plaintext payloads pass through
+async fn open_payload(
+    plugin: &VisualQueueManager,
+    stored: serde_json::Value,
+) -> Result<serde_json::Value, AppError> {
+    if !is_encrypted_payload(&stored) {
+        return Ok(stored);
+    }
+
+    let encryptor = plugin
+        .payload_encryptor()
+        .await
+        .ok_or_else(|| AppError::internal("Message payload is encrypted but no key is loaded"))?;
+    encryptor
+        .open(stored)
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to decrypt payload: {}", e)))
+}
+
+/// Load a message for display, with encrypted payloads redacted
 async fn get_message_by_id(pool: &PgPool, message_id: Uuid) -> Result<MessageResponse, AppError> {
+    let mut message = fetch_message(pool, message_id).await?;
+    message.payload = redact_encrypted_payload(message.payload);
+    Ok(message)
+}
+
+/// Load a message with its payload as stored
+async fn fetch_message(pool: &PgPool, message_id: Uuid) -> Result<MessageResponse, AppError> {
     let row: MessageRow = sqlx::query_as(
         r#"
         SELECT
@@ -7,7 +7,8 @@
 
 use axum::{
     extract::{Extension, Json, Path, Query},
-    http::StatusCode,
+    http::{header, StatusCode},
+    response::IntoResponse,
     routing::{delete, get, post, put},
     Router,
 };
@@ -30,6 +31,8 @@ use crate::VisualQueueManager;
 
 pub fn router() -> Router {
     Router::new()
+        // Prometheus scrape target, served from the engine's gauges
+        .route("/", get(metrics_exposition))
         // Dashboard overview
         .route("/dashboard", get(get_dashboard))
         // Real-time metrics
@@ -1092,6 +1095,32 @@ async fn get_system_health(
     Ok(Json(ApiResponse::success(health)))
 }
 
+/// Prometheus exposition of the engine gauges.
+///
+/// Values are refreshed by the metrics collector on its own interval, so a
+/// scrape never queries the database.
+async fn metrics_exposition(
+    Extension(plugin): Extension<Arc<VisualQueueManager>>,
+) -> Result<impl IntoResponse, AppError> {
+    let exporter = plugin.metrics_exporter().ok_or_else(|| {
+        AppError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::new(
+                "METRICS_UNAVAILABLE",
+                "Queue engine metrics are not attached",
+            ),
+        )
+    })?;
+
+    Ok((
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        exporter.encode(),
+    ))
+}
+
 /// Prometheus metrics endpoint
 async fn prometheus_metrics(
     Extension(plugin): Extension<Arc<VisualQueueManager>>,
@@ -6,6 +6,7 @@
 // =============================================================================
 
 pub mod admin;
+pub mod dlq;
 pub mod handlers;
 pub mod messages;
 pub mod metrics;
@@ -46,6 +47,8 @@ pub fn build_router(plugin: Arc<VisualQueueManager>) -> Router {
         .nest("/scheduled", scheduled::router())
         // Admin Operations (Point 35)
         .nest("/admin", admin::router())
+        // Failed message inspection and replay
+        .nest("/dlq", dlq::router())
         // Attach plugin state
         .layer(Extension(plugin))
 }
@@ -308,6 +311,10 @@ impl AuthUser {
         self.has_capability("vqm_view_metrics")
     }
 
+    pub fn can_view_sensitive_payloads(&self) -> bool {
+        self.has_capability("vqm_view_sensitive_payloads")
+    }
+
     pub fn can_admin(&self) -> bool {
         self.has_capability("vqm_manage_all")
     }
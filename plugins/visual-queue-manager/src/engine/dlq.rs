@@ -9,6 +9,7 @@ use std::sync::Arc;
 use tokio::sync::broadcast;
 use uuid::Uuid;
 
+use super::replay::record_history;
 use super::storage::StorageBackend;
 use super::{EngineError, EngineEvent};
 
@@ -234,6 +235,18 @@ impl DeadLetterQueue {
         .execute(&self.pool)
         .await?;
 
+        if let Err(e) = record_history(
+            &self.pool,
+            message_id,
+            "moved_to_dlq",
+            serde_json::json!({ "dlq_id": dlq_id, "reason": reason }),
+            None,
+        )
+        .await
+        {
+            tracing::warn!(message_id = %message_id, "Failed to record DLQ move in history: {}", e);
+        }
+
         // Emit event
         let _ = self.event_tx.send(EngineEvent::MessageMovedToDlq {
             queue_id: message.queue_id,
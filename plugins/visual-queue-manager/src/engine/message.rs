@@ -5,16 +5,33 @@
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use sqlx::{FromRow, PgPool, Row};
-use std::sync::Arc;
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder, Row};
+use std::sync::{Arc, OnceLock};
 use std::time::Instant;
 use tokio::sync::broadcast;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+use super::replay::record_history;
 use super::retry::RetryPolicy;
 use super::storage::StorageBackend;
 use super::{EngineError, EngineEvent};
+use crate::enterprise::{is_encrypted_payload, PayloadEncryptor};
+
+/// Largest batch accepted by `enqueue_batch` and `acknowledge_batch` by default
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 1000;
+
+/// Values bound per message by a batched insert
+const INSERT_COLUMNS: usize = 16;
+
+/// Rows per INSERT statement, within Postgres' limit of 65535 bind parameters
+const MAX_ROWS_PER_INSERT: usize = u16::MAX as usize / INSERT_COLUMNS;
+
+/// Lease granted when a message is claimed, unless configured otherwise
+pub const DEFAULT_VISIBILITY_TIMEOUT_SECS: i64 = 300;
+
+/// Longest a single claim may run, however often its lease is extended
+pub const DEFAULT_MAX_PROCESSING_TIME_SECS: i64 = 3600;
 
 /// Message status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -61,6 +78,14 @@ struct QueueConfigRow {
     max_retries: Option<i32>,
 }
 
+/// Row struct for the queue lock taken by a batch enqueue
+#[derive(FromRow)]
+struct BatchQueueRow {
+    max_retries: Option<i32>,
+    max_queue_size: Option<i64>,
+    depth: i64,
+}
+
 /// Row struct for claimed messages query
 #[derive(FromRow)]
 struct ClaimedMessageRow {
@@ -94,6 +119,30 @@ struct AcknowledgeRow {
     processing_started_at: Option<DateTime<Utc>>,
 }
 
+/// Row struct for batch acknowledge query
+#[derive(FromRow)]
+struct BatchAcknowledgeRow {
+    id: Uuid,
+    queue_id: Uuid,
+    processing_started_at: Option<DateTime<Utc>>,
+}
+
+/// Row struct for the lease check made when an extension is refused
+#[derive(FromRow)]
+struct LeaseRow {
+    status: String,
+    claimed_by: Option<Uuid>,
+    processing_started_at: Option<DateTime<Utc>>,
+    visibility_timeout_at: Option<DateTime<Utc>>,
+}
+
+/// Row struct for messages failed for running too long
+#[derive(FromRow)]
+struct OverrunRow {
+    id: Uuid,
+    queue_id: Uuid,
+}
+
 /// Row struct for get message query
 #[derive(FromRow)]
 struct MessageRow {
@@ -199,9 +248,17 @@ pub struct MessageProcessor {
     retry_policy: RetryPolicy,
     event_tx: broadcast::Sender<EngineEvent>,
     batch_size: usize,
+    /// Maximum messages per batch enqueue or acknowledge
+    max_batch_size: usize,
     /// Internal statistics
     stats: RwLock<InternalStats>,
     start_time: Instant,
+    /// Encrypts payloads before they are written, once enabled
+    payload_encryptor: OnceLock<Arc<PayloadEncryptor>>,
+    /// Lease granted on claim; workers extend it with `extend_lease`
+    visibility_timeout: Duration,
+    /// Deadline for a claim, after which the message is failed
+    max_processing_time: Duration,
 }
 
 #[derive(Debug, Default)]
@@ -228,9 +285,72 @@ impl MessageProcessor {
             retry_policy,
             event_tx,
             batch_size,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
             stats: RwLock::new(InternalStats::default()),
             start_time: Instant::now(),
+            payload_encryptor: OnceLock::new(),
+            visibility_timeout: Duration::seconds(DEFAULT_VISIBILITY_TIMEOUT_SECS),
+            max_processing_time: Duration::seconds(DEFAULT_MAX_PROCESSING_TIME_SECS),
+        }
+    }
+
+    /// Set the maximum number of messages per batch operation
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size.max(1);
+        self
+    }
+
+    /// Set the lease granted when a message is claimed
+    pub fn with_visibility_timeout(mut self, timeout: Duration) -> Self {
+        self.visibility_timeout = timeout.max(Duration::seconds(1));
+        self
+    }
+
+    /// Set how long a claim may run in total before the message is failed
+    pub fn with_max_processing_time(mut self, max: Duration) -> Self {
+        self.max_processing_time = max.max(Duration::seconds(1));
+        self
+    }
+
+    /// Encrypt payloads of all messages enqueued from now on
+    pub fn enable_payload_encryption(
+        &self,
+        encryptor: Arc<PayloadEncryptor>,
+    ) -> Result<(), EngineError> {
+        self.payload_encryptor
+            .set(encryptor)
+            .map_err(|_| EngineError::InvalidConfig("Payload encryption already enabled".into()))
+    }
+
+    /// Payload as it should be stored; never plaintext once encryption is on
+    pub(crate) async fn seal_payload(
+        &self,
+        payload: &serde_json::Value,
+    ) -> Result<serde_json::Value, EngineError> {
+        match self.payload_encryptor.get() {
+            Some(encryptor) => encryptor
+                .seal(payload)
+                .await
+                .map_err(|e| EngineError::Encryption(e.to_string())),
+            None => Ok(payload.clone()),
+        }
+    }
+
+    /// Decrypt a stored payload if it was sealed
+    pub(crate) async fn open_payload(
+        &self,
+        stored: serde_json::Value,
+    ) -> Result<serde_json::Value, EngineError> {
+        if !is_encrypted_payload(&stored) {
+            return Ok(stored);
         }
+        let encryptor = self.payload_encryptor.get().ok_or_else(|| {
+            EngineError::Encryption("Payload is encrypted but no key is loaded".to_string())
+        })?;
+        encryptor
+            .open(stored)
+            .await
+            .map_err(|e| EngineError::Encryption(e.to_string()))
     }
 
     /// Enqueue a single message
@@ -273,8 +393,16 @@ impl MessageProcessor {
             "pending"
         };
 
-        // Generate content-based deduplication ID if needed
+        let encrypted = self.payload_encryptor.get().is_some();
+        let stored_payload = self.seal_payload(&request.payload).await?;
+
+        // Generate content-based deduplication ID if needed. A plain hash of
+        // an encrypted payload would let anyone with database access confirm
+        // guesses of its content, so encrypted messages don't get one.
         let dedup_id = request.deduplication_id.or_else(|| {
+            if encrypted {
+                return None;
+            }
             // Generate hash of payload for content-based deduplication
             let mut hasher = Sha256::new();
             hasher.update(request.payload.to_string().as_bytes());
@@ -295,7 +423,7 @@ impl MessageProcessor {
         .bind(id)
         .bind(request.queue_id)
         .bind(&request.message_type)
-        .bind(&request.payload)
+        .bind(&stored_payload)
         .bind(&request.headers)
         .bind(request.priority)
         .bind(status)
@@ -345,88 +473,116 @@ impl MessageProcessor {
         Ok(message)
     }
 
-    /// Enqueue multiple messages in batch
+    /// Enqueue a batch of messages into one queue
+    ///
+    /// The batch is written in a single transaction: either every message is
+    /// stored or none is. The queue row is locked while its depth is checked,
+    /// so concurrent batches cannot together overrun `max_queue_size`. The
+    /// returned messages keep the order of `requests`, letting callers match
+    /// each request to its message id.
     pub async fn enqueue_batch(
         &self,
+        queue_id: Uuid,
         requests: Vec<EnqueueRequest>,
-    ) -> Result<Vec<Message>, EngineError> {
-        let mut messages = Vec::with_capacity(requests.len());
-        let mut tx = self.pool.begin().await?;
+    ) -> Result<MessageBatch, EngineError> {
+        if requests.is_empty() {
+            return Ok(MessageBatch {
+                messages: Vec::new(),
+                queue_id,
+            });
+        }
+        if requests.len() > self.max_batch_size {
+            return Err(EngineError::InvalidConfig(format!(
+                "Batch of {} messages exceeds the maximum of {}",
+                requests.len(),
+                self.max_batch_size
+            )));
+        }
+        if let Some(request) = requests.iter().find(|r| r.queue_id != queue_id) {
+            return Err(EngineError::InvalidConfig(format!(
+                "Batch for queue {} contains a message for queue {}",
+                queue_id, request.queue_id
+            )));
+        }
 
-        for request in requests {
-            let id = Uuid::new_v4();
-            let now = Utc::now();
+        // Seal before opening the transaction; any failure rejects the batch
+        let mut stored_payloads = Vec::with_capacity(requests.len());
+        for request in &requests {
+            stored_payloads.push(self.seal_payload(&request.payload).await?);
+        }
 
-            let queue_config: QueueConfigRow = sqlx::query_as::<_, QueueConfigRow>(
-                "SELECT max_retries FROM vqm_queues WHERE id = $1",
-            )
-            .bind(request.queue_id)
-            .fetch_optional(&mut *tx)
-            .await?
-            .ok_or(EngineError::QueueNotFound(request.queue_id))?;
-
-            let max_attempts = queue_config.max_retries.unwrap_or(3);
-            let status = if request.scheduled_at.is_some() {
-                "scheduled"
-            } else {
-                "pending"
-            };
+        let mut tx = self.pool.begin().await?;
 
-            let dedup_id = request.deduplication_id.clone();
+        let queue: BatchQueueRow = sqlx::query_as::<_, BatchQueueRow>(
+            r#"
+            SELECT q.max_retries, q.max_queue_size,
+                   (SELECT COUNT(*) FROM vqm_messages m
+                    WHERE m.queue_id = q.id
+                    AND m.status IN ('pending', 'scheduled', 'processing')) AS depth
+            FROM vqm_queues q
+            WHERE q.id = $1
+            FOR UPDATE OF q
+            "#,
+        )
+        .bind(queue_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(EngineError::QueueNotFound(queue_id))?;
+
+        if let Some(max_size) = queue.max_queue_size {
+            if queue.depth + requests.len() as i64 > max_size {
+                return Err(EngineError::QueueFull {
+                    queue_id,
+                    depth: queue.depth,
+                    max_size,
+                });
+            }
+        }
 
-            sqlx::query(
-                r#"
-                INSERT INTO vqm_messages (
-                    id, queue_id, message_type, payload, headers, priority, status,
-                    attempt_count, max_attempts, created_at, scheduled_at,
-                    deduplication_id, group_id, correlation_id, trace_id, metadata
-                ) VALUES (
-                    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16
-                )
-                "#,
-            )
-            .bind(id)
-            .bind(request.queue_id)
-            .bind(&request.message_type)
-            .bind(&request.payload)
-            .bind(&request.headers)
-            .bind(request.priority)
-            .bind(status)
-            .bind(0i32)
-            .bind(max_attempts)
-            .bind(now)
-            .bind(request.scheduled_at)
-            .bind(&dedup_id)
-            .bind(&request.group_id)
-            .bind(&request.correlation_id)
-            .bind(&request.trace_id)
-            .bind(&request.metadata)
-            .execute(&mut *tx)
-            .await?;
+        let max_attempts = queue.max_retries.unwrap_or(3);
+        let now = Utc::now();
+        let messages: Vec<Message> = requests
+            .into_iter()
+            .map(|request| {
+                let status = if request.scheduled_at.is_some() {
+                    MessageStatus::Scheduled
+                } else {
+                    MessageStatus::Pending
+                };
+                Message {
+                    id: Uuid::new_v4(),
+                    queue_id,
+                    message_type: request.message_type,
+                    payload: request.payload,
+                    headers: request.headers,
+                    priority: request.priority,
+                    status,
+                    attempt_count: 0,
+                    max_attempts,
+                    created_at: now,
+                    scheduled_at: request.scheduled_at,
+                    processing_started_at: None,
+                    completed_at: None,
+                    visibility_timeout_at: None,
+                    deduplication_id: request.deduplication_id,
+                    group_id: request.group_id,
+                    correlation_id: request.correlation_id,
+                    trace_id: request.trace_id,
+                    claimed_by: None,
+                    last_error: None,
+                    metadata: request.metadata,
+                }
+            })
+            .collect();
 
-            messages.push(Message {
-                id,
-                queue_id: request.queue_id,
-                message_type: request.message_type,
-                payload: request.payload,
-                headers: request.headers,
-                priority: request.priority,
-                status: MessageStatus::from(status.to_string()),
-                attempt_count: 0,
-                max_attempts,
-                created_at: now,
-                scheduled_at: request.scheduled_at,
-                processing_started_at: None,
-                completed_at: None,
-                visibility_timeout_at: None,
-                deduplication_id: dedup_id,
-                group_id: request.group_id,
-                correlation_id: request.correlation_id,
-                trace_id: request.trace_id,
-                claimed_by: None,
-                last_error: None,
-                metadata: request.metadata,
-            });
+        for (chunk, payloads) in messages
+            .chunks(MAX_ROWS_PER_INSERT)
+            .zip(stored_payloads.chunks(MAX_ROWS_PER_INSERT))
+        {
+            insert_batch_query(chunk, payloads)
+                .build()
+                .execute(&mut *tx)
+                .await?;
         }
 
         tx.commit().await?;
@@ -440,7 +596,7 @@ impl MessageProcessor {
             });
         }
 
-        Ok(messages)
+        Ok(MessageBatch { messages, queue_id })
     }
 
     /// Claim messages for processing
@@ -455,7 +611,7 @@ impl MessageProcessor {
         }
 
         let now = Utc::now();
-        let visibility_timeout = Duration::seconds(300); // 5 minutes default
+        let visibility_timeout = self.visibility_timeout.min(self.max_processing_time);
 
         // Use SELECT FOR UPDATE SKIP LOCKED for concurrent claim
         let rows: Vec<ClaimedMessageRow> = sqlx::query_as::<_, ClaimedMessageRow>(
@@ -493,45 +649,80 @@ impl MessageProcessor {
         .fetch_all(&self.pool)
         .await?;
 
-        let messages: Vec<Message> = rows
-            .into_iter()
-            .map(|row| {
-                // Emit event for each claimed message
-                let _ = self.event_tx.send(EngineEvent::MessageProcessingStarted {
-                    queue_id: row.queue_id,
-                    message_id: row.id,
-                    worker_id,
-                });
-
-                Message {
-                    id: row.id,
-                    queue_id: row.queue_id,
-                    message_type: row.message_type,
-                    payload: row.payload,
-                    headers: row.headers.unwrap_or(serde_json::json!({})),
-                    priority: row.priority.unwrap_or(0),
-                    status: MessageStatus::Processing,
-                    attempt_count: row.attempt_count.unwrap_or(1),
-                    max_attempts: row.max_attempts.unwrap_or(3),
-                    created_at: row.created_at,
-                    scheduled_at: row.scheduled_at,
-                    processing_started_at: row.processing_started_at,
-                    completed_at: row.completed_at,
-                    visibility_timeout_at: row.visibility_timeout_at,
-                    deduplication_id: row.deduplication_id,
-                    group_id: row.group_id,
-                    correlation_id: row.correlation_id,
-                    trace_id: row.trace_id,
-                    claimed_by: row.claimed_by,
-                    last_error: row.last_error,
-                    metadata: row.metadata.unwrap_or(serde_json::json!({})),
+        let mut messages = Vec::with_capacity(rows.len());
+        for row in rows {
+            let payload = match self.open_payload(row.payload).await {
+                Ok(payload) => payload,
+                Err(e) => {
+                    // Retrying can't fix an unreadable payload; fail it so it
+                    // doesn't block the queue
+                    tracing::error!(message_id = %row.id, "Failed to decrypt payload: {}", e);
+                    self.fail_unreadable(row.id, worker_id, &e.to_string())
+                        .await?;
+                    continue;
                 }
-            })
-            .collect();
+            };
+
+            // Emit event for each claimed message
+            let _ = self.event_tx.send(EngineEvent::MessageProcessingStarted {
+                queue_id: row.queue_id,
+                message_id: row.id,
+                worker_id,
+            });
+
+            messages.push(Message {
+                id: row.id,
+                queue_id: row.queue_id,
+                message_type: row.message_type,
+                payload,
+                headers: row.headers.unwrap_or(serde_json::json!({})),
+                priority: row.priority.unwrap_or(0),
+                status: MessageStatus::Processing,
+                attempt_count: row.attempt_count.unwrap_or(1),
+                max_attempts: row.max_attempts.unwrap_or(3),
+                created_at: row.created_at,
+                scheduled_at: row.scheduled_at,
+                processing_started_at: row.processing_started_at,
+                completed_at: row.completed_at,
+                visibility_timeout_at: row.visibility_timeout_at,
+                deduplication_id: row.deduplication_id,
+                group_id: row.group_id,
+                correlation_id: row.correlation_id,
+                trace_id: row.trace_id,
+                claimed_by: row.claimed_by,
+                last_error: row.last_error,
+                metadata: row.metadata.unwrap_or(serde_json::json!({})),
+            });
+        }
 
         Ok(messages)
     }
 
+    /// Mark a claimed message whose payload can't be decrypted as failed
+    async fn fail_unreadable(
+        &self,
+        message_id: Uuid,
+        worker_id: Uuid,
+        error: &str,
+    ) -> Result<(), EngineError> {
+        sqlx::query(
+            r#"
+            UPDATE vqm_messages
+            SET status = 'failed',
+                completed_at = NOW(),
+                last_error = $2
+            WHERE id = $1 AND claimed_by = $3
+            "#,
+        )
+        .bind(message_id)
+        .bind(error)
+        .bind(worker_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     /// Acknowledge successful message processing
     pub async fn acknowledge(&self, message_id: Uuid, worker_id: Uuid) -> Result<(), EngineError> {
         let now = Utc::now();
@@ -577,6 +768,73 @@ impl MessageProcessor {
         Ok(())
     }
 
+    /// Acknowledge a batch of processed messages in one statement
+    ///
+    /// Only messages still claimed by `worker_id` are completed; the ids of
+    /// those are returned, so callers can tell which acknowledgements were
+    /// rejected (e.g. because the visibility timeout already expired).
+    pub async fn acknowledge_batch(
+        &self,
+        message_ids: &[Uuid],
+        worker_id: Uuid,
+    ) -> Result<Vec<Uuid>, EngineError> {
+        if message_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        if message_ids.len() > self.max_batch_size {
+            return Err(EngineError::InvalidConfig(format!(
+                "Batch of {} acknowledgements exceeds the maximum of {}",
+                message_ids.len(),
+                self.max_batch_size
+            )));
+        }
+
+        let now = Utc::now();
+
+        let rows: Vec<BatchAcknowledgeRow> = sqlx::query_as::<_, BatchAcknowledgeRow>(
+            r#"
+            UPDATE vqm_messages
+            SET status = 'completed',
+                completed_at = $2
+            WHERE id = ANY($1) AND claimed_by = $3 AND status = 'processing'
+            RETURNING id, queue_id, processing_started_at
+            "#,
+        )
+        .bind(message_ids)
+        .bind(now)
+        .bind(worker_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let processing_times: Vec<u64> = rows
+            .iter()
+            .map(|row| {
+                row.processing_started_at
+                    .map(|start| (now - start).num_milliseconds() as u64)
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        // Update statistics
+        {
+            let mut stats = self.stats.write().await;
+            stats.total_processed += rows.len() as u64;
+            stats.total_processing_time_ms += processing_times.iter().sum::<u64>();
+            stats.recent_successes += rows.len() as u64;
+        }
+
+        // Emit events
+        for (row, processing_time_ms) in rows.iter().zip(processing_times) {
+            let _ = self.event_tx.send(EngineEvent::MessageProcessed {
+                queue_id: row.queue_id,
+                message_id: row.id,
+                processing_time_ms,
+            });
+        }
+
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+
     /// Negative acknowledge (failed processing)
     pub async fn negative_acknowledge(
         &self,
@@ -614,6 +872,9 @@ impl MessageProcessor {
             .execute(&self.pool)
             .await?;
 
+            self.record_failed_attempt(&message, worker_id, error, true)
+                .await;
+
             // Emit retry event
             let _ = self.event_tx.send(EngineEvent::MessageFailed {
                 queue_id: message.queue_id,
@@ -638,6 +899,9 @@ impl MessageProcessor {
             .execute(&self.pool)
             .await?;
 
+            self.record_failed_attempt(&message, worker_id, error, false)
+                .await;
+
             // Update statistics
             {
                 let mut stats = self.stats.write().await;
@@ -657,6 +921,33 @@ impl MessageProcessor {
         Ok(())
     }
 
+    /// Add a failed attempt to the message's history, shown when replaying
+    /// it. Never fails the acknowledgement itself.
+    async fn record_failed_attempt(
+        &self,
+        message: &Message,
+        worker_id: Uuid,
+        error: &str,
+        will_retry: bool,
+    ) {
+        let data = serde_json::json!({
+            "attempt": message.attempt_count,
+            "error": error,
+            "will_retry": will_retry,
+        });
+        if let Err(e) = record_history(
+            &self.pool,
+            message.id,
+            "attempt_failed",
+            data,
+            Some(worker_id),
+        )
+        .await
+        {
+            tracing::warn!(message_id = %message.id, "Failed to record attempt history: {}", e);
+        }
+    }
+
     /// Schedule a message for retry
     pub async fn schedule_retry(&self, message_id: Uuid, delay_ms: u64) -> Result<(), EngineError> {
         let retry_at = Utc::now() + Duration::milliseconds(delay_ms as i64);
@@ -697,11 +988,13 @@ impl MessageProcessor {
         .await?
         .ok_or(EngineError::MessageNotFound(id))?;
 
+        let payload = self.open_payload(row.payload).await?;
+
         Ok(Message {
             id: row.id,
             queue_id: row.queue_id,
             message_type: row.message_type,
-            payload: row.payload,
+            payload,
             headers: row.headers.unwrap_or(serde_json::json!({})),
             priority: row.priority.unwrap_or(0),
             status: MessageStatus::from(row.status),
@@ -859,6 +1152,114 @@ impl MessageProcessor {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Extend the lease on a claimed message (worker heartbeat)
+    ///
+    /// The lease is pushed to at least `extra_ms` from now, but never past
+    /// the claim's maximum processing time. Only the worker holding a live
+    /// lease may extend it: once the lease has lapsed the message may already
+    /// have been reclaimed, so the worker must stop. A message that has run
+    /// past its maximum processing time is failed. Returns the new deadline.
+    pub async fn extend_lease(
+        &self,
+        message_id: Uuid,
+        worker_id: Uuid,
+        extra_ms: u64,
+    ) -> Result<DateTime<Utc>, EngineError> {
+        let now = Utc::now();
+        let extra = Duration::milliseconds(extra_ms.min(i64::MAX as u64) as i64);
+        let max_ms = self.max_processing_time.num_milliseconds();
+
+        let extended: Option<DateTime<Utc>> = sqlx::query_scalar(
+            r#"
+            UPDATE vqm_messages
+            SET visibility_timeout_at = LEAST(
+                    GREATEST(visibility_timeout_at, $3),
+                    processing_started_at + INTERVAL '1 millisecond' * $5
+                )
+            WHERE id = $1 AND claimed_by = $2 AND status = 'processing'
+            AND visibility_timeout_at >= $4
+            AND processing_started_at + INTERVAL '1 millisecond' * $5 > $4
+            RETURNING visibility_timeout_at
+            "#,
+        )
+        .bind(message_id)
+        .bind(worker_id)
+        .bind(now + extra)
+        .bind(now)
+        .bind(max_ms)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if let Some(deadline) = extended {
+            return Ok(deadline);
+        }
+
+        let lease: LeaseRow = sqlx::query_as::<_, LeaseRow>(
+            r#"
+            SELECT status, claimed_by, processing_started_at, visibility_timeout_at
+            FROM vqm_messages WHERE id = $1
+            "#,
+        )
+        .bind(message_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(EngineError::MessageNotFound(message_id))?;
+
+        let error = lease_rejection(message_id, worker_id, &lease, now, self.max_processing_time);
+        if matches!(error, EngineError::ProcessingTimeExceeded(_)) {
+            self.fail_overrunning_messages().await?;
+        }
+        Err(error)
+    }
+
+    /// Fail claimed messages that ran past the maximum processing time
+    ///
+    /// These are failed outright rather than retried: a handler that keeps
+    /// extending its lease is likely stuck, and retrying would only repeat
+    /// that.
+    pub async fn fail_overrunning_messages(&self) -> Result<u64, EngineError> {
+        let error = format!(
+            "Exceeded maximum processing time of {}s",
+            self.max_processing_time.num_seconds()
+        );
+
+        let rows: Vec<OverrunRow> = sqlx::query_as::<_, OverrunRow>(
+            r#"
+            UPDATE vqm_messages
+            SET status = 'failed',
+                completed_at = $1,
+                visibility_timeout_at = NULL,
+                last_error = $3
+            WHERE status = 'processing'
+            AND processing_started_at + INTERVAL '1 millisecond' * $2 <= $1
+            RETURNING id, queue_id
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(self.max_processing_time.num_milliseconds())
+        .bind(&error)
+        .fetch_all(&self.pool)
+        .await?;
+
+        if !rows.is_empty() {
+            let mut stats = self.stats.write().await;
+            stats.total_failed += rows.len() as u64;
+            stats.recent_failures += rows.len() as u64;
+        }
+
+        for row in &rows {
+            tracing::warn!(message_id = %row.id, "{}", error);
+            let _ = self.event_tx.send(EngineEvent::MessageFailed {
+                queue_id: row.queue_id,
+                message_id: row.id,
+                error: error.clone(),
+                will_retry: false,
+            });
+        }
+
+        Ok(rows.len() as u64)
+    }
+
     /// Extend visibility timeout for a message
     pub async fn extend_visibility(
         &self,
@@ -882,3 +1283,185 @@ impl MessageProcessor {
         Ok(())
     }
 }
+
+/// Why a lease extension was refused
+fn lease_rejection(
+    message_id: Uuid,
+    worker_id: Uuid,
+    lease: &LeaseRow,
+    now: DateTime<Utc>,
+    max_processing_time: Duration,
+) -> EngineError {
+    if lease.status != "processing" || lease.claimed_by != Some(worker_id) {
+        return EngineError::LeaseNotHeld {
+            message_id,
+            worker_id,
+        };
+    }
+    if lease
+        .processing_started_at
+        .is_some_and(|started| started + max_processing_time <= now)
+    {
+        return EngineError::ProcessingTimeExceeded(message_id);
+    }
+    match lease.visibility_timeout_at {
+        // Lease was live when checked, so it changed under the update
+        Some(deadline) if deadline >= now => EngineError::Conflict(format!(
+            "Lease on message {} changed while being extended",
+            message_id
+        )),
+        // Still claimed by this worker, but due to be reclaimed
+        _ => EngineError::LeaseExpired(message_id),
+    }
+}
+
+/// Build one multi-row INSERT for a chunk of a batch
+fn insert_batch_query<'a>(
+    messages: &'a [Message],
+    payloads: &'a [serde_json::Value],
+) -> QueryBuilder<'a, Postgres> {
+    let mut builder = QueryBuilder::new(
+        "INSERT INTO vqm_messages (\
+            id, queue_id, message_type, payload, headers, priority, status, \
+            attempt_count, max_attempts, created_at, scheduled_at, \
+            deduplication_id, group_id, correlation_id, trace_id, metadata) ",
+    );
+    builder.push_values(
+        messages.iter().zip(payloads),
+        |mut row, (message, payload)| {
+            row.push_bind(message.id)
+                .push_bind(message.queue_id)
+                .push_bind(&message.message_type)
+                .push_bind(payload)
+                .push_bind(&message.headers)
+                .push_bind(message.priority)
+                .push_bind(message.status.to_string())
+                .push_bind(message.attempt_count)
+                .push_bind(message.max_attempts)
+                .push_bind(message.created_at)
+                .push_bind(message.scheduled_at)
+                .push_bind(&message.deduplication_id)
+                .push_bind(&message.group_id)
+                .push_bind(&message.correlation_id)
+                .push_bind(&message.trace_id)
+                .push_bind(&message.metadata);
+        },
+    );
+    builder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(index: usize) -> Message {
+        Message {
+            id: Uuid::new_v4(),
+            queue_id: Uuid::nil(),
+            message_type: "test".to_string(),
+            payload: serde_json::json!({ "index": index }),
+            headers: serde_json::json!({}),
+            priority: 0,
+            status: MessageStatus::Pending,
+            attempt_count: 0,
+            max_attempts: 3,
+            created_at: Utc::now(),
+            scheduled_at: None,
+            processing_started_at: None,
+            completed_at: None,
+            visibility_timeout_at: None,
+            deduplication_id: None,
+            group_id: None,
+            correlation_id: None,
+            trace_id: None,
+            claimed_by: None,
+            last_error: None,
+            metadata: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn test_insert_batch_query_binds_every_column() {
+        let messages: Vec<Message> = (0..3).map(message).collect();
+        let payloads: Vec<serde_json::Value> = messages.iter().map(|m| m.payload.clone()).collect();
+
+        let builder = insert_batch_query(&messages, &payloads);
+        let sql = builder.sql();
+
+        assert_eq!(sql.matches("INSERT INTO").count(), 1);
+        assert_eq!(sql.matches('$').count(), 3 * INSERT_COLUMNS);
+        assert!(sql.contains(&format!("${}", 3 * INSERT_COLUMNS)));
+    }
+
+    #[test]
+    fn test_batching_cuts_round_trips() {
+        let messages: Vec<Message> = (0..DEFAULT_MAX_BATCH_SIZE).map(message).collect();
+
+        // One at a time, every message costs a queue lookup and an insert
+        let unbatched = messages.len() * 2;
+        // A batch costs BEGIN, the queue lock, one INSERT per chunk and COMMIT
+        let inserts = messages.chunks(MAX_ROWS_PER_INSERT).count();
+        let batched = 3 + inserts;
+
+        assert_eq!(inserts, 1);
+        assert_eq!(batched, 4);
+        assert!(batched * 100 < unbatched);
+
+        // Even the largest chunk stays within the bind parameter limit
+        assert!(MAX_ROWS_PER_INSERT * INSERT_COLUMNS <= u16::MAX as usize);
+    }
+
+    fn lease(worker_id: Uuid, started_secs_ago: i64, expires_in_secs: i64) -> LeaseRow {
+        let now = Utc::now();
+        LeaseRow {
+            status: "processing".to_string(),
+            claimed_by: Some(worker_id),
+            processing_started_at: Some(now - Duration::seconds(started_secs_ago)),
+            visibility_timeout_at: Some(now + Duration::seconds(expires_in_secs)),
+        }
+    }
+
+    #[test]
+    fn test_lease_rejection_other_worker() {
+        let owner = Uuid::new_v4();
+        let error = lease_rejection(
+            Uuid::nil(),
+            Uuid::new_v4(),
+            &lease(owner, 10, 30),
+            Utc::now(),
+            Duration::seconds(DEFAULT_MAX_PROCESSING_TIME_SECS),
+        );
+        assert!(matches!(error, EngineError::LeaseNotHeld { .. }));
+
+        let mut released = lease(owner, 10, 30);
+        released.status = "pending".to_string();
+        released.claimed_by = None;
+        let error = lease_rejection(
+            Uuid::nil(),
+            owner,
+            &released,
+            Utc::now(),
+            Duration::seconds(DEFAULT_MAX_PROCESSING_TIME_SECS),
+        );
+        assert!(matches!(error, EngineError::LeaseNotHeld { .. }));
+    }
+
+    #[test]
+    fn test_lease_rejection_lapsed_or_overrun() {
+        let worker = Uuid::new_v4();
+        let max = Duration::seconds(600);
+
+        let error = lease_rejection(Uuid::nil(), worker, &lease(worker, 60, -5), Utc::now(), max);
+        assert!(matches!(error, EngineError::LeaseExpired(_)));
+
+        // Past the deadline wins over a lapsed lease: the message is failed
+        let error = lease_rejection(
+            Uuid::nil(),
+            worker,
+            &lease(worker, 601, -5),
+            Utc::now(),
+            max,
+        );
+        assert!(matches!(error, EngineError::ProcessingTimeExceeded(_)));
+    }
+}
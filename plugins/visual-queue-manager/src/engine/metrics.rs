@@ -3,9 +3,15 @@
 //! Collects and aggregates metrics for monitoring and alerting.
 
 use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
+use prometheus_client::encoding::text::encode;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::registry::Registry;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, PgPool, Row};
 use std::collections::HashMap;
+use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::RwLock;
@@ -51,6 +57,19 @@ struct TimeSeriesRow {
     count: i64,
 }
 
+/// Row type for the per-queue Prometheus refresh query
+#[derive(Debug, Clone, FromRow)]
+pub(crate) struct QueueGaugeRow {
+    name: String,
+    tenant: Option<String>,
+    pending: i64,
+    processing: i64,
+    dlq: i64,
+    completed: i64,
+    failed: i64,
+    avg_time: Option<f64>,
+}
+
 use super::EngineError;
 
 /// Metric type
@@ -208,6 +227,294 @@ pub struct ThroughputHistoryPoint {
     pub processed: i64,
 }
 
+/// Default cap on the number of queues exported as separate series
+pub const DEFAULT_MAX_QUEUE_SERIES: usize = 200;
+
+/// Label value used for queues folded together past the series cap
+const OVERFLOW_LABEL: &str = "_other";
+
+/// Label value for queues without a tenant when tenant labels are on
+const DEFAULT_TENANT_LABEL: &str = "default";
+
+/// Window used for rate-style gauges
+const RATE_WINDOW_SECS: f64 = 60.0;
+
+type Labels = Vec<(&'static str, String)>;
+type GaugeFamily = Family<Labels, Gauge<f64, AtomicU64>>;
+
+/// Prometheus gauges for the engine, refreshed on the collector tick.
+///
+/// Scrapes only encode the last refreshed values, so the cost of a scrape
+/// doesn't depend on queue or message volume. Queue series are capped at
+/// `max_queue_series`; the quietest queues past the cap are folded into a
+/// single `queue="_other"` series so a burst of queue creation can't blow up
+/// label cardinality.
+pub struct PrometheusExporter {
+    registry: Registry,
+    queue_depth: GaugeFamily,
+    queue_processing: GaugeFamily,
+    queue_dlq: GaugeFamily,
+    queue_messages_per_second: GaugeFamily,
+    queue_error_rate: GaugeFamily,
+    queue_processing_time: GaugeFamily,
+    workers: GaugeFamily,
+    dlq_depth: Gauge<f64, AtomicU64>,
+    last_refresh: Gauge<f64, AtomicU64>,
+    tenant_labels: bool,
+    max_queue_series: usize,
+    /// Held while families are rewritten so a scrape never sees half an update
+    update_lock: Mutex<()>,
+}
+
+impl PrometheusExporter {
+    /// Create an exporter; `tenant_labels` adds a `tenant` label to queue series
+    pub fn new(tenant_labels: bool, max_queue_series: usize) -> Self {
+        let mut registry = Registry::with_prefix("vqm");
+
+        let queue_depth = GaugeFamily::default();
+        registry.register(
+            "queue_depth",
+            "Pending messages per queue",
+            queue_depth.clone(),
+        );
+        let queue_processing = GaugeFamily::default();
+        registry.register(
+            "queue_processing",
+            "Messages being processed per queue",
+            queue_processing.clone(),
+        );
+        let queue_dlq = GaugeFamily::default();
+        registry.register(
+            "queue_dlq_depth",
+            "Dead-lettered messages per queue",
+            queue_dlq.clone(),
+        );
+        let queue_messages_per_second = GaugeFamily::default();
+        registry.register(
+            "queue_messages_per_second",
+            "Messages completed per second over the last minute",
+            queue_messages_per_second.clone(),
+        );
+        let queue_error_rate = GaugeFamily::default();
+        registry.register(
+            "queue_error_rate",
+            "Failed share of messages finished in the last minute",
+            queue_error_rate.clone(),
+        );
+        let queue_processing_time = GaugeFamily::default();
+        registry.register(
+            "queue_avg_processing_time_ms",
+            "Average processing time of messages finished in the last minute",
+            queue_processing_time.clone(),
+        );
+        let workers = GaugeFamily::default();
+        registry.register("workers", "Workers by status", workers.clone());
+        let dlq_depth = Gauge::<f64, AtomicU64>::default();
+        registry.register(
+            "dlq_depth",
+            "Total dead letter queue size",
+            dlq_depth.clone(),
+        );
+        let last_refresh = Gauge::<f64, AtomicU64>::default();
+        registry.register(
+            "metrics_last_refresh_timestamp_seconds",
+            "Unix time the gauges were last refreshed",
+            last_refresh.clone(),
+        );
+
+        Self {
+            registry,
+            queue_depth,
+            queue_processing,
+            queue_dlq,
+            queue_messages_per_second,
+            queue_error_rate,
+            queue_processing_time,
+            workers,
+            dlq_depth,
+            last_refresh,
+            tenant_labels,
+            max_queue_series: max_queue_series.max(1),
+            update_lock: Mutex::new(()),
+        }
+    }
+
+    /// Re-read queue, worker and DLQ state and update the gauges
+    pub async fn refresh(&self, pool: &PgPool) -> Result<(), EngineError> {
+        let queues: Vec<QueueGaugeRow> = sqlx::query_as::<_, QueueGaugeRow>(
+            r#"
+            SELECT
+                q.name,
+                q.metadata->>'tenant_id' as tenant,
+                COUNT(m.id) FILTER (WHERE m.status = 'pending') as pending,
+                COUNT(m.id) FILTER (WHERE m.status = 'processing') as processing,
+                COALESCE(d.dlq, 0) as dlq,
+                COUNT(m.id) FILTER (
+                    WHERE m.status = 'completed' AND m.completed_at > NOW() - INTERVAL '1 minute'
+                ) as completed,
+                COUNT(m.id) FILTER (
+                    WHERE m.status = 'failed' AND m.completed_at > NOW() - INTERVAL '1 minute'
+                ) as failed,
+                AVG(EXTRACT(EPOCH FROM (m.completed_at - m.processing_started_at)) * 1000)
+                    FILTER (
+                        WHERE m.status = 'completed'
+                        AND m.completed_at > NOW() - INTERVAL '1 minute'
+                    ) as avg_time
+            FROM vqm_queues q
+            LEFT JOIN vqm_messages m ON q.id = m.queue_id
+            LEFT JOIN (
+                SELECT queue_id, COUNT(*) as dlq
+                FROM vqm_dead_letter_queue
+                GROUP BY queue_id
+            ) d ON d.queue_id = q.id
+            GROUP BY q.id, q.name, d.dlq
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let workers: Vec<(String, i64)> = sqlx::query_as::<_, (String, i64)>(
+            r#"
+            SELECT status, COUNT(*)
+            FROM vqm_workers
+            GROUP BY status
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let dlq_depth: i64 =
+            sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM vqm_dead_letter_queue"#)
+                .fetch_one(pool)
+                .await?;
+
+        self.apply(queues, &workers, dlq_depth);
+        Ok(())
+    }
+
+    /// Replace the gauge values with a fresh sample
+    pub(crate) fn apply(
+        &self,
+        mut queues: Vec<QueueGaugeRow>,
+        workers: &[(String, i64)],
+        dlq_depth: i64,
+    ) {
+        // Busiest queues keep their own series; the rest share one
+        queues.sort_by(|a, b| {
+            (b.pending + b.processing + b.dlq)
+                .cmp(&(a.pending + a.processing + a.dlq))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        let overflow = if queues.len() > self.max_queue_series {
+            let folded = queues.split_off(self.max_queue_series - 1);
+            Some(fold_queues(folded))
+        } else {
+            None
+        };
+
+        let _guard = self.update_lock.lock();
+
+        for family in [
+            &self.queue_depth,
+            &self.queue_processing,
+            &self.queue_dlq,
+            &self.queue_messages_per_second,
+            &self.queue_error_rate,
+            &self.queue_processing_time,
+            &self.workers,
+        ] {
+            family.clear();
+        }
+
+        for row in queues.iter().chain(overflow.as_ref()) {
+            let labels = self.queue_labels(row);
+            let finished = row.completed + row.failed;
+
+            self.queue_depth
+                .get_or_create(&labels)
+                .set(row.pending as f64);
+            self.queue_processing
+                .get_or_create(&labels)
+                .set(row.processing as f64);
+            self.queue_dlq.get_or_create(&labels).set(row.dlq as f64);
+            self.queue_messages_per_second
+                .get_or_create(&labels)
+                .set(row.completed as f64 / RATE_WINDOW_SECS);
+            self.queue_error_rate
+                .get_or_create(&labels)
+                .set(if finished > 0 {
+                    row.failed as f64 / finished as f64
+                } else {
+                    0.0
+                });
+            self.queue_processing_time
+                .get_or_create(&labels)
+                .set(row.avg_time.unwrap_or(0.0));
+        }
+
+        for (status, count) in workers {
+            self.workers
+                .get_or_create(&vec![("status", status.clone())])
+                .set(*count as f64);
+        }
+
+        self.dlq_depth.set(dlq_depth as f64);
+        self.last_refresh
+            .set(Utc::now().timestamp_millis() as f64 / 1000.0);
+    }
+
+    /// Encode the current values in the Prometheus text format
+    pub fn encode(&self) -> String {
+        let _guard = self.update_lock.lock();
+        let mut output = String::new();
+        if let Err(e) = encode(&mut output, &self.registry) {
+            tracing::error!("Failed to encode Prometheus metrics: {}", e);
+        }
+        output
+    }
+
+    fn queue_labels(&self, row: &QueueGaugeRow) -> Labels {
+        let mut labels = vec![("queue", row.name.clone())];
+        if self.tenant_labels {
+            let tenant = row
+                .tenant
+                .clone()
+                .unwrap_or_else(|| DEFAULT_TENANT_LABEL.to_string());
+            labels.push(("tenant", tenant));
+        }
+        labels
+    }
+}
+
+/// Sum a set of queues into the overflow series
+fn fold_queues(rows: Vec<QueueGaugeRow>) -> QueueGaugeRow {
+    let mut folded = QueueGaugeRow {
+        name: OVERFLOW_LABEL.to_string(),
+        tenant: Some(OVERFLOW_LABEL.to_string()),
+        pending: 0,
+        processing: 0,
+        dlq: 0,
+        completed: 0,
+        failed: 0,
+        avg_time: None,
+    };
+
+    let mut weighted_time = 0.0;
+    for row in rows {
+        folded.pending += row.pending;
+        folded.processing += row.processing;
+        folded.dlq += row.dlq;
+        folded.completed += row.completed;
+        folded.failed += row.failed;
+        weighted_time += row.avg_time.unwrap_or(0.0) * row.completed as f64;
+    }
+    if folded.completed > 0 {
+        folded.avg_time = Some(weighted_time / folded.completed as f64);
+    }
+
+    folded
+}
+
 /// Metrics collector for the engine
 pub struct MetricsCollector {
     pool: PgPool,
@@ -220,6 +527,8 @@ pub struct MetricsCollector {
     gauges: RwLock<HashMap<String, f64>>,
     /// Processing time samples for percentile calculation
     processing_times: RwLock<Vec<f64>>,
+    /// Prometheus gauges refreshed on each collection tick
+    prometheus: Arc<PrometheusExporter>,
 }
 
 impl MetricsCollector {
@@ -233,9 +542,21 @@ impl MetricsCollector {
             counters: RwLock::new(HashMap::new()),
             gauges: RwLock::new(HashMap::new()),
             processing_times: RwLock::new(Vec::with_capacity(10000)),
+            prometheus: Arc::new(PrometheusExporter::new(false, DEFAULT_MAX_QUEUE_SERIES)),
         }
     }
 
+    /// Configure the Prometheus exporter's labels and series cap
+    pub fn with_prometheus(mut self, tenant_labels: bool, max_queue_series: usize) -> Self {
+        self.prometheus = Arc::new(PrometheusExporter::new(tenant_labels, max_queue_series));
+        self
+    }
+
+    /// Get the Prometheus exporter
+    pub fn prometheus(&self) -> Arc<PrometheusExporter> {
+        self.prometheus.clone()
+    }
+
     /// Start metrics collection
     pub async fn start(&self) -> Result<(), EngineError> {
         let mut running = self.running.write().await;
@@ -265,6 +586,7 @@ impl MetricsCollector {
         let pool = self.pool.clone();
         let running = self.running.clone();
         let interval = self.interval_secs;
+        let prometheus = self.prometheus.clone();
 
         tokio::spawn(async move {
             let mut ticker = tokio::time::interval(tokio::time::Duration::from_secs(interval));
@@ -279,6 +601,10 @@ impl MetricsCollector {
                 if let Err(e) = collect_and_store_metrics(&pool).await {
                     tracing::error!("Failed to collect metrics: {}", e);
                 }
+
+                if let Err(e) = prometheus.refresh(&pool).await {
+                    tracing::error!("Failed to refresh Prometheus metrics: {}", e);
+                }
             }
         });
     }
@@ -816,3 +1142,73 @@ fn get_memory_usage() -> u64 {
     // In production, you'd use a system-specific API
     0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(name: &str, tenant: Option<&str>, pending: i64) -> QueueGaugeRow {
+        QueueGaugeRow {
+            name: name.to_string(),
+            tenant: tenant.map(str::to_string),
+            pending,
+            processing: 1,
+            dlq: 0,
+            completed: 30,
+            failed: 10,
+            avg_time: Some(12.5),
+        }
+    }
+
+    #[test]
+    fn test_prometheus_export_queue_labels() {
+        let exporter = PrometheusExporter::new(true, DEFAULT_MAX_QUEUE_SERIES);
+        exporter.apply(
+            vec![queue("emails", Some("acme"), 7), queue("webhooks", None, 2)],
+            &[("busy".to_string(), 3), ("idle".to_string(), 1)],
+            4,
+        );
+
+        let output = exporter.encode();
+        assert!(output.contains("# TYPE vqm_queue_depth gauge"));
+        assert!(output.contains("vqm_queue_depth{queue=\"emails\",tenant=\"acme\"} 7.0"));
+        assert!(output.contains("vqm_queue_depth{queue=\"webhooks\",tenant=\"default\"} 2.0"));
+        assert!(
+            output.contains("vqm_queue_messages_per_second{queue=\"emails\",tenant=\"acme\"} 0.5")
+        );
+        assert!(output.contains("vqm_queue_error_rate{queue=\"emails\",tenant=\"acme\"} 0.25"));
+        assert!(output.contains("vqm_workers{status=\"busy\"} 3.0"));
+        assert!(output.contains("vqm_dlq_depth 4.0"));
+
+        let untenanted = PrometheusExporter::new(false, DEFAULT_MAX_QUEUE_SERIES);
+        untenanted.apply(vec![queue("emails", Some("acme"), 7)], &[], 0);
+        assert!(untenanted
+            .encode()
+            .contains("vqm_queue_depth{queue=\"emails\"} 7.0"));
+    }
+
+    #[test]
+    fn test_prometheus_export_caps_queue_series() {
+        let exporter = PrometheusExporter::new(false, 3);
+        let queues = (0..10)
+            .map(|i| queue(&format!("queue-{}", i), None, i))
+            .collect();
+        exporter.apply(queues, &[], 0);
+
+        let output = exporter.encode();
+        let depth_series: Vec<&str> = output
+            .lines()
+            .filter(|l| l.starts_with("vqm_queue_depth{"))
+            .collect();
+        assert_eq!(depth_series.len(), 3);
+        assert!(output.contains("vqm_queue_depth{queue=\"queue-9\"} 9.0"));
+        assert!(output.contains("vqm_queue_depth{queue=\"queue-8\"} 8.0"));
+        // queue-0 ..= queue-7 folded together
+        assert!(output.contains("vqm_queue_depth{queue=\"_other\"} 28.0"));
+        assert!(output.contains("vqm_queue_avg_processing_time_ms{queue=\"_other\"} 12.5"));
+
+        // Series for queues that disappear are dropped on the next refresh
+        exporter.apply(vec![queue("queue-9", None, 1)], &[], 0);
+        assert!(!exporter.encode().contains("queue-8"));
+    }
+}
@@ -15,8 +15,10 @@ pub mod dlq;
 pub mod message;
 pub mod metrics;
 pub mod queue;
+pub mod replay;
 pub mod retry;
 pub mod scheduler;
+pub mod schema;
 pub mod storage;
 pub mod worker;
 
@@ -32,10 +34,11 @@ pub use circuit_breaker::{CircuitBreaker, CircuitConfig, CircuitState};
 pub use dispatcher::{DispatchConfig, DispatchResult, EventDispatcher};
 pub use dlq::{DeadLetterQueue, DlqPolicy};
 pub use message::{MessageBatch, MessageProcessor, ProcessingResult};
-pub use metrics::{EngineMetrics, MetricsCollector};
+pub use metrics::{EngineMetrics, MetricsCollector, PrometheusExporter};
 pub use queue::{QueueConfig, QueueManager, QueueState};
+pub use replay::{AuditActor, DlqReplay, FailedMessage, FailedSource, ReplayOutcome};
 pub use retry::{BackoffCalculator, RetryPolicy, RetryStrategy};
-pub use scheduler::{JobConfig, JobScheduler, ScheduledJob};
+pub use scheduler::{JobConfig, JobScheduler, MissedRunPolicy, ScheduleType, ScheduledJob};
 pub use storage::{PostgresStorage, StorageBackend};
 pub use worker::{WorkerConfig, WorkerHandle, WorkerPool, WorkerState};
 
@@ -44,14 +47,28 @@ pub use worker::{WorkerConfig, WorkerHandle, WorkerPool, WorkerState};
 pub struct EngineConfig {
     /// Maximum concurrent message processing
     pub max_concurrent_processing: usize,
-    /// Default message timeout in seconds
+    /// Default message timeout in seconds: the lease a worker gets when it
+    /// claims a message, extendable with `MessageProcessor::extend_lease`
     pub default_message_timeout_secs: u64,
+    /// Longest a claimed message may be processed, however often its lease
+    /// is extended, before it is failed
+    #[serde(default = "default_max_processing_time_secs")]
+    pub max_processing_time_secs: u64,
+    /// How often lapsed leases are reclaimed, in seconds
+    #[serde(default = "default_lease_check_interval_secs")]
+    pub lease_check_interval_secs: u64,
     /// Worker heartbeat interval in seconds
     pub worker_heartbeat_interval_secs: u64,
     /// Stale worker threshold in seconds
     pub stale_worker_threshold_secs: u64,
     /// Metrics collection interval in seconds
     pub metrics_interval_secs: u64,
+    /// Add a `tenant` label to exported queue metrics (enable with multi-tenancy)
+    #[serde(default)]
+    pub metrics_tenant_labels: bool,
+    /// Maximum number of queues exported as separate Prometheus series
+    #[serde(default = "default_max_metric_queue_series")]
+    pub max_metric_queue_series: usize,
     /// Enable dead letter queue
     pub enable_dlq: bool,
     /// Maximum retry attempts
@@ -66,6 +83,9 @@ pub struct EngineConfig {
     pub circuit_breaker_reset_secs: u64,
     /// Batch size for processing
     pub batch_size: usize,
+    /// Maximum messages per batch enqueue or acknowledge
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
     /// Cleanup interval for old messages in hours
     pub cleanup_interval_hours: u64,
     /// Message retention days
@@ -76,10 +96,14 @@ impl Default for EngineConfig {
     fn default() -> Self {
         Self {
             max_concurrent_processing: 100,
-            default_message_timeout_secs: 300,
+            default_message_timeout_secs: message::DEFAULT_VISIBILITY_TIMEOUT_SECS as u64,
+            max_processing_time_secs: default_max_processing_time_secs(),
+            lease_check_interval_secs: default_lease_check_interval_secs(),
             worker_heartbeat_interval_secs: 30,
             stale_worker_threshold_secs: 90,
             metrics_interval_secs: 10,
+            metrics_tenant_labels: false,
+            max_metric_queue_series: metrics::DEFAULT_MAX_QUEUE_SERIES,
             enable_dlq: true,
             max_retry_attempts: 3,
             base_retry_delay_ms: 1000,
@@ -87,12 +111,29 @@ impl Default for EngineConfig {
             circuit_breaker_threshold: 5,
             circuit_breaker_reset_secs: 60,
             batch_size: 100,
+            max_batch_size: message::DEFAULT_MAX_BATCH_SIZE,
             cleanup_interval_hours: 24,
             message_retention_days: 30,
         }
     }
 }
 
+fn default_max_processing_time_secs() -> u64 {
+    message::DEFAULT_MAX_PROCESSING_TIME_SECS as u64
+}
+
+fn default_lease_check_interval_secs() -> u64 {
+    5
+}
+
+fn default_max_metric_queue_series() -> usize {
+    metrics::DEFAULT_MAX_QUEUE_SERIES
+}
+
+fn default_max_batch_size() -> usize {
+    message::DEFAULT_MAX_BATCH_SIZE
+}
+
 /// Engine event for internal communication
 #[derive(Debug, Clone)]
 pub enum EngineEvent {
@@ -152,7 +193,11 @@ pub enum EngineEvent {
         new_state: CircuitState,
     },
     /// Scheduled job executed
-    ScheduledJobExecuted { job_id: Uuid, success: bool },
+    ScheduledJobExecuted {
+        job_id: Uuid,
+        success: bool,
+        error: Option<String>,
+    },
     /// Alert triggered
     AlertTriggered { alert_id: Uuid, message: String },
 }
@@ -208,13 +253,22 @@ impl QueueEngine {
             },
         );
 
-        let message_processor = Arc::new(MessageProcessor::new(
-            pool.clone(),
-            storage.clone(),
-            retry_policy,
-            event_tx.clone(),
-            config.batch_size,
-        ));
+        let message_processor = Arc::new(
+            MessageProcessor::new(
+                pool.clone(),
+                storage.clone(),
+                retry_policy,
+                event_tx.clone(),
+                config.batch_size,
+            )
+            .with_max_batch_size(config.max_batch_size)
+            .with_visibility_timeout(chrono::Duration::seconds(
+                config.default_message_timeout_secs as i64,
+            ))
+            .with_max_processing_time(chrono::Duration::seconds(
+                config.max_processing_time_secs as i64,
+            )),
+        );
 
         let worker_pool = Arc::new(WorkerPool::new(
             pool.clone(),
@@ -233,10 +287,10 @@ impl QueueEngine {
 
         let job_scheduler = Arc::new(JobScheduler::new(pool.clone(), event_tx.clone()));
 
-        let metrics = Arc::new(MetricsCollector::new(
-            pool.clone(),
-            config.metrics_interval_secs,
-        ));
+        let metrics = Arc::new(
+            MetricsCollector::new(pool.clone(), config.metrics_interval_secs)
+                .with_prometheus(config.metrics_tenant_labels, config.max_metric_queue_series),
+        );
 
         let dlq = Arc::new(DeadLetterQueue::new(
             pool.clone(),
@@ -280,6 +334,9 @@ impl QueueEngine {
         // Start cleanup task
         self.start_cleanup_task().await?;
 
+        // Reclaim messages whose lease lapsed
+        self.start_lease_reaper().await?;
+
         tracing::info!("Queue engine started successfully");
         Ok(())
     }
@@ -395,6 +452,41 @@ impl QueueEngine {
         Ok(())
     }
 
+    /// Start the task that fails overrunning messages and returns messages
+    /// with lapsed leases to their queue
+    async fn start_lease_reaper(&self) -> Result<(), EngineError> {
+        let processor = self.message_processor.clone();
+        let running = self.running.clone();
+        let check_interval = self.config.lease_check_interval_secs.max(1);
+
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(tokio::time::Duration::from_secs(check_interval));
+
+            loop {
+                interval.tick().await;
+
+                if !*running.read().await {
+                    break;
+                }
+
+                // Overruns first, so they are failed rather than released
+                if let Err(e) = processor.fail_overrunning_messages().await {
+                    tracing::error!("Failed to fail overrunning messages: {}", e);
+                }
+                match processor.release_timed_out_messages().await {
+                    Ok(0) => {}
+                    Ok(released) => {
+                        tracing::info!(released, "Reclaimed messages with lapsed leases")
+                    }
+                    Err(e) => tracing::error!("Failed to release timed out messages: {}", e),
+                }
+            }
+        });
+
+        Ok(())
+    }
+
     /// Get the queue manager
     pub fn queue_manager(&self) -> Arc<QueueManager> {
         self.queue_manager.clone()
@@ -425,11 +517,30 @@ impl QueueEngine {
         self.metrics.clone()
     }
 
+    /// Encrypt message payloads at rest from now on
+    pub fn enable_payload_encryption(
+        &self,
+        encryptor: Arc<crate::enterprise::PayloadEncryptor>,
+    ) -> Result<(), EngineError> {
+        self.job_scheduler
+            .enable_payload_encryption(encryptor.clone())?;
+        self.message_processor.enable_payload_encryption(encryptor)
+    }
+
     /// Get the DLQ handler
     pub fn dlq(&self) -> Arc<DeadLetterQueue> {
         self.dlq.clone()
     }
 
+    /// Create a replay service for failed messages
+    pub fn dlq_replay(&self) -> Arc<DlqReplay> {
+        Arc::new(DlqReplay::new(
+            self.pool.clone(),
+            self.message_processor.clone(),
+            self.event_tx.clone(),
+        ))
+    }
+
     /// Subscribe to engine events
     pub fn subscribe_events(&self) -> broadcast::Receiver<EngineEvent> {
         self.event_tx.subscribe()
@@ -547,6 +658,13 @@ pub enum EngineError {
     #[error("Message not found: {0}")]
     MessageNotFound(Uuid),
 
+    #[error("Queue {queue_id} is full ({depth} of {max_size} messages)")]
+    QueueFull {
+        queue_id: Uuid,
+        depth: i64,
+        max_size: i64,
+    },
+
     #[error("Worker not found: {0}")]
     WorkerNotFound(Uuid),
 
@@ -559,6 +677,15 @@ pub enum EngineError {
     #[error("Message timeout")]
     MessageTimeout,
 
+    #[error("Worker {worker_id} does not hold the lease on message {message_id}")]
+    LeaseNotHeld { message_id: Uuid, worker_id: Uuid },
+
+    #[error("Lease on message {0} has expired")]
+    LeaseExpired(Uuid),
+
+    #[error("Message {0} exceeded the maximum processing time")]
+    ProcessingTimeExceeded(Uuid),
+
     #[error("Retry limit exceeded")]
     RetryLimitExceeded,
 
@@ -571,6 +698,18 @@ pub enum EngineError {
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+
+    #[error("Message cannot be retried: {0}")]
+    NotRetryable(Uuid),
+
+    #[error("Payload does not match the handler schema: {}", .0.join("; "))]
+    SchemaViolation(Vec<String>),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
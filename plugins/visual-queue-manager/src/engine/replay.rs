@@ -0,0 +1,612 @@
+//! Dead Letter Replay Module
+//!
+//! Backend for the admin replay screen. Failed messages are either dead
+//! lettered (in `vqm_dead_letter_queue`) or left in `failed` status when no
+//! DLQ is configured; both are listed, inspected, replayed and deleted here.
+//!
+//! Every change is written to `vqm_audit_logs` with the state before and
+//! after, in the same transaction as the change itself.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, PgPool};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use super::message::MessageProcessor;
+use super::schema::validate_payload;
+use super::{EngineError, EngineEvent};
+use crate::enterprise::is_encrypted_payload;
+use crate::models::AuditAction;
+
+/// Failed messages across both sources, with common columns
+const FAILED_MESSAGES: &str = r#"
+    SELECT 'dead_letter' AS source, d.id, d.original_message_id AS message_id,
+           d.queue_id, d.message_type, d.payload, d.headers, d.last_error, d.reason,
+           COALESCE(d.failure_count, 0) AS attempts,
+           COALESCE(d.retry_count, 0) AS retry_count,
+           d.moved_to_dlq_at AS failed_at,
+           COALESCE(d.can_retry, true) AS can_retry
+    FROM vqm_dead_letter_queue d
+    UNION ALL
+    SELECT 'failed', m.id, m.id, m.queue_id, m.message_type, m.payload, m.headers,
+           m.last_error, NULL, m.attempt_count, 0,
+           COALESCE(m.completed_at, m.created_at), true
+    FROM vqm_messages m
+    WHERE m.status = 'failed'
+"#;
+
+/// Where a failed message is held
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailedSource {
+    /// Moved to the dead letter queue
+    DeadLetter,
+    /// Out of attempts, still in its queue with `failed` status
+    Failed,
+}
+
+impl FailedSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DeadLetter => "dead_letter",
+            Self::Failed => "failed",
+        }
+    }
+
+    fn parse(source: &str) -> Self {
+        match source {
+            "dead_letter" => Self::DeadLetter,
+            _ => Self::Failed,
+        }
+    }
+}
+
+/// One entry in a message's processing history
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct HistoryEntry {
+    #[serde(skip)]
+    pub message_id: Uuid,
+    pub event_type: String,
+    pub event_data: serde_json::Value,
+    pub worker_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A failed message with its retry history
+///
+/// `payload` is as stored, so it is sealed when payload encryption is on.
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedMessage {
+    /// DLQ entry ID for dead lettered messages, message ID otherwise
+    pub id: Uuid,
+    pub source: FailedSource,
+    /// ID of the message that failed
+    pub message_id: Uuid,
+    pub queue_id: Uuid,
+    pub message_type: String,
+    pub payload: serde_json::Value,
+    pub headers: serde_json::Value,
+    pub last_error: Option<String>,
+    /// Why the message was dead lettered
+    pub reason: Option<String>,
+    pub attempts: i32,
+    /// Times this entry has already been replayed
+    pub retry_count: i32,
+    pub failed_at: DateTime<Utc>,
+    pub can_retry: bool,
+    pub history: Vec<HistoryEntry>,
+}
+
+impl FailedMessage {
+    /// State recorded in the audit log
+    fn audit_state(&self) -> serde_json::Value {
+        serde_json::json!({
+            "source": self.source,
+            "message_id": self.message_id,
+            "queue_id": self.queue_id,
+            "message_type": self.message_type,
+            "payload": self.payload,
+            "last_error": self.last_error,
+            "attempts": self.attempts,
+            "retry_count": self.retry_count,
+            "can_retry": self.can_retry,
+        })
+    }
+}
+
+#[derive(FromRow)]
+struct FailedMessageRow {
+    source: String,
+    id: Uuid,
+    message_id: Uuid,
+    queue_id: Uuid,
+    message_type: String,
+    payload: serde_json::Value,
+    headers: Option<serde_json::Value>,
+    last_error: Option<String>,
+    reason: Option<String>,
+    attempts: i32,
+    retry_count: i32,
+    failed_at: DateTime<Utc>,
+    can_retry: bool,
+}
+
+impl FailedMessageRow {
+    fn into_message(self, history: Vec<HistoryEntry>) -> FailedMessage {
+        FailedMessage {
+            id: self.id,
+            source: FailedSource::parse(&self.source),
+            message_id: self.message_id,
+            queue_id: self.queue_id,
+            message_type: self.message_type,
+            payload: self.payload,
+            headers: self.headers.unwrap_or(serde_json::json!({})),
+            last_error: self.last_error,
+            reason: self.reason,
+            attempts: self.attempts,
+            retry_count: self.retry_count,
+            failed_at: self.failed_at,
+            can_retry: self.can_retry,
+            history,
+        }
+    }
+}
+
+/// User an action is performed by, for the audit log
+#[derive(Debug, Clone)]
+pub struct AuditActor {
+    pub id: Uuid,
+    pub name: String,
+}
+
+/// Result of replaying a failed message
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayOutcome {
+    /// Message that will be processed again
+    pub message_id: Uuid,
+    /// Whether the payload was replaced
+    pub edited: bool,
+    /// Whether a handler schema was found and checked
+    pub schema_checked: bool,
+}
+
+/// Inspect, replay and delete failed messages
+pub struct DlqReplay {
+    pool: PgPool,
+    processor: Arc<MessageProcessor>,
+    event_tx: broadcast::Sender<EngineEvent>,
+}
+
+impl DlqReplay {
+    /// Create a replay service sealing payloads through `processor`
+    pub fn new(
+        pool: PgPool,
+        processor: Arc<MessageProcessor>,
+        event_tx: broadcast::Sender<EngineEvent>,
+    ) -> Self {
+        Self {
+            pool,
+            processor,
+            event_tx,
+        }
+    }
+
+    /// List failed messages, newest first, with their history
+    pub async fn list(
+        &self,
+        queue_id: Option<Uuid>,
+        source: Option<FailedSource>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<FailedMessage>, i64), EngineError> {
+        let filter =
+            "($1::uuid IS NULL OR f.queue_id = $1) AND ($2::text IS NULL OR f.source = $2)";
+        let source = source.map(|s| s.as_str());
+
+        let total: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM ({}) f WHERE {}",
+            FAILED_MESSAGES, filter
+        ))
+        .bind(queue_id)
+        .bind(source)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let rows: Vec<FailedMessageRow> = sqlx::query_as(&format!(
+            "SELECT * FROM ({}) f WHERE {} ORDER BY f.failed_at DESC OFFSET $3 LIMIT $4",
+            FAILED_MESSAGES, filter
+        ))
+        .bind(queue_id)
+        .bind(source)
+        .bind(offset)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let ids: Vec<Uuid> = rows.iter().map(|r| r.message_id).collect();
+        let mut history = self.history(&ids).await?;
+        let messages = rows
+            .into_iter()
+            .map(|row| {
+                let entries = history.remove(&row.message_id).unwrap_or_default();
+                row.into_message(entries)
+            })
+            .collect();
+
+        Ok((messages, total))
+    }
+
+    /// Get a failed message by DLQ entry or message ID
+    pub async fn get(&self, id: Uuid) -> Result<FailedMessage, EngineError> {
+        let row = self.fetch(&self.pool, id).await?;
+        let history = self
+            .history(&[row.message_id])
+            .await?
+            .remove(&row.message_id)
+            .unwrap_or_default();
+        Ok(row.into_message(history))
+    }
+
+    /// Decrypt a payload for display, recording who saw it
+    pub async fn reveal_payload(
+        &self,
+        message: &FailedMessage,
+        actor: &AuditActor,
+    ) -> Result<serde_json::Value, EngineError> {
+        if !is_encrypted_payload(&message.payload) {
+            return Ok(message.payload.clone());
+        }
+        let payload = self.processor.open_payload(message.payload.clone()).await?;
+        let mut conn = self.pool.acquire().await?;
+        write_audit(
+            &mut conn,
+            AuditChange {
+                event_type: "dlq.payload_viewed",
+                action: AuditAction::DlqPayloadView,
+                entity_id: message.id,
+                actor,
+                old_values: None,
+                new_values: None,
+            },
+        )
+        .await?;
+        Ok(payload)
+    }
+
+    /// Process a failed message again, optionally with an edited payload.
+    ///
+    /// The payload is checked against the schema of the handler for the
+    /// message type before anything changes, and rejected with
+    /// [`EngineError::SchemaViolation`] if it does not match.
+    pub async fn replay(
+        &self,
+        id: Uuid,
+        payload: Option<serde_json::Value>,
+        actor: &AuditActor,
+    ) -> Result<ReplayOutcome, EngineError> {
+        let before = self.get(id).await?;
+        if !before.can_retry {
+            return Err(EngineError::NotRetryable(id));
+        }
+
+        let edited = payload.is_some();
+        let plaintext = match payload {
+            Some(payload) => payload,
+            None => self.processor.open_payload(before.payload.clone()).await?,
+        };
+        let schema = self
+            .handler_schema(before.queue_id, &before.message_type)
+            .await?;
+        if let Some(schema) = &schema {
+            validate_payload(schema, &plaintext).map_err(EngineError::SchemaViolation)?;
+        }
+        let stored = if edited {
+            self.processor.seal_payload(&plaintext).await?
+        } else {
+            before.payload.clone()
+        };
+
+        let mut tx = self.pool.begin().await?;
+        // Lock the entry so two admins can't replay it at once
+        if self.lock(&mut tx, &before).await? != before.retry_count {
+            return Err(EngineError::Conflict(format!(
+                "Message {} was replayed by someone else",
+                id
+            )));
+        }
+
+        let message_id = match before.source {
+            FailedSource::DeadLetter => {
+                let message_id = Uuid::new_v4();
+                sqlx::query(
+                    r#"
+                    INSERT INTO vqm_messages (
+                        id, queue_id, message_type, payload, headers, status,
+                        attempt_count, created_at, metadata
+                    )
+                    SELECT $1, queue_id, message_type, $2, headers, 'pending', 0, NOW(), metadata
+                    FROM vqm_dead_letter_queue WHERE id = $3
+                    "#,
+                )
+                .bind(message_id)
+                .bind(&stored)
+                .bind(before.id)
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query(
+                    r#"
+                    UPDATE vqm_dead_letter_queue
+                    SET retry_count = COALESCE(retry_count, 0) + 1,
+                        last_retry_at = NOW(),
+                        retried_message_id = $2
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(before.id)
+                .bind(message_id)
+                .execute(&mut *tx)
+                .await?;
+                message_id
+            }
+            FailedSource::Failed => {
+                sqlx::query(
+                    r#"
+                    UPDATE vqm_messages
+                    SET payload = $2, status = 'pending', attempt_count = 0,
+                        last_error = NULL, completed_at = NULL
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(before.id)
+                .bind(&stored)
+                .execute(&mut *tx)
+                .await?;
+                before.id
+            }
+        };
+
+        record_history(
+            &mut *tx,
+            before.message_id,
+            "replayed",
+            serde_json::json!({
+                "replayed_as": message_id,
+                "edited": edited,
+                "by": actor.name,
+            }),
+            None,
+        )
+        .await?;
+
+        let after = serde_json::json!({
+            "status": "pending",
+            "replayed_as": message_id,
+            "payload": stored,
+            "retry_count": before.retry_count + 1,
+        });
+        write_audit(
+            &mut tx,
+            AuditChange {
+                event_type: "dlq.replayed",
+                action: if edited {
+                    AuditAction::DlqEditReplay
+                } else {
+                    AuditAction::DlqReplay
+                },
+                entity_id: before.id,
+                actor,
+                old_values: Some(before.audit_state()),
+                new_values: Some(after),
+            },
+        )
+        .await?;
+        tx.commit().await?;
+
+        let _ = self.event_tx.send(EngineEvent::MessageEnqueued {
+            queue_id: before.queue_id,
+            message_id,
+            priority: 0,
+        });
+        tracing::info!(
+            failed_id = %before.id,
+            message_id = %message_id,
+            edited,
+            actor = %actor.name,
+            "Failed message replayed"
+        );
+
+        Ok(ReplayOutcome {
+            message_id,
+            edited,
+            schema_checked: schema.is_some(),
+        })
+    }
+
+    /// Delete a failed message
+    pub async fn delete(&self, id: Uuid, actor: &AuditActor) -> Result<(), EngineError> {
+        let before = self.get(id).await?;
+
+        let mut tx = self.pool.begin().await?;
+        let result = match before.source {
+            FailedSource::DeadLetter => {
+                sqlx::query("DELETE FROM vqm_dead_letter_queue WHERE id = $1")
+                    .bind(before.id)
+                    .execute(&mut *tx)
+                    .await?
+            }
+            FailedSource::Failed => {
+                sqlx::query("DELETE FROM vqm_messages WHERE id = $1 AND status = 'failed'")
+                    .bind(before.id)
+                    .execute(&mut *tx)
+                    .await?
+            }
+        };
+        if result.rows_affected() == 0 {
+            return Err(EngineError::MessageNotFound(id));
+        }
+
+        write_audit(
+            &mut tx,
+            AuditChange {
+                event_type: "dlq.deleted",
+                action: AuditAction::DlqDelete,
+                entity_id: before.id,
+                actor,
+                old_values: Some(before.audit_state()),
+                new_values: None,
+            },
+        )
+        .await?;
+        tx.commit().await?;
+
+        tracing::info!(failed_id = %before.id, actor = %actor.name, "Failed message deleted");
+        Ok(())
+    }
+
+    /// JSON Schema the handler for `message_type` expects, preferring a
+    /// handler bound to the queue over a global one
+    async fn handler_schema(
+        &self,
+        queue_id: Uuid,
+        message_type: &str,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        let schema = sqlx::query_scalar(
+            r#"
+            SELECT payload_schema FROM vqm_event_handlers
+            WHERE event_type = $1 AND (queue_id = $2 OR queue_id IS NULL)
+            AND is_active AND payload_schema IS NOT NULL
+            ORDER BY queue_id NULLS LAST, priority DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(message_type)
+        .bind(queue_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(schema)
+    }
+
+    async fn fetch<'e, E>(&self, executor: E, id: Uuid) -> Result<FailedMessageRow, EngineError>
+    where
+        E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as(&format!(
+            "SELECT * FROM ({}) f WHERE f.id = $1",
+            FAILED_MESSAGES
+        ))
+        .bind(id)
+        .fetch_optional(executor)
+        .await?
+        .ok_or(EngineError::MessageNotFound(id))
+    }
+
+    /// Lock a failed message's row, returning its current retry count
+    async fn lock(
+        &self,
+        conn: &mut PgConnection,
+        message: &FailedMessage,
+    ) -> Result<i32, EngineError> {
+        let retry_count: Option<i32> = match message.source {
+            FailedSource::DeadLetter => {
+                sqlx::query_scalar(
+                    "SELECT COALESCE(retry_count, 0) FROM vqm_dead_letter_queue WHERE id = $1 FOR UPDATE",
+                )
+                .bind(message.id)
+                .fetch_optional(conn)
+                .await?
+            }
+            FailedSource::Failed => {
+                sqlx::query_scalar(
+                    "SELECT 0 FROM vqm_messages WHERE id = $1 AND status = 'failed' FOR UPDATE",
+                )
+                .bind(message.id)
+                .fetch_optional(conn)
+                .await?
+            }
+        };
+        retry_count.ok_or(EngineError::MessageNotFound(message.id))
+    }
+
+    async fn history(
+        &self,
+        message_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<HistoryEntry>>, EngineError> {
+        let entries: Vec<HistoryEntry> = sqlx::query_as(
+            r#"
+            SELECT message_id, event_type, event_data, worker_id, created_at
+            FROM vqm_message_history
+            WHERE message_id = ANY($1)
+            ORDER BY created_at ASC, id ASC
+            "#,
+        )
+        .bind(message_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut by_message: HashMap<Uuid, Vec<HistoryEntry>> = HashMap::new();
+        for entry in entries {
+            by_message.entry(entry.message_id).or_default().push(entry);
+        }
+        Ok(by_message)
+    }
+}
+
+/// Append an event to a message's processing history
+pub(crate) async fn record_history<'e, E>(
+    executor: E,
+    message_id: Uuid,
+    event_type: &str,
+    event_data: serde_json::Value,
+    worker_id: Option<Uuid>,
+) -> Result<(), EngineError>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO vqm_message_history (message_id, event_type, event_data, worker_id)
+        VALUES ($1, $2, $3, $4)
+        "#,
+    )
+    .bind(message_id)
+    .bind(event_type)
+    .bind(event_data)
+    .bind(worker_id)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+struct AuditChange<'a> {
+    event_type: &'a str,
+    action: AuditAction,
+    entity_id: Uuid,
+    actor: &'a AuditActor,
+    old_values: Option<serde_json::Value>,
+    new_values: Option<serde_json::Value>,
+}
+
+async fn write_audit(conn: &mut PgConnection, change: AuditChange<'_>) -> Result<(), EngineError> {
+    sqlx::query(
+        r#"
+        INSERT INTO vqm_audit_logs (
+            event_type, event_category, entity_type, entity_id, action,
+            actor_id, actor_name, actor_type, old_values, new_values, is_sensitive
+        ) VALUES ($1, 'dead_letter', 'failed_message', $2, $3, $4, $5, 'user', $6, $7, true)
+        "#,
+    )
+    .bind(change.event_type)
+    .bind(change.entity_id)
+    .bind(change.action)
+    .bind(change.actor.id)
+    .bind(&change.actor.name)
+    .bind(change.old_values)
+    .bind(change.new_values)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
@@ -3,17 +3,22 @@
 //! Handles scheduled job execution with cron and interval-based scheduling.
 
 use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
 use cron::Schedule;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, PgPool, Row};
 use std::collections::HashMap;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use tokio::sync::broadcast;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use super::{EngineError, EngineEvent};
+use crate::enterprise::PayloadEncryptor;
+
+/// How late a one-shot run may start before it counts as missed
+const MISSED_RUN_GRACE_SECS: i64 = 60;
 
 /// Database row for scheduled job
 #[derive(Debug, FromRow)]
@@ -28,6 +33,7 @@ struct ScheduledJobRow {
     cron_expression: Option<String>,
     interval_seconds: Option<i64>,
     run_at: Option<DateTime<Utc>>,
+    missed_run_policy: Option<String>,
     timezone: Option<String>,
     status: String,
     timeout_secs: Option<i32>,
@@ -69,6 +75,7 @@ pub enum JobStatus {
     Running,
     Completed,
     Failed,
+    Skipped,
 }
 
 impl From<String> for JobStatus {
@@ -79,6 +86,7 @@ impl From<String> for JobStatus {
             "running" => JobStatus::Running,
             "completed" => JobStatus::Completed,
             "failed" => JobStatus::Failed,
+            "skipped" => JobStatus::Skipped,
             _ => JobStatus::Active,
         }
     }
@@ -92,6 +100,7 @@ impl ToString for JobStatus {
             JobStatus::Running => "running".to_string(),
             JobStatus::Completed => "completed".to_string(),
             JobStatus::Failed => "failed".to_string(),
+            JobStatus::Skipped => "skipped".to_string(),
         }
     }
 }
@@ -100,9 +109,49 @@ impl ToString for JobStatus {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ScheduleType {
-    Cron { expression: String },
-    Interval { seconds: u64 },
-    Once { at: DateTime<Utc> },
+    /// Recurring, evaluated in the job's timezone
+    Cron {
+        expression: String,
+    },
+    Interval {
+        seconds: u64,
+    },
+    /// Run once at `at`, then disable the job
+    Once {
+        at: DateTime<Utc>,
+        #[serde(default)]
+        on_missed: MissedRunPolicy,
+    },
+}
+
+/// What to do with a one-shot job whose run time passed while the
+/// scheduler was not running
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissedRunPolicy {
+    /// Run it as soon as the scheduler is back
+    #[default]
+    CatchUp,
+    /// Don't run it; the job is marked skipped
+    Skip,
+}
+
+impl MissedRunPolicy {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MissedRunPolicy::CatchUp => "catch_up",
+            MissedRunPolicy::Skip => "skip",
+        }
+    }
+}
+
+impl From<String> for MissedRunPolicy {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "skip" => MissedRunPolicy::Skip,
+            _ => MissedRunPolicy::CatchUp,
+        }
+    }
 }
 
 /// Job configuration
@@ -120,7 +169,7 @@ pub struct JobConfig {
     pub payload_template: serde_json::Value,
     /// Schedule configuration
     pub schedule: ScheduleType,
-    /// Timezone for cron expressions
+    /// IANA timezone for cron expressions, e.g. `Europe/Berlin`
     pub timezone: String,
     /// Maximum execution time in seconds
     pub timeout_secs: u64,
@@ -187,6 +236,8 @@ pub struct JobScheduler {
     running: Arc<RwLock<bool>>,
     /// Job cache
     jobs: Arc<RwLock<HashMap<Uuid, ScheduledJob>>>,
+    /// Encrypts payloads of the messages jobs enqueue, once enabled
+    payload_encryptor: Arc<OnceLock<Arc<PayloadEncryptor>>>,
 }
 
 impl JobScheduler {
@@ -197,9 +248,20 @@ impl JobScheduler {
             event_tx,
             running: Arc::new(RwLock::new(false)),
             jobs: Arc::new(RwLock::new(HashMap::new())),
+            payload_encryptor: Arc::new(OnceLock::new()),
         }
     }
 
+    /// Encrypt payloads of messages enqueued by jobs from now on
+    pub fn enable_payload_encryption(
+        &self,
+        encryptor: Arc<PayloadEncryptor>,
+    ) -> Result<(), EngineError> {
+        self.payload_encryptor
+            .set(encryptor)
+            .map_err(|_| EngineError::InvalidConfig("Payload encryption already enabled".into()))
+    }
+
     /// Start the scheduler
     pub async fn start(&self) -> Result<(), EngineError> {
         let mut running = self.running.write().await;
@@ -233,6 +295,7 @@ impl JobScheduler {
         let event_tx = self.event_tx.clone();
         let running = self.running.clone();
         let jobs_cache = self.jobs.clone();
+        let payload_encryptor = self.payload_encryptor.clone();
 
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(1));
@@ -245,7 +308,8 @@ impl JobScheduler {
                 }
 
                 // Find and execute due jobs
-                if let Err(e) = execute_due_jobs(&pool, &event_tx, &jobs_cache).await {
+                let encryptor = payload_encryptor.get().map(|e| e.as_ref());
+                if let Err(e) = execute_due_jobs(&pool, &event_tx, &jobs_cache, encryptor).await {
                     tracing::error!("Error executing scheduled jobs: {}", e);
                 }
             }
@@ -258,7 +322,7 @@ impl JobScheduler {
             r#"
             SELECT id, name, description, queue_id, message_type, payload_template,
                    schedule_type, cron_expression, interval_seconds, run_at,
-                   timezone, status, timeout_secs, max_concurrent, current_concurrent,
+                   missed_run_policy, timezone, status, timeout_secs, max_concurrent, current_concurrent,
                    retry_on_failure, max_retries, total_runs, successful_runs,
                    failed_runs, last_run_at, next_run_at, metadata, created_at, updated_at
             FROM vqm_scheduled_jobs WHERE status IN ('active', 'paused')
@@ -278,6 +342,10 @@ impl JobScheduler {
                 },
                 "once" => ScheduleType::Once {
                     at: row.run_at.unwrap_or_else(Utc::now),
+                    on_missed: row
+                        .missed_run_policy
+                        .map(MissedRunPolicy::from)
+                        .unwrap_or_default(),
                 },
                 _ => ScheduleType::Interval { seconds: 60 },
             };
@@ -328,13 +396,13 @@ impl JobScheduler {
         let now = Utc::now();
 
         // Validate schedule
-        let (schedule_type, cron_expr, interval_secs, run_at) = match &config.schedule {
+        let (schedule_type, cron_expr, interval_secs, run_at, on_missed) = match &config.schedule {
             ScheduleType::Cron { expression } => {
                 // Validate cron expression
                 Schedule::from_str(expression).map_err(|e| {
                     EngineError::InvalidConfig(format!("Invalid cron expression: {}", e))
                 })?;
-                ("cron", Some(expression.clone()), None, None)
+                ("cron", Some(expression.clone()), None, None, None)
             }
             ScheduleType::Interval { seconds } => {
                 if *seconds < 1 {
@@ -342,23 +410,37 @@ impl JobScheduler {
                         "Interval must be at least 1 second".into(),
                     ));
                 }
-                ("interval", None, Some(*seconds as i64), None)
+                ("interval", None, Some(*seconds as i64), None, None)
+            }
+            ScheduleType::Once { at, on_missed } => {
+                if *on_missed == MissedRunPolicy::Skip && is_missed(*at, now) {
+                    return Err(EngineError::InvalidConfig(
+                        "One-shot run time is already past".into(),
+                    ));
+                }
+                ("once", None, None, Some(*at), Some(on_missed.as_str()))
             }
-            ScheduleType::Once { at } => ("once", None, None, Some(*at)),
         };
 
-        // Calculate next run time
-        let next_run = calculate_next_run(&config.schedule, &config.timezone, None)?;
+        // Calculate next run time; a one-shot created slightly in the past
+        // still fires once
+        let next_run = match &config.schedule {
+            ScheduleType::Once { at, .. } => {
+                parse_timezone(&config.timezone)?;
+                Some(*at)
+            }
+            schedule => calculate_next_run(schedule, &config.timezone, None)?,
+        };
 
         sqlx::query(
             r#"
             INSERT INTO vqm_scheduled_jobs (
                 id, name, description, queue_id, message_type, payload_template,
-                schedule_type, cron_expression, interval_seconds, run_at,
+                schedule_type, cron_expression, interval_seconds, run_at, missed_run_policy,
                 timezone, status, timeout_secs, max_concurrent, current_concurrent,
                 retry_on_failure, max_retries, next_run_at, metadata, created_at, updated_at
             ) VALUES (
-                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22
             )
             "#
         )
@@ -372,6 +454,7 @@ impl JobScheduler {
         .bind(&cron_expr)
         .bind(interval_secs)
         .bind(run_at)
+        .bind(on_missed)
         .bind(&config.timezone)
         .bind("active")
         .bind(config.timeout_secs as i32)
@@ -531,7 +614,8 @@ impl JobScheduler {
             ));
         }
 
-        execute_job(&self.pool, &self.event_tx, &job).await
+        let encryptor = self.payload_encryptor.get().map(|e| e.as_ref());
+        execute_job(&self.pool, &self.event_tx, &job, encryptor).await
     }
 
     /// List all jobs
@@ -628,6 +712,7 @@ async fn execute_due_jobs(
     pool: &PgPool,
     event_tx: &broadcast::Sender<EngineEvent>,
     jobs_cache: &RwLock<HashMap<Uuid, ScheduledJob>>,
+    encryptor: Option<&PayloadEncryptor>,
 ) -> Result<(), EngineError> {
     let now = Utc::now();
 
@@ -651,23 +736,54 @@ async fn execute_due_jobs(
         };
 
         if let Some(job) = job {
+            if let ScheduleType::Once {
+                at,
+                on_missed: MissedRunPolicy::Skip,
+            } = job.schedule
+            {
+                if is_missed(at, now) {
+                    tracing::warn!(job_id = %job.id, scheduled_for = %at, "Skipping missed one-shot job");
+                    finish_one_shot(pool, jobs_cache, job_id, JobStatus::Skipped).await?;
+                    continue;
+                }
+            }
+
             // Check dependencies
             if !check_dependencies(pool, &job).await? {
                 continue;
             }
 
             // Execute the job
-            match execute_job(pool, event_tx, &job).await {
+            let succeeded = match execute_job(pool, event_tx, &job, encryptor).await {
                 Ok(execution) => {
                     tracing::info!(
-                        "Job {} executed successfully (execution: {})",
+                        "Job {} executed (execution: {}, status: {})",
                         job.id,
-                        execution.id
+                        execution.id,
+                        execution.status.to_string()
                     );
+                    execution.error.is_none()
                 }
                 Err(e) => {
                     tracing::error!("Failed to execute job {}: {}", job.id, e);
+                    let _ = event_tx.send(EngineEvent::ScheduledJobExecuted {
+                        job_id: job.id,
+                        success: false,
+                        error: Some(e.to_string()),
+                    });
+                    false
                 }
+            };
+
+            // One-shot jobs disable themselves once they have fired
+            if let ScheduleType::Once { .. } = job.schedule {
+                let status = if succeeded {
+                    JobStatus::Completed
+                } else {
+                    JobStatus::Failed
+                };
+                finish_one_shot(pool, jobs_cache, job_id, status).await?;
+                continue;
             }
 
             // Update next run time
@@ -688,6 +804,39 @@ async fn execute_due_jobs(
     Ok(())
 }
 
+/// Retire a one-shot job so it never runs again
+async fn finish_one_shot(
+    pool: &PgPool,
+    jobs_cache: &RwLock<HashMap<Uuid, ScheduledJob>>,
+    job_id: Uuid,
+    status: JobStatus,
+) -> Result<(), EngineError> {
+    sqlx::query(
+        r#"
+        UPDATE vqm_scheduled_jobs
+        SET status = $2, next_run_at = NULL, updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(job_id)
+    .bind(status.to_string())
+    .execute(pool)
+    .await?;
+
+    if let Some(cached_job) = jobs_cache.write().await.get_mut(&job_id) {
+        cached_job.status = status;
+        cached_job.next_run_at = None;
+        cached_job.updated_at = Utc::now();
+    }
+
+    Ok(())
+}
+
+/// Whether a one-shot due at `at` is too late to run at `now`
+fn is_missed(at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    now - at > Duration::seconds(MISSED_RUN_GRACE_SECS)
+}
+
 /// Check if job dependencies are satisfied
 async fn check_dependencies(pool: &PgPool, job: &ScheduledJob) -> Result<bool, EngineError> {
     if job.dependencies.is_empty() {
@@ -729,6 +878,7 @@ async fn execute_job(
     pool: &PgPool,
     event_tx: &broadcast::Sender<EngineEvent>,
     job: &ScheduledJob,
+    encryptor: Option<&PayloadEncryptor>,
 ) -> Result<JobExecution, EngineError> {
     let execution_id = Uuid::new_v4();
     let now = Utc::now();
@@ -770,24 +920,34 @@ async fn execute_job(
         obj.insert("_scheduled_at".to_string(), serde_json::json!(now));
     }
 
-    let result = sqlx::query(
-        r#"
-        INSERT INTO vqm_messages (
-            id, queue_id, message_type, payload, status, created_at
-        ) VALUES ($1, $2, $3, $4, 'pending', $5)
-        "#,
-    )
-    .bind(message_id)
-    .bind(job.queue_id)
-    .bind(&job.message_type)
-    .bind(&payload)
-    .bind(now)
-    .execute(pool)
-    .await;
+    // Never fall back to storing plaintext when encryption is on
+    let stored_payload = match encryptor {
+        Some(encryptor) => encryptor.seal(&payload).await.map_err(|e| e.to_string()),
+        None => Ok(payload),
+    };
+
+    let result = match stored_payload {
+        Ok(stored_payload) => sqlx::query(
+            r#"
+            INSERT INTO vqm_messages (
+                id, queue_id, message_type, payload, status, created_at
+            ) VALUES ($1, $2, $3, $4, 'pending', $5)
+            "#,
+        )
+        .bind(message_id)
+        .bind(job.queue_id)
+        .bind(&job.message_type)
+        .bind(&stored_payload)
+        .bind(now)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
 
     let (status, error) = match result {
         Ok(_) => ("completed", None),
-        Err(e) => ("failed", Some(e.to_string())),
+        Err(e) => ("failed", Some(e)),
     };
 
     let completed_at = Utc::now();
@@ -839,6 +999,7 @@ async fn execute_job(
     let _ = event_tx.send(EngineEvent::ScheduledJobExecuted {
         job_id: job.id,
         success: error.is_none(),
+        error: error.clone(),
     });
 
     Ok(JobExecution {
@@ -859,23 +1020,36 @@ async fn execute_job(
     })
 }
 
+/// Parse an IANA timezone name
+fn parse_timezone(timezone: &str) -> Result<Tz, EngineError> {
+    Tz::from_str(timezone)
+        .map_err(|_| EngineError::InvalidConfig(format!("Unknown timezone: {}", timezone)))
+}
+
 /// Calculate the next run time for a schedule
+///
+/// Cron expressions are matched against wall-clock time in `timezone`, so
+/// "09:00 Europe/Berlin" stays at 09:00 local time across DST changes.
 fn calculate_next_run(
     schedule: &ScheduleType,
-    _timezone: &str,
+    timezone: &str,
     after: Option<DateTime<Utc>>,
 ) -> Result<Option<DateTime<Utc>>, EngineError> {
     let base = after.unwrap_or_else(Utc::now);
+    let tz = parse_timezone(timezone)?;
 
     match schedule {
         ScheduleType::Cron { expression } => {
             let cron_schedule = Schedule::from_str(expression)
                 .map_err(|e| EngineError::InvalidConfig(format!("Invalid cron: {}", e)))?;
 
-            Ok(cron_schedule.after(&base).next())
+            Ok(cron_schedule
+                .after(&base.with_timezone(&tz))
+                .next()
+                .map(|next| next.with_timezone(&Utc)))
         }
         ScheduleType::Interval { seconds } => Ok(Some(base + Duration::seconds(*seconds as i64))),
-        ScheduleType::Once { at } => {
+        ScheduleType::Once { at, .. } => {
             if *at > base {
                 Ok(Some(*at))
             } else {
@@ -884,3 +1058,79 @@ fn calculate_next_run(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_cron_follows_timezone_across_dst() {
+        let daily_9am = ScheduleType::Cron {
+            expression: "0 0 9 * * *".to_string(),
+        };
+
+        // Before the spring change Berlin is UTC+1
+        let next = calculate_next_run(&daily_9am, "Europe/Berlin", Some(utc(2024, 3, 29, 12, 0)));
+        assert_eq!(next.unwrap(), Some(utc(2024, 3, 30, 8, 0)));
+
+        // Clocks go forward on 31 March, after which it is UTC+2
+        let next = calculate_next_run(&daily_9am, "Europe/Berlin", Some(utc(2024, 3, 30, 12, 0)));
+        assert_eq!(next.unwrap(), Some(utc(2024, 3, 31, 7, 0)));
+
+        let next = calculate_next_run(&daily_9am, "UTC", Some(utc(2024, 3, 30, 12, 0)));
+        assert_eq!(next.unwrap(), Some(utc(2024, 3, 31, 9, 0)));
+    }
+
+    #[test]
+    fn test_unknown_timezone_is_rejected() {
+        let schedule = ScheduleType::Interval { seconds: 60 };
+        assert!(matches!(
+            calculate_next_run(&schedule, "Mars/Olympus_Mons", None),
+            Err(EngineError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_one_shot_schedule() {
+        let at = utc(2024, 6, 1, 12, 0);
+        let once = ScheduleType::Once {
+            at,
+            on_missed: MissedRunPolicy::Skip,
+        };
+
+        let before = utc(2024, 6, 1, 11, 0);
+        assert_eq!(
+            calculate_next_run(&once, "UTC", Some(before)).unwrap(),
+            Some(at)
+        );
+        assert_eq!(calculate_next_run(&once, "UTC", Some(at)).unwrap(), None);
+
+        // A run picked up by the next scheduler tick is not missed
+        assert!(!is_missed(at, at + Duration::seconds(1)));
+        assert!(is_missed(at, at + Duration::minutes(10)));
+    }
+
+    #[test]
+    fn test_missed_run_policy_defaults_to_catch_up() {
+        let once: ScheduleType =
+            serde_json::from_value(serde_json::json!({ "once": { "at": "2024-06-01T12:00:00Z" } }))
+                .unwrap();
+        assert!(matches!(
+            once,
+            ScheduleType::Once {
+                on_missed: MissedRunPolicy::CatchUp,
+                ..
+            }
+        ));
+
+        assert_eq!(
+            MissedRunPolicy::from(MissedRunPolicy::Skip.as_str().to_string()),
+            MissedRunPolicy::Skip
+        );
+    }
+}
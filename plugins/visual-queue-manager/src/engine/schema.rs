@@ -0,0 +1,230 @@
+//! Payload Schema Validation
+//!
+//! Checks message payloads against the JSON Schema a handler declares for
+//! the message type it processes. Covers the subset of JSON Schema handlers
+//! use to describe payloads:
+//! - `type` (single type or list of types)
+//! - `properties`, `required` and `additionalProperties: false`
+//! - `items`, `minItems` and `maxItems`
+//! - `enum` and `const`
+//! - `minLength`, `maxLength`, `minimum` and `maximum`
+//!
+//! Keywords outside this subset are ignored rather than rejected, so a schema
+//! written for a full validator still accepts every payload it should.
+
+use serde_json::Value;
+
+/// Most violations reported for a single payload
+const MAX_VIOLATIONS: usize = 20;
+
+/// Validate `payload` against `schema`, returning every violation found
+/// as `path: problem`, e.g. `$.order.total: expected number`.
+pub fn validate_payload(schema: &Value, payload: &Value) -> Result<(), Vec<String>> {
+    let mut violations = Vec::new();
+    check(schema, payload, "$", &mut violations);
+    violations.truncate(MAX_VIOLATIONS);
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+fn check(schema: &Value, value: &Value, path: &str, violations: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        // `true` accepts everything, `false` nothing
+        if schema == &Value::Bool(false) {
+            violations.push(format!("{}: not allowed", path));
+        }
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+            violations.push(format!("{}: expected {}", path, types.join(" or ")));
+            // Nothing else is meaningful for a value of the wrong type
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            violations.push(format!("{}: not one of the allowed values", path));
+        }
+    }
+    if let Some(constant) = schema.get("const") {
+        if constant != value {
+            violations.push(format!("{}: must equal {}", path, constant));
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !object.contains_key(key) {
+                        violations.push(format!("{}.{}: required", path, key));
+                    }
+                }
+            }
+            for (key, field) in object {
+                let field_path = format!("{}.{}", path, key);
+                match properties.and_then(|p| p.get(key)) {
+                    Some(field_schema) => check(field_schema, field, &field_path, violations),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            violations.push(format!("{}: unexpected property", field_path))
+                        }
+                        Some(extra @ Value::Object(_)) => {
+                            check(extra, field, &field_path, violations)
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    violations.push(format!("{}: expected at least {} items", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if items.len() as u64 > max {
+                    violations.push(format!("{}: expected at most {} items", path, max));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    check(
+                        item_schema,
+                        item,
+                        &format!("{}[{}]", path, index),
+                        violations,
+                    );
+                }
+            }
+        }
+        Value::String(s) => {
+            let length = s.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if length < min {
+                    violations.push(format!("{}: shorter than {} characters", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if length > max {
+                    violations.push(format!("{}: longer than {} characters", path, max));
+                }
+            }
+        }
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if n < min {
+                    violations.push(format!("{}: less than {}", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if n > max {
+                    violations.push(format!("{}: greater than {}", path, max));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        // Unknown type names are not ours to reject
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn order_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["order_id", "items"],
+            "additionalProperties": false,
+            "properties": {
+                "order_id": { "type": "integer", "minimum": 1 },
+                "status": { "enum": ["paid", "refunded"] },
+                "note": { "type": ["string", "null"], "maxLength": 10 },
+                "items": {
+                    "type": "array",
+                    "minItems": 1,
+                    "items": {
+                        "type": "object",
+                        "required": ["sku"],
+                        "properties": { "sku": { "type": "string", "minLength": 1 } }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_valid_payload_passes() {
+        let payload = json!({
+            "order_id": 42,
+            "status": "paid",
+            "note": null,
+            "items": [{ "sku": "A-1", "qty": 2 }]
+        });
+        assert_eq!(validate_payload(&order_schema(), &payload), Ok(()));
+        // Schemas without constraints accept anything
+        assert_eq!(validate_payload(&json!({}), &json!([1, "two"])), Ok(()));
+    }
+
+    #[test]
+    fn test_violations_are_reported_with_paths() {
+        let payload = json!({
+            "order_id": "42",
+            "status": "lost",
+            "note": "far too long for this",
+            "items": [{ "sku": "" }, {}],
+            "coupon": "FREE"
+        });
+        let violations = validate_payload(&order_schema(), &payload).unwrap_err();
+
+        for expected in [
+            "$.order_id: expected integer",
+            "$.status: not one of the allowed values",
+            "$.note: longer than 10 characters",
+            "$.items[0].sku: shorter than 1 characters",
+            "$.items[1].sku: required",
+            "$.coupon: unexpected property",
+        ] {
+            assert!(
+                violations.iter().any(|v| v == expected),
+                "missing {:?} in {:?}",
+                expected,
+                violations
+            );
+        }
+
+        let violations = validate_payload(&order_schema(), &json!("order")).unwrap_err();
+        assert_eq!(violations, vec!["$: expected object".to_string()]);
+    }
+}
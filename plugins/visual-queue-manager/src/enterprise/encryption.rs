@@ -2,7 +2,14 @@
 //!
 //! Provides encryption capabilities for message data and sensitive configuration.
 
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes128Gcm, Aes256Gcm};
+use base64::Engine as _;
+use chacha20poly1305::ChaCha20Poly1305;
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -84,6 +91,67 @@ impl EncryptionService {
         Ok(key)
     }
 
+    /// Load a key from a configured secret.
+    ///
+    /// A secret that base64-decodes to exactly the key size is used as-is;
+    /// anything else is hashed with SHA-256 and truncated. The key id is
+    /// derived from the key material, so the same secret always gets the
+    /// same id across restarts and ciphertexts stay attributable to it.
+    pub async fn import_key(
+        &self,
+        secret: &str,
+        algorithm: KeyAlgorithm,
+    ) -> Result<EncryptionKey, super::EnterpriseError> {
+        if secret.trim().is_empty() {
+            return Err(super::EnterpriseError::Encryption(
+                "Encryption key is empty".to_string(),
+            ));
+        }
+        let key_size = key_size(&algorithm);
+        if key_size == 0 {
+            return Err(super::EnterpriseError::Encryption(
+                "Cannot import a key for algorithm None".to_string(),
+            ));
+        }
+
+        let key_material = match base64::engine::general_purpose::STANDARD.decode(secret.trim()) {
+            Ok(raw) if raw.len() == key_size => raw,
+            _ => Sha256::digest(secret.as_bytes())[..key_size].to_vec(),
+        };
+        let fingerprint = Sha256::digest(&key_material);
+        let key_id = format!(
+            "local-{}",
+            fingerprint[..8]
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        );
+        let now = chrono::Utc::now();
+
+        let mut store = self.key_store.write().await;
+        let (key, _) = store.keys.entry(key_id.clone()).or_insert_with(|| {
+            (
+                EncryptionKey {
+                    id: key_id,
+                    algorithm,
+                    status: KeyStatus::Active,
+                    created_at: now,
+                    expires_at: now + chrono::Duration::days(self.config.key_rotation_days as i64),
+                    version: 1,
+                    metadata: HashMap::new(),
+                },
+                key_material,
+            )
+        });
+
+        Ok(key.clone())
+    }
+
+    /// Whether data is actually encrypted rather than passed through
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
     /// Set the active encryption key
     pub async fn set_active_key(&self, key_id: &str) -> Result<(), super::EnterpriseError> {
         let store = self.key_store.read().await;
@@ -132,7 +200,6 @@ impl EncryptionService {
             .get(&key_id)
             .ok_or_else(|| super::EnterpriseError::Encryption("Key not found".to_string()))?;
 
-        // In a real implementation, this would perform actual encryption
         let (ciphertext, iv, auth_tag) = self.do_encrypt(data, key_material, &key.algorithm)?;
 
         Ok(EncryptedData {
@@ -159,7 +226,6 @@ impl EncryptionService {
             .get(&encrypted.key_id)
             .ok_or_else(|| super::EnterpriseError::Encryption("Key not found".to_string()))?;
 
-        // In a real implementation, this would perform actual decryption
         let plaintext = self.do_decrypt(
             &encrypted.ciphertext,
             key_material,
@@ -224,19 +290,8 @@ impl EncryptionService {
         &self,
         algorithm: &KeyAlgorithm,
     ) -> Result<Vec<u8>, super::EnterpriseError> {
-        // In a real implementation, this would use a CSPRNG
-        let key_size = match algorithm {
-            KeyAlgorithm::Aes128Gcm => 16,
-            KeyAlgorithm::Aes256Gcm => 32,
-            KeyAlgorithm::ChaCha20Poly1305 => 32,
-            KeyAlgorithm::None => 0,
-        };
-
-        // Placeholder: generate random bytes
-        let mut key = vec![0u8; key_size];
-        for (i, byte) in key.iter_mut().enumerate() {
-            *byte = (i as u8).wrapping_mul(37).wrapping_add(13);
-        }
+        let mut key = vec![0u8; key_size(algorithm)];
+        rand::rngs::OsRng.fill_bytes(&mut key);
 
         Ok(key)
     }
@@ -244,43 +299,51 @@ impl EncryptionService {
     fn do_encrypt(
         &self,
         data: &[u8],
-        _key: &[u8],
+        key: &[u8],
         algorithm: &KeyAlgorithm,
     ) -> Result<(Vec<u8>, Vec<u8>, Option<Vec<u8>>), super::EnterpriseError> {
-        // Placeholder encryption - in production, use ring or another crypto library
-        match algorithm {
-            KeyAlgorithm::Aes256Gcm | KeyAlgorithm::Aes128Gcm => {
-                // Generate IV
-                let iv = vec![0u8; 12]; // 96-bit IV for GCM
-
-                // Simulate encryption (XOR with key for demo)
-                let ciphertext = data.to_vec();
-
-                // Auth tag
-                let auth_tag = Some(vec![0u8; 16]);
+        // 96-bit random nonce, as required by all three AEADs
+        let mut iv = vec![0u8; NONCE_SIZE];
+        rand::rngs::OsRng.fill_bytes(&mut iv);
+
+        let mut sealed = match algorithm {
+            KeyAlgorithm::Aes128Gcm => aead_encrypt::<Aes128Gcm>(key, &iv, data)?,
+            KeyAlgorithm::Aes256Gcm => aead_encrypt::<Aes256Gcm>(key, &iv, data)?,
+            KeyAlgorithm::ChaCha20Poly1305 => aead_encrypt::<ChaCha20Poly1305>(key, &iv, data)?,
+            KeyAlgorithm::None => return Ok((data.to_vec(), Vec::new(), None)),
+        };
 
-                Ok((ciphertext, iv, auth_tag))
-            }
-            KeyAlgorithm::ChaCha20Poly1305 => {
-                let iv = vec![0u8; 12];
-                let ciphertext = data.to_vec();
-                let auth_tag = Some(vec![0u8; 16]);
-                Ok((ciphertext, iv, auth_tag))
-            }
-            KeyAlgorithm::None => Ok((data.to_vec(), Vec::new(), None)),
-        }
+        // The AEADs append the tag; keep it separate in `EncryptedData`
+        let auth_tag = sealed.split_off(sealed.len() - TAG_SIZE);
+        Ok((sealed, iv, Some(auth_tag)))
     }
 
     fn do_decrypt(
         &self,
         ciphertext: &[u8],
-        _key: &[u8],
-        _algorithm: &KeyAlgorithm,
-        _iv: &[u8],
-        _auth_tag: &Option<Vec<u8>>,
+        key: &[u8],
+        algorithm: &KeyAlgorithm,
+        iv: &[u8],
+        auth_tag: &Option<Vec<u8>>,
     ) -> Result<Vec<u8>, super::EnterpriseError> {
-        // Placeholder decryption
-        Ok(ciphertext.to_vec())
+        if *algorithm == KeyAlgorithm::None {
+            return Ok(ciphertext.to_vec());
+        }
+
+        let auth_tag = auth_tag.as_deref().unwrap_or_default();
+        if iv.len() != NONCE_SIZE || auth_tag.len() != TAG_SIZE {
+            return Err(super::EnterpriseError::Encryption(
+                "Malformed ciphertext".to_string(),
+            ));
+        }
+
+        let sealed = [ciphertext, auth_tag].concat();
+        match algorithm {
+            KeyAlgorithm::Aes128Gcm => aead_decrypt::<Aes128Gcm>(key, iv, &sealed),
+            KeyAlgorithm::Aes256Gcm => aead_decrypt::<Aes256Gcm>(key, iv, &sealed),
+            KeyAlgorithm::ChaCha20Poly1305 => aead_decrypt::<ChaCha20Poly1305>(key, iv, &sealed),
+            KeyAlgorithm::None => Ok(ciphertext.to_vec()),
+        }
     }
 
     async fn connect_vault(&self) -> Result<(), super::EnterpriseError> {
@@ -308,6 +371,49 @@ impl EncryptionService {
     }
 }
 
+const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+
+fn key_size(algorithm: &KeyAlgorithm) -> usize {
+    match algorithm {
+        KeyAlgorithm::Aes128Gcm => 16,
+        KeyAlgorithm::Aes256Gcm => 32,
+        KeyAlgorithm::ChaCha20Poly1305 => 32,
+        KeyAlgorithm::None => 0,
+    }
+}
+
+fn invalid_key<E>(_: E) -> super::EnterpriseError {
+    super::EnterpriseError::Encryption("Invalid key length".to_string())
+}
+
+/// Deliberately vague: don't tell callers whether the key or the data was wrong
+fn aead_failed<E>(_: E) -> super::EnterpriseError {
+    super::EnterpriseError::Encryption("AEAD operation failed".to_string())
+}
+
+fn aead_encrypt<C: KeyInit + Aead>(
+    key: &[u8],
+    iv: &[u8],
+    data: &[u8],
+) -> Result<Vec<u8>, super::EnterpriseError> {
+    C::new_from_slice(key)
+        .map_err(invalid_key)?
+        .encrypt(GenericArray::from_slice(iv), data)
+        .map_err(aead_failed)
+}
+
+fn aead_decrypt<C: KeyInit + Aead>(
+    key: &[u8],
+    iv: &[u8],
+    sealed: &[u8],
+) -> Result<Vec<u8>, super::EnterpriseError> {
+    C::new_from_slice(key)
+        .map_err(invalid_key)?
+        .decrypt(GenericArray::from_slice(iv), sealed)
+        .map_err(aead_failed)
+}
+
 /// Key store for managing encryption keys
 struct KeyStore {
     keys: HashMap<String, (EncryptionKey, Vec<u8>)>,
@@ -494,3 +600,193 @@ impl FieldEncryptor {
         Ok(data)
     }
 }
+
+/// Envelope field holding an encrypted message payload
+pub const ENCRYPTED_PAYLOAD_FIELD: &str = "_payload_encrypted";
+
+/// Encrypts whole message payloads for storage.
+///
+/// Sealed payloads are stored as a JSON envelope carrying the base64-encoded
+/// `EncryptedData` and the id of the key that sealed it, so payloads written
+/// before a key rotation can still be opened as long as the old key stays
+/// loaded. Payloads without an envelope are returned unchanged, which keeps
+/// messages written before encryption was enabled readable.
+pub struct PayloadEncryptor {
+    service: Arc<EncryptionService>,
+}
+
+impl PayloadEncryptor {
+    pub fn new(service: Arc<EncryptionService>) -> Self {
+        Self { service }
+    }
+
+    /// Encrypt a payload, failing rather than ever storing plaintext
+    pub async fn seal(
+        &self,
+        payload: &serde_json::Value,
+    ) -> Result<serde_json::Value, super::EnterpriseError> {
+        if !self.service.is_enabled() {
+            return Err(super::EnterpriseError::Encryption(
+                "Payload encryption requested but the encryption service is disabled".to_string(),
+            ));
+        }
+
+        let bytes = serde_json::to_vec(payload)
+            .map_err(|e| super::EnterpriseError::Encryption(e.to_string()))?;
+        let encrypted = self.service.encrypt(&bytes).await?;
+        if encrypted.algorithm == KeyAlgorithm::None {
+            return Err(super::EnterpriseError::Encryption(
+                "Active key does not encrypt".to_string(),
+            ));
+        }
+
+        Ok(serde_json::json!({
+            ENCRYPTED_PAYLOAD_FIELD: base64::engine::general_purpose::STANDARD
+                .encode(encrypted.to_bytes()),
+            "key_id": encrypted.key_id,
+        }))
+    }
+
+    /// Decrypt a stored payload; plaintext payloads pass through
+    pub async fn open(
+        &self,
+        stored: serde_json::Value,
+    ) -> Result<serde_json::Value, super::EnterpriseError> {
+        let Some(encoded) = encrypted_payload(&stored) else {
+            return Ok(stored);
+        };
+
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| super::EnterpriseError::Encryption(e.to_string()))?;
+        let encrypted = EncryptedData::from_bytes(&bytes)?;
+        let plaintext = self.service.decrypt(&encrypted).await?;
+
+        serde_json::from_slice(&plaintext)
+            .map_err(|e| super::EnterpriseError::Encryption(e.to_string()))
+    }
+}
+
+/// The sealed part of a stored payload, if it is encrypted
+fn encrypted_payload(stored: &serde_json::Value) -> Option<&str> {
+    stored.get(ENCRYPTED_PAYLOAD_FIELD)?.as_str()
+}
+
+/// Check whether a stored payload is an encryption envelope
+pub fn is_encrypted_payload(stored: &serde_json::Value) -> bool {
+    encrypted_payload(stored).is_some()
+}
+
+/// Replace an encrypted payload with a marker that reveals only the key id
+pub fn redact_encrypted_payload(stored: serde_json::Value) -> serde_json::Value {
+    if !is_encrypted_payload(&stored) {
+        return stored;
+    }
+    serde_json::json!({
+        "encrypted": true,
+        "key_id": stored.get("key_id").cloned().unwrap_or(serde_json::Value::Null),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> Arc<EncryptionService> {
+        Arc::new(EncryptionService::new(EncryptionConfig {
+            enabled: true,
+            ..Default::default()
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_payload_round_trip_across_key_rotation() {
+        let service = service();
+        let old_key = service
+            .import_key("first secret", KeyAlgorithm::Aes256Gcm)
+            .await
+            .unwrap();
+        service.set_active_key(&old_key.id).await.unwrap();
+
+        let encryptor = PayloadEncryptor::new(service.clone());
+        let payload = serde_json::json!({ "email": "user@example.com", "amount": 42 });
+        let sealed = encryptor.seal(&payload).await.unwrap();
+
+        assert!(is_encrypted_payload(&sealed));
+        assert_eq!(sealed["key_id"], old_key.id.as_str());
+        assert!(!sealed.to_string().contains("user@example.com"));
+
+        // Rotate to a new configured key; the old one stays loaded
+        let new_key = service
+            .import_key("second secret", KeyAlgorithm::ChaCha20Poly1305)
+            .await
+            .unwrap();
+        service.set_active_key(&new_key.id).await.unwrap();
+        assert_ne!(old_key.id, new_key.id);
+
+        assert_eq!(encryptor.open(sealed).await.unwrap(), payload);
+        let resealed = encryptor.seal(&payload).await.unwrap();
+        assert_eq!(resealed["key_id"], new_key.id.as_str());
+        assert_eq!(encryptor.open(resealed).await.unwrap(), payload);
+
+        // Plaintext written before encryption was enabled passes through
+        assert_eq!(encryptor.open(payload.clone()).await.unwrap(), payload);
+    }
+
+    #[tokio::test]
+    async fn test_payload_encryption_fails_loudly() {
+        let disabled = Arc::new(EncryptionService::new(EncryptionConfig::default()));
+        let payload = serde_json::json!({ "secret": true });
+        assert!(PayloadEncryptor::new(disabled)
+            .seal(&payload)
+            .await
+            .is_err());
+
+        // Enabled but no key loaded
+        let service = service();
+        let encryptor = PayloadEncryptor::new(service.clone());
+        assert!(encryptor.seal(&payload).await.is_err());
+
+        // Tampered ciphertext is rejected rather than returned
+        let key = service
+            .import_key("secret", KeyAlgorithm::Aes128Gcm)
+            .await
+            .unwrap();
+        service.set_active_key(&key.id).await.unwrap();
+        let sealed = encryptor.seal(&payload).await.unwrap();
+        let mut encrypted = EncryptedData::from_bytes(
+            &base64::engine::general_purpose::STANDARD
+                .decode(sealed[ENCRYPTED_PAYLOAD_FIELD].as_str().unwrap())
+                .unwrap(),
+        )
+        .unwrap();
+        encrypted.ciphertext[0] ^= 1;
+        let tampered = serde_json::json!({
+            ENCRYPTED_PAYLOAD_FIELD: base64::engine::general_purpose::STANDARD
+                .encode(encrypted.to_bytes()),
+        });
+        assert!(encryptor.open(tampered).await.is_err());
+
+        assert_eq!(
+            redact_encrypted_payload(sealed),
+            serde_json::json!({ "encrypted": true, "key_id": key.id })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_key_is_stable() {
+        let a = service()
+            .import_key("same secret", KeyAlgorithm::Aes256Gcm)
+            .await
+            .unwrap();
+        let b = service()
+            .import_key("same secret", KeyAlgorithm::Aes256Gcm)
+            .await
+            .unwrap();
+        assert_eq!(a.id, b.id);
+        assert!(service()
+            .import_key("  ", KeyAlgorithm::Aes256Gcm)
+            .await
+            .is_err());
+    }
+}
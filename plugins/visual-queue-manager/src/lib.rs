@@ -74,8 +74,8 @@ pub use admin::{
 pub use enterprise::{
     AuditEvent, ComplianceConfig, ComplianceManager, DataClassification, EncryptedData,
     EncryptionConfig, EncryptionService, EnterpriseConfig, EnterpriseError, EnterpriseManager,
-    KeyAlgorithm, RateLimitConfig, RateLimitKey, RateLimitResult, RateLimiter, TenancyConfig,
-    TenancyManager, Tenant, TenantContext, TenantTier,
+    KeyAlgorithm, PayloadEncryptor, RateLimitConfig, RateLimitKey, RateLimitResult, RateLimiter,
+    TenancyConfig, TenancyManager, Tenant, TenantContext, TenantTier,
 };
 
 // =============================================================================
@@ -131,6 +131,12 @@ pub struct PluginConfig {
     pub encryption_key: String,
     /// Encrypt message payloads
     pub encrypt_payloads: bool,
+    /// Algorithm used to encrypt payloads
+    #[serde(default = "default_encryption_algorithm")]
+    pub encryption_algorithm: KeyAlgorithm,
+    /// Retired encryption keys, kept so older payloads stay readable
+    #[serde(default)]
+    pub previous_encryption_keys: Vec<String>,
     /// Enable audit logging
     pub audit_logging: bool,
     /// Maximum payload size in KB
@@ -202,6 +208,8 @@ impl Default for PluginConfig {
             enable_realtime_updates: true,
             encryption_key: String::new(),
             encrypt_payloads: false,
+            encryption_algorithm: default_encryption_algorithm(),
+            previous_encryption_keys: Vec::new(),
             audit_logging: true,
             max_payload_size_kb: 1024,
             webhook_url: None,
@@ -247,6 +255,10 @@ pub struct HealthCheckResult {
 
 /// Visual Queue Manager Plugin
 ///
+fn default_encryption_algorithm() -> KeyAlgorithm {
+    KeyAlgorithm::Aes256Gcm
+}
+
 /// The main plugin struct that implements the RustPress Plugin trait.
 pub struct VisualQueueManager {
     /// Plugin information
@@ -261,6 +273,12 @@ pub struct VisualQueueManager {
     enterprise_manager: Arc<RwLock<Option<Arc<EnterpriseManager>>>>,
     /// Plugin state
     state: Arc<RwLock<PluginState>>,
+    /// Prometheus gauges of the running queue engine
+    metrics_exporter: std::sync::OnceLock<Arc<engine::PrometheusExporter>>,
+    /// Failed message replay backed by the running queue engine
+    dlq_replay: std::sync::OnceLock<Arc<engine::DlqReplay>>,
+    /// Payload encryption, loaded when `encrypt_payloads` is enabled
+    payload_encryptor: Arc<RwLock<Option<Arc<PayloadEncryptor>>>>,
 }
 
 impl VisualQueueManager {
@@ -294,6 +312,9 @@ impl VisualQueueManager {
             admin_module: Arc::new(RwLock::new(None)),
             enterprise_manager: Arc::new(RwLock::new(None)),
             state: Arc::new(RwLock::new(PluginState::default())),
+            metrics_exporter: std::sync::OnceLock::new(),
+            dlq_replay: std::sync::OnceLock::new(),
+            payload_encryptor: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -315,6 +336,70 @@ impl VisualQueueManager {
             .expect("Database pool not initialized - ensure init_pool() is called first")
     }
 
+    /// Serve the engine's Prometheus gauges from the `/metrics` endpoint
+    pub fn attach_metrics_exporter(&self, exporter: Arc<engine::PrometheusExporter>) -> Result<()> {
+        self.metrics_exporter
+            .set(exporter)
+            .map_err(|_| anyhow::anyhow!("Metrics exporter already attached"))?;
+        Ok(())
+    }
+
+    /// Get the attached Prometheus exporter
+    pub fn metrics_exporter(&self) -> Option<&Arc<engine::PrometheusExporter>> {
+        self.metrics_exporter.get()
+    }
+
+    /// Serve the failed message replay endpoints from the running engine
+    pub fn attach_dlq_replay(&self, replay: Arc<engine::DlqReplay>) -> Result<()> {
+        self.dlq_replay
+            .set(replay)
+            .map_err(|_| anyhow::anyhow!("DLQ replay already attached"))?;
+        Ok(())
+    }
+
+    /// Get the attached failed message replay service
+    pub fn dlq_replay(&self) -> Option<&Arc<engine::DlqReplay>> {
+        self.dlq_replay.get()
+    }
+
+    /// Load the payload encryption keys from the configuration
+    ///
+    /// Previous keys are imported first so payloads sealed before a key
+    /// rotation can still be opened; new payloads use the current key.
+    pub async fn enable_payload_encryption(&self, config: &PluginConfig) -> Result<()> {
+        let service = Arc::new(EncryptionService::new(EncryptionConfig {
+            enabled: true,
+            ..Default::default()
+        }));
+
+        for secret in &config.previous_encryption_keys {
+            service
+                .import_key(secret, config.encryption_algorithm.clone())
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to load previous encryption key: {}", e))?;
+        }
+        let key = service
+            .import_key(&config.encryption_key, config.encryption_algorithm.clone())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load encryption key: {}", e))?;
+        service
+            .set_active_key(&key.id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to activate encryption key: {}", e))?;
+
+        let mut encryptor = self.payload_encryptor.write().await;
+        *encryptor = Some(Arc::new(PayloadEncryptor::new(service)));
+
+        tracing::info!(key_id = %key.id, "Payload encryption enabled");
+        Ok(())
+    }
+
+    /// Get the payload encryptor, if payload encryption is enabled
+    pub async fn payload_encryptor(&self) -> Option<Arc<PayloadEncryptor>> {
+        let guard = self.payload_encryptor.read().await;
+        guard.clone()
+    }
+
     /// Log an audit event
     pub async fn log_audit(
         &self,
@@ -476,6 +561,11 @@ impl Plugin for VisualQueueManager {
             state.workers_running = true;
         }
 
+        // Refuse to start without a usable key rather than store plaintext
+        if config.encrypt_payloads {
+            self.enable_payload_encryption(&config).await?;
+        }
+
         // Initialize enterprise features if multi-tenancy is enabled
         if config.features.multi_tenancy {
             let enterprise_config = EnterpriseConfig::default();
@@ -27,6 +27,12 @@ pub enum AuditAction {
     MessageDelete,
     MessageMoveToDlq,
 
+    // Dead letter replay actions
+    DlqPayloadView,
+    DlqReplay,
+    DlqEditReplay,
+    DlqDelete,
+
     // Worker actions
     WorkerRegister,
     WorkerUnregister,